@@ -4,6 +4,7 @@ use crate::model::{Id, ModelManager, RunBmc, RunForCreate, TaskBmc, TaskForCreat
 pub fn create_run(mm: &ModelManager, label: &str) -> Result<Id> {
 	let run_c = RunForCreate {
 		parent_id: None,
+		parent_task_id: None,
 		agent_name: Some(label.to_string()),
 		agent_path: Some(format!("path/{label}")),
 		has_task_stages: None,
@@ -1,5 +1,6 @@
 use crate::_test_support::assert_contains;
-use crate::agent::Agent;
+use crate::agent::{Agent, AgentDoc};
+use crate::model::Stage;
 
 pub type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
 
@@ -87,3 +88,81 @@ return "hello"
 
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_agent_parse_meta_section() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"
+# Meta
+
+```toml
+description = "Proofread the given files"
+
+[[params]]
+name = "style"
+default = "casual"
+```
+
+# User
+
+Some user prompt
+```toml
+# Meta
+description = "Not the meta"
+```
+		"#;
+
+	// -- Exec
+	let agent = Agent::mock_from_content(content)?;
+
+	// -- Check
+	let parts = agent.prompt_parts();
+	assert_eq!(parts.len(), 1);
+	assert_eq!(agent.meta().description(), Some("Proofread the given files"));
+	assert_eq!(agent.meta().params().len(), 1);
+	assert_eq!(agent.args(), &serde_json::json!({"style": "casual"}));
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_agent_parse_stage_lua_blocks() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"# Data
+```lua
+local a = 1
+return a
+```
+
+```lua
+-- Not the stage block
+```
+
+# User
+
+```lua
+-- Not a stage
+```
+
+# Output
+
+````lua
+return "```"
+````
+"#;
+	let doc = AgentDoc::from_content("mock-agent.aip", content)?;
+
+	// -- Exec
+	let blocks = doc.stage_lua_blocks()?;
+
+	// -- Check
+	assert_eq!(blocks.len(), 2);
+	assert_eq!(blocks[0].stage, Stage::Data);
+	assert_eq!((blocks[0].start_line, blocks[0].end_line), (2, 5));
+	assert_eq!(blocks[0].content, "local a = 1\nreturn a");
+	assert_eq!(blocks[1].stage, Stage::Output);
+	assert_eq!((blocks[1].start_line, blocks[1].end_line), (19, 21));
+	assert_eq!(blocks[1].content, "return \"```\"");
+
+	Ok(())
+}
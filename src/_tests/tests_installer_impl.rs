@@ -1,6 +1,6 @@
 use super::*;
 use crate::_test_support::{remove_test_dir, save_file_content};
-use crate::exec::packer::{self, install_pack};
+use crate::exec::packer::{self, install_locked, install_pack};
 use crate::runtime::Runtime;
 use simple_fs::{SPath, ensure_dir};
 
@@ -14,11 +14,11 @@ async fn test_installer_impl_local_file_simple() -> Result<()> {
 	let dir_context = runtime.dir_context();
 	// Prep the pack dir
 	let to_pack_dir = SPath::new("tests-data/test_packs_folder/test_pack_01");
-	let pack_result = packer::pack_dir(to_pack_dir, dir_context.current_dir())?;
+	let pack_result = packer::pack_dir(to_pack_dir, dir_context.current_dir()).await?;
 	let aipack_file_path = pack_result.pack_file;
 
 	// -- Exec
-	let installed_pack = install_pack(dir_context, aipack_file_path.as_str(), true).await?;
+	let installed_pack = install_pack(dir_context, aipack_file_path.as_str(), true, None, &|_| Ok(true)).await?;
 
 	let InstallResponse::Installed(installed_pack) = installed_pack else {
 		return Err("Should be installed_pack".into());
@@ -77,10 +77,10 @@ version = "0.2.0"
 "#;
 	save_file_content(&old_pack_dir.join("pack.toml"), old_pack_toml)?;
 
-	let old_pack_data = packer::pack_dir(&old_pack_dir, dir_context.current_dir())?;
+	let old_pack_data = packer::pack_dir(&old_pack_dir, dir_context.current_dir()).await?;
 	let old_pack_file = old_pack_data.pack_file;
 	// Install the old pack (version 0.2.0)
-	let _installed_old_pack = install_pack(dir_context, old_pack_file.as_str(), true).await?;
+	let _installed_old_pack = install_pack(dir_context, old_pack_file.as_str(), true, None, &|_| Ok(true)).await?;
 	// (Optional: assert that installed_old_pack.pack_toml.version == "0.2.0")
 
 	// Step 2: Create new pack directory (version 0.1.0)
@@ -94,11 +94,11 @@ version = "0.1.0"
 "#;
 	save_file_content(&new_pack_dir.join("pack.toml"), new_pack_toml)?;
 	save_file_content(&new_pack_dir.join("main.aip"), main_aip_content)?;
-	let new_pack_data = packer::pack_dir(&new_pack_dir, dir_context.current_dir())?;
+	let new_pack_data = packer::pack_dir(&new_pack_dir, dir_context.current_dir()).await?;
 	let new_pack_file = new_pack_data.pack_file;
 
 	// -- Execute: Try to install the new pack (version 0.1.0)
-	let result = install_pack(dir_context, new_pack_file.as_str(), true).await;
+	let result = install_pack(dir_context, new_pack_file.as_str(), true, None, &|_| Ok(true)).await;
 
 	// -- Check: The new pack installation should fail.
 	assert!(result.is_err(), "Installing lower version should fail");
@@ -147,11 +147,11 @@ version = "0.1.0-alpha"
 	)?;
 
 	// Pack the directory into a .aipack file
-	let pack_data = packer::pack_dir(&invalid_pack_dir, dir_context.current_dir())?;
+	let pack_data = packer::pack_dir(&invalid_pack_dir, dir_context.current_dir()).await?;
 	let pack_file_str = pack_data.pack_file.as_str();
 
 	// Attempt to install the pack, expecting an error due to invalid prerelease format
-	let result = install_pack(dir_context, pack_file_str, true).await;
+	let result = install_pack(dir_context, pack_file_str, true, None, &|_| Ok(true)).await;
 
 	assert!(
 		result.is_err(),
@@ -171,3 +171,224 @@ version = "0.1.0-alpha"
 	remove_test_dir(dir_context.current_dir())?;
 	Ok(())
 }
+
+#[tokio::test]
+async fn test_installer_impl_content_hash_verify() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let to_pack_dir = SPath::new("tests-data/test_packs_folder/test_pack_01");
+	let pack_result = packer::pack_dir(&to_pack_dir, dir_context.current_dir().join("ok")).await?;
+	let tampered_result = packer::pack_dir(&to_pack_dir, dir_context.current_dir().join("tampered")).await?;
+	zip::append_file(&tampered_result.pack_file, "injected.lua", b"os.execute('echo')")?;
+
+	// -- Exec
+	let tampered_res = install_pack(dir_context, tampered_result.pack_file.as_str(), true, None, &|_| {
+		Ok(true)
+	})
+	.await;
+	let installed_res = install_pack(dir_context, pack_result.pack_file.as_str(), true, None, &|_| Ok(true)).await?;
+
+	// -- Check
+	let err = tampered_res.err().ok_or("Tampered pack install should fail")?;
+	assert!(err.to_string().contains("content hash mismatch"), "{err}");
+	let InstallResponse::Installed(installed_pack) = installed_res else {
+		return Err("Should be installed_pack".into());
+	};
+	let install_info = InstallInfo::read(&installed_pack.path).ok_or("Should have install info")?;
+	assert_eq!(install_info.content_hash, Some(pack_result.content_hash));
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_installer_impl_git_repo() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let repo_dir = dir_context.current_dir().join("acme-packs.git");
+	let pack_dir = repo_dir.join("packs/git-pack");
+	ensure_dir(&pack_dir)?;
+	let pack_toml = r#"
+[pack]
+namespace = "test_git"
+name = "git-pack"
+version = "0.1.0"
+"#;
+	save_file_content(&pack_dir.join("pack.toml"), pack_toml)?;
+	save_file_content(&pack_dir.join("main.aip"), "# Git Pack Main")?;
+	let git = |args: &[&str]| -> Result<String> {
+		let output = std::process::Command::new("git")
+			.args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+			.args(args)
+			.current_dir(repo_dir.as_std_path())
+			.output()?;
+		Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+	};
+	git(&["init", "--quiet", "-b", "dev"])?;
+	git(&["add", "."])?;
+	git(&["commit", "--quiet", "-m", "first"])?;
+	let commit = git(&["rev-parse", "HEAD"])?;
+	let git_uri = format!("file://{}#dev", repo_dir.as_str());
+
+	// -- Exec
+	let installed_res = install_pack(dir_context, &git_uri, false, None, &|_| Ok(true)).await?;
+	let reinstalled_res = install_pack(dir_context, &git_uri, false, None, &|_| Ok(true)).await?;
+
+	// -- Check
+	let InstallResponse::Installed(installed_pack) = installed_res else {
+		return Err("Should be installed_pack".into());
+	};
+	assert_eq!(installed_pack.pack_toml.name, "git-pack");
+	assert_eq!(installed_pack.git_commit.as_deref(), Some(commit.as_str()));
+	assert!(installed_pack.path.join("main.aip").exists());
+	let install_info = InstallInfo::read(&installed_pack.path).ok_or("Should have install info")?;
+	assert_eq!(install_info.version, commit);
+	assert!(install_info.origin.starts_with("git repo 'file://"));
+	assert!(matches!(reinstalled_res, InstallResponse::UpToDate(_)));
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_installer_impl_dependencies() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let dep_b = save_dep_pack(dir_context.current_dir(), "dep-b", "0.1.3", "").await?;
+	let dep_a_deps = format!(r#""test_deps@dep-b" = {{ version = "^0.1", source = "{dep_b}" }}"#);
+	let dep_a = save_dep_pack(dir_context.current_dir(), "dep-a", "0.2.1", &dep_a_deps).await?;
+	let root_deps = format!(
+		r#""test_deps@dep-a" = {{ version = "^0.2", source = "{dep_a}" }}
+"test_deps@dep-b" = {{ version = ">=0.1.2", source = "{dep_b}" }}"#
+	);
+	let root = save_dep_pack(dir_context.current_dir(), "root", "1.0.0", &root_deps).await?;
+
+	// -- Exec
+	let install_res = install_pack(dir_context, root.as_str(), false, None, &|_| Ok(true)).await?;
+
+	// -- Check
+	let InstallResponse::Installed(installed_pack) = install_res else {
+		return Err("Should be installed_pack".into());
+	};
+	let deps: Vec<(&str, &str)> = installed_pack
+		.dependencies
+		.iter()
+		.map(|dep| (dep.pack.as_str(), dep.version.as_str()))
+		.collect();
+	assert_eq!(deps, vec![("test_deps@dep-a", "0.2.1"), ("test_deps@dep-b", "0.1.3")]);
+	assert_eq!(
+		installed_pack.dependencies[1].required_by,
+		vec!["test_deps@dep-a", "test_deps@root"]
+	);
+	let installed_dir = dir_context.aipack_paths().get_base_pack_installed_dir()?;
+	assert!(installed_dir.join("test_deps/dep-b/pack.toml").exists());
+	let lock_path = dir_context.aipack_paths().aipack_lock_path().ok_or("Should have a lock path")?;
+	let lock_content = std::fs::read_to_string(lock_path.as_std_path())?;
+	assert!(lock_content.contains(r#"pack = "test_deps@dep-b""#));
+	assert!(lock_content.contains(r#"pack = "test_deps@root""#));
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_installer_impl_dependencies_cycle_and_conflict_err() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let cycle_root_file = dir_context.current_dir().join("test_deps@cycle-root-v1.0.0.aipack");
+	let cycle_a_deps = format!(r#""test_deps@cycle-root" = {{ version = "^1", source = "{cycle_root_file}" }}"#);
+	let cycle_a = save_dep_pack(dir_context.current_dir(), "cycle-a", "0.1.0", &cycle_a_deps).await?;
+	let cycle_root_deps = format!(r#""test_deps@cycle-a" = {{ version = "*", source = "{cycle_a}" }}"#);
+	let cycle_root = save_dep_pack(dir_context.current_dir(), "cycle-root", "1.0.0", &cycle_root_deps).await?;
+
+	let old_b = save_dep_pack(dir_context.current_dir(), "old-b", "0.1.0", "").await?;
+	let conflict_deps = format!(r#""test_deps@old-b" = {{ version = "^0.2", source = "{old_b}" }}"#);
+	let conflict_root = save_dep_pack(dir_context.current_dir(), "conflict-root", "1.0.0", &conflict_deps).await?;
+
+	// -- Exec
+	let cycle_res = install_pack(dir_context, cycle_root.as_str(), false, None, &|_| Ok(true)).await;
+	let conflict_res = install_pack(dir_context, conflict_root.as_str(), false, None, &|_| Ok(true)).await;
+
+	// -- Check
+	let err = cycle_res.err().ok_or("Cycle should fail")?.to_string();
+	assert!(
+		err.contains("Dependency cycle: test_deps@cycle-root -> test_deps@cycle-a -> test_deps@cycle-root"),
+		"{err}"
+	);
+	let err = conflict_res.err().ok_or("Conflict should fail")?.to_string();
+	assert!(err.contains("v0.1.0 does not satisfy '^0.2'"), "{err}");
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_installer_impl_locked() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let lock_b = save_dep_pack(dir_context.current_dir(), "lock-b", "0.1.0", "").await?;
+	let root_deps = format!(r#""test_deps@lock-b" = {{ version = "^0.1", source = "{lock_b}" }}"#);
+	let lock_root = save_dep_pack(dir_context.current_dir(), "lock-root", "1.0.0", &root_deps).await?;
+	install_pack(dir_context, lock_root.as_str(), false, None, &|_| Ok(true)).await?;
+
+	// -- Exec
+	let locked_res = install_locked(dir_context, Some("test_deps@lock-root"), false, &|_| Ok(true)).await?;
+	// Same version, other content (e.g., republished)
+	save_file_content(&dir_context.current_dir().join("dep_packs/lock-b/extra.md"), "# Extra")?;
+	packer::pack_dir(
+		dir_context.current_dir().join("dep_packs/lock-b"),
+		dir_context.current_dir(),
+	)
+	.await?;
+	let mismatch_res = install_locked(dir_context, None, true, &|_| Ok(true)).await;
+
+	// -- Check
+	let lock_path = dir_context.aipack_paths().aipack_lock_path().ok_or("Should have a lock path")?;
+	let lock_content = std::fs::read_to_string(lock_path.as_std_path())?;
+	assert!(lock_content.contains(r#"content_hash = "blake3:"#), "{lock_content}");
+	assert!(lock_content.contains(&format!(r#"uri = "{lock_b}""#)), "{lock_content}");
+	let locked: Vec<&str> = locked_res
+		.iter()
+		.map(|res| match res {
+			InstallResponse::UpToDate(p) => p.pack_toml.name.as_str(),
+			InstallResponse::Installed(_) => "(installed)",
+		})
+		.collect();
+	// The dependencies first, and up to date (same version and content hash)
+	assert_eq!(locked, vec!["lock-b", "lock-root"]);
+	let err = mismatch_res
+		.err()
+		.ok_or("Should fail with the content hash mismatch")?
+		.to_string();
+	assert!(err.contains("Locked content hash mismatch"), "{err}");
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+	Ok(())
+}
+
+// region:    --- Support
+
+/// Save the `test_deps@{name}` pack dir, with the `[dependencies]` lines, and pack it in the test dir (returns the .aipack file).
+async fn save_dep_pack(test_dir: &SPath, name: &str, version: &str, dependencies: &str) -> Result<SPath> {
+	let pack_dir = test_dir.join("dep_packs").join(name);
+	ensure_dir(&pack_dir)?;
+	let pack_toml = format!(
+		"[pack]\nnamespace = \"test_deps\"\nname = \"{name}\"\nversion = \"{version}\"\n\n[dependencies]\n{dependencies}\n"
+	);
+	save_file_content(&pack_dir.join("pack.toml"), &pack_toml)?;
+	save_file_content(&pack_dir.join("main.aip"), "# Dep Pack Main")?;
+	let pack_data = packer::pack_dir(&pack_dir, test_dir).await?;
+	Ok(pack_data.pack_file)
+}
+
+// endregion: --- Support
@@ -1,4 +1,4 @@
-use crate::_test_support::remove_test_dir;
+use crate::_test_support::{remove_test_dir, save_file_content};
 use crate::exec::packer::{self};
 use crate::runtime::Runtime;
 use simple_fs::SPath;
@@ -14,7 +14,7 @@ async fn test_packer_impl_pack_simple() -> Result<()> {
 	let to_pack_dir = SPath::new("tests-data/test_packs_folder/test_pack_01");
 
 	// -- Exec
-	let pack_result = packer::pack_dir(to_pack_dir, dir_context.current_dir())?;
+	let pack_result = packer::pack_dir(to_pack_dir, dir_context.current_dir()).await?;
 
 	// -- Check
 	// Verify that the pack file was created with correct structure
@@ -38,6 +38,94 @@ async fn test_packer_impl_pack_simple() -> Result<()> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn test_packer_impl_pack_reproducible() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let to_pack_dir = SPath::new("tests-data/test_packs_folder/test_pack_01");
+	let dest_dir_1 = dir_context.current_dir().join("build-1");
+	let dest_dir_2 = dir_context.current_dir().join("build-2");
+
+	// -- Exec
+	let pack_result_1 = packer::pack_dir(&to_pack_dir, &dest_dir_1).await?;
+	let pack_result_2 = packer::pack_dir(&to_pack_dir, &dest_dir_2).await?;
+
+	// -- Check
+	assert!(pack_result_1.content_hash.starts_with("blake3:"));
+	assert_eq!(pack_result_1.content_hash, pack_result_2.content_hash);
+	assert_eq!(
+		fs::read(pack_result_1.pack_file.path())?,
+		fs::read(pack_result_2.pack_file.path())?,
+		"Two builds of the same source should be identical"
+	);
+	let meta = crate::support::zip::extract_text_content(&pack_result_1.pack_file, ".aipack-meta.toml")?;
+	assert!(meta.contains(&pack_result_1.content_hash));
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+
+	Ok(())
+}
+
+#[tokio::test]
+async fn test_packer_impl_pack_build_steps() -> Result<()> {
+	// -- Setup & Fixtures
+	let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+	let dir_context = runtime.dir_context();
+	let pack_dir = dir_context.current_dir().join("pack_src/acme/helper");
+	save_file_content(
+		&pack_dir.join("pack.toml"),
+		r#"
+[pack]
+namespace = "acme"
+name = "helper"
+version = "0.1.0"
+
+[build]
+exclude = ["build/**"]
+
+[[build.steps]]
+kind = "bundle"
+src = ["build/prompts/*.md"]
+dest = "prompts.md"
+separator = "\n---\n"
+
+[[build.steps]]
+kind = "render"
+src = "build/main.aip.hbs"
+dest = "main.aip"
+data = "build/data.toml"
+"#,
+	)?;
+	save_file_content(&pack_dir.join("build/prompts/a.md"), "Prompt A")?;
+	save_file_content(&pack_dir.join("build/prompts/b.md"), "Prompt B")?;
+	save_file_content(&pack_dir.join("build/data.toml"), "model = \"gpt-5-mini\"")?;
+	save_file_content(
+		&pack_dir.join("build/main.aip.hbs"),
+		"# {{pack.namespace}}@{{pack.name}} v{{pack.version}} ({{data.model}})",
+	)?;
+
+	// -- Exec
+	let pack_result = packer::pack_dir(&pack_dir, dir_context.current_dir()).await?;
+
+	// -- Check
+	assert_eq!(pack_result.built_files, vec!["prompts.md", "main.aip"]);
+	let entries = crate::support::zip::list_entries_with_globs(&pack_result.pack_file, None::<&[String]>)?;
+	assert!(entries.iter().all(|entry| !entry.starts_with("build")), "{entries:?}");
+	let main_aip = crate::support::zip::extract_text_content(&pack_result.pack_file, "main.aip")?;
+	assert_eq!(main_aip, "# acme@helper v0.1.0 (gpt-5-mini)");
+	let prompts = crate::support::zip::extract_text_content(&pack_result.pack_file, "prompts.md")?;
+	assert_eq!(prompts, "Prompt A\n---\nPrompt B");
+	// the pack dir is not modified
+	assert!(!pack_dir.join("main.aip").exists());
+
+	// -- Cleanup
+	remove_test_dir(dir_context.current_dir())?;
+
+	Ok(())
+}
+
 // region:    --- Support
 
 // Test helper to verify the structure of a created .aipack file
@@ -1,5 +1,5 @@
 use crate::_test_support::assert_contains;
-use crate::support::md::MdBlockIter;
+use crate::support::md::{MdBlockIter, md_replace_block};
 use crate::types::{Extrude, MdBlock};
 
 type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
@@ -253,3 +253,38 @@ def hello():
 
 	Ok(())
 }
+
+#[test]
+fn test_md_block_iter_fence_info_and_replace() -> Result<()> {
+	// -- Setup & Fixtures
+	let content = r#"Intro
+```rust file=src/main.rs title="Main file" no_run
+fn main() {}
+```
+```py
+print("hi")
+```
+"#;
+
+	// -- Exec
+	let blocks: Vec<MdBlock> = MdBlockIter::new(content, Some("rust"), None).collect();
+	let block = blocks.first().ok_or("Should have rust block")?;
+	let replaced = md_replace_block(content, 5, 7, "print(\"bye\")\n")?;
+
+	// -- Check
+	assert_eq!(blocks.len(), 1);
+	assert_eq!(block.lang.as_deref(), Some("rust"));
+	assert_eq!(
+		block.info.as_deref(),
+		Some(r#"rust file=src/main.rs title="Main file" no_run"#)
+	);
+	let attrs = block.attrs.as_ref().ok_or("Should have attrs")?;
+	assert_eq!(attrs.get("file").and_then(|v| v.as_str()), Some("src/main.rs"));
+	assert_eq!(attrs.get("title").and_then(|v| v.as_str()), Some("Main file"));
+	assert_eq!(attrs.get("no_run").and_then(|v| v.as_bool()), Some(true));
+	assert_eq!((block.start_line, block.end_line), (Some(2), Some(4)));
+	assert!(replaced.ends_with("```py\nprint(\"bye\")\n```\n"));
+	assert!(md_replace_block(content, 2, 3, "x").is_err());
+
+	Ok(())
+}
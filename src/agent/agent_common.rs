@@ -1,10 +1,14 @@
-use crate::agent::PromptPart;
 use crate::agent::agent_options::AgentOptions;
 use crate::agent::agent_ref::AgentRef;
+use crate::agent::agent_tool::AgentTool;
+use crate::agent::{AgentMeta, PromptPart};
+use crate::dir_context::AipackConfig;
 use crate::{Error, Result};
 use genai::ModelName;
 use genai::chat::ChatOptions;
+use serde_json::Value;
 use simple_fs::SPath;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// A sync efficient & friendly Agent containing the AgentInner
@@ -15,6 +19,12 @@ pub struct Agent {
 	model_resolved: ModelName,
 	agent_options_ov: Option<Arc<AgentOptions>>,
 	genai_chat_options: Arc<ChatOptions>,
+	/// The config the agent options were resolved from (None for inline/test agents)
+	config: Option<AipackConfig>,
+	/// The resolved `# Meta` params values (`args` in Lua), defaults until `with_args`
+	args: Arc<Value>,
+	/// The tools declared by the `# Before All` (see `aip.flow.before_all_response`)
+	tools: Arc<Vec<AgentTool>>,
 }
 
 /// Constructor from AgentInner
@@ -37,12 +47,18 @@ impl Agent {
 		// -- Initial genai chat_options
 		let chat_options = inner.agent_options.to_genai_options(None);
 
+		// -- Default args
+		let args = inner.meta.default_args();
+
 		Ok(Agent {
 			inner,
 			model,
 			model_resolved,
 			agent_options_ov: None,
 			genai_chat_options: chat_options.into(),
+			config: None,
+			args: Arc::new(args),
+			tools: Arc::default(),
 		})
 	}
 
@@ -66,8 +82,28 @@ impl Agent {
 			model_resolved,
 			agent_options_ov: Some(Arc::new(options)),
 			genai_chat_options: chat_options.into(),
+			config: self.config.clone(),
+			args: self.args.clone(),
+			tools: self.tools.clone(),
 		})
 	}
+
+	pub fn with_config(mut self, config: AipackConfig) -> Agent {
+		self.config = Some(config);
+		self
+	}
+
+	/// Set the args resolved from the `--arg name=value` (see `AgentMeta::resolve_args`)
+	pub fn with_args(mut self, args: Value) -> Agent {
+		self.args = Arc::new(args);
+		self
+	}
+
+	/// Set the tools declared by the `# Before All` stage
+	pub fn with_tools(mut self, tools: Vec<AgentTool>) -> Agent {
+		self.tools = Arc::new(tools);
+		self
+	}
 }
 
 /// Getters
@@ -97,6 +133,22 @@ impl Agent {
 			.unwrap_or(&self.inner.agent_options)
 	}
 
+	pub fn config(&self) -> Option<&AipackConfig> {
+		self.config.as_ref()
+	}
+
+	pub fn meta(&self) -> &AgentMeta {
+		&self.inner.meta
+	}
+
+	pub fn args(&self) -> &Value {
+		&self.args
+	}
+
+	pub fn tools(&self) -> &[AgentTool] {
+		&self.tools
+	}
+
 	pub fn agent_ref(&self) -> &AgentRef {
 		&self.inner.agent_ref
 	}
@@ -153,6 +205,10 @@ impl Agent {
 		self.inner.prompt_parts.iter().collect()
 	}
 
+	pub fn partials(&self) -> &HashMap<String, String> {
+		&self.inner.partials
+	}
+
 	pub fn data_script(&self) -> Option<&str> {
 		self.inner.data_script.as_deref()
 	}
@@ -234,11 +290,17 @@ pub(super) struct AgentInner {
 	/// The model that came from the options
 	pub model_name: Option<ModelName>,
 
+	/// The `# Meta` section (description, params, examples)
+	pub meta: AgentMeta,
+
 	pub before_all_script: Option<String>,
 
 	/// Contains the instruction, system, assistant in order of the file
 	pub prompt_parts: Vec<PromptPart>,
 
+	/// The handlebars partials of the prompt parts (from the `# Meta` `partials` and `extends`)
+	pub partials: HashMap<String, String>,
+
 	/// Script
 	pub data_script: Option<String>,
 	pub output_script: Option<String>,
@@ -1,11 +1,14 @@
 use crate::Result;
+use crate::agent::agent_meta::AgentMeta;
 use crate::agent::agent_options::AgentOptions;
 use crate::agent::agent_ref::AgentRef;
 use crate::agent::{Agent, AgentInner, PartKind, PromptPart, get_prompt_part_kind, get_prompt_part_options_str};
+use crate::model::Stage;
 use crate::support::md::InBlockState;
 use crate::support::tomls::parse_toml_into_json;
 use genai::ModelName;
 use simple_fs::{SPath, read_to_string};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -15,6 +18,30 @@ pub struct AgentDoc {
 	raw_content: String,
 }
 
+/// The parsed sections of an agent doc (eventually merged with its `extends` base, see `merge_base`)
+#[derive(Debug, Default)]
+pub struct AgentSections {
+	/// The `# Options` of the doc (merged over the config options)
+	pub options_ov: Option<AgentOptions>,
+	pub meta: AgentMeta,
+	pub before_all_script: Option<String>,
+	pub data_script: Option<String>,
+	pub prompt_parts: Vec<PromptPart>,
+	pub output_script: Option<String>,
+	pub after_all_script: Option<String>,
+}
+
+/// A stage Lua code block of an agent doc, with its lines in the doc (e.g., for `aip lint-lua`)
+#[derive(Debug)]
+pub struct StageLuaBlock {
+	pub stage: Stage,
+	/// The line of the opening fence (1 based)
+	pub start_line: usize,
+	/// The line of the closing fence (1 based)
+	pub end_line: usize,
+	pub content: String,
+}
+
 // region:    --- Capture State
 
 #[derive(Debug)]
@@ -81,15 +108,111 @@ impl AgentDoc {
 		Ok(Self { spath, raw_content })
 	}
 
-	pub fn into_agent(self, name: &str, agent_ref: AgentRef, options: AgentOptions) -> Result<Agent> {
-		let agent_inner = self.into_agent_inner(name, agent_ref, options)?;
-		let agent = Agent::new(agent_inner)?;
-		Ok(agent)
+	/// Parse only the `# Meta` section (e.g., for `aip list`), without building the agent.
+	pub fn meta(&self) -> Result<AgentMeta> {
+		match extract_meta_toml(&self.raw_content) {
+			Some(meta_toml) => AgentMeta::from_toml(&meta_toml),
+			None => Ok(AgentMeta::default()),
+		}
+	}
+
+	pub fn spath(&self) -> &SPath {
+		&self.spath
+	}
+
+	/// Build the agent from the (composed) sections of this doc and the resolved partials (name to content).
+	pub fn into_agent_with_sections(
+		self,
+		name: &str,
+		agent_ref: AgentRef,
+		agent_options: AgentOptions,
+		sections: AgentSections,
+		partials: HashMap<String, String>,
+	) -> Result<Agent> {
+		let AgentSections {
+			options_ov,
+			meta,
+			before_all_script,
+			data_script,
+			prompt_parts,
+			output_script,
+			after_all_script,
+		} = sections;
+
+		let agent_options = match options_ov {
+			Some(options_ov) => agent_options.merge(options_ov)?,
+			None => agent_options,
+		};
+
+		// -- Get the model name
+		let model_name = agent_options.model().map(ModelName::from);
+
+		// -- Build the AgentInner
+		let agent_inner = AgentInner {
+			agent_options: Arc::new(agent_options),
+
+			name: name.to_string(),
+			agent_ref,
+
+			file_name: self.spath.name().to_string(),
+			file_path: self.spath.as_str().to_string(),
+
+			model_name,
+
+			meta,
+
+			before_all_script,
+			data_script,
+
+			prompt_parts,
+			partials,
+
+			output_script,
+			after_all_script,
+		};
+
+		Agent::new(agent_inner)
+	}
+
+	/// Parse the sections of this doc.
+	pub fn sections(&self) -> Result<AgentSections> {
+		let LexedDoc {
+			options_toml,
+			before_all_script,
+			data_script,
+			prompt_parts,
+			output_script,
+			after_all_script,
+			..
+		} = self.lex()?;
+
+		let options_ov: Option<AgentOptions> = if let Some(options_toml) = options_toml {
+			Some(AgentOptions::from_options_value(parse_toml_into_json(&options_toml)?)?)
+		} else {
+			None
+		};
+
+		Ok(AgentSections {
+			options_ov,
+			meta: self.meta()?,
+			before_all_script,
+			data_script,
+			prompt_parts,
+			output_script,
+			after_all_script,
+		})
+	}
+
+	/// The Lua code blocks of the `# Before All`, `# Data`, `# Output`, and `# After All` sections,
+	/// with their lines in the doc (from the same pass as `sections()`).
+	///
+	/// NOTE: A block not closed is not returned.
+	pub fn stage_lua_blocks(&self) -> Result<Vec<StageLuaBlock>> {
+		Ok(self.lex()?.stage_lua_blocks)
 	}
 
-	/// Internal method to create the first part of the agent inner
 	/// This is sort of a Lexer, but very customize to extracting the Agent parts
-	fn into_agent_inner(self, name: &str, agent_ref: AgentRef, agent_options: AgentOptions) -> Result<AgentInner> {
+	fn lex(&self) -> Result<LexedDoc> {
 		let mut capture_mode = CaptureMode::None;
 
 		// -- The buffers
@@ -103,6 +226,11 @@ impl AgentDoc {
 		// the vec String allow to be more efficient (as join later is more efficient)
 		let mut current_part: Option<CurrentPromptPart> = None;
 
+		// -- The stage Lua blocks spans
+		let mut stage_lua_blocks: Vec<StageLuaBlock> = Vec::new();
+		// (start_line, start index in the stage script buffer) of the stage code block being captured
+		let mut stage_block_start: (usize, usize) = (0, 0);
+
 		// -- The actual parsing
 		// NOTE: Need custom parser/lexer given the nature of the agent format.
 		//       Markdown parsers tend to be lossless and would need wuite a bit of extra post-processing anyway.
@@ -110,7 +238,7 @@ impl AgentDoc {
 
 		let mut block_state = InBlockState::Out;
 
-		for line in self.raw_content.lines() {
+		for (idx, line) in self.raw_content.lines().enumerate() {
 			// Update block state regardless of capture mode
 			let old_block_state = block_state;
 			block_state = block_state.compute_new(line);
@@ -150,6 +278,10 @@ impl AgentDoc {
 				continue;
 			}
 
+			let is_lua_block_open =
+				(line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out();
+			let is_block_close = line.starts_with("```") && block_state.is_out() && !old_block_state.is_out();
+
 			// Handle content based on current capture mode
 			match capture_mode {
 				CaptureMode::None => {}
@@ -169,7 +301,7 @@ impl AgentDoc {
 					}
 				}
 				CaptureMode::OptionsTomlBlock => {
-					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+					if is_block_close {
 						capture_mode = CaptureMode::None;
 						continue;
 					} else {
@@ -179,14 +311,22 @@ impl AgentDoc {
 
 				// -- Before All
 				CaptureMode::BeforeAllSection => {
-					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
+					if is_lua_block_open {
 						capture_mode = CaptureMode::BeforeAllCodeBlock;
+						stage_block_start = (idx + 1, before_all_script.len());
 						continue;
 					}
 				}
 				CaptureMode::BeforeAllCodeBlock => {
-					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+					if is_block_close {
 						capture_mode = CaptureMode::None;
+						push_stage_lua_block(
+							&mut stage_lua_blocks,
+							Stage::BeforeAll,
+							stage_block_start,
+							idx + 1,
+							&before_all_script,
+						);
 						continue;
 					} else {
 						push_line(&mut before_all_script, line);
@@ -195,14 +335,22 @@ impl AgentDoc {
 
 				// -- Data
 				CaptureMode::DataSection => {
-					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
+					if is_lua_block_open {
 						capture_mode = CaptureMode::DataCodeBlock;
+						stage_block_start = (idx + 1, data_script.len());
 						continue;
 					}
 				}
 				CaptureMode::DataCodeBlock => {
-					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+					if is_block_close {
 						capture_mode = CaptureMode::None;
+						push_stage_lua_block(
+							&mut stage_lua_blocks,
+							Stage::Data,
+							stage_block_start,
+							idx + 1,
+							&data_script,
+						);
 						continue;
 					} else {
 						push_line(&mut data_script, line);
@@ -211,14 +359,22 @@ impl AgentDoc {
 
 				// -- Output
 				CaptureMode::OutputSection => {
-					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
+					if is_lua_block_open {
 						capture_mode = CaptureMode::OutputCodeBlock;
+						stage_block_start = (idx + 1, output_script.len());
 						continue;
 					}
 				}
 				CaptureMode::OutputCodeBlock => {
-					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+					if is_block_close {
 						capture_mode = CaptureMode::None;
+						push_stage_lua_block(
+							&mut stage_lua_blocks,
+							Stage::Output,
+							stage_block_start,
+							idx + 1,
+							&output_script,
+						);
 						continue;
 					} else {
 						push_line(&mut output_script, line);
@@ -227,14 +383,22 @@ impl AgentDoc {
 
 				// -- After All
 				CaptureMode::AfterAllSection => {
-					if (line.starts_with("```lua") || line.starts_with("````lua")) && old_block_state.is_out() {
+					if is_lua_block_open {
 						capture_mode = CaptureMode::AfterAllCodeBlock;
+						stage_block_start = (idx + 1, after_all_script.len());
 						continue;
 					}
 				}
 				CaptureMode::AfterAllCodeBlock => {
-					if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+					if is_block_close {
 						capture_mode = CaptureMode::None;
+						push_stage_lua_block(
+							&mut stage_lua_blocks,
+							Stage::AfterAll,
+							stage_block_start,
+							idx + 1,
+							&after_all_script,
+						);
 						continue;
 					} else {
 						push_line(&mut after_all_script, line);
@@ -247,45 +411,58 @@ impl AgentDoc {
 		finalize_current_prompt_part(&mut current_part, &mut prompt_parts);
 
 		// -- Returning the data
-
-		let options_toml = buffer_to_string(options_toml);
-
-		let agent_options_ov: Option<AgentOptions> = if let Some(options_toml) = options_toml {
-			Some(AgentOptions::from_options_value(parse_toml_into_json(&options_toml)?)?)
-		} else {
-			None
-		};
-
-		let agent_options = match agent_options_ov {
-			Some(agent_options_ov) => agent_options.merge(agent_options_ov)?,
-			None => agent_options,
-		};
-
-		// -- Get the model name
-		let model_name = agent_options.model().map(ModelName::from);
-
-		// -- Build the AgentInner
-		let agent_inner = AgentInner {
-			agent_options: Arc::new(agent_options),
-
-			name: name.to_string(),
-			agent_ref,
-
-			file_name: self.spath.name().to_string(),
-			file_path: self.spath.as_str().to_string(),
-
-			model_name,
-
+		Ok(LexedDoc {
+			options_toml: buffer_to_string(options_toml),
 			before_all_script: buffer_to_string(before_all_script),
 			data_script: buffer_to_string(data_script),
-
 			prompt_parts,
-
 			output_script: buffer_to_string(output_script),
 			after_all_script: buffer_to_string(after_all_script),
+			stage_lua_blocks,
+		})
+	}
+}
+
+/// Composition
+impl AgentSections {
+	/// Merge the `extends` base sections under these ones:
+	/// - The options and meta are merged (these ones win).
+	/// - The scripts and prompt parts of the base are used when not in these ones
+	///   (the base prompt parts are also the `base_system`, `base_instruction`, `base_assistant` partials).
+	pub fn merge_base(self, base: AgentSections) -> Result<AgentSections> {
+		let options_ov = match (base.options_ov, self.options_ov) {
+			(Some(base_options), Some(options)) => Some(base_options.merge(options)?),
+			(base_options, options) => options.or(base_options),
+		};
+		let prompt_parts = if self.prompt_parts.is_empty() {
+			base.prompt_parts
+		} else {
+			self.prompt_parts
 		};
 
-		Ok(agent_inner)
+		Ok(AgentSections {
+			options_ov,
+			meta: self.meta.merge_base(base.meta),
+			before_all_script: self.before_all_script.or(base.before_all_script),
+			data_script: self.data_script.or(base.data_script),
+			prompt_parts,
+			output_script: self.output_script.or(base.output_script),
+			after_all_script: self.after_all_script.or(base.after_all_script),
+		})
+	}
+
+	/// The `base_system`, `base_instruction`, and `base_assistant` partials of these prompt parts (when used as base)
+	pub fn prompt_part_partials(&self) -> HashMap<String, String> {
+		let mut partials: HashMap<String, String> = HashMap::new();
+		for part in self.prompt_parts.iter() {
+			let name = match part.kind {
+				PartKind::System => "base_system",
+				PartKind::Instruction => "base_instruction",
+				PartKind::Assistant => "base_assistant",
+			};
+			partials.entry(name.to_string()).or_default().push_str(&part.content);
+		}
+		partials
 	}
 }
 
@@ -297,10 +474,45 @@ impl AgentDoc {
 		let raw_content = content.into();
 		Ok(Self { spath, raw_content })
 	}
+
+	/// Build the agent from this doc only (without resolving the `# Meta` `extends` and `partials`, see `find_agent`)
+	pub fn into_agent(self, name: &str, agent_ref: AgentRef, options: AgentOptions) -> Result<Agent> {
+		let sections = self.sections()?;
+		self.into_agent_with_sections(name, agent_ref, options, sections, HashMap::new())
+	}
 }
 
 // region:    --- Support
 
+/// The result of the `AgentDoc::lex` pass (the options toml is not parsed yet)
+struct LexedDoc {
+	options_toml: Option<String>,
+	before_all_script: Option<String>,
+	data_script: Option<String>,
+	prompt_parts: Vec<PromptPart>,
+	output_script: Option<String>,
+	after_all_script: Option<String>,
+	stage_lua_blocks: Vec<StageLuaBlock>,
+}
+
+/// Push the stage Lua block ending at `end_line`, with its content from the `start` index of the stage script buffer
+fn push_stage_lua_block(
+	blocks: &mut Vec<StageLuaBlock>,
+	stage: Stage,
+	(start_line, start): (usize, usize),
+	end_line: usize,
+	script: &[&str],
+) {
+	let content = script[start..].concat();
+	let content = content.strip_suffix('\n').unwrap_or(&content).to_string();
+	blocks.push(StageLuaBlock {
+		stage,
+		start_line,
+		end_line,
+		content,
+	});
+}
+
 /// Type of the function below and the `into_agent_inner` lexer
 /// (PartKind, PartOptionsStr, Content)
 struct CurrentPromptPart<'a>(PartKind, Option<String>, Vec<&'a str>);
@@ -324,6 +536,39 @@ fn finalize_current_prompt_part(current_part: &mut Option<CurrentPromptPart<'_>>
 	}
 }
 
+/// Extract the content of the first toml block of the `# Meta` section (if any).
+/// NOTE: The `# Meta` section is ignored by the `into_agent_inner` lexer (unknown section).
+fn extract_meta_toml(raw_content: &str) -> Option<String> {
+	let mut block_state = InBlockState::Out;
+	let mut in_meta_section = false;
+	let mut meta_toml: Option<Vec<&str>> = None;
+
+	for line in raw_content.lines() {
+		let old_block_state = block_state;
+		block_state = block_state.compute_new(line);
+
+		if let Some(meta_toml) = meta_toml.as_mut() {
+			if line.starts_with("```") && block_state.is_out() && !old_block_state.is_out() {
+				break;
+			}
+			push_line(meta_toml, line);
+			continue;
+		}
+
+		if block_state.is_out() && line.starts_with('#') && !line.starts_with("##") {
+			in_meta_section = line[1..].trim().eq_ignore_ascii_case("meta");
+			continue;
+		}
+
+		if in_meta_section && (line.starts_with("```toml") || line.starts_with("````toml")) && old_block_state.is_out()
+		{
+			meta_toml = Some(Vec::new());
+		}
+	}
+
+	meta_toml.and_then(buffer_to_string)
+}
+
 /// Push a new line and the a \n to respect the new line
 fn push_line<'a, 'b, 'c: 'b>(content: &'a mut Vec<&'b str>, line: &'c str) {
 	content.push(line);
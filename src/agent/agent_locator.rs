@@ -2,26 +2,47 @@
 //!
 
 use crate::agent::agent_ref::{AgentRef, PartialAgentRef};
-use crate::agent::{Agent, AgentDoc, AgentOptions};
-use crate::dir_context::{DirContext, PathResolver, find_to_run_pack_dir};
+use crate::agent::{Agent, AgentDoc, AgentSections};
+use crate::dir_context::{PathResolver, find_to_run_pack_dir};
 use crate::runtime::Runtime;
-use crate::support::tomls::parse_toml_into_json;
 use crate::types::LocalPackRef;
 use crate::{Error, Result};
 use simple_fs::{SPath, read_to_string};
+use std::collections::HashMap;
+
+/// The max depth of the `# Meta` `extends` chain (catches the cycles)
+const MAX_EXTENDS_DEPTH: usize = 8;
 
 /// Find an agent by it's name, dir_context, and eventual base_dir
 /// Note - When base_dir, it means that this will be the relative path to look for this agent if relative
 ///        This is used for the aip.agent.run, to make sure we are relative to the caller agent
 pub fn find_agent(name: &str, runtime: &Runtime, base_dir: Option<&SPath>) -> Result<Agent> {
+	// Load the merged base and workspace config
+	let config = runtime.dir_context().load_config()?;
+
+	let (found_path, agent_ref) = locate_agent_file(name, runtime, base_dir)?;
+
+	// -- The base options (with the eventual `[pack_options."namespace@pack_name"]` of the config)
+	let base_options = match &agent_ref {
+		AgentRef::LocalPath(_) => config.agent_options(None)?,
+		AgentRef::PackRef(local_pack_ref) => config.agent_options(Some(local_pack_ref.identity()))?,
+	};
+
+	// -- Build the agent (with the eventual `# Meta` `extends` and `partials`)
+	let doc = AgentDoc::from_file(found_path)?;
+	let (sections, partials) = compose_agent_doc(runtime, &doc, 0)?;
+	let agent = doc.into_agent_with_sections(name, agent_ref, base_options, sections, partials)?;
+
+	Ok(agent.with_config(config))
+}
+
+/// Locate the agent file by it's name (see `find_agent`), with its final agent ref
+fn locate_agent_file(name: &str, runtime: &Runtime, base_dir: Option<&SPath>) -> Result<(SPath, AgentRef)> {
 	let dir_context = runtime.dir_context();
 
 	let partial_agent_ref = PartialAgentRef::new(name)?;
 
-	// Merge the workspace and base agent options
-	let base_options = load_and_merge_configs_agent_options(dir_context)?;
-
-	let agent = match partial_agent_ref {
+	match partial_agent_ref {
 		// -- If local path, we try to find the .aip and run it
 		PartialAgentRef::LocalPath(local_path) => {
 			let path = SPath::new(&local_path);
@@ -39,11 +60,8 @@ pub fn find_agent(name: &str, runtime: &Runtime, base_dir: Option<&SPath>) -> Re
 					"No agent found for local path: '{local_path}'\n   (full path: {path})"
 				))
 			})?;
-			let doc = AgentDoc::from_file(found_path)?;
 
-			let agent_ref = AgentRef::LocalPath(local_path.to_string());
-
-			doc.into_agent(name, agent_ref, base_options)?
+			Ok((found_path, AgentRef::LocalPath(local_path.to_string())))
 		}
 		PartialAgentRef::PackRef(pack_ref) => {
 			let pack_dir = find_to_run_pack_dir(dir_context, &pack_ref)?;
@@ -62,15 +80,83 @@ pub fn find_agent(name: &str, runtime: &Runtime, base_dir: Option<&SPath>) -> Re
 
 			// -- Buid the final agent_ref with the resolved namespace
 			// TODO: Need to cleanup this strategy. Perhaps have PartialPackRef, and PackRef with namespace and pack_name
-			let agent_ref = AgentRef::PackRef(LocalPackRef::from_partial(pack_dir, pack_ref));
+			let local_pack_ref = LocalPackRef::from_partial(pack_dir, pack_ref);
+
+			Ok((found_path, AgentRef::PackRef(local_pack_ref)))
+		}
+	}
+}
 
-			// -- Build and return the agent
-			let doc = AgentDoc::from_file(found_path)?;
-			doc.into_agent(name, agent_ref, base_options)?
+/// Parse the sections of the agent doc, merged with its `# Meta` `extends` chain,
+/// and load the `# Meta` `partials` (the base ones first, then the `base_...` prompt parts, then this doc ones).
+///
+/// NOTE: The `extends` and `partials` paths are relative to the doc file (or pack refs).
+fn compose_agent_doc(
+	runtime: &Runtime,
+	doc: &AgentDoc,
+	depth: usize,
+) -> Result<(AgentSections, HashMap<String, String>)> {
+	let sections = doc.sections()?;
+	let doc_dir = doc.spath().parent();
+	let mut partials: HashMap<String, String> = HashMap::new();
+
+	let sections = match sections.meta.extends().map(|extends| extends.to_string()) {
+		Some(extends) => {
+			if depth >= MAX_EXTENDS_DEPTH {
+				return Err(Error::custom(format!(
+					"Agent '{}' extends chain is deeper than {MAX_EXTENDS_DEPTH} (cycle?) at extends = '{extends}'",
+					doc.spath()
+				)));
+			}
+			let (base_path, _) = locate_agent_file(&extends, runtime, doc_dir.as_ref()).map_err(|err| {
+				Error::custom(format!(
+					"Agent '{}' extends = '{extends}' not found. Cause: {err}",
+					doc.spath()
+				))
+			})?;
+			let base_doc = AgentDoc::from_file(base_path)?;
+			let (base_sections, base_partials) = compose_agent_doc(runtime, &base_doc, depth + 1)?;
+			partials.extend(base_partials);
+			partials.extend(base_sections.prompt_part_partials());
+			sections.merge_base(base_sections)?
 		}
+		None => sections,
 	};
 
-	Ok(agent)
+	for (name, path) in sections.meta.partials() {
+		let partial_path = resolve_partial_path(runtime, path, doc_dir.as_ref())?;
+		let content = read_to_string(&partial_path).map_err(|err| {
+			Error::custom(format!(
+				"Agent '{}' partial '{name}' cannot be read from '{partial_path}'. Cause: {err}",
+				doc.spath()
+			))
+		})?;
+		partials.insert(name.to_string(), content);
+	}
+
+	Ok((sections, partials))
+}
+
+/// Resolve a `# Meta` partial path, a pack ref file (e.g., `ns@pack/partials/style.md`) or relative to the doc dir
+fn resolve_partial_path(runtime: &Runtime, path: &str, doc_dir: Option<&SPath>) -> Result<SPath> {
+	match PartialAgentRef::new(path)? {
+		PartialAgentRef::PackRef(pack_ref) => {
+			let pack_dir = find_to_run_pack_dir(runtime.dir_context(), &pack_ref)?;
+			let sub_path = pack_ref.sub_path.as_deref().ok_or_else(|| {
+				Error::custom(format!(
+					"Partial '{path}' must be a file of the pack (e.g., 'ns@pack/partials/style.md')"
+				))
+			})?;
+			Ok(pack_dir.path.join(sub_path))
+		}
+		PartialAgentRef::LocalPath(local_path) => {
+			let local_path = SPath::new(local_path);
+			match doc_dir {
+				Some(doc_dir) if !local_path.is_absolute() => Ok(doc_dir.join(local_path)),
+				_ => Ok(local_path),
+			}
+		}
+	}
 }
 
 // region:    --- Support
@@ -98,38 +184,6 @@ pub fn possible_aip_paths(path: SPath, as_dir: bool) -> Vec<SPath> {
 	}
 }
 
-/// Loads the base agent options.
-///
-pub fn load_and_merge_configs_agent_options(dir_context: &DirContext) -> Result<AgentOptions> {
-	let config_paths = dir_context.aipack_paths().get_wks_config_toml_paths()?;
-
-	let mut all_options = Vec::new();
-
-	for config_path in config_paths {
-		let config_content = read_to_string(&config_path)?;
-		let config_value = parse_toml_into_json(&config_content)?;
-
-		let options = AgentOptions::from_config_value(config_value).map_err(|err| Error::Config {
-			path: config_path.to_string(),
-			reason: err.to_string(),
-		})?;
-		all_options.push(options);
-	}
-	let mut options: Option<AgentOptions> = None;
-	for item_options in all_options {
-		options = match options {
-			Some(options) => Some(options.merge(item_options)?),
-			None => Some(item_options),
-		}
-	}
-
-	let Some(options) = options else {
-		return Err(Error::custom("No agent options found"));
-	};
-
-	Ok(options)
-}
-
 // endregion: --- Support
 
 // region:    --- Tests
@@ -139,7 +193,7 @@ mod tests {
 	type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 	use super::*;
-	use crate::_test_support::assert_contains;
+	use crate::_test_support::{assert_contains, gen_test_dir_path, remove_test_dir, save_file_content};
 	use crate::runtime::Runtime;
 	use simple_fs::SPath;
 
@@ -235,6 +289,108 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_agent_locator_find_agent_extends_and_partials() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let test_dir = gen_test_dir_path();
+		let fx_base = r#"
+# Meta
+
+```toml
+description = "Base review"
+[partials]
+style = "partials/style.md"
+```
+
+# Options
+
+```toml
+input_concurrency = 3
+temperature = 0.2
+```
+
+# Data
+
+```lua
+return "base data"
+```
+
+# System
+
+You are a reviewer. {{> style}}
+
+# Instruction
+
+Review the input.
+"#;
+		let fx_child = r#"
+# Meta
+
+```toml
+extends = "../base.aip"
+[partials]
+rules = "partials/rules.md"
+```
+
+# Options
+
+```toml
+temperature = 0.5
+```
+
+# Instruction
+
+{{> base_instruction}}
+{{> rules}}
+"#;
+		save_file_content(&test_dir.join("base.aip"), fx_base)?;
+		save_file_content(&test_dir.join("partials/style.md"), "Be concise.")?;
+		save_file_content(&test_dir.join("child/child.aip"), fx_child)?;
+		save_file_content(&test_dir.join("child/partials/rules.md"), "No nits.")?;
+		save_file_content(
+			&test_dir.join("cycle.aip"),
+			"# Meta\n\n```toml\nextends = \"cycle.aip\"\n```\n",
+		)?;
+		save_file_content(
+			&test_dir.join("orphan.aip"),
+			"# Meta\n\n```toml\nextends = \"nope.aip\"\n```\n",
+		)?;
+		let dir = test_dir.canonicalize()?;
+
+		// -- Exec
+		let agent = find_agent(dir.join("child/child.aip").as_str(), &runtime, None);
+		let cycle_res = find_agent(dir.join("cycle.aip").as_str(), &runtime, None);
+		let not_found_res = find_agent(dir.join("orphan.aip").as_str(), &runtime, None);
+		remove_test_dir(&test_dir)?;
+		let agent = agent?;
+
+		// -- Check
+		assert_eq!(agent.data_script().map(|v| v.trim()), Some(r#"return "base data""#));
+		assert_eq!(agent.prompt_parts().len(), 1, "child instruction only");
+		assert_eq!(agent.meta().description(), Some("Base review"));
+		assert_eq!(agent.options().input_concurrency(), Some(3));
+		assert_eq!(agent.options().temperature(), Some(0.5));
+		let partials = agent.partials();
+		assert_eq!(partials.get("style").map(|v| v.as_str()), Some("Be concise."));
+		assert_eq!(partials.get("rules").map(|v| v.as_str()), Some("No nits."));
+		assert_contains(
+			partials.get("base_instruction").ok_or("no base_instruction")?,
+			"Review the input.",
+		);
+		assert_contains(partials.get("base_system").ok_or("no base_system")?, "{{> style}}");
+		assert_contains(
+			&cycle_res.err().ok_or("cycle should fail")?.to_string(),
+			"extends chain is deeper",
+		);
+		assert_contains(
+			&not_found_res.err().ok_or("should fail")?.to_string(),
+			"extends = 'nope.aip' not found",
+		);
+
+		Ok(())
+	}
+
 	// endregion: --- find_agent
 
 	// region:    --- possiple_aip_paths
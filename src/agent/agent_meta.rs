@@ -0,0 +1,419 @@
+//! The agent `# Meta` section (toml block), which describes the agent for the users.
+//!
+//! ```toml
+//! description = "Proofread the given files"
+//! examples = ["aip run my@proof -f README.md --arg style=formal"]
+//!
+//! # The base agent (pack ref, or path relative to this agent file), see `AgentSections::merge_base`
+//! extends = "my@base/proof-base.aip"
+//!
+//! # The handlebars partials of the prompt parts (e.g., `{{> style}}`), pack ref or relative path
+//! [partials]
+//! style = "partials/style.md"
+//!
+//! [[params]]
+//! name = "style"
+//! type = "string"  # string | number | integer | boolean (default "string")
+//! default = "casual"
+//! description = "The writing style"
+//! ```
+//!
+//! The `aip run ... --arg name=value` (and `--args-json '{...}'`) values are validated against the `params`,
+//! and exposed to Lua (and handlebars) as `args` (with the defaults).
+
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct AgentMeta {
+	description: Option<String>,
+
+	#[serde(default)]
+	params: Vec<AgentParam>,
+
+	#[serde(default)]
+	examples: Vec<String>,
+
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	extends: Option<String>,
+
+	/// The partial name to its file (pack ref or relative path)
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	partials: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AgentParam {
+	pub name: String,
+
+	#[serde(rename = "type", default)]
+	pub kind: ParamKind,
+
+	pub default: Option<Value>,
+
+	pub description: Option<String>,
+
+	#[serde(default)]
+	pub required: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, strum::IntoStaticStr)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ParamKind {
+	#[default]
+	String,
+	Number,
+	Integer,
+	Boolean,
+}
+
+/// Constructor
+impl AgentMeta {
+	/// Parse the `# Meta` toml block content.
+	pub fn from_toml(toml_content: &str) -> Result<Self> {
+		let value = parse_toml_into_json(toml_content)?;
+		let meta: AgentMeta = serde_json::from_value(value)
+			.map_err(|err| Error::custom(format!("# Meta section is invalid. Cause: {err}")))?;
+
+		// -- Validate the params
+		for (idx, param) in meta.params.iter().enumerate() {
+			if meta.params[..idx].iter().any(|p| p.name == param.name) {
+				return Err(Error::custom(format!(
+					"# Meta param '{}' is declared more than once",
+					param.name
+				)));
+			}
+			if let Some(default) = param.default.as_ref()
+				&& !param.kind.is_value_of_kind(default)
+			{
+				return Err(Error::custom(format!(
+					"# Meta param '{}' default '{default}' is not of type '{}'",
+					param.name,
+					param.kind.as_str()
+				)));
+			}
+		}
+
+		Ok(meta)
+	}
+}
+
+/// Getters
+impl AgentMeta {
+	pub fn description(&self) -> Option<&str> {
+		self.description.as_deref()
+	}
+
+	pub fn params(&self) -> &[AgentParam] {
+		&self.params
+	}
+
+	pub fn examples(&self) -> &[String] {
+		&self.examples
+	}
+
+	pub fn extends(&self) -> Option<&str> {
+		self.extends.as_deref()
+	}
+
+	pub fn partials(&self) -> &BTreeMap<String, String> {
+		&self.partials
+	}
+}
+
+/// Composition
+impl AgentMeta {
+	/// Merge the `extends` base meta under this one
+	/// (the base description, examples, and params, unless declared by this one).
+	pub fn merge_base(mut self, base: AgentMeta) -> AgentMeta {
+		if self.description.is_none() {
+			self.description = base.description;
+		}
+		if self.examples.is_empty() {
+			self.examples = base.examples;
+		}
+		let mut params: Vec<AgentParam> = base
+			.params
+			.into_iter()
+			.filter(|base_param| !self.params.iter().any(|p| p.name == base_param.name))
+			.collect();
+		params.append(&mut self.params);
+		self.params = params;
+
+		self
+	}
+}
+
+/// Args
+impl AgentMeta {
+	/// The args object with only the param defaults.
+	pub fn default_args(&self) -> Value {
+		let args: Map<String, Value> = self
+			.params
+			.iter()
+			.filter_map(|p| p.default.clone().map(|default| (p.name.clone(), default)))
+			.collect();
+		Value::Object(args)
+	}
+
+	/// Resolve the `--args-json` object and the `--arg name=value` CLI args (validated against the params)
+	/// into the args object (with the defaults).
+	///
+	/// Precedence: defaults, then `json_args`, then `cli_args`.
+	///
+	/// NOTE: A boolean param can be given as `--arg name` (for `true`).
+	pub fn resolve_args(&self, cli_args: &[&str], json_args: Option<&Value>) -> Result<Value> {
+		let mut args = match self.default_args() {
+			Value::Object(args) => args,
+			_ => Map::new(),
+		};
+
+		// -- Merge the json args
+		if let Some(json_args) = json_args {
+			let json_args = json_args
+				.as_object()
+				.ok_or("--args-json must be a JSON object (e.g., '{\"env\": \"prod\"}')")?;
+			for (name, value) in json_args {
+				let param = self.find_param(name, "--args-json")?;
+				if !param.kind.is_value_of_kind(value) {
+					return Err(Error::custom(format!(
+						"--args-json '{name}' value '{value}' is not of type '{}'",
+						param.kind.as_str()
+					)));
+				}
+				args.insert(param.name.clone(), value.clone());
+			}
+		}
+
+		// -- Merge the cli args
+		for cli_arg in cli_args {
+			let (name, value) = match cli_arg.split_once('=') {
+				Some((name, value)) => (name.trim(), Some(value)),
+				None => (cli_arg.trim(), None),
+			};
+
+			let param = self.find_param(name, "--arg")?;
+
+			let value = match (value, param.kind) {
+				(Some(value), kind) => kind.parse_value(value).ok_or_else(|| {
+					Error::custom(format!("--arg {name}='{value}' is not of type '{}'", kind.as_str()))
+				})?,
+				(None, ParamKind::Boolean) => Value::Bool(true),
+				(None, kind) => {
+					return Err(Error::custom(format!(
+						"--arg '{name}' requires a value (e.g., --arg {name}=...) of type '{}'",
+						kind.as_str()
+					)));
+				}
+			};
+
+			args.insert(param.name.clone(), value);
+		}
+
+		// -- Check the required params
+		let missing = self
+			.params
+			.iter()
+			.filter(|p| p.required && !args.contains_key(&p.name))
+			.map(|p| p.name.as_str())
+			.collect::<Vec<_>>();
+		if !missing.is_empty() {
+			return Err(Error::custom(format!(
+				"Missing required agent parameter(s): {} (use --arg name=value)",
+				missing.join(", ")
+			)));
+		}
+
+		Ok(Value::Object(args))
+	}
+}
+
+/// Support
+impl AgentMeta {
+	fn find_param(&self, name: &str, flag: &str) -> Result<&AgentParam> {
+		self.params.iter().find(|p| p.name == name).ok_or_else(|| {
+			let names = self.params.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+			let names = if names.is_empty() {
+				"none".to_string()
+			} else {
+				names.join(", ")
+			};
+			Error::custom(format!(
+				"{flag} '{name}' is not a parameter of this agent (parameters: {names})"
+			))
+		})
+	}
+}
+
+impl AgentParam {
+	/// e.g., `style: string = "casual"` or `max: integer (required)`
+	pub fn signature(&self) -> String {
+		let mut sig = format!("{}: {}", self.name, self.kind.as_str());
+		if let Some(default) = self.default.as_ref() {
+			sig.push_str(&format!(" = {default}"));
+		} else if self.required {
+			sig.push_str(" (required)");
+		}
+		sig
+	}
+}
+
+impl ParamKind {
+	pub fn as_str(&self) -> &'static str {
+		self.into()
+	}
+
+	fn parse_value(&self, value: &str) -> Option<Value> {
+		match self {
+			ParamKind::String => Some(Value::String(value.to_string())),
+			ParamKind::Number => value.trim().parse::<f64>().ok().map(Value::from),
+			ParamKind::Integer => value.trim().parse::<i64>().ok().map(Value::from),
+			ParamKind::Boolean => match value.trim().to_lowercase().as_str() {
+				"true" | "yes" | "1" => Some(Value::Bool(true)),
+				"false" | "no" | "0" => Some(Value::Bool(false)),
+				_ => None,
+			},
+		}
+	}
+
+	fn is_value_of_kind(&self, value: &Value) -> bool {
+		match self {
+			ParamKind::String => value.is_string(),
+			ParamKind::Number => value.is_number(),
+			ParamKind::Integer => value.is_i64() || value.is_u64(),
+			ParamKind::Boolean => value.is_boolean(),
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use serde_json::json;
+
+	const FX_META_TOML: &str = r#"
+description = "Proofread the given files"
+examples = ["aip run my@proof -f README.md --arg style=formal"]
+
+[[params]]
+name = "style"
+default = "casual"
+
+[[params]]
+name = "max_len"
+type = "integer"
+required = true
+
+[[params]]
+name = "dry"
+type = "boolean"
+default = false
+	"#;
+
+	#[test]
+	fn test_agent_meta_resolve_args_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let meta = AgentMeta::from_toml(FX_META_TOML)?;
+
+		// -- Exec
+		let args = meta.resolve_args(&["max_len=120", "dry"], None)?;
+
+		// -- Check
+		assert_eq!(meta.description(), Some("Proofread the given files"));
+		assert_eq!(meta.examples().len(), 1);
+		assert_eq!(meta.params()[1].signature(), "max_len: integer (required)");
+		assert_eq!(args, json!({"style": "casual", "max_len": 120, "dry": true}));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_meta_resolve_args_json() -> Result<()> {
+		// -- Setup & Fixtures
+		let meta = AgentMeta::from_toml(FX_META_TOML)?;
+		let fx_json_args = json!({"style": "formal", "max_len": 80});
+
+		// -- Exec
+		let args = meta.resolve_args(&["max_len=100"], Some(&fx_json_args))?;
+
+		// -- Check
+		// the --arg wins over the --args-json
+		assert_eq!(args, json!({"style": "formal", "max_len": 100, "dry": false}));
+		assert!(meta.resolve_args(&[], Some(&json!({"max_len": "ten"}))).is_err());
+		assert!(meta.resolve_args(&[], Some(&json!({"max_len": 1, "color": "red"}))).is_err());
+		assert!(meta.resolve_args(&["max_len=1"], Some(&json!(["style"]))).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_meta_merge_base() -> Result<()> {
+		// -- Setup & Fixtures
+		let base = AgentMeta::from_toml(FX_META_TOML)?;
+		let meta = AgentMeta::from_toml(
+			r#"
+extends = "my@base/proof-base.aip"
+
+[partials]
+style = "partials/style.md"
+
+[[params]]
+name = "style"
+default = "formal"
+		"#,
+		)?;
+
+		// -- Exec
+		let meta = meta.merge_base(base);
+
+		// -- Check
+		assert_eq!(meta.extends(), Some("my@base/proof-base.aip"));
+		assert_eq!(
+			meta.partials().get("style").map(|v| v.as_str()),
+			Some("partials/style.md")
+		);
+		assert_eq!(meta.description(), Some("Proofread the given files"));
+		let names: Vec<&str> = meta.params().iter().map(|p| p.name.as_str()).collect();
+		assert_eq!(names, ["max_len", "dry", "style"]);
+		assert_eq!(meta.default_args()["style"], json!("formal"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_agent_meta_resolve_args_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let meta = AgentMeta::from_toml(FX_META_TOML)?;
+		let fx_cli_args: &[&[&str]] = &[
+			// missing required
+			&["style=formal"],
+			// unknown param
+			&["max_len=10", "color=red"],
+			// wrong type
+			&["max_len=ten"],
+			// value required for non boolean
+			&["max_len"],
+		];
+
+		// -- Exec & Check
+		for cli_args in fx_cli_args {
+			assert!(
+				meta.resolve_args(cli_args, None).is_err(),
+				"Should fail for: {cli_args:?}"
+			);
+		}
+		assert!(AgentMeta::from_toml("[[params]]\nname = \"n\"\ntype = \"integer\"\ndefault = \"x\"").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,5 +1,6 @@
 use crate::Result;
-use genai::chat::ChatOptions;
+use crate::script::{lua_value_to_serde_value, serde_value_to_lua_value};
+use genai::chat::{ChatOptions, ChatResponseFormat, JsonSpec};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::borrow::Cow;
@@ -24,7 +25,54 @@ pub struct AgentOptions {
 
 	allow_run_on_task_fail: Option<bool>,
 
+	/// When true, the tasks of the run share a conversation: the previous turns (user prompt and AI response)
+	/// are sent before the task prompt. Best with `input_concurrency = 1`, for the turns to be in order.
+	conversation: Option<bool>,
+
+	/// The Lua predicate keeping an input (truthy) before its task is created (with `input`, `args`, `options`,
+	/// `before_all`, and `last_run_start` in scope). A single expression, without `return`, is accepted.
+	input_filter: Option<String>,
+
+	/// When true, the tasks whose input (file content, or value) and agent are unchanged since the last `Ok` run
+	/// of the agent (in the runs history) are skipped, marked as cached.
+	incremental: Option<bool>,
+
 	model_aliases: Option<ModelAliases>,
+
+	// Output settings
+	/// When `json`, the AI response must be JSON (validated with `output_schema` if present)
+	output_format: Option<OutputFormat>,
+
+	/// The JSON schema of the AI response (only with `output_format = "json"`)
+	output_schema: Option<Value>,
+
+	/// The GBNF grammar constraining the AI response (forwarded to the providers supporting it, see `grammar_extra_body`)
+	output_grammar: Option<String>,
+
+	/// When true, the token log probabilities are requested (from the providers supporting them, see `logprobs_extra_body`)
+	logprobs: Option<bool>,
+
+	/// The number of most likely alternatives returned for each token (with `logprobs = true`)
+	top_logprobs: Option<u8>,
+
+	/// The max number of continuation requests when the AI response is truncated by the max tokens (none by default)
+	auto_continue: Option<u8>,
+
+	/// The post-processors applied in order to the AI response content, before the output stage
+	/// (built-ins `strip_fences`, `trim`, `ensure_trailing_newline`, `dos2unix`, or a custom Lua `module#function`)
+	post: Option<Vec<String>>,
+
+	// Safety settings
+	/// When true, the `aip.file.save`, `append`, and `save_changes` writes must be approved by the user (diff preview)
+	confirm_writes: Option<bool>,
+
+	/// The workspace relative globs of the files written without approval (with `confirm_writes = true`)
+	confirm_writes_allow: Option<Vec<String>>,
+
+	// Environment settings
+	/// The variables injected in the `aip.cmd.exec` commands (and read with `aip.env.get`).
+	/// The `{ secret = "NAME" }` values are masked in the logs, store records, and TUI output.
+	env: Option<HashMap<String, EnvValue>>,
 }
 
 impl AgentOptions {
@@ -41,10 +89,66 @@ impl AgentOptions {
 		if let Some(top_p) = self.top_p() {
 			chat_options.top_p = Some(top_p);
 		}
+		// response_format (JSON mode, or structured output when schema)
+		match (self.output_format, self.output_schema.as_ref()) {
+			(Some(OutputFormat::Json), Some(schema)) => {
+				chat_options.response_format = Some(JsonSpec::new("output", schema.clone()).into());
+			}
+			(Some(OutputFormat::Json), None) => chat_options.response_format = Some(ChatResponseFormat::JsonMode),
+			(Some(OutputFormat::Text), _) => chat_options.response_format = None,
+			(None, _) => (),
+		}
 		chat_options
 	}
 }
 
+// region:    --- OutputFormat
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+	Text,
+	Json,
+}
+
+impl OutputFormat {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			OutputFormat::Text => "text",
+			OutputFormat::Json => "json",
+		}
+	}
+}
+
+impl mlua::FromLua for OutputFormat {
+	fn from_lua(value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		match value.as_string().map(|s| s.to_string_lossy()).as_deref() {
+			Some("text") => Ok(OutputFormat::Text),
+			Some("json") => Ok(OutputFormat::Json),
+			_ => Err(mlua::Error::runtime(format!(
+				r#"output_format invalid.\n    Cause: must be "text" or "json", but was {value:?}"#
+			))),
+		}
+	}
+}
+
+// endregion: --- OutputFormat
+
+// region:    --- EnvValue
+
+/// A value of the agent option `env` (e.g., `APP_ENV = "staging"`, or `GITHUB_TOKEN = { secret = "GITHUB_TOKEN" }`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EnvValue {
+	Value(String),
+	/// From the keychain `aipack_secrets/<NAME>` (see `support::cred`), or the environment variable
+	Secret {
+		secret: String,
+	},
+}
+
+// endregion: --- EnvValue
+
 // region:    --- ModelAliases
 
 /// TODO Must have a Arc<inner> for perf
@@ -156,6 +260,18 @@ impl AgentOptions {
 		self.allow_run_on_task_fail
 	}
 
+	pub fn conversation(&self) -> Option<bool> {
+		self.conversation
+	}
+
+	pub fn input_filter(&self) -> Option<&str> {
+		self.input_filter.as_deref()
+	}
+
+	pub fn incremental(&self) -> Option<bool> {
+		self.incremental
+	}
+
 	pub fn temperature(&self) -> Option<f64> {
 		self.temperature
 	}
@@ -164,6 +280,51 @@ impl AgentOptions {
 		self.top_p
 	}
 
+	pub fn output_format(&self) -> Option<OutputFormat> {
+		self.output_format
+	}
+
+	pub fn output_schema(&self) -> Option<&Value> {
+		self.output_schema.as_ref()
+	}
+
+	pub fn output_grammar(&self) -> Option<&str> {
+		self.output_grammar.as_deref()
+	}
+
+	pub fn logprobs(&self) -> Option<bool> {
+		self.logprobs
+	}
+
+	pub fn top_logprobs(&self) -> Option<u8> {
+		self.top_logprobs
+	}
+
+	pub fn auto_continue(&self) -> Option<u8> {
+		self.auto_continue
+	}
+
+	pub fn post(&self) -> Option<&[String]> {
+		self.post.as_deref()
+	}
+
+	/// Returns true if the AI response must be JSON
+	pub fn is_output_json(&self) -> bool {
+		self.output_format == Some(OutputFormat::Json)
+	}
+
+	pub fn confirm_writes(&self) -> Option<bool> {
+		self.confirm_writes
+	}
+
+	pub fn confirm_writes_allow(&self) -> Option<&[String]> {
+		self.confirm_writes_allow.as_deref()
+	}
+
+	pub fn env(&self) -> Option<&HashMap<String, EnvValue>> {
+		self.env.as_ref()
+	}
+
 	#[allow(unused)]
 	fn get_model_for_alias(&self, alias: &str) -> Option<&str> {
 		self.model_aliases
@@ -225,7 +386,20 @@ impl AgentOptions {
 			top_p: options_ov.top_p.or(self.top_p),
 			input_concurrency: options_ov.input_concurrency.or(self.input_concurrency),
 			allow_run_on_task_fail: options_ov.allow_run_on_task_fail.or(self.allow_run_on_task_fail),
+			conversation: options_ov.conversation.or(self.conversation),
+			input_filter: options_ov.input_filter.or(self.input_filter),
+			incremental: options_ov.incremental.or(self.incremental),
 			model_aliases,
+			output_format: options_ov.output_format.or(self.output_format),
+			output_schema: options_ov.output_schema.or(self.output_schema),
+			output_grammar: options_ov.output_grammar.or(self.output_grammar),
+			logprobs: options_ov.logprobs.or(self.logprobs),
+			top_logprobs: options_ov.top_logprobs.or(self.top_logprobs),
+			auto_continue: options_ov.auto_continue.or(self.auto_continue),
+			post: options_ov.post.or(self.post),
+			confirm_writes: options_ov.confirm_writes.or(self.confirm_writes),
+			confirm_writes_allow: options_ov.confirm_writes_allow.or(self.confirm_writes_allow),
+			env: merge_env(self.env, options_ov.env),
 		})
 	}
 
@@ -241,11 +415,38 @@ impl AgentOptions {
 			top_p: options_ov.top_p.or(self.top_p),
 			input_concurrency: options_ov.input_concurrency.or(self.input_concurrency),
 			allow_run_on_task_fail: options_ov.allow_run_on_task_fail.or(self.allow_run_on_task_fail),
+			conversation: options_ov.conversation.or(self.conversation),
+			input_filter: options_ov.input_filter.or(self.input_filter.clone()),
+			incremental: options_ov.incremental.or(self.incremental),
 			model_aliases,
+			output_format: options_ov.output_format.or(self.output_format),
+			output_schema: options_ov.output_schema.or(self.output_schema.clone()),
+			output_grammar: options_ov.output_grammar.or(self.output_grammar.clone()),
+			logprobs: options_ov.logprobs.or(self.logprobs),
+			top_logprobs: options_ov.top_logprobs.or(self.top_logprobs),
+			auto_continue: options_ov.auto_continue.or(self.auto_continue),
+			post: options_ov.post.or(self.post.clone()),
+			confirm_writes: options_ov.confirm_writes.or(self.confirm_writes),
+			confirm_writes_allow: options_ov.confirm_writes_allow.or(self.confirm_writes_allow.clone()),
+			env: merge_env(self.env.clone(), options_ov.env),
 		})
 	}
 }
 
+/// The override variables replace the base ones of the same name
+fn merge_env(
+	env: Option<HashMap<String, EnvValue>>,
+	env_ov: Option<HashMap<String, EnvValue>>,
+) -> Option<HashMap<String, EnvValue>> {
+	match (env, env_ov) {
+		(Some(mut env), Some(env_ov)) => {
+			env.extend(env_ov);
+			Some(env)
+		}
+		(env, env_ov) => env_ov.or(env),
+	}
+}
+
 // region:    --- IntoLua
 
 impl mlua::IntoLua for &AgentOptions {
@@ -257,10 +458,36 @@ impl mlua::IntoLua for &AgentOptions {
 		table.set("top_p", self.top_p)?;
 		table.set("input_concurrency", self.input_concurrency)?;
 		table.set("allow_run_on_task_fail", self.allow_run_on_task_fail)?;
+		table.set("conversation", self.conversation)?;
+		table.set("input_filter", self.input_filter.as_deref())?;
+		table.set("incremental", self.incremental)?;
 
 		let model_aliases = self.model_aliases.as_ref();
 		table.set("model_aliases", model_aliases)?;
 
+		table.set("output_format", self.output_format.map(|f| f.as_str()))?;
+		if let Some(output_schema) = self.output_schema.clone() {
+			let output_schema = serde_value_to_lua_value(lua, output_schema).map_err(mlua::Error::external)?;
+			table.set("output_schema", output_schema)?;
+		}
+		table.set("output_grammar", self.output_grammar.as_deref())?;
+		table.set("logprobs", self.logprobs)?;
+		table.set("top_logprobs", self.top_logprobs)?;
+		table.set("auto_continue", self.auto_continue)?;
+		table.set("post", self.post.clone())?;
+
+		table.set("confirm_writes", self.confirm_writes)?;
+		table.set("confirm_writes_allow", self.confirm_writes_allow.clone())?;
+
+		// NOTE: the secrets are given by name (their values are not exposed)
+		if let Some(env) = self.env.as_ref() {
+			let env = serde_json::to_value(env).map_err(mlua::Error::external)?;
+			table.set(
+				"env",
+				serde_value_to_lua_value(lua, env).map_err(mlua::Error::external)?,
+			)?;
+		}
+
 		Ok(mlua::Value::Table(table))
 	}
 }
@@ -273,18 +500,61 @@ impl mlua::FromLua for AgentOptions {
 			let top_p = table.get::<Option<f64>>("top_p")?;
 			let input_concurrency = table.get::<Option<usize>>("input_concurrency")?;
 			let allow_run_on_task_fail = table.get::<Option<bool>>("allow_run_on_task_fail")?;
+			let conversation = table.get::<Option<bool>>("conversation")?;
+			let input_filter = table.get::<Option<String>>("input_filter")?;
+			let incremental = table.get::<Option<bool>>("incremental")?;
 
 			// --
 			let model_aliases = table.get::<Option<mlua::Value>>("model_aliases")?;
 			let model_aliases = model_aliases.map(|v| ModelAliases::from_lua(v, lua)).transpose()?;
 
+			let output_format = table.get::<Option<OutputFormat>>("output_format")?;
+			let output_schema = table
+				.get::<Option<mlua::Value>>("output_schema")?
+				.map(lua_value_to_serde_value)
+				.transpose()
+				.map_err(mlua::Error::external)?;
+			let output_grammar = table.get::<Option<String>>("output_grammar")?;
+			let logprobs = table.get::<Option<bool>>("logprobs")?;
+			let top_logprobs = table.get::<Option<u8>>("top_logprobs")?;
+			let auto_continue = table.get::<Option<u8>>("auto_continue")?;
+			let post = table.get::<Option<Vec<String>>>("post")?;
+
+			let confirm_writes = table.get::<Option<bool>>("confirm_writes")?;
+			let confirm_writes_allow = table.get::<Option<Vec<String>>>("confirm_writes_allow")?;
+
+			let env = table
+				.get::<Option<mlua::Value>>("env")?
+				.map(|env| {
+					let env = lua_value_to_serde_value(env).map_err(mlua::Error::external)?;
+					serde_json::from_value::<HashMap<String, EnvValue>>(env).map_err(|err| {
+						mlua::Error::runtime(format!(
+							r#"env invalid.\n    Cause: must be a table of string or {{ secret = "NAME" }} values. {err}"#
+						))
+					})
+				})
+				.transpose()?;
+
 			let options = AgentOptions {
 				model,
 				temperature,
 				top_p,
 				input_concurrency,
 				allow_run_on_task_fail,
+				conversation,
+				input_filter,
+				incremental,
 				model_aliases,
+				output_format,
+				output_schema,
+				output_grammar,
+				logprobs,
+				top_logprobs,
+				auto_continue,
+				post,
+				confirm_writes,
+				confirm_writes_allow,
+				env,
 			};
 
 			Ok(options)
@@ -297,6 +567,7 @@ impl mlua::FromLua for AgentOptions {
 
 // region:    --- Parsing
 
+#[allow(clippy::large_enum_variant)] // ok, parsed once
 enum OptionsParsing {
 	Parsed(AgentOptions),
 	#[allow(unused)]
@@ -349,7 +620,20 @@ impl AgentOptions {
 			top_p: None,
 			input_concurrency: None,
 			allow_run_on_task_fail: None,
+			conversation: None,
+			input_filter: None,
+			incremental: None,
 			model_aliases: None,
+			output_format: None,
+			output_schema: None,
+			output_grammar: None,
+			logprobs: None,
+			top_logprobs: None,
+			auto_continue: None,
+			post: None,
+			confirm_writes: None,
+			confirm_writes_allow: None,
+			env: None,
 		}
 	}
 }
@@ -397,7 +681,8 @@ return {
 	temperature = 0.3,
 	model_aliases = { small = "flash-001" },
 	item_concurrency = nil, -- same as absent
-	allow_run_on_task_fail = true
+	allow_run_on_task_fail = true,
+	post = { "strip_fences", "trim" }
 }"#,
 		);
 		let options_lua = options_chunk.eval::<mlua::Value>()?;
@@ -413,6 +698,10 @@ return {
 			"input concurrency should be none"
 		);
 		assert_eq!(options.allow_run_on_task_fail(), Some(true));
+		assert_eq!(
+			options.post(),
+			Some(&["strip_fences".to_string(), "trim".to_string()][..])
+		);
 		assert_eq!(options.get_model_for_alias("small"), Some("flash-001"));
 		assert!(
 			options.get_model_for_alias("non-existent").is_none(),
@@ -450,6 +739,62 @@ return {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_options_env_merge() -> Result<()> {
+		// -- Setup & Fixtures
+		let base = AgentOptions::from_options_value(parse_toml_into_json(
+			r#"
+	env = { APP_ENV = "dev", GITHUB_TOKEN = { secret = "GITHUB_TOKEN" } }
+		"#,
+		)?)?;
+		let options_ov = AgentOptions::from_options_value(parse_toml_into_json(
+			r#"
+	env = { APP_ENV = "staging" }
+		"#,
+		)?)?;
+
+		// -- Exec
+		let options = base.merge(options_ov)?;
+
+		// -- Check
+		let env = options.env().ok_or("Should have env")?;
+		assert_eq!(env.get("APP_ENV"), Some(&EnvValue::Value("staging".to_string())));
+		assert_eq!(
+			env.get("GITHUB_TOKEN"),
+			Some(&EnvValue::Secret {
+				secret: "GITHUB_TOKEN".to_string()
+			})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_options_output_grammar() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = mlua::Lua::new();
+		let base = AgentOptions::from_options_value(parse_toml_into_json(
+			r#"
+	output_grammar = 'root ::= "yes" | "no"'
+		"#,
+		)?)?;
+		let options_ov = AgentOptions::from_options_value(parse_toml_into_json(r#"model = "genai_1::local""#)?)?;
+
+		// -- Exec
+		let options = base.merge(options_ov)?;
+		let options_lua = options.into_lua(&lua)?;
+
+		// -- Check
+		assert_eq!(options.output_grammar(), Some(r#"root ::= "yes" | "no""#));
+		let options_table = options_lua.as_table().ok_or("Should be a table")?;
+		assert_eq!(
+			&options_table.get::<String>("output_grammar")?,
+			r#"root ::= "yes" | "no""#
+		);
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
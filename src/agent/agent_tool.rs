@@ -0,0 +1,90 @@
+use crate::{Error, Result};
+use genai::chat::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A tool (function) the agent exposes to the model, declared with `aip.flow.before_all_response({tools = ...})`.
+///
+/// NOTE: Lua functions cannot cross stages (each stage has its own Lua engine),
+///       so the `handler` is a Lua script evaluated for each tool call (with `tool_args` in scope).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentTool {
+	pub name: String,
+	pub description: Option<String>,
+	/// The JSON schema of the tool arguments
+	pub schema: Option<Value>,
+	/// The Lua script of the tool call, its return value is the tool response sent back to the model
+	pub handler: String,
+}
+
+/// Constructors
+impl AgentTool {
+	/// Parse the `tools` of the before all response (a list of tool tables).
+	pub fn list_from_value(value: Value) -> Result<Vec<AgentTool>> {
+		match value {
+			Value::Null => Ok(Vec::new()),
+			// NOTE: An empty Lua table is serialized as an empty object
+			Value::Object(obj) if obj.is_empty() => Ok(Vec::new()),
+			Value::Array(_) => serde_json::from_value(value).map_err(|err| Error::BeforeAllFailWrongReturn {
+				cause: format!(
+					"aip.flow.before_all_response(arg) - 'arg.tools' items must be {{name, description?, schema?, handler}}.\nCause: {err}"
+				),
+			}),
+			_ => Err(Error::BeforeAllFailWrongReturn {
+				cause: "aip.flow.before_all_response(arg) - 'arg.tools' must be nil or a list of tools".to_string(),
+			}),
+		}
+	}
+}
+
+/// Transformers
+impl AgentTool {
+	pub fn to_genai_tool(&self) -> Tool {
+		let mut tool = Tool::new(self.name.clone());
+		if let Some(description) = self.description.as_ref() {
+			tool = tool.with_description(description.clone());
+		}
+		if let Some(schema) = self.schema.as_ref() {
+			tool = tool.with_schema(schema.clone());
+		}
+		tool
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_agent_tool_list_from_value() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_tools = json!([{
+			"name": "get_weather",
+			"description": "Get the weather of a city",
+			"schema": {"type": "object", "properties": {"city": {"type": "string"}}},
+			"handler": "return 'sunny in ' .. tool_args.city"
+		}]);
+
+		// -- Exec
+		let tools = AgentTool::list_from_value(fx_tools)?;
+
+		// -- Check
+		assert_eq!(tools.len(), 1);
+		let tool = tools[0].to_genai_tool();
+		assert_eq!(tool.name.as_str(), "get_weather");
+		assert_eq!(tool.description.as_deref(), Some("Get the weather of a city"));
+		assert!(AgentTool::list_from_value(json!({}))?.is_empty());
+		assert!(AgentTool::list_from_value(json!([{"name": "no_handler"}])).is_err());
+		assert!(AgentTool::list_from_value(json!("get_weather")).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,15 +3,19 @@
 mod agent_common;
 mod agent_doc;
 mod agent_locator;
+mod agent_meta;
 mod agent_options;
 mod agent_ref;
+mod agent_tool;
 mod prompt_part;
 
 pub use agent_common::*;
 pub use agent_doc::*;
 pub use agent_locator::*;
+pub use agent_meta::*;
 pub use agent_options::*;
 pub use agent_ref::*;
+pub use agent_tool::*;
 pub use prompt_part::*;
 
 // endregion: --- Modules
@@ -7,6 +7,9 @@ use std::ops::Deref;
 // Because the bin with .aip
 const BIN_DIR: &str = "bin";
 
+/// The `aip schedule` recurring runs
+const SCHEDULES_FILE: &str = "schedules.json";
+
 /// BaseAipackPath is the typed wrapper of the `~/.aipack-base` absolute path
 #[derive(Debug, Clone)]
 pub struct AipackBaseDir {
@@ -37,6 +40,9 @@ impl AipackBaseDir {
 	pub fn bin_tmp_dir(&self) -> SPath {
 		self.path.join(BIN_DIR).join("tmp")
 	}
+	pub fn schedules_file(&self) -> SPath {
+		self.path.join(SCHEDULES_FILE)
+	}
 }
 
 /// Pathroughts to SPath
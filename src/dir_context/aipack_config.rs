@@ -0,0 +1,706 @@
+//! The aipack config, merged from the config files returned by `AipackPaths::get_wks_config_toml_paths`
+//! (base `config-default.toml`, base `config-user.toml`, workspace `.aipack/config.toml`, later ones override).
+//!
+//! - `[options]` - The default agent options (model, temperature, input_concurrency, model_aliases, ...).
+//! - `[pack_options."namespace@pack_name"]` - The agent options overrides for the agents of this pack.
+//! - `[run]` - `input_globs`, the file globs of an `aip run` without `-f` or `-i`,
+//!   and `max_tasks`, the max running tasks of the top runs of the process (see `TaskScheduler`).
+//! - `[governance]` - The optional run end report endpoint (see `GovernanceConfig`).
+//! - `[model_policy]` - The optional allowed and banned models and providers (see `ModelPolicy`).
+//! - `[store]` - The optional runs history db location (a file or a Postgres url), which can be shared (see `StoreConfig`).
+//! - `[sandbox]` - The optional paths the installed packs can never access (see `SandboxConfig`).
+//! - `[db]` - The optional `aip.db` connection aliases and default row limit (see `DbConfig`).
+//! - `AIPACK_MODEL`, `AIPACK_TEMPERATURE`, `AIPACK_INPUT_CONCURRENCY` environment variables
+//!   override the config options (but not the agent `# Options`).
+//!
+//! The merged raw config is exposed to Lua as `CTX.config`.
+
+use crate::agent::AgentOptions;
+use crate::dir_context::{AipackPaths, ModelPolicy};
+use crate::support::tomls::parse_toml_into_json;
+use crate::types::PackIdentity;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use simple_fs::read_to_string;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The environment variables overriding the config options, with their option name.
+const ENV_OPTIONS: &[(&str, &str)] = &[
+	("AIPACK_MODEL", "model"),
+	("AIPACK_TEMPERATURE", "temperature"),
+	("AIPACK_INPUT_CONCURRENCY", "input_concurrency"),
+];
+
+#[derive(Debug, Clone)]
+pub struct AipackConfig {
+	inner: Arc<ConfigInner>,
+}
+
+#[derive(Debug)]
+struct ConfigInner {
+	/// The deep merged content of all config files
+	value: Value,
+
+	/// The merged `[options]` of all config files
+	options: AgentOptions,
+
+	/// The merged `[pack_options."namespace@pack_name"]` of all config files, by pack identity
+	pack_options: HashMap<String, AgentOptions>,
+
+	/// The options from the `AIPACK_...` environment variables
+	env_options: AgentOptions,
+
+	/// The last `[run] input_globs`
+	input_globs: Option<Vec<String>>,
+
+	/// The last `[run] max_tasks`
+	run_max_tasks: Option<usize>,
+
+	/// The merged `[governance]`
+	governance: Option<GovernanceConfig>,
+
+	/// The merged `[model_policy]`
+	model_policy: Option<ModelPolicy>,
+
+	/// The merged `[store]`
+	store: Option<StoreConfig>,
+
+	/// The merged `[sandbox]`
+	sandbox: Option<SandboxConfig>,
+
+	/// The merged `[db]`
+	db: Option<DbConfig>,
+}
+
+/// The `[governance]` config, to POST the run metadata to a company controlled endpoint at run end (opt-in).
+///
+/// ```toml
+/// [governance]
+/// endpoint        = "https://audit.acme.com/aipack/runs"
+/// token_env       = "ACME_AUDIT_TOKEN" # optional, sent as `Authorization: Bearer <token>`
+/// include_prompts = false              # optional, the agent prompt templates are not sent by default
+/// max_retries     = 3                  # optional, with exponential back-off
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GovernanceConfig {
+	pub endpoint: String,
+	pub token_env: Option<String>,
+	#[serde(default)]
+	pub include_prompts: bool,
+	#[serde(default = "default_governance_max_retries")]
+	pub max_retries: u32,
+}
+
+fn default_governance_max_retries() -> u32 {
+	3
+}
+
+/// The `[store]` config, to point the runs history db to another location than the workspace
+/// `.aipack/.session/_history.db`, e.g., a file or a Postgres database shared by a team (see `HistoryStore`).
+///
+/// ```toml
+/// [store]
+/// history_db = "~/team-share/aipack/_history.db" # `~/`, absolute, or relative to the workspace dir
+/// # or
+/// history_db = "postgres://aipack@db.acme.internal/aipack"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StoreConfig {
+	pub history_db: Option<String>,
+}
+
+/// The `[sandbox]` config, the paths the installed packs cannot read or write (even in the workspace).
+///
+/// ```toml
+/// [sandbox]
+/// paths_deny = ["**/.env", "secrets/**", "~/.ssh/**"] # relative globs match the workspace relative paths
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxConfig {
+	pub paths_deny: Option<Vec<String>>,
+}
+
+/// The `[db]` config, the `aip.db` connection aliases (so that the agents do not have the urls and credentials),
+/// and the default max rows of the queries.
+///
+/// ```toml
+/// [db]
+/// max_rows = 500 # optional (default 1000)
+///
+/// [db.aliases]
+/// app       = "sqlite:data/app.db"             # relative to the workspace dir
+/// reporting = { url_env = "REPORTING_DB_URL" } # the url from an environment variable (with the credentials)
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DbConfig {
+	pub max_rows: Option<usize>,
+	#[serde(default)]
+	pub aliases: HashMap<String, DbAliasConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum DbAliasConfig {
+	Url(String),
+	UrlEnv { url_env: String },
+}
+
+/// Loaders
+impl AipackConfig {
+	pub fn load(aipack_paths: &AipackPaths) -> Result<Self> {
+		Self::load_with_env(aipack_paths, |name| std::env::var(name).ok())
+	}
+
+	fn load_with_env(aipack_paths: &AipackPaths, get_env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+		let config_paths = aipack_paths.get_wks_config_toml_paths()?;
+
+		let mut value = Value::Object(Map::new());
+		let mut options: Option<AgentOptions> = None;
+		let mut pack_options: HashMap<String, AgentOptions> = HashMap::new();
+		let mut input_globs: Option<Vec<String>> = None;
+		let mut run_max_tasks: Option<usize> = None;
+
+		for config_path in config_paths {
+			let config_content = read_to_string(&config_path)?;
+			let config_value = parse_toml_into_json(&config_content)?;
+			let to_config_err = |err: Error| Error::Config {
+				path: config_path.to_string(),
+				reason: err.to_string(),
+			};
+
+			// -- Options
+			let item_options = AgentOptions::from_config_value(config_value.clone()).map_err(to_config_err)?;
+			options = match options {
+				Some(options) => Some(options.merge(item_options)?),
+				None => Some(item_options),
+			};
+
+			// -- Pack Options
+			for (pack_identity, item_options) in parse_pack_options(&config_value).map_err(to_config_err)? {
+				let item_options = match pack_options.remove(&pack_identity) {
+					Some(options) => options.merge(item_options)?,
+					None => item_options,
+				};
+				pack_options.insert(pack_identity, item_options);
+			}
+
+			// -- Run input globs
+			if let Some(item_globs) = parse_input_globs(&config_value).map_err(to_config_err)? {
+				input_globs = Some(item_globs);
+			}
+
+			// -- Run max tasks
+			if let Some(item_max_tasks) = parse_run_max_tasks(&config_value).map_err(to_config_err)? {
+				run_max_tasks = Some(item_max_tasks);
+			}
+
+			merge_json_into(&mut value, config_value);
+		}
+
+		let Some(options) = options else {
+			return Err(Error::custom("No agent options found"));
+		};
+
+		let governance = parse_governance(&value).map_err(|err| Error::Config {
+			path: "[governance]".to_string(),
+			reason: err.to_string(),
+		})?;
+
+		let model_policy = parse_model_policy(&value).map_err(|err| Error::Config {
+			path: "[model_policy]".to_string(),
+			reason: err.to_string(),
+		})?;
+
+		let store = parse_store(&value).map_err(|err| Error::Config {
+			path: "[store]".to_string(),
+			reason: err.to_string(),
+		})?;
+
+		let sandbox = parse_sandbox(&value).map_err(|err| Error::Config {
+			path: "[sandbox]".to_string(),
+			reason: err.to_string(),
+		})?;
+
+		let db = parse_db(&value).map_err(|err| Error::Config {
+			path: "[db]".to_string(),
+			reason: err.to_string(),
+		})?;
+
+		let env_options = parse_env_options(get_env)?;
+
+		Ok(Self {
+			inner: Arc::new(ConfigInner {
+				value,
+				options,
+				pack_options,
+				env_options,
+				input_globs,
+				run_max_tasks,
+				governance,
+				model_policy,
+				store,
+				sandbox,
+				db,
+			}),
+		})
+	}
+}
+
+/// Getters
+impl AipackConfig {
+	/// The deep merged config (e.g., for `CTX.config`)
+	pub fn value(&self) -> &Value {
+		&self.inner.value
+	}
+
+	pub fn input_globs(&self) -> Option<Vec<&str>> {
+		self.inner
+			.input_globs
+			.as_ref()
+			.map(|globs| globs.iter().map(|s| s.as_str()).collect())
+	}
+
+	/// The max running tasks of the top runs of the process (None for no bound)
+	pub fn run_max_tasks(&self) -> Option<usize> {
+		self.inner.run_max_tasks
+	}
+
+	pub fn governance(&self) -> Option<&GovernanceConfig> {
+		self.inner.governance.as_ref()
+	}
+
+	pub fn model_policy(&self) -> Option<&ModelPolicy> {
+		self.inner.model_policy.as_ref()
+	}
+
+	pub fn store(&self) -> Option<&StoreConfig> {
+		self.inner.store.as_ref()
+	}
+
+	pub fn sandbox(&self) -> Option<&SandboxConfig> {
+		self.inner.sandbox.as_ref()
+	}
+
+	pub fn db(&self) -> Option<&DbConfig> {
+		self.inner.db.as_ref()
+	}
+
+	/// Returns the base agent options for an agent, with the eventual pack options and the environment overrides.
+	pub fn agent_options(&self, pack_identity: Option<&PackIdentity>) -> Result<AgentOptions> {
+		let inner = &self.inner;
+
+		let options = match pack_identity.and_then(|identity| inner.pack_options.get(&identity.to_string())) {
+			Some(pack_options) => inner.options.merge_new(pack_options.clone())?,
+			None => inner.options.clone(),
+		};
+
+		options.merge(inner.env_options.clone())
+	}
+}
+
+// region:    --- Support
+
+fn parse_pack_options(config_value: &Value) -> Result<Vec<(String, AgentOptions)>> {
+	let Some(pack_options) = config_value.get("pack_options") else {
+		return Ok(Vec::new());
+	};
+	let pack_options = pack_options
+		.as_object()
+		.ok_or("[pack_options] must be a table of '[pack_options.\"namespace@pack_name\"]' tables")?;
+
+	let mut res = Vec::with_capacity(pack_options.len());
+	for (pack_identity, options) in pack_options {
+		let pack_identity: PackIdentity = pack_identity.parse()?;
+		let options = AgentOptions::from_options_value(options.clone())
+			.map_err(|err| Error::custom(format!("[pack_options.\"{pack_identity}\"] is invalid. Cause: {err}")))?;
+		res.push((pack_identity.to_string(), options));
+	}
+
+	Ok(res)
+}
+
+fn parse_input_globs(config_value: &Value) -> Result<Option<Vec<String>>> {
+	let Some(globs) = config_value.pointer("/run/input_globs") else {
+		return Ok(None);
+	};
+
+	let globs = globs
+		.as_array()
+		.and_then(|globs| {
+			globs
+				.iter()
+				.map(|glob| glob.as_str().map(|s| s.to_string()))
+				.collect::<Option<Vec<_>>>()
+		})
+		.ok_or("[run] input_globs must be an array of strings (e.g., input_globs = [\"src/**/*.rs\"])")?;
+
+	Ok(Some(globs))
+}
+
+fn parse_run_max_tasks(config_value: &Value) -> Result<Option<usize>> {
+	let Some(max_tasks) = config_value.pointer("/run/max_tasks") else {
+		return Ok(None);
+	};
+
+	let max_tasks = max_tasks
+		.as_u64()
+		.filter(|max_tasks| *max_tasks > 0)
+		.ok_or("[run] max_tasks must be a number greater than 0 (e.g., max_tasks = 8)")?;
+
+	Ok(Some(max_tasks as usize))
+}
+
+fn parse_governance(config_value: &Value) -> Result<Option<GovernanceConfig>> {
+	let Some(governance) = config_value.get("governance") else {
+		return Ok(None);
+	};
+
+	let governance: GovernanceConfig = serde_json::from_value(governance.clone())
+		.map_err(|err| Error::custom(format!("[governance] is invalid. Cause: {err}")))?;
+	if !governance.endpoint.starts_with("https://") && !governance.endpoint.starts_with("http://") {
+		return Err(Error::custom(format!(
+			"[governance] endpoint '{}' must be a http(s) URL",
+			governance.endpoint
+		)));
+	}
+
+	Ok(Some(governance))
+}
+
+fn parse_store(config_value: &Value) -> Result<Option<StoreConfig>> {
+	let Some(store) = config_value.get("store") else {
+		return Ok(None);
+	};
+
+	let store: StoreConfig = serde_json::from_value(store.clone())
+		.map_err(|err| Error::custom(format!("[store] is invalid. Cause: {err}")))?;
+	if store.history_db.as_deref().is_some_and(|path| path.trim().is_empty()) {
+		return Err(Error::custom("[store] history_db cannot be empty"));
+	}
+
+	Ok(Some(store))
+}
+
+fn parse_sandbox(config_value: &Value) -> Result<Option<SandboxConfig>> {
+	let Some(sandbox) = config_value.get("sandbox") else {
+		return Ok(None);
+	};
+
+	let sandbox: SandboxConfig = serde_json::from_value(sandbox.clone())
+		.map_err(|err| Error::custom(format!("[sandbox] is invalid. Cause: {err}")))?;
+	for glob in sandbox.paths_deny.iter().flatten() {
+		simple_fs::get_glob_set(&[glob.as_str()])
+			.map_err(|err| Error::custom(format!("[sandbox] paths_deny '{glob}' is invalid. Cause: {err}")))?;
+	}
+
+	Ok(Some(sandbox))
+}
+
+fn parse_db(config_value: &Value) -> Result<Option<DbConfig>> {
+	let Some(db) = config_value.get("db") else {
+		return Ok(None);
+	};
+
+	let db: DbConfig =
+		serde_json::from_value(db.clone()).map_err(|err| Error::custom(format!("[db] is invalid. Cause: {err}")))?;
+	if db.max_rows == Some(0) {
+		return Err(Error::custom("[db] max_rows must be greater than 0"));
+	}
+
+	Ok(Some(db))
+}
+
+fn parse_model_policy(config_value: &Value) -> Result<Option<ModelPolicy>> {
+	let Some(model_policy) = config_value.get("model_policy") else {
+		return Ok(None);
+	};
+
+	let model_policy: ModelPolicy = serde_json::from_value(model_policy.clone())
+		.map_err(|err| Error::custom(format!("[model_policy] is invalid. Cause: {err}")))?;
+	model_policy.validate()?;
+
+	Ok(Some(model_policy))
+}
+
+fn parse_env_options(get_env: impl Fn(&str) -> Option<String>) -> Result<AgentOptions> {
+	let mut options = Map::new();
+
+	for (env_name, option_name) in ENV_OPTIONS {
+		let Some(env_value) = get_env(env_name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty()) else {
+			continue;
+		};
+		let value = match *option_name {
+			"model" => Value::String(env_value),
+			_ => serde_json::from_str::<Value>(&env_value)
+				.ok()
+				.filter(|v| v.is_number())
+				.ok_or_else(|| {
+					Error::custom(format!(
+						"Environment variable {env_name}='{env_value}' must be a number"
+					))
+				})?,
+		};
+		options.insert(option_name.to_string(), value);
+	}
+
+	AgentOptions::from_options_value(Value::Object(options))
+		.map_err(|err| Error::custom(format!("AIPACK_... environment variables are invalid. Cause: {err}")))
+}
+
+/// Deep merge the `overlay` into the `target` (objects are merged, other values are replaced).
+fn merge_json_into(target: &mut Value, overlay: Value) {
+	match (target, overlay) {
+		(Value::Object(target), Value::Object(overlay)) => {
+			for (key, value) in overlay {
+				match target.get_mut(&key) {
+					Some(target_value) => merge_json_into(target_value, value),
+					None => {
+						target.insert(key, value);
+					}
+				}
+			}
+		}
+		(target, overlay) => *target = overlay,
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_aipack_config_merge_json_into() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut target =
+			json!({"options": {"model": "a", "model_aliases": {"x": "m1"}}, "run": {"input_globs": ["*.md"]}});
+		let overlay = json!({"options": {"model_aliases": {"y": "m2"}}, "run": {"input_globs": ["src/**/*.rs"]}});
+
+		// -- Exec
+		merge_json_into(&mut target, overlay);
+
+		// -- Check
+		assert_eq!(
+			target,
+			json!({"options": {"model": "a", "model_aliases": {"x": "m1", "y": "m2"}}, "run": {"input_globs": ["src/**/*.rs"]}})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_pack_options_and_globs() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json(
+			r#"
+[run]
+input_globs = ["src/**/*.rs"]
+
+[pack_options."jc@coder"]
+model = "pack-model"
+input_concurrency = 8
+		"#,
+		)?;
+
+		// -- Exec
+		let pack_options = parse_pack_options(&config_value)?;
+		let input_globs = parse_input_globs(&config_value)?;
+
+		// -- Check
+		let (pack_identity, options) = pack_options.first().ok_or("Should have one pack options")?;
+		assert_eq!(pack_identity, "jc@coder");
+		assert_eq!(options.model(), Some("pack-model"));
+		assert_eq!(options.input_concurrency(), Some(8));
+		assert_eq!(input_globs, Some(vec!["src/**/*.rs".to_string()]));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let bad_identity = parse_toml_into_json("[pack_options.coder]\nmodel = \"m\"")?;
+		let bad_globs = parse_toml_into_json("[run]\ninput_globs = \"src/**/*.rs\"")?;
+
+		// -- Exec & Check
+		assert!(parse_pack_options(&bad_identity).is_err());
+		assert!(parse_input_globs(&bad_globs).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_governance() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json(
+			r#"
+[governance]
+endpoint = "https://audit.acme.com/aipack/runs"
+token_env = "ACME_AUDIT_TOKEN"
+		"#,
+		)?;
+		let bad_endpoint = parse_toml_into_json("[governance]\nendpoint = \"audit.acme.com\"")?;
+		let bad_key = parse_toml_into_json("[governance]\nendpoint = \"https://a.com\"\ninclude_prompt = true")?;
+
+		// -- Exec
+		let governance = parse_governance(&config_value)?.ok_or("Should have governance")?;
+
+		// -- Check
+		assert_eq!(governance.endpoint, "https://audit.acme.com/aipack/runs");
+		assert_eq!(governance.token_env.as_deref(), Some("ACME_AUDIT_TOKEN"));
+		assert!(!governance.include_prompts);
+		assert_eq!(governance.max_retries, 3);
+		assert!(parse_governance(&json!({}))?.is_none());
+		assert!(parse_governance(&bad_endpoint).is_err());
+		assert!(parse_governance(&bad_key).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_model_policy() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json(
+			r#"
+[model_policy]
+allowed_providers = ["openai"]
+banned_models = ["*-preview"]
+		"#,
+		)?;
+		let bad_key = parse_toml_into_json("[model_policy]\nallowed_model = [\"gpt-5\"]")?;
+		let bad_glob = parse_toml_into_json("[model_policy]\nallowed_models = [\"gpt-[5\"]")?;
+
+		// -- Exec
+		let model_policy = parse_model_policy(&config_value)?.ok_or("Should have model_policy")?;
+
+		// -- Check
+		assert_eq!(model_policy.allowed_providers, vec!["openai"]);
+		assert!(model_policy.allowed_models.is_empty());
+		assert!(model_policy.check("gpt-5-preview", "openai").is_err());
+		assert!(parse_model_policy(&json!({}))?.is_none());
+		assert!(parse_model_policy(&bad_key).is_err());
+		assert!(parse_model_policy(&bad_glob).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_run_max_tasks() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json("[run]\nmax_tasks = 8")?;
+		let zero = parse_toml_into_json("[run]\nmax_tasks = 0")?;
+		let not_number = parse_toml_into_json("[run]\nmax_tasks = \"8\"")?;
+
+		// -- Exec
+		let max_tasks = parse_run_max_tasks(&config_value)?;
+
+		// -- Check
+		assert_eq!(max_tasks, Some(8));
+		assert!(parse_run_max_tasks(&json!({"run": {"input_globs": ["*.md"]}}))?.is_none());
+		assert!(parse_run_max_tasks(&zero).is_err());
+		assert!(parse_run_max_tasks(&not_number).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_store() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json("[store]\nhistory_db = \"~/team/_history.db\"")?;
+		let bad_key = parse_toml_into_json("[store]\nbackend = \"postgres\"")?;
+		let empty_path = parse_toml_into_json("[store]\nhistory_db = \" \"")?;
+
+		// -- Exec
+		let store = parse_store(&config_value)?.ok_or("Should have store")?;
+
+		// -- Check
+		assert_eq!(store.history_db.as_deref(), Some("~/team/_history.db"));
+		assert!(parse_store(&json!({}))?.is_none());
+		assert!(parse_store(&bad_key).is_err());
+		assert!(parse_store(&empty_path).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_sandbox() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json("[sandbox]\npaths_deny = [\"**/.env\", \"~/.ssh/**\"]")?;
+		let bad_key = parse_toml_into_json("[sandbox]\npaths_allow = [\"/tmp/**\"]")?;
+		let bad_glob = parse_toml_into_json("[sandbox]\npaths_deny = [\"src/[a\"]")?;
+
+		// -- Exec
+		let sandbox = parse_sandbox(&config_value)?.ok_or("Should have sandbox")?;
+
+		// -- Check
+		assert_eq!(
+			sandbox.paths_deny,
+			Some(vec!["**/.env".to_string(), "~/.ssh/**".to_string()])
+		);
+		assert!(parse_sandbox(&json!({}))?.is_none());
+		assert!(parse_sandbox(&bad_key).is_err());
+		assert!(parse_sandbox(&bad_glob).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_db() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json(
+			"[db]\nmax_rows = 50\n[db.aliases]\napp = \"sqlite:data/app.db\"\nreporting = { url_env = \"REPORTING_DB_URL\" }",
+		)?;
+		let bad_key = parse_toml_into_json("[db]\nmax_row = 50")?;
+		let zero_rows = parse_toml_into_json("[db]\nmax_rows = 0")?;
+
+		// -- Exec
+		let db = parse_db(&config_value)?.ok_or("Should have db")?;
+
+		// -- Check
+		assert_eq!(db.max_rows, Some(50));
+		assert!(matches!(db.aliases.get("app"), Some(DbAliasConfig::Url(url)) if url == "sqlite:data/app.db"));
+		assert!(
+			matches!(db.aliases.get("reporting"), Some(DbAliasConfig::UrlEnv { url_env }) if url_env == "REPORTING_DB_URL")
+		);
+		assert!(parse_db(&json!({}))?.is_none());
+		assert!(parse_db(&bad_key).is_err());
+		assert!(parse_db(&zero_rows).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_aipack_config_parse_env_options() -> Result<()> {
+		// -- Setup & Fixtures
+		let env = |name: &str| match name {
+			"AIPACK_MODEL" => Some("env-model".to_string()),
+			"AIPACK_INPUT_CONCURRENCY" => Some("4".to_string()),
+			_ => None,
+		};
+
+		// -- Exec
+		let options = parse_env_options(env)?;
+
+		// -- Check
+		assert_eq!(options.model(), Some("env-model"));
+		assert_eq!(options.input_concurrency(), Some(4));
+		assert_eq!(options.temperature(), None);
+		assert!(parse_env_options(|name| (name == "AIPACK_TEMPERATURE").then(|| "hot".to_string())).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,11 +1,15 @@
 use super::path_consts::{PACK_CUSTOM, PACK_INSTALLED};
 use crate::dir_context::path_consts::{AIPACK_DIR_NAME, PACK_DOWNLOAD};
-use crate::dir_context::{AipackBaseDir, AipackWksDir, CONFIG_BASE_DEFAULT_FILE_NAME, CONFIG_BASE_USER_FILE_NAME};
+use crate::dir_context::{
+	AipackBaseDir, AipackWksDir, CONFIG_BASE_DEFAULT_FILE_NAME, CONFIG_BASE_USER_FILE_NAME, PackSource,
+	load_base_pack_sources,
+};
 use crate::runtime::Session;
 use crate::support::files::current_dir;
 use crate::{Error, Result};
 use simple_fs::SPath;
 use std::path::Path;
+use std::sync::Arc;
 
 /// AipackPaths is the component that manages all of the Aipack Paths from
 /// - workspace paths `./.aipack` (optional)
@@ -21,6 +25,9 @@ pub struct AipackPaths {
 
 	/// This is absolute path of `~/.aipack-base/`
 	aipack_base_dir: AipackBaseDir,
+
+	/// The `[[pack_sources]]` of the base config (see `pack_source.rs`)
+	pack_sources: Arc<Vec<PackSource>>,
 }
 
 impl AipackPaths {
@@ -34,6 +41,36 @@ impl AipackPaths {
 		self.aipack_wks_dir()
 			.map(|aip_dir| aip_dir.join(format!(".session/{}/tmp", session.as_str())))
 	}
+
+	/// The persisted runs history db (`.aipack/.session/_history.db`), shared across sessions.
+	pub fn history_db_path(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join(".session/_history.db"))
+	}
+
+	/// The edited task prompts of the TUI task redo (`.aipack/.session/_task_prompts/`), shared across sessions.
+	pub fn task_prompts_dir(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join(".session/_task_prompts"))
+	}
+
+	/// The TUI tasks outputs exports (`.aipack/.session/_task_exports/`), shared across sessions.
+	pub fn task_exports_dir(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join(".session/_task_exports"))
+	}
+
+	/// The checkpoints of the interrupted top runs, to resume them (`.aipack/.session/_checkpoints/`).
+	pub fn checkpoints_dir(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join(".session/_checkpoints"))
+	}
+
+	/// The `aip.vec` vector store db (`.aipack/.session/_vec.db`), shared across sessions.
+	pub fn vec_db_path(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join(".session/_vec.db"))
+	}
+
+	/// The pack lock of the installs (`.aipack/aipack.lock`), for `aip install --locked`.
+	pub fn aipack_lock_path(&self) -> Option<SPath> {
+		self.aipack_wks_dir().map(|aip_dir| aip_dir.join("aipack.lock"))
+	}
 }
 
 /// Constructor
@@ -48,10 +85,12 @@ impl AipackPaths {
 				let current_dir = current_dir()?.canonicalize()?;
 				let aipack_base_dir = AipackBaseDir::new()?;
 				let aipack_wks_dir = Some(AipackWksDir::new_from_wks_dir(&current_dir)?);
+				let pack_sources = load_base_pack_sources(&aipack_base_dir)?;
 				Ok(Self {
 					wks_dir: Some(current_dir),
 					aipack_wks_dir,
 					aipack_base_dir,
+					pack_sources: Arc::new(pack_sources),
 				})
 			}
 		}
@@ -84,11 +123,13 @@ impl AipackPaths {
 
 		// -- Compute the aipack_base_dir
 		let aipack_base_dir = AipackBaseDir::new()?;
+		let pack_sources = load_base_pack_sources(&aipack_base_dir)?;
 
 		Ok(Self {
 			wks_dir: Some(wks_dir),
 			aipack_wks_dir,
 			aipack_base_dir,
+			pack_sources: Arc::new(pack_sources),
 		})
 	}
 }
@@ -102,12 +143,14 @@ impl AipackPaths {
 		wks_dir: SPath, // The workspace dir (before the .aipack/)
 	) -> Result<Self> {
 		let aipack_wks_dir = AipackWksDir::new_from_wks_dir(&wks_dir)?;
+		let pack_sources = load_base_pack_sources(&base_aipack_dir)?;
 
 		// AipackWksDir is now passed directly as an option
 		Ok(AipackPaths {
 			wks_dir: Some(wks_dir),
 			aipack_wks_dir: Some(aipack_wks_dir),
 			aipack_base_dir: base_aipack_dir,
+			pack_sources: Arc::new(pack_sources),
 		})
 	}
 
@@ -129,12 +172,18 @@ impl AipackPaths {
 	pub fn aipack_base_dir(&self) -> &AipackBaseDir {
 		&self.aipack_base_dir
 	}
+
+	pub fn pack_sources(&self) -> &[PackSource] {
+		&self.pack_sources
+	}
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum RepoKind {
 	WksCustom,
 	BaseCustom,
+	/// A `[[pack_sources]]` dir of the base config
+	Source,
 	BaseInstalled,
 }
 
@@ -143,6 +192,7 @@ impl RepoKind {
 		match self {
 			Self::WksCustom => "workspace custom - .aipack/pack/custom",
 			Self::BaseCustom => "base custom - ~/.aipack-base/pack/custom",
+			Self::Source => "pack source - base config pack_sources",
 			Self::BaseInstalled => "base installed - ~/.aipack-base/pack/installed",
 		}
 		.to_string()
@@ -153,12 +203,25 @@ impl RepoKind {
 pub struct PackRepo {
 	pub kind: RepoKind,
 	pub path: SPath,
+	/// When set, only these namespaces resolve from this repo (from the pack source `namespaces`)
+	pub namespaces: Option<Vec<String>>,
 }
 
 /// Constructor & Getters
 impl PackRepo {
 	pub fn new(kind: RepoKind, path: SPath) -> Self {
-		Self { kind, path }
+		Self {
+			kind,
+			path,
+			namespaces: None,
+		}
+	}
+
+	pub fn accepts_namespace(&self, namespace: &str) -> bool {
+		self.namespaces
+			.as_ref()
+			.map(|namespaces| namespaces.iter().any(|ns| ns == namespace))
+			.unwrap_or(true)
 	}
 
 	#[allow(unused)]
@@ -232,6 +295,7 @@ impl AipackPaths {
 	/// The array will contain (if they exist):
 	/// - `/path/to/wks/.aipack/pack/custom` (if `.aipack` and the `custom` dir exist)
 	/// - `/path/user/home/.aipack-base/pack/custom`
+	/// - the `[[pack_sources]]` dirs of the base config, in their order
 	/// - `/path/user/home/.aipack-base/pack/installed`
 	pub fn get_pack_repo_dirs(&self) -> Result<Vec<PackRepo>> {
 		let mut dirs = Vec::new();
@@ -250,7 +314,18 @@ impl AipackPaths {
 			dirs.push(PackRepo::new(RepoKind::BaseCustom, base_custom));
 		}
 
-		// 3. Base installed directory: ~/.aipack-base/pack/installed
+		// 3. Pack sources of the base config
+		for pack_source in self.pack_sources.iter() {
+			if pack_source.path.exists() {
+				dirs.push(PackRepo {
+					kind: RepoKind::Source,
+					path: pack_source.path.clone(),
+					namespaces: pack_source.namespaces.clone(),
+				});
+			}
+		}
+
+		// 4. Base installed directory: ~/.aipack-base/pack/installed
 		let base_installed = self.get_base_pack_installed_dir()?;
 		if base_installed.exists() {
 			dirs.push(PackRepo::new(RepoKind::BaseInstalled, base_installed));
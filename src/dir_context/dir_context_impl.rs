@@ -1,3 +1,4 @@
+use crate::dir_context::AipackConfig;
 use crate::dir_context::aipack_paths::AipackPaths;
 use crate::dir_context::resolve_pack_ref_base_path;
 use crate::runtime::Session;
@@ -87,6 +88,46 @@ impl DirContext {
 	}
 }
 
+/// Config
+impl DirContext {
+	/// Load the merged base and workspace config (see `AipackConfig`).
+	/// NOTE: Loaded on each call, so config changes are picked up on the next run (e.g., on redo).
+	pub fn load_config(&self) -> Result<AipackConfig> {
+		AipackConfig::load(self.aipack_paths())
+	}
+
+	/// The runs history db location (see `new_history_store`), the `[store] history_db` config if present
+	/// (a db file path, or a `postgres://` url), otherwise the `.aipack/.session/_history.db` path.
+	/// NOTE: None when no config `history_db` and no workspace `.aipack/`.
+	pub fn history_db(&self) -> Result<Option<String>> {
+		let config = self.load_config()?;
+		let Some(history_db) = config.store().and_then(|store| store.history_db.as_deref()) else {
+			return Ok(self.aipack_paths().history_db_path().map(|path| path.to_string()));
+		};
+		if history_db.starts_with("postgres://") || history_db.starts_with("postgresql://") {
+			return Ok(Some(history_db.to_string()));
+		}
+
+		let path = self.maybe_tilde_path_into_home(SPath::new(history_db));
+		let path = match (path.is_absolute(), self.wks_dir()) {
+			(false, Some(wks_dir)) => wks_dir.join(path),
+			(false, None) => self.current_dir().join(path),
+			(true, _) => path,
+		};
+		Ok(Some(path.to_string()))
+	}
+
+	/// The workspace/user of the runs saved in a shared history db (e.g., `jen:/home/jen/my-project`),
+	/// so that the history trims are per workspace/user (see `new_history_store`).
+	pub fn history_scope(&self) -> String {
+		let user = std::env::var("USER")
+			.or_else(|_| std::env::var("USERNAME"))
+			.unwrap_or_else(|_| "unknown".to_string());
+		let dir = self.wks_dir().unwrap_or_else(|| self.current_dir());
+		format!("{user}:{dir}")
+	}
+}
+
 /// Formatters
 impl DirContext {
 	/// Return the display path
@@ -1,18 +1,24 @@
 // region:    --- Modules
 
 mod aipack_base_dir;
+mod aipack_config;
 mod aipack_paths;
 mod aipack_wks_dir; // Added new module
 mod dir_context_impl;
+mod model_policy;
 mod pack_dir;
+mod pack_source;
 mod path_consts;
 mod path_resolvers;
 
 pub use aipack_base_dir::*;
+pub use aipack_config::*;
 pub use aipack_paths::*;
 pub use aipack_wks_dir::*; // Export new type
 pub use dir_context_impl::*;
+pub use model_policy::*;
 pub use pack_dir::*;
+pub use pack_source::*;
 pub use path_consts::*;
 pub use path_resolvers::*; // Export path constants
 
@@ -0,0 +1,131 @@
+//! The `[model_policy]` config, the allowed and banned models and providers of the workspace,
+//! enforced by the run engine before each AI call (e.g., for the teams that can only use approved providers).
+//!
+//! ```toml
+//! [model_policy]
+//! allowed_providers = ["openai", "anthropic"] # if not empty, only these providers
+//! banned_providers  = ["deepseek"]
+//! allowed_models    = ["gpt-5*", "claude-*"]  # globs, if not empty, only the matching models
+//! banned_models     = ["*-preview"]
+//! ```
+//!
+//! The banned lists win over the allowed lists. The model globs match the resolved model name
+//! (after the aliases, without the `provider::` namespace), and the providers are the genai adapter kinds (lowercase).
+
+use crate::{Error, Result};
+use serde::Deserialize;
+use simple_fs::get_glob_set;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPolicy {
+	#[serde(default)]
+	pub allowed_providers: Vec<String>,
+	#[serde(default)]
+	pub banned_providers: Vec<String>,
+	#[serde(default)]
+	pub allowed_models: Vec<String>,
+	#[serde(default)]
+	pub banned_models: Vec<String>,
+}
+
+/// Validators
+impl ModelPolicy {
+	/// Validate the model globs (so that a bad policy fails at config load time).
+	pub(super) fn validate(&self) -> Result<()> {
+		for globs in [&self.allowed_models, &self.banned_models] {
+			glob_match(globs, "")?;
+		}
+		Ok(())
+	}
+
+	/// Returns a `Error::ModelPolicyViolation` if the `model` of the `provider` is not allowed.
+	pub fn check(&self, model: &str, provider: &str) -> Result<()> {
+		let violation = |reason: String| Error::ModelPolicyViolation {
+			model: model.to_string(),
+			provider: provider.to_string(),
+			reason,
+		};
+
+		if contains_provider(&self.banned_providers, provider) {
+			return Err(violation(format!("provider '{provider}' is in banned_providers")));
+		}
+		if glob_match(&self.banned_models, model)? {
+			return Err(violation(format!(
+				"model matches banned_models {:?}",
+				self.banned_models
+			)));
+		}
+		if !self.allowed_providers.is_empty() && !contains_provider(&self.allowed_providers, provider) {
+			return Err(violation(format!(
+				"provider '{provider}' is not in allowed_providers {:?}",
+				self.allowed_providers
+			)));
+		}
+		if !self.allowed_models.is_empty() && !glob_match(&self.allowed_models, model)? {
+			return Err(violation(format!(
+				"model does not match allowed_models {:?}",
+				self.allowed_models
+			)));
+		}
+
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+fn contains_provider(providers: &[String], provider: &str) -> bool {
+	providers.iter().any(|item| item.eq_ignore_ascii_case(provider))
+}
+
+/// Returns true if the `value` matches one of the `globs` (false if no globs)
+fn glob_match(globs: &[String], value: &str) -> Result<bool> {
+	if globs.is_empty() {
+		return Ok(false);
+	}
+	let glob_refs = globs.iter().map(String::as_str).collect::<Vec<_>>();
+	let glob_set = get_glob_set(&glob_refs)
+		.map_err(|err| Error::custom(format!("[model_policy] invalid model globs {globs:?}. {err}")))?;
+	Ok(glob_set.is_match(value))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_model_policy_check() -> Result<()> {
+		// -- Setup & Fixtures
+		let policy = ModelPolicy {
+			allowed_providers: vec!["openai".to_string(), "Anthropic".to_string()],
+			banned_providers: vec![],
+			allowed_models: vec!["gpt-5*".to_string(), "claude-*".to_string()],
+			banned_models: vec!["*-preview".to_string()],
+		};
+
+		// -- Exec & Check
+		assert!(policy.check("gpt-5-mini", "openai").is_ok());
+		assert!(policy.check("claude-sonnet-4-5", "anthropic").is_ok());
+		assert!(policy.check("gpt-4o", "openai").is_err());
+		assert!(policy.check("gpt-5-preview", "openai").is_err());
+		assert!(policy.check("gemini-2.5-pro", "gemini").is_err());
+		assert!(ModelPolicy::default().check("any-model", "ollama").is_ok());
+		let banned = ModelPolicy {
+			banned_providers: vec!["deepseek".to_string()],
+			..Default::default()
+		};
+		let err = banned.check("deepseek-chat", "deepseek").err().ok_or("Should be a violation")?;
+		assert!(matches!(err, Error::ModelPolicyViolation { .. }));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
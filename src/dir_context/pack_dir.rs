@@ -33,6 +33,7 @@ impl PackDir {
 		let prefix = match self.repo_kind {
 			RepoKind::WksCustom => "",
 			RepoKind::BaseCustom => "~/",
+			RepoKind::Source => "",
 			RepoKind::BaseInstalled => "~/",
 		};
 		format!("{prefix}{last_five}")
@@ -53,6 +54,9 @@ pub fn find_pack_dirs(dir_context: &DirContext, pack_ref: &PackRef) -> Result<Ve
 	let mut pack_dirs = Vec::new();
 
 	for repo_dir in repo_dirs {
+		if !repo_dir.accepts_namespace(&pack_ref.namespace) {
+			continue;
+		}
 		let ns_dirs = list_dirs(repo_dir.path(), 1, true);
 		let found_ns_dir = ns_dirs.into_iter().find(|ns_dir| ns_dir.name() == pack_ref.namespace);
 		let repo_kind = repo_dir.kind;
@@ -117,6 +121,9 @@ pub fn lookup_pack_dirs(dir_context: &DirContext, ns: Option<&str>, pack_name: O
 		let repo_kind = repo_dir.kind;
 		match (ns, pack_name) {
 			(Some(ns_name), Some(pack_name)) => {
+				if !repo_dir.accepts_namespace(ns_name) {
+					continue;
+				}
 				let ns_dirs = list_dirs(repo_dir.path(), 1, true);
 				let found_ns_dir = ns_dirs.into_iter().find(|ns_dir| ns_dir.name() == ns_name);
 
@@ -135,6 +142,9 @@ pub fn lookup_pack_dirs(dir_context: &DirContext, ns: Option<&str>, pack_name: O
 				for pack_path in pack_dir_paths {
 					let path_pack_name = pack_path.name();
 					let path_ns = pack_path.parent_name();
+					if !repo_dir.accepts_namespace(path_ns) {
+						continue;
+					}
 
 					// compute if we should include or not (if input is none, then, match, hence the unwrap_or true)
 					let pass = match (ns, pack_name) {
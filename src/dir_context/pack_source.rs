@@ -0,0 +1,134 @@
+//! The additional pack sources, from the `[[pack_sources]]` of the base config
+//! (`~/.aipack-base/config-default.toml` and `config-user.toml`, the last one defining it wins).
+//!
+//! ```toml
+//! [[pack_sources]]
+//! name       = "acme-internal"        # optional, for display
+//! path       = "/opt/acme/aipacks"    # dir of `namespace/pack_name/` pack dirs (`~/` or relative to `~/.aipack-base/`)
+//! namespaces = ["acme"]               # optional, only these namespaces resolve from this source
+//! ```
+//!
+//! The sources resolve in their order, after the workspace and base `pack/custom/`, and before the base `pack/installed/`.
+//!
+//! NOTE: The workspace `.aipack/config.toml` cannot define pack sources.
+
+use crate::dir_context::{AipackBaseDir, CONFIG_BASE_DEFAULT_FILE_NAME, CONFIG_BASE_USER_FILE_NAME};
+use crate::support::files::home_dir;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use simple_fs::{SPath, read_to_string};
+
+#[derive(Debug, Clone)]
+pub struct PackSource {
+	pub name: Option<String>,
+	/// The absolute dir of the `namespace/pack_name/` pack dirs
+	pub path: SPath,
+	/// When set, only these namespaces resolve from this source
+	pub namespaces: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PackSourceConfig {
+	name: Option<String>,
+	path: String,
+	namespaces: Option<Vec<String>>,
+}
+
+/// Load the pack sources of the base config files (the ones that do not exist are skipped).
+pub fn load_base_pack_sources(aipack_base_dir: &AipackBaseDir) -> Result<Vec<PackSource>> {
+	let mut pack_sources = Vec::new();
+
+	for file_name in [CONFIG_BASE_DEFAULT_FILE_NAME, CONFIG_BASE_USER_FILE_NAME] {
+		let config_path = aipack_base_dir.join(file_name);
+		if !config_path.exists() {
+			continue;
+		}
+		let config_value = parse_toml_into_json(&read_to_string(&config_path)?)?;
+		if let Some(item_sources) = parse_pack_sources(aipack_base_dir, &config_value).map_err(|err| Error::Config {
+			path: config_path.to_string(),
+			reason: err.to_string(),
+		})? {
+			pack_sources = item_sources;
+		}
+	}
+
+	Ok(pack_sources)
+}
+
+// region:    --- Support
+
+fn parse_pack_sources(aipack_base_dir: &AipackBaseDir, config_value: &Value) -> Result<Option<Vec<PackSource>>> {
+	let Some(sources_value) = config_value.get("pack_sources") else {
+		return Ok(None);
+	};
+
+	let sources: Vec<PackSourceConfig> = serde_json::from_value(sources_value.clone()).map_err(|err| {
+		Error::custom(format!(
+			"'pack_sources' must be an array of {{ name?, path, namespaces? }}.\nCause: {err}"
+		))
+	})?;
+
+	let sources = sources
+		.into_iter()
+		.map(|PackSourceConfig { name, path, namespaces }| {
+			let path = SPath::new(path);
+			let path = if path.starts_with("~/") {
+				path.into_replace_prefix("~", home_dir())
+			} else if path.is_absolute() {
+				path
+			} else {
+				aipack_base_dir.join(path)
+			};
+			PackSource {
+				name,
+				path: path.into_collapsed(),
+				namespaces,
+			}
+		})
+		.collect();
+
+	Ok(Some(sources))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_pack_source_parse_pack_sources() -> Result<()> {
+		// -- Setup & Fixtures
+		let base_dir = AipackBaseDir::new_for_test("/tmp/.aipack-base")?;
+		let fx_config = json!({
+			"pack_sources": [
+				{"name": "acme-internal", "path": "/opt/acme/aipacks", "namespaces": ["acme"]},
+				{"path": "team/aipacks"}
+			]
+		});
+
+		// -- Exec
+		let sources = parse_pack_sources(&base_dir, &fx_config)?.ok_or("Should have pack sources")?;
+
+		// -- Check
+		assert_eq!(sources.len(), 2);
+		assert_eq!(sources[0].path.as_str(), "/opt/acme/aipacks");
+		assert_eq!(sources[0].namespaces.as_deref(), Some(&["acme".to_string()][..]));
+		assert_eq!(sources[1].path.as_str(), "/tmp/.aipack-base/team/aipacks");
+		assert!(sources[1].namespaces.is_none());
+		assert!(parse_pack_sources(&base_dir, &json!({}))?.is_none());
+		assert!(parse_pack_sources(&base_dir, &json!({"pack_sources": [{"dir": "x"}]})).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -28,6 +28,15 @@ pub enum Error {
 		reason: String,
 	},
 
+	#[display(
+		"Model '{model}' (provider '{provider}') is not allowed by the [model_policy] config\n  reason: {reason}"
+	)]
+	ModelPolicyViolation {
+		model: String,
+		provider: String,
+		reason: String,
+	},
+
 	// -- Pack
 	#[display("Pack Identity '{origin_path}' is not valid.\nCause: {cause}")]
 	InvalidPackIdentity {
@@ -92,6 +101,12 @@ pub enum Error {
 		new_version: String,
 	},
 
+	#[display("Cannot install pack '{pack_ref}'.\n{details}")]
+	InstallFailConflict {
+		pack_ref: String,
+		details: String,
+	},
+
 	#[display("Invalid prerelease format in version {version}. Prereleases must end with .number (e.g., -alpha.1)")]
 	InvalidPrereleaseFormat {
 		version: String,
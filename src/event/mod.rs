@@ -2,10 +2,12 @@
 
 mod cancel;
 mod one_shot;
+mod pause;
 mod unbound;
 
 pub use cancel::*;
 pub use one_shot::*;
+pub use pause::*;
 pub use unbound::*;
 
 // endregion: --- Modules
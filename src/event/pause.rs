@@ -0,0 +1,151 @@
+//!
+//! Pause helpers built on top of a `tokio::sync::watch` channel.
+//! Provides the `PauseTx` / `PauseRx` API similar to the `CancelTx` / `CancelRx` helpers.
+//!
+//! ## Design Points
+//!
+//! - Unlike cancellation, pause is a state (paused or not), so a watch channel is a natural fit.
+//! - `PauseRx::wait_if_paused` returns right away when not paused (hot path only reads the watch value).
+//! - Pausing does not interrupt the work in progress, it only holds the next unit of work (e.g., the next task).
+
+use std::fmt;
+use tokio::sync::watch;
+
+/// Create a new pause pair (not paused).
+/// - `name` - Static identifier used for diagnostics and tracing.
+pub fn new_pause_trx(name: &'static str) -> PauseTrx {
+	let (tx, rx) = watch::channel(false);
+
+	PauseTrx(PauseTx { tx, name }, PauseRx { rx, name })
+}
+
+#[derive(Clone)]
+pub struct PauseTrx(PauseTx, PauseRx);
+
+impl PauseTrx {
+	pub fn tx(&self) -> &PauseTx {
+		&self.0
+	}
+	pub fn rx(&self) -> &PauseRx {
+		&self.1
+	}
+}
+
+impl fmt::Debug for PauseTrx {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PauseTrx")
+			.field("name", &self.0.name())
+			.field("paused", &self.0.is_paused())
+			.finish()
+	}
+}
+
+// region:    --- PauseTx
+
+#[derive(Clone)]
+pub struct PauseTx {
+	tx: watch::Sender<bool>,
+	name: &'static str,
+}
+
+impl PauseTx {
+	/// Pause. This is idempotent.
+	pub fn pause(&self) {
+		self.tx.send_replace(true);
+	}
+
+	/// Resume. This is idempotent.
+	pub fn resume(&self) {
+		self.tx.send_replace(false);
+	}
+
+	pub fn is_paused(&self) -> bool {
+		*self.tx.borrow()
+	}
+
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+}
+
+impl fmt::Debug for PauseTx {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PauseTx")
+			.field("name", &self.name)
+			.field("paused", &self.is_paused())
+			.finish()
+	}
+}
+
+// endregion: --- PauseTx
+
+// region:    --- PauseRx
+
+#[derive(Clone)]
+pub struct PauseRx {
+	rx: watch::Receiver<bool>,
+	name: &'static str,
+}
+
+impl PauseRx {
+	/// Future that resolves right away if not paused, otherwise, once resumed.
+	pub async fn wait_if_paused(&self) {
+		let mut rx = self.rx.clone();
+		// NOTE: Only fails if the sender is dropped, in which case, nobody can resume, so we do not wait.
+		let _ = rx.wait_for(|paused| !paused).await;
+	}
+
+	pub fn is_paused(&self) -> bool {
+		*self.rx.borrow()
+	}
+
+	pub fn name(&self) -> &'static str {
+		self.name
+	}
+}
+
+impl fmt::Debug for PauseRx {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PauseRx")
+			.field("name", &self.name)
+			.field("paused", &self.is_paused())
+			.finish()
+	}
+}
+
+// endregion: --- PauseRx
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_event_pause_wait_if_paused() -> Result<()> {
+		// -- Setup & Fixtures
+		let trx = new_pause_trx("test_pause");
+		let rx = trx.rx().clone();
+
+		// -- Exec & Check (not paused)
+		tokio::time::timeout(Duration::from_millis(50), rx.wait_if_paused()).await?;
+
+		// -- Exec & Check (paused)
+		trx.tx().pause();
+		assert!(rx.is_paused());
+		let res = tokio::time::timeout(Duration::from_millis(50), rx.wait_if_paused()).await;
+		assert!(res.is_err(), "Should still be paused");
+
+		// -- Exec & Check (resumed)
+		let waiter = tokio::spawn(async move { rx.wait_if_paused().await });
+		trx.tx().resume();
+		tokio::time::timeout(Duration::from_millis(200), waiter).await??;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,4 +1,5 @@
 use crate::exec::ExecActionEvent;
+use crate::run::{RunCtrlCmd, RunCtrlRequest};
 use clap::{Parser, Subcommand};
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +10,14 @@ pub struct CliArgs {
 	/// Subcommands
 	#[command(subcommand)]
 	pub cmd: CliCommand,
+
+	/// Print the output as JSON (for `aip list`, `aip info`, `aip check-keys`, and `aip self doctor`)
+	#[arg(long = "json", global = true)]
+	pub json: bool,
 }
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // ok, parsed once
 pub enum CliCommand {
 	/// Initialize the workspace `.aipack/` and the base `~/.aipack-base/` aipack directories
 	Init(InitArgs),
@@ -33,29 +39,47 @@ pub enum CliCommand {
     # Use -f to give file or globs (each matched file will be a input)\n\
     aip run some/agent.aip -f \"src/**/*.js\"\n\
 		\n\
+    # Give agent parameters (declared in the agent # Meta section)\n\
+    aip run some/agent.aip --arg style=formal --arg max_len=120\n\
+    aip run some/agent.aip --args-json '{\"style\": \"formal\", \"max_len\": 120}'\n\
+		\n\
     # Run the demo@craft/code AIP agent\n\
     aip run demo@craft/code\n\
     \n\
     # Run the demo@proof main.aip agent and provide a single file as input\n\
     aip run demo@proof -f ./README.md\n\
     \n\
+    # Cancel, pause, or resume a run of another aip process (by its run uid, shown in the run info)\n\
+    aip run --cancel 0199e8a2-6b1c-7f3e-9a41-2d5c8e7b1f60\n\
+    \n\
+    # Distributed mode, dispatch the tasks to the workers (joined with `aip worker --join host:7878`)\n\
+    AIPACK_WORKER_TOKEN=my-token aip run demo@proof -f \"docs/**/*.md\" --workers-listen 0.0.0.0:7878\n\
+    \n\
+    # Export the run report (per task inputs, outputs, durations, costs) as markdown, json, or html\n\
+    aip run demo@proof -f ./README.md --export .aipack/.reports/proof.html\n\
+    \n\
     ```"
 	)]
 	Run(RunArgs),
 
-	/// Create a new agent from a built-in template
-	/// Disabled for now
-	//New(NewArgs),
+	/// Create a new pack from a built-in template `aip new --pack` or `aip new --pack my@pack_name`
+	New(NewArgs),
 
 	/// List the available aipacks `aip run list` or `aip run list demo@`
 	List(ListArgs),
 
+	/// Show a pack info (README, metadata, agents, changelog) `aip info demo@proof`
+	Info(InfoArgs),
+
 	/// Pack a directory into a .aipack file
 	Pack(PackArgs),
 
 	/// Install an aipack file
 	Install(InstallArgs),
 
+	/// Uninstall an installed pack `aip uninstall demo@proof` (with `--dry-run` to only show what would be removed)
+	Uninstall(UninstallArgs),
+
 	/// Unpack a repo pack into the workspace custom pack area
 	Unpack(UnpackArgs),
 
@@ -63,10 +87,31 @@ pub enum CliCommand {
 	#[command(name = "check-keys", about = "Check available API keys in the environment")]
 	CheckKeys(CheckKeysArgs),
 
+	/// Format and check the Lua of the workspace agent stages `aip lint-lua` (with `--fix` to format them)
+	#[command(name = "lint-lua")]
+	LintLua(LintLuaArgs),
+
+	/// Replay into the TUI the hub events recorded with `aip run ... --record-events <file>` (developer mode)
+	#[command(name = "replay-events")]
+	ReplayEvents(ReplayEventsArgs),
+
 	/// Create a .gitignore file from a template
 	#[command(name = "create-gitignore", about = "Create a .gitignore file from a template")]
 	CreateGitignore(CreateGitignoreArgs),
 
+	/// Join a coordinator run (`aip run ... --workers-listen <addr>`) as a worker `aip worker --join host:7878`
+	Worker(WorkerArgs),
+
+	/// Register a recurring agent run `aip schedule "0 9 * * 1" my-pack@agent`, and run them with `aip schedule --daemon`
+	Schedule(ScheduleArgs),
+
+	/// Serve the runs over a REST API (with a Server-Sent Events stream) `aip serve --listen 127.0.0.1:7979`
+	Serve(ServeArgs),
+
+	/// Serve the pack agents as MCP tools over stdio (pack.toml `[[mcp.tools]]`), e.g., for Claude Desktop
+	#[command(name = "mcp-serve")]
+	McpServe(McpServeArgs),
+
 	/// Self management commands (e.g., setup, update)
 	#[command(name = "self", about = "Manage the aip CLI itself")]
 	Xelf(XelfArgs),
@@ -77,18 +122,27 @@ impl CliCommand {
 	/// Returns true if this CliCommand should be in interative mode.
 	///
 	/// For now, for all Run, the interactive is on by default, regardless if it watch.
+	/// (except for the run control commands, e.g., `aip run --cancel <run-uid>`)
 	pub fn is_interactive(&self) -> bool {
 		match self {
-			CliCommand::Run(run_args) => !run_args.single_shot,
+			CliCommand::Run(run_args) => !run_args.single_shot && run_args.run_ctrl_request().is_none(),
 			CliCommand::Init(_) => false,
 			CliCommand::InitBase => false,
-			//CliCommand::New(_) => true,
+			CliCommand::New(_) => false,
 			CliCommand::List(_) => false,
+			CliCommand::Info(_) => false,
 			CliCommand::Pack(_) => false,
 			CliCommand::Install(_) => false,
+			CliCommand::Uninstall(_) => false,
 			CliCommand::Unpack(_) => false,
-			CliCommand::CheckKeys(_) => false,       // Non-interactive
+			CliCommand::CheckKeys(_) => false, // Non-interactive
+			CliCommand::LintLua(_) => false,   // Non-interactive
+			CliCommand::ReplayEvents(_) => true,
 			CliCommand::CreateGitignore(_) => false, // Non-interactive
+			CliCommand::Worker(_) => false,          // Non-interactive
+			CliCommand::Schedule(_) => false,        // Non-interactive
+			CliCommand::Serve(_) => false,           // Non-interactive
+			CliCommand::McpServe(_) => false,        // Non-interactive
 			CliCommand::Xelf(_) => false,            // Non-interactive
 		}
 	}
@@ -98,16 +152,30 @@ impl CliCommand {
 			CliCommand::Run(run_args) => run_args.is_tui(),
 			CliCommand::Init(_) => false,
 			CliCommand::InitBase => false,
-			//CliCommand::New(_) => false,
+			CliCommand::New(_) => false,
 			CliCommand::List(_) => false,
+			CliCommand::Info(_) => false,
 			CliCommand::Pack(_) => false,
 			CliCommand::Install(_) => false,
+			CliCommand::Uninstall(_) => false,
 			CliCommand::Unpack(_) => false,
-			CliCommand::CheckKeys(_) => false,       // Non-interactive
+			CliCommand::CheckKeys(_) => false, // Non-interactive
+			CliCommand::LintLua(_) => false,   // Non-interactive
+			CliCommand::ReplayEvents(_) => true,
 			CliCommand::CreateGitignore(_) => false, // Non-interactive
+			CliCommand::Worker(_) => false,          // Non-interactive
+			CliCommand::Schedule(_) => false,        // Non-interactive
+			CliCommand::Serve(_) => false,           // Non-interactive
+			CliCommand::McpServe(_) => false,        // Non-interactive
 			CliCommand::Xelf(_) => false,            // Non-interactive
 		}
 	}
+
+	/// Returns true if the stdout is a protocol channel (e.g., `aip mcp-serve`),
+	/// then, the messages must be printed to the stderr.
+	pub fn is_stdio_server(&self) -> bool {
+		matches!(self, CliCommand::McpServe(_))
+	}
 }
 
 // region:    --- Sub Command Args
@@ -119,7 +187,10 @@ pub struct RunArgs {
 - A AIP pack reference:\n\
   `aip run demo@proof`\n\
 - Or a direct file:\n\
-  `aip run path/to/agent.aip`")]
+  `aip run path/to/agent.aip`",
+		default_value = "",
+		required_unless_present_any = ["cancel_run", "pause_run", "resume_run"]
+	)]
 	pub cmd_agent_name: String,
 
 	/// Optional input, allowing multiple input
@@ -132,6 +203,16 @@ pub struct RunArgs {
 	#[arg(short = 'f', long = "on-files")]
 	pub on_files: Option<Vec<String>>,
 
+	/// Agent parameter `name=value` (validated against the agent `# Meta` params), allowing multiple
+	/// (available in Lua as `args.name`)
+	#[arg(long = "arg", value_name = "NAME=VALUE")]
+	pub args: Option<Vec<String>>,
+
+	/// Agent parameters as a JSON object (e.g., `{"env": "prod"}`), validated as the `--arg`
+	/// (the `--arg` values take precedence)
+	#[arg(long = "args-json", value_name = "JSON")]
+	pub args_json: Option<String>,
+
 	/// Optional watch flag
 	#[arg(short = 'w', long = "watch")]
 	pub watch: bool,
@@ -160,6 +241,44 @@ pub struct RunArgs {
 	/// The Old Terminal
 	#[arg(long = "old-term")]
 	old_term: bool,
+
+	/// Cancel the run (by its run uid) of another aip process (no agent run)
+	#[arg(long = "cancel", value_name = "RUN_UID", group = "run_ctrl")]
+	pub cancel_run: Option<String>,
+
+	/// Pause the run (by its run uid) of another aip process (no agent run)
+	#[arg(long = "pause", value_name = "RUN_UID", group = "run_ctrl")]
+	pub pause_run: Option<String>,
+
+	/// Resume the run (by its run uid) of another aip process (no agent run)
+	#[arg(long = "resume", value_name = "RUN_UID", group = "run_ctrl")]
+	pub resume_run: Option<String>,
+
+	/// Distributed mode, listen for the workers (`aip worker --join <addr>`) on this address (e.g., `0.0.0.0:7878`)
+	/// and dispatch the tasks to them (the before all and after all stages still run locally).
+	/// A non loopback address requires the `AIPACK_WORKER_TOKEN` shared token (sent in plaintext)
+	#[arg(long = "workers-listen", value_name = "ADDR")]
+	pub workers_listen: Option<String>,
+
+	/// Export the run report (per task inputs, outputs, durations, tokens, costs) at the end of the run,
+	/// with the format from the extension (`.md`, `.json`, or `.html`)
+	#[arg(long = "export", value_name = "PATH")]
+	pub export: Option<String>,
+
+	/// The priority class of the run tasks, 'interactive', 'normal' (default), or 'batch'
+	/// (when the config `[run] max_tasks` bounds the running tasks of the runs)
+	#[arg(long = "priority", value_parser = ["interactive", "normal", "batch"])]
+	pub priority: Option<String>,
+
+	/// Start the run from the beginning, ignoring the checkpoint of an interrupted run
+	/// of the same agent, args, and inputs (resumed by default)
+	#[arg(long = "fresh")]
+	pub fresh: bool,
+
+	/// Record the hub events of the session to this file (JSON Lines),
+	/// to reproduce the UI issues with `aip replay-events <file>`
+	#[arg(long = "record-events", value_name = "PATH")]
+	pub record_events: Option<String>,
 }
 
 impl RunArgs {
@@ -167,6 +286,19 @@ impl RunArgs {
 		// self.xp_tui // for 0.7.x
 		!self.old_term // for 0.8.x
 	}
+
+	/// Returns the run control request if this is a `--cancel`, `--pause`, or `--resume` command.
+	pub fn run_ctrl_request(&self) -> Option<RunCtrlRequest> {
+		let (run_ref, cmd) = if let Some(run_ref) = self.cancel_run.as_ref() {
+			(run_ref, RunCtrlCmd::Cancel)
+		} else if let Some(run_ref) = self.pause_run.as_ref() {
+			(run_ref, RunCtrlCmd::Pause)
+		} else {
+			(self.resume_run.as_ref()?, RunCtrlCmd::Resume)
+		};
+
+		Some(RunCtrlRequest::new(run_ref, cmd))
+	}
 }
 /// Arguments for the `pack` subcommand
 #[derive(Parser, Debug)]
@@ -184,13 +316,45 @@ pub struct PackArgs {
 #[derive(Parser, Debug)]
 pub struct InstallArgs {
 	/// Force installation even if the pack is already installed and up to date
+	/// (also installs when the same namespace/name exists from another source)
 	#[arg(long = "force")]
 	pub force: bool,
 
+	/// Install the pack under another `namespace@name` (e.g., when it conflicts with a pack from another source)
+	#[arg(long = "as", value_name = "NAMESPACE@NAME")]
+	pub alias: Option<String>,
+
+	/// Grant the capabilities declared by the pack (e.g., `net`, `exec`) without prompting
+	#[arg(short = 'y', long = "yes")]
+	pub yes: bool,
+
+	/// Install exactly the packs of the workspace `.aipack/aipack.lock` (all, or the given pack and its dependencies),
+	/// failing if a source does not match its locked version, commit, or content hash
+	#[arg(long = "locked", conflicts_with = "alias")]
+	pub locked: bool,
+
 	/// The path to the .aipack file to install
 	/// Can be the path to the `path/to/some-pack.aipack`
 	/// Or later, can be `namspace@pack_name` and in this case, it will look aipack.ai registry
-	pub aipack_ref: String,
+	/// Or a git repo, with an optional branch, tag, or commit (e.g., `https://github.com/org/repo.git#main`)
+	/// (optional with `--locked`)
+	#[arg(required_unless_present = "locked")]
+	pub aipack_ref: Option<String>,
+}
+
+/// Arguments for the `uninstall` subcommand
+#[derive(Parser, Debug)]
+pub struct UninstallArgs {
+	/// The pack identity to uninstall (e.g., `namespace@pack_name`)
+	pub pack_ref: String,
+
+	/// Also remove the pack support data (`support/pack/<namespace>/<name>/` of the base and workspace)
+	#[arg(long = "data")]
+	pub data: bool,
+
+	/// Only show what would be removed
+	#[arg(long = "dry-run")]
+	pub dry_run: bool,
 }
 
 /// Arguments for the `unpack` subcommand
@@ -204,6 +368,29 @@ pub struct UnpackArgs {
 	pub force: bool,
 }
 
+/// Arguments for the `lint-lua` subcommand
+#[derive(Parser, Debug)]
+pub struct LintLuaArgs {
+	/// The agent files or globs to lint, relative to the workspace
+	/// (default all the workspace agents `**/*.aip`)
+	pub paths: Vec<String>,
+
+	/// Format the stage Lua blocks in place (the other issues are only reported)
+	#[arg(long = "fix")]
+	pub fix: bool,
+}
+
+/// Arguments for the `replay-events` subcommand
+#[derive(Parser, Debug)]
+pub struct ReplayEventsArgs {
+	/// The hub events record file (from `aip run ... --record-events <file>`)
+	pub file: String,
+
+	/// The replay speed factor (e.g., `2` for twice as fast, `0.5` for half speed)
+	#[arg(long = "speed", default_value_t = 1.0)]
+	pub speed: f64,
+}
+
 /// Arguments for the `list` subcommand
 #[derive(Parser, Debug)]
 pub struct ListArgs {
@@ -212,18 +399,47 @@ pub struct ListArgs {
 	/// e.g., `pro@coder` or `jc@` or `@coder`
 	pub pack_ref: Option<String>,
 
+	/// Only the packs with this tag (pack.toml `[pack] tags = [...]`)
+	#[arg(long = "tag")]
+	pub tag: Option<String>,
+
+	/// Print the list as JSON (with the status, version, tags, and main agent meta)
+	/// (set from the global `--json` flag)
+	#[arg(skip)]
+	pub json: bool,
+
+	/// Check the installed packs against the repo latest versions (to show the outdated ones)
+	#[arg(long = "check-updates")]
+	pub check_updates: bool,
+
 	/// Open the .aipack file, and the target file if exists.
 	/// Note: For now assume vscode `code ...` is installed
 	#[arg(short = 'o', long = "open")]
 	pub open: bool,
 }
 
-/// DISABLED FOR NOW
+/// Arguments for the `info` subcommand
+#[derive(Parser, Debug)]
+pub struct InfoArgs {
+	/// The pack reference (e.g., `demo@proof`)
+	pub pack_ref: String,
+
+	/// Print the info as JSON (set from the global `--json` flag)
+	#[arg(skip)]
+	pub json: bool,
+}
+
 /// Arguments for the `new` subcommand
+/// NOTE: For now, only the `--pack` mode is supported
 #[derive(Parser, Debug)]
 pub struct NewArgs {
+	/// The agent path, or with `--pack`, the `namespace@pack_name` of the new pack (prompted if absent)
 	pub agent_path: Option<String>,
 
+	/// Create a new pack (pack.toml, main.aip, agents/, README.md) in `.aipack/pack/custom/` from a template
+	#[arg(long)]
+	pub pack: bool,
+
 	/// Open the .aipack file, and the target file if exists.
 	/// Note: For now assume vscode `code ...` is installed
 	#[arg(short = 'o', long = "open")]
@@ -240,7 +456,11 @@ pub struct InitArgs {
 
 /// Arguments for the `check-keys` subcommand
 #[derive(Parser, Debug)]
-pub struct CheckKeysArgs {}
+pub struct CheckKeysArgs {
+	/// Print the keys status as JSON (set from the global `--json` flag)
+	#[arg(skip)]
+	pub json: bool,
+}
 
 /// Arguments for the `create-gitignore` subcommand
 #[derive(Parser, Debug)]
@@ -250,6 +470,72 @@ pub struct CreateGitignoreArgs {
 	pub force: bool,
 }
 
+/// Arguments for the `worker` subcommand
+#[derive(Parser, Debug)]
+pub struct WorkerArgs {
+	/// The coordinator address (the `aip run ... --workers-listen <addr>`), e.g., `host:7878`
+	#[arg(long = "join", value_name = "ADDR")]
+	pub join: String,
+
+	/// The number of tasks this worker runs at the same time
+	#[arg(long = "slots", default_value_t = 1)]
+	pub slots: usize,
+
+	/// The worker name shown by the coordinator (default the HOSTNAME env)
+	#[arg(long = "name")]
+	pub name: Option<String>,
+}
+
+/// Arguments for the `schedule` subcommand
+#[derive(Parser, Debug)]
+pub struct ScheduleArgs {
+	/// The cron expression `minute hour day-of-month month day-of-week` (local time), e.g., `"0 9 * * 1"` or `@daily`
+	#[arg(conflicts_with_all = ["list", "remove", "daemon"])]
+	pub cron: Option<String>,
+
+	/// The agent to run (pack reference or agent file)
+	pub agent: Option<String>,
+
+	/// The inputs of the scheduled run
+	#[arg(short = 'i', long = "input")]
+	pub on_inputs: Option<Vec<String>>,
+
+	/// The file globs of the scheduled run (relative to the current dir)
+	#[arg(short = 'f', long = "on-files")]
+	pub on_files: Option<Vec<String>>,
+
+	/// The agent parameters of the scheduled run (NAME=VALUE)
+	#[arg(long = "arg", value_name = "NAME=VALUE")]
+	pub args: Option<Vec<String>>,
+
+	/// List the schedules (the default without a cron expression)
+	#[arg(long = "list")]
+	pub list: bool,
+
+	/// Remove a schedule by id
+	#[arg(long = "remove", value_name = "ID")]
+	pub remove: Option<u32>,
+
+	/// Run the schedules (long running process)
+	#[arg(long = "daemon", conflicts_with = "remove")]
+	pub daemon: bool,
+}
+
+/// Arguments for the `mcp-serve` subcommand
+#[derive(Parser, Debug)]
+pub struct McpServeArgs {
+	/// Only the tools of these packs, a complete or partial aipack reference (e.g., `jc@coder` or `jc@`)
+	pub pack_ref: Option<String>,
+}
+
+/// Arguments for the `serve` subcommand
+#[derive(Parser, Debug)]
+pub struct ServeArgs {
+	/// The address to listen on (e.g., `0.0.0.0:7979` to accept remote connections, requires `AIPACK_SERVE_TOKEN`)
+	#[arg(long = "listen", default_value = "127.0.0.1:7979")]
+	pub listen: String,
+}
+
 /// Arguments for the `self` subcommand
 #[derive(Parser, Debug)]
 pub struct XelfArgs {
@@ -263,6 +549,8 @@ pub enum XelfCommand {
 	/// Perform initial setup for the aip CLI environment
 	Setup(XelfSetupArgs),
 	Update(XelfUpdateArgs),
+	/// Check the aipack environment (base dir, workspace, API keys, PATH, terminal) and print the fixes
+	Doctor(XelfDoctorArgs),
 }
 
 /// Arguments for the `self setup` subcommand
@@ -277,29 +565,67 @@ pub struct XelfUpdateArgs {
 	pub version: Option<String>,
 }
 
+/// Arguments for the `self doctor` subcommand
+#[derive(Parser, Debug)]
+pub struct XelfDoctorArgs {
+	/// Print the checks as JSON (set from the global `--json` flag)
+	#[arg(skip)]
+	pub json: bool,
+}
+
 // endregion: --- Sub Command Args
 
 // region:    --- From CliCommand to ExecCommand
 
+impl From<CliArgs> for ExecActionEvent {
+	fn from(cli_args: CliArgs) -> Self {
+		let CliArgs { mut cmd, json } = cli_args;
+
+		// -- Propagate the global flags to the sub command args
+		match &mut cmd {
+			CliCommand::List(list_args) => list_args.json = json,
+			CliCommand::Info(info_args) => info_args.json = json,
+			CliCommand::CheckKeys(check_keys_args) => check_keys_args.json = json,
+			CliCommand::Xelf(XelfArgs {
+				cmd: XelfCommand::Doctor(doctor_args),
+			}) => doctor_args.json = json,
+			_ => (),
+		}
+
+		cmd.into()
+	}
+}
+
 impl From<CliCommand> for ExecActionEvent {
 	fn from(cli_cmd: CliCommand) -> Self {
 		match cli_cmd {
 			CliCommand::Init(init_args) => ExecActionEvent::CmdInit(init_args),
 			CliCommand::InitBase => ExecActionEvent::CmdInitBase,
-			CliCommand::Run(run_args) => ExecActionEvent::Run(run_args),
-			// CliCommand::New(new_args) => ExecActionEvent::CmdNew(new_args),
-			// CliCommand::New(new_args) => ExecCommand::NewCommandAgent(new_args),
+			CliCommand::Run(run_args) => match run_args.run_ctrl_request() {
+				Some(request) => ExecActionEvent::CmdRunCtrl(request),
+				None => ExecActionEvent::Run(run_args),
+			},
+			CliCommand::New(new_args) => ExecActionEvent::CmdNew(new_args),
 			CliCommand::List(list_args) => ExecActionEvent::CmdList(list_args),
+			CliCommand::Info(info_args) => ExecActionEvent::CmdInfo(info_args),
 			CliCommand::Pack(pack_args) => ExecActionEvent::CmdPack(pack_args),
 			CliCommand::Install(install_args) => ExecActionEvent::CmdInstall(install_args),
+			CliCommand::Uninstall(uninstall_args) => ExecActionEvent::CmdUninstall(uninstall_args),
 			CliCommand::Unpack(unpack_args) => ExecActionEvent::CmdUnpack(unpack_args),
 			CliCommand::CheckKeys(args) => ExecActionEvent::CmdCheckKeys(args),
+			CliCommand::LintLua(args) => ExecActionEvent::CmdLintLua(args),
+			CliCommand::ReplayEvents(args) => ExecActionEvent::CmdReplayEvents(args),
 			CliCommand::CreateGitignore(args) => ExecActionEvent::CmdCreateGitignore(args),
+			CliCommand::Worker(args) => ExecActionEvent::CmdWorker(args),
+			CliCommand::Schedule(args) => ExecActionEvent::CmdSchedule(args),
+			CliCommand::Serve(args) => ExecActionEvent::CmdServe(args),
+			CliCommand::McpServe(args) => ExecActionEvent::CmdMcpServe(args),
 			CliCommand::Xelf(xelf_args) => {
 				// Map Xelf subcommands to specific ExecActionEvent variants
 				match xelf_args.cmd {
 					XelfCommand::Setup(args) => ExecActionEvent::CmdXelfSetup(args),
 					XelfCommand::Update(args) => ExecActionEvent::CmdXelfUpdate(args),
+					XelfCommand::Doctor(args) => ExecActionEvent::CmdXelfDoctor(args),
 				}
 			}
 		}
@@ -307,3 +633,211 @@ impl From<CliCommand> for ExecActionEvent {
 }
 
 // endregion: --- From CliCommand to ExecCommand
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use clap::CommandFactory as _;
+
+	#[test]
+	fn test_cli_args_global_json() -> Result<()> {
+		// -- Exec
+		let list_args = CliArgs::try_parse_from(["aip", "list", "--json", "jc@"])?;
+		let check_keys_args = CliArgs::try_parse_from(["aip", "--json", "check-keys"])?;
+		let no_json_args = CliArgs::try_parse_from(["aip", "list"])?;
+		let doctor_args = CliArgs::try_parse_from(["aip", "self", "doctor", "--json"])?;
+
+		// -- Check
+		let ExecActionEvent::CmdList(list_args) = list_args.into() else {
+			return Err("Should be a CmdList".into());
+		};
+		assert!(list_args.json);
+		assert_eq!(list_args.pack_ref.as_deref(), Some("jc@"));
+		let ExecActionEvent::CmdCheckKeys(check_keys_args) = check_keys_args.into() else {
+			return Err("Should be a CmdCheckKeys".into());
+		};
+		assert!(check_keys_args.json);
+		let ExecActionEvent::CmdList(no_json_args) = no_json_args.into() else {
+			return Err("Should be a CmdList".into());
+		};
+		assert!(!no_json_args.json);
+		let ExecActionEvent::CmdXelfDoctor(doctor_args) = doctor_args.into() else {
+			return Err("Should be a CmdXelfDoctor".into());
+		};
+		assert!(doctor_args.json);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_run_ctrl() -> Result<()> {
+		// -- Setup & Fixtures
+		CliArgs::command().debug_assert();
+
+		// -- Exec
+		let cancel_args = CliArgs::try_parse_from(["aip", "run", "--cancel", "3"])?;
+		let run_args = CliArgs::try_parse_from(["aip", "run", "my-agent"])?;
+		let no_agent_res = CliArgs::try_parse_from(["aip", "run"]);
+		let conflict_res = CliArgs::try_parse_from(["aip", "run", "--cancel", "3", "--pause", "3"]);
+
+		// -- Check
+		assert!(!cancel_args.cmd.is_interactive());
+		let ExecActionEvent::CmdRunCtrl(request) = cancel_args.cmd.into() else {
+			return Err("Should be a CmdRunCtrl".into());
+		};
+		assert_eq!(request.run_ref, "3");
+		assert_eq!(request.cmd, RunCtrlCmd::Cancel);
+		assert!(run_args.cmd.is_interactive());
+		assert!(
+			no_agent_res.is_err(),
+			"agent name should be required without run control"
+		);
+		assert!(conflict_res.is_err(), "run controls should be exclusive");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_worker() -> Result<()> {
+		// -- Exec
+		let worker_args = CliArgs::try_parse_from(["aip", "worker", "--join", "host:7878", "--slots", "4"])?;
+		let run_args = CliArgs::try_parse_from(["aip", "run", "my-agent", "--workers-listen", "0.0.0.0:7878"])?;
+		let no_join_res = CliArgs::try_parse_from(["aip", "worker"]);
+
+		// -- Check
+		assert!(!worker_args.cmd.is_interactive());
+		let ExecActionEvent::CmdWorker(worker_args) = worker_args.cmd.into() else {
+			return Err("Should be a CmdWorker".into());
+		};
+		assert_eq!(worker_args.join, "host:7878");
+		assert_eq!(worker_args.slots, 4);
+		let ExecActionEvent::Run(run_args) = run_args.cmd.into() else {
+			return Err("Should be a Run".into());
+		};
+		assert_eq!(run_args.workers_listen.as_deref(), Some("0.0.0.0:7878"));
+		assert!(no_join_res.is_err(), "--join should be required");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_lint_lua() -> Result<()> {
+		// -- Exec
+		let all_args = CliArgs::try_parse_from(["aip", "lint-lua"])?;
+		let fix_args = CliArgs::try_parse_from(["aip", "lint-lua", "--fix", "agents/*.aip", "main.aip"])?;
+
+		// -- Check
+		assert!(!all_args.cmd.is_interactive());
+		let ExecActionEvent::CmdLintLua(all_args) = all_args.cmd.into() else {
+			return Err("Should be a CmdLintLua".into());
+		};
+		assert!(all_args.paths.is_empty());
+		assert!(!all_args.fix);
+		let ExecActionEvent::CmdLintLua(fix_args) = fix_args.cmd.into() else {
+			return Err("Should be a CmdLintLua".into());
+		};
+		assert_eq!(fix_args.paths, ["agents/*.aip", "main.aip"]);
+		assert!(fix_args.fix);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_replay_events() -> Result<()> {
+		// -- Exec
+		let args = CliArgs::try_parse_from(["aip", "replay-events", "events.jsonl", "--speed", "4"])?;
+		let default_args = CliArgs::try_parse_from(["aip", "replay-events", "events.jsonl"])?;
+		let run_args = CliArgs::try_parse_from(["aip", "run", "my-agent", "--record-events", ".tmp/events.jsonl"])?;
+
+		// -- Check
+		assert!(args.cmd.is_interactive());
+		assert!(args.cmd.is_tui());
+		let ExecActionEvent::CmdReplayEvents(args) = args.cmd.into() else {
+			return Err("Should be a CmdReplayEvents".into());
+		};
+		assert_eq!(args.file, "events.jsonl");
+		assert_eq!(args.speed, 4.0);
+		let ExecActionEvent::CmdReplayEvents(default_args) = default_args.cmd.into() else {
+			return Err("Should be a CmdReplayEvents".into());
+		};
+		assert_eq!(default_args.speed, 1.0);
+		let CliCommand::Run(run_args) = run_args.cmd else {
+			return Err("Should be a Run".into());
+		};
+		assert_eq!(run_args.record_events.as_deref(), Some(".tmp/events.jsonl"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_schedule() -> Result<()> {
+		// -- Exec
+		let register_args = CliArgs::try_parse_from(["aip", "schedule", "0 9 * * 1", "my-pack@agent", "-i", "hello"])?;
+		let daemon_args = CliArgs::try_parse_from(["aip", "schedule", "--daemon"])?;
+		let conflict_res = CliArgs::try_parse_from(["aip", "schedule", "0 9 * * 1", "my-pack@agent", "--remove", "1"]);
+
+		// -- Check
+		assert!(!register_args.cmd.is_interactive());
+		let ExecActionEvent::CmdSchedule(register_args) = register_args.cmd.into() else {
+			return Err("Should be a CmdSchedule".into());
+		};
+		assert_eq!(register_args.cron.as_deref(), Some("0 9 * * 1"));
+		assert_eq!(register_args.agent.as_deref(), Some("my-pack@agent"));
+		assert_eq!(register_args.on_inputs, Some(vec!["hello".to_string()]));
+		let ExecActionEvent::CmdSchedule(daemon_args) = daemon_args.cmd.into() else {
+			return Err("Should be a CmdSchedule".into());
+		};
+		assert!(daemon_args.daemon);
+		assert!(conflict_res.is_err(), "cron and --remove should be exclusive");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_serve() -> Result<()> {
+		// -- Exec
+		let default_args = CliArgs::try_parse_from(["aip", "serve"])?;
+		let listen_args = CliArgs::try_parse_from(["aip", "serve", "--listen", "0.0.0.0:8080"])?;
+
+		// -- Check
+		assert!(!default_args.cmd.is_interactive());
+		assert!(!default_args.cmd.is_tui());
+		let ExecActionEvent::CmdServe(default_args) = default_args.cmd.into() else {
+			return Err("Should be a CmdServe".into());
+		};
+		assert_eq!(default_args.listen, "127.0.0.1:7979");
+		let ExecActionEvent::CmdServe(listen_args) = listen_args.cmd.into() else {
+			return Err("Should be a CmdServe".into());
+		};
+		assert_eq!(listen_args.listen, "0.0.0.0:8080");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_cli_args_mcp_serve() -> Result<()> {
+		// -- Exec
+		let all_args = CliArgs::try_parse_from(["aip", "mcp-serve"])?;
+		let pack_args = CliArgs::try_parse_from(["aip", "mcp-serve", "jc@"])?;
+
+		// -- Check
+		assert!(all_args.cmd.is_stdio_server());
+		assert!(!all_args.cmd.is_interactive());
+		let ExecActionEvent::CmdMcpServe(all_args) = all_args.cmd.into() else {
+			return Err("Should be a CmdMcpServe".into());
+		};
+		assert!(all_args.pack_ref.is_none());
+		let ExecActionEvent::CmdMcpServe(pack_args) = pack_args.cmd.into() else {
+			return Err("Should be a CmdMcpServe".into());
+		};
+		assert_eq!(pack_args.pack_ref.as_deref(), Some("jc@"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -2,12 +2,14 @@
 //! Note: For now, the content of the variant of the ExecCommand often contain the CliArgs,
 //!       but this will eventual change to have it's own
 
+use crate::exec::ScheduledRun;
 use crate::exec::cli::{
-	CheckKeysArgs, CreateGitignoreArgs, InitArgs, InstallArgs, ListArgs, NewArgs, PackArgs, RunArgs, UnpackArgs,
-	XelfSetupArgs, XelfUpdateArgs,
+	CheckKeysArgs, CreateGitignoreArgs, InfoArgs, InitArgs, InstallArgs, LintLuaArgs, ListArgs, McpServeArgs, NewArgs,
+	PackArgs, ReplayEventsArgs, RunArgs, ScheduleArgs, ServeArgs, UninstallArgs, UnpackArgs, WorkerArgs,
+	XelfDoctorArgs, XelfSetupArgs, XelfUpdateArgs,
 };
 use crate::model::Id;
-use crate::run::RunSubAgentParams;
+use crate::run::{RunCtrlRequest, RunSubAgentParams};
 use derive_more::From;
 
 /// Executor Action Event that needs to be performed
@@ -30,19 +32,39 @@ pub enum ExecActionEvent {
 	CmdInitBase,
 
 	CmdList(ListArgs),
+	CmdInfo(InfoArgs),
 	CmdPack(PackArgs),
 	CmdInstall(InstallArgs),
+	CmdUninstall(UninstallArgs),
 	CmdUnpack(UnpackArgs),
 	/// Check for API keys in the environment
 	CmdCheckKeys(CheckKeysArgs),
+	/// Format and check the Lua of the workspace agents (`aip lint-lua`)
+	CmdLintLua(LintLuaArgs),
+	/// Replay the recorded hub events into the TUI (`aip replay-events`)
+	CmdReplayEvents(ReplayEventsArgs),
 	/// Create a .gitignore file from template
 	CmdCreateGitignore(CreateGitignoreArgs),
 	/// Perform `self setup` action
 	CmdXelfSetup(XelfSetupArgs),
 	/// Preform `self update`
 	CmdXelfUpdate(XelfUpdateArgs),
+	/// Perform `self doctor`
+	CmdXelfDoctor(XelfDoctorArgs),
 	/// Trigger an agent run (either from CLI or UI)
 	Run(RunArgs),
+	/// Request to cancel/pause/resume a run of another process (e.g., `aip run --cancel <run-uid>`)
+	CmdRunCtrl(RunCtrlRequest),
+	/// Join a coordinator as a worker (`aip worker --join <addr>`)
+	CmdWorker(WorkerArgs),
+	/// Register, list, or remove the schedules, or run them (`aip schedule --daemon`)
+	CmdSchedule(ScheduleArgs),
+	/// A schedule run (sent by the schedule daemon)
+	ScheduledRun(ScheduledRun),
+	/// Serve the runs over a REST API (`aip serve`)
+	CmdServe(ServeArgs),
+	/// Serve the pack agents as MCP tools over stdio (`aip mcp-serve`)
+	CmdMcpServe(McpServeArgs),
 
 	// -- Interactive Commands
 	OpenAgent,
@@ -50,11 +72,25 @@ pub enum ExecActionEvent {
 	// -- Run Commands
 	/// When press r
 	Redo,
+	/// When press R or F in the tasks view (redo only these tasks of the last run, eventually with the edited prompt)
+	RedoTasks {
+		task_idxs: Vec<usize>,
+		instruction: Option<String>,
+	},
+	/// When sending a message in the chat tab (a follow-up turn of the last run conversation)
+	ChatFollowUp(String),
 	/// When called from
 	#[from]
 	RunSubAgent(RunSubAgentParams),
 
+	/// When press C in the tasks view (cancel the selected tasks, when not ended)
+	CancelTasks(Vec<Id>),
+	/// When press X in the tasks view (export the outputs of the selected tasks)
+	ExportTasksOutputs(Vec<Id>),
+
 	CancelRun,
+	PauseRun,
+	ResumeRun,
 
 	// -- Work Lifecycle
 	WorkConfirm(Id),
@@ -76,7 +112,13 @@ impl ExecActionEvent {
 		match self {
 			ExecActionEvent::Run(run_args) => run_args.is_tui(),
 			ExecActionEvent::Redo
+			| ExecActionEvent::RedoTasks { .. }
+			| ExecActionEvent::ChatFollowUp(_)
+			| ExecActionEvent::CancelTasks(_)
+			| ExecActionEvent::ExportTasksOutputs(_)
 			| ExecActionEvent::CancelRun
+			| ExecActionEvent::PauseRun
+			| ExecActionEvent::ResumeRun
 			| ExecActionEvent::WorkConfirm(_)
 			| ExecActionEvent::WorkCancel(_)
 			| ExecActionEvent::OpenAgent => true,
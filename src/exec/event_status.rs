@@ -1,12 +1,13 @@
 //! The executor event
 
 use derive_more::derive::Display;
+use serde::{Deserialize, Serialize};
 
 /// This is the status event sent by the executor to the Hub.
 ///
 /// NOTE: This is not sent to the executor.command_tx is they are not commands,
 ///       but status events.
-#[derive(Debug, Clone, Display)]
+#[derive(Debug, Clone, Display, Serialize, Deserialize)]
 pub enum ExecStatusEvent {
 	/// Start an exec command like run,  init, ...
 	/// Get triggers for all executor event
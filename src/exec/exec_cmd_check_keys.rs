@@ -1,14 +1,26 @@
 use crate::Result;
 use crate::exec::cli::CheckKeysArgs;
 use crate::exec::support::{KEY_ENV_VARS, get_available_api_keys};
-use crate::hub::get_hub;
+use crate::hub::{HubEvent, get_hub};
 use crate::tui_v1::PrintEvent;
+use serde_json::json;
 
-/// Executes the check-keys command by getting available keys and publishing a PrintEvent.
-pub async fn exec_check_keys(_args: CheckKeysArgs) -> Result<()> {
+/// Executes the check-keys command by getting available keys and publishing a PrintEvent
+/// (or the JSON `[{name, available}]` with `--json`).
+pub async fn exec_check_keys(args: CheckKeysArgs) -> Result<()> {
 	// Get the set of available keys from the environment
 	let available_keys = get_available_api_keys();
 
+	if args.json {
+		let keys = KEY_ENV_VARS
+			.iter()
+			.map(|name| json!({"name": name, "available": available_keys.contains(*name)}))
+			.collect::<Vec<_>>();
+		let json = serde_json::to_string_pretty(&keys)?;
+		get_hub().publish(HubEvent::Message(json.into())).await;
+		return Ok(());
+	}
+
 	// Create the print event
 	let event = PrintEvent::ApiKeysStatus {
 		all_keys: KEY_ENV_VARS,
@@ -0,0 +1,125 @@
+use crate::Result;
+use crate::agent::{AgentDoc, AgentMeta};
+use crate::dir_context::{DirContext, RepoKind, find_to_run_pack_dir};
+use crate::exec::PackStatus;
+use crate::exec::cli::InfoArgs;
+use crate::exec::support::{PackTomlInfo, read_pack_toml_info};
+use crate::hub::{HubEvent, get_hub};
+use crate::tui_v1::PrintEvent;
+use crate::types::PackRef;
+use serde::Serialize;
+use simple_fs::list_files;
+
+/// The `aip info <pack>` info of a pack (the one that would be run).
+#[derive(Debug, Clone, Serialize)]
+pub struct PackInfo {
+	/// e.g., `demo@proof`
+	pub pack_ref: String,
+	pub status: PackStatus,
+	pub path: String,
+	#[serde(flatten)]
+	pub pack_toml: PackTomlInfo,
+	/// The pack `README.md` content
+	pub readme: Option<String>,
+	/// The pack `CHANGELOG.md` content
+	pub changelog: Option<String>,
+	pub agents: Vec<PackAgentInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PackAgentInfo {
+	/// The agent ref to run, e.g., `demo@proof` (for `main.aip`) or `demo@proof/sub-agent`
+	pub agent_ref: String,
+	/// The `# Meta` of the agent (description, params, examples)
+	#[serde(flatten)]
+	pub meta: AgentMeta,
+}
+
+pub async fn exec_info(dir_context: DirContext, info_args: InfoArgs) -> Result<()> {
+	let pack_info = build_pack_info(&dir_context, &info_args.pack_ref)?;
+
+	if info_args.json {
+		let json = serde_json::to_string_pretty(&pack_info)?;
+		get_hub().publish(HubEvent::Message(json.into())).await;
+	} else {
+		get_hub().publish(PrintEvent::PackInfo(Box::new(pack_info))).await;
+	}
+
+	Ok(())
+}
+
+fn build_pack_info(dir_context: &DirContext, pack_ref_str: &str) -> Result<PackInfo> {
+	let pack_ref: PackRef = pack_ref_str.parse()?;
+	let pack_dir = find_to_run_pack_dir(dir_context, &pack_ref)?;
+	let pack_ref_str = format!("{}@{}", pack_ref.namespace, pack_ref.name);
+
+	let status = match pack_dir.repo_kind {
+		RepoKind::WksCustom | RepoKind::BaseCustom => PackStatus::Custom,
+		RepoKind::Source => PackStatus::Source,
+		RepoKind::BaseInstalled => PackStatus::Installed,
+	};
+
+	// -- The docs
+	let read_doc = |file_name: &str| std::fs::read_to_string(pack_dir.path.join(file_name).as_std_path()).ok();
+	let readme = read_doc("README.md");
+	let changelog = read_doc("CHANGELOG.md");
+
+	// -- The agents (main first)
+	let mut agents = Vec::new();
+	for file in list_files(&pack_dir.path, Some(&["**/*.aip"]), None)? {
+		let Some(rel_path) = file.diff(&pack_dir.path) else {
+			continue;
+		};
+		let rel_path = rel_path.as_str().trim_end_matches(".aip");
+		let agent_ref = if rel_path == "main" {
+			pack_ref_str.clone()
+		} else {
+			format!("{pack_ref_str}/{rel_path}")
+		};
+		let meta = AgentDoc::from_file(&file)
+			.ok()
+			.and_then(|doc| doc.meta().ok())
+			.unwrap_or_default();
+		agents.push(PackAgentInfo { agent_ref, meta });
+	}
+	agents.sort_by_key(|a| (a.agent_ref != pack_ref_str, a.agent_ref.clone()));
+
+	Ok(PackInfo {
+		pack_ref: pack_ref_str,
+		status,
+		path: pack_dir.pretty_path(),
+		pack_toml: read_pack_toml_info(&pack_dir.path),
+		readme,
+		changelog,
+		agents,
+	})
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::runtime::Runtime;
+
+	#[tokio::test]
+	async fn test_exec_info_build_pack_info_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+
+		// -- Exec
+		let pack_info = build_pack_info(runtime.dir_context(), "ns_b@pack_b_2")?;
+
+		// -- Check
+		assert_eq!(pack_info.pack_ref, "ns_b@pack_b_2");
+		assert_eq!(pack_info.status, PackStatus::Custom);
+		assert!(pack_info.agents.iter().any(|a| a.agent_ref == "ns_b@pack_b_2"));
+		assert!(build_pack_info(runtime.dir_context(), "ns_b@no_pack").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
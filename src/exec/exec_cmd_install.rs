@@ -1,22 +1,38 @@
-use crate::Result;
 use crate::dir_context::DirContext;
 use crate::exec::cli::InstallArgs;
-use crate::exec::packer::{InstallResponse, InstalledPack, install_pack};
+use crate::exec::packer::{InstallResponse, InstalledPack, PackToml, install_locked, install_pack};
 use crate::hub::get_hub;
+use crate::term::{init_term, prompt_input, safer_println};
+use crate::{Error, Result, term};
 use size::Size;
 
 /// Executes the install command which installs an aipack file
 pub async fn exec_install(dir_context: DirContext, install_args: InstallArgs) -> Result<InstalledPack> {
 	let hub = get_hub();
-	let install_res = install_pack(&dir_context, &install_args.aipack_ref, install_args.force).await?;
+	let aipack_ref = install_args
+		.aipack_ref
+		.as_deref()
+		.ok_or_else(|| Error::custom("'aip install' requires the pack to install (or '--locked')"))?;
+	let consent = |pack_toml: &PackToml| {
+		if install_args.yes {
+			Ok(true)
+		} else {
+			prompt_capabilities_consent(pack_toml)
+		}
+	};
+	let install_res = install_pack(
+		&dir_context,
+		aipack_ref,
+		install_args.force,
+		install_args.alias.as_deref(),
+		&consent,
+	)
+	.await?;
 
 	let (installed_pack, skipped) = match install_res {
 		InstallResponse::Installed(pack) => {
-			hub.publish(format!(
-				"\n==== Installing aipack:\n\n{:>15} {}",
-				"From:", install_args.aipack_ref
-			))
-			.await;
+			hub.publish(format!("\n==== Installing aipack:\n\n{:>15} {}", "From:", aipack_ref))
+				.await;
 			(pack, false)
 		}
 		InstallResponse::UpToDate(pack) => {
@@ -29,6 +45,39 @@ pub async fn exec_install(dir_context: DirContext, install_args: InstallArgs) ->
 		}
 	};
 
+	let capabilities = if installed_pack.pack_toml.capabilities.is_empty() {
+		"none".to_string()
+	} else {
+		installed_pack
+			.pack_toml
+			.capabilities
+			.iter()
+			.map(|c| c.as_str())
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+
+	let paths_allow = if installed_pack.pack_toml.paths_allow.is_empty() {
+		"none".to_string()
+	} else {
+		installed_pack.pack_toml.paths_allow.join(", ")
+	};
+
+	// The commit of the packs installed from a git repo
+	let version = match installed_pack.git_commit.as_deref() {
+		Some(commit) => format!("{} (commit {commit})", installed_pack.pack_toml.version),
+		None => installed_pack.pack_toml.version.clone(),
+	};
+
+	// The resolved dependencies (pack.toml `[dependencies]`)
+	let dependencies = installed_pack
+		.dependencies
+		.iter()
+		.map(|dep| format!("{:>15} {} v{} (from {})", "", dep.pack, dep.version, dep.origin))
+		.collect::<Vec<_>>();
+	let dependencies =
+		(!dependencies.is_empty()).then(|| format!("{:>15}\n{}", "Dependencies:", dependencies.join("\n")));
+
 	// Format the zip size using the size crate
 	let formatted_zip_size = Size::from_bytes(installed_pack.zip_size as u64).to_string();
 
@@ -42,27 +91,120 @@ pub async fn exec_install(dir_context: DirContext, install_args: InstallArgs) ->
 			installed_pack.pack_toml.namespace,
 			installed_pack.pack_toml.name,
 			"Version:",
-			installed_pack.pack_toml.version,
+			version,
 			"Installed At:",
 			installed_pack.path
 		))
 		.await;
+		if let Some(dependencies) = dependencies {
+			hub.publish(dependencies).await;
+		}
 		hub.publish("\n==== DONE (Skipped)".to_string()).await;
 	} else {
 		hub.publish(format!(
-			"{:>15} {formatted_zip_size}\n{:>15} {}@{}\n{:>15} {}\n{:>15} {}",
+			"{:>15} {formatted_zip_size}\n{:>15} {}@{}\n{:>15} {}\n{:>15} {}\n{:>15} {capabilities}\n{:>15} {paths_allow}",
 			".aipack Size:",
 			"Pack:",
 			installed_pack.pack_toml.namespace,
 			installed_pack.pack_toml.name,
 			"Version:",
-			installed_pack.pack_toml.version,
+			version,
 			"Installed At:",
-			installed_pack.path
+			installed_pack.path,
+			"Capabilities:",
+			"Paths Allow:",
 		))
 		.await;
+		if let Some(dependencies) = dependencies {
+			hub.publish(dependencies).await;
+		}
 		hub.publish("\n==== DONE".to_string()).await;
 	}
 
 	Ok(installed_pack)
 }
+
+/// Executes `aip install --locked`, which installs the packs of the pack lock (all, or the given one and its dependencies)
+pub async fn exec_install_locked(dir_context: DirContext, install_args: InstallArgs) -> Result<Vec<InstalledPack>> {
+	let hub = get_hub();
+	let consent = |pack_toml: &PackToml| {
+		if install_args.yes {
+			Ok(true)
+		} else {
+			prompt_capabilities_consent(pack_toml)
+		}
+	};
+
+	let lock_path = dir_context.aipack_paths().aipack_lock_path();
+	hub.publish(format!(
+		"\n==== Installing from pack lock:\n\n{:>15} {}",
+		"Lock:",
+		lock_path.map(|p| p.to_string()).unwrap_or_default()
+	))
+	.await;
+
+	let install_responses = install_locked(
+		&dir_context,
+		install_args.aipack_ref.as_deref(),
+		install_args.force,
+		&consent,
+	)
+	.await?;
+
+	let mut installed_packs = Vec::new();
+	for install_res in install_responses {
+		let (label, pack) = match install_res {
+			InstallResponse::Installed(pack) => ("Installed:", pack),
+			InstallResponse::UpToDate(pack) => ("Up to date:", pack),
+		};
+		let version = match pack.git_commit.as_deref() {
+			Some(commit) => format!("{} (commit {commit})", pack.pack_toml.version),
+			None => pack.pack_toml.version.clone(),
+		};
+		hub.publish(format!(
+			"{label:>15} {}@{} v{version}",
+			pack.pack_toml.namespace, pack.pack_toml.name
+		))
+		.await;
+		installed_packs.push(pack);
+	}
+
+	hub.publish("\n==== DONE".to_string()).await;
+
+	Ok(installed_packs)
+}
+
+// region:    --- Support
+
+/// Show the capabilities and paths the pack declares, and ask the user to grant them
+fn prompt_capabilities_consent(pack_toml: &PackToml) -> Result<bool> {
+	let mut msg = format!(
+		"\nPack '{}@{}' v{} requires the following:\n",
+		pack_toml.namespace, pack_toml.name, pack_toml.version
+	);
+	if !pack_toml.capabilities.is_empty() {
+		msg.push_str("\nCapabilities:\n");
+	}
+	for capability in pack_toml.capabilities.iter() {
+		msg.push_str(&format!(
+			"  - {:<24} {}\n",
+			capability.as_str(),
+			capability.description()
+		));
+	}
+	if !pack_toml.paths_allow.is_empty() {
+		msg.push_str("\nPaths outside the workspace (read and write):\n");
+		for path in pack_toml.paths_allow.iter() {
+			msg.push_str(&format!("  - {path}\n"));
+		}
+	}
+	safer_println(&msg, true);
+
+	let mut stdout = init_term()?;
+	let confirm = prompt_input(&mut stdout, "Grant these capabilities and install (y/N)", false)?;
+	crossterm::terminal::disable_raw_mode()?;
+
+	Ok(term::is_input_yes(&confirm))
+}
+
+// endregion: --- Support
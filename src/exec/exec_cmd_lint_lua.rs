@@ -0,0 +1,181 @@
+use crate::agent::AgentDoc;
+use crate::exec::cli::LintLuaArgs;
+use crate::hub::get_hub;
+use crate::model::Stage;
+use crate::runtime::Runtime;
+use crate::script::{LuaIssue, LuaIssueKind, LuaLintRegistry, check_lua, format_lua};
+use crate::support::md::md_replace_block;
+use crate::{Error, Result};
+use simple_fs::{ListOptions, SPath, list_files};
+use std::fs;
+
+const EXCLUDE_GLOBS: &[&str] = &["**/.git/**", "**/target/**", "**/node_modules/**", "**/.aipack/.session/**"];
+
+/// The lint of an agent file
+#[derive(Debug, Default)]
+pub struct LintLuaFileReport {
+	/// The issues with their line in the agent file (1 based), and their stage
+	pub issues: Vec<(usize, Stage, LuaIssue)>,
+	/// The number of stage Lua blocks formatted (with `--fix`)
+	pub formatted_blocks: usize,
+}
+
+/// Executes `aip lint-lua`, the formatting and static checks of the stage Lua blocks of the workspace agents.
+///
+/// Returns an error when some issues remain (e.g., for CI).
+pub async fn exec_lint_lua(args: LintLuaArgs, runtime: Runtime) -> Result<()> {
+	let hub = get_hub();
+
+	let wks_dir = runtime
+		.dir_context()
+		.try_wks_dir_with_err_ctx("aip lint-lua requires an aipack workspace")?;
+	let registry = LuaLintRegistry::from_runtime(&runtime).await?;
+
+	let globs: Vec<&str> = if args.paths.is_empty() {
+		vec!["**/*.aip"]
+	} else {
+		args.paths.iter().map(|p| p.as_str()).collect()
+	};
+	let list_options = ListOptions::new(Some(EXCLUDE_GLOBS)).with_relative_glob();
+	let files = list_files(wks_dir, Some(&globs), Some(list_options))?;
+
+	hub.publish(format!("\n==== Lint Lua ({} agent file(s))\n", files.len())).await;
+
+	let mut issues_count = 0;
+	let mut files_with_issues = 0;
+	let mut formatted_blocks = 0;
+	for file in files.iter() {
+		let report = lint_lua_agent_file(file, &registry, args.fix)?;
+		let rel_path = file.diff(wks_dir).unwrap_or_else(|| file.clone());
+
+		if report.formatted_blocks > 0 {
+			hub.publish(format!(
+				"{rel_path} - {} Lua block(s) formatted",
+				report.formatted_blocks
+			))
+			.await;
+		}
+		for (line, stage, issue) in report.issues.iter() {
+			let kind: &'static str = issue.kind.into();
+			hub.publish(format!("{rel_path}:{line}: [{kind}] {} ({stage})", issue.msg))
+				.await;
+		}
+
+		if !report.issues.is_empty() {
+			files_with_issues += 1;
+		}
+		issues_count += report.issues.len();
+		formatted_blocks += report.formatted_blocks;
+	}
+
+	let fixed_msg = if args.fix {
+		format!(", {formatted_blocks} Lua block(s) formatted")
+	} else {
+		String::new()
+	};
+	hub.publish(format!(
+		"\n==== DONE ({issues_count} issue(s) in {files_with_issues} of {} agent file(s){fixed_msg})",
+		files.len()
+	))
+	.await;
+
+	if issues_count > 0 {
+		return Err(Error::custom(format!("aip lint-lua found {issues_count} issue(s)")));
+	}
+
+	Ok(())
+}
+
+/// Lint the stage Lua blocks of an agent file, and format them in place when `fix`
+/// (the formatting issues are then not returned, and the blocks with a syntax error are not formatted).
+fn lint_lua_agent_file(file: &SPath, registry: &LuaLintRegistry, fix: bool) -> Result<LintLuaFileReport> {
+	let doc = AgentDoc::from_file(file)?;
+	let mut report = LintLuaFileReport::default();
+	// (start_line, end_line, formatted content)
+	let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+
+	for block in doc.stage_lua_blocks()? {
+		for issue in check_lua(&block.content, registry) {
+			if fix && issue.kind == LuaIssueKind::Format {
+				replacements.push((block.start_line, block.end_line, format_lua(&block.content)));
+				continue;
+			}
+			report.issues.push((block.start_line + issue.line, block.stage, issue));
+		}
+	}
+
+	if !replacements.is_empty() {
+		let mut content = fs::read_to_string(file)?;
+		// NOTE: From the last block, so that the lines of the other blocks do not change
+		for (start_line, end_line, formatted) in replacements.iter().rev() {
+			content = md_replace_block(&content, *start_line, *end_line, formatted)?;
+		}
+		fs::write(file, content)?;
+		report.formatted_blocks = replacements.len();
+	}
+
+	Ok(report)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::_test_support::{create_test_file, remove_test_file};
+
+	#[test]
+	fn test_exec_lint_lua_agent_file_fix() -> Result<()> {
+		// -- Setup & Fixtures
+		let content = r#"# Data
+
+```lua
+if input then
+    return aip.file.loadd(input)
+end
+```
+
+# Output
+
+```lua
+local unused = 1
+return data
+```
+"#;
+		let file = create_test_file("test_exec_lint_lua_agent_file_fix/agent.aip", content)?;
+		let registry = LuaLintRegistry::new(["file", "file.load"], ["aip", "string"]);
+
+		// -- Exec
+		let report = lint_lua_agent_file(&file, &registry, true)?;
+		let fixed_content = fs::read_to_string(&file)?;
+		let report_again = lint_lua_agent_file(&file, &registry, false)?;
+
+		// -- Check
+		assert_eq!(report.formatted_blocks, 1);
+		let issues: Vec<(usize, Stage, LuaIssueKind)> = report
+			.issues
+			.iter()
+			.map(|(line, stage, issue)| (*line, *stage, issue.kind))
+			.collect();
+		assert_eq!(
+			issues,
+			[
+				(5, Stage::Data, LuaIssueKind::Undefined),
+				(12, Stage::Output, LuaIssueKind::Unused)
+			]
+		);
+		assert!(fixed_content.contains("if input then\n\treturn aip.file.loadd(input)\nend\n```"));
+		assert!(fixed_content.ends_with("return data\n```\n"));
+		assert_eq!(report_again.formatted_blocks, 0);
+		assert_eq!(report_again.issues.len(), 2, "formatting issue should be fixed");
+
+		// -- Clean
+		remove_test_file(file)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
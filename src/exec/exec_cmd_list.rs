@@ -1,9 +1,85 @@
 use crate::Result;
-use crate::dir_context::{DirContext, lookup_pack_dirs};
+use crate::agent::{AgentDoc, AgentMeta};
+use crate::dir_context::{DirContext, RepoKind, lookup_pack_dirs};
 use crate::exec::cli::ListArgs;
-use crate::hub::get_hub;
+use crate::exec::packer::{fetch_repo_latest_version, validate_version_update};
+use crate::exec::support::{PackTomlInfo, read_pack_toml_info};
+use crate::hub::{HubEvent, get_hub};
+use crate::types::PackIdentity;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// A pack of the `aip list`, with its pack.toml info and the `# Meta` of its main agent.
+#[derive(Debug, Serialize)]
+pub struct PackListItem {
+	/// e.g., `demo@proof`
+	pub pack_ref: String,
+	pub namespace: String,
+	pub name: String,
+	pub status: PackStatus,
+	/// False when this pack is shadowed by a pack with the same ref in a repo of higher precedence
+	pub active: bool,
+	pub path: String,
+	pub version: Option<String>,
+	/// The repo latest version (only with `--check-updates`, for the installed packs)
+	pub latest_version: Option<String>,
+	pub tags: Vec<String>,
+	/// The pack.toml capabilities (e.g., `net`, `exec`)
+	pub capabilities: Vec<String>,
+	/// The pack.toml path globs outside the workspace
+	pub paths_allow: Vec<String>,
+	/// The `# Meta` of the pack `main.aip` (description, params, examples)
+	#[serde(flatten)]
+	pub meta: AgentMeta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, strum::IntoStaticStr)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PackStatus {
+	/// A local pack (workspace or base `pack/custom/`), e.g., a local link to a pack in development
+	Custom,
+	/// From a `[[pack_sources]]` dir of the base config (e.g., a company or team shared packs dir)
+	Source,
+	Installed,
+	/// Installed, with a newer version in the repo (only with `--check-updates`)
+	Outdated,
+}
 
 pub async fn exec_list(dir_context: DirContext, list_args: ListArgs) -> Result<()> {
+	let mut items = build_pack_list_items(&dir_context, &list_args)?;
+
+	// -- Check the installed packs against the repo latest versions (network)
+	if list_args.check_updates {
+		for item in items.iter_mut().filter(|item| item.status == PackStatus::Installed) {
+			let pack_identity = PackIdentity {
+				namespace: item.namespace.clone(),
+				name: item.name.clone(),
+			};
+			let Some(latest_version) = fetch_repo_latest_version(&pack_identity).await? else {
+				continue;
+			};
+			if let Some(version) = item.version.as_deref()
+				&& validate_version_update(version, &latest_version)?.is_gt()
+			{
+				item.status = PackStatus::Outdated;
+			}
+			item.latest_version = Some(latest_version);
+		}
+	}
+
+	if list_args.json {
+		let json = serde_json::to_string_pretty(&items)?;
+		get_hub().publish(HubEvent::Message(json.into())).await;
+	} else {
+		get_hub().publish(items).await;
+	}
+
+	Ok(())
+}
+
+/// Build the pack list items for the `aip list` args (pack ref and tag filters), without the repo checks.
+pub(super) fn build_pack_list_items(dir_context: &DirContext, list_args: &ListArgs) -> Result<Vec<PackListItem>> {
 	// -- extract the optional namespace / pack_name from the args
 	// if no, @, then, assume it is the namespace
 	// TODO: Handle the case where we have some special char in namespace
@@ -16,9 +92,96 @@ pub async fn exec_list(dir_context: DirContext, list_args: ListArgs) -> Result<(
 		(None, None)
 	};
 
-	let pack_dirs = lookup_pack_dirs(&dir_context, namespace, pack_name)?;
+	let pack_dirs = lookup_pack_dirs(dir_context, namespace, pack_name)?;
 
-	get_hub().publish(pack_dirs).await;
+	let mut existing_set: HashSet<String> = HashSet::new();
+	let mut items = Vec::with_capacity(pack_dirs.len());
 
-	Ok(())
+	for pack_dir in pack_dirs {
+		let pack_ref = pack_dir.to_string();
+		// NOTE: The first one (in precedence order) is the active one
+		let active = existing_set.insert(pack_ref.clone());
+
+		let pack_toml_info = read_pack_toml_info(&pack_dir.path);
+		let PackTomlInfo {
+			version,
+			tags,
+			capabilities,
+			paths_allow,
+			..
+		} = pack_toml_info;
+
+		if let Some(tag) = list_args.tag.as_deref()
+			&& !tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+		{
+			continue;
+		}
+
+		let meta = AgentDoc::from_file(pack_dir.path.join("main.aip"))
+			.ok()
+			.and_then(|doc| doc.meta().ok())
+			.unwrap_or_default();
+
+		let status = match pack_dir.repo_kind {
+			RepoKind::WksCustom | RepoKind::BaseCustom => PackStatus::Custom,
+			RepoKind::Source => PackStatus::Source,
+			RepoKind::BaseInstalled => PackStatus::Installed,
+		};
+
+		items.push(PackListItem {
+			pack_ref,
+			path: pack_dir.pretty_path(),
+			namespace: pack_dir.namespace,
+			name: pack_dir.name,
+			status,
+			active,
+			version,
+			latest_version: None,
+			tags,
+			capabilities,
+			paths_allow,
+			meta,
+		});
+	}
+
+	Ok(items)
 }
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::runtime::Runtime;
+
+	#[tokio::test]
+	async fn test_exec_list_build_pack_list_items_ns() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let list_args = ListArgs {
+			pack_ref: Some("ns_b@".to_string()),
+			open: false,
+			tag: None,
+			json: false,
+			check_updates: false,
+		};
+
+		// -- Exec
+		let items = build_pack_list_items(runtime.dir_context(), &list_args)?;
+
+		// -- Check
+		assert!(items.iter().all(|item| item.namespace == "ns_b"));
+		let b_2 = items
+			.iter()
+			.filter(|item| item.pack_ref == "ns_b@pack_b_2")
+			.map(|item| (item.status, item.active))
+			.collect::<Vec<_>>();
+		assert_eq!(b_2, [(PackStatus::Custom, true), (PackStatus::Installed, false)]);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,245 @@
+//! The MCP tools declared by the packs, in their pack.toml `[[mcp.tools]]`.
+//!
+//! ```toml
+//! [[mcp.tools]]
+//! agent       = "proof"              # The pack agent (e.g., `proof.aip`), the pack `main.aip` if absent
+//! name        = "proofread"          # The tool name, `<namespace>_<name>[_<agent>]` if absent
+//! description = "Proofread a text"   # The agent `# Meta` description if absent
+//!
+//! [mcp.tools.input_schema]           # The JSON Schema of the tool arguments (given as the agent `input`)
+//! type     = "object"
+//! required = ["text"]
+//! properties.text = { type = "string", description = "The text to proofread" }
+//! ```
+
+use crate::agent::AgentDoc;
+use crate::dir_context::{DirContext, lookup_pack_dirs};
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+/// The max length of a MCP tool name
+const TOOL_NAME_MAX_LEN: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct McpTool {
+	pub name: String,
+	pub description: String,
+	pub input_schema: Value,
+	/// The agent to run, e.g., `demo@proof/proof` or `demo@proof` (for the `main.aip`)
+	pub agent_ref: String,
+}
+
+/// The pack.toml `[[mcp.tools]]` item
+#[derive(Debug, Deserialize)]
+struct McpToolDecl {
+	agent: Option<String>,
+	name: Option<String>,
+	description: Option<String>,
+	input_schema: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PackTomlMcp {
+	#[serde(default)]
+	tools: Vec<McpToolDecl>,
+}
+
+impl McpTool {
+	/// The tool of the MCP `tools/list` response
+	pub fn to_list_value(&self) -> Value {
+		json!({
+			"name": self.name,
+			"description": self.description,
+			"inputSchema": self.input_schema,
+		})
+	}
+
+	/// Check the call arguments against the input schema (the object type and the required properties).
+	pub fn validate_arguments(&self, arguments: &Value) -> Result<()> {
+		let arguments = arguments
+			.as_object()
+			.ok_or_else(|| Error::custom(format!("Tool '{}' arguments must be an object", self.name)))?;
+		let missing = self
+			.input_schema
+			.get("required")
+			.and_then(|r| r.as_array())
+			.into_iter()
+			.flatten()
+			.filter_map(|r| r.as_str())
+			.filter(|r| !arguments.contains_key(*r))
+			.collect::<Vec<_>>();
+		if !missing.is_empty() {
+			return Err(Error::custom(format!(
+				"Tool '{}' missing required argument(s): {}",
+				self.name,
+				missing.join(", ")
+			)));
+		}
+		Ok(())
+	}
+}
+
+/// Load the MCP tools of the active packs (optionally filtered by a partial pack ref, e.g., `jc@` or `jc@coder`).
+///
+/// Returns the tools and the errors of the packs with an invalid `[[mcp.tools]]` (those are skipped).
+pub fn load_mcp_tools(dir_context: &DirContext, pack_ref: Option<&str>) -> Result<(Vec<McpTool>, Vec<Error>)> {
+	let (namespace, pack_name) = match pack_ref.map(|r| r.split_once('@').unwrap_or((r, ""))) {
+		Some((namespace, pack_name)) => (
+			Some(namespace).filter(|n| !n.is_empty()),
+			Some(pack_name).filter(|n| !n.is_empty()),
+		),
+		None => (None, None),
+	};
+	let pack_dirs = lookup_pack_dirs(dir_context, namespace, pack_name)?;
+
+	let mut tools: Vec<McpTool> = Vec::new();
+	let mut errors = Vec::new();
+	let mut existing_packs: HashSet<String> = HashSet::new();
+
+	for pack_dir in pack_dirs {
+		// NOTE: The first one (in precedence order) is the active one
+		if !existing_packs.insert(pack_dir.to_string()) {
+			continue;
+		}
+		let Ok(toml_content) = std::fs::read_to_string(pack_dir.path.join("pack.toml").as_std_path()) else {
+			continue;
+		};
+		match parse_pack_mcp_tools(&pack_dir.namespace, &pack_dir.name, &toml_content) {
+			Ok(pack_tools) => {
+				for mut tool in pack_tools {
+					if tool.description.is_empty() {
+						tool.description = agent_description(&pack_dir.path, &tool.agent_ref)
+							.unwrap_or_else(|| format!("Run the aipack agent '{}'", tool.agent_ref));
+					}
+					if tools.iter().any(|t| t.name == tool.name) {
+						errors.push(Error::custom(format!(
+							"MCP tool '{}' of pack '{pack_dir}' is already declared by another pack (skipped)",
+							tool.name
+						)));
+						continue;
+					}
+					tools.push(tool);
+				}
+			}
+			Err(err) => errors.push(Error::cc(format!("Invalid [[mcp.tools]] in pack '{pack_dir}'"), err)),
+		}
+	}
+
+	Ok((tools, errors))
+}
+
+/// Parse the `[[mcp.tools]]` of a pack.toml content (the descriptions are empty when not declared).
+fn parse_pack_mcp_tools(namespace: &str, pack_name: &str, toml_content: &str) -> Result<Vec<McpTool>> {
+	let value: toml::Value = toml::from_str(toml_content)?;
+	let Some(mcp) = value.get("mcp") else {
+		return Ok(Vec::new());
+	};
+	let mcp: PackTomlMcp = mcp.clone().try_into()?;
+
+	let mut tools = Vec::with_capacity(mcp.tools.len());
+	for decl in mcp.tools {
+		let agent = decl
+			.agent
+			.as_deref()
+			.map(|a| a.trim_end_matches(".aip"))
+			.filter(|a| !a.is_empty());
+		let agent_ref = match agent {
+			Some(agent) => format!("{namespace}@{pack_name}/{agent}"),
+			None => format!("{namespace}@{pack_name}"),
+		};
+
+		let name = match decl.name {
+			Some(name) => name,
+			None => [Some(namespace), Some(pack_name), agent]
+				.into_iter()
+				.flatten()
+				.collect::<Vec<_>>()
+				.join("_")
+				.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-', "_"),
+		};
+		if name.is_empty()
+			|| name.len() > TOOL_NAME_MAX_LEN
+			|| !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+		{
+			return Err(Error::custom(format!(
+				"MCP tool name '{name}' must be 1 to {TOOL_NAME_MAX_LEN} chars of [a-zA-Z0-9_-]"
+			)));
+		}
+
+		if decl.input_schema.get("type").and_then(|t| t.as_str()) != Some("object") {
+			return Err(Error::custom(format!(
+				"MCP tool '{name}' input_schema must be a JSON Schema with type = \"object\""
+			)));
+		}
+
+		tools.push(McpTool {
+			name,
+			description: decl.description.unwrap_or_default(),
+			input_schema: decl.input_schema,
+			agent_ref,
+		});
+	}
+
+	Ok(tools)
+}
+
+/// The `# Meta` description of the pack agent
+fn agent_description(pack_path: &simple_fs::SPath, agent_ref: &str) -> Option<String> {
+	let agent = agent_ref.split_once('/').map(|(_, agent)| agent).unwrap_or("main");
+	let doc = AgentDoc::from_file(pack_path.join(format!("{agent}.aip"))).ok()?;
+	doc.meta().ok()?.description().map(|d| d.to_string())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_exec_mcp_serve_parse_pack_mcp_tools() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_toml = r#"
+[pack]
+namespace = "demo"
+name = "proof"
+
+[[mcp.tools]]
+agent = "proof"
+description = "Proofread a text"
+[mcp.tools.input_schema]
+type = "object"
+required = ["text"]
+properties.text = { type = "string" }
+
+[[mcp.tools]]
+name = "demo-main"
+input_schema = { type = "object" }
+		"#;
+
+		// -- Exec
+		let tools = parse_pack_mcp_tools("demo", "proof", fx_toml)?;
+		let no_tools = parse_pack_mcp_tools("demo", "proof", "[pack]\nname = \"proof\"")?;
+		let invalid_res = parse_pack_mcp_tools("demo", "proof", "[[mcp.tools]]\ninput_schema = { type = \"string\" }");
+
+		// -- Check
+		assert_eq!(tools.len(), 2);
+		assert_eq!(tools[0].name, "demo_proof_proof");
+		assert_eq!(tools[0].agent_ref, "demo@proof/proof");
+		assert_eq!(tools[0].description, "Proofread a text");
+		assert_eq!(tools[1].name, "demo-main");
+		assert_eq!(tools[1].agent_ref, "demo@proof");
+		assert!(tools[0].validate_arguments(&json!({"text": "hello"})).is_ok());
+		assert!(tools[0].validate_arguments(&json!({})).is_err());
+		assert!(no_tools.is_empty());
+		assert!(invalid_res.is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
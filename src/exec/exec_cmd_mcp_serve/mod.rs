@@ -0,0 +1,239 @@
+//! The `aip mcp-serve` command, the MCP server (stdio transport) exposing the pack agents as MCP tools
+//! (e.g., for Claude Desktop or other MCP clients).
+//!
+//! - The tools are declared by the packs, in their pack.toml `[[mcp.tools]]` (see `mcp_tools`).
+//! - A `tools/call` runs the agent with the tool arguments as its single `input`,
+//!   and returns the task output (or the `# After All` result when the output is nil).
+//! - The stdout is the JSON-RPC channel (one message per line), the run messages go to the stderr.
+
+// region:    --- Modules
+
+mod mcp_tools;
+
+use mcp_tools::*;
+
+// endregion: --- Modules
+
+use crate::agent::find_agent;
+use crate::exec::cli::McpServeArgs;
+use crate::hub::get_hub;
+use crate::run::{RunBaseOptions, run_agent};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt as _, AsyncWriteExt as _, BufReader};
+use tokio::sync::Mutex;
+
+/// The MCP protocol version used when the client does not give one
+const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+
+// -- The JSON-RPC error codes
+const RPC_PARSE_ERROR: i64 = -32700;
+const RPC_METHOD_NOT_FOUND: i64 = -32601;
+const RPC_INVALID_PARAMS: i64 = -32602;
+
+/// Exec for the `aip mcp-serve` command (runs until the client closes the stdin)
+pub async fn exec_mcp_serve(args: McpServeArgs, runtime: Runtime) -> Result<()> {
+	let hub = get_hub();
+
+	let (tools, errors) = load_mcp_tools(runtime.dir_context(), args.pack_ref.as_deref())?;
+	for err in errors {
+		hub.publish(err).await;
+	}
+	let tool_names = tools.iter().map(|t| t.name.as_str()).collect::<Vec<_>>().join(", ");
+	hub.publish(format!(
+		"aip mcp-serve ready with {} tool(s): {tool_names}",
+		tools.len()
+	))
+	.await;
+
+	let tools = Arc::new(tools);
+	let stdout = Arc::new(Mutex::new(tokio::io::stdout()));
+	let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+	while let Some(line) = lines
+		.next_line()
+		.await
+		.map_err(|err| Error::custom(format!("Cannot read the MCP stdin. Cause: {err}")))?
+	{
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		// NOTE: Each message in its own task, so that the long tool calls do not block the others (e.g., ping)
+		let (runtime, tools, stdout) = (runtime.clone(), tools.clone(), stdout.clone());
+		tokio::spawn(async move {
+			let Some(response) = handle_message(&runtime, &tools, &line).await else {
+				return;
+			};
+			let mut stdout = stdout.lock().await;
+			let _ = stdout.write_all(format!("{response}\n").as_bytes()).await;
+			let _ = stdout.flush().await;
+		});
+	}
+
+	Ok(())
+}
+
+// region:    --- JSON-RPC
+
+/// Handle a JSON-RPC message, returning the response (None for the notifications)
+async fn handle_message(runtime: &Runtime, tools: &[McpTool], line: &str) -> Option<Value> {
+	let msg: Value = match serde_json::from_str(line) {
+		Ok(msg) => msg,
+		Err(err) => return Some(rpc_error(Value::Null, RPC_PARSE_ERROR, format!("Parse error. {err}"))),
+	};
+
+	// -- Notifications (no id), e.g., `notifications/initialized`
+	let id = msg.get("id").cloned()?;
+	let method = msg.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+	let params = msg.get("params").cloned().unwrap_or(Value::Null);
+
+	let res = match method {
+		"initialize" => {
+			let protocol_version = params
+				.get("protocolVersion")
+				.and_then(|v| v.as_str())
+				.unwrap_or(MCP_PROTOCOL_VERSION);
+			Ok(json!({
+				"protocolVersion": protocol_version,
+				"capabilities": { "tools": {} },
+				"serverInfo": { "name": "aipack", "version": crate::VERSION },
+			}))
+		}
+		"ping" => Ok(json!({})),
+		"tools/list" => Ok(json!({
+			"tools": tools.iter().map(McpTool::to_list_value).collect::<Vec<_>>()
+		})),
+		"tools/call" => call_tool(runtime, tools, &params).await,
+		_ => Err((RPC_METHOD_NOT_FOUND, format!("Method not found: '{method}'"))),
+	};
+
+	Some(match res {
+		Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+		Err((code, message)) => rpc_error(id, code, message),
+	})
+}
+
+/// Run the agent of the tool, the agent errors are returned as the tool result errors (`isError`)
+async fn call_tool(runtime: &Runtime, tools: &[McpTool], params: &Value) -> core::result::Result<Value, (i64, String)> {
+	let name = params.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+	let Some(tool) = tools.iter().find(|t| t.name == name) else {
+		return Err((RPC_INVALID_PARAMS, format!("Unknown tool: '{name}'")));
+	};
+	let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+	if let Err(err) = tool.validate_arguments(&arguments) {
+		return Err((RPC_INVALID_PARAMS, err.to_string()));
+	}
+
+	let res = run_tool_agent(runtime, tool, arguments).await;
+	let (text, is_error) = match res {
+		Ok(text) => (text, false),
+		Err(err) => (err.to_string(), true),
+	};
+
+	Ok(json!({
+		"content": [{ "type": "text", "text": text }],
+		"isError": is_error,
+	}))
+}
+
+async fn run_tool_agent(runtime: &Runtime, tool: &McpTool, arguments: Value) -> Result<String> {
+	let agent = find_agent(&tool.agent_ref, runtime, None)?;
+	let res = run_agent(
+		runtime,
+		None,
+		agent,
+		Some(vec![arguments]),
+		&RunBaseOptions::default(),
+		true,
+	)
+	.await?;
+
+	let output = res
+		.outputs
+		.and_then(|outputs| outputs.into_iter().next())
+		.filter(|output| !output.is_null())
+		.or(res.after_all)
+		.unwrap_or(Value::Null);
+
+	let text = match output {
+		Value::String(text) => text,
+		Value::Null => String::new(),
+		other => serde_json::to_string_pretty(&other)?,
+	};
+	Ok(text)
+}
+
+fn rpc_error(id: Value, code: i64, message: impl Into<String>) -> Value {
+	json!({
+		"jsonrpc": "2.0",
+		"id": id,
+		"error": { "code": code, "message": message.into() },
+	})
+}
+
+// endregion: --- JSON-RPC
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use value_ext::JsonValueExt as _;
+
+	#[tokio::test]
+	async fn test_exec_mcp_serve_handle_message_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_tools = vec![McpTool {
+			name: "demo_proof".to_string(),
+			description: "Proofread".to_string(),
+			input_schema: json!({"type": "object", "required": ["text"]}),
+			agent_ref: "demo@proof".to_string(),
+		}];
+
+		// -- Exec
+		let init_res = handle_message(
+			&runtime,
+			&fx_tools,
+			r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26"}}"#,
+		)
+		.await
+		.ok_or("Should have a response")?;
+		let notif_res = handle_message(
+			&runtime,
+			&fx_tools,
+			r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+		)
+		.await;
+		let list_res = handle_message(&runtime, &fx_tools, r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#)
+			.await
+			.ok_or("Should have a response")?;
+		let call_res = handle_message(
+			&runtime,
+			&fx_tools,
+			r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"demo_proof","arguments":{}}}"#,
+		)
+		.await
+		.ok_or("Should have a response")?;
+		let unknown_res = handle_message(&runtime, &fx_tools, r#"{"jsonrpc":"2.0","id":4,"method":"nope"}"#)
+			.await
+			.ok_or("Should have a response")?;
+
+		// -- Check
+		assert_eq!(init_res.x_get_str("/result/protocolVersion")?, "2025-03-26");
+		assert!(notif_res.is_none());
+		assert_eq!(list_res.x_get_str("/result/tools/0/name")?, "demo_proof");
+		assert_eq!(list_res.x_get_str("/result/tools/0/inputSchema/type")?, "object");
+		assert_eq!(call_res.x_get_i64("/error/code")?, RPC_INVALID_PARAMS);
+		assert_eq!(unknown_res.x_get_i64("/error/code")?, RPC_METHOD_NOT_FOUND);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
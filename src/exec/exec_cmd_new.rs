@@ -1,33 +1,109 @@
-#![allow(unused)]
 use crate::dir_context::DirContext;
+use crate::exec::assets;
 use crate::exec::cli::NewArgs;
+use crate::exec::init::list_template_pack_file_paths;
+use crate::hub::get_hub;
 use crate::support::AsStrsExt;
 use crate::term::{init_term, prompt_input, prompt_select, safer_println};
-use crate::{Result, term};
+use crate::types::PackIdentity;
+use crate::{Error, Result, term};
+use aho_corasick::AhoCorasick;
+use simple_fs::{SPath, ensure_dir};
+use std::io::Stdout;
+use std::str::FromStr;
 
 // (name, title)
+#[allow(unused)]
 const AGENT_TEMPLATES: [(&str, &str); 2] = [
 	//
 	("hello-world", "Hello World Agent"),
 	("ask", "Generic Ask Agent with parametric prompt"),
 ];
 
+// (name, title) of the `_init/_template/pack-<name>/` templates
+const PACK_TEMPLATES: [(&str, &str); 2] = [
+	//
+	("basic", "Basic pack with a main agent and a sub agent"),
+	("ask", "Ask pack with a `--arg prompt` main agent, and files as context"),
+];
+
 /// exec for the New command
-/// Will create a new pack in base or workspace custom (not sure yet)
 ///
-/// NOTE: THIS IS DISABLED FOR NOW
-pub async fn exec_new(new_args: NewArgs, _dir_context: DirContext) -> Result<()> {
+/// NOTE: For now, only the `--pack` mode is supported (the new agent mode is not implemented yet)
+pub async fn exec_new(new_args: NewArgs, dir_context: DirContext) -> Result<()> {
+	if new_args.pack {
+		exec_new_pack(new_args, dir_context).await
+	} else {
+		Err(Error::custom(
+			"'aip new' for a single agent is not supported yet.\nUse 'aip new --pack' to create a new pack.",
+		))
+	}
+}
+
+/// Create a new pack in the workspace `.aipack/pack/custom/<namespace>/<pack_name>/` from a pack template
+async fn exec_new_pack(new_args: NewArgs, dir_context: DirContext) -> Result<()> {
+	let pack_custom_dir = dir_context
+		.aipack_paths()
+		.aipack_wks_dir()
+		.ok_or("Cannot create a new pack, no '.aipack/' workspace dir")?
+		.get_pack_custom_dir()?;
+
+	let mut stdout = init_term()?;
+
+	// -- Prompt the template
+	let template_name = prompt_template(&mut stdout, "Select the pack template you want", &PACK_TEMPLATES)?;
+
+	// -- Get the namespace and pack name (from the arg, or prompted)
+	let pack_identity = match new_args.agent_path.as_deref() {
+		Some(pack_ref) => PackIdentity::from_str(pack_ref)?,
+		None => {
+			let namespace = prompt_input(&mut stdout, "Pack namespace (e.g., my):", false)?;
+			let name = prompt_input(&mut stdout, "Pack name:", false)?;
+			PackIdentity::from_str(&format!("{}@{}", namespace.trim(), name.trim()))?
+		}
+	};
+	let PackIdentity { namespace, name } = pack_identity;
+	let pack_dir = pack_custom_dir.join(&namespace).join(&name);
+
+	let confirm_prompt = format!(
+		r#"
+Creating new pack
+    pack: {namespace}@{name}
+     dir: {pack_dir}
+template: {template_name}
+"#
+	);
+	safer_println(&confirm_prompt, true);
+
+	let confirm = prompt_input(&mut stdout, "Confirm creation (Y/n)", false)?;
+	if !term::is_input_yes(&confirm) {
+		safer_println("Pack creation cancelled", true);
+		return Ok(());
+	}
+
+	// -- Create
+	let hub = get_hub();
+	let files = create_pack_from_template(template_name, &pack_dir, &namespace, &name)?;
+	for file in files {
+		hub.publish(format!("-> {:<18} '{}'", "Create file", file.try_diff(&pack_dir)?))
+			.await;
+	}
+	hub.publish(format!(
+		"\nPack '{namespace}@{name}' created at '{pack_dir}'\nRun it with: aip run {namespace}@{name}"
+	))
+	.await;
+
+	Ok(())
+}
+
+#[allow(unused)]
+async fn exec_new_agent(new_args: NewArgs, _dir_context: DirContext) -> Result<()> {
 	// let aipack_paths = dir_context.aipack_paths();
 
 	let mut stdout = init_term()?;
 
 	// -- Prompt the template
-	let max_len = AGENT_TEMPLATES.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
-	let labels = AGENT_TEMPLATES
-		.iter()
-		.map(|(name, title)| format!(" {name:>max_len$} - {title}"))
-		.collect::<Vec<_>>();
-	let template_idx = prompt_select(&mut stdout, "Select the agent template you want", &labels.x_as_strs())?;
+	let template_name = prompt_template(&mut stdout, "Select the agent template you want", &AGENT_TEMPLATES)?;
 
 	// -- Prompt the name
 	let name_prompt = if let Some(name) = new_args.agent_path {
@@ -40,11 +116,6 @@ pub async fn exec_new(new_args: NewArgs, _dir_context: DirContext) -> Result<()>
 
 	let agent_name = if agent_name.is_empty() { "my-agent" } else { agent_name };
 
-	let template_name = AGENT_TEMPLATES
-		.get(template_idx)
-		.map(|(name, _)| name)
-		.ok_or_else(|| format!("No template found for index {template_idx}"))?;
-
 	let confirm_prompt = format!(
 		r#"
 Creating new agent
@@ -65,3 +136,98 @@ template: {template_name}
 
 	Ok(())
 }
+
+// region:    --- Support
+
+/// Prompt the template to use, and returns its name
+fn prompt_template(stdout: &mut Stdout, prompt: &str, templates: &[(&'static str, &str)]) -> Result<&'static str> {
+	let max_len = templates.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+	let labels = templates
+		.iter()
+		.map(|(name, title)| format!(" {name:>max_len$} - {title}"))
+		.collect::<Vec<_>>();
+	let template_idx = prompt_select(stdout, prompt, &labels.x_as_strs())?;
+
+	let template_name = templates
+		.get(template_idx)
+		.map(|(name, _)| *name)
+		.ok_or_else(|| format!("No template found for index {template_idx}"))?;
+
+	Ok(template_name)
+}
+
+/// Create the pack dir from the `_template/pack-<template_name>/` files
+/// (with the `PACK_NS` and `PACK_NAME` placeholders replaced), and returns the created files.
+fn create_pack_from_template(template_name: &str, pack_dir: &SPath, namespace: &str, name: &str) -> Result<Vec<SPath>> {
+	if pack_dir.exists() {
+		return Err(Error::custom(format!(
+			"Cannot create pack, '{pack_dir}' already exists"
+		)));
+	}
+
+	let file_paths = list_template_pack_file_paths(template_name)?;
+	if file_paths.is_empty() {
+		return Err(Error::custom(format!("No pack template found for '{template_name}'")));
+	}
+
+	let ac = AhoCorasick::new(["PACK_NS", "PACK_NAME"])
+		.map_err(|err| Error::custom(format!("AhoCorasick pattern fail.\nCause: {err}")))?;
+
+	let template_prefix = format!("pack-{template_name}/");
+	let mut files = Vec::with_capacity(file_paths.len());
+	for file_path in file_paths {
+		let zfile = assets::extract_template_zfile(&file_path)?;
+		let content = String::from_utf8(zfile.content)
+			.map_err(|_| Error::custom(format!("Template file '{file_path}' is not UTF8")))?;
+		let content = ac.replace_all(&content, &[namespace, name]);
+
+		let rel_path = file_path.strip_prefix(&template_prefix).unwrap_or(&file_path);
+		let dest_path = pack_dir.join(rel_path);
+		if let Some(parent_dir) = dest_path.parent() {
+			ensure_dir(parent_dir)?;
+		}
+		std::fs::write(dest_path.as_std_path(), content)?;
+		files.push(dest_path);
+	}
+
+	Ok(files)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::agent::AgentDoc;
+
+	#[test]
+	fn test_exec_new_create_pack_from_template() -> Result<()> {
+		// -- Setup & Fixtures
+		let pack_dir = SPath::new("tests-data/sandbox-01/.tmp/test_exec_new/acme/deploy-helper");
+		if pack_dir.exists() {
+			std::fs::remove_dir_all(pack_dir.as_std_path())?;
+		}
+
+		// -- Exec
+		let files = create_pack_from_template("basic", &pack_dir, "acme", "deploy-helper")?;
+
+		// -- Check
+		assert!(files.len() >= 4);
+		let pack_toml = std::fs::read_to_string(pack_dir.join("pack.toml").as_std_path())?;
+		assert!(pack_toml.contains(r#"namespace = "acme""#));
+		assert!(pack_toml.contains(r#"name = "deploy-helper""#));
+		assert!(pack_dir.join("README.md").exists());
+		assert!(pack_dir.join("agents/hello.aip").exists());
+		let meta = AgentDoc::from_file(pack_dir.join("main.aip"))?.meta()?;
+		assert_eq!(meta.description(), Some("The acme@deploy-helper main agent"));
+		assert!(create_pack_from_template("basic", &pack_dir, "acme", "deploy-helper").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
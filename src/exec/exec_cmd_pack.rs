@@ -34,11 +34,14 @@ pub async fn exec_pack(pack_args: &PackArgs) -> Result<()> {
 	hub.publish(format!("\nPacking directory '{src_dir}' into a .aipack file..."))
 		.await;
 
-	match pack_dir(&src_dir, &dest_dir) {
+	match pack_dir(&src_dir, &dest_dir).await {
 		Ok(pack_data) => {
+			for built_file in pack_data.built_files.iter() {
+				hub.publish(format!("-> {:<18} '{built_file}'", "Built")).await;
+			}
 			hub.publish(format!(
-				"\nSuccessfully packed directory into '{}'",
-				pack_data.pack_file
+				"\nSuccessfully packed directory into '{}'\nContent hash: {}",
+				pack_data.pack_file, pack_data.content_hash
 			))
 			.await;
 			Ok(())
@@ -70,10 +73,13 @@ pub async fn exec_pack(pack_args: &PackArgs) -> Result<()> {
 
 			if term::is_input_yes(&input) {
 				// Try packing again
-				match pack_dir(&src_dir, &dest_dir) {
+				match pack_dir(&src_dir, &dest_dir).await {
 					Ok(pack_data) => {
-						hub.publish(format!("Successfully packed directory into '{}'", pack_data.pack_file))
-							.await;
+						hub.publish(format!(
+							"Successfully packed directory into '{}'\nContent hash: {}",
+							pack_data.pack_file, pack_data.content_hash
+						))
+						.await;
 					}
 					Err(retry_err) => {
 						hub.publish(format!(
@@ -0,0 +1,51 @@
+use crate::exec::cli::ReplayEventsArgs;
+use crate::hub::{HubEvent, HubRecordEvent, get_hub, read_hub_record};
+use crate::model::{EntityAction, ModelManager};
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::time::Duration;
+
+/// Executes `aip replay-events <file>`, the replay into the TUI of the hub events
+/// recorded with `aip run ... --record-events <file>`, at the recorded pace (divided by `--speed`).
+///
+/// The rows of the model events are written to the runtime db before the event is sent,
+/// so that the TUI reads the same state as when recorded.
+pub async fn exec_replay_events(args: ReplayEventsArgs, mm: ModelManager) -> Result<()> {
+	let hub = get_hub();
+
+	if args.speed.is_nan() || args.speed <= 0.0 {
+		return Err(Error::custom(format!(
+			"aip replay-events --speed must be greater than 0 (was {})",
+			args.speed
+		)));
+	}
+
+	let lines = read_hub_record(&SPath::new(&args.file))?;
+	let events_count = lines.len();
+
+	let mut last_time_us = 0;
+	for line in lines {
+		let wait_us = (line.time_us - last_time_us).max(0) as f64 / args.speed;
+		last_time_us = line.time_us;
+		if wait_us >= 1.0 {
+			tokio::time::sleep(Duration::from_micros(wait_us as u64)).await;
+		}
+
+		if let HubRecordEvent::Model { model_event } = &line.event
+			&& model_event.action != EntityAction::Deleted
+			&& !line.rows.is_empty()
+		{
+			mm.db().upsert_json_rows(model_event.entity.table(), &line.rows)?;
+		}
+
+		hub.publish(line.event.into_hub_event()).await;
+	}
+
+	hub.publish(HubEvent::info_short(format!(
+		"Replay done ({events_count} events of '{}')",
+		args.file
+	)))
+	.await;
+
+	Ok(())
+}
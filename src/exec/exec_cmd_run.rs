@@ -60,6 +60,7 @@ pub async fn exec_run_first(run_args: RunArgs, runtime: Runtime) -> Result<(RunR
 					run_options.clone(),
 					redo_requested,
 					run_options.flow_redo_count(),
+					run_agent_res.run_redo_data,
 				),
 				redo_requested,
 			));
@@ -74,6 +75,7 @@ pub async fn exec_run_first(run_args: RunArgs, runtime: Runtime) -> Result<(RunR
 			run_options.clone(),
 			false,
 			run_options.flow_redo_count(),
+			None,
 		),
 		false,
 	))
@@ -104,6 +106,7 @@ pub async fn exec_run_redo(run_redo_ctx: &RunRedoCtx) -> Option<RunRedoCtx> {
 			run_options,
 			run_agent_res.redo_requested,
 			run_redo_ctx.flow_redo_count(),
+			run_agent_res.run_redo_data,
 		)),
 		Err(err) => {
 			hub.publish(err).await;
@@ -156,9 +159,19 @@ pub fn exec_run_watch(redo_ctx: RunRedoCtx) {
 
 /// Do one run
 async fn do_run(run_command_options: &RunTopAgentParams, runtime: &Runtime, agent: &Agent) -> Result<RunAgentResponse> {
+	// -- Validate the `--args-json` and `--arg name=value` against the agent `# Meta` params
+	let args = agent
+		.meta()
+		.resolve_args(&run_command_options.cli_args(), run_command_options.cli_args_json())?;
+	let agent = agent.clone().with_args(args);
+
 	let inputs = if let Some(on_inputs) = run_command_options.on_inputs() {
 		Some(into_values(on_inputs)?)
-	} else if let Some(on_file_globs) = run_command_options.on_file_globs() {
+	} else if let Some(on_file_globs) = run_command_options
+		.on_file_globs()
+		// When no `-f` or `-i`, the eventual config `[run] input_globs`
+		.or_else(|| agent.config().and_then(|config| config.input_globs()))
+	{
 		// -- First, normalize the globs
 		// Note: here we add the eventual `./` for relative globs so that it works both ways
 		//       when we do a `-f "./src/*.rs"` or `-f "src/*.rs"`
@@ -203,7 +216,7 @@ async fn do_run(run_command_options: &RunTopAgentParams, runtime: &Runtime, agen
 	let res = run_agent(
 		runtime,
 		None,
-		agent.clone(),
+		agent,
 		inputs,
 		run_command_options.base_run_options(),
 		false,
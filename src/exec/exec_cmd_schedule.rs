@@ -0,0 +1,317 @@
+//! The `aip schedule` command, the recurring agent runs.
+//!
+//! - `aip schedule "0 9 * * 1" my-pack@agent` registers a schedule (for the current workspace dir)
+//! - `aip schedule --list` and `aip schedule --remove <id>` manage them
+//! - `aip schedule --daemon` runs them (each one through the Executor, as a `ScheduledRun`)
+//!
+//! The schedules are persisted in `~/.aipack-base/schedules.json`.
+
+use crate::dir_context::AipackBaseDir;
+use crate::exec::cli::{RunArgs, ScheduleArgs};
+use crate::exec::{ExecActionEvent, ExecutorTx};
+use crate::hub::get_hub;
+use crate::model::Id;
+use crate::support::cron::CronExpr;
+use crate::support::time::{now_local, now_rfc3339_local_sec};
+use crate::{Error, Result};
+use clap::Parser as _;
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_file_dir};
+use std::time::Duration;
+use time::format_description::well_known::Rfc3339;
+
+// region:    --- Types
+
+/// A recurring agent run (`aip schedule <cron> <agent>`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+	pub id: u32,
+	pub cron: String,
+	/// The workspace dir the run is executed in (the current dir at registration)
+	pub wks_dir: String,
+	pub run_args: RunArgs,
+	pub created: String,
+
+	pub last_run: Option<String>,
+	/// `ok` or the error message
+	pub last_status: Option<String>,
+	pub last_run_id: Option<i64>,
+}
+
+/// A schedule run sent by the daemon to the Executor
+#[derive(Debug)]
+pub struct ScheduledRun {
+	pub schedule_id: u32,
+	pub wks_dir: String,
+	pub run_args: RunArgs,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleStore {
+	schedules: Vec<Schedule>,
+}
+
+// endregion: --- Types
+
+/// Exec for the `aip schedule` command
+pub async fn exec_schedule(args: ScheduleArgs, executor_tx: ExecutorTx) -> Result<()> {
+	let hub = get_hub();
+	let schedules_file = AipackBaseDir::new()?.schedules_file();
+
+	if args.daemon {
+		return run_daemon(&schedules_file, executor_tx).await;
+	}
+
+	if let Some(id) = args.remove {
+		let mut store = ScheduleStore::load(&schedules_file)?;
+		let len = store.schedules.len();
+		store.schedules.retain(|s| s.id != id);
+		if store.schedules.len() == len {
+			return Err(Error::custom(format!(
+				"Schedule '{id}' not found (see `aip schedule --list`)"
+			)));
+		}
+		store.save(&schedules_file)?;
+		hub.publish(format!("Schedule '{id}' removed")).await;
+		return Ok(());
+	}
+
+	match (args.cron.as_deref(), args.agent.as_deref()) {
+		(Some(cron), Some(agent)) => {
+			let schedule = register_schedule(&schedules_file, &args, cron, agent)?;
+			let next = next_run_fmt(&schedule.cron);
+			hub.publish(format!(
+				"Schedule '{}' registered: '{}' {agent} (in '{}')\n   Next run: {next} (runs with `aip schedule --daemon`)",
+				schedule.id, schedule.cron, schedule.wks_dir
+			))
+			.await;
+		}
+		(Some(_), None) => {
+			return Err("Missing the agent to schedule, e.g., `aip schedule \"0 9 * * 1\" my-pack@agent`".into());
+		}
+		_ => {
+			let store = ScheduleStore::load(&schedules_file)?;
+			hub.publish(fmt_schedules(&store.schedules)).await;
+		}
+	}
+
+	Ok(())
+}
+
+/// Record the result of a schedule run (called by the Executor when the run is done)
+pub fn record_schedule_run(schedule_id: u32, run_id: Option<Id>, res: &Result<()>) -> Result<()> {
+	let schedules_file = AipackBaseDir::new()?.schedules_file();
+	let mut store = ScheduleStore::load(&schedules_file)?;
+	// NOTE: The schedule might have been removed while running
+	if let Some(schedule) = store.schedules.iter_mut().find(|s| s.id == schedule_id) {
+		schedule.last_run = Some(now_rfc3339_local_sec()?);
+		schedule.last_status = Some(match res {
+			Ok(_) => "ok".to_string(),
+			Err(err) => err.to_string(),
+		});
+		schedule.last_run_id = run_id.map(|id| id.as_i64());
+		store.save(&schedules_file)?;
+	}
+	Ok(())
+}
+
+// region:    --- Support
+
+fn register_schedule(schedules_file: &SPath, args: &ScheduleArgs, cron: &str, agent: &str) -> Result<Schedule> {
+	// -- Validate the cron expression
+	cron.parse::<CronExpr>()?;
+
+	let wks_dir = SPath::new(std::env::current_dir()?.to_string_lossy().to_string()).canonicalize()?;
+
+	// -- Build the run args (single shot, with the file globs relative to the workspace dir)
+	let mut run_cli: Vec<String> = vec!["run".into(), agent.into(), "--single-shot".into()];
+	for input in args.on_inputs.iter().flatten() {
+		run_cli.extend(["-i".into(), input.clone()]);
+	}
+	for glob in args.on_files.iter().flatten() {
+		let glob = if SPath::new(glob).is_absolute() {
+			glob.clone()
+		} else {
+			wks_dir.join(glob).to_string()
+		};
+		run_cli.extend(["-f".into(), glob]);
+	}
+	for arg in args.args.iter().flatten() {
+		run_cli.extend(["--arg".into(), arg.clone()]);
+	}
+	let run_args = RunArgs::try_parse_from(run_cli)
+		.map_err(|err| Error::custom(format!("Schedule run args invalid.\nCause: {err}")))?;
+
+	// -- Save
+	let mut store = ScheduleStore::load(schedules_file)?;
+	let id = store.schedules.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+	let schedule = Schedule {
+		id,
+		cron: cron.to_string(),
+		wks_dir: wks_dir.to_string(),
+		run_args,
+		created: now_rfc3339_local_sec()?,
+		last_run: None,
+		last_status: None,
+		last_run_id: None,
+	};
+	store.schedules.push(schedule.clone());
+	store.save(schedules_file)?;
+
+	Ok(schedule)
+}
+
+/// Check the schedules at each minute, and send the matching ones to the Executor.
+///
+/// NOTE: The schedules file is reloaded at each minute, so that the schedules registered
+///       or removed while the daemon runs are taken into account.
+async fn run_daemon(schedules_file: &SPath, executor_tx: ExecutorTx) -> Result<()> {
+	let hub = get_hub();
+
+	let store = ScheduleStore::load(schedules_file)?;
+	hub.publish(format!(
+		"Schedule daemon started ({} schedule(s) in '{schedules_file}')\n{}",
+		store.schedules.len(),
+		fmt_schedules(&store.schedules)
+	))
+	.await;
+
+	loop {
+		// -- Wait for the next minute
+		let now = now_local();
+		let secs_to_next_minute = 60 - now.second() as u64;
+		tokio::time::sleep(Duration::from_secs(secs_to_next_minute)).await;
+
+		let now = now_local();
+		let store = match ScheduleStore::load(schedules_file) {
+			Ok(store) => store,
+			Err(err) => {
+				hub.publish(Error::cc("Schedule daemon fail to load the schedules", err)).await;
+				continue;
+			}
+		};
+
+		for schedule in store.schedules {
+			let cron_expr = match schedule.cron.parse::<CronExpr>() {
+				Ok(cron_expr) => cron_expr,
+				Err(err) => {
+					hub.publish(Error::cc(format!("Schedule '{}' skipped", schedule.id), err)).await;
+					continue;
+				}
+			};
+			if !cron_expr.matches(&now) {
+				continue;
+			}
+
+			hub.publish(format!(
+				"\n==== Schedule '{}' - {} (in '{}')",
+				schedule.id, schedule.run_args.cmd_agent_name, schedule.wks_dir
+			))
+			.await;
+			executor_tx
+				.send(ExecActionEvent::ScheduledRun(ScheduledRun {
+					schedule_id: schedule.id,
+					wks_dir: schedule.wks_dir,
+					run_args: schedule.run_args,
+				}))
+				.await;
+		}
+	}
+}
+
+fn next_run_fmt(cron: &str) -> String {
+	cron.parse::<CronExpr>()
+		.ok()
+		.and_then(|cron_expr| cron_expr.next_after(&now_local()))
+		.and_then(|next| next.format(&Rfc3339).ok())
+		.unwrap_or_else(|| "none".to_string())
+}
+
+fn fmt_schedules(schedules: &[Schedule]) -> String {
+	if schedules.is_empty() {
+		return "No schedules (register one with `aip schedule \"0 9 * * 1\" my-pack@agent`)".to_string();
+	}
+
+	let mut buf = String::new();
+	for schedule in schedules {
+		buf.push_str(&format!(
+			"- [{}] '{}' {}\n    Workspace: {}\n    Next run:  {}\n",
+			schedule.id,
+			schedule.cron,
+			schedule.run_args.cmd_agent_name,
+			schedule.wks_dir,
+			next_run_fmt(&schedule.cron)
+		));
+		if let Some(last_run) = schedule.last_run.as_deref() {
+			let run_id = schedule.last_run_id.map(|id| format!(" (run {id})")).unwrap_or_default();
+			buf.push_str(&format!(
+				"    Last run:  {last_run} - {}{run_id}\n",
+				schedule.last_status.as_deref().unwrap_or_default()
+			));
+		}
+	}
+	buf
+}
+
+impl ScheduleStore {
+	fn load(file: &SPath) -> Result<Self> {
+		if !file.exists() {
+			return Ok(Self::default());
+		}
+		let content = simple_fs::read_to_string(file)?;
+		serde_json::from_str(&content)
+			.map_err(|err| Error::custom(format!("Schedules file '{file}' is invalid.\nCause: {err}")))
+	}
+
+	fn save(&self, file: &SPath) -> Result<()> {
+		ensure_file_dir(file)?;
+		std::fs::write(file.std_path(), serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_exec_schedule_store_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let file = SPath::new("tests-data/sandbox-01/.tmp/test_exec_schedule/schedules.json");
+		let run_args = RunArgs::try_parse_from(["run", "demo@proof", "--single-shot", "-i", "hello"])?;
+		let store = ScheduleStore {
+			schedules: vec![Schedule {
+				id: 1,
+				cron: "0 9 * * 1".to_string(),
+				wks_dir: "/tmp/wks".to_string(),
+				run_args,
+				created: "2025-01-06T09:00:00Z".to_string(),
+				last_run: None,
+				last_status: None,
+				last_run_id: None,
+			}],
+		};
+
+		// -- Exec
+		store.save(&file)?;
+		let loaded = ScheduleStore::load(&file)?;
+
+		// -- Check
+		let schedule = loaded.schedules.first().ok_or("Should have a schedule")?;
+		assert_eq!(schedule.cron, "0 9 * * 1");
+		assert_eq!(schedule.run_args.cmd_agent_name, "demo@proof");
+		assert!(schedule.run_args.single_shot);
+		assert_eq!(schedule.run_args.on_inputs, Some(vec!["hello".to_string()]));
+		assert!(fmt_schedules(&loaded.schedules).contains("[1] '0 9 * * 1' demo@proof"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
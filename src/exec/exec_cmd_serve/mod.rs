@@ -0,0 +1,131 @@
+//! The `aip serve` command, the HTTP server exposing the runs over a REST API (e.g., for editors, CI, and webhooks).
+//!
+//! - `GET  /api/health`            - The server status and version
+//! - `GET  /api/packs`             - The packs (as `aip list --json`, with the `pack_ref` and `tag` query filters)
+//! - `POST /api/runs`              - Trigger a run `{"agent": "demo@proof", "inputs"?: [], "files"?: [], "params"?: {}}`
+//! - `GET  /api/runs`              - The runs (latest first, `limit` query, default 50)
+//! - `GET  /api/runs/{id}`         - A run
+//! - `GET  /api/runs/{id}/tasks`   - The tasks of a run
+//! - `GET  /api/tasks/{id}`        - A task, with its input, output, and error
+//! - `GET  /api/events`            - The Server-Sent Events stream of the hub events (messages, errors, run/task changes)
+//!
+//! The runs are executed by the Executor of the `aip serve` process (in its workspace), one at a time.
+//! When `AIPACK_SERVE_TOKEN` is set, the requests must have the `Authorization: Bearer <token>` header.
+//! Without it, only the local non-browser clients are accepted (loopback client address and `Host`, no `Origin` header),
+//! and the listen address must be a loopback address.
+
+// region:    --- Modules
+
+mod serve_http;
+mod serve_routes;
+
+use serve_http::*;
+use serve_routes::*;
+
+// endregion: --- Modules
+
+use crate::dir_context::DirContext;
+use crate::exec::ExecutorTx;
+use crate::exec::cli::ServeArgs;
+use crate::hub::get_hub;
+use crate::model::ModelManager;
+use crate::{Error, Result};
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::io::{AsyncWrite, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The env var of the bearer token (when set, the requests must have it)
+const SERVE_TOKEN_ENV: &str = "AIPACK_SERVE_TOKEN";
+
+/// The SSE keep alive interval (also how fast a closed events connection is detected when idle)
+const SSE_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Exec for the `aip serve` command (runs until the process is stopped)
+pub async fn exec_serve(
+	args: ServeArgs,
+	dir_context: DirContext,
+	mm: ModelManager,
+	executor_tx: ExecutorTx,
+) -> Result<()> {
+	let hub = get_hub();
+
+	let listener = TcpListener::bind(&args.listen)
+		.await
+		.map_err(|err| Error::custom(format!("Cannot listen on '{}'. Cause: {err}", args.listen)))?;
+	let addr = listener
+		.local_addr()
+		.map_err(|err| Error::custom(format!("Cannot get the listen address. Cause: {err}")))?;
+
+	let token = std::env::var(SERVE_TOKEN_ENV).ok().filter(|t| !t.is_empty());
+	if token.is_none() && !addr.ip().is_loopback() {
+		return Err(Error::custom(format!(
+			"aip serve on the non loopback address '{addr}' requires a bearer token (set {SERVE_TOKEN_ENV})"
+		)));
+	}
+	let auth_info = if token.is_some() {
+		format!("bearer token required ({SERVE_TOKEN_ENV})")
+	} else {
+		format!("no auth, local clients only, set {SERVE_TOKEN_ENV} to require a bearer token")
+	};
+	hub.publish(format!(
+		"aip serve listening on http://{addr}/api ({auth_info})\n   Workspace: {}",
+		dir_context.wks_dir().map(|d| d.to_string()).unwrap_or_default()
+	))
+	.await;
+
+	let ctx = ServeCtx {
+		dir_context,
+		mm,
+		executor_tx,
+		token,
+	};
+
+	loop {
+		match listener.accept().await {
+			Ok((stream, peer)) => {
+				let ctx = ctx.clone();
+				tokio::spawn(async move {
+					if let Err(err) = handle_connection(&ctx, stream, peer.ip()).await {
+						tracing::warn!("aip serve connection '{peer}' failed: {err}");
+					}
+				});
+			}
+			Err(err) => hub.publish_err("aip serve cannot accept a connection", Some(err)).await,
+		}
+	}
+}
+
+// region:    --- Support
+
+async fn handle_connection(ctx: &ServeCtx, stream: TcpStream, peer_ip: IpAddr) -> Result<()> {
+	let (reader, mut writer) = stream.into_split();
+	let mut reader = BufReader::new(reader);
+
+	let req = match read_request(&mut reader).await {
+		Ok(Some(req)) => req,
+		Ok(None) => return Ok(()),
+		Err(err) => return HttpResponse::error(400, err.to_string()).write_to(&mut writer).await,
+	};
+
+	match route(ctx, &req, peer_ip).await {
+		Route::Response(res) => res.write_to(&mut writer).await,
+		Route::Events => stream_events(&mut writer).await,
+	}
+}
+
+/// Stream the hub events until the connection is closed
+async fn stream_events<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<()> {
+	let tap_rx = get_hub().subscribe_tap();
+	write_sse_head(writer).await?;
+
+	loop {
+		match tokio::time::timeout(SSE_KEEP_ALIVE, tap_rx.recv_async()).await {
+			Ok(Ok(value)) => write_sse_event(writer, &value).await?,
+			Ok(Err(_)) => return Ok(()),
+			Err(_) => write_sse_keep_alive(writer).await?,
+		}
+	}
+}
+
+// endregion: --- Support
@@ -0,0 +1,287 @@
+//! Minimal HTTP/1.1 support for `aip serve` (one request per connection, `Connection: close`).
+
+use crate::{Error, Result};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt as _, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+
+/// The max size of a request head (request line and headers)
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+/// The max size of a request body
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+// region:    --- HttpRequest
+
+#[derive(Debug)]
+pub struct HttpRequest {
+	pub method: String,
+	/// The path, without the query (e.g., `/api/runs/12`)
+	pub path: String,
+	pub query: Vec<(String, String)>,
+	/// The headers, with the lowercase names
+	pub headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+	pub fn header(&self, name: &str) -> Option<&str> {
+		self.headers.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+	}
+
+	pub fn query_param(&self, name: &str) -> Option<&str> {
+		self.query.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+	}
+
+	/// The path segments (e.g., `["api", "runs", "12"]`)
+	pub fn path_segments(&self) -> Vec<&str> {
+		self.path.split('/').filter(|s| !s.is_empty()).collect()
+	}
+
+	pub fn body_json(&self) -> Result<Value> {
+		serde_json::from_slice(&self.body)
+			.map_err(|err| Error::custom(format!("Request body is not valid JSON. {err}")))
+	}
+}
+
+/// Read a request. Returns None when the connection is closed before the request line.
+pub async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<HttpRequest>> {
+	let mut head_size = 0;
+	let mut read_line = async |reader: &mut R| -> Result<Option<String>> {
+		let mut line = String::new();
+		// NOTE: The read is capped, so that a line without a newline cannot grow past the head max size.
+		let remaining = (MAX_HEAD_SIZE + 1).saturating_sub(head_size) as u64;
+		let n = (&mut *reader)
+			.take(remaining)
+			.read_line(&mut line)
+			.await
+			.map_err(|err| Error::custom(format!("Cannot read the request. {err}")))?;
+		head_size += n;
+		if head_size > MAX_HEAD_SIZE {
+			return Err("Request head too large".into());
+		}
+		Ok((n > 0).then(|| line.trim_end_matches(['\r', '\n']).to_string()))
+	};
+
+	// -- Request line
+	let Some(request_line) = read_line(reader).await? else {
+		return Ok(None);
+	};
+	let mut parts = request_line.split_whitespace();
+	let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+		return Err(Error::custom(format!("Invalid request line '{request_line}'")));
+	};
+	let (path, query) = match target.split_once('?') {
+		Some((path, query)) => (path, parse_query(query)),
+		None => (target, Vec::new()),
+	};
+
+	// -- Headers
+	let mut headers = Vec::new();
+	while let Some(line) = read_line(reader).await? {
+		if line.is_empty() {
+			break;
+		}
+		if let Some((name, value)) = line.split_once(':') {
+			headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+		}
+	}
+
+	let mut request = HttpRequest {
+		method: method.to_uppercase(),
+		path: path.to_string(),
+		query,
+		headers,
+		body: Vec::new(),
+	};
+
+	// -- Body
+	let content_length: usize = match request.header("content-length") {
+		Some(len) => len
+			.parse()
+			.map_err(|_| Error::custom(format!("Invalid content-length '{len}'")))?,
+		None => 0,
+	};
+	if content_length > MAX_BODY_SIZE {
+		return Err(Error::custom(format!(
+			"Request body too large (max {MAX_BODY_SIZE} bytes)"
+		)));
+	}
+	if content_length > 0 {
+		let mut body = vec![0; content_length];
+		reader
+			.read_exact(&mut body)
+			.await
+			.map_err(|err| Error::custom(format!("Cannot read the request body. {err}")))?;
+		request.body = body;
+	}
+
+	Ok(Some(request))
+}
+
+/// Parse the `a=1&b=2` query (the `+` and `%XX` are decoded)
+fn parse_query(query: &str) -> Vec<(String, String)> {
+	query
+		.split('&')
+		.filter(|pair| !pair.is_empty())
+		.map(|pair| {
+			let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+			(url_decode(name), url_decode(value))
+		})
+		.collect()
+}
+
+fn url_decode(value: &str) -> String {
+	let bytes = value.as_bytes();
+	let mut out: Vec<u8> = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		match bytes[i] {
+			b'+' => out.push(b' '),
+			b'%' if i + 2 < bytes.len() => {
+				let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+				match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+					Some(byte) => {
+						out.push(byte);
+						i += 2;
+					}
+					None => out.push(b'%'),
+				}
+			}
+			byte => out.push(byte),
+		}
+		i += 1;
+	}
+	String::from_utf8_lossy(&out).to_string()
+}
+
+// endregion: --- HttpRequest
+
+// region:    --- HttpResponse
+
+#[derive(Debug)]
+pub struct HttpResponse {
+	pub status: u16,
+	pub content_type: &'static str,
+	pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+	pub fn json(status: u16, value: &Value) -> Self {
+		Self {
+			status,
+			content_type: "application/json",
+			body: value.to_string().into_bytes(),
+		}
+	}
+
+	/// The error response `{"error": "..."}`
+	pub fn error(status: u16, message: impl Into<String>) -> Self {
+		Self::json(status, &serde_json::json!({ "error": message.into() }))
+	}
+
+	pub async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> Result<()> {
+		let head = format!(
+			"HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+			self.status,
+			status_reason(self.status),
+			self.content_type,
+			self.body.len()
+		);
+		write_all(writer, head.as_bytes()).await?;
+		write_all(writer, &self.body).await?;
+		Ok(())
+	}
+}
+
+// endregion: --- HttpResponse
+
+// region:    --- Sse
+
+/// Write the head of a Server-Sent Events response (the events follow, until the connection closes)
+pub async fn write_sse_head<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<()> {
+	let head =
+		"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n";
+	write_all(writer, head.as_bytes()).await
+}
+
+/// Write a SSE event (`event: <name>` when the value has a `type`)
+pub async fn write_sse_event<W: AsyncWrite + Unpin>(writer: &mut W, value: &Value) -> Result<()> {
+	let event = match value["type"].as_str() {
+		Some(name) => format!("event: {name}\ndata: {value}\n\n"),
+		None => format!("data: {value}\n\n"),
+	};
+	write_all(writer, event.as_bytes()).await
+}
+
+/// Write a SSE comment (keeps the connection alive, and detects the closed ones)
+pub async fn write_sse_keep_alive<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<()> {
+	write_all(writer, b": keep-alive\n\n").await
+}
+
+// endregion: --- Sse
+
+// region:    --- Support
+
+async fn write_all<W: AsyncWrite + Unpin>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+	writer
+		.write_all(bytes)
+		.await
+		.map_err(|err| Error::custom(format!("Cannot write the response. {err}")))?;
+	writer
+		.flush()
+		.await
+		.map_err(|err| Error::custom(format!("Cannot flush the response. {err}")))
+}
+
+fn status_reason(status: u16) -> &'static str {
+	match status {
+		200 => "OK",
+		202 => "Accepted",
+		400 => "Bad Request",
+		401 => "Unauthorized",
+		403 => "Forbidden",
+		404 => "Not Found",
+		405 => "Method Not Allowed",
+		415 => "Unsupported Media Type",
+		500 => "Internal Server Error",
+		_ => "Unknown",
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use tokio::io::BufReader;
+
+	#[tokio::test]
+	async fn test_exec_serve_read_request_head_max_size() -> Result<()> {
+		// -- Setup & Fixtures
+		let ok_raw = "POST /api/runs?limit=2 HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Length: 2\r\n\r\n{}";
+		// a request line without newline, larger than the head max size
+		let big_raw = format!("GET /{}", "a".repeat(MAX_HEAD_SIZE * 2));
+
+		// -- Exec
+		let ok_req = read_request(&mut BufReader::new(ok_raw.as_bytes()))
+			.await?
+			.ok_or("Should have a request")?;
+		let big_res = read_request(&mut BufReader::new(big_raw.as_bytes())).await;
+
+		// -- Check
+		assert_eq!(ok_req.method, "POST");
+		assert_eq!(ok_req.path, "/api/runs");
+		assert_eq!(ok_req.query_param("limit"), Some("2"));
+		assert_eq!(ok_req.header("host"), Some("127.0.0.1"));
+		assert_eq!(ok_req.body, b"{}");
+		let err = big_res.err().ok_or("Should be an error")?;
+		assert!(err.to_string().contains("too large"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
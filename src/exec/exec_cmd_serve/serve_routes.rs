@@ -0,0 +1,371 @@
+//! The `aip serve` REST API routes.
+
+use crate::dir_context::DirContext;
+use crate::exec::cli::{ListArgs, RunArgs};
+use crate::exec::exec_cmd_list::build_pack_list_items;
+use crate::exec::exec_cmd_serve::serve_http::{HttpRequest, HttpResponse};
+use crate::exec::{ExecActionEvent, ExecutorTx};
+use crate::model::{ErrBmc, Id, ModelManager, Run, RunBmc, Task, TaskBmc};
+use crate::{Error, Result};
+use clap::Parser as _;
+use serde_json::{Value, json};
+use std::net::IpAddr;
+
+/// The default number of runs of `GET /api/runs`
+const DEFAULT_RUNS_LIMIT: i64 = 50;
+
+/// The context shared by all the requests
+#[derive(Clone)]
+pub struct ServeCtx {
+	pub dir_context: DirContext,
+	pub mm: ModelManager,
+	pub executor_tx: ExecutorTx,
+	/// When set, the requests must have the `Authorization: Bearer <token>` header
+	pub token: Option<String>,
+}
+
+/// The route of a request
+pub enum Route {
+	Response(HttpResponse),
+	/// `GET /api/events`, the SSE stream of the hub events
+	Events,
+}
+
+/// Route the request of the `peer_ip` client (the errors are returned as the JSON error responses, `500` for the store errors)
+pub async fn route(ctx: &ServeCtx, req: &HttpRequest, peer_ip: IpAddr) -> Route {
+	// -- Auth
+	if let Some(token) = ctx.token.as_deref()
+		&& req.header("authorization").and_then(|v| v.strip_prefix("Bearer ")) != Some(token)
+	{
+		return Route::Response(HttpResponse::error(401, "Missing or invalid bearer token"));
+	}
+	// NOTE: Without a token, only the local non browser clients are accepted (no cross site or DNS rebinding requests).
+	if ctx.token.is_none()
+		&& let Err(err) = check_local_client(req, peer_ip)
+	{
+		return Route::Response(HttpResponse::error(403, err.to_string()));
+	}
+
+	let segments = req.path_segments();
+	let res = match (req.method.as_str(), segments.as_slice()) {
+		("GET", ["api", "health"]) => Ok(HttpResponse::json(
+			200,
+			&json!({"status": "ok", "version": env!("CARGO_PKG_VERSION")}),
+		)),
+		("GET", ["api", "events"]) => return Route::Events,
+		("GET", ["api", "packs"]) => list_packs(ctx, req),
+		("GET", ["api", "runs"]) => list_runs(ctx, req),
+		("POST", ["api", "runs"]) => create_run(ctx, req).await,
+		("GET", ["api", "runs", run_id]) => with_id(run_id, |id| get_run(ctx, id)),
+		("GET", ["api", "runs", run_id, "tasks"]) => with_id(run_id, |id| list_run_tasks(ctx, id)),
+		("GET", ["api", "tasks", task_id]) => with_id(task_id, |id| get_task(ctx, id)),
+		(_, ["api", ..]) if is_known_path(&segments) => Ok(HttpResponse::error(405, "Method not allowed")),
+		_ => Ok(HttpResponse::error(404, format!("No route for '{}'", req.path))),
+	};
+
+	Route::Response(res.unwrap_or_else(|err| HttpResponse::error(500, err.to_string())))
+}
+
+// region:    --- Handlers
+
+fn list_packs(ctx: &ServeCtx, req: &HttpRequest) -> Result<HttpResponse> {
+	let list_args = ListArgs {
+		pack_ref: req.query_param("pack_ref").map(|v| v.to_string()),
+		tag: req.query_param("tag").map(|v| v.to_string()),
+		json: true,
+		check_updates: false,
+		open: false,
+	};
+	let items = build_pack_list_items(&ctx.dir_context, &list_args)?;
+	Ok(HttpResponse::json(200, &serde_json::to_value(items)?))
+}
+
+fn list_runs(ctx: &ServeCtx, req: &HttpRequest) -> Result<HttpResponse> {
+	let limit = match req.query_param("limit").map(|limit| (limit, limit.parse::<i64>())) {
+		Some((_, Ok(limit))) => limit,
+		Some((limit, Err(_))) => {
+			return Ok(HttpResponse::error(
+				400,
+				format!("Query param 'limit' must be a number, was '{limit}'"),
+			));
+		}
+		None => DEFAULT_RUNS_LIMIT,
+	};
+	let runs: Vec<Value> = RunBmc::list_for_display(&ctx.mm, Some(limit))?.iter().map(run_json).collect();
+	Ok(HttpResponse::json(200, &Value::Array(runs)))
+}
+
+fn get_run(ctx: &ServeCtx, run_id: Id) -> Result<HttpResponse> {
+	let Ok(run) = RunBmc::get(&ctx.mm, run_id) else {
+		return Ok(not_found("Run", run_id));
+	};
+	let mut value = run_json(&run);
+	value["error"] = json!(get_err_content(&ctx.mm, run.end_err_id)?);
+	Ok(HttpResponse::json(200, &value))
+}
+
+fn list_run_tasks(ctx: &ServeCtx, run_id: Id) -> Result<HttpResponse> {
+	if RunBmc::get(&ctx.mm, run_id).is_err() {
+		return Ok(not_found("Run", run_id));
+	}
+	let tasks: Vec<Value> = TaskBmc::list_for_run(&ctx.mm, run_id)?.iter().map(task_json).collect();
+	Ok(HttpResponse::json(200, &Value::Array(tasks)))
+}
+
+fn get_task(ctx: &ServeCtx, task_id: Id) -> Result<HttpResponse> {
+	let Ok(task) = TaskBmc::get(&ctx.mm, task_id) else {
+		return Ok(not_found("Task", task_id));
+	};
+	let mut value = task_json(&task);
+	value["input"] = json!(TaskBmc::get_input_for_display(&ctx.mm, &task)?);
+	value["output"] = json!(TaskBmc::get_output_for_display(&ctx.mm, &task)?);
+	value["error"] = json!(get_err_content(&ctx.mm, task.end_err_id)?);
+	Ok(HttpResponse::json(200, &value))
+}
+
+/// `POST /api/runs` with `{"agent": "...", "inputs"?: [...], "files"?: [...], "params"?: {...}}`.
+/// The run is sent to the executor (the run id comes with the `/api/events` model events, or `GET /api/runs`).
+async fn create_run(ctx: &ServeCtx, req: &HttpRequest) -> Result<HttpResponse> {
+	// NOTE: The JSON content type cannot be sent cross site without a CORS preflight (which is never allowed).
+	if !is_json_content_type(req) {
+		return Ok(HttpResponse::error(
+			415,
+			"Request content-type must be 'application/json'",
+		));
+	}
+	let run_args = match req.body_json().and_then(|body| run_args_from_body(&body)) {
+		Ok(run_args) => run_args,
+		Err(err) => return Ok(HttpResponse::error(400, err.to_string())),
+	};
+	let agent = run_args.cmd_agent_name.clone();
+
+	ctx.executor_tx.send(ExecActionEvent::Run(run_args)).await;
+
+	Ok(HttpResponse::json(202, &json!({"accepted": true, "agent": agent})))
+}
+
+// endregion: --- Handlers
+
+// region:    --- Support
+
+/// Build the (single shot) run args from the `POST /api/runs` body
+fn run_args_from_body(body: &Value) -> Result<RunArgs> {
+	let agent = body["agent"].as_str().ok_or("Request body must have an 'agent' string")?;
+	// NOTE: The agent is a CLI arg, so, it cannot be a flag.
+	if agent.is_empty() || agent.starts_with('-') {
+		return Err(Error::custom(format!(
+			"Request 'agent' '{agent}' is not a valid agent name"
+		)));
+	}
+
+	let mut run_cli: Vec<String> = vec!["run".into(), agent.into(), "--single-shot".into()];
+	for (flag, name) in [("-i", "inputs"), ("-f", "files")] {
+		for value in body[name].as_array().into_iter().flatten() {
+			let value = match value {
+				Value::String(value) => value.clone(),
+				other => other.to_string(),
+			};
+			run_cli.extend([flag.into(), value]);
+		}
+	}
+	if let Some(params) = body["params"].as_object() {
+		for (name, value) in params {
+			let value = match value {
+				Value::String(value) => value.clone(),
+				other => other.to_string(),
+			};
+			run_cli.extend(["--arg".into(), format!("{name}={value}")]);
+		}
+	}
+
+	RunArgs::try_parse_from(run_cli).map_err(|err| Error::custom(format!("Invalid run request. {err}")))
+}
+
+/// Check that the request comes from a local client, and not from a browser page
+/// (loopback peer address, no `Origin` header, and a loopback `Host`, which rejects the DNS rebinding requests).
+///
+/// NOTE: The `Host` header is set by the client, so, only the peer address tells that the client is local.
+fn check_local_client(req: &HttpRequest, peer_ip: IpAddr) -> Result<()> {
+	if !peer_ip.is_loopback() {
+		return Err(Error::custom(format!(
+			"Client '{peer_ip}' is not a loopback client (set a serve token to allow it)"
+		)));
+	}
+	if let Some(origin) = req.header("origin") {
+		return Err(Error::custom(format!(
+			"Requests with an origin ('{origin}') are not allowed (set a serve token to allow them)"
+		)));
+	}
+	let host = req.header("host").unwrap_or_default();
+	if !is_loopback_host(host) {
+		return Err(Error::custom(format!(
+			"Request host '{host}' is not a loopback host (set a serve token to allow it)"
+		)));
+	}
+	Ok(())
+}
+
+/// Returns true for `localhost`, `127.0.0.1`, and `[::1]` (with or without a port)
+fn is_loopback_host(host: &str) -> bool {
+	let name = match host.strip_prefix('[') {
+		Some(rest) => rest.split_once(']').map(|(name, _)| name).unwrap_or(rest),
+		None => host.split_once(':').map(|(name, _)| name).unwrap_or(host),
+	};
+	matches!(name.to_lowercase().as_str(), "localhost" | "127.0.0.1" | "::1")
+}
+
+fn is_json_content_type(req: &HttpRequest) -> bool {
+	req.header("content-type")
+		.and_then(|v| v.split(';').next())
+		.is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Call the handler with the parsed path id (`400` when not a number)
+fn with_id(id: &str, handler: impl FnOnce(Id) -> Result<HttpResponse>) -> Result<HttpResponse> {
+	match id.parse::<i64>() {
+		Ok(id) => handler(id.into()),
+		Err(_) => Ok(HttpResponse::error(400, format!("Id must be a number, was '{id}'"))),
+	}
+}
+
+fn not_found(entity: &str, id: Id) -> HttpResponse {
+	HttpResponse::error(404, format!("{entity} '{}' not found", id.as_i64()))
+}
+
+fn is_known_path(segments: &[&str]) -> bool {
+	matches!(
+		segments,
+		["api", "health" | "events" | "packs" | "runs"]
+			| ["api", "runs", _]
+			| ["api", "runs", _, "tasks"]
+			| ["api", "tasks", _]
+	)
+}
+
+fn get_err_content(mm: &ModelManager, err_id: Option<Id>) -> Result<Option<String>> {
+	match err_id {
+		Some(err_id) => Ok(ErrBmc::get(mm, err_id)?.content),
+		None => Ok(None),
+	}
+}
+
+fn run_json(run: &Run) -> Value {
+	json!({
+		"id": run.id.as_i64(),
+		"uid": run.uid.to_string(),
+		"parent_id": run.parent_id.map(|id| id.as_i64()),
+		"parent_task_id": run.parent_task_id.map(|id| id.as_i64()),
+		"label": run.label,
+		"agent_name": run.agent_name,
+		"agent_path": run.agent_path,
+		"model": run.model,
+		"start": run.start.map(|v| v.as_i64()),
+		"end": run.end.map(|v| v.as_i64()),
+		"end_state": run.end_state.map(|v| v.as_ref().to_string()),
+		"total_cost": run.total_cost,
+		"total_task_ms": run.total_task_ms,
+		"res_cpu_ms": run.res_cpu_ms,
+		"res_peak_mem": run.res_peak_mem,
+		"res_read_bytes": run.res_read_bytes,
+		"res_write_bytes": run.res_write_bytes,
+	})
+}
+
+fn task_json(task: &Task) -> Value {
+	json!({
+		"id": task.id.as_i64(),
+		"uid": task.uid.to_string(),
+		"run_id": task.run_id.as_i64(),
+		"idx": task.idx,
+		"label": task.label,
+		"start": task.start.map(|v| v.as_i64()),
+		"end": task.end.map(|v| v.as_i64()),
+		"end_state": task.end_state.map(|v| v.as_ref().to_string()),
+		"progress": task.progress,
+		"model": task.model_upstream.as_ref().or(task.model_ov.as_ref()),
+		"cost": task.cost,
+		"input_short": task.input_short,
+		"output_short": task.output_short,
+	})
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_exec_serve_run_args_from_body() -> Result<()> {
+		// -- Setup & Fixtures
+		let body = json!({
+			"agent": "demo@proof",
+			"inputs": ["hello", 12],
+			"files": ["src/**/*.rs"],
+			"params": {"mode": "fast"},
+		});
+
+		// -- Exec
+		let run_args = run_args_from_body(&body)?;
+		let no_agent_res = run_args_from_body(&json!({"inputs": ["hello"]}));
+		let flag_agent_res = run_args_from_body(&json!({"agent": "--help"}));
+		let flag_short_agent_res = run_args_from_body(&json!({"agent": "-w"}));
+
+		// -- Check
+		assert_eq!(run_args.cmd_agent_name, "demo@proof");
+		assert!(run_args.single_shot);
+		assert_eq!(run_args.on_inputs, Some(vec!["hello".to_string(), "12".to_string()]));
+		assert_eq!(run_args.on_files, Some(vec!["src/**/*.rs".to_string()]));
+		assert!(no_agent_res.is_err());
+		let err = flag_agent_res.err().ok_or("Flag agent should fail")?;
+		assert!(err.to_string().contains("is not a valid agent name"));
+		assert!(flag_short_agent_res.is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_exec_serve_check_local_client() -> Result<()> {
+		// -- Setup & Fixtures
+		let req = |headers: &[(&str, &str)]| HttpRequest {
+			method: "POST".into(),
+			path: "/api/runs".into(),
+			query: Vec::new(),
+			headers: headers.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect(),
+			body: Vec::new(),
+		};
+
+		let local_ip: IpAddr = "127.0.0.1".parse()?;
+		let remote_ip: IpAddr = "203.0.113.7".parse()?;
+
+		// -- Exec & Check
+		assert!(check_local_client(&req(&[("host", "127.0.0.1:7979")]), local_ip).is_ok());
+		assert!(check_local_client(&req(&[("host", "localhost:7979")]), local_ip).is_ok());
+		assert!(check_local_client(&req(&[("host", "[::1]:7979")]), local_ip).is_ok());
+		assert!(check_local_client(&req(&[]), local_ip).is_err());
+		assert!(check_local_client(&req(&[("host", "evil.example.com:7979")]), local_ip).is_err());
+		assert!(
+			check_local_client(
+				&req(&[("host", "127.0.0.1:7979"), ("origin", "https://evil.example.com")]),
+				local_ip
+			)
+			.is_err()
+		);
+		// a remote client with a spoofed loopback host
+		assert!(check_local_client(&req(&[("host", "localhost:7979")]), remote_ip).is_err());
+		assert!(is_json_content_type(&req(&[(
+			"content-type",
+			"application/json; charset=utf-8"
+		)])));
+		assert!(!is_json_content_type(&req(&[("content-type", "text/plain")])));
+		assert!(!is_json_content_type(&req(&[])));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,48 @@
+use crate::Result;
+use crate::dir_context::DirContext;
+use crate::exec::cli::UninstallArgs;
+use crate::exec::packer::{UninstalledPack, uninstall_pack};
+use crate::hub::get_hub;
+
+/// Executes the uninstall command which removes an installed pack (and its downloads, and eventually its support data)
+pub async fn exec_uninstall(dir_context: DirContext, uninstall_args: UninstallArgs) -> Result<UninstalledPack> {
+	let hub = get_hub();
+
+	let uninstalled = uninstall_pack(
+		&dir_context,
+		&uninstall_args.pack_ref,
+		uninstall_args.data,
+		uninstall_args.dry_run,
+	)?;
+
+	let title = if uninstalled.dry_run {
+		"==== Uninstall (dry run, nothing removed)"
+	} else {
+		"==== Uninstalling aipack"
+	};
+	hub.publish(format!(
+		"\n{title}:\n\n{:>15} {}@{}\n{:>15} {}",
+		"Pack:", uninstalled.namespace, uninstalled.name, "Installed At:", uninstalled.installed_dir
+	))
+	.await;
+
+	for file in uninstalled.download_files.iter() {
+		hub.publish(format!("{:>15} {file}", "Download:")).await;
+	}
+	for support_dir in uninstalled.support_dirs.iter() {
+		hub.publish(format!("{:>15} {support_dir}", "Support Data:")).await;
+	}
+	if !uninstall_args.data {
+		hub.publish("\nNote: The pack support data (if any) is kept. Use '--data' to remove it as well.".to_string())
+			.await;
+	}
+
+	let done = if uninstalled.dry_run {
+		"\n==== DONE (Dry run)"
+	} else {
+		"\n==== DONE"
+	};
+	hub.publish(done.to_string()).await;
+
+	Ok(uninstalled)
+}
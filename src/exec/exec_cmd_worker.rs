@@ -0,0 +1,10 @@
+use crate::Result;
+use crate::exec::cli::WorkerArgs;
+use crate::run::run_worker;
+use crate::runtime::Runtime;
+
+/// Exec for the `aip worker --join <addr>` command
+/// Runs the tasks of the coordinator until it closes the connection.
+pub async fn exec_worker(args: WorkerArgs, runtime: Runtime) -> Result<()> {
+	run_worker(runtime, &args.join, args.slots.max(1), args.name).await
+}
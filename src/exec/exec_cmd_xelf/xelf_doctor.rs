@@ -0,0 +1,335 @@
+//! The `aip self doctor` command, which checks the aipack environment (base dir, workspace, API keys, PATH,
+//! legacy devai dirs, terminal) and prints the actionable fixes (e.g., for the support triage).
+//!
+//! NOTE: The doctor does not change anything (it does not init the base or the workspace).
+
+use crate::dir_context::{
+	AIPACK_DIR_NAME, AipackBaseDir, AipackPaths, CONFIG_BASE_DEFAULT_FILE_NAME, CONFIG_FILE_NAME, find_wks_dir,
+};
+use crate::exec::cli::XelfDoctorArgs;
+use crate::exec::exec_cmd_xelf::support;
+use crate::exec::support::{KEY_ENV_VARS, get_available_api_keys};
+use crate::hub::{HubEvent, get_hub};
+use crate::support::files::home_dir;
+use crate::support::tomls::parse_toml_into_json;
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::{SPath, read_to_string};
+use std::io::IsTerminal as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+	Ok,
+	Warn,
+	Err,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DoctorCheck {
+	pub name: &'static str,
+	pub status: CheckStatus,
+	pub detail: String,
+	/// The actionable fix (when not ok)
+	pub fix: Option<String>,
+}
+
+/// Executes the `self doctor` command.
+pub async fn exec_xelf_doctor(args: XelfDoctorArgs) -> Result<()> {
+	let hub = get_hub();
+
+	let base_dir = AipackBaseDir::new()?;
+	let current_dir = SPath::from_std_path(std::env::current_dir()?)?;
+	let checks = run_checks(&base_dir, &current_dir);
+
+	if args.json {
+		let json = serde_json::to_string_pretty(&checks)?;
+		hub.publish(HubEvent::Message(json.into())).await;
+		return Ok(());
+	}
+
+	hub.publish(format!("\n==== aip self doctor (aipack {}) ====\n", crate::VERSION))
+		.await;
+	for check in checks.iter() {
+		let prefix = match check.status {
+			CheckStatus::Ok => "-> OK  ",
+			CheckStatus::Warn => "-! WARN",
+			CheckStatus::Err => "-! ERR ",
+		};
+		let mut msg = format!("{prefix} {:<18} {}", check.name, check.detail);
+		if let Some(fix) = check.fix.as_deref() {
+			msg.push_str(&format!("\n          fix: {}", fix.replace('\n', "\n               ")));
+		}
+		hub.publish(msg).await;
+	}
+
+	let err_count = checks.iter().filter(|c| c.status == CheckStatus::Err).count();
+	let warn_count = checks.iter().filter(|c| c.status == CheckStatus::Warn).count();
+	hub.publish(format!("\n==== {err_count} error(s), {warn_count} warning(s) ====\n"))
+		.await;
+
+	Ok(())
+}
+
+fn run_checks(base_dir: &SPath, current_dir: &SPath) -> Vec<DoctorCheck> {
+	let mut checks = check_base_dir(base_dir);
+	checks.push(check_wks(current_dir));
+	checks.push(check_config(base_dir, current_dir));
+	checks.push(check_api_keys());
+	checks.push(check_path(base_dir));
+	checks.extend(check_legacy_dirs(&home_dir(), current_dir));
+	checks.push(check_terminal());
+	checks
+}
+
+// region:    --- Checks
+
+fn check_base_dir(base_dir: &SPath) -> Vec<DoctorCheck> {
+	let name = "Base dir";
+	if !base_dir.exists() {
+		return vec![DoctorCheck::err(
+			name,
+			format!("'{base_dir}' does not exist"),
+			"Run 'aip init-base' (or 'aip init' in your project dir)",
+		)];
+	}
+
+	let mut issues = Vec::new();
+
+	// -- Version
+	let version_path = base_dir.join("version.txt");
+	let base_version = read_to_string(&version_path)
+		.ok()
+		.and_then(|content| content.lines().next().map(|line| line.trim().to_string()));
+	match base_version.as_deref() {
+		Some(version) if version == crate::VERSION => (),
+		Some(version) => issues.push(format!("version.txt is '{version}' (aip is '{}')", crate::VERSION)),
+		None => issues.push("version.txt is missing".to_string()),
+	}
+
+	// -- Config default
+	let config_default_path = base_dir.join(CONFIG_BASE_DEFAULT_FILE_NAME);
+	if let Err(err) = check_toml_file(&config_default_path) {
+		issues.push(err);
+	}
+
+	// -- Core pack
+	if !base_dir.join("pack/installed/core").exists() {
+		issues.push("pack/installed/core is missing".to_string());
+	}
+
+	if issues.is_empty() {
+		vec![DoctorCheck::ok(name, format!("'{base_dir}'"))]
+	} else {
+		vec![DoctorCheck::err(
+			name,
+			format!("'{base_dir}' - {}", issues.join(", ")),
+			"Run 'aip init-base' to restore the base files (your config-user.toml is kept)",
+		)]
+	}
+}
+
+fn check_wks(current_dir: &SPath) -> DoctorCheck {
+	let name = "Workspace";
+	match find_wks_dir(current_dir.clone()) {
+		Ok(Some(wks_dir)) => DoctorCheck::ok(name, format!("'{}'", wks_dir.join(AIPACK_DIR_NAME))),
+		Ok(None) => DoctorCheck::warn(
+			name,
+			format!("No '{AIPACK_DIR_NAME}/' in '{current_dir}' or its parents"),
+			"Run 'aip init' in your project root dir (the current dir is used as the workspace otherwise)",
+		),
+		Err(err) => DoctorCheck::err(name, err.to_string(), "Check the current dir permissions"),
+	}
+}
+
+fn check_config(base_dir: &SPath, current_dir: &SPath) -> DoctorCheck {
+	let name = "Config";
+	if !base_dir.exists() {
+		return DoctorCheck::warn(name, "Not checked (no base dir)", "Fix the base dir first");
+	}
+	let wks_dir = match find_wks_dir(current_dir.clone()) {
+		Ok(Some(wks_dir)) => wks_dir,
+		_ => return DoctorCheck::ok(name, "No workspace config (base config only)"),
+	};
+	let res = AipackPaths::from_wks_dir(&wks_dir).and_then(|paths| {
+		// NOTE: The workspace config.toml is optional, but must be valid toml when present
+		let wks_config_path = wks_dir.join(AIPACK_DIR_NAME).join(CONFIG_FILE_NAME);
+		if wks_config_path.exists() {
+			check_toml_file(&wks_config_path).map_err(Error::custom)?;
+		}
+		crate::dir_context::AipackConfig::load(&paths)
+	});
+	match res {
+		Ok(_) => DoctorCheck::ok(name, "Base and workspace configs are valid"),
+		Err(err) => DoctorCheck::err(
+			name,
+			err.to_string(),
+			"Fix the reported config file (see ~/.aipack-base/config-default.toml for the documented properties)",
+		),
+	}
+}
+
+fn check_api_keys() -> DoctorCheck {
+	let name = "API keys";
+	let available_keys = get_available_api_keys();
+	if available_keys.is_empty() {
+		DoctorCheck::warn(
+			name,
+			format!("None of the {} known API key variables are set", KEY_ENV_VARS.len()),
+			crate::support::os::get_set_api_key_message(),
+		)
+	} else {
+		let mut keys = available_keys.into_iter().collect::<Vec<_>>();
+		keys.sort();
+		DoctorCheck::ok(name, keys.join(", "))
+	}
+}
+
+fn check_path(base_dir: &SPath) -> DoctorCheck {
+	let name = "PATH";
+	let bin_dir = base_dir.join("bin");
+	let current_exe = std::env::current_exe()
+		.map(|exe| exe.to_string_lossy().to_string())
+		.unwrap_or_default();
+
+	if support::has_aip_in_path() {
+		DoctorCheck::ok(name, format!("'{bin_dir}' is in PATH (running '{current_exe}')"))
+	} else {
+		DoctorCheck::warn(
+			name,
+			format!("'{bin_dir}' is not in PATH (running '{current_exe}')"),
+			"Run 'aip self setup' to install aip in ~/.aipack-base/bin and set the PATH",
+		)
+	}
+}
+
+/// The legacy `devai` dirs (before the aipack rename), which are not used anymore.
+fn check_legacy_dirs(home_dir: &SPath, current_dir: &SPath) -> Vec<DoctorCheck> {
+	let name = "Legacy devai";
+	let candidates = [
+		home_dir.join(".devai"),
+		home_dir.join(".devai-base"),
+		current_dir.join(".devai"),
+	];
+	let found = candidates.into_iter().filter(|dir| dir.is_dir()).collect::<Vec<_>>();
+
+	if found.is_empty() {
+		return vec![DoctorCheck::ok(name, "No legacy devai dirs")];
+	}
+
+	found
+		.into_iter()
+		.map(|dir| {
+			DoctorCheck::warn(
+				name,
+				format!("'{dir}' found (not used by aipack)"),
+				"Move your custom agents to '.aipack/pack/custom/' (or '~/.aipack-base/pack/custom/'), then delete this dir",
+			)
+		})
+		.collect()
+}
+
+fn check_terminal() -> DoctorCheck {
+	let name = "Terminal";
+	let is_tty = std::io::stdout().is_terminal() && std::io::stdin().is_terminal();
+	let term = std::env::var("TERM").unwrap_or_default();
+	let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+	let size = crossterm::terminal::size().ok();
+
+	let size_str = size.map(|(w, h)| format!("{w}x{h}")).unwrap_or_else(|| "unknown".to_string());
+	let detail = format!("tty: {is_tty}, TERM: '{term}', COLORTERM: '{colorterm}', size: {size_str}");
+
+	if !is_tty {
+		DoctorCheck::warn(
+			name,
+			detail,
+			"Not an interactive terminal, the TUI is not available (use 'aip run ... --single-shot' in scripts)",
+		)
+	} else if term == "dumb" {
+		DoctorCheck::warn(
+			name,
+			detail,
+			"TERM=dumb does not support the TUI, use a terminal like xterm-256color",
+		)
+	} else if size.is_some_and(|(w, h)| w < 80 || h < 20) {
+		DoctorCheck::warn(name, detail, "The terminal is small, the TUI needs at least 80x20")
+	} else {
+		DoctorCheck::ok(name, detail)
+	}
+}
+
+// endregion: --- Checks
+
+// region:    --- Support
+
+impl DoctorCheck {
+	fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+		Self {
+			name,
+			status: CheckStatus::Ok,
+			detail: detail.into(),
+			fix: None,
+		}
+	}
+
+	fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+		Self {
+			name,
+			status: CheckStatus::Warn,
+			detail: detail.into(),
+			fix: Some(fix.into()),
+		}
+	}
+
+	fn err(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+		Self {
+			name,
+			status: CheckStatus::Err,
+			detail: detail.into(),
+			fix: Some(fix.into()),
+		}
+	}
+}
+
+/// Returns the error message if the file is missing or not valid toml
+fn check_toml_file(path: &SPath) -> core::result::Result<(), String> {
+	let content = read_to_string(path).map_err(|_| format!("'{path}' is missing"))?;
+	parse_toml_into_json(&content).map_err(|err| format!("'{path}' is not valid toml ({err})"))?;
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_xelf_doctor_check_base_and_legacy_dirs() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = SPath::new("tests-data/sandbox-01/.tmp/test_xelf_doctor");
+		simple_fs::ensure_dir(fx_dir.join(".devai"))?;
+
+		// -- Exec
+		let base_checks = check_base_dir(&fx_dir.join("no-base-dir"));
+		let legacy_checks = check_legacy_dirs(&fx_dir.join("no-home"), &fx_dir);
+		let no_legacy_checks = check_legacy_dirs(&fx_dir.join("no-home"), &fx_dir.join("no-wks"));
+
+		// -- Check
+		assert_eq!(base_checks.len(), 1);
+		assert_eq!(base_checks[0].status, CheckStatus::Err);
+		assert!(base_checks[0].fix.as_deref().is_some_and(|fix| fix.contains("aip init-base")));
+		assert_eq!(legacy_checks.len(), 1);
+		assert_eq!(legacy_checks[0].status, CheckStatus::Warn);
+		assert!(legacy_checks[0].detail.contains(".devai"));
+		assert_eq!(no_legacy_checks[0].status, CheckStatus::Ok);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
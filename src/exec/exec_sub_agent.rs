@@ -1,5 +1,5 @@
 use crate::agent::find_agent;
-use crate::run::{RunBaseOptions, RunSubAgentParams, run_agent};
+use crate::run::{RunBaseOptions, RunParent, RunSubAgentParams, run_agent};
 use crate::types::RunAgentResponse;
 use crate::{Error, Result};
 
@@ -8,10 +8,12 @@ pub async fn exec_run_sub_agent(params: RunSubAgentParams) -> Result<()> {
 	let RunSubAgentParams {
 		runtime,
 		parent_uid,
+		parent_task_uid,
 		agent_dir,
 		agent_name,
 		inputs,
 		agent_options,
+		parent_capabilities,
 		response_shot,
 	} = params;
 
@@ -32,9 +34,14 @@ pub async fn exec_run_sub_agent(params: RunSubAgentParams) -> Result<()> {
 
 		// -- Build the environment
 		// NOTE: For now, do not inherit the parent run, But eventually mgith be past in the RunAgentParams
-		let run_base_options = RunBaseOptions::default();
+		// NOTE: The sub agent run cannot do more than the caller pack (see `PackCapabilities::with_parent`)
+		let run_base_options = RunBaseOptions::default().with_parent_capabilities(parent_capabilities.map(|c| *c));
 
-		let res = run_agent(&runtime, Some(parent_uid), agent, inputs, &run_base_options, true)
+		let parent = RunParent {
+			run_uid: parent_uid,
+			task_uid: parent_task_uid,
+		};
+		let res = run_agent(&runtime, Some(parent), agent, inputs, &run_base_options, true)
 			.await
 			.map_err(|e| Error::custom(format!("Failed to run agent '{agent_name}': {e}")))?;
 		Ok(res)
@@ -2,29 +2,40 @@
 //! Will create it's own queue and listen to ExecCommand events.
 
 use crate::agent::find_agent;
-use crate::event::{CancelTrx, new_cancel_trx};
+use crate::dir_context::AipackPaths;
 use crate::exec::event_action::ExecActionEvent;
-use crate::exec::exec_cmd_xelf::exec_xelf_update;
-use crate::exec::exec_sub_agent::exec_run_sub_agent;
+use crate::exec::exec_cmd_xelf::{exec_xelf_doctor, exec_xelf_update};
 use crate::exec::init::{init_base, init_base_and_dir_context, init_wks};
 use crate::exec::{
 	ExecStatusEvent,
+	ScheduledRun,
 	exec_check_keys,
 	exec_create_gitignore,
+	exec_info,
 	exec_install,
+	exec_install_locked,
+	exec_lint_lua,
 	exec_list,
+	exec_mcp_serve,
 	exec_new,
 	exec_pack,
-	exec_run,
-	exec_run_redo,
+	exec_replay_events,
+	exec_schedule,
+	exec_serve,
+	exec_uninstall,
 	exec_unpack,
+	exec_worker,
 	exec_xelf_setup, // Added import
+	record_schedule_run,
 };
 use crate::hub::{HubEvent, get_hub};
 use crate::model::{
 	EndState, ErrBmc, ErrForCreate, InstallData, OnceModelManager, WorkBmc, WorkForCreate, WorkForUpdate, WorkKind,
 };
-use crate::run::{RunQueueExecutor, RunQueueTx, RunRedoCtx};
+use crate::run::{
+	ChatTurn, RunCtrl, RunQueueAction, RunQueueExecutor, RunQueueMessage, RunQueueTx, RunRedoCtx, RunRedoJob,
+	RunTopAgentJob, TaskRedo, WorkerPool, export_tasks_outputs,
+};
 use crate::runtime::Runtime;
 use crate::support::editor;
 use crate::support::time::now_micro;
@@ -68,11 +79,15 @@ pub struct Executor {
 	/// Used to send StartExec and EndExec events only when needed
 	active_actions: Arc<AtomicUsize>,
 
-	cancel_trx: Option<CancelTrx>,
+	/// The run controls (cancel, pause) of the RunQueueExecutor, given to the runs Runtime
+	run_ctrl: RunCtrl,
 
-	/// NOT USED YET
-	#[allow(unused)]
+	/// The RunQueueExecutor sender (all runs, and their controls, go through it)
 	run_queue_tx: RunQueueTx,
+
+	/// The workers of the distributed mode (started on the first `aip run ... --workers-listen <addr>`,
+	/// and kept for the next runs, so that the workers stay joined)
+	worker_pool: Arc<Mutex<Option<WorkerPool>>>,
 }
 
 /// Contructor
@@ -80,18 +95,18 @@ impl Executor {
 	pub fn new(once_mm: OnceModelManager) -> Self {
 		let (tx, rx) = flume::unbounded();
 		let run_executor = RunQueueExecutor::new();
+		let run_ctrl = run_executor.run_ctrl().clone();
 		let run_queue_tx = run_executor.start();
 
-		let cancel_trx = new_cancel_trx("cancel_run");
-
 		Executor {
 			once_mm,
 			action_rx: rx,
 			action_sender: ExecutorTx::new(tx),
 			current_redo_ctx: Default::default(),
 			active_actions: Arc::new(AtomicUsize::new(0)),
-			cancel_trx: Some(cancel_trx),
+			run_ctrl,
 			run_queue_tx,
+			worker_pool: Default::default(),
 		}
 	}
 }
@@ -114,6 +129,27 @@ impl Executor {
 		*guard = Some(redo_ctx);
 	}
 
+	/// Returns the worker pool, started on the first call (None when `workers_listen` is None)
+	async fn get_or_start_worker_pool(&self, workers_listen: Option<&str>) -> Result<Option<WorkerPool>> {
+		let Some(workers_listen) = workers_listen else {
+			return Ok(None);
+		};
+		let mut guard = self.worker_pool.lock().await;
+		if let Some(worker_pool) = guard.as_ref() {
+			return Ok(Some(worker_pool.clone()));
+		}
+		let worker_pool = WorkerPool::start(workers_listen).await?;
+		get_hub()
+			.publish(format!(
+				"Listening for workers on '{}' (join with `aip worker --join <host>:{}`)",
+				worker_pool.addr(),
+				worker_pool.addr().port()
+			))
+			.await;
+		*guard = Some(worker_pool.clone());
+		Ok(Some(worker_pool))
+	}
+
 	async fn take_current_redo_ctx(&self) -> Option<RunRedoCtx> {
 		let mut guard = self.current_redo_ctx.lock().await;
 		guard.take()
@@ -209,7 +245,7 @@ impl Executor {
 			ExecActionEvent::CmdNew(new_args) => {
 				if let Err(err) = exec_new(new_args, init_wks(None, false).await?).await {
 					if matches!(err, Error::UserInterrupted) {
-						hub.publish(HubEvent::InfoShort("New agent creation cancelled by user".into()))
+						hub.publish(HubEvent::InfoShort("New pack creation cancelled by user".into()))
 							.await;
 						hub.publish(HubEvent::Quit).await;
 					} else {
@@ -223,10 +259,23 @@ impl Executor {
 				exec_list(init_base_and_dir_context(false).await?, list_args).await?
 			}
 
+			ExecActionEvent::CmdInfo(info_args) => {
+				exec_info(init_base_and_dir_context(false).await?, info_args).await?
+			}
+
 			ExecActionEvent::CmdPack(pack_args) => exec_pack(&pack_args).await?,
 
 			ExecActionEvent::CmdInstall(install_args) => {
-				let _ = exec_install(init_base_and_dir_context(false).await?, install_args).await?;
+				let dir_context = init_base_and_dir_context(false).await?;
+				if install_args.locked {
+					exec_install_locked(dir_context, install_args).await?;
+				} else {
+					let _ = exec_install(dir_context, install_args).await?;
+				}
+			}
+
+			ExecActionEvent::CmdUninstall(uninstall_args) => {
+				exec_uninstall(init_base_and_dir_context(false).await?, uninstall_args).await?;
 			}
 
 			ExecActionEvent::CmdUnpack(unpack_args) => {
@@ -238,6 +287,20 @@ impl Executor {
 				exec_check_keys(args).await?;
 			}
 
+			ExecActionEvent::CmdLintLua(args) => {
+				init_base(false).await?;
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				// NOTE: The runtime is for the Lua engine registry (the `aip` modules and globals)
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, Some(self.run_ctrl.clone()), None).await?;
+				exec_lint_lua(args, runtime).await?;
+			}
+
+			ExecActionEvent::CmdReplayEvents(args) => {
+				// NOTE: No workspace required, the replay fills the (fresh) runtime db from the record
+				exec_replay_events(args, self.once_mm.get().await?).await?;
+			}
+
 			ExecActionEvent::CmdCreateGitignore(args) => {
 				exec_create_gitignore(args).await?;
 			}
@@ -252,6 +315,11 @@ impl Executor {
 				exec_xelf_update(args).await?;
 			}
 
+			ExecActionEvent::CmdXelfDoctor(args) => {
+				// Does not require dir_context or runtime (and does not init anything)
+				exec_xelf_doctor(args).await?;
+			}
+
 			ExecActionEvent::OpenAgent => {
 				//
 				if let Some(agent_file_path) = self.get_agent_file_path().await
@@ -276,13 +344,16 @@ impl Executor {
 				let exec_sender = self.sender();
 				let mm = self.once_mm.get().await?;
 
+				let worker_pool = self.get_or_start_worker_pool(run_args.workers_listen.as_deref()).await?;
+
 				// -- Attempt to find agent early to detect missing packs
 				let agent_name = run_args.cmd_agent_name.clone();
 				let runtime = Runtime::new(
 					dir_ctx.clone(),
 					exec_sender.clone(),
 					mm.clone(),
-					self.cancel_trx.clone(),
+					Some(self.run_ctrl.clone()),
+					worker_pool,
 				)
 				.await?;
 
@@ -290,7 +361,9 @@ impl Executor {
 
 				match agent_res {
 					Ok(_agent) => {
-						let (redo_ctx, redo_requested) = exec_run(run_args, runtime).await?;
+						let (job, response_rx) = RunTopAgentJob::new_and_rx(run_args, runtime);
+						self.send_run_queue_and_wait(job).await?;
+						let (redo_ctx, redo_requested) = response_rx.recv().await??;
 						self.set_current_redo_ctx(redo_ctx).await;
 
 						if redo_requested {
@@ -343,9 +416,12 @@ impl Executor {
 						redo_ctx.run_options().with_flow_redo_count(flow_redo_count),
 						redo_ctx.redo_requested(),
 						flow_redo_count,
+						redo_ctx.run_redo_data().cloned(),
 					);
 					// if sucessful, we recapture the redo_ctx to have the latest agent.
-					if let Some(redo_ctx) = exec_run_redo(&redo_ctx).await {
+					let (job, response_rx) = RunRedoJob::new_and_rx(redo_ctx.clone());
+					self.send_run_queue_and_wait(job).await?;
+					if let Some(redo_ctx) = response_rx.recv().await? {
 						let redo_requested = redo_ctx.redo_requested();
 						let next_redo_ctx = if redo_requested {
 							let next_flow_redo_count = redo_ctx.flow_redo_count();
@@ -356,6 +432,7 @@ impl Executor {
 								run_options,
 								redo_requested,
 								next_flow_redo_count,
+								redo_ctx.run_redo_data().cloned(),
 							)
 						} else {
 							redo_ctx
@@ -376,16 +453,177 @@ impl Executor {
 				hub.publish(ExecStatusEvent::RunEnd).await;
 			}
 
+			ExecActionEvent::RedoTasks { task_idxs, instruction } => {
+				if let Some(redo_ctx) = self.take_current_redo_ctx().await {
+					hub.publish(ExecStatusEvent::RunStart).await;
+					// NOTE: The before all output and inputs of the last run are reused (when it had tasks)
+					let task_redo =
+						TaskRedo::new(task_idxs, instruction).with_redo_of(redo_ctx.run_redo_data().cloned());
+					let task_redo_ctx = RunRedoCtx::new(
+						redo_ctx.runtime().clone(),
+						redo_ctx.agent().clone(),
+						redo_ctx.run_options().with_flow_redo_count(0).with_task_redo(Some(task_redo)),
+						false,
+						0,
+						None,
+					);
+					let (job, response_rx) = RunRedoJob::new_and_rx(task_redo_ctx);
+					self.send_run_queue_and_wait(job).await?;
+					// NOTE: Keep the full run redo ctx (with the eventual reloaded agent),
+					//       so that a next `r` still replays the whole run.
+					let next_redo_ctx = match response_rx.recv().await? {
+						Some(task_redo_ctx) => RunRedoCtx::new(
+							redo_ctx.runtime().clone(),
+							task_redo_ctx.agent().clone(),
+							redo_ctx.run_options().clone(),
+							redo_ctx.redo_requested(),
+							redo_ctx.flow_redo_count(),
+							task_redo_ctx.run_redo_data().cloned(),
+						),
+						None => redo_ctx,
+					};
+					self.set_current_redo_ctx(next_redo_ctx).await;
+				} else {
+					hub.publish(HubEvent::InfoShort("Agent currently running, wait until done.".into()))
+						.await;
+				}
+				hub.publish(ExecStatusEvent::RunEnd).await;
+			}
+
+			ExecActionEvent::ChatFollowUp(message) => {
+				if let Some(redo_ctx) = self.take_current_redo_ctx().await {
+					hub.publish(ExecStatusEvent::RunStart).await;
+					// NOTE: The before all output and the conversation of the last run are continued (when it had tasks)
+					let chat_turn = ChatTurn::new(message, redo_ctx.run_redo_data().cloned());
+					let chat_turn_ctx = RunRedoCtx::new(
+						redo_ctx.runtime().clone(),
+						redo_ctx.agent().clone(),
+						redo_ctx.run_options().with_flow_redo_count(0).with_chat_turn(Some(chat_turn)),
+						false,
+						0,
+						None,
+					);
+					let (job, response_rx) = RunRedoJob::new_and_rx(chat_turn_ctx);
+					self.send_run_queue_and_wait(job).await?;
+					// NOTE: The next follow-up continues this turn run,
+					//       and a next `r` still replays the whole run.
+					let next_redo_ctx = match response_rx.recv().await? {
+						Some(chat_turn_ctx) => RunRedoCtx::new(
+							redo_ctx.runtime().clone(),
+							chat_turn_ctx.agent().clone(),
+							redo_ctx.run_options().clone(),
+							redo_ctx.redo_requested(),
+							redo_ctx.flow_redo_count(),
+							chat_turn_ctx.run_redo_data().cloned(),
+						),
+						None => redo_ctx,
+					};
+					self.set_current_redo_ctx(next_redo_ctx).await;
+				} else {
+					hub.publish(HubEvent::InfoShort("Agent currently running, wait until done.".into()))
+						.await;
+				}
+				hub.publish(ExecStatusEvent::RunEnd).await;
+			}
+
 			ExecActionEvent::RunSubAgent(run_agent_params) => {
-				if let Err(err) = exec_run_sub_agent(run_agent_params).await {
-					hub.publish(Error::cc("Fail to run agent", err)).await;
+				// NOTE: The RunQueueExecutor runs each sub agent in its own task,
+				//       we wait for the done signal to keep the active actions count accurate.
+				self.send_run_queue_and_wait(run_agent_params).await?;
+			}
+
+			ExecActionEvent::CancelTasks(task_ids) => {
+				self.run_ctrl.cancel_tasks(&task_ids);
+				hub.publish(format!("-> {} task(s) canceled", task_ids.len())).await;
+			}
+
+			ExecActionEvent::ExportTasksOutputs(task_ids) => {
+				let mm = self.once_mm.get().await?;
+				let export_file = AipackPaths::new()?
+					.task_exports_dir()
+					.ok_or("No workspace `.aipack/` for the tasks outputs export")?
+					.join(format!("tasks-outputs-{}.md", now_micro()));
+				let count = export_tasks_outputs(&mm, &task_ids, &export_file)?;
+				hub.publish(format!("-> {count} task output(s) exported to '{export_file}'"))
+					.await;
+			}
+
+			ExecActionEvent::CancelRun => self.send_run_queue_and_wait(RunQueueAction::Cancel).await?,
+
+			ExecActionEvent::PauseRun => self.send_run_queue_and_wait(RunQueueAction::Pause).await?,
+
+			ExecActionEvent::ResumeRun => self.send_run_queue_and_wait(RunQueueAction::Resume).await?,
+
+			ExecActionEvent::CmdRunCtrl(request) => {
+				// NOTE: The run is owned by another process, which picks up the request file.
+				let dir_ctx = init_wks(None, false).await?;
+				let file = request.write(&dir_ctx)?;
+				let cmd: &'static str = request.cmd.into();
+				hub.publish(format!(
+					"Run '{}' - {cmd} requested (picked up by the aip process running it).
+   ({file})",
+					request.run_ref
+				))
+				.await;
+			}
+
+			ExecActionEvent::CmdWorker(args) => {
+				init_base(false).await?;
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				// NOTE: The worker runs the tasks as its own runs (with the run controls of this process)
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, Some(self.run_ctrl.clone()), None).await?;
+				exec_worker(args, runtime).await?;
+			}
+
+			ExecActionEvent::CmdSchedule(args) => {
+				if args.daemon {
+					init_base(false).await?;
 				}
+				exec_schedule(args, self.sender()).await?;
 			}
 
-			ExecActionEvent::CancelRun => {
-				if let Some(tx) = self.cancel_trx.as_ref().map(|trx| trx.tx()) {
-					tx.cancel();
+			ExecActionEvent::CmdServe(args) => {
+				init_base(false).await?;
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				exec_serve(args, dir_ctx, mm, self.sender()).await?;
+			}
+
+			ExecActionEvent::CmdMcpServe(args) => {
+				init_base(false).await?;
+				let dir_ctx = init_wks(None, false).await?;
+				let mm = self.once_mm.get().await?;
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, Some(self.run_ctrl.clone()), None).await?;
+				exec_mcp_serve(args, runtime).await?;
+			}
+
+			ExecActionEvent::ScheduledRun(ScheduledRun {
+				schedule_id,
+				wks_dir,
+				run_args,
+			}) => {
+				hub.publish(ExecStatusEvent::RunStart).await;
+				let dir_ctx = init_wks(Some(wks_dir.as_str()), false).await?;
+				let mm = self.once_mm.get().await?;
+				let runtime = Runtime::new(dir_ctx, self.sender(), mm, Some(self.run_ctrl.clone()), None).await?;
+
+				let (job, response_rx) = RunTopAgentJob::new_and_rx(run_args, runtime);
+				self.send_run_queue_and_wait(job).await?;
+				let (run_id, res) = match response_rx.recv().await? {
+					Ok((redo_ctx, _)) => (redo_ctx.run_redo_data().map(|d| d.run_id), Ok(())),
+					Err(err) => (None, Err(err)),
+				};
+				// NOTE: The run error is recorded in the schedule, the daemon keeps running
+				if let Err(err) = res.as_ref() {
+					hub.publish(Error::cc(
+						format!("Schedule '{schedule_id}' run failed"),
+						err.to_string(),
+					))
+					.await;
 				}
+				record_schedule_run(schedule_id, run_id, &res)?;
+				hub.publish(ExecStatusEvent::RunEnd).await;
 			}
 
 			ExecActionEvent::WorkConfirm(id) => {
@@ -409,8 +647,12 @@ impl Executor {
 					let install_res = exec_install(
 						dir_ctx,
 						crate::exec::cli::InstallArgs {
-							aipack_ref: pack_ref.clone(),
+							aipack_ref: Some(pack_ref.clone()),
 							force: true,
+							alias: None,
+							// NOTE: The user confirmed the install in the TUI
+							yes: true,
+							locked: false,
 						},
 					)
 					.await;
@@ -476,6 +718,14 @@ impl Executor {
 		Ok(())
 	}
 
+	/// Send the action to the RunQueueExecutor and wait until it is done.
+	async fn send_run_queue_and_wait(&self, action: impl Into<RunQueueAction>) -> Result<()> {
+		let (msg, done_rx) = RunQueueMessage::new_and_rx(action);
+		self.run_queue_tx.send(msg).await?;
+		done_rx.recv().await?;
+		Ok(())
+	}
+
 	async fn send_redo_with_delay(&self) {
 		sleep(Duration::from_millis(500)).await;
 		self.sender().send(ExecActionEvent::Redo).await;
@@ -27,6 +27,11 @@ pub fn extract_template_pack_toml_zfile() -> Result<ZFile> {
 	assets::extract_template_zfile("pack.toml")
 }
 
+/// The file paths (e.g., `pack-basic/main.aip`) of the `_template/pack-<template_name>/` pack template
+pub fn list_template_pack_file_paths(template_name: &str) -> Result<Vec<String>> {
+	assets::list_file_paths_start_with("_template", &format!("pack-{template_name}/"))
+}
+
 // endregion: --- Template ZFiles
 
 // region:    --- Base ZFiles
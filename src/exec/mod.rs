@@ -7,12 +7,20 @@ mod event_action;
 mod event_status;
 mod exec_cmd_check_keys;
 mod exec_cmd_create_gitignore;
+mod exec_cmd_info;
 mod exec_cmd_install;
+mod exec_cmd_lint_lua;
 mod exec_cmd_list;
+mod exec_cmd_mcp_serve;
 mod exec_cmd_new;
 mod exec_cmd_pack;
+mod exec_cmd_replay_events;
 mod exec_cmd_run;
+mod exec_cmd_schedule;
+mod exec_cmd_serve;
+mod exec_cmd_uninstall;
 mod exec_cmd_unpack;
+mod exec_cmd_worker;
 mod exec_cmd_xelf;
 mod exec_sub_agent;
 mod executor;
@@ -21,15 +29,22 @@ pub use event_action::*;
 pub use event_status::*;
 use exec_cmd_check_keys::*;
 use exec_cmd_create_gitignore::*;
+pub use exec_cmd_info::*;
 use exec_cmd_install::*;
-use exec_cmd_list::*;
+use exec_cmd_lint_lua::*;
+pub use exec_cmd_list::*;
+use exec_cmd_mcp_serve::*;
 use exec_cmd_new::*;
 use exec_cmd_pack::*;
-use exec_cmd_run::*;
+use exec_cmd_replay_events::*;
+pub use exec_cmd_run::*;
+pub use exec_cmd_schedule::*;
+use exec_cmd_serve::*;
+use exec_cmd_uninstall::*;
 use exec_cmd_unpack::*;
+use exec_cmd_worker::*;
 use exec_cmd_xelf::*;
-#[allow(unused)]
-use exec_sub_agent::*;
+pub use exec_sub_agent::*;
 pub use executor::*;
 
 pub mod cli;
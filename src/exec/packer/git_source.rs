@@ -0,0 +1,136 @@
+//! Install from a git repository (`aip install https://github.com/org/repo.git#branch`).
+//!
+//! The repo is cloned in the pack download dir, its pack dir (the one with the pack.toml) is packed as a local pack
+//! (with its `[build]` steps), and the commit hash is returned to be recorded as the installed version.
+
+use crate::dir_context::DirContext;
+use crate::exec::packer::packer_impl::pack_dir;
+use crate::exec::packer::support::PackUri;
+use crate::support::files::{DeleteCheck, safer_trash_dir};
+use crate::support::text::blake3_b64u;
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// Max depth of the pack.toml lookup in the cloned repo (e.g., `packs/my-pack/pack.toml`)
+const PACK_TOML_MAX_DEPTH: usize = 4;
+
+/// Clone the git repo, pack its pack dir, and return the `.aipack` file (in the download dir) and the commit hash.
+pub(super) async fn build_from_git_repo(dir_context: &DirContext, pack_uri: &PackUri) -> Result<(SPath, String)> {
+	let PackUri::GitRepo { url, git_ref } = pack_uri else {
+		return Err(Error::custom(
+			"Expected GitRepo variant but got a different one".to_string(),
+		));
+	};
+	let fail = |cause: String| Error::FailToInstall {
+		aipack_ref: pack_uri.to_string(),
+		cause,
+	};
+
+	let download_dir = dir_context.aipack_paths().get_base_pack_download_dir()?;
+	ensure_dir(&download_dir)?;
+	let clone_dir = download_dir.join(format!(
+		"git-{}",
+		blake3_b64u(&[url, git_ref.as_deref().unwrap_or_default()])
+	));
+	if clone_dir.exists() {
+		safer_trash_dir(&clone_dir, Some(DeleteCheck::CONTAINS_AIPACK_BASE))?;
+	}
+
+	let res = async {
+		clone_repo(url, git_ref.as_deref(), &clone_dir).map_err(fail)?;
+		let commit = run_git(&["rev-parse", "HEAD"], Some(&clone_dir)).map_err(fail)?;
+		let pack_dir_path = find_pack_dir(&clone_dir).map_err(fail)?;
+		let pack_data = pack_dir(&pack_dir_path, &download_dir).await?;
+		Ok((pack_data.pack_file, commit))
+	}
+	.await;
+
+	safer_trash_dir(&clone_dir, Some(DeleteCheck::CONTAINS_AIPACK_BASE))?;
+
+	res
+}
+
+// region:    --- Support
+
+/// Shallow clone of the branch or tag, and full clone with checkout for the other refs (e.g., a commit hash).
+fn clone_repo(url: &str, git_ref: Option<&str>, clone_dir: &SPath) -> core::result::Result<(), String> {
+	let dir = clone_dir.as_str();
+	let Some(git_ref) = git_ref else {
+		return run_git(&["clone", "--quiet", "--depth", "1", url, dir], None).map(|_| ());
+	};
+
+	if run_git(
+		&["clone", "--quiet", "--depth", "1", "--branch", git_ref, url, dir],
+		None,
+	)
+	.is_ok()
+	{
+		return Ok(());
+	}
+	if clone_dir.exists() {
+		std::fs::remove_dir_all(clone_dir.as_std_path()).map_err(|err| err.to_string())?;
+	}
+	run_git(&["clone", "--quiet", url, dir], None)?;
+	run_git(&["checkout", "--quiet", git_ref], Some(clone_dir))
+		.map_err(|err| format!("git ref '{git_ref}' not found in the repo. {err}"))?;
+
+	Ok(())
+}
+
+/// Run the git command, and return its trimmed stdout (or the stderr as the error).
+fn run_git(args: &[&str], current_dir: Option<&SPath>) -> core::result::Result<String, String> {
+	let mut command = Command::new("git");
+	command.args(args).env("GIT_TERMINAL_PROMPT", "0");
+	if let Some(current_dir) = current_dir {
+		command.current_dir(current_dir.as_std_path());
+	}
+	let output = command
+		.output()
+		.map_err(|err| format!("Cannot execute 'git' (is git installed?). Cause: {err}"))?;
+
+	if output.status.success() {
+		Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+	} else {
+		Err(format!(
+			"'git {}' failed: {}",
+			args.first().unwrap_or(&""),
+			String::from_utf8_lossy(&output.stderr).trim()
+		))
+	}
+}
+
+/// The dir of the pack.toml at the repo root, or of the only pack.toml in the repo.
+fn find_pack_dir(repo_dir: &SPath) -> core::result::Result<SPath, String> {
+	if repo_dir.join("pack.toml").exists() {
+		return Ok(repo_dir.clone());
+	}
+
+	let pack_dirs: Vec<SPath> = WalkDir::new(repo_dir.as_std_path())
+		.max_depth(PACK_TOML_MAX_DEPTH)
+		.into_iter()
+		.filter_entry(|entry| entry.file_name() != ".git")
+		.filter_map(|entry| entry.ok())
+		.filter(|entry| entry.file_type().is_file() && entry.file_name() == "pack.toml")
+		.filter_map(|entry| entry.path().parent().map(|dir| dir.to_path_buf()))
+		.filter_map(|dir| SPath::from_std_path_buf(dir).ok())
+		.collect();
+
+	match pack_dirs.as_slice() {
+		[pack_dir] => Ok(pack_dir.clone()),
+		[] => Err("No pack.toml found in the git repo".to_string()),
+		dirs => {
+			let dirs: Vec<String> = dirs
+				.iter()
+				.map(|dir| dir.diff(repo_dir).map(|d| d.to_string()).unwrap_or_else(|| dir.to_string()))
+				.collect();
+			Err(format!(
+				"The git repo has several pack.toml ({}), only the repos with one pack are supported",
+				dirs.join(", ")
+			))
+		}
+	}
+}
+
+// endregion: --- Support
@@ -0,0 +1,219 @@
+//! The install info of an installed pack (`.aipack-install.toml` in the installed pack dir),
+//! to know where it was installed from, and detect the install conflicts (same pack from another source).
+
+use crate::dir_context::{DirContext, RepoKind};
+use crate::exec::packer::support::PackUri;
+use crate::exec::support::read_pack_toml_info;
+use crate::support::files::list_dirs;
+use crate::types::PackCapability;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::SPath;
+
+pub const INSTALL_INFO_FILE: &str = ".aipack-install.toml";
+
+const REPO_SOURCE: &str = "aipack.ai";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallInfo {
+	/// The normalized source used to detect the conflicts (`aipack.ai`, `local`, or the URL scheme and host)
+	pub source: String,
+	/// The display origin (e.g., `aipack.ai repo`, `local file '...'`, `URL '...'`, `git repo '...'`)
+	pub origin: String,
+	/// The pack.toml version, or the commit hash for the packs installed from a git repo
+	pub version: String,
+	/// The `namespace@name` of the pack.toml, when installed under another name (`aip install ... --as ns@name`)
+	pub alias_of: Option<String>,
+	/// The capabilities the user consented to at install (the ones declared in the pack.toml)
+	#[serde(default)]
+	pub capabilities: Vec<PackCapability>,
+	/// The paths outside the workspace the user consented to at install (the pack.toml `paths_allow`)
+	#[serde(default)]
+	pub paths_allow: Vec<String>,
+	/// The content hash of the archive, verified when stamped by `aip pack` (None for the packs installed before the content hash)
+	pub content_hash: Option<String>,
+	/// The install uri, to install it again (e.g., `acme@coder`, the `.aipack` absolute path, `git+https://...#main`)
+	pub uri: Option<String>,
+	/// The versioned `.aipack` URL, for the aipack.ai repo packs
+	pub download_url: Option<String>,
+}
+
+/// Constructors & Persistence
+impl InstallInfo {
+	pub fn new(pack_uri: &PackUri, version: impl Into<String>, alias_of: Option<String>) -> Self {
+		let (source, origin) = match pack_uri {
+			PackUri::RepoPack(_) => (REPO_SOURCE.to_string(), format!("{REPO_SOURCE} repo")),
+			PackUri::LocalPath(_) => ("local".to_string(), pack_uri.to_string()),
+			PackUri::HttpLink(url) => (url_source(url), pack_uri.to_string()),
+			// NOTE: The branches of the same repo are the same source
+			PackUri::GitRepo { url, .. } => (format!("git+{url}"), pack_uri.to_string()),
+		};
+		Self {
+			source,
+			origin,
+			version: version.into(),
+			alias_of,
+			capabilities: Vec::new(),
+			paths_allow: Vec::new(),
+			content_hash: None,
+			uri: None,
+			download_url: None,
+		}
+	}
+
+	/// Read the install info of an installed pack dir (None if absent or invalid, e.g., installed before the install info)
+	pub fn read(installed_pack_dir: &SPath) -> Option<Self> {
+		let content = std::fs::read_to_string(installed_pack_dir.join(INSTALL_INFO_FILE).as_std_path()).ok()?;
+		toml::from_str(&content).ok()
+	}
+
+	pub fn write(&self, installed_pack_dir: &SPath) -> Result<()> {
+		let content =
+			toml::to_string(self).map_err(|err| Error::custom(format!("Cannot write install info. {err}")))?;
+		std::fs::write(installed_pack_dir.join(INSTALL_INFO_FILE).as_std_path(), content)?;
+		Ok(())
+	}
+}
+
+// region:    --- Conflicts
+
+/// A pack with the same `namespace@name` from another source than the one being installed.
+#[derive(Debug)]
+pub struct InstallConflict {
+	/// e.g., `installed from local file '...'` or `pack source - base config pack_sources`
+	pub origin: String,
+	pub version: Option<String>,
+	pub path: SPath,
+}
+
+/// Returns the packs with this `namespace@name` from another source than the `install_info` one:
+/// - The installed pack, if it was installed from another source.
+/// - The custom and pack source packs (they take precedence over the installed packs).
+pub fn find_install_conflicts(
+	dir_context: &DirContext,
+	namespace: &str,
+	name: &str,
+	install_info: &InstallInfo,
+) -> Result<Vec<InstallConflict>> {
+	let mut conflicts = Vec::new();
+
+	for repo in dir_context.aipack_paths().get_pack_repo_dirs()? {
+		if !repo.accepts_namespace(namespace) {
+			continue;
+		}
+		let Some(pack_dir) = find_repo_pack_dir(repo.path(), namespace, name) else {
+			continue;
+		};
+		let version = read_pack_toml_info(&pack_dir).version;
+
+		let origin = match repo.kind {
+			RepoKind::BaseInstalled => {
+				// NOTE: Installed before the install info, so the source is unknown (not a conflict)
+				let Some(installed_info) = InstallInfo::read(&pack_dir) else {
+					continue;
+				};
+				if installed_info.source == install_info.source {
+					continue;
+				}
+				format!("installed from {}", installed_info.origin)
+			}
+			kind => kind.to_pretty_lower(),
+		};
+
+		conflicts.push(InstallConflict {
+			origin,
+			version,
+			path: pack_dir,
+		});
+	}
+
+	Ok(conflicts)
+}
+
+/// Format the conflicts for the install error, with the pack being installed first.
+pub fn format_install_conflicts(pack_ref: &str, install_info: &InstallInfo, conflicts: &[InstallConflict]) -> String {
+	let mut msg = format!(
+		"Pack '{pack_ref}' conflicts with a pack with the same namespace/name from another source.\n\n\
+		 {:>12} v{} from {}\n",
+		"Installing:", install_info.version, install_info.origin
+	);
+	for conflict in conflicts {
+		let version = conflict
+			.version
+			.as_deref()
+			.map(|v| format!("v{v}"))
+			.unwrap_or_else(|| "(no version)".into());
+		msg.push_str(&format!(
+			"{:>12} {version} from {} ('{}')\n",
+			"Existing:", conflict.origin, conflict.path
+		));
+	}
+	msg.push_str(
+		"\nUse '--force' to install anyway, or '--as namespace@name' to install it under another namespace/name.",
+	);
+	msg
+}
+
+// endregion: --- Conflicts
+
+// region:    --- Support
+
+fn find_repo_pack_dir(repo_dir: &SPath, namespace: &str, name: &str) -> Option<SPath> {
+	let ns_dir = list_dirs(repo_dir, 1, true).into_iter().find(|dir| dir.name() == namespace)?;
+	list_dirs(&ns_dir, 1, true).into_iter().find(|dir| dir.name() == name)
+}
+
+/// `https://acme.com/packs/x.aipack` -> `https://acme.com`
+fn url_source(url: &str) -> String {
+	let (scheme, rest) = url.split_once("://").unwrap_or(("", url));
+	let host = rest.split('/').next().unwrap_or(rest);
+	format!("{scheme}://{host}")
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::runtime::Runtime;
+
+	#[test]
+	fn test_install_info_new_source() -> Result<()> {
+		// -- Exec
+		let repo = InstallInfo::new(&PackUri::parse("acme@deploy-helper"), "0.1.0", None);
+		let local = InstallInfo::new(&PackUri::parse("./acme@deploy-helper-v0.1.0.aipack"), "0.1.0", None);
+		let url = InstallInfo::new(&PackUri::parse("https://packs.acme.com/a/b.aipack"), "0.1.0", None);
+
+		// -- Check
+		assert_eq!(repo.source, "aipack.ai");
+		assert_eq!(local.source, "local");
+		assert_eq!(url.source, "https://packs.acme.com");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_install_info_find_install_conflicts_custom() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let install_info = InstallInfo::new(&PackUri::parse("ns_b@pack_b_2"), "0.2.0", None);
+
+		// -- Exec
+		let conflicts = find_install_conflicts(runtime.dir_context(), "ns_b", "pack_b_2", &install_info)?;
+
+		// -- Check
+		// NOTE: The sandbox installed `ns_b@pack_b_2` has no install info, so only the custom one conflicts
+		assert_eq!(conflicts.len(), 1);
+		assert!(conflicts[0].origin.contains("custom"));
+		let msg = format_install_conflicts("ns_b@pack_b_2", &install_info, &conflicts);
+		assert!(msg.contains("--force") && msg.contains("v0.2.0 from aipack.ai repo"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,23 +1,37 @@
 use crate::dir_context::DirContext;
+use crate::exec::packer::git_source::build_from_git_repo;
+use crate::exec::packer::install_info::{InstallInfo, find_install_conflicts, format_install_conflicts};
+use crate::exec::packer::pack_deps::resolve_dependencies;
+use crate::exec::packer::pack_lock::{LockedPack, record_installed_pack};
+use crate::exec::packer::pack_meta::verify_aipack_content_hash;
 use crate::exec::packer::pack_toml::parse_validate_pack_toml;
 use crate::exec::packer::support::PackUri;
 use crate::exec::packer::{PackToml, support};
 use crate::support::files::{DeleteCheck, safer_trash_dir, safer_trash_file};
 use crate::support::zip;
+use crate::types::PackIdentity;
 use crate::{Error, Result};
 use simple_fs::{SPath, ensure_dir};
+use std::str::FromStr;
 
 pub enum InstallResponse {
 	Installed(InstalledPack),
 	UpToDate(InstalledPack),
 }
 
+/// Asked before installing a pack which declares capabilities or paths_allow, returns true if the user consents to them.
+pub type CapabilitiesConsent<'a> = &'a (dyn Fn(&PackToml) -> Result<bool> + Sync);
+
 pub struct InstalledPack {
 	pub pack_toml: PackToml,
 	pub path: SPath,
 	#[allow(unused)]
 	pub size: usize,
 	pub zip_size: usize,
+	/// The commit hash, when installed from a git repo
+	pub git_commit: Option<String>,
+	/// The resolved dependencies (the pack.toml `[dependencies]`, recursively), empty if none
+	pub dependencies: Vec<LockedPack>,
 }
 
 /// Install a `file.aipack` into the .aipack-base/pack/installed directory
@@ -31,14 +45,97 @@ pub struct InstalledPack {
 /// - Probably need to remove the existing pack files; otherwise, some leftover files can be an issue.
 ///
 /// Returns the InstalledPack with information about the installed pack.
-pub async fn install_pack(dir_context: &DirContext, pack_uri: &str, force: bool) -> Result<InstallResponse> {
+///
+/// - With `alias` (`namespace@name`), the pack is installed under this identity (e.g., when it conflicts).
+/// - If a pack with the same `namespace@name` exists from another source (installed from elsewhere, custom, or pack source),
+///   fails with `Error::InstallFailConflict`, unless `force`.
+/// - If the pack declares capabilities or paths_allow, `consent` is asked, and the consented ones are recorded in the install info.
+/// - If the .aipack has a content hash (stamped by `aip pack`), it is verified, and recorded in the install info.
+/// - A git repo (`https://github.com/org/repo.git#branch`) is cloned, and its pack dir is packed and installed like a local pack,
+///   with the commit hash recorded as the install info version (reinstalled when the commit changes).
+/// - The pack.toml `[dependencies]` are resolved and installed recursively (see `pack_deps`).
+/// - The pack and its dependencies are recorded in the workspace `.aipack/aipack.lock` (see `pack_lock`).
+pub async fn install_pack(
+	dir_context: &DirContext,
+	pack_uri: &str,
+	force: bool,
+	alias: Option<&str>,
+	consent: CapabilitiesConsent<'_>,
+) -> Result<InstallResponse> {
+	let mut install_res = install_pack_uri(dir_context, pack_uri, force, alias, None, consent).await?;
+
+	match install_res {
+		InstallResponse::Installed(ref mut p) | InstallResponse::UpToDate(ref mut p) => {
+			p.dependencies = resolve_dependencies(dir_context, p, consent).await?;
+			record_installed_pack(dir_context, p)?;
+		}
+	}
+
+	Ok(install_res)
+}
+
+/// Install the pack of the uri, without its dependencies.
+///
+/// With `locked` (`aip install --locked`), the locked version is installed (the repo pack from its versioned URL,
+/// the git repo at its commit), and the install fails if the version, commit, or content hash does not match.
+pub(super) async fn install_pack_uri(
+	dir_context: &DirContext,
+	pack_uri: &str,
+	force: bool,
+	alias: Option<&str>,
+	locked: Option<&LockedPack>,
+	consent: CapabilitiesConsent<'_>,
+) -> Result<InstallResponse> {
+	let alias = alias.map(PackIdentity::from_str).transpose()?;
+	let mut uri = pack_uri.to_string();
 	let pack_uri = PackUri::parse(pack_uri);
 
-	// Get the aipack file path, downloading if needed
+	// Get the aipack file path, downloading (or building from the git repo) if needed
+	let mut git_commit = None;
+	let mut download_url = None;
 	let (aipack_zipped_file, pack_uri) = match pack_uri {
-		pack_uri @ PackUri::RepoPack(_) => support::download_from_repo(dir_context, pack_uri).await?,
-		pack_uri @ PackUri::LocalPath(_) => support::resolve_local_path(dir_context, pack_uri)?,
+		PackUri::RepoPack(ref pack_identity) => {
+			let url = match locked.and_then(|l| l.download_url.clone()) {
+				Some(url) => url,
+				None => support::fetch_repo_pack_url(pack_identity).await?,
+			};
+			let (aipack_file, _) = support::download_pack(dir_context, PackUri::HttpLink(url.clone())).await?;
+			download_url = Some(url);
+			(aipack_file, pack_uri)
+		}
+		pack_uri @ PackUri::LocalPath(_) => {
+			let (aipack_file, pack_uri) = support::resolve_local_path(dir_context, pack_uri)?;
+			uri = aipack_file.to_string();
+			(aipack_file, pack_uri)
+		}
 		pack_uri @ PackUri::HttpLink(_) => support::download_pack(dir_context, pack_uri).await?,
+		PackUri::GitRepo { url, git_ref } => {
+			// NOTE: The locked commit is checked out (the branch may have moved)
+			let locked_commit = locked.and_then(|l| l.commit.clone());
+			let build_uri = PackUri::GitRepo {
+				url: url.clone(),
+				git_ref: locked_commit.clone().or(git_ref.clone()),
+			};
+			let (aipack_file, commit) = build_from_git_repo(dir_context, &build_uri).await?;
+			if let Some(locked_commit) = locked_commit
+				&& !commit.starts_with(&locked_commit)
+			{
+				return Err(Error::FailToInstall {
+					aipack_ref: build_uri.to_string(),
+					cause: format!(
+						"Locked commit mismatch: the repo is at '{commit}', the pack lock has '{locked_commit}'"
+					),
+				});
+			}
+			git_commit = Some(commit);
+			(aipack_file, PackUri::GitRepo { url, git_ref })
+		}
+	};
+	let source = PackSource {
+		pack_uri: &pack_uri,
+		uri,
+		download_url,
+		git_commit: git_commit.as_deref(),
 	};
 
 	// Validate file exists and has correct extension
@@ -48,7 +145,14 @@ pub async fn install_pack(dir_context: &DirContext, pack_uri: &str, force: bool)
 	let zip_size = support::get_file_size(&aipack_zipped_file, &pack_uri.to_string())?;
 
 	// Common installation steps for both local and remote files
-	let mut install_res = install_aipack_file(dir_context, &aipack_zipped_file, &pack_uri, force)?;
+	let install_res = install_aipack_file(dir_context, &aipack_zipped_file, &source, force, alias, locked, consent);
+
+	// If the file was downloaded or built (RepoPack, HttpLink, or GitRepo), trash the temporary file
+	if !matches!(pack_uri, PackUri::LocalPath(_)) {
+		safer_trash_file(&aipack_zipped_file, Some(DeleteCheck::CONTAINS_AIPACK_BASE))?;
+	}
+
+	let mut install_res = install_res?;
 
 	match install_res {
 		InstallResponse::Installed(ref mut p) | InstallResponse::UpToDate(ref mut p) => {
@@ -56,22 +160,41 @@ pub async fn install_pack(dir_context: &DirContext, pack_uri: &str, force: bool)
 		}
 	}
 
-	// If the file was downloaded (RepoPack or HttpLink), trash the temporary file
-	if matches!(pack_uri, PackUri::RepoPack(_) | PackUri::HttpLink(_)) {
-		safer_trash_file(&aipack_zipped_file, Some(DeleteCheck::CONTAINS_AIPACK_BASE))?;
-	}
-
 	Ok(install_res)
 }
 
+/// Where the aipack file comes from (recorded in the install info)
+struct PackSource<'a> {
+	pack_uri: &'a PackUri,
+	/// The uri to install it again (the `.aipack` absolute path for the local files)
+	uri: String,
+	/// The versioned `.aipack` URL, for the repo packs
+	download_url: Option<String>,
+	/// The commit hash, when built from a git repo
+	git_commit: Option<&'a str>,
+}
+
 /// Common installation logic for both local and remote aipack files
 /// Return the InstalledPack containing pack information and installation details
+///
+/// With `git_commit` (installed from a git repo), the commit is recorded as the install info version,
+/// and the installed pack is up to date only if it was installed from the same repo and commit.
+///
+/// With `locked`, the installed pack is up to date only if it has the locked version and content hash
+/// (otherwise reinstalled, even if the installed version is above).
 fn install_aipack_file(
 	dir_context: &DirContext,
 	aipack_zipped_file: &SPath,
-	pack_uri: &PackUri,
+	source: &PackSource,
 	force: bool,
+	alias: Option<PackIdentity>,
+	locked: Option<&LockedPack>,
+	consent: CapabilitiesConsent<'_>,
 ) -> Result<InstallResponse> {
+	let PackSource {
+		pack_uri, git_commit, ..
+	} = *source;
+
 	// -- Get the aipack base pack install dir
 	// This is the pack base dir and now, we need ot add `namespace/pack_name`
 	let pack_installed_dir = dir_context.aipack_paths().get_base_pack_installed_dir()?;
@@ -91,11 +214,55 @@ fn install_aipack_file(
 	}
 
 	// -- Extract the pack.toml from zip and validate
-	let new_pack_toml = support::extract_pack_toml_from_pack_file(aipack_zipped_file)?;
+	let mut new_pack_toml = support::extract_pack_toml_from_pack_file(aipack_zipped_file)?;
 
 	// NEW: Validate prerelease format for installation
 	support::validate_version_for_install(&new_pack_toml.version)?;
 
+	if let Some(locked) = locked
+		&& new_pack_toml.version != locked.version
+	{
+		return Err(Error::FailToInstall {
+			aipack_ref: pack_uri.to_string(),
+			cause: format!(
+				"Locked version mismatch: the source has v{}, the pack lock has v{}",
+				new_pack_toml.version, locked.version
+			),
+		});
+	}
+
+	// -- Install under the alias identity (the installed dir is the pack identity)
+	let alias_of = match alias {
+		Some(PackIdentity { namespace, name }) => {
+			let alias_of = format!("{}@{}", new_pack_toml.namespace, new_pack_toml.name);
+			new_pack_toml.namespace = namespace;
+			new_pack_toml.name = name;
+			Some(alias_of)
+		}
+		None => None,
+	};
+	let install_version = git_commit.unwrap_or(&new_pack_toml.version);
+	let mut install_info = InstallInfo::new(pack_uri, install_version, alias_of);
+	install_info.uri = Some(source.uri.clone());
+	install_info.download_url = source.download_url.clone();
+
+	// -- Check the conflicts with the packs of the same namespace/name from other sources
+	if !force {
+		let conflicts = find_install_conflicts(
+			dir_context,
+			&new_pack_toml.namespace,
+			&new_pack_toml.name,
+			&install_info,
+		)?;
+		if !conflicts.is_empty() {
+			let pack_ref = format!("{}@{}", new_pack_toml.namespace, new_pack_toml.name);
+			return Err(Error::InstallFailConflict {
+				details: format_install_conflicts(&pack_ref, &install_info, &conflicts),
+				pack_ref,
+			});
+		}
+	}
+
 	// -- Check if a pack with the same namespace/name is already installed
 	let potential_existing_path = pack_installed_dir.join(&new_pack_toml.namespace).join(&new_pack_toml.name);
 
@@ -110,7 +277,40 @@ fn install_aipack_file(
 			None
 		};
 
-		if let Some(existing_pack_toml) = existing_pack_toml {
+		if let Some(locked) = locked {
+			// NOTE: Up to date only with the locked content (otherwise reinstalled, even if above)
+			let installed_info = InstallInfo::read(&potential_existing_path);
+			if let (Some(existing_pack_toml), Some(installed_info)) = (existing_pack_toml, installed_info)
+				&& existing_pack_toml.version == locked.version
+				&& installed_info.content_hash.is_some()
+				&& installed_info.content_hash == locked.content_hash
+			{
+				return Ok(InstallResponse::UpToDate(InstalledPack {
+					pack_toml: existing_pack_toml,
+					path: potential_existing_path,
+					size: 0,
+					zip_size: 0,
+					git_commit: git_commit.map(str::to_string),
+					dependencies: Vec::new(),
+				}));
+			}
+		} else if let Some(commit) = git_commit {
+			// NOTE: A branch can move to any version, so only the same commit is up to date
+			let installed_info = InstallInfo::read(&potential_existing_path);
+			if let (Some(existing_pack_toml), Some(installed_info)) = (existing_pack_toml, installed_info)
+				&& installed_info.source == install_info.source
+				&& installed_info.version == commit
+			{
+				return Ok(InstallResponse::UpToDate(InstalledPack {
+					pack_toml: existing_pack_toml,
+					path: potential_existing_path,
+					size: 0,
+					zip_size: 0,
+					git_commit: Some(commit.to_string()),
+					dependencies: Vec::new(),
+				}));
+			}
+		} else if let Some(existing_pack_toml) = existing_pack_toml {
 			let ord = support::validate_version_update(&existing_pack_toml.version, &new_pack_toml.version)?;
 			match ord {
 				std::cmp::Ordering::Equal => {
@@ -119,6 +319,8 @@ fn install_aipack_file(
 						path: potential_existing_path,
 						size: 0,
 						zip_size: 0,
+						git_commit: None,
+						dependencies: Vec::new(),
 					}));
 				}
 				std::cmp::Ordering::Less => {
@@ -132,6 +334,34 @@ fn install_aipack_file(
 		}
 	}
 
+	// -- Ask the consent for the declared capabilities and paths
+	let needs_consent = !new_pack_toml.capabilities.is_empty() || !new_pack_toml.paths_allow.is_empty();
+	if needs_consent && !consent(&new_pack_toml)? {
+		return Err(Error::FailToInstall {
+			aipack_ref: pack_uri.to_string(),
+			cause: "The pack capabilities were not granted".to_string(),
+		});
+	}
+	install_info.capabilities = new_pack_toml.capabilities.clone();
+	install_info.paths_allow = new_pack_toml.paths_allow.clone();
+
+	// -- Verify the content hash stamped by `aip pack` (if stamped), and the locked one
+	let content_hash = verify_aipack_content_hash(aipack_zipped_file).map_err(|e| Error::FailToInstall {
+		aipack_ref: pack_uri.to_string(),
+		cause: e.to_string(),
+	})?;
+	if let Some(locked_hash) = locked.and_then(|l| l.content_hash.as_deref())
+		&& content_hash != locked_hash
+	{
+		return Err(Error::FailToInstall {
+			aipack_ref: pack_uri.to_string(),
+			cause: format!(
+				"Locked content hash mismatch, the pack content differs from the locked one.\n     locked: {locked_hash}\n     source: {content_hash}"
+			),
+		});
+	}
+	install_info.content_hash = Some(content_hash);
+
 	// If we've gotten here, either there's no existing pack or the new version is greater than or equal to the installed version
 	let pack_target_dir = pack_installed_dir.join(&new_pack_toml.namespace).join(&new_pack_toml.name);
 
@@ -150,6 +380,12 @@ fn install_aipack_file(
 		cause: format!("Failed to unzip pack: {e}"),
 	})?;
 
+	// -- Record where it was installed from
+	install_info.write(&pack_target_dir).map_err(|e| Error::FailToInstall {
+		aipack_ref: pack_uri.to_string(),
+		cause: format!("Failed to write the install info: {e}"),
+	})?;
+
 	// Calculate the size of the installed pack
 	let size = support::calculate_directory_size(&pack_target_dir)?;
 
@@ -158,6 +394,8 @@ fn install_aipack_file(
 		path: pack_target_dir,
 		size,
 		zip_size: 0, // This will be populated by the caller
+		git_commit: git_commit.map(str::to_string),
+		dependencies: Vec::new(),
 	}))
 }
 
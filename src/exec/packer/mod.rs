@@ -3,13 +3,24 @@
 mod pack_toml;
 mod support;
 
+mod git_source;
+mod install_info;
 mod installer_impl;
+mod pack_build;
+mod pack_deps;
+mod pack_lock;
+mod pack_meta;
 mod packer_impl;
+mod uninstaller_impl;
 mod unpacker_impl;
 
+pub use install_info::InstallInfo;
 pub use installer_impl::{InstallResponse, InstalledPack, install_pack};
+pub use pack_lock::install_locked;
 pub use pack_toml::PackToml;
 pub use packer_impl::*;
+pub use support::{fetch_repo_latest_version, validate_version_update};
+pub use uninstaller_impl::{UninstalledPack, uninstall_pack};
 pub use unpacker_impl::{UnpackedPack, unpack_pack};
 
 // endregion: --- Modules
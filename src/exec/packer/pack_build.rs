@@ -0,0 +1,319 @@
+//! The pack build steps (`[build]` of the `pack.toml`), executed by `aip pack` before archiving,
+//! so the pack sources can stay DRY while the shipped packs are self-contained.
+//!
+//! ```toml
+//! [build]
+//! exclude = ["build/**"]   # files not shipped in the .aipack (e.g., the build sources)
+//!
+//! [[build.steps]]          # render a handlebars template (with `pack` and the optional `data` file)
+//! kind = "render"
+//! src  = "build/prompt.md.hbs"
+//! dest = "agents/prompt.md"
+//! data = "build/data.toml" # optional, .toml or .json
+//!
+//! [[build.steps]]          # concatenate files (sorted by path)
+//! kind      = "bundle"
+//! src       = ["build/prompts/*.md"]
+//! dest      = "prompts.md"
+//! separator = "\n\n"       # optional (default "\n\n")
+//!
+//! [[build.steps]]          # fetch a vendored data file, with its pinned hash
+//! kind = "fetch"
+//! url  = "https://example.com/data/words.txt"
+//! dest = "data/words.txt"
+//! hash = "sha256:9f86d0..." # or "blake3:..."
+//! ```
+//!
+//! The steps run in order in a staging copy of the pack dir (a step can use the output of a previous one),
+//! and the `exclude` files are removed before archiving. The pack dir is never modified.
+
+use crate::exec::packer::PackToml;
+use crate::support::files::{hash_file_hex, hash_file_sha256_hex};
+use crate::support::tomls::parse_toml_into_json;
+use crate::support::webc;
+use crate::{Error, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use simple_fs::{SPath, ensure_dir, get_glob_set};
+use walkdir::WalkDir;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PackBuild {
+	#[serde(default)]
+	pub exclude: Vec<String>,
+	#[serde(default)]
+	pub steps: Vec<BuildStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum BuildStep {
+	Render {
+		src: String,
+		dest: String,
+		data: Option<String>,
+	},
+	Bundle {
+		src: Vec<String>,
+		dest: String,
+		separator: Option<String>,
+	},
+	Fetch {
+		url: String,
+		dest: String,
+		hash: String,
+	},
+}
+
+#[derive(Deserialize)]
+struct PackTomlBuild {
+	build: Option<PackBuild>,
+}
+
+/// Constructors
+impl PackBuild {
+	/// Parse the `[build]` of the pack.toml content (None if absent)
+	pub fn from_pack_toml(toml_content: &str, toml_path: &str) -> Result<Option<Self>> {
+		let pack_toml: PackTomlBuild = toml::from_str(toml_content)
+			.map_err(|err| Error::custom(format!("Invalid [build] in {toml_path}. Cause: {err}")))?;
+
+		let Some(build) = pack_toml.build else {
+			return Ok(None);
+		};
+
+		// -- Validate the paths (relative to the pack dir)
+		for step in build.steps.iter() {
+			validate_rel_path(step.dest(), toml_path)?;
+		}
+		if build.exclude.iter().any(|glob| glob == "pack.toml") {
+			return Err(Error::custom(format!(
+				"Invalid [build] in {toml_path}. 'pack.toml' cannot be excluded"
+			)));
+		}
+
+		Ok(Some(build))
+	}
+}
+
+impl BuildStep {
+	pub fn dest(&self) -> &str {
+		match self {
+			BuildStep::Render { dest, .. } | BuildStep::Bundle { dest, .. } | BuildStep::Fetch { dest, .. } => dest,
+		}
+	}
+
+	fn kind(&self) -> &'static str {
+		match self {
+			BuildStep::Render { .. } => "render",
+			BuildStep::Bundle { .. } => "bundle",
+			BuildStep::Fetch { .. } => "fetch",
+		}
+	}
+}
+
+/// Executors
+impl PackBuild {
+	/// Run the build steps in the `stage_dir` (a copy of the pack dir), then remove the `exclude` files.
+	///
+	/// Returns the built files (the step dest paths, relative to the pack dir).
+	pub async fn run(&self, stage_dir: &SPath, pack_toml: &PackToml) -> Result<Vec<String>> {
+		let mut built_files = Vec::new();
+
+		for (idx, step) in self.steps.iter().enumerate() {
+			let dest_path = stage_dir.join(step.dest());
+			if let Some(parent_dir) = dest_path.parent() {
+				ensure_dir(parent_dir)?;
+			}
+
+			run_step(step, stage_dir, &dest_path, pack_toml).await.map_err(|err| {
+				Error::custom(format!(
+					"Pack build step #{} ({} '{}') failed. Cause: {err}",
+					idx + 1,
+					step.kind(),
+					step.dest()
+				))
+			})?;
+
+			built_files.push(step.dest().to_string());
+		}
+
+		// -- Remove the excluded files
+		if !self.exclude.is_empty() {
+			for file in list_rel_files(stage_dir, &self.exclude)? {
+				std::fs::remove_file(stage_dir.join(&file).as_std_path())?;
+			}
+			remove_empty_dirs(stage_dir)?;
+		}
+
+		Ok(built_files)
+	}
+}
+
+// region:    --- Support
+
+async fn run_step(step: &BuildStep, stage_dir: &SPath, dest_path: &SPath, pack_toml: &PackToml) -> Result<()> {
+	match step {
+		BuildStep::Render { src, data, .. } => {
+			let tmpl = std::fs::read_to_string(stage_dir.join(src).as_std_path())
+				.map_err(|err| Error::custom(format!("Cannot read template '{src}'. Cause: {err}")))?;
+			let data = match data {
+				Some(data) => load_data_file(&stage_dir.join(data))?,
+				None => Value::Null,
+			};
+			let data_root = json!({
+				"pack": {
+					"namespace": pack_toml.namespace,
+					"name": pack_toml.name,
+					"version": pack_toml.version,
+				},
+				"data": data,
+			});
+			let content = crate::support::hbs::hbs_render(&tmpl, &data_root)?;
+			std::fs::write(dest_path.as_std_path(), content)?;
+		}
+
+		BuildStep::Bundle { src, separator, .. } => {
+			let files = list_rel_files(stage_dir, src)?;
+			if files.is_empty() {
+				return Err(Error::custom(format!("No files match {src:?}")));
+			}
+			let separator = separator.as_deref().unwrap_or("\n\n");
+			let mut contents = Vec::with_capacity(files.len());
+			for file in files {
+				contents.push(std::fs::read_to_string(stage_dir.join(&file).as_std_path())?);
+			}
+			std::fs::write(dest_path.as_std_path(), contents.join(separator))?;
+		}
+
+		BuildStep::Fetch { url, hash, .. } => {
+			webc::web_download_to_file(url, dest_path).await?;
+			let actual_hash = match hash.split_once(':') {
+				Some(("sha256", _)) => format!("sha256:{}", hash_file_sha256_hex(dest_path)?),
+				Some(("blake3", _)) => format!("blake3:{}", hash_file_hex(dest_path)?),
+				_ => {
+					return Err(Error::custom(format!(
+						"Invalid hash '{hash}', must be 'sha256:<hex>' or 'blake3:<hex>'"
+					)));
+				}
+			};
+			if !actual_hash.eq_ignore_ascii_case(hash) {
+				let _ = std::fs::remove_file(dest_path.as_std_path());
+				return Err(Error::custom(format!(
+					"Hash mismatch for '{url}'.\n  expected: {hash}\n    actual: {actual_hash}"
+				)));
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn load_data_file(path: &SPath) -> Result<Value> {
+	let content = std::fs::read_to_string(path.as_std_path())
+		.map_err(|err| Error::custom(format!("Cannot read data file '{path}'. Cause: {err}")))?;
+	match path.ext() {
+		"toml" => parse_toml_into_json(&content),
+		"json" => Ok(serde_json::from_str(&content)?),
+		ext => Err(Error::custom(format!(
+			"Data file '{path}' must be .toml or .json (was '.{ext}')"
+		))),
+	}
+}
+
+/// Returns the files of `dir` matching the globs (relative paths with `/`, sorted)
+fn list_rel_files(dir: &SPath, globs: &[String]) -> Result<Vec<String>> {
+	let glob_refs = globs.iter().map(String::as_str).collect::<Vec<_>>();
+	let glob_set = get_glob_set(&glob_refs).map_err(|err| Error::custom(format!("Invalid globs {globs:?}. {err}")))?;
+
+	let mut files = Vec::new();
+	for entry in WalkDir::new(dir.as_std_path()).sort_by_file_name() {
+		let entry = entry.map_err(|err| Error::custom(format!("Cannot list '{dir}'. Cause: {err}")))?;
+		if !entry.file_type().is_file() {
+			continue;
+		}
+		let Ok(rel_path) = entry.path().strip_prefix(dir.as_std_path()) else {
+			continue;
+		};
+		let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+		if glob_set.is_match(&rel_path) {
+			files.push(rel_path);
+		}
+	}
+
+	Ok(files)
+}
+
+fn remove_empty_dirs(dir: &SPath) -> Result<()> {
+	// NOTE: contents_first, so the sub dirs are removed before their parent
+	for entry in WalkDir::new(dir.as_std_path()).min_depth(1).contents_first(true) {
+		let entry = entry.map_err(|err| Error::custom(format!("Cannot list '{dir}'. Cause: {err}")))?;
+		if entry.file_type().is_dir() && std::fs::read_dir(entry.path())?.next().is_none() {
+			std::fs::remove_dir(entry.path())?;
+		}
+	}
+	Ok(())
+}
+
+fn validate_rel_path(path: &str, toml_path: &str) -> Result<()> {
+	let spath = SPath::new(path);
+	if path.is_empty() || spath.is_absolute() || path.split(['/', '\\']).any(|part| part == "..") {
+		return Err(Error::custom(format!(
+			"Invalid [build] in {toml_path}. The step dest '{path}' must be relative to the pack dir"
+		)));
+	}
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_pack_build_from_pack_toml() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_toml = r#"
+[pack]
+namespace = "acme"
+name = "helper"
+version = "0.1.0"
+
+[build]
+exclude = ["build/**"]
+
+[[build.steps]]
+kind = "render"
+src = "build/prompt.md.hbs"
+dest = "prompt.md"
+
+[[build.steps]]
+kind = "fetch"
+url = "https://example.com/words.txt"
+dest = "data/words.txt"
+hash = "sha256:abc"
+"#;
+
+		// -- Exec
+		let build = PackBuild::from_pack_toml(fx_toml, "pack.toml")?.ok_or("Should have build")?;
+
+		// -- Check
+		assert_eq!(build.exclude, vec!["build/**"]);
+		assert_eq!(build.steps.len(), 2);
+		assert!(matches!(build.steps[1], BuildStep::Fetch { .. }));
+		assert!(PackBuild::from_pack_toml("[pack]\nname = \"x\"", "pack.toml")?.is_none());
+		let bad_dest = "[[build.steps]]\nkind = \"bundle\"\nsrc = [\"a.md\"]\ndest = \"../out.md\"";
+		assert!(PackBuild::from_pack_toml(bad_dest, "pack.toml").is_err());
+		let bad_kind = "[[build.steps]]\nkind = \"shell\"\ndest = \"out.md\"";
+		assert!(PackBuild::from_pack_toml(bad_kind, "pack.toml").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
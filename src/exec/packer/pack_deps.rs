@@ -0,0 +1,178 @@
+//! The pack dependencies of `aip install` (the pack.toml `[dependencies]`).
+//!
+//! The dependencies are resolved depth first: a dependency already installed with a matching version is kept,
+//! otherwise it is installed from its `source` (or the aipack.ai repo). A dependency required twice must satisfy
+//! both ranges (version conflict), and a dependency requiring one of its requirers is a cycle.
+//!
+//! The resolved set is recorded in the workspace pack lock (see `pack_lock`).
+
+use crate::dir_context::DirContext;
+use crate::exec::packer::PackToml;
+use crate::exec::packer::installer_impl::{CapabilitiesConsent, InstallResponse, InstalledPack, install_pack_uri};
+use crate::exec::packer::pack_lock::{LockedPack, locked_pack};
+use crate::exec::packer::pack_toml::{PackDependency, parse_validate_pack_toml};
+use crate::{Error, Result};
+use semver::{Version, VersionReq};
+use simple_fs::SPath;
+use std::collections::BTreeMap;
+
+/// Resolve and install the dependencies of the installed pack (recursively).
+///
+/// Returns the resolved dependencies (without the installed pack), sorted by `namespace@name`.
+pub(super) async fn resolve_dependencies(
+	dir_context: &DirContext,
+	installed_pack: &InstalledPack,
+	consent: CapabilitiesConsent<'_>,
+) -> Result<Vec<LockedPack>> {
+	if installed_pack.pack_toml.dependencies.is_empty() {
+		return Ok(Vec::new());
+	}
+
+	let mut resolver = DepsResolver {
+		dir_context,
+		consent,
+		resolved: BTreeMap::new(),
+	};
+	let mut path = Vec::new();
+	resolver.resolve(&installed_pack.pack_toml, &mut path).await?;
+
+	let dependencies = resolver.resolved.into_values().map(|dep| dep.locked).collect();
+
+	Ok(dependencies)
+}
+
+// region:    --- DepsResolver
+
+struct ResolvedDep {
+	locked: LockedPack,
+	/// The `(requirer, range)` of the requirements, for the conflict errors
+	requirements: Vec<(String, VersionReq)>,
+}
+
+struct DepsResolver<'a> {
+	dir_context: &'a DirContext,
+	consent: CapabilitiesConsent<'a>,
+	/// The resolved dependencies, by `namespace@name`
+	resolved: BTreeMap<String, ResolvedDep>,
+}
+
+impl DepsResolver<'_> {
+	/// Resolve the dependencies of the pack, with `path` the chain of the requirers (for the cycle detection).
+	async fn resolve(&mut self, pack_toml: &PackToml, path: &mut Vec<String>) -> Result<()> {
+		let requirer = pack_ref(pack_toml);
+		path.push(requirer.clone());
+
+		for dep in pack_toml.dependencies.iter() {
+			let dep_ref = dep.identity.to_string();
+
+			// -- Cycle
+			if path.contains(&dep_ref) {
+				return Err(Error::FailToInstall {
+					aipack_ref: dep_ref.clone(),
+					cause: format!("Dependency cycle: {} -> {dep_ref}", path.join(" -> ")),
+				});
+			}
+
+			// -- Already resolved (must satisfy this range as well)
+			if let Some(resolved) = self.resolved.get_mut(&dep_ref) {
+				if !version_matches(&dep.version_req, &resolved.locked.version) {
+					let previous = resolved
+						.requirements
+						.iter()
+						.map(|(by, req)| format!("'{req}' by {by}"))
+						.collect::<Vec<_>>()
+						.join(", ");
+					return Err(Error::FailToInstall {
+						aipack_ref: dep_ref.clone(),
+						cause: format!(
+							"Dependency version conflict: v{} (required {previous}) does not satisfy '{}' required by {requirer}",
+							resolved.locked.version, dep.version_req
+						),
+					});
+				}
+				resolved.requirements.push((requirer.clone(), dep.version_req.clone()));
+				resolved.locked.required_by.push(requirer.clone());
+				continue;
+			}
+
+			// -- Installed or install
+			let (dep_pack_toml, dep_path) = self.install_dep(dep, &requirer).await?;
+			if !version_matches(&dep.version_req, &dep_pack_toml.version) {
+				return Err(Error::FailToInstall {
+					aipack_ref: dep_ref.clone(),
+					cause: format!(
+						"Dependency version conflict: v{} does not satisfy '{}' required by {requirer}",
+						dep_pack_toml.version, dep.version_req
+					),
+				});
+			}
+			self.resolved.insert(
+				dep_ref,
+				ResolvedDep {
+					locked: locked_pack(&dep_pack_toml, &dep_path, vec![requirer.clone()]),
+					requirements: vec![(requirer.clone(), dep.version_req.clone())],
+				},
+			);
+
+			Box::pin(self.resolve(&dep_pack_toml, path)).await?;
+		}
+
+		path.pop();
+		Ok(())
+	}
+
+	/// Returns the installed pack if its version matches, otherwise installs it.
+	async fn install_dep(&self, dep: &PackDependency, requirer: &str) -> Result<(PackToml, SPath)> {
+		let installed_dir = self
+			.dir_context
+			.aipack_paths()
+			.get_base_pack_installed_dir()?
+			.join(&dep.identity.namespace)
+			.join(&dep.identity.name);
+		if let Some(installed_toml) = read_installed_pack_toml(&installed_dir)
+			&& version_matches(&dep.version_req, &installed_toml.version)
+		{
+			return Ok((installed_toml, installed_dir));
+		}
+
+		let dep_ref = dep.identity.to_string();
+		let uri = dep.source.as_deref().unwrap_or(&dep_ref);
+		let install_res = install_pack_uri(self.dir_context, uri, false, None, None, self.consent)
+			.await
+			.map_err(|err| Error::FailToInstall {
+				aipack_ref: dep_ref.clone(),
+				cause: format!("Dependency '{}' of {requirer}. {err}", dep.version_req),
+			})?;
+
+		let (InstallResponse::Installed(installed) | InstallResponse::UpToDate(installed)) = install_res;
+		let installed_ref = pack_ref(&installed.pack_toml);
+		if installed_ref != dep_ref {
+			return Err(Error::FailToInstall {
+				aipack_ref: dep_ref.clone(),
+				cause: format!("The source '{uri}' of the dependency is the pack '{installed_ref}'"),
+			});
+		}
+
+		Ok((installed.pack_toml, installed.path))
+	}
+}
+
+// endregion: --- DepsResolver
+
+// region:    --- Support
+
+fn pack_ref(pack_toml: &PackToml) -> String {
+	format!("{}@{}", pack_toml.namespace, pack_toml.name)
+}
+
+fn version_matches(version_req: &VersionReq, version: &str) -> bool {
+	Version::parse(version.trim_start_matches('v')).is_ok_and(|version| version_req.matches(&version))
+}
+
+fn read_installed_pack_toml(installed_dir: &SPath) -> Option<PackToml> {
+	let toml_path = installed_dir.join("pack.toml");
+	let content = std::fs::read_to_string(toml_path.as_std_path()).ok()?;
+	parse_validate_pack_toml(&content, toml_path.as_str()).ok()
+}
+
+// endregion: --- Support
@@ -0,0 +1,180 @@
+//! The workspace pack lock (`.aipack/aipack.lock`), written on install, with the exact version, install uri,
+//! and content hash of the installed packs (and their dependencies).
+//!
+//! `aip install --locked` installs exactly the locked packs (the repo packs from their versioned URL, the git repos
+//! at their commit), and fails if a source does not match its locked version, commit, or content hash.
+
+use crate::dir_context::DirContext;
+use crate::exec::packer::PackToml;
+use crate::exec::packer::install_info::InstallInfo;
+use crate::exec::packer::installer_impl::{CapabilitiesConsent, InstallResponse, InstalledPack, install_pack_uri};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::SPath;
+
+const AIPACK_LOCK_HEADER: &str = concat!(
+	"# Generated by `aip install` - the installed packs, for `aip install --locked`\n",
+	"# Commit it to share the exact pack versions\n\n"
+);
+
+/// A locked pack of the `.aipack/aipack.lock`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockedPack {
+	/// The `namespace@name`
+	pub pack: String,
+	pub version: String,
+	/// The install origin (e.g., `aipack.ai repo`, `git repo '...'`)
+	pub origin: String,
+	/// The install uri (e.g., `acme@coder`, the `.aipack` path, `git+https://...#main`)
+	pub uri: Option<String>,
+	/// The versioned `.aipack` URL, for the aipack.ai repo packs
+	pub download_url: Option<String>,
+	/// The commit hash, for the packs installed from a git repo
+	pub commit: Option<String>,
+	/// The content hash of the pack files (e.g., `blake3:9f2c...`)
+	pub content_hash: Option<String>,
+	/// The `namespace@name` of the pack.toml, when installed under another name (`--as`)
+	pub alias_of: Option<String>,
+	/// The `namespace@name` of the packs requiring it (empty for the packs installed directly)
+	#[serde(default)]
+	pub required_by: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PackLock {
+	#[serde(default)]
+	pack: Vec<LockedPack>,
+}
+
+/// Record the installed pack and its resolved dependencies in the pack lock (if in a workspace).
+pub(super) fn record_installed_pack(dir_context: &DirContext, installed_pack: &InstalledPack) -> Result<()> {
+	let Some(lock_path) = dir_context.aipack_paths().aipack_lock_path() else {
+		return Ok(());
+	};
+
+	let mut packs = installed_pack.dependencies.clone();
+	packs.push(locked_pack(&installed_pack.pack_toml, &installed_pack.path, Vec::new()));
+
+	let mut pack_lock = read_pack_lock(&lock_path).unwrap_or_default();
+	pack_lock.pack.retain(|locked| !packs.iter().any(|p| p.pack == locked.pack));
+	pack_lock.pack.extend(packs);
+	pack_lock.pack.sort_by(|a, b| a.pack.cmp(&b.pack));
+
+	write_pack_lock(&lock_path, &pack_lock)
+}
+
+/// Install the locked packs (`aip install --locked`), all of them, or the `pack_ref` one and its dependencies.
+///
+/// The lock is not updated (it is the source of truth).
+pub async fn install_locked(
+	dir_context: &DirContext,
+	pack_ref: Option<&str>,
+	force: bool,
+	consent: CapabilitiesConsent<'_>,
+) -> Result<Vec<InstallResponse>> {
+	let lock_path = dir_context
+		.aipack_paths()
+		.aipack_lock_path()
+		.ok_or_else(|| Error::custom("'aip install --locked' must be run in a workspace (with a '.aipack/' dir)"))?;
+	let pack_lock = read_pack_lock(&lock_path).ok_or_else(|| {
+		Error::custom(format!(
+			"No pack lock found at '{lock_path}'.\n   recommendation: Run 'aip install <pack>' first (it writes the pack lock)"
+		))
+	})?;
+
+	let locked_packs = select_locked_packs(&pack_lock, pack_ref)?;
+
+	let mut responses = Vec::new();
+	for locked in locked_packs {
+		let uri = locked.uri.as_deref().ok_or_else(|| Error::FailToInstall {
+			aipack_ref: locked.pack.clone(),
+			cause: "No install uri in the pack lock, install it again without '--locked'".to_string(),
+		})?;
+		let alias = locked.alias_of.as_ref().map(|_| locked.pack.as_str());
+		let install_res = install_pack_uri(dir_context, uri, force, alias, Some(locked), consent).await?;
+		responses.push(install_res);
+	}
+
+	Ok(responses)
+}
+
+/// The `LockedPack` of an installed pack (from its install info).
+pub(super) fn locked_pack(pack_toml: &PackToml, installed_dir: &SPath, required_by: Vec<String>) -> LockedPack {
+	let install_info = InstallInfo::read(installed_dir);
+	let commit = install_info
+		.as_ref()
+		.filter(|info| info.source.starts_with("git+"))
+		.map(|info| info.version.clone());
+	let (origin, uri, download_url, content_hash, alias_of) = match install_info {
+		Some(info) => (
+			info.origin,
+			info.uri,
+			info.download_url,
+			info.content_hash,
+			info.alias_of,
+		),
+		None => ("installed".to_string(), None, None, None, None),
+	};
+	LockedPack {
+		pack: format!("{}@{}", pack_toml.namespace, pack_toml.name),
+		version: pack_toml.version.clone(),
+		origin,
+		uri,
+		download_url,
+		commit,
+		content_hash,
+		alias_of,
+		required_by,
+	}
+}
+
+// region:    --- Support
+
+/// The locked packs to install, the dependencies first (as in the resolution).
+fn select_locked_packs<'a>(pack_lock: &'a PackLock, pack_ref: Option<&str>) -> Result<Vec<&'a LockedPack>> {
+	let Some(pack_ref) = pack_ref else {
+		let (dependencies, packs): (Vec<_>, Vec<_>) =
+			pack_lock.pack.iter().partition(|locked| !locked.required_by.is_empty());
+		return Ok(dependencies.into_iter().chain(packs).collect());
+	};
+
+	let root = pack_lock
+		.pack
+		.iter()
+		.find(|locked| locked.pack == pack_ref)
+		.ok_or_else(|| Error::FailToInstall {
+			aipack_ref: pack_ref.to_string(),
+			cause: "Pack not found in the pack lock (install it without '--locked' to add it)".to_string(),
+		})?;
+
+	// -- The dependencies of the pack (recursively), the deepest first
+	let mut selected: Vec<&LockedPack> = vec![root];
+	let mut idx = 0;
+	while idx < selected.len() {
+		let requirer = selected[idx].pack.clone();
+		for locked in pack_lock.pack.iter() {
+			if locked.required_by.contains(&requirer) && !selected.iter().any(|s| s.pack == locked.pack) {
+				selected.push(locked);
+			}
+		}
+		idx += 1;
+	}
+	selected.reverse();
+
+	Ok(selected)
+}
+
+fn read_pack_lock(lock_path: &SPath) -> Option<PackLock> {
+	let content = std::fs::read_to_string(lock_path.as_std_path()).ok()?;
+	toml::from_str(&content).ok()
+}
+
+fn write_pack_lock(lock_path: &SPath, pack_lock: &PackLock) -> Result<()> {
+	let content = toml::to_string(pack_lock).map_err(|err| Error::custom(format!("Cannot write pack lock. {err}")))?;
+	simple_fs::ensure_file_dir(lock_path)?;
+	std::fs::write(lock_path.as_std_path(), format!("{AIPACK_LOCK_HEADER}{content}"))?;
+
+	Ok(())
+}
+
+// endregion: --- Support
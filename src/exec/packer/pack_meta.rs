@@ -0,0 +1,132 @@
+//! The build metadata of a `.aipack` archive (`.aipack-meta.toml` entry), stamped by `aip pack`
+//! with the content hash of the pack files, so that an archive can be verified at install (and against the registry).
+//!
+//! The content hash only depends on the pack file paths and contents (not on the archive order, timestamps, or compression),
+//! so two builds of the same source have the same content hash (and, with the deterministic zip, the same archive bytes).
+
+use crate::exec::packer::install_info::INSTALL_INFO_FILE;
+use crate::support::zip;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::SPath;
+
+pub const PACK_META_FILE: &str = ".aipack-meta.toml";
+
+/// The files of a pack dir which are not part of the pack content (build and install metadata)
+pub const NON_CONTENT_FILES: [&str; 2] = [PACK_META_FILE, INSTALL_INFO_FILE];
+
+const CONTENT_HASH_PREFIX: &str = "blake3:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackMeta {
+	/// e.g., `blake3:9f2c...`
+	pub content_hash: String,
+}
+
+/// Constructors & Transformers
+impl PackMeta {
+	pub fn from_toml(content: &str) -> Result<Self> {
+		toml::from_str(content).map_err(|err| Error::custom(format!("Invalid '{PACK_META_FILE}'. Cause: {err}")))
+	}
+
+	pub fn to_toml(&self) -> Result<String> {
+		toml::to_string(self).map_err(|err| Error::custom(format!("Cannot write '{PACK_META_FILE}'. Cause: {err}")))
+	}
+}
+
+/// Stamp the content hash of the `aipack_file` archive into it (as the `.aipack-meta.toml` entry),
+/// and returns the content hash.
+///
+/// NOTE: The archive must not already have the meta entry (e.g., zipped with `zip_dir_excluding(.., &NON_CONTENT_FILES)`).
+pub fn stamp_aipack_content_hash(aipack_file: &SPath) -> Result<String> {
+	let files = zip::read_file_entries(aipack_file)?;
+	let content_hash = compute_content_hash(&files);
+
+	let meta = PackMeta {
+		content_hash: content_hash.clone(),
+	};
+	zip::append_file(aipack_file, PACK_META_FILE, meta.to_toml()?.as_bytes())?;
+
+	Ok(content_hash)
+}
+
+/// Verify the stamped content hash of the `aipack_file` archive against its files.
+///
+/// Returns the content hash (computed, if the archive is not stamped, e.g., packed before the content hash).
+pub fn verify_aipack_content_hash(aipack_file: &SPath) -> Result<String> {
+	let mut files = zip::read_file_entries(aipack_file)?;
+
+	let Some(meta_idx) = files.iter().position(|(name, _)| name == PACK_META_FILE) else {
+		return Ok(compute_content_hash(&files));
+	};
+	let (_, meta_content) = files.remove(meta_idx);
+	let meta_content =
+		String::from_utf8(meta_content).map_err(|_| Error::custom(format!("Invalid '{PACK_META_FILE}', not UTF8")))?;
+	let meta = PackMeta::from_toml(&meta_content)?;
+
+	let content_hash = compute_content_hash(&files);
+	if content_hash != meta.content_hash {
+		return Err(Error::custom(format!(
+			"Pack content hash mismatch, the archive content was modified after packing.\n   stamped: {}\n  computed: {content_hash}",
+			meta.content_hash
+		)));
+	}
+
+	Ok(content_hash)
+}
+
+// region:    --- Support
+
+/// `blake3:<hex>` of the (path, content) files, sorted by path.
+fn compute_content_hash(files: &[(String, Vec<u8>)]) -> String {
+	let mut files: Vec<&(String, Vec<u8>)> = files.iter().collect();
+	files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+	let mut hasher = blake3::Hasher::new();
+	for (path, content) in files {
+		// NOTE: The lengths make the (path, content) boundaries unambiguous
+		hasher.update(&(path.len() as u64).to_le_bytes());
+		hasher.update(path.as_bytes());
+		hasher.update(&(content.len() as u64).to_le_bytes());
+		hasher.update(content);
+	}
+
+	format!("{CONTENT_HASH_PREFIX}{}", hasher.finalize().to_hex())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_pack_meta_compute_content_hash_order_independent() -> Result<()> {
+		// -- Setup & Fixtures
+		let files_a = vec![
+			("pack.toml".to_string(), b"[pack]".to_vec()),
+			("main.aip".to_string(), b"# Data".to_vec()),
+		];
+		let files_b = vec![files_a[1].clone(), files_a[0].clone()];
+		let files_c = vec![
+			("pack.toml".to_string(), b"[pack]".to_vec()),
+			("main.aip".to_string(), b"# Data!".to_vec()),
+		];
+
+		// -- Exec
+		let hash_a = compute_content_hash(&files_a);
+
+		// -- Check
+		assert!(hash_a.starts_with("blake3:"));
+		assert_eq!(hash_a, compute_content_hash(&files_b));
+		assert_ne!(hash_a, compute_content_hash(&files_c));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,13 +1,28 @@
-use crate::types::PackIdentity;
+use crate::types::{PackCapability, PackIdentity};
 use crate::{Error, Result};
 use lazy_regex::regex;
+use semver::VersionReq;
 use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
 
 /// Represents the partial structure of pack.toml with optional fields
 /// for initial parsing and validation
 #[derive(Deserialize)]
 pub struct PartialPackToml {
 	pub pack: Option<PartialPackInfo>,
+	/// `"namespace@name" = "^0.2"` or `"namespace@name" = { version = "^0.2", source = "https://...git#main" }`
+	pub dependencies: Option<BTreeMap<String, PartialPackDependency>>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum PartialPackDependency {
+	Version(String),
+	Detailed {
+		version: Option<String>,
+		source: Option<String>,
+	},
 }
 
 /// Contains the inner pack information that may be partial/incomplete
@@ -16,6 +31,8 @@ pub struct PartialPackInfo {
 	pub version: Option<String>,
 	pub namespace: Option<String>,
 	pub name: Option<String>,
+	pub capabilities: Option<Vec<String>>,
+	pub paths_allow: Option<Vec<String>>,
 }
 
 /// Contains the validated required fields from pack.toml
@@ -24,6 +41,21 @@ pub struct PackToml {
 	pub version: String,
 	pub namespace: String,
 	pub name: String,
+	/// The declared capabilities (`[pack] capabilities = ["net", "exec"]`), empty if absent
+	pub capabilities: Vec<PackCapability>,
+	/// The path globs outside the workspace the pack needs (`[pack] paths_allow = ["~/.config/acme/**"]`), empty if absent
+	pub paths_allow: Vec<String>,
+	/// The packs this pack depends on (`[dependencies]`), sorted by `namespace@name`
+	pub dependencies: Vec<PackDependency>,
+}
+
+/// A pack dependency of the pack.toml `[dependencies]`
+#[derive(Debug, Clone)]
+pub struct PackDependency {
+	pub identity: PackIdentity,
+	pub version_req: VersionReq,
+	/// The pack uri to install it from (e.g., a git repo or URL), the aipack.ai repo if None
+	pub source: Option<String>,
 }
 
 /// Validates the pack.toml content and returns a PackToml struct if valid
@@ -67,13 +99,68 @@ pub(super) fn parse_validate_pack_toml(toml_content: &str, toml_path: &str) -> R
 	// Validate namespace and name format
 	validate_names(&namespace, &name, toml_path)?;
 
+	let capabilities = PackCapability::list_from_names(pack_info.capabilities.as_deref().unwrap_or_default())
+		.map_err(|err| Error::custom(format!("Invalid capabilities in {toml_path}. {err}")))?;
+
+	let paths_allow = pack_info.paths_allow.unwrap_or_default();
+	validate_paths_allow(&paths_allow, toml_path)?;
+
+	let dependencies = parse_dependencies(partial_config.dependencies.unwrap_or_default(), toml_path)?;
+
 	Ok(PackToml {
 		version,
 		namespace,
 		name,
+		capabilities,
+		paths_allow,
+		dependencies,
 	})
 }
 
+/// Parse the `[dependencies]`, with their pack identity and semver range (e.g., `^0.2`, `>=1.0, <2.0`, `*`).
+fn parse_dependencies(
+	dependencies: BTreeMap<String, PartialPackDependency>,
+	toml_path: &str,
+) -> Result<Vec<PackDependency>> {
+	let mut res = Vec::with_capacity(dependencies.len());
+	for (pack_ref, dependency) in dependencies {
+		let identity = PackIdentity::from_str(&pack_ref)
+			.map_err(|err| Error::custom(format!("Invalid dependency '{pack_ref}' in {toml_path}. {err}")))?;
+		let (version, source) = match dependency {
+			PartialPackDependency::Version(version) => (version, None),
+			PartialPackDependency::Detailed { version, source } => (version.unwrap_or_else(|| "*".to_string()), source),
+		};
+		let version_req = VersionReq::parse(&version).map_err(|err| {
+			Error::custom(format!(
+				"Invalid dependency '{pack_ref}' version '{version}' in {toml_path}. {err}"
+			))
+		})?;
+		res.push(PackDependency {
+			identity,
+			version_req,
+			source,
+		});
+	}
+	Ok(res)
+}
+
+/// Validates the `paths_allow` globs
+///
+/// Each glob must be absolute or home based (`~/`), and valid.
+pub(super) fn validate_paths_allow(paths_allow: &[String], toml_path: &str) -> Result<()> {
+	for glob in paths_allow {
+		let is_rooted = glob.starts_with('/') || glob.starts_with("~/") || regex!(r"^[a-zA-Z]:[/\\]").is_match(glob);
+		if !is_rooted {
+			return Err(Error::custom(format!(
+				"Invalid paths_allow '{glob}' in {toml_path}. The paths must be absolute or start with '~/'"
+			)));
+		}
+		simple_fs::get_glob_set(&[glob.as_str()])
+			.map_err(|err| Error::custom(format!("Invalid paths_allow '{glob}' in {toml_path}. {err}")))?;
+	}
+	Ok(())
+}
+
 /// Validates the version string according to semver compatibility
 ///
 /// Version must follow the format x.y.z and can optionally have a -suffix.number
@@ -133,6 +220,91 @@ name = "pack"
 		Ok(())
 	}
 
+	#[test]
+	fn test_packer_pack_toml_validate_capabilities() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_toml = r#"
+[pack]
+version = "1.0.0"
+namespace = "test"
+name = "pack"
+capabilities = ["net", "exec"]
+"#;
+		let fx_toml_invalid = fx_toml.replace(r#""exec""#, r#""shell""#);
+
+		// -- Exec
+		let pack_toml = parse_validate_pack_toml(fx_toml, "pack.toml")?;
+
+		// -- Check
+		assert_eq!(pack_toml.capabilities, vec![PackCapability::Net, PackCapability::Exec]);
+		assert!(parse_validate_pack_toml(&fx_toml_invalid, "pack.toml").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_packer_pack_toml_validate_paths_allow() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_toml = r#"
+[pack]
+version = "1.0.0"
+namespace = "test"
+name = "pack"
+paths_allow = ["~/.config/acme/**", "/opt/acme/data/*.json"]
+"#;
+		let fx_toml_relative = fx_toml.replace(r#""/opt/acme"#, r#""opt/acme"#);
+
+		// -- Exec
+		let pack_toml = parse_validate_pack_toml(fx_toml, "pack.toml")?;
+
+		// -- Check
+		assert_eq!(
+			pack_toml.paths_allow,
+			vec!["~/.config/acme/**", "/opt/acme/data/*.json"]
+		);
+		let err = parse_validate_pack_toml(&fx_toml_relative, "pack.toml")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("must be absolute or start with '~/'"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_packer_pack_toml_validate_dependencies() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_toml = r#"
+[pack]
+version = "1.0.0"
+namespace = "test"
+name = "pack"
+
+[dependencies]
+"acme@utils" = "^0.2"
+"acme@git-tools" = { version = ">=1.0, <2.0", source = "https://github.com/acme/git-tools.git#main" }
+"#;
+		let fx_toml_invalid = fx_toml.replace(r#""^0.2""#, r#""latest""#);
+
+		// -- Exec
+		let pack_toml = parse_validate_pack_toml(fx_toml, "pack.toml")?;
+
+		// -- Check
+		assert_eq!(pack_toml.dependencies.len(), 2);
+		let git_tools = &pack_toml.dependencies[0];
+		assert_eq!(git_tools.identity.to_string(), "acme@git-tools");
+		assert!(git_tools.version_req.matches(&semver::Version::parse("1.4.0")?));
+		assert!(git_tools.source.as_deref().is_some_and(|s| s.ends_with("#main")));
+		let utils = &pack_toml.dependencies[1];
+		assert!(utils.version_req.matches(&semver::Version::parse("0.2.5")?));
+		assert!(!utils.version_req.matches(&semver::Version::parse("0.3.0")?));
+		let err = parse_validate_pack_toml(&fx_toml_invalid, "pack.toml")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("Invalid dependency 'acme@utils' version 'latest'"));
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_packer_pack_toml_validate_missing_fields() -> Result<()> {
 		// -- Setup & Fixtures
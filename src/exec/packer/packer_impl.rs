@@ -1,7 +1,10 @@
 //! Module that pack the files into their .aipack
 
 use crate::exec::packer::PackToml;
+use crate::exec::packer::pack_build::PackBuild;
+use crate::exec::packer::pack_meta::{NON_CONTENT_FILES, stamp_aipack_content_hash};
 use crate::exec::packer::pack_toml::parse_validate_pack_toml;
+use crate::exec::packer::unpacker_impl::copy_dir_recursive;
 use crate::support::zip;
 use crate::{Error, Result};
 use simple_fs::SPath;
@@ -13,10 +16,19 @@ pub struct PackDirData {
 	pub pack_file: SPath,
 	#[allow(unused)]
 	pub pack_toml: PackToml,
+	/// The content hash stamped in the `.aipack-meta.toml` of the .aipack (e.g., `blake3:9f2c...`)
+	pub content_hash: String,
+	/// The files built by the `[build]` steps of the pack.toml (relative to the pack dir)
+	pub built_files: Vec<String>,
 }
 
 /// Packs a directory into a .aipack file
 ///
+/// The build is reproducible: two builds of the same source give the same .aipack bytes,
+/// with the same content hash stamped in its `.aipack-meta.toml`.
+///
+/// If the pack.toml has `[build]` steps, they run in a staging copy of the pack dir, which is archived instead.
+///
 /// # Parameters
 /// - `pack_dir`: The directory containing the content to be packed
 /// - `dest_dir`: The directory where the .aipack file will be created
@@ -24,7 +36,7 @@ pub struct PackDirData {
 /// # Returns
 /// - Ok(PackDirData): If packing is successful, containing the path to the created .aipack file and pack.toml data
 /// - Err(Error): If any error occurs during packing
-pub fn pack_dir(pack_dir: impl AsRef<SPath>, dest_dir: impl AsRef<SPath>) -> Result<PackDirData> {
+pub async fn pack_dir(pack_dir: impl AsRef<SPath>, dest_dir: impl AsRef<SPath>) -> Result<PackDirData> {
 	let pack_dir = pack_dir.as_ref();
 	let dest_dir = dest_dir.as_ref();
 
@@ -37,6 +49,7 @@ pub fn pack_dir(pack_dir: impl AsRef<SPath>, dest_dir: impl AsRef<SPath>) -> Res
 	// Read and validate the TOML file
 	let toml_content = fs::read_to_string(&toml_path)?;
 	let pack_toml = parse_validate_pack_toml(&toml_content, toml_path.as_str())?;
+	let pack_build = PackBuild::from_pack_toml(&toml_content, toml_path.as_str())?;
 
 	// Normalize version - replace special characters with hyphens
 	let pack_version = &pack_toml.version;
@@ -50,15 +63,52 @@ pub fn pack_dir(pack_dir: impl AsRef<SPath>, dest_dir: impl AsRef<SPath>) -> Res
 		fs::create_dir_all(dest_dir)?;
 	}
 
-	// Zip the directory
-	zip::zip_dir(pack_dir, &aipack_path)?;
+	let (content_hash, built_files) = match pack_build {
+		None => (zip_and_stamp(pack_dir, &aipack_path)?, Vec::new()),
+		Some(pack_build) => {
+			let stage_dir = build_stage_dir(&pack_toml)?;
+			if stage_dir.exists() {
+				fs::remove_dir_all(stage_dir.as_std_path())?;
+			}
+			copy_dir_recursive(pack_dir, &stage_dir)?;
+
+			let res = match pack_build.run(&stage_dir, &pack_toml).await {
+				Ok(built_files) => zip_and_stamp(&stage_dir, &aipack_path).map(|hash| (hash, built_files)),
+				Err(err) => Err(err),
+			};
+			fs::remove_dir_all(stage_dir.as_std_path())?;
+			res?
+		}
+	};
 
 	Ok(PackDirData {
 		pack_file: aipack_path,
 		pack_toml,
+		content_hash,
+		built_files,
 	})
 }
 
+// region:    --- Support
+
+/// Zip the dir (without the build and install metadata, e.g., when packing an installed pack),
+/// and stamp the content hash (returned).
+fn zip_and_stamp(dir: &SPath, aipack_path: &SPath) -> Result<String> {
+	zip::zip_dir_excluding(dir, aipack_path, &NON_CONTENT_FILES)?;
+	stamp_aipack_content_hash(aipack_path)
+}
+
+/// The staging dir of the pack build (in the system temp dir)
+fn build_stage_dir(pack_toml: &PackToml) -> Result<SPath> {
+	let temp_dir = SPath::from_std_path_buf(std::env::temp_dir())
+		.map_err(|err| Error::custom(format!("Invalid temp dir. Cause: {err}")))?;
+	Ok(temp_dir
+		.join("aipack-build")
+		.join(format!("{}@{}", pack_toml.namespace, pack_toml.name)))
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -20,6 +20,11 @@ pub enum PackUri {
 	RepoPack(PackIdentity),
 	LocalPath(String),
 	HttpLink(String),
+	/// A git repository, with an optional branch, tag, or commit (`https://github.com/org/repo.git#main`)
+	GitRepo {
+		url: String,
+		git_ref: Option<String>,
+	},
 }
 
 impl PackUri {
@@ -29,6 +34,11 @@ impl PackUri {
 			return PackUri::RepoPack(pack_identity);
 		}
 
+		// Git repository (before the HTTP link, as it can be an https URL)
+		if let Some((url, git_ref)) = parse_git_uri(uri) {
+			return PackUri::GitRepo { url, git_ref };
+		}
+
 		// If not a PackIdentity, check if it's an HTTP link
 		if uri.starts_with("http://") || uri.starts_with("https://") {
 			PackUri::HttpLink(uri.to_string())
@@ -45,10 +55,40 @@ impl std::fmt::Display for PackUri {
 			PackUri::RepoPack(identity) => write!(f, "{identity}"),
 			PackUri::LocalPath(path) => write!(f, "local file '{path}'"),
 			PackUri::HttpLink(url) => write!(f, "URL '{url}'"),
+			PackUri::GitRepo {
+				url,
+				git_ref: Some(git_ref),
+			} => write!(f, "git repo '{url}#{git_ref}'"),
+			PackUri::GitRepo { url, git_ref: None } => write!(f, "git repo '{url}'"),
 		}
 	}
 }
 
+/// Returns the `(url, git_ref)` if the uri is a git repository:
+/// - `git+https://...`, `git+ssh://...` (the `git+` prefix is removed)
+/// - `git@host:org/repo`, `ssh://...`, `git://...`
+/// - `https://...`, `http://...`, `file://...` ending with `.git`
+///
+/// The optional `#branch` (or tag, or commit) is the git ref.
+fn parse_git_uri(uri: &str) -> Option<(String, Option<String>)> {
+	let (url, git_ref) = match uri.split_once('#') {
+		Some((url, git_ref)) => (url, Some(git_ref.trim()).filter(|r| !r.is_empty())),
+		None => (uri, None),
+	};
+
+	let is_git_scheme = url.starts_with("git@") || url.starts_with("ssh://") || url.starts_with("git://");
+	let is_git_url = (url.starts_with("https://") || url.starts_with("http://") || url.starts_with("file://"))
+		&& url.trim_end_matches('/').ends_with(".git");
+
+	let url = match url.strip_prefix("git+") {
+		Some(url) => url,
+		None if is_git_scheme || is_git_url => url,
+		None => return None,
+	};
+
+	Some((url.to_string(), git_ref.map(str::to_string)))
+}
+
 // endregion: --- PackUri
 
 // region:    --- LatestToml
@@ -135,18 +175,23 @@ pub(super) fn build_repo_pack_url(pack_identity: &PackIdentity, rel_path: &str)
 	)
 }
 
+/// The URL of the latest stable `.aipack` file of the repo pack (the URL is versioned, so it pins this version).
+pub(super) async fn fetch_repo_pack_url(pack_identity: &PackIdentity) -> Result<String> {
+	let latest_toml = fetch_repo_latest_toml(pack_identity).await?;
+
+	// Validate the latest.toml content
+	let (_version, rel_path) = latest_toml.validate()?;
+
+	// Construct the full URL to the .aipack file
+	Ok(build_repo_pack_url(pack_identity, rel_path))
+}
+
 /// Downloads a pack from a repo pack identity, resolving via latest.toml.
 ///
 /// Returns the path to the downloaded `.aipack` file and the original PackUri.
 pub(super) async fn download_from_repo(dir_context: &DirContext, pack_uri: PackUri) -> Result<(SPath, PackUri)> {
 	if let PackUri::RepoPack(ref pack_identity) = pack_uri {
-		let latest_toml = fetch_repo_latest_toml(pack_identity).await?;
-
-		// Validate the latest.toml content
-		let (_version, rel_path) = latest_toml.validate()?;
-
-		// Construct the full URL to the .aipack file
-		let aipack_url = build_repo_pack_url(pack_identity, rel_path);
+		let aipack_url = fetch_repo_pack_url(pack_identity).await?;
 
 		// Use HttpLink to download the actual pack
 		let http_uri = PackUri::HttpLink(aipack_url);
@@ -230,7 +275,7 @@ pub(super) async fn download_pack(dir_context: &DirContext, pack_uri: PackUri) -
 ///
 /// Returns `Ok(Some(version))` if the remote latest.toml was successfully fetched and validated,
 /// or `Ok(None)` if the remote could not be reached or the metadata was invalid.
-pub(super) async fn fetch_repo_latest_version(pack_identity: &PackIdentity) -> Result<Option<String>> {
+pub async fn fetch_repo_latest_version(pack_identity: &PackIdentity) -> Result<Option<String>> {
 	match fetch_repo_latest_toml(pack_identity).await {
 		Ok(latest_toml) => match latest_toml.validate() {
 			Ok((version, _rel_path)) => Ok(Some(version.to_string())),
@@ -158,3 +158,30 @@ fn test_packer_support_pack_uri_display() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_packer_support_pack_uri_parse_git() -> Result<()> {
+	// -- Exec & Check
+	let PackUri::GitRepo { url, git_ref } = PackUri::parse("https://github.com/acme/packs.git#dev") else {
+		return Err("Should be a GitRepo".into());
+	};
+	assert_eq!(url, "https://github.com/acme/packs.git");
+	assert_eq!(git_ref.as_deref(), Some("dev"));
+
+	let PackUri::GitRepo { url, git_ref } = PackUri::parse("git+https://gitlab.acme.com/acme/packs") else {
+		return Err("Should be a GitRepo".into());
+	};
+	assert_eq!(url, "https://gitlab.acme.com/acme/packs");
+	assert!(git_ref.is_none());
+
+	assert!(matches!(
+		PackUri::parse("git@github.com:acme/packs.git"),
+		PackUri::GitRepo { .. }
+	));
+	assert!(matches!(
+		PackUri::parse("https://packs.acme.com/acme@pack-v0.1.0.aipack"),
+		PackUri::HttpLink(_)
+	));
+
+	Ok(())
+}
@@ -0,0 +1,171 @@
+use crate::dir_context::DirContext;
+use crate::dir_context::join_support_pack_ref;
+use crate::support::files::{DeleteCheck, safer_trash_dir, safer_trash_file};
+use crate::types::PackIdentity;
+use crate::{Error, Result};
+use simple_fs::{SPath, list_files};
+use std::str::FromStr;
+
+/// What an uninstall removed (or would remove, with `dry_run`)
+pub struct UninstalledPack {
+	pub namespace: String,
+	pub name: String,
+	/// The `~/.aipack-base/pack/installed/<namespace>/<name>/` dir
+	pub installed_dir: SPath,
+	/// The downloaded `.aipack` files left in `~/.aipack-base/pack/.download/`
+	pub download_files: Vec<SPath>,
+	/// The pack support dirs (`support/pack/<namespace>/<name>/` of the base and workspace), only with `data`
+	pub support_dirs: Vec<SPath>,
+	pub dry_run: bool,
+}
+
+/// Uninstall an installed pack.
+///
+/// - Requires a full pack identity (`namespace@name`).
+/// - Only the installed pack is removed (custom packs are the user's own dirs).
+/// - With `data`, also removes the pack support data (`$base` and `$workspace` support dirs).
+/// - With `dry_run`, nothing is removed, the returned `UninstalledPack` is what would be removed.
+pub fn uninstall_pack(
+	dir_context: &DirContext,
+	pack_ref_str: &str,
+	data: bool,
+	dry_run: bool,
+) -> Result<UninstalledPack> {
+	// -- Parse and validate pack identity
+	let pack_identity = PackIdentity::from_str(pack_ref_str).map_err(|e| {
+		Error::custom(format!(
+			"Invalid pack reference for uninstall: '{pack_ref_str}'.\n\
+			 Uninstall requires a full pack identity in the form 'namespace@name'.\nCause: {e}"
+		))
+	})?;
+	let PackIdentity { namespace, name } = pack_identity;
+	let aipack_paths = dir_context.aipack_paths();
+
+	// -- The installed pack dir
+	let installed_dir = aipack_paths.get_base_pack_installed_dir()?.join(&namespace).join(&name);
+	if !installed_dir.exists() {
+		return Err(Error::custom(format!(
+			"Pack '{namespace}@{name}' is not installed (no '{installed_dir}').\n\
+			 Note: Custom packs (in 'pack/custom/') are not removed by uninstall."
+		)));
+	}
+
+	// -- The downloaded .aipack files (e.g., `2025-01-01-10-00-00-ns@name-v0.1.0.aipack`)
+	let download_dir = aipack_paths.get_base_pack_download_dir()?;
+	let download_files = if download_dir.exists() {
+		list_files(&download_dir, Some(&["*.aipack"]), None)?
+			.into_iter()
+			.filter(|file| is_pack_download_file(file.name(), &namespace, &name))
+			.collect()
+	} else {
+		Vec::new()
+	};
+
+	// -- The support dirs
+	let mut support_dirs = Vec::new();
+	if data {
+		let identity_path = SPath::new(namespace.as_str()).join(&name);
+		let mut aipack_dirs = vec![aipack_paths.aipack_base_dir().path()];
+		if let Some(wks_dir) = aipack_paths.aipack_wks_dir() {
+			aipack_dirs.push(wks_dir.path());
+		}
+		for aipack_dir in aipack_dirs {
+			let support_dir = join_support_pack_ref(aipack_dir, &identity_path);
+			if support_dir.exists() {
+				support_dirs.push(support_dir);
+			}
+		}
+	}
+
+	// -- Remove
+	if !dry_run {
+		safer_trash_dir(&installed_dir, Some(DeleteCheck::CONTAINS_AIPACK_BASE)).map_err(|e| {
+			Error::custom(format!(
+				"Failed to remove the installed pack '{installed_dir}'.\nCause: {e}"
+			))
+		})?;
+		for file in download_files.iter() {
+			safer_trash_file(file, Some(DeleteCheck::CONTAINS_AIPACK_BASE))?;
+		}
+		for support_dir in support_dirs.iter() {
+			// NOTE: The base support dir is in `.aipack-base/`, the workspace one in `.aipack/`
+			let delete_check = if support_dir.starts_with(aipack_paths.aipack_base_dir().path()) {
+				DeleteCheck::CONTAINS_AIPACK_BASE
+			} else {
+				DeleteCheck::CONTAINS_AIPACK
+			};
+			safer_trash_dir(support_dir, Some(delete_check))?;
+		}
+	}
+
+	Ok(UninstalledPack {
+		namespace,
+		name,
+		installed_dir,
+		download_files,
+		support_dirs,
+		dry_run,
+	})
+}
+
+// region:    --- Support
+
+/// Returns true if the download file name is a `.aipack` of this pack (`...ns@name.aipack` or `...ns@name-v...aipack`)
+fn is_pack_download_file(file_name: &str, namespace: &str, name: &str) -> bool {
+	let pack_ref = format!("{namespace}@{name}");
+	let Some(idx) = file_name.find(&pack_ref) else {
+		return false;
+	};
+	let rest = &file_name[idx + pack_ref.len()..];
+	rest.starts_with('-') || rest.starts_with('.')
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::runtime::Runtime;
+
+	#[test]
+	fn test_uninstaller_is_pack_download_file() -> Result<()> {
+		assert!(is_pack_download_file(
+			"2025-01-01-ns_b@pack_b_2-v0.1.0.aipack",
+			"ns_b",
+			"pack_b_2"
+		));
+		assert!(is_pack_download_file("ns_b@pack_b_2.aipack", "ns_b", "pack_b_2"));
+		assert!(!is_pack_download_file(
+			"ns_b@pack_b_22-v0.1.0.aipack",
+			"ns_b",
+			"pack_b_2"
+		));
+		assert!(!is_pack_download_file("ns_a@pack_b_2.aipack", "ns_b", "pack_b_2"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_uninstaller_uninstall_pack_dry_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+
+		// -- Exec
+		let uninstalled = uninstall_pack(runtime.dir_context(), "ns_b@pack_b_2", true, true)?;
+
+		// -- Check
+		assert!(uninstalled.installed_dir.exists(), "dry run should not remove");
+		assert!(uninstalled.installed_dir.as_str().ends_with("pack/installed/ns_b/pack_b_2"));
+		assert_eq!(uninstalled.support_dirs.len(), 1);
+		assert!(uninstalled.support_dirs[0].as_str().ends_with("support/pack/ns_b/pack_b_2"));
+		assert!(uninstall_pack(runtime.dir_context(), "ns_a@pack_a_1", false, true).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
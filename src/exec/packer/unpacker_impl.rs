@@ -181,7 +181,7 @@ fn determine_source(
 }
 
 /// Recursively copy a directory tree from src to dest
-fn copy_dir_recursive(src: &SPath, dest: &SPath) -> Result<()> {
+pub(super) fn copy_dir_recursive(src: &SPath, dest: &SPath) -> Result<()> {
 	if !src.exists() {
 		return Err(Error::custom(format!(
 			"Source directory does not exist for copy: '{src}'"
@@ -1,5 +1,7 @@
 use crate::Error;
 use crate::hub::get_hub;
+use serde::Serialize;
+use simple_fs::SPath;
 use std::collections::HashSet;
 use std::path::Path;
 use std::process::Command;
@@ -67,3 +69,94 @@ pub async fn open_vscode(path: impl AsRef<Path>) {
 		}
 	}
 }
+
+/// The optional info of a pack `pack.toml` `[pack]` section (all None/empty if absent or invalid).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PackTomlInfo {
+	pub version: Option<String>,
+	pub tags: Vec<String>,
+	pub license: Option<String>,
+	pub homepage: Option<String>,
+	pub repo: Option<String>,
+	pub author: Option<String>,
+	/// The declared capabilities (e.g., `net`, `exec`), consented to at install
+	pub capabilities: Vec<String>,
+	/// The path globs outside the workspace the pack needs, consented to at install
+	pub paths_allow: Vec<String>,
+}
+
+pub fn read_pack_toml_info(pack_dir: &SPath) -> PackTomlInfo {
+	let pack_info = std::fs::read_to_string(pack_dir.join("pack.toml").as_std_path())
+		.ok()
+		.and_then(|content| toml::from_str::<toml::Value>(&content).ok())
+		.and_then(|value| value.get("pack").cloned());
+	let Some(pack_info) = pack_info else {
+		return PackTomlInfo::default();
+	};
+
+	let get_str = |name: &str| pack_info.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+	let get_strs = |name: &str| -> Vec<String> {
+		pack_info
+			.get(name)
+			.and_then(|v| v.as_array())
+			.map(|items| items.iter().filter_map(|t| t.as_str()).map(|t| t.to_string()).collect())
+			.unwrap_or_default()
+	};
+
+	PackTomlInfo {
+		version: get_str("version"),
+		tags: get_strs("tags"),
+		license: get_str("license"),
+		homepage: get_str("homepage"),
+		repo: get_str("repo"),
+		author: get_str("author"),
+		capabilities: get_strs("capabilities"),
+		paths_allow: get_strs("paths_allow"),
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir};
+	use simple_fs::ensure_dir;
+
+	#[test]
+	fn test_exec_support_read_pack_toml_info_capabilities() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		ensure_dir(&dir)?;
+		std::fs::write(
+			dir.join("pack.toml").as_std_path(),
+			r#"
+[pack]
+namespace = "acme"
+name = "reporter"
+version = "0.1.0"
+tags = ["report"]
+capabilities = ["net", "exec"]
+paths_allow = ["~/.config/acme/**"]
+"#,
+		)?;
+
+		// -- Exec
+		let info = read_pack_toml_info(&dir);
+
+		// -- Check
+		assert_eq!(info.version.as_deref(), Some("0.1.0"));
+		assert_eq!(info.tags, ["report"]);
+		assert_eq!(info.capabilities, ["net", "exec"]);
+		assert_eq!(info.paths_allow, ["~/.config/acme/**"]);
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
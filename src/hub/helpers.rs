@@ -11,7 +11,7 @@ pub async fn hub_prompt(hub: &Hub, msg: impl Into<String>) -> Result<String> {
 
 	let result = rx.recv().await?;
 
-	Ok(result)
+	Ok(result.unwrap_or_default())
 }
 
 // endregion: --- Prompt Via Hub
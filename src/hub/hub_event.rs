@@ -1,8 +1,11 @@
 use crate::Error;
 use crate::exec::ExecStatusEvent;
 use crate::model::ModelEvent;
+use crate::support::text::{has_registered_secrets, mask_registered_secrets};
 use crate::tui_v1::{PrintEvent, PromptParams};
 use derive_more::derive::From;
+use serde_json::{Value, json};
+use std::borrow::Cow;
 use std::sync::Arc;
 
 /// HubEvent is sent by any part of the system that wants to share some information with the rest of the system.
@@ -57,6 +60,57 @@ impl HubEvent {
 	pub fn info_short(msg: impl Into<String>) -> Self {
 		HubEvent::InfoShort(msg.into().into())
 	}
+
+	/// Returns the event with the registered secret values masked in its messages (see `register_secret`)
+	pub fn into_masked(self) -> Self {
+		if !has_registered_secrets() {
+			return self;
+		}
+		let mask = |msg: Arc<str>| -> Arc<str> {
+			match mask_registered_secrets(&msg) {
+				Cow::Owned(masked) => masked.into(),
+				Cow::Borrowed(_) => msg,
+			}
+		};
+		match self {
+			HubEvent::Message(msg) => HubEvent::Message(mask(msg)),
+			HubEvent::InfoShort(msg) => HubEvent::InfoShort(mask(msg)),
+			HubEvent::LuaPrint(msg) => HubEvent::LuaPrint(mask(msg)),
+			HubEvent::Error { error } => match mask_registered_secrets(&error.to_string()) {
+				Cow::Owned(masked) => HubEvent::Error {
+					error: Arc::new(Error::Custom(masked)),
+				},
+				Cow::Borrowed(_) => HubEvent::Error { error },
+			},
+			other => other,
+		}
+	}
+
+	/// The JSON view of the event for the hub taps (e.g., the `aip serve` events stream).
+	/// Returns None for the events not relevant outside of the process (print, prompt, quit, ...).
+	pub fn to_tap_value(&self) -> Option<Value> {
+		let value = match self {
+			HubEvent::Message(msg) => json!({"type": "message", "message": msg.as_ref()}),
+			HubEvent::InfoShort(msg) => json!({"type": "info", "message": msg.as_ref()}),
+			HubEvent::LuaPrint(msg) => json!({"type": "print", "message": msg.as_ref()}),
+			HubEvent::Error { error } => json!({"type": "error", "message": error.to_string()}),
+			HubEvent::Executor(status) => json!({"type": "exec", "status": status.to_string()}),
+			HubEvent::Model(model_event) => json!({
+				"type": "model",
+				"entity": format!("{:?}", model_event.entity),
+				"action": format!("{:?}", model_event.action),
+				"id": model_event.id.map(|id| id.as_i64()),
+				"run_id": model_event.rel_ids.run_id.map(|id| id.as_i64()),
+				"task_id": model_event.rel_ids.task_id.map(|id| id.as_i64()),
+			}),
+			HubEvent::Print(_)
+			| HubEvent::Prompt(_)
+			| HubEvent::RtModelChange
+			| HubEvent::DoExecRedo
+			| HubEvent::Quit => return None,
+		};
+		Some(value)
+	}
 }
 
 // endregion: --- Convenient
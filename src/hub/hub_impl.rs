@@ -1,7 +1,10 @@
 use crate::event::{Rx, Tx, new_channel};
 use crate::hub::hub_event::HubEvent;
+use crate::hub::hub_record::HubRecorder;
 use crate::{Error, Result};
+use serde_json::Value;
 use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
 
 /// Hub for receiving and broadcasting all OutEvent to the systems.
@@ -9,6 +12,12 @@ use std::sync::{Arc, LazyLock, Mutex};
 pub struct Hub {
 	tx: Tx<HubEvent>,
 	rx_holder: Arc<Mutex<Option<Rx<HubEvent>>>>,
+	/// The taps receiving the JSON view of the events (see `HubEvent::to_tap_value`), e.g., `aip serve` SSE
+	taps: Mutex<Vec<flume::Sender<Value>>>,
+	/// True when a user can answer the `HubEvent::Prompt` (e.g., `aip run` in a terminal)
+	interactive: AtomicBool,
+	/// The recorder of the events, when the session is recorded (`aip run ... --record-events <file>`)
+	recorder: Mutex<Option<HubRecorder>>,
 }
 
 /// Core Hub Methods
@@ -18,7 +27,22 @@ impl Hub {
 
 		let rx_holder = Mutex::new(Some(rx)).into();
 
-		Self { tx, rx_holder }
+		Self {
+			tx,
+			rx_holder,
+			taps: Mutex::new(Vec::new()),
+			interactive: AtomicBool::new(false),
+			recorder: Mutex::new(None),
+		}
+	}
+
+	/// Set by the main when a user can answer the prompts (false for the servers and daemons)
+	pub fn set_interactive(&self, interactive: bool) {
+		self.interactive.store(interactive, Ordering::Relaxed);
+	}
+
+	pub fn is_interactive(&self) -> bool {
+		self.interactive.load(Ordering::Relaxed)
 	}
 
 	pub fn take_rx(&self) -> Result<Rx<HubEvent>> {
@@ -30,12 +54,54 @@ impl Hub {
 
 		Ok(rx)
 	}
+
+	/// Subscribe a tap to the JSON view of the next events (unlike the Rx, can be taken many times).
+	/// The tap is removed once its receiver is dropped.
+	pub fn subscribe_tap(&self) -> flume::Receiver<Value> {
+		let (tx, rx) = flume::unbounded();
+		if let Ok(mut taps) = self.taps.lock() {
+			taps.push(tx);
+		}
+		rx
+	}
+
+	/// Record the next events of this session (see `HubRecorder`)
+	pub fn start_record(&self, recorder: HubRecorder) {
+		if let Ok(mut guard) = self.recorder.lock() {
+			*guard = Some(recorder);
+		}
+	}
+
+	fn send_to_recorder(&self, event: &HubEvent) {
+		let Ok(mut guard) = self.recorder.lock() else {
+			return;
+		};
+		if let Some(recorder) = guard.as_mut()
+			&& let Err(err) = recorder.record(event)
+		{
+			tracing::warn!("AIPACK INTERNAL WARNING - failed to record hub event - {err}");
+		}
+	}
+
+	fn send_to_taps(&self, event: &HubEvent) {
+		let Ok(mut taps) = self.taps.lock() else {
+			return;
+		};
+		if taps.is_empty() {
+			return;
+		}
+		if let Some(value) = event.to_tap_value() {
+			taps.retain(|tap| tap.send(value.clone()).is_ok());
+		}
+	}
 }
 
 /// Publish event
 impl Hub {
 	pub async fn publish(&self, event: impl Into<HubEvent>) {
-		let event = event.into();
+		let event = event.into().into_masked();
+		self.send_to_taps(&event);
+		self.send_to_recorder(&event);
 
 		match self.tx.send(event).await {
 			Ok(_) => (),
@@ -44,6 +110,10 @@ impl Hub {
 	}
 
 	pub fn publish_sync(&self, event: impl Into<HubEvent>) {
+		let event = event.into().into_masked();
+		self.send_to_taps(&event);
+		self.send_to_recorder(&event);
+
 		match self.tx.send_sync(event) {
 			Ok(_) => (),
 			Err(err) => tracing::warn!("AIPACK INTERNAL WARNING - failed to send event to hub - {err}"),
@@ -120,6 +190,31 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_hub_subscribe_tap_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let hub = Hub::new();
+		let _rx = hub.take_rx()?;
+		let tap_rx = hub.subscribe_tap();
+
+		// -- Exec
+		hub.publish("Hello tap").await;
+		hub.publish(HubEvent::Quit).await;
+		let value = tap_rx.try_recv()?;
+		let quit_res = tap_rx.try_recv();
+		drop(tap_rx);
+		hub.publish("After drop").await;
+
+		// -- Check
+		assert_eq!(value["type"], "message");
+		assert_eq!(value["message"], "Hello tap");
+		assert!(quit_res.is_err(), "quit should not be tapped");
+		let taps = hub.taps.lock().map_err(|err| err.to_string())?;
+		assert!(taps.is_empty(), "dropped tap should be removed");
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
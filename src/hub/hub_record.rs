@@ -0,0 +1,223 @@
+//! The hub event records, to persist the hub events of a session (`aip run ... --record-events <file>`),
+//! and replay them into the TUI (`aip replay-events <file>`), e.g., to reproduce a user rendering or state issue.
+//!
+//! The record is a JSON Lines file, one `HubRecordLine` per event, with its time since the record start.
+//! Since the TUI reads its state from the runtime db, the model events have the snapshot of their rows
+//! (after the change), which are written into the replay db before the event is sent.
+
+use crate::exec::ExecStatusEvent;
+use crate::hub::HubEvent;
+use crate::model::{JsonRow, ModelEvent, ModelManager};
+use crate::support::time::now_micro;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, ensure_file_dir};
+use std::fs::File;
+use std::io::{BufRead as _, BufReader, BufWriter, Write as _};
+use std::sync::Arc;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HubRecordLine {
+	/// The time since the record start (in microseconds)
+	pub time_us: i64,
+	pub event: HubRecordEvent,
+	/// The rows of the model event entity (the db state after the change)
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub rows: Vec<JsonRow>,
+}
+
+/// The recorded view of a `HubEvent`
+/// (the print, prompt, redo, and quit events are not recorded, as they are not UI states, or would trigger actions)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HubRecordEvent {
+	Message { message: String },
+	InfoShort { message: String },
+	Error { message: String },
+	LuaPrint { message: String },
+	Executor { status: ExecStatusEvent },
+	Model { model_event: ModelEvent },
+	RtModelChange,
+}
+
+/// Conversions
+impl HubRecordEvent {
+	/// Returns None for the events not recorded
+	pub fn from_hub_event(event: &HubEvent) -> Option<Self> {
+		let record_event = match event {
+			HubEvent::Message(msg) => Self::Message {
+				message: msg.to_string(),
+			},
+			HubEvent::InfoShort(msg) => Self::InfoShort {
+				message: msg.to_string(),
+			},
+			HubEvent::Error { error } => Self::Error {
+				message: error.to_string(),
+			},
+			HubEvent::LuaPrint(msg) => Self::LuaPrint {
+				message: msg.to_string(),
+			},
+			HubEvent::Executor(status) => Self::Executor { status: status.clone() },
+			HubEvent::Model(model_event) => Self::Model {
+				model_event: *model_event,
+			},
+			HubEvent::RtModelChange => Self::RtModelChange,
+			HubEvent::Print(_) | HubEvent::Prompt(_) | HubEvent::DoExecRedo | HubEvent::Quit => return None,
+		};
+		Some(record_event)
+	}
+
+	pub fn into_hub_event(self) -> HubEvent {
+		match self {
+			Self::Message { message } => HubEvent::Message(message.into()),
+			Self::InfoShort { message } => HubEvent::InfoShort(message.into()),
+			Self::Error { message } => HubEvent::Error {
+				error: Arc::new(Error::Custom(message)),
+			},
+			Self::LuaPrint { message } => HubEvent::LuaPrint(message.into()),
+			Self::Executor { status } => HubEvent::Executor(status),
+			Self::Model { model_event } => HubEvent::Model(model_event),
+			Self::RtModelChange => HubEvent::RtModelChange,
+		}
+	}
+}
+
+// region:    --- HubRecorder
+
+/// Writes the hub events to the record file (see `Hub::start_record`)
+pub struct HubRecorder {
+	writer: BufWriter<File>,
+	mm: ModelManager,
+	start_us: i64,
+}
+
+impl HubRecorder {
+	/// Create (or truncate) the record file
+	pub fn new(path: &SPath, mm: ModelManager) -> Result<Self> {
+		ensure_file_dir(path)?;
+		let file = File::create(path).map_err(|err| Error::cc(format!("Cannot create record file '{path}'"), err))?;
+
+		Ok(Self {
+			writer: BufWriter::new(file),
+			mm,
+			start_us: now_micro(),
+		})
+	}
+
+	/// Append the event (when recorded) to the record file
+	/// NOTE: Flushed for each event, so that the record is complete even if the process crashes.
+	pub fn record(&mut self, event: &HubEvent) -> Result<()> {
+		let Some(record_event) = HubRecordEvent::from_hub_event(event) else {
+			return Ok(());
+		};
+
+		let rows = match &record_event {
+			HubRecordEvent::Model { model_event } => {
+				self.mm
+					.db()
+					.fetch_json_rows(model_event.entity.table(), model_event.id, model_event.rel_ids.run_id)?
+			}
+			_ => Vec::new(),
+		};
+
+		let line = HubRecordLine {
+			time_us: now_micro() - self.start_us,
+			event: record_event,
+			rows,
+		};
+		serde_json::to_writer(&mut self.writer, &line).map_err(|err| Error::cc("Cannot write hub record", err))?;
+		self.writer.write_all(b"\n")?;
+		self.writer.flush()?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- HubRecorder
+
+/// Read the lines of a record file (made by `HubRecorder`)
+pub fn read_hub_record(path: &SPath) -> Result<Vec<HubRecordLine>> {
+	let file = File::open(path).map_err(|err| Error::cc(format!("Cannot open record file '{path}'"), err))?;
+
+	let mut lines = Vec::new();
+	for (idx, line) in BufReader::new(file).lines().enumerate() {
+		let line = line?;
+		if line.trim().is_empty() {
+			continue;
+		}
+		let record_line: HubRecordLine = serde_json::from_str(&line)
+			.map_err(|err| Error::cc(format!("Invalid record line {} of '{path}'", idx + 1), err))?;
+		lines.push(record_line);
+	}
+
+	Ok(lines)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, remove_test_dir};
+	use crate::model::{EntityAction, EntityType, RelIds, RunBmc, RunForCreate};
+
+	#[tokio::test]
+	async fn test_hub_record_record_and_read() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = gen_test_dir_path();
+		let path = dir.join("hub-events.jsonl");
+		let mm = ModelManager::new().await?;
+		let run_id = RunBmc::create(
+			&mm,
+			RunForCreate {
+				parent_id: None,
+				parent_task_id: None,
+				agent_name: Some("my-agent".to_string()),
+				agent_path: None,
+				has_task_stages: None,
+				has_prompt_parts: None,
+			},
+		)?;
+		let mut recorder = HubRecorder::new(&path, mm)?;
+
+		// -- Exec
+		recorder.record(&HubEvent::from("Hello record"))?;
+		recorder.record(&HubEvent::Quit)?;
+		recorder.record(&HubEvent::Executor(ExecStatusEvent::RunStart))?;
+		recorder.record(&HubEvent::Model(ModelEvent {
+			entity: EntityType::Run,
+			action: EntityAction::Created,
+			id: Some(run_id),
+			rel_ids: RelIds::default(),
+		}))?;
+		let lines = read_hub_record(&path)?;
+
+		// -- Check
+		assert_eq!(lines.len(), 3, "quit should not be recorded");
+		assert!(matches!(&lines[0].event, HubRecordEvent::Message { message } if message == "Hello record"));
+		assert!(matches!(
+			&lines[1].event,
+			HubRecordEvent::Executor {
+				status: ExecStatusEvent::RunStart
+			}
+		));
+		assert!(lines[0].rows.is_empty());
+		assert_eq!(lines[2].rows.len(), 1);
+		assert_eq!(lines[2].rows[0]["agent_name"], "my-agent");
+		assert!(lines[2].time_us >= lines[0].time_us);
+		let HubEvent::Model(model_event) = lines.into_iter().nth(2).ok_or("Should have line")?.event.into_hub_event()
+		else {
+			return Err("Should be a model event".into());
+		};
+		assert_eq!(model_event.id, Some(run_id));
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,9 +3,11 @@
 pub mod helpers;
 pub mod hub_event;
 pub mod hub_impl;
+pub mod hub_record;
 
 pub use helpers::*;
 pub use hub_event::*;
 pub use hub_impl::*;
+pub use hub_record::*;
 
 // endregion: --- Modules
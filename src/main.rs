@@ -21,13 +21,15 @@ mod types;
 mod _test_support;
 
 use crate::exec::Executor;
-use crate::exec::cli::CliArgs;
-use crate::hub::{HubEvent, get_hub};
+use crate::exec::cli::{CliArgs, CliCommand};
+use crate::hub::{HubEvent, HubRecorder, get_hub};
 use crate::model::OnceModelManager;
 use crate::tui_v1::TuiAppV1;
 use clap::{Parser, crate_version};
 use derive_aliases::*;
 use error::{Error, Result};
+use simple_fs::SPath;
+use std::io::IsTerminal as _;
 use tracing_appender::rolling::never;
 use tracing_subscriber::EnvFilter;
 
@@ -66,6 +68,14 @@ async fn main() -> Result<()> {
 	// This way, ModelManager is only created when needed
 	let once_mm = OnceModelManager;
 
+	// -- Record the hub events of the session (before the executor start, to have them all)
+	if let CliCommand::Run(run_args) = &args.cmd
+		&& let Some(record_path) = run_args.record_events.as_deref()
+	{
+		let recorder = HubRecorder::new(&SPath::new(record_path), once_mm.get().await?)?;
+		get_hub().start_record(recorder);
+	}
+
 	// -- Start executor
 	let executor = Executor::new(once_mm);
 	let exec_tx = executor.sender();
@@ -80,12 +90,18 @@ async fn main() -> Result<()> {
 		}
 	});
 
+	// -- The prompts (e.g., `aip.flow.approve`) can only be answered by a user in a terminal
+	get_hub().set_interactive(matches!(args.cmd, CliCommand::Run(_)) && std::io::stdin().is_terminal());
+
 	// -- Start UI
 	// NOTE: For now, if interactive, we go to new TUI
 	//       Otherwise, if non interactive, we go to v1
 	if args.cmd.is_interactive() && args.cmd.is_tui() {
 		let mm = once_mm.get().await?;
 		tui::start_tui(mm, exec_tx, args).await?;
+	} else if args.cmd.is_stdio_server() {
+		// NOTE: The stdout is the protocol channel (e.g., MCP), so the messages go to the stderr
+		TuiAppV1::new(exec_tx).start_stdio_with_args(args).await?;
 	} else {
 		let tui_v1 = TuiAppV1::new(exec_tx);
 		// This will wait until all done
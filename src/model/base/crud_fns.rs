@@ -1,13 +1,14 @@
 use crate::hub::get_hub;
 use crate::model::base::DbBmc;
 use crate::model::base::prep_fields::{
-	prep_fields_for_create, prep_fields_for_create_uid_included, prep_fields_for_update,
+	masked_values, prep_fields_for_create, prep_fields_for_create_uid_included, prep_fields_for_update,
 };
 use crate::model::{EntityAction, Id, ModelEvent, ModelManager, RelIds, Result};
 use crate::support::consts;
 use modql::SqliteFromRow;
 use modql::field::{HasSqliteFields, SqliteFields};
 use modql::filter::ListOptions;
+use rusqlite::ToSql;
 use uuid::Uuid;
 
 pub fn create<MC>(mm: &ModelManager, fields: SqliteFields) -> Result<Id>
@@ -27,7 +28,8 @@ where
 	let sql = format!("UPDATE {} SET {} WHERE id = ?", MC::table_ref(), fields.sql_setters(),);
 
 	// -- Execute the command
-	let mut values = fields.values_as_dyn_to_sql_vec();
+	let values = masked_values(fields.values_as_dyn_to_sql_vec())?;
+	let mut values: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
 	values.push(&id);
 	let db = mm.db();
 
@@ -87,7 +89,8 @@ RETURNING id",
 
 	// -- Execute the command
 	let fields = fields.extended(not_exists_fields);
-	let values = fields.values_as_dyn_to_sql_vec();
+	let values = masked_values(fields.values_as_dyn_to_sql_vec())?;
+	let values: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
 	let db = mm.db();
 
 	let id: Option<i64> = db.exec_returning_as_optional(&sql, &*values)?;
@@ -127,7 +130,8 @@ where
 	);
 
 	// -- Execute the command
-	let values = fields.values_as_dyn_to_sql_vec();
+	let values = masked_values(fields.values_as_dyn_to_sql_vec())?;
+	let values: Vec<&dyn ToSql> = values.iter().map(|v| v as &dyn ToSql).collect();
 	let db = mm.db();
 
 	let id = db.exec_returning_num(&sql, &*values)?;
@@ -2,8 +2,13 @@
 //   - Make sure to use the `now_utc_fmt` for Rfc3339 for time, otherwise, rusqlite format it wrong.
 
 use super::DbBmc;
+use crate::model::Result;
+use crate::support::text::{has_registered_secrets, mask_registered_secrets};
 use crate::support::time::now_micro;
 use modql::field::{SqliteField, SqliteFields};
+use rusqlite::ToSql;
+use rusqlite::types::{ToSqlOutput, Value, ValueRef};
+use std::borrow::Cow;
 use uuid::Uuid;
 
 /// This method must be called when a model controller intends to create its entity with a new uid
@@ -28,6 +33,31 @@ where
 	add_timestamps_for_update(fields);
 }
 
+/// Returns the field values with the registered secrets masked in their text (see `register_secret`),
+/// so that they are not stored (e.g., in the logs, or the task outputs).
+pub fn masked_values<'a>(values: Vec<&'a dyn ToSql>) -> Result<Vec<ToSqlOutput<'a>>> {
+	let has_secrets = has_registered_secrets();
+	let mut res = Vec::with_capacity(values.len());
+	for value in values {
+		let value = value.to_sql()?;
+		let masked = match &value {
+			ToSqlOutput::Borrowed(ValueRef::Text(text)) if has_secrets => mask_text(text),
+			ToSqlOutput::Owned(Value::Text(text)) if has_secrets => mask_text(text.as_bytes()),
+			_ => None,
+		};
+		res.push(masked.map(|text| ToSqlOutput::Owned(Value::Text(text))).unwrap_or(value));
+	}
+	Ok(res)
+}
+
+fn mask_text(text: &[u8]) -> Option<String> {
+	let text = std::str::from_utf8(text).ok()?;
+	match mask_registered_secrets(text) {
+		Cow::Owned(masked) => Some(masked),
+		Cow::Borrowed(_) => None,
+	}
+}
+
 /// Update the timestamps info for create
 /// (e.g., cid, ctime, and mid, mtime will be updated with the same values)
 fn add_timestamps_for_create(fields: &mut SqliteFields) {
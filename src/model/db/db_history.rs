@@ -0,0 +1,250 @@
+use crate::model::db::Db;
+use crate::model::db::rt_db_setup::recreate_db;
+use crate::model::{Error, Id, Result};
+use rusqlite::Connection;
+use simple_fs::{SPath, ensure_file_dir};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// NOTE: The history db is a file db with the same schema as the runtime db.
+//       The runs are copied with new ids (appended after the existing ones), so the ids
+//       references (run_id, task_id, ...) are offset by the max id of their target table.
+
+const HIST_SCHEMA: &str = "hist";
+
+/// The wait for the history db lock (it can be shared by several aipack instances, see `[store] history_db`)
+const HIST_BUSY_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(super) const HIST_RUN_IDS: &str = "(SELECT id FROM temp._hist_run_ids)";
+
+/// The history tables with the where clause (on the source schema `{s}`) of the rows of the `_hist_run_ids` runs.
+/// NOTE: The `prompt` and `work` tables are not persisted (they are interactive/system states).
+pub(super) const HIST_TABLES: &[(&str, &str)] = &[
+	("run", "id IN {ids}"),
+	("task", "run_id IN {ids}"),
+	("err", "run_id IN {ids}"),
+	("log", "run_id IN {ids}"),
+	("pin", "run_id IN {ids}"),
+	("conv_msg", "run_id IN {ids}"),
+	(
+		"ucontent",
+		"id IN (SELECT ucontent_id FROM {s}.pin WHERE run_id IN {ids})",
+	),
+	("inout", "task_uid IN (SELECT uid FROM {s}.task WHERE run_id IN {ids})"),
+];
+
+/// Constructor
+impl Db {
+	/// Open (or create) a file db with the runtime schema (e.g., `.aipack/.session/_history.db`).
+	pub fn new_file(path: &SPath) -> Result<Self> {
+		ensure_file_dir(path).map_err(|err| Error::cc(format!("Cannot create dir for '{path}'"), err))?;
+		let con = Connection::open(path.as_std_path())?;
+		con.busy_timeout(HIST_BUSY_TIMEOUT)?;
+		recreate_db(&con)?;
+		let con = Arc::new(Mutex::new(con));
+
+		Ok(Self { con })
+	}
+}
+
+/// History
+impl Db {
+	/// Copy the run and its sub runs (with their tasks, errors, logs, pins, and inouts) into the db file at `path`,
+	/// and keep only the `max_root_runs` last top runs of this file db.
+	pub fn copy_run_tree_to_file(&self, run_id: Id, path: &SPath, max_root_runs: usize) -> Result<()> {
+		// NOTE: Make sure the file db and its schema exist (the attached one cannot be created with the same create sql)
+		drop(Db::new_file(path)?);
+
+		let con = self.con.lock()?;
+		con.busy_timeout(HIST_BUSY_TIMEOUT)?;
+		con.execute(&format!("ATTACH DATABASE ?1 AS {HIST_SCHEMA}"), [path.as_str()])?;
+
+		// NOTE: In an immediate transaction, so that the id offsets stay valid when other instances share the file db
+		let res = con
+			.execute_batch("BEGIN IMMEDIATE")
+			.map_err(Into::into)
+			.and_then(|_| copy_run_tree(&con, run_id))
+			.and_then(|_| trim_root_runs(&con, max_root_runs));
+		let end_sql = if res.is_ok() { "COMMIT" } else { "ROLLBACK" };
+		// NOTE: The rollback can fail when the begin failed (no transaction), the begin error is the one to report
+		let end_res = con.execute_batch(end_sql);
+
+		con.execute(&format!("DETACH DATABASE {HIST_SCHEMA}"), [])?;
+		res?;
+		end_res?;
+
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+fn copy_run_tree(con: &Connection, run_id: Id) -> Result<()> {
+	fill_hist_run_ids(con, "main", "SELECT ?1", [run_id.as_i64()])?;
+
+	// -- The id offsets (the hist max ids, before any insert)
+	let mut offsets: HashMap<&str, i64> = HashMap::new();
+	for (table, _) in HIST_TABLES {
+		let sql = format!("SELECT COALESCE(MAX(id), 0) FROM {HIST_SCHEMA}.{table}");
+		let max_id = con.query_row(&sql, [], |r| r.get::<_, i64>(0))?;
+		offsets.insert(table, max_id);
+	}
+
+	// -- Copy the rows (with the remapped ids)
+	for (table, where_tpl) in HIST_TABLES {
+		let mut stmt = con.prepare("SELECT name FROM pragma_table_info(?1)")?;
+		let cols = stmt
+			.query_map([table], |r| r.get::<_, String>(0))?
+			.collect::<core::result::Result<Vec<_>, _>>()?;
+
+		let exprs = cols
+			.iter()
+			.map(|col| match id_ref_table(table, col).and_then(|t| offsets.get(t)) {
+				Some(offset) => format!("{col} + {offset}"),
+				None => col.to_string(),
+			})
+			.collect::<Vec<_>>();
+
+		let where_clause = where_tpl.replace("{s}", "main").replace("{ids}", HIST_RUN_IDS);
+		let sql = format!(
+			"INSERT INTO {HIST_SCHEMA}.{table} ({}) SELECT {} FROM main.{table} WHERE {where_clause}",
+			cols.join(", "),
+			exprs.join(", ")
+		);
+		con.execute(&sql, [])?;
+	}
+
+	Ok(())
+}
+
+/// Delete the oldest top runs (and their sub runs) of the history db to keep only `max_root_runs`.
+fn trim_root_runs(con: &Connection, max_root_runs: usize) -> Result<()> {
+	fill_hist_run_ids(
+		con,
+		HIST_SCHEMA,
+		"SELECT id FROM hist.run WHERE parent_id IS NULL
+		   AND id NOT IN (SELECT id FROM hist.run WHERE parent_id IS NULL ORDER BY id DESC LIMIT ?1)",
+		[max_root_runs as i64],
+	)?;
+
+	// NOTE: Reverse order, so that the ucontent/inout are deleted before their pin/task
+	for (table, where_tpl) in HIST_TABLES.iter().rev() {
+		let where_clause = where_tpl.replace("{s}", HIST_SCHEMA).replace("{ids}", HIST_RUN_IDS);
+		con.execute(&format!("DELETE FROM {HIST_SCHEMA}.{table} WHERE {where_clause}"), [])?;
+	}
+
+	Ok(())
+}
+
+/// The table referenced by an id column (the `id` column references its own table), to offset the copied ids.
+pub(super) fn id_ref_table<'a>(table: &'a str, col: &str) -> Option<&'a str> {
+	match col {
+		"id" => Some(table),
+		"run_id" | "parent_id" => Some("run"),
+		"task_id" | "parent_task_id" => Some("task"),
+		"end_err_id" => Some("err"),
+		"ucontent_id" => Some("ucontent"),
+		_ => None,
+	}
+}
+
+/// Fill the `temp._hist_run_ids` with the runs (and their sub runs) of the `root_select` on the `schema` run table.
+pub(super) fn fill_hist_run_ids(con: &Connection, schema: &str, root_select: &str, params: [i64; 1]) -> Result<()> {
+	con.execute(
+		"CREATE TEMP TABLE IF NOT EXISTS _hist_run_ids (id INTEGER PRIMARY KEY)",
+		[],
+	)?;
+	con.execute("DELETE FROM temp._hist_run_ids", [])?;
+	let sql = format!(
+		"INSERT INTO temp._hist_run_ids (id)
+		 WITH RECURSIVE tree(id) AS (
+		   {root_select}
+		   UNION
+		   SELECT r.id FROM {schema}.run r JOIN tree ON r.parent_id = tree.id
+		 )
+		 SELECT id FROM tree"
+	);
+	con.execute(&sql, params)?;
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use crate::model::{LogBmc, LogForCreate, ModelManager, RunBmc, RunForCreate, TaskBmc, TaskForCreate};
+	use simple_fs::SPath;
+
+	fn run_c(parent_id: Option<crate::model::Id>, name: &str) -> RunForCreate {
+		RunForCreate {
+			parent_id,
+			parent_task_id: None,
+			agent_name: Some(name.to_string()),
+			agent_path: Some(format!("path/{name}")),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		}
+	}
+
+	#[tokio::test]
+	async fn test_model_db_history_copy_run_tree() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("tests-data/sandbox-01/.tmp/test_model_db_history/_history.db");
+		if path.exists() {
+			std::fs::remove_file(path.as_std_path())?;
+		}
+		let mm = ModelManager::new().await?;
+		let mut root_ids = Vec::new();
+		for i in 0..3 {
+			let root_id = RunBmc::create(&mm, run_c(None, &format!("root-{i}")))?;
+			let sub_id = RunBmc::create(&mm, run_c(Some(root_id), &format!("sub-{i}")))?;
+			TaskBmc::create(&mm, TaskForCreate::new(sub_id, 0, None, None))?;
+			LogBmc::create(
+				&mm,
+				LogForCreate {
+					run_id: root_id,
+					task_id: None,
+					kind: None,
+					step: None,
+					stage: None,
+					message: Some(format!("log-{i}")),
+				},
+			)?;
+			root_ids.push(root_id);
+		}
+
+		// -- Exec
+		for root_id in root_ids {
+			mm.db().copy_run_tree_to_file(root_id, &path, 2)?;
+		}
+
+		// -- Check
+		let hist_mm = ModelManager::new_history(&path)?;
+		let runs = RunBmc::list_for_display(&hist_mm, None)?;
+		let names = runs.iter().filter_map(|r| r.agent_name.as_deref()).collect::<Vec<_>>();
+		assert_eq!(names.len(), 4);
+		assert!(names.contains(&"root-2") && names.contains(&"sub-1"));
+		assert!(!names.contains(&"root-0") && !names.contains(&"sub-0"));
+		let sub_2 = runs
+			.iter()
+			.find(|r| r.agent_name.as_deref() == Some("sub-2"))
+			.ok_or("Should have sub-2")?;
+		let root_2 = runs
+			.iter()
+			.find(|r| r.agent_name.as_deref() == Some("root-2"))
+			.ok_or("Should have root-2")?;
+		assert_eq!(sub_2.parent_id, Some(root_2.id));
+		assert_eq!(TaskBmc::list_for_run(&hist_mm, sub_2.id)?.len(), 1);
+		let logs = LogBmc::list_for_run_only(&hist_mm, root_2.id)?;
+		assert_eq!(logs.first().and_then(|l| l.message.as_deref()), Some("log-2"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,419 @@
+use crate::model::db::Db;
+use crate::model::db::db_history::{HIST_RUN_IDS, HIST_TABLES, fill_hist_run_ids, id_ref_table};
+use crate::model::{Error, Id, Result};
+use rusqlite::Connection;
+use rusqlite::types::Value as SqlValue;
+use sqlx::postgres::{PgArguments, PgConnection, PgRow};
+use sqlx::query::Query;
+use sqlx::{Connection as _, Postgres, Row as _};
+
+// NOTE: The Postgres history has the tables of the runtime db history (same names and columns) in the `aipack_hist` schema.
+//       They are created (and their new columns added) from the runtime db schema, so that they follow its changes.
+//       As for the file history db, the runs are copied with new ids (appended after the existing ones).
+//       The runs also have the `hist_scope` column (the workspace/user that saved them), so that each aipack
+//       instance only trims its own runs of the shared history.
+
+const PG_SCHEMA: &str = "aipack_hist";
+
+/// The Postgres only column of the history `run` table (not loaded in the runtime db)
+const PG_SCOPE_COL: &str = "hist_scope";
+
+/// The advisory lock key of the history writes (so that the id offsets stay valid across the aipack instances)
+const PG_HIST_LOCK_KEY: i64 = 0x6169_7061_636b; // "aipack"
+
+/// A column of the runtime db, with its (STRICT) sqlite type
+struct Col {
+	name: String,
+	sqlite_type: String,
+}
+
+/// Constructor
+impl Db {
+	/// Load the Postgres runs history into a new runtime db (in memory), to browse the past runs.
+	pub fn new_from_pg_history(url: &str) -> Result<Self> {
+		let db = Db::new()?;
+		db.recreate()?;
+
+		let tables = {
+			let con = db.con.lock()?;
+			hist_table_cols(&con)?
+		};
+
+		let rows_by_table = block_on_pg(async {
+			let mut pg = connect(url).await?;
+			let mut tx = pg.begin().await?;
+			lock_and_ensure_schema(&mut tx, &tables).await?;
+
+			let mut rows_by_table = Vec::new();
+			for (table, cols) in tables.iter() {
+				let sql = format!(
+					"SELECT {} FROM {PG_SCHEMA}.{table} ORDER BY id",
+					cols.iter().map(|c| quote(&c.name)).collect::<Vec<_>>().join(", ")
+				);
+				let pg_rows = sqlx::query(&sql).fetch_all(&mut *tx).await?;
+				let rows = pg_rows.iter().map(|row| pg_row_values(row, cols)).collect::<Result<Vec<_>>>()?;
+				rows_by_table.push((*table, rows));
+			}
+			tx.commit().await?;
+
+			Ok(rows_by_table)
+		})?;
+
+		// -- Insert the rows in the runtime db
+		let con = db.con.lock()?;
+		for ((table, cols), (_, rows)) in tables.iter().zip(rows_by_table) {
+			let sql = format!(
+				"INSERT INTO main.{table} ({}) VALUES ({})",
+				cols.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", "),
+				vec!["?"; cols.len()].join(", ")
+			);
+			let mut stmt = con.prepare(&sql)?;
+			for row in rows {
+				stmt.execute(rusqlite::params_from_iter(row))?;
+			}
+		}
+		drop(con);
+
+		Ok(db)
+	}
+}
+
+/// History
+impl Db {
+	/// Copy the run and its sub runs (as `copy_run_tree_to_file`) into the Postgres history at `url`,
+	/// and keep only the `max_root_runs` last top runs of this `scope` (the workspace/user).
+	pub fn copy_run_tree_to_pg(&self, run_id: Id, url: &str, scope: &str, max_root_runs: usize) -> Result<()> {
+		// -- The rows of the run tree (in the runtime db)
+		let (tables, rows_by_table) = {
+			let con = self.con.lock()?;
+			let tables = hist_table_cols(&con)?;
+			fill_hist_run_ids(&con, "main", "SELECT ?1", [run_id.as_i64()])?;
+
+			let mut rows_by_table = Vec::new();
+			for ((table, cols), (_, where_tpl)) in tables.iter().zip(HIST_TABLES) {
+				let where_clause = where_tpl.replace("{s}", "main").replace("{ids}", HIST_RUN_IDS);
+				let sql = format!(
+					"SELECT {} FROM main.{table} WHERE {where_clause}",
+					cols.iter().map(|c| c.name.as_str()).collect::<Vec<_>>().join(", ")
+				);
+				let mut stmt = con.prepare(&sql)?;
+				let rows = stmt
+					.query_map([], |r| (0..cols.len()).map(|i| r.get::<_, SqlValue>(i)).collect())?
+					.collect::<core::result::Result<Vec<Vec<SqlValue>>, _>>()?;
+				rows_by_table.push(rows);
+			}
+
+			(tables, rows_by_table)
+		};
+
+		block_on_pg(async {
+			let mut pg = connect(url).await?;
+			let mut tx = pg.begin().await?;
+			lock_and_ensure_schema(&mut tx, &tables).await?;
+
+			// -- The id offsets (the history max ids, before any insert)
+			let mut offsets: Vec<(&str, i64)> = Vec::new();
+			for (table, _) in tables.iter() {
+				let sql = format!("SELECT COALESCE(MAX(id), 0) FROM {PG_SCHEMA}.{table}");
+				let max_id: i64 = sqlx::query_scalar(&sql).fetch_one(&mut *tx).await?;
+				offsets.push((table, max_id));
+			}
+			let offset_of = |table: &str| offsets.iter().find(|(t, _)| *t == table).map(|(_, offset)| *offset);
+
+			// -- Copy the rows (with the remapped ids)
+			for ((table, cols), rows) in tables.iter().zip(rows_by_table) {
+				let col_offsets: Vec<Option<i64>> = cols
+					.iter()
+					.map(|col| id_ref_table(table, &col.name).and_then(offset_of))
+					.collect();
+				let sql = format!(
+					"INSERT INTO {PG_SCHEMA}.{table} ({}) VALUES ({})",
+					cols.iter().map(|c| quote(&c.name)).collect::<Vec<_>>().join(", "),
+					(1..=cols.len()).map(|i| format!("${i}")).collect::<Vec<_>>().join(", ")
+				);
+				for row in rows {
+					let mut query = sqlx::query(&sql);
+					for ((col, value), offset) in cols.iter().zip(row).zip(&col_offsets) {
+						let value = match (value, offset) {
+							(SqlValue::Integer(id), Some(offset)) => SqlValue::Integer(id + offset),
+							(value, _) => value,
+						};
+						query = bind_value(query, col, value);
+					}
+					query.execute(&mut *tx).await?;
+				}
+			}
+
+			// -- Scope the copied runs (the new ids, after the run id offset)
+			let run_offset = offset_of("run").unwrap_or_default();
+			sqlx::query(&format!("UPDATE {PG_SCHEMA}.run SET {PG_SCOPE_COL} = $1 WHERE id > $2"))
+				.bind(scope)
+				.bind(run_offset)
+				.execute(&mut *tx)
+				.await?;
+
+			trim_pg_root_runs(&mut tx, scope, max_root_runs).await?;
+			tx.commit().await?;
+
+			Ok(())
+		})
+	}
+}
+
+// region:    --- Support
+
+/// The columns of the history tables (in the `HIST_TABLES` order), from the runtime db schema.
+fn hist_table_cols(con: &Connection) -> Result<Vec<(&'static str, Vec<Col>)>> {
+	let mut tables = Vec::new();
+	for (table, _) in HIST_TABLES {
+		let mut stmt = con.prepare("SELECT name, type FROM pragma_table_info(?1)")?;
+		let cols = stmt
+			.query_map([table], |r| {
+				Ok(Col {
+					name: r.get(0)?,
+					sqlite_type: r.get::<_, String>(1)?.to_uppercase(),
+				})
+			})?
+			.collect::<core::result::Result<Vec<_>, _>>()?;
+		tables.push((*table, cols));
+	}
+	Ok(tables)
+}
+
+/// Take the history lock (until the end of the transaction), and create the missing schema, tables, and columns.
+async fn lock_and_ensure_schema(pg: &mut PgConnection, tables: &[(&str, Vec<Col>)]) -> Result<()> {
+	sqlx::query("SELECT pg_advisory_xact_lock($1)")
+		.bind(PG_HIST_LOCK_KEY)
+		.execute(&mut *pg)
+		.await?;
+
+	sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {PG_SCHEMA}"))
+		.execute(&mut *pg)
+		.await?;
+	for (table, cols) in tables {
+		let sql = format!("CREATE TABLE IF NOT EXISTS {PG_SCHEMA}.{table} (id BIGINT PRIMARY KEY)");
+		sqlx::query(&sql).execute(&mut *pg).await?;
+		for col in cols.iter().filter(|c| c.name != "id") {
+			let sql = format!(
+				"ALTER TABLE {PG_SCHEMA}.{table} ADD COLUMN IF NOT EXISTS {} {}",
+				quote(&col.name),
+				pg_type(&col.sqlite_type)
+			);
+			sqlx::query(&sql).execute(&mut *pg).await?;
+		}
+	}
+	let sql = format!("ALTER TABLE {PG_SCHEMA}.run ADD COLUMN IF NOT EXISTS {PG_SCOPE_COL} TEXT");
+	sqlx::query(&sql).execute(&mut *pg).await?;
+
+	Ok(())
+}
+
+/// Delete the oldest top runs (and their sub runs) of this scope in the Postgres history to keep only `max_root_runs`.
+/// NOTE: The runs of the other scopes (workspaces/users) are not touched.
+async fn trim_pg_root_runs(pg: &mut PgConnection, scope: &str, max_root_runs: usize) -> Result<()> {
+	let sql = format!(
+		"WITH RECURSIVE tree(id) AS (
+		   SELECT id FROM {PG_SCHEMA}.run WHERE parent_id IS NULL AND {PG_SCOPE_COL} = $2
+		     AND id NOT IN (SELECT id FROM {PG_SCHEMA}.run WHERE parent_id IS NULL AND {PG_SCOPE_COL} = $2
+		                    ORDER BY id DESC LIMIT $1)
+		   UNION
+		   SELECT r.id FROM {PG_SCHEMA}.run r JOIN tree ON r.parent_id = tree.id
+		 )
+		 SELECT id FROM tree"
+	);
+	let run_ids: Vec<i64> = sqlx::query_scalar(&sql)
+		.bind(max_root_runs as i64)
+		.bind(scope)
+		.fetch_all(&mut *pg)
+		.await?;
+	if run_ids.is_empty() {
+		return Ok(());
+	}
+
+	// NOTE: Reverse order, so that the ucontent/inout are deleted before their pin/task
+	for (table, where_tpl) in HIST_TABLES.iter().rev() {
+		let where_clause = where_tpl
+			.replace("{s}", PG_SCHEMA)
+			.replace("{ids}", "(SELECT UNNEST($1::BIGINT[]))");
+		sqlx::query(&format!("DELETE FROM {PG_SCHEMA}.{table} WHERE {where_clause}"))
+			.bind(&run_ids)
+			.execute(&mut *pg)
+			.await?;
+	}
+
+	Ok(())
+}
+
+async fn connect(url: &str) -> Result<PgConnection> {
+	// NOTE: The url is not in the error, as it can have the password
+	PgConnection::connect(url)
+		.await
+		.map_err(|err| Error::cc("Cannot connect to the Postgres runs history", err))
+}
+
+/// Run the Postgres calls on their own thread and runtime
+/// (the history calls are sync, and can come from an async context).
+fn block_on_pg<T: Send>(fut: impl Future<Output = Result<T>> + Send) -> Result<T> {
+	std::thread::scope(|scope| {
+		scope
+			.spawn(|| {
+				let rt = tokio::runtime::Builder::new_current_thread()
+					.enable_all()
+					.build()
+					.map_err(|err| Error::cc("Cannot start the Postgres history runtime", err))?;
+				rt.block_on(fut)
+			})
+			.join()
+			.map_err(|_| Error::custom("Postgres history thread panicked"))?
+	})
+}
+
+fn pg_type(sqlite_type: &str) -> &'static str {
+	match sqlite_type {
+		"INTEGER" => "BIGINT",
+		"REAL" => "DOUBLE PRECISION",
+		"BLOB" => "BYTEA",
+		_ => "TEXT",
+	}
+}
+
+/// Bind a runtime db value with the Postgres type of its column (the nulls are typed too).
+fn bind_value<'q>(
+	query: Query<'q, Postgres, PgArguments>,
+	col: &Col,
+	value: SqlValue,
+) -> Query<'q, Postgres, PgArguments> {
+	match (col.sqlite_type.as_str(), value) {
+		("INTEGER", SqlValue::Integer(v)) => query.bind(Some(v)),
+		("INTEGER", _) => query.bind(None::<i64>),
+		("REAL", SqlValue::Real(v)) => query.bind(Some(v)),
+		("REAL", SqlValue::Integer(v)) => query.bind(Some(v as f64)),
+		("REAL", _) => query.bind(None::<f64>),
+		("BLOB", SqlValue::Blob(v)) => query.bind(Some(v)),
+		("BLOB", _) => query.bind(None::<Vec<u8>>),
+		(_, SqlValue::Text(v)) => query.bind(Some(v)),
+		(_, SqlValue::Integer(v)) => query.bind(Some(v.to_string())),
+		(_, SqlValue::Real(v)) => query.bind(Some(v.to_string())),
+		(_, _) => query.bind(None::<String>),
+	}
+}
+
+/// The values of a Postgres history row, as runtime db values (by the column types).
+fn pg_row_values(row: &PgRow, cols: &[Col]) -> Result<Vec<SqlValue>> {
+	cols.iter()
+		.enumerate()
+		.map(|(i, col)| {
+			let value = match col.sqlite_type.as_str() {
+				"INTEGER" => row.try_get::<Option<i64>, _>(i)?.map(SqlValue::Integer),
+				"REAL" => row.try_get::<Option<f64>, _>(i)?.map(SqlValue::Real),
+				"BLOB" => row.try_get::<Option<Vec<u8>>, _>(i)?.map(SqlValue::Blob),
+				_ => row.try_get::<Option<String>, _>(i)?.map(SqlValue::Text),
+			};
+			Ok(value.unwrap_or(SqlValue::Null))
+		})
+		.collect()
+}
+
+/// Quote a column name (e.g., `end` is a Postgres keyword)
+fn quote(name: &str) -> String {
+	format!("\"{name}\"")
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::model::{LogBmc, LogForCreate, ModelManager, RunBmc, RunForCreate, TaskBmc, TaskForCreate};
+
+	/// The url of a throwaway Postgres database (its `aipack_hist` schema is dropped)
+	const TEST_PG_URL_ENV: &str = "AIPACK_TEST_PG_URL";
+
+	fn run_c(parent_id: Option<Id>, name: &str) -> RunForCreate {
+		RunForCreate {
+			parent_id,
+			parent_task_id: None,
+			agent_name: Some(name.to_string()),
+			agent_path: Some(format!("path/{name}")),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		}
+	}
+
+	#[tokio::test]
+	#[ignore = "requires a throwaway Postgres database url in AIPACK_TEST_PG_URL"]
+	async fn test_model_db_history_pg_copy_run_tree() -> Result<()> {
+		// -- Setup & Fixtures
+		let url = std::env::var(TEST_PG_URL_ENV).map_err(|_| format!("{TEST_PG_URL_ENV} must be set"))?;
+		block_on_pg(async {
+			let mut pg = connect(&url).await?;
+			sqlx::query(&format!("DROP SCHEMA IF EXISTS {PG_SCHEMA} CASCADE"))
+				.execute(&mut pg)
+				.await?;
+			Ok(())
+		})?;
+		let mm = ModelManager::new().await?;
+		let other_root_id = RunBmc::create(&mm, run_c(None, "other-root"))?;
+		mm.db().copy_run_tree_to_pg(other_root_id, &url, "user-b:/wks-b", 1)?;
+		let mut root_ids = Vec::new();
+		for i in 0..3 {
+			let root_id = RunBmc::create(&mm, run_c(None, &format!("root-{i}")))?;
+			let sub_id = RunBmc::create(&mm, run_c(Some(root_id), &format!("sub-{i}")))?;
+			TaskBmc::create(&mm, TaskForCreate::new(sub_id, 0, None, None))?;
+			LogBmc::create(
+				&mm,
+				LogForCreate {
+					run_id: root_id,
+					task_id: None,
+					kind: None,
+					step: None,
+					stage: None,
+					message: Some(format!("log-{i}")),
+				},
+			)?;
+			root_ids.push(root_id);
+		}
+
+		// -- Exec
+		for root_id in root_ids {
+			mm.db().copy_run_tree_to_pg(root_id, &url, "user-a:/wks-a", 2)?;
+		}
+
+		// -- Check
+		let hist_mm = ModelManager::from_db(Db::new_from_pg_history(&url)?);
+		let runs = RunBmc::list_for_display(&hist_mm, None)?;
+		let names = runs.iter().filter_map(|r| r.agent_name.as_deref()).collect::<Vec<_>>();
+		assert_eq!(names.len(), 5);
+		assert!(names.contains(&"root-2") && names.contains(&"sub-1"));
+		assert!(!names.contains(&"root-0") && !names.contains(&"sub-0"));
+		assert!(
+			names.contains(&"other-root"),
+			"The runs of the other scope should not be trimmed"
+		);
+		let sub_2 = runs
+			.iter()
+			.find(|r| r.agent_name.as_deref() == Some("sub-2"))
+			.ok_or("Should have sub-2")?;
+		let root_2 = runs
+			.iter()
+			.find(|r| r.agent_name.as_deref() == Some("root-2"))
+			.ok_or("Should have root-2")?;
+		assert_eq!(sub_2.parent_id, Some(root_2.id));
+		let src_sub_2 = RunBmc::list_for_display(&mm, None)?
+			.into_iter()
+			.find(|r| r.agent_name.as_deref() == Some("sub-2"))
+			.ok_or("Should have source sub-2")?;
+		assert_eq!(sub_2.uid, src_sub_2.uid);
+		assert_eq!(TaskBmc::list_for_run(&hist_mm, sub_2.id)?.len(), 1);
+		let logs = LogBmc::list_for_run_only(&hist_mm, root_2.id)?;
+		assert_eq!(logs.first().and_then(|l| l.message.as_deref()), Some("log-2"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
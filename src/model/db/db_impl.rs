@@ -7,7 +7,7 @@ use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct Db {
-	con: Arc<Mutex<Connection>>,
+	pub(super) con: Arc<Mutex<Connection>>,
 }
 
 pub struct DbTx<'a> {
@@ -0,0 +1,183 @@
+use crate::model::db::Db;
+use crate::model::{Id, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
+use rusqlite::Connection;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use serde_json::{Map, Value, json};
+
+// NOTE: The rows as JSON objects (column name to value), e.g., for the hub event records (see `HubRecorder`).
+//       The BLOB values (e.g., the uids) are `{"blob": "<base64>"}`.
+
+pub type JsonRow = Map<String, Value>;
+
+/// JSON Rows
+impl Db {
+	/// The rows of the table, by `id` when given, otherwise by `run_id` when given (and the table has it),
+	/// otherwise all the rows.
+	///
+	/// NOTE: The table must be a trusted name (e.g., `EntityType::table()`).
+	pub fn fetch_json_rows(&self, table: &str, id: Option<Id>, run_id: Option<Id>) -> Result<Vec<JsonRow>> {
+		let con = self.con.lock()?;
+		let cols = table_columns(&con, table)?;
+
+		let (where_clause, param) = match (id, run_id) {
+			(Some(id), _) => ("WHERE id = ?1", Some(id)),
+			(None, Some(run_id)) if cols.iter().any(|c| c == "run_id") => ("WHERE run_id = ?1", Some(run_id)),
+			_ => ("", None),
+		};
+		let sql = format!("SELECT * FROM {table} {where_clause}");
+
+		let mut stmt = con.prepare(&sql)?;
+		let to_json_row = |row: &rusqlite::Row| -> rusqlite::Result<JsonRow> {
+			let mut json_row = JsonRow::new();
+			for (idx, col) in cols.iter().enumerate() {
+				json_row.insert(col.clone(), value_ref_to_json(row.get_ref(idx)?));
+			}
+			Ok(json_row)
+		};
+		let rows = match param {
+			Some(param) => stmt.query_map([param.as_i64()], to_json_row)?,
+			None => stmt.query_map([], to_json_row)?,
+		};
+
+		Ok(rows.collect::<core::result::Result<Vec<_>, _>>()?)
+	}
+
+	/// Insert the rows, or update them when their id exists.
+	///
+	/// The keys not columns of the table are ignored.
+	pub fn upsert_json_rows(&self, table: &str, rows: &[JsonRow]) -> Result<()> {
+		let con = self.con.lock()?;
+		let cols = table_columns(&con, table)?;
+
+		for row in rows {
+			let row_cols: Vec<&String> = cols.iter().filter(|c| row.contains_key(c.as_str())).collect();
+			if row_cols.is_empty() {
+				continue;
+			}
+			let placeholders = (1..=row_cols.len()).map(|i| format!("?{i}")).collect::<Vec<_>>();
+			let setters = row_cols
+				.iter()
+				.filter(|c| c.as_str() != "id")
+				.map(|c| format!("{c} = excluded.{c}"))
+				.collect::<Vec<_>>();
+			let on_conflict = if setters.is_empty() {
+				"DO NOTHING".to_string()
+			} else {
+				format!("DO UPDATE SET {}", setters.join(", "))
+			};
+			let sql = format!(
+				"INSERT INTO {table} ({}) VALUES ({}) ON CONFLICT(id) {on_conflict}",
+				row_cols.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", "),
+				placeholders.join(", ")
+			);
+
+			let values: Vec<SqlValue> = row_cols.iter().map(|c| json_to_sql_value(&row[c.as_str()])).collect();
+			con.execute(&sql, rusqlite::params_from_iter(values))?;
+		}
+
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+fn table_columns(con: &Connection, table: &str) -> Result<Vec<String>> {
+	let mut stmt = con.prepare("SELECT name FROM pragma_table_info(?1)")?;
+	let cols = stmt
+		.query_map([table], |r| r.get::<_, String>(0))?
+		.collect::<core::result::Result<Vec<_>, _>>()?;
+	if cols.is_empty() {
+		return Err(format!("No table '{table}'").into());
+	}
+	Ok(cols)
+}
+
+fn value_ref_to_json(value: ValueRef) -> Value {
+	match value {
+		ValueRef::Null => Value::Null,
+		ValueRef::Integer(v) => json!(v),
+		ValueRef::Real(v) => json!(v),
+		ValueRef::Text(v) => Value::String(String::from_utf8_lossy(v).into_owned()),
+		ValueRef::Blob(v) => json!({"blob": B64.encode(v)}),
+	}
+}
+
+fn json_to_sql_value(value: &Value) -> SqlValue {
+	match value {
+		Value::Null => SqlValue::Null,
+		Value::Bool(v) => SqlValue::Integer(i64::from(*v)),
+		Value::Number(v) => match v.as_i64() {
+			Some(v) => SqlValue::Integer(v),
+			None => SqlValue::Real(v.as_f64().unwrap_or_default()),
+		},
+		Value::String(v) => SqlValue::Text(v.clone()),
+		Value::Object(obj) => match obj.get("blob").and_then(|b| b.as_str()).map(|b| B64.decode(b)) {
+			Some(Ok(blob)) => SqlValue::Blob(blob),
+			_ => SqlValue::Text(value.to_string()),
+		},
+		Value::Array(_) => SqlValue::Text(value.to_string()),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use crate::model::{ModelManager, RunBmc, RunForCreate, RunForUpdate, TaskBmc, TaskForCreate};
+
+	#[tokio::test]
+	async fn test_model_db_rows_json_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_id = RunBmc::create(
+			&mm,
+			RunForCreate {
+				parent_id: None,
+				parent_task_id: None,
+				agent_name: Some("my-agent".to_string()),
+				agent_path: Some("path/my-agent.aip".to_string()),
+				has_task_stages: None,
+				has_prompt_parts: None,
+			},
+		)?;
+		TaskBmc::create(&mm, TaskForCreate::new(run_id, 0, None, None))?;
+		TaskBmc::create(&mm, TaskForCreate::new(run_id, 1, None, None))?;
+		let replay_mm = ModelManager::new().await?;
+
+		// -- Exec
+		let run_rows = mm.db().fetch_json_rows("run", Some(run_id), None)?;
+		let task_rows = mm.db().fetch_json_rows("task", None, Some(run_id))?;
+		replay_mm.db().upsert_json_rows("run", &run_rows)?;
+		replay_mm.db().upsert_json_rows("task", &task_rows)?;
+		// the update of an existing row
+		RunBmc::update(
+			&mm,
+			run_id,
+			RunForUpdate {
+				label: Some("Updated".to_string()),
+				..Default::default()
+			},
+		)?;
+		let run_rows = mm.db().fetch_json_rows("run", Some(run_id), None)?;
+		replay_mm.db().upsert_json_rows("run", &run_rows)?;
+
+		// -- Check
+		assert_eq!(task_rows.len(), 2);
+		assert!(run_rows[0]["uid"]["blob"].is_string());
+		let run = RunBmc::get(&mm, run_id)?;
+		let replay_run = RunBmc::get(&replay_mm, run_id)?;
+		assert_eq!(replay_run.uid, run.uid);
+		assert_eq!(replay_run.label.as_deref(), Some("Updated"));
+		assert_eq!(TaskBmc::list_for_run(&replay_mm, run_id)?.len(), 2);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
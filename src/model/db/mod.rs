@@ -2,8 +2,12 @@
 
 mod rt_db_setup;
 
+mod db_history;
+mod db_history_pg;
 mod db_impl;
+mod db_rows;
 
 pub use db_impl::*;
+pub use db_rows::*;
 
 // endregion: --- Modules
@@ -28,6 +28,7 @@ CREATE TABLE IF NOT EXISTS run (
 		label       TEXT,	-- Only when agent call. aip.task.set_label('some label')
 
 		parent_id   INTEGER,
+		parent_task_id INTEGER, -- The parent run task calling this sub agent (when from a task stage)
 
 		ctime  INTEGER NOT NULL,
 		mtime  INTEGER NOT NULL,
@@ -58,8 +59,20 @@ CREATE TABLE IF NOT EXISTS run (
 
 		-- Computed
 		total_cost    REAL,
+		cost_embed    REAL, -- embeddings outside of the tasks (e.g., before all)
 		total_task_ms INTEGER, -- cummulative time
-		flow_redo_count INTEGER
+		flow_redo_count INTEGER,
+
+		redo_of_run_uid BLOB, -- The run this run redoes some tasks of (TUI task redo)
+
+		-- Resource usage (aip.cmd.exec processes, and aip.file content bytes)
+		res_cpu_ms      INTEGER,
+		res_peak_mem    INTEGER, -- bytes
+		res_read_bytes  INTEGER,
+		res_write_bytes INTEGER,
+
+		-- The hash of the agent content (agent option `incremental`)
+		agent_hash      TEXT
 
 ) STRICT",
 );
@@ -100,6 +113,7 @@ CREATE TABLE IF NOT EXISTS task (
 		-- Model
 		model_ov         TEXT,
 		model_upstream   TEXT,    -- from te provider
+		finish_reason    TEXT,    -- normalized (e.g., completed, max_tokens, content_filter)
 
 		-- Model Pricing
 		pricing_model         TEXT,
@@ -120,7 +134,24 @@ CREATE TABLE IF NOT EXISTS task (
 		cost_cache_write    REAL,
 		cost_cache_saving   REAL,
 
+		-- Embeddings (aip.embed)
+		tk_embed_total      INTEGER,
+		cost_embed          REAL,
+
 		label               TEXT,
+		ui_cols             TEXT, -- json object, the TUI display columns (from the data stage `_ui.cols`)
+
+		-- Progress (aip.task.set_progress)
+		progress            REAL, -- 0.0 to 1.0
+		progress_msg        TEXT,
+
+		-- Running command tail (aip.cmd.exec)
+		cmd_line            TEXT,
+		cmd_tail            TEXT, -- the last stdout/stderr lines (with the ANSI codes)
+		cmd_running         INTEGER,
+
+		-- Streaming AI response (chat follow-ups from the TUI)
+		ai_stream           TEXT,
 
 		input_uid           BLOB,
 		input_short         TEXT,
@@ -128,7 +159,17 @@ CREATE TABLE IF NOT EXISTS task (
 
 		output_uid          BLOB,
 		output_short        TEXT,
-		output_has_display  INTEGER
+		output_has_display  INTEGER,
+
+		-- The rendered prompt (text, in the inout table)
+		prompt_uid          BLOB,
+
+		-- The task this task is a new attempt of (TUI task redo)
+		redo_of_task_uid    BLOB,
+
+		-- The hash of the input (agent option `incremental`), and if skipped as unchanged since the last run
+		input_hash          TEXT,
+		cached              INTEGER
 
 ) STRICT",
 );
@@ -271,6 +312,25 @@ CREATE TABLE IF NOT EXISTS work (
 ) STRICT",
 );
 
+/// The run conversation messages (multi-turn agents)
+const CONV_MSG_TABLE: (&str, &str) = (
+	"conv_msg",
+	"
+CREATE TABLE IF NOT EXISTS conv_msg (
+		id        INTEGER PRIMARY KEY AUTOINCREMENT,
+		uid       BLOB NOT NULL,
+
+		ctime     INTEGER NOT NULL,
+		mtime     INTEGER NOT NULL,
+
+		run_id    INTEGER NOT NULL, -- Should always belong to a run
+		task_id   INTEGER,          -- The task adding it (none when added from the run stages)
+
+		role      TEXT,             -- system, user, assistant
+		content   TEXT
+) STRICT",
+);
+
 const ALL_MAIN_TABLES: &[(&str, &str)] = &[
 	RUN_TABLE,
 	TASK_TABLE,
@@ -280,6 +340,7 @@ const ALL_MAIN_TABLES: &[(&str, &str)] = &[
 	PIN_TABLE,
 	UCONTENT_TABLE,
 	WORK_TABLE,
+	CONV_MSG_TABLE,
 ];
 
 // endregion: --- Main Tables
@@ -340,6 +401,37 @@ CREATE INDEX IF NOT EXISTS idx_ucontent_hash ON ucontent(hash);
 		(),
 	)?;
 
+	add_missing_columns(con)?;
+
+	Ok(())
+}
+
+/// The columns added after the table creation (table, column, type),
+/// so that the existing file dbs (e.g., the runs history) get them.
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+	("run", "cost_embed", "REAL"),
+	("task", "tk_embed_total", "INTEGER"),
+	("task", "cost_embed", "REAL"),
+	("task", "ai_stream", "TEXT"),
+	("run", "res_cpu_ms", "INTEGER"),
+	("run", "res_peak_mem", "INTEGER"),
+	("run", "res_read_bytes", "INTEGER"),
+	("run", "res_write_bytes", "INTEGER"),
+	("run", "agent_hash", "TEXT"),
+	("task", "input_hash", "TEXT"),
+	("task", "cached", "INTEGER"),
+];
+
+fn add_missing_columns(con: &Connection) -> Result<()> {
+	let mut stmt = con.prepare("SELECT name FROM pragma_table_info(?1)")?;
+	for (table, column, col_type) in ADDED_COLUMNS {
+		let cols = stmt
+			.query_map([table], |r| r.get::<_, String>(0))?
+			.collect::<core::result::Result<Vec<_>, _>>()?;
+		if !cols.iter().any(|col| col == column) {
+			con.execute(&format!("ALTER TABLE {table} ADD COLUMN {column} {col_type}"), ())?;
+		}
+	}
 	Ok(())
 }
 
@@ -0,0 +1,154 @@
+use crate::hub::get_hub;
+use crate::model::base::{self, DbBmc};
+use crate::model::{EntityAction, EntityType, EpochUs, Id, ModelEvent, ModelManager, RelIds, Result};
+use modql::SqliteFromRow;
+use modql::field::{Fields, HasSqliteFields};
+use modql::filter::ListOptions;
+use uuid::Uuid;
+
+// region:    --- Types
+
+/// A message of the run conversation (multi-turn agents, with the agent option `conversation = true`,
+/// or `aip.run.append_message`)
+#[derive(Debug, Clone, Fields, SqliteFromRow)]
+pub struct ConvMsg {
+	pub id: Id,
+	pub uid: Uuid,
+
+	pub ctime: EpochUs,
+	pub mtime: EpochUs,
+
+	// Foreign keys
+	pub run_id: Id,
+	pub task_id: Option<Id>,
+
+	/// `system`, `user`, or `assistant`
+	pub role: Option<String>,
+
+	pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Fields, SqliteFromRow)]
+pub struct ConvMsgForCreate {
+	pub run_id: Id,
+	pub task_id: Option<Id>,
+
+	pub role: String,
+	pub content: String,
+}
+
+#[derive(Debug, Default, Clone, Fields, SqliteFromRow)]
+pub struct ConvMsgFilter {
+	pub run_id: Option<Id>,
+	pub task_id: Option<Id>,
+}
+
+// endregion: --- Types
+
+// region:    --- Bmc
+
+pub struct ConvMsgBmc;
+
+impl DbBmc for ConvMsgBmc {
+	const TABLE: &'static str = "conv_msg";
+	const ENTITY_TYPE: EntityType = EntityType::ConvMsg;
+}
+
+impl ConvMsgBmc {
+	pub fn create(mm: &ModelManager, conv_msg_c: ConvMsgForCreate) -> Result<Id> {
+		let rel_ids = RelIds {
+			run_id: Some(conv_msg_c.run_id),
+			task_id: conv_msg_c.task_id,
+			..Default::default()
+		};
+		let fields = conv_msg_c.sqlite_not_none_fields();
+		let id = base::create::<Self>(mm, fields)?;
+
+		get_hub().publish_sync(ModelEvent {
+			entity: EntityType::ConvMsg,
+			action: EntityAction::Created,
+			id: Some(id),
+			rel_ids,
+		});
+
+		Ok(id)
+	}
+
+	#[allow(unused)]
+	pub fn get(mm: &ModelManager, id: Id) -> Result<ConvMsg> {
+		base::get::<Self, _>(mm, id)
+	}
+
+	/// Returns the conversation of the run, in order.
+	pub fn list_for_run(mm: &ModelManager, run_id: Id) -> Result<Vec<ConvMsg>> {
+		let list_options = ListOptions::from_order_bys("id");
+		let filter = ConvMsgFilter {
+			run_id: Some(run_id),
+			..Default::default()
+		};
+		let filter_fields = Some(filter.sqlite_not_none_fields());
+		base::list::<Self, _>(mm, Some(list_options), filter_fields)
+	}
+}
+
+// endregion: --- Bmc
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::model::{RunBmc, RunForCreate};
+
+	#[tokio::test]
+	async fn test_model_conv_msg_bmc_list_for_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			parent_task_id: None,
+			agent_name: Some("chat".to_string()),
+			agent_path: None,
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let run_id = RunBmc::create(&mm, run_c.clone())?;
+		let other_run_id = RunBmc::create(&mm, run_c)?;
+		for (run_id, role, content) in [
+			(run_id, "user", "Hello"),
+			(other_run_id, "user", "Other"),
+			(run_id, "assistant", "Hi!"),
+		] {
+			ConvMsgBmc::create(
+				&mm,
+				ConvMsgForCreate {
+					run_id,
+					task_id: None,
+					role: role.to_string(),
+					content: content.to_string(),
+				},
+			)?;
+		}
+
+		// -- Exec
+		let msgs = ConvMsgBmc::list_for_run(&mm, run_id)?;
+
+		// -- Check
+		let msgs: Vec<(&str, &str)> = msgs
+			.iter()
+			.map(|msg| {
+				(
+					msg.role.as_deref().unwrap_or_default(),
+					msg.content.as_deref().unwrap_or_default(),
+				)
+			})
+			.collect();
+		assert_eq!(msgs, vec![("user", "Hello"), ("assistant", "Hi!")]);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -42,6 +42,8 @@ pub enum LogKind {
 	SysDebug,
 	AgentPrint,
 	AgentSkip,
+	/// Message is the JSON of a `uc::TimelineMarker`
+	AgentMarker,
 }
 
 impl Log {
@@ -213,6 +215,7 @@ mod tests {
 	async fn create_run(mm: &ModelManager, label: &str) -> Result<Id> {
 		let run_c = RunForCreate {
 			parent_id: None,
+			parent_task_id: None,
 			agent_name: Some(label.to_string()),
 			agent_path: Some(format!("path/{label}")),
 			has_task_stages: None,
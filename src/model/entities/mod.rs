@@ -1,5 +1,6 @@
 // region:    --- Modules
 
+mod conv_msg;
 mod err;
 mod inout;
 mod log;
@@ -11,6 +12,7 @@ mod ucontent;
 mod work;
 mod work_data;
 
+pub use conv_msg::*;
 pub use err::*;
 pub use inout::*;
 pub use log::*;
@@ -15,6 +15,8 @@ pub struct Run {
 	pub label: Option<String>,
 
 	pub parent_id: Option<Id>,
+	/// The parent run task calling this sub agent run (if called from a task stage)
+	pub parent_task_id: Option<Id>,
 
 	pub ctime: EpochUs,
 	pub mtime: EpochUs,
@@ -49,8 +51,22 @@ pub struct Run {
 	pub concurrency: Option<i32>,
 
 	pub total_cost: Option<f64>,
+	pub cost_embed: Option<f64>,
 	pub total_task_ms: Option<i64>,
 	pub flow_redo_count: Option<i32>,
+
+	/// The run this run redoes some tasks of (see `TaskRedo`)
+	pub redo_of_run_uid: Option<Uuid>,
+
+	// -- Resource usage (see `ResUsage`)
+	pub res_cpu_ms: Option<i64>,
+	/// The max resident memory of the spawned processes, in bytes
+	pub res_peak_mem: Option<i64>,
+	pub res_read_bytes: Option<i64>,
+	pub res_write_bytes: Option<i64>,
+
+	/// The hash of the agent content (see `Incremental`)
+	pub agent_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Fields, SqliteFromRow)]
@@ -75,6 +91,7 @@ impl Run {
 #[derive(Debug, Clone, Fields, SqliteFromRow)]
 pub struct RunForCreate {
 	pub parent_id: Option<Id>,
+	pub parent_task_id: Option<Id>,
 
 	pub agent_name: Option<String>,
 	pub agent_path: Option<String>,
@@ -116,8 +133,22 @@ pub struct RunForUpdate {
 	pub concurrency: Option<i32>,
 
 	pub total_cost: Option<f64>,
+	pub cost_embed: Option<f64>,
 	pub total_task_ms: Option<i64>,
 	pub flow_redo_count: Option<i32>,
+
+	/// The run this run redoes some tasks of (see `TaskRedo`)
+	pub redo_of_run_uid: Option<Uuid>,
+
+	// -- Resource usage (see `ResUsage`)
+	pub res_cpu_ms: Option<i64>,
+	/// The max resident memory of the spawned processes, in bytes
+	pub res_peak_mem: Option<i64>,
+	pub res_read_bytes: Option<i64>,
+	pub res_write_bytes: Option<i64>,
+
+	/// The hash of the agent content (see `Incremental`)
+	pub agent_hash: Option<String>,
 }
 
 // endregion: --- Types
@@ -189,6 +220,45 @@ impl RunBmc {
 		Ok(RunForUids { id, uid, parent_uid })
 	}
 
+	/// Returns the uid of the root run (this run when it is not a sub agent run)
+	pub fn get_root_uid(mm: &ModelManager, id: Id) -> Result<Uuid> {
+		let mut run = base::get::<Self, RunForIds>(mm, id)?;
+		while let Some(parent_id) = run.parent_id {
+			run = base::get::<Self, RunForIds>(mm, parent_id)?;
+		}
+		Ok(run.uid)
+	}
+
+	/// Returns the sub agent runs of this run (not recursive), by id
+	/// NOTE: For now, doing it manually, until modql support those for sqlite for filters
+	pub fn list_for_parent(mm: &ModelManager, parent_id: Id) -> Result<Vec<Run>> {
+		let sql = format!(
+			"SELECT {} FROM {} WHERE parent_id = ? ORDER BY id",
+			Run::sqlite_columns_for_select(),
+			Self::table_ref(),
+		);
+
+		let db = mm.db();
+		let entities: Vec<Run> = db.fetch_all(&sql, (parent_id,))?;
+
+		Ok(entities)
+	}
+
+	/// Returns the last top run of the agent (by agent path) that ended `Ok`, if any
+	/// (e.g., from the runs history db, for the agent option `input_filter`).
+	pub fn last_ok_top_run_for_agent(mm: &ModelManager, agent_path: &str) -> Result<Option<Run>> {
+		let sql = format!(
+			"SELECT {} FROM {} WHERE parent_id IS NULL AND agent_path = ? AND end_state = 'Ok' ORDER BY id DESC LIMIT 1",
+			Run::sqlite_columns_for_select(),
+			Self::table_ref(),
+		);
+
+		let db = mm.db();
+		let mut entities: Vec<Run> = db.fetch_all(&sql, (agent_path,))?;
+
+		Ok(entities.pop())
+	}
+
 	pub fn list_for_display(mm: &ModelManager, limit: Option<i64>) -> Result<Vec<Run>> {
 		let mut options = ListOptions::from_order_bys("!id");
 		if let Some(limit) = limit {
@@ -245,6 +315,7 @@ mod tests {
 			agent_name: Some("Test Run".to_string()),
 			agent_path: Some("test/path".to_string()),
 			parent_id: None,
+			parent_task_id: None,
 			has_task_stages: None,
 			has_prompt_parts: None,
 		};
@@ -264,6 +335,7 @@ mod tests {
 		let mm = ModelManager::new().await?;
 		let run_c = RunForCreate {
 			parent_id: None,
+			parent_task_id: None,
 			agent_name: Some("Test Run".to_string()),
 			agent_path: Some("test/path".to_string()),
 			has_task_stages: None,
@@ -285,6 +357,49 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_model_run_bmc_last_ok_top_run_for_agent() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_c = |parent_id: Option<Id>, agent_path: &str| RunForCreate {
+			parent_id,
+			parent_task_id: None,
+			agent_name: None,
+			agent_path: Some(agent_path.to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let end_ok = RunForUpdate {
+			end_state: Some(EndState::Ok),
+			..Default::default()
+		};
+		let ok_id = RunBmc::create(&mm, run_c(None, "agent-a"))?;
+		RunBmc::update(&mm, ok_id, end_ok.clone())?;
+		let sub_id = RunBmc::create(&mm, run_c(Some(ok_id), "agent-a"))?;
+		RunBmc::update(&mm, sub_id, end_ok.clone())?;
+		let err_id = RunBmc::create(&mm, run_c(None, "agent-a"))?;
+		RunBmc::update(
+			&mm,
+			err_id,
+			RunForUpdate {
+				end_state: Some(EndState::Err),
+				..Default::default()
+			},
+		)?;
+		let other_id = RunBmc::create(&mm, run_c(None, "agent-b"))?;
+		RunBmc::update(&mm, other_id, end_ok)?;
+
+		// -- Exec
+		let run = RunBmc::last_ok_top_run_for_agent(&mm, "agent-a")?;
+		let none_run = RunBmc::last_ok_top_run_for_agent(&mm, "agent-c")?;
+
+		// -- Check
+		assert_eq!(run.map(|r| r.id), Some(ok_id));
+		assert!(none_run.is_none());
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_model_run_bmc_list_simple() -> Result<()> {
 		// -- Fixture
@@ -292,6 +407,7 @@ mod tests {
 		for i in 0..3 {
 			let run_c = RunForCreate {
 				parent_id: None,
+				parent_task_id: None,
 				agent_name: Some(format!("label-{i}")),
 				agent_path: Some(format!("path/label-{i}")),
 				has_task_stages: None,
@@ -339,6 +455,7 @@ mod tests {
 		for i in 0..3 {
 			let run_c = RunForCreate {
 				parent_id: None,
+				parent_task_id: None,
 				agent_name: Some(format!("label-{i}")),
 				agent_path: Some(format!("path/label-{i}")),
 				has_task_stages: None,
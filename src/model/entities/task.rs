@@ -1,8 +1,8 @@
 use crate::hub::get_hub;
 use crate::model::base::{self, DbBmc};
 use crate::model::{
-	EndState, EntityAction, EntityType, EpochUs, Id, Inout, InoutBmc, InoutForCreate, InoutOnlyDisplay, ModelEvent,
-	ModelManager, RelIds, Result, RunningState, Stage, TypedContent,
+	ContentTyp, EndState, EntityAction, EntityType, EpochUs, Id, Inout, InoutBmc, InoutForCreate, InoutOnlyDisplay,
+	ModelEvent, ModelManager, RelIds, Result, RunningState, Stage, TypedContent,
 };
 use crate::support::time::now_micro;
 use modql::SqliteFromRow;
@@ -30,6 +30,20 @@ pub struct Task {
 	pub uid: Uuid,
 
 	pub label: Option<String>,
+	/// The TUI display columns, json object (from the data stage `_ui.cols`)
+	pub ui_cols: Option<String>,
+
+	// -- Progress (aip.task.set_progress)
+	pub progress: Option<f64>, // 0.0 to 1.0
+	pub progress_msg: Option<String>,
+
+	// -- Running command tail (aip.cmd.exec)
+	pub cmd_line: Option<String>,
+	pub cmd_tail: Option<String>, // the last stdout/stderr lines (with the ANSI codes)
+	pub cmd_running: Option<bool>,
+
+	// -- Streaming AI response (chat follow-ups from the TUI)
+	pub ai_stream: Option<String>,
 
 	pub ctime: EpochUs,
 	pub mtime: EpochUs,
@@ -60,6 +74,7 @@ pub struct Task {
 
 	pub model_ov: Option<String>,       // Eventual override
 	pub model_upstream: Option<String>, // From the provider
+	pub finish_reason: Option<String>,  // Normalized (see `finish_reason_name`)
 
 	// -- Model Pricing
 	pub pricing_model: Option<String>,
@@ -78,6 +93,10 @@ pub struct Task {
 	pub cost_cache_write: Option<f64>,
 	pub cost_cache_saving: Option<f64>,
 
+	// -- Embeddings (aip.embed)
+	pub tk_embed_total: Option<i64>,
+	pub cost_embed: Option<f64>,
+
 	pub input_uid: Option<Uuid>,
 	pub input_short: Option<String>,
 	pub input_has_display: Option<bool>,
@@ -85,6 +104,15 @@ pub struct Task {
 	pub output_uid: Option<Uuid>,
 	pub output_short: Option<String>,
 	pub output_has_display: Option<bool>,
+
+	pub prompt_uid: Option<Uuid>,
+
+	/// The task this task is a new attempt of (see `TaskRedo`)
+	pub redo_of_task_uid: Option<Uuid>,
+
+	/// The hash of the input, and if skipped as unchanged since the last run (see `Incremental`)
+	pub input_hash: Option<String>,
+	pub cached: Option<bool>,
 }
 
 #[derive(Debug, Clone, Fields, SqliteFromRow)]
@@ -127,6 +155,19 @@ impl TaskForCreate {
 #[derive(Debug, Default, Clone, Fields, SqliteFromRow)]
 pub struct TaskForUpdate {
 	pub label: Option<String>,
+	pub ui_cols: Option<String>,
+
+	// -- Progress
+	pub progress: Option<f64>,
+	pub progress_msg: Option<String>,
+
+	// -- Running command tail
+	pub cmd_line: Option<String>,
+	pub cmd_tail: Option<String>,
+	pub cmd_running: Option<bool>,
+
+	// -- Streaming AI response
+	pub ai_stream: Option<String>,
 
 	// -- Step Timestamps
 	pub start: Option<EpochUs>,
@@ -150,6 +191,7 @@ pub struct TaskForUpdate {
 	// -- Model
 	pub model_ov: Option<String>,
 	pub model_upstream: Option<String>,
+	pub finish_reason: Option<String>,
 
 	// -- Model Pricing
 	pub pricing_model: Option<String>,
@@ -168,6 +210,10 @@ pub struct TaskForUpdate {
 	pub cost_cache_write: Option<f64>,
 	pub cost_cache_saving: Option<f64>,
 
+	// -- Embeddings (aip.embed)
+	pub tk_embed_total: Option<i64>,
+	pub cost_embed: Option<f64>,
+
 	pub input_uid: Option<Uuid>,
 	pub input_short: Option<String>,
 	pub input_has_display: Option<bool>,
@@ -175,6 +221,15 @@ pub struct TaskForUpdate {
 	pub output_uid: Option<Uuid>,
 	pub output_short: Option<String>,
 	pub output_has_display: Option<bool>,
+
+	pub prompt_uid: Option<Uuid>,
+
+	/// The task this task is a new attempt of (see `TaskRedo`)
+	pub redo_of_task_uid: Option<Uuid>,
+
+	/// The hash of the input, and if skipped as unchanged since the last run (see `Incremental`)
+	pub input_hash: Option<String>,
+	pub cached: Option<bool>,
 }
 
 impl TaskForUpdate {
@@ -564,6 +619,70 @@ impl TaskBmc {
 		}
 	}
 
+	/// Link the tasks of a redo run to the tasks of the same idx of the redone run.
+	pub fn link_redo_tasks(mm: &ModelManager, run_id: Id, redo_of_run_id: Id) -> Result<()> {
+		let redo_of_tasks = TaskBmc::list_for_run(mm, redo_of_run_id)?;
+
+		for task in TaskBmc::list_for_run(mm, run_id)? {
+			let Some(redo_of_task) = redo_of_tasks.iter().find(|t| t.idx.is_some() && t.idx == task.idx) else {
+				continue;
+			};
+			TaskBmc::update(
+				mm,
+				task.id,
+				TaskForUpdate {
+					redo_of_task_uid: Some(redo_of_task.uid),
+					..Default::default()
+				},
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// The rendered prompt text of the task (see `chat_messages_to_prompt_text`)
+	/// Note: Used by tui
+	pub fn get_prompt(mm: &ModelManager, task: &Task) -> Result<Option<String>> {
+		let Some(prompt_uid) = task.prompt_uid.as_ref() else {
+			return Ok(None);
+		};
+		Ok(InoutBmc::get_by_uid::<Inout>(mm, *prompt_uid).map(|i| i.content).ok().flatten())
+	}
+
+	/// Store the rendered prompt text (always in the inout table, as it is rarely short)
+	pub fn update_prompt(mm: &ModelManager, id: Id, prompt: &str) -> Result<()> {
+		let task = TaskBmc::get(mm, id)?;
+		let prompt_uid = Uuid::now_v7();
+
+		base::create_uid_included_with_rel_ids::<InoutBmc>(
+			mm,
+			InoutForCreate {
+				uid: prompt_uid,
+				task_uid: task.uid,
+				typ: Some(ContentTyp::Text),
+				content: Some(prompt.to_string()),
+				display: None,
+			}
+			.sqlite_not_none_fields(),
+			RelIds {
+				run_id: Some(task.run_id),
+				task_id: Some(id),
+				..Default::default()
+			},
+		)?;
+
+		TaskBmc::update(
+			mm,
+			id,
+			TaskForUpdate {
+				prompt_uid: Some(prompt_uid),
+				..Default::default()
+			},
+		)?;
+
+		Ok(())
+	}
+
 	/// Update the input (called by create)
 	pub fn update_input(mm: &ModelManager, id: Id, input_content: TypedContent) -> Result<()> {
 		let task = TaskBmc::get(mm, id)?;
@@ -731,6 +850,7 @@ mod tests {
 	async fn create_run(mm: &ModelManager, label: &str) -> Result<Id> {
 		let run_c = RunForCreate {
 			parent_id: None,
+			parent_task_id: None,
 			agent_name: Some(label.to_string()),
 			agent_path: Some(format!("path/{label}")),
 			has_task_stages: None,
@@ -788,6 +908,55 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_model_task_bmc_update_prompt() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_id = create_run(&mm, "run-1").await?;
+		let task_c = TaskForCreate {
+			run_id,
+			idx: 0,
+			label: None,
+			input_content: None,
+		};
+		let id = TaskBmc::create(&mm, task_c)?;
+		let task = TaskBmc::get(&mm, id)?;
+		assert!(TaskBmc::get_prompt(&mm, &task)?.is_none());
+
+		// -- Exec
+		TaskBmc::update_prompt(&mm, id, "# Instruction\n\nHello\n")?;
+
+		// -- Check
+		let task = TaskBmc::get(&mm, id)?;
+		let prompt = TaskBmc::get_prompt(&mm, &task)?.ok_or("Should have prompt")?;
+		assert_eq!(prompt, "# Instruction\n\nHello\n");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_model_task_bmc_link_redo_tasks() -> Result<()> {
+		// -- Fixture
+		let mm = ModelManager::new().await?;
+		let run_id = create_run(&mm, "run-1").await?;
+		let mut task_uids = Vec::new();
+		for idx in 0..3 {
+			let id = TaskBmc::create(&mm, TaskForCreate::new(run_id, idx, None, None))?;
+			task_uids.push(TaskBmc::get_uid(&mm, id)?);
+		}
+		let redo_run_id = create_run(&mm, "run-redo").await?;
+		let redo_task_id = TaskBmc::create(&mm, TaskForCreate::new(redo_run_id, 2, None, None))?;
+
+		// -- Exec
+		TaskBmc::link_redo_tasks(&mm, redo_run_id, run_id)?;
+
+		// -- Check
+		let redo_task = TaskBmc::get(&mm, redo_task_id)?;
+		assert_eq!(redo_task.redo_of_task_uid, Some(task_uids[2]));
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_model_task_bmc_list_simple() -> Result<()> {
 		// -- Fixture
@@ -13,6 +13,8 @@ pub enum Error {
 	// -- Externals
 	#[from]
 	Rusqlite(rusqlite::Error),
+	#[from]
+	Sqlx(sqlx::Error),
 }
 
 // region:    --- Froms
@@ -0,0 +1,130 @@
+//! The runs history store backends (the config `[store] history_db`, default `.aipack/.session/_history.db`).
+//!
+//! - `SqliteHistoryStore` - A SQLite db file, which can be on a share (the aipack instances wait for each other's writes).
+//! - `PgHistoryStore` - A Postgres database (`postgres://...`), shared by the aipack instances of a team.
+//!
+//! The history is browsed as a runtime db (`ModelManager`), so the TUI history mode and the incremental runs
+//! work the same for all the backends.
+
+use crate::model::db::Db;
+use crate::model::{Id, ModelManager, Result};
+use simple_fs::SPath;
+
+/// A runs history store backend.
+pub trait HistoryStore: Send + Sync {
+	/// Persist the ended run of `mm` (and its sub runs), keeping only the `max_root_runs` last top runs.
+	fn save_run_tree(&self, mm: &ModelManager, run_id: Id, max_root_runs: usize) -> Result<()>;
+
+	/// Open the history as a model manager, to browse the past runs.
+	fn open(&self) -> Result<ModelManager>;
+
+	/// Returns false when the store has no history yet (so that it is not created just to be read).
+	fn exists(&self) -> bool;
+}
+
+/// Returns the history store of a location, a `postgres://` (or `postgresql://`) url, or a SQLite db file path.
+/// - `scope` - The workspace/user of the saved runs (see `DirContext::history_scope`), the Postgres history
+///   keeps the `max_root_runs` last top runs per scope.
+pub fn new_history_store(location: &str, scope: &str) -> Box<dyn HistoryStore> {
+	if location.starts_with("postgres://") || location.starts_with("postgresql://") {
+		Box::new(PgHistoryStore {
+			url: location.to_string(),
+			scope: scope.to_string(),
+		})
+	} else {
+		Box::new(SqliteHistoryStore {
+			path: SPath::new(location),
+		})
+	}
+}
+
+// region:    --- SqliteHistoryStore
+
+pub struct SqliteHistoryStore {
+	path: SPath,
+}
+
+impl HistoryStore for SqliteHistoryStore {
+	fn save_run_tree(&self, mm: &ModelManager, run_id: Id, max_root_runs: usize) -> Result<()> {
+		mm.db().copy_run_tree_to_file(run_id, &self.path, max_root_runs)
+	}
+
+	fn open(&self) -> Result<ModelManager> {
+		ModelManager::new_history(&self.path)
+	}
+
+	fn exists(&self) -> bool {
+		self.path.exists()
+	}
+}
+
+// endregion: --- SqliteHistoryStore
+
+// region:    --- PgHistoryStore
+
+pub struct PgHistoryStore {
+	url: String,
+	scope: String,
+}
+
+impl HistoryStore for PgHistoryStore {
+	fn save_run_tree(&self, mm: &ModelManager, run_id: Id, max_root_runs: usize) -> Result<()> {
+		mm.db().copy_run_tree_to_pg(run_id, &self.url, &self.scope, max_root_runs)
+	}
+
+	fn open(&self) -> Result<ModelManager> {
+		Ok(ModelManager::from_db(Db::new_from_pg_history(&self.url)?))
+	}
+
+	/// NOTE: The history tables are created on the first access.
+	fn exists(&self) -> bool {
+		true
+	}
+}
+
+// endregion: --- PgHistoryStore
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::model::{RunBmc, RunForCreate};
+
+	#[tokio::test]
+	async fn test_model_history_store_sqlite_save_and_open() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("tests-data/sandbox-01/.tmp/test_model_history_store/_history.db");
+		if path.exists() {
+			std::fs::remove_file(path.as_std_path())?;
+		}
+		let mm = ModelManager::new().await?;
+		let run_c = RunForCreate {
+			parent_id: None,
+			parent_task_id: None,
+			agent_name: Some("store-agent".to_string()),
+			agent_path: Some("path/store-agent".to_string()),
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		let run_id = RunBmc::create(&mm, run_c)?;
+		let store = new_history_store(path.as_str(), "test-user:test-wks");
+
+		// -- Exec
+		let existed = store.exists();
+		mm.persist_run_to_history(run_id, store.as_ref())?;
+		let hist_mm = store.open()?;
+
+		// -- Check
+		assert!(!existed);
+		assert!(store.exists());
+		let runs = RunBmc::list_for_display(&hist_mm, None)?;
+		assert_eq!(runs.first().and_then(|r| r.agent_name.as_deref()), Some("store-agent"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -4,13 +4,16 @@ mod db;
 mod derive_aliases;
 mod entities;
 mod error;
+mod history_store;
 mod model_manager;
 mod runtime_ctx;
 mod types;
 
+pub use db::JsonRow;
 use derive_aliases::*;
 pub use entities::*;
 pub use error::{Error, Result};
+pub use history_store::*;
 pub use model_manager::*;
 pub use runtime_ctx::*;
 pub use types::*;
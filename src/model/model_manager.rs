@@ -1,5 +1,9 @@
-use crate::model::Result;
 use crate::model::db::Db;
+use crate::model::{HistoryStore, Id, Result};
+use simple_fs::SPath;
+
+/// The max number of top runs kept in the history db
+const HISTORY_MAX_RUNS: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct ModelManager {
@@ -13,6 +17,17 @@ impl ModelManager {
 		db.recreate()?;
 		Ok(Self { db })
 	}
+
+	/// Open the persisted runs history db (e.g., `.aipack/.session/_history.db`), to browse the past runs.
+	pub fn new_history(path: &SPath) -> Result<Self> {
+		let db = Db::new_file(path)?;
+		Ok(Self { db })
+	}
+
+	/// For the history stores loading their runs into a runtime db (e.g., `PgHistoryStore`)
+	pub(in crate::model) fn from_db(db: Db) -> Self {
+		Self { db }
+	}
 }
 
 /// Getters
@@ -37,6 +52,12 @@ impl ModelManager {
 		Ok(run_count + task_count + log_count + work_count)
 	}
 
+	/// Persist the run (and its sub runs) into the history store,
+	/// keeping only the last `HISTORY_MAX_RUNS` top runs.
+	pub fn persist_run_to_history(&self, run_id: Id, store: &dyn HistoryStore) -> Result<()> {
+		store.save_run_tree(self, run_id, HISTORY_MAX_RUNS)
+	}
+
 	pub fn db_size(&self) -> Result<i64> {
 		let db = self.db();
 		let sql = r#"
@@ -76,6 +97,7 @@ impl ModelManager {
 		for i in 0..10 {
 			let run_c = RunForCreate {
 				parent_id: None,
+				parent_task_id: None,
 				agent_name: Some(format!("agent_name-{i}")),
 				agent_path: Some(format!("agent_path-{i}")),
 				has_task_stages: None,
@@ -3,6 +3,7 @@ use macro_rules_attribute as mra;
 
 // Simple wrapper for SQLite Ids
 #[mra::derive(Debug, ScalarStruct!)]
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Id(i64);
 
 impl Id {
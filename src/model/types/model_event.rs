@@ -1,8 +1,9 @@
 use crate::model::Id;
+use serde::{Deserialize, Serialize};
 
 // region:    --- Types
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EntityType {
 	Run,
 	Task,
@@ -13,9 +14,10 @@ pub enum EntityType {
 	Ucontent,
 	Work,
 	Inout,
+	ConvMsg,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EntityAction {
 	Created,
 	Updated,
@@ -23,7 +25,7 @@ pub enum EntityAction {
 	Deleted,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RelIds {
 	pub run_id: Option<Id>,
 	pub task_id: Option<Id>,
@@ -36,7 +38,7 @@ pub struct RelIds {
 	pub inout_id: Option<Id>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModelEvent {
 	pub entity: EntityType,
 	pub action: EntityAction,
@@ -45,3 +47,21 @@ pub struct ModelEvent {
 }
 
 // endregion: --- Types
+
+impl EntityType {
+	/// The db table of the entity
+	pub fn table(&self) -> &'static str {
+		match self {
+			EntityType::Run => "run",
+			EntityType::Task => "task",
+			EntityType::Log => "log",
+			EntityType::Err => "err",
+			EntityType::Prompt => "prompt",
+			EntityType::Pin => "pin",
+			EntityType::Ucontent => "ucontent",
+			EntityType::Work => "work",
+			EntityType::Inout => "inout",
+			EntityType::ConvMsg => "conv_msg",
+		}
+	}
+}
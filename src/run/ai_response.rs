@@ -1,15 +1,19 @@
 // region:    --- AiResponse
 
+use crate::script::serde_value_to_lua_value;
 use crate::support::W;
 use genai::ModelName;
 use genai::adapter::AdapterKind;
-use genai::chat::Usage;
+use genai::chat::{StopReason, Usage};
 use mlua::IntoLua;
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Debug, Serialize)]
 pub struct AiResponse {
 	pub content: Option<String>,
+	/// The parsed (and validated) JSON content, when the agent `output_format = "json"`
+	pub json: Option<Value>,
 	pub reasoning_content: Option<String>,
 	pub model_name: ModelName,
 	pub adapter_kind: AdapterKind,
@@ -17,13 +21,33 @@ pub struct AiResponse {
 	pub price_usd: Option<f64>,
 	pub duration_sec: f64,
 	pub info: String,
+	/// The normalized finish reason (see `finish_reason_name`)
+	pub finish_reason: Option<String>,
+	/// The finish reason as returned by the provider
+	pub finish_reason_raw: Option<String>,
+	/// The token log probabilities, when requested (agent option `logprobs = true`) and supported by the provider
+	pub logprobs: Option<AiLogprobs>,
+}
+
+impl AiResponse {
+	/// Returns true if the response was cut by the max tokens limit
+	pub fn is_truncated(&self) -> bool {
+		self.finish_reason.as_deref() == Some(FINISH_MAX_TOKENS)
+	}
 }
 
 impl IntoLua for AiResponse {
 	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
 		let table = lua.create_table()?;
+		let is_truncated = self.is_truncated();
 
 		table.set("content", self.content.into_lua(lua)?)?;
+		if let Some(json) = self.json {
+			table.set(
+				"json",
+				serde_value_to_lua_value(lua, json).map_err(mlua::Error::external)?,
+			)?;
+		}
 		table.set("reasoning_content", self.reasoning_content.into_lua(lua)?)?;
 		table.set("model_name", self.model_name.into_lua(lua)?)?;
 		table.set("adapter_kind", self.adapter_kind.as_str().into_lua(lua)?)?;
@@ -31,11 +55,137 @@ impl IntoLua for AiResponse {
 		table.set("price_usd", self.price_usd.into_lua(lua)?)?;
 		table.set("duration_sec", self.duration_sec.into_lua(lua)?)?;
 		table.set("info", self.info.into_lua(lua)?)?;
+		table.set("is_truncated", is_truncated)?;
+		table.set("finish_reason", self.finish_reason.into_lua(lua)?)?;
+		table.set("finish_reason_raw", self.finish_reason_raw.into_lua(lua)?)?;
+		if let Some(logprobs) = self.logprobs {
+			table.set("logprobs", logprobs.into_lua(lua)?)?;
+		}
+
+		Ok(mlua::Value::Table(table))
+	}
+}
+
+// endregion: --- AiResponse
+
+// region:    --- Finish Reason
+
+pub const FINISH_COMPLETED: &str = "completed";
+pub const FINISH_MAX_TOKENS: &str = "max_tokens";
+pub const FINISH_TOOL_CALL: &str = "tool_call";
+pub const FINISH_CONTENT_FILTER: &str = "content_filter";
+pub const FINISH_STOP_SEQUENCE: &str = "stop_sequence";
+pub const FINISH_OTHER: &str = "other";
+
+/// Returns the normalized finish reason name (the same for all the providers)
+pub fn finish_reason_name(stop_reason: &StopReason) -> &'static str {
+	match stop_reason {
+		StopReason::Completed(_) => FINISH_COMPLETED,
+		StopReason::MaxTokens(_) => FINISH_MAX_TOKENS,
+		StopReason::ToolCall(_) => FINISH_TOOL_CALL,
+		StopReason::ContentFilter(_) => FINISH_CONTENT_FILTER,
+		StopReason::StopSequence(_) => FINISH_STOP_SEQUENCE,
+		StopReason::Other(_) => FINISH_OTHER,
+	}
+}
+
+// endregion: --- Finish Reason
+
+// region:    --- AiLogprobs
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiLogprobs {
+	pub tokens: Vec<AiTokenLogprob>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AiTokenLogprob {
+	pub token: String,
+	pub logprob: f64,
+	/// The most likely alternatives (with the agent option `top_logprobs`)
+	pub top_logprobs: Vec<(String, f64)>,
+}
+
+impl AiLogprobs {
+	/// Read the logprobs of an OpenAI Chat Completions compatible raw response body
+	/// (`choices[0].logprobs.content`).
+	///
+	/// Returns `None` if the body has no logprobs.
+	pub fn from_raw_body(body: &Value) -> Option<Self> {
+		let content = body.pointer("/choices/0/logprobs/content")?.as_array()?;
+
+		let tokens: Vec<AiTokenLogprob> = content
+			.iter()
+			.filter_map(|item| {
+				let token = item.get("token")?.as_str()?.to_string();
+				let logprob = item.get("logprob")?.as_f64()?;
+				let top_logprobs = item
+					.get("top_logprobs")
+					.and_then(|v| v.as_array())
+					.map(|tops| {
+						tops.iter()
+							.filter_map(|top| {
+								Some((top.get("token")?.as_str()?.to_string(), top.get("logprob")?.as_f64()?))
+							})
+							.collect()
+					})
+					.unwrap_or_default();
+				Some(AiTokenLogprob {
+					token,
+					logprob,
+					top_logprobs,
+				})
+			})
+			.collect();
+
+		if tokens.is_empty() { None } else { Some(Self { tokens }) }
+	}
+
+	/// The average token logprob (the closer to 0, the more confident)
+	pub fn avg_logprob(&self) -> f64 {
+		let sum: f64 = self.tokens.iter().map(|t| t.logprob).sum();
+		sum / self.tokens.len().max(1) as f64
+	}
+
+	/// The lowest token logprob (the least confident token)
+	pub fn min_logprob(&self) -> f64 {
+		self.tokens.iter().map(|t| t.logprob).fold(0.0, f64::min)
+	}
+}
+
+impl IntoLua for AiLogprobs {
+	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
+		let table = lua.create_table()?;
+		table.set("avg_logprob", self.avg_logprob())?;
+		table.set("min_logprob", self.min_logprob())?;
+		// exp of the average logprob, so, from 0 to 1
+		table.set("confidence", self.avg_logprob().exp())?;
+
+		let tokens = lua.create_table()?;
+		for token in self.tokens {
+			let token_table = lua.create_table()?;
+			token_table.set("token", token.token)?;
+			token_table.set("logprob", token.logprob)?;
+			let tops = lua.create_table()?;
+			for (top_token, top_logprob) in token.top_logprobs {
+				let top = lua.create_table()?;
+				top.set("token", top_token)?;
+				top.set("logprob", top_logprob)?;
+				tops.push(top)?;
+			}
+			token_table.set("top_logprobs", tops)?;
+			tokens.push(token_table)?;
+		}
+		table.set("tokens", tokens)?;
 
 		Ok(mlua::Value::Table(table))
 	}
 }
 
+// endregion: --- AiLogprobs
+
+// region:    --- Usage
+
 impl IntoLua for W<&Usage> {
 	fn into_lua(self, lua: &mlua::Lua) -> mlua::Result<mlua::Value> {
 		let table = lua.create_table()?;
@@ -85,4 +235,46 @@ impl IntoLua for W<&Usage> {
 	}
 }
 
-// endregion: --- AiResponse
+// endregion: --- Usage
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_run_ai_logprobs_from_raw_body() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_body = json!({
+			"choices": [{
+				"finish_reason": "length",
+				"logprobs": {"content": [
+					{"token": "Yes", "logprob": -0.1, "top_logprobs": [{"token": "Yes", "logprob": -0.1}, {"token": "No", "logprob": -2.4}]},
+					{"token": "!", "logprob": -0.5, "top_logprobs": []}
+				]}
+			}]
+		});
+
+		// -- Exec
+		let logprobs = AiLogprobs::from_raw_body(&fx_body).ok_or("Should have logprobs")?;
+
+		// -- Check
+		assert_eq!(logprobs.tokens.len(), 2);
+		assert_eq!(logprobs.tokens[0].top_logprobs[1], ("No".to_string(), -2.4));
+		assert!((logprobs.avg_logprob() - -0.3).abs() < 1e-9);
+		assert_eq!(logprobs.min_logprob(), -0.5);
+		assert!(AiLogprobs::from_raw_body(&json!({"choices": [{"message": {}}]})).is_none());
+		assert_eq!(
+			finish_reason_name(&StopReason::from("length".to_string())),
+			FINISH_MAX_TOKENS
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
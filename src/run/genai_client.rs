@@ -1,8 +1,9 @@
 //! Module about AI support functions.
 
-use crate::Result;
+use crate::{Error, Result};
 use genai::adapter::AdapterKind;
 use genai::chat::ChatOptions;
+use genai::embed::{EmbedOptions, EmbedResponse};
 use genai::resolver::AuthData;
 use genai::{Client, ModelIden};
 
@@ -61,3 +62,50 @@ pub fn new_genai_client() -> Result<genai::Client> {
 
 	Ok(client)
 }
+
+/// Returns the request extra body with the GBNF `grammar`, if the provider (adapter) supports it.
+///
+/// For now, only the custom endpoints (e.g., `genai_1::my-model`) are supported,
+/// since they are the OpenAI compatible local servers (e.g., llama.cpp server) accepting the `grammar` field.
+/// The other providers would reject or ignore it (use `output_schema` for their structured output).
+pub fn grammar_extra_body(adapter_kind: AdapterKind, grammar: &str) -> Option<serde_json::Value> {
+	match adapter_kind {
+		AdapterKind::Custom(_) => Some(serde_json::json!({ "grammar": grammar })),
+		_ => None,
+	}
+}
+
+/// Returns the request extra body asking for the token log probabilities, if the provider (adapter) supports it.
+///
+/// For now, only the OpenAI Chat Completions compatible providers returning them are supported
+/// (the logprobs are then read from the captured raw response body, see `AiLogprobs::from_raw_body`).
+pub fn logprobs_extra_body(adapter_kind: AdapterKind, top_logprobs: Option<u8>) -> Option<serde_json::Value> {
+	match adapter_kind {
+		AdapterKind::OpenAI | AdapterKind::Together | AdapterKind::Fireworks | AdapterKind::Custom(_) => {
+			let mut body = serde_json::json!({ "logprobs": true });
+			if let Some(top_logprobs) = top_logprobs {
+				body["top_logprobs"] = top_logprobs.into();
+			}
+			Some(body)
+		}
+		_ => None,
+	}
+}
+
+/// The default embedding model (when not specified in `aip.embed.generate` options)
+pub const DEFAULT_EMBED_MODEL: &str = "text-embedding-3-small";
+
+/// Create the embeddings of the `texts` (in one provider call), with the optional output `dimensions`.
+pub async fn exec_embed(
+	client: &genai::Client,
+	model: &str,
+	texts: Vec<String>,
+	dimensions: Option<usize>,
+) -> Result<EmbedResponse> {
+	let options = dimensions.map(|dimensions| EmbedOptions::new().with_dimensions(dimensions));
+	let res = client
+		.embed_batch(model, texts, options.as_ref())
+		.await
+		.map_err(|err| Error::cc(format!("Fail to create the embeddings with model '{model}'"), err))?;
+	Ok(res)
+}
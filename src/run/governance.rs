@@ -0,0 +1,159 @@
+//! The governance run report (opt-in `[governance]` config), POSTed to a company controlled endpoint
+//! at the end of the top runs, for the platform teams audit requirements.
+//!
+//! The report has the run metadata (agent, pack, model, timing, tokens, cost, capability usage),
+//! but not the prompt content, unless `include_prompts = true` (then the agent prompt templates, not the rendered prompts).
+
+use crate::agent::{Agent, AgentRef};
+use crate::dir_context::GovernanceConfig;
+use crate::hub::get_hub;
+use crate::model::{EndState, Id, RunBmc, TaskBmc};
+use crate::runtime::Runtime;
+use crate::types::PackCapabilities;
+use crate::{Error, Result};
+use genai::chat::ChatRole;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const FIRST_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Build and send the governance report of the run (the errors are published, and do not fail the run).
+pub(super) async fn send_run_report(
+	runtime: &Runtime,
+	run_id: Id,
+	agent: &Agent,
+	pack_capabilities: Option<&PackCapabilities>,
+	governance: &GovernanceConfig,
+) {
+	let res = match build_run_report(runtime, run_id, agent, pack_capabilities, governance.include_prompts) {
+		Ok(report) => post_report(governance, &report).await,
+		Err(err) => Err(err),
+	};
+
+	if let Err(err) = res {
+		get_hub()
+			.publish_err(
+				format!("Cannot send the governance run report to '{}'", governance.endpoint),
+				Some(err),
+			)
+			.await;
+	}
+}
+
+// region:    --- Support
+
+fn build_run_report(
+	runtime: &Runtime,
+	run_id: Id,
+	agent: &Agent,
+	pack_capabilities: Option<&PackCapabilities>,
+	include_prompts: bool,
+) -> Result<Value> {
+	let mm = runtime.mm();
+	let run = RunBmc::get(mm, run_id)?;
+	let tasks = TaskBmc::list_for_run(mm, run_id)?;
+
+	let sum_tk = |get: fn(&crate::model::Task) -> Option<i64>| tasks.iter().filter_map(get).sum::<i64>();
+	let tasks_err = tasks
+		.iter()
+		.filter(|task| matches!(task.end_state, Some(EndState::Err)))
+		.count();
+
+	let pack_identity = match agent.agent_ref() {
+		AgentRef::PackRef(pack_ref) => Some(pack_ref.identity().to_string()),
+		_ => None,
+	};
+
+	let capabilities = pack_capabilities.map(|capabilities| {
+		json!({
+			"granted": capabilities.granted(),
+			"usage": capabilities.usage(),
+		})
+	});
+
+	let mut report = json!({
+		"event": "run_end",
+		"aipack_version": crate::VERSION,
+		"run": {
+			"uid": run.uid.to_string(),
+			"label": run.label,
+			"start_us": run.start.map(|v| v.as_i64()),
+			"end_us": run.end.map(|v| v.as_i64()),
+			"end_state": run.end_state.map(|v| v.as_ref().to_string()),
+			"total_cost": run.total_cost,
+		},
+		"agent": {
+			"name": run.agent_name,
+			"path": run.agent_path,
+			"pack": pack_identity,
+			"model": run.model,
+		},
+		"tasks": {
+			"count": tasks.len(),
+			"err_count": tasks_err,
+			"tk_prompt_total": sum_tk(|t| t.tk_prompt_total),
+			"tk_completion_total": sum_tk(|t| t.tk_completion_total),
+			"tk_embed_total": sum_tk(|t| t.tk_embed_total),
+		},
+		"capabilities": capabilities,
+	});
+
+	if include_prompts {
+		let prompts: Vec<Value> = agent
+			.prompt_parts()
+			.into_iter()
+			.map(|part| json!({"role": ChatRole::from(&part.kind).to_string(), "content": part.content}))
+			.collect();
+		report["prompts"] = Value::Array(prompts);
+	}
+
+	Ok(report)
+}
+
+/// POST the report, with exponential back-off retries on the network errors, 429, and 5xx responses.
+async fn post_report(governance: &GovernanceConfig, report: &Value) -> Result<()> {
+	let client = reqwest::Client::builder()
+		.timeout(REQUEST_TIMEOUT)
+		.build()
+		.map_err(|err| Error::cc("Cannot build the governance http client", err))?;
+	let token = match governance.token_env.as_deref() {
+		Some(env_name) => Some(std::env::var(env_name).map_err(|_| {
+			Error::custom(format!(
+				"[governance] token_env '{env_name}' environment variable is not set"
+			))
+		})?),
+		None => None,
+	};
+
+	let mut backoff = FIRST_BACKOFF;
+	let mut attempt = 0;
+	loop {
+		attempt += 1;
+		let mut request = client.post(&governance.endpoint).json(report);
+		if let Some(token) = token.as_deref() {
+			request = request.bearer_auth(token);
+		}
+
+		let err_msg = match request.send().await {
+			Ok(res) if res.status().is_success() => return Ok(()),
+			Ok(res) => {
+				let status = res.status();
+				let retryable = status.is_server_error() || status.as_u16() == 429;
+				if !retryable {
+					return Err(Error::custom(format!("Endpoint responded with status {status}")));
+				}
+				format!("Endpoint responded with status {status}")
+			}
+			Err(err) => err.to_string(),
+		};
+
+		if attempt > governance.max_retries {
+			return Err(Error::custom(format!("{err_msg} (after {attempt} attempts)")));
+		}
+		tokio::time::sleep(backoff).await;
+		backoff *= 2;
+	}
+}
+
+// endregion: --- Support
@@ -1,8 +1,10 @@
 use crate::Result;
 use crate::agent::{Agent, AgentRef};
-use crate::dir_context::join_support_pack_ref;
+use crate::dir_context::{AipackConfig, join_support_pack_ref};
+use crate::exec::packer::InstallInfo;
 use crate::runtime::Runtime;
 use crate::script::LuaEngine;
+use crate::types::{PackCapabilities, RunEnv, WriteConfirm};
 use std::sync::Arc;
 
 /// TODO: Will need to put the Vec in Arc, since this clone what a bit
@@ -11,15 +13,34 @@ pub struct Literals {
 	/// The store of all literals, pattern and value
 	/// e.g. `vec![("&AIPACK_AGENT_DIR","./.aipack/custom/command-agent/some.aipack")]`
 	store: Arc<Vec<(&'static str, String)>>,
+
+	/// The agent config, exposed as `CTX.config`
+	config: Option<AipackConfig>,
+
+	/// The consented capabilities of the agent pack, when installed with `aip install` (enforced by the Lua engine)
+	pack_capabilities: Option<PackCapabilities>,
+
+	/// The `confirm_writes` agent option (enforced by the Lua `aip.file` writes)
+	write_confirm: Option<WriteConfirm>,
+
+	/// The `env` agent option, resolved (for `aip.cmd.exec` and `aip.env.get`)
+	run_env: Option<RunEnv>,
 }
 
 /// Constructors
 impl Literals {
-	pub(super) fn from_runtime_and_agent_path(runtime: &Runtime, agent: &Agent) -> Result<Literals> {
+	/// NOTE: The `parent_capabilities` are the ones of the caller run, for a sub agent run
+	///       (see `with_parent_capabilities`).
+	pub(super) fn from_runtime_and_agent_path(
+		runtime: &Runtime,
+		agent: &Agent,
+		parent_capabilities: Option<&PackCapabilities>,
+	) -> Result<Literals> {
 		// let mut literals = Literals::default();
 		let dir_context = runtime.dir_context();
 
 		let mut store = Vec::new();
+		let mut pack_capabilities = None;
 
 		let agent_path = dir_context.current_dir().join(agent.file_path());
 
@@ -66,6 +87,36 @@ impl Literals {
 
 			let pack_support_base = join_support_pack_ref(aipack_paths.aipack_base_dir().path(), identity_path);
 			store.push(("PACK_BASE_SUPPORT_DIR", pack_support_base.to_string()));
+
+			// -- The capabilities and paths consented at install (the packs installed before it are not enforced)
+			if pack_ref.pack_dir.starts_with(aipack_paths.get_base_pack_installed_dir()?)
+				&& let Some(install_info) = InstallInfo::read(&pack_ref.pack_dir)
+			{
+				let to_home_globs = |globs: Vec<String>| -> Vec<String> {
+					globs
+						.into_iter()
+						.map(|glob| dir_context.maybe_tilde_path_into_home(glob.into()).to_string())
+						.collect()
+				};
+				let paths_deny = agent
+					.config()
+					.and_then(|config| config.sandbox())
+					.and_then(|sandbox| sandbox.paths_deny.clone())
+					.unwrap_or_default();
+				pack_capabilities = Some(
+					PackCapabilities::new(
+						pack_ref.identity().to_string(),
+						install_info.capabilities,
+						pack_support_base,
+					)
+					.with_paths(
+						pack_ref.pack_dir.clone(),
+						dir_context.wks_dir().cloned(),
+						to_home_globs(install_info.paths_allow),
+						to_home_globs(paths_deny),
+					)?,
+				);
+			}
 		}
 
 		// -- Workspace / base dirs
@@ -87,7 +138,31 @@ impl Literals {
 		store.push(("AGENT_FILE_DIR", agent_dir.to_string()));
 		store.push(("AGENT_FILE_STEM", agent_path.stem().to_string()));
 
-		Ok(Self { store: Arc::new(store) })
+		// -- The writes to confirm (agent option `confirm_writes`)
+		let agent_options = agent.options_as_ref();
+		let write_confirm = if agent_options.confirm_writes() == Some(true) {
+			let allow_globs = agent_options.confirm_writes_allow().map(|g| g.to_vec()).unwrap_or_default();
+			Some(WriteConfirm::new(allow_globs)?)
+		} else {
+			None
+		};
+
+		let literals = Self {
+			store: Arc::new(store),
+			config: agent.config().cloned(),
+			pack_capabilities,
+			write_confirm,
+			run_env: None,
+		}
+		.with_parent_capabilities(parent_capabilities);
+
+		// -- The env variables (agent option `env`, with the secrets checked against the final capabilities)
+		let run_env = agent_options
+			.env()
+			.map(|env| RunEnv::new(env, literals.pack_capabilities()))
+			.transpose()?;
+
+		Ok(Self { run_env, ..literals })
 	}
 }
 
@@ -98,15 +173,51 @@ impl Literals {
 	// }
 
 	// Your existing add method...
+	pub fn pack_capabilities(&self) -> Option<&PackCapabilities> {
+		self.pack_capabilities.as_ref()
+	}
+
+	pub fn write_confirm(&self) -> Option<&WriteConfirm> {
+		self.write_confirm.as_ref()
+	}
+
+	pub fn run_env(&self) -> Option<&RunEnv> {
+		self.run_env.as_ref()
+	}
+
+	/// The value of a literal (e.g., `"WORKSPACE_DIR"`), the last one if pushed many times
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.store.iter().rev().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+	}
+
 	#[allow(unused)]
 	pub fn as_strs(&self) -> Vec<(&str, &str)> {
 		self.store.iter().map(|(p, v)| (*p, v.as_str())).collect()
 	}
 
+	/// Restrict the pack capabilities by the ones of the caller run (for a sub agent run).
+	/// NOTE: An agent without enforced capabilities (e.g., a workspace agent written by the caller pack)
+	///       gets the caller ones.
+	fn with_parent_capabilities(mut self, parent_capabilities: Option<&PackCapabilities>) -> Self {
+		if let Some(parent_capabilities) = parent_capabilities {
+			self.pack_capabilities = Some(match self.pack_capabilities.take() {
+				Some(pack_capabilities) => pack_capabilities.with_parent(parent_capabilities.clone()),
+				None => parent_capabilities.clone(),
+			});
+		}
+		self
+	}
+
 	pub fn append(&self, pattern: &'static str, value: impl Into<String>) -> Self {
 		let mut store = self.store.as_ref().clone();
 		store.push((pattern, value.into()));
-		Self { store: Arc::new(store) }
+		Self {
+			store: Arc::new(store),
+			config: self.config.clone(),
+			pack_capabilities: self.pack_capabilities.clone(),
+			write_confirm: self.write_confirm.clone(),
+			run_env: self.run_env.clone(),
+		}
 	}
 }
 
@@ -119,6 +230,9 @@ impl Literals {
 		for (name, value) in self.as_strs() {
 			table.set(name, value)?;
 		}
+		if let Some(config) = self.config.as_ref() {
+			table.set("config", lua_engine.serde_to_lua_value(config.value().clone())?)?;
+		}
 		Ok(mlua::Value::Table(table))
 	}
 }
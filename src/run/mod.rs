@@ -3,23 +3,34 @@ mod literals;
 mod pricing;
 mod proc_after_all;
 mod proc_ai;
+mod proc_ai_post;
 mod proc_before_all;
 mod proc_data;
+mod proc_incremental;
+mod proc_input_filter;
 mod proc_output;
+mod prompt_text;
 mod run_agent_task;
 
 mod ai_response;
 mod genai_client;
+mod governance;
 mod run_agent;
+mod run_checkpoint;
 mod run_executor;
+mod run_export;
+mod run_otel;
 mod run_types;
+mod run_worker;
 
 pub use ai_response::*;
 pub use genai_client::*;
 pub use literals::Literals;
-pub use pricing::ModelPricing;
+pub use pricing::{ModelPricing, price_it};
 pub use run_agent::*;
 pub use run_executor::*;
+pub use run_export::export_tasks_outputs;
 pub use run_types::*;
+pub use run_worker::*;
 
 // endregion: --- Modules
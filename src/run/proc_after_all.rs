@@ -44,6 +44,7 @@ pub async fn process_after_all(
 		lua_scope.set("outputs", lua_engine.serde_to_lua_value(outputs_value)?)?;
 		lua_scope.set("before_all", lua_engine.serde_to_lua_value(before_all)?)?;
 		lua_scope.set("options", agent.options_as_ref())?;
+		lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
 
 		// -- Rt Step - After All Start
 		rt_step.step_aa_start(run_id).await?;
@@ -1,24 +1,49 @@
-use crate::Result;
-use crate::agent::{Agent, AgentOptions, PromptPart, parse_prompt_part_options};
+use crate::agent::{Agent, AgentOptions, AgentTool, PromptPart, parse_prompt_part_options};
 use crate::hub::get_hub;
-use crate::model::{AiPrice, Id};
+use crate::model::{AiPrice, Id, RuntimeCtx, Stage, TaskBmc, TaskForUpdate};
 use crate::run::pricing::{model_pricing, price_it};
-use crate::run::{AiResponse, Attachments, DryMode, RunBaseOptions};
+use crate::run::proc_ai_post::process_ai_post;
+use crate::run::{
+	AiLogprobs, AiResponse, Attachments, ConvMessage, DryMode, FINISH_CONTENT_FILTER, FINISH_MAX_TOKENS, Literals,
+	RunBaseOptions, finish_reason_name, grammar_extra_body, logprobs_extra_body,
+};
 use crate::runtime::Runtime;
-use crate::support::hbs::hbs_render;
-use crate::support::text::{self, format_duration, format_usage};
-use genai::chat::{CacheControl, ChatMessage, ChatOptions, ChatRequest, ChatResponse, ContentPart};
+use crate::support::ai_parse::stitch_continuation;
+use crate::support::hbs::hbs_render_with_partials;
+use crate::support::jsons::validate_json_schema;
+use crate::support::text::{
+	self, TokenEncoding, format_duration, format_token_estimate, format_usage, prompt_token_count,
+};
+use crate::{Error, Result};
+use futures::StreamExt as _;
+use genai::chat::{
+	CacheControl, ChatMessage, ChatOptions, ChatRequest, ChatResponse, ChatRole, ChatStreamEvent, ContentPart,
+	MessageContent, StopReason, ToolCall, ToolResponse, Usage,
+};
 use genai::{ModelIden, ModelName};
 use serde_json::Value;
 use simple_fs::SPath;
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// The max number of model calls answered with tool responses (per task), to avoid endless tool loops
+const MAX_TOOL_ROUNDS: usize = 20;
+
+/// The max number of repair prompts sent back when the JSON output is invalid (with `output_format = "json"`)
+const MAX_JSON_REPAIRS: usize = 2;
+
+/// The prompt requesting the rest of a truncated response (with the agent option `auto_continue`)
+const CONTINUATION_PROMPT: &str = "Your previous response was cut off by the output token limit. \
+Continue exactly where it stopped, without repeating anything, and without any introduction.";
 
 pub struct ProcAiResponse {
 	pub ai_response: Option<AiResponse>,
 }
 
+/// Build the chat messages of the task: the attachments, then the rendered prompt parts,
+/// with the eventual conversation `messages` before the first non-system part.
+#[allow(clippy::too_many_arguments)]
 pub fn build_chat_messages(
 	runtime: &Runtime,
 	agent: &Agent,
@@ -26,6 +51,7 @@ pub fn build_chat_messages(
 	input: &Value,
 	data: &Value,
 	attachments: &Attachments,
+	messages: &[ConvMessage],
 ) -> Result<Vec<ChatMessage>> {
 	let data_scope = HashMap::from([
 		// The hbs scope data
@@ -33,32 +59,50 @@ pub fn build_chat_messages(
 		("data", data),
 		("input", input),
 		("before_all", before_all),
+		("args", agent.args()),
 	]);
 
 	let mut chat_messages: Vec<ChatMessage> = Vec::new();
 
 	// -- Add the eventual attachments
 	for att in attachments {
-		// Resolve
 		let file_source = SPath::new(&att.file_source);
-		let file_path = match runtime.resolve_path_default(file_source.clone(), None) {
-			Ok(file_path) => file_path,
-			Err(err) => {
-				let chat_msg = ChatMessage::user(format!(
-					"Error while attaching file '{}'\nCause: {err}",
-					att.file_source
-				));
-				chat_messages.push(chat_msg);
-				continue;
-			}
+
+		// -- Get the binary content part (the base64 content, or the resolved file)
+		let file_cp = if let Some(content) = att.content.as_ref() {
+			att.validate_content()?;
+			let content_type = att
+				.content_type
+				.clone()
+				.unwrap_or_else(|| "application/octet-stream".to_string());
+			let file_name = att.file_name.clone().unwrap_or_else(|| file_source.name().to_string());
+			Ok(ContentPart::from_binary_base64(
+				content_type,
+				content.as_str(),
+				Some(file_name),
+			))
+		} else {
+			// Resolve
+			let file_path = match runtime.resolve_path_default(file_source.clone(), None) {
+				Ok(file_path) => file_path,
+				Err(err) => {
+					let chat_msg = ChatMessage::user(format!(
+						"Error while attaching file '{}'\nCause: {err}",
+						att.file_source
+					));
+					chat_messages.push(chat_msg);
+					continue;
+				}
+			};
+			// NOTE: Type and size errors fail the task (the provider would reject them anyway)
+			att.validate_file(&file_path)?;
+			ContentPart::from_binary_file(&file_path)
+				.map_err(|err| format!("Error while attaching file '{file_path}'\nCause: {err}"))
 		};
 
-		let chat_msg = match ContentPart::from_binary_file(&file_path) {
+		let chat_msg = match file_cp {
 			Ok(file_cp) => {
-				let file_name = att
-					.file_name
-					.as_deref()
-					.unwrap_or(file_path.file_name().unwrap_or("no file name"));
+				let file_name = att.file_name.as_deref().unwrap_or(file_source.name());
 
 				let m = format!(
 					"Here is file attachment.
@@ -79,13 +123,14 @@ File Name: '{file_name}'
 					file_cp,
 				])
 			}
-			Err(err) => ChatMessage::user(format!("Error while attaching file '{file_path}'\nCause: {err}")),
+			Err(err_msg) => ChatMessage::user(err_msg),
 		};
 
 		chat_messages.push(chat_msg);
 	}
 
 	// -- Add the prompt parts from the agent (.aip markdown template)
+	let mut conv_messages = Some(messages);
 	for prompt_part in agent.prompt_parts() {
 		let PromptPart {
 			kind,
@@ -102,7 +147,7 @@ File Name: '{file_name}'
 			(false, Cow::Borrowed(content))
 		};
 
-		let rendered_content = hbs_render(content.as_str(), &data_scope)?;
+		let rendered_content = hbs_render_with_partials(content.as_str(), agent.partials(), &data_scope)?;
 
 		// If options_line, then we extract it
 		let (options_str, rendered_content) = if options_line {
@@ -124,13 +169,23 @@ File Name: '{file_name}'
 			} else {
 				None
 			};
+			let role: ChatRole = kind.into();
+			if role != ChatRole::System
+				&& let Some(messages) = conv_messages.take()
+			{
+				chat_messages.extend(messages.iter().map(ConvMessage::to_chat_message));
+			}
 			chat_messages.push(ChatMessage {
-				role: kind.into(),
+				role,
 				content: rendered_content.into(),
 				options,
 			})
 		}
 	}
+	// The conversation messages when no non-system part (e.g., the prompt is the last user message)
+	if let Some(messages) = conv_messages {
+		chat_messages.extend(messages.iter().map(ConvMessage::to_chat_message));
+	}
 
 	Ok(chat_messages)
 }
@@ -139,6 +194,8 @@ File Name: '{file_name}'
 pub async fn process_ai(
 	runtime: &Runtime,
 	client: &genai::Client,
+	base_rt_ctx: &RuntimeCtx,
+	literals: &Literals,
 	run_base_options: &RunBaseOptions,
 	run_model_resolved: &ModelName,
 	run_id: Id,
@@ -166,9 +223,23 @@ pub async fn process_ai(
 		}
 	}
 
-	// if dry_mode req, we stop
+	// if dry_mode req, we stop (with the prompt token estimate)
 	// NOTE: dry_mode will be checked also upstream
 	if matches!(run_base_options.dry_mode(), DryMode::Req) {
+		if !is_inst_empty {
+			let encoding = TokenEncoding::for_model(agent.model_resolved());
+			let contents: Vec<String> = chat_messages
+				.iter()
+				.map(|msg| msg.content.joined_texts().unwrap_or_default())
+				.collect();
+			let count = prompt_token_count(contents.iter().map(String::as_str), encoding);
+			hub.publish(format!(
+				"-> Dry run (req) prompt for '{}': {}",
+				agent.model_resolved(),
+				format_token_estimate(count, encoding)
+			))
+			.await;
+		}
 		return Ok(ProcAiResponse { ai_response: None });
 	}
 
@@ -180,6 +251,9 @@ pub async fn process_ai(
 	}
 
 	let ai_response: Option<AiResponse> = if !is_inst_empty {
+		// -- Check the model policy (of the config), before any provider call
+		check_model_policy(client, &agent, model_resolved).await?;
+
 		let prompt_size: usize = chat_messages.iter().map(|c| c.size()).sum();
 
 		// Rt Step Ai Gen start
@@ -188,6 +262,8 @@ pub async fn process_ai(
 		let res = process_send_to_genai(
 			runtime,
 			client,
+			base_rt_ctx,
+			literals,
 			&agent,
 			run_base_options,
 			run_id,
@@ -216,6 +292,8 @@ pub async fn process_ai(
 async fn process_send_to_genai(
 	runtime: &Runtime,
 	client: &genai::Client,
+	base_rt_ctx: &RuntimeCtx,
+	literals: &Literals,
 	agent: &Agent,
 	run_base_options: &RunBaseOptions,
 	run_id: Id,
@@ -231,32 +309,203 @@ async fn process_send_to_genai(
 		.iter()
 		.any(|message| message.options.as_ref().is_some_and(|options| options.cache_control.is_some()));
 
-	let chat_req = ChatRequest::from_messages(chat_messages);
+	let mut chat_req = ChatRequest::from_messages(chat_messages);
+	let tools = agent.tools();
+	if !tools.is_empty() {
+		chat_req = chat_req.with_tools(tools.iter().map(AgentTool::to_genai_tool));
+	}
 
 	hub.publish(format!("-> Sending rendered instruction to {model_resolved} ..."))
 		.await;
 
-	if let Ok(service_target) = client.resolve_service_target(model_resolved).await
+	let service_target = client.resolve_service_target(model_resolved).await.ok();
+	if let Some(service_target) = service_target.as_ref()
 		&& let Some(pricing) = model_pricing(&service_target.model)
 	{
 		// If error, that's fine. Might want to trace it.
 		let _ = rt_model.update_task_model_pricing(run_id, task_id, &pricing).await;
 	}
 
+	// -- The grammar extra body (when the provider supports it)
+	let grammar_body = match agent.options().output_grammar() {
+		Some(grammar) => {
+			let grammar_body = service_target
+				.as_ref()
+				.and_then(|target| grammar_extra_body(target.model.adapter_kind, grammar));
+			if grammar_body.is_none() {
+				hub.publish(format!(
+					"-> Agent option 'output_grammar' ignored (not supported by the provider of model '{model_resolved}')"
+				))
+				.await;
+			}
+			grammar_body
+		}
+		None => None,
+	};
+
+	// -- The logprobs extra body (when the provider supports it)
+	let logprobs_body = if agent.options().logprobs() == Some(true) {
+		let logprobs_body = service_target
+			.as_ref()
+			.and_then(|target| logprobs_extra_body(target.model.adapter_kind, agent.options().top_logprobs()));
+		if logprobs_body.is_none() {
+			hub.publish(format!(
+				"-> Agent option 'logprobs' ignored (not supported by the provider of model '{model_resolved}')"
+			))
+			.await;
+		}
+		logprobs_body
+	} else {
+		None
+	};
+
+	// -- A chat follow-up (from the TUI chat view) streams its response (the logprobs are only in the raw body)
+	let stream_response = run_base_options.chat_turn().is_some() && logprobs_body.is_none();
+
 	let start = Instant::now();
 
-	// compute the cache options with the eventual cache key
+	// compute the cache options with the eventual cache key, grammar, and logprobs
 	// Note: For now, we use the runtime session as the key. Later, we will allow payload to provide it
-	let c_chat_options: Cow<ChatOptions> = if has_cache_control {
-		let opts = agent.genai_chat_options().clone();
-		Cow::Owned(opts.with_prompt_cache_key(runtime.session_str().to_string()))
+	let c_chat_options: Cow<ChatOptions> = if has_cache_control || grammar_body.is_some() || logprobs_body.is_some() {
+		let mut opts = agent.genai_chat_options().clone();
+		if has_cache_control {
+			opts = opts.with_prompt_cache_key(runtime.session_str().to_string());
+		}
+		let mut extra_body = serde_json::Map::new();
+		if let Some(Value::Object(grammar_body)) = grammar_body {
+			// The grammar already constrains the output (and the servers do not combine it with the response format)
+			opts.response_format = None;
+			extra_body.extend(grammar_body);
+		}
+		if let Some(Value::Object(logprobs_body)) = logprobs_body {
+			// The logprobs are only in the raw response body
+			opts = opts.with_capture_raw_body(true);
+			extra_body.extend(logprobs_body);
+		}
+		if !extra_body.is_empty() {
+			opts = opts.with_extra_body(Value::Object(extra_body));
+		}
+		Cow::Owned(opts)
 	} else {
 		Cow::Borrowed(agent.genai_chat_options())
 	};
 
-	let chat_res = client
-		.exec_chat(model_resolved, chat_req, Some(c_chat_options.as_ref()))
-		.await?;
+	let mut chat_res = if stream_response {
+		exec_chat_streamed(
+			runtime,
+			client,
+			task_id,
+			model_resolved,
+			chat_req.clone(),
+			&c_chat_options,
+		)
+		.await?
+	} else {
+		client
+			.exec_chat(model_resolved, chat_req.clone(), Some(c_chat_options.as_ref()))
+			.await?
+	};
+	let mut ai_price = get_price(&chat_res);
+	let mut total_usage = chat_res.usage.clone();
+
+	// -- The tool calls loop (call the tool handlers, and send back their responses until the model is done)
+	let mut tool_rounds = 0;
+	while !tools.is_empty() && chat_res.content.contains_tool_call() {
+		if tool_rounds >= MAX_TOOL_ROUNDS {
+			return Err(Error::custom(format!(
+				"Model '{model_resolved}' still calling tools after {MAX_TOOL_ROUNDS} tool rounds. Stopping."
+			)));
+		}
+		tool_rounds += 1;
+
+		let tool_calls: Vec<ToolCall> = chat_res.content.tool_calls().into_iter().cloned().collect();
+		let thought_signatures = chat_res.content.clone().into_thought_signatures();
+		chat_req = chat_req.append_message(ChatMessage::assistant_tool_calls_with_thoughts(
+			tool_calls.clone(),
+			thought_signatures,
+		));
+
+		let mut tool_responses = Vec::with_capacity(tool_calls.len());
+		for tool_call in tool_calls.iter() {
+			hub.publish(format!("-> Tool call '{}'", tool_call.fn_name)).await;
+			let content = exec_tool_call(runtime, base_rt_ctx, literals, agent, tool_call).await?;
+			tool_responses.push(ToolResponse::from_tool_call(tool_call, content));
+		}
+		chat_req = chat_req.append_message(tool_responses);
+
+		chat_res = client
+			.exec_chat(model_resolved, chat_req.clone(), Some(c_chat_options.as_ref()))
+			.await?;
+		ai_price = add_price(ai_price, get_price(&chat_res));
+		add_usage(&mut total_usage, &chat_res.usage);
+	}
+
+	// -- The auto continuation (request the rest of the truncated response, and stitch the segments)
+	let agent_options = agent.options();
+	let max_continuations = agent_options.auto_continue().unwrap_or(0);
+	let mut continuations = 0;
+	if chat_res.stop_reason.as_ref().is_some_and(StopReason::is_max_tokens) && max_continuations > 0 {
+		let mut content = chat_res.content.joined_texts().unwrap_or_default();
+		while chat_res.stop_reason.as_ref().is_some_and(StopReason::is_max_tokens) && continuations < max_continuations
+		{
+			continuations += 1;
+			hub.publish(format!(
+				"-> Response truncated (max tokens), requesting continuation ({continuations}/{max_continuations})"
+			))
+			.await;
+
+			let continuation_req = chat_req
+				.clone()
+				.append_message(ChatMessage::assistant(content.clone()))
+				.append_message(ChatMessage::user(CONTINUATION_PROMPT));
+			chat_res = client
+				.exec_chat(model_resolved, continuation_req, Some(c_chat_options.as_ref()))
+				.await?;
+			ai_price = add_price(ai_price, get_price(&chat_res));
+			add_usage(&mut total_usage, &chat_res.usage);
+
+			let continuation = chat_res.content.joined_texts().unwrap_or_default();
+			content = stitch_continuation(&content, &continuation);
+		}
+		chat_res.content = MessageContent::from_text(content);
+	}
+
+	// -- The JSON output (parse and validate, and send back a repair prompt until valid)
+	let mut json_repairs = 0;
+	let json = if agent_options.is_output_json() {
+		loop {
+			let content = chat_res.content.joined_texts().unwrap_or_default();
+			let errors = match parse_json_output(&content, agent_options.output_schema()) {
+				Ok(json) => break Some(json),
+				Err(errors) => errors,
+			};
+			if json_repairs >= MAX_JSON_REPAIRS {
+				return Err(Error::custom(format!(
+					"Model '{model_resolved}' did not return a valid JSON output (after {MAX_JSON_REPAIRS} repair prompts).\nCause:\n{errors}"
+				)));
+			}
+			json_repairs += 1;
+			hub.publish(format!(
+				"-> Invalid JSON output, sending repair prompt ({json_repairs}/{MAX_JSON_REPAIRS})"
+			))
+			.await;
+
+			chat_req = chat_req
+				.append_message(ChatMessage::assistant(content))
+				.append_message(ChatMessage::user(json_repair_prompt(
+					&errors,
+					agent_options.output_schema(),
+				)));
+			chat_res = client
+				.exec_chat(model_resolved, chat_req.clone(), Some(c_chat_options.as_ref()))
+				.await?;
+			ai_price = add_price(ai_price, get_price(&chat_res));
+			add_usage(&mut total_usage, &chat_res.usage);
+		}
+	} else {
+		None
+	};
+
 	let duration = start.elapsed();
 
 	// region:    --- First Info Part
@@ -269,7 +518,6 @@ async fn process_send_to_genai(
 	let mut info = duration_msg;
 
 	// Compute the price
-	let ai_price = get_price(&chat_res);
 	let price_usd = ai_price.as_ref().map(|ap| ap.cost);
 
 	// -- Rt Rec - Update Cost
@@ -290,9 +538,30 @@ async fn process_send_to_genai(
 		info = format!("{info} | ~${}", ai_price.cost)
 	}
 
-	let usage_msg = format_usage(&chat_res.usage);
+	let usage_msg = format_usage(&total_usage);
 	info = format!("{info} | {usage_msg}");
 
+	if tool_rounds > 0 {
+		info = format!("{info} | Tool rounds: {tool_rounds}");
+	}
+
+	if continuations > 0 {
+		info = format!("{info} | Continuations: {continuations}");
+	}
+
+	if json_repairs > 0 {
+		info = format!("{info} | JSON repairs: {json_repairs}");
+	}
+
+	// -- Finish Reason (of the last response)
+	let finish_reason = chat_res.stop_reason.as_ref().map(finish_reason_name);
+	if let Some(finish_reason) = finish_reason {
+		let _ = rt_model.update_task_finish_reason(run_id, task_id, finish_reason).await;
+		if matches!(finish_reason, FINISH_MAX_TOKENS | FINISH_CONTENT_FILTER) {
+			info = format!("{info} | Finish: {finish_reason}");
+		}
+	}
+
 	// endregion: --- First Info Part
 
 	hub.publish(format!(
@@ -304,18 +573,27 @@ async fn process_send_to_genai(
 	let ChatResponse {
 		content,
 		reasoning_content,
-		usage,
 		model_iden: res_model_iden,
 		provider_model_iden,
+		stop_reason,
+		captured_raw_body,
 		..
 	} = chat_res;
 
+	let logprobs = captured_raw_body.as_ref().and_then(AiLogprobs::from_raw_body);
+
 	// -- Rt Rec - Update Task Usage
+	let usage = total_usage;
 	rt_model
 		.update_task_usage(run_id, task_id, &usage, &provider_model_iden)
 		.await?;
 
-	let ai_response_content = content.into_joined_texts().filter(|s| !s.is_empty());
+	// -- The post-processors (agent option `post`)
+	let ai_response_content = match content.into_joined_texts() {
+		Some(content) => Some(process_ai_post(runtime, base_rt_ctx, literals, agent, content).await?),
+		None => None,
+	};
+	let ai_response_content = ai_response_content.filter(|s| !s.is_empty());
 	let ai_response_reasoning_content = reasoning_content;
 
 	let model_info = format_model(agent, &res_model_iden, &provider_model_iden, &agent.options());
@@ -331,6 +609,7 @@ async fn process_send_to_genai(
 
 	Ok(AiResponse {
 		content: ai_response_content,
+		json,
 		reasoning_content: ai_response_reasoning_content,
 		model_name: res_model_iden.model_name,
 		adapter_kind: res_model_iden.adapter_kind,
@@ -338,11 +617,190 @@ async fn process_send_to_genai(
 		price_usd,
 		usage,
 		info,
+		finish_reason: finish_reason.map(|v| v.to_string()),
+		finish_reason_raw: stop_reason.map(|v| v.raw().to_string()),
+		logprobs,
 	})
 }
 
 // region:    --- Support
 
+/// Enforce the `[model_policy]` of the config (if any) on the resolved model and its provider.
+async fn check_model_policy(client: &genai::Client, agent: &Agent, model_resolved: &ModelName) -> Result<()> {
+	let Some(model_policy) = agent.config().and_then(|config| config.model_policy()) else {
+		return Ok(());
+	};
+	let service_target = client.resolve_service_target(model_resolved).await?;
+	let model_iden = service_target.model;
+	model_policy.check(&model_iden.model_name, model_iden.adapter_kind.as_lower_str())
+}
+
+/// Evaluate the tool handler script (with `tool_args`) and returns its value as the tool response content.
+async fn exec_tool_call(
+	runtime: &Runtime,
+	base_rt_ctx: &RuntimeCtx,
+	literals: &Literals,
+	agent: &Agent,
+	tool_call: &ToolCall,
+) -> Result<String> {
+	let tool = agent
+		.tools()
+		.iter()
+		.find(|tool| tool.name == tool_call.fn_name)
+		.ok_or_else(|| Error::custom(format!("Model called an unknown tool '{}'", tool_call.fn_name)))?;
+
+	let lua_engine = runtime.new_lua_engine_with_ctx(literals, base_rt_ctx.with_stage(Stage::Ai))?;
+	let lua_scope = lua_engine.create_table()?;
+	lua_scope.set(
+		"tool_args",
+		lua_engine.serde_to_lua_value(tool_call.fn_arguments.clone())?,
+	)?;
+	lua_scope.set("options", agent.options_as_ref())?;
+	lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
+
+	let lua_value = lua_engine
+		.eval_with_paths(&tool.handler, Some(lua_scope), agent.context_dirs())
+		.await
+		.map_err(|err| Error::custom(format!("Tool '{}' handler failed.\nCause: {err}", tool.name)))?;
+
+	let content = match serde_json::to_value(lua_value)? {
+		Value::String(content) => content,
+		Value::Null => String::new(),
+		other => other.to_string(),
+	};
+
+	Ok(content)
+}
+
+/// Parse the AI response content as JSON (the eventual markdown code fence is removed),
+/// and validate it with the eventual schema.
+///
+/// Returns the validation errors (one per line) when invalid.
+/// Exec the chat request with streaming, and update the task `ai_stream` with the text received so far
+/// (shown in the TUI chat view).
+///
+/// NOTE: The task updates are throttled, and their errors do not fail the request.
+async fn exec_chat_streamed(
+	runtime: &Runtime,
+	client: &genai::Client,
+	task_id: Id,
+	model_resolved: &ModelName,
+	chat_req: ChatRequest,
+	chat_options: &ChatOptions,
+) -> Result<ChatResponse> {
+	const FLUSH_INTERVAL: Duration = Duration::from_millis(150);
+
+	let chat_options = chat_options
+		.clone()
+		.with_capture_usage(true)
+		.with_capture_content(true)
+		.with_capture_reasoning_content(true)
+		.with_capture_tool_calls(true);
+	let stream_res = client.exec_chat_stream(model_resolved, chat_req, Some(&chat_options)).await?;
+	let model_iden = stream_res.model_iden;
+	let mut stream = stream_res.stream;
+
+	let update_ai_stream = |text: &str| {
+		let task_u = TaskForUpdate {
+			ai_stream: Some(text.to_string()),
+			..Default::default()
+		};
+		let _ = TaskBmc::update(runtime.mm(), task_id, task_u);
+	};
+
+	let mut text = String::new();
+	let mut last_flush = Instant::now();
+	update_ai_stream(&text);
+	let mut stream_end = None;
+	while let Some(event) = stream.next().await {
+		match event? {
+			ChatStreamEvent::Chunk(chunk) => {
+				text.push_str(&chunk.content);
+				if last_flush.elapsed() >= FLUSH_INTERVAL {
+					update_ai_stream(&text);
+					last_flush = Instant::now();
+				}
+			}
+			ChatStreamEvent::End(end) => stream_end = Some(end),
+			_ => (),
+		}
+	}
+	update_ai_stream(&text);
+
+	let stream_end = stream_end.unwrap_or_default();
+	Ok(ChatResponse {
+		content: stream_end.captured_content.unwrap_or_else(|| MessageContent::from_text(text)),
+		reasoning_content: stream_end.captured_reasoning_content,
+		provider_model_iden: model_iden.clone(),
+		model_iden,
+		stop_reason: stream_end.captured_stop_reason,
+		usage: stream_end.captured_usage.unwrap_or_default(),
+		captured_raw_body: None,
+		response_id: stream_end.captured_response_id,
+	})
+}
+
+fn parse_json_output(content: &str, schema: Option<&Value>) -> core::result::Result<Value, String> {
+	let content = content.trim();
+	let content = match content.strip_prefix("```") {
+		Some(rest) => {
+			// NOTE: Remove the eventual language of the fence (e.g., ```json)
+			let rest = rest.split_once('\n').map(|(_, rest)| rest).unwrap_or_default();
+			rest.trim_end().strip_suffix("```").unwrap_or(rest)
+		}
+		None => content,
+	};
+
+	let json: Value = serde_json::from_str(content).map_err(|err| format!("- Not valid JSON: {err}"))?;
+
+	if let Some(schema) = schema {
+		let errors = validate_json_schema(&json, schema);
+		if !errors.is_empty() {
+			return Err(errors.iter().map(|err| format!("- {err}")).collect::<Vec<_>>().join("\n"));
+		}
+	}
+
+	Ok(json)
+}
+
+fn json_repair_prompt(errors: &str, schema: Option<&Value>) -> String {
+	let mut prompt = format!(
+		"Your previous response is not a valid JSON output.\n\nErrors:\n{errors}\n\n\
+		 Reply again with only the corrected JSON (no explanation, no markdown code fence)."
+	);
+	if let Some(schema) = schema {
+		let schema = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+		prompt.push_str(&format!("\n\nIt must match this JSON schema:\n{schema}"));
+	}
+	prompt
+}
+
+fn add_price(acc: Option<AiPrice>, price: Option<AiPrice>) -> Option<AiPrice> {
+	let add_opt = |a: Option<f64>, b: Option<f64>| match (a, b) {
+		(None, None) => None,
+		(a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+	};
+	match (acc, price) {
+		(Some(acc), Some(price)) => Some(AiPrice {
+			cost: acc.cost + price.cost,
+			cost_cache_write: add_opt(acc.cost_cache_write, price.cost_cache_write),
+			cost_cache_saving: add_opt(acc.cost_cache_saving, price.cost_cache_saving),
+		}),
+		(acc, price) => acc.or(price),
+	}
+}
+
+/// Add the token counts of `usage` to `acc` (the details stay the ones of `acc`)
+fn add_usage(acc: &mut Usage, usage: &Usage) {
+	let add_opt = |a: Option<i32>, b: Option<i32>| match (a, b) {
+		(None, None) => None,
+		(a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+	};
+	acc.prompt_tokens = add_opt(acc.prompt_tokens, usage.prompt_tokens);
+	acc.completion_tokens = add_opt(acc.completion_tokens, usage.completion_tokens);
+	acc.total_tokens = add_opt(acc.total_tokens, usage.total_tokens);
+}
+
 fn get_price(chat_res: &ChatResponse) -> Option<AiPrice> {
 	let provider = chat_res.model_iden.adapter_kind.as_lower_str();
 	let model_name = &*chat_res.model_iden.model_name;
@@ -387,3 +845,81 @@ fn format_model(
 }
 
 // endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::load_inline_agent;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_proc_ai_build_chat_messages_with_messages() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = load_inline_agent(
+			"mock-chat-agent.aip",
+			"# System\n\nYou are helpful.\n\n# Instruction\n\n{{input}}\n",
+		)?;
+		let messages = vec![ConvMessage::new("user", "What is 2+2?")?, ConvMessage::new("assistant", "4")?];
+
+		// -- Exec
+		let chat_messages = build_chat_messages(
+			&runtime,
+			&agent,
+			&Value::Null,
+			&json!("And 3+3?"),
+			&Value::Null,
+			&Attachments::new(Vec::new()),
+			&messages,
+		)?;
+
+		// -- Check
+		let chat_messages: Vec<(String, String)> = chat_messages
+			.iter()
+			.map(|msg| (msg.role.to_string(), msg.content.joined_texts().unwrap_or_default()))
+			.collect();
+		let chat_messages: Vec<(&str, &str)> = chat_messages
+			.iter()
+			.map(|(role, content)| (role.as_str(), content.trim()))
+			.collect();
+		assert_eq!(
+			chat_messages,
+			vec![
+				("System", "You are helpful."),
+				("User", "What is 2+2?"),
+				("Assistant", "4"),
+				("User", "And 3+3?"),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_proc_ai_parse_json_output() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_schema = json!({
+			"type": "object",
+			"properties": {"files": {"type": "array", "items": {"type": "string"}}},
+			"required": ["files"]
+		});
+
+		// -- Exec
+		let fenced = parse_json_output("```json\n{\"files\": [\"a.rs\"]}\n```", Some(&fx_schema))?;
+		let not_json = parse_json_output("Here are the files: a.rs", Some(&fx_schema));
+		let invalid = parse_json_output(r#"{"files": "a.rs"}"#, Some(&fx_schema));
+
+		// -- Check
+		assert_eq!(fenced, json!({"files": ["a.rs"]}));
+		assert!(not_json.is_err_and(|errors| errors.contains("Not valid JSON")));
+		assert!(invalid.is_err_and(|errors| errors.contains("$.files: expected type 'array'")));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
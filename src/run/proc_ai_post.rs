@@ -0,0 +1,144 @@
+//! The AI response post-processors (agent option `post`), applied in order to the `ai_response.content`
+//! before the output stage.
+//!
+//! - Built-ins: `strip_fences`, `trim`, `ensure_trailing_newline`, `dos2unix`
+//! - Custom: `module#function`, the function of a Lua module of the agent or pack `lua/` dir,
+//!   called with the content, and returning the new content.
+
+use crate::agent::Agent;
+use crate::model::{RuntimeCtx, Stage};
+use crate::run::Literals;
+use crate::runtime::Runtime;
+use crate::support::ai_parse::strip_fences;
+use crate::{Error, Result};
+use serde_json::Value;
+
+const BUILTIN_POSTS: &[&str] = &["strip_fences", "trim", "ensure_trailing_newline", "dos2unix"];
+
+/// Apply the post-processors of the agent option `post` (if any) to the AI response content.
+pub async fn process_ai_post(
+	runtime: &Runtime,
+	base_rt_ctx: &RuntimeCtx,
+	literals: &Literals,
+	agent: &Agent,
+	content: String,
+) -> Result<String> {
+	let Some(posts) = agent.options_as_ref().post() else {
+		return Ok(content);
+	};
+
+	let mut content = content;
+	for post in posts {
+		content = match apply_builtin_post(post, &content) {
+			Some(new_content) => new_content,
+			None => exec_custom_post(runtime, base_rt_ctx, literals, agent, post, content).await?,
+		};
+	}
+
+	Ok(content)
+}
+
+// region:    --- Support
+
+/// Returns `None` when the name is not a built-in post-processor.
+fn apply_builtin_post(name: &str, content: &str) -> Option<String> {
+	let content = match name {
+		"strip_fences" => strip_fences(content).to_string(),
+		"trim" => content.trim().to_string(),
+		"ensure_trailing_newline" => {
+			if content.ends_with('\n') {
+				content.to_string()
+			} else {
+				format!("{content}\n")
+			}
+		}
+		"dos2unix" => content.replace("\r\n", "\n"),
+		_ => return None,
+	};
+	Some(content)
+}
+
+/// Call the `module#function` Lua function with the content (with the agent and pack `lua/` dirs in the Lua path).
+async fn exec_custom_post(
+	runtime: &Runtime,
+	base_rt_ctx: &RuntimeCtx,
+	literals: &Literals,
+	agent: &Agent,
+	post: &str,
+	content: String,
+) -> Result<String> {
+	let (module, fn_name) = parse_custom_post(post)?;
+
+	let lua_engine = runtime.new_lua_engine_with_ctx(literals, base_rt_ctx.with_stage(Stage::Ai))?;
+	let lua_scope = lua_engine.create_table()?;
+	lua_scope.set("content", content)?;
+
+	let script = format!(r#"return require("{module}")["{fn_name}"](content)"#);
+	let lua_value = lua_engine
+		.eval_with_paths(&script, Some(lua_scope), agent.context_dirs())
+		.await
+		.map_err(|err| Error::custom(format!("Post processor '{post}' failed.\nCause: {err}")))?;
+
+	match serde_json::to_value(lua_value)? {
+		Value::String(content) => Ok(content),
+		other => Err(Error::custom(format!(
+			"Post processor '{post}' must return a string, but returned: {other}"
+		))),
+	}
+}
+
+/// Parse the `module#function` custom post-processor (e.g., `utils.post#remove_preamble`).
+fn parse_custom_post(post: &str) -> Result<(&str, &str)> {
+	let is_ident = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+	match post.split_once('#') {
+		Some((module, fn_name)) if module.split(['.', '/']).all(is_ident) && is_ident(fn_name) => Ok((module, fn_name)),
+		_ => Err(Error::custom(format!(
+			"Post processor '{post}' is invalid.\nMust be one of {} or a custom 'module#function' (from the agent or pack lua/ dir)",
+			BUILTIN_POSTS.join(", ")
+		))),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_run_proc_ai_post_builtins() -> Result<()> {
+		// -- Setup & Fixtures
+		let content = "\r\n```rust\r\nfn main() {}\r\n```\r\n";
+
+		// -- Exec
+		let mut res = content.to_string();
+		for name in ["dos2unix", "strip_fences", "trim", "ensure_trailing_newline"] {
+			res = apply_builtin_post(name, &res).ok_or("Should be a built-in")?;
+		}
+
+		// -- Check
+		assert_eq!(res, "fn main() {}\n");
+		assert!(apply_builtin_post("my_mod#fn", &res).is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_proc_ai_post_parse_custom() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_custom_post("utils.post#clean")?, ("utils.post", "clean"));
+		assert_eq!(parse_custom_post("post_utils#clean_v2")?, ("post_utils", "clean_v2"));
+		assert!(parse_custom_post("unknown_post").is_err());
+		assert!(parse_custom_post("utils#clean\")").is_err());
+		assert!(parse_custom_post("#clean").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
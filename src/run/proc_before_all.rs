@@ -1,6 +1,6 @@
 //! The before all processor
 
-use crate::agent::{Agent, AgentOptions};
+use crate::agent::{Agent, AgentOptions, AgentTool};
 use crate::model::{Id, LogKind, RuntimeCtx, Stage};
 use crate::run::Literals;
 use crate::runtime::Runtime;
@@ -101,6 +101,7 @@ async fn process_before_all_script(
 	let lua_inputs = inputs.clone().map(Value::Array).unwrap_or(Value::Null);
 	lua_scope.set("inputs", lua_engine.serde_to_lua_value(lua_inputs)?)?;
 	lua_scope.set("options", agent.options_as_ref())?;
+	lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
 
 	// -- Exec the script
 	let lua_value = lua_engine
@@ -128,10 +129,12 @@ async fn process_before_all_script(
 			inputs: inputs_ov,
 			before_all,
 			options,
+			tools,
 		})) => BeforeAllResponse {
 			inputs: inputs_ov.or(inputs),
 			before_all,
 			options,
+			tools,
 		},
 
 		// if it is another AipackCustom, we throw error
@@ -148,6 +151,7 @@ async fn process_before_all_script(
 			inputs,
 			before_all: Some(value),
 			options: None,
+			tools: None,
 		},
 	};
 
@@ -155,6 +159,7 @@ async fn process_before_all_script(
 		inputs,
 		before_all,
 		options: options_to_merge,
+		tools,
 	} = before_all_response;
 
 	// -- Merge the eventual options from before all
@@ -169,6 +174,12 @@ async fn process_before_all_script(
 		None => agent,
 	};
 
+	// -- Set the eventual tools from before all
+	let agent = match tools {
+		Some(tools) => agent.with_tools(AgentTool::list_from_value(tools)?),
+		None => agent,
+	};
+
 	// -- Get the Inputs and Before All data for the next stage
 	// so, if empty, we have one input of value Value::Null
 	let before_all = before_all.unwrap_or_default();
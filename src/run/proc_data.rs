@@ -1,6 +1,6 @@
 use crate::agent::{Agent, AgentOptions};
 use crate::model::{Id, RuntimeCtx, Stage};
-use crate::run::{Attachments, Literals};
+use crate::run::{Attachments, ConvMessage, Literals};
 use crate::runtime::Runtime;
 use crate::script::{AipackCustom, DataResponse, FromValue};
 use crate::{Error, Result};
@@ -14,11 +14,62 @@ pub struct ProcDataResponse {
 	pub input: Value, // will be Null if it was None
 	pub data: Value,
 	pub attachments: Attachments,
+	/// The conversation messages (from the data stage `messages`)
+	pub messages: Vec<ConvMessage>,
 	pub run_model_resolved: ModelName,
+	pub ui: Option<TaskUi>,
 	pub skip: bool,
 	pub redo: bool,
 }
 
+/// The task display metadata returned by the data stage (`_ui = { label = "...", cols = { size = "12kb" } }`),
+/// shown in the TUI tasks overview.
+#[derive(Debug, Default)]
+pub struct TaskUi {
+	pub label: Option<String>,
+	/// The columns as a json object string (the values are strings)
+	pub cols: Option<String>,
+}
+
+impl TaskUi {
+	/// Extract the `_ui` of the data (if any).
+	fn from_data(data: &Value) -> Result<Option<Self>> {
+		let Some(ui) = data.get("_ui") else {
+			return Ok(None);
+		};
+
+		let label = match ui.get("label") {
+			None | Some(Value::Null) => None,
+			Some(Value::String(label)) => Some(label.to_string()),
+			Some(other) => Some(other.to_string()),
+		};
+
+		let cols = match ui.get("cols") {
+			None | Some(Value::Null) => None,
+			Some(Value::Object(cols)) => {
+				let cols: serde_json::Map<String, Value> = cols
+					.iter()
+					.map(|(name, val)| {
+						let val = match val {
+							Value::String(val) => val.to_string(),
+							other => other.to_string(),
+						};
+						(name.to_string(), Value::String(val))
+					})
+					.collect();
+				Some(Value::Object(cols).to_string())
+			}
+			Some(_) => {
+				return Err(Error::custom(
+					"Data stage '_ui.cols' must be a table of column name to value",
+				));
+			}
+		};
+
+		Ok(Some(Self { label, cols }))
+	}
+}
+
 impl ProcDataResponse {
 	pub fn new_skip(agent: Agent, input: Value, run_model_resolved: ModelName) -> Self {
 		Self {
@@ -26,7 +77,9 @@ impl ProcDataResponse {
 			input,
 			data: Value::Null,
 			attachments: Attachments::new(Vec::new()),
+			messages: Vec::new(),
 			run_model_resolved,
+			ui: None,
 			skip: true,
 			redo: false,
 		}
@@ -59,6 +112,7 @@ pub async fn process_data(
 		data,
 		attachments: attachments_val,
 		options,
+		messages,
 	} = if let Some(data_script) = agent.data_script().as_ref() {
 		// -- Build the scope
 		// Note: Probably way to optimize the number of lua engine we create
@@ -69,6 +123,7 @@ pub async fn process_data(
 		lua_scope.set("input", lua_engine.serde_to_lua_value(input.clone())?)?;
 		lua_scope.set("before_all", lua_engine.serde_to_lua_value(before_all.clone())?)?;
 		lua_scope.set("options", agent.options_as_ref())?;
+		lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
 
 		// -- Rt Step - Data Start
 		rt_step.step_task_data_start(run_id, task_id).await?;
@@ -111,11 +166,13 @@ pub async fn process_data(
 				data,
 				attachments,
 				options,
+				messages,
 			})) => DataResponse {
 				input: input_ov.or(Some(input)),
 				data,
 				attachments,
 				options,
+				messages,
 			},
 
 			FromValue::AipackCustom(other) => {
@@ -132,15 +189,18 @@ pub async fn process_data(
 			data: None,
 			attachments: None,
 			options: None,
+			messages: None,
 		}
 	};
 
 	// -- Normalize the context
 	let input = input.unwrap_or(Value::Null);
 	let data = data.unwrap_or(Value::Null);
+	let ui = TaskUi::from_data(&data)?;
 
 	// Convert raw Value to Attachments using custom deserializer
 	let attachments: Attachments = serde_json::from_value(attachments_val.unwrap_or(Value::Null))?;
+	let messages = ConvMessage::list_from_value(messages)?;
 
 	let agent = if let Some(options_to_merge) = options {
 		let options_to_merge: AgentOptions = serde_json::from_value(options_to_merge)?;
@@ -156,8 +216,40 @@ pub async fn process_data(
 		input,
 		data,
 		attachments,
+		messages,
 		run_model_resolved,
+		ui,
 		skip: false,
 		redo: false,
 	})
 }
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_run_proc_data_task_ui_from_data() -> Result<()> {
+		// -- Setup & Fixtures
+		let data = json!({"content": "...", "_ui": {"label": "main.rs", "cols": {"size": "12kb", "lines": 120}}});
+
+		// -- Exec
+		let ui = TaskUi::from_data(&data)?.ok_or("Should have _ui")?;
+
+		// -- Check
+		assert_eq!(ui.label.as_deref(), Some("main.rs"));
+		let cols: Value = serde_json::from_str(ui.cols.as_deref().ok_or("Should have cols")?)?;
+		assert_eq!(cols, json!({"size": "12kb", "lines": "120"}));
+		assert!(TaskUi::from_data(&json!({"content": "..."}))?.is_none());
+		assert!(TaskUi::from_data(&json!({"_ui": {"cols": "size"}})).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,150 @@
+//! The agent option `incremental`, the change detection skipping the unchanged inputs since the last run.
+//!
+//! - The agent hash is the hash of its resolved content (prompt parts, stage scripts, and options).
+//! - The input hash is the hash of the file content (with its path) for the file inputs (`FileInfo`, `FileRecord`),
+//!   otherwise the hash of the input value.
+//! - When the last `Ok` top run of the agent (in the runs history db) has the same agent hash,
+//!   the inputs with the hash of one of its `Ok` (or cached) tasks are skipped and marked as cached.
+
+use crate::agent::Agent;
+use crate::dir_context::PathResolver;
+use crate::run::run_checkpoint::agent_hash;
+use crate::runtime::Runtime;
+use crate::support::files::hash_file_b58;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use value_ext::JsonValueExt as _;
+
+pub struct Incremental {
+	agent_hash: String,
+	/// The input hashes, by input idx
+	input_hashes: HashMap<usize, String>,
+	/// The input hashes of the last `Ok` run (only when its agent hash is the same)
+	prev_input_hashes: HashSet<String>,
+}
+
+/// Constructor
+impl Incremental {
+	/// Returns None when the agent option `incremental` is not true.
+	pub fn new(runtime: &Runtime, agent: &Agent, indexed_inputs: &[(usize, Value)]) -> Option<Self> {
+		if agent.options_as_ref().incremental() != Some(true) {
+			return None;
+		}
+
+		let agent_hash = agent_hash(agent);
+		let input_hashes = indexed_inputs
+			.iter()
+			.map(|(idx, input)| (*idx, input_hash(runtime, input)))
+			.collect();
+
+		// NOTE: No history (or not readable) is not an error, all the inputs are then run
+		let prev_input_hashes = match runtime.rt_model().last_ok_run_input_hashes(agent) {
+			Ok(Some((run, hashes))) if run.agent_hash.as_deref() == Some(agent_hash.as_str()) => hashes,
+			_ => HashSet::new(),
+		};
+
+		Some(Self {
+			agent_hash,
+			input_hashes,
+			prev_input_hashes,
+		})
+	}
+}
+
+/// Getters
+impl Incremental {
+	pub fn agent_hash(&self) -> &str {
+		&self.agent_hash
+	}
+
+	pub fn input_hash(&self, idx: usize) -> Option<&str> {
+		self.input_hashes.get(&idx).map(|h| h.as_str())
+	}
+
+	/// Returns true when the input (by idx) is unchanged since the last `Ok` run (with the same agent)
+	pub fn is_cached(&self, idx: usize) -> bool {
+		self.input_hash(idx).is_some_and(|hash| self.prev_input_hashes.contains(hash))
+	}
+}
+
+// region:    --- Support
+
+fn input_hash(runtime: &Runtime, input: &Value) -> String {
+	let mut hasher = blake3::Hasher::new();
+
+	let is_file_item = matches!(input.x_get_str("_type"), Ok("FileRecord") | Ok("FileInfo"));
+	let file_hash = input.x_get_str("path").ok().filter(|_| is_file_item).and_then(|path| {
+		let full_path = runtime
+			.dir_context()
+			.resolve_path(runtime.session(), path.into(), PathResolver::WksDir, None)
+			.ok()?;
+		let file_hash = hash_file_b58(&full_path).ok()?;
+		Some(format!("{path}#{file_hash}"))
+	});
+
+	match file_hash {
+		// NOTE: Not the file info itself, which has the file times
+		Some(file_hash) => hasher.update(file_hash.as_bytes()),
+		None => hasher.update(input.to_string().as_bytes()),
+	};
+
+	bs58::encode(hasher.finalize().as_bytes()).into_string()
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::load_inline_agent;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_proc_incremental_input_hash() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let file_01 = json!({"_type": "FileInfo", "path": "file-01.txt", "mtime": 1});
+		let file_01_touched = json!({"_type": "FileInfo", "path": "file-01.txt", "mtime": 2});
+		let file_02 = json!({"_type": "FileInfo", "path": "file-02.txt", "mtime": 1});
+
+		// -- Exec
+		let hash_01 = input_hash(&runtime, &file_01);
+		let hash_01_touched = input_hash(&runtime, &file_01_touched);
+		let hash_02 = input_hash(&runtime, &file_02);
+		let hash_value = input_hash(&runtime, &json!("file-01.txt"));
+
+		// -- Check
+		assert_eq!(hash_01, hash_01_touched, "file times should not change the hash");
+		assert_ne!(hash_01, hash_02);
+		assert_ne!(hash_01, hash_value);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_proc_incremental_is_cached() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = load_inline_agent(
+			"mock-incremental-agent.aip",
+			"# Options\n```toml\nincremental = true\n```\n# Output\n```lua\nreturn input\n```",
+		)?;
+		let inputs = vec![(0, json!("one")), (1, json!("two"))];
+		let mut incremental = Incremental::new(&runtime, &agent, &inputs).ok_or("Should be incremental")?;
+		let prev_hash = incremental.input_hash(0).ok_or("Should have a hash")?.to_string();
+		incremental.prev_input_hashes.insert(prev_hash);
+
+		// -- Exec & Check
+		assert!(incremental.is_cached(0));
+		assert!(!incremental.is_cached(1));
+		assert!(!incremental.is_cached(2));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
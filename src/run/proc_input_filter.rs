@@ -0,0 +1,101 @@
+//! The agent option `input_filter`, the Lua predicate narrowing the inputs before the tasks are created.
+
+use crate::agent::Agent;
+use crate::hub::{HubEvent, get_hub};
+use crate::model::{LogKind, RuntimeCtx, Stage};
+use crate::run::Literals;
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use serde_json::Value;
+
+/// Returns the inputs kept by the agent `input_filter` (all of them when no filter),
+/// with their original index (so that the task idx matches the input idx).
+///
+/// The filter is evaluated for each input, with `input`, `args`, `options`, `before_all`,
+/// and `last_run_start` (the start, in epoch us, of the last `Ok` run of this agent in the runs history) in scope.
+pub async fn filter_inputs(
+	runtime: &Runtime,
+	base_rt_ctx: &RuntimeCtx,
+	agent: &Agent,
+	literals: &Literals,
+	before_all: &Value,
+	inputs: Vec<Value>,
+) -> Result<Vec<(usize, Value)>> {
+	let Some(input_filter) = agent.options_as_ref().input_filter() else {
+		return Ok(inputs.into_iter().enumerate().collect());
+	};
+
+	let script = filter_script(input_filter);
+	// NOTE: No history is not an error (all inputs are then "changed")
+	let last_run_start = runtime.rt_model().last_ok_run_start(agent).ok().flatten();
+
+	let lua_engine = runtime.new_lua_engine_with_ctx(literals, base_rt_ctx.with_stage(Stage::BeforeAll))?;
+
+	let inputs_count = inputs.len();
+	let mut kept_inputs = Vec::new();
+	for (idx, input) in inputs.into_iter().enumerate() {
+		let lua_scope = lua_engine.create_table()?;
+		lua_scope.set("input", lua_engine.serde_to_lua_value(input.clone())?)?;
+		lua_scope.set("before_all", lua_engine.serde_to_lua_value(before_all.clone())?)?;
+		lua_scope.set("options", agent.options_as_ref())?;
+		lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
+		lua_scope.set("last_run_start", last_run_start)?;
+
+		let keep = lua_engine
+			.eval_with_paths(&script, Some(lua_scope), agent.context_dirs())
+			.await
+			.map_err(|err| {
+				Error::custom(format!(
+					"Agent option 'input_filter' failed for input {idx}.\nCause: {err}"
+				))
+			})?;
+
+		if !matches!(keep, mlua::Value::Nil | mlua::Value::Boolean(false)) {
+			kept_inputs.push((idx, input));
+		}
+	}
+
+	let msg = format!("Input filter kept {} of {inputs_count} input(s)", kept_inputs.len());
+	let _ = runtime.rec_log_with_rt_ctx(base_rt_ctx, LogKind::SysInfo, &msg);
+	get_hub().publish(HubEvent::info_short(msg)).await;
+
+	Ok(kept_inputs)
+}
+
+/// The filter as a Lua script (a single expression is returned).
+fn filter_script(input_filter: &str) -> String {
+	let input_filter = input_filter.trim();
+	let has_return = input_filter
+		.split(|c: char| !c.is_alphanumeric() && c != '_')
+		.any(|word| word == "return");
+
+	if has_return {
+		input_filter.to_string()
+	} else {
+		format!("return ({input_filter})")
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_proc_input_filter_filter_script() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(filter_script(" input.ext == 'rs' "), "return (input.ext == 'rs')");
+		assert_eq!(
+			filter_script("if input == nil then return false end\nreturn true"),
+			"if input == nil then return false end\nreturn true"
+		);
+		assert_eq!(filter_script("input.returned"), "return (input.returned)");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
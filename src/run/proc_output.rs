@@ -29,6 +29,7 @@ pub async fn process_output(
 		lua_scope.set("before_all", lua_engine.serde_to_lua_value(before_all)?)?;
 		lua_scope.set("ai_response", ai_response)?;
 		lua_scope.set("options", agent.options_as_ref())?;
+		lua_scope.set("args", lua_engine.serde_to_lua_value(agent.args().clone())?)?;
 
 		let lua_value = lua_engine
 			.eval_with_paths(output_script, Some(lua_scope), agent.context_dirs())
@@ -0,0 +1,160 @@
+//! The text form of the rendered prompt (the chat messages from the agent prompt parts)
+//!
+//! Uses the same `# System`, `# Instruction`, `# Assistant` headers as the `.aip` file,
+//! so that a rendered prompt can be stored, edited by the user, and parsed back for a task redo.
+
+use crate::Result;
+use crate::agent::{PartKind, get_prompt_part_kind, get_prompt_part_options_str, parse_prompt_part_options};
+use genai::chat::{CacheControl, ChatMessage, ChatRole};
+
+/// Render the chat messages as the prompt text.
+///
+/// NOTE: Only the text parts are rendered (binary parts, like attachments, are not part of the prompt text).
+pub fn chat_messages_to_prompt_text(chat_messages: &[ChatMessage]) -> String {
+	let mut buf = String::new();
+
+	for msg in chat_messages {
+		let header = match msg.role {
+			ChatRole::System => "# System",
+			ChatRole::User => "# Instruction",
+			ChatRole::Assistant => "# Assistant",
+			ChatRole::Tool => continue,
+		};
+		let content = msg.content.texts().join("\n");
+
+		if !buf.is_empty() {
+			buf.push('\n');
+		}
+		buf.push_str(header);
+		if msg.options.as_ref().is_some_and(|o| o.cache_control.is_some()) {
+			buf.push_str(" `cache = true`");
+		}
+		buf.push_str("\n\n");
+		buf.push_str(content.trim_end());
+		buf.push('\n');
+	}
+
+	buf
+}
+
+/// Parse a prompt text (as rendered by `chat_messages_to_prompt_text` and eventually edited) into chat messages.
+///
+/// - Headers inside code blocks are ignored.
+/// - Content before the first header is considered as an instruction.
+/// - Empty parts are skipped.
+pub fn prompt_text_to_chat_messages(prompt_text: &str) -> Result<Vec<ChatMessage>> {
+	let mut chat_messages: Vec<ChatMessage> = Vec::new();
+
+	let mut current: (PartKind, Option<String>, Vec<&str>) = (PartKind::Instruction, None, Vec::new());
+	let mut in_code_block = false;
+
+	for line in prompt_text.lines() {
+		if line.starts_with("```") {
+			in_code_block = !in_code_block;
+		}
+
+		let part_kind = if !in_code_block && line.starts_with("# ") {
+			get_prompt_part_kind(&line[2..].to_lowercase())
+		} else {
+			None
+		};
+
+		if let Some(part_kind) = part_kind {
+			let options_str = get_prompt_part_options_str(line)?;
+			let previous = std::mem::replace(&mut current, (part_kind, options_str, Vec::new()));
+			push_chat_message(&mut chat_messages, previous)?;
+		} else {
+			current.2.push(line);
+		}
+	}
+	push_chat_message(&mut chat_messages, current)?;
+
+	Ok(chat_messages)
+}
+
+// region:    --- Support
+
+fn push_chat_message(
+	chat_messages: &mut Vec<ChatMessage>,
+	(kind, options_str, lines): (PartKind, Option<String>, Vec<&str>),
+) -> Result<()> {
+	let content = lines.join("\n");
+	let content = content.trim();
+	if content.is_empty() {
+		return Ok(());
+	}
+
+	let options = match options_str {
+		Some(options_str) => parse_prompt_part_options(&options_str)?,
+		None => None,
+	};
+	let options = if options.is_some_and(|o| o.cache) {
+		Some(CacheControl::Ephemeral.into())
+	} else {
+		None
+	};
+
+	chat_messages.push(ChatMessage {
+		role: kind.into(),
+		content: format!("{content}\n").into(),
+		options,
+	});
+
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_run_prompt_text_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let chat_messages = vec![
+			ChatMessage::system("Be concise."),
+			ChatMessage::user("Summarize:\n\n```md\n# Instruction\nnot a header\n```\n\n# Title\n"),
+			ChatMessage::assistant("Sure,"),
+		];
+
+		// -- Exec
+		let text = chat_messages_to_prompt_text(&chat_messages);
+		let parsed = prompt_text_to_chat_messages(&text)?;
+
+		// -- Check
+		assert!(text.starts_with("# System\n\nBe concise.\n"));
+		assert_eq!(parsed.len(), 3);
+		assert!(matches!(parsed[0].role, ChatRole::System));
+		assert!(matches!(parsed[1].role, ChatRole::User));
+		assert!(matches!(parsed[2].role, ChatRole::Assistant));
+		let inst = parsed[1].content.first_text().ok_or("Should have instruction text")?;
+		assert!(inst.contains("# Instruction\nnot a header"));
+		assert!(inst.contains("# Title"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_prompt_text_parse_edited() -> Result<()> {
+		// -- Setup & Fixtures
+		let text = "Leading text\n\n# System `cache = true`\n\nSystem text\n\n# Assistant\n\n";
+
+		// -- Exec
+		let parsed = prompt_text_to_chat_messages(text)?;
+
+		// -- Check
+		assert_eq!(parsed.len(), 2, "empty assistant part should be skipped");
+		assert!(matches!(parsed[0].role, ChatRole::User));
+		assert!(matches!(parsed[1].role, ChatRole::System));
+		assert!(parsed[1].options.as_ref().is_some_and(|o| o.cache_control.is_some()));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
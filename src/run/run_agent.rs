@@ -1,25 +1,31 @@
 use crate::agent::{Agent, AgentRef};
-use crate::hub::get_hub;
-use crate::model::{Id, LogKind, RuntimeCtx, Stage, TaskForCreate};
-use crate::run::RunBaseOptions;
+use crate::hub::{HubEvent, get_hub};
+use crate::model::{Id, LogKind, RunBmc, RuntimeCtx, Stage, TaskForCreate};
+use crate::run::governance;
 use crate::run::literals::Literals;
 use crate::run::proc_after_all::{ProcAfterAllResponse, process_after_all};
 use crate::run::proc_before_all::{ProcBeforeAllResponse, process_before_all};
+use crate::run::proc_incremental::Incremental;
+use crate::run::proc_input_filter::filter_inputs;
 use crate::run::run_agent_task::run_agent_task_outer;
+use crate::run::run_checkpoint::{RunCheckpoint, agent_hash};
+use crate::run::run_export;
+use crate::run::run_otel::{self, OtelConfig};
+use crate::run::{RunBaseOptions, RunParent, RunRedoData, TaskScheduler, WorkerPool};
 use crate::runtime::Runtime;
 use crate::script::{AipackCustom, FromValue};
 use crate::types::RunAgentResponse;
 use crate::{Error, Result};
 use serde_json::Value;
+use simple_fs::SPath;
 use tokio::task::{JoinError, JoinSet};
-use uuid::Uuid;
 use value_ext::JsonValueExt;
 
 const DEFAULT_CONCURRENCY: usize = 1;
 
 pub async fn run_agent(
 	runtime: &Runtime,
-	parent_uid: Option<Uuid>,
+	parent: Option<RunParent>,
 	agent: Agent,
 	inputs: Option<Vec<Value>>,
 	run_base_options: &RunBaseOptions,
@@ -32,27 +38,80 @@ pub async fn run_agent(
 	// runtime.rec_trim().await?;
 	// display relative agent path if possible
 	// -- Rt Create - New run
-	let run_id = rt_model.create_run(parent_uid, &agent).await?;
+	let run_id = rt_model.create_run(parent, &agent).await?;
 
 	// -- Rt Step - Start Run
 	let run_id = rt_step.step_run_start(run_id).await?;
 
+	// -- The run controls (for all the runs, and for this run only, e.g., `aip run --cancel <run-uid>`)
 	let cancel_rx_opt = runtime.cancel_rx().cloned();
+	let run_uid = RunBmc::get(runtime.mm(), run_id)?.uid;
+	let run_trx = runtime.run_trx(run_uid);
+
+	// -- The governance report data (the pack capabilities clone shares the usage of the run Lua engines)
+	let literals_res = Literals::from_runtime_and_agent_path(runtime, &agent, run_base_options.parent_capabilities());
+	let governance = agent.config().and_then(|config| config.governance()).cloned();
+	let report_data = governance.map(|governance| {
+		let pack_capabilities = literals_res.as_ref().ok().and_then(|l| l.pack_capabilities().cloned());
+		(governance, agent.clone(), pack_capabilities)
+	});
+
+	// -- The run report export (`--export`, only for the top run)
+	let export_data = match (parent, run_base_options.export_path()) {
+		(None, Some(export_path)) => Some((export_path.to_string(), agent.clone())),
+		_ => None,
+	};
+
+	// -- Only the top agent run tasks are dispatched to the workers (distributed mode)
+	let worker_pool = if parent.is_none() {
+		runtime.worker_pool().cloned()
+	} else {
+		None
+	};
+
+	// -- Only the top agent run tasks take a task slot (the sub agent runs use the slot of their parent task)
+	let task_scheduler = if parent.is_none() {
+		runtime.task_scheduler().cloned()
+	} else {
+		None
+	};
+
+	// -- Only the top agent runs are checkpointed (to be resumed after an interruption, see `RunCheckpoint`)
+	let checkpoint_file = if parent.is_none() {
+		RunCheckpoint::file_for(runtime, &agent, inputs.as_deref(), run_base_options)
+	} else {
+		None
+	};
 
-	let run_future = run_agent_inner(runtime, run_id, agent, inputs, run_base_options, return_output_values);
+	let run_future = run_agent_inner(
+		runtime,
+		run_id,
+		agent,
+		literals_res,
+		inputs,
+		run_base_options,
+		worker_pool,
+		task_scheduler,
+		checkpoint_file.as_ref(),
+		return_output_values,
+	);
 	tokio::pin!(run_future);
 
-	let (run_agent_res, canceled) = if let Some(cancel_rx) = cancel_rx_opt {
+	let (run_agent_res, canceled) = if let (Some(cancel_rx), Some(run_trx)) = (cancel_rx_opt, run_trx) {
 		let cancel_fut = cancel_rx.cancelled();
+		let run_cancel_fut = run_trx.cancelled();
 		tokio::pin!(cancel_fut);
+		tokio::pin!(run_cancel_fut);
 
 		tokio::select! {
 			res = &mut run_future => (res, false),
-			_ = &mut cancel_fut => (Ok(RunAgentResponse{ outputs: None, after_all: None, redo_requested: false }), true)
+			_ = &mut cancel_fut => (Ok(RunAgentResponse::default()), true),
+			_ = &mut run_cancel_fut => (Ok(RunAgentResponse::default()), true)
 		}
 	} else {
 		(run_future.await, false)
 	};
+	runtime.remove_run_trx(run_uid);
 
 	match run_agent_res.as_ref() {
 		// NOTE: Eventually we want to store the after all response as well
@@ -70,19 +129,56 @@ pub async fn run_agent(
 			rt_step.step_run_end_err(run_id, err).await?;
 		}
 	}
-	if parent_uid.is_none() {
+	if parent.is_none() {
 		runtime.file_write_manager().swap_if_used();
+
+		// -- The run ended ok, it will not be resumed (should not fail the run)
+		// NOTE: The checkpoint of an errored or canceled run is kept, so that the next same run resumes it.
+		if let Some(checkpoint_file) = checkpoint_file.as_ref()
+			&& run_agent_res.is_ok()
+			&& !canceled
+			&& let Err(err) = RunCheckpoint::remove(checkpoint_file)
+		{
+			get_hub().publish_err("Cannot remove the run checkpoint", Some(err)).await;
+		}
+
+		// -- Persist to the runs history (should not fail the run)
+		if let Err(err) = rt_model.persist_run_to_history(run_id) {
+			get_hub()
+				.publish_err("Cannot persist the run to the runs history", Some(err))
+				.await;
+		}
+
+		// -- Governance report (opt-in)
+		if let Some((governance, agent, pack_capabilities)) = report_data {
+			governance::send_run_report(runtime, run_id, &agent, pack_capabilities.as_ref(), &governance).await;
+		}
+
+		// -- Run report export (should not fail the run)
+		if let Some((export_path, agent)) = export_data {
+			run_export::export_run_report(runtime, run_id, &agent, &export_path).await;
+		}
+
+		// -- OpenTelemetry traces (opt-in from the OTEL env, should not fail the run)
+		if let Some(otel_config) = OtelConfig::from_env() {
+			run_otel::send_run_traces(runtime, run_id, &otel_config).await;
+		}
 	}
 
 	run_agent_res
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_agent_inner(
 	runtime: &Runtime,
 	run_id: Id,
 	agent: Agent,
+	literals_res: Result<Literals>,
 	inputs: Option<Vec<Value>>,
 	run_base_options: &RunBaseOptions,
+	worker_pool: Option<WorkerPool>,
+	task_scheduler: Option<TaskScheduler>,
+	checkpoint_file: Option<&SPath>,
 	return_output_values: bool,
 ) -> Result<RunAgentResponse> {
 	let hub = get_hub();
@@ -96,28 +192,76 @@ async fn run_agent_inner(
 		.update_run_flow_redo_count(run_id, run_base_options.flow_redo_count())
 		.await?;
 
-	let literals = Literals::from_runtime_and_agent_path(runtime, &agent)?
-		.append("RUN_FLOW_REDO_COUNT", run_base_options.flow_redo_count().to_string());
+	let literals = literals_res?.append("RUN_FLOW_REDO_COUNT", run_base_options.flow_redo_count().to_string());
+
+	// -- The chat follow-up message is the only input (chat view of the TUI)
+	let chat_turn = run_base_options.chat_turn();
+	let inputs = match chat_turn {
+		Some(chat_turn) => Some(vec![Value::String(chat_turn.message().to_string())]),
+		None => inputs,
+	};
+
+	// -- The checkpoint of the interrupted run of the same agent (unchanged), args, and inputs
+	let agent_hash = checkpoint_file.map(|_| agent_hash(&agent));
+	let resumed = match (checkpoint_file, agent_hash.as_deref()) {
+		(Some(checkpoint_file), Some(_)) if run_base_options.fresh() => {
+			RunCheckpoint::remove(checkpoint_file)?;
+			None
+		}
+		(Some(checkpoint_file), Some(agent_hash)) => RunCheckpoint::load(checkpoint_file, agent_hash),
+		_ => None,
+	};
 
 	// -- Process Before All
-	// Rt Step - Start Before All
-	rt_step.step_ba_start(run_id).await?;
-	// process
-	let res = process_before_all(
-		runtime,
-		base_rt_ctx.clone(),
-		run_id,
-		agent.clone(),
-		literals.clone(),
-		inputs.clone(),
-	)
-	.await;
-	// Capture error if anyw
-	if let Err(err) = res.as_ref() {
-		rt_model.set_run_end_error(run_id, Some(Stage::BeforeAll), err)?;
-	}
-	// -- Rt Step - End Before All
-	rt_step.step_ba_end(run_id).await?;
+	// NOTE: For a task redo of a previous run, its before all output and inputs are reused.
+	//       For a chat follow-up of a previous run, its before all output is reused.
+	//       For a resumed run, its before all output, agent options and tools, and task inputs are reused.
+	let redo_of = run_base_options.task_redo().and_then(|t| t.redo_of());
+	let follow_up_of = chat_turn.and_then(|c| c.follow_up_of());
+	let res = match (redo_of, follow_up_of, resumed.as_ref()) {
+		(Some(redo_of), _, _) => Ok(ProcBeforeAllResponse {
+			before_all: redo_of.before_all.clone(),
+			agent,
+			inputs: Some(redo_of.inputs.clone()),
+			skip: false,
+			redo: false,
+		}),
+		(None, Some(follow_up_of), _) => Ok(ProcBeforeAllResponse {
+			before_all: follow_up_of.before_all.clone(),
+			agent,
+			inputs: inputs.clone(),
+			skip: false,
+			redo: false,
+		}),
+		(None, None, Some(resumed)) => resumed.resumed_agent(agent).map(|agent| ProcBeforeAllResponse {
+			before_all: resumed.before_all().clone(),
+			agent,
+			inputs: Some(resumed.indexed_inputs().iter().map(|(_, input)| input.clone()).collect()),
+			skip: false,
+			redo: false,
+		}),
+		(None, None, None) => {
+			// Rt Step - Start Before All
+			rt_step.step_ba_start(run_id).await?;
+			// process
+			let res = process_before_all(
+				runtime,
+				base_rt_ctx.clone(),
+				run_id,
+				agent.clone(),
+				literals.clone(),
+				inputs.clone(),
+			)
+			.await;
+			// Capture error if anyw
+			if let Err(err) = res.as_ref() {
+				rt_model.set_run_end_error(run_id, Some(Stage::BeforeAll), err)?;
+			}
+			// -- Rt Step - End Before All
+			rt_step.step_ba_end(run_id).await?;
+			res
+		}
+	};
 
 	let ProcBeforeAllResponse {
 		before_all,
@@ -139,11 +283,30 @@ async fn run_agent_inner(
 		});
 	}
 
+	// -- Continue the conversation of the followed run (chat follow-up)
+	if let Some(follow_up_of) = follow_up_of {
+		let messages = rt_model.list_conv_messages(follow_up_of.run_id)?;
+		rt_model.add_conv_messages(run_id, None, &messages)?;
+	}
+
+	// -- Continue the conversation of the resumed run
+	if let Some(resumed) = resumed.as_ref() {
+		rt_model.add_conv_messages(run_id, None, resumed.conv_messages())?;
+		hub.publish(HubEvent::info_short(format!(
+			"Resumed the interrupted run ({} of {} task(s) done, `--fresh` to start from the beginning)",
+			resumed.done_count(),
+			resumed.indexed_inputs().len()
+		)))
+		.await;
+	}
+
 	// -- Print the run info
 	print_run_info(runtime, run_id, &agent).await?;
 
 	// -- Run Tasks
+	let mut run_redo_data = None;
 	let (inputs, outputs) = if inputs.as_ref().is_some_and(|v| !v.is_empty()) || agent.has_task_stages() {
+		let has_inputs = inputs.as_ref().is_some_and(|v| !v.is_empty());
 		// IMPORTANT - if if input is None or empty, we create a array of one nil, so that we can one task since we have some task stage
 		let inputs = match inputs {
 			Some(mut inputs) => {
@@ -166,6 +329,65 @@ async fn run_agent_inner(
 			None => vec![Value::Null],
 		};
 
+		// -- The data to redo some tasks of this run (the redone run data for a task redo)
+		run_redo_data = Some(redo_of.cloned().unwrap_or_else(|| RunRedoData {
+			run_id,
+			before_all: before_all.clone(),
+			inputs: inputs.clone(),
+		}));
+
+		// -- Keep only the eventual redo task inputs (the tasks keep their original idx)
+		let indexed_inputs: Vec<(usize, Value)> = match run_base_options.task_redo() {
+			Some(task_redo) => {
+				let task_idxs = task_redo.task_idxs();
+				if let Some(task_idx) = task_idxs.iter().find(|idx| **idx >= inputs.len()) {
+					return Err(Error::custom(format!(
+						"Cannot redo task {task_idx}, the run only has {} input(s)",
+						inputs.len()
+					)));
+				}
+				let idxs_txt = task_idxs.iter().map(|idx| idx.to_string()).collect::<Vec<_>>().join(", ");
+				hub.publish(HubEvent::info_short(format!("Redo task(s) {idxs_txt} only"))).await;
+				inputs
+					.into_iter()
+					.enumerate()
+					.filter(|(idx, _)| task_idxs.contains(idx))
+					.collect()
+			}
+			// NOTE: The resumed run inputs were already filtered (and keep their task idx)
+			None if let Some(resumed) = resumed.as_ref() => resumed.indexed_inputs().to_vec(),
+			// NOTE: The chat follow-up message is never filtered (nor the nil input of the agents run without inputs)
+			None if has_inputs && chat_turn.is_none() => {
+				filter_inputs(runtime, &base_rt_ctx, &agent, &literals, &before_all, inputs).await?
+			}
+			None => inputs.into_iter().enumerate().collect(),
+		};
+
+		// -- The change detection of the incremental runs (not for the task redo and chat follow-up runs)
+		let incremental = (has_inputs && run_base_options.task_redo().is_none() && chat_turn.is_none())
+			.then(|| Incremental::new(runtime, &agent, &indexed_inputs))
+			.flatten();
+		if let Some(incremental) = incremental.as_ref() {
+			rt_model.update_run_agent_hash(run_id, incremental.agent_hash())?;
+		}
+
+		// -- The checkpoint to resume this run (the resumed one, or a new one after the before all and input filter)
+		// NOTE: Not checkpointing should not fail the run
+		let checkpoint = match (resumed, checkpoint_file, agent_hash.as_deref()) {
+			(Some(resumed), _, _) => Some(resumed),
+			(None, Some(checkpoint_file), Some(agent_hash)) => {
+				match RunCheckpoint::start(checkpoint_file, agent_hash, &agent, &before_all, &indexed_inputs) {
+					Ok(checkpoint) => Some(checkpoint),
+					Err(err) => {
+						hub.publish_err("Cannot checkpoint the run (it cannot be resumed)", Some(err))
+							.await;
+						None
+					}
+				}
+			}
+			_ => None,
+		};
+
 		// Rt Step - Tasks Start
 		rt_step.step_tasks_start(run_id).await?;
 
@@ -175,8 +397,13 @@ async fn run_agent_inner(
 			&agent,
 			&literals,
 			run_base_options,
+			worker_pool.as_ref(),
+			task_scheduler.as_ref(),
 			&before_all,
-			&inputs,
+			&indexed_inputs,
+			incremental.as_ref(),
+			checkpoint,
+			redo_of.map(|r| r.run_id),
 			return_output_values,
 		)
 		.await?;
@@ -196,10 +423,12 @@ async fn run_agent_inner(
 			return Ok(RunAgentResponse {
 				outputs,
 				redo_requested: true,
+				run_redo_data,
 				..Default::default()
 			});
 		}
 
+		let inputs = indexed_inputs.into_iter().map(|(_, input)| input).collect::<Vec<_>>();
 		(Some(inputs), outputs)
 	} else {
 		(inputs, None)
@@ -244,6 +473,7 @@ async fn run_agent_inner(
 		after_all,
 		outputs,
 		redo_requested,
+		run_redo_data,
 	})
 }
 
@@ -274,9 +504,11 @@ async fn print_run_info(runtime: &Runtime, run_id: Id, agent: &Agent) -> Result<
 		agent_info = Some(format!(" ({pack_ref} from {kind_pretty})"))
 	}
 	let agent_info = agent_info.as_deref().unwrap_or_default();
+	// The run uid, for the run control of another process (e.g., `aip run --cancel <run-uid>`)
+	let run_uid = RunBmc::get(runtime.mm(), run_id)?.uid;
 	// TODO: might simplify message
 	let msg = format!(
-		"Running agent command: {agent_name}{agent_info}\n                 from: {agent_path}\n   with default model: {model_info}{genai_info}"
+		"Running agent command: {agent_name}{agent_info}\n                 from: {agent_path}\n   with default model: {model_info}{genai_info}\n              run uid: {run_uid}"
 	);
 
 	// -- Rt Rec - Message
@@ -293,11 +525,18 @@ async fn run_tasks(
 	agent: &Agent,
 	literals: &Literals,
 	run_base_options: &RunBaseOptions,
+	worker_pool: Option<&WorkerPool>,
+	task_scheduler: Option<&TaskScheduler>,
 	before_all: &Value,
-	inputs: &[Value],
+	indexed_inputs: &[(usize, Value)],
+	incremental: Option<&Incremental>,
+	mut checkpoint: Option<RunCheckpoint>,
+	redo_of_run_id: Option<Id>,
 	return_output_values: bool,
 ) -> Result<(Option<Vec<(usize, Value)>>, bool)> {
 	let rt_model = runtime.rt_model();
+	// -- The controls of this run only (the pause, e.g., `aip run --pause <run-uid>`)
+	let run_trx = runtime.run_trx(RunBmc::get(runtime.mm(), run_id)?.uid);
 
 	// -- Initialize outputs for capture
 	let mut captured_outputs: Option<Vec<(usize, Value)>> =
@@ -308,9 +547,18 @@ async fn run_tasks(
 		};
 
 	// extract concurrency and allow_run_on_task_fail
-	let concurrency = agent.options().input_concurrency().unwrap_or(DEFAULT_CONCURRENCY);
+	// NOTE: In distributed mode, the concurrency is bounded by the worker slots (the tasks wait for a free slot)
+	let concurrency = match worker_pool {
+		Some(_) => WorkerPool::MAX_IN_FLIGHT_TASKS,
+		None => agent.options().input_concurrency().unwrap_or(DEFAULT_CONCURRENCY),
+	};
 	let allow_run_on_task_fail = agent.options().allow_run_on_task_fail().unwrap_or_default();
 
+	// -- The task slots shared with the other top runs (only when the config `[run] max_tasks` is set)
+	let max_tasks = agent.config().and_then(|config| config.run_max_tasks());
+	let task_scheduler = task_scheduler.zip(max_tasks);
+	let priority = run_base_options.priority();
+
 	// -- Rt Update - model name & concurrency
 	let _ = rt_model
 		.update_run_model_and_concurrency(run_id, agent.model_resolved(), concurrency)
@@ -323,22 +571,25 @@ async fn run_tasks(
 
 	// -- Rt Create all tasks (with their input)
 	// Build tasks-for-create for batch insertion to reduce events and improve performance.
-	let tasks_for_create: Vec<TaskForCreate> = inputs
+	let tasks_for_create: Vec<TaskForCreate> = indexed_inputs
 		.iter()
-		.enumerate()
-		.map(|(idx, input)| TaskForCreate::new_with_input(run_id, idx as i64, None, input))
+		.map(|(idx, input)| TaskForCreate::new_with_input(run_id, *idx as i64, None, input))
 		.collect();
 
 	// Create all tasks in one operation; ids are returned in input order.
 	let task_ids: Vec<Id> = rt_model.create_tasks_batch(run_id, tasks_for_create).await?;
 
+	// -- Rt Update - Link the new attempts to the redone run tasks (task redo)
+	if let Some(redo_of_run_id) = redo_of_run_id {
+		rt_model.link_redo_run(run_id, redo_of_run_id).await?;
+	}
+
 	// Pair each original input with its corresponding index and created task id.
-	let input_idx_task_id_list: Vec<(Value, usize, Id)> = inputs
+	let input_idx_task_id_list: Vec<(Value, usize, Id)> = indexed_inputs
 		.iter()
 		.cloned()
 		.zip(task_ids)
-		.enumerate()
-		.map(|(idx, (input, task_id))| (input, idx, task_id))
+		.map(|((idx, input), task_id)| (input, idx, task_id))
 		.collect();
 
 	// -- Iterate and run each task (concurrency as setup)
@@ -347,34 +598,97 @@ async fn run_tasks(
 			break;
 		}
 
+		// -- Skip the task ended before the interruption of the resumed run (with its output)
+		if let Some(output) = checkpoint.as_ref().and_then(|c| c.done_output(task_idx)) {
+			let reason = "Resumed - ended before the run interruption".to_string();
+			rt_model.rec_resumed_task(run_id, task_id, reason).await?;
+			if let Some(captured_outputs) = captured_outputs.as_mut() {
+				captured_outputs.push((task_idx, output.clone()));
+			}
+			continue;
+		}
+
+		// -- Skip the task when its input and the agent are unchanged since the last run (its output is null)
+		if let Some(incremental) = incremental
+			&& let Some(input_hash) = incremental.input_hash(task_idx)
+		{
+			if incremental.is_cached(task_idx) {
+				let reason = "Cached - input and agent unchanged since the last run".to_string();
+				rt_model.rec_cached_task(run_id, task_id, input_hash, reason).await?;
+				if let Some(captured_outputs) = captured_outputs.as_mut() {
+					captured_outputs.push((task_idx, Value::Null));
+				}
+				continue;
+			}
+			rt_model.update_task_input_hash(task_id, input_hash)?;
+		}
+
 		let runtime_clone = runtime.clone();
 		let agent_clone = agent.clone();
 		let before_all_clone = before_all.clone();
 		let literals = literals.clone();
 
 		let base_run_config_clone = run_base_options.clone();
+		let worker_pool = worker_pool.cloned();
+
+		// -- Hold the next task while the runs, or this run, are paused (tasks in progress complete)
+		if let Some(pause_rx) = runtime.pause_rx() {
+			pause_rx.wait_if_paused().await;
+		}
+		if let Some(run_trx) = run_trx.as_ref() {
+			run_trx.pause_trx().rx().wait_if_paused().await;
+		}
+
+		// -- Wait for a task slot (the higher priority runs get the free slots first)
+		let task_slot = match task_scheduler {
+			Some((task_scheduler, max_tasks)) => Some(task_scheduler.acquire(priority, max_tasks).await?),
+			None => None,
+		};
 
 		// -- Spawn tasks up to the concurrency limit
 		let rt = runtime.clone();
 		join_set.spawn(async move {
+			// NOTE: The slot is given back when the task ends
+			let _task_slot = task_slot;
 			let rt_step = rt.rt_step();
 
 			// -- Rt Step - Task Start
 			let _ = rt_step.step_task_start(run_id, task_id).await;
 
 			// Execute the command agent (this will perform do Data, Instruction, and Output stages)
-			let res = run_agent_task_outer(
-				run_id,
-				task_id,
-				task_idx,
-				&runtime_clone,
-				&agent_clone,
-				before_all_clone,
-				input,
-				&literals,
-				&base_run_config_clone,
-			)
-			.await;
+			// NOTE: In distributed mode, the stages are performed by a worker.
+			let task_fut = async {
+				match worker_pool {
+					Some(worker_pool) => {
+						worker_pool
+							.run_agent_task(&runtime_clone, task_id, task_idx, &agent_clone, before_all_clone, input)
+							.await
+					}
+					None => {
+						run_agent_task_outer(
+							run_id,
+							task_id,
+							task_idx,
+							&runtime_clone,
+							&agent_clone,
+							before_all_clone,
+							input,
+							&literals,
+							&base_run_config_clone,
+						)
+						.await
+					}
+				}
+			};
+
+			// -- Stop the task when canceled (its output is null, the run continues)
+			let res = tokio::select! {
+				res = task_fut => res,
+				_ = rt.task_cancelled(task_id) => {
+					rt_step.step_task_end_canceled(run_id, task_id).await?;
+					return Ok((task_idx, Value::Null));
+				}
+			};
 
 			// -- Rt Step - Task End
 			match res {
@@ -400,7 +714,15 @@ async fn run_tasks(
 		// If we've reached the concurrency limit, wait for one task to complete
 		if in_progress >= concurrency
 			&& let Some(res) = join_set.join_next().await
-			&& process_join_set_res(res, &mut in_progress, &mut captured_outputs).await?
+			&& process_join_set_res(
+				runtime,
+				run_id,
+				res,
+				&mut in_progress,
+				&mut captured_outputs,
+				checkpoint.as_mut(),
+			)
+			.await?
 		{
 			redo_requested = true;
 		}
@@ -409,7 +731,15 @@ async fn run_tasks(
 	// Wait for the remaining tasks to complete
 	while in_progress > 0 {
 		if let Some(res) = join_set.join_next().await
-			&& process_join_set_res(res, &mut in_progress, &mut captured_outputs).await?
+			&& process_join_set_res(
+				runtime,
+				run_id,
+				res,
+				&mut in_progress,
+				&mut captured_outputs,
+				checkpoint.as_mut(),
+			)
+			.await?
 		{
 			redo_requested = true;
 		}
@@ -420,13 +750,23 @@ async fn run_tasks(
 
 type JoinSetResult = core::result::Result<Result<(usize, Value)>, JoinError>;
 async fn process_join_set_res(
+	runtime: &Runtime,
+	run_id: Id,
 	res: JoinSetResult,
 	in_progress: &mut usize,
 	outputs_vec: &mut Option<Vec<(usize, Value)>>,
+	checkpoint: Option<&mut RunCheckpoint>,
 ) -> Result<bool> {
 	*in_progress -= 1;
 	match res {
 		Ok(Ok((task_idx, output))) => {
+			// -- Checkpoint the ended task (should not fail the run)
+			if let Some(checkpoint) = checkpoint
+				&& let Err(err) = checkpoint.rec_task_done(runtime, run_id, task_idx, &output)
+			{
+				get_hub().publish_err("Cannot checkpoint the task", Some(err)).await;
+			}
+
 			// Check for redo
 			let redo = matches!(
 				AipackCustom::from_value(output.clone()),
@@ -456,7 +796,7 @@ pub async fn run_command_agent_input_for_test(
 ) -> Result<Option<Value>> {
 	use crate::run::run_agent_task::run_agent_task_outer;
 
-	let literals = Literals::from_runtime_and_agent_path(runtime, agent)?;
+	let literals = Literals::from_runtime_and_agent_path(runtime, agent, run_base_options.parent_capabilities())?;
 
 	//NOTE: Need to reactive.
 	let (idx, output) = run_agent_task_outer(
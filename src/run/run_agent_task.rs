@@ -5,10 +5,12 @@ use crate::run::literals::Literals;
 use crate::run::proc_ai::{ProcAiResponse, build_chat_messages, process_ai};
 use crate::run::proc_data::{ProcDataResponse, process_data};
 use crate::run::proc_output::process_output;
-use crate::run::{AiResponse, DryMode, RunBaseOptions};
+use crate::run::prompt_text::{chat_messages_to_prompt_text, prompt_text_to_chat_messages};
+use crate::run::{AiResponse, ConvMessage, DryMode, RunBaseOptions};
 use crate::runtime::Runtime;
 use crate::script::{AipackCustom, FromValue};
 use crate::{Error, Result};
+use genai::chat::{ChatMessage, ChatRole};
 use serde::Serialize;
 use serde_json::Value;
 use value_ext::JsonValueExt as _;
@@ -139,6 +141,8 @@ impl RunAgentInputResponse {
 	/// - If OutputResponse, then, the value is result
 	pub fn into_value(self) -> Value {
 		match self {
+			// NOTE: With `output_format = "json"`, the parsed JSON is the value
+			RunAgentInputResponse::AiReponse(AiResponse { json: Some(json), .. }) => json,
 			RunAgentInputResponse::AiReponse(ai_response) => ai_response.content.into(),
 			RunAgentInputResponse::OutputResponse(value) => value,
 		}
@@ -204,7 +208,9 @@ pub async fn run_agent_task(
 		input,
 		data,
 		attachments,
+		messages: data_messages,
 		run_model_resolved,
+		ui,
 		skip,
 		redo: _redo_data,
 	} = res?;
@@ -213,15 +219,83 @@ pub async fn run_agent_task(
 		return Ok(None);
 	}
 
+	// -- Rt Rec - The task display metadata (for the TUI)
+	if let Some(ui) = ui {
+		rt_model.update_task_ui(task_id, ui.label, ui.cols).await?;
+	}
+
 	// -- Execute genai if we have an instruction
 
 	// Rt Step - Start AI stage
 	rt_step.step_task_ai_start(run_id, task_id).await?;
 
-	let chat_messages = build_chat_messages(runtime, &agent, &before_all_result, &input, &data, &attachments)?;
+	// -- The conversation messages (the previous turns of the run, then the data stage `messages`)
+	// NOTE: A chat follow-up (from the TUI) is always a conversation turn.
+	let chat_turn = run_base_options.chat_turn();
+	let conversation = agent.options().conversation() == Some(true) || chat_turn.is_some();
+	let mut messages = if conversation {
+		rt_model.list_conv_messages(run_id)?
+	} else {
+		Vec::new()
+	};
+	let history_len = messages.len();
+	messages.extend(data_messages);
+
+	let mut chat_messages = build_chat_messages(
+		runtime,
+		&agent,
+		&before_all_result,
+		&input,
+		&data,
+		&attachments,
+		&messages,
+	)?;
+
+	// -- Replace the prompt with the eventual edited one (task redo from the TUI)
+	// NOTE: The attachments are the first messages (one per attachment), and they are kept.
+	let attachments_len = attachments.list.len();
+	if let Some(instruction) = run_base_options.task_redo().and_then(|t| t.instruction()) {
+		chat_messages.truncate(attachments_len);
+		chat_messages.extend(prompt_text_to_chat_messages(instruction)?);
+	}
+
+	// -- For a chat follow-up, the message is the user turn (rather than the agent prompt parts)
+	// NOTE: The conversation messages are after the system parts of the prompt (see `build_chat_messages`).
+	if let Some(chat_turn) = chat_turn {
+		let system_len = chat_messages
+			.get(attachments_len..)
+			.unwrap_or_default()
+			.iter()
+			.take_while(|msg| matches!(msg.role, ChatRole::System))
+			.count();
+		chat_messages.truncate(attachments_len + system_len + messages.len());
+		chat_messages.push(ChatMessage::user(chat_turn.message()));
+	}
+
+	// -- Rt Rec - Store the rendered prompt (without the attachments)
+	let prompt_text = chat_messages_to_prompt_text(chat_messages.get(attachments_len..).unwrap_or_default());
+	if !prompt_text.is_empty() {
+		rt_model.update_task_prompt(task_id, &prompt_text).await?;
+	}
+
+	// -- The new turn of the conversation (the user and assistant messages, without the previous turns)
+	let mut turn_messages: Vec<ConvMessage> = if conversation {
+		chat_messages
+			.get(attachments_len..)
+			.unwrap_or_default()
+			.iter()
+			.filter_map(ConvMessage::from_chat_message)
+			.skip(history_len)
+			.collect()
+	} else {
+		Vec::new()
+	};
+
 	let res = process_ai(
 		runtime,
 		client,
+		&base_rt_ctx,
+		literals,
 		run_base_options,
 		&run_model_resolved,
 		run_id,
@@ -244,6 +318,12 @@ pub async fn run_agent_task(
 		return Ok(None);
 	}
 
+	// -- Rt Rec - Append the turn to the run conversation
+	if conversation && let Some(content) = ai_response.as_ref().and_then(|res| res.content.as_deref()) {
+		turn_messages.push(ConvMessage::new("assistant", content)?);
+		rt_model.add_conv_messages(run_id, Some(task_id), &turn_messages)?;
+	}
+
 	// -- Exec output
 	// -- Rt Step - start output
 	rt_step.step_task_output_start(run_id, task_id).await?;
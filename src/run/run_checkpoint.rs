@@ -0,0 +1,446 @@
+//! The run checkpoints, to resume an interrupted top run (e.g., aipack quit during its tasks) where it left off.
+//!
+//! - The checkpoint file is `.aipack/.session/_checkpoints/<key>.jsonl`, the key being the hash of the agent path,
+//!   its args, and the run inputs (so the same `aip run ...` finds it).
+//! - Its first line is written after the `# Before All` and the input filter (before all output, agent options
+//!   and tools, and the task inputs), then one line per ended task (its output, and the new conversation messages).
+//! - When the same agent (unchanged content) is run again with the same args and inputs, the run is resumed:
+//!   the `# Before All` and the input filter are not re-run, and the ended tasks are skipped with their output.
+//! - It is removed when the run ends ok (kept when it ends in error or is canceled), and ignored (removed)
+//!   with `aip run ... --fresh`.
+//! - The sub agent, task redo, chat follow-up, and dry runs are not checkpointed.
+
+use crate::agent::{Agent, AgentOptions, AgentTool};
+use crate::model::Id;
+use crate::run::{ConvMessage, DryMode, RunBaseOptions};
+use crate::runtime::Runtime;
+use crate::support::text::blake3_b64u;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use simple_fs::{SPath, ensure_file_dir};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead as _, BufReader, Write as _};
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CheckpointLine {
+	Start {
+		agent_hash: String,
+		before_all: Value,
+		/// The agent options after the before all (e.g., `aip.flow.before_all_response({options = ...})`)
+		options: Box<AgentOptions>,
+		tools: Vec<AgentTool>,
+		/// The task inputs, with their task idx (after the input filter)
+		indexed_inputs: Vec<(usize, Value)>,
+	},
+	TaskDone {
+		task_idx: usize,
+		output: Value,
+	},
+	ConvMessages {
+		messages: Vec<ConvMessage>,
+	},
+}
+
+#[derive(Debug)]
+pub struct RunCheckpoint {
+	file: SPath,
+	before_all: Value,
+	options: AgentOptions,
+	tools: Vec<AgentTool>,
+	indexed_inputs: Vec<(usize, Value)>,
+	/// The ended task outputs, by task idx
+	done_outputs: HashMap<usize, Value>,
+	/// The run conversation (agent option `conversation = true`)
+	conv_messages: Vec<ConvMessage>,
+}
+
+/// Constructors
+impl RunCheckpoint {
+	/// The checkpoint file of the top run, None when the run is not checkpointed
+	/// (task redo, chat follow-up, dry mode, or no workspace).
+	pub fn file_for(
+		runtime: &Runtime,
+		agent: &Agent,
+		inputs: Option<&[Value]>,
+		run_base_options: &RunBaseOptions,
+	) -> Option<SPath> {
+		if run_base_options.task_redo().is_some()
+			|| run_base_options.chat_turn().is_some()
+			|| !matches!(run_base_options.dry_mode(), DryMode::None)
+		{
+			return None;
+		}
+		let checkpoints_dir = runtime.dir_context().aipack_paths().checkpoints_dir()?;
+
+		let args = agent.args().to_string();
+		let inputs = inputs
+			.map(|inputs| Value::from(inputs.to_vec()).to_string())
+			.unwrap_or_default();
+		let key = blake3_b64u(&[agent.file_path(), &args, &inputs]);
+
+		Some(checkpoints_dir.join(format!("{key}.jsonl")))
+	}
+
+	/// Write the start of the checkpoint (replacing the eventual previous one)
+	pub fn start(
+		file: &SPath,
+		agent_hash: &str,
+		agent: &Agent,
+		before_all: &Value,
+		indexed_inputs: &[(usize, Value)],
+	) -> Result<Self> {
+		let checkpoint = Self {
+			file: file.clone(),
+			before_all: before_all.clone(),
+			options: agent.options_as_ref().clone(),
+			tools: agent.tools().to_vec(),
+			indexed_inputs: indexed_inputs.to_vec(),
+			done_outputs: HashMap::new(),
+			conv_messages: Vec::new(),
+		};
+
+		ensure_file_dir(file)?;
+		let mut writer =
+			File::create(file).map_err(|err| Error::cc(format!("Cannot create run checkpoint '{file}'"), err))?;
+		let start_line = CheckpointLine::Start {
+			agent_hash: agent_hash.to_string(),
+			before_all: checkpoint.before_all.clone(),
+			options: Box::new(checkpoint.options.clone()),
+			tools: checkpoint.tools.clone(),
+			indexed_inputs: checkpoint.indexed_inputs.clone(),
+		};
+		write_line(&mut writer, &start_line)?;
+
+		Ok(checkpoint)
+	}
+
+	/// Load the checkpoint of an interrupted run, None when there is none,
+	/// or when it was made by another agent content (`agent_hash`).
+	///
+	/// NOTE: A truncated last line (e.g., the process killed while writing it) is ignored.
+	pub fn load(file: &SPath, agent_hash: &str) -> Option<Self> {
+		let reader = BufReader::new(File::open(file).ok()?);
+		let mut lines = reader
+			.lines()
+			.map_while(|line| line.ok())
+			.map_while(|line| serde_json::from_str::<CheckpointLine>(&line).ok());
+
+		let Some(CheckpointLine::Start {
+			agent_hash: checkpoint_agent_hash,
+			before_all,
+			options,
+			tools,
+			indexed_inputs,
+		}) = lines.next()
+		else {
+			return None;
+		};
+		if checkpoint_agent_hash != agent_hash {
+			return None;
+		}
+
+		let mut checkpoint = Self {
+			file: file.clone(),
+			before_all,
+			options: *options,
+			tools,
+			indexed_inputs,
+			done_outputs: HashMap::new(),
+			conv_messages: Vec::new(),
+		};
+		for line in lines {
+			match line {
+				CheckpointLine::TaskDone { task_idx, output } => {
+					checkpoint.done_outputs.insert(task_idx, output);
+				}
+				CheckpointLine::ConvMessages { messages } => checkpoint.conv_messages.extend(messages),
+				// NOTE: Only one start line per checkpoint file
+				CheckpointLine::Start { .. } => return None,
+			}
+		}
+
+		Some(checkpoint)
+	}
+
+	pub fn remove(file: &SPath) -> Result<()> {
+		if file.exists() {
+			fs::remove_file(file).map_err(|err| Error::cc(format!("Cannot remove run checkpoint '{file}'"), err))?;
+		}
+		Ok(())
+	}
+}
+
+/// Getters
+impl RunCheckpoint {
+	pub fn before_all(&self) -> &Value {
+		&self.before_all
+	}
+
+	pub fn indexed_inputs(&self) -> &[(usize, Value)] {
+		&self.indexed_inputs
+	}
+
+	pub fn done_output(&self, task_idx: usize) -> Option<&Value> {
+		self.done_outputs.get(&task_idx)
+	}
+
+	pub fn done_count(&self) -> usize {
+		self.done_outputs.len()
+	}
+
+	pub fn conv_messages(&self) -> &[ConvMessage] {
+		&self.conv_messages
+	}
+
+	/// The agent with the options and tools of the checkpointed run (as after its before all)
+	pub fn resumed_agent(&self, agent: Agent) -> Result<Agent> {
+		let agent = agent.new_merge(self.options.clone())?;
+		Ok(agent.with_tools(self.tools.clone()))
+	}
+}
+
+/// Recorders
+impl RunCheckpoint {
+	/// Append the ended task output, and the conversation messages not yet in the checkpoint.
+	pub fn rec_task_done(&mut self, runtime: &Runtime, run_id: Id, task_idx: usize, output: &Value) -> Result<()> {
+		let mut writer = OpenOptions::new()
+			.append(true)
+			.open(&self.file)
+			.map_err(|err| Error::cc(format!("Cannot open run checkpoint '{}'", self.file), err))?;
+
+		let conv_messages = runtime.rt_model().list_conv_messages(run_id)?;
+		if conv_messages.len() > self.conv_messages.len() {
+			let new_messages = conv_messages[self.conv_messages.len()..].to_vec();
+			write_line(
+				&mut writer,
+				&CheckpointLine::ConvMessages {
+					messages: new_messages.clone(),
+				},
+			)?;
+			self.conv_messages.extend(new_messages);
+		}
+
+		write_line(
+			&mut writer,
+			&CheckpointLine::TaskDone {
+				task_idx,
+				output: output.clone(),
+			},
+		)?;
+		self.done_outputs.insert(task_idx, output.clone());
+
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+/// The hash of the agent content (prompt parts, stage scripts, and options)
+pub(super) fn agent_hash(agent: &Agent) -> String {
+	let mut hasher = blake3::Hasher::new();
+	for part in agent.prompt_parts() {
+		hasher.update(part.content.as_bytes());
+		hasher.update(part.options_str.as_deref().unwrap_or_default().as_bytes());
+	}
+	let scripts = [
+		agent.before_all_script(),
+		agent.data_script(),
+		agent.output_script(),
+		agent.after_all_script(),
+	];
+	for script in scripts {
+		hasher.update(script.unwrap_or_default().as_bytes());
+	}
+	if let Ok(options) = serde_json::to_string(agent.options_as_ref()) {
+		hasher.update(options.as_bytes());
+	}
+
+	bs58::encode(hasher.finalize().as_bytes()).into_string()
+}
+
+fn write_line(writer: &mut File, line: &CheckpointLine) -> Result<()> {
+	let mut content = serde_json::to_string(line).map_err(|err| Error::cc("Cannot serialize run checkpoint", err))?;
+	content.push('\n');
+	writer.write_all(content.as_bytes())?;
+	writer.flush()?;
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{gen_test_dir_path, load_inline_agent, remove_test_dir};
+	use crate::model::ModelManager;
+	use crate::run::{RunCtrl, run_agent};
+	use serde_json::json;
+	use simple_fs::{ensure_dir, read_to_string};
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn test_run_checkpoint_start_and_load() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let dir = gen_test_dir_path();
+		let file = dir.join("checkpoint.jsonl");
+		let agent = load_inline_agent(
+			"mock-checkpoint-agent.aip",
+			"# Options\n```toml\ninput_concurrency = 3\n```\n# Output\n```lua\nreturn input\n```",
+		)?;
+		let indexed_inputs = vec![(0, json!("one")), (2, json!("three"))];
+		let run_id = Id::from(0);
+
+		// -- Exec
+		let mut checkpoint = RunCheckpoint::start(&file, "hash-01", &agent, &json!({"some": "data"}), &indexed_inputs)?;
+		checkpoint.rec_task_done(&runtime, run_id, 2, &json!("THREE"))?;
+		// the truncated line of an interrupted write
+		let mut writer = OpenOptions::new().append(true).open(&file)?;
+		writer.write_all(br#"{"type":"task_done","task_idx":0,"out"#)?;
+		let loaded = RunCheckpoint::load(&file, "hash-01").ok_or("Should load the checkpoint")?;
+		let other_agent = RunCheckpoint::load(&file, "hash-02");
+		let resumed_agent = loaded.resumed_agent(agent)?;
+
+		// -- Check
+		assert_eq!(loaded.before_all(), &json!({"some": "data"}));
+		assert_eq!(loaded.indexed_inputs(), indexed_inputs.as_slice());
+		assert_eq!(loaded.done_count(), 1);
+		assert_eq!(loaded.done_output(2), Some(&json!("THREE")));
+		assert_eq!(loaded.done_output(0), None);
+		assert!(other_agent.is_none(), "other agent content should not resume");
+		assert_eq!(resumed_agent.options().input_concurrency(), Some(3));
+
+		// -- Clean
+		RunCheckpoint::remove(&file)?;
+		assert!(RunCheckpoint::load(&file, "hash-01").is_none());
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_checkpoint_resume_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent = load_inline_agent(
+			"mock-checkpoint-resume-agent.aip",
+			"# Output\n```lua\nreturn string.upper(input)\n```",
+		)?;
+		let inputs = vec![json!("checkpoint-one"), json!("checkpoint-two")];
+		let run_base_options = RunBaseOptions::default();
+		let file = RunCheckpoint::file_for(&runtime, &agent, Some(&inputs), &run_base_options)
+			.ok_or("Should have a checkpoint file")?;
+		// the checkpoint of the interrupted run, with the first task ended
+		let indexed_inputs: Vec<(usize, Value)> = inputs.iter().cloned().enumerate().collect();
+		let mut checkpoint = RunCheckpoint::start(&file, &agent_hash(&agent), &agent, &Value::Null, &indexed_inputs)?;
+		checkpoint.rec_task_done(&runtime, Id::from(0), 0, &json!("RESUMED-ONE"))?;
+
+		// -- Exec
+		let res = run_agent(&runtime, None, agent, Some(inputs), &run_base_options, true).await?;
+
+		// -- Check
+		let outputs = res.outputs.ok_or("Should have outputs")?;
+		assert_eq!(outputs, [json!("RESUMED-ONE"), json!("CHECKPOINT-TWO")]);
+		assert!(!file.exists(), "ended run checkpoint should be removed");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+	async fn test_run_checkpoint_cancel_and_resume_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		// the same workspace, with the run controls to cancel the run
+		let runtime_ctrl = Runtime::new(
+			runtime.dir_context().clone(),
+			runtime.executor_sender(),
+			ModelManager::new().await?,
+			Some(RunCtrl::new()),
+			None,
+		)
+		.await?;
+		let dir = gen_test_dir_path();
+		ensure_dir(&dir)?;
+		let dir = dir.canonicalize()?;
+		let log_file = dir.join("task-one.log");
+		let marker_file = dir.join("task-two.marker");
+		let release_file = dir.join("task-two.release");
+		let released_file = dir.join("task-two.released");
+		// the first task logs each of its runs, the second one waits for the release only in its first run
+		// NOTE: The command blocks a worker thread, hence the release after the cancel.
+		let agent_content = format!(
+			r#"# Output
+```lua
+if input == "checkpoint-cancel-one" then
+	aip.cmd.exec("sh", {{"-c", "echo one >> {log_file}"}})
+else
+	aip.cmd.exec("sh", {{"-c", "test -f {marker_file} || (touch {marker_file} && while [ ! -f {release_file} ]; do sleep 0.05; done; touch {released_file})"}})
+end
+return string.upper(input)
+```"#
+		);
+		let agent = load_inline_agent("mock-checkpoint-cancel-agent.aip", &agent_content)?;
+		let inputs = vec![json!("checkpoint-cancel-one"), json!("checkpoint-cancel-two")];
+		let run_base_options = RunBaseOptions::default();
+		let file = RunCheckpoint::file_for(&runtime, &agent, Some(&inputs), &run_base_options)
+			.ok_or("Should have a checkpoint file")?;
+
+		// -- Exec
+		// first run, canceled while its second task runs
+		let run_handle = tokio::spawn({
+			let (runtime, agent, inputs, run_base_options) = (
+				runtime_ctrl.clone(),
+				agent.clone(),
+				inputs.clone(),
+				run_base_options.clone(),
+			);
+			async move { run_agent(&runtime, None, agent, Some(inputs), &run_base_options, true).await }
+		});
+		for _ in 0..100 {
+			if marker_file.exists() {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+		assert!(marker_file.exists(), "second task should have started");
+		runtime_ctrl.cancel_tx().ok_or("Should have run ctrl")?.cancel();
+		run_handle.await??;
+		std::fs::write(&release_file, "")?;
+		// NOTE: The first run command must end before the test dir removal (otherwise it waits forever).
+		for _ in 0..100 {
+			if released_file.exists() {
+				break;
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+		let checkpoint = RunCheckpoint::load(&file, &agent_hash(&agent));
+		// second run, resumed
+		let res = run_agent(&runtime, None, agent, Some(inputs), &run_base_options, true).await?;
+
+		// -- Check
+		let checkpoint = checkpoint.ok_or("canceled run checkpoint should be kept")?;
+		assert_eq!(checkpoint.done_count(), 1);
+		assert_eq!(checkpoint.done_output(0), Some(&json!("CHECKPOINT-CANCEL-ONE")));
+		let outputs = res.outputs.ok_or("Should have outputs")?;
+		assert_eq!(
+			outputs,
+			[json!("CHECKPOINT-CANCEL-ONE"), json!("CHECKPOINT-CANCEL-TWO")]
+		);
+		assert_eq!(read_to_string(&log_file)?, "one\n", "first task should not run again");
+		assert!(!file.exists(), "resumed run checkpoint should be removed once ok");
+
+		// -- Clean
+		remove_test_dir(&dir)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
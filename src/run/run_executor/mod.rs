@@ -1,21 +1,27 @@
-#![allow(unused)]
-
-//! NOT INTEGRATED YET
+//! The run queue executor responsible for executing runs.
+//!
+//! Any start, pause, or cancel operation for a run goes through the RunQueueExecutor.
 //!
-//! This will be the new run queue executor responsible for executing runs.
-//! The concept is that any start, pause, or cancel operation for a run will go through the
-//! RunQueueExecutor.
-//! Thus, the exec::Executor will send the top-level agent run to the RunQueueExecutor, and
-//! the aip_agent run will likely send the Sub Agent Run command directly to the RunQueueExecutor
-//! (currently, it sends it back to exec::Executor).
+//! - The top agent runs (`aip run ...` and redo) and the sub agent runs (from `aip.agent.run` and
+//!   `aip.agent.run_parallel`) are forwarded by the exec::Executor to the RunQueueExecutor,
+//!   which runs each of them in its own task.
+//! - The RunQueueExecutor owns the `RunCtrl` (cancel, pause, and the task scheduler), given to the Runtime of the runs.
+//! - The `TaskScheduler` shares the task slots (config `[run] max_tasks`) between the top agent runs,
+//!   by priority class (see `RunPriority`).
+//! - The run control requests from other processes (e.g., `aip run --cancel <run-uid>`) are picked up
+//!   by the RunQueueExecutor of the process owning the run, and applied to that run only (see `RunCtrlRequest`, `RunTrx`).
 //!
 
 // region:    --- Module
 
+mod run_ctrl;
 mod run_queue_event;
 mod run_queue_executor;
+mod task_scheduler;
 
+pub use run_ctrl::*;
 pub use run_queue_event::*;
 pub use run_queue_executor::*;
+pub use task_scheduler::*;
 
 // endregion: --- Module
@@ -0,0 +1,316 @@
+//! The run controls (cancel, pause, resume) of the RunQueueExecutor.
+//!
+//! - `RunCtrl` holds the cancel and pause channels shared by the RunQueueExecutor (which triggers them)
+//!   and the Runtime (which listens to them), the canceled tasks (e.g., the TUI selected tasks cancel),
+//!   and the `TaskScheduler` (the task slots shared by the top agent runs).
+//! - `RunTrx` holds the cancel and pause channels of a single run (by run uid), for the `RunCtrlRequest`s.
+//! - `RunCtrlRequest` is the cross-process control path (e.g., `aip run --cancel <run-uid>`),
+//!   persisted as a file in `.aipack/.session/_run-ctrl/` and picked up by the process running the run.
+
+use crate::dir_context::DirContext;
+use crate::event::{CancelTrx, PauseTrx, new_cancel_trx, new_pause_trx};
+use crate::model::Id;
+use crate::run::run_executor::TaskScheduler;
+use crate::{Error, Result};
+use simple_fs::{SPath, ensure_dir, list_files};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+/// Control request files older than this are considered stale (no process picked them up).
+const RUN_CTRL_REQUEST_TTL: Duration = Duration::from_secs(10);
+
+// region:    --- RunCtrl
+
+#[derive(Debug, Clone)]
+pub struct RunCtrl {
+	cancel_trx: CancelTrx,
+	pause_trx: PauseTrx,
+	/// The canceled task ids (the task ids are unique across the runs)
+	canceled_tasks_tx: Arc<watch::Sender<HashSet<Id>>>,
+	task_scheduler: TaskScheduler,
+	/// The controls of each run (by run uid), removed at the end of the run
+	run_trxs: Arc<Mutex<HashMap<Uuid, RunTrx>>>,
+}
+
+/// Constructor
+impl RunCtrl {
+	pub fn new() -> Self {
+		Self {
+			cancel_trx: new_cancel_trx("cancel_run"),
+			pause_trx: new_pause_trx("pause_run"),
+			canceled_tasks_tx: Arc::new(watch::Sender::new(HashSet::new())),
+			task_scheduler: TaskScheduler::default(),
+			run_trxs: Arc::new(Mutex::new(HashMap::new())),
+		}
+	}
+}
+
+/// Getters
+impl RunCtrl {
+	pub fn cancel_trx(&self) -> &CancelTrx {
+		&self.cancel_trx
+	}
+
+	pub fn pause_trx(&self) -> &PauseTrx {
+		&self.pause_trx
+	}
+
+	pub fn task_scheduler(&self) -> &TaskScheduler {
+		&self.task_scheduler
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.pause_trx.tx().is_paused()
+	}
+
+	/// The controls of the run (created on the first call, until `remove_run_trx`)
+	pub fn run_trx(&self, run_uid: Uuid) -> RunTrx {
+		let mut run_trxs = self.run_trxs.lock().unwrap_or_else(|err| err.into_inner());
+		run_trxs.entry(run_uid).or_insert_with(RunTrx::new).clone()
+	}
+
+	pub fn remove_run_trx(&self, run_uid: Uuid) {
+		let mut run_trxs = self.run_trxs.lock().unwrap_or_else(|err| err.into_inner());
+		run_trxs.remove(&run_uid);
+	}
+}
+
+/// Controls
+impl RunCtrl {
+	/// Apply the command to this run only (e.g., `aip run --pause <run-uid>`)
+	pub fn apply_to_run(&self, run_uid: Uuid, cmd: RunCtrlCmd) {
+		self.run_trx(run_uid).apply(cmd);
+	}
+
+	/// Cancel the current runs.
+	/// NOTE: Also resume, otherwise the next run would start paused.
+	pub fn cancel(&self) {
+		self.cancel_trx.tx().cancel();
+		self.pause_trx.tx().resume();
+	}
+
+	/// Pause the current runs (the tasks in progress complete, the next ones wait for resume).
+	pub fn pause(&self) {
+		self.pause_trx.tx().pause();
+	}
+
+	pub fn resume(&self) {
+		self.pause_trx.tx().resume();
+	}
+
+	/// Cancel these tasks (the not started ones do not start, the running ones are stopped).
+	pub fn cancel_tasks(&self, task_ids: &[Id]) {
+		self.canceled_tasks_tx
+			.send_modify(|canceled| canceled.extend(task_ids.iter().copied()));
+	}
+
+	/// Resolves when the task is canceled (immediately if it already is).
+	pub async fn task_cancelled(&self, task_id: Id) {
+		let mut canceled_rx = self.canceled_tasks_tx.subscribe();
+		// NOTE: The sender is held by self, so the wait fails only if dropped (never while self is alive)
+		if canceled_rx.wait_for(|canceled| canceled.contains(&task_id)).await.is_err() {
+			std::future::pending::<()>().await;
+		}
+	}
+}
+
+// endregion: --- RunCtrl
+
+// region:    --- RunTrx
+
+/// The cancel and pause channels of a single run
+#[derive(Debug, Clone)]
+pub struct RunTrx {
+	cancel_trx: CancelTrx,
+	pause_trx: PauseTrx,
+}
+
+impl RunTrx {
+	fn new() -> Self {
+		Self {
+			cancel_trx: new_cancel_trx("cancel_one_run"),
+			pause_trx: new_pause_trx("pause_one_run"),
+		}
+	}
+
+	pub fn pause_trx(&self) -> &PauseTrx {
+		&self.pause_trx
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.pause_trx.tx().is_paused()
+	}
+
+	/// Returns true if the run was canceled
+	/// NOTE: The channels are per run, so any cancel is for this run (even before the run listens).
+	pub fn is_cancelled(&self) -> bool {
+		self.cancel_trx.tx().is_cancelled()
+	}
+
+	/// Resolves when the run is canceled (immediately if it already is).
+	pub async fn cancelled(&self) {
+		let cancel_rx = self.cancel_trx.rx().clone();
+		if self.is_cancelled() {
+			return;
+		}
+		cancel_rx.cancelled().await
+	}
+
+	/// NOTE: Like `RunCtrl::cancel`, a cancel also resumes the run.
+	fn apply(&self, cmd: RunCtrlCmd) {
+		match cmd {
+			RunCtrlCmd::Cancel => {
+				self.cancel_trx.tx().cancel();
+				self.pause_trx.tx().resume();
+			}
+			RunCtrlCmd::Pause => self.pause_trx.tx().pause(),
+			RunCtrlCmd::Resume => self.pause_trx.tx().resume(),
+		}
+	}
+}
+
+// endregion: --- RunTrx
+
+// region:    --- RunCtrlRequest
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum RunCtrlCmd {
+	Cancel,
+	Pause,
+	Resume,
+}
+
+/// A control request for a run, by run reference (the run uid, as the run ids are per process).
+#[derive(Debug, Clone)]
+pub struct RunCtrlRequest {
+	pub run_ref: String,
+	pub cmd: RunCtrlCmd,
+}
+
+impl RunCtrlRequest {
+	pub fn new(run_ref: impl Into<String>, cmd: RunCtrlCmd) -> Self {
+		Self {
+			run_ref: run_ref.into(),
+			cmd,
+		}
+	}
+
+	/// Write the request file (`.aipack/.session/_run-ctrl/<run_ref>.<cmd>`) and return its path.
+	pub fn write(&self, dir_context: &DirContext) -> Result<SPath> {
+		let run_ref = self.run_ref.trim();
+		if run_ref.is_empty() || !run_ref.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+			return Err(Error::custom(format!(
+				"Run reference '{run_ref}' is not valid. Must be a run uid."
+			)));
+		}
+
+		let dir = run_ctrl_dir(dir_context)?;
+		ensure_dir(&dir)?;
+
+		let cmd: &'static str = self.cmd.into();
+		let file = dir.join(format!("{run_ref}.{cmd}"));
+		fs::write(&file, "").map_err(|err| Error::cc(format!("Cannot write run control file '{file}'"), err))?;
+
+		Ok(file)
+	}
+
+	/// Returns the pending requests (with their file) of the workspace.
+	/// Stale request files are removed.
+	pub fn list_pending(dir_context: &DirContext) -> Result<Vec<(RunCtrlRequest, SPath)>> {
+		let dir = run_ctrl_dir(dir_context)?;
+		if !dir.exists() {
+			return Ok(Vec::new());
+		}
+
+		let mut requests = Vec::new();
+		for file in list_files(&dir, Some(&["*.*"]), None)? {
+			let Some(cmd) = file.ext().parse::<RunCtrlCmd>().ok() else {
+				continue;
+			};
+
+			let is_stale = fs::metadata(&file)
+				.and_then(|meta| meta.modified())
+				.ok()
+				.and_then(|modified| SystemTime::now().duration_since(modified).ok())
+				.is_some_and(|age| age > RUN_CTRL_REQUEST_TTL);
+			if is_stale {
+				let _ = fs::remove_file(&file);
+				continue;
+			}
+
+			requests.push((RunCtrlRequest::new(file.stem(), cmd), file));
+		}
+
+		Ok(requests)
+	}
+}
+
+// endregion: --- RunCtrlRequest
+
+// region:    --- Support
+
+fn run_ctrl_dir(dir_context: &DirContext) -> Result<SPath> {
+	let aipack_wks_dir = dir_context
+		.aipack_paths()
+		.aipack_wks_dir()
+		.ok_or("Cannot control runs as no workspace '.aipack/' was found.")?;
+
+	Ok(aipack_wks_dir.join(".session/_run-ctrl"))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::runtime::Runtime;
+
+	#[test]
+	fn test_run_ctrl_cancel_resumes() -> Result<()> {
+		// -- Setup & Fixtures
+		let run_ctrl = RunCtrl::new();
+
+		// -- Exec
+		run_ctrl.pause();
+		let paused = run_ctrl.is_paused();
+		run_ctrl.cancel();
+
+		// -- Check
+		assert!(paused);
+		assert!(!run_ctrl.is_paused());
+		assert!(run_ctrl.cancel_trx().tx().is_cancelled());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_ctrl_request_write_and_list() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+		let dir_context = runtime.dir_context();
+
+		// -- Exec
+		let file = RunCtrlRequest::new("42", RunCtrlCmd::Pause).write(dir_context)?;
+		let pending = RunCtrlRequest::list_pending(dir_context)?;
+
+		// -- Check
+		assert_eq!(file.name(), "42.pause");
+		let (request, _) = pending.first().ok_or("Should have one pending request")?;
+		assert_eq!(request.run_ref, "42");
+		assert_eq!(request.cmd, RunCtrlCmd::Pause);
+		assert!(RunCtrlRequest::new("../x", RunCtrlCmd::Cancel).write(dir_context).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
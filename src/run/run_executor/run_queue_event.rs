@@ -1,6 +1,8 @@
 use crate::Result;
-use crate::event::{OneShotRx, OneShotTx, Tx, new_one_shot_channel};
-use crate::run::{RunSubAgentParams, RunTopAgentParams};
+use crate::event::{OneShotRx, OneShotTx, new_one_shot_channel};
+use crate::exec::cli::RunArgs;
+use crate::run::{RunRedoCtx, RunSubAgentParams};
+use crate::runtime::Runtime;
 use derive_more::From;
 
 #[derive(Debug)]
@@ -23,10 +25,56 @@ impl RunQueueMessage {
 #[derive(Debug, From)]
 #[allow(clippy::large_enum_variant)]
 pub enum RunQueueAction {
-	RunTopAgent(RunTopAgentParams),
+	#[from]
+	RunTopAgent(RunTopAgentJob),
+	#[from]
+	RedoTopAgent(RunRedoJob),
+	#[from]
 	RunSubAgent(RunSubAgentParams),
+	Pause,
+	Resume,
+	Cancel,
 }
 
+// region:    --- Jobs
+
+/// Top agent run (e.g., `aip run my-agent`), responds with the `(redo_ctx, redo_requested)`
+#[derive(Debug)]
+pub struct RunTopAgentJob {
+	pub run_args: RunArgs,
+	pub runtime: Runtime,
+	pub response_tx: OneShotTx<Result<(RunRedoCtx, bool)>>,
+}
+
+impl RunTopAgentJob {
+	pub fn new_and_rx(run_args: RunArgs, runtime: Runtime) -> (Self, OneShotRx<Result<(RunRedoCtx, bool)>>) {
+		let (response_tx, response_rx) = new_one_shot_channel("run_top_agent_response");
+		let job = Self {
+			run_args,
+			runtime,
+			response_tx,
+		};
+		(job, response_rx)
+	}
+}
+
+/// Redo of a top agent run, responds with the new redo_ctx (None if it failed)
+#[derive(Debug)]
+pub struct RunRedoJob {
+	pub redo_ctx: RunRedoCtx,
+	pub response_tx: OneShotTx<Option<RunRedoCtx>>,
+}
+
+impl RunRedoJob {
+	pub fn new_and_rx(redo_ctx: RunRedoCtx) -> (Self, OneShotRx<Option<RunRedoCtx>>) {
+		let (response_tx, response_rx) = new_one_shot_channel("run_redo_response");
+		let job = Self { redo_ctx, response_tx };
+		(job, response_rx)
+	}
+}
+
+// endregion: --- Jobs
+
 // region:    --- QueueTrx
 
 #[derive(Debug, Clone, From)]
@@ -34,7 +82,7 @@ pub struct RunDoneTx(OneShotTx<()>);
 
 impl RunDoneTx {
 	pub async fn send(self, t: ()) -> Result<()> {
-		self.0.send(()).await
+		self.0.send(t).await
 	}
 }
 
@@ -1,10 +1,18 @@
 use crate::event::{Rx, Tx, new_channel};
-use crate::hub::get_hub;
+use crate::exec::{exec_run, exec_run_redo, exec_run_sub_agent};
+use crate::hub::{HubEvent, get_hub};
+use crate::model::RunBmc;
 use crate::run::run_executor::RunQueueMessage;
-use crate::run::run_executor::run_queue_event::RunQueueAction;
+use crate::run::run_executor::run_queue_event::{RunDoneTx, RunQueueAction};
+use crate::run::run_executor::{RunCtrl, RunCtrlRequest};
 use crate::runtime::Runtime;
 use crate::{Error, Result};
 use derive_more::{Deref, From};
+use std::fs;
+use std::time::Duration;
+
+/// How often the run control requests from other processes are checked.
+const RUN_CTRL_WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone, From, Deref)]
 pub struct RunQueueTx(Tx<RunQueueMessage>);
@@ -12,12 +20,22 @@ pub struct RunQueueTx(Tx<RunQueueMessage>);
 pub struct RunQueueExecutor {
 	rx: Rx<RunQueueMessage>,
 	_tx: RunQueueTx,
+	run_ctrl: RunCtrl,
 }
 
 impl RunQueueExecutor {
 	pub fn new() -> Self {
 		let (tx, rx) = new_channel::<RunQueueMessage>("run_queue_executor");
-		Self { rx, _tx: tx.into() }
+		Self {
+			rx,
+			_tx: tx.into(),
+			run_ctrl: RunCtrl::new(),
+		}
+	}
+
+	/// The run controls (cancel, pause) to be given to the Runtime of the runs.
+	pub fn run_ctrl(&self) -> &RunCtrl {
+		&self.run_ctrl
 	}
 
 	/// Consume the key, start it.
@@ -25,20 +43,192 @@ impl RunQueueExecutor {
 	pub fn start(self) -> RunQueueTx {
 		tokio::spawn(async move {
 			let hub = get_hub();
+			let mut ctrl_watcher_started = false;
 			loop {
-				let mut msg = match self.rx.recv().await {
+				let msg = match self.rx.recv().await {
 					Ok(msg) => msg,
 					Err(err) => {
-						hub.publish(Error::cc("Fail in RunQueueExecutor recv", err));
+						hub.publish(Error::cc("Fail in RunQueueExecutor recv", err)).await;
 						continue;
 					}
 				};
 
-				let tx = msg.done_tx;
-				tx.send(()).await;
+				let done_tx = msg.done_tx;
+				match msg.action {
+					// NOTE: Each run gets its own task, so that multiple runs can be performed concurrently
+					//       (e.g., sub agents of `aip.agent.run_parallel(...)`, or a redo while watching).
+					RunQueueAction::RunTopAgent(job) => {
+						// The control requests are only relevant once there is a run (and its workspace)
+						if !ctrl_watcher_started {
+							ctrl_watcher_started = true;
+							start_run_ctrl_watcher(job.runtime.clone(), self.run_ctrl.clone());
+						}
+						tokio::spawn(async move {
+							let res = exec_run(job.run_args, job.runtime).await;
+							if let Err(err) = job.response_tx.send(res).await {
+								get_hub().publish(Error::cc("Fail to send run top agent response", err)).await;
+							}
+							send_done(done_tx).await;
+						});
+					}
+					RunQueueAction::RedoTopAgent(job) => {
+						tokio::spawn(async move {
+							let res = exec_run_redo(&job.redo_ctx).await;
+							if let Err(err) = job.response_tx.send(res).await {
+								get_hub().publish(Error::cc("Fail to send run redo response", err)).await;
+							}
+							send_done(done_tx).await;
+						});
+					}
+					RunQueueAction::RunSubAgent(params) => {
+						tokio::spawn(async move {
+							if let Err(err) = exec_run_sub_agent(params).await {
+								get_hub().publish(Error::cc("Fail to run sub agent", err)).await;
+							}
+							send_done(done_tx).await;
+						});
+					}
+					RunQueueAction::Pause => {
+						self.run_ctrl.pause();
+						send_done(done_tx).await;
+					}
+					RunQueueAction::Resume => {
+						self.run_ctrl.resume();
+						send_done(done_tx).await;
+					}
+					RunQueueAction::Cancel => {
+						self.run_ctrl.cancel();
+						send_done(done_tx).await;
+					}
+				}
 			}
 		});
 
 		self._tx.clone()
 	}
 }
+
+// region:    --- Support
+
+async fn send_done(done_tx: RunDoneTx) {
+	if let Err(err) = done_tx.send(()).await {
+		get_hub().publish(Error::cc("Fail to send RunQueueExecutor done", err)).await;
+	}
+}
+
+/// Watch the run control requests of the workspace (e.g., from `aip run --cancel <run-uid>`).
+fn start_run_ctrl_watcher(runtime: Runtime, run_ctrl: RunCtrl) {
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(RUN_CTRL_WATCH_INTERVAL);
+		loop {
+			interval.tick().await;
+			if let Err(err) = process_run_ctrl_requests(&runtime, &run_ctrl).await {
+				get_hub().publish(Error::cc("Fail to process run control requests", err)).await;
+			}
+		}
+	});
+}
+
+/// Apply the pending requests which target a run of this process (active runs only), to this run only.
+/// The requests for other processes are left untouched (they expire on their own).
+async fn process_run_ctrl_requests(runtime: &Runtime, run_ctrl: &RunCtrl) -> Result<()> {
+	let requests = RunCtrlRequest::list_pending(runtime.dir_context())?;
+	if requests.is_empty() {
+		return Ok(());
+	}
+
+	let active_runs: Vec<_> = RunBmc::list(runtime.mm(), None)?
+		.into_iter()
+		.filter(|run| !run.is_done())
+		.collect();
+
+	for (request, file) in requests {
+		// NOTE: Matched by uid only, as the run ids are per process (all the processes of the workspace poll the requests).
+		let Some(run) = active_runs.iter().find(|run| run.uid.to_string() == request.run_ref) else {
+			continue;
+		};
+
+		// NOTE: Only this run is controlled (the other runs of the process continue)
+		run_ctrl.apply_to_run(run.uid, request.cmd);
+		let _ = fs::remove_file(&file);
+
+		let cmd: &'static str = request.cmd.into();
+		get_hub()
+			.publish(HubEvent::InfoShort(
+				format!("Run '{}' - {cmd} requested", request.run_ref).into(),
+			))
+			.await;
+	}
+
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::create_run;
+	use crate::run::RunCtrlCmd;
+
+	#[tokio::test]
+	async fn test_run_queue_executor_run_ctrl_requests_by_uid() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+		let run_id = create_run(runtime.mm(), "run-ctrl-run")?;
+		let run = RunBmc::get(runtime.mm(), run_id)?;
+		let run_ctrl = RunCtrl::new();
+		// the run ids are per process, so a run id request could target the run of another process
+		let id_file =
+			RunCtrlRequest::new(run_id.as_i64().to_string(), RunCtrlCmd::Pause).write(runtime.dir_context())?;
+
+		// -- Exec
+		process_run_ctrl_requests(&runtime, &run_ctrl).await?;
+		let paused_by_id = run_ctrl.run_trx(run.uid).is_paused();
+		let uid_file = RunCtrlRequest::new(run.uid.to_string(), RunCtrlCmd::Pause).write(runtime.dir_context())?;
+		process_run_ctrl_requests(&runtime, &run_ctrl).await?;
+
+		// -- Check
+		assert!(!paused_by_id);
+		assert!(
+			id_file.exists(),
+			"the run id request should be left for the other processes"
+		);
+		assert!(run_ctrl.run_trx(run.uid).is_paused());
+		assert!(!uid_file.exists(), "the run uid request should be consumed");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_queue_executor_run_ctrl_requests_per_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_for_temp_dir().await?;
+		let run_a = RunBmc::get(runtime.mm(), create_run(runtime.mm(), "run-ctrl-run-a")?)?;
+		let run_b = RunBmc::get(runtime.mm(), create_run(runtime.mm(), "run-ctrl-run-b")?)?;
+		let run_ctrl = RunCtrl::new();
+
+		// -- Exec
+		RunCtrlRequest::new(run_a.uid.to_string(), RunCtrlCmd::Pause).write(runtime.dir_context())?;
+		RunCtrlRequest::new(run_b.uid.to_string(), RunCtrlCmd::Cancel).write(runtime.dir_context())?;
+		process_run_ctrl_requests(&runtime, &run_ctrl).await?;
+
+		// -- Check
+		let (run_a_trx, run_b_trx) = (run_ctrl.run_trx(run_a.uid), run_ctrl.run_trx(run_b.uid));
+		assert!(run_a_trx.is_paused());
+		assert!(!run_a_trx.is_cancelled());
+		assert!(!run_b_trx.is_paused());
+		assert!(run_b_trx.is_cancelled());
+		// the other runs of the process are not controlled
+		assert!(!run_ctrl.is_paused());
+		assert!(!run_ctrl.cancel_trx().tx().is_cancelled());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
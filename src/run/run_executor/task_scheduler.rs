@@ -0,0 +1,286 @@
+//! The task scheduler of the RunQueueExecutor, the task slots shared by the top agent runs of the process.
+//!
+//! - The slots are bounded by the config `[run] max_tasks` (no bound, and no scheduling, when not set).
+//! - A task of a top run takes a slot before it starts, and gives it back when it ends (the task boundary).
+//!   The sub agent runs tasks run in the slot of their parent task (otherwise the parents could hold all the slots).
+//! - A free slot goes to the waiting task of the highest priority class (`interactive` > `normal` > `batch`),
+//!   so a batch run yields its next slots to an interactive run, without stopping its running tasks.
+//! - In a class, the slots go in request order (each run waits for one slot at a time, so the runs take turns),
+//!   and a waiting class gets a slot after `MAX_CLASS_SKIPS` slots went to a higher class (no starvation).
+
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+/// The number of slots a waiting class can be skipped for higher classes before it gets one.
+const MAX_CLASS_SKIPS: u32 = 8;
+
+// region:    --- RunPriority
+
+/// The priority class of a run (`aip run ... --priority <class>`).
+/// NOTE: The TUI task redo and chat follow-up runs are `Interactive`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, strum::EnumString, strum::IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum RunPriority {
+	Interactive,
+	#[default]
+	Normal,
+	Batch,
+}
+
+impl RunPriority {
+	/// The classes, highest priority first
+	const ALL: [RunPriority; 3] = [RunPriority::Interactive, RunPriority::Normal, RunPriority::Batch];
+
+	fn class_idx(self) -> usize {
+		match self {
+			RunPriority::Interactive => 0,
+			RunPriority::Normal => 1,
+			RunPriority::Batch => 2,
+		}
+	}
+}
+
+// endregion: --- RunPriority
+
+// region:    --- TaskScheduler
+
+#[derive(Debug, Clone, Default)]
+pub struct TaskScheduler {
+	inner: Arc<Mutex<SchedulerInner>>,
+}
+
+#[derive(Debug, Default)]
+struct SchedulerInner {
+	/// The max number of running tasks (from the last acquire)
+	max_tasks: usize,
+	/// The number of slots taken
+	running: usize,
+	/// The waiting tasks, by class (in request order)
+	waiting: [VecDeque<oneshot::Sender<TaskSlot>>; 3],
+	/// The number of slots given to a higher class while this class was waiting
+	skips: [u32; 3],
+}
+
+/// The slot of a running task, given back to the scheduler on drop.
+#[derive(Debug)]
+pub struct TaskSlot {
+	scheduler: Option<TaskScheduler>,
+}
+
+/// Scheduling
+impl TaskScheduler {
+	/// Wait for a task slot (at most `max_tasks` slots are taken at a time).
+	pub async fn acquire(&self, priority: RunPriority, max_tasks: usize) -> Result<TaskSlot> {
+		let slot_rx = {
+			let mut inner = self.lock()?;
+			inner.max_tasks = max_tasks.max(1);
+			let (slot_tx, slot_rx) = oneshot::channel();
+			inner.waiting[priority.class_idx()].push_back(slot_tx);
+			self.grant_free_slots(&mut inner);
+			slot_rx
+		};
+
+		slot_rx
+			.await
+			.map_err(|err| Error::custom(format!("Task scheduler dropped the slot request. Cause: {err}")))
+	}
+
+	/// The number of slots taken, and the number of waiting tasks.
+	pub fn stats(&self) -> (usize, usize) {
+		match self.lock() {
+			Ok(inner) => (inner.running, inner.waiting.iter().map(|w| w.len()).sum()),
+			Err(_) => (0, 0),
+		}
+	}
+
+	fn release(&self) {
+		// NOTE: A poisoned lock only happens on a panic while scheduling, the slot is then lost.
+		if let Ok(mut inner) = self.lock() {
+			inner.running = inner.running.saturating_sub(1);
+			self.grant_free_slots(&mut inner);
+		}
+	}
+
+	/// Give the free slots to the waiting tasks (see the module doc for the order).
+	fn grant_free_slots(&self, inner: &mut SchedulerInner) {
+		while inner.running < inner.max_tasks {
+			let Some(priority) = next_class(inner) else {
+				break;
+			};
+			let Some(slot_tx) = inner.waiting[priority.class_idx()].pop_front() else {
+				break;
+			};
+
+			// -- Update the skips of the lower waiting classes
+			inner.skips[priority.class_idx()] = 0;
+			for lower in RunPriority::ALL.iter().filter(|p| p.class_idx() > priority.class_idx()) {
+				if !inner.waiting[lower.class_idx()].is_empty() {
+					inner.skips[lower.class_idx()] += 1;
+				}
+			}
+
+			// NOTE: When the waiting task is gone (e.g., run canceled), the slot is not taken.
+			//       When it goes away after the send, the slot is dropped with the receiver (and given back).
+			match slot_tx.send(TaskSlot {
+				scheduler: Some(self.clone()),
+			}) {
+				Ok(()) => inner.running += 1,
+				Err(mut slot) => slot.scheduler = None,
+			}
+		}
+	}
+
+	fn lock(&self) -> Result<std::sync::MutexGuard<'_, SchedulerInner>> {
+		self.inner
+			.lock()
+			.map_err(|err| Error::custom(format!("Task scheduler lock poisoned. Cause: {err}")))
+	}
+}
+
+impl Drop for TaskSlot {
+	fn drop(&mut self) {
+		if let Some(scheduler) = self.scheduler.take() {
+			scheduler.release();
+		}
+	}
+}
+
+/// The class of the next slot: a class skipped `MAX_CLASS_SKIPS` times (lowest first), otherwise the highest waiting class.
+fn next_class(inner: &SchedulerInner) -> Option<RunPriority> {
+	let waiting = |p: &&RunPriority| !inner.waiting[p.class_idx()].is_empty();
+
+	RunPriority::ALL
+		.iter()
+		.rev()
+		.filter(waiting)
+		.find(|p| inner.skips[p.class_idx()] >= MAX_CLASS_SKIPS)
+		.or_else(|| RunPriority::ALL.iter().find(waiting))
+		.copied()
+}
+
+// endregion: --- TaskScheduler
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use std::time::Duration;
+
+	/// Spawn a task waiting for a slot, which sends its name when granted (and gives the slot back right away)
+	fn spawn_waiter(
+		scheduler: &TaskScheduler,
+		name: &'static str,
+		priority: RunPriority,
+		granted_tx: &tokio::sync::mpsc::UnboundedSender<&'static str>,
+	) {
+		let scheduler = scheduler.clone();
+		let granted_tx = granted_tx.clone();
+		tokio::spawn(async move {
+			let _slot = scheduler.acquire(priority, 1).await;
+			let _ = granted_tx.send(name);
+		});
+	}
+
+	async fn wait_for_waiting(scheduler: &TaskScheduler, count: usize) -> Result<()> {
+		tokio::time::timeout(Duration::from_secs(2), async {
+			while scheduler.stats().1 < count {
+				tokio::time::sleep(Duration::from_millis(5)).await;
+			}
+		})
+		.await?;
+		Ok(())
+	}
+
+	async fn recv_all(
+		granted_rx: &mut tokio::sync::mpsc::UnboundedReceiver<&'static str>,
+		count: usize,
+	) -> Result<Vec<&'static str>> {
+		let mut granted = Vec::new();
+		for _ in 0..count {
+			let name = tokio::time::timeout(Duration::from_secs(2), granted_rx.recv()).await?;
+			granted.push(name.ok_or("Should have a granted task")?);
+		}
+		Ok(granted)
+	}
+
+	#[tokio::test]
+	async fn test_task_scheduler_acquire_priority_order() -> Result<()> {
+		// -- Setup & Fixtures
+		let scheduler = TaskScheduler::default();
+		let running_slot = scheduler.acquire(RunPriority::Batch, 1).await?;
+		let (granted_tx, mut granted_rx) = tokio::sync::mpsc::unbounded_channel();
+		let waiters = [
+			("batch", RunPriority::Batch),
+			("normal-1", RunPriority::Normal),
+			("interactive", RunPriority::Interactive),
+			("normal-2", RunPriority::Normal),
+		];
+		for (count, (name, priority)) in waiters.into_iter().enumerate() {
+			spawn_waiter(&scheduler, name, priority, &granted_tx);
+			wait_for_waiting(&scheduler, count + 1).await?;
+		}
+
+		// -- Exec
+		drop(running_slot);
+		let granted = recv_all(&mut granted_rx, waiters.len()).await?;
+
+		// -- Check
+		assert_eq!(granted, ["interactive", "normal-1", "normal-2", "batch"]);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_task_scheduler_acquire_no_starvation() -> Result<()> {
+		// -- Setup & Fixtures
+		let scheduler = TaskScheduler::default();
+		let running_slot = scheduler.acquire(RunPriority::Interactive, 1).await?;
+		let (granted_tx, mut granted_rx) = tokio::sync::mpsc::unbounded_channel();
+		spawn_waiter(&scheduler, "batch", RunPriority::Batch, &granted_tx);
+		let waiter_count = MAX_CLASS_SKIPS as usize + 3;
+		for count in 1..waiter_count {
+			spawn_waiter(&scheduler, "interactive", RunPriority::Interactive, &granted_tx);
+			wait_for_waiting(&scheduler, count + 1).await?;
+		}
+
+		// -- Exec
+		drop(running_slot);
+		let granted = recv_all(&mut granted_rx, waiter_count).await?;
+
+		// -- Check
+		let batch_pos = granted.iter().position(|n| *n == "batch").ok_or("Batch should be granted")?;
+		assert_eq!(batch_pos, MAX_CLASS_SKIPS as usize);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_task_scheduler_acquire_dropped_waiter() -> Result<()> {
+		// -- Setup & Fixtures
+		let scheduler = TaskScheduler::default();
+		let running_slot = scheduler.acquire(RunPriority::Normal, 1).await?;
+
+		// -- Exec
+		let res = tokio::time::timeout(
+			Duration::from_millis(50),
+			scheduler.acquire(RunPriority::Interactive, 1),
+		)
+		.await;
+		drop(running_slot);
+		let next_slot = tokio::time::timeout(Duration::from_secs(1), scheduler.acquire(RunPriority::Batch, 1)).await;
+
+		// -- Check
+		assert!(res.is_err(), "Should wait while the slot is taken");
+		assert!(next_slot.is_ok(), "Should get the slot of the dropped waiter");
+		assert_eq!(scheduler.stats(), (1, 0));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
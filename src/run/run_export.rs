@@ -0,0 +1,560 @@
+//! The run report export (`aip run ... --export report.md|json|html`), written at the end of the top runs,
+//! for sharing and audit, and the tasks outputs export (the TUI selected tasks export).
+//!
+//! The report is built from the store models (run, tasks, errors, and the task logs), with the agent
+//! prompt templates (the rendered prompts are not stored). The task output is the `# Output` stage return value,
+//! or the AI response content when the agent has no output stage.
+
+use crate::agent::Agent;
+use crate::hub::get_hub;
+use crate::model::{EndState, ErrBmc, Id, LogBmc, LogKind, ModelManager, RunBmc, Task, TaskBmc};
+use crate::runtime::Runtime;
+use crate::support::text::{format_duration_us, format_pretty_size};
+use crate::{Error, Result};
+use genai::chat::ChatRole;
+use serde_json::{Value, json};
+use simple_fs::{SPath, ensure_file_dir};
+
+/// The export format, from the export path extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ExportFormat {
+	Markdown,
+	Json,
+	Html,
+}
+
+impl ExportFormat {
+	pub(super) fn from_path(path: &str) -> Result<Self> {
+		let ext = SPath::new(path).ext().to_lowercase();
+		match ext.as_str() {
+			"md" | "markdown" => Ok(Self::Markdown),
+			"json" => Ok(Self::Json),
+			"html" | "htm" => Ok(Self::Html),
+			_ => Err(Error::custom(format!(
+				"--export '{path}' must end with '.md', '.json', or '.html' (the export format)"
+			))),
+		}
+	}
+}
+
+/// Build and write the run report to the export path (the errors are published, and do not fail the run).
+pub(super) async fn export_run_report(runtime: &Runtime, run_id: Id, agent: &Agent, export_path: &str) {
+	let hub = get_hub();
+
+	let export_file = runtime.dir_context().current_dir().join(export_path);
+	match write_run_report(runtime, run_id, agent, &export_file) {
+		Ok(()) => hub.publish(format!("-> Run report exported to '{export_path}'")).await,
+		Err(err) => {
+			hub.publish_err(format!("Cannot export the run report to '{export_path}'"), Some(err))
+				.await
+		}
+	}
+}
+
+/// Write the outputs of these tasks (in this order) as a markdown file (e.g., the TUI selected tasks export).
+/// Returns the number of tasks exported.
+pub fn export_tasks_outputs(mm: &ModelManager, task_ids: &[Id], export_file: &SPath) -> Result<usize> {
+	let mut tasks: Vec<Value> = Vec::new();
+	for task_id in task_ids {
+		let task = TaskBmc::get(mm, *task_id)?;
+		tasks.push(json!({
+			"idx": task.idx,
+			"label": task.label,
+			"end_state": task.end_state.map(|v| v.as_ref().to_string()),
+			"output": TaskBmc::get_output_for_display(mm, &task)?,
+		}));
+	}
+
+	ensure_file_dir(export_file).map_err(Error::from)?;
+	std::fs::write(export_file.as_std_path(), render_tasks_outputs_markdown(&tasks))
+		.map_err(|err| Error::cc(format!("Cannot write '{export_file}'"), err))?;
+
+	Ok(tasks.len())
+}
+
+// region:    --- Support
+
+fn write_run_report(runtime: &Runtime, run_id: Id, agent: &Agent, export_file: &SPath) -> Result<()> {
+	let format = ExportFormat::from_path(export_file.as_str())?;
+	let report = build_run_report(runtime.mm(), run_id, agent)?;
+
+	let content = match format {
+		ExportFormat::Markdown => render_markdown(&report),
+		ExportFormat::Json => {
+			serde_json::to_string_pretty(&report).map_err(|err| Error::cc("Cannot serialize the run report", err))?
+		}
+		ExportFormat::Html => render_html(&report),
+	};
+
+	ensure_file_dir(export_file).map_err(Error::from)?;
+	std::fs::write(export_file.as_std_path(), content)
+		.map_err(|err| Error::cc(format!("Cannot write '{export_file}'"), err))?;
+
+	Ok(())
+}
+
+fn build_run_report(mm: &ModelManager, run_id: Id, agent: &Agent) -> Result<Value> {
+	let run = RunBmc::get(mm, run_id)?;
+	let tasks = TaskBmc::list_for_run(mm, run_id)?;
+
+	let prompts: Vec<Value> = agent
+		.prompt_parts()
+		.into_iter()
+		.map(|part| json!({"role": ChatRole::from(&part.kind).to_string(), "content": part.content}))
+		.collect();
+
+	let task_values = tasks
+		.iter()
+		.map(|task| build_task_report(mm, task))
+		.collect::<Result<Vec<_>>>()?;
+
+	let sum_tk = |get: fn(&Task) -> Option<i64>| tasks.iter().filter_map(get).sum::<i64>();
+	let tasks_cost: f64 = tasks.iter().filter_map(|t| t.cost).sum();
+	let err_count = tasks
+		.iter()
+		.filter(|task| matches!(task.end_state, Some(EndState::Err)))
+		.count();
+
+	Ok(json!({
+		"aipack_version": crate::VERSION,
+		"run": {
+			"id": run.id.as_i64(),
+			"uid": run.uid.to_string(),
+			"label": run.label,
+			"agent_name": run.agent_name,
+			"agent_path": run.agent_path,
+			"model": run.model,
+			"start_us": run.start.map(|v| v.as_i64()),
+			"end_us": run.end.map(|v| v.as_i64()),
+			"duration_us": duration_us(run.start.map(|v| v.as_i64()), run.end.map(|v| v.as_i64())),
+			"end_state": run.end_state.map(|v| v.as_ref().to_string()),
+			"error": get_err_content(mm, run.end_err_id)?,
+			"total_cost": run.total_cost,
+			"redo_of_run_uid": run.redo_of_run_uid.map(|uid| uid.to_string()),
+			"res_cpu_ms": run.res_cpu_ms,
+			"res_peak_mem": run.res_peak_mem,
+			"res_read_bytes": run.res_read_bytes,
+			"res_write_bytes": run.res_write_bytes,
+		},
+		"totals": {
+			"task_count": tasks.len(),
+			"err_count": err_count,
+			"tk_prompt_total": sum_tk(|t| t.tk_prompt_total),
+			"tk_completion_total": sum_tk(|t| t.tk_completion_total),
+			"cost": tasks_cost,
+		},
+		"prompts": prompts,
+		"tasks": task_values,
+	}))
+}
+
+fn build_task_report(mm: &ModelManager, task: &Task) -> Result<Value> {
+	let start = task.start.map(|v| v.as_i64());
+	let end = task.end.map(|v| v.as_i64());
+	let ai_start = task.ai_start.map(|v| v.as_i64());
+	let ai_end = task.ai_end.map(|v| v.as_i64());
+
+	// Only the agent prints and the warnings/errors (not the run steps or debug)
+	let logs: Vec<Value> = LogBmc::list_for_task(mm, task.id)?
+		.into_iter()
+		.filter(|log| {
+			matches!(
+				log.kind,
+				Some(LogKind::AgentPrint | LogKind::SysWarn | LogKind::SysError)
+			)
+		})
+		.map(|log| json!({"kind": log.kind.map(|k| k.to_string()), "message": log.message}))
+		.collect();
+
+	Ok(json!({
+		"idx": task.idx,
+		"label": task.label,
+		"end_state": task.end_state.map(|v| v.as_ref().to_string()),
+		"skip_reason": task.end_skip_reason,
+		"cached": task.cached,
+		"model": task.model_upstream.as_ref().or(task.model_ov.as_ref()),
+		"finish_reason": task.finish_reason,
+		"redo_of_task_uid": task.redo_of_task_uid.map(|uid| uid.to_string()),
+		"duration_us": duration_us(start, end),
+		"ai_duration_us": duration_us(ai_start, ai_end),
+		"tk_prompt_total": task.tk_prompt_total,
+		"tk_completion_total": task.tk_completion_total,
+		"cost": task.cost,
+		"input": TaskBmc::get_input_for_display(mm, task)?,
+		"output": TaskBmc::get_output_for_display(mm, task)?,
+		"error": get_err_content(mm, task.end_err_id)?,
+		"logs": logs,
+	}))
+}
+
+fn get_err_content(mm: &ModelManager, err_id: Option<Id>) -> Result<Option<String>> {
+	match err_id {
+		Some(err_id) => Ok(ErrBmc::get(mm, err_id)?.content),
+		None => Ok(None),
+	}
+}
+
+fn duration_us(start: Option<i64>, end: Option<i64>) -> Option<i64> {
+	match (start, end) {
+		(Some(start), Some(end)) if end >= start => Some(end - start),
+		_ => None,
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Renderers
+
+/// The report summary rows (label, value), shared by the markdown and html renderers
+fn summary_rows(report: &Value) -> Vec<(&'static str, String)> {
+	let run = &report["run"];
+	let totals = &report["totals"];
+
+	let mut rows = vec![
+		("Agent", str_or_dash(&run["agent_name"])),
+		("Agent Path", str_or_dash(&run["agent_path"])),
+		("Model", str_or_dash(&run["model"])),
+		("Run", format!("{} ({})", run["id"], str_or_dash(&run["uid"]))),
+		("End State", str_or_dash(&run["end_state"])),
+		("Duration", fmt_duration(&run["duration_us"])),
+		(
+			"Tasks",
+			format!("{} ({} in error)", totals["task_count"], totals["err_count"]),
+		),
+		(
+			"Tokens",
+			format!(
+				"{} prompt / {} completion",
+				totals["tk_prompt_total"], totals["tk_completion_total"]
+			),
+		),
+		("Cost", fmt_cost(&run["total_cost"])),
+	];
+	if let Some(resources) = fmt_resources(run) {
+		rows.push(("Resources", resources));
+	}
+	if let Some(redo_of_run_uid) = run["redo_of_run_uid"].as_str() {
+		rows.push(("Redo Of Run", redo_of_run_uid.to_string()));
+	}
+	if let Some(error) = run["error"].as_str() {
+		rows.push(("Error", error.to_string()));
+	}
+
+	rows
+}
+
+/// The recorded resources of the run (`None` when no command or file calls)
+fn fmt_resources(run: &Value) -> Option<String> {
+	let cpu_ms = run["res_cpu_ms"].as_i64().unwrap_or_default();
+	let peak_mem = run["res_peak_mem"].as_i64().unwrap_or_default();
+	let read_bytes = run["res_read_bytes"].as_i64().unwrap_or_default();
+	let write_bytes = run["res_write_bytes"].as_i64().unwrap_or_default();
+	if cpu_ms == 0 && peak_mem == 0 && read_bytes == 0 && write_bytes == 0 {
+		return None;
+	}
+
+	let size_fmt = |bytes: i64| format_pretty_size(bytes.max(0) as u64, None).trim().to_string();
+	Some(format!(
+		"{} cpu / {} peak mem / {} read / {} written",
+		format_duration_us(cpu_ms * 1000),
+		size_fmt(peak_mem),
+		size_fmt(read_bytes),
+		size_fmt(write_bytes)
+	))
+}
+
+fn task_title(task: &Value) -> String {
+	let idx = task["idx"].as_i64().unwrap_or_default();
+	match task["label"].as_str() {
+		Some(label) => format!("Task {idx} - {label}"),
+		None => format!("Task {idx}"),
+	}
+}
+
+fn task_rows(task: &Value) -> Vec<(&'static str, String)> {
+	vec![
+		("End State", str_or_dash(&task["end_state"])),
+		("Model", str_or_dash(&task["model"])),
+		("Finish Reason", str_or_dash(&task["finish_reason"])),
+		("Duration", fmt_duration(&task["duration_us"])),
+		("AI Duration", fmt_duration(&task["ai_duration_us"])),
+		(
+			"Tokens",
+			format!(
+				"{} prompt / {} completion",
+				num_or_dash(&task["tk_prompt_total"]),
+				num_or_dash(&task["tk_completion_total"])
+			),
+		),
+		("Cost", fmt_cost(&task["cost"])),
+	]
+}
+
+/// The task content sections (title, content), in order
+fn task_sections(task: &Value) -> Vec<(&'static str, String)> {
+	let mut sections = Vec::new();
+	for (title, name) in [("Input", "input"), ("Output", "output"), ("Error", "error")] {
+		if let Some(content) = task[name].as_str() {
+			sections.push((title, content.to_string()));
+		}
+	}
+	if let Some(reason) = task["skip_reason"].as_str() {
+		sections.push(("Skip Reason", reason.to_string()));
+	}
+	if let Some(logs) = task["logs"].as_array()
+		&& !logs.is_empty()
+	{
+		let logs = logs
+			.iter()
+			.map(|log| format!("[{}] {}", str_or_dash(&log["kind"]), str_or_dash(&log["message"])))
+			.collect::<Vec<_>>()
+			.join("\n");
+		sections.push(("Logs", logs));
+	}
+	sections
+}
+
+fn render_markdown(report: &Value) -> String {
+	let mut md = String::new();
+
+	md.push_str(&format!(
+		"# Run Report - {}\n\n",
+		str_or_dash(&report["run"]["agent_name"])
+	));
+	md.push_str("| | |\n|---|---|\n");
+	for (label, value) in summary_rows(report) {
+		md.push_str(&format!(
+			"| {label} | {} |\n",
+			value.replace('|', "\\|").replace('\n', " ")
+		));
+	}
+
+	if let Some(prompts) = report["prompts"].as_array()
+		&& !prompts.is_empty()
+	{
+		md.push_str("\n## Prompts\n");
+		for prompt in prompts {
+			md.push_str(&format!("\n### {}\n\n", str_or_dash(&prompt["role"])));
+			md.push_str(&md_fence(prompt["content"].as_str().unwrap_or_default()));
+		}
+	}
+
+	md.push_str("\n## Tasks\n");
+	for task in report["tasks"].as_array().into_iter().flatten() {
+		md.push_str(&format!("\n### {}\n\n", task_title(task)));
+		for (label, value) in task_rows(task) {
+			md.push_str(&format!("- {label}: {value}\n"));
+		}
+		for (title, content) in task_sections(task) {
+			md.push_str(&format!("\n#### {title}\n\n"));
+			md.push_str(&md_fence(&content));
+		}
+	}
+
+	md
+}
+
+fn render_tasks_outputs_markdown(tasks: &[Value]) -> String {
+	let mut md = String::from("# Tasks Outputs\n");
+	for task in tasks {
+		md.push_str(&format!("\n## {}\n\n", task_title(task)));
+		match task["output"].as_str() {
+			Some(output) => md.push_str(&md_fence(output)),
+			None => md.push_str(&format!("_No output ({})_\n", str_or_dash(&task["end_state"]))),
+		}
+	}
+	md
+}
+
+fn render_html(report: &Value) -> String {
+	let title = format!("Run Report - {}", str_or_dash(&report["run"]["agent_name"]));
+	let mut body = String::new();
+
+	body.push_str(&format!("<h1>{}</h1>\n<table>\n", escape_html(&title)));
+	for (label, value) in summary_rows(report) {
+		body.push_str(&format!("<tr><th>{label}</th><td>{}</td></tr>\n", escape_html(&value)));
+	}
+	body.push_str("</table>\n");
+
+	if let Some(prompts) = report["prompts"].as_array()
+		&& !prompts.is_empty()
+	{
+		body.push_str("<h2>Prompts</h2>\n");
+		for prompt in prompts {
+			body.push_str(&format!(
+				"<details><summary>{}</summary><pre>{}</pre></details>\n",
+				escape_html(&str_or_dash(&prompt["role"])),
+				escape_html(prompt["content"].as_str().unwrap_or_default())
+			));
+		}
+	}
+
+	body.push_str("<h2>Tasks</h2>\n");
+	for task in report["tasks"].as_array().into_iter().flatten() {
+		body.push_str(&format!(
+			"<section>\n<h3>{}</h3>\n<table>\n",
+			escape_html(&task_title(task))
+		));
+		for (label, value) in task_rows(task) {
+			body.push_str(&format!("<tr><th>{label}</th><td>{}</td></tr>\n", escape_html(&value)));
+		}
+		body.push_str("</table>\n");
+		for (title, content) in task_sections(task) {
+			body.push_str(&format!(
+				"<details open><summary>{title}</summary><pre>{}</pre></details>\n",
+				escape_html(&content)
+			));
+		}
+		body.push_str("</section>\n");
+	}
+
+	format!(
+		r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: system-ui, sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; color: #222; }}
+table {{ border-collapse: collapse; margin: 0.5rem 0; }}
+th, td {{ text-align: left; padding: 0.25rem 0.75rem; border-bottom: 1px solid #ddd; vertical-align: top; }}
+pre {{ background: #f6f8fa; padding: 0.75rem; overflow-x: auto; white-space: pre-wrap; }}
+section {{ border-top: 2px solid #eee; margin-top: 1.5rem; }}
+summary {{ cursor: pointer; font-weight: 600; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+		title = escape_html(&title)
+	)
+}
+
+fn md_fence(content: &str) -> String {
+	// Use a longer fence than any backtick run in the content
+	let mut fence = "```".to_string();
+	while content.contains(&fence) {
+		fence.push('`');
+	}
+	format!("{fence}\n{}\n{fence}\n", content.trim_end())
+}
+
+fn escape_html(text: &str) -> String {
+	let mut out = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'>' => out.push_str("&gt;"),
+			'"' => out.push_str("&quot;"),
+			'\'' => out.push_str("&#39;"),
+			_ => out.push(c),
+		}
+	}
+	out
+}
+
+fn str_or_dash(value: &Value) -> String {
+	value.as_str().unwrap_or("-").to_string()
+}
+
+fn num_or_dash(value: &Value) -> String {
+	if value.is_number() {
+		value.to_string()
+	} else {
+		"-".to_string()
+	}
+}
+
+fn fmt_duration(value: &Value) -> String {
+	value.as_i64().map(format_duration_us).unwrap_or_else(|| "-".to_string())
+}
+
+fn fmt_cost(value: &Value) -> String {
+	value
+		.as_f64()
+		.map(|cost| format!("${cost:.4}"))
+		.unwrap_or_else(|| "-".to_string())
+}
+
+// endregion: --- Renderers
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::assert_contains;
+
+	fn fx_report() -> Value {
+		json!({
+			"run": {"id": 3, "uid": "run-uid", "agent_name": "proof", "model": "gpt-5-mini",
+				"end_state": "Ok", "duration_us": 2_500_000, "total_cost": 0.0123,
+				"res_cpu_ms": 1_500, "res_peak_mem": 2_048, "res_read_bytes": 512, "res_write_bytes": null},
+			"totals": {"task_count": 1, "err_count": 0, "tk_prompt_total": 120, "tk_completion_total": 30, "cost": 0.0123},
+			"prompts": [{"role": "user", "content": "Proofread {{data.file.content}}"}],
+			"tasks": [{"idx": 0, "label": "README.md", "end_state": "Ok", "duration_us": 2_000_000,
+				"tk_prompt_total": 120, "tk_completion_total": 30, "cost": 0.0123,
+				"input": "README.md", "output": "Use <b> & ```code```", "logs": []}]
+		})
+	}
+
+	#[test]
+	fn test_run_export_format_from_path() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(ExportFormat::from_path("out/report.md")?, ExportFormat::Markdown);
+		assert_eq!(ExportFormat::from_path("report.JSON")?, ExportFormat::Json);
+		assert_eq!(ExportFormat::from_path("report.html")?, ExportFormat::Html);
+		assert!(ExportFormat::from_path("report.txt").is_err());
+		assert!(ExportFormat::from_path("report").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_export_render_markdown_and_html() -> Result<()> {
+		// -- Setup & Fixtures
+		let report = fx_report();
+
+		// -- Exec
+		let md = render_markdown(&report);
+		let html = render_html(&report);
+
+		// -- Check
+		assert_contains(&md, "# Run Report - proof");
+		assert_contains(&md, "| Cost | $0.0123 |");
+		assert_contains(&md, "| Resources | 1s 500ms cpu / ");
+		assert_contains(&md, " read / 0 B written |");
+		assert_contains(&md, "### Task 0 - README.md");
+		assert_contains(&md, "- Tokens: 120 prompt / 30 completion");
+		assert_contains(&md, "````\nUse <b> & ```code```\n````");
+		assert_contains(&html, "<title>Run Report - proof</title>");
+		assert_contains(&html, "Use &lt;b&gt; &amp; ```code```");
+		assert_contains(&html, "Proofread {{data.file.content}}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_export_render_tasks_outputs_markdown() -> Result<()> {
+		// -- Setup & Fixtures
+		let tasks = vec![
+			json!({"idx": 3, "label": "README.md", "end_state": "Ok", "output": "Done"}),
+			json!({"idx": 7, "label": null, "end_state": "Err", "output": null}),
+		];
+
+		// -- Exec
+		let md = render_tasks_outputs_markdown(&tasks);
+
+		// -- Check
+		assert_contains(&md, "## Task 3 - README.md\n\n```\nDone\n```");
+		assert_contains(&md, "## Task 7\n\n_No output (Err)_");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
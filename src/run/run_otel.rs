@@ -0,0 +1,503 @@
+//! The OpenTelemetry traces of the runs (opt-in with the standard `OTEL_EXPORTER_OTLP_*` env variables),
+//! exported with OTLP/HTTP JSON at the end of the top runs.
+//!
+//! The spans are built from the store models (the run and task step timestamps), as:
+//!
+//! - `aip.run` (the top run), with the `before_all` and `after_all` spans
+//! - `aip.task` for each task, with the `data`, `ai`, and `output` stage spans
+//! - `aip.run` for each sub agent run, under its parent run span
+//!
+//! The token counts (`gen_ai.usage.*`) and cost (`aip.cost`) are the span attributes.
+
+use crate::hub::get_hub;
+use crate::model::{EndState, EpochUs, ErrBmc, Id, ModelManager, Run, RunBmc, Task, TaskBmc};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use serde_json::{Value, json};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_SERVICE_NAME: &str = "aipack";
+
+// OTLP span kinds
+const SPAN_KIND_INTERNAL: i32 = 1;
+const SPAN_KIND_CLIENT: i32 = 3;
+
+/// The OTLP exporter config, from the env
+#[derive(Debug, Clone)]
+pub(super) struct OtelConfig {
+	traces_endpoint: String,
+	headers: Vec<(String, String)>,
+	service_name: String,
+}
+
+impl OtelConfig {
+	/// Returns `None` if no OTLP endpoint is set (or `OTEL_SDK_DISABLED=true`).
+	pub(super) fn from_env() -> Option<Self> {
+		Self::from_vars(|name| std::env::var(name).ok())
+	}
+
+	fn from_vars(get_var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+		let get_var = |name: &str| get_var(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+		if get_var("OTEL_SDK_DISABLED").is_some_and(|v| v.eq_ignore_ascii_case("true")) {
+			return None;
+		}
+
+		let traces_endpoint = match get_var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+			Some(endpoint) => endpoint,
+			None => {
+				let endpoint = get_var("OTEL_EXPORTER_OTLP_ENDPOINT")?;
+				format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+			}
+		};
+
+		let headers = get_var("OTEL_EXPORTER_OTLP_TRACES_HEADERS")
+			.or_else(|| get_var("OTEL_EXPORTER_OTLP_HEADERS"))
+			.map(|headers| parse_headers(&headers))
+			.unwrap_or_default();
+
+		let service_name = get_var("OTEL_SERVICE_NAME").unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+
+		Some(Self {
+			traces_endpoint,
+			headers,
+			service_name,
+		})
+	}
+}
+
+/// Build and send the traces of the run (the errors are published, and do not fail the run).
+pub(super) async fn send_run_traces(runtime: &Runtime, run_id: Id, config: &OtelConfig) {
+	let res = match build_traces(runtime.mm(), run_id, &config.service_name) {
+		Ok(traces) => post_traces(config, &traces).await,
+		Err(err) => Err(err),
+	};
+
+	if let Err(err) = res {
+		get_hub()
+			.publish_err(
+				format!("Cannot send the OpenTelemetry traces to '{}'", config.traces_endpoint),
+				Some(err),
+			)
+			.await;
+	}
+}
+
+// region:    --- Support
+
+fn build_traces(mm: &ModelManager, run_id: Id, service_name: &str) -> Result<Value> {
+	let run = RunBmc::get(mm, run_id)?;
+	let trace_id = run.uid.simple().to_string();
+
+	let mut spans = Vec::new();
+	collect_run_spans(mm, &run, &trace_id, None, &mut spans)?;
+
+	Ok(json!({
+		"resourceSpans": [{
+			"resource": {
+				"attributes": [
+					attr_str("service.name", Some(service_name)),
+					attr_str("service.version", Some(crate::VERSION)),
+				]
+			},
+			"scopeSpans": [{
+				"scope": {"name": "aipack", "version": crate::VERSION},
+				"spans": spans,
+			}]
+		}]
+	}))
+}
+
+/// Add the spans of the run, its tasks, and its sub agent runs (recursively).
+fn collect_run_spans(
+	mm: &ModelManager,
+	run: &Run,
+	trace_id: &str,
+	parent_span_id: Option<&str>,
+	spans: &mut Vec<Value>,
+) -> Result<()> {
+	// NOTE: A run without start/end (e.g., canceled before start) has no span (nor its tasks)
+	let (Some(start), Some(end)) = (run.start, run.end) else {
+		return Ok(());
+	};
+	let run_uid = run.uid.to_string();
+	let run_span_id = span_id(&run_uid, "run");
+
+	let tasks = TaskBmc::list_for_run(mm, run.id)?;
+	let sum_tk = |get: fn(&Task) -> Option<i64>| tasks.iter().filter_map(get).sum::<i64>();
+
+	let attributes = vec![
+		attr_str("aip.run.uid", Some(&run_uid)),
+		attr_str("aip.agent.name", run.agent_name.as_deref()),
+		attr_str("aip.agent.path", run.agent_path.as_deref()),
+		attr_str("gen_ai.request.model", run.model.as_deref()),
+		attr_str("aip.end_state", run.end_state.as_ref().map(|s| s.as_ref())),
+		attr_int("aip.task.count", Some(tasks.len() as i64)),
+		attr_int("gen_ai.usage.input_tokens", Some(sum_tk(|t| t.tk_prompt_total))),
+		attr_int("gen_ai.usage.output_tokens", Some(sum_tk(|t| t.tk_completion_total))),
+		attr_f64("aip.cost", run.total_cost),
+	];
+	let name = format!("aip.run {}", run.agent_name.as_deref().unwrap_or_default());
+	spans.push(span_json(SpanData {
+		trace_id,
+		span_id: &run_span_id,
+		parent_span_id,
+		name: &name,
+		kind: SPAN_KIND_INTERNAL,
+		start,
+		end,
+		attributes,
+		error: end_error(mm, run.end_state.as_ref(), run.end_err_id)?,
+	}));
+
+	// -- Before all & After all
+	for (stage, start, end) in [
+		("before_all", run.ba_start, run.ba_end),
+		("after_all", run.aa_start, run.aa_end),
+	] {
+		if let (Some(start), Some(end)) = (start, end) {
+			spans.push(span_json(SpanData {
+				trace_id,
+				span_id: &span_id(&run_uid, stage),
+				parent_span_id: Some(&run_span_id),
+				name: stage,
+				kind: SPAN_KIND_INTERNAL,
+				start,
+				end,
+				attributes: Vec::new(),
+				error: None,
+			}));
+		}
+	}
+
+	// -- Tasks
+	for task in tasks.iter() {
+		collect_task_spans(mm, task, trace_id, &run_span_id, spans)?;
+	}
+
+	// -- Sub agent runs
+	for sub_run in RunBmc::list_for_parent(mm, run.id)? {
+		collect_run_spans(mm, &sub_run, trace_id, Some(&run_span_id), spans)?;
+	}
+
+	Ok(())
+}
+
+fn collect_task_spans(
+	mm: &ModelManager,
+	task: &Task,
+	trace_id: &str,
+	run_span_id: &str,
+	spans: &mut Vec<Value>,
+) -> Result<()> {
+	let (Some(start), Some(end)) = (task.start, task.end) else {
+		return Ok(());
+	};
+	let task_uid = task.uid.to_string();
+	let task_span_id = span_id(&task_uid, "task");
+	let model = task.model_upstream.as_deref().or(task.model_ov.as_deref());
+
+	let usage_attributes = || {
+		vec![
+			attr_int("gen_ai.usage.input_tokens", task.tk_prompt_total),
+			attr_int("gen_ai.usage.output_tokens", task.tk_completion_total),
+			attr_f64("aip.cost", task.cost),
+		]
+	};
+
+	let mut attributes = vec![
+		attr_int("aip.task.idx", task.idx),
+		attr_str("aip.task.label", task.label.as_deref()),
+		attr_str("aip.end_state", task.end_state.as_ref().map(|s| s.as_ref())),
+		attr_str("gen_ai.response.model", model),
+	];
+	attributes.extend(usage_attributes());
+	let name = format!("aip.task {}", task.idx.unwrap_or_default());
+	spans.push(span_json(SpanData {
+		trace_id,
+		span_id: &task_span_id,
+		parent_span_id: Some(run_span_id),
+		name: &name,
+		kind: SPAN_KIND_INTERNAL,
+		start,
+		end,
+		attributes,
+		error: end_error(mm, task.end_state.as_ref(), task.end_err_id)?,
+	}));
+
+	// -- Stages
+	let stages = [
+		("data", task.data_start, task.data_end),
+		("ai", task.ai_start, task.ai_end),
+		("output", task.output_start, task.output_end),
+	];
+	for (stage, start, end) in stages {
+		let (Some(start), Some(end)) = (start, end) else {
+			continue;
+		};
+		let (kind, attributes) = if stage == "ai" {
+			let mut attributes = vec![
+				attr_str("gen_ai.response.model", model),
+				attr_int("aip.prompt_size", task.prompt_size),
+			];
+			attributes.extend(usage_attributes());
+			(SPAN_KIND_CLIENT, attributes)
+		} else {
+			(SPAN_KIND_INTERNAL, Vec::new())
+		};
+		spans.push(span_json(SpanData {
+			trace_id,
+			span_id: &span_id(&task_uid, stage),
+			parent_span_id: Some(&task_span_id),
+			name: stage,
+			kind,
+			start,
+			end,
+			attributes,
+			error: None,
+		}));
+	}
+
+	Ok(())
+}
+
+/// The error message of an `Err` end state (`None` for the other states)
+fn end_error(mm: &ModelManager, end_state: Option<&EndState>, err_id: Option<Id>) -> Result<Option<String>> {
+	if !matches!(end_state, Some(EndState::Err)) {
+		return Ok(None);
+	}
+	let content = match err_id {
+		Some(err_id) => ErrBmc::get(mm, err_id)?.content,
+		None => None,
+	};
+	Ok(Some(content.unwrap_or_default()))
+}
+
+async fn post_traces(config: &OtelConfig, traces: &Value) -> Result<()> {
+	let client = reqwest::Client::builder()
+		.timeout(REQUEST_TIMEOUT)
+		.build()
+		.map_err(|err| Error::cc("Cannot build the OpenTelemetry http client", err))?;
+
+	let mut request = client.post(&config.traces_endpoint).json(traces);
+	for (name, value) in config.headers.iter() {
+		request = request.header(name, value);
+	}
+
+	let res = request.send().await.map_err(|err| Error::cc("OTLP request failed", err))?;
+	let status = res.status();
+	if !status.is_success() {
+		return Err(Error::custom(format!("OTLP endpoint responded with status {status}")));
+	}
+
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Span Json
+
+struct SpanData<'a> {
+	trace_id: &'a str,
+	span_id: &'a str,
+	parent_span_id: Option<&'a str>,
+	name: &'a str,
+	kind: i32,
+	start: EpochUs,
+	end: EpochUs,
+	attributes: Vec<Value>,
+	/// When set, the span status is error, with this message
+	error: Option<String>,
+}
+
+fn span_json(span: SpanData) -> Value {
+	// The unset attributes are `Value::Null`
+	let attributes: Vec<Value> = span.attributes.into_iter().filter(|a| !a.is_null()).collect();
+	let status = match span.error {
+		Some(message) => json!({"code": 2, "message": message}),
+		None => json!({"code": 1}),
+	};
+
+	let mut value = json!({
+		"traceId": span.trace_id,
+		"spanId": span.span_id,
+		"name": span.name,
+		"kind": span.kind,
+		"startTimeUnixNano": epoch_nano(span.start),
+		"endTimeUnixNano": epoch_nano(span.end),
+		"attributes": attributes,
+		"status": status,
+	});
+	if let Some(parent_span_id) = span.parent_span_id {
+		value["parentSpanId"] = parent_span_id.into();
+	}
+
+	value
+}
+
+/// The 8 bytes span id (16 hex chars), stable for the entity uid and span name
+fn span_id(uid: &str, name: &str) -> String {
+	let hash = blake3::hash(format!("{uid}/{name}").as_bytes());
+	hex::encode(&hash.as_bytes()[..8])
+}
+
+/// The OTLP JSON uint64 nanos (as a string)
+fn epoch_nano(epoch_us: EpochUs) -> String {
+	(epoch_us.as_i64().max(0) as u64 * 1000).to_string()
+}
+
+fn attr_str(key: &str, value: Option<&str>) -> Value {
+	match value {
+		Some(value) => json!({"key": key, "value": {"stringValue": value}}),
+		None => Value::Null,
+	}
+}
+
+fn attr_int(key: &str, value: Option<i64>) -> Value {
+	match value {
+		Some(value) => json!({"key": key, "value": {"intValue": value.to_string()}}),
+		None => Value::Null,
+	}
+}
+
+fn attr_f64(key: &str, value: Option<f64>) -> Value {
+	match value {
+		Some(value) => json!({"key": key, "value": {"doubleValue": value}}),
+		None => Value::Null,
+	}
+}
+
+/// Parse the `key1=value1,key2=value2` headers (the W3C baggage format of the OTEL env)
+fn parse_headers(headers: &str) -> Vec<(String, String)> {
+	headers
+		.split(',')
+		.filter_map(|pair| {
+			let (name, value) = pair.split_once('=')?;
+			let name = name.trim();
+			(!name.is_empty()).then(|| (name.to_string(), value.trim().to_string()))
+		})
+		.collect()
+}
+
+// endregion: --- Span Json
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::model::{RunForCreate, RunForUpdate, TaskForCreate, TaskForUpdate};
+	use std::collections::HashMap;
+
+	#[test]
+	fn test_run_otel_config_from_vars() -> Result<()> {
+		// -- Setup & Fixtures
+		let vars = HashMap::from([
+			("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318/"),
+			("OTEL_EXPORTER_OTLP_HEADERS", "x-api-key=abc, x-team = ai "),
+		]);
+
+		// -- Exec
+		let config = OtelConfig::from_vars(|name| vars.get(name).map(|v| v.to_string())).ok_or("Should have config")?;
+
+		// -- Check
+		assert_eq!(config.traces_endpoint, "http://localhost:4318/v1/traces");
+		assert_eq!(config.service_name, "aipack");
+		assert_eq!(
+			config.headers,
+			vec![
+				("x-api-key".to_string(), "abc".to_string()),
+				("x-team".to_string(), "ai".to_string())
+			]
+		);
+		assert!(OtelConfig::from_vars(|_| None).is_none());
+		assert!(
+			OtelConfig::from_vars(|name| match name {
+				"OTEL_EXPORTER_OTLP_ENDPOINT" => Some("http://localhost:4318".to_string()),
+				"OTEL_SDK_DISABLED" => Some("true".to_string()),
+				_ => None,
+			})
+			.is_none()
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_otel_build_traces() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let run_id = RunBmc::create(
+			&mm,
+			RunForCreate {
+				parent_id: None,
+				parent_task_id: None,
+				agent_name: Some("proof".to_string()),
+				agent_path: None,
+				has_task_stages: None,
+				has_prompt_parts: None,
+			},
+		)?;
+		RunBmc::update(
+			&mm,
+			run_id,
+			RunForUpdate {
+				start: Some(1_000.into()),
+				ba_start: Some(1_000.into()),
+				ba_end: Some(1_500.into()),
+				end: Some(9_000.into()),
+				end_state: Some(EndState::Ok),
+				..Default::default()
+			},
+		)?;
+		let task_id = TaskBmc::create(
+			&mm,
+			TaskForCreate {
+				run_id,
+				idx: 0,
+				label: Some("README.md".to_string()),
+				input_content: None,
+			},
+		)?;
+		TaskBmc::update(
+			&mm,
+			task_id,
+			TaskForUpdate {
+				start: Some(2_000.into()),
+				ai_start: Some(3_000.into()),
+				ai_end: Some(7_000.into()),
+				end: Some(8_000.into()),
+				tk_prompt_total: Some(120),
+				tk_completion_total: Some(30),
+				..Default::default()
+			},
+		)?;
+
+		// -- Exec
+		let traces = build_traces(&mm, run_id, "aipack-test")?;
+
+		// -- Check
+		let spans = traces["resourceSpans"][0]["scopeSpans"][0]["spans"]
+			.as_array()
+			.ok_or("Should have spans")?;
+		let names: Vec<&str> = spans.iter().filter_map(|s| s["name"].as_str()).collect();
+		assert_eq!(names, vec!["aip.run proof", "before_all", "aip.task 0", "ai"]);
+		let run_span = &spans[0];
+		let task_span = &spans[2];
+		assert_eq!(run_span["traceId"].as_str().map(|v| v.len()), Some(32));
+		assert_eq!(run_span["startTimeUnixNano"].as_str(), Some("1000000"));
+		assert!(run_span.get("parentSpanId").is_none());
+		assert_eq!(task_span["parentSpanId"], run_span["spanId"]);
+		assert_eq!(spans[3]["parentSpanId"], task_span["spanId"]);
+		assert_eq!(spans[3]["kind"].as_i64(), Some(SPAN_KIND_CLIENT as i64));
+		let run_attrs = run_span["attributes"].to_string();
+		assert!(run_attrs.contains(r#"{"key":"gen_ai.usage.input_tokens","value":{"intValue":"120"}}"#));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
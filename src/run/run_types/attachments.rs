@@ -1,17 +1,126 @@
+use crate::{Error, Result};
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use simple_fs::SPath;
+
+/// The max size of an attachment (file size, or decoded base64 content size)
+const ATTACHMENT_MAX_SIZE: u64 = 20 * 1024 * 1024;
+
+/// The supported attachment file extensions and their content types
+const ATTACHMENT_CONTENT_TYPES: &[(&str, &str)] = &[
+	("png", "image/png"),
+	("jpg", "image/jpeg"),
+	("jpeg", "image/jpeg"),
+	("gif", "image/gif"),
+	("webp", "image/webp"),
+	("pdf", "application/pdf"),
+];
 
 // region:    --- Attachment
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attachment {
-	// For now, only support file local path.
+	/// The local file path (for the base64 attachments, the original file path)
 	pub file_source: String,
 	pub file_name: Option<String>,
 	pub title: Option<String>,
+	/// The mime type of the base64 `content` (e.g., `image/png`)
+	pub content_type: Option<String>,
+	/// The base64 content (e.g., from `aip.image.to_b64`). When present, the `file_source` is not read.
+	pub content: Option<String>,
+}
+
+impl Attachment {
+	pub fn from_file_source(file_source: impl Into<String>) -> Self {
+		Self {
+			file_source: file_source.into(),
+			file_name: None,
+			title: None,
+			content_type: None,
+			content: None,
+		}
+	}
+}
+
+/// Validations
+impl Attachment {
+	/// Validate the type and size of the resolved attachment file.
+	/// Returns the content type of the file.
+	pub fn validate_file(&self, file_path: &SPath) -> Result<&'static str> {
+		let content_type = content_type_from_ext(&file_path.ext().to_lowercase()).ok_or_else(|| {
+			Error::custom(format!(
+				"Attachment '{}' file type not supported. Supported extensions: {}",
+				self.file_source,
+				supported_extensions()
+			))
+		})?;
+
+		let size = std::fs::metadata(file_path.as_std_path())
+			.map_err(|err| Error::cc(format!("Attachment '{}' cannot be read", self.file_source), err))?
+			.len();
+		check_size(&self.file_source, size)?;
+
+		Ok(content_type)
+	}
+
+	/// Validate the `content_type` and size of the base64 `content` (when present).
+	pub fn validate_content(&self) -> Result<()> {
+		let Some(content) = self.content.as_ref() else {
+			return Ok(());
+		};
+
+		let content_type = self.content_type.as_deref().ok_or_else(|| {
+			Error::custom(format!(
+				"Attachment '{}' has a 'content' but no 'content_type' (e.g., 'image/png')",
+				self.file_source
+			))
+		})?;
+		if !ATTACHMENT_CONTENT_TYPES.iter().any(|(_, ct)| *ct == content_type) {
+			return Err(Error::custom(format!(
+				"Attachment '{}' content type '{content_type}' not supported. Supported extensions: {}",
+				self.file_source,
+				supported_extensions()
+			)));
+		}
+
+		// NOTE: base64 is 4 chars for 3 bytes
+		check_size(&self.file_source, content.len() as u64 / 4 * 3)?;
+
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+fn content_type_from_ext(ext: &str) -> Option<&'static str> {
+	ATTACHMENT_CONTENT_TYPES
+		.iter()
+		.find(|(att_ext, _)| *att_ext == ext)
+		.map(|(_, content_type)| *content_type)
+}
+
+fn supported_extensions() -> String {
+	ATTACHMENT_CONTENT_TYPES
+		.iter()
+		.map(|(ext, _)| *ext)
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+fn check_size(file_source: &str, size: u64) -> Result<()> {
+	if size > ATTACHMENT_MAX_SIZE {
+		return Err(Error::custom(format!(
+			"Attachment '{file_source}' is too large ({} MB). Max size is {} MB",
+			size / (1024 * 1024),
+			ATTACHMENT_MAX_SIZE / (1024 * 1024)
+		)));
+	}
+	Ok(())
 }
 
+// endregion: --- Support
+
 // endregion: --- Attachment
 
 // region:    --- Attachments
@@ -29,7 +138,7 @@ impl Attachments {
 }
 
 impl<'de> Deserialize<'de> for Attachments {
-	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
 	where
 		D: Deserializer<'de>,
 	{
@@ -38,10 +147,12 @@ impl<'de> Deserialize<'de> for Attachments {
 		let list = match value {
 			Value::Array(arr) => arr
 				.into_iter()
-				.map(serde_json::from_value)
-				.collect::<Result<Vec<Attachment>, _>>()
+				.map(attachment_from_value)
+				.collect::<core::result::Result<Vec<Attachment>, _>>()
 				.map_err(|e| DeError::custom(format!("Failed to deserialize array elements into Attachment: {e}")))?,
 			Value::Null => Vec::new(),
+			// Allow a single file path
+			Value::String(file_source) => vec![Attachment::from_file_source(file_source)],
 			// Allow single object to be deserialized as a single-item list
 			Value::Object(obj) => {
 				if obj.is_empty() {
@@ -58,7 +169,7 @@ impl<'de> Deserialize<'de> for Attachments {
 			}
 			other => {
 				return Err(DeError::custom(format!(
-					"Expected an array of attachments, a single attachment object, or null, found: {}",
+					"Expected an array of attachments (or file paths), a single attachment object (or file path), or null, found: {}",
 					other
 				)));
 			}
@@ -68,6 +179,14 @@ impl<'de> Deserialize<'de> for Attachments {
 	}
 }
 
+/// An attachment item can be an attachment object or just the file path (e.g., `"images/screenshot.png"`)
+fn attachment_from_value(value: Value) -> serde_json::Result<Attachment> {
+	match value {
+		Value::String(file_source) => Ok(Attachment::from_file_source(file_source)),
+		other => serde_json::from_value(other),
+	}
+}
+
 impl IntoIterator for Attachments {
 	type Item = Attachment;
 	type IntoIter = std::vec::IntoIter<Self::Item>;
@@ -87,3 +206,56 @@ impl<'a> IntoIterator for &'a Attachments {
 }
 
 // endregion: --- Attachments
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_run_attachments_deserialize_paths_and_objects() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_value = json!(["images/a.png", {"file_source": "docs/spec.pdf", "title": "Spec"}]);
+
+		// -- Exec
+		let attachments: Attachments = serde_json::from_value(fx_value)?;
+		let single: Attachments = serde_json::from_value(json!("images/b.png"))?;
+
+		// -- Check
+		let sources = attachments.list.iter().map(|a| a.file_source.as_str()).collect::<Vec<_>>();
+		assert_eq!(sources, ["images/a.png", "docs/spec.pdf"]);
+		assert_eq!(attachments.list[1].title.as_deref(), Some("Spec"));
+		assert_eq!(single.list.len(), 1);
+		assert_eq!(single.list[0].file_source, "images/b.png");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_run_attachments_validate() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_att = Attachment::from_file_source("some/file.txt");
+		let fx_att_content = Attachment {
+			content_type: Some("image/png".to_string()),
+			content: Some("aGVsbG8=".to_string()),
+			..Attachment::from_file_source("some/image.png")
+		};
+		let fx_att_no_type = Attachment {
+			content_type: None,
+			..fx_att_content.clone()
+		};
+
+		// -- Exec & Check
+		assert!(fx_att.validate_file(&SPath::new("some/file.txt")).is_err());
+		assert!(fx_att_content.validate_content().is_ok());
+		assert!(fx_att_no_type.validate_content().is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
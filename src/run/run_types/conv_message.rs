@@ -0,0 +1,130 @@
+use crate::model::ConvMsg;
+use crate::{Error, Result};
+use genai::chat::{ChatMessage, ChatRole};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A conversation message, from the data stage `messages`, or the run conversation (agent option `conversation = true`)
+///
+/// NOTE: Only the `user` and `assistant` roles, the system prompt being the `# System` of the agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConvMessage {
+	pub role: String,
+	pub content: String,
+}
+
+/// Constructors
+impl ConvMessage {
+	pub fn new(role: impl Into<String>, content: impl Into<String>) -> Result<Self> {
+		let role = role.into();
+		if !matches!(role.as_str(), "user" | "assistant") {
+			return Err(Error::custom(format!(
+				"Conversation message role '{role}' not supported. Must be 'user' or 'assistant'"
+			)));
+		}
+		Ok(Self {
+			role,
+			content: content.into(),
+		})
+	}
+
+	/// Parse the data stage `messages` (a list of `{role, content}`).
+	pub fn list_from_value(value: Option<Value>) -> Result<Vec<Self>> {
+		let items = match value {
+			None | Some(Value::Null) => return Ok(Vec::new()),
+			Some(Value::Array(items)) => items,
+			// NOTE: An empty lua table is an empty object
+			Some(Value::Object(obj)) if obj.is_empty() => return Ok(Vec::new()),
+			Some(_) => {
+				return Err(Error::custom(
+					"Data stage 'messages' must be a list of {role, content} (e.g., { {role = 'user', content = '...'} })",
+				));
+			}
+		};
+
+		items
+			.into_iter()
+			.map(|item| {
+				let role = item.get("role").and_then(|v| v.as_str()).unwrap_or_default();
+				let content = item.get("content").and_then(|v| v.as_str()).ok_or_else(|| {
+					Error::custom(format!(
+						"Data stage 'messages' item must have a string 'content'. Was: {item}"
+					))
+				})?;
+				Self::new(role, content)
+			})
+			.collect()
+	}
+
+	/// From the stored run conversation (ignoring the invalid rows)
+	pub fn list_from_conv_msgs(conv_msgs: Vec<ConvMsg>) -> Vec<Self> {
+		conv_msgs
+			.into_iter()
+			.filter_map(|msg| Self::new(msg.role?, msg.content.unwrap_or_default()).ok())
+			.collect()
+	}
+
+	/// The conversation message of a chat message (when it is a text user or assistant message)
+	pub fn from_chat_message(msg: &ChatMessage) -> Option<Self> {
+		let role = match msg.role {
+			ChatRole::User => "user",
+			ChatRole::Assistant => "assistant",
+			_ => return None,
+		};
+		let content = msg.content.joined_texts()?;
+		Some(Self {
+			role: role.to_string(),
+			content,
+		})
+	}
+}
+
+/// Transformers
+impl ConvMessage {
+	pub fn to_chat_message(&self) -> ChatMessage {
+		match self.role.as_str() {
+			"assistant" => ChatMessage::assistant(self.content.as_str()),
+			_ => ChatMessage::user(self.content.as_str()),
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::assert_contains;
+	use serde_json::json;
+
+	#[test]
+	fn test_run_conv_message_list_from_value() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_messages = json!([
+			{"role": "user", "content": "What is 2+2?"},
+			{"role": "assistant", "content": "4"},
+		]);
+
+		// -- Exec
+		let messages = ConvMessage::list_from_value(Some(fx_messages))?;
+		let empty = ConvMessage::list_from_value(Some(json!({})))?;
+		let role_err = ConvMessage::list_from_value(Some(json!([{"role": "tool", "content": "x"}])));
+
+		// -- Check
+		assert_eq!(
+			messages,
+			vec![ConvMessage::new("user", "What is 2+2?")?, ConvMessage::new("assistant", "4")?]
+		);
+		assert!(empty.is_empty());
+		assert_contains(
+			&role_err.err().ok_or("should fail")?.to_string(),
+			"role 'tool' not supported",
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
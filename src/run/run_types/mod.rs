@@ -1,11 +1,15 @@
 // region:    --- Modules
 
 mod attachments;
+mod conv_message;
+mod run_parent;
 mod run_redo_ctx;
 mod run_sub_agent_params;
 mod run_top_agent_params;
 
 pub use attachments::*;
+pub use conv_message::*;
+pub use run_parent::*;
 pub use run_redo_ctx::*;
 pub use run_sub_agent_params::*;
 pub use run_top_agent_params::*;
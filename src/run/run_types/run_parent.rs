@@ -0,0 +1,9 @@
+use uuid::Uuid;
+
+/// The parent of a sub agent run, the run (and the eventual task) calling `aip.agent.run`
+#[derive(Debug, Clone, Copy)]
+pub struct RunParent {
+	pub run_uid: Uuid,
+	/// The calling task (none when called from the `# Before All` or `# After All`)
+	pub task_uid: Option<Uuid>,
+}
@@ -1,6 +1,8 @@
 use crate::agent::Agent;
+use crate::model::Id;
 use crate::run::RunTopAgentParams;
 use crate::runtime::Runtime;
+use serde_json::Value;
 use std::sync::Arc;
 
 // #[derive(From)]
@@ -35,6 +37,7 @@ impl RunRedoCtx {
 		run_options: RunTopAgentParams,
 		redo_requested: bool,
 		flow_redo_count: i32,
+		run_redo_data: Option<RunRedoData>,
 	) -> Self {
 		Self {
 			inner: Arc::new(CtxInner {
@@ -43,6 +46,7 @@ impl RunRedoCtx {
 				run_options,
 				redo_requested,
 				flow_redo_count,
+				run_redo_data,
 			}),
 		}
 	}
@@ -69,6 +73,10 @@ impl RunRedoCtx {
 	pub fn flow_redo_count(&self) -> i32 {
 		self.inner.flow_redo_count
 	}
+
+	pub fn run_redo_data(&self) -> Option<&RunRedoData> {
+		self.inner.run_redo_data.as_ref()
+	}
 }
 
 /// A Context that hold the information to redo this run
@@ -79,4 +87,15 @@ struct CtxInner {
 	run_options: RunTopAgentParams,
 	redo_requested: bool,
 	flow_redo_count: i32,
+	run_redo_data: Option<RunRedoData>,
+}
+
+/// The data of a run with tasks, to redo some of its tasks
+/// without re-running its before all (see `TaskRedo`).
+#[derive(Debug, Clone)]
+pub struct RunRedoData {
+	pub run_id: Id,
+	pub before_all: Value,
+	/// The inputs as given to the tasks (the task idx is the input idx)
+	pub inputs: Vec<Value>,
 }
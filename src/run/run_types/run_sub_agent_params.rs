@@ -2,7 +2,7 @@ use crate::Result;
 use crate::agent::AgentOptions;
 use crate::event::OneShotTx;
 use crate::runtime::Runtime;
-use crate::types::{RunAgentOptions, RunAgentResponse};
+use crate::types::{PackCapabilities, RunAgentOptions, RunAgentResponse};
 use simple_fs::SPath;
 use uuid::Uuid;
 
@@ -13,6 +13,9 @@ pub struct RunSubAgentParams {
 
 	pub parent_uid: Uuid,
 
+	/// The parent task calling the sub agent (when from a task stage)
+	pub parent_task_uid: Option<Uuid>,
+
 	pub agent_dir: Option<SPath>,
 
 	pub agent_name: String,
@@ -23,6 +26,9 @@ pub struct RunSubAgentParams {
 	/// The eventual agent option overlay
 	pub agent_options: Option<AgentOptions>,
 
+	/// The capabilities of the caller pack, which restrict the sub agent run (see `PackCapabilities::with_parent`)
+	pub parent_capabilities: Option<Box<PackCapabilities>>,
+
 	/// The response oneshot with the RunAgentResponse
 	pub response_shot: Option<OneShotTx<Result<RunAgentResponse>>>,
 }
@@ -31,6 +37,7 @@ impl RunSubAgentParams {
 	pub fn new(
 		runtime: Runtime,
 		parent_uid: Uuid,
+		parent_task_uid: Option<Uuid>,
 		parent_agent_dir: Option<SPath>,
 		agent_name: impl Into<String>,
 		run_options: RunAgentOptions,
@@ -47,11 +54,19 @@ impl RunSubAgentParams {
 		Ok(Self {
 			runtime,
 			parent_uid,
+			parent_task_uid,
 			agent_dir,
 			agent_name: agent_name.into(),
 			inputs,
 			agent_options,
+			parent_capabilities: None,
 			response_shot,
 		})
 	}
+
+	/// Restrict the sub agent run by the capabilities of the caller pack
+	pub fn with_parent_capabilities(mut self, parent_capabilities: Option<PackCapabilities>) -> Self {
+		self.parent_capabilities = parent_capabilities.map(Box::new);
+		self
+	}
 }
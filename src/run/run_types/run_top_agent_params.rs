@@ -1,5 +1,9 @@
-use crate::Result;
 use crate::exec::cli::RunArgs;
+use crate::run::run_export::ExportFormat;
+use crate::run::{RunPriority, RunRedoData};
+use crate::types::PackCapabilities;
+use crate::{Error, Result};
+use serde_json::Value;
 use std::sync::Arc;
 
 // region:    --- RunCommandOptions
@@ -14,6 +18,10 @@ pub struct RunTopAgentParams {
 struct ParamsInner {
 	on_file_globs: Option<Vec<String>>,
 	on_inputs: Option<Vec<String>>,
+	/// The `--arg name=value` agent args
+	cli_args: Vec<String>,
+	/// The `--args-json` agent args (JSON object)
+	cli_args_json: Option<Value>,
 	flow_redo_count: i32,
 
 	base_run_options: RunBaseOptions,
@@ -38,6 +46,14 @@ impl RunTopAgentParams {
 		self.inner.on_inputs.as_ref().map(|v| v.iter().map(|s| s.as_str()).collect())
 	}
 
+	pub fn cli_args(&self) -> Vec<&str> {
+		self.inner.cli_args.iter().map(|s| s.as_str()).collect()
+	}
+
+	pub fn cli_args_json(&self) -> Option<&Value> {
+		self.inner.cli_args_json.as_ref()
+	}
+
 	pub fn base_run_options(&self) -> &RunBaseOptions {
 		&self.inner.base_run_options
 	}
@@ -74,9 +90,36 @@ impl RunTopAgentParams {
 			None
 		};
 
+		// -- Parse the args json
+		let cli_args_json = args
+			.args_json
+			.as_deref()
+			.map(serde_json::from_str::<Value>)
+			.transpose()
+			.map_err(|err| Error::cc("--args-json is not valid JSON", err))?;
+
 		// -- Parse dry_mode
 		let dry_mode = parse_dry_mode(args.dry_mode.as_deref());
 
+		// -- Parse the priority class
+		let priority = args
+			.priority
+			.as_deref()
+			.map(|priority| {
+				priority.parse::<RunPriority>().map_err(|_| {
+					Error::custom(format!(
+						"--priority '{priority}' is not valid. Must be 'interactive', 'normal', or 'batch'"
+					))
+				})
+			})
+			.transpose()?
+			.unwrap_or_default();
+
+		// -- Validate the export format (from the path extension)
+		if let Some(export_path) = args.export.as_deref() {
+			ExportFormat::from_path(export_path)?;
+		}
+
 		// -- Build the base Options
 		let base_run_options = RunBaseOptions {
 			watch: args.watch,
@@ -84,11 +127,19 @@ impl RunTopAgentParams {
 			dry_mode,
 			open: args.open,
 			flow_redo_count: 0,
+			export_path: args.export,
+			task_redo: None,
+			chat_turn: None,
+			priority,
+			fresh: args.fresh,
+			parent_capabilities: None,
 		};
 
 		Ok(ParamsInner {
 			on_file_globs,
 			on_inputs: args.on_inputs,
+			cli_args: args.args.unwrap_or_default(),
+			cli_args_json,
 			flow_redo_count: 0,
 			base_run_options,
 		}
@@ -99,6 +150,8 @@ impl RunTopAgentParams {
 		ParamsInner {
 			on_file_globs: self.inner.on_file_globs.clone(),
 			on_inputs: self.inner.on_inputs.clone(),
+			cli_args: self.inner.cli_args.clone(),
+			cli_args_json: self.inner.cli_args_json.clone(),
 			flow_redo_count,
 			base_run_options: RunBaseOptions {
 				flow_redo_count,
@@ -107,6 +160,43 @@ impl RunTopAgentParams {
 		}
 		.into()
 	}
+
+	/// Return new params to redo only one task of the run (eventually with an edited prompt)
+	/// NOTE: The task redo runs are `Interactive` (from the TUI).
+	pub fn with_task_redo(&self, task_redo: Option<TaskRedo>) -> Self {
+		ParamsInner {
+			on_file_globs: self.inner.on_file_globs.clone(),
+			on_inputs: self.inner.on_inputs.clone(),
+			cli_args: self.inner.cli_args.clone(),
+			cli_args_json: self.inner.cli_args_json.clone(),
+			flow_redo_count: self.inner.flow_redo_count,
+			base_run_options: RunBaseOptions {
+				priority: interactive_if(task_redo.is_some(), self.inner.base_run_options.priority),
+				task_redo,
+				..self.inner.base_run_options.clone()
+			},
+		}
+		.into()
+	}
+
+	/// Return new params to run one chat follow-up turn of the run (from the TUI chat view)
+	/// NOTE: The chat turn runs are `Interactive`.
+	pub fn with_chat_turn(&self, chat_turn: Option<ChatTurn>) -> Self {
+		ParamsInner {
+			on_file_globs: self.inner.on_file_globs.clone(),
+			on_inputs: self.inner.on_inputs.clone(),
+			cli_args: self.inner.cli_args.clone(),
+			cli_args_json: self.inner.cli_args_json.clone(),
+			flow_redo_count: self.inner.flow_redo_count,
+			base_run_options: RunBaseOptions {
+				task_redo: None,
+				priority: interactive_if(chat_turn.is_some(), self.inner.base_run_options.priority),
+				chat_turn,
+				..self.inner.base_run_options.clone()
+			},
+		}
+		.into()
+	}
 }
 
 // endregion: --- RunCommandOptions
@@ -131,6 +221,18 @@ pub struct RunBaseOptions {
 	dry_mode: DryMode,
 	open: bool,
 	flow_redo_count: i32,
+	/// The `--export` run report path (only for the top runs)
+	export_path: Option<String>,
+	/// When set, only this task is redone (from the TUI)
+	task_redo: Option<TaskRedo>,
+	/// When set, the run is one chat follow-up turn (from the TUI)
+	chat_turn: Option<ChatTurn>,
+	/// The priority class of the run tasks (see `TaskScheduler`)
+	priority: RunPriority,
+	/// When true, the checkpoint of an interrupted run is not resumed (see `RunCheckpoint`)
+	fresh: bool,
+	/// The capabilities of the caller run pack (for a sub agent run), which restrict the ones of the run
+	parent_capabilities: Option<PackCapabilities>,
 }
 
 impl RunBaseOptions {
@@ -153,12 +255,115 @@ impl RunBaseOptions {
 	pub fn flow_redo_count(&self) -> i32 {
 		self.flow_redo_count
 	}
+
+	pub fn export_path(&self) -> Option<&str> {
+		self.export_path.as_deref()
+	}
+
+	pub fn task_redo(&self) -> Option<&TaskRedo> {
+		self.task_redo.as_ref()
+	}
+
+	pub fn chat_turn(&self) -> Option<&ChatTurn> {
+		self.chat_turn.as_ref()
+	}
+
+	pub fn priority(&self) -> RunPriority {
+		self.priority
+	}
+
+	pub fn fresh(&self) -> bool {
+		self.fresh
+	}
+
+	pub fn parent_capabilities(&self) -> Option<&PackCapabilities> {
+		self.parent_capabilities.as_ref()
+	}
+
+	/// Restrict the run by the capabilities of its caller run pack (for a sub agent run)
+	pub fn with_parent_capabilities(mut self, parent_capabilities: Option<PackCapabilities>) -> Self {
+		self.parent_capabilities = parent_capabilities;
+		self
+	}
+}
+
+/// The redo of some tasks of a run.
+/// - `task_idxs` are the indexes of the inputs (and tasks) in the run
+/// - `instruction` is the eventual edited prompt text (see `prompt_text_to_chat_messages`)
+/// - `redo_of` is the eventual redone run data (when present, its before all output and inputs are reused)
+#[derive(Debug, Clone)]
+pub struct TaskRedo {
+	task_idxs: Vec<usize>,
+	instruction: Option<String>,
+	redo_of: Option<RunRedoData>,
+}
+
+impl TaskRedo {
+	pub fn new(task_idxs: Vec<usize>, instruction: Option<String>) -> Self {
+		Self {
+			task_idxs,
+			instruction,
+			redo_of: None,
+		}
+	}
+
+	pub fn with_redo_of(mut self, redo_of: Option<RunRedoData>) -> Self {
+		self.redo_of = redo_of;
+		self
+	}
+
+	pub fn task_idxs(&self) -> &[usize] {
+		&self.task_idxs
+	}
+
+	pub fn instruction(&self) -> Option<&str> {
+		self.instruction.as_deref()
+	}
+
+	pub fn redo_of(&self) -> Option<&RunRedoData> {
+		self.redo_of.as_ref()
+	}
+}
+
+/// A chat follow-up turn of a run (from the TUI chat view).
+/// - `message` is the user message (the input of the single task, and the user turn of the conversation)
+/// - `follow_up_of` is the eventual followed run data (when present, its before all output is reused,
+///   and its conversation is continued)
+#[derive(Debug, Clone)]
+pub struct ChatTurn {
+	message: String,
+	follow_up_of: Option<RunRedoData>,
+}
+
+impl ChatTurn {
+	pub fn new(message: impl Into<String>, follow_up_of: Option<RunRedoData>) -> Self {
+		Self {
+			message: message.into(),
+			follow_up_of,
+		}
+	}
+
+	pub fn message(&self) -> &str {
+		&self.message
+	}
+
+	pub fn follow_up_of(&self) -> Option<&RunRedoData> {
+		self.follow_up_of.as_ref()
+	}
 }
 
 // endregion: --- Common
 
 // region:    --- Support
 
+fn interactive_if(interactive: bool, priority: RunPriority) -> RunPriority {
+	if interactive {
+		RunPriority::Interactive
+	} else {
+		priority
+	}
+}
+
 fn parse_dry_mode(dry_mode: Option<&str>) -> DryMode {
 	match dry_mode {
 		Some("req") => DryMode::Req,
@@ -0,0 +1,20 @@
+//! The distributed mode, where the tasks of a top agent run are dispatched to remote aipack workers.
+//!
+//! - The coordinator is the `aip run ... --workers-listen <addr>` process, which listens for the workers
+//!   and dispatches each task (input) to a free worker slot (see `WorkerPool`).
+//! - A worker is a `aip worker --join <addr>` process, which runs the tasks in its own workspace
+//!   (the agent is resolved by name, so the packs/agent files must be available on each worker).
+//! - The before all and after all stages still run on the coordinator.
+//! - The messages are JSON lines over TCP (see `worker_protocol`), with an optional shared token (`AIPACK_WORKER_TOKEN`).
+//!
+
+// region:    --- Modules
+
+mod worker_client;
+mod worker_pool;
+mod worker_protocol;
+
+pub use worker_client::*;
+pub use worker_pool::*;
+
+// endregion: --- Modules
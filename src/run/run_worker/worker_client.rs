@@ -0,0 +1,212 @@
+use crate::agent::find_agent;
+use crate::hub::get_hub;
+use crate::model::TaskForCreate;
+use crate::run::RunBaseOptions;
+use crate::run::literals::Literals;
+use crate::run::run_agent_task::run_agent_task_outer;
+use crate::run::run_worker::worker_protocol::{RemoteTaskJob, WORKER_TOKEN_ENV, WorkerMsg, read_msg, write_msg};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt as _, BufReader};
+use tokio::net::TcpStream;
+
+/// Join a coordinator (`aip worker --join <addr>`) and run its tasks until it closes the connection.
+///
+/// Each job is run as a single task run of the local workspace (so it shows in the local runs),
+/// and its result is sent back as soon as it completes.
+pub async fn run_worker(runtime: Runtime, coordinator: &str, slots: usize, name: Option<String>) -> Result<()> {
+	let hub = get_hub();
+
+	let stream = TcpStream::connect(coordinator)
+		.await
+		.map_err(|err| Error::custom(format!("Cannot join coordinator '{coordinator}'. Cause: {err}")))?;
+	let (read_half, mut write_half) = stream.into_split();
+	let mut lines = BufReader::new(read_half).lines();
+
+	// -- Handshake
+	let name = name
+		.or_else(|| std::env::var("HOSTNAME").ok())
+		.unwrap_or_else(|| "worker".to_string());
+	let hello = WorkerMsg::Hello {
+		name: name.clone(),
+		version: crate::VERSION.to_string(),
+		slots,
+		token: std::env::var(WORKER_TOKEN_ENV).ok().filter(|t| !t.is_empty()),
+	};
+	write_msg(&mut write_half, &hello).await?;
+	match read_msg(&mut lines).await? {
+		Some(WorkerMsg::Welcome) => (),
+		Some(WorkerMsg::Reject { reason }) => {
+			return Err(Error::custom(format!(
+				"Coordinator '{coordinator}' rejected the worker. {reason}"
+			)));
+		}
+		_ => {
+			return Err(Error::custom(format!(
+				"Coordinator '{coordinator}' did not welcome the worker"
+			)));
+		}
+	}
+	hub.publish(format!(
+		"Worker '{name}' joined coordinator '{coordinator}' ({slots} slots). Waiting for tasks..."
+	))
+	.await;
+
+	// -- Writer (results to the coordinator)
+	let (done_tx, done_rx) = flume::unbounded::<WorkerMsg>();
+	let writer = tokio::spawn(async move {
+		while let Ok(msg) = done_rx.recv_async().await {
+			if write_msg(&mut write_half, &msg).await.is_err() {
+				break;
+			}
+		}
+	});
+
+	// -- Reader (jobs from the coordinator)
+	while let Some(msg) = read_msg(&mut lines).await? {
+		let WorkerMsg::Job(job) = msg else {
+			continue;
+		};
+		let runtime = runtime.clone();
+		let done_tx = done_tx.clone();
+		tokio::spawn(async move {
+			let job_id = job.job_id;
+			let msg = match run_remote_job(&runtime, job).await {
+				Ok(output) => WorkerMsg::Done {
+					job_id,
+					output: Some(output),
+					error: None,
+				},
+				Err(err) => WorkerMsg::Done {
+					job_id,
+					output: None,
+					error: Some(err.to_string()),
+				},
+			};
+			let _ = done_tx.send(msg);
+		});
+	}
+
+	drop(done_tx);
+	writer.abort();
+	hub.publish(format!(
+		"Coordinator '{coordinator}' closed the connection. Worker done."
+	))
+	.await;
+
+	Ok(())
+}
+
+async fn run_remote_job(runtime: &Runtime, job: RemoteTaskJob) -> Result<Value> {
+	let RemoteTaskJob {
+		agent_name,
+		args,
+		input_idx,
+		before_all,
+		input,
+		..
+	} = job;
+
+	// -- The agent, with the args of the coordinator run (the defaults when none)
+	let agent = find_agent(&agent_name, runtime, None)?;
+	let agent = if args.is_null() { agent } else { agent.with_args(args) };
+	let rt_model = runtime.rt_model();
+	let rt_step = runtime.rt_step();
+
+	// -- Rt Create - the local run of this single task
+	let run_id = rt_model.create_run(None, &agent).await?;
+	let run_id = rt_step.step_run_start(run_id).await?;
+	let task_ids = rt_model
+		.create_tasks_batch(
+			run_id,
+			vec![TaskForCreate::new_with_input(run_id, input_idx as i64, None, &input)],
+		)
+		.await?;
+	let task_id = *task_ids.first().ok_or_else(|| Error::custom("Worker task was not created"))?;
+
+	// -- Run the task
+	rt_step.step_task_start(run_id, task_id).await?;
+	let res = match Literals::from_runtime_and_agent_path(runtime, &agent, None) {
+		Ok(literals) => {
+			run_agent_task_outer(
+				run_id,
+				task_id,
+				input_idx,
+				runtime,
+				&agent,
+				before_all,
+				input,
+				&literals,
+				&RunBaseOptions::default(),
+			)
+			.await
+		}
+		Err(err) => Err(err),
+	};
+
+	// -- Rt Step - End
+	match res {
+		Ok((_, output)) => {
+			rt_step.step_task_end_ok(run_id, task_id).await?;
+			rt_step.step_run_end_ok(run_id).await?;
+			Ok(output)
+		}
+		Err(err) => {
+			rt_step.step_task_end_err(run_id, task_id, &err).await?;
+			rt_step.step_run_end_err(run_id, &err).await?;
+			Err(err)
+		}
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{clean_sanbox_01_tmp_file, create_sanbox_01_tmp_file};
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn test_run_worker_client_remote_job_args() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let agent_content = r#"
+# Data
+```lua
+return {input = input, mode = args.mode}
+```
+# Output
+```lua
+return data
+```
+"#;
+		let agent_file = create_sanbox_01_tmp_file("worker-args-agent.aip", agent_content)?;
+		let job = |job_id: u64, args: Value| RemoteTaskJob {
+			job_id,
+			agent_name: agent_file.to_string(),
+			args,
+			input_idx: 0,
+			before_all: Value::Null,
+			input: json!("hello"),
+		};
+
+		// -- Exec
+		let output = run_remote_job(&runtime, job(1, json!({"mode": "fast"}))).await;
+		let default_output = run_remote_job(&runtime, job(2, Value::Null)).await;
+		clean_sanbox_01_tmp_file(agent_file.clone())?;
+
+		// -- Check
+		let output = output?;
+		assert_eq!(output["input"], "hello");
+		assert_eq!(output["mode"], "fast");
+		assert!(default_output?.get("mode").is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,513 @@
+use crate::agent::Agent;
+use crate::event::{OneShotTx, new_one_shot_channel};
+use crate::hub::get_hub;
+use crate::model::Id;
+use crate::run::run_worker::worker_protocol::{RemoteTaskJob, WORKER_TOKEN_ENV, WorkerMsg, read_msg, write_msg};
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt as _, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The number of workers a job is tried on when the worker running it disconnects
+const MAX_JOB_ATTEMPTS: usize = 3;
+
+/// The coordinator side of the distributed mode (`aip run ... --workers-listen <addr>`).
+///
+/// The workers (`aip worker --join <addr>`) join at any time, each with a number of slots.
+/// The tasks of the top agent runs are dispatched to the free slots, and the results are streamed back.
+#[derive(Debug, Clone)]
+pub struct WorkerPool {
+	inner: Arc<PoolInner>,
+}
+
+#[derive(Debug)]
+struct PoolInner {
+	addr: SocketAddr,
+	token: Option<String>,
+	slot_tx: flume::Sender<WorkerSlot>,
+	slot_rx: flume::Receiver<WorkerSlot>,
+	next_job_id: AtomicU64,
+	/// The number of connected workers
+	workers: AtomicUsize,
+	/// So that the "waiting for a worker" message is shown once (until a worker joins)
+	wait_notified: AtomicBool,
+}
+
+/// Constructor
+impl WorkerPool {
+	/// Bind the listen address and start accepting the workers.
+	///
+	/// A non loopback listen address requires the `AIPACK_WORKER_TOKEN` shared token.
+	pub async fn start(listen_addr: &str) -> Result<Self> {
+		let listener = TcpListener::bind(listen_addr)
+			.await
+			.map_err(|err| Error::custom(format!("Cannot listen for workers on '{listen_addr}'. Cause: {err}")))?;
+		let addr = listener
+			.local_addr()
+			.map_err(|err| Error::custom(format!("Cannot get the workers listen address. Cause: {err}")))?;
+
+		// NOTE: The token is sent in plaintext (no TLS), so it only guards against the unintended workers
+		//       of a trusted network.
+		let token = std::env::var(WORKER_TOKEN_ENV).ok().filter(|t| !t.is_empty());
+		if token.is_none() && !addr.ip().is_loopback() {
+			return Err(Error::custom(format!(
+				"Workers listen on the non loopback address '{addr}' requires a shared token (set {WORKER_TOKEN_ENV})"
+			)));
+		}
+
+		let (slot_tx, slot_rx) = flume::unbounded();
+		let pool = Self {
+			inner: Arc::new(PoolInner {
+				addr,
+				token,
+				slot_tx,
+				slot_rx,
+				next_job_id: AtomicU64::new(1),
+				workers: AtomicUsize::new(0),
+				wait_notified: AtomicBool::new(false),
+			}),
+		};
+
+		let pool_clone = pool.clone();
+		tokio::spawn(async move {
+			loop {
+				match listener.accept().await {
+					Ok((stream, peer)) => {
+						let pool = pool_clone.clone();
+						tokio::spawn(async move {
+							if let Err(err) = pool.handle_worker(stream, peer).await {
+								get_hub()
+									.publish_err(format!("Worker '{peer}' connection failed"), Some(err))
+									.await;
+							}
+						});
+					}
+					Err(err) => {
+						get_hub().publish_err("Workers listener stopped", Some(err)).await;
+						break;
+					}
+				}
+			}
+		});
+
+		Ok(pool)
+	}
+}
+
+/// Getters
+impl WorkerPool {
+	/// The max number of tasks of a run waiting for (or running on) the worker slots
+	pub const MAX_IN_FLIGHT_TASKS: usize = 256;
+
+	pub fn addr(&self) -> SocketAddr {
+		self.inner.addr
+	}
+}
+
+/// Dispatch
+impl WorkerPool {
+	/// Run a task of a coordinator run on the next free worker slot, and record its output.
+	///
+	/// NOTE: Waits for a worker to join when none is available.
+	///       When the worker disconnects, the task is retried on another worker (up to `MAX_JOB_ATTEMPTS`).
+	pub async fn run_agent_task(
+		&self,
+		runtime: &Runtime,
+		task_id: Id,
+		input_idx: usize,
+		agent: &Agent,
+		before_all: Value,
+		input: Value,
+	) -> Result<(usize, Value)> {
+		let hub = get_hub();
+
+		for attempt in 1..=MAX_JOB_ATTEMPTS {
+			// -- Hold the task while the run is paused (as the local tasks)
+			if let Some(pause_rx) = runtime.pause_rx() {
+				pause_rx.wait_if_paused().await;
+			}
+
+			let slot = self.next_slot().await?;
+			let job_id = self.inner.next_job_id.fetch_add(1, Ordering::Relaxed);
+			let job = RemoteTaskJob {
+				job_id,
+				agent_name: agent.name().to_string(),
+				args: agent.args().clone(),
+				input_idx,
+				before_all: before_all.clone(),
+				input: input.clone(),
+			};
+
+			hub.publish(format!(
+				"\n==== Running input: {input_idx} (on worker '{}')",
+				slot.conn.name
+			))
+			.await;
+			let outcome = slot.conn.send_job(job).await;
+
+			// -- Give back the slot (dead workers slots are dropped on next_slot)
+			if slot.conn.is_alive() {
+				let _ = self.inner.slot_tx.send(slot.clone());
+			}
+
+			match outcome {
+				JobOutcome::Output(output) => {
+					hub.publish(format!("==== DONE (input: {input_idx}, worker: '{}')", slot.conn.name))
+						.await;
+					runtime.rt_model().update_task_output(task_id, &output).await?;
+					return Ok((input_idx, output));
+				}
+				JobOutcome::TaskErr(err) => {
+					return Err(Error::custom(format!(
+						"Task failed on worker '{}'. Cause: {err}",
+						slot.conn.name
+					)));
+				}
+				JobOutcome::Lost => {
+					hub.publish(format!(
+						"Worker '{}' disconnected while running input {input_idx} (attempt {attempt}/{MAX_JOB_ATTEMPTS})",
+						slot.conn.name
+					))
+					.await;
+				}
+			}
+		}
+
+		Err(Error::custom(format!(
+			"Input {input_idx} could not complete on any worker ({MAX_JOB_ATTEMPTS} attempts)"
+		)))
+	}
+
+	async fn next_slot(&self) -> Result<WorkerSlot> {
+		loop {
+			if self.inner.workers.load(Ordering::Relaxed) == 0
+				&& !self.inner.wait_notified.swap(true, Ordering::Relaxed)
+			{
+				get_hub()
+					.publish(format!(
+						"Waiting for a worker to join (with `aip worker --join {}`)",
+						self.inner.addr
+					))
+					.await;
+			}
+			let slot = self
+				.inner
+				.slot_rx
+				.recv_async()
+				.await
+				.map_err(|err| Error::custom(format!("Worker slots channel closed. Cause: {err}")))?;
+			if slot.conn.is_alive() {
+				return Ok(slot);
+			}
+		}
+	}
+}
+
+/// Worker connections
+impl WorkerPool {
+	async fn handle_worker(&self, stream: TcpStream, peer: SocketAddr) -> Result<()> {
+		let hub = get_hub();
+		let (read_half, mut write_half) = stream.into_split();
+		let mut lines = BufReader::new(read_half).lines();
+
+		// -- Handshake
+		let Some(WorkerMsg::Hello {
+			name,
+			version,
+			slots,
+			token,
+		}) = read_msg(&mut lines).await?
+		else {
+			return Err(Error::custom("First worker message must be a 'hello'"));
+		};
+		if self.inner.token.is_some() && token != self.inner.token {
+			let reason = format!("Invalid worker token (see {WORKER_TOKEN_ENV})");
+			write_msg(&mut write_half, &WorkerMsg::Reject { reason: reason.clone() }).await?;
+			return Err(Error::custom(format!("Worker '{name}' rejected. {reason}")));
+		}
+		if version != crate::VERSION {
+			hub.publish(format!(
+				"WARNING - Worker '{name}' version '{version}' differs from coordinator version '{}'",
+				crate::VERSION
+			))
+			.await;
+		}
+		write_msg(&mut write_half, &WorkerMsg::Welcome).await?;
+
+		// -- Writer (jobs to the worker)
+		let (job_tx, job_rx) = flume::unbounded::<RemoteTaskJob>();
+		tokio::spawn(async move {
+			while let Ok(job) = job_rx.recv_async().await {
+				if write_msg(&mut write_half, &WorkerMsg::Job(job)).await.is_err() {
+					break;
+				}
+			}
+		});
+
+		let name = format!("{name}@{peer}");
+		let slots = slots.max(1);
+		let conn = Arc::new(WorkerConn {
+			name: name.clone(),
+			job_tx,
+			state: Mutex::new(ConnState {
+				alive: true,
+				pending: HashMap::new(),
+			}),
+		});
+		for _ in 0..slots {
+			let _ = self.inner.slot_tx.send(WorkerSlot { conn: conn.clone() });
+		}
+		self.inner.workers.fetch_add(1, Ordering::Relaxed);
+		self.inner.wait_notified.store(false, Ordering::Relaxed);
+		hub.publish(format!("Worker '{name}' joined ({slots} slots)")).await;
+
+		// -- Reader (results from the worker)
+		let res = loop {
+			match read_msg(&mut lines).await {
+				Ok(Some(WorkerMsg::Done { job_id, output, error })) => {
+					let outcome = match error {
+						Some(err) => JobOutcome::TaskErr(err),
+						None => JobOutcome::Output(output.unwrap_or_default()),
+					};
+					conn.resolve(job_id, outcome).await;
+				}
+				Ok(Some(_)) => (), // other messages are ignored
+				Ok(None) => break Ok(()),
+				Err(err) => break Err(err),
+			}
+		};
+
+		conn.close().await;
+		self.inner.workers.fetch_sub(1, Ordering::Relaxed);
+		hub.publish(format!("Worker '{name}' left")).await;
+
+		res
+	}
+}
+
+// region:    --- WorkerConn
+
+#[derive(Debug, Clone)]
+struct WorkerSlot {
+	conn: Arc<WorkerConn>,
+}
+
+#[derive(Debug)]
+enum JobOutcome {
+	Output(Value),
+	TaskErr(String),
+	/// The worker disconnected before sending the result
+	Lost,
+}
+
+#[derive(Debug)]
+struct WorkerConn {
+	name: String,
+	job_tx: flume::Sender<RemoteTaskJob>,
+	state: Mutex<ConnState>,
+}
+
+#[derive(Debug)]
+struct ConnState {
+	alive: bool,
+	pending: HashMap<u64, OneShotTx<JobOutcome>>,
+}
+
+impl WorkerConn {
+	fn is_alive(&self) -> bool {
+		self.state.lock().map(|state| state.alive).unwrap_or(false)
+	}
+
+	async fn send_job(&self, job: RemoteTaskJob) -> JobOutcome {
+		let (tx, rx) = new_one_shot_channel("worker_job_outcome");
+
+		// NOTE: Registered under the state lock, so that a close drains it (no lost wait).
+		{
+			let Ok(mut state) = self.state.lock() else {
+				return JobOutcome::Lost;
+			};
+			if !state.alive {
+				return JobOutcome::Lost;
+			}
+			state.pending.insert(job.job_id, tx);
+		}
+
+		if self.job_tx.send(job).is_err() {
+			return JobOutcome::Lost;
+		}
+
+		rx.recv().await.unwrap_or(JobOutcome::Lost)
+	}
+
+	async fn resolve(&self, job_id: u64, outcome: JobOutcome) {
+		let tx = self.state.lock().ok().and_then(|mut state| state.pending.remove(&job_id));
+		if let Some(tx) = tx {
+			let _ = tx.send(outcome).await;
+		}
+	}
+
+	async fn close(&self) {
+		let pending = match self.state.lock() {
+			Ok(mut state) => {
+				state.alive = false;
+				state.pending.drain().map(|(_, tx)| tx).collect::<Vec<_>>()
+			}
+			Err(_) => Vec::new(),
+		};
+		for tx in pending {
+			let _ = tx.send(JobOutcome::Lost).await;
+		}
+	}
+}
+
+// endregion: --- WorkerConn
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{assert_contains, create_run, create_task, load_inline_agent};
+	use serde_json::json;
+	use tokio::io::Lines;
+	use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+
+	type FakeWorker = (Lines<BufReader<OwnedReadHalf>>, OwnedWriteHalf);
+
+	#[tokio::test]
+	async fn test_run_worker_pool_dispatch_with_args() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let task_id = create_test_task(&runtime)?;
+		let agent = load_inline_agent("worker-agent.aip", "# Output\n```lua\nreturn input\n```")?
+			.with_args(json!({"mode": "fast"}));
+		let pool = WorkerPool::start("127.0.0.1:0").await?;
+		let (mut lines, mut writer) = join_fake_worker(&pool, "fake-worker").await?;
+
+		// -- Exec
+		let dispatch = {
+			let pool = pool.clone();
+			let runtime = runtime.clone();
+			tokio::spawn(async move {
+				pool.run_agent_task(&runtime, task_id, 2, &agent, Value::Null, json!("hello"))
+					.await
+			})
+		};
+		let job = recv_job(&mut lines).await?;
+		let done = WorkerMsg::Done {
+			job_id: job.job_id,
+			output: Some(json!("HELLO")),
+			error: None,
+		};
+		write_msg(&mut writer, &done).await?;
+		let (input_idx, output) = dispatch.await??;
+
+		// -- Check
+		assert_eq!(job.agent_name, "inline-agent");
+		assert_eq!(job.args, json!({"mode": "fast"}));
+		assert_eq!(job.input_idx, 2);
+		assert_eq!(job.input, json!("hello"));
+		assert_eq!(input_idx, 2);
+		assert_eq!(output, json!("HELLO"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_worker_pool_retry_on_lost_worker() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let task_id = create_test_task(&runtime)?;
+		let agent = load_inline_agent("worker-agent.aip", "# Output\n```lua\nreturn input\n```")?
+			.with_args(json!({"mode": "fast"}));
+		let pool = WorkerPool::start("127.0.0.1:0").await?;
+		let (mut lost_lines, lost_writer) = join_fake_worker(&pool, "lost-worker").await?;
+
+		// -- Exec
+		let dispatch = {
+			let pool = pool.clone();
+			let runtime = runtime.clone();
+			tokio::spawn(async move {
+				pool.run_agent_task(&runtime, task_id, 0, &agent, Value::Null, json!("hello"))
+					.await
+			})
+		};
+		// the first worker disconnects while running the job
+		let lost_job = recv_job(&mut lost_lines).await?;
+		drop(lost_lines);
+		drop(lost_writer);
+		// the job is retried on the next worker
+		let (mut lines, mut writer) = join_fake_worker(&pool, "fake-worker").await?;
+		let job = recv_job(&mut lines).await?;
+		let done = WorkerMsg::Done {
+			job_id: job.job_id,
+			output: Some(json!("retried")),
+			error: None,
+		};
+		write_msg(&mut writer, &done).await?;
+		let (_, output) = dispatch.await??;
+
+		// -- Check
+		assert_ne!(lost_job.job_id, job.job_id);
+		assert_eq!(job.args, json!({"mode": "fast"}));
+		assert_eq!(job.input, lost_job.input);
+		assert_eq!(output, json!("retried"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_run_worker_pool_start_err_remote_no_token() -> Result<()> {
+		// -- Exec
+		let res = WorkerPool::start("0.0.0.0:0").await;
+
+		// -- Check
+		let err = res.err().ok_or("Should fail without a token")?;
+		assert_contains(&err.to_string(), "requires a shared token");
+
+		Ok(())
+	}
+
+	// region:    --- Support
+
+	fn create_test_task(runtime: &Runtime) -> Result<Id> {
+		let run_id = create_run(runtime.mm(), "worker-pool-run")?;
+		Ok(create_task(runtime.mm(), run_id, 0)?)
+	}
+
+	async fn join_fake_worker(pool: &WorkerPool, name: &str) -> Result<FakeWorker> {
+		let stream = TcpStream::connect(pool.addr()).await?;
+		let (read_half, mut writer) = stream.into_split();
+		let mut lines = BufReader::new(read_half).lines();
+		let hello = WorkerMsg::Hello {
+			name: name.to_string(),
+			version: crate::VERSION.to_string(),
+			slots: 1,
+			token: None,
+		};
+		write_msg(&mut writer, &hello).await?;
+		let Some(WorkerMsg::Welcome) = read_msg(&mut lines).await? else {
+			return Err("Should be welcomed".into());
+		};
+		Ok((lines, writer))
+	}
+
+	async fn recv_job(lines: &mut Lines<BufReader<OwnedReadHalf>>) -> Result<RemoteTaskJob> {
+		let msg = tokio::time::timeout(std::time::Duration::from_secs(10), read_msg(lines)).await??;
+		match msg {
+			Some(WorkerMsg::Job(job)) => Ok(job),
+			other => Err(format!("Should be a job, was {other:?}").into()),
+		}
+	}
+
+	// endregion: --- Support
+}
+
+// endregion: --- Tests
@@ -0,0 +1,129 @@
+//! The coordinator <-> worker wire protocol (one JSON message per line over TCP).
+
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufRead, AsyncWrite, AsyncWriteExt as _, Lines};
+
+/// The env var of the shared token (when set on the coordinator, the workers must send the same)
+pub const WORKER_TOKEN_ENV: &str = "AIPACK_WORKER_TOKEN";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WorkerMsg {
+	/// worker -> coordinator, first message
+	Hello {
+		name: String,
+		version: String,
+		slots: usize,
+		token: Option<String>,
+	},
+	/// coordinator -> worker, the hello was accepted
+	Welcome,
+	/// coordinator -> worker, the hello was rejected (the connection is closed after)
+	Reject { reason: String },
+	/// coordinator -> worker, a task to run
+	Job(RemoteTaskJob),
+	/// worker -> coordinator, the result of a job (sent as soon as the task completes)
+	Done {
+		job_id: u64,
+		output: Option<Value>,
+		error: Option<String>,
+	},
+}
+
+/// A task of a coordinator run, executed by a worker in its own workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteTaskJob {
+	pub job_id: u64,
+	/// The agent name as given to `aip run` (resolved by the worker, e.g., `demo@proof` or `path/to/agent.aip`)
+	pub agent_name: String,
+	/// The args resolved by the coordinator (from the `--arg name=value`), `null` for the agent defaults
+	#[serde(default)]
+	pub args: Value,
+	pub input_idx: usize,
+	pub before_all: Value,
+	pub input: Value,
+}
+
+/// Write a message as a single JSON line.
+pub async fn write_msg<W: AsyncWrite + Unpin>(writer: &mut W, msg: &WorkerMsg) -> Result<()> {
+	let mut line = serde_json::to_string(msg)?;
+	line.push('\n');
+	writer
+		.write_all(line.as_bytes())
+		.await
+		.map_err(|err| Error::custom(format!("Cannot write worker message. Cause: {err}")))?;
+	writer
+		.flush()
+		.await
+		.map_err(|err| Error::custom(format!("Cannot flush worker message. Cause: {err}")))?;
+	Ok(())
+}
+
+/// Read the next message (None when the connection is closed).
+pub async fn read_msg<R: AsyncBufRead + Unpin>(lines: &mut Lines<R>) -> Result<Option<WorkerMsg>> {
+	loop {
+		let line = lines
+			.next_line()
+			.await
+			.map_err(|err| Error::custom(format!("Cannot read worker message. Cause: {err}")))?;
+		let Some(line) = line else {
+			return Ok(None);
+		};
+		if line.trim().is_empty() {
+			continue;
+		}
+		let msg = serde_json::from_str::<WorkerMsg>(&line)
+			.map_err(|err| Error::custom(format!("Invalid worker message '{line}'. Cause: {err}")))?;
+		return Ok(Some(msg));
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+	use tokio::io::{AsyncBufReadExt as _, BufReader};
+
+	#[tokio::test]
+	async fn test_run_worker_protocol_write_read() -> Result<()> {
+		// -- Setup & Fixtures
+		let job = RemoteTaskJob {
+			job_id: 7,
+			agent_name: "demo@proof".to_string(),
+			args: json!({"mode": "fast"}),
+			input_idx: 3,
+			before_all: Value::Null,
+			input: json!({"path": "docs/a\nb.md"}),
+		};
+		let mut buf: Vec<u8> = Vec::new();
+
+		// -- Exec
+		write_msg(&mut buf, &WorkerMsg::Job(job)).await?;
+		write_msg(&mut buf, &WorkerMsg::Welcome).await?;
+		let mut lines = BufReader::new(buf.as_slice()).lines();
+		let first = read_msg(&mut lines).await?;
+		let second = read_msg(&mut lines).await?;
+		let end = read_msg(&mut lines).await?;
+
+		// -- Check
+		assert_eq!(String::from_utf8(buf.clone())?.lines().count(), 2);
+		let Some(WorkerMsg::Job(job)) = first else {
+			return Err("Should be a Job".into());
+		};
+		assert_eq!(job.job_id, 7);
+		assert_eq!(job.args["mode"], "fast");
+		assert_eq!(job.input["path"], "docs/a\nb.md");
+		assert!(matches!(second, Some(WorkerMsg::Welcome)));
+		assert!(end.is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
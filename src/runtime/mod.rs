@@ -15,5 +15,6 @@ pub use rt_log::*;
 pub use rt_model::*;
 pub use rt_step::*;
 pub use runtime_impl::*;
+pub use support::{BusMessage, BusPoll, BusScope};
 
 // endregion: --- Modules
@@ -3,15 +3,17 @@ use crate::agent::Agent;
 use crate::hub::get_hub;
 use crate::model::base::DbBmc;
 use crate::model::{
-	EndState, Id, LogBmc, LogForCreate, LogKind, ModelManager, RunBmc, RunForCreate, RunForUpdate, Stage, TaskBmc,
-	TaskForCreate, TaskForUpdate, TypedContent,
+	ConvMsgBmc, ConvMsgForCreate, EndState, Id, LogBmc, LogForCreate, LogKind, ModelManager, Run, RunBmc, RunForCreate,
+	RunForUpdate, Stage, TaskBmc, TaskForCreate, TaskForUpdate, TypedContent, new_history_store,
 };
-use crate::run::ModelPricing;
+use crate::run::{ConvMessage, ModelPricing, RunParent};
 use crate::runtime::Runtime;
+use crate::support::time::now_micro;
+use crate::types::ResUsage;
 use derive_more::From;
 use genai::ModelIden;
 use serde_json::Value;
-use uuid::Uuid;
+use std::collections::HashSet;
 
 #[derive(Debug, From)]
 pub struct RtModel<'a> {
@@ -27,30 +29,40 @@ impl<'a> RtModel<'a> {
 	fn mm(&self) -> &ModelManager {
 		self.runtime.mm()
 	}
+
+	/// The agent path of its runs (relative to the workspace when possible)
+	fn agent_display_path(&self, agent: &Agent) -> String {
+		match self.runtime.dir_context().get_display_path(agent.file_path()) {
+			Ok(path) => path.to_string(),
+			Err(_) => agent.file_path().to_string(),
+		}
+	}
 }
 
 /// Run Create/Update model
 impl<'a> RtModel<'a> {
-	pub async fn create_run(&self, parent_uid: Option<Uuid>, agent: &Agent) -> Result<Id> {
+	pub async fn create_run(&self, parent: Option<RunParent>, agent: &Agent) -> Result<Id> {
 		let hub = get_hub();
 
-		let agent_path = match self.runtime.dir_context().get_display_path(agent.file_path()) {
-			Ok(path) => path.to_string(),
-			Err(_) => agent.file_path().to_string(),
-		};
+		let agent_path = self.agent_display_path(agent);
 		let agent_name = agent.name();
 
-		let parent_id = if let Some(uid) = parent_uid {
-			Some(RunBmc::get_id_for_uid(self.mm(), uid)?)
+		let parent_id = if let Some(parent) = parent {
+			Some(RunBmc::get_id_for_uid(self.mm(), parent.run_uid)?)
 		} else {
 			None
 		};
+		// NOTE: The parent task is for display only (grouping in the TUI), so, not an error if not found.
+		let parent_task_id = parent
+			.and_then(|p| p.task_uid)
+			.and_then(|task_uid| TaskBmc::get_id_for_uid(self.mm(), task_uid).ok());
 
 		// -- Create Run
 		let run_id = RunBmc::create(
 			self.mm(),
 			RunForCreate {
 				parent_id,
+				parent_task_id,
 				agent_name: Some(agent_name.to_string()),
 				agent_path: Some(agent_path.to_string()),
 				has_task_stages: Some(agent.has_task_stages()),
@@ -112,6 +124,70 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	/// Persist the ended top run (and its sub runs) into the runs history db
+	/// (`.aipack/.session/_history.db`, or the config `[store] history_db`).
+	/// NOTE: No-op when no history db (no config and no workspace `.aipack/`).
+	pub fn persist_run_to_history(&self, run_id: Id) -> Result<()> {
+		let Some(history_db) = self.runtime.dir_context().history_db()? else {
+			return Ok(());
+		};
+		let history_store = new_history_store(&history_db, &self.runtime.dir_context().history_scope());
+		self.mm().persist_run_to_history(run_id, history_store.as_ref())?;
+		// So that the TUI history mode gets refreshed
+		get_hub().publish_rt_model_change_sync();
+		Ok(())
+	}
+
+	/// Returns the start (epoch us) of the last top run of the agent that ended `Ok`, from the runs history db.
+	/// NOTE: None when no history db (or no such run).
+	pub fn last_ok_run_start(&self, agent: &Agent) -> Result<Option<i64>> {
+		let run = self.last_ok_hist_run(agent)?.map(|(_, run)| run);
+
+		Ok(run.and_then(|run| run.start).map(|start| start.as_i64()))
+	}
+
+	/// Returns the last top run of the agent that ended `Ok` (from the runs history db),
+	/// and the input hashes of its tasks that ended `Ok` or were cached (see `Incremental`).
+	pub fn last_ok_run_input_hashes(&self, agent: &Agent) -> Result<Option<(Run, HashSet<String>)>> {
+		let Some((hist_mm, run)) = self.last_ok_hist_run(agent)? else {
+			return Ok(None);
+		};
+		let input_hashes = TaskBmc::list_for_run(&hist_mm, run.id)?
+			.into_iter()
+			.filter(|task| task.end_state == Some(EndState::Ok) || task.cached == Some(true))
+			.filter_map(|task| task.input_hash)
+			.collect();
+
+		Ok(Some((run, input_hashes)))
+	}
+
+	fn last_ok_hist_run(&self, agent: &Agent) -> Result<Option<(ModelManager, Run)>> {
+		let Some(history_db) = self.runtime.dir_context().history_db()? else {
+			return Ok(None);
+		};
+		let history_store = new_history_store(&history_db, &self.runtime.dir_context().history_scope());
+		if !history_store.exists() {
+			return Ok(None);
+		}
+		let hist_mm = history_store.open()?;
+		let agent_path = self.agent_display_path(agent);
+		let run = RunBmc::last_ok_top_run_for_agent(&hist_mm, &agent_path)?;
+
+		Ok(run.map(|run| (hist_mm, run)))
+	}
+
+	pub fn update_run_agent_hash(&self, run_id: Id, agent_hash: &str) -> Result<()> {
+		RunBmc::update(
+			self.mm(),
+			run_id,
+			RunForUpdate {
+				agent_hash: Some(agent_hash.to_string()),
+				..Default::default()
+			},
+		)?;
+		Ok(())
+	}
+
 	/// NOTE: Probably shoul put the end state as well
 	pub async fn rec_skip_run(&self, run_id: Id, stage: Stage, reason: Option<String>) -> Result<()> {
 		let mm = self.mm();
@@ -206,6 +282,15 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	pub async fn update_task_finish_reason(&self, _run_id: Id, task_id: Id, finish_reason: &str) -> Result<()> {
+		let task_u = TaskForUpdate {
+			finish_reason: Some(finish_reason.to_string()),
+			..Default::default()
+		};
+		TaskBmc::update(self.mm(), task_id, task_u)?;
+		Ok(())
+	}
+
 	pub async fn update_task_cost(
 		&self,
 		run_id: Id,
@@ -223,17 +308,78 @@ impl<'a> RtModel<'a> {
 		};
 		TaskBmc::update(self.mm(), task_id, task_u)?;
 
-		// -- Update the run total cost
-		// NOTE: Here we recompute the total cost rather than doing a simple add to avoid
-		//       any race condition
+		self.update_run_total_cost(run_id)?;
+
+		Ok(())
+	}
+
+	/// Add the embedding usage (from `aip.embed`) to the task, or to the run when outside of a task (e.g., before all).
+	pub fn add_embed_usage(&self, run_id: Id, task_id: Option<Id>, tokens: i64, cost: Option<f64>) -> Result<()> {
+		let mm = self.mm();
+		let add_opt = |acc: Option<f64>, val: Option<f64>| match (acc, val) {
+			(None, None) => None,
+			(acc, val) => Some(acc.unwrap_or_default() + val.unwrap_or_default()),
+		};
+
+		if let Some(task_id) = task_id {
+			let task = TaskBmc::get(mm, task_id)?;
+			let task_u = TaskForUpdate {
+				tk_embed_total: Some(task.tk_embed_total.unwrap_or_default() + tokens),
+				cost_embed: add_opt(task.cost_embed, cost),
+				..Default::default()
+			};
+			TaskBmc::update(mm, task_id, task_u)?;
+		} else {
+			let run = RunBmc::get(mm, run_id)?;
+			let run_u = RunForUpdate {
+				cost_embed: add_opt(run.cost_embed, cost),
+				..Default::default()
+			};
+			RunBmc::update(mm, run_id, run_u)?;
+		}
+
+		if cost.is_some() {
+			self.update_run_total_cost(run_id)?;
+		}
+
+		Ok(())
+	}
+
+	/// Add the resource usage (from `aip.cmd.exec` and `aip.file`) to the run (the peak memory is the max).
+	pub fn add_res_usage(&self, run_id: Id, usage: ResUsage) -> Result<()> {
+		if usage.is_empty() {
+			return Ok(());
+		}
+
+		let mm = self.mm();
+		let run = RunBmc::get(mm, run_id)?;
+		let add = |acc: Option<i64>, val: i64| (val > 0).then(|| acc.unwrap_or_default() + val).or(acc);
+		let run_u = RunForUpdate {
+			res_cpu_ms: add(run.res_cpu_ms, usage.cpu_ms),
+			res_peak_mem: (usage.peak_mem > run.res_peak_mem.unwrap_or_default())
+				.then_some(usage.peak_mem)
+				.or(run.res_peak_mem),
+			res_read_bytes: add(run.res_read_bytes, usage.read_bytes),
+			res_write_bytes: add(run.res_write_bytes, usage.write_bytes),
+			..Default::default()
+		};
+		RunBmc::update(mm, run_id, run_u)?;
+
+		Ok(())
+	}
+
+	/// Recompute the run total cost (the task AI and embedding costs, and the run embedding cost).
+	/// NOTE: Here we recompute the total cost rather than doing a simple add to avoid
+	///       any race condition
+	fn update_run_total_cost(&self, run_id: Id) -> Result<()> {
 		let tasks = TaskBmc::list_for_run(self.mm(), run_id)?;
-		let total_cost: f64 = tasks.iter().filter_map(|t| t.cost).sum();
+		let run = RunBmc::get(self.mm(), run_id)?;
+		let tasks_cost: f64 = tasks.iter().flat_map(|t| [t.cost, t.cost_embed]).flatten().sum();
 		let run_u = RunForUpdate {
-			total_cost: Some(total_cost),
+			total_cost: Some(tasks_cost + run.cost_embed.unwrap_or_default()),
 			..Default::default()
 		};
 		RunBmc::update(self.mm(), run_id, run_u)?;
-
 		Ok(())
 	}
 
@@ -248,6 +394,37 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	/// Link the run (and its tasks) to the run it redoes some tasks of
+	pub async fn link_redo_run(&self, run_id: Id, redo_of_run_id: Id) -> Result<()> {
+		let redo_of_run_uid = RunBmc::get_uid(self.mm(), redo_of_run_id)?;
+		RunBmc::update(
+			self.mm(),
+			run_id,
+			RunForUpdate {
+				redo_of_run_uid: Some(redo_of_run_uid),
+				..Default::default()
+			},
+		)?;
+		TaskBmc::link_redo_tasks(self.mm(), run_id, redo_of_run_id)?;
+		Ok(())
+	}
+
+	/// Update the task display metadata (from the data stage `_ui`)
+	pub async fn update_task_ui(&self, task_id: Id, label: Option<String>, ui_cols: Option<String>) -> Result<()> {
+		let task_u = TaskForUpdate {
+			label,
+			ui_cols,
+			..Default::default()
+		};
+		TaskBmc::update(self.mm(), task_id, task_u)?;
+		Ok(())
+	}
+
+	pub async fn update_task_prompt(&self, task_id: Id, prompt: &str) -> Result<()> {
+		TaskBmc::update_prompt(self.mm(), task_id, prompt)?;
+		Ok(())
+	}
+
 	pub fn set_task_end_error(&self, _run_id: Id, task_id: Id, stage: Option<Stage>, err: &crate::Error) -> Result<()> {
 		TaskBmc::set_end_error_no_end(self.mm(), task_id, stage, err)?;
 		Ok(())
@@ -267,6 +444,53 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 
+	pub fn update_task_input_hash(&self, task_id: Id, input_hash: &str) -> Result<()> {
+		TaskBmc::update(
+			self.mm(),
+			task_id,
+			TaskForUpdate {
+				input_hash: Some(input_hash.to_string()),
+				..Default::default()
+			},
+		)?;
+		Ok(())
+	}
+
+	/// Record the task as cached, skipped because its input and the agent are unchanged since the last run
+	/// (see `Incremental`). The task starts and ends right away.
+	pub async fn rec_cached_task(&self, run_id: Id, task_id: Id, input_hash: &str, reason: String) -> Result<()> {
+		let now = now_micro();
+		TaskBmc::update(
+			self.mm(),
+			task_id,
+			TaskForUpdate {
+				start: Some(now.into()),
+				end: Some(now.into()),
+				end_state: Some(EndState::Skip),
+				input_hash: Some(input_hash.to_string()),
+				cached: Some(true),
+				..Default::default()
+			},
+		)?;
+		self.rec_skip_task(run_id, task_id, Stage::Data, Some(reason)).await
+	}
+
+	/// Record the task ended before the interruption of its resumed run (see `RunCheckpoint`)
+	pub async fn rec_resumed_task(&self, run_id: Id, task_id: Id, reason: String) -> Result<()> {
+		let now = now_micro();
+		TaskBmc::update(
+			self.mm(),
+			task_id,
+			TaskForUpdate {
+				start: Some(now.into()),
+				end: Some(now.into()),
+				end_state: Some(EndState::Skip),
+				..Default::default()
+			},
+		)?;
+		self.rec_skip_task(run_id, task_id, Stage::Data, Some(reason)).await
+	}
+
 	pub async fn rec_skip_task(&self, run_id: Id, task_id: Id, stage: Stage, reason: Option<String>) -> Result<()> {
 		let mm = self.mm();
 
@@ -301,3 +525,26 @@ impl<'a> RtModel<'a> {
 		Ok(())
 	}
 }
+
+/// Run Conversation model
+impl<'a> RtModel<'a> {
+	/// Returns the run conversation (with the agent option `conversation = true`, or `aip.run.append_message`)
+	pub fn list_conv_messages(&self, run_id: Id) -> Result<Vec<ConvMessage>> {
+		let conv_msgs = ConvMsgBmc::list_for_run(self.mm(), run_id)?;
+		Ok(ConvMessage::list_from_conv_msgs(conv_msgs))
+	}
+
+	/// Append the messages to the run conversation
+	pub fn add_conv_messages(&self, run_id: Id, task_id: Option<Id>, messages: &[ConvMessage]) -> Result<()> {
+		for msg in messages {
+			let conv_msg_c = ConvMsgForCreate {
+				run_id,
+				task_id,
+				role: msg.role.clone(),
+				content: msg.content.clone(),
+			};
+			ConvMsgBmc::create(self.mm(), conv_msg_c)?;
+		}
+		Ok(())
+	}
+}
@@ -1,5 +1,5 @@
 use crate::Result;
-use crate::model::{EndState, Id, LogKind, ModelManager, RunBmc, RunForUpdate, RunStep, TaskBmc, TaskForUpdate};
+use crate::model::{EndState, Id, LogKind, ModelManager, Run, RunBmc, RunForUpdate, RunStep, TaskBmc, TaskForUpdate};
 use crate::runtime::{RtLog, Runtime};
 use crate::support::time::now_micro;
 use derive_more::From;
@@ -22,6 +22,13 @@ impl<'a> RtStep<'a> {
 	fn rt_log(&self) -> RtLog<'_> {
 		RtLog::new(self.runtime)
 	}
+
+	/// Clear the `aip.bus` run scope (when the root run ends, the sub agent runs share it)
+	fn clear_run_bus(&self, run: &Run) {
+		if run.parent_id.is_none() {
+			self.runtime.message_bus().clear_run(run.uid);
+		}
+	}
 }
 
 /// Run Steps
@@ -349,6 +356,30 @@ impl<'a> RtStep<'a> {
 		Ok(())
 	}
 
+	/// Mark the task as canceled (e.g., the TUI selected tasks cancel)
+	pub async fn step_task_end_canceled(&self, run_id: Id, task_id: Id) -> Result<()> {
+		// -- Update Task State
+		let task_u = TaskForUpdate {
+			end: Some(now_micro().into()),
+			end_state: Some(EndState::Cancel),
+			..Default::default()
+		};
+		TaskBmc::update(self.mm(), task_id, task_u)?;
+
+		// -- Add log line
+		self.rt_log()
+			.rec_log_no_msg(
+				run_id,
+				Some(task_id),
+				Some(RunStep::TaskEnd),
+				None,
+				Some(LogKind::RunStep),
+			)
+			.await?;
+
+		Ok(())
+	}
+
 	/// Note will update
 	pub async fn step_task_end_err(&self, run_id: Id, task_id: Id, err: &crate::Error) -> Result<()> {
 		let mm = self.mm();
@@ -433,6 +464,9 @@ impl<'a> RtStep<'a> {
 		let run_u = get_run_u_for_end(mm, run_id, end_state)?;
 		RunBmc::update(self.mm(), run_id, run_u)?;
 
+		// -- Clear the run bus topics
+		self.clear_run_bus(&run);
+
 		// -- Add log line
 		self.rt_log()
 			.rec_log_no_msg(run_id, None, Some(RunStep::End), None, Some(LogKind::RunStep))
@@ -461,6 +495,9 @@ impl<'a> RtStep<'a> {
 		// -- Update the tasks that are not ended
 		TaskBmc::cancel_all_not_ended_for_run(mm, run_id)?;
 
+		// -- Clear the run bus topics
+		self.clear_run_bus(&run);
+
 		// -- Add log line
 		self.rt_log()
 			.rec_log_no_msg(run_id, None, Some(RunStep::End), None, Some(LogKind::RunStep))
@@ -492,6 +529,9 @@ impl<'a> RtStep<'a> {
 		// -- Now update all the tasks of the run
 		TaskBmc::cancel_all_not_ended_for_run(mm, run_id)?;
 
+		// -- Clear the run bus topics
+		self.clear_run_bus(&run);
+
 		// -- Add log line
 		self.rt_log()
 			.rec_log_no_msg(run_id, None, Some(RunStep::End), None, Some(LogKind::RunStep))
@@ -1,13 +1,13 @@
 use crate::Result;
 use crate::dir_context::DirContext;
-use crate::event::{CancelRx, CancelTrx, CancelTx};
+use crate::event::{CancelRx, CancelTx, PauseRx};
 use crate::exec::ExecutorTx;
 use crate::hub::get_hub;
-use crate::model::{ModelManager, RuntimeCtx};
-use crate::run::{Literals, new_genai_client};
+use crate::model::{Id, ModelManager, RuntimeCtx};
+use crate::run::{Literals, RunCtrl, RunTrx, TaskScheduler, WorkerPool, new_genai_client};
 use crate::runtime::queue::{RunEvent, RunQueue};
 use crate::runtime::runtime_inner::RuntimeInner;
-use crate::runtime::support::FileWriteManager;
+use crate::runtime::support::{FileWriteManager, MessageBus};
 use crate::runtime::{RtLog, RtModel, RtStep};
 use crate::script::LuaEngine;
 use genai::Client;
@@ -31,7 +31,8 @@ impl Runtime {
 		dir_context: DirContext,
 		executor_tx: ExecutorTx,
 		mm: ModelManager,
-		cancel_trx: Option<CancelTrx>,
+		run_ctrl: Option<RunCtrl>,
+		worker_pool: Option<WorkerPool>,
 	) -> Result<Self> {
 		// Note: Make the type explicit for clarity
 		let genai_client = new_genai_client()?;
@@ -48,7 +49,9 @@ impl Runtime {
 			session: Session::new(),
 			mm,
 			file_write_manager: FileWriteManager::new().into(),
-			cancel_trx,
+			message_bus: MessageBus::new().into(),
+			run_ctrl,
+			worker_pool,
 		};
 
 		let runtime = Self { inner: Arc::new(inner) };
@@ -132,16 +135,51 @@ impl Runtime {
 	}
 
 	pub fn cancel_tx(&self) -> Option<&CancelTx> {
-		self.inner.cancel_trx.as_ref().map(|trx| trx.tx())
+		self.inner.run_ctrl.as_ref().map(|ctrl| ctrl.cancel_trx().tx())
 	}
 
 	pub fn cancel_rx(&self) -> Option<&CancelRx> {
-		self.inner.cancel_trx.as_ref().map(|trx| trx.rx())
+		self.inner.run_ctrl.as_ref().map(|ctrl| ctrl.cancel_trx().rx())
+	}
+
+	pub fn pause_rx(&self) -> Option<&PauseRx> {
+		self.inner.run_ctrl.as_ref().map(|ctrl| ctrl.pause_trx().rx())
+	}
+
+	pub fn task_scheduler(&self) -> Option<&TaskScheduler> {
+		self.inner.run_ctrl.as_ref().map(|ctrl| ctrl.task_scheduler())
+	}
+
+	/// The controls of this run only (see `RunCtrl::run_trx`)
+	pub fn run_trx(&self, run_uid: Uuid) -> Option<RunTrx> {
+		self.inner.run_ctrl.as_ref().map(|ctrl| ctrl.run_trx(run_uid))
+	}
+
+	pub fn remove_run_trx(&self, run_uid: Uuid) {
+		if let Some(run_ctrl) = self.inner.run_ctrl.as_ref() {
+			run_ctrl.remove_run_trx(run_uid);
+		}
+	}
+
+	/// Resolves when the task is canceled (never without run controls).
+	pub async fn task_cancelled(&self, task_id: Id) {
+		match self.inner.run_ctrl.as_ref() {
+			Some(run_ctrl) => run_ctrl.task_cancelled(task_id).await,
+			None => std::future::pending().await,
+		}
 	}
 
 	pub fn file_write_manager(&self) -> &FileWriteManager {
 		self.inner.file_write_manager()
 	}
+
+	pub fn message_bus(&self) -> &MessageBus {
+		self.inner.message_bus()
+	}
+
+	pub fn worker_pool(&self) -> Option<&WorkerPool> {
+		self.inner.worker_pool.as_ref()
+	}
 }
 
 // region:    --- Session
@@ -236,7 +274,7 @@ mod tests_support {
 			});
 			let mm = ModelManager::new().await?;
 
-			Self::new(dir_context, exec_sender, mm, None).await
+			Self::new(dir_context, exec_sender, mm, None, None).await
 		}
 	}
 }
@@ -1,10 +1,10 @@
 use crate::dir_context::DirContext;
-use crate::event::CancelTrx;
 use crate::exec::ExecutorTx;
 use crate::model::ModelManager;
+use crate::run::{RunCtrl, WorkerPool};
 use crate::runtime::Session;
 use crate::runtime::queue::RunTx;
-use crate::runtime::support::FileWriteManager;
+use crate::runtime::support::{FileWriteManager, MessageBus};
 use genai::Client;
 use std::sync::Arc;
 
@@ -17,8 +17,12 @@ pub struct RuntimeInner {
 	pub(super) run_tx: RunTx,
 	pub(super) mm: ModelManager,
 	pub(super) file_write_manager: Arc<FileWriteManager>,
+	/// The `aip.bus` topics (shared by the runs of this process)
+	pub(super) message_bus: Arc<MessageBus>,
 
-	pub(super) cancel_trx: Option<CancelTrx>,
+	pub(super) run_ctrl: Option<RunCtrl>,
+	/// The coordinator workers (distributed mode, `aip run ... --workers-listen <addr>`)
+	pub(super) worker_pool: Option<WorkerPool>,
 }
 
 /// Getters
@@ -46,4 +50,8 @@ impl RuntimeInner {
 	pub fn file_write_manager(&self) -> &FileWriteManager {
 		&self.file_write_manager
 	}
+
+	pub fn message_bus(&self) -> &MessageBus {
+		&self.message_bus
+	}
 }
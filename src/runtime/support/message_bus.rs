@@ -0,0 +1,247 @@
+use crate::support::time::now_micro;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// The max messages kept per topic (the oldest are dropped)
+const MAX_TOPIC_MESSAGES: usize = 1000;
+
+/// Shared process-level publish/subscribe bus of `aip.bus`.
+///
+/// The topics are scoped to a root run (shared by its sub agent runs) or global (shared by all the runs of the process,
+/// e.g., with `aip serve` or `aip schedule --daemon`).
+/// Each subscriber has its own cursor per topic, so the subscribers sharing a name share the messages (queue semantics).
+#[derive(Debug, Default)]
+pub struct MessageBus {
+	inner: Mutex<BusInner>,
+	notify: Notify,
+}
+
+#[derive(Debug, Default)]
+struct BusInner {
+	last_seq: i64,
+	topics: HashMap<(BusScope, String), VecDeque<BusMessage>>,
+	/// The last seq polled, by (scope, topic, subscriber)
+	cursors: HashMap<(BusScope, String, String), i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BusScope {
+	/// The root run uid
+	Run(Uuid),
+	Global,
+}
+
+#[derive(Debug, Clone)]
+pub struct BusMessage {
+	/// The bus sequence number (increasing across topics)
+	pub seq: i64,
+	pub topic: String,
+	pub data: Value,
+	pub run_uid: Option<Uuid>,
+	pub task_uid: Option<Uuid>,
+	/// The publish time (epoch micro)
+	pub time: i64,
+}
+
+/// The poll query of a subscriber
+#[derive(Debug, Clone)]
+pub struct BusPoll<'a> {
+	pub scope: BusScope,
+	pub topic: &'a str,
+	pub subscriber: &'a str,
+	/// When set, returns the messages after this seq (rather than after the subscriber cursor)
+	pub since: Option<i64>,
+	pub limit: Option<usize>,
+}
+
+impl MessageBus {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Publish the message and wake up the waiting pollers. Returns the message seq.
+	pub fn publish(
+		&self,
+		scope: BusScope,
+		topic: &str,
+		data: Value,
+		run_uid: Option<Uuid>,
+		task_uid: Option<Uuid>,
+	) -> i64 {
+		let mut inner = self.lock();
+		inner.last_seq += 1;
+		let seq = inner.last_seq;
+
+		let messages = inner.topics.entry((scope, topic.to_string())).or_default();
+		messages.push_back(BusMessage {
+			seq,
+			topic: topic.to_string(),
+			data,
+			run_uid,
+			task_uid,
+			time: now_micro(),
+		});
+		if messages.len() > MAX_TOPIC_MESSAGES {
+			messages.pop_front();
+		}
+		drop(inner);
+
+		self.notify.notify_waiters();
+		seq
+	}
+
+	/// Returns the messages after the subscriber cursor (or `since`), and moves the cursor after them.
+	pub fn poll(&self, query: &BusPoll) -> Vec<BusMessage> {
+		let mut inner = self.lock();
+		let cursor_key = (query.scope, query.topic.to_string(), query.subscriber.to_string());
+		let after = query.since.or_else(|| inner.cursors.get(&cursor_key).copied()).unwrap_or(0);
+
+		let messages: Vec<BusMessage> = inner
+			.topics
+			.get(&(query.scope, query.topic.to_string()))
+			.into_iter()
+			.flatten()
+			.filter(|msg| msg.seq > after)
+			.take(query.limit.unwrap_or(usize::MAX))
+			.cloned()
+			.collect();
+
+		if let Some(last) = messages.last() {
+			inner.cursors.insert(cursor_key, last.seq);
+		}
+
+		messages
+	}
+
+	/// Same as `poll`, but waits up to `timeout` for the first messages.
+	pub async fn poll_wait(&self, query: &BusPoll<'_>, timeout: Duration) -> Vec<BusMessage> {
+		let deadline = tokio::time::Instant::now() + timeout;
+		loop {
+			// NOTE: Enabled before the poll, so that a publish in between is not missed.
+			let notified = self.notify.notified();
+			tokio::pin!(notified);
+			notified.as_mut().enable();
+
+			let messages = self.poll(query);
+			if !messages.is_empty() || tokio::time::timeout_at(deadline, notified).await.is_err() {
+				return messages;
+			}
+		}
+	}
+
+	/// Remove the topics and cursors of the run scope (when the root run ends).
+	pub fn clear_run(&self, root_run_uid: Uuid) {
+		let scope = BusScope::Run(root_run_uid);
+		let mut inner = self.lock();
+		inner.topics.retain(|(topic_scope, _), _| *topic_scope != scope);
+		inner.cursors.retain(|(cursor_scope, _, _), _| *cursor_scope != scope);
+	}
+
+	fn lock(&self) -> MutexGuard<'_, BusInner> {
+		self.inner.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+	use std::sync::Arc;
+
+	fn query<'a>(scope: BusScope, topic: &'a str, subscriber: &'a str) -> BusPoll<'a> {
+		BusPoll {
+			scope,
+			topic,
+			subscriber,
+			since: None,
+			limit: None,
+		}
+	}
+
+	#[test]
+	fn test_runtime_message_bus_poll_cursors() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = MessageBus::new();
+		let run_scope = BusScope::Run(Uuid::now_v7());
+		bus.publish(run_scope, "jobs", json!({"n": 1}), None, None);
+		bus.publish(run_scope, "jobs", json!({"n": 2}), None, None);
+		bus.publish(run_scope, "other", json!("x"), None, None);
+		bus.publish(BusScope::Global, "jobs", json!({"n": 99}), None, None);
+
+		// -- Exec
+		let worker_a_first = bus.poll(&BusPoll {
+			limit: Some(1),
+			..query(run_scope, "jobs", "worker")
+		});
+		let worker_b_next = bus.poll(&query(run_scope, "jobs", "worker"));
+		let worker_empty = bus.poll(&query(run_scope, "jobs", "worker"));
+		let coordinator = bus.poll(&query(run_scope, "jobs", "coordinator"));
+		let global = bus.poll(&query(BusScope::Global, "jobs", "worker"));
+
+		// -- Check
+		assert_eq!(worker_a_first.len(), 1);
+		assert_eq!(worker_a_first[0].data, json!({"n": 1}));
+		assert_eq!(worker_b_next.len(), 1);
+		assert_eq!(worker_b_next[0].data, json!({"n": 2}));
+		assert!(worker_empty.is_empty());
+		assert_eq!(coordinator.len(), 2);
+		assert_eq!(global.len(), 1);
+		assert_eq!(global[0].data, json!({"n": 99}));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_runtime_message_bus_clear_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = MessageBus::new();
+		let run_uid = Uuid::now_v7();
+		bus.publish(BusScope::Run(run_uid), "jobs", json!(1), None, None);
+		bus.publish(BusScope::Global, "jobs", json!(2), None, None);
+
+		// -- Exec
+		bus.clear_run(run_uid);
+
+		// -- Check
+		assert!(bus.poll(&query(BusScope::Run(run_uid), "jobs", "s")).is_empty());
+		assert_eq!(bus.poll(&query(BusScope::Global, "jobs", "s")).len(), 1);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_runtime_message_bus_poll_wait() -> Result<()> {
+		// -- Setup & Fixtures
+		let bus = Arc::new(MessageBus::new());
+		let publisher = bus.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(Duration::from_millis(50)).await;
+			publisher.publish(BusScope::Global, "done", json!(true), None, None);
+		});
+
+		// -- Exec
+		let timed_out = bus
+			.poll_wait(&query(BusScope::Global, "nothing", "s"), Duration::from_millis(10))
+			.await;
+		let messages = bus
+			.poll_wait(&query(BusScope::Global, "done", "s"), Duration::from_secs(5))
+			.await;
+
+		// -- Check
+		assert!(timed_out.is_empty());
+		assert_eq!(messages.len(), 1);
+		assert_eq!(messages[0].data, json!(true));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
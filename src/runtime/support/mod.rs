@@ -1,3 +1,5 @@
 mod file_write_manager;
+mod message_bus;
 
 pub use file_write_manager::*;
+pub use message_bus::*;
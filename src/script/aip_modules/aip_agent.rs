@@ -12,17 +12,24 @@
 //! ### Functions
 //!
 //! - `aip.agent.run(agent_name: string, options?: table): any`
+//! - `aip.agent.run_parallel(specs: (string | table)[], options?: table): any[]`
 //! - `aip.agent.extract_options(value: any): table | nil`
 
 use crate::event::new_one_shot_channel;
-use crate::model::RuntimeCtx;
+use crate::model::base::DbBmc as _;
+use crate::model::{RunBmc, RuntimeCtx};
 use crate::run::RunSubAgentParams;
 use crate::runtime::Runtime;
 use crate::script::LuaValueExt;
-use crate::types::{RunAgentOptions, RunAgentResponse};
+use crate::types::{PackCapabilities, RunAgentOptions, RunAgentResponse};
 use crate::{Error, Result};
-use mlua::{IntoLua, Lua, Table, Value};
+use mlua::{FromLua as _, IntoLua, Lua, Table, Value};
 use simple_fs::SPath;
+use tokio::task::JoinSet;
+
+/// The `run_parallel` concurrency when neither the `concurrency` option nor the calling run concurrency is known
+/// (same as the agent `input_concurrency` default)
+const DEFAULT_RUN_PARALLEL_CONCURRENCY: usize = 1;
 
 pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
@@ -35,9 +42,16 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 		},
 	)?;
 
+	let rt = runtime.clone();
+	let agent_run_parallel = lua.create_async_function(move |lua, (specs, options): (Table, Option<Table>)| {
+		let rt = rt.clone();
+		async move { aip_agent_run_parallel(&lua, &rt, specs, options).await }
+	})?;
+
 	let extract_options = lua.create_function(move |lua, value: Value| aip_agent_extract_options(lua, value))?;
 
 	table.set("run", agent_run)?;
+	table.set("run_parallel", agent_run_parallel)?;
 	table.set("extract_options", extract_options)?;
 
 	Ok(table)
@@ -55,6 +69,10 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 /// Executes the agent specified by `agent_name`. The function waits for the called agent
 /// to complete and returns its result. This allows for chaining agents together.
 ///
+/// In the TUI runs navigation, the sub agent run is nested under the calling run, grouped by the
+/// calling task (e.g., `Task-2`), and its cost is rolled up into the calling run cost.
+/// The `a`/`d` keys (or a click on the `▸`/`▾` arrow) collapse/expand the sub agent runs.
+///
 /// ### Arguments
 ///
 /// - `agent_name: string`: The name of the agent to run. This can be a relative path
@@ -134,16 +152,19 @@ pub async fn aip_agent_run(
 	let parent_uid = rt_ctx
 		.run_uid()
 		.ok_or(Error::custom("Cannot call agent, no parent run uid found"))?;
+	let parent_task_uid = rt_ctx.task_uid();
 
 	let run_agent_params = RunSubAgentParams::new(
 		//
 		runtime.clone(),
 		parent_uid,
+		parent_task_uid,
 		parent_agent_dir,
 		agent_name,
 		run_options,
 		Some(tx),
-	)?;
+	)?
+	.with_parent_capabilities(lua.app_data_ref::<PackCapabilities>().map(|c| c.clone()));
 
 	// Send asynchronously
 	runtime.executor_sender().send(run_agent_params.into()).await;
@@ -155,6 +176,170 @@ pub async fn aip_agent_run(
 	run_agent_response.into_lua(lua)
 }
 
+/// ## Lua Documentation
+///
+/// Runs multiple agents concurrently and returns their responses in order.
+///
+/// ```lua
+/// -- API Signature
+/// aip.agent.run_parallel(specs: (string | AgentRunSpec)[], options?: {concurrency?: number}): RunAgentResponse[]
+/// ```
+///
+/// The sub agent runs are dispatched to the run queue, at most `concurrency` at a time, so they execute concurrently.
+/// The function waits for all of them to complete, and each one is shown as a child run of the
+/// current run (under the calling task) in the TUI.
+///
+/// ### Arguments
+///
+/// - `specs: (string | AgentRunSpec)[]`: The list of agents to run. Each item is either the agent name,
+///   or a table with the agent `name` and the same params as `aip.agent.run(agent_name, params)`.
+///   ```ts
+///   type AgentRunSpec = {
+///     name: string,            // The agent name (same as `aip.agent.run` agent_name)
+///     input?: any,
+///     inputs?: any[],
+///     options?: table,
+///     agent_base_dir?: string
+///   }
+///   ```
+/// - `options?: table`:
+///   - `concurrency?: number`: The maximum number of sub agent runs at a time
+///     (defaults to the `input_concurrency` of the calling agent, which defaults to 1).
+///
+/// ### Example
+///
+/// ```lua
+/// local responses = aip.agent.run_parallel({
+///   { name = "agent-review", inputs = { "src/main.rs" } },
+///   { name = "agent-review", inputs = { "src/lib.rs" } },
+///   "agent-summary"
+/// }, { concurrency = 2 })
+/// -- responses[1].outputs, responses[2].outputs, responses[3].outputs
+/// ```
+///
+/// ### Returns
+///
+/// A list of `RunAgentResponse` (see `aip.agent.run`), in the same order as the `specs`.
+///
+/// ### Error
+///
+/// Returns an error if a spec or the `concurrency` is invalid, or if any of the agent runs fails (after all runs completed).
+/// The error message includes the position (1-based) and name of the failing spec.
+pub async fn aip_agent_run_parallel(
+	lua: &Lua,
+	runtime: &Runtime,
+	specs: Table,
+	options: Option<Table>,
+) -> mlua::Result<Value> {
+	let parent_agent_dir = get_agent_dir_from_lua(lua);
+
+	let rt_ctx = RuntimeCtx::extract_from_global(lua)?;
+	let parent_uid = rt_ctx
+		.run_uid()
+		.ok_or(Error::custom("Cannot call agent, no parent run uid found"))?;
+	let parent_task_uid = rt_ctx.task_uid();
+
+	// -- Parse all of the specs first (so that we do not start any run if one spec is invalid)
+	let mut agent_specs: Vec<(String, RunAgentOptions)> = Vec::new();
+	for (idx, spec) in specs.sequence_values::<Value>().enumerate() {
+		let spec = spec?;
+		let agent_spec = match spec {
+			Value::String(name) => (name.to_string_lossy(), RunAgentOptions::default()),
+			Value::Table(ref table) => {
+				let name = table.x_get_string("name").ok_or_else(|| {
+					Error::custom(format!(
+						"aip.agent.run_parallel - spec #{} must have a 'name' property",
+						idx + 1
+					))
+				})?;
+				(name, RunAgentOptions::from_lua(spec, lua)?)
+			}
+			other => {
+				return Err(Error::custom(format!(
+					"aip.agent.run_parallel - spec #{} must be a string or a table, but was a {}",
+					idx + 1,
+					other.type_name()
+				))
+				.into());
+			}
+		};
+		agent_specs.push(agent_spec);
+	}
+
+	// -- The concurrency (the option, otherwise the one of the calling run, its `input_concurrency`)
+	let concurrency = match options.as_ref().and_then(|options| options.x_get_i64("concurrency")) {
+		Some(concurrency) if concurrency > 0 => concurrency as usize,
+		Some(concurrency) => {
+			return Err(Error::custom(format!(
+				"aip.agent.run_parallel - options.concurrency must be greater than 0, but was {concurrency}"
+			))
+			.into());
+		}
+		None => RunBmc::get_id_for_uid(runtime.mm(), parent_uid)
+			.and_then(|run_id| RunBmc::get(runtime.mm(), run_id))
+			.ok()
+			.and_then(|run| run.concurrency)
+			.map(|concurrency| concurrency.max(1) as usize)
+			.unwrap_or(DEFAULT_RUN_PARALLEL_CONCURRENCY),
+	};
+
+	// -- Dispatch the sub agent runs (up to the concurrency limit)
+	let mut results: Vec<Option<(String, Result<RunAgentResponse>)>> = agent_specs.iter().map(|_| None).collect();
+	let mut join_set = JoinSet::new();
+	for (idx, (agent_name, run_options)) in agent_specs.into_iter().enumerate() {
+		// -- Wait for a free slot
+		while join_set.len() >= concurrency
+			&& let Some(res) = join_set.join_next().await
+		{
+			let (idx, agent_name, res) =
+				res.map_err(|err| Error::custom(format!("Error while running agent. Cause {err}")))?;
+			results[idx] = Some((agent_name, res));
+		}
+
+		let (tx, rx) = new_one_shot_channel::<Result<RunAgentResponse>>("agent-run-parallel");
+		let run_agent_params = RunSubAgentParams::new(
+			runtime.clone(),
+			parent_uid,
+			parent_task_uid,
+			parent_agent_dir.clone(),
+			agent_name.clone(),
+			run_options,
+			Some(tx),
+		)?
+		.with_parent_capabilities(lua.app_data_ref::<PackCapabilities>().map(|c| c.clone()));
+		runtime.executor_sender().send(run_agent_params.into()).await;
+
+		join_set.spawn(async move {
+			let res = rx
+				.recv()
+				.await
+				.map_err(|err| Error::custom(format!("rx.recv_async fail. Cause: {err}")))
+				.and_then(|res| res);
+			(idx, agent_name, res)
+		});
+	}
+
+	// -- Wait for the remaining ones
+	while let Some(res) = join_set.join_next().await {
+		let (idx, agent_name, res) =
+			res.map_err(|err| Error::custom(format!("Error while running agent. Cause {err}")))?;
+		results[idx] = Some((agent_name, res));
+	}
+
+	let list = lua.create_table()?;
+	for (idx, (agent_name, res)) in results.into_iter().flatten().enumerate() {
+		let response = res.map_err(|err| {
+			Error::custom(format!(
+				"aip.agent.run_parallel - spec #{} ('{agent_name}') failed. Cause: {err}",
+				idx + 1
+			))
+		})?;
+		list.push(response.into_lua(lua)?)?;
+	}
+
+	Ok(Value::Table(list))
+}
+
 /// ## Lua Documentation
 ///
 /// Extracts relevant agent options from a given Lua value.
@@ -230,9 +415,16 @@ fn get_agent_dir_from_lua(lua: &Lua) -> Option<SPath> {
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
-	use crate::_test_support::{assert_contains, eval_lua, run_reflective_agent, run_test_agent, setup_lua};
+	use crate::_test_support::{
+		assert_contains, clean_sanbox_01_tmp_file, eval_lua, run_reflective_agent, run_reflective_agent_with_options,
+		run_reflective_agent_with_runtime, run_test_agent, setup_lua,
+	};
 	use crate::agent::Agent;
+	use crate::model::RunBmc;
+	use crate::run::RunBaseOptions;
 	use crate::runtime::Runtime;
+	use crate::types::{PackCapabilities, PackCapability};
+	use simple_fs::SPath;
 	use value_ext::JsonValueExt;
 
 	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -256,6 +448,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parent_task() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let script = r#"
+            return aip.agent.run("agent-script/agent-hello-world")
+        "#;
+
+		// -- Exec
+		run_reflective_agent_with_runtime(script, None, runtime.clone()).await?;
+
+		// -- Check
+		// NOTE: The reflective agent run and task have the id 0
+		let sub_runs = RunBmc::list_for_parent(runtime.mm(), 0.into())?;
+		let sub_run = sub_runs.first().ok_or("Should have a sub run")?;
+		assert_eq!(sub_run.parent_task_id, Some(0.into()));
+
+		Ok(())
+	}
+
 	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 	async fn test_script_aip_agent_run_relative() -> Result<()> {
 		// -- Setup & Fixtures
@@ -325,6 +537,133 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parallel_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let script = r#"
+            local responses = aip.agent.run_parallel({
+              { name = "agent-script/agent-hello", inputs = {"John"} },
+              { name = "agent-script/agent-hello", input = "Jen" },
+              "agent-script/agent-hello-world"
+            })
+            return responses
+        "#;
+
+		// -- Exec
+		let res = run_reflective_agent(script, None).await?;
+
+		// -- Check
+		let responses = res.as_array().ok_or("Should be an array")?;
+		assert_eq!(responses.len(), 3);
+		assert_contains(res.x_get_str("/0/outputs/0")?, "Hello 'John' from agent-hello.aip");
+		assert_contains(res.x_get_str("/1/outputs/0")?, "Hello 'Jen' from agent-hello.aip");
+		assert_contains(res.x_get_str("/2/outputs/0")?, "Hello Wonderful World");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parallel_err_missing_name() -> Result<()> {
+		// -- Setup & Fixtures
+		let script = r#"
+            return aip.agent.run_parallel({ { inputs = {"John"} } })
+        "#;
+
+		// -- Exec
+		let res = run_reflective_agent(script, None).await;
+
+		// -- Check
+		let err = res.err().ok_or("Should have failed")?;
+		assert_contains(&err.to_string(), "spec #1 must have a 'name' property");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parallel_concurrency_one() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let script = r#"
+            return aip.agent.run_parallel({
+              { name = "agent-script/agent-hello", inputs = {"John"} },
+              { name = "agent-script/agent-hello", input = "Jen" },
+              "agent-script/agent-hello-world"
+            }, { concurrency = 1 })
+        "#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(script, None, runtime.clone()).await?;
+
+		// -- Check
+		assert_contains(res.x_get_str("/1/outputs/0")?, "Hello 'Jen' from agent-hello.aip");
+		// NOTE: The reflective agent run has the id 0
+		let mut sub_runs = RunBmc::list_for_parent(runtime.mm(), 0.into())?;
+		sub_runs.sort_by_key(|run| run.id);
+		assert_eq!(sub_runs.len(), 3);
+		for pair in sub_runs.windows(2) {
+			let prev_end = pair[0].end.ok_or("Should have end")?;
+			let start = pair[1].start.ok_or("Should have start")?;
+			assert!(start >= prev_end, "Sub runs should not overlap with concurrency = 1");
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parallel_err_concurrency() -> Result<()> {
+		// -- Setup & Fixtures
+		let script = r#"
+            return aip.agent.run_parallel({ "agent-script/agent-hello-world" }, { concurrency = 0 })
+        "#;
+
+		// -- Exec
+		let res = run_reflective_agent(script, None).await;
+
+		// -- Check
+		let err = res.err().ok_or("Should have failed")?;
+		assert_contains(&err.to_string(), "options.concurrency must be greater than 0");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_agent_run_parent_capabilities_no_escalation() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let wks_dir = runtime.dir_context().wks_dir().cloned().ok_or("Should have wks dir")?;
+		// A pack without the `exec` capability
+		let fx_capabilities = PackCapabilities::new(
+			"acme@restricted",
+			vec![PackCapability::Net],
+			SPath::new("/tmp/.aipack-base/support/pack/acme/restricted"),
+		)
+		.with_paths(
+			SPath::new("/tmp/.aipack-base/pack/installed/acme/restricted"),
+			Some(wks_dir),
+			Vec::new(),
+			Vec::new(),
+		)?;
+		let run_base_options = RunBaseOptions::default().with_parent_capabilities(Some(fx_capabilities));
+		// The pack writes an agent in the workspace, and runs it
+		let script = r##"
+            local agent_path = ".tmp/test_script_aip_agent_run_parent_capabilities/escalate.aip"
+            aip.file.save(agent_path, "# Data\n```lua\nreturn aip.cmd.exec('echo', {'escalated'})\n```\n")
+            return aip.agent.run(CTX.WORKSPACE_DIR .. "/" .. agent_path)
+        "##;
+
+		// -- Exec
+		let res = run_reflective_agent_with_options(script, None, runtime, &run_base_options).await;
+
+		// -- Check
+		let err = res.err().ok_or("Sub agent should not be able to exec")?;
+		assert_contains(&err.to_string(), "requires the 'exec' capability");
+		clean_sanbox_01_tmp_file(SPath::new(
+			".tmp/test_script_aip_agent_run_parent_capabilities/escalate.aip",
+		))?;
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_script_aip_agent_extract_options_simple() -> Result<()> {
 		// -- Setup & Fixtures
@@ -0,0 +1,171 @@
+//! Defines the `aip.ai` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.ai` module exposes tolerant parsers for the model (AI) outputs, so that the output stages
+//! do not fail on cosmetic deviations (e.g., prose around the JSON, wrapping markdown fences, trailing commas).
+//!
+//! ### Functions
+//!
+//! - `aip.ai.parse_json(content: string | nil): any | nil`
+//! - `aip.ai.parse_list(content: string | nil): string[] | nil`
+//! - `aip.ai.strip_fences(content: string | nil): string | nil`
+
+use crate::Result;
+use crate::runtime::Runtime;
+use crate::script::serde_value_to_lua_value;
+use crate::support::ai_parse;
+use mlua::{Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let parse_json_fn = lua.create_function(move |lua, content: Option<String>| parse_json(lua, content))?;
+	let parse_list_fn = lua.create_function(move |_lua, content: Option<String>| parse_list(content))?;
+	let strip_fences_fn = lua.create_function(move |_lua, content: Option<String>| strip_fences(content))?;
+
+	table.set("parse_json", parse_json_fn)?;
+	table.set("parse_list", parse_list_fn)?;
+	table.set("strip_fences", strip_fences_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+/// ---
+/// Extract and parse the first JSON value of a model output.
+///
+/// ```lua
+/// -- API Signature
+/// aip.ai.parse_json(content: string | nil): any | nil
+/// ```
+///
+/// In order, it tries the whole content (without the wrapping markdown fence), the first fenced block,
+/// and the first `{...}` or `[...]` in the prose. The JSON is parsed leniently
+/// (comments, trailing commas, single quotes, and unquoted property names are accepted).
+///
+/// ### Arguments
+///
+/// - `content: string | nil` - The model output. If nil, returns nil.
+///
+/// ### Returns
+///
+/// - `any | nil` - The parsed value, or nil if no JSON value was found.
+///
+/// ### Example
+///
+/// ```lua
+/// local data = aip.ai.parse_json("Sure! Here it is:\n{'name': 'demo', tags: ['a', 'b',],}\nAnything else?")
+/// print(data.name)    -- prints "demo"
+/// print(data.tags[2]) -- prints "b"
+/// ```
+fn parse_json(lua: &Lua, content: Option<String>) -> mlua::Result<Value> {
+	let Some(content) = content else {
+		return Ok(Value::Nil);
+	};
+
+	match ai_parse::extract_json(&content) {
+		Some(json_value) => Ok(serde_value_to_lua_value(lua, json_value)?),
+		None => Ok(Value::Nil),
+	}
+}
+
+/// ## Lua Documentation
+/// ---
+/// Parse the numbered (e.g., `1.` or `1)`) or bulleted (`-`, `*`, `+`) list items of a model output.
+///
+/// ```lua
+/// -- API Signature
+/// aip.ai.parse_list(content: string | nil): string[] | nil
+/// ```
+///
+/// The more indented lines (e.g., sub items or wrapped text) are appended to their item (space separated),
+/// and the other lines (e.g., the intro prose) are ignored.
+///
+/// ### Arguments
+///
+/// - `content: string | nil` - The model output. If nil, returns nil.
+///
+/// ### Returns
+///
+/// - `string[] | nil` - The trimmed item texts (without the markers), empty if no list was found.
+///
+/// ### Example
+///
+/// ```lua
+/// local steps = aip.ai.parse_list("Here are the steps:\n1. Install\n2) Run\n   with --help")
+/// -- steps = { "Install", "Run with --help" }
+/// ```
+fn parse_list(content: Option<String>) -> mlua::Result<Option<Vec<String>>> {
+	Ok(content.map(|content| ai_parse::parse_list(&content)))
+}
+
+/// ## Lua Documentation
+/// ---
+/// Remove the markdown fence wrapping a whole model output.
+///
+/// ```lua
+/// -- API Signature
+/// aip.ai.strip_fences(content: string | nil): string | nil
+/// ```
+///
+/// The content is trimmed, and the closing fence is optional (e.g., for a truncated output).
+/// When the content does not start with a fence, the trimmed content is returned.
+///
+/// ### Arguments
+///
+/// - `content: string | nil` - The model output. If nil, returns nil.
+///
+/// ### Returns
+///
+/// - `string | nil` - The content without the wrapping fence.
+///
+/// ### Example
+///
+/// ```lua
+/// local code = aip.ai.strip_fences("```rust\nfn main() {}\n```")
+/// -- code = "fn main() {}"
+/// ```
+fn strip_fences(content: Option<String>) -> mlua::Result<Option<String>> {
+	Ok(content.map(|content| ai_parse::strip_fences(&content).to_string()))
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_ai;
+	use value_ext::JsonValueExt as _;
+
+	#[tokio::test]
+	async fn test_lua_ai_parse_json_list_strip_fences() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_ai::init_module, "ai").await?;
+		let fx_script = r###"
+local data = aip.ai.parse_json("Sure! Here it is:\n{'name': 'demo', tags: ['a', 'b',],}\nAnything else?")
+local list = aip.ai.parse_list("Steps:\n1. Install\n2) Run\n   with --help")
+local code = aip.ai.strip_fences("```rust\nfn main() {}\n```")
+local none = aip.ai.parse_json("No json")
+return { data = data, list = list, code = code, has_none = none == nil }
+		"###;
+
+		// -- Exec
+		let res = eval_lua(&lua, fx_script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/data/name")?, "demo");
+		assert_eq!(res.x_get_str("/data/tags/1")?, "b");
+		assert_eq!(res.x_get_str("/list/1")?, "Run with --help");
+		assert_eq!(res.x_get_str("code")?, "fn main() {}");
+		assert!(res.x_get_bool("has_none")?);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,472 @@
+//! Defines the `aip.api` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.api` module builds API clients from their OpenAPI specs (OpenAPI 3.x or Swagger 2.0, JSON or YAML),
+//! so that integration agents can call services without writing the requests of each endpoint.
+//!
+//! ### Functions
+//!
+//! - `aip.api.from_openapi(spec: string, options?: ApiClientOptions): ApiClient`
+//! - `ApiClient.<operation_id>(args?: table): WebResponse`
+
+use crate::dir_context::PathResolver;
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{check_access_read, check_pack_capability};
+use crate::script::lua_value_to_serde_value;
+use crate::support::cred::get_secret;
+use crate::support::openapi::{OpenApiOp, OpenApiSpec, ParamLocation};
+use crate::types::{PackCapability, WebOptions, WebResponse};
+use crate::{Error, Result};
+use mlua::{FromLua as _, IntoLua as _, Lua, Table, Value};
+use reqwest::{Client, Method, header};
+use simple_fs::SPath;
+use std::sync::Arc;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let from_openapi_fn = lua.create_function(move |lua, args| api_from_openapi(lua, &rt, args))?;
+
+	table.set("from_openapi", from_openapi_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Loads an OpenAPI spec (file or url), and returns a client with one function per operation.
+///
+/// ```lua
+/// -- API Signature
+/// aip.api.from_openapi(spec: string, options?: ApiClientOptions): ApiClient
+/// ```
+///
+/// ### Arguments
+///
+/// - `spec: string`: The spec file path (relative to the workspace), or its `http(s)://` url.
+/// - `options?: ApiClientOptions`: The `WebOptions` of the requests (e.g., `headers`, `timeout_ms`), plus:
+///
+/// ```ts
+/// {
+///   base_url?: string,  // Overrides the spec servers url (required when the spec has none)
+///   parse?: boolean,    // Parse the JSON responses (default true)
+///   auth?: {
+///     bearer?: string, bearer_env?: string,
+///     basic?: {username: string, password?: string, password_env?: string},
+///     api_key?: string, api_key_env?: string,
+///     api_key_name?: string,              // default the spec apiKey scheme name, or "X-API-Key"
+///     api_key_in?: "header" | "query",    // default the spec apiKey scheme location, or "header"
+///   }
+/// }
+/// ```
+///
+/// The `*_env` values are read from the environment variable, or the keychain (`aipack_secrets/<NAME>`),
+/// which requires the `secrets` capability.
+///
+/// ### Returns (ApiClient)
+///
+/// ```ts
+/// {
+///   title?: string,
+///   base_url?: string,
+///   operations: {id: string, method: string, path: string, summary?: string,
+///                params: {name: string, in: string, required: boolean, type?: string}[],
+///                body?: {required: boolean, content_type: string}}[],
+///   [operation_id]: function, // client.<operation_id>(args?): WebResponse
+/// }
+/// ```
+///
+/// Each operation function takes its parameters by name (path, query, header, and cookie),
+/// and the request body as `body`. The arguments are validated (unknown names, required, type, and enum)
+/// before the request is sent.
+///
+/// ### Example
+///
+/// ```lua
+/// local billing = aip.api.from_openapi("specs/billing.yaml", {
+///   auth = { bearer_env = "BILLING_TOKEN" },
+/// })
+/// local res = billing.getInvoice({ id = "inv_123", expand = "lines" })
+/// if res.success then
+///   print(res.content.total)
+/// end
+/// local res = billing.createRefund({ body = { invoice_id = "inv_123", amount = 500 } })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the spec cannot be loaded or parsed, or an auth `*_env` is not set.
+/// The operation functions return an error if the arguments are invalid, there is no base url,
+/// or the request fails (a non-2xx response is returned, with `success = false`).
+fn api_from_openapi(lua: &Lua, runtime: &Runtime, (spec, options): (String, Option<Value>)) -> mlua::Result<Value> {
+	let options = options.unwrap_or(Value::Nil);
+
+	// -- Load the spec
+	let is_url = spec.starts_with("http://") || spec.starts_with("https://");
+	let content = if is_url {
+		check_pack_capability(lua, PackCapability::Net, "aip.api.from_openapi")?;
+		fetch_spec(&spec)?
+	} else {
+		let full_path =
+			runtime
+				.dir_context()
+				.resolve_path(runtime.session(), SPath::new(&spec), PathResolver::WksDir, None)?;
+		check_access_read(lua, &full_path, "aip.api.from_openapi")?;
+		simple_fs::read_to_string(&full_path)
+			.map_err(|err| Error::cc(format!("aip.api.from_openapi - cannot read spec '{spec}'"), err))?
+	};
+	let openapi = OpenApiSpec::parse(&content)
+		.map_err(|err| Error::cc(format!("aip.api.from_openapi - invalid spec '{spec}'"), err))?;
+
+	// -- The client
+	let base_url = options
+		.x_get_string("base_url")
+		.or_else(|| openapi.resolve_base_url(is_url.then_some(spec.as_str())));
+	let auth = ApiAuth::from_options(lua, &options, &openapi)?;
+	let web_opts = WebOptions::from_lua(options, lua)?;
+	let parse = web_opts.parse.unwrap_or(true);
+	let http_client = web_opts
+		.apply_to_reqwest_builder(Client::builder())
+		.build()
+		.map_err(Error::from)?;
+	let api_client = Arc::new(ApiClient {
+		http_client,
+		base_url: base_url.clone(),
+		auth,
+		parse,
+	});
+
+	let client = lua.create_table()?;
+	if let Some(title) = openapi.title.as_deref() {
+		client.set("title", title)?;
+	}
+	if let Some(base_url) = base_url {
+		client.set("base_url", base_url)?;
+	}
+
+	let operations = lua.create_table()?;
+	for op in openapi.operations {
+		operations.push(op_info(lua, &op)?)?;
+		let op_id = op.id.clone();
+		let api_client = api_client.clone();
+		let op_fn = lua.create_function(move |lua, args: Option<Value>| api_client.call(lua, &op, args))?;
+		client.set(op_id, op_fn)?;
+	}
+	client.set("operations", operations)?;
+
+	Ok(Value::Table(client))
+}
+
+// region:    --- ApiClient
+
+struct ApiClient {
+	http_client: Client,
+	base_url: Option<String>,
+	auth: Option<ApiAuth>,
+	parse: bool,
+}
+
+impl ApiClient {
+	fn call(&self, lua: &Lua, op: &OpenApiOp, args: Option<Value>) -> mlua::Result<Value> {
+		let fn_name = format!("aip.api operation '{}'", op.id);
+		check_pack_capability(lua, PackCapability::Net, &fn_name)?;
+
+		// -- Build the request
+		let args = match lua_value_to_serde_value(args.unwrap_or(Value::Nil))? {
+			serde_json::Value::Object(args) => args,
+			serde_json::Value::Null => Default::default(),
+			serde_json::Value::Array(values) if values.is_empty() => Default::default(),
+			_ => {
+				return Err(Error::custom(format!("{fn_name} - the arguments must be a table of name/value")).into());
+			}
+		};
+		let req = op
+			.build_request(self.base_url.as_deref().unwrap_or_default(), &args)
+			.map_err(|err| Error::cc(&fn_name, err))?;
+		if self.base_url.is_none() {
+			return Err(Error::custom(format!(
+				"{fn_name} - the spec has no servers url, set the 'base_url' option of aip.api.from_openapi"
+			))
+			.into());
+		}
+
+		let method = Method::from_bytes(op.method.as_bytes())
+			.map_err(|err| Error::cc(format!("{fn_name} - invalid method"), err))?;
+		let mut url = url::Url::parse(&req.url)
+			.map_err(|err| Error::cc(format!("{fn_name} - invalid url '{}'", req.url), err))?;
+		let mut query = req.query;
+		if let Some(ApiAuth::ApiKey {
+			location: ParamLocation::Query,
+			name,
+			value,
+		}) = &self.auth
+		{
+			query.push((name.clone(), value.clone()));
+		}
+		if !query.is_empty() {
+			url.query_pairs_mut().extend_pairs(query);
+		}
+		let url = url.to_string();
+		let mut request_builder = self.http_client.request(method, &url);
+		for (name, value) in req.headers {
+			request_builder = request_builder.header(name, value);
+		}
+		request_builder = match &self.auth {
+			Some(ApiAuth::Bearer(token)) => request_builder.bearer_auth(token),
+			Some(ApiAuth::Basic { username, password }) => request_builder.basic_auth(username, password.as_ref()),
+			Some(ApiAuth::ApiKey {
+				location: ParamLocation::Query,
+				..
+			})
+			| None => request_builder,
+			Some(ApiAuth::ApiKey { name, value, .. }) => request_builder.header(name.as_str(), value),
+		};
+		if let Some(body) = req.body {
+			let content_type = op
+				.body
+				.as_ref()
+				.map(|body| body.content_type.as_str())
+				.unwrap_or("application/json");
+			request_builder = match body {
+				serde_json::Value::String(text) => {
+					request_builder.header(header::CONTENT_TYPE, content_type).body(text)
+				}
+				body if content_type == "application/x-www-form-urlencoded" => request_builder.form(&body),
+				body => request_builder
+					.header(header::CONTENT_TYPE, content_type)
+					.body(body.to_string()),
+			};
+		}
+
+		// -- Send
+		let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+		let web_res = tokio::task::block_in_place(|| {
+			rt.block_on(async {
+				let response = request_builder
+					.send()
+					.await
+					.map_err(|err| Error::custom(format!("{fn_name} failed for url: {url}\nCause: {err}")))?;
+				WebResponse::from_reqwest_response(response, Some(self.parse)).await
+			})
+		})?;
+
+		get_hub().publish_sync(format!("-> lua api::{} OK ({url}) ", op.id));
+
+		web_res.into_lua(lua)
+	}
+}
+
+enum ApiAuth {
+	Bearer(String),
+	Basic {
+		username: String,
+		password: Option<String>,
+	},
+	ApiKey {
+		location: ParamLocation,
+		name: String,
+		value: String,
+	},
+}
+
+impl ApiAuth {
+	fn from_options(lua: &Lua, options: &Value, openapi: &OpenApiSpec) -> Result<Option<Self>> {
+		let Some(auth) = options
+			.as_table()
+			.and_then(|opts| opts.get::<Option<Table>>("auth").ok().flatten())
+		else {
+			return Ok(None);
+		};
+		let auth = Value::Table(auth);
+
+		// -- Bearer
+		if let Some(token) = auth_value(lua, &auth, "bearer")? {
+			return Ok(Some(ApiAuth::Bearer(token)));
+		}
+
+		// -- Basic
+		if let Some(basic) = auth.as_table().and_then(|t| t.get::<Option<Table>>("basic").ok().flatten()) {
+			let basic = Value::Table(basic);
+			let username = basic
+				.x_get_string("username")
+				.ok_or_else(|| Error::custom("aip.api.from_openapi - auth.basic requires a 'username'"))?;
+			let password = auth_value(lua, &basic, "password")?;
+			return Ok(Some(ApiAuth::Basic { username, password }));
+		}
+
+		// -- Api Key
+		if let Some(value) = auth_value(lua, &auth, "api_key")? {
+			let (default_location, default_name) = openapi
+				.api_key_scheme
+				.clone()
+				.unwrap_or((ParamLocation::Header, "X-API-Key".to_string()));
+			let location = match auth.x_get_string("api_key_in").as_deref() {
+				None => default_location,
+				Some("header") => ParamLocation::Header,
+				Some("query") => ParamLocation::Query,
+				Some(other) => {
+					return Err(Error::custom(format!(
+						"aip.api.from_openapi - auth.api_key_in '{other}' not supported. Must be 'header' or 'query'"
+					)));
+				}
+			};
+			let name = auth.x_get_string("api_key_name").unwrap_or(default_name);
+			return Ok(Some(ApiAuth::ApiKey { location, name, value }));
+		}
+
+		Err(Error::custom(
+			"aip.api.from_openapi - auth must have 'bearer', 'basic', or 'api_key' (or their '_env' variants)",
+		))
+	}
+}
+
+/// Returns the `name` value, or the `name_env` value from the environment (or keychain).
+fn auth_value(lua: &Lua, table: &Value, name: &str) -> Result<Option<String>> {
+	if let Some(value) = table.x_get_string(name) {
+		return Ok(Some(value));
+	}
+	let Some(env_name) = table.x_get_string(&format!("{name}_env")) else {
+		return Ok(None);
+	};
+	check_pack_capability(lua, PackCapability::Secrets, "aip.api.from_openapi auth")?;
+	let value = std::env::var(&env_name)
+		.ok()
+		.filter(|value| !value.trim().is_empty())
+		.or_else(|| get_secret(&env_name))
+		.ok_or_else(|| {
+			Error::custom(format!(
+				"aip.api.from_openapi - auth '{name}_env' variable '{env_name}' is not set (environment variable, or keychain 'aipack_secrets/{env_name}')"
+			))
+		})?;
+	Ok(Some(value))
+}
+
+// endregion: --- ApiClient
+
+// region:    --- Support
+
+fn fetch_spec(url: &str) -> Result<String> {
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	tokio::task::block_in_place(|| {
+		rt.block_on(async {
+			let response = reqwest::get(url)
+				.await
+				.map_err(|err| Error::cc(format!("aip.api.from_openapi - cannot fetch spec '{url}'"), err))?;
+			let status = response.status();
+			if !status.is_success() {
+				return Err(Error::custom(format!(
+					"aip.api.from_openapi - cannot fetch spec '{url}' (status {status})"
+				)));
+			}
+			response
+				.text()
+				.await
+				.map_err(|err| Error::cc(format!("aip.api.from_openapi - cannot read spec '{url}'"), err))
+		})
+	})
+}
+
+fn op_info(lua: &Lua, op: &OpenApiOp) -> mlua::Result<Table> {
+	let info = lua.create_table()?;
+	info.set("id", op.id.as_str())?;
+	info.set("method", op.method.as_str())?;
+	info.set("path", op.path.as_str())?;
+	if let Some(summary) = op.summary.as_deref() {
+		info.set("summary", summary)?;
+	}
+	let params = lua.create_table()?;
+	for param in op.params.iter() {
+		let param_info = lua.create_table()?;
+		param_info.set("name", param.name.as_str())?;
+		param_info.set("in", param.location.as_str())?;
+		param_info.set("required", param.required)?;
+		if let Some(schema_type) = param.schema_type.as_deref() {
+			param_info.set("type", schema_type)?;
+		}
+		params.push(param_info)?;
+	}
+	info.set("params", params)?;
+	if let Some(body) = op.body.as_ref() {
+		let body_info = lua.create_table()?;
+		body_info.set("required", body.required)?;
+		body_info.set("content_type", body.content_type.as_str())?;
+		info.set("body", body_info)?;
+	}
+	Ok(info)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_api;
+	use simple_fs::SPath;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_api_from_openapi() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("tests-data/sandbox-01/.tmp/test_lua_api_from_openapi/todo-api.json");
+		simple_fs::ensure_file_dir(&path)?;
+		std::fs::write(
+			path.as_std_path(),
+			r#"{
+  "openapi": "3.0.0",
+  "info": { "title": "Todo API" },
+  "paths": {
+    "/todos/{id}": {
+      "get": {
+        "operationId": "getTodo",
+        "parameters": [{ "name": "id", "in": "path", "schema": { "type": "integer" } }]
+      }
+    }
+  }
+}"#,
+		)?;
+		let lua = setup_lua(aip_api::init_module, "api").await?;
+		let script = r#"
+local todo = aip.api.from_openapi(".tmp/test_lua_api_from_openapi/todo-api.json")
+local ok_missing, err_missing = pcall(todo.getTodo, {})
+local ok_base, err_base = pcall(todo.getTodo, {id = 1})
+return {
+	title = todo.title,
+	base_url = todo.base_url,
+	op = todo.operations[1],
+	err_missing = tostring(err_missing),
+	err_base = tostring(err_base),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["title"].as_str(), Some("Todo API"));
+		assert!(res.get("base_url").is_none());
+		assert_eq!(res["op"]["id"].as_str(), Some("getTodo"));
+		assert_eq!(res["op"]["method"].as_str(), Some("GET"));
+		assert_eq!(res["op"]["params"][0]["type"].as_str(), Some("integer"));
+		assert_contains(
+			res["err_missing"].as_str().ok_or("should have err")?,
+			"requires the path parameter 'id'",
+		);
+		assert_contains(
+			res["err_base"].as_str().ok_or("should have err")?,
+			"set the 'base_url' option",
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,230 @@
+//! Defines the `aip.bin` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.bin` module reads and inspects binary data (e.g., firmware images, file headers).
+//! The bytes are Lua strings (as in `aip.blob` and `aip.encode`).
+//!
+//! ### Functions
+//!
+//! - `aip.bin.read(path: string, options?: {offset?: number, len?: number}): string`
+//! - `aip.bin.hexdump(bytes: string, options?: {offset?: number, width?: number}): string`
+//! - `aip.bin.unpack(fmt: string, bytes: string, options?: {offset?: number}): any[]`
+
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::check_access_read;
+use crate::support::bin::{self, BinValue};
+use crate::{Error, Result};
+use mlua::{Lua, LuaString, Table, Value};
+use simple_fs::SPath;
+
+/// Default bytes per line of the hexdump
+const HEXDUMP_WIDTH: usize = 16;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let read_fn =
+		lua.create_function(move |lua, (path, options): (String, Option<Value>)| bin_read(lua, &rt, path, options))?;
+	let hexdump_fn = lua.create_function(bin_hexdump)?;
+	let unpack_fn = lua.create_function(bin_unpack)?;
+
+	table.set("read", read_fn)?;
+	table.set("hexdump", hexdump_fn)?;
+	table.set("unpack", unpack_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Reads the bytes of a file, or of a range of it.
+///
+/// ```lua
+/// -- API Signature
+/// aip.bin.read(path: string, options?: {offset?: number, len?: number}): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string`: The file path (relative to the workspace, or pack ref).
+/// - `options?: table`:
+///   - `offset?: number`: The start byte offset (default `0`).
+///   - `len?: number`: The max number of bytes (default: to the end of the file).
+///
+/// ### Returns
+///
+/// - `string`: The bytes (as a Lua string, fewer than `len` if the file ends before).
+///
+/// ### Example
+///
+/// ```lua
+/// local header = aip.bin.read("firmware/app.bin", { offset = 0, len = 64 })
+/// print(aip.bin.hexdump(header))
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the file cannot be read, or the offset or len is negative.
+fn bin_read(lua: &Lua, runtime: &Runtime, path: String, options: Option<Value>) -> mlua::Result<Value> {
+	let full_path = runtime.resolve_path_default(SPath::new(&path), None)?;
+	check_access_read(lua, &full_path, "aip.bin.read")?;
+
+	let offset = get_non_negative(&options, "offset", "aip.bin.read")?.unwrap_or(0);
+	let len = get_non_negative(&options, "len", "aip.bin.read")?;
+
+	let bytes = bin::read_file_range(&full_path, offset, len)
+		.map_err(|err| Error::custom(format!("aip.bin.read failed for '{path}'. {err}")))?;
+
+	Ok(Value::String(lua.create_string(&bytes)?))
+}
+
+/// ## Lua Documentation
+///
+/// Formats the bytes as a hexdump (as `hexdump -C`), with the offset, the hex bytes, and the printable ASCII chars.
+///
+/// ```lua
+/// -- API Signature
+/// aip.bin.hexdump(bytes: string, options?: {offset?: number, width?: number}): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `bytes: string`: The bytes (as a Lua string).
+/// - `options?: table`:
+///   - `offset?: number`: The offset of the first byte, for the displayed offsets (e.g., the `aip.bin.read` offset, default `0`).
+///   - `width?: number`: The bytes per line (default `16`).
+///
+/// ### Returns
+///
+/// - `string`: The hexdump lines, e.g., `00000000  7f 45 4c 46 02 01 01 00  00 00 00 00 00 00 00 00  |.ELF............|`
+///
+/// ### Example
+///
+/// ```lua
+/// local bytes = aip.bin.read("firmware/app.bin", { offset = 256, len = 32 })
+/// print(aip.bin.hexdump(bytes, { offset = 256 }))
+/// ```
+fn bin_hexdump(_lua: &Lua, (bytes, options): (LuaString, Option<Value>)) -> mlua::Result<String> {
+	let offset = get_non_negative(&options, "offset", "aip.bin.hexdump")?.unwrap_or(0);
+	let width = get_non_negative(&options, "width", "aip.bin.hexdump")?
+		.map(|w| w as usize)
+		.unwrap_or(HEXDUMP_WIDTH);
+
+	Ok(bin::hexdump(&bytes.as_bytes(), offset, width))
+}
+
+/// ## Lua Documentation
+///
+/// Unpacks the bytes with a struct format (the Python `struct` format, with the standard sizes and no alignment).
+///
+/// ```lua
+/// -- API Signature
+/// aip.bin.unpack(fmt: string, bytes: string, options?: {offset?: number}): any[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `fmt: string`: The struct format, e.g., `"<4sBBH"`.
+///   - Byte order (first char): `<` little-endian, `>` or `!` big-endian, `=` or `@` native (default).
+///   - Codes, with an optional count (e.g., `3H`): `x` pad byte (no value), `c` char, `b`/`B` int8/uint8, `?` bool,
+///     `h`/`H` int16/uint16, `i`/`I` and `l`/`L` int32/uint32, `q`/`Q` int64/uint64, `f` float32, `d` float64,
+///     `s` bytes (the count is the length, e.g., `4s`).
+///   - The whitespaces are ignored.
+/// - `bytes: string`: The bytes (as a Lua string).
+/// - `options?: table`:
+///   - `offset?: number`: The byte offset to start unpacking at (default `0`).
+///
+/// ### Returns
+///
+/// - `any[]`: The values, in the format order (numbers, booleans, and strings for `c` and `s`).
+///   The `Q` values above the Lua integer max are floats.
+///
+/// ### Example
+///
+/// ```lua
+/// local header = aip.bin.read("firmware/app.bin", { len = 16 })
+/// local vals = aip.bin.unpack("<4sBBBx", header)
+/// local magic, class, endian, version = vals[1], vals[2], vals[3], vals[4]
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the format is invalid, or the bytes are too short for it.
+fn bin_unpack(lua: &Lua, (fmt, bytes, options): (String, LuaString, Option<Value>)) -> mlua::Result<Value> {
+	let offset = get_non_negative(&options, "offset", "aip.bin.unpack")?.unwrap_or(0) as usize;
+	let bytes = bytes.as_bytes();
+	let bytes = bytes.get(offset..).unwrap_or_default();
+
+	let values = bin::unpack(&fmt, bytes).map_err(|err| Error::custom(format!("aip.bin.unpack - {err}")))?;
+
+	let table = lua.create_table()?;
+	for value in values {
+		let value = match value {
+			BinValue::Int(v) => Value::Integer(v),
+			BinValue::UInt(v) => match i64::try_from(v) {
+				Ok(v) => Value::Integer(v),
+				Err(_) => Value::Number(v as f64),
+			},
+			BinValue::Float(v) => Value::Number(v),
+			BinValue::Bool(v) => Value::Boolean(v),
+			BinValue::Bytes(v) => Value::String(lua.create_string(&v)?),
+		};
+		table.push(value)?;
+	}
+
+	Ok(Value::Table(table))
+}
+
+// region:    --- Support
+
+fn get_non_negative(options: &Option<Value>, key: &str, fn_name: &str) -> Result<Option<u64>> {
+	match options.x_get_i64(key) {
+		Some(v) if v < 0 => Err(Error::custom(format!(
+			"{fn_name} - '{key}' must be positive, but was {v}"
+		))),
+		Some(v) => Ok(Some(v as u64)),
+		None => Ok(None),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_bin;
+
+	#[tokio::test]
+	async fn test_lua_bin_read_unpack() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_bin::init_module, "bin").await?;
+		let script = r#"
+local bytes = aip.bin.read("./agent-script/agent-hello.aip", { offset = 2, len = 4 })
+local vals = aip.bin.unpack("<2sBx", "\x7fELF\x02", { offset = 1 })
+return { len = #bytes, s = vals[1], b = vals[2], n = #vals, dump = aip.bin.hexdump("AB") }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["len"].as_i64(), Some(4));
+		assert_eq!(res["s"].as_str(), Some("EL"));
+		assert_eq!(res["b"].as_i64(), Some(70));
+		assert_eq!(res["n"].as_i64(), Some(2));
+		assert!(res["dump"].as_str().ok_or("Should have dump")?.starts_with("00000000  41 42 "));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
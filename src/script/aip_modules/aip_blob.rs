@@ -0,0 +1,376 @@
+//! Defines the `aip.blob` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.blob` module exposes functions to read and write the objects of the S3 (`s3://bucket/key`)
+//! and Google Cloud Storage (`gs://bucket/key`) buckets.
+//!
+//! The credentials come from the environment variables, or from the `aipack_secrets` keychain
+//! (`aipack_secrets/<NAME>`, as the AI provider keys):
+//!
+//! - S3: `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and optional `AWS_SESSION_TOKEN`, `AWS_REGION` (default `us-east-1`),
+//!   `AWS_ENDPOINT_URL` (for the S3 compatible services, e.g., MinIO, R2)
+//! - GCS: `GCS_ACCESS_KEY_ID`, `GCS_SECRET_ACCESS_KEY` (the HMAC keys of the GCS interoperability API)
+//!
+//! All functions require the `net` pack capability.
+//!
+//! ### Functions
+//!
+//! - `aip.blob.get(url: string, options?: {dest?: string}): BlobObject`
+//! - `aip.blob.put(url: string, content: string | {path: string}, options?: {content_type?: string}): BlobPutResult`
+//! - `aip.blob.list(url_prefix: string, options?: {limit?: integer}): BlobItem[]`
+//! - `aip.blob.presign(url: string, options?: {method?: "GET" | "PUT", expires?: integer}): string`
+
+use crate::dir_context::PathResolver;
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{check_access_read, check_access_write, check_pack_capability};
+use crate::support::blob::{BlobClient, BlobUrl};
+use crate::types::PackCapability;
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+use reqwest::Method;
+use simple_fs::{SPath, ensure_file_dir};
+
+/// The default max number of objects returned by `aip.blob.list`
+const DEFAULT_LIST_LIMIT: i64 = 1000;
+
+/// The default validity of the presigned urls (1 hour)
+const DEFAULT_PRESIGN_EXPIRES_SECS: i64 = 3600;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let get_fn =
+		lua.create_function(move |lua, (url, options): (String, Option<Value>)| blob_get(lua, &rt, url, options))?;
+
+	let rt = runtime.clone();
+	let put_fn = lua.create_function(move |lua, (url, content, options): (String, Value, Option<Value>)| {
+		blob_put(lua, &rt, url, content, options)
+	})?;
+
+	let list_fn =
+		lua.create_function(move |lua, (url, options): (String, Option<Value>)| blob_list(lua, url, options))?;
+
+	let presign_fn =
+		lua.create_function(move |lua, (url, options): (String, Option<Value>)| blob_presign(lua, url, options))?;
+
+	table.set("get", get_fn)?;
+	table.set("put", put_fn)?;
+	table.set("list", list_fn)?;
+	table.set("presign", presign_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Downloads an object, as a string, or into a workspace file with `options.dest`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.blob.get(url: string, options?: {dest?: string}): BlobObject
+/// ```
+///
+/// ### Arguments
+///
+/// - `url: string` - The object url (e.g., `"s3://my-bucket/docs/report.md"`).
+/// - `options?: {dest?: string}` - With `dest`, the object is written to this file (relative to the workspace),
+///   and the `content` is not returned.
+///
+/// ### Returns
+///
+/// ```ts
+/// {
+///   url: string,
+///   size: integer,          // The object size in bytes
+///   content_type?: string,
+///   etag?: string,
+///   content?: string,       // The object content (when no dest)
+///   path?: string,          // The dest path (when dest)
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local obj = aip.blob.get("s3://my-bucket/docs/report.md")
+/// print(obj.content)
+///
+/// aip.blob.get("gs://my-bucket/images/logo.png", { dest = "assets/logo.png" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the url is invalid, the credentials are missing, the object does not exist,
+/// or the dest cannot be written.
+fn blob_get(lua: &Lua, runtime: &Runtime, url: String, options: Option<Value>) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.blob.get")?;
+	let blob_url: BlobUrl = url.parse()?;
+	let dest = options.unwrap_or(Value::Nil).x_get_string("dest");
+
+	// -- Check the dest before the download
+	let dest_path = match dest.as_deref() {
+		Some(dest) => {
+			let dir_context = runtime.dir_context();
+			let dest_path =
+				dir_context.resolve_path(runtime.session(), SPath::new(dest), PathResolver::WksDir, None)?;
+			let wks_dir =
+				dir_context.try_wks_dir_with_err_ctx("aip.blob.get with dest requires a aipack workspace setup")?;
+			check_access_write(lua, &dest_path, wks_dir)?;
+			Some(dest_path)
+		}
+		None => None,
+	};
+
+	let client = BlobClient::new(blob_url.provider)?;
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let obj = tokio::task::block_in_place(|| rt.block_on(async { client.get(&blob_url).await }))?;
+
+	let res = lua.create_table()?;
+	res.set("url", blob_url.to_string())?;
+	res.set("size", obj.content.len())?;
+	res.set("content_type", obj.content_type)?;
+	res.set("etag", obj.etag)?;
+
+	match (dest, dest_path) {
+		(Some(dest), Some(dest_path)) => {
+			ensure_file_dir(&dest_path).map_err(Error::from)?;
+			std::fs::write(&dest_path, &obj.content)
+				.map_err(|err| Error::custom(format!("aip.blob.get cannot write '{dest}'. Cause: {err}")))?;
+			res.set("path", dest)?;
+		}
+		_ => res.set("content", lua.create_string(&obj.content)?)?,
+	}
+
+	get_hub().publish_sync(format!("-> lua blob::get OK ({blob_url}) "));
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Uploads an object, from a string, or from a workspace file with `{path = "..."}`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.blob.put(url: string, content: string | {path: string}, options?: {content_type?: string}): BlobPutResult
+/// ```
+///
+/// ### Arguments
+///
+/// - `url: string` - The object url (e.g., `"s3://my-bucket/docs/report.md"`).
+/// - `content: string | {path: string}` - The object content, or the file to upload.
+/// - `options?: {content_type?: string}` - The `Content-Type` of the object (default set by the provider).
+///
+/// ### Returns
+///
+/// ```ts
+/// {
+///   url: string,
+///   size: integer,   // The uploaded bytes
+///   etag?: string,
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// aip.blob.put("s3://my-bucket/reports/summary.md", summary, { content_type = "text/markdown" })
+/// aip.blob.put("gs://my-bucket/images/logo.png", { path = "assets/logo.png" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the url is invalid, the credentials are missing, the file cannot be read,
+/// or the upload fails.
+fn blob_put(lua: &Lua, runtime: &Runtime, url: String, content: Value, options: Option<Value>) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.blob.put")?;
+	let blob_url: BlobUrl = url.parse()?;
+	let content_type = options.unwrap_or(Value::Nil).x_get_string("content_type");
+
+	let content: Vec<u8> = match content {
+		Value::String(content) => content.as_bytes().to_vec(),
+		Value::Table(_) => {
+			let Some(path) = content.x_get_string("path") else {
+				return Err(Error::custom(
+					"aip.blob.put - the content table must have a 'path' (e.g., {path = \"docs/a.md\"})",
+				)
+				.into());
+			};
+			let full_path =
+				runtime
+					.dir_context()
+					.resolve_path(runtime.session(), SPath::new(&path), PathResolver::WksDir, None)?;
+			check_access_read(lua, &full_path, "aip.blob.put")?;
+			std::fs::read(&full_path)
+				.map_err(|err| Error::custom(format!("aip.blob.put cannot read '{path}'. Cause: {err}")))?
+		}
+		_ => return Err(Error::custom("aip.blob.put - content must be a string or a {path = string} table").into()),
+	};
+	let size = content.len();
+
+	let client = BlobClient::new(blob_url.provider)?;
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let etag = tokio::task::block_in_place(|| {
+		rt.block_on(async { client.put(&blob_url, content, content_type.as_deref()).await })
+	})?;
+
+	let res = lua.create_table()?;
+	res.set("url", blob_url.to_string())?;
+	res.set("size", size)?;
+	res.set("etag", etag)?;
+
+	get_hub().publish_sync(format!("-> lua blob::put OK ({blob_url}) "));
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Lists the objects under a url prefix (recursively, in key order).
+///
+/// ```lua
+/// -- API Signature
+/// aip.blob.list(url_prefix: string, options?: {limit?: integer}): BlobItem[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `url_prefix: string` - The bucket and key prefix (e.g., `"s3://my-bucket/docs/"`, or `"gs://my-bucket"` for all).
+/// - `options?: {limit?: integer}` - The max number of objects returned (default 1000).
+///
+/// ### Returns
+///
+/// ```ts
+/// {
+///   url: string,            // e.g., "s3://my-bucket/docs/a.md"
+///   key: string,            // e.g., "docs/a.md"
+///   size: integer,
+///   last_modified?: string, // RFC 3339
+///   etag?: string,
+/// }[]
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// for _, item in ipairs(aip.blob.list("s3://my-bucket/docs/")) do
+///   print(item.key .. " (" .. item.size .. " bytes)")
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the url is invalid, the credentials are missing, or the bucket cannot be listed.
+fn blob_list(lua: &Lua, url: String, options: Option<Value>) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.blob.list")?;
+	let blob_url: BlobUrl = url.parse()?;
+	let limit = options
+		.unwrap_or(Value::Nil)
+		.x_get_i64("limit")
+		.unwrap_or(DEFAULT_LIST_LIMIT)
+		.max(1) as usize;
+
+	let client = BlobClient::new(blob_url.provider)?;
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let items = tokio::task::block_in_place(|| rt.block_on(async { client.list(&blob_url, limit).await }))?;
+
+	let res = lua.create_table()?;
+	for item in items {
+		let item_table = lua.create_table()?;
+		item_table.set("url", blob_url.with_key(&item.key).to_string())?;
+		item_table.set("key", item.key)?;
+		item_table.set("size", item.size)?;
+		item_table.set("last_modified", item.last_modified)?;
+		item_table.set("etag", item.etag)?;
+		res.push(item_table)?;
+	}
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Returns a presigned url, to `GET` (download) or `PUT` (upload) the object without credentials.
+///
+/// ```lua
+/// -- API Signature
+/// aip.blob.presign(url: string, options?: {method?: "GET" | "PUT", expires?: integer}): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `url: string` - The object url (e.g., `"s3://my-bucket/docs/report.md"`).
+/// - `options?: {method?: "GET" | "PUT", expires?: integer}`
+///   - `method` - The allowed request method (default `"GET"`).
+///   - `expires` - The validity in seconds (default 3600, max 604800, 7 days).
+///
+/// ### Example
+///
+/// ```lua
+/// local link = aip.blob.presign("s3://my-bucket/reports/summary.md", { expires = 24 * 3600 })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the url, method, or expires is invalid, or the credentials are missing.
+///
+/// NOTE: No request is sent (the url is signed locally).
+fn blob_presign(lua: &Lua, url: String, options: Option<Value>) -> mlua::Result<String> {
+	check_pack_capability(lua, PackCapability::Net, "aip.blob.presign")?;
+	let blob_url: BlobUrl = url.parse()?;
+	let options = options.unwrap_or(Value::Nil);
+
+	let method = match options.x_get_string("method").map(|m| m.to_uppercase()).as_deref() {
+		None | Some("GET") => Method::GET,
+		Some("PUT") => Method::PUT,
+		Some(other) => {
+			return Err(Error::custom(format!(
+				"aip.blob.presign - method '{other}' not supported (must be 'GET' or 'PUT')"
+			))
+			.into());
+		}
+	};
+	let expires = options.x_get_i64("expires").unwrap_or(DEFAULT_PRESIGN_EXPIRES_SECS).max(0) as u64;
+
+	let client = BlobClient::new(blob_url.provider)?;
+	let presigned = client.presign(&blob_url, method, expires)?;
+
+	Ok(presigned)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_blob;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_blob_invalid_url() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_blob::init_module, "blob").await?;
+
+		// -- Exec
+		let get_res = eval_lua(&lua, r#"return aip.blob.get("https://my-bucket/a.md")"#);
+		let list_res = eval_lua(&lua, r#"return aip.blob.list("s3://")"#);
+		let put_res = eval_lua(&lua, r#"return aip.blob.put("gs://my-bucket/a.md", 123)"#);
+
+		// -- Check
+		let get_err = get_res.err().ok_or("aip.blob.get should fail")?.to_string();
+		assert!(get_err.contains("Must start with 's3://' or 'gs://'"), "{get_err}");
+		assert!(list_res.is_err());
+		assert!(put_res.is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
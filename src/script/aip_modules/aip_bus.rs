@@ -0,0 +1,294 @@
+//! Defines the `aip.bus` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.bus` module is a publish/subscribe message bus, so that the concurrent tasks and agents
+//! of a run can exchange events (e.g., a coordinator and its workers).
+//!
+//! ### Functions
+//!
+//! - `aip.bus.publish(topic: string, data: any, options?: {scope?: "run" | "global"}): integer`
+//! - `aip.bus.poll(topic: string, options?: BusPollOptions): BusMessage[]`
+
+use crate::model::{RunBmc, RuntimeCtx};
+use crate::runtime::{BusMessage, BusPoll, BusScope, Runtime};
+use crate::script::{LuaValueExt as _, lua_value_to_serde_value, serde_value_to_lua_value};
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+use serde_json::json;
+use std::time::Duration;
+
+/// The max `timeout_ms` of `aip.bus.poll` (10 minutes)
+const MAX_POLL_TIMEOUT_MS: i64 = 600_000;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let publish_fn = lua.create_function(move |lua, (topic, data, options): (String, Value, Option<Value>)| {
+		bus_publish(lua, &rt, topic, data, options).map_err(mlua::Error::external)
+	})?;
+
+	let rt = runtime.clone();
+	let poll_fn = lua.create_function(move |lua, (topic, options): (String, Option<Value>)| {
+		bus_poll(lua, &rt, topic, options).map_err(mlua::Error::external)
+	})?;
+
+	table.set("publish", publish_fn)?;
+	table.set("poll", poll_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Publishes a message on a topic, for all the subscribers of the topic (see `aip.bus.poll`).
+///
+/// ```lua
+/// -- API Signature
+/// aip.bus.publish(topic: string, data: any, options?: {scope?: "run" | "global"}): integer
+/// ```
+///
+/// ### Arguments
+///
+/// - `topic: string`: The topic name (e.g., `"jobs"`, `"results"`).
+/// - `data: any`: The message data (JSON compatible, e.g., a table or a string).
+/// - `options?: {scope?: "run" | "global"}`:
+///   - `scope = "run"` (default): The topics of the root run, shared by the tasks of the run and the sub agent runs.
+///     They are cleared when the root run ends.
+///   - `scope = "global"`: The topics of the `aip` process, shared by all of its runs (e.g., with `aip serve`).
+///
+/// ### Returns
+///
+/// The message `seq` (increasing across the topics).
+///
+/// ### Example
+///
+/// ```lua
+/// for _, file in ipairs(files) do
+///   aip.bus.publish("jobs", { path = file.path })
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the topic is empty, the scope is invalid, or the scope is `"run"` outside of a run.
+fn bus_publish(lua: &Lua, runtime: &Runtime, topic: String, data: Value, options: Option<Value>) -> Result<i64> {
+	let fn_name = "aip.bus.publish";
+	let options = options.unwrap_or(Value::Nil);
+	let rt_ctx = RuntimeCtx::extract_from_global(lua)?;
+	let scope = resolve_scope(runtime, &rt_ctx, fn_name, &topic, &options)?;
+
+	let data = lua_value_to_serde_value(data)?;
+	let seq = runtime
+		.message_bus()
+		.publish(scope, &topic, data, rt_ctx.run_uid(), rt_ctx.task_uid());
+
+	Ok(seq)
+}
+
+/// ## Lua Documentation
+///
+/// Returns the messages of the topic published since the last poll of this subscriber.
+///
+/// ```lua
+/// -- API Signature
+/// aip.bus.poll(topic: string, options?: BusPollOptions): BusMessage[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `topic: string`: The topic name.
+/// - `options?: BusPollOptions`:
+///   ```ts
+///   {
+///     scope?: "run" | "global", // default "run" (see `aip.bus.publish`)
+///     timeout_ms?: integer,     // Wait up to this time for the first messages (default 0, no wait, max 600000)
+///     limit?: integer,          // The max messages returned (default all)
+///     since?: integer,          // Returns the messages after this seq (rather than after the subscriber last poll)
+///     subscriber?: string,      // The subscriber name (default the current task, or run).
+///                               // The pollers with the same name share the messages (each message is returned once).
+///   }
+///   ```
+///
+/// ### Returns (BusMessage[])
+///
+/// ```ts
+/// {
+///   seq: integer,
+///   topic: string,
+///   data: any,
+///   run_uid?: string,  // The publisher run
+///   task_uid?: string, // The publisher task
+///   time: integer,     // The publish time (epoch microseconds)
+/// }[]
+/// ```
+///
+/// Only the last 1000 messages of each topic are kept.
+///
+/// ### Example
+///
+/// ```lua
+/// -- Worker tasks (sharing the "workers" subscriber, so each job goes to one worker)
+/// local jobs = aip.bus.poll("jobs", { subscriber = "workers", limit = 1, timeout_ms = 5000 })
+/// for _, job in ipairs(jobs) do
+///   aip.bus.publish("results", { path = job.data.path, ok = true })
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the topic is empty, the options are invalid, or the scope is `"run"` outside of a run.
+fn bus_poll(lua: &Lua, runtime: &Runtime, topic: String, options: Option<Value>) -> Result<Value> {
+	let fn_name = "aip.bus.poll";
+	let options = options.unwrap_or(Value::Nil);
+	let rt_ctx = RuntimeCtx::extract_from_global(lua)?;
+	let scope = resolve_scope(runtime, &rt_ctx, fn_name, &topic, &options)?;
+
+	let timeout_ms = options.x_get_i64("timeout_ms").unwrap_or(0);
+	if !(0..=MAX_POLL_TIMEOUT_MS).contains(&timeout_ms) {
+		return Err(Error::custom(format!(
+			"{fn_name} - timeout_ms must be between 0 and {MAX_POLL_TIMEOUT_MS}, was {timeout_ms}"
+		)));
+	}
+	let limit = match options.x_get_i64("limit") {
+		Some(limit) if limit < 1 => {
+			return Err(Error::custom(format!(
+				"{fn_name} - limit must be 1 or more, was {limit}"
+			)));
+		}
+		limit => limit.map(|limit| limit as usize),
+	};
+	let subscriber = options
+		.x_get_string("subscriber")
+		.or_else(|| rt_ctx.task_uid().or(rt_ctx.run_uid()).map(|uid| uid.to_string()))
+		.unwrap_or_else(|| "default".to_string());
+
+	let query = BusPoll {
+		scope,
+		topic: &topic,
+		subscriber: &subscriber,
+		since: options.x_get_i64("since"),
+		limit,
+	};
+	let bus = runtime.message_bus();
+	let messages = if timeout_ms > 0 {
+		let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+		let timeout = Duration::from_millis(timeout_ms as u64);
+		tokio::task::block_in_place(|| rt.block_on(bus.poll_wait(&query, timeout)))
+	} else {
+		bus.poll(&query)
+	};
+
+	let messages: Vec<serde_json::Value> = messages.into_iter().map(message_json).collect();
+	serde_value_to_lua_value(lua, messages.into())
+}
+
+// region:    --- Support
+
+fn resolve_scope(
+	runtime: &Runtime,
+	rt_ctx: &RuntimeCtx,
+	fn_name: &str,
+	topic: &str,
+	options: &Value,
+) -> Result<BusScope> {
+	if topic.trim().is_empty() {
+		return Err(Error::custom(format!("{fn_name} - topic cannot be empty")));
+	}
+
+	match options.x_get_string("scope").as_deref() {
+		None | Some("run") => {
+			let run_id = rt_ctx.get_run_id(runtime.mm())?.ok_or_else(|| {
+				Error::custom(format!(
+					"{fn_name} - scope 'run' requires a run context (use scope = 'global' outside of a run)"
+				))
+			})?;
+			Ok(BusScope::Run(RunBmc::get_root_uid(runtime.mm(), run_id)?))
+		}
+		Some("global") => Ok(BusScope::Global),
+		Some(other) => Err(Error::custom(format!(
+			"{fn_name} - scope '{other}' not supported. Must be 'run' or 'global'"
+		))),
+	}
+}
+
+fn message_json(message: BusMessage) -> serde_json::Value {
+	json!({
+		"seq": message.seq,
+		"topic": message.topic,
+		"data": message.data,
+		"run_uid": message.run_uid.map(|uid| uid.to_string()),
+		"task_uid": message.task_uid.map(|uid| uid.to_string()),
+		"time": message.time,
+	})
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, eval_lua, run_reflective_agent, setup_lua};
+	use crate::script::aip_modules::aip_bus;
+	use value_ext::JsonValueExt as _;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_bus_publish_poll_global() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_bus::init_module, "bus").await?;
+		let script = r#"
+local seq = aip.bus.publish("jobs", { path = "a.md" }, { scope = "global" })
+aip.bus.publish("jobs", "b.md", { scope = "global" })
+local first = aip.bus.poll("jobs", { scope = "global", subscriber = "workers", limit = 1 })
+local rest = aip.bus.poll("jobs", { scope = "global", subscriber = "workers", timeout_ms = 10 })
+local empty = aip.bus.poll("jobs", { scope = "global", subscriber = "workers" })
+local all = aip.bus.poll("jobs", { scope = "global", subscriber = "coordinator" })
+local _, scope_err = pcall(aip.bus.publish, "jobs", 1, { scope = "daemon" })
+return { seq = seq, first = first, rest = rest, empty_count = #empty, all_count = #all, scope_err = tostring(scope_err) }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let seq = res.x_get_i64("seq")?;
+		assert_eq!(res.x_get_i64("/first/0/seq")?, seq);
+		assert_eq!(res.x_get_str("/first/0/data/path")?, "a.md");
+		assert_eq!(res.x_get_str("/rest/0/data")?, "b.md");
+		assert_eq!(res.x_get_i64("empty_count")?, 0);
+		assert_eq!(res.x_get_i64("all_count")?, 2);
+		assert_contains(res.x_get_str("scope_err")?, "scope 'daemon' not supported");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_bus_publish_poll_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let script = r#"
+aip.bus.publish("events", { kind = "started" })
+return aip.bus.poll("events", { subscriber = "test" })
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent(script, None).await?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/0/data/kind")?, "started");
+		assert_eq!(res.x_get_str("/0/topic")?, "events");
+		assert!(
+			res.x_get_str("/0/task_uid").is_ok(),
+			"should have the publisher task_uid"
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
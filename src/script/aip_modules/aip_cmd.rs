@@ -8,18 +8,32 @@
 //!
 //! ### Functions
 //!
-//! - `aip.cmd.exec(cmd_name: string, args?: string | list): {stdout: string, stderr: string, exit: number}`
+//! - `aip.cmd.exec(cmd_name: string, args?: string | list, options?: CmdExecOptions): {stdout: string, stderr: string, exit: number, timed_out: boolean}`
 
 use crate::Result;
+use crate::hub::{HubEvent, get_hub};
+use crate::model::{Id, LogKind, ModelManager, RuntimeCtx, TaskBmc, TaskForUpdate};
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::{check_pack_capability, rec_res_usage};
 use crate::script::support::into_vec_of_strings;
-use mlua::{Lua, Table, Value};
-use std::process::Command;
-
-pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+use crate::support::proc::ProcUsageSampler;
+use crate::types::{PackCapability, ResUsage, RunEnv};
+use mlua::{FromLua, Lua, Table, Value};
+use std::collections::VecDeque;
+use std::io::{BufRead as _, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
-	let exec_fn = lua.create_function(cmd_exec)?;
+	let rt = runtime.clone();
+	let exec_fn = lua.create_function(
+		move |lua, (cmd_name, args, options): (String, Option<Value>, Option<Value>)| {
+			cmd_exec(lua, &rt, cmd_name, args, options)
+		},
+	)?;
 
 	table.set("exec", exec_fn)?;
 
@@ -32,7 +46,7 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 ///
 /// ```lua
 /// -- API Signature
-/// aip.cmd.exec(cmd_name: string, args?: string | list): CmdResponse
+/// aip.cmd.exec(cmd_name: string, args?: string | list, options?: CmdExecOptions): CmdResponse
 /// ```
 ///
 /// Executes the specified command using the system shell. Arguments can be provided as a single string
@@ -40,11 +54,26 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 ///
 /// On Windows, the command will be wrapped with `cmd /C cmd_name args..` to maximize compatibility.
 ///
+/// The variables of the agent option `env` are added to the command environment.
+///
+/// When called in a task, the last stdout/stderr lines of the running command are shown live
+/// in the TUI task view (the `Cmd:` section, with the ANSI colors).
+///
+/// In a run, the cpu time, peak memory, and disk bytes read/written of the command (and its sub processes)
+/// are added to the run resource usage (sampled, shown in the TUI run header).
+///
 /// ### Arguments
 ///
 /// - `cmd_name: string` - The name or path of the command to execute.
 /// - `args?: string | list<string>` (optional) - Arguments to pass to the command. Can be a single string
 ///   (which might be parsed by the shell) or a Lua list of strings.
+/// - `options?: CmdExecOptions` (optional) - For long-running commands:
+///   ```ts
+///   {
+///     stream?: boolean,     // Log each stdout/stderr line to the run log as it comes (live in the TUI)
+///     timeout_ms?: number,  // Kill the command (and its process group) after this duration
+///   }
+///   ```
 ///
 /// ### Return (CmdResponse)
 ///
@@ -55,7 +84,8 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 /// {
 ///   stdout: string,  // Standard output captured from the command
 ///   stderr: string,  // Standard error captured from the command
-///   exit:   number   // Exit code returned by the command (0 usually indicates success)
+///   exit:   number,  // Exit code returned by the command (0 usually indicates success, -1 when killed)
+///   timed_out: boolean // True if the command was killed by the `timeout_ms` (stdout/stderr are the lines captured until then)
 /// }
 /// ```
 ///
@@ -87,22 +117,75 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 /// local result = aip.cmd.exec("ls", {"-l", "-a"})
 /// print("stdout:", result.stdout)
 /// print("exit:", result.exit)
+///
+/// -- Long-running command, streamed to the run log, killed after 60s
+/// local result = aip.cmd.exec("cargo", {"build"}, {stream = true, timeout_ms = 60000})
+/// if result.timed_out then
+///   print("build timed out")
+/// end
 /// ```
-fn cmd_exec(lua: &Lua, (cmd_name, args): (String, Option<Value>)) -> mlua::Result<Value> {
+fn cmd_exec(
+	lua: &Lua,
+	runtime: &Runtime,
+	cmd_name: String,
+	args: Option<Value>,
+	options: Option<Value>,
+) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Exec, "aip.cmd.exec")?;
 	let args = args.map(|args| into_vec_of_strings(args, "command args")).transpose()?;
+	let options = options
+		.map(|options| CmdExecOptions::from_lua(options, lua))
+		.transpose()?
+		.unwrap_or_default();
 
 	let mut command = cross_command(&cmd_name, args)?;
+	// The agent option `env` variables
+	if let Some(run_env) = lua.app_data_ref::<RunEnv>() {
+		command.envs(run_env.vars().iter().map(|(name, value)| (name, value)));
+	}
 
-	match command.output() {
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	// NOTE: In a run, the command is always streamed, to sample its resource usage,
+	//       and, in a task, to tail its output in the TUI task view.
+	let run_id = ctx.get_run_id(runtime.mm()).ok().flatten();
+	let task_id = ctx.get_task_id(runtime.mm()).ok().flatten();
+
+	let output = if options.stream || options.timeout_ms.is_some() || run_id.is_some() {
+		let mut cmd_tail = task_id.map(|task_id| CmdTail::start(runtime.mm(), task_id, &command));
+		let output = exec_streamed(&mut command, &options, |line| {
+			if let Some(cmd_tail) = cmd_tail.as_mut() {
+				cmd_tail.push(line);
+			}
+			if options.stream {
+				// NOTE: The log requires a run (not the case for the `aip.cmd` unit tests), and should not fail the command.
+				let _ = runtime.rec_log_with_rt_ctx(&ctx, LogKind::AgentPrint, line);
+				// -- For legacy tui
+				get_hub().publish_sync(HubEvent::LuaPrint(line.to_string().into()));
+			}
+		});
+		if let Some(cmd_tail) = cmd_tail {
+			cmd_tail.end();
+		}
+		output
+	} else {
+		command.output().map(|output| CmdOutput {
+			stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+			stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+			exit: output.status.code().unwrap_or(-1) as i64,
+			timed_out: false,
+			usage: ResUsage::default(),
+		})
+	};
+
+	match output {
 		Ok(output) => {
-			let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-			let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-			let exit_code = output.status.code().unwrap_or(-1) as i64;
+			rec_res_usage(lua, runtime, output.usage);
 
 			let res = lua.create_table()?;
-			res.set("stdout", stdout.as_str())?;
-			res.set("stderr", stderr.as_str())?;
-			res.set("exit", exit_code)?;
+			res.set("stdout", output.stdout)?;
+			res.set("stderr", output.stderr)?;
+			res.set("exit", output.exit)?;
+			res.set("timed_out", output.timed_out)?;
 
 			// NOTE: We return the table even on non-zero exit codes as this is the
 			//       expected behavior of the Lua API. The caller can check the `exit` code.
@@ -126,6 +209,41 @@ Cause:\n{err}"
 	}
 }
 
+// region:    --- Options & Output
+
+#[derive(Debug, Default)]
+struct CmdExecOptions {
+	/// Send each stdout/stderr line to the run log as it comes
+	stream: bool,
+	/// Kill the command process group after this duration
+	timeout_ms: Option<u64>,
+}
+
+impl FromLua for CmdExecOptions {
+	fn from_lua(value: Value, _lua: &Lua) -> mlua::Result<Self> {
+		let table = value
+			.as_table()
+			.ok_or(crate::Error::custom("aip.cmd.exec options should be a table"))?;
+		let stream: Option<bool> = table.get("stream")?;
+		let timeout_ms: Option<u64> = table.get("timeout_ms")?;
+		Ok(Self {
+			stream: stream.unwrap_or(false),
+			timeout_ms,
+		})
+	}
+}
+
+struct CmdOutput {
+	stdout: String,
+	stderr: String,
+	exit: i64,
+	timed_out: bool,
+	/// The sampled resource usage of the command (and its sub processes)
+	usage: ResUsage,
+}
+
+// endregion: --- Options & Output
+
 // region:    --- Support
 
 /// Create a command, and make it a `cmd /C cmd_name args..` for windows compatibility.
@@ -153,6 +271,186 @@ fn cross_command(cmd_name: &str, args: Option<Vec<String>>) -> Result<Command> {
 
 	Ok(command)
 }
+
+/// Spawn the command, and capture its stdout/stderr line by line (calling `on_line` for each line),
+/// until it ends or the `options.timeout_ms` expires (then, the process group is killed).
+/// The resource usage of the command is sampled meanwhile (see `ProcUsageSampler`).
+fn exec_streamed(
+	command: &mut Command,
+	options: &CmdExecOptions,
+	mut on_line: impl FnMut(&str),
+) -> std::io::Result<CmdOutput> {
+	// NOTE: Own process group, so that the timeout kills the eventual sub processes as well
+	#[cfg(unix)]
+	std::os::unix::process::CommandExt::process_group(command, 0);
+
+	// NOTE: Null stdin (as `Command::output`), so that the command does not read the terminal (e.g., the TUI)
+	let mut child = command
+		.stdin(Stdio::null())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()?;
+
+	// -- One reader thread per pipe, sending (is_stderr, line)
+	let (tx, rx) = mpsc::channel::<(bool, String)>();
+	if let Some(stdout) = child.stdout.take() {
+		spawn_line_reader(stdout, false, tx.clone());
+	}
+	if let Some(stderr) = child.stderr.take() {
+		spawn_line_reader(stderr, true, tx.clone());
+	}
+	drop(tx);
+
+	let mut sampler = ProcUsageSampler::start(child.id());
+	let deadline = options.timeout_ms.map(|ms| Instant::now() + Duration::from_millis(ms));
+	let (mut stdout, mut stderr) = (String::new(), String::new());
+	let mut timed_out = false;
+
+	let mut push_line = |is_stderr: bool, line: String| {
+		on_line(line.trim_end_matches(['\r', '\n']));
+		if is_stderr {
+			stderr.push_str(&line);
+		} else {
+			stdout.push_str(&line);
+		}
+	};
+
+	loop {
+		let wait = match deadline {
+			Some(deadline) => deadline
+				.saturating_duration_since(Instant::now())
+				.min(ProcUsageSampler::INTERVAL),
+			None => ProcUsageSampler::INTERVAL,
+		};
+		match rx.recv_timeout(wait) {
+			Ok((is_stderr, line)) => push_line(is_stderr, line),
+			Err(RecvTimeoutError::Disconnected) => break,
+			Err(RecvTimeoutError::Timeout) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+				timed_out = true;
+				sampler.sample();
+				kill_process_group(&mut child);
+				break;
+			}
+			Err(RecvTimeoutError::Timeout) => (),
+		}
+		sampler.maybe_sample();
+	}
+
+	// NOTE: Last sample before the wait (the ended process is still there until waited)
+	sampler.sample();
+	let status = child.wait()?;
+
+	// -- The lines read before the kill
+	for (is_stderr, line) in rx.try_iter() {
+		push_line(is_stderr, line);
+	}
+
+	Ok(CmdOutput {
+		stdout,
+		stderr,
+		exit: status.code().unwrap_or(-1) as i64,
+		timed_out,
+		usage: sampler.usage(),
+	})
+}
+
+fn spawn_line_reader(pipe: impl Read + Send + 'static, is_stderr: bool, tx: mpsc::Sender<(bool, String)>) {
+	std::thread::spawn(move || {
+		let mut reader = BufReader::new(pipe);
+		let mut buf = Vec::new();
+		// NOTE: `read_until` (rather than `lines`) so that non-UTF-8 output does not stop the reading
+		while let Ok(n) = reader.read_until(b'\n', &mut buf)
+			&& n > 0
+		{
+			if tx.send((is_stderr, String::from_utf8_lossy(&buf).to_string())).is_err() {
+				break;
+			}
+			buf.clear();
+		}
+	});
+}
+
+/// The live tail of the running command of a task (the `cmd_line`, `cmd_tail`, and `cmd_running` task fields),
+/// shown in the TUI task view.
+///
+/// NOTE: The task updates are throttled, and their errors do not fail the command.
+struct CmdTail<'a> {
+	mm: &'a ModelManager,
+	task_id: Id,
+	lines: VecDeque<String>,
+	last_flush: Instant,
+	dirty: bool,
+}
+
+impl<'a> CmdTail<'a> {
+	const MAX_LINES: usize = 200;
+	const FLUSH_INTERVAL: Duration = Duration::from_millis(150);
+
+	fn start(mm: &'a ModelManager, task_id: Id, command: &Command) -> Self {
+		let cmd_line = std::iter::once(command.get_program())
+			.chain(command.get_args())
+			.map(|a| a.to_string_lossy())
+			.collect::<Vec<_>>()
+			.join(" ");
+		let task_u = TaskForUpdate {
+			cmd_line: Some(cmd_line),
+			cmd_tail: Some(String::new()),
+			cmd_running: Some(true),
+			..Default::default()
+		};
+		let _ = TaskBmc::update(mm, task_id, task_u);
+
+		Self {
+			mm,
+			task_id,
+			lines: VecDeque::new(),
+			last_flush: Instant::now(),
+			dirty: false,
+		}
+	}
+
+	fn push(&mut self, line: &str) {
+		if self.lines.len() >= Self::MAX_LINES {
+			self.lines.pop_front();
+		}
+		self.lines.push_back(line.to_string());
+		self.dirty = true;
+
+		if self.last_flush.elapsed() >= Self::FLUSH_INTERVAL {
+			self.flush(None);
+		}
+	}
+
+	fn end(mut self) {
+		self.dirty = true;
+		self.flush(Some(false));
+	}
+
+	fn flush(&mut self, cmd_running: Option<bool>) {
+		if !self.dirty {
+			return;
+		}
+		let task_u = TaskForUpdate {
+			cmd_tail: Some(self.lines.iter().map(String::as_str).collect::<Vec<_>>().join("\n")),
+			cmd_running,
+			..Default::default()
+		};
+		let _ = TaskBmc::update(self.mm, self.task_id, task_u);
+		self.last_flush = Instant::now();
+		self.dirty = false;
+	}
+}
+
+fn kill_process_group(child: &mut Child) {
+	let pid = child.id().to_string();
+	#[cfg(unix)]
+	let _ = Command::new("kill").args(["-KILL", "--", &format!("-{pid}")]).status();
+	#[cfg(windows)]
+	let _ = Command::new("taskkill").args(["/T", "/F", "/PID", &pid]).status();
+	// NOTE: In case the group kill did not work
+	let _ = child.kill();
+}
+
 // endregion: --- Support
 
 // region:    --- Tests
@@ -161,8 +459,12 @@ fn cross_command(cmd_name: &str, args: Option<Vec<String>>) -> Result<Command> {
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
-	use crate::_test_support::{assert_contains, eval_lua, setup_lua};
+	use super::{CmdExecOptions, exec_streamed};
+	use crate::_test_support::{assert_contains, eval_lua, run_reflective_agent_with_runtime, setup_lua};
+	use crate::model::TaskBmc;
+	use crate::runtime::Runtime;
 	use crate::script::aip_modules::aip_cmd;
+	use std::process::Command;
 	use value_ext::JsonValueExt as _;
 
 	#[tokio::test]
@@ -248,6 +550,118 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_lua_cmd_exec_stream_lines() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_cmd::init_module, "cmd").await?;
+		let script = r#"
+			return aip.cmd.exec("sh", {"-c", "echo one; echo two >&2; echo three"}, {stream = true})
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("stdout")?, "one\nthree\n");
+		assert_eq!(res.x_get_str("stderr")?, "two\n");
+		assert_eq!(res.x_get_i64("exit")?, 0);
+		assert!(!res.x_get_bool("timed_out")?);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_lua_cmd_exec_output_fidelity() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_cmd::init_module, "cmd").await?;
+		// no trailing new line, CRLF, an empty line, and the stderr
+		let fx_args = r#"{"-c", "printf 'one\r\n\ntwo'; printf 'err' >&2"}"#;
+
+		// -- Exec
+		// not streamed (outside of a run), and streamed
+		let res = eval_lua(&lua, &format!(r#"return aip.cmd.exec("sh", {fx_args})"#))?;
+		let res_streamed = eval_lua(
+			&lua,
+			&format!(r#"return aip.cmd.exec("sh", {fx_args}, {{stream = true}})"#),
+		)?;
+
+		// -- Check
+		for res in [res, res_streamed] {
+			assert_eq!(res.x_get_str("stdout")?, "one\r\n\ntwo");
+			assert_eq!(res.x_get_str("stderr")?, "err");
+			assert_eq!(res.x_get_i64("exit")?, 0);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_lua_cmd_exec_streamed_usage() -> Result<()> {
+		// -- Setup & Fixtures
+		// a cpu busy shell loop (a fraction of a second)
+		let mut command = Command::new("sh");
+		command.args(["-c", "i=0; while [ $i -lt 200000 ]; do i=$((i+1)); done; echo done"]);
+
+		// -- Exec
+		let mut lines = Vec::new();
+		let output = exec_streamed(&mut command, &CmdExecOptions::default(), |line| {
+			lines.push(line.to_string())
+		})?;
+
+		// -- Check
+		assert_eq!(output.stdout, "done\n");
+		assert_eq!(lines, ["done"]);
+		assert!(output.usage.cpu_ms > 0, "should have the cpu time of the command");
+		assert!(output.usage.peak_mem > 0, "should have the memory of the command");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_cmd_exec_timeout_kill() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_cmd::init_module, "cmd").await?;
+		let script = r#"
+			return aip.cmd.exec("sh", {"-c", "echo start; sleep 10; echo end"}, {timeout_ms = 300})
+		"#;
+
+		// -- Exec
+		let start = std::time::Instant::now();
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert!(start.elapsed().as_secs() < 5, "should have been killed by the timeout");
+		assert!(res.x_get_bool("timed_out")?);
+		assert_eq!(res.x_get_str("stdout")?, "start\n");
+		assert_ne!(res.x_get_i64("exit")?, 0);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_cmd_exec_task_cmd_tail() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+return aip.cmd.exec("sh", {"-c", "echo one; printf '\\033[32mtwo\\033[0m\\n' >&2"})
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("stdout")?, "one\n");
+		// NOTE: The reflective agent test run and task have the id 0
+		let task = TaskBmc::get(runtime.mm(), 0.into())?;
+		assert_eq!(task.cmd_running, Some(false));
+		assert_contains(task.cmd_line.as_deref().unwrap_or_default(), "sh -c echo one;");
+		let cmd_tail = task.cmd_tail.as_deref().unwrap_or_default();
+		assert_contains(cmd_tail, "one");
+		assert_contains(cmd_tail, "\x1b[32mtwo\x1b[0m");
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_lua_cmd_exec_non_zero_exit() -> Result<()> {
 		// -- Setup & Fixtures
@@ -8,12 +8,16 @@
 //!
 //! Options are shared for both functions; fields not applicable to `parse_row` are ignored.
 //!
+//! It also exposes `stream` and `write` to process large CSV files row by row, without loading them entirely.
+//!
 //! ### Functions
 //!
 //! - `aip.csv.parse_row(row: string, options?: CsvOptions): string[]`
 //! - `aip.csv.parse(content: string, options?: CsvOptions): CsvContent`
 //! - `aip.csv.values_to_row(values: any[]): string`
 //! - `aip.csv.value_lists_to_rows(value_lists: any[][]): string[]`
+//! - `aip.csv.stream(path: string, options?: CsvStreamOptions): fun(): (row, idx)`
+//! - `aip.csv.write(path: string, rows: (any[] | table)[], options?: CsvWriteOptions): FileInfo`
 //!
 //! ### Related Types
 //!
@@ -26,7 +30,8 @@
 //!   trim_fields?: boolean,      -- default false
 //!   has_header?: boolean,       -- default true for parse()
 //!   skip_empty_lines?: boolean, -- default true
-//!   comment?: string            -- e.g., "#", optional
+//!   comment?: string,           -- e.g., "#", optional
+//!   encoding?: string           -- file functions only, e.g., "latin1", default "utf-8"
 //! }
 //! ```
 //!
@@ -34,12 +39,18 @@
 //! - `parse_row` ignores: `has_header`, `skip_empty_lines`, and `comment`.
 //! - When an option expecting a character is given a multi-character string, only the first byte is used.
 
-use crate::Result;
+use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
 use crate::script::lua_helpers::lua_value_to_serde_value;
 use crate::support::W;
-use crate::types::CsvOptions;
-use mlua::{FromLua as _, IntoLua as _, Lua, Table, Value};
+use crate::support::csvs::CsvRowReader;
+use crate::types::{CsvContent, CsvOptions, FileInfo};
+use crate::{Error, Result};
+use mlua::{FromLua as _, Function, IntoLua as _, Lua, Table, Value};
+use std::collections::BTreeSet;
+use std::sync::Mutex;
 
 /// Convert a Lua value to a string suitable for CSV.
 /// - Strings are returned as is.
@@ -88,7 +99,7 @@ pub fn lua_matrix_to_rows(matrix: Table) -> mlua::Result<Vec<Vec<String>>> {
 	Ok(rows)
 }
 
-pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
 	let parse_row_fn =
@@ -105,6 +116,17 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	table.set("values_to_row", values_to_row_fn)?;
 	table.set("value_lists_to_rows", value_lists_to_rows_fn)?;
 
+	let rt = runtime.clone();
+	let stream_fn =
+		lua.create_function(move |lua, (path, opts): (String, Option<Value>)| lua_stream(lua, &rt, path, opts))?;
+	table.set("stream", stream_fn)?;
+
+	let rt = runtime.clone();
+	let write_fn = lua.create_function(move |lua, (path, rows, opts): (String, Value, Option<Value>)| {
+		lua_write(lua, &rt, path, rows, opts)
+	})?;
+	table.set("write", write_fn)?;
+
 	Ok(table)
 }
 
@@ -219,6 +241,204 @@ fn lua_value_lists_to_rows(lua: &Lua, value_lists: Value, opts_val: Option<Value
 	Ok(rows)
 }
 
+/// ## Lua Documentation
+///
+/// Returns an iterator over the rows of a CSV file, reading the file incrementally (for large files).
+///
+/// ```lua
+/// -- API Signature
+/// aip.csv.stream(path: string, options?: CsvStreamOptions): fun(): (row: table | string[], idx: integer)
+/// ```
+///
+/// - `path`: The CSV file path, relative to the workspace root.
+/// - `options`: The `CsvOptions` (`has_header` defaults to `true`), plus:
+///   - `filter?: fun(row, idx): boolean` - Only the rows with a truthy return are yielded.
+///   - `map?: fun(row, idx): any` - The returned value is yielded instead of the row (`nil` skips the row).
+///
+/// When `has_header` is true, the row is a record keyed by the headers (remapped with `header_labels`),
+/// otherwise the row is the list of cell strings. The `idx` is the 1-based data row index in the file.
+///
+/// ```lua
+/// for row, idx in aip.csv.stream("data/big.csv", { filter = function(r) return r.status == "open" end }) do
+///   print(idx, row.id)
+/// end
+/// ```
+fn lua_stream(lua: &Lua, runtime: &Runtime, path: String, opts_val: Option<Value>) -> mlua::Result<Function> {
+	let full_path =
+		runtime
+			.dir_context()
+			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.csv.stream")?;
+
+	let (filter, map) = match &opts_val {
+		Some(Value::Table(opts)) => (get_opt_fn(opts, "filter")?, get_opt_fn(opts, "map")?),
+		_ => (None, None),
+	};
+	let opts = match opts_val {
+		Some(v) => CsvOptions::from_lua(v, lua)?,
+		None => CsvOptions::default(),
+	};
+
+	let reader = CsvRowReader::open(&full_path, Some(opts)).map_err(|e| {
+		Error::from(format!(
+			"aip.csv.stream - Failed to open csv file '{path}'.\nCause: {e}",
+		))
+	})?;
+	let headers = reader.headers().map(|headers| headers.to_vec());
+	let state = Mutex::new((reader, 0_usize));
+
+	lua.create_function(move |lua, ()| {
+		loop {
+			// NOTE: The state is not locked during the callbacks (they could use the iterator)
+			let (row, idx) = {
+				let mut state = state
+					.lock()
+					.map_err(|_| Error::custom("aip.csv.stream - Iterator state lock poisoned"))?;
+				let Some(row) = state.0.next_row().map_err(|e| {
+					Error::from(format!(
+						"aip.csv.stream - Failed to read csv file '{path}'.\nCause: {e}",
+					))
+				})?
+				else {
+					return Ok((Value::Nil, Value::Nil));
+				};
+				state.1 += 1;
+				(csv_row_to_lua(lua, headers.as_deref(), row)?, state.1)
+			};
+
+			if let Some(filter) = &filter {
+				let keep: Value = filter.call((row.clone(), idx))?;
+				if matches!(keep, Value::Nil | Value::Boolean(false)) {
+					continue;
+				}
+			}
+			let row = match &map {
+				Some(map) => map.call::<Value>((row, idx))?,
+				None => row,
+			};
+			if row.is_nil() {
+				continue;
+			}
+
+			return Ok((row, idx.into_lua(lua)?));
+		}
+	})
+}
+
+/// ## Lua Documentation
+///
+/// Writes (appends by default) rows to a CSV file, with the proper quoting.
+///
+/// ```lua
+/// -- API Signature
+/// aip.csv.write(path: string, rows: (any[] | table)[], options?: CsvWriteOptions): FileInfo
+/// ```
+///
+/// - `rows`: The value lists (e.g., `{"a", 1}`) and/or the records (e.g., `{id = 1, name = "a"}`).
+///   Tables are written as JSON, and `nil` as empty cells.
+/// - `options`: The `CsvOptions`, plus:
+///   - `headers?: string[]` - The record keys (column order), also written as the header row when the file is new.
+///   - `append?: boolean` - Default `true`. When `false`, the file is overwritten.
+///
+/// When the records have no `headers`, the header row of the existing file is used (with `header_labels`),
+/// otherwise the sorted union of the record keys.
+///
+/// ```lua
+/// aip.csv.write("out/report.csv", { { id = 1, name = "Alice" } }, { headers = { "id", "name" } })
+/// ```
+fn lua_write(lua: &Lua, runtime: &Runtime, path: String, rows: Value, opts_val: Option<Value>) -> mlua::Result<Value> {
+	let dir_context = runtime.dir_context();
+	let full_path = dir_context.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	let lock_handle = runtime.file_write_manager().lock_for_path(&full_path);
+	let _guard = lock_handle.lock();
+
+	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.csv.write requires a aipack workspace setup")?;
+	check_access_write(lua, &full_path, wks_dir)?;
+
+	let Value::Table(rows) = rows else {
+		return Err(Error::custom("aip.csv.write - rows must be a list of value lists or records").into());
+	};
+	let rows = rows.sequence_values::<Table>().collect::<mlua::Result<Vec<_>>>()?;
+
+	// -- Extract the options
+	let (headers, append) = match &opts_val {
+		Some(Value::Table(opts)) => {
+			let headers = match opts.x_get_value("headers") {
+				Some(Value::Table(headers)) => {
+					Some(headers.sequence_values::<String>().collect::<mlua::Result<Vec<_>>>()?)
+				}
+				_ => None,
+			};
+			(headers, opts.x_get_bool("append").unwrap_or(true))
+		}
+		_ => (None, true),
+	};
+	let opts = match opts_val {
+		Some(v) => CsvOptions::from_lua(v, lua)?,
+		None => CsvOptions::default(),
+	};
+
+	// -- Resolve the headers (the record keys)
+	let is_record = |row: &Table| row.raw_len() == 0 && row.pairs::<Value, Value>().next().is_some();
+	let headers = match headers {
+		Some(headers) => headers,
+		None if rows.iter().any(is_record) => {
+			let file_exists = std::fs::metadata(&full_path).is_ok_and(|meta| meta.len() > 0);
+			if append && file_exists {
+				crate::support::csvs::load_csv_headers(&full_path, Some(opts.clone())).map_err(|e| {
+					Error::from(format!(
+						"aip.csv.write - Failed to read the headers of csv file '{path}'.\nCause: {e}",
+					))
+				})?
+			} else {
+				let mut keys = BTreeSet::new();
+				for row in rows.iter().filter(|row| is_record(row)) {
+					for pair in row.pairs::<String, Value>() {
+						keys.insert(pair?.0);
+					}
+				}
+				keys.into_iter().collect()
+			}
+		}
+		None => Vec::new(),
+	};
+
+	// -- Build the rows
+	let mut csv_rows = Vec::with_capacity(rows.len());
+	for row in rows {
+		let csv_row = if is_record(&row) {
+			headers
+				.iter()
+				.map(|key| lua_value_to_csv_string(row.get(key.as_str())?))
+				.collect::<mlua::Result<Vec<_>>>()?
+		} else {
+			row.sequence_values::<Value>()
+				.map(|value| lua_value_to_csv_string(value?))
+				.collect::<mlua::Result<Vec<_>>>()?
+		};
+		csv_rows.push(csv_row);
+	}
+
+	// -- Write
+	let content = CsvContent {
+		headers,
+		rows: csv_rows,
+	};
+	let res = if append {
+		crate::support::csvs::append_csv(&full_path, &content, Some(opts))
+	} else {
+		crate::support::csvs::save_csv(&full_path, &content, Some(opts))
+	};
+	res.map_err(|e| {
+		Error::from(format!(
+			"aip.csv.write - Failed to write csv file '{path}'.\nCause: {e}",
+		))
+	})?;
+
+	let file_info = FileInfo::new(runtime.dir_context(), path, &full_path);
+	file_info.into_lua(lua)
+}
+
 fn values_to_row_inner(values: Value, opts: Option<CsvOptions>, ctx: &str) -> mlua::Result<String> {
 	let table = match values {
 		Value::Table(t) => t,
@@ -242,14 +462,45 @@ fn values_to_row_inner(values: Value, opts: Option<CsvOptions>, ctx: &str) -> ml
 
 // endregion: --- Lua Fns
 
+// region:    --- Support
+
+fn get_opt_fn(opts: &Table, name: &str) -> mlua::Result<Option<Function>> {
+	match opts.x_get_value(name) {
+		None => Ok(None),
+		Some(Value::Function(func)) => Ok(Some(func)),
+		Some(other) => Err(Error::custom(format!(
+			"CsvStreamOptions.{name} must be a function, but was '{}'",
+			other.type_name()
+		))
+		.into()),
+	}
+}
+
+/// The record keyed by the headers (the extra cells are ignored), or the list of cells when no headers
+fn csv_row_to_lua(lua: &Lua, headers: Option<&[String]>, row: Vec<String>) -> mlua::Result<Value> {
+	match headers {
+		Some(headers) => {
+			let record = lua.create_table()?;
+			for (key, cell) in headers.iter().zip(row) {
+				record.set(key.as_str(), cell)?;
+			}
+			Ok(Value::Table(record))
+		}
+		None => W(row).into_lua(lua),
+	}
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
 
-	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::_test_support::{SANDBOX_01_WKS_DIR, eval_lua, setup_lua};
 	use crate::script::aip_modules::aip_csv;
+	use simple_fs::SPath;
 	use value_ext::JsonValueExt as _;
 
 	#[tokio::test]
@@ -433,6 +684,56 @@ Jane,25
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_aip_csv_write_and_stream() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_csv::init_module, "csv").await?;
+		let fx_path = ".tmp/test_aip_csv_write_and_stream.csv";
+		let fx_full_path = SPath::new(SANDBOX_01_WKS_DIR).join(fx_path);
+		if fx_full_path.exists() {
+			std::fs::remove_file(fx_full_path.as_std_path())?;
+		}
+		let script = format!(
+			r#"
+aip.csv.write("{fx_path}", {{ {{ id = 1, name = "Alice, A." }}, {{ id = 2, name = "Bob" }} }}, {{ headers = {{"id", "name"}} }})
+aip.csv.write("{fx_path}", {{ {{ name = "Carl", id = 3 }}, {{ 4, "Dan" }} }})
+local names = {{}}
+for name, idx in aip.csv.stream("{fx_path}", {{
+	filter = function(row) return row.id ~= "2" end,
+	map = function(row, idx) return idx .. ":" .. row.name end
+}}) do
+	table.insert(names, name)
+end
+local rows = {{}}
+for row in aip.csv.stream("{fx_path}", {{ has_header = false }}) do
+	table.insert(rows, row)
+end
+return {{ names = names, rows = rows }}
+		"#
+		);
+
+		// -- Exec
+		let res = eval_lua(&lua, &script)?;
+
+		// -- Check
+		assert_eq!(
+			std::fs::read_to_string(fx_full_path.as_std_path())?,
+			"id,name\n1,\"Alice, A.\"\n2,Bob\n3,Carl\n4,Dan\n"
+		);
+		let names: Vec<&str> = res["names"]
+			.as_array()
+			.ok_or("Should have names")?
+			.iter()
+			.filter_map(|v| v.as_str())
+			.collect();
+		assert_eq!(names, vec!["1:Alice, A.", "3:Carl", "4:Dan"]);
+		assert_eq!(res.x_get_str("/rows/0/1")?, "name");
+		assert_eq!(res["rows"].as_array().map(|v| v.len()), Some(5));
+
+		std::fs::remove_file(fx_full_path.as_std_path())?;
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
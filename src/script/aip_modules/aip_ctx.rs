@@ -0,0 +1,156 @@
+//! Defines the `aip.ctx` table, set in the lua engine of each stage.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.ctx` table exposes the identifiers and context of the current run and task
+//! (e.g., for logging, artifact naming, or idempotency keys).
+//!
+//! ### Fields
+//!
+//! ```ts
+//! {
+//!   run_id: number,                       // The run id (in this aip process)
+//!   run_uid: string,                      // The run uid (uuid v7, unique across the aip processes)
+//!   parent_run_id?: number,               // The parent run id (for sub agents, `aip.agent.run`)
+//!   parent_run_uid?: string,
+//!   task_id?: number,                     // The task id (in the task stages: data, ai, output)
+//!   task_uid?: string,
+//!   task_num?: number,                    // The task number in the run (starting at 1)
+//!   stage?: string,                       // e.g., "before_all", "data", "output", "after_all"
+//!   attempt: number,                      // The run attempt (1, then +1 for each run redo)
+//!   task_attempt?: number,                // The task attempt (1, then +1 for each TUI task redo)
+//!   agent_ref: string,                    // The pack reference (e.g., "demo@craft/text") or the agent file path
+//!   agent_name: string,
+//!   agent_path: string,                   // The agent file path
+//!   session_uid: string,
+//!   workspace_dir?: string,               // The workspace dir (absolute)
+//!   workspace_aipack_dir?: string,        // The workspace `.aipack/` dir (absolute)
+//!   pack_dir?: string,                    // The pack dir, for the pack agents (absolute)
+//!   pack_workspace_support_dir?: string,  // The pack workspace support dir, for the pack agents
+//!   pack_base_support_dir?: string,       // The pack base support dir, for the pack agents
+//! }
+//! ```
+
+use crate::Result;
+use crate::model::base::DbBmc as _;
+use crate::model::{ModelManager, RunBmc, RuntimeCtx, TaskBmc};
+use crate::run::Literals;
+use crate::runtime::Runtime;
+use mlua::{Lua, Table};
+use uuid::Uuid;
+
+/// The max task redo chain walked for the `task_attempt` (safety net)
+const MAX_TASK_ATTEMPTS: i64 = 1000;
+
+/// Create the `aip.ctx` table for the given stage context (set by the lua engine in the `aip` table).
+pub fn create_ctx_table(lua: &Lua, runtime: &Runtime, literals: &Literals, rt_ctx: &RuntimeCtx) -> Result<Table> {
+	let table = lua.create_table()?;
+	let mm = runtime.mm();
+
+	// -- Run
+	if let Some(run_id) = rt_ctx.get_run_id(mm)? {
+		table.set("run_id", run_id.as_i64())?;
+	}
+	if let Some(run_uid) = rt_ctx.run_uid() {
+		table.set("run_uid", run_uid.to_string())?;
+	}
+	if let Some(parent_run_uid) = rt_ctx.parent_run_uid() {
+		table.set("parent_run_id", RunBmc::get_id_for_uid(mm, parent_run_uid)?.as_i64())?;
+		table.set("parent_run_uid", parent_run_uid.to_string())?;
+	}
+
+	// -- Task
+	if let Some(task_uid) = rt_ctx.task_uid() {
+		let task_id = TaskBmc::get_id_for_uid(mm, task_uid)?;
+		table.set("task_id", task_id.as_i64())?;
+		table.set("task_uid", task_uid.to_string())?;
+		table.set("task_attempt", task_attempt(mm, task_uid)?)?;
+	}
+	if let Some(task_num) = rt_ctx.task_num() {
+		table.set("task_num", task_num)?;
+	}
+	if let Some(stage) = rt_ctx.stage() {
+		table.set("stage", stage.to_string())?;
+	}
+	table.set("attempt", rt_ctx.flow_redo_run_count().unwrap_or(0) + 1)?;
+
+	// -- Agent & Dirs (from the CTX literals)
+	let agent_path = literals.get("AGENT_FILE_PATH");
+	if let Some(agent_ref) = literals.get("PACK_REF").or(agent_path) {
+		table.set("agent_ref", agent_ref)?;
+	}
+	for (lua_name, literal_name) in [
+		("agent_name", "AGENT_NAME"),
+		("agent_path", "AGENT_FILE_PATH"),
+		("session_uid", "SESSION_UID"),
+		("workspace_dir", "WORKSPACE_DIR"),
+		("workspace_aipack_dir", "WORKSPACE_AIPACK_DIR"),
+		("pack_dir", "PACK_DIR"),
+		("pack_workspace_support_dir", "PACK_WORKSPACE_SUPPORT_DIR"),
+		("pack_base_support_dir", "PACK_BASE_SUPPORT_DIR"),
+	] {
+		if let Some(value) = literals.get(literal_name) {
+			table.set(lua_name, value)?;
+		}
+	}
+
+	Ok(table)
+}
+
+// region:    --- Support
+
+/// The task attempt, 1 + the number of tasks it is a redo of (see `Task.redo_of_task_uid`)
+fn task_attempt(mm: &ModelManager, task_uid: Uuid) -> Result<i64> {
+	let mut attempt = 1;
+	let mut task = TaskBmc::get(mm, TaskBmc::get_id_for_uid(mm, task_uid)?)?;
+	while let Some(redo_of_task_uid) = task.redo_of_task_uid
+		&& attempt < MAX_TASK_ATTEMPTS
+	{
+		// NOTE: The previous attempt might not be in this db (e.g., history), then, it still counts.
+		attempt += 1;
+		let Ok(redo_of_task_id) = TaskBmc::get_id_for_uid(mm, redo_of_task_uid) else {
+			break;
+		};
+		task = TaskBmc::get(mm, redo_of_task_id)?;
+	}
+	Ok(attempt)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_ends_with, run_reflective_agent};
+	use value_ext::JsonValueExt as _;
+
+	#[tokio::test]
+	async fn test_lua_ctx_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let script = r#"
+return aip.ctx
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent(script, None).await?;
+
+		// -- Check
+		assert_eq!(res.x_get_i64("run_id")?, 0);
+		assert_eq!(res.x_get_i64("task_id")?, 0);
+		assert_eq!(res.x_get_i64("attempt")?, 1);
+		assert_eq!(res.x_get_i64("task_attempt")?, 1);
+		assert_eq!(res.x_get_str("agent_name")?, "inline-agent");
+		assert_ends_with(res.x_get_str("agent_ref")?, "mock-reflective-agent.aip");
+		assert_ends_with(res.x_get_str("workspace_dir")?, "tests-data/sandbox-01");
+		assert!(res.x_get_str("pack_dir").is_err(), "not a pack agent");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -17,6 +17,7 @@
 use crate::Result;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use crate::support::editor;
 use mlua::{IntoLua, Lua, Table, Value};
 
@@ -99,6 +100,7 @@ fn open_file(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<Value>
 
 	// Resolve the path similar to aip.file.load
 	let full_path = dir_context.resolve_path(runtime.session(), (&path).into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.editor.open_file")?;
 
 	// Check if file exists
 	if !full_path.is_file() {
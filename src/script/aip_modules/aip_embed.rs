@@ -0,0 +1,209 @@
+//! Defines the `aip.embed` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.embed` module exposes functions to create text embeddings with the AI providers
+//! (via the same genai client and API keys as the agent AI calls), e.g., for retrieval-augmented agents.
+//!
+//! ### Functions
+//!
+//! - `aip.embed.generate(texts: string | string[], options?: EmbedOptions): EmbedResult`
+
+use crate::model::RuntimeCtx;
+use crate::run::{DEFAULT_EMBED_MODEL, exec_embed, price_it};
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let generate_fn = lua.create_function(move |lua, args| embed_generate(lua, &rt, args))?;
+
+	table.set("generate", generate_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Creates the embedding vectors of one or more texts, in one provider call.
+///
+/// ```lua
+/// -- API Signature
+/// aip.embed.generate(texts: string | string[], options?: EmbedOptions): EmbedResult
+/// ```
+///
+/// The embedding token usage (and cost, when the model pricing is known) is added to the current task
+/// (or to the run, when called outside of a task, e.g., in `# Before All`), and included in the run total cost.
+///
+/// ### Arguments
+///
+/// - `texts: string | string[]`: The text, or the list of texts, to embed.
+/// - `options?: EmbedOptions`
+///   ```ts
+///   {
+///     model?: string,       // The embedding model (default "text-embedding-3-small"), e.g., "gemini-embedding-001"
+///     dimensions?: integer, // The output dimensions (when supported by the model)
+///   }
+///   ```
+///
+/// ### Returns (EmbedResult)
+///
+/// ```ts
+/// {
+///   vectors: number[][],  // One vector per text, in the order of `texts`
+///   model: string,        // The provider model name
+///   dimensions: integer,  // The dimensions of the vectors (0 if no texts)
+///   usage: {
+///     prompt_tokens: integer,
+///     total_tokens: integer,
+///   },
+///   price_usd?: number,   // The estimated cost (if the model pricing is known)
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local res = aip.embed.generate({"first chunk", "second chunk"}, {model = "text-embedding-3-small"})
+/// for i, vector in ipairs(res.vectors) do
+///   -- store the vector with its chunk
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if `texts` is not a string or a list of strings, or if the provider call fails.
+fn embed_generate(lua: &Lua, runtime: &Runtime, (texts, options): (Value, Option<Value>)) -> mlua::Result<Value> {
+	let texts = texts_from_value(texts)?;
+	let options = options.unwrap_or(Value::Nil);
+	let model = options.x_get_string("model").unwrap_or_else(|| DEFAULT_EMBED_MODEL.to_string());
+	let dimensions = options.x_get_i64("dimensions").map(|dim| dim.max(1) as usize);
+
+	let res = lua.create_table()?;
+	let usage = lua.create_table()?;
+
+	// -- No texts, no provider call
+	if texts.is_empty() {
+		res.set("vectors", lua.create_table()?)?;
+		res.set("model", model)?;
+		res.set("dimensions", 0)?;
+		usage.set("prompt_tokens", 0)?;
+		usage.set("total_tokens", 0)?;
+		res.set("usage", usage)?;
+		return Ok(Value::Table(res));
+	}
+
+	let text_count = texts.len();
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let embed_res = tokio::task::block_in_place(|| {
+		rt.block_on(async { exec_embed(runtime.genai_client(), &model, texts, dimensions).await })
+	})?;
+
+	// -- Price & Rt Rec
+	let provider = embed_res.model_iden.adapter_kind.as_lower_str();
+	let price = price_it(provider, &embed_res.model_iden.model_name, &embed_res.usage).map(|price| price.cost);
+	let prompt_tokens = embed_res.usage.prompt_tokens.unwrap_or_default() as i64;
+	let total_tokens = embed_res.usage.total_tokens.map(|v| v as i64).unwrap_or(prompt_tokens);
+
+	rec_embed_usage(lua, runtime, total_tokens, price)?;
+
+	// -- Build the result
+	let mut embeddings = embed_res.embeddings;
+	embeddings.sort_by_key(|embedding| embedding.index);
+	if embeddings.len() != text_count {
+		return Err(Error::custom(format!(
+			"aip.embed.generate - expected {text_count} embeddings, but the provider returned {}",
+			embeddings.len()
+		))
+		.into());
+	}
+	let dimensions = embeddings.first().map(|embedding| embedding.vector.len()).unwrap_or_default();
+	let vectors = lua.create_table()?;
+	for embedding in embeddings {
+		vectors.push(lua.create_sequence_from(embedding.vector)?)?;
+	}
+
+	res.set("vectors", vectors)?;
+	res.set("model", embed_res.provider_model_iden.model_name.to_string())?;
+	res.set("dimensions", dimensions)?;
+	usage.set("prompt_tokens", prompt_tokens)?;
+	usage.set("total_tokens", total_tokens)?;
+	res.set("usage", usage)?;
+	if let Some(price) = price {
+		res.set("price_usd", price)?;
+	}
+
+	Ok(Value::Table(res))
+}
+
+// region:    --- Support
+
+/// Add the embedding usage to the current task (or run), when in a run context.
+fn rec_embed_usage(lua: &Lua, runtime: &Runtime, tokens: i64, price: Option<f64>) -> Result<()> {
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	let mm = runtime.mm();
+	if let Some(run_id) = ctx.get_run_id(mm)? {
+		let task_id = ctx.get_task_id(mm)?;
+		runtime.rt_model().add_embed_usage(run_id, task_id, tokens, price)?;
+	}
+	Ok(())
+}
+
+fn texts_from_value(value: Value) -> mlua::Result<Vec<String>> {
+	let err = || Error::custom("aip.embed.generate - 'texts' must be a string or a list of strings");
+	match value {
+		Value::String(text) => Ok(vec![text.to_string_lossy()]),
+		Value::Table(table) => {
+			let mut texts = Vec::new();
+			for text in table.sequence_values::<Value>() {
+				match text? {
+					Value::String(text) => texts.push(text.to_string_lossy()),
+					_ => return Err(err().into()),
+				}
+			}
+			Ok(texts)
+		}
+		_ => Err(err().into()),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_embed;
+
+	#[tokio::test]
+	async fn test_lua_embed_generate_empty_and_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_embed::init_module, "embed").await?;
+
+		// -- Exec
+		let res = eval_lua(
+			&lua,
+			r#"return aip.embed.generate({}, {model = "text-embedding-3-large"})"#,
+		)?;
+
+		// -- Check
+		assert_eq!(res["dimensions"].as_i64(), Some(0));
+		assert_eq!(res["model"].as_str(), Some("text-embedding-3-large"));
+		assert_eq!(res["usage"]["total_tokens"].as_i64(), Some(0));
+		assert!(eval_lua(&lua, "return aip.embed.generate(123)").is_err());
+		assert!(eval_lua(&lua, r#"return aip.embed.generate({"ok", 12})"#).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,262 @@
+//! Defines the `aip.encode` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.encode` module encodes and decodes the binary formats of the services, MessagePack and Protobuf
+//! (with the message types of a `protoc` descriptor set). The bytes are Lua strings (as in `aip.blob`).
+//!
+//! ### Functions
+//!
+//! - `aip.encode.msgpack_encode(value: any): string`
+//! - `aip.encode.msgpack_decode(bytes: string): any`
+//! - `aip.encode.proto_encode(descriptor_path: string, message_type: string, value: table): string`
+//! - `aip.encode.proto_decode(descriptor_path: string, message_type: string, bytes: string): table`
+
+use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
+use crate::script::{lua_value_to_serde_value, serde_value_to_lua_value};
+use crate::support::encode::{ProtoDescriptors, msgpack_decode, msgpack_encode, proto_decode, proto_encode};
+use crate::{Error, Result};
+use mlua::{Lua, LuaString, Table, Value};
+use simple_fs::SPath;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let msgpack_encode_fn = lua.create_function(encode_msgpack_encode)?;
+	let msgpack_decode_fn = lua.create_function(encode_msgpack_decode)?;
+
+	let rt = runtime.clone();
+	let proto_encode_fn = lua.create_function(
+		move |lua, (descriptor_path, message_type, value): (String, String, Value)| {
+			encode_proto_encode(lua, &rt, descriptor_path, message_type, value)
+		},
+	)?;
+
+	let rt = runtime.clone();
+	let proto_decode_fn = lua.create_function(
+		move |lua, (descriptor_path, message_type, bytes): (String, String, LuaString)| {
+			encode_proto_decode(lua, &rt, descriptor_path, message_type, bytes)
+		},
+	)?;
+
+	table.set("msgpack_encode", msgpack_encode_fn)?;
+	table.set("msgpack_decode", msgpack_decode_fn)?;
+	table.set("proto_encode", proto_encode_fn)?;
+	table.set("proto_decode", proto_decode_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Encodes the value to MessagePack.
+///
+/// ```lua
+/// -- API Signature
+/// aip.encode.msgpack_encode(value: any): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `value: any`: The value (table, string, number, boolean, or nil). The tables are encoded as their JSON
+///   (the list tables as arrays, the others as maps).
+///
+/// ### Returns
+///
+/// - `string`: The MessagePack bytes (as a Lua string, e.g., for `aip.blob.put` or `aip.web.post`).
+///
+/// ### Example
+///
+/// ```lua
+/// local bytes = aip.encode.msgpack_encode({ id = 42, tags = { "a", "b" } })
+/// print(#bytes)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the value cannot be converted (e.g., a function).
+fn encode_msgpack_encode(lua: &Lua, value: Value) -> mlua::Result<Value> {
+	let value = lua_value_to_serde_value(value)?;
+	let bytes = msgpack_encode(&value).map_err(|err| Error::custom(format!("aip.encode.msgpack_encode - {err}")))?;
+	Ok(Value::String(lua.create_string(&bytes)?))
+}
+
+/// ## Lua Documentation
+///
+/// Decodes the MessagePack bytes.
+///
+/// ```lua
+/// -- API Signature
+/// aip.encode.msgpack_decode(bytes: string): any
+/// ```
+///
+/// ### Arguments
+///
+/// - `bytes: string`: The MessagePack bytes (as a Lua string).
+///
+/// ### Returns
+///
+/// - `any`: The decoded value. The `bin` values are strings (or lists of byte numbers when not valid UTF-8),
+///   and the non-string map keys are their string (e.g., `"1"`).
+///
+/// ### Example
+///
+/// ```lua
+/// local value = aip.encode.msgpack_decode(bytes)
+/// print(value.id)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the bytes are not valid MessagePack, or have `ext` values (not supported).
+fn encode_msgpack_decode(lua: &Lua, bytes: LuaString) -> mlua::Result<Value> {
+	let value =
+		msgpack_decode(&bytes.as_bytes()).map_err(|err| Error::custom(format!("aip.encode.msgpack_decode - {err}")))?;
+	Ok(serde_value_to_lua_value(lua, value)?)
+}
+
+/// ## Lua Documentation
+///
+/// Encodes the value to Protobuf, as the message type of the descriptor set.
+///
+/// ```lua
+/// -- API Signature
+/// aip.encode.proto_encode(descriptor_path: string, message_type: string, value: table): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `descriptor_path: string`: The descriptor set file (relative to the workspace, or pack ref), generated with
+///   `protoc --descriptor_set_out=app.desc --include_imports app.proto`.
+/// - `message_type: string`: The full name of the message type (e.g., `"acme.v1.User"`).
+/// - `value: table`: The message, keyed by the field names (or their JSON names). The 64 bit integers can be
+///   numbers or strings, the enums their value names (or numbers), the `bytes` base64 strings, and the maps tables.
+///
+/// ### Returns
+///
+/// - `string`: The Protobuf bytes (as a Lua string).
+///
+/// ### Example
+///
+/// ```lua
+/// local bytes = aip.encode.proto_encode("proto/acme.desc", "acme.v1.User", {
+///   name = "Jen", role = "ROLE_ADMIN", scores = { 1, 2 }
+/// })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the descriptor set cannot be read, the message type is not found, or a field is unknown
+/// or has a value not matching its type.
+fn encode_proto_encode(
+	lua: &Lua,
+	runtime: &Runtime,
+	descriptor_path: String,
+	message_type: String,
+	value: Value,
+) -> mlua::Result<Value> {
+	let descriptors = load_descriptors(lua, runtime, &descriptor_path, "aip.encode.proto_encode")?;
+	let value = lua_value_to_serde_value(value)?;
+	let bytes = proto_encode(&descriptors, &message_type, &value)
+		.map_err(|err| Error::custom(format!("aip.encode.proto_encode - {err}")))?;
+	Ok(Value::String(lua.create_string(&bytes)?))
+}
+
+/// ## Lua Documentation
+///
+/// Decodes the Protobuf bytes, as the message type of the descriptor set.
+///
+/// ```lua
+/// -- API Signature
+/// aip.encode.proto_decode(descriptor_path: string, message_type: string, bytes: string): table
+/// ```
+///
+/// ### Arguments
+///
+/// - `descriptor_path: string`: The descriptor set file (see `aip.encode.proto_encode`).
+/// - `message_type: string`: The full name of the message type (e.g., `"acme.v1.User"`).
+/// - `bytes: string`: The Protobuf bytes (as a Lua string).
+///
+/// ### Returns
+///
+/// - `table`: The message, keyed by the field names. The enums are their value names, the `bytes` base64 strings,
+///   and the maps tables. The fields not in the bytes are absent (no default values), and the unknown fields are skipped.
+///
+/// ### Example
+///
+/// ```lua
+/// local user = aip.encode.proto_decode("proto/acme.desc", "acme.v1.User", bytes)
+/// print(user.name, user.role)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the descriptor set cannot be read, the message type is not found, or the bytes are not
+/// a valid message of this type.
+fn encode_proto_decode(
+	lua: &Lua,
+	runtime: &Runtime,
+	descriptor_path: String,
+	message_type: String,
+	bytes: LuaString,
+) -> mlua::Result<Value> {
+	let descriptors = load_descriptors(lua, runtime, &descriptor_path, "aip.encode.proto_decode")?;
+	let value = proto_decode(&descriptors, &message_type, &bytes.as_bytes())
+		.map_err(|err| Error::custom(format!("aip.encode.proto_decode - {err}")))?;
+	Ok(serde_value_to_lua_value(lua, value)?)
+}
+
+// region:    --- Support
+
+fn load_descriptors(lua: &Lua, runtime: &Runtime, descriptor_path: &str, fn_name: &str) -> Result<ProtoDescriptors> {
+	let full_path = runtime.resolve_path_default(SPath::new(descriptor_path), None)?;
+	check_access_read(lua, &full_path, fn_name)?;
+
+	let bytes = std::fs::read(full_path.as_std_path()).map_err(|err| {
+		Error::cc(
+			format!("{fn_name} - cannot read descriptor set '{descriptor_path}'"),
+			err,
+		)
+	})?;
+	ProtoDescriptors::from_descriptor_set(&bytes)
+		.map_err(|err| Error::custom(format!("{fn_name} - invalid descriptor set '{descriptor_path}'. {err}")))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_encode;
+
+	#[tokio::test]
+	async fn test_lua_encode_msgpack_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_encode::init_module, "encode").await?;
+		let script = r#"
+local bytes = aip.encode.msgpack_encode({ name = "acme", count = 300, tags = { "a", "b" } })
+local value = aip.encode.msgpack_decode(bytes)
+return { len = #bytes, name = value.name, count = value.count, tag = value.tags[2] }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["name"].as_str(), Some("acme"));
+		assert_eq!(res["count"].as_i64(), Some(300));
+		assert_eq!(res["tag"].as_str(), Some("b"));
+		assert_eq!(res["len"].as_i64(), Some(30));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
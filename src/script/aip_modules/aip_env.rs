@@ -0,0 +1,124 @@
+//! Defines the `aip.env` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.env` module exposes the environment variables of the run,
+//! from the agent option `env` (the ones injected in `aip.cmd.exec`), or from the process environment.
+//!
+//! ### Functions
+//!
+//! - `aip.env.get(name: string, default?: string): string | nil`
+
+use crate::Result;
+use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_pack_capability;
+use crate::types::{PackCapability, RunEnv};
+use mlua::{Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let get_fn = lua.create_function(env_get)?;
+
+	table.set("get", get_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Returns the value of an environment variable of the run.
+///
+/// ```lua
+/// -- API Signature
+/// aip.env.get(name: string, default?: string): string | nil
+/// ```
+///
+/// The variables of the agent option `env` come first (including their secrets, which are masked
+/// in the logs, store records, and TUI output). Otherwise, the variable is read from the process environment,
+/// which requires the `secrets` capability for the installed packs (as `os.getenv`).
+///
+/// ### Arguments
+///
+/// - `name: string`: The variable name (e.g., `"APP_ENV"`).
+/// - `default?: string`: The value returned when the variable is not set.
+///
+/// ### Returns
+///
+/// The variable value, the `default`, or `nil`.
+///
+/// ### Example
+///
+/// ```lua
+/// -- with the agent options: env = { APP_ENV = "staging", GITHUB_TOKEN = { secret = "GITHUB_TOKEN" } }
+/// local app_env = aip.env.get("APP_ENV", "dev")
+/// local token = aip.env.get("GITHUB_TOKEN")
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the variable is not in the agent option `env`, and the installed pack
+/// does not have the `secrets` capability.
+fn env_get(lua: &Lua, (name, default): (String, Option<String>)) -> mlua::Result<Value> {
+	let run_env_value = lua
+		.app_data_ref::<RunEnv>()
+		.and_then(|run_env| run_env.get(&name).map(str::to_string));
+
+	let value = match run_env_value {
+		Some(value) => Some(value),
+		None => {
+			check_pack_capability(lua, PackCapability::Secrets, "aip.env.get (process environment)")?;
+			std::env::var(&name).ok()
+		}
+	};
+
+	match value.or(default) {
+		Some(value) => Ok(Value::String(lua.create_string(&value)?)),
+		None => Ok(Value::Nil),
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::agent::EnvValue;
+	use crate::script::aip_modules::aip_env;
+	use crate::types::RunEnv;
+	use std::collections::HashMap;
+
+	#[tokio::test]
+	async fn test_lua_env_get() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_env::init_module, "env").await?;
+		let run_env = RunEnv::new(
+			&HashMap::from([("APP_ENV".to_string(), EnvValue::Value("staging".to_string()))]),
+			None,
+		)?;
+		lua.set_app_data(run_env);
+		let script = r#"
+return {
+	app_env = aip.env.get("APP_ENV"),
+	missing = aip.env.get("AIPACK_TEST_ENV_NOT_SET"),
+	default = aip.env.get("AIPACK_TEST_ENV_NOT_SET", "dev"),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["app_env"].as_str(), Some("staging"));
+		assert!(res.get("missing").is_none());
+		assert_eq!(res["default"].as_str(), Some("dev"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,178 @@
+//! Defines the `aip.feed` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.feed` module parses the RSS (2.0 and 1.0/RDF) and Atom feeds, with their entry dates normalized.
+//!
+//! ### Functions
+//!
+//! - `aip.feed.parse(url_or_text: string, options?: WebOptions): Feed`
+
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_pack_capability;
+use crate::script::serde_value_to_lua_value;
+use crate::support::feed::parse_feed;
+use crate::types::{PackCapability, WebOptions};
+use crate::{Error, Result};
+use mlua::{FromLua as _, Lua, Table, Value};
+use reqwest::{Client, header};
+
+pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let parse_fn = lua.create_function(feed_parse)?;
+
+	table.set("parse", parse_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Parses a RSS or Atom feed, from its URL (fetched) or its XML text.
+///
+/// ```lua
+/// -- API Signature
+/// aip.feed.parse(url_or_text: string, options?: WebOptions): Feed
+/// ```
+///
+/// ### Arguments
+///
+/// - `url_or_text: string`: The feed URL (`http://` or `https://`, requires the `net` capability for the installed packs),
+///   or the feed XML text.
+/// - `options?: WebOptions`: The web options of the fetch (e.g., `headers`, `timeout_ms`).
+///
+/// ### Returns (Feed)
+///
+/// The dates are normalized to RFC 3339 UTC (e.g., `"2024-06-03T14:05:00Z"`), `date_raw` is the date as in the feed.
+///
+/// ```ts
+/// {
+///   kind: "rss" | "atom",
+///   title?: string,
+///   link?: string,
+///   description?: string,
+///   updated?: string,
+///   entries: {
+///     id?: string,       // RSS guid, or Atom id
+///     title?: string,
+///     link?: string,
+///     date?: string,     // The published date (or updated), normalized
+///     date_raw?: string,
+///     author?: string,
+///     summary?: string,  // RSS description (when it has a content:encoded), or Atom summary
+///     content?: string,  // RSS content:encoded (or description), or Atom content (or summary)
+///   }[]
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local feed = aip.feed.parse("https://github.com/aipack-ai/aipack/releases.atom")
+/// for _, entry in ipairs(feed.entries) do
+///   if entry.date and entry.date > "2024-06-01" then
+///     print(entry.date .. " - " .. entry.title .. " (" .. entry.link .. ")")
+///   end
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the URL cannot be fetched (or a non-2xx status), or the content is not a RSS or Atom feed.
+fn feed_parse(lua: &Lua, (url_or_text, options): (String, Option<Value>)) -> mlua::Result<Value> {
+	let url_or_text_trimmed = url_or_text.trim();
+	let is_url = url_or_text_trimmed.starts_with("http://") || url_or_text_trimmed.starts_with("https://");
+
+	let xml = if is_url {
+		check_pack_capability(lua, PackCapability::Net, "aip.feed.parse")?;
+		fetch_feed(lua, url_or_text_trimmed, options.unwrap_or(Value::Nil))?
+	} else {
+		url_or_text
+	};
+
+	let feed = parse_feed(&xml)?;
+	let feed = serde_json::to_value(feed).map_err(|err| Error::custom(format!("aip.feed.parse - {err}")))?;
+
+	Ok(serde_value_to_lua_value(lua, feed)?)
+}
+
+// region:    --- Support
+
+fn fetch_feed(lua: &Lua, url: &str, options: Value) -> Result<String> {
+	let web_opts = WebOptions::from_lua(options, lua)?;
+	let client = web_opts
+		.apply_to_reqwest_builder(Client::builder())
+		.build()
+		.map_err(Error::from)?;
+
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let xml = tokio::task::block_in_place(|| {
+		rt.block_on(async {
+			let response = client
+				.get(url)
+				.header(
+					header::ACCEPT,
+					"application/rss+xml, application/atom+xml, application/xml;q=0.9, text/xml;q=0.8, */*;q=0.5",
+				)
+				.send()
+				.await
+				.map_err(|err| Error::cc(format!("aip.feed.parse - cannot fetch '{url}'"), err))?;
+			let status = response.status();
+			if !status.is_success() {
+				return Err(Error::custom(format!(
+					"aip.feed.parse - cannot fetch '{url}' (status {status})"
+				)));
+			}
+			response
+				.text()
+				.await
+				.map_err(|err| Error::cc(format!("aip.feed.parse - cannot read the response of '{url}'"), err))
+		})
+	})?;
+
+	get_hub().publish_sync(format!("-> lua feed OK ({url}) "));
+
+	Ok(xml)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_feed;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_feed_parse_text() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_feed::init_module, "feed").await?;
+		let script = r#"
+local feed = aip.feed.parse([[<rss version="2.0"><channel><title>Acme</title>
+  <item><title>v1.0.0</title><link>https://acme.com/v1</link><pubDate>Sat, 01 Jun 2024 10:00:00 +0200</pubDate></item>
+</channel></rss>]])
+local entry = feed.entries[1]
+return { kind = feed.kind, title = entry.title, link = entry.link, date = entry.date }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["kind"].as_str(), Some("rss"));
+		assert_eq!(res["title"].as_str(), Some("v1.0.0"));
+		assert_eq!(res["link"].as_str(), Some("https://acme.com/v1"));
+		assert_eq!(res["date"].as_str(), Some("2024-06-01T08:00:00Z"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -7,43 +7,54 @@
 //!
 //! ### Functions
 //!
-//! - `aip.file.save_changes(rel_path: string, changes: string): FileInfo, ChangesInfo`
+//! - `aip.file.save_changes(rel_path: string, changes: string | {search: string, replace: string}[]): FileInfo, ChangesInfo`
 //!
-//! The helper applies an *aip change-block* to a file, saves it, and returns
-//! the resulting [`FileInfo`].
+//! The helper applies *aip change-blocks*, a unified diff, or a list of search/replace edits
+//! to a file, saves it (atomically), and returns the resulting [`FileInfo`] and [`ChangesInfo`].
 //!
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::hub::get_hub;
 use crate::runtime::Runtime;
-use crate::script::aip_modules::support::check_access_write;
-use crate::support::text;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{check_access_write, check_confirm_write};
+use crate::support::text::{self, ChangeEdit};
 use crate::types::{ChangesInfo, FileInfo};
 use mlua::{IntoLua, Lua, Value};
 use simple_fs::{SPath, ensure_file_dir};
-use std::fs::write;
 
 /// ## Lua Documentation
 ///
-/// Applies a set of changes to a file and saves it, returning [`FileInfo`].
+/// Applies a set of changes to a file and saves it, returning [`FileInfo`] and [`ChangesInfo`].
+///
+/// The changes can be:
+/// - A string with `<<<<<<< SEARCH` / `=======` / `>>>>>>> REPLACE` change blocks.
+/// - A string with a single file unified diff (starting with `diff --git`, `--- `, or `@@`).
+///   Each `@@` hunk is located by its content (context and `-` lines), not by its line numbers.
+/// - A list of `{search, replace}` edits.
+/// - Any other string, which replaces the whole file content.
+///
+/// The changes (blocks, hunks, or edits) are applied in order. The ones that cannot be applied are
+/// reported in `ChangesInfo.failed_changes` (with their 1-based `idx`), and the others are still applied.
+/// The file is written atomically (temporary file then rename), so it is never left partially written.
 ///
 /// This function is typically used with `aip.rust.find_items` which can generate
 /// a changes string.
 ///
 /// ```lua
 /// -- API Signature
-/// aip.file.save_changes(rel_path: string, changes: string): FileInfo, ChangesInfo
+/// aip.file.save_changes(rel_path: string, changes: string | {search: string, replace: string}[]): FileInfo, ChangesInfo
 /// ```
 ///
 /// ### Arguments
 ///
 /// - `rel_path: string` - The path to the file to be changed.
-/// - `changes: string` - The change block string.
+/// - `changes: string | {search: string, replace: string}[]` - The change blocks, unified diff, or list of edits.
 ///
 /// ### Returns
 ///
 /// - `FileInfo` - A [`FileInfo`] object for the saved file.
-/// - `ChangesInfo` - A table containing `changed_count` and `failed_changes`.
+/// - `ChangesInfo` - A table containing `changed_count` and `failed_changes` (`{idx, search, replace, reason}[]`).
 ///
 /// ### Example
 ///
@@ -64,13 +75,32 @@ use std::fs::write;
 /// if changes then
 ///   aip.file.save_changes("src/main.rs", changes)
 /// end
+///
+/// -- With a unified diff
+/// local _, info = aip.file.save_changes("src/main.rs", [[
+/// @@ -1,3 +1,3 @@
+///  fn main() {
+/// -    println!("Hello");
+/// +    println!("Hello, world!");
+///  }
+/// ]])
+///
+/// -- With a list of edits
+/// local _, info = aip.file.save_changes("src/main.rs", {
+///   { search = "Hello", replace = "Hello, world!" },
+/// })
+/// if info.failed_changes then
+///   print("Failed edit #" .. info.failed_changes[1].idx)
+/// end
 /// ```
 pub(super) fn file_save_changes(
 	lua: &Lua,
 	runtime: &Runtime,
 	rel_path: String,
-	changes: String,
+	changes: Value,
 ) -> mlua::Result<(Value, Value)> {
+	let changes = LuaChanges::from_lua_value(changes)?;
+
 	let dir_context = runtime.dir_context();
 	let full_path = dir_context.resolve_path(runtime.session(), (&rel_path).into(), PathResolver::WksDir, None)?;
 	let lock_handle = runtime.file_write_manager().lock_for_path(&full_path);
@@ -79,24 +109,37 @@ pub(super) fn file_save_changes(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.save requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	ensure_file_dir(&full_path).map_err(Error::from)?;
 
-	let (content, apply_changes_info) = if full_path.exists() {
-		let content = simple_fs::read_to_string(&full_path).map_err(Error::custom)?;
-		text::apply_changes(content, changes)?
+	let original = if full_path.exists() {
+		Some(simple_fs::read_to_string(&full_path).map_err(Error::custom)?)
 	} else {
-		(
+		None
+	};
+
+	let (content, apply_changes_info) = match (original, changes) {
+		(Some(original), LuaChanges::Text(changes)) if text::is_udiff(&changes) => {
+			text::apply_udiff(original, &changes)?
+		}
+		(Some(original), LuaChanges::Text(changes)) => text::apply_changes(original, changes)?,
+		// NOTE: For a new file, a unified diff can only be a file creation (`@@ -0,0 ...`)
+		(None, LuaChanges::Text(changes)) if text::is_udiff(&changes) => text::apply_udiff("", &changes)?,
+		(None, LuaChanges::Text(changes)) => (
 			changes,
 			ChangesInfo {
 				changed_count: 1,
 				failed_changes: Vec::new(),
 			},
-		)
+		),
+		(original, LuaChanges::Edits(edits)) => text::apply_edits(original.unwrap_or_default(), edits),
 	};
 
-	write(&full_path, content).map_err(|err| Error::custom(format!("Fail to save file {rel_path}.\nCause {err}")))?;
+	check_confirm_write(lua, "aip.file.save_changes", &full_path, wks_dir, &content, false)?;
+
+	write_atomic(&full_path, &content)
+		.map_err(|err| Error::custom(format!("Fail to save file {rel_path}.\nCause {err}")))?;
 
 	let rel_path_for_hub = full_path.diff(wks_dir).unwrap_or_else(|| full_path.clone());
 	get_hub().publish_sync(format!("-> Lua aip.file.save called on: {rel_path_for_hub}"));
@@ -104,3 +147,50 @@ pub(super) fn file_save_changes(
 	let file_info = FileInfo::new(runtime.dir_context(), SPath::new(rel_path), &full_path);
 	Ok((file_info.into_lua(lua)?, apply_changes_info.into_lua(lua)?))
 }
+
+// region:    --- Support
+
+enum LuaChanges {
+	Text(String),
+	Edits(Vec<ChangeEdit>),
+}
+
+impl LuaChanges {
+	fn from_lua_value(value: Value) -> mlua::Result<Self> {
+		match value {
+			Value::String(s) => Ok(LuaChanges::Text(s.to_string_lossy())),
+			Value::Table(table) => {
+				let mut edits = Vec::new();
+				for (idx, item) in table.sequence_values::<Value>().enumerate() {
+					let item = item?;
+					let (Some(search), Some(replace)) = (item.x_get_string("search"), item.x_get_string("replace"))
+					else {
+						return Err(Error::custom(format!(
+							"aip.file.save_changes - edit {} must be a {{search: string, replace: string}} table",
+							idx + 1
+						))
+						.into());
+					};
+					edits.push(ChangeEdit { search, replace });
+				}
+				Ok(LuaChanges::Edits(edits))
+			}
+			other => Err(Error::custom(format!(
+				"aip.file.save_changes - changes must be a string or a list of {{search, replace}} edits, but was a '{}'",
+				other.type_name()
+			))
+			.into()),
+		}
+	}
+}
+
+/// Write to a temporary sibling file, and then rename it to the target file.
+fn write_atomic(full_path: &SPath, content: &str) -> std::io::Result<()> {
+	let tmp_path = full_path.new_sibling(format!(".{}.aip-tmp", full_path.name()));
+	std::fs::write(&tmp_path, content)?;
+	std::fs::rename(&tmp_path, full_path).inspect_err(|_| {
+		let _ = std::fs::remove_file(&tmp_path);
+	})
+}
+
+// endregion: --- Support
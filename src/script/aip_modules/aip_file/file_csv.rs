@@ -19,7 +19,7 @@ use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
 use crate::script::aip_modules::aip_csv::{lua_matrix_to_rows, lua_value_to_csv_string};
-use crate::script::aip_modules::support::check_access_write;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
 use crate::script::support::{collect_string_sequence, expect_table};
 use crate::support::W;
 use crate::types::{CsvContent, CsvOptions, FileInfo};
@@ -58,6 +58,7 @@ pub(super) fn file_load_csv_headers(lua: &Lua, runtime: &Runtime, path: String)
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_csv_headers")?;
 
 	let headers = crate::support::csvs::load_csv_headers(&full_path, None).map_err(|e| {
 		Error::from(format!(
@@ -109,6 +110,7 @@ pub(super) fn file_load_csv(lua: &Lua, runtime: &Runtime, path: String, options:
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_csv")?;
 
 	let opts = match options {
 		Some(v) => CsvOptions::from_lua(v, lua)?,
@@ -149,7 +151,7 @@ pub(super) fn file_save_as_csv(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.save_as_csv requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	let opts = match options {
 		Some(v) => Some(CsvOptions::from_lua(v, lua)?),
@@ -197,7 +199,7 @@ pub(super) fn file_save_records_as_csv(
 	let wks_dir =
 		dir_context.try_wks_dir_with_err_ctx("aip.file.save_records_as_csv requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	let records_tbl = expect_table(records, "aip.file.save_records_as_csv", "records")?;
 
@@ -264,7 +266,7 @@ pub(super) fn file_append_csv_rows(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.append_csv_rows requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	let rows = match value_lists {
 		Value::Table(t) => lua_matrix_to_rows(t)?,
@@ -318,7 +320,7 @@ pub(super) fn file_append_csv_row(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.append_csv_row requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	let row = match values {
 		Value::Table(t) => {
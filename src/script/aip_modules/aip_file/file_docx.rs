@@ -8,6 +8,7 @@
 //!
 //! - `aip.file.save_docx_to_md(docx_path: string, dest?: DestOptions): FileInfo`
 //! - `aip.file.load_docx_as_md(docx_path: string): string`
+//! - `aip.file.save_docx(path: string, content: string | DocxBlock[]): FileInfo`
 //!
 //! This helper loads a DOCX file, converts it to Markdown, saves the result,
 //! and returns the [`FileInfo`] describing the newly-created file.
@@ -16,8 +17,10 @@ use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
 use crate::script::aip_modules;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
+use crate::support::docx::{DocxBlock, DocxRun, md_to_docx_blocks, write_docx};
 use crate::types::FileInfo;
-use mlua::{IntoLua, Lua, Value};
+use mlua::{IntoLua, Lua, Table, Value};
 use simple_fs::SPath;
 use std::fs::write;
 use std::path::Path;
@@ -85,6 +88,7 @@ pub(super) fn file_save_docx_to_md(
 	// -- resolve source path
 	let rel_docx = SPath::new(docx_path.clone());
 	let full_docx = dir_context.resolve_path(runtime.session(), rel_docx.clone(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_docx, "aip.file.save_docx_to_md")?;
 
 	// -- convert to Markdown using support::docx
 	let md_content = crate::support::docx::docx_convert(Path::new(full_docx.as_str())).map_err(|e| {
@@ -135,6 +139,7 @@ pub(super) fn file_load_docx_as_md(lua: &Lua, runtime: &Runtime, docx_path: Stri
 	// -- resolve source path
 	let rel_docx = SPath::new(docx_path.clone());
 	let full_docx = dir_context.resolve_path(runtime.session(), rel_docx, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_docx, "aip.file.load_docx_as_md")?;
 
 	// -- convert to Markdown using support::docx
 	let md_content = crate::support::docx::docx_convert(Path::new(full_docx.as_str())).map_err(|e| {
@@ -145,3 +150,222 @@ pub(super) fn file_load_docx_as_md(lua: &Lua, runtime: &Runtime, docx_path: Stri
 
 	md_content.into_lua(lua)
 }
+
+/// ## Lua Documentation
+///
+/// Saves markdown, or a list of structured blocks, as a DOCX (Word) file.
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.save_docx(path: string, content: string | DocxBlock[]): FileInfo
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string`: Path of the DOCX file to write (overwritten), relative to the workspace root.
+/// - `content: string | DocxBlock[]`:
+///   - `string`: Markdown (headings, paragraphs, bold/italic/strikethrough, inline code, links,
+///     nested lists, block quotes, code blocks, and tables).
+///   - `DocxBlock[]`: The blocks, in order (a plain string is a paragraph):
+///     - `{ type = "heading", text: string, level?: integer }` (`level` from 1 to 6, default 1)
+///     - `{ type = "paragraph", text: string }`
+///     - `{ type = "list", items: string[], ordered?: boolean }`
+///     - `{ type = "table", headers?: string[], rows: string[][] }`
+///     - `{ type = "code", text: string }`
+///     - `{ type = "quote", text: string }`
+///
+/// ### Returns
+///
+/// - `FileInfo`: Metadata about the written DOCX file.
+///
+/// ### Example
+///
+/// ```lua
+/// aip.file.save_docx("reports/summary.docx", "# Summary\n\nAll **12** checks passed.")
+///
+/// aip.file.save_docx("reports/stats.docx", {
+///   { type = "heading", text = "Stats", level = 1 },
+///   "Generated by the stats agent.",
+///   { type = "table", headers = { "Name", "Count" }, rows = { { "files", 42 } } },
+/// })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - The path is not writable (outside the workspace, or no workspace),
+/// - A block is invalid (e.g., unknown `type`),
+/// - The file cannot be written.
+pub(super) fn file_save_docx(lua: &Lua, runtime: &Runtime, path: String, content: Value) -> mlua::Result<Value> {
+	let dir_context = runtime.dir_context();
+	let full_path = dir_context.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+
+	// We might not want that once workspace is truely optional
+	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.save_docx requires a aipack workspace setup")?;
+
+	check_access_write(lua, &full_path, wks_dir)?;
+
+	let blocks = match content {
+		Value::String(md) => md_to_docx_blocks(&md.to_str()?),
+		Value::Table(table) => lua_blocks_to_docx(table)?,
+		other => {
+			return Err(Error::custom(format!(
+				"aip.file.save_docx - content must be a markdown string or a list of blocks, but was '{}'",
+				other.type_name()
+			))
+			.into());
+		}
+	};
+
+	simple_fs::ensure_file_dir(&full_path).map_err(Error::from)?;
+	write_docx(&full_path, &blocks).map_err(|e| {
+		Error::from(format!(
+			"aip.file.save_docx - Failed to save docx file '{path}'.\nCause: {e}"
+		))
+	})?;
+
+	let file_info = FileInfo::new(runtime.dir_context(), path, &full_path);
+	file_info.into_lua(lua)
+}
+
+// region:    --- Support
+
+fn lua_blocks_to_docx(table: Table) -> mlua::Result<Vec<DocxBlock>> {
+	let mut blocks = Vec::new();
+	// Each lua list is its own docx list (for the ordered numbering)
+	let mut list_id = 0;
+
+	for value in table.sequence_values::<Value>() {
+		let block = match value? {
+			Value::Table(block) => block,
+			other => {
+				blocks.push(DocxBlock::Paragraph {
+					runs: vec![DocxRun::plain(lua_to_text(other)?)],
+				});
+				continue;
+			}
+		};
+
+		let block_type: Option<String> = block.get("type")?;
+		let text = || -> mlua::Result<Vec<DocxRun>> { Ok(vec![DocxRun::plain(lua_to_text(block.get("text")?)?)]) };
+		match block_type.as_deref().unwrap_or("paragraph") {
+			"heading" => {
+				let level: Option<i64> = block.get("level")?;
+				blocks.push(DocxBlock::Heading {
+					level: level.unwrap_or(1).clamp(1, 6) as u8,
+					runs: text()?,
+				});
+			}
+			"paragraph" => blocks.push(DocxBlock::Paragraph { runs: text()? }),
+			"quote" => blocks.push(DocxBlock::Quote { runs: text()? }),
+			"code" => blocks.push(DocxBlock::Code {
+				text: lua_to_text(block.get("text")?)?,
+			}),
+			"list" => {
+				let ordered = block.get::<Option<bool>>("ordered")?.unwrap_or(false);
+				let items: Option<Table> = block.get("items")?;
+				for item in items.iter().flat_map(|items| items.sequence_values::<Value>()) {
+					blocks.push(DocxBlock::ListItem {
+						ordered,
+						list_id,
+						level: 0,
+						runs: vec![DocxRun::plain(lua_to_text(item?)?)],
+					});
+				}
+				list_id += 1;
+			}
+			"table" => {
+				let mut rows: Vec<Vec<Vec<DocxRun>>> = Vec::new();
+				if let Some(headers) = block.get::<Option<Table>>("headers")? {
+					rows.push(lua_to_cells(headers)?);
+				}
+				if let Some(lua_rows) = block.get::<Option<Table>>("rows")? {
+					for row in lua_rows.sequence_values::<Table>() {
+						rows.push(lua_to_cells(row?)?);
+					}
+				}
+				blocks.push(DocxBlock::Table { rows });
+			}
+			other => {
+				return Err(Error::custom(format!(
+					"aip.file.save_docx - Block type '{other}' not supported (heading, paragraph, list, table, code, quote)"
+				))
+				.into());
+			}
+		}
+	}
+
+	Ok(blocks)
+}
+
+fn lua_to_cells(row: Table) -> mlua::Result<Vec<Vec<DocxRun>>> {
+	row.sequence_values::<Value>()
+		.map(|cell| Ok(vec![DocxRun::plain(lua_to_text(cell?)?)]))
+		.collect()
+}
+
+/// The text of a string, number, or boolean value (nil is empty).
+fn lua_to_text(value: Value) -> mlua::Result<String> {
+	match value {
+		Value::Nil => Ok(String::new()),
+		Value::String(s) => Ok(s.to_str()?.to_string()),
+		Value::Integer(n) => Ok(n.to_string()),
+		Value::Number(n) => Ok(n.to_string()),
+		Value::Boolean(b) => Ok(b.to_string()),
+		other => Err(Error::custom(format!(
+			"aip.file.save_docx - Block text must be a string or a number, but was '{}'",
+			other.type_name()
+		))
+		.into()),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{clean_sanbox_01_tmp_file, gen_sandbox_01_temp_file_path, run_reflective_agent};
+
+	#[tokio::test]
+	async fn test_lua_file_save_docx_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_md_path = gen_sandbox_01_temp_file_path("test_lua_file_save_docx_ok-md.docx");
+		let fx_blocks_path = gen_sandbox_01_temp_file_path("test_lua_file_save_docx_ok-blocks.docx");
+		let fx_lua = format!(
+			r###"
+aip.file.save_docx("{fx_md_path}", "# Report\n\nAll **12** checks passed.\n\n- fast\n- safe")
+aip.file.save_docx("{fx_blocks_path}", {{
+  {{ type = "heading", text = "Stats", level = 1 }},
+  "Generated by the agent.",
+  {{ type = "list", items = {{ "one", "two" }} }},
+}})
+return {{ md = aip.file.load_docx_as_md("{fx_md_path}"), blocks = aip.file.load_docx_as_md("{fx_blocks_path}") }}
+		"###
+		);
+
+		// -- Exec
+		let res = run_reflective_agent(&fx_lua, None).await?;
+
+		// -- Check
+		let md = res.get("md").and_then(|v| v.as_str()).ok_or("Should have md")?;
+		assert!(md.contains("# Report"), "{md}");
+		assert!(md.contains("checks passed."), "{md}");
+		assert!(md.contains("- safe"), "{md}");
+		let blocks = res.get("blocks").and_then(|v| v.as_str()).ok_or("Should have blocks")?;
+		assert!(blocks.contains("# Stats"), "{blocks}");
+		assert!(blocks.contains("Generated by the agent."), "{blocks}");
+		assert!(blocks.contains("- two"), "{blocks}");
+
+		// -- Clean
+		clean_sanbox_01_tmp_file(fx_md_path)?;
+		clean_sanbox_01_tmp_file(fx_blocks_path)?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -37,6 +37,7 @@
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use crate::support::files::{
 	hash_file_b58 as blake3_hash_file_b58, hash_file_b64 as blake3_hash_file_b64,
 	hash_file_b64u as blake3_hash_file_b64u, hash_file_hex as blake3_hash_file_hex, hash_file_sha256_b58,
@@ -61,6 +62,7 @@ where
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, ctx)?;
 	let hash_string =
 		hash_fn(full_path).map_err(|e| Error::from(format!("{ctx} - Failed to hash file '{path}'.\nCause: {e}")))?;
 	hash_string.into_lua(lua)
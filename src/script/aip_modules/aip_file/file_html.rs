@@ -16,6 +16,7 @@
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use crate::script::{LuaValueExt, aip_modules};
 use crate::types::{DestOptions, FileInfo};
 use mlua::{FromLua as _, IntoLua, Lua, Value};
@@ -97,6 +98,7 @@ pub(super) fn file_save_html_to_md(
 	// -- resolve and read source
 	let rel_html = SPath::new(html_path.clone());
 	let full_html = dir_context.resolve_path(runtime.session(), rel_html.clone(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_html, "aip.file.save_html_to_md")?;
 	let html_content = read_to_string(&full_html)
 		.map_err(|e| Error::Custom(format!("Failed to read HTML file '{html_path}'.\nCause: {e}")))?;
 
@@ -194,6 +196,7 @@ pub(super) fn file_save_html_to_slim(
 	let rel_html_src = SPath::new(html_path.clone());
 	let full_html_src =
 		dir_context.resolve_path(runtime.session(), rel_html_src.clone(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_html_src, "aip.file.save_html_to_slim")?;
 	let html_content = read_to_string(&full_html_src)
 		.map_err(|e| Error::Custom(format!("Failed to read HTML file '{html_path}'.\nCause: {e}")))?;
 
@@ -248,6 +251,7 @@ pub(super) fn file_load_html_as_slim(lua: &Lua, runtime: &Runtime, html_path: St
 	// -- resolve and read source
 	let rel_html = SPath::new(html_path.clone());
 	let full_html = dir_context.resolve_path(runtime.session(), rel_html, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_html, "aip.file.load_html_as_slim")?;
 	let html_content = read_to_string(&full_html)
 		.map_err(|e| Error::Custom(format!("Failed to read HTML file '{html_path}'.\nCause: {e}")))?;
 
@@ -305,6 +309,7 @@ pub(super) fn file_load_html_as_md(
 	// -- resolve and read source
 	let rel_html = SPath::new(html_path.clone());
 	let full_html = dir_context.resolve_path(runtime.session(), rel_html, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_html, "aip.file.load_html_as_md")?;
 	let html_content = read_to_string(&full_html)
 		.map_err(|e| Error::Custom(format!("Failed to read HTML file '{html_path}'.\nCause: {e}")))?;
 
@@ -14,7 +14,7 @@
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
-use crate::script::aip_modules::support::check_access_write;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
 use crate::script::{lua_value_list_to_serde_values, lua_value_to_serde_value, serde_value_to_lua_value};
 use crate::support::jsons;
 use crate::types::FileInfo;
@@ -78,6 +78,7 @@ pub(super) fn file_load_json(lua: &Lua, runtime: &Runtime, path: String) -> mlua
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_json")?;
 
 	let json_value = jsons::load_json_to_serde_value(&full_path).map_err(|e| {
 		Error::from(format!(
@@ -152,6 +153,7 @@ pub(super) fn file_load_ndjson(lua: &Lua, runtime: &Runtime, path: String) -> ml
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_ndjson")?;
 
 	let json_values = simple_fs::load_ndjson(full_path).map_err(|e| {
 		Error::from(format!(
@@ -231,7 +233,7 @@ pub(super) fn file_append_json_line(lua: &Lua, runtime: &Runtime, path: String,
 	let wks_dir =
 		dir_context.try_wks_dir_with_err_ctx("aip.file.append_json_line requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	// Convert Lua value to serde_json::Value
 	let json_value = lua_value_to_serde_value(data).map_err(|e| {
@@ -334,7 +336,7 @@ pub(super) fn file_append_json_lines(lua: &Lua, runtime: &Runtime, path: String,
 	let wks_dir =
 		dir_context.try_wks_dir_with_err_ctx("aip.file.append_json_lines requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 	ensure_file_dir(&full_path).map_err(Error::from)?;
 
 	// -- Append using simple_fs
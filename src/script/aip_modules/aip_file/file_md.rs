@@ -19,6 +19,7 @@
 
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use crate::script::support::into_vec_of_strings;
 use crate::support::md::MdSectionIter;
 use mlua::{IntoLua, Lua, Value};
@@ -53,6 +54,7 @@ pub(super) fn file_load_md_sections(
 	let path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), path.into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &path, "aip.file.load_md_sections")?;
 	let sec_iter = MdSectionIter::from_path(path, headings.as_deref())?;
 	let sections = sec_iter.collect::<Vec<_>>();
 	sections.into_lua(lua)
@@ -70,6 +72,7 @@ pub(super) fn file_load_md_split_first(lua: &Lua, runtime: &Runtime, path: Strin
 	let path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), path.into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &path, "aip.file.load_md_split_first")?;
 	let mut sec_iter = MdSectionIter::from_path(path, None)?;
 	let split_first = sec_iter.split_first();
 	split_first.into_lua(lua)
@@ -0,0 +1,312 @@
+//! Defines the file metadata and permission functions for the `aip.file` Lua module.
+//!
+//! ---
+//!
+//! ## Lua API
+//!
+//! ### Functions
+//!
+//! - `aip.file.metadata(path: string): FileMetadata | nil`
+//! - `aip.file.chmod(path: string, mode: string | number): FileMetadata`
+//! - `aip.file.symlink(target: string, link_path: string, options?: {overwrite?: boolean | string}): FileMetadata`
+
+use crate::dir_context::PathResolver;
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::aip_modules::aip_file::file_write::apply_overwrite_policy;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
+use crate::script::serde_value_to_lua_value;
+use crate::support::files::{FileMetadata, compute_file_mode, create_symlink, read_file_metadata, set_file_mode};
+use crate::types::{FileOverOptions, OverwritePolicy};
+use crate::{Error, Result};
+use mlua::{Lua, Value};
+use simple_fs::{SPath, ensure_file_dir};
+
+/// ## Lua Documentation
+///
+/// Retrieves the detailed metadata of a file, dir, or symlink (permissions, owner, symlink target, times).
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.metadata(path: string): FileMetadata | nil
+/// ```
+///
+/// The symlinks are not followed for the `kind` and `symlink_target`, but the other properties are the target ones
+/// (or the link ones when the target does not exist).
+///
+/// ### Arguments
+///
+/// - `path: string` - The file, dir, or symlink path (relative to the workspace, or pack ref).
+///
+/// ### Returns
+///
+/// - `FileMetadata | nil`: The metadata, or `nil` if the path does not exist.
+///
+/// ```ts
+/// {
+///   path: string,            // the path as given
+///   kind: string,            // "file" | "dir" | "symlink"
+///   size: number,            // in bytes
+///   readonly: boolean,
+///   mode?: number,           // unix permission bits (e.g., 493 for 0o755)
+///   permissions?: string,    // unix (e.g., "rwxr-xr-x")
+///   uid?: number,            // unix
+///   gid?: number,            // unix
+///   owner?: string,          // unix, the owner user name (when found)
+///   symlink_target?: string, // for "symlink", the target as stored in the link
+///   ctime?: number,          // created, in microseconds since epoch (when supported by the platform)
+///   mtime?: number,          // modified, in microseconds since epoch
+///   atime?: number           // accessed, in microseconds since epoch
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local meta = aip.file.metadata("scripts/build.sh")
+/// if meta and meta.permissions and not meta.permissions:find("x") then
+///   aip.file.chmod("scripts/build.sh", "u+x")
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the path cannot be resolved, or its metadata cannot be read.
+pub(super) fn file_metadata(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<Value> {
+	let full_path = resolve_full_path(runtime, &path)?;
+	check_access_read(lua, &full_path, "aip.file.metadata")?;
+
+	match read_file_metadata(&full_path)? {
+		Some(meta) => file_metadata_to_lua(lua, &path, meta),
+		None => Ok(Value::Nil),
+	}
+}
+
+/// ## Lua Documentation
+///
+/// Changes the permissions of a file or dir.
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.chmod(path: string, mode: string | number): FileMetadata
+/// ```
+///
+/// On non unix platforms, only the readonly flag is changed (readonly when the owner write bit is not set).
+///
+/// ### Arguments
+///
+/// - `path: string` - The file or dir path (relative to the workspace).
+/// - `mode: string | number` - The new mode:
+///   - octal string, e.g., `"755"`, `"0o644"`
+///   - symbolic string, as `chmod`, e.g., `"u+x"`, `"go-w"`, `"a=r,u+w"`
+///   - number, the mode bits (e.g., `tonumber("755", 8)`)
+///
+/// ### Returns
+///
+/// - `FileMetadata`: The updated metadata (see `aip.file.metadata`).
+///
+/// ### Example
+///
+/// ```lua
+/// aip.file.chmod("scripts/build.sh", "755")
+/// aip.file.chmod("secrets/.env", "go-rwx")
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the path does not exist or is outside the workspace, the mode is invalid,
+/// or the permissions cannot be changed.
+pub(super) fn file_chmod(lua: &Lua, runtime: &Runtime, path: String, mode: Value) -> mlua::Result<Value> {
+	let full_path = resolve_full_path(runtime, &path)?;
+	let wks_dir = runtime
+		.dir_context()
+		.try_wks_dir_with_err_ctx("aip.file.chmod requires a aipack workspace setup")?;
+	check_access_write(lua, &full_path, wks_dir)?;
+
+	let Some(meta) = read_file_metadata(&full_path)? else {
+		return Err(Error::custom(format!("aip.file.chmod failed - `{path}` does not exist")).into());
+	};
+	let current = meta.mode.unwrap_or(if meta.readonly { 0o444 } else { 0o644 });
+
+	let new_mode = match mode {
+		Value::String(spec) => compute_file_mode(current, &spec.to_str()?)?,
+		Value::Integer(bits) if (0..=0o7777).contains(&bits) => bits as u32,
+		other => {
+			return Err(Error::custom(format!(
+				"aip.file.chmod - mode must be a string (e.g., '755', 'u+x') or the mode bits number, but was {}",
+				other.type_name()
+			))
+			.into());
+		}
+	};
+
+	set_file_mode(&full_path, new_mode).map_err(|err| Error::custom(format!("aip.file.chmod failed. {err}")))?;
+
+	let rel_path = full_path.diff(wks_dir).unwrap_or_else(|| full_path.clone());
+	get_hub().publish_sync(format!("-> Lua aip.file.chmod called on: {rel_path} ({new_mode:o})"));
+
+	let meta = read_file_metadata(&full_path)?
+		.ok_or_else(|| Error::custom(format!("aip.file.chmod - `{path}` not found after chmod")))?;
+	file_metadata_to_lua(lua, &path, meta)
+}
+
+/// ## Lua Documentation
+///
+/// Creates a symlink to a target.
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.symlink(target: string, link_path: string, options?: {overwrite?: boolean | string}): FileMetadata
+/// ```
+///
+/// The `target` is stored as given in the link, so a relative target is relative to the link dir
+/// (e.g., `aip.file.symlink("v2/config.toml", "config/current.toml")` links to `config/v2/config.toml`).
+/// The link parent dirs are created if they do not exist.
+///
+/// On windows, creating a symlink requires the symlink privilege (or the developer mode).
+///
+/// ### Arguments
+///
+/// - `target: string` - The link target.
+/// - `link_path: string` - The link path (relative to the workspace).
+/// - `options?: table`:
+///   - `overwrite?: boolean | string`: The policy when a file or symlink exists at `link_path` (see `aip.file.move`),
+///     `"error"` (or `false`, default), `"replace"` (or `true`), `"skip"` (returns the existing one), or `"trash"`.
+///
+/// ### Returns
+///
+/// - `FileMetadata`: The link metadata (see `aip.file.metadata`).
+///
+/// ### Example
+///
+/// ```lua
+/// aip.file.symlink("../shared/.env", "app/.env", { overwrite = true })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the link is outside the workspace, the link path exists (without `overwrite`)
+/// or is a dir, or the symlink cannot be created.
+pub(super) fn file_symlink(
+	lua: &Lua,
+	runtime: &Runtime,
+	target: String,
+	link_path: String,
+	options: Option<FileOverOptions>,
+) -> mlua::Result<Value> {
+	let options = options.unwrap_or_default();
+	let link_full = resolve_full_path(runtime, &link_path)?;
+	let wks_dir = runtime
+		.dir_context()
+		.try_wks_dir_with_err_ctx("aip.file.symlink requires a aipack workspace setup")?;
+	check_access_write(lua, &link_full, wks_dir)?;
+
+	// -- Check the target read access (a relative target is relative to the link dir)
+	let target_full = match link_full.parent() {
+		Some(link_dir) if !SPath::new(&target).is_absolute() => link_dir.join(&target),
+		_ => SPath::new(&target),
+	}
+	.into_collapsed();
+	check_access_read(lua, &target_full, "aip.file.symlink")?;
+
+	if let Some(existing) = read_file_metadata(&link_full)? {
+		if existing.kind == "dir" {
+			return Err(Error::custom(format!("aip.file.symlink failed - `{link_path}` is a directory")).into());
+		}
+		let overwrite = options.overwrite();
+		if !apply_overwrite_policy(lua, "aip.file.symlink", &link_full, &link_path, wks_dir, overwrite)? {
+			return file_metadata_to_lua(lua, &link_path, existing);
+		}
+		// NOTE: The "trash" policy already moved the existing link to the trash
+		if overwrite == OverwritePolicy::Replace {
+			std::fs::remove_file(link_full.as_std_path())
+				.map_err(|err| Error::cc(format!("aip.file.symlink failed to remove `{link_path}`"), err))?;
+		}
+	}
+
+	ensure_file_dir(&link_full).map_err(Error::from)?;
+	create_symlink(&target, &link_full).map_err(|err| Error::custom(format!("aip.file.symlink failed. {err}")))?;
+
+	let rel_link = link_full.diff(wks_dir).unwrap_or_else(|| link_full.clone());
+	get_hub().publish_sync(format!("-> Lua aip.file.symlink called: {rel_link} -> {target}"));
+
+	let meta = read_file_metadata(&link_full)?
+		.ok_or_else(|| Error::custom(format!("aip.file.symlink - `{link_path}` not found after creation")))?;
+	file_metadata_to_lua(lua, &link_path, meta)
+}
+
+// region:    --- Support
+
+fn resolve_full_path(runtime: &Runtime, path: &str) -> Result<SPath> {
+	let full_path =
+		runtime
+			.dir_context()
+			.resolve_path(runtime.session(), SPath::new(path), PathResolver::WksDir, None)?;
+	Ok(full_path)
+}
+
+fn file_metadata_to_lua(lua: &Lua, path: &str, meta: FileMetadata) -> mlua::Result<Value> {
+	let mut value =
+		serde_json::to_value(meta).map_err(|err| Error::custom(format!("Cannot convert file metadata. {err}")))?;
+	if let Some(obj) = value.as_object_mut() {
+		obj.insert("path".to_string(), path.into());
+	}
+	Ok(serde_value_to_lua_value(lua, value)?)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_file;
+
+	#[cfg(unix)]
+	#[tokio::test]
+	async fn test_lua_file_metadata_chmod_symlink() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_file::init_module, "file").await?;
+		let fx_dir = ".tmp/test_lua_file_metadata_chmod_symlink";
+		let script = format!(
+			r#"
+aip.file.save("{fx_dir}/run.sh", "echo hi")
+local before = aip.file.metadata("{fx_dir}/run.sh")
+aip.file.chmod("{fx_dir}/run.sh", "644")
+local after = aip.file.chmod("{fx_dir}/run.sh", "u+x,g+x")
+local link = aip.file.symlink("run.sh", "{fx_dir}/current.sh", {{ overwrite = true }})
+local ok = pcall(function() return aip.file.symlink("run.sh", "{fx_dir}/current.sh") end)
+return {{
+  kind = before.kind,
+  size = before.size,
+  permissions = after.permissions,
+  link_kind = link.kind,
+  link_target = link.symlink_target,
+  link_size = link.size,
+  second_ok = ok,
+  missing = aip.file.metadata("{fx_dir}/nope.txt") == nil
+}}
+		"#
+		);
+
+		// -- Exec
+		let res = eval_lua(&lua, &script)?;
+
+		// -- Check
+		assert_eq!(res["kind"].as_str(), Some("file"));
+		assert_eq!(res["size"].as_i64(), Some(7));
+		assert_eq!(res["permissions"].as_str(), Some("rwxr-xr--"));
+		assert_eq!(res["link_kind"].as_str(), Some("symlink"));
+		assert_eq!(res["link_target"].as_str(), Some("run.sh"));
+		assert_eq!(res["link_size"].as_i64(), Some(7));
+		assert_eq!(res["second_ok"].as_bool(), Some(false));
+		assert_eq!(res["missing"].as_bool(), Some(true));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
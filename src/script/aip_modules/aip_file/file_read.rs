@@ -18,11 +18,12 @@ use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
 use crate::script::LuaValueExt;
 use crate::script::aip_modules::support::{
-	base_dir_and_globs, compute_base_dir, create_file_records, list_files_with_options,
+	base_dir_and_globs, check_access_list, check_access_read, compute_base_dir, create_file_records, is_access_denied,
+	list_base_path, list_files_with_options, rec_res_usage,
 };
 use crate::script::support::into_option_string;
 use crate::support::AsStrsExt;
-use crate::types::{FileInfo, FileRecord, FileStats};
+use crate::types::{FileInfo, FileRecord, FileStats, ResUsage};
 use mlua::{IntoLua, Lua, Value};
 use simple_fs::{SMeta, SPath, iter_files};
 
@@ -110,6 +111,12 @@ pub(super) fn file_stats(
 	let absolute = options.x_get_bool("absolute").unwrap_or(false);
 
 	let file_refs = list_files_with_options(runtime, base_path.as_ref(), &include_globs.x_as_strs(), absolute, false)?;
+	let file_refs = check_access_list(
+		lua,
+		list_base_path(runtime, base_path.as_ref()),
+		file_refs,
+		"aip.file.stats",
+	)?;
 
 	if file_refs.is_empty() {
 		return FileStats::default().into_lua(lua);
@@ -233,10 +240,12 @@ pub(super) fn file_load(
 		(Some(base_path), false) => base_path.join(full_path),
 		_ => full_path,
 	};
+	check_access_read(lua, &full_path, "aip.file.load")?;
 
 	let rel_path = SPath::new(rel_path);
 
 	let file_record = FileRecord::load_from_full_path(runtime.dir_context(), &full_path, rel_path)?;
+	rec_res_usage(lua, runtime, ResUsage::from_read(file_record.content.len()));
 	let res = file_record.into_lua(lua)?;
 
 	Ok(res)
@@ -283,8 +292,8 @@ pub(super) fn file_load(
 ///   error: string // Error message
 /// }
 /// ```
-pub(super) fn file_exists(_lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<bool> {
-	Ok(crate::script::support::path_exists(runtime, &path))
+pub(super) fn file_exists(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<bool> {
+	Ok(crate::script::support::path_exists(lua, runtime, &path))
 }
 
 /// ## Lua Documentation
@@ -338,6 +347,7 @@ pub(super) fn file_info(lua: &Lua, runtime: &Runtime, path: Value) -> mlua::Resu
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), rel_path.clone(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.info")?;
 
 	if !full_path.is_file() {
 		return Ok(Value::Nil);
@@ -442,6 +452,12 @@ pub(super) fn file_list(
 	// let with_meta = options.x_get_bool("with_meta").unwrap_or(true);
 
 	let spaths = list_files_with_options(runtime, base_path.as_ref(), &include_globs.x_as_strs(), absolute, true)?;
+	let spaths = check_access_list(
+		lua,
+		list_base_path(runtime, base_path.as_ref()),
+		spaths,
+		"aip.file.list",
+	)?;
 
 	let file_infos: Vec<FileInfo> = spaths
 		.into_iter()
@@ -532,8 +548,16 @@ pub(super) fn file_list_load(
 	let absolute = options.x_get_bool("absolute").unwrap_or(false);
 
 	let file_refs = list_files_with_options(runtime, base_path.as_ref(), &include_globs.x_as_strs(), absolute, true)?;
+	let file_refs = check_access_list(
+		lua,
+		list_base_path(runtime, base_path.as_ref()),
+		file_refs,
+		"aip.file.list_load",
+	)?;
 
 	let file_records = create_file_records(runtime, file_refs, base_path.as_ref(), absolute)?;
+	let read_bytes = file_records.iter().map(|record| record.content.len()).sum();
+	rec_res_usage(lua, runtime, ResUsage::from_read(read_bytes));
 
 	let res = file_records.into_lua(lua)?;
 
@@ -636,11 +660,13 @@ pub(super) fn file_first(
 	)
 	.map_err(crate::Error::from)?;
 
-	let Some(sfile) = sfiles.next() else {
+	// NOTE: The `[sandbox] paths_deny` files are skipped (as not matched)
+	let Some(sfile) = sfiles.find(|sfile| !is_access_denied(lua, &SPath::from(sfile))) else {
 		return Ok(Value::Nil);
 	};
 
 	let absolute_path = SPath::from(&sfile);
+	check_access_read(lua, &absolute_path, "aip.file.first")?;
 
 	let spath = if absolute {
 		sfile
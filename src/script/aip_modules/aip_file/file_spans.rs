@@ -15,6 +15,7 @@
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use mlua::{Lua, Value};
 use simple_fs::{self, SPath};
 
@@ -51,6 +52,7 @@ pub(super) fn file_line_spans(lua: &Lua, runtime: &Runtime, path: String) -> mlu
 	let full_path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), rel_path, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.line_spans")?;
 
 	let spans = simple_fs::line_spans(&full_path).map_err(Error::from)?;
 	let table = spans_to_lua_table(lua, &spans)?;
@@ -87,6 +89,7 @@ pub(super) fn file_csv_row_spans(lua: &Lua, runtime: &Runtime, path: String) ->
 	let full_path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), rel_path, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.csv_row_spans")?;
 
 	let spans = simple_fs::csv_row_spans(&full_path).map_err(Error::from)?;
 	let table = spans_to_lua_table(lua, &spans)?;
@@ -120,13 +123,7 @@ pub(super) fn file_csv_row_spans(lua: &Lua, runtime: &Runtime, path: String) ->
 ///
 /// Returns an error if the offsets are negative, `end` is smaller than `start`, the path cannot be resolved,
 /// the file is missing, or the requested slice cannot be read.
-pub(super) fn file_read_span(
-	_lua: &Lua,
-	runtime: &Runtime,
-	path: String,
-	start: i64,
-	end: i64,
-) -> mlua::Result<String> {
+pub(super) fn file_read_span(lua: &Lua, runtime: &Runtime, path: String, start: i64, end: i64) -> mlua::Result<String> {
 	if start < 0 || end < 0 {
 		return Err(Error::custom("read_span expects non-negative start/end offsets").into());
 	}
@@ -138,6 +135,7 @@ pub(super) fn file_read_span(
 	let full_path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), rel_path, PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.read_span")?;
 
 	let text = simple_fs::read_span(&full_path, start as usize, end as usize).map_err(Error::from)?;
 	Ok(text)
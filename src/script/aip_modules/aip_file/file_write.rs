@@ -21,12 +21,16 @@ use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::hub::get_hub;
 use crate::runtime::Runtime;
-use crate::script::aip_modules::support::{check_access_delete, check_access_write, process_path_reference};
-use crate::support::files::safer_trash_file;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{
+	check_access_delete, check_access_read, check_access_write, check_confirm_file_op, check_confirm_write,
+	process_path_reference, rec_res_usage,
+};
+use crate::support::files::{safer_remove_file, safer_trash_file};
 use crate::support::text::{ensure_single_trailing_newline, trim_end_if_needed, trim_start_if_needed};
-use crate::types::{FileInfo, FileOverOptions, SaveOptions};
+use crate::types::{FileInfo, FileOverOptions, OverwritePolicy, ResUsage, SaveOptions};
 use mlua::{FromLua, IntoLua, Lua, Value};
-use simple_fs::ensure_file_dir;
+use simple_fs::{SPath, ensure_file_dir};
 use std::fs::{File, write};
 use std::io::Write;
 
@@ -116,11 +120,14 @@ pub(super) fn file_save(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.save requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
+	check_confirm_write(lua, "aip.file.save", &full_path, wks_dir, &content, false)?;
 
 	ensure_file_dir(&full_path).map_err(Error::from)?;
 
+	let write_bytes = content.len();
 	write(&full_path, content).map_err(|err| Error::custom(format!("Fail to save file {rel_path}.\nCause {err}")))?;
+	rec_res_usage(lua, runtime, ResUsage::from_write(write_bytes));
 
 	let rel_path = full_path.diff(wks_dir).unwrap_or_else(|| full_path.clone());
 	get_hub().publish_sync(format!("-> Lua aip.file.save called on: {rel_path}"));
@@ -137,28 +144,39 @@ pub(super) fn file_save(
 ///
 /// ```lua
 /// -- API Signature
-/// aip.file.move(src_path: string, dest_path: string, options?: {overwrite?: boolean}): FileInfo
+/// aip.file.move(src_path: string, dest_path: string, options?: {overwrite?: boolean | string}): FileInfo
 /// ```
 ///
 /// Renames (moves) the file at `src_path` to `dest_path`.
 /// Both paths are resolved relative to the workspace root and support pack references (`ns@pack/...`).
 /// Parent directories for the destination are created automatically if they don't exist.
+/// When the destination is on another device, the file is copied, then the source is removed.
 ///
 /// ### Arguments
 ///
 /// - `src_path: string` - The source file path.
 /// - `dest_path: string` - The destination file path.
 /// - `options?: table` (optional) - Options:
-///   - `overwrite?: boolean`: If `false`, the operation fails if the destination exists. Defaults to `false`.
+///   - `overwrite?: boolean | string`: The policy when the destination exists. Defaults to `"error"`.
+///     - `"error"` (or `false`): the operation fails.
+///     - `"replace"` (or `true`): the destination is replaced.
+///     - `"skip"`: nothing is done (the source stays), and the existing destination is returned.
+///     - `"trash"`: the destination is moved to the trash first.
 ///
 /// ### Returns
 ///
 /// - `FileInfo`: A [`FileInfo`] object for the moved destination file.
 ///
+/// ### Example
+///
+/// ```lua
+/// aip.file.move("drafts/post.md", "posts/post.md", { overwrite = "trash" })
+/// ```
+///
 /// ### Error
 ///
 /// Returns an error if the source file doesn't exist, if the source or destination is outside the workspace
-/// (or restricted areas), or if an I/O error occurs (e.g., cross-device move failure).
+/// (or restricted areas), if the destination exists (with the `"error"` policy), or if an I/O error occurs.
 pub(super) fn file_move(
 	lua: &Lua,
 	runtime: &Runtime,
@@ -169,47 +187,108 @@ pub(super) fn file_move(
 	let dir_context = runtime.dir_context();
 	let options = options.unwrap_or_default();
 
-	let src_full = process_path_reference(runtime, &src_path)?;
-	let dest_full = process_path_reference(runtime, &dest_path)?;
+	let src_full = dir_context.resolve_path(runtime.session(), (&src_path).into(), PathResolver::WksDir, None)?;
+	let dest_full = dir_context.resolve_path(runtime.session(), (&dest_path).into(), PathResolver::WksDir, None)?;
 
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.move requires a aipack workspace setup")?;
 
-	check_access_delete(&src_full, wks_dir)?;
-	check_access_write(&dest_full, wks_dir)?;
+	check_access_delete(lua, &src_full, wks_dir)?;
+	check_access_write(lua, &dest_full, wks_dir)?;
 
-	if !src_full.exists() {
+	if !src_full.is_file() {
 		return Err(Error::custom(format!("Move file failed - Source `{src_path}` does not exist")).into());
 	}
 
-	if !options.overwrite() && dest_full.exists() {
-		return Err(Error::custom(format!(
-			"Move file failed - Destination `{dest_path}` already exists and overwrite is set to false.\nUse `aip.file.move(src_path, dst_path,{{overwrite = true}}` to allow overwrite."
-		))
-		.into());
+	let proceed = apply_overwrite_policy(
+		lua,
+		"aip.file.move",
+		&dest_full,
+		&dest_path,
+		wks_dir,
+		options.overwrite(),
+	)?;
+
+	if proceed {
+		let rel_dest = dest_full.diff(wks_dir).unwrap_or_else(|| dest_full.clone());
+		let operation = format!("move to '{rel_dest}'");
+		check_confirm_file_op(lua, "aip.file.move", &[&src_full, &dest_full], wks_dir, &operation)?;
+
+		ensure_file_dir(&dest_full).map_err(Error::from)?;
+		move_file(&src_full, &dest_full)
+			.map_err(|err| Error::custom(format!("Fail to move from `{src_path}` to `{dest_path}`.\nCause {err}")))?;
+
+		get_hub().publish_sync(format!("-> Lua aip.file.move called to: {rel_dest}"));
 	}
 
-	ensure_file_dir(&dest_full).map_err(Error::from)?;
-
-	std::fs::rename(&src_full, &dest_full)
-		.map_err(|err| Error::custom(format!("Fail to move from `{src_path}` to `{dest_path}`.\nCause {err}")))?;
-
-	let rel_dest = dest_full.diff(wks_dir).unwrap_or_else(|| dest_full.clone());
-	get_hub().publish_sync(format!("-> Lua aip.file.move called to: {rel_dest}"));
-
 	let file_info = FileInfo::new(runtime.dir_context(), dest_full, true);
 	let file_info = file_info.into_lua(lua)?;
 
 	Ok(file_info)
 }
 
+/// ## Lua Documentation
+///
+/// Renames a file in its directory, returning a [`FileInfo`] object for the renamed file.
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.rename(path: string, new_name: string, options?: {overwrite?: boolean | string}): FileInfo
+/// ```
+///
+/// Same as `aip.file.move(path, <path dir>/<new_name>, options)`, with the same checks.
+///
+/// ### Arguments
+///
+/// - `path: string` - The file path.
+/// - `new_name: string` - The new file name (without directory, e.g., `"README.old.md"`).
+/// - `options?: table` (optional) - Options:
+///   - `overwrite?: boolean | string`: The policy when a file with the new name exists (see `aip.file.move`).
+///     Defaults to `"error"`.
+///
+/// ### Returns
+///
+/// - `FileInfo`: A [`FileInfo`] object for the renamed file.
+///
+/// ### Example
+///
+/// ```lua
+/// aip.file.rename("docs/notes.md", "notes-archived.md")
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the new name is not a file name (e.g., has a `/`), or for the `aip.file.move` errors.
+pub(super) fn file_rename(
+	lua: &Lua,
+	runtime: &Runtime,
+	path: String,
+	new_name: String,
+	options: Option<FileOverOptions>,
+) -> mlua::Result<mlua::Value> {
+	let new_name = new_name.trim();
+	if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name == "." || new_name == ".." {
+		return Err(Error::custom(format!(
+			"Rename file failed - The new name `{new_name}` must be a file name (without directory)"
+		))
+		.into());
+	}
+
+	let dest_path = match SPath::new(&path).parent() {
+		Some(dir) if !dir.as_str().is_empty() => dir.join(new_name).to_string(),
+		_ => new_name.to_string(),
+	};
+
+	file_move(lua, runtime, path, dest_path, options)
+}
+
 /// ## Lua Documentation
 ///
 /// Copies a file from `src_path` to `dest_path`, returning a [`FileInfo`] object for the destination.
 ///
 /// ```lua
 /// -- API Signature
-/// aip.file.copy(src_path: string, dest_path: string, options?: {overwrite?: boolean}): FileInfo
+/// aip.file.copy(src_path: string, dest_path: string, options?: {overwrite?: boolean | string}): FileInfo
 /// ```
 ///
 /// Performs a binary, streaming copy of the file at `src_path` to `dest_path`.
@@ -221,7 +300,8 @@ pub(super) fn file_move(
 /// - `src_path: string` - The source file path.
 /// - `dest_path: string` - The destination file path.
 /// - `options?: table` (optional) - Options:
-///   - `overwrite?: boolean`: If `false`, the operation fails if the destination exists. Defaults to `false`.
+///   - `overwrite?: boolean | string`: The policy when the destination exists (see `aip.file.move`).
+///     Defaults to `"error"`.
 ///
 /// ### Returns
 ///
@@ -230,7 +310,7 @@ pub(super) fn file_move(
 /// ### Error
 ///
 /// Returns an error if the source file doesn't exist, if the destination is outside the workspace,
-/// or if an I/O error occurs.
+/// if the destination exists (with the `"error"` policy), or if an I/O error occurs.
 pub(super) fn file_copy(
 	lua: &Lua,
 	runtime: &Runtime,
@@ -241,37 +321,46 @@ pub(super) fn file_copy(
 	let dir_context = runtime.dir_context();
 	let options = options.unwrap_or_default();
 
-	let src_full = process_path_reference(runtime, &src_path)?;
-	let dest_full = process_path_reference(runtime, &dest_path)?;
+	let src_full = dir_context.resolve_path(runtime.session(), (&src_path).into(), PathResolver::WksDir, None)?;
+	let dest_full = dir_context.resolve_path(runtime.session(), (&dest_path).into(), PathResolver::WksDir, None)?;
 
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.copy requires a aipack workspace setup")?;
 
-	check_access_write(&dest_full, wks_dir)?;
+	check_access_read(lua, &src_full, "aip.file.copy")?;
+	check_access_write(lua, &dest_full, wks_dir)?;
 
-	if !options.overwrite() && dest_full.exists() {
-		return Err(Error::custom(format!(
-			"Copy file failed - Destination `{dest_path}` already exists and overwrite is set to false.\nUse `aip.file.copy(src_path, dst_path,{{overwrite = true}}` to allow overwrite."
-		))
-		.into());
-	}
+	let proceed = apply_overwrite_policy(
+		lua,
+		"aip.file.copy",
+		&dest_full,
+		&dest_path,
+		wks_dir,
+		options.overwrite(),
+	)?;
 
-	ensure_file_dir(&dest_full).map_err(Error::from)?;
+	if proceed {
+		let rel_src = src_full.diff(wks_dir).unwrap_or_else(|| src_full.clone());
+		let operation = format!("copy from '{rel_src}'");
+		check_confirm_file_op(lua, "aip.file.copy", &[&dest_full], wks_dir, &operation)?;
 
-	let mut src_file = File::open(&src_full)
-		.map_err(|err| Error::custom(format!("Fail to open source file `{src_path}` for copy.\nCause {err}")))?;
+		ensure_file_dir(&dest_full).map_err(Error::from)?;
 
-	let mut dest_file = File::create(&dest_full).map_err(|err| {
-		Error::custom(format!(
-			"Fail to create destination file `{dest_path}` for copy.\nCause {err}"
-		))
-	})?;
+		let mut src_file = File::open(&src_full)
+			.map_err(|err| Error::custom(format!("Fail to open source file `{src_path}` for copy.\nCause {err}")))?;
 
-	std::io::copy(&mut src_file, &mut dest_file)
-		.map_err(|err| Error::custom(format!("Fail to copy from `{src_path}` to `{dest_path}`.\nCause {err}")))?;
+		let mut dest_file = File::create(&dest_full).map_err(|err| {
+			Error::custom(format!(
+				"Fail to create destination file `{dest_path}` for copy.\nCause {err}"
+			))
+		})?;
 
-	let rel_dest = dest_full.diff(wks_dir).unwrap_or_else(|| dest_full.clone());
-	get_hub().publish_sync(format!("-> Lua aip.file.copy called to: {rel_dest}"));
+		std::io::copy(&mut src_file, &mut dest_file)
+			.map_err(|err| Error::custom(format!("Fail to copy from `{src_path}` to `{dest_path}`.\nCause {err}")))?;
+
+		let rel_dest = dest_full.diff(wks_dir).unwrap_or_else(|| dest_full.clone());
+		get_hub().publish_sync(format!("-> Lua aip.file.copy called to: {rel_dest}"));
+	}
 
 	let file_info = FileInfo::new(runtime.dir_context(), dest_full, true);
 	let file_info = file_info.into_lua(lua)?;
@@ -285,11 +374,12 @@ pub(super) fn file_copy(
 ///
 /// ```lua
 /// -- API Signature
-/// aip.file.delete(path: string): boolean
+/// aip.file.delete(path: string, options?: {to_trash?: boolean}): boolean
 /// ```
 ///
 /// Attempts to delete the file specified by `path`.
 /// The path is resolved relative to the workspace root. If the file does not exist, returns `false`.
+/// By default, the file is moved to the system trash (so it can be restored).
 ///
 /// Security:
 /// - Deleting files is only allowed within the current workspace directory.
@@ -298,30 +388,55 @@ pub(super) fn file_copy(
 /// ### Arguments
 ///
 /// - `path: string` - The path to the file to delete, relative to the workspace root.
+/// - `options?: table` (optional) - Options:
+///   - `to_trash?: boolean`: If `false`, the file is permanently deleted. Defaults to `true`.
 ///
 /// ### Returns
 ///
 /// - `boolean`: `true` if a file was deleted, `false` if the file did not exist.
 ///
+/// ### Example
+///
+/// ```lua
+/// aip.file.delete("out/report.md")                        -- to the trash
+/// aip.file.delete(".tmp/cache.json", { to_trash = false }) -- permanent
+/// ```
+///
 /// ### Error
 ///
 /// Returns an error if:
 /// - The path attempts to delete outside the allowed workspace directory.
 /// - The target is in the `.aipack-base` folder (always forbidden).
-/// - The file cannot be deleted due to permissions or other I/O errors.
+/// - The file cannot be deleted (or trashed) due to permissions or other I/O errors.
 /// - The operation requires a workspace context, but none is found.
-pub(super) fn file_delete(lua: &Lua, runtime: &Runtime, rel_path: String) -> mlua::Result<mlua::Value> {
+pub(super) fn file_delete(
+	lua: &Lua,
+	runtime: &Runtime,
+	rel_path: String,
+	options: Option<Value>,
+) -> mlua::Result<mlua::Value> {
 	let dir_context = runtime.dir_context();
 	let full_path = dir_context.resolve_path(runtime.session(), (&rel_path).into(), PathResolver::WksDir, None)?;
+	let to_trash = options.x_get_bool("to_trash").unwrap_or(true);
 
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.delete requires a aipack workspace setup")?;
 
-	check_access_delete(&full_path, wks_dir)?;
+	check_access_delete(lua, &full_path, wks_dir)?;
 
 	let removed = if full_path.exists() {
-		// std::fs::remove_file(&full_path).map(|_| true).map_err(Error::from)?
-		safer_trash_file(&full_path, None)?
+		let operation = if to_trash {
+			"delete (to the trash)"
+		} else {
+			"delete (permanent)"
+		};
+		check_confirm_file_op(lua, "aip.file.delete", &[&full_path], wks_dir, operation)?;
+
+		if to_trash {
+			safer_trash_file(&full_path, None)?
+		} else {
+			safer_remove_file(&full_path)?
+		}
 	} else {
 		false
 	};
@@ -393,7 +508,8 @@ pub(super) fn file_append(
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.append requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
+	check_confirm_write(lua, "aip.file.append", &full_path, wks_dir, &content, true)?;
 
 	ensure_file_dir(&full_path).map_err(Error::from)?;
 
@@ -404,6 +520,7 @@ pub(super) fn file_append(
 		.map_err(Error::from)?;
 
 	file.write_all(content.as_bytes())?;
+	rec_res_usage(lua, runtime, ResUsage::from_write(content.len()));
 
 	// NOTE: Could be too many prints
 	// get_hub().publish_sync(format!("-> Lua aip.file.append called on: {}", rel_path));
@@ -497,7 +614,7 @@ pub(super) fn file_ensure_exists(
 
 	let dir_context = runtime.dir_context();
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.ensure_exists requires a aipack workspace setup")?;
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	// if the file does not exist, create it.
 	if !full_path.exists() {
@@ -566,7 +683,7 @@ pub(super) fn file_ensure_dir(lua: &Lua, runtime: &Runtime, path: String) -> mlu
 	// We might not want that once workspace is truely optional
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.file.ensure_dir requires a aipack workspace setup")?;
 
-	check_access_write(&full_path, wks_dir)?;
+	check_access_write(lua, &full_path, wks_dir)?;
 
 	if full_path.exists() {
 		if !full_path.is_dir() {
@@ -606,14 +723,60 @@ impl FromLua for EnsureExistsOptions {
 
 // endregion: --- Options
 
+// region:    --- Support
+
+/// Apply the overwrite policy when the destination exists (`what` is the call, e.g., `aip.file.move`).
+///
+/// Returns `false` if the operation must be skipped (the `skip` policy).
+pub(super) fn apply_overwrite_policy(
+	lua: &Lua,
+	what: &str,
+	dest_full: &SPath,
+	dest_path: &str,
+	wks_dir: &SPath,
+	policy: OverwritePolicy,
+) -> crate::Result<bool> {
+	// NOTE: symlink_metadata so that a broken symlink destination is an existing destination
+	if std::fs::symlink_metadata(dest_full.as_std_path()).is_err() {
+		return Ok(true);
+	}
+
+	match policy {
+		OverwritePolicy::Error => Err(Error::custom(format!(
+			"{what} failed - Destination `{dest_path}` already exists and overwrite is set to false.\nUse `{what}(..., {{overwrite = true}})` (or `\"replace\"`, `\"skip\"`, `\"trash\"`) to allow overwrite."
+		))),
+		OverwritePolicy::Replace => Ok(true),
+		OverwritePolicy::Skip => Ok(false),
+		OverwritePolicy::Trash => {
+			check_access_delete(lua, dest_full, wks_dir)?;
+			safer_trash_file(dest_full, None)?;
+			Ok(true)
+		}
+	}
+}
+
+/// Rename, or copy and remove when the destination is on another device.
+fn move_file(src: &SPath, dest: &SPath) -> std::io::Result<()> {
+	match std::fs::rename(src, dest) {
+		Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => {
+			std::fs::copy(src, dest)?;
+			std::fs::remove_file(src)
+		}
+		res => res,
+	}
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
-	use crate::_test_support::{assert_contains, run_reflective_agent};
+	use crate::_test_support::{assert_contains, eval_lua, run_reflective_agent, setup_lua};
 	use crate::runtime::Runtime;
+	use crate::script::aip_modules::aip_file;
 
 	/// Note: need the multi-thread, because save do a `get_hub().publish_sync`
 	///       which does a tokio blocking (requiring multi thread)
@@ -839,11 +1002,9 @@ mod tests {
 		// -- Setup & Fixtures
 		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
 		let dir_context = runtime.dir_context();
+		let wks_dir = dir_context.wks_dir().ok_or("Should have workspace setup")?;
 		let fx_src_path = "agent-script/agent-hello.aip";
-		let fx_dest_path = dir_context
-			.wks_dir()
-			.ok_or("Should have workspace setup")?
-			.join(".tmp/test_lua_file_copy_simple_ok.aip");
+		let fx_dest_path = wks_dir.join(".tmp/test_lua_file_copy_simple_ok.aip");
 
 		// -- Exec
 		let _res = run_reflective_agent(
@@ -854,7 +1015,7 @@ mod tests {
 
 		// -- Check
 		assert!(fx_dest_path.exists());
-		let src_content = std::fs::read_to_string(fx_src_path)?;
+		let src_content = std::fs::read_to_string(wks_dir.join(fx_src_path))?;
 		let dest_content = std::fs::read_to_string(fx_dest_path)?;
 		assert_eq!(src_content, dest_content);
 
@@ -893,6 +1054,53 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_lua_file_rename_delete_overwrite_policies() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_file::init_module, "file").await?;
+		let fx_dir = ".tmp/test_lua_file_rename_delete_overwrite_policies";
+		let script = format!(
+			r#"
+aip.file.save("{fx_dir}/a.txt", "A")
+aip.file.save("{fx_dir}/b.txt", "B")
+local err_ok = pcall(function() return aip.file.copy("{fx_dir}/a.txt", "{fx_dir}/b.txt") end)
+aip.file.copy("{fx_dir}/a.txt", "{fx_dir}/b.txt", {{ overwrite = "skip" }})
+local b_after_skip = aip.file.load("{fx_dir}/b.txt").content
+local renamed = aip.file.rename("{fx_dir}/a.txt", "c.txt")
+aip.file.move("{fx_dir}/c.txt", "{fx_dir}/b.txt", {{ overwrite = "replace" }})
+local bad_name_ok = pcall(function() return aip.file.rename("{fx_dir}/b.txt", "sub/d.txt") end)
+local bad_policy_ok = pcall(function() return aip.file.copy("{fx_dir}/b.txt", "{fx_dir}/e.txt", {{ overwrite = "maybe" }}) end)
+return {{
+  err_ok = err_ok,
+  b_after_skip = b_after_skip,
+  renamed_name = renamed.name,
+  b_after_replace = aip.file.load("{fx_dir}/b.txt").content,
+  c_exists = aip.file.exists("{fx_dir}/c.txt"),
+  bad_name_ok = bad_name_ok,
+  bad_policy_ok = bad_policy_ok,
+  deleted = aip.file.delete("{fx_dir}/b.txt", {{ to_trash = false }}),
+  deleted_again = aip.file.delete("{fx_dir}/b.txt", {{ to_trash = false }}),
+}}
+		"#
+		);
+
+		// -- Exec
+		let res = eval_lua(&lua, &script)?;
+
+		// -- Check
+		assert_eq!(res["err_ok"].as_bool(), Some(false));
+		assert_eq!(res["b_after_skip"].as_str(), Some("B"));
+		assert_eq!(res["renamed_name"].as_str(), Some("c.txt"));
+		assert_eq!(res["b_after_replace"].as_str(), Some("A"));
+		assert_eq!(res["c_exists"].as_bool(), Some(false));
+		assert_eq!(res["bad_name_ok"].as_bool(), Some(false));
+		assert_eq!(res["bad_policy_ok"].as_bool(), Some(false));
+		assert_eq!(res["deleted"].as_bool(), Some(true));
+		assert_eq!(res["deleted_again"].as_bool(), Some(false));
+
+		Ok(())
+	}
+
 	#[tokio::test(flavor = "multi_thread")]
 	async fn test_lua_file_tmp_with_ctx() -> Result<()> {
 		// -- Setup & Fixtures
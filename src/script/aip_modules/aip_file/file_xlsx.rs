@@ -0,0 +1,326 @@
+//! Defines XLSX-related helpers for the `aip.file` Lua module.
+//!
+//! ---
+//!
+//! ## Lua documentation for `aip.file` XLSX helpers
+//!
+//! ### Functions
+//!
+//! - `aip.file.load_xlsx(path: string, options?: table): XlsxSheet[]`
+//!
+//! The cell values are read without the styles, so the dates are their serial numbers.
+//!
+use crate::Error;
+use crate::dir_context::PathResolver;
+use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
+use mlua::{Lua, LuaSerdeExt as _, Table, Value};
+use serde_json::Value as JsonValue;
+use std::cell::Cell;
+
+/// ## Lua Documentation
+///
+/// Loads the worksheets of an XLSX (Excel) file as row tables.
+///
+/// ```lua
+/// -- API Signature
+/// aip.file.load_xlsx(
+///   path: string,
+///   options?: {
+///     sheets?: string | integer | (string | integer)[],
+///     has_header?: boolean,
+///     header_row?: integer,
+///     skip_empty_rows?: boolean
+///   }
+/// ): XlsxSheet[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string`: Path to the XLSX file, relative to the workspace root.
+/// - `options?: table`:
+///   - `sheets?: string | integer | (string | integer)[]`: The sheet names, or 1 based indexes (default all the sheets).
+///   - `has_header?: boolean`: Whether the header row gives the record keys (default `true`).
+///   - `header_row?: integer`: The 1 based row number of the header, the rows above it are skipped (default `1`).
+///   - `skip_empty_rows?: boolean`: Whether the rows without values are skipped (default `true`).
+///
+/// ### Returns
+///
+/// - `XlsxSheet[]`: The selected sheets, in the workbook order:
+///   - `name: string`
+///   - `headers: string[]`: The header cells (`col_<n>` when empty), empty if `has_header = false`.
+///   - `rows`: The records keyed by the headers (the empty cells are absent),
+///     or the cell arrays (`""` for the empty cells) if `has_header = false`.
+///     The numbers and booleans are kept as Lua numbers and booleans.
+///
+/// ### Example
+///
+/// ```lua
+/// local sheets = aip.file.load_xlsx("data/people.xlsx", { sheets = "People" })
+/// for _, person in ipairs(sheets[1].rows) do
+///   print(person.name, person.age)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - The path cannot be resolved,
+/// - The file cannot be found or is not a valid XLSX file,
+/// - A selected sheet does not exist.
+pub(super) fn file_load_xlsx(
+	lua: &Lua,
+	runtime: &Runtime,
+	path: String,
+	options: Option<Value>,
+) -> mlua::Result<Value> {
+	let full_path =
+		runtime
+			.dir_context()
+			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_xlsx")?;
+
+	let opts = XlsxOptions::from_lua_opt(options)?;
+
+	// The workbook sheet count (to check the selected indexes)
+	let sheet_count = Cell::new(0);
+	let sheets = crate::support::xlsx::load_xlsx(&full_path, |idx, name| {
+		sheet_count.set(idx + 1);
+		opts.is_selected(idx, name)
+	})
+	.map_err(|e| {
+		Error::from(format!(
+			"aip.file.load_xlsx - Failed to load xlsx file '{path}'.\nCause: {e}"
+		))
+	})?;
+
+	// -- Check that all the selected sheets were found
+	if let Some(selectors) = &opts.sheets {
+		for selector in selectors {
+			let found = match selector {
+				SheetSelector::Name(name) => sheets.iter().any(|s| &s.name == name),
+				SheetSelector::Index(idx) => *idx < sheet_count.get(),
+			};
+			if !found {
+				return Err(Error::from(format!(
+					"aip.file.load_xlsx - Sheet {selector} not found in xlsx file '{path}'"
+				))
+				.into());
+			}
+		}
+	}
+
+	let res = lua.create_table()?;
+	for sheet in sheets {
+		res.push(sheet_to_lua(lua, &opts, sheet.name, sheet.rows)?)?;
+	}
+
+	Ok(Value::Table(res))
+}
+
+// region:    --- Options
+
+enum SheetSelector {
+	Name(String),
+	/// 0 based
+	Index(usize),
+}
+
+impl std::fmt::Display for SheetSelector {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Name(name) => write!(f, "'{name}'"),
+			Self::Index(idx) => write!(f, "#{}", idx + 1),
+		}
+	}
+}
+
+struct XlsxOptions {
+	sheets: Option<Vec<SheetSelector>>,
+	has_header: bool,
+	/// 0 based
+	header_row: usize,
+	skip_empty_rows: bool,
+}
+
+impl XlsxOptions {
+	fn from_lua_opt(options: Option<Value>) -> mlua::Result<Self> {
+		let mut opts = Self {
+			sheets: None,
+			has_header: true,
+			header_row: 0,
+			skip_empty_rows: true,
+		};
+		let Some(Value::Table(table)) = options else {
+			return Ok(opts);
+		};
+
+		opts.sheets = match table.get::<Value>("sheets")? {
+			Value::Nil => None,
+			Value::Table(list) => Some(
+				list.sequence_values::<Value>()
+					.map(|v| v.and_then(sheet_selector_from_lua))
+					.collect::<mlua::Result<Vec<_>>>()?,
+			),
+			other => Some(vec![sheet_selector_from_lua(other)?]),
+		};
+		if let Some(has_header) = table.get::<Option<bool>>("has_header")? {
+			opts.has_header = has_header;
+		}
+		if let Some(header_row) = table.get::<Option<i64>>("header_row")? {
+			if header_row < 1 {
+				return Err(Error::custom("aip.file.load_xlsx - header_row must be 1 or more").into());
+			}
+			opts.header_row = header_row as usize - 1;
+		}
+		if let Some(skip_empty_rows) = table.get::<Option<bool>>("skip_empty_rows")? {
+			opts.skip_empty_rows = skip_empty_rows;
+		}
+
+		Ok(opts)
+	}
+
+	fn is_selected(&self, idx: usize, name: &str) -> bool {
+		match &self.sheets {
+			None => true,
+			Some(selectors) => selectors.iter().any(|selector| match selector {
+				SheetSelector::Name(sel_name) => sel_name == name,
+				SheetSelector::Index(sel_idx) => *sel_idx == idx,
+			}),
+		}
+	}
+}
+
+fn sheet_selector_from_lua(value: Value) -> mlua::Result<SheetSelector> {
+	match value {
+		Value::String(name) => Ok(SheetSelector::Name(name.to_str()?.to_string())),
+		Value::Integer(idx) if idx >= 1 => Ok(SheetSelector::Index(idx as usize - 1)),
+		other => Err(Error::custom(format!(
+			"aip.file.load_xlsx - sheets must be sheet names or 1 based indexes, but was '{}'",
+			other.type_name()
+		))
+		.into()),
+	}
+}
+
+// endregion: --- Options
+
+// region:    --- Support
+
+fn sheet_to_lua(lua: &Lua, opts: &XlsxOptions, name: String, rows: Vec<Vec<JsonValue>>) -> mlua::Result<Table> {
+	let mut rows = rows.into_iter().skip(opts.header_row);
+
+	// -- Headers
+	let headers: Vec<String> = if opts.has_header {
+		let header_cells = rows.next().unwrap_or_default();
+		header_cells
+			.iter()
+			.enumerate()
+			.map(|(i, cell)| match cell_to_string(cell) {
+				Some(header) => header,
+				None => format!("col_{}", i + 1),
+			})
+			.collect()
+	} else {
+		Vec::new()
+	};
+
+	// -- Rows
+	let lua_rows = lua.create_table()?;
+	for row in rows {
+		if opts.skip_empty_rows && row.iter().all(|cell| cell_to_string(cell).is_none()) {
+			continue;
+		}
+		let lua_row = lua.create_table()?;
+		for (i, cell) in row.into_iter().enumerate() {
+			if opts.has_header {
+				if cell.is_null() {
+					continue;
+				}
+				let key = headers.get(i).cloned().unwrap_or_else(|| format!("col_{}", i + 1));
+				lua_row.set(key, lua.to_value(&cell)?)?;
+			} else if cell.is_null() {
+				lua_row.push("")?;
+			} else {
+				lua_row.push(lua.to_value(&cell)?)?;
+			}
+		}
+		lua_rows.push(lua_row)?;
+	}
+
+	let sheet = lua.create_table()?;
+	sheet.set("_type", "XlsxSheet")?;
+	sheet.set("name", name)?;
+	sheet.set("headers", headers)?;
+	sheet.set("rows", lua_rows)?;
+	Ok(sheet)
+}
+
+/// Returns the trimmed cell text, or `None` if the cell is empty.
+fn cell_to_string(cell: &JsonValue) -> Option<String> {
+	let text = match cell {
+		JsonValue::Null => return None,
+		JsonValue::String(s) => s.trim().to_string(),
+		other => other.to_string(),
+	};
+	if text.is_empty() { None } else { Some(text) }
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::run_reflective_agent;
+	use value_ext::JsonValueExt as _;
+
+	#[tokio::test]
+	async fn test_lua_file_load_xlsx_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_lua = r#"
+local people = aip.file.load_xlsx("other/simple.xlsx", { sheets = "People" })[1]
+local raw = aip.file.load_xlsx("other/simple.xlsx", { sheets = 1, has_header = false, skip_empty_rows = false })[1]
+return { people = people, raw = raw }
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent(fx_lua, None).await?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/people/_type")?, "XlsxSheet");
+		assert_eq!(res.x_get_str("/people/headers/1")?, "Age");
+		assert_eq!(res.x_get_str("/people/rows/1/Name")?, "Bob & Co");
+		assert_eq!(res.x_get_f64("/people/rows/1/Age")?, 25.5);
+		assert_eq!(res.x_get_i64("/people/rows/0/Age")?, 30);
+		assert!(!res.x_get_bool("/people/rows/1/Active")?);
+		assert_eq!(res.x_get_str("/people/rows/2/Name")?, "Carl");
+		assert!(res.pointer("/people/rows/2/Age").is_none());
+		assert_eq!(res.x_get_str("/raw/rows/0/0")?, "Name");
+		// The empty row 4 is kept
+		assert_eq!(res.x_get_str("/raw/rows/4/0")?, "Carl");
+		assert_eq!(res.x_get_str("/raw/rows/4/1")?, "");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_file_load_xlsx_missing_sheet_err() -> Result<()> {
+		// -- Exec
+		let res = run_reflective_agent(
+			r#"return aip.file.load_xlsx("other/simple.xlsx", { sheets = 3 })"#,
+			None,
+		)
+		.await;
+
+		// -- Check
+		let err = res.err().ok_or("Should be an error")?.to_string();
+		assert!(err.contains("Sheet #3 not found"), "{err}");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -11,6 +11,7 @@
 use crate::Error;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_access_read;
 use crate::support::yamls;
 use mlua::{IntoLua, Lua, Value};
 use simple_fs::read_to_string;
@@ -61,6 +62,7 @@ pub(super) fn file_load_yaml(lua: &Lua, runtime: &Runtime, path: String) -> mlua
 		runtime
 			.dir_context()
 			.resolve_path(runtime.session(), path.clone().into(), PathResolver::WksDir, None)?;
+	check_access_read(lua, &full_path, "aip.file.load_yaml")?;
 
 	let content = read_to_string(&full_path).map_err(|e| {
 		Error::from(format!(
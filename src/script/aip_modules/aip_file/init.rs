@@ -36,6 +36,14 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 		},
 	)?;
 
+	// -- rename
+	let rt = runtime.clone();
+	let file_rename_fn = lua.create_function(
+		move |lua, (path, new_name, options): (String, String, Option<FileOverOptions>)| {
+			file_rename(lua, &rt, path, new_name, options)
+		},
+	)?;
+
 	// -- append
 	let rt = runtime.clone();
 	let file_append_fn =
@@ -43,7 +51,8 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 
 	// -- delete
 	let rt = runtime.clone();
-	let file_delete_fn = lua.create_function(move |lua, (path,): (String,)| file_delete(lua, &rt, path))?;
+	let file_delete_fn =
+		lua.create_function(move |lua, (path, options): (String, Option<Value>)| file_delete(lua, &rt, path, options))?;
 
 	// -- ensure_exists
 	let rt = runtime.clone();
@@ -65,6 +74,23 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let rt = runtime.clone();
 	let file_info_fn = lua.create_function(move |lua, path: Value| file_info(lua, &rt, path))?;
 
+	// -- metadata
+	let rt = runtime.clone();
+	let file_metadata_fn = lua.create_function(move |lua, path: String| file_metadata(lua, &rt, path))?;
+
+	// -- chmod
+	let rt = runtime.clone();
+	let file_chmod_fn =
+		lua.create_function(move |lua, (path, mode): (String, Value)| file_chmod(lua, &rt, path, mode))?;
+
+	// -- symlink
+	let rt = runtime.clone();
+	let file_symlink_fn = lua.create_function(
+		move |lua, (target, link_path, options): (String, String, Option<FileOverOptions>)| {
+			file_symlink(lua, &rt, target, link_path, options)
+		},
+	)?;
+
 	// -- list
 	let rt = runtime.clone();
 	let file_list_fn =
@@ -197,11 +223,22 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let file_load_docx_as_md_fn =
 		lua.create_function(move |lua, (docx_path,): (String,)| file_load_docx_as_md(lua, &rt, docx_path))?;
 
+	// -- save_docx
+	let rt = runtime.clone();
+	let file_save_docx_fn =
+		lua.create_function(move |lua, (path, content): (String, Value)| file_save_docx(lua, &rt, path, content))?;
+
+	// -- load_xlsx
+	let rt = runtime.clone();
+	let file_load_xlsx_fn = lua.create_function(move |lua, (path, options): (String, Option<Value>)| {
+		file_load_xlsx(lua, &rt, path, options)
+	})?;
+
 	// -- save_changes
 
 	let rt = runtime.clone();
 	let file_save_changes_fn =
-		lua.create_function(move |lua, (path, changes): (String, String)| file_save_changes(lua, &rt, path, changes))?;
+		lua.create_function(move |lua, (path, changes): (String, Value)| file_save_changes(lua, &rt, path, changes))?;
 
 	// -- line_spans
 	let rt = runtime.clone();
@@ -257,12 +294,16 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	table.set("save", file_save_fn)?;
 	table.set("copy", file_copy_fn)?;
 	table.set("move", file_move_fn)?;
+	table.set("rename", file_rename_fn)?;
 	table.set("append", file_append_fn)?;
 	table.set("delete", file_delete_fn)?;
 	table.set("ensure_exists", file_ensure_exists_fn)?;
 	table.set("ensure_dir", file_ensure_dir_fn)?;
 	table.set("exists", file_exists_fn)?;
 	table.set("info", file_info_fn)?;
+	table.set("metadata", file_metadata_fn)?;
+	table.set("chmod", file_chmod_fn)?;
+	table.set("symlink", file_symlink_fn)?;
 	table.set("list", file_list_fn)?;
 	table.set("list_load", file_list_load_fn)?;
 	table.set("first", file_first_fn)?;
@@ -287,6 +328,8 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	table.set("load_html_as_md", file_load_html_as_md_fn)?;
 	table.set("save_docx_to_md", file_save_docx_to_md_fn)?;
 	table.set("load_docx_as_md", file_load_docx_as_md_fn)?;
+	table.set("save_docx", file_save_docx_fn)?;
+	table.set("load_xlsx", file_load_xlsx_fn)?;
 	table.set("save_changes", file_save_changes_fn)?;
 	table.set("line_spans", file_line_spans_fn)?;
 	table.set("csv_row_spans", file_csv_row_spans_fn)?;
@@ -5,10 +5,12 @@ mod file_hash;
 mod file_html;
 mod file_json;
 mod file_md;
+mod file_meta;
 mod file_read;
 mod file_spans;
 mod file_toml;
 mod file_write;
+mod file_xlsx;
 mod file_yaml;
 
 use file_change::*;
@@ -18,10 +20,12 @@ use file_hash::*;
 use file_html::*;
 use file_json::*;
 use file_md::*;
+use file_meta::*;
 use file_read::*;
 use file_spans::*;
 use file_toml::*;
 use file_write::*;
+use file_xlsx::*;
 use file_yaml::*;
 
 mod init;
@@ -12,12 +12,23 @@
 //! - `aip.flow.before_all_response(data: BeforeAllData) -> table`
 //! - `aip.flow.data_response(data: DataData) -> table`
 //! - `aip.flow.skip(reason?: string) -> table`
+//! - `aip.flow.skip_if(cond: any, reason?: string) -> table | nil`
+//! - `aip.flow.skip_unless(cond: any, reason?: string) -> table | nil`
 //! - `aip.flow.redo_run() -> table`
+//! - `aip.flow.ask_user(question: string, options?: AskUserOptions) -> string | nil`
+//! - `aip.flow.approve(summary: string, options?: ApproveOptions) -> ApproveResponse`
 
-use crate::Result;
+use crate::hub::{HubEvent, get_hub};
 use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::ask_user_choice;
+use crate::tui_v1::PromptParams;
+use crate::{Error, Result};
 use mlua::{Lua, Table, Value};
 
+/// The max number of times the question is asked again on invalid answers (not one of the choices)
+const ASK_USER_MAX_ATTEMPTS: usize = 3;
+
 pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
@@ -33,6 +44,18 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	let skip_fn = lua.create_function(aipack_skip)?;
 	table.set("skip", skip_fn)?;
 
+	let skip_if_fn = lua.create_function(aipack_skip_if)?;
+	table.set("skip_if", skip_if_fn)?;
+
+	let skip_unless_fn = lua.create_function(aipack_skip_unless)?;
+	table.set("skip_unless", skip_unless_fn)?;
+
+	let ask_user_fn = lua.create_function(aipack_ask_user)?;
+	table.set("ask_user", ask_user_fn)?;
+
+	let approve_fn = lua.create_function(aipack_approve)?;
+	table.set("approve", approve_fn)?;
+
 	Ok(table)
 }
 
@@ -58,9 +81,21 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 ///     inputs?:  any[],        // Optional. A list of new inputs to use for the agent run cycle. Overrides initial inputs.
 ///     options?: AgentOptions, // Optional. Partial AgentOptions to override for this run.
 ///     before_all?: any,       // Optional. The before_all data that can be access via before_all...
+///     tools?: AgentTool[],    // Optional. The tools (functions) the model can call during the AI stage.
 ///   } & any // Can also include other arbitrary data fields if needed.
+///
+///   type AgentTool = {
+///     name: string,
+///     description?: string,
+///     schema?: table,  // The JSON schema of the tool arguments
+///     handler: string, // Lua script run for each tool call, with `tool_args` (and `args`, `options`) in scope.
+///                      // Its return (string, or table as JSON) is sent back to the model.
+///   }
 ///   ```
 ///
+///   When `tools` are given, the AI stage runs the `handler` of each tool call and sends the responses
+///   back until the model answers without tool calls.
+///
 ///
 /// ### Example
 ///
@@ -125,6 +160,8 @@ fn aipack_before_all_response(lua: &Lua, data: Value) -> mlua::Result<Value> {
 ///     input?: any | nil,     // Optional. The new input to use for this cycle. If nil, the original input is used.
 ///     data?: any | nil,      // Data that will be available in the next stage. Same as returning a simple data.
 ///     options?: AgentOptions // Optional. Partial AgentOptions to override for this cycle.
+///     messages?: {role: "user" | "assistant", content: string}[] // Optional. The conversation messages sent
+///                            // before the agent prompt (after the `# System`), for the multi-turn agents.
 ///   } & any // Can also include other arbitrary data fields (e.g., computed values, flags)
 ///   ```
 ///
@@ -138,6 +175,15 @@ fn aipack_before_all_response(lua: &Lua, data: Value) -> mlua::Result<Value> {
 ///   options = { model = "gpt-5" },
 /// })
 /// -- The agent executor will process this result table.
+///
+/// -- Multi-turn, with the previous turns (see also the agent option `conversation = true`)
+/// return aip.flow.data_response({
+///   data = data,
+///   messages = {
+///     { role = "user",      content = "What is the capital of France?" },
+///     { role = "assistant", content = "Paris." },
+///   },
+/// })
 /// ```
 ///
 /// ### Error
@@ -230,6 +276,62 @@ fn aipack_skip(lua: &Lua, reason: Option<String>) -> mlua::Result<Value> {
 	Ok(Value::Table(outer))
 }
 
+/// ## Lua Documentation
+///
+/// Returns the skip response (see `aip.flow.skip`) when `cond` is truthy (not `nil` or `false`), otherwise `nil`.
+///
+/// Meant to be combined with `or`, so that the `data` block returns the skip or its data in one line.
+///
+/// ```lua
+/// -- API Signature
+/// aip.flow.skip_if(cond: any, reason?: string) -> table | nil
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// -- Skip the empty files (see also the agent option `input_filter`, to not create their tasks)
+/// return aip.flow.skip_if(input.size == 0, "Empty file") or { file = aip.file.load(input.path) }
+/// ```
+///
+/// ### Error
+///
+/// This function does not directly return any errors. Errors might occur during the creation of lua table.
+fn aipack_skip_if(lua: &Lua, (cond, reason): (Value, Option<String>)) -> mlua::Result<Value> {
+	if is_truthy(&cond) {
+		aipack_skip(lua, reason)
+	} else {
+		Ok(Value::Nil)
+	}
+}
+
+/// ## Lua Documentation
+///
+/// Returns the skip response (see `aip.flow.skip`) when `cond` is falsy (`nil` or `false`), otherwise `nil`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.flow.skip_unless(cond: any, reason?: string) -> table | nil
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local content = aip.file.load(input.path).content
+/// return aip.flow.skip_unless(content:find("TODO"), "No TODO") or { content = content }
+/// ```
+///
+/// ### Error
+///
+/// This function does not directly return any errors. Errors might occur during the creation of lua table.
+fn aipack_skip_unless(lua: &Lua, (cond, reason): (Value, Option<String>)) -> mlua::Result<Value> {
+	if is_truthy(&cond) {
+		Ok(Value::Nil)
+	} else {
+		aipack_skip(lua, reason)
+	}
+}
+
 /// ## Lua Documentation
 ///
 /// Returns a response instructing AIPACK to redo the entire agent execution.
@@ -277,8 +379,284 @@ fn aipack_redo_run(lua: &Lua, _: ()) -> mlua::Result<Value> {
 	Ok(Value::Table(outer))
 }
 
+/// ## Lua Documentation
+///
+/// Asks the user a question and returns the answer (human-in-the-loop agents).
+///
+/// The calling task waits for the answer, which is asked with an input dialog in the TUI,
+/// or with a terminal prompt otherwise (e.g., `--single-shot` or the legacy terminal UI).
+///
+/// ```lua
+/// -- API Signature
+/// aip.flow.ask_user(question: string, options?: AskUserOptions) -> string | nil
+/// ```
+///
+/// ### Arguments
+///
+/// - `question: string` - The question to ask.
+/// - `options?: table`
+///   ```ts
+///   type AskUserOptions = {
+///     choices?: string[], // The valid answers (case insensitive, or their 1-based number). Any text if absent.
+///     default?: string,   // The answer when the user just press Enter.
+///   }
+///   ```
+///
+/// ### Returns
+///
+/// The answer (trimmed, and as declared in `choices` when choices), or `nil` if the user cancelled (`Esc` in the TUI).
+///
+/// ### Example
+///
+/// ```lua
+/// local answer = aip.flow.ask_user("Apply the changes to " .. input.path .. "?", {
+///   choices = {"yes", "no"},
+///   default = "yes",
+/// })
+/// if answer ~= "yes" then
+///   return aip.flow.skip("Not approved by the user")
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the options are invalid (e.g., the default is not one of the choices),
+/// if there is no UI to ask the user, or after 3 invalid answers.
+fn aipack_ask_user(_lua: &Lua, (question, options): (String, Option<Value>)) -> mlua::Result<Option<String>> {
+	let options = options.unwrap_or(Value::Nil);
+	let choices = choices_from_options(&options)?;
+	let default = options.x_get_string("default");
+	if let Some(default) = default.as_deref()
+		&& !choices.is_empty()
+		&& !choices.iter().any(|choice| choice.eq_ignore_ascii_case(default))
+	{
+		return Err(Error::custom(format!(
+			"aip.flow.ask_user - default '{default}' must be one of the choices {choices:?}"
+		))
+		.into());
+	}
+
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let mut message = format!("\n-? {question}\n");
+	for _ in 0..ASK_USER_MAX_ATTEMPTS {
+		let (params, rx) = PromptParams::new(message.clone());
+		let params = params.with_choices(choices.clone(), default.clone());
+
+		let answer = tokio::task::block_in_place(|| {
+			rt.block_on(async {
+				get_hub().publish(HubEvent::Prompt(params)).await;
+				rx.recv().await
+			})
+		})
+		.map_err(|err| Error::custom(format!("aip.flow.ask_user - no user answer. Cause: {err}")))?;
+
+		let Some(answer) = answer else {
+			return Ok(None);
+		};
+		match resolve_answer(&answer, &choices, default.as_deref()) {
+			Some(answer) => return Ok(Some(answer)),
+			None => {
+				message = format!(
+					"\n-! Invalid answer '{}', must be one of {choices:?}\n-? {question}\n",
+					answer.trim()
+				)
+			}
+		}
+	}
+
+	Err(Error::custom(format!(
+		"aip.flow.ask_user - no valid answer after {ASK_USER_MAX_ATTEMPTS} attempts (choices: {choices:?})"
+	))
+	.into())
+}
+
+/// ## Lua Documentation
+///
+/// Asks the user to approve a step (e.g., before changing a production config), and returns the decision.
+///
+/// The calling task waits for the decision, which is asked in the TUI with the `diff` (or the summary) to review,
+/// and the `approve`, `reject`, and `edit` choices (`edit` then asks for the changes to make).
+/// In non-interactive mode (e.g., `aip serve`, `aip schedule --daemon`, or no terminal), it follows
+/// the `non_interactive` option, which fails by default.
+///
+/// ```lua
+/// -- API Signature
+/// aip.flow.approve(summary: string, options?: ApproveOptions) -> ApproveResponse
+/// ```
+///
+/// ### Arguments
+///
+/// - `summary: string` - What is to be approved (e.g., "Update the prod nginx.conf").
+/// - `options?: table`
+///   ```ts
+///   type ApproveOptions = {
+///     diff?: string,        // The content to review (e.g., a unified diff). Default the summary.
+///     allow_edit?: boolean, // If false, only the `approve` and `reject` choices (default true)
+///     non_interactive?: "approve" | "reject" | "fail", // The decision without a user (default "fail")
+///   }
+///   ```
+///
+/// ### Returns
+///
+/// ```ts
+/// type ApproveResponse = {
+///   decision: "approve" | "reject" | "edit",
+///   approved: boolean,      // true only for "approve"
+///   edit?: string,          // The changes asked by the user (for "edit")
+///   by: "user" | "default", // "default" when from the `non_interactive` option
+/// }
+/// ```
+///
+/// Closing the prompt (`Esc` in the TUI) rejects.
+///
+/// ### Example
+///
+/// ```lua
+/// local res = aip.flow.approve("Update " .. input.path, { diff = data.diff, non_interactive = "reject" })
+/// if res.decision == "edit" then
+///   return aip.flow.skip("Changes requested: " .. res.edit)
+/// elseif not res.approved then
+///   return aip.flow.skip("Not approved")
+/// end
+/// aip.file.save(input.path, data.new_content)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the options are invalid, or in non-interactive mode when `non_interactive` is `"fail"` (the default).
+fn aipack_approve(lua: &Lua, (summary, options): (String, Option<Value>)) -> mlua::Result<Value> {
+	let options = options.unwrap_or(Value::Nil);
+	let allow_edit = options.x_get_bool("allow_edit").unwrap_or(true);
+	let non_interactive = options.x_get_string("non_interactive");
+	let non_interactive = non_interactive.as_deref().unwrap_or("fail");
+	if !matches!(non_interactive, "approve" | "reject" | "fail") {
+		return Err(Error::custom(format!(
+			"aip.flow.approve - non_interactive '{non_interactive}' not supported. Must be 'approve', 'reject', or 'fail'"
+		))
+		.into());
+	}
+
+	let res = lua.create_table()?;
+
+	// -- Non-interactive
+	if !get_hub().is_interactive() {
+		if non_interactive == "fail" {
+			return Err(Error::custom(format!(
+				"aip.flow.approve - '{summary}' requires a user approval, but there is no interactive UI \
+(set the option non_interactive to 'approve' or 'reject' to decide without a user)"
+			))
+			.into());
+		}
+		res.set("decision", non_interactive)?;
+		res.set("approved", non_interactive == "approve")?;
+		res.set("by", "default")?;
+		return Ok(Value::Table(res));
+	}
+
+	// -- Interactive
+	let choices: Vec<String> = ["approve", "reject", "edit"]
+		.into_iter()
+		.filter(|choice| allow_edit || *choice != "edit")
+		.map(|choice| choice.to_string())
+		.collect();
+	let choice_refs: Vec<&str> = choices.iter().map(|choice| choice.as_str()).collect();
+	let preview = options.x_get_string("diff").unwrap_or_else(|| summary.clone());
+
+	let mut message = format!("\n-? Approval required: {summary}\n");
+	let mut decision = "reject".to_string();
+	for _ in 0..ASK_USER_MAX_ATTEMPTS {
+		let answer = ask_user_choice(message.clone(), &choice_refs, "reject", preview.clone())?;
+		match resolve_answer(&answer, &choices, Some("reject")) {
+			Some(answer) => {
+				decision = answer;
+				break;
+			}
+			None => {
+				message = format!(
+					"\n-! Invalid answer '{}', must be one of {choices:?}\n-? Approval required: {summary}\n",
+					answer.trim()
+				)
+			}
+		}
+	}
+
+	if decision == "edit" {
+		let (params, rx) = PromptParams::new(format!("\n-? Changes to make for: {summary}\n"));
+		let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+		let edit = tokio::task::block_in_place(|| {
+			rt.block_on(async {
+				get_hub().publish(HubEvent::Prompt(params)).await;
+				rx.recv().await
+			})
+		})
+		.map_err(|err| Error::custom(format!("aip.flow.approve - no user answer. Cause: {err}")))?;
+		match edit.map(|edit| edit.trim().to_string()).filter(|edit| !edit.is_empty()) {
+			Some(edit) => res.set("edit", edit)?,
+			// NOTE: No changes given (or cancelled), then, rejected
+			None => decision = "reject".to_string(),
+		}
+	}
+
+	res.set("approved", decision == "approve")?;
+	res.set("decision", decision)?;
+	res.set("by", "user")?;
+
+	Ok(Value::Table(res))
+}
+
 // endregion: --- Lua Functions
 
+// region:    --- Support
+
+/// The Lua truthiness (only `nil` and `false` are falsy)
+fn is_truthy(value: &Value) -> bool {
+	!matches!(value, Value::Nil | Value::Boolean(false))
+}
+
+fn choices_from_options(options: &Value) -> Result<Vec<String>> {
+	let Some(choices) = options.x_get_value("choices") else {
+		return Ok(Vec::new());
+	};
+	let err = || Error::custom("aip.flow.ask_user - 'choices' must be a list of strings");
+	let Value::Table(choices) = choices else {
+		return Err(err());
+	};
+	let mut res = Vec::new();
+	for choice in choices.sequence_values::<Value>() {
+		match choice? {
+			Value::String(choice) => res.push(choice.to_string_lossy()),
+			_ => return Err(err()),
+		}
+	}
+	Ok(res)
+}
+
+/// Returns the answer (the default if empty, the declared choice if choices), or None if not a valid choice.
+fn resolve_answer(answer: &str, choices: &[String], default: Option<&str>) -> Option<String> {
+	let answer = answer.trim();
+	let answer = match (answer.is_empty(), default) {
+		(true, Some(default)) => default,
+		_ => answer,
+	};
+
+	if choices.is_empty() {
+		return Some(answer.to_string());
+	}
+
+	if let Some(choice) = choices.iter().find(|choice| choice.eq_ignore_ascii_case(answer)) {
+		return Some(choice.clone());
+	}
+	// The 1-based number of the choice
+	answer
+		.parse::<usize>()
+		.ok()
+		.and_then(|num| num.checked_sub(1))
+		.and_then(|idx| choices.get(idx))
+		.cloned()
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -352,6 +730,104 @@ mod tests {
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_script_lua_aip_flow_skip_if_and_unless() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_flow::init_module, "flow").await?;
+
+		// -- Exec
+		let skip_if_true = eval_lua(&lua, r#"return aip.flow.skip_if(1 < 2, "Too small")"#)?;
+		let skip_if_false = eval_lua(&lua, r#"return aip.flow.skip_if(nil, "Nope") or "data""#)?;
+		let skip_unless_false = eval_lua(&lua, r#"return aip.flow.skip_unless(false)"#)?;
+		let skip_unless_true = eval_lua(&lua, r#"return aip.flow.skip_unless(0, "Nope") or "data""#)?;
+
+		// -- Check
+		assert_eq!(skip_if_true.x_get_str("/_aipack_/kind")?, "Skip");
+		assert_eq!(skip_if_true.x_get_str("/_aipack_/data/reason")?, "Too small");
+		assert_eq!(skip_if_false.as_str(), Some("data"));
+		assert_eq!(skip_unless_false.x_get_str("/_aipack_/kind")?, "Skip");
+		// NOTE: 0 is truthy in Lua
+		assert_eq!(skip_unless_true.as_str(), Some("data"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_script_lua_aip_flow_ask_user_invalid_options() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_flow::init_module, "flow").await?;
+
+		// -- Exec & Check
+		assert!(eval_lua(&lua, r#"return aip.flow.ask_user("Ok?", {choices = {"yes", 2}})"#).is_err());
+		assert!(
+			eval_lua(
+				&lua,
+				r#"return aip.flow.ask_user("Ok?", {choices = {"yes"}, default = "no"})"#
+			)
+			.is_err()
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_script_lua_aip_flow_approve_non_interactive() -> Result<()> {
+		// -- Setup & Fixtures
+		// NOTE: The hub is not interactive in the tests (only `aip run` in a terminal)
+		let lua = setup_lua(aip_flow::init_module, "flow").await?;
+
+		// -- Exec
+		let approved = eval_lua(
+			&lua,
+			r#"return aip.flow.approve("Update prod.toml", {non_interactive = "approve"})"#,
+		)?;
+		let rejected = eval_lua(
+			&lua,
+			r#"return aip.flow.approve("Update prod.toml", {diff = "-a\n+b", non_interactive = "reject"})"#,
+		)?;
+		let fail_err = eval_lua(&lua, r#"return aip.flow.approve("Update prod.toml")"#)
+			.err()
+			.ok_or("should fail without a user")?;
+		let invalid_res = eval_lua(&lua, r#"return aip.flow.approve("Ok?", {non_interactive = "maybe"})"#);
+
+		// -- Check
+		assert_eq!(approved.x_get_str("decision")?, "approve");
+		assert!(approved.x_get_bool("approved")?);
+		assert_eq!(approved.x_get_str("by")?, "default");
+		assert_eq!(rejected.x_get_str("decision")?, "reject");
+		assert!(!rejected.x_get_bool("approved")?);
+		assert!(fail_err.to_string().contains("requires a user approval"));
+		assert!(invalid_res.is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_script_lua_aip_flow_resolve_answer() -> Result<()> {
+		// -- Setup & Fixtures
+		let choices = vec!["Yes".to_string(), "No".to_string()];
+
+		// -- Exec & Check
+		assert_eq!(
+			aip_flow::resolve_answer(" yes\n", &choices, None).as_deref(),
+			Some("Yes")
+		);
+		assert_eq!(aip_flow::resolve_answer("2", &choices, None).as_deref(), Some("No"));
+		assert_eq!(
+			aip_flow::resolve_answer("\n", &choices, Some("No")).as_deref(),
+			Some("No")
+		);
+		assert_eq!(aip_flow::resolve_answer("maybe", &choices, None), None);
+		assert_eq!(aip_flow::resolve_answer("3", &choices, None), None);
+		assert_eq!(aip_flow::resolve_answer("", &[], None).as_deref(), Some(""));
+		assert_eq!(
+			aip_flow::resolve_answer(" free text \n", &[], Some("x")).as_deref(),
+			Some("free text")
+		);
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_script_lua_aip_flow_redo_run() -> Result<()> {
 		// -- Setup & Fixtures
@@ -0,0 +1,604 @@
+//! The GitHub / GitLab REST client of `aip.gh`, with the responses normalized to the same shapes
+//! (issues, pull requests / merge requests, and comments).
+
+use crate::types::WebOptions;
+use crate::{Error, Result};
+use reqwest::{Client, Method, RequestBuilder, header};
+use serde_json::{Value, json};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITLAB_API_URL: &str = "https://gitlab.com/api/v4";
+const GITHUB_API_VERSION: &str = "2022-11-28";
+
+/// The max pages of the GitLab merge request diffs (100 files per page)
+const GITLAB_DIFFS_MAX_PAGES: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhProvider {
+	GitHub,
+	GitLab,
+}
+
+impl GhProvider {
+	pub fn from_name(name: Option<&str>) -> Result<Self> {
+		match name {
+			None | Some("github") => Ok(Self::GitHub),
+			Some("gitlab") => Ok(Self::GitLab),
+			Some(other) => Err(Error::custom(format!(
+				"provider '{other}' not supported. Must be 'github' or 'gitlab'"
+			))),
+		}
+	}
+
+	/// The default token environment variable (or keychain name)
+	pub fn default_token_env(&self) -> &'static str {
+		match self {
+			Self::GitHub => "GITHUB_TOKEN",
+			Self::GitLab => "GITLAB_TOKEN",
+		}
+	}
+
+	fn name(&self) -> &'static str {
+		match self {
+			Self::GitHub => "GitHub",
+			Self::GitLab => "GitLab",
+		}
+	}
+}
+
+pub struct GhClient {
+	provider: GhProvider,
+	base_url: String,
+	token: Option<String>,
+	http_client: Client,
+}
+
+/// Constructor
+impl GhClient {
+	pub fn new(
+		provider: GhProvider,
+		base_url: Option<String>,
+		token: Option<String>,
+		web_options: WebOptions,
+	) -> Result<Self> {
+		let base_url = base_url.unwrap_or_else(|| {
+			match provider {
+				GhProvider::GitHub => GITHUB_API_URL,
+				GhProvider::GitLab => GITLAB_API_URL,
+			}
+			.to_string()
+		});
+		let http_client = web_options
+			.apply_to_reqwest_builder(Client::builder())
+			.build()
+			.map_err(Error::from)?;
+
+		Ok(Self {
+			provider,
+			base_url: base_url.trim_end_matches('/').to_string(),
+			token,
+			http_client,
+		})
+	}
+}
+
+/// The API calls (returning the normalized values)
+impl GhClient {
+	/// Create a pull request (GitLab merge request), on the repo default branch when no `base`
+	pub async fn create_pr(&self, repo: &str, pr: &GhPrCreate) -> Result<Value> {
+		let base = match pr.base.as_deref() {
+			Some(base) => base.to_string(),
+			None => self.default_branch(repo).await?,
+		};
+
+		let res = match self.provider {
+			GhProvider::GitHub => {
+				let body = json!({
+					"title": pr.title,
+					"head": pr.head,
+					"base": base,
+					"body": pr.body,
+					"draft": pr.draft,
+				});
+				self.send_json(Method::POST, &format!("/repos/{repo}/pulls"), &[], Some(body))
+					.await?
+			}
+			GhProvider::GitLab => {
+				let title = if pr.draft {
+					format!("Draft: {}", pr.title)
+				} else {
+					pr.title.clone()
+				};
+				let body = json!({
+					"title": title,
+					"source_branch": pr.head,
+					"target_branch": base,
+					"description": pr.body,
+				});
+				let path = format!("/projects/{}/merge_requests", project_id(repo));
+				self.send_json(Method::POST, &path, &[], Some(body)).await?
+			}
+		};
+
+		Ok(self.normalize_pr(&res))
+	}
+
+	pub async fn list_issues(&self, repo: &str, filter: &GhIssueFilter) -> Result<Vec<Value>> {
+		let state = filter.state.as_deref().unwrap_or("open");
+		let mut query: Vec<(&str, String)> = Vec::new();
+		if let Some(labels) = filter.labels.as_ref() {
+			query.push(("labels", labels.join(",")));
+		}
+		if let Some(per_page) = filter.per_page {
+			query.push(("per_page", per_page.to_string()));
+		}
+		if let Some(page) = filter.page {
+			query.push(("page", page.to_string()));
+		}
+
+		let (path, res) = match self.provider {
+			GhProvider::GitHub => {
+				query.push(("state", state.to_string()));
+				if let Some(assignee) = filter.assignee.as_ref() {
+					query.push(("assignee", assignee.to_string()));
+				}
+				let path = format!("/repos/{repo}/issues");
+				let res = self.send_json(Method::GET, &path, &query, None).await?;
+				(path, res)
+			}
+			GhProvider::GitLab => {
+				let state = if state == "open" { "opened" } else { state };
+				query.push(("state", state.to_string()));
+				if let Some(assignee) = filter.assignee.as_ref() {
+					query.push(("assignee_username", assignee.to_string()));
+				}
+				let path = format!("/projects/{}/issues", project_id(repo));
+				let res = self.send_json(Method::GET, &path, &query, None).await?;
+				(path, res)
+			}
+		};
+
+		let items = res.as_array().ok_or_else(|| {
+			Error::custom(format!(
+				"{} API response of '{path}' is not a list",
+				self.provider.name()
+			))
+		})?;
+		let issues = items
+			.iter()
+			.map(|item| self.normalize_issue(item))
+			// NOTE: The GitHub issues list has the pull requests as well
+			.filter(|issue| filter.include_prs || issue.get("is_pr") != Some(&Value::Bool(true)))
+			.collect();
+
+		Ok(issues)
+	}
+
+	pub async fn get_issue(&self, repo: &str, number: i64) -> Result<Value> {
+		let path = match self.provider {
+			GhProvider::GitHub => format!("/repos/{repo}/issues/{number}"),
+			GhProvider::GitLab => format!("/projects/{}/issues/{number}", project_id(repo)),
+		};
+		let res = self.send_json(Method::GET, &path, &[], None).await?;
+		Ok(self.normalize_issue(&res))
+	}
+
+	/// Comment an issue or a pull request (`is_pr` is only needed for GitLab, the merge request notes)
+	pub async fn comment(&self, repo: &str, number: i64, body: &str, is_pr: bool) -> Result<Value> {
+		let path = match self.provider {
+			GhProvider::GitHub => format!("/repos/{repo}/issues/{number}/comments"),
+			GhProvider::GitLab if is_pr => format!("/projects/{}/merge_requests/{number}/notes", project_id(repo)),
+			GhProvider::GitLab => format!("/projects/{}/issues/{number}/notes", project_id(repo)),
+		};
+		let res = self.send_json(Method::POST, &path, &[], Some(json!({ "body": body }))).await?;
+		Ok(self.normalize_comment(&res))
+	}
+
+	/// The unified diff of the pull request (GitLab merge request)
+	pub async fn get_pr_diff(&self, repo: &str, number: i64) -> Result<String> {
+		match self.provider {
+			GhProvider::GitHub => {
+				let path = format!("/repos/{repo}/pulls/{number}");
+				let req = self
+					.request(Method::GET, &path, &[])?
+					.header(header::ACCEPT, "application/vnd.github.diff");
+				self.send(req, Method::GET, &path).await
+			}
+			GhProvider::GitLab => {
+				let path = format!("/projects/{}/merge_requests/{number}/diffs", project_id(repo));
+				let mut diff = String::new();
+				for page in 1..=GITLAB_DIFFS_MAX_PAGES {
+					let query = [("per_page", "100".to_string()), ("page", page.to_string())];
+					let res = self.send_json(Method::GET, &path, &query, None).await?;
+					let files = res.as_array().map(Vec::as_slice).unwrap_or_default();
+					diff.push_str(&gitlab_diffs_to_unified(files));
+					if files.len() < 100 {
+						break;
+					}
+				}
+				Ok(diff)
+			}
+		}
+	}
+
+	async fn default_branch(&self, repo: &str) -> Result<String> {
+		let path = match self.provider {
+			GhProvider::GitHub => format!("/repos/{repo}"),
+			GhProvider::GitLab => format!("/projects/{}", project_id(repo)),
+		};
+		let res = self.send_json(Method::GET, &path, &[], None).await?;
+		res.get("default_branch")
+			.and_then(Value::as_str)
+			.map(str::to_string)
+			.ok_or_else(|| Error::custom(format!("Cannot find the default branch of repo '{repo}'")))
+	}
+}
+
+/// The requests
+impl GhClient {
+	fn request(&self, method: Method, path: &str, query: &[(&str, String)]) -> Result<RequestBuilder> {
+		let mut url = url::Url::parse(&format!("{}{path}", self.base_url))
+			.map_err(|err| Error::cc(format!("Invalid {} API url for '{path}'", self.provider.name()), err))?;
+		if !query.is_empty() {
+			url.query_pairs_mut()
+				.extend_pairs(query.iter().map(|(name, value)| (*name, value.as_str())));
+		}
+
+		let mut req = self.http_client.request(method, url);
+		req = match self.provider {
+			GhProvider::GitHub => req
+				.header(header::ACCEPT, "application/vnd.github+json")
+				.header("X-GitHub-Api-Version", GITHUB_API_VERSION),
+			GhProvider::GitLab => req.header(header::ACCEPT, "application/json"),
+		};
+		if let Some(token) = self.token.as_deref() {
+			req = match self.provider {
+				GhProvider::GitHub => req.bearer_auth(token),
+				GhProvider::GitLab => req.header("PRIVATE-TOKEN", token),
+			};
+		}
+
+		Ok(req)
+	}
+
+	async fn send_json(
+		&self,
+		method: Method,
+		path: &str,
+		query: &[(&str, String)],
+		body: Option<Value>,
+	) -> Result<Value> {
+		let mut req = self.request(method.clone(), path, query)?;
+		if let Some(body) = body {
+			req = req.header(header::CONTENT_TYPE, "application/json").body(body.to_string());
+		}
+		let text = self.send(req, method, path).await?;
+		serde_json::from_str(&text).map_err(|err| {
+			Error::cc(
+				format!("{} API response of '{path}' is not valid json", self.provider.name()),
+				err,
+			)
+		})
+	}
+
+	/// Send the request, and return the response text (an error for the non-2xx responses, with the API message)
+	async fn send(&self, req: RequestBuilder, method: Method, path: &str) -> Result<String> {
+		let provider = self.provider.name();
+		let response = req
+			.send()
+			.await
+			.map_err(|err| Error::cc(format!("{provider} API request '{method} {path}' failed"), err))?;
+		let status = response.status();
+		let text = response.text().await.map_err(|err| {
+			Error::cc(
+				format!("{provider} API request '{method} {path}' failed reading the response"),
+				err,
+			)
+		})?;
+
+		if !status.is_success() {
+			let message = serde_json::from_str::<Value>(&text)
+				.ok()
+				.and_then(|res| res.get("message").or_else(|| res.get("error")).cloned())
+				.map(|message| match message {
+					Value::String(message) => message,
+					other => other.to_string(),
+				})
+				.unwrap_or_else(|| text.chars().take(200).collect());
+			let hint = if matches!(status.as_u16(), 401 | 403) && self.token.is_none() {
+				format!(" (no token, set {})", self.provider.default_token_env())
+			} else {
+				String::new()
+			};
+			return Err(Error::custom(format!(
+				"{provider} API error (status {}) for '{method} {path}'{hint}: {message}",
+				status.as_u16()
+			)));
+		}
+
+		Ok(text)
+	}
+}
+
+// region:    --- Params
+
+#[derive(Debug, Default)]
+pub struct GhPrCreate {
+	pub title: String,
+	pub head: String,
+	pub base: Option<String>,
+	pub body: Option<String>,
+	pub draft: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct GhIssueFilter {
+	/// "open" (default), "closed", or "all"
+	pub state: Option<String>,
+	pub labels: Option<Vec<String>>,
+	pub assignee: Option<String>,
+	pub per_page: Option<i64>,
+	pub page: Option<i64>,
+	/// Keep the pull requests (the GitHub issues list has them)
+	pub include_prs: bool,
+}
+
+// endregion: --- Params
+
+// region:    --- Normalize
+
+impl GhClient {
+	/// `{number, title, body?, state, url, author?, labels, assignees, comments_count?, is_pr, created_at, updated_at}`
+	fn normalize_issue(&self, item: &Value) -> Value {
+		match self.provider {
+			GhProvider::GitHub => json!({
+				"number": item.get("number"),
+				"title": item.get("title"),
+				"body": item.get("body"),
+				"state": item.get("state"),
+				"url": item.get("html_url"),
+				"author": item.pointer("/user/login"),
+				"labels": names(item.get("labels"), "name"),
+				"assignees": names(item.get("assignees"), "login"),
+				"comments_count": item.get("comments"),
+				"is_pr": item.get("pull_request").is_some(),
+				"created_at": item.get("created_at"),
+				"updated_at": item.get("updated_at"),
+			}),
+			GhProvider::GitLab => json!({
+				"number": item.get("iid"),
+				"title": item.get("title"),
+				"body": item.get("description"),
+				"state": gitlab_state(item.get("state")),
+				"url": item.get("web_url"),
+				"author": item.pointer("/author/username"),
+				"labels": names(item.get("labels"), "name"),
+				"assignees": names(item.get("assignees"), "username"),
+				"comments_count": item.get("user_notes_count"),
+				"is_pr": false,
+				"created_at": item.get("created_at"),
+				"updated_at": item.get("updated_at"),
+			}),
+		}
+	}
+
+	/// `{number, title, body?, state, url, author?, head, base, draft, created_at}`
+	fn normalize_pr(&self, item: &Value) -> Value {
+		match self.provider {
+			GhProvider::GitHub => json!({
+				"number": item.get("number"),
+				"title": item.get("title"),
+				"body": item.get("body"),
+				"state": item.get("state"),
+				"url": item.get("html_url"),
+				"author": item.pointer("/user/login"),
+				"head": item.pointer("/head/ref"),
+				"base": item.pointer("/base/ref"),
+				"draft": item.get("draft").and_then(Value::as_bool).unwrap_or(false),
+				"created_at": item.get("created_at"),
+			}),
+			GhProvider::GitLab => json!({
+				"number": item.get("iid"),
+				"title": item.get("title"),
+				"body": item.get("description"),
+				"state": gitlab_state(item.get("state")),
+				"url": item.get("web_url"),
+				"author": item.pointer("/author/username"),
+				"head": item.get("source_branch"),
+				"base": item.get("target_branch"),
+				"draft": item.get("draft").or_else(|| item.get("work_in_progress")).and_then(Value::as_bool).unwrap_or(false),
+				"created_at": item.get("created_at"),
+			}),
+		}
+	}
+
+	/// `{id, body, url?, author?, created_at}`
+	fn normalize_comment(&self, item: &Value) -> Value {
+		match self.provider {
+			GhProvider::GitHub => json!({
+				"id": item.get("id"),
+				"body": item.get("body"),
+				"url": item.get("html_url"),
+				"author": item.pointer("/user/login"),
+				"created_at": item.get("created_at"),
+			}),
+			GhProvider::GitLab => json!({
+				"id": item.get("id"),
+				"body": item.get("body"),
+				"author": item.pointer("/author/username"),
+				"created_at": item.get("created_at"),
+			}),
+		}
+	}
+}
+
+/// The GitLab labels are strings (or objects with `with_labels_details`), the others are objects with `key`
+fn names(items: Option<&Value>, key: &str) -> Vec<String> {
+	items
+		.and_then(Value::as_array)
+		.into_iter()
+		.flatten()
+		.filter_map(|item| match item {
+			Value::String(name) => Some(name.clone()),
+			other => other.get(key).and_then(Value::as_str).map(str::to_string),
+		})
+		.collect()
+}
+
+/// The GitLab states ("opened", "closed", "merged", "locked") with "opened" as "open" (as GitHub)
+fn gitlab_state(state: Option<&Value>) -> Value {
+	match state.and_then(Value::as_str) {
+		Some("opened") => "open".into(),
+		Some(state) => state.into(),
+		None => Value::Null,
+	}
+}
+
+/// The GitLab merge request diffs (one per file, without the headers) as a unified diff
+fn gitlab_diffs_to_unified(files: &[Value]) -> String {
+	let mut diff = String::new();
+	for file in files {
+		let str_of = |name: &str| file.get(name).and_then(Value::as_str).unwrap_or_default();
+		let bool_of = |name: &str| file.get(name).and_then(Value::as_bool).unwrap_or(false);
+		let (old_path, new_path) = (str_of("old_path"), str_of("new_path"));
+
+		diff.push_str(&format!("diff --git a/{old_path} b/{new_path}\n"));
+		if bool_of("new_file") {
+			diff.push_str("--- /dev/null\n");
+		} else {
+			diff.push_str(&format!("--- a/{old_path}\n"));
+		}
+		if bool_of("deleted_file") {
+			diff.push_str("+++ /dev/null\n");
+		} else {
+			diff.push_str(&format!("+++ b/{new_path}\n"));
+		}
+		let file_diff = str_of("diff");
+		diff.push_str(file_diff);
+		if !file_diff.is_empty() && !file_diff.ends_with('\n') {
+			diff.push('\n');
+		}
+	}
+	diff
+}
+
+/// The GitLab project id, the url encoded full path (e.g., `group%2Fsub%2Fproject`)
+fn project_id(repo: &str) -> String {
+	url::form_urlencoded::byte_serialize(repo.as_bytes()).collect()
+}
+
+// endregion: --- Normalize
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use value_ext::JsonValueExt as _;
+
+	fn client(provider: GhProvider) -> Result<GhClient> {
+		Ok(GhClient::new(provider, None, None, WebOptions::default())?)
+	}
+
+	#[test]
+	fn test_gh_client_normalize_issue_github() -> Result<()> {
+		// -- Setup & Fixtures
+		let client = client(GhProvider::GitHub)?;
+		let fx_item = json!({
+			"number": 12,
+			"title": "Crash on start",
+			"body": "Stack trace",
+			"state": "open",
+			"html_url": "https://github.com/acme/app/issues/12",
+			"user": { "login": "jdoe" },
+			"labels": [{ "name": "bug" }, { "name": "p1" }],
+			"assignees": [{ "login": "jc" }],
+			"comments": 3,
+			"pull_request": { "url": "..." },
+			"created_at": "2025-01-02T10:00:00Z",
+			"updated_at": "2025-01-03T10:00:00Z",
+		});
+
+		// -- Exec
+		let issue = client.normalize_issue(&fx_item);
+
+		// -- Check
+		assert_eq!(issue.x_get_i64("number")?, 12);
+		assert_eq!(issue.x_get_str("author")?, "jdoe");
+		assert_eq!(issue.x_get_str("/labels/1")?, "p1");
+		assert_eq!(issue.x_get_str("/assignees/0")?, "jc");
+		assert_eq!(issue.x_get_i64("comments_count")?, 3);
+		assert!(issue.x_get_bool("is_pr")?);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_gh_client_normalize_issue_and_pr_gitlab() -> Result<()> {
+		// -- Setup & Fixtures
+		let client = client(GhProvider::GitLab)?;
+		let fx_issue = json!({
+			"iid": 7,
+			"title": "Slow query",
+			"description": "Takes 10s",
+			"state": "opened",
+			"web_url": "https://gitlab.com/acme/app/-/issues/7",
+			"author": { "username": "jdoe" },
+			"labels": ["perf"],
+			"assignees": [{ "username": "jc" }],
+			"user_notes_count": 1,
+		});
+		let fx_mr = json!({
+			"iid": 8,
+			"title": "Draft: Fix query",
+			"state": "merged",
+			"source_branch": "fix-query",
+			"target_branch": "main",
+			"draft": true,
+		});
+
+		// -- Exec
+		let issue = client.normalize_issue(&fx_issue);
+		let pr = client.normalize_pr(&fx_mr);
+
+		// -- Check
+		assert_eq!(issue.x_get_i64("number")?, 7);
+		assert_eq!(issue.x_get_str("state")?, "open");
+		assert_eq!(issue.x_get_str("body")?, "Takes 10s");
+		assert_eq!(issue.x_get_str("/labels/0")?, "perf");
+		assert!(!issue.x_get_bool("is_pr")?);
+		assert_eq!(pr.x_get_str("state")?, "merged");
+		assert_eq!(pr.x_get_str("head")?, "fix-query");
+		assert_eq!(pr.x_get_str("base")?, "main");
+		assert!(pr.x_get_bool("draft")?);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_gh_client_gitlab_diffs_to_unified() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_files = json!([
+			{ "old_path": "src/main.rs", "new_path": "src/main.rs", "diff": "@@ -1 +1 @@\n-a\n+b\n" },
+			{ "old_path": "NEW.md", "new_path": "NEW.md", "new_file": true, "diff": "@@ -0,0 +1 @@\n+hello" },
+		]);
+		let fx_files = fx_files.as_array().ok_or("Should be array")?;
+
+		// -- Exec
+		let diff = gitlab_diffs_to_unified(fx_files);
+
+		// -- Check
+		assert_eq!(
+			diff,
+			"diff --git a/src/main.rs b/src/main.rs\n--- a/src/main.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-a\n+b\n\
+			 diff --git a/NEW.md b/NEW.md\n--- /dev/null\n+++ b/NEW.md\n@@ -0,0 +1 @@\n+hello\n"
+		);
+		assert_eq!(project_id("group/sub/app"), "group%2Fsub%2Fapp");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
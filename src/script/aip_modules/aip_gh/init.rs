@@ -0,0 +1,404 @@
+use super::gh_client::{GhClient, GhIssueFilter, GhPrCreate, GhProvider};
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::check_pack_capability;
+use crate::script::serde_value_to_lua_value;
+use crate::support::cred::get_secret;
+use crate::types::{PackCapability, WebOptions};
+use crate::{Error, Result};
+use mlua::{FromLua as _, Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	table.set("create_pr", lua.create_function(gh_create_pr)?)?;
+	table.set("list_issues", lua.create_function(gh_list_issues)?)?;
+	table.set("get_issue", lua.create_function(gh_get_issue)?)?;
+	table.set("comment", lua.create_function(gh_comment)?)?;
+	table.set("get_pr_diff", lua.create_function(gh_get_pr_diff)?)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Creates a pull request (GitLab merge request).
+///
+/// ```lua
+/// -- API Signature
+/// aip.gh.create_pr(repo: string, pr: GhPrCreate, options?: GhOptions): GhPullRequest
+/// ```
+///
+/// ### Arguments
+///
+/// - `repo: string`: The repository, `"owner/name"` (GitLab: the project full path, e.g., `"group/sub/project"`).
+/// - `pr: GhPrCreate`:
+///   ```ts
+///   {
+///     title: string,
+///     head: string,      // The branch with the changes
+///     base?: string,     // The target branch (default the repo default branch)
+///     body?: string,
+///     draft?: boolean,   // default false
+///   }
+///   ```
+/// - `options?: GhOptions`: The `WebOptions` (e.g., `timeout_ms`), plus:
+///   ```ts
+///   {
+///     provider?: "github" | "gitlab", // default "github"
+///     base_url?: string,               // The API url (default "https://api.github.com", or "https://gitlab.com/api/v4")
+///     token?: string,                  // The API token (default from the `token_env`)
+///     token_env?: string,              // default "GITHUB_TOKEN" (or "GH_TOKEN"), or "GITLAB_TOKEN"
+///   }
+///   ```
+///   The `token_env` is read from the environment variable, or the keychain (`aipack_secrets/<NAME>`),
+///   which requires the `secrets` capability.
+///
+/// ### Returns (GhPullRequest)
+///
+/// ```ts
+/// {
+///   number: integer, title: string, body?: string,
+///   state: string,   // "open", "closed" (GitLab "merged" and "locked" as well)
+///   url: string, author?: string, head: string, base: string, draft: boolean, created_at: string,
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local pr = aip.gh.create_pr("acme/app", { title = "Fix the login", head = "fix-login", body = "Closes #12" })
+/// print(pr.url)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the arguments are invalid, the request fails, or the API returns a non-2xx status
+/// (with the API message).
+fn gh_create_pr(lua: &Lua, (repo, pr, options): (String, Value, Option<Value>)) -> mlua::Result<Value> {
+	let fn_name = "aip.gh.create_pr";
+	let options = options.unwrap_or(Value::Nil);
+	let client = gh_client(lua, fn_name, &repo, &options)?;
+
+	let required = |name: &str| {
+		pr.x_get_string(name)
+			.ok_or_else(|| Error::custom(format!("{fn_name} - the pr '{name}' is required")))
+	};
+	let pr_c = GhPrCreate {
+		title: required("title")?,
+		head: required("head")?,
+		base: pr.x_get_string("base"),
+		body: pr.x_get_string("body"),
+		draft: pr.x_get_bool("draft").unwrap_or(false),
+	};
+
+	let res = block_on(fn_name, client.create_pr(&repo, &pr_c))?;
+	get_hub().publish_sync(format!("-> lua gh::create_pr OK ({repo}) "));
+
+	Ok(serde_value_to_lua_value(lua, res)?)
+}
+
+/// ## Lua Documentation
+///
+/// Lists the issues of the repo (without the pull requests by default).
+///
+/// ```lua
+/// -- API Signature
+/// aip.gh.list_issues(repo: string, options?: GhListIssuesOptions): GhIssue[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `repo: string`: The repository, `"owner/name"` (GitLab: the project full path).
+/// - `options?: GhListIssuesOptions`: The `GhOptions` (see `aip.gh.create_pr`), plus:
+///   ```ts
+///   {
+///     state?: "open" | "closed" | "all", // default "open"
+///     labels?: string[] | string,        // The issues with all of these labels (e.g., {"bug", "p1"} or "bug,p1")
+///     assignee?: string,                 // The assignee login (GitLab username)
+///     per_page?: integer,                // default 30 (GitHub), 20 (GitLab)
+///     page?: integer,                    // default 1
+///     include_prs?: boolean,             // Keep the GitHub pull requests (default false)
+///   }
+///   ```
+///
+/// ### Returns (GhIssue[])
+///
+/// ```ts
+/// {
+///   number: integer, title: string, body?: string,
+///   state: string,   // "open", "closed"
+///   url: string, author?: string, labels: string[], assignees: string[],
+///   comments_count?: integer, is_pr: boolean, created_at: string, updated_at: string,
+/// }[]
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local issues = aip.gh.list_issues("acme/app", { labels = { "bug" }, per_page = 50 })
+/// for _, issue in ipairs(issues) do
+///   print("#" .. issue.number .. " " .. issue.title)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the arguments are invalid, the request fails, or the API returns a non-2xx status.
+fn gh_list_issues(lua: &Lua, (repo, options): (String, Option<Value>)) -> mlua::Result<Value> {
+	let fn_name = "aip.gh.list_issues";
+	let options = options.unwrap_or(Value::Nil);
+	let client = gh_client(lua, fn_name, &repo, &options)?;
+
+	let state = options.x_get_string("state");
+	if let Some(state) = state.as_deref()
+		&& !matches!(state, "open" | "closed" | "all")
+	{
+		return Err(Error::custom(format!(
+			"{fn_name} - state '{state}' not supported. Must be 'open', 'closed', or 'all'"
+		))
+		.into());
+	}
+	let labels = match options.x_get_value("labels") {
+		Some(Value::String(labels)) => Some(
+			labels
+				.to_string_lossy()
+				.split(',')
+				.map(|label| label.trim().to_string())
+				.filter(|label| !label.is_empty())
+				.collect(),
+		),
+		Some(labels @ Value::Table(_)) => Some(Vec::<String>::from_lua(labels, lua)?),
+		_ => None,
+	};
+	let filter = GhIssueFilter {
+		state,
+		labels,
+		assignee: options.x_get_string("assignee"),
+		per_page: options.x_get_i64("per_page"),
+		page: options.x_get_i64("page"),
+		include_prs: options.x_get_bool("include_prs").unwrap_or(false),
+	};
+
+	let issues = block_on(fn_name, client.list_issues(&repo, &filter))?;
+	get_hub().publish_sync(format!("-> lua gh::list_issues OK ({repo}, {} issues) ", issues.len()));
+
+	Ok(serde_value_to_lua_value(lua, issues.into())?)
+}
+
+/// ## Lua Documentation
+///
+/// Returns an issue of the repo (GitHub: the pull requests as well, with `is_pr = true`).
+///
+/// ```lua
+/// -- API Signature
+/// aip.gh.get_issue(repo: string, number: integer, options?: GhOptions): GhIssue
+/// ```
+///
+/// ### Arguments
+///
+/// - `repo: string`: The repository, `"owner/name"` (GitLab: the project full path).
+/// - `number: integer`: The issue number (GitLab: the issue `iid`).
+/// - `options?: GhOptions`: See `aip.gh.create_pr`.
+///
+/// ### Returns (GhIssue)
+///
+/// See `aip.gh.list_issues`.
+///
+/// ### Example
+///
+/// ```lua
+/// local issue = aip.gh.get_issue("acme/app", 12)
+/// print(issue.title, issue.state, table.concat(issue.labels, ", "))
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the arguments are invalid, the request fails, or the API returns a non-2xx status
+/// (e.g., `404` when the issue does not exist).
+fn gh_get_issue(lua: &Lua, (repo, number, options): (String, i64, Option<Value>)) -> mlua::Result<Value> {
+	let fn_name = "aip.gh.get_issue";
+	let options = options.unwrap_or(Value::Nil);
+	let client = gh_client(lua, fn_name, &repo, &options)?;
+
+	let issue = block_on(fn_name, client.get_issue(&repo, number))?;
+	get_hub().publish_sync(format!("-> lua gh::get_issue OK ({repo}#{number}) "));
+
+	Ok(serde_value_to_lua_value(lua, issue)?)
+}
+
+/// ## Lua Documentation
+///
+/// Adds a comment to an issue or a pull request.
+///
+/// ```lua
+/// -- API Signature
+/// aip.gh.comment(repo: string, number: integer, body: string, options?: GhCommentOptions): GhComment
+/// ```
+///
+/// ### Arguments
+///
+/// - `repo: string`: The repository, `"owner/name"` (GitLab: the project full path).
+/// - `number: integer`: The issue or pull request number.
+/// - `body: string`: The comment (markdown).
+/// - `options?: GhCommentOptions`: The `GhOptions` (see `aip.gh.create_pr`), plus:
+///   ```ts
+///   {
+///     pr?: boolean, // GitLab only, true to comment the merge request `number` (default false, the issue)
+///   }
+///   ```
+///
+/// ### Returns (GhComment)
+///
+/// ```ts
+/// { id: integer, body: string, url?: string, author?: string, created_at: string }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local diff = aip.gh.get_pr_diff("acme/app", 34)
+/// local review = aip.agent.run("code-review", { input = diff }).outputs[1]
+/// aip.gh.comment("acme/app", 34, review)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the arguments are invalid, the request fails, or the API returns a non-2xx status.
+fn gh_comment(lua: &Lua, (repo, number, body, options): (String, i64, String, Option<Value>)) -> mlua::Result<Value> {
+	let fn_name = "aip.gh.comment";
+	let options = options.unwrap_or(Value::Nil);
+	let client = gh_client(lua, fn_name, &repo, &options)?;
+	let is_pr = options.x_get_bool("pr").unwrap_or(false);
+
+	let comment = block_on(fn_name, client.comment(&repo, number, &body, is_pr))?;
+	get_hub().publish_sync(format!("-> lua gh::comment OK ({repo}#{number}) "));
+
+	Ok(serde_value_to_lua_value(lua, comment)?)
+}
+
+/// ## Lua Documentation
+///
+/// Returns the unified diff of a pull request (GitLab merge request).
+///
+/// ```lua
+/// -- API Signature
+/// aip.gh.get_pr_diff(repo: string, number: integer, options?: GhOptions): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `repo: string`: The repository, `"owner/name"` (GitLab: the project full path).
+/// - `number: integer`: The pull request number (GitLab: the merge request `iid`).
+/// - `options?: GhOptions`: See `aip.gh.create_pr`.
+///
+/// ### Returns
+///
+/// The unified diff (`diff --git ...`), e.g., for `aip.udiffx` or a review prompt.
+///
+/// ### Example
+///
+/// ```lua
+/// local diff = aip.gh.get_pr_diff("acme/app", 34)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the arguments are invalid, the request fails, or the API returns a non-2xx status.
+fn gh_get_pr_diff(lua: &Lua, (repo, number, options): (String, i64, Option<Value>)) -> mlua::Result<Value> {
+	let fn_name = "aip.gh.get_pr_diff";
+	let options = options.unwrap_or(Value::Nil);
+	let client = gh_client(lua, fn_name, &repo, &options)?;
+
+	let diff = block_on(fn_name, client.get_pr_diff(&repo, number))?;
+	get_hub().publish_sync(format!("-> lua gh::get_pr_diff OK ({repo}#{number}) "));
+
+	Ok(Value::String(lua.create_string(&diff)?))
+}
+
+// region:    --- Support
+
+/// Build the client from the `GhOptions` (after validating the repo, and with the eventual token)
+fn gh_client(lua: &Lua, fn_name: &str, repo: &str, options: &Value) -> Result<GhClient> {
+	check_pack_capability(lua, PackCapability::Net, fn_name)?;
+
+	let parts: Vec<&str> = repo.split('/').collect();
+	if parts.len() < 2
+		|| parts
+			.iter()
+			.any(|part| part.trim().is_empty() || part.contains(char::is_whitespace))
+	{
+		return Err(Error::custom(format!(
+			"{fn_name} - repo '{repo}' must be 'owner/name' (or the GitLab project full path)"
+		)));
+	}
+
+	let provider =
+		GhProvider::from_name(options.x_get_string("provider").as_deref()).map_err(|err| Error::cc(fn_name, err))?;
+	let token = match options.x_get_string("token") {
+		Some(token) => Some(token),
+		None => {
+			check_pack_capability(lua, PackCapability::Secrets, &format!("{fn_name} token"))?;
+			let token_envs = match options.x_get_string("token_env") {
+				Some(token_env) => vec![token_env],
+				None if provider == GhProvider::GitHub => vec!["GITHUB_TOKEN".to_string(), "GH_TOKEN".to_string()],
+				None => vec![provider.default_token_env().to_string()],
+			};
+			token_envs.iter().find_map(|name| {
+				std::env::var(name)
+					.ok()
+					.filter(|value| !value.trim().is_empty())
+					.or_else(|| get_secret(name))
+			})
+		}
+	};
+	let web_options = WebOptions::from_lua(options.clone(), lua)?;
+
+	GhClient::new(provider, options.x_get_string("base_url"), token, web_options)
+}
+
+fn block_on<T>(fn_name: &str, fut: impl Future<Output = Result<T>>) -> Result<T> {
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	tokio::task::block_in_place(|| rt.block_on(fut)).map_err(|err| Error::cc(fn_name, err))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_gh;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_gh_invalid_args() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_gh::init_module, "gh").await?;
+		let script = r#"
+local _, repo_err = pcall(aip.gh.get_issue, "no-slash", 1)
+local _, provider_err = pcall(aip.gh.get_pr_diff, "acme/app", 1, { provider = "bitbucket", token = "x" })
+local _, state_err = pcall(aip.gh.list_issues, "acme/app", { state = "pending", token = "x" })
+local _, pr_err = pcall(aip.gh.create_pr, "acme/app", { title = "Fix" }, { token = "x" })
+return { tostring(repo_err), tostring(provider_err), tostring(state_err), tostring(pr_err) }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let errs = res.as_array().ok_or("should be an array")?;
+		let err_at = |idx: usize| errs.get(idx).and_then(|v| v.as_str()).unwrap_or_default();
+		assert_contains(err_at(0), "aip.gh.get_issue - repo 'no-slash' must be 'owner/name'");
+		assert_contains(err_at(1), "provider 'bitbucket' not supported");
+		assert_contains(err_at(2), "aip.gh.list_issues - state 'pending' not supported");
+		assert_contains(err_at(3), "aip.gh.create_pr - the pr 'head' is required");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,26 @@
+//! Defines the `aip.gh` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.gh` module calls the GitHub (default) or GitLab REST API for the pull requests (merge requests)
+//! and issues, with the responses normalized to the same shapes, so that the review and triage agents
+//! can work end-to-end.
+//!
+//! ### Functions
+//!
+//! - `aip.gh.create_pr(repo: string, pr: GhPrCreate, options?: GhOptions): GhPullRequest`
+//! - `aip.gh.list_issues(repo: string, options?: GhListIssuesOptions): GhIssue[]`
+//! - `aip.gh.get_issue(repo: string, number: integer, options?: GhOptions): GhIssue`
+//! - `aip.gh.comment(repo: string, number: integer, body: string, options?: GhCommentOptions): GhComment`
+//! - `aip.gh.get_pr_diff(repo: string, number: integer, options?: GhOptions): string`
+
+// region:    --- Modules
+
+mod gh_client;
+mod init;
+
+pub use init::*;
+
+// endregion: --- Modules
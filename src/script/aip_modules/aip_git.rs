@@ -12,6 +12,8 @@
 
 use crate::hub::get_hub;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::check_pack_capability;
+use crate::types::PackCapability;
 use crate::{Error, Result};
 use mlua::{IntoLua, Lua, Table, Value};
 
@@ -56,6 +58,7 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 ///
 /// Throws an error if the command's stderr output is not empty.
 fn git_restore(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Exec, "aip.git.restore")?;
 	let current_dir = runtime
 		.dir_context()
 		.try_wks_dir_with_err_ctx("aip.git.restore requires a aipack workspace setup")?;
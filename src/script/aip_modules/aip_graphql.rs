@@ -0,0 +1,316 @@
+//! Defines the `aip.graphql` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.graphql` module exposes GraphQL queries over HTTP (POST with a JSON body),
+//! with the GraphQL errors (returned in `errors`) separated from the transport errors (raised).
+//!
+//! ### Functions
+//!
+//! - `aip.graphql.query(endpoint: string, query: string, variables?: table, options?: GraphqlOptions): GraphqlResponse`
+//! - `aip.graphql.introspect(endpoint: string, options?: GraphqlIntrospectOptions): table`
+//!
+//! ### Related Types
+//!
+//! Where `GraphqlOptions` is the `WebOptions` (e.g., `headers`, `timeout_ms`), plus:
+//! ```lua
+//! {
+//!   operation_name?: string, -- the operation to run, when the query has several
+//!   raise_errors?: boolean,  -- if true, the GraphQL errors are raised as well (default false)
+//! }
+//! ```
+
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::check_pack_capability;
+use crate::script::{lua_value_to_serde_value, serde_value_to_lua_value};
+use crate::support::graphql::{GraphqlResponse, INTROSPECTION_QUERY, request_body};
+use crate::support::text::blake3_b64u;
+use crate::types::{PackCapability, WebOptions};
+use crate::{Error, Result};
+use mlua::{FromLua as _, Lua, Table, Value};
+use reqwest::{Client, header};
+use simple_fs::SPath;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let query_fn = lua.create_function(graphql_query)?;
+	let rt = runtime.clone();
+	let introspect_fn = lua.create_function(move |lua, args| graphql_introspect(lua, &rt, args))?;
+
+	table.set("query", query_fn)?;
+	table.set("introspect", introspect_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Sends a GraphQL query (or mutation) to the endpoint, and returns its data and errors.
+///
+/// ```lua
+/// -- API Signature
+/// aip.graphql.query(endpoint: string, query: string, variables?: table, options?: GraphqlOptions): GraphqlResponse
+/// ```
+///
+/// ### Arguments
+///
+/// - `endpoint: string`: The GraphQL endpoint url (e.g., `"https://api.github.com/graphql"`).
+/// - `query: string`: The GraphQL document.
+/// - `variables?: table`: The query variables.
+/// - `options?: GraphqlOptions`: The `WebOptions` (e.g., `headers` for the auth), plus `operation_name` and `raise_errors`.
+///
+/// ### Returns (GraphqlResponse)
+///
+/// ```ts
+/// {
+///   ok: boolean,           // true when there are no GraphQL errors
+///   status: integer,       // The HTTP status
+///   data?: table,          // Can be partial when there are errors
+///   errors?: {message: string, locations?: {line: integer, column: integer}[], path?: (string | integer)[], extensions?: table}[],
+///   extensions?: table,
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local res = aip.graphql.query("https://api.github.com/graphql",
+///   "query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) { stargazerCount } }",
+///   { owner = "aipack-ai", name = "aipack" },
+///   { headers = { Authorization = "Bearer " .. aip.env.get("GITHUB_TOKEN") } })
+/// if res.ok then
+///   print(res.data.repository.stargazerCount)
+/// else
+///   print("GraphQL errors: " .. res.errors[1].message)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error on the transport errors (the request fails, or the response is not a GraphQL response,
+/// e.g., a `502` html page), and on the GraphQL errors with `raise_errors = true`.
+fn graphql_query(
+	lua: &Lua,
+	(endpoint, query, variables, options): (String, String, Option<Value>, Option<Value>),
+) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.graphql.query")?;
+	let options = options.unwrap_or(Value::Nil);
+	let variables = variables.map(lua_value_to_serde_value).transpose()?;
+	let operation_name = options.x_get_string("operation_name");
+	let raise_errors = options.x_get_bool("raise_errors").unwrap_or(false);
+
+	let body = request_body(&query, variables, operation_name.as_deref());
+	let (status, gql_res) = graphql_post(lua, &endpoint, body, options, "aip.graphql.query")?;
+
+	if raise_errors && !gql_res.errors.is_empty() {
+		return Err(Error::custom(format!(
+			"aip.graphql.query - GraphQL errors for endpoint '{endpoint}': {}",
+			gql_res.errors_summary()
+		))
+		.into());
+	}
+
+	let res = lua.create_table()?;
+	res.set("ok", gql_res.errors.is_empty())?;
+	res.set("status", status)?;
+	if let Some(data) = gql_res.data {
+		res.set("data", serde_value_to_lua_value(lua, data)?)?;
+	}
+	if !gql_res.errors.is_empty() {
+		res.set("errors", serde_value_to_lua_value(lua, gql_res.errors.into())?)?;
+	}
+	if let Some(extensions) = gql_res.extensions {
+		res.set("extensions", serde_value_to_lua_value(lua, extensions)?)?;
+	}
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Returns the schema of the endpoint (the introspection `__schema`), cached in the workspace by default.
+///
+/// ```lua
+/// -- API Signature
+/// aip.graphql.introspect(endpoint: string, options?: GraphqlIntrospectOptions): table
+/// ```
+///
+/// ### Arguments
+///
+/// - `endpoint: string`: The GraphQL endpoint url.
+/// - `options?: GraphqlIntrospectOptions`: The `WebOptions` (e.g., `headers`), plus:
+///   ```ts
+///   {
+///     cache?: boolean,   // Read and write the cached schema (default true), in `.aipack/.session/_graphql/`
+///     refresh?: boolean, // Query the schema even if cached (and update the cache)
+///   }
+///   ```
+///
+/// ### Returns
+///
+/// The `__schema` table (`queryType`, `mutationType`, `subscriptionType`, and `types`, with their `fields`, `args`, ...).
+///
+/// ### Example
+///
+/// ```lua
+/// local schema = aip.graphql.introspect("https://api.acme.com/graphql", { headers = { Authorization = "Bearer ..." } })
+/// for _, type in ipairs(schema.types) do
+///   if type.name == schema.queryType.name then
+///     for _, field in ipairs(type.fields) do print(field.name) end
+///   end
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error on the transport errors, or when the endpoint returns GraphQL errors
+/// (e.g., the introspection is disabled).
+fn graphql_introspect(
+	lua: &Lua,
+	runtime: &Runtime,
+	(endpoint, options): (String, Option<Value>),
+) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.graphql.introspect")?;
+	let options = options.unwrap_or(Value::Nil);
+	let use_cache = options.x_get_bool("cache").unwrap_or(true);
+	let refresh = options.x_get_bool("refresh").unwrap_or(false);
+
+	let cache_file = use_cache
+		.then(|| {
+			runtime
+				.dir_context()
+				.aipack_paths()
+				.aipack_wks_dir()
+				.map(|aip_dir| aip_dir.join(format!(".session/_graphql/{}.json", blake3_b64u(&[&endpoint]))))
+		})
+		.flatten();
+
+	// -- From the cache
+	if let Some(cache_file) = cache_file.as_ref()
+		&& !refresh
+		&& cache_file.exists()
+	{
+		let schema: serde_json::Value = simple_fs::load_json(cache_file).map_err(Error::from)?;
+		return Ok(serde_value_to_lua_value(lua, schema)?);
+	}
+
+	// -- From the endpoint
+	let body = request_body(INTROSPECTION_QUERY, None, None);
+	let (_, gql_res) = graphql_post(lua, &endpoint, body, options, "aip.graphql.introspect")?;
+	if !gql_res.errors.is_empty() {
+		return Err(Error::custom(format!(
+			"aip.graphql.introspect - GraphQL errors for endpoint '{endpoint}': {}",
+			gql_res.errors_summary()
+		))
+		.into());
+	}
+	let schema = gql_res
+		.data
+		.and_then(|mut data| data.get_mut("__schema").map(serde_json::Value::take))
+		.ok_or_else(|| {
+			Error::custom(format!(
+				"aip.graphql.introspect - the response of endpoint '{endpoint}' has no data.__schema"
+			))
+		})?;
+
+	if let Some(cache_file) = cache_file.as_ref() {
+		save_schema_cache(cache_file, &schema)?;
+	}
+
+	Ok(serde_value_to_lua_value(lua, schema)?)
+}
+
+// region:    --- Support
+
+/// POST the GraphQL body, and return the HTTP status and the GraphQL response.
+/// NOTE: The transport errors (the request fails, or the body is not a GraphQL response) are returned as errors.
+fn graphql_post(
+	lua: &Lua,
+	endpoint: &str,
+	body: serde_json::Value,
+	options: Value,
+	fn_name: &str,
+) -> Result<(u16, GraphqlResponse)> {
+	let web_opts = WebOptions::from_lua(options, lua)?;
+	let client = web_opts
+		.apply_to_reqwest_builder(Client::builder())
+		.build()
+		.map_err(Error::from)?;
+
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let (status, text) = tokio::task::block_in_place(|| {
+		rt.block_on(async {
+			let response = client
+				.post(endpoint)
+				.header(header::CONTENT_TYPE, "application/json")
+				.header(header::ACCEPT, "application/graphql-response+json, application/json")
+				.body(body.to_string())
+				.send()
+				.await
+				.map_err(|err| Error::cc(format!("{fn_name} - transport error for endpoint '{endpoint}'"), err))?;
+			let status = response.status().as_u16();
+			let text = response.text().await.map_err(|err| {
+				Error::cc(
+					format!("{fn_name} - transport error reading the response of endpoint '{endpoint}'"),
+					err,
+				)
+			})?;
+			Ok::<_, Error>((status, text))
+		})
+	})?;
+
+	let gql_res = GraphqlResponse::from_body(&text).ok_or_else(|| {
+		let excerpt: String = text.chars().take(200).collect();
+		Error::custom(format!(
+			"{fn_name} - transport error for endpoint '{endpoint}' (status {status}), not a GraphQL response: {excerpt}"
+		))
+	})?;
+
+	get_hub().publish_sync(format!("-> lua graphql OK ({endpoint}) "));
+
+	Ok((status, gql_res))
+}
+
+fn save_schema_cache(cache_file: &SPath, schema: &serde_json::Value) -> Result<()> {
+	simple_fs::ensure_file_dir(cache_file)?;
+	simple_fs::save_json(cache_file, schema)?;
+	Ok(())
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_graphql;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_graphql_query_transport_error() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_graphql::init_module, "graphql").await?;
+		let script = r#"
+local ok, err = pcall(aip.graphql.query, "not-a-url", "query { viewer { login } }")
+return tostring(err)
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let err = res.as_str().ok_or("should be a string")?;
+		assert_contains(err, "aip.graphql.query - transport error for endpoint 'not-a-url'");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
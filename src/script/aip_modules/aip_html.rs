@@ -10,6 +10,7 @@
 //!
 //! - `aip.html.slim(html_content: string) -> string`
 //! - `aip.html.select(html_content: string, selectors: string | string[]) -> Elem[]`
+//! - `aip.html.select_first(html_content: string, selectors: string | string[]) -> Elem | nil`
 //! - `aip.html.to_md(html_content: string) -> string`
 
 use crate::runtime::Runtime;
@@ -31,6 +32,11 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	})?;
 	table.set("select", select_fn)?;
 
+	let select_first_fn = lua.create_function(move |lua, (html_content, selectors): (String, Value)| {
+		html_select_first(lua, html_content, selectors)
+	})?;
+	table.set("select_first", select_first_fn)?;
+
 	let to_md_fn = lua.create_function(html_to_md)?;
 	table.set("to_md", to_md_fn)?;
 
@@ -90,11 +96,7 @@ fn html_select(lua: &Lua, html_content: String, selectors: Value) -> mlua::Resul
 		return Ok(Value::Table(seq));
 	}
 
-	// extract as selectors
-	let selectors = into_vec_of_strings(selectors, "aip.html.select")?;
-
-	let els = htmlr::select(&html_content, &selectors)
-		.map_err(|err| crate::Error::custom(format!("Cannot apply selector '{selectors:?}'.\nCause: {err}")))?;
+	let els = select_elems(&html_content, selectors, "aip.html.select")?;
 
 	let els: Vec<mlua::Value> = els
 		.into_iter()
@@ -109,6 +111,40 @@ fn html_select(lua: &Lua, html_content: String, selectors: Value) -> mlua::Resul
 	Ok(Value::Table(seq))
 }
 
+/// ## Lua Documentation
+///
+/// Returns the first element matching the CSS selectors, or nil if none.
+///
+/// ```lua
+/// -- API Signature
+/// aip.html.select_first(html_content: string, selectors: string | string[]): Elem | nil
+/// ```
+///
+/// Same `selectors` and `Elem` as `aip.html.select(...)`.
+///
+/// ### Example
+///
+/// ```lua
+/// local title = aip.html.select_first(html, "article h1")
+/// if title then print(title.text) end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the selectors are invalid.
+fn html_select_first(lua: &Lua, html_content: String, selectors: Value) -> mlua::Result<Value> {
+	if selectors.is_nil() {
+		return Ok(Value::Nil);
+	}
+
+	let els = select_elems(&html_content, selectors, "aip.html.select_first")?;
+
+	match els.into_iter().next() {
+		Some(el) => W(el).into_lua(lua),
+		None => Ok(Value::Nil),
+	}
+}
+
 /// ## Lua Documentation
 ///
 /// Converts HTML content to Markdown format.
@@ -147,6 +183,19 @@ fn html_to_md(_lua: &Lua, html_content: String) -> mlua::Result<String> {
 		.map_err(|err| mlua::Error::RuntimeError(format!("Failed to convert HTML to Markdown: {err}")))
 }
 
+// region:    --- Support
+
+fn select_elems(html_content: &str, selectors: Value, fn_name: &'static str) -> Result<Vec<Elem>> {
+	let selectors = into_vec_of_strings(selectors, fn_name)?;
+
+	let els = htmlr::select(html_content, &selectors)
+		.map_err(|err| crate::Error::custom(format!("Cannot apply selector '{selectors:?}'.\nCause: {err}")))?;
+
+	Ok(els)
+}
+
+// endregion: --- Support
+
 // region:    --- Froms
 
 impl IntoLua for W<Elem> {
@@ -250,6 +299,36 @@ return aip.html.select(html_content, ".me")
 		Ok(())
 	}
 
+	#[tokio::test]
+	async fn test_lua_html_select_first() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_html::init_module, "html").await?;
+		let fx_script = r#"
+local html_content = [[
+<ul>
+	<li><a href="/one" class="link">One</a></li>
+	<li><a href="/two" class="link">Two</a></li>
+</ul>
+]]
+return {
+	first = aip.html.select_first(html_content, {"nav a", "a.link"}),
+	none  = aip.html.select_first(html_content, "table"),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, fx_script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/first/tag")?, "a");
+		assert_eq!(res.x_get_str("/first/attrs/href")?, "/one");
+		assert_eq!(res.x_get_str("/first/text")?, "One");
+		assert!(res.get("none").is_none());
+		assert!(eval_lua(&lua, r#"return aip.html.select_first("<p>x</p>", "p[")"#).is_err());
+
+		Ok(())
+	}
+
 	#[tokio::test]
 	async fn test_lua_html_to_md_ok() -> Result<()> {
 		// -- Setup & Fixtures
@@ -0,0 +1,280 @@
+//! Defines the `image` module, used in the Lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.image` module exposes functions to read image metadata, resize images,
+//! and build base64 image attachments for the multi-modal models.
+//!
+//! ### Functions
+//!
+//! - `aip.image.info(path: string): ImageInfo`
+//! - `aip.image.resize(path: string, dest: string, options?: ImageResizeOptions): FileInfo`
+//! - `aip.image.to_b64(path: string, options?: ImageResizeOptions): Attachment`
+
+use crate::dir_context::PathResolver;
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::aip_modules::support::{check_access_read, check_access_write};
+use crate::support::images::{self, ImageResizeOptions};
+use crate::types::FileInfo;
+use crate::{Error, Result};
+use mlua::{IntoLua, Lua, Table, Value};
+use simple_fs::SPath;
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let info_fn = lua.create_function(move |lua, path: String| image_info(lua, &rt, path))?;
+
+	let rt = runtime.clone();
+	let resize_fn = lua.create_function(move |lua, (path, dest, options): (String, String, Option<Value>)| {
+		image_resize(lua, &rt, path, dest, options)
+	})?;
+
+	let rt = runtime.clone();
+	let to_b64_fn = lua
+		.create_function(move |lua, (path, options): (String, Option<Value>)| image_to_b64(lua, &rt, path, options))?;
+
+	table.set("info", info_fn)?;
+	table.set("resize", resize_fn)?;
+	table.set("to_b64", to_b64_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Returns the image metadata (dimensions, format, and file size), without decoding the whole image.
+///
+/// ```lua
+/// -- API Signature
+/// aip.image.info(path: string): ImageInfo
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string` - The path to the image file (relative to the workspace, or pack ref).
+///
+/// ### Returns
+///
+/// - `ImageInfo` - `{path: string, width: number, height: number, format: string, size: number}`
+///   (`format` is `png`, `jpeg`, `gif`, or `webp`, `size` is in bytes).
+///
+/// ### Example
+///
+/// ```lua
+/// local info = aip.image.info("docs/screenshot.png")
+/// print(info.width .. "x" .. info.height, info.format, info.size)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the file does not exist, or is not a supported image.
+fn image_info(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<Value> {
+	let full_path = runtime.resolve_path_default(SPath::new(&path), None)?;
+	check_access_read(lua, &full_path, "aip.image.info")?;
+
+	let info = images::image_info(&full_path)
+		.map_err(|err| Error::custom(format!("aip.image.info failed for '{path}'. {err}")))?;
+
+	let table = lua.create_table()?;
+	table.set("path", path)?;
+	table.set("width", info.width)?;
+	table.set("height", info.height)?;
+	table.set("format", info.format)?;
+	table.set("size", info.size)?;
+	Ok(Value::Table(table))
+}
+
+/// ## Lua Documentation
+///
+/// Resizes an image to fit within the max dimensions (keeps the aspect ratio, never upscales),
+/// and saves it to `dest`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.image.resize(path: string, dest: string, options?: ImageResizeOptions): FileInfo
+/// ```
+///
+/// The output format is the `options.format`, or the one of the `dest` extension.
+///
+/// ### Arguments
+///
+/// - `path: string` - The path to the source image.
+/// - `dest: string` - The path of the image to create (must be in the workspace or base directory).
+/// - `options?: ImageResizeOptions` (optional) - `{max_width?: number, max_height?: number, format?: "png" | "jpeg" | "gif" | "webp"}`
+///
+/// ### Returns
+///
+/// - `FileInfo` - A [`FileInfo`] object for the created image.
+///
+/// ### Example
+///
+/// ```lua
+/// local file = aip.image.resize("docs/screenshot.png", ".tmp/screenshot-small.jpg", { max_width = 800 })
+/// print(file.path, file.size)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the source is not a supported image, the options are invalid,
+/// or the destination cannot be written.
+fn image_resize(
+	lua: &Lua,
+	runtime: &Runtime,
+	path: String,
+	dest: String,
+	options: Option<Value>,
+) -> mlua::Result<Value> {
+	let options = parse_resize_options(options)?;
+
+	let dir_context = runtime.dir_context();
+	let src_path = runtime.resolve_path_default(SPath::new(&path), None)?;
+	check_access_read(lua, &src_path, "aip.image.resize")?;
+	let dest_path = dir_context.resolve_path(runtime.session(), SPath::new(&dest), PathResolver::WksDir, None)?;
+
+	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.image.resize requires a aipack workspace setup")?;
+	check_access_write(lua, &dest_path, wks_dir)?;
+
+	images::resize_image(&src_path, &dest_path, &options)
+		.map_err(|err| Error::custom(format!("aip.image.resize failed for '{path}'. {err}")))?;
+
+	let file_info = FileInfo::new(runtime.dir_context(), SPath::new(dest), &dest_path);
+	file_info.into_lua(lua)
+}
+
+/// ## Lua Documentation
+///
+/// Returns a base64 image attachment, ready to be used in the `attachments` of `aip.flow.data_response`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.image.to_b64(path: string, options?: ImageResizeOptions): Attachment
+/// ```
+///
+/// Without options, the file content is encoded as is. With options, the image is resized/converted first
+/// (e.g., to reduce the tokens of a large screenshot).
+///
+/// ### Arguments
+///
+/// - `path: string` - The path to the image file.
+/// - `options?: ImageResizeOptions` (optional) - `{max_width?: number, max_height?: number, format?: "png" | "jpeg" | "gif" | "webp"}`
+///
+/// ### Returns
+///
+/// - `Attachment` - `{file_source: string, file_name: string, content_type: string, content: string}`
+///   (`content` is the base64 encoded image).
+///
+/// ### Example
+///
+/// ```lua
+/// local att = aip.image.to_b64("docs/screenshot.png", { max_width = 1024, format = "jpeg" })
+/// return aip.flow.data_response({ attachments = { att } })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the file does not exist, is not a supported image, or the options are invalid.
+fn image_to_b64(lua: &Lua, runtime: &Runtime, path: String, options: Option<Value>) -> mlua::Result<Value> {
+	let options = parse_resize_options(options)?;
+
+	let full_path = runtime.resolve_path_default(SPath::new(&path), None)?;
+	check_access_read(lua, &full_path, "aip.image.to_b64")?;
+
+	let (content_type, content) = images::image_to_b64(&full_path, &options)
+		.map_err(|err| Error::custom(format!("aip.image.to_b64 failed for '{path}'. {err}")))?;
+
+	let table = lua.create_table()?;
+	table.set("file_name", full_path.name())?;
+	table.set("file_source", path)?;
+	table.set("content_type", content_type)?;
+	table.set("content", content)?;
+	Ok(Value::Table(table))
+}
+
+// region:    --- Support
+
+fn parse_resize_options(options: Option<Value>) -> Result<ImageResizeOptions> {
+	let Some(options) = options.filter(|v| !v.is_nil()) else {
+		return Ok(ImageResizeOptions::default());
+	};
+	if !options.is_table() {
+		return Err(Error::custom(
+			"aip.image options must be a table {max_width?, max_height?, format?}",
+		));
+	}
+
+	let to_dim = |name: &str| -> Result<Option<u32>> {
+		match options.x_get_i64(name) {
+			Some(v) if v > 0 => Ok(Some(v as u32)),
+			Some(v) => Err(Error::custom(format!(
+				"aip.image option '{name}' must be a positive number, but was {v}"
+			))),
+			None => Ok(None),
+		}
+	};
+
+	let format = options
+		.x_get_string("format")
+		.map(|format| images::parse_image_format(&format))
+		.transpose()?;
+
+	Ok(ImageResizeOptions {
+		max_width: to_dim("max_width")?,
+		max_height: to_dim("max_height")?,
+		format,
+	})
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_image;
+	use image::{Rgba, RgbaImage};
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_lua_image_info_resize_to_b64() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_image::init_module, "image").await?;
+		let fx_src = "tests-data/sandbox-01/.tmp/test_lua_image/src.png";
+		simple_fs::ensure_file_dir(fx_src)?;
+		RgbaImage::from_pixel(40, 20, Rgba([200, 10, 10, 255])).save(fx_src)?;
+		let script = r#"
+local info = aip.image.info(".tmp/test_lua_image/src.png")
+local file = aip.image.resize(".tmp/test_lua_image/src.png", ".tmp/test_lua_image/small.jpg", { max_width = 10 })
+local small = aip.image.info(file.path)
+local att = aip.image.to_b64(file.path)
+return { info = info, small = small, att = att }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.pointer("/info/width").and_then(|v| v.as_i64()), Some(40));
+		assert_eq!(res.pointer("/info/format").and_then(|v| v.as_str()), Some("png"));
+		assert_eq!(res.pointer("/small/width").and_then(|v| v.as_i64()), Some(10));
+		assert_eq!(res.pointer("/small/height").and_then(|v| v.as_i64()), Some(5));
+		assert_eq!(
+			res.pointer("/att/content_type").and_then(|v| v.as_str()),
+			Some("image/jpeg")
+		);
+		assert_eq!(
+			res.pointer("/att/file_name").and_then(|v| v.as_str()),
+			Some("small.jpg")
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
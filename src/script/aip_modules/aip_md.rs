@@ -12,15 +12,25 @@
 //! ### Functions
 //!
 //! - `aip.md.extract_blocks(md_content: string, options?: string | {lang?: string, extrude?: "content"}): list<MdBlock> | (list<MdBlock>, string)`
+//! - `aip.md.replace_block(md_content: string, block: integer | MdBlock, new_content: string, options?: {lang?: string}): string`
 //! - `aip.md.extract_meta(md_content: string): table, string`
 //! - `aip.md.outer_block_content_or_raw(md_content: string): string`
+//! - `aip.md.parse_table(md_content: string): (list<table>, list<string>)`
+//! - `aip.md.table(records: list<table>, options?: {columns?: list<string | MdTableColumn>}): string`
+//! - `aip.md.outline(md_content: string): list<MdOutlineHeading>`
+//! - `aip.md.update_section(md_content: string, heading_path: string | list<string>, new_body: string): string`
+//! - `aip.md.insert_section(md_content: string, heading_path: string | list<string> | nil, new_section: string, options?: {position?: "before" | "after"}): string`
 
-use crate::Result;
 use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
 use crate::script::support::into_option_string;
 use crate::support::W;
-use crate::support::md::{self, MdRefIter};
+use crate::support::md::{
+	self, MdInsertPosition, MdRefIter, MdTableAlign, md_insert_section, md_outline, md_replace_block,
+	md_update_section, parse_md_table, render_md_table,
+};
 use crate::types::{Extrude, MdBlock, MdRef};
+use crate::{Error, Result};
 use mlua::{IntoLua, Lua, LuaSerdeExt, MultiValue, Table, Value};
 
 // region:    --- Module Init
@@ -32,11 +42,23 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	let outer_block_content_or_raw_fn = lua.create_function(outer_block_content_or_raw)?;
 	let extract_meta_fn = lua.create_function(extract_meta)?;
 	let extract_refs_fn = lua.create_function(extract_refs)?;
+	let parse_table_fn = lua.create_function(parse_table)?;
+	let table_fn = lua.create_function(md_table)?;
+	let outline_fn = lua.create_function(outline)?;
+	let update_section_fn = lua.create_function(update_section)?;
+	let insert_section_fn = lua.create_function(insert_section)?;
+	let replace_block_fn = lua.create_function(replace_block)?;
 
 	table.set("extract_blocks", extract_blocks_fn)?;
 	table.set("extract_meta", extract_meta_fn)?;
 	table.set("extract_refs", extract_refs_fn)?;
 	table.set("outer_block_content_or_raw", outer_block_content_or_raw_fn)?;
+	table.set("parse_table", parse_table_fn)?;
+	table.set("table", table_fn)?;
+	table.set("outline", outline_fn)?;
+	table.set("update_section", update_section_fn)?;
+	table.set("insert_section", insert_section_fn)?;
+	table.set("replace_block", replace_block_fn)?;
 
 	Ok(table)
 }
@@ -69,9 +91,9 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 ///
 /// - `md_content: string`: The markdown content string to process.
 /// - `options?: string | table` (optional):
-///   - If a string, it is treated as the `lang` filter. Only blocks with this language identifier will be returned.
+///   - If a string, it is treated as the `lang` filter. Only blocks with this language identifier (or full info string) will be returned.
 ///   - If a table, it can contain the following optional fields:
-///     - `lang?: string` (optional): Filters blocks by this language identifier. If nil or not present, all blocks are included.
+///     - `lang?: string` (optional): Filters blocks by this language identifier (or full info string). If nil or not present, all blocks are included.
 ///     - `extrude?: "content"` (optional): If set to the string `"content"`, the function will also return the content of the markdown string that is *outside* of the extracted blocks.
 ///
 /// ### Returns
@@ -82,9 +104,12 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 ///   - `list<MdBlock>`: A Lua list (table) of `MdBlock` objects. Each object represents a parsed block:
 ///     ```ts
 ///     {
-///       block_type: "code" | "block", // Type of block (currently only "code" supported)
-///       lang: string | nil,          // Language identifier for code blocks (e.g., "rust", "js"), nil otherwise
-///       content: string              // The content inside the block (excluding fence lines)
+///       lang: string | nil,          // Language identifier, the first word of the fence info string (e.g., "rust", "js")
+///       content: string,             // The content inside the block (excluding fence lines)
+///       info?: string,               // The full fence info string, when it has more than the lang (e.g., "rust file=main.rs")
+///       attrs?: table,               // The info string attributes (e.g., {file = "main.rs"}, flags are `true`)
+///       start_line: integer,         // The opening fence line (1 based)
+///       end_line: integer            // The closing fence line (1 based)
 ///     }
 ///     ```
 /// - If `extrude = "content"` is specified:
@@ -378,6 +403,435 @@ fn extract_refs(lua: &Lua, md_content: Value) -> mlua::Result<Value> {
 	Ok(lua_value)
 }
 
+/// ## Lua Documentation
+///
+/// Parses the first markdown table of the content into records (one table per row, keyed by the headers).
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.parse_table(md_content: string): list<table>, list<string>
+/// ```
+///
+/// The tables inside the code blocks are ignored. The cells are trimmed strings (the escaped `\|` are unescaped),
+/// the missing cells are empty strings, and the extra cells are ignored.
+///
+/// ### Arguments
+///
+/// - `md_content: string`: The markdown content (e.g., a model response).
+///
+/// ### Returns
+///
+/// - `list<table>`: The records (empty if no table was found).
+/// - `list<string>`: The headers, in the table order (e.g., for the `columns` of `aip.md.table`).
+///   (An empty header is named `col_<n>`, e.g., `col_1`.)
+///
+/// ### Example
+///
+/// ```lua
+/// local records, headers = aip.md.parse_table(ai_response.content)
+/// for _, rec in ipairs(records) do
+///   print(rec.Name .. " - " .. rec.Status)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if `md_content` is not a string.
+fn parse_table(lua: &Lua, md_content: String) -> mlua::Result<MultiValue> {
+	let records = lua.create_table()?;
+	let headers = lua.create_table()?;
+
+	if let Some(md_table) = parse_md_table(&md_content) {
+		let keys: Vec<String> = md_table
+			.headers
+			.into_iter()
+			.enumerate()
+			.map(|(i, header)| {
+				if header.is_empty() {
+					format!("col_{}", i + 1)
+				} else {
+					header
+				}
+			})
+			.collect();
+		for row in md_table.rows {
+			let record = lua.create_table()?;
+			for (key, cell) in keys.iter().zip(row) {
+				record.set(key.as_str(), cell)?;
+			}
+			records.push(record)?;
+		}
+		for key in keys {
+			headers.push(key)?;
+		}
+	}
+
+	Ok(MultiValue::from_vec(vec![Value::Table(records), Value::Table(headers)]))
+}
+
+/// ## Lua Documentation
+///
+/// Renders records as an aligned markdown table.
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.table(records: list<table>, options?: {columns?: list<string | MdTableColumn>}): string
+/// ```
+///
+/// The cells are escaped (`|` to `\|`, and the new lines to `<br>`). The numbers and booleans are converted to strings,
+/// the missing values are empty, and the tables are JSON.
+///
+/// ### Arguments
+///
+/// - `records: list<table>`: The records (e.g., from `aip.md.parse_table`).
+/// - `options?: table`:
+///   - `columns?: list<string | MdTableColumn>`: The columns, in order (default all the record keys, sorted).
+///     A column can be the record key, or:
+///     ```ts
+///     {
+///       key: string,
+///       label?: string,                            // the header (default the key)
+///       align?: "left" | "center" | "right" | "none" // (default "none")
+///     }
+///     ```
+///
+/// ### Returns
+///
+/// - `string`: The markdown table (ending with a new line).
+///
+/// ### Example
+///
+/// ```lua
+/// local md = aip.md.table(records, {
+///   columns = { "name", { key = "cost", label = "Cost ($)", align = "right" } }
+/// })
+/// -- | name  | Cost ($) |
+/// -- | ----- | -------: |
+/// -- | gpt-5 |     0.12 |
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if `records` is not a list of tables, or a column is not valid.
+fn md_table(lua: &Lua, (records, options): (Value, Option<Table>)) -> mlua::Result<String> {
+	let Value::Table(records) = records else {
+		return Err(Error::custom(format!(
+			"aip.md.table - records must be a list of tables, but was {}",
+			records.type_name()
+		))
+		.into());
+	};
+	let records: Vec<Table> = records
+		.sequence_values::<Table>()
+		.collect::<mlua::Result<_>>()
+		.map_err(|err| Error::custom(format!("aip.md.table - records must be a list of tables. {err}")))?;
+
+	// -- The columns
+	let columns = match options.as_ref().and_then(|options| options.x_get_value("columns")) {
+		Some(columns) => parse_table_columns(columns)?,
+		None => {
+			let mut keys = std::collections::BTreeSet::new();
+			for record in records.iter() {
+				for pair in record.pairs::<String, Value>() {
+					keys.insert(pair?.0);
+				}
+			}
+			keys.into_iter().map(|key| (key.clone(), key, MdTableAlign::None)).collect()
+		}
+	};
+
+	// -- The rows
+	let mut rows = Vec::with_capacity(records.len());
+	for record in records.iter() {
+		let mut row = Vec::with_capacity(columns.len());
+		for (key, _, _) in columns.iter() {
+			row.push(lua_value_to_cell(lua, record.get::<Value>(key.as_str())?)?);
+		}
+		rows.push(row);
+	}
+
+	let headers: Vec<String> = columns.iter().map(|(_, label, _)| label.clone()).collect();
+	let aligns: Vec<MdTableAlign> = columns.iter().map(|(_, _, align)| *align).collect();
+
+	Ok(render_md_table(&headers, &aligns, &rows))
+}
+
+/// ## Lua Documentation
+///
+/// Returns the heading tree of the markdown content, with the section line spans.
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.outline(md_content: string): list<MdOutlineHeading>
+/// ```
+///
+/// A section is the heading line and all the lines until the next heading of the same or upper level
+/// (so, including its sub sections). The headings in the code blocks are ignored.
+///
+/// ### Arguments
+///
+/// - `md_content: string`: The markdown content.
+///
+/// ### Returns
+///
+/// - `list<MdOutlineHeading>`: The top headings (empty if no heading), with:
+///   ```ts
+///   {
+///     level: number,      // 1 for "#", 2 for "##", ...
+///     name: string,       // the heading text (trimmed)
+///     start_line: number, // the heading line (1 based)
+///     end_line: number,   // the last line of the section, including the sub sections (inclusive)
+///     children: MdOutlineHeading[]
+///   }
+///   ```
+///
+/// ### Example
+///
+/// ```lua
+/// local outline = aip.md.outline(readme)
+/// for _, h in ipairs(outline[1].children) do
+///   print(h.name .. " (lines " .. h.start_line .. "-" .. h.end_line .. ")")
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if `md_content` is not a string.
+fn outline(lua: &Lua, md_content: String) -> mlua::Result<Value> {
+	let outline = md_outline(&md_content);
+	lua.to_value(&outline)
+}
+
+/// ## Lua Documentation
+///
+/// Replaces the body of a section (all the lines after its heading line, including the sub sections).
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.update_section(md_content: string, heading_path: string | list<string>, new_body: string): string
+/// ```
+///
+/// The body is separated from the heading, and from the next section, by one blank line.
+///
+/// ### Arguments
+///
+/// - `md_content: string`: The markdown content.
+/// - `heading_path: string | list<string>`: The heading names, each heading being under the previous one
+///   (e.g., `{"Guide", "Install"}`). A name can have its level (e.g., `"## Install"`). The first match is used.
+/// - `new_body: string`: The new section body (without the heading line).
+///
+/// ### Returns
+///
+/// - `string`: The updated markdown content.
+///
+/// ### Example
+///
+/// ```lua
+/// local readme = aip.file.load("README.md").content
+/// readme = aip.md.update_section(readme, {"Guide", "## Install"}, "Run `cargo install my-tool`.")
+/// aip.file.save("README.md", readme)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the heading path is not found.
+fn update_section(_lua: &Lua, (md_content, heading_path, new_body): (String, Value, String)) -> mlua::Result<String> {
+	let heading_path = heading_path_from_value(heading_path, "aip.md.update_section")?;
+	let content = md_update_section(&md_content, &heading_path, &new_body)
+		.map_err(|err| Error::custom(format!("aip.md.update_section failed. {err}")))?;
+	Ok(content)
+}
+
+/// ## Lua Documentation
+///
+/// Inserts a new section (with its heading line) before or after a section, or at the end of the content.
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.insert_section(md_content: string, heading_path: string | list<string> | nil, new_section: string, options?: {position?: "before" | "after"}): string
+/// ```
+///
+/// The new section is separated from the surrounding content by one blank line.
+///
+/// ### Arguments
+///
+/// - `md_content: string`: The markdown content.
+/// - `heading_path: string | list<string> | nil`: The reference section (see `aip.md.update_section`).
+///   When `nil`, the new section is added at the end of the content.
+/// - `new_section: string`: The new section markdown, with its heading line (e.g., `"## FAQ\n\nAsk away."`).
+/// - `options?: table`:
+///   - `position?: "before" | "after"`: Before the reference heading line, or after the reference section
+///     and its sub sections (default `"after"`).
+///
+/// ### Returns
+///
+/// - `string`: The updated markdown content.
+///
+/// ### Example
+///
+/// ```lua
+/// readme = aip.md.insert_section(readme, "Usage", "## FAQ\n\nAsk away.", { position = "before" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the heading path is not found, or the position is not valid.
+fn insert_section(
+	_lua: &Lua,
+	(md_content, heading_path, new_section, options): (String, Value, String, Option<Table>),
+) -> mlua::Result<String> {
+	let heading_path = match heading_path {
+		Value::Nil => None,
+		heading_path => Some(heading_path_from_value(heading_path, "aip.md.insert_section")?),
+	};
+	let position = match options.and_then(|options| options.x_get_string("position")) {
+		Some(position) => MdInsertPosition::from_name(&position)
+			.map_err(|err| Error::custom(format!("aip.md.insert_section - {err}")))?,
+		None => MdInsertPosition::default(),
+	};
+
+	let content = md_insert_section(&md_content, heading_path.as_deref(), &new_section, position)
+		.map_err(|err| Error::custom(format!("aip.md.insert_section failed. {err}")))?;
+	Ok(content)
+}
+
+/// ## Lua Documentation
+///
+/// Replaces the content of a fenced code block in place (the fence lines are kept).
+///
+/// ```lua
+/// -- API Signature
+/// aip.md.replace_block(md_content: string, block: integer | MdBlock, new_content: string, options?: {lang?: string}): string
+/// ```
+///
+/// ### Arguments
+///
+/// - `md_content: string`: The markdown content.
+/// - `block: integer | MdBlock`: The 1-based index of the block (among the blocks matching `options.lang`),
+///   or an `MdBlock` extracted from this same content (by its `start_line` and `end_line`).
+/// - `new_content: string`: The new block content (without the fence lines).
+/// - `options?: table`:
+///   - `lang?: string`: The language filter for the block index (same as `aip.md.extract_blocks`).
+///
+/// ### Returns
+///
+/// - `string`: The updated markdown content.
+///
+/// ### Example
+///
+/// ```lua
+/// local blocks = aip.md.extract_blocks(content, "rust")
+/// content = aip.md.replace_block(content, blocks[1], "fn main() {}")
+/// -- or by index
+/// content = aip.md.replace_block(content, 1, "fn main() {}", { lang = "rust" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the block is not found (e.g., the `MdBlock` is from another content).
+fn replace_block(
+	_lua: &Lua,
+	(md_content, block, new_content, options): (String, Value, String, Option<Table>),
+) -> mlua::Result<String> {
+	let lang = options.and_then(|options| options.x_get_string("lang"));
+	let (start_line, end_line) = match block {
+		Value::Integer(idx) if idx > 0 => md::MdBlockIter::new(&md_content, lang.as_deref(), None)
+			.nth(idx as usize - 1)
+			.and_then(|block| block.start_line.zip(block.end_line))
+			.ok_or_else(|| Error::custom(format!("aip.md.replace_block - No code block at index {idx}")))?,
+		Value::Table(block) => {
+			let start_line = block.get::<Option<usize>>("start_line")?;
+			let end_line = block.get::<Option<usize>>("end_line")?;
+			start_line.zip(end_line).ok_or_else(|| {
+				Error::custom("aip.md.replace_block - The block must have a 'start_line' and 'end_line'")
+			})?
+		}
+		other => {
+			return Err(Error::custom(format!(
+				"aip.md.replace_block - block must be a 1-based index or an MdBlock, but was '{}'",
+				other.type_name()
+			))
+			.into());
+		}
+	};
+
+	let content = md_replace_block(&md_content, start_line, end_line, &new_content)
+		.map_err(|err| Error::custom(format!("aip.md.replace_block failed. {err}")))?;
+	Ok(content)
+}
+
+// region:    --- Support
+
+fn heading_path_from_value(value: Value, what: &str) -> Result<Vec<String>> {
+	match value {
+		Value::String(heading) => Ok(vec![heading.to_string_lossy()]),
+		Value::Table(headings) => headings
+			.sequence_values::<String>()
+			.collect::<mlua::Result<Vec<_>>>()
+			.map_err(|err| Error::custom(format!("{what} - heading_path must be a list of strings. {err}"))),
+		other => Err(Error::custom(format!(
+			"{what} - heading_path must be a string or a list of strings, but was {}",
+			other.type_name()
+		))),
+	}
+}
+
+/// Returns the `(key, label, align)` of the `columns` option
+fn parse_table_columns(columns: Value) -> Result<Vec<(String, String, MdTableAlign)>> {
+	let Value::Table(columns) = columns else {
+		return Err(Error::custom("aip.md.table - columns must be a list"));
+	};
+
+	let mut res = Vec::new();
+	for column in columns.sequence_values::<Value>() {
+		match column? {
+			Value::String(key) => {
+				let key = key.to_string_lossy();
+				res.push((key.clone(), key, MdTableAlign::None));
+			}
+			Value::Table(column) => {
+				let key = column
+					.x_get_string("key")
+					.ok_or_else(|| Error::custom("aip.md.table - column table must have a 'key'"))?;
+				let label = column.x_get_string("label").unwrap_or_else(|| key.clone());
+				let align = match column.x_get_string("align") {
+					Some(align) => MdTableAlign::from_name(&align).ok_or_else(|| {
+						Error::custom(format!(
+							"aip.md.table - column align '{align}' is not valid (must be 'left', 'center', 'right', or 'none')"
+						))
+					})?,
+					None => MdTableAlign::None,
+				};
+				res.push((key, label, align));
+			}
+			other => {
+				return Err(Error::custom(format!(
+					"aip.md.table - column must be a string or a table, but was {}",
+					other.type_name()
+				)));
+			}
+		}
+	}
+
+	Ok(res)
+}
+
+fn lua_value_to_cell(lua: &Lua, value: Value) -> mlua::Result<String> {
+	let cell = match value {
+		Value::Nil => String::new(),
+		value if value.x_is_null() => String::new(),
+		Value::String(s) => s.to_string_lossy(),
+		Value::Table(_) => {
+			let json: serde_json::Value = lua.from_value(value)?;
+			json.to_string()
+		}
+		other => other.to_string()?,
+	};
+	Ok(cell)
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -385,6 +839,7 @@ mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
 	use crate::_test_support::{assert_contains, assert_not_contains, eval_lua, run_reflective_agent, setup_lua};
+	use crate::script::aip_modules::aip_md;
 	use serde_json::Value;
 	use value_ext::JsonValueExt;
 
@@ -723,6 +1178,103 @@ return aip.md.outer_block_content_or_raw(content)
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_lua_md_parse_table_and_table() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_md::init_module, "md").await?;
+		let fx_script = r#"
+local content = "Some text\n\n| Name | Cost |\n|---|--:|\n| gpt-5 | 0.12 |\n| a \\| b | |\n\nThe end"
+local records, headers = aip.md.parse_table(content)
+records[1].Cost = 0.5
+local md = aip.md.table(records, { columns = { "Name", { key = "Cost", label = "Cost ($)", align = "right" } } })
+local md_default = aip.md.table({ { b = true, a = 1 } })
+local empty = aip.md.parse_table("no table")
+return { records = records, headers = headers, md = md, md_default = md_default, empty_count = #empty }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, fx_script)?;
+
+		// -- Check
+		assert_eq!(res.pointer("/records/1/Name").and_then(|v| v.as_str()), Some("a | b"));
+		assert_eq!(res.pointer("/records/1/Cost").and_then(|v| v.as_str()), Some(""));
+		assert_eq!(res.pointer("/headers/1").and_then(|v| v.as_str()), Some("Cost"));
+		assert_eq!(
+			res.x_get_str("md")?,
+			"| Name   | Cost ($) |\n| ------ | -------: |\n| gpt-5  |      0.5 |\n| a \\| b |          |\n"
+		);
+		assert_eq!(
+			res.x_get_str("md_default")?,
+			"| a   | b    |\n| --- | ---- |\n| 1   | true |\n"
+		);
+		assert_eq!(res.x_get_i64("empty_count")?, 0);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_md_outline_update_insert_section() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_md::init_module, "md").await?;
+		let fx_script = r###"
+local content = "# Guide\n\n## Install\n\nOld.\n\n### Linux\n\napt\n\n## Usage\n\nUse it.\n"
+local outline = aip.md.outline(content)
+local updated = aip.md.update_section(content, {"Guide", "## Install"}, "New.")
+updated = aip.md.insert_section(updated, "Usage", "## FAQ\n\nAsk.", { position = "before" })
+local ok = pcall(function() return aip.md.update_section(content, "Nope", "x") end)
+return { outline = outline, updated = updated, not_found_ok = ok }
+		"###;
+
+		// -- Exec
+		let res = eval_lua(&lua, fx_script)?;
+
+		// -- Check
+		assert_eq!(res.pointer("/outline/0/name").and_then(|v| v.as_str()), Some("Guide"));
+		assert_eq!(
+			res.pointer("/outline/0/children/0/children/0/name").and_then(|v| v.as_str()),
+			Some("Linux")
+		);
+		assert_eq!(
+			res.pointer("/outline/0/children/0/end_line").and_then(|v| v.as_i64()),
+			Some(10)
+		);
+		assert_eq!(
+			res.x_get_str("updated")?,
+			"# Guide\n\n## Install\n\nNew.\n\n## FAQ\n\nAsk.\n\n## Usage\n\nUse it.\n"
+		);
+		assert!(!res.x_get_bool("not_found_ok")?);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_md_replace_block() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_md::init_module, "md").await?;
+		let fx_script = r###"
+local content = "Intro\n\n```lua file=a.lua\nprint(1)\n```\n\n```lua\nprint(2)\n```\n"
+local blocks = aip.md.extract_blocks(content, "lua")
+local by_block = aip.md.replace_block(content, blocks[1], "print(10)")
+local by_idx = aip.md.replace_block(by_block, 2, "print(20)\nprint(21)", { lang = "lua" })
+local ok = pcall(function() return aip.md.replace_block(content, 3, "x") end)
+return { blocks = blocks, content = by_idx, not_found_ok = ok }
+		"###;
+
+		// -- Exec
+		let res = eval_lua(&lua, fx_script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/blocks/0/attrs/file")?, "a.lua");
+		assert_eq!(res.x_get_i64("/blocks/1/start_line")?, 7);
+		assert_eq!(
+			res.x_get_str("content")?,
+			"Intro\n\n```lua file=a.lua\nprint(10)\n```\n\n```lua\nprint(20)\nprint(21)\n```\n"
+		);
+		assert!(!res.x_get_bool("not_found_ok")?);
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -23,6 +23,7 @@
 use crate::Result;
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::is_access_denied;
 use crate::script::support::{into_option_string, into_vec_of_strings};
 use crate::support::W;
 use crate::types::FileInfo;
@@ -38,7 +39,7 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 
 	// -- exists
 	let rt = runtime.clone();
-	let path_exists_fn = lua.create_function(move |_lua, path: String| path_exists(&rt, path))?;
+	let path_exists_fn = lua.create_function(move |lua, path: String| path_exists(lua, &rt, path))?;
 
 	// -- resolve
 	let rt = runtime.clone();
@@ -46,11 +47,11 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 
 	// -- is_file
 	let rt = runtime.clone();
-	let path_is_file_fn = lua.create_function(move |_lua, path: String| path_is_file(&rt, path))?;
+	let path_is_file_fn = lua.create_function(move |lua, path: String| path_is_file(lua, &rt, path))?;
 
 	// -- is_dir
 	let rt = runtime.clone();
-	let path_is_dir_fn = lua.create_function(move |_lua, path: String| path_is_dir(&rt, path))?;
+	let path_is_dir_fn = lua.create_function(move |lua, path: String| path_is_dir(lua, &rt, path))?;
 
 	// -- diff
 	let rt = runtime.clone();
@@ -355,8 +356,8 @@ fn path_resolve(runtime: &Runtime, path: String) -> mlua::Result<String> {
 ///   error: string // Error message
 /// }
 /// ```
-fn path_exists(runtime: &Runtime, path: String) -> mlua::Result<bool> {
-	Ok(crate::script::support::path_exists(runtime, &path))
+fn path_exists(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<bool> {
+	Ok(crate::script::support::path_exists(lua, runtime, &path))
 }
 
 /// ## Lua Documentation
@@ -400,11 +401,11 @@ fn path_exists(runtime: &Runtime, path: String) -> mlua::Result<bool> {
 ///   error: string // Error message
 /// }
 /// ```
-fn path_is_file(runtime: &Runtime, path: String) -> mlua::Result<bool> {
+fn path_is_file(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<bool> {
 	let path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), (&path).into(), PathResolver::WksDir, None)?;
-	Ok(path.is_file())
+	Ok(path.is_file() && !is_access_denied(lua, &path))
 }
 
 /// ## Lua Documentation
@@ -498,11 +499,11 @@ fn path_diff(runtime: &Runtime, file_path: String, base_path: String) -> mlua::R
 ///   error: string // Error message
 /// }
 /// ```
-fn path_is_dir(runtime: &Runtime, path: String) -> mlua::Result<bool> {
+fn path_is_dir(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<bool> {
 	let path = runtime
 		.dir_context()
 		.resolve_path(runtime.session(), (&path).into(), PathResolver::WksDir, None)?;
-	Ok(path.is_dir())
+	Ok(path.is_dir() && !is_access_denied(lua, &path))
 }
 
 /// ## Lua Documentation
@@ -12,6 +12,12 @@
 //!   Returns the number of pages in a PDF file.
 //! - `aip.pdf.split_pages(path: string, dest_dir?: string): string[]`
 //!   Splits a PDF into individual page files.
+//! - `aip.pdf.load(path: string, options?: table): PdfContent`
+//!   Returns the metadata and the per page text of a PDF file.
+//! - `aip.pdf.extract_text(path: string, options?: table): string`
+//!   Returns the text of the PDF pages.
+//! - `aip.pdf.to_images(path: string, dest_dir?: string, options?: table): list<FileInfo>`
+//!   Renders the PDF pages to image files (requires `pdftoppm`).
 
 use crate::runtime::Runtime;
 use crate::support::pdf;
@@ -29,8 +35,23 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let page_split_fn = lua
 		.create_function(move |lua, (path, dest_dir): (String, Option<String>)| page_split(lua, &rt, path, dest_dir))?;
 
+	let load_fn = lua.create_function(move |lua, (path, options): (String, Option<Value>)| load(lua, path, options))?;
+
+	let extract_text_fn =
+		lua.create_function(move |lua, (path, options): (String, Option<Value>)| extract_text(lua, path, options))?;
+
+	let rt = runtime.clone();
+	let to_images_fn = lua.create_function(
+		move |lua, (path, dest_dir, options): (String, Option<String>, Option<Value>)| {
+			to_images(lua, &rt, path, dest_dir, options)
+		},
+	)?;
+
 	table.set("page_count", page_count_fn)?;
 	table.set("split_pages", page_split_fn)?;
+	table.set("load", load_fn)?;
+	table.set("extract_text", extract_text_fn)?;
+	table.set("to_images", to_images_fn)?;
 
 	Ok(table)
 }
@@ -156,3 +177,256 @@ fn page_split(lua: &Lua, runtime: &Runtime, path: String, dest_dir: Option<Strin
 
 	file_infos.into_lua(lua)
 }
+
+/// ## Lua Documentation
+///
+/// Loads the metadata and the per page text of a PDF file.
+///
+/// ```lua
+/// -- API Signature
+/// aip.pdf.load(path: string, options?: {pages?: string | number | number[], layout?: boolean}): PdfContent
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string` - The path to the PDF file.
+/// - `options?: table` (optional)
+///   - `pages?: string | number | number[]` - The pages to extract, starting at 1 (default all).
+///     The string is a page ranges spec, e.g., `"1-3,5,8-"` (`8-` is until the last page).
+///   - `layout?: boolean` - When true, the text keeps the page layout (lines and columns by the text positions),
+///     which is better for the tables and multi columns pages (default `false`).
+///
+/// ### Returns
+///
+/// - `PdfContent`
+///   ```lua
+///   {
+///     _type      = "PdfContent",
+///     path       = string,
+///     title?     = string,
+///     author?    = string,
+///     subject?   = string,
+///     page_count = number,     -- total pages of the document
+///     pages      = { page: number, text: string }[]
+///   }
+///   ```
+///
+/// ### Example
+///
+/// ```lua
+/// local pdf = aip.pdf.load("docs/report.pdf", { pages = "1-2", layout = true })
+/// print(pdf.title, pdf.page_count)
+/// for _, page in ipairs(pdf.pages) do
+///   print("-- page " .. page.page .. "\n" .. page.text)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - The file does not exist or is not a valid PDF.
+/// - The pages are invalid or out of range.
+fn load(lua: &Lua, path: String, options: Option<Value>) -> mlua::Result<Value> {
+	let (doc, pages_text) = load_pages_text(&path, options, "aip.pdf.load")?;
+	let meta = pdf::pdf_meta(&doc);
+
+	let res = lua.create_table()?;
+	res.set("_type", "PdfContent")?;
+	res.set("path", path)?;
+	res.set("title", meta.title)?;
+	res.set("author", meta.author)?;
+	res.set("subject", meta.subject)?;
+	res.set("page_count", meta.page_count)?;
+	let pages = lua.create_table()?;
+	for page_text in pages_text {
+		let page = lua.create_table()?;
+		page.set("page", page_text.page)?;
+		page.set("text", page_text.text)?;
+		pages.push(page)?;
+	}
+	res.set("pages", pages)?;
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Extracts the text of the PDF pages.
+///
+/// ```lua
+/// -- API Signature
+/// aip.pdf.extract_text(path: string, options?: {pages?: string | number | number[], layout?: boolean}): string
+/// ```
+///
+/// Same options as `aip.pdf.load`. The page texts are joined with a blank line.
+///
+/// ### Example
+///
+/// ```lua
+/// local text = aip.pdf.extract_text("docs/report.pdf", { pages = 3 })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - The file does not exist or is not a valid PDF.
+/// - The pages are invalid or out of range.
+fn extract_text(_lua: &Lua, path: String, options: Option<Value>) -> mlua::Result<String> {
+	let (_, pages_text) = load_pages_text(&path, options, "aip.pdf.extract_text")?;
+	let texts: Vec<&str> = pages_text.iter().map(|page| page.text.trim_end()).collect();
+
+	Ok(texts.join("\n\n"))
+}
+
+/// ## Lua Documentation
+///
+/// Renders the PDF pages to image files (e.g., for the vision models).
+///
+/// ```lua
+/// -- API Signature
+/// aip.pdf.to_images(
+///   path: string,
+///   dest_dir?: string,
+///   options?: {pages?: string | number | number[], dpi?: number, format?: "png" | "jpeg"}
+/// ): list<FileInfo>
+/// ```
+///
+/// The pages are rendered with the `pdftoppm` command (from poppler, e.g., the `poppler-utils` package),
+/// which must be on the PATH.
+///
+/// If `dest_dir` is not provided, the destination directory defaults to a folder
+/// in the same location as the source PDF, named after the PDF's stem (as `aip.pdf.split_pages`).
+///
+/// Each image file is named `{stem}-page-{NNNN}.{png|jpg}`.
+///
+/// ### Arguments
+///
+/// - `path: string` - The path to the PDF file.
+/// - `dest_dir?: string` (optional) - The destination directory for the image files.
+/// - `options?: table` (optional)
+///   - `pages?: string | number | number[]` - The pages to render, as in `aip.pdf.load` (default all).
+///   - `dpi?: number` - The resolution (default `150`).
+///   - `format?: "png" | "jpeg"` - The image format (default `"png"`).
+///
+/// ### Returns
+///
+/// - `list<FileInfo>` - A list of [`FileInfo`] objects for each created image file.
+///
+/// ### Example
+///
+/// ```lua
+/// local images = aip.pdf.to_images("docs/report.pdf", "output/pages", { pages = "1-2", dpi = 200 })
+/// for _, image in ipairs(images) do
+///   print(image.path) -- e.g., "output/pages/report-page-0001.png"
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if:
+/// - The file does not exist or is not a valid PDF.
+/// - The pages are invalid or out of range.
+/// - The `pdftoppm` command is not available or fails.
+fn to_images(
+	lua: &Lua,
+	runtime: &Runtime,
+	path: String,
+	dest_dir: Option<String>,
+	options: Option<Value>,
+) -> mlua::Result<Value> {
+	let pdf_path =
+		SPath::from_std_path(&path).map_err(|err| Error::custom(format!("aip.pdf.to_images failed. {err}")))?;
+	let doc = pdf::load_pdf_doc(&pdf_path).map_err(|err| Error::custom(format!("aip.pdf.to_images failed. {err}")))?;
+
+	let options = match options {
+		Some(Value::Table(table)) => Some(table),
+		_ => None,
+	};
+	let pages = get_pages_opt(&doc, options.as_ref(), "aip.pdf.to_images")?;
+	let dpi = match &options {
+		Some(table) => table.get::<Option<u32>>("dpi")?,
+		None => None,
+	}
+	.unwrap_or(150);
+	let format = match &options {
+		Some(table) => table.get::<Option<String>>("format")?,
+		None => None,
+	}
+	.unwrap_or_else(|| "png".to_string());
+
+	// Same default destination directory as split_pages
+	let dest_dir_path = if let Some(dir) = dest_dir {
+		SPath::new(dir)
+	} else {
+		let parent = pdf_path.parent().unwrap_or_else(|| SPath::new("."));
+		parent.join(pdf_path.stem())
+	};
+
+	let stem = pdf_path.stem();
+	let mut file_infos: Vec<FileInfo> = Vec::with_capacity(pages.len());
+	for page in pages {
+		let dest_stem = dest_dir_path.join(format!("{stem}-page-{page:04}"));
+		let image_path = pdf::render_pdf_page_image(&pdf_path, page, &dest_stem, dpi, &format)
+			.map_err(|err| Error::custom(format!("aip.pdf.to_images failed. {err}")))?;
+		file_infos.push(FileInfo::new(runtime.dir_context(), image_path.clone(), &image_path));
+	}
+
+	file_infos.into_lua(lua)
+}
+
+// region:    --- Support
+
+fn load_pages_text(
+	path: &str,
+	options: Option<Value>,
+	fn_name: &str,
+) -> mlua::Result<(pdf::PdfDoc, Vec<pdf::PdfPageText>)> {
+	let spath = SPath::from_std_path(path).map_err(|err| Error::custom(format!("{fn_name} failed. {err}")))?;
+	let doc = pdf::load_pdf_doc(&spath).map_err(|err| Error::custom(format!("{fn_name} failed. {err}")))?;
+
+	let options = match options {
+		Some(Value::Table(table)) => Some(table),
+		_ => None,
+	};
+	let pages = get_pages_opt(&doc, options.as_ref(), fn_name)?;
+	let layout = match &options {
+		Some(table) => table.get::<Option<bool>>("layout")?.unwrap_or(false),
+		None => false,
+	};
+
+	let pages_text = pdf::extract_pdf_pages_text(&doc, &pages, layout)
+		.map_err(|err| Error::custom(format!("{fn_name} failed. {err}")))?;
+
+	Ok((doc, pages_text))
+}
+
+/// Returns the page numbers (starting at 1) of the `pages` option (all the pages when absent)
+fn get_pages_opt(doc: &pdf::PdfDoc, options: Option<&Table>, fn_name: &str) -> mlua::Result<Vec<usize>> {
+	let page_count = pdf::page_count(doc);
+	let pages_value = match options {
+		Some(table) => table.get::<Value>("pages")?,
+		None => Value::Nil,
+	};
+
+	let spec = match pages_value {
+		Value::Nil => return Ok((1..=page_count).collect()),
+		Value::String(spec) => spec.to_str()?.to_string(),
+		Value::Integer(num) => num.to_string(),
+		Value::Table(list) => list
+			.sequence_values::<i64>()
+			.map(|num| num.map(|num| num.to_string()))
+			.collect::<mlua::Result<Vec<_>>>()?
+			.join(","),
+		other => {
+			return Err(Error::custom(format!(
+				"{fn_name} failed. The pages option must be a string, number, or number list, but was '{}'",
+				other.type_name()
+			))
+			.into());
+		}
+	};
+
+	Ok(pdf::parse_page_ranges(&spec, page_count).map_err(|err| Error::custom(format!("{fn_name} failed. {err}")))?)
+}
+
+// endregion: --- Support
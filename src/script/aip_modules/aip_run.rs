@@ -11,16 +11,19 @@
 //! - `aip.run.set_label(label: string)`  
 //! - `aip.run.pin(iden: string, content: string | {label?: string, content: string})`  
 //! - `aip.run.pin(iden: string, priority: number, content: string | {label?: string, content: string})`  
+//! - `aip.run.messages(): {role: "user" | "assistant", content: string}[]`  
+//! - `aip.run.append_message(role: "user" | "assistant", content: string)`  
 //!   
 
 use crate::Result;
 use crate::model::{RunBmc, RunForUpdate, RuntimeCtx};
+use crate::run::ConvMessage;
 use crate::runtime::Runtime;
-use crate::script::LuaValueExt;
 use crate::script::support::create_pin;
+use crate::script::{LuaValueExt, serde_value_to_lua_value};
 use mlua::{Lua, Table, Value, Variadic};
 
-/// Registers the `run.set_label` and `run.pin` helpers in Lua.
+/// Registers the `run.set_label`, `run.pin`, and the run conversation helpers in Lua.
 pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
@@ -41,6 +44,22 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 		table.set("pin", run_pin_fn)?;
 	}
 
+	// -- run.messages
+	{
+		let rt = runtime.clone();
+		let messages_fn = lua.create_function(move |lua, ()| run_messages(lua, &rt).map_err(mlua::Error::external))?;
+		table.set("messages", messages_fn)?;
+	}
+
+	// -- run.append_message
+	{
+		let rt = runtime.clone();
+		let append_message_fn = lua.create_function(move |lua, (role, content): (String, String)| {
+			run_append_message(lua, &rt, role, content).map_err(mlua::Error::external)
+		})?;
+		table.set("append_message", append_message_fn)?;
+	}
+
 	Ok(table)
 }
 
@@ -67,15 +86,76 @@ fn set_run_label(lua: &Lua, runtime: &Runtime, label: Value) -> Result<()> {
 	Ok(())
 }
 
+/// ## Lua Documentation
+///
+/// Returns the run conversation (the turns recorded with the agent option `conversation = true`,
+/// and the messages appended with `aip.run.append_message`), in order.
+///
+/// ```lua
+/// -- API Signature
+/// aip.run.messages(): {role: "user" | "assistant", content: string}[]
+/// ```
+///
+/// ### Error
+///
+/// Returns an error when called outside of a run context.
+fn run_messages(lua: &Lua, runtime: &Runtime) -> Result<Value> {
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	let run_id = ctx
+		.get_run_id(runtime.mm())?
+		.ok_or("Cannot call 'aip.run.messages()' outside of a run context.")?;
+
+	let messages = runtime.rt_model().list_conv_messages(run_id)?;
+	serde_value_to_lua_value(lua, serde_json::to_value(messages)?)
+}
+
+/// ## Lua Documentation
+///
+/// Appends a message to the run conversation, sent before the prompt of the next tasks
+/// with the agent option `conversation = true` (e.g., a user follow-up from `aip.flow.ask_user`).
+///
+/// ```lua
+/// -- API Signature
+/// aip.run.append_message(role: "user" | "assistant", content: string)
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local follow_up = aip.flow.ask_user("Follow-up question?")
+/// if follow_up then
+///   aip.run.append_message("user", follow_up)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the role is not `"user"` or `"assistant"`, or when called outside of a run context.
+fn run_append_message(lua: &Lua, runtime: &Runtime, role: String, content: String) -> Result<()> {
+	let message = ConvMessage::new(role, content)?;
+
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	let mm = runtime.mm();
+	let run_id = ctx
+		.get_run_id(mm)?
+		.ok_or("Cannot call 'aip.run.append_message(...)' outside of a run context.")?;
+	let task_id = ctx.get_task_id(mm)?;
+
+	runtime.rt_model().add_conv_messages(run_id, task_id, &[message])?;
+
+	Ok(())
+}
+
 // region:    --- Tests
 
 #[cfg(test)]
 mod tests {
 	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
 
-	use crate::_test_support::run_reflective_agent_with_runtime;
-	use crate::model::{PinBmc, RunBmc};
+	use crate::_test_support::{assert_contains, run_reflective_agent_with_runtime};
+	use crate::model::{ConvMsgBmc, PinBmc, RunBmc};
 	use crate::runtime::Runtime;
+	use value_ext::JsonValueExt as _;
 
 	#[tokio::test(flavor = "multi_thread")]
 	async fn test_lua_run_set_label_simple() -> Result<()> {
@@ -141,6 +221,32 @@ return "OK"
 
 		Ok(())
 	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_run_append_message_and_messages() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+aip.run.append_message("user", "What is 2+2?")
+aip.run.append_message("assistant", "4")
+local ok, err = pcall(aip.run.append_message, "tool", "x")
+return { messages = aip.run.messages(), err = tostring(err) }
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
+
+		// -- Check
+		assert_eq!(res.x_get_str("/messages/0/role")?, "user");
+		assert_eq!(res.x_get_str("/messages/0/content")?, "What is 2+2?");
+		assert_eq!(res.x_get_str("/messages/1/role")?, "assistant");
+		assert_eq!(res.x_get_str("/messages/1/content")?, "4");
+		assert_contains(res.x_get_str("err")?, "role 'tool' not supported");
+		let msgs = ConvMsgBmc::list_for_run(runtime.mm(), 0.into())?;
+		assert_eq!(msgs.len(), 2);
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -8,18 +8,42 @@
 //! ### Functions
 //!
 //! - `aip.rust.prune_to_declarations(code: string) -> string | {error: string}`
+//! - `aip.rust.list_fns(path: string) -> RustItem[]`
+//! - `aip.rust.extract_fn(path: string, name: string) -> RustItem | nil`
+//! - `aip.rust.list_items(include_globs: string | string[], options?: {base_dir?: string}) -> RustItem[]`
 
-use crate::Result;
 use crate::runtime::Runtime;
-use crate::support::code::run_prune_to_declarations;
+use crate::script::aip_modules::support::{
+	base_dir_and_globs, check_access_list, check_access_read, list_base_path, list_files_with_options,
+};
+use crate::script::serde_value_to_lua_value;
+use crate::support::AsStrsExt as _;
+use crate::support::code::{RustItem, find_rust_fns, parse_rust_items, run_prune_to_declarations};
+use crate::{Error, Result};
 use mlua::{Lua, Table, Value};
+use simple_fs::SPath;
 
-pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
 	let prune_fn = lua.create_function(prune_to_declarations)?;
 
+	let rt = runtime.clone();
+	let list_fns_fn = lua.create_function(move |lua, path: String| rust_list_fns(lua, &rt, path))?;
+
+	let rt = runtime.clone();
+	let extract_fn_fn =
+		lua.create_function(move |lua, (path, name): (String, String)| rust_extract_fn(lua, &rt, path, name))?;
+
+	let rt = runtime.clone();
+	let list_items_fn = lua.create_function(move |lua, (include_globs, options): (Value, Option<Value>)| {
+		rust_list_items(lua, &rt, include_globs, options)
+	})?;
+
 	table.set("prune_to_declarations", prune_fn)?;
+	table.set("list_fns", list_fns_fn)?;
+	table.set("extract_fn", extract_fn_fn)?;
+	table.set("list_items", list_items_fn)?;
 
 	Ok(table)
 }
@@ -59,6 +83,182 @@ fn prune_to_declarations(lua: &Lua, code: String) -> mlua::Result<Value> {
 	}
 }
 
+/// ## Lua Documentation
+///
+/// Lists the functions of a Rust file (the free fns, and the `impl`, `trait`, and inline `mod` fns),
+/// parsed with a real Rust parser.
+///
+/// ```lua
+/// -- API Signature
+/// aip.rust.list_fns(path: string): RustItem[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string`: The Rust file path (relative to the workspace, or pack ref).
+///
+/// ### Returns
+///
+/// - `RustItem[]`: The `fn` items, in the source order.
+///
+/// ```ts
+/// {
+///   kind: string,         // "fn", "struct", "enum", "union", "trait", "impl", "type", "const", "static", "mod", "macro"
+///   name: string,         // e.g., "new" (for "impl", the self type, e.g., "Foo", or "Display for Foo")
+///   parent?: string,      // the containing mod, impl self type, or trait path (e.g., "Foo", "tests")
+///   start_line: number,   // 1-based, including the doc comments and attributes
+///   end_line: number,     // 1-based, inclusive
+///   doc?: string,         // the doc comments, without the "///"
+///   signature?: string,   // for "fn" (e.g., "pub fn new(name: &str) -> Self")
+///   code: string,         // the source of the item lines
+///   path?: string         // the file path (for aip.rust.list_items)
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// for _, item in ipairs(aip.rust.list_fns("src/main.rs")) do
+///   print(item.start_line .. "-" .. item.end_line .. " " .. item.signature)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the file cannot be read, or is not valid Rust code.
+fn rust_list_fns(lua: &Lua, runtime: &Runtime, path: String) -> mlua::Result<Value> {
+	let items = load_rust_items(lua, runtime, &path, "aip.rust.list_fns")?;
+	let fns = items.into_iter().filter(|item| item.kind == "fn");
+	rust_items_to_lua(lua, fns, None)
+}
+
+/// ## Lua Documentation
+///
+/// Extracts a function of a Rust file, by name.
+///
+/// ```lua
+/// -- API Signature
+/// aip.rust.extract_fn(path: string, name: string): RustItem | nil
+/// ```
+///
+/// ### Arguments
+///
+/// - `path: string`: The Rust file path (relative to the workspace, or pack ref).
+/// - `name: string`: The fn name (e.g., `new`), or its qualified name (e.g., `Foo::new`, `tests::test_new`).
+///   When several fns match, the first one is returned.
+///
+/// ### Returns
+///
+/// - `RustItem | nil`: The `fn` item (see `aip.rust.list_fns`), or `nil` if not found.
+///
+/// ### Example
+///
+/// ```lua
+/// local item = aip.rust.extract_fn("src/model.rs", "Model::save")
+/// if item then
+///   print(item.code)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the file cannot be read, or is not valid Rust code.
+fn rust_extract_fn(lua: &Lua, runtime: &Runtime, path: String, name: String) -> mlua::Result<Value> {
+	let items = load_rust_items(lua, runtime, &path, "aip.rust.extract_fn")?;
+	match find_rust_fns(&items, &name).into_iter().next() {
+		Some(item) => rust_item_to_lua(lua, item, None),
+		None => Ok(Value::Nil),
+	}
+}
+
+/// ## Lua Documentation
+///
+/// Lists the items (fns, structs, enums, traits, impl blocks, ...) of the Rust files matching the globs.
+///
+/// ```lua
+/// -- API Signature
+/// aip.rust.list_items(include_globs: string | string[], options?: {base_dir?: string}): RustItem[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `include_globs: string | string[]`: The globs of the Rust files (e.g., `"src/**/*.rs"`).
+/// - `options?: table`:
+///   - `base_dir?: string`: The base dir of the globs (default: the workspace dir).
+///
+/// ### Returns
+///
+/// - `RustItem[]`: The items of all the files (see `aip.rust.list_fns`), with their file `path`,
+///   in the file then source order (an `impl` or `mod` before its items).
+///
+/// ### Example
+///
+/// ```lua
+/// local items = aip.rust.list_items("src/**/*.rs")
+/// for _, item in ipairs(items) do
+///   if item.kind == "struct" then
+///     print(item.path .. ":" .. item.start_line .. " " .. item.name)
+///   end
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if a file cannot be read, or is not valid Rust code (with the file path).
+fn rust_list_items(lua: &Lua, runtime: &Runtime, include_globs: Value, options: Option<Value>) -> mlua::Result<Value> {
+	let (base_path, include_globs) = base_dir_and_globs(runtime, include_globs, options.as_ref())?;
+	let file_refs = list_files_with_options(runtime, base_path.as_ref(), &include_globs.x_as_strs(), false, true)?;
+	let list_base = list_base_path(runtime, base_path.as_ref()).cloned();
+	let file_refs = check_access_list(lua, list_base.as_ref(), file_refs, "aip.rust.list_items")?;
+
+	let table = lua.create_table()?;
+	for file_ref in file_refs {
+		let full_path = match &list_base {
+			Some(base) if !file_ref.spath.is_absolute() => base.join(&file_ref.spath),
+			_ => file_ref.spath.clone(),
+		};
+		let items = read_rust_items(&full_path, file_ref.spath.as_str())?;
+		for item in items.iter() {
+			table.push(rust_item_to_lua(lua, item, Some(file_ref.spath.as_str()))?)?;
+		}
+	}
+
+	Ok(Value::Table(table))
+}
+
+// region:    --- Support
+
+fn load_rust_items(lua: &Lua, runtime: &Runtime, path: &str, what: &str) -> Result<Vec<RustItem>> {
+	let full_path = runtime.resolve_path_default(SPath::new(path), None)?;
+	check_access_read(lua, &full_path, what)?;
+	read_rust_items(&full_path, path).map_err(|err| Error::custom(format!("{what} failed. {err}")))
+}
+
+fn read_rust_items(full_path: &SPath, path: &str) -> Result<Vec<RustItem>> {
+	let content = std::fs::read_to_string(full_path.as_std_path())
+		.map_err(|err| Error::cc(format!("Cannot read Rust file '{path}'"), err))?;
+	parse_rust_items(&content).map_err(|err| Error::custom(format!("Rust file '{path}'. {err}")))
+}
+
+fn rust_items_to_lua(lua: &Lua, items: impl Iterator<Item = RustItem>, path: Option<&str>) -> mlua::Result<Value> {
+	let table = lua.create_table()?;
+	for item in items {
+		table.push(rust_item_to_lua(lua, &item, path)?)?;
+	}
+	Ok(Value::Table(table))
+}
+
+fn rust_item_to_lua(lua: &Lua, item: &RustItem, path: Option<&str>) -> mlua::Result<Value> {
+	let mut value =
+		serde_json::to_value(item).map_err(|err| Error::custom(format!("Cannot convert Rust item. {err}")))?;
+	if let (Some(path), Some(obj)) = (path, value.as_object_mut()) {
+		obj.insert("path".to_string(), path.into());
+	}
+	Ok(serde_value_to_lua_value(lua, value)?)
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -104,6 +304,66 @@ fn some_normal() {
 		assert!(!res.contains("// DOING SOME STUFF"), "DOING SOME STUFF");
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_lua_rust_list_fns_extract_fn() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_rust::init_module, "rust").await?;
+		let script = r#"
+local fns = aip.rust.list_fns("./other/rust_items_example.rs")
+local item = aip.rust.extract_fn("./other/rust_items_example.rs", "Point::norm")
+local missing = aip.rust.extract_fn("./other/rust_items_example.rs", "nope")
+return { count = #fns, first = fns[1].name, item = item, missing = missing == nil }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["count"].as_i64(), Some(3));
+		assert_eq!(res["first"].as_str(), Some("new"));
+		assert_eq!(res["missing"].as_bool(), Some(true));
+		let item = &res["item"];
+		assert_eq!(item["parent"].as_str(), Some("Point"));
+		assert_eq!(item["doc"].as_str(), Some("The distance to the origin"));
+		assert_eq!(item["signature"].as_str(), Some("pub fn norm(&self) -> f64"));
+		assert_contains(item["code"].as_str().ok_or("Should have code")?, "self.x.hypot(self.y)");
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_rust_list_items() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_rust::init_module, "rust").await?;
+		let script = r#"
+local items = aip.rust.list_items("other/rust_items_*.rs")
+local kinds = {}
+for _, item in ipairs(items) do
+  table.insert(kinds, item.kind .. " " .. item.name)
+end
+return { kinds = kinds, path = items[1].path, start_line = items[1].start_line }
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let kinds: Vec<&str> = res["kinds"]
+			.as_array()
+			.ok_or("Should have kinds")?
+			.iter()
+			.filter_map(|v| v.as_str())
+			.collect();
+		assert_eq!(
+			kinds,
+			vec!["struct Point", "impl Point", "fn new", "fn norm", "fn origin_dist"]
+		);
+		assert_eq!(res["path"].as_str(), Some("other/rust_items_example.rs"));
+		assert_eq!(res["start_line"].as_i64(), Some(1));
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -0,0 +1,481 @@
+//! Defines the `aip.scaffold` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.scaffold` module renders a directory of Handlebars templates to a destination directory
+//! (e.g., for the project generator agents).
+//!
+//! ### Functions
+//!
+//! - `aip.scaffold.render(template_dir: string, dest_dir: string, vars?: table, options?: ScaffoldOptions): ScaffoldSummary`
+
+use crate::dir_context::PathResolver;
+use crate::hub::get_hub;
+use crate::runtime::Runtime;
+use crate::script::aip_modules::support::{
+	ask_user_choice, check_access_read, check_access_write, check_confirm_file_op, check_confirm_write,
+};
+use crate::support::hbs::hbs_render;
+use crate::types::{ConflictPolicy, ScaffoldOptions, WriteConfirm};
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+use serde_json::json;
+use simple_fs::{SPath, ensure_file_dir, get_glob_set};
+use walkdir::WalkDir;
+
+/// The template file extension removed from the destination file names
+const TEMPLATE_EXT: &str = ".hbs";
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let render_fn = lua.create_function(
+		move |lua,
+		      (template_dir, dest_dir, vars, options): (String, String, Option<Value>, Option<ScaffoldOptions>)| {
+			scaffold_render(lua, &rt, template_dir, dest_dir, vars, options)
+		},
+	)?;
+
+	table.set("render", render_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Renders a directory of Handlebars templates to a destination directory.
+///
+/// ```lua
+/// -- API Signature
+/// aip.scaffold.render(template_dir: string, dest_dir: string, vars?: table, options?: ScaffoldOptions): ScaffoldSummary
+/// ```
+///
+/// Each file of `template_dir` (recursively) is rendered with `vars`, and written at the same relative path
+/// under `dest_dir`, with these rules:
+///
+/// - The relative paths are rendered as well (e.g., `src/{{name}}.rs`).
+/// - The `.hbs` extension is removed (e.g., `Cargo.toml.hbs` is written as `Cargo.toml`).
+/// - The non UTF-8 files (e.g., images), and the files matching `raw_globs`, are copied as is.
+/// - The existing files with the same content are left untouched (`unchanged`).
+///
+/// The writes follow the agent `confirm_writes` option (as `aip.file.save`).
+///
+/// ### Arguments
+///
+/// - `template_dir: string` - The templates dir (relative to the workspace, or pack ref, e.g., `my@pack/templates/rust-lib`).
+/// - `dest_dir: string` - The destination dir (relative to the workspace).
+/// - `vars?: table` - The Handlebars data (e.g., `{ name = "my-lib", authors = {"Jen"} }`).
+/// - `options?: ScaffoldOptions`:
+///   - `conflict?: string`: When a destination file exists (with a different content):
+///     - `"skip"` (default): keep the existing file
+///     - `"overwrite"`: replace it
+///     - `"prompt"`: ask the user (with the diff preview) for each file
+///     - `"error"`: fail before writing any file
+///   - `globs?: string | string[]`: The template files to render (relative to `template_dir`, default all).
+///   - `raw_globs?: string | string[]`: The template files copied without rendering (e.g., `".github/**"`).
+///
+/// ### Returns
+///
+/// - `ScaffoldSummary`: The destination paths (relative to the workspace), by outcome.
+///
+/// ```ts
+/// {
+///   created: string[],
+///   overwritten: string[],
+///   skipped: string[],   // existing, and kept (by the "skip" or "prompt" conflict policy)
+///   unchanged: string[]  // existing, with the same content
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local summary = aip.scaffold.render("my@pack/templates/rust-lib", "crates/" .. name, {
+///   name    = name,
+///   authors = { "Jen Donavan" },
+/// }, { conflict = "prompt" })
+/// print("Created: " .. #summary.created .. " files")
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the template dir does not exist, a rendered path is not relative to `dest_dir`
+/// (e.g., `../a.txt`), a template fails to render, a destination is outside the workspace,
+/// an existing file is found with the `"error"` policy, or a write is not approved (`confirm_writes`).
+fn scaffold_render(
+	lua: &Lua,
+	runtime: &Runtime,
+	template_dir: String,
+	dest_dir: String,
+	vars: Option<Value>,
+	options: Option<ScaffoldOptions>,
+) -> mlua::Result<Value> {
+	let options = options.unwrap_or_default();
+	let dir_context = runtime.dir_context();
+	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.scaffold.render requires a aipack workspace setup")?;
+
+	// -- Resolve the dirs
+	let template_full =
+		dir_context.resolve_path(runtime.session(), SPath::new(&template_dir), PathResolver::WksDir, None)?;
+	if !template_full.is_dir() {
+		return Err(Error::custom(format!(
+			"aip.scaffold.render failed - template dir `{template_dir}` does not exist or is not a directory"
+		))
+		.into());
+	}
+	check_access_read(lua, &template_full, "aip.scaffold.render")?;
+	let dest_full = dir_context.resolve_path(runtime.session(), SPath::new(&dest_dir), PathResolver::WksDir, None)?;
+
+	// -- Build the files to write
+	let vars = match vars {
+		Some(vars) => serde_json::to_value(&vars)
+			.map_err(|err| Error::custom(format!("aip.scaffold.render - Cannot convert the vars. {err}")))?,
+		None => json!({}),
+	};
+	let items = build_scaffold_items(lua, &template_full, &dest_full, wks_dir, &vars, &options)?;
+
+	// -- Check the conflicts (all before any write for the "error" policy)
+	if options.conflict == ConflictPolicy::Error {
+		let existing: Vec<&str> = items
+			.iter()
+			.filter(|item| item.dest_full.exists())
+			.map(|item| item.rel_dest.as_str())
+			.collect();
+		if !existing.is_empty() {
+			return Err(Error::custom(format!(
+				"aip.scaffold.render failed - destination files exist (conflict = 'error'): {}",
+				existing.join(", ")
+			))
+			.into());
+		}
+	}
+
+	// -- Write
+	let mut summary = ScaffoldSummary::default();
+	for item in items {
+		check_access_write(lua, &item.dest_full, wks_dir)?;
+
+		let lock_handle = runtime.file_write_manager().lock_for_path(&item.dest_full);
+		let _guard = lock_handle.lock();
+
+		let existing = if item.dest_full.exists() {
+			Some(
+				std::fs::read(item.dest_full.as_std_path())
+					.map_err(|err| Error::cc(format!("aip.scaffold.render - Cannot read `{}`", item.rel_dest), err))?,
+			)
+		} else {
+			None
+		};
+
+		let mut user_approved = false;
+		if let Some(existing) = existing.as_ref() {
+			if *existing == item.content {
+				summary.unchanged.push(item.rel_dest);
+				continue;
+			}
+			match options.conflict {
+				ConflictPolicy::Skip => {
+					summary.skipped.push(item.rel_dest);
+					continue;
+				}
+				ConflictPolicy::Prompt => {
+					if !ask_overwrite(&item, existing)? {
+						summary.skipped.push(item.rel_dest);
+						continue;
+					}
+					user_approved = true;
+				}
+				ConflictPolicy::Overwrite | ConflictPolicy::Error => (),
+			}
+		}
+
+		if !user_approved {
+			match std::str::from_utf8(&item.content) {
+				Ok(content) => {
+					check_confirm_write(lua, "aip.scaffold.render", &item.dest_full, wks_dir, content, false)?
+				}
+				Err(_) => check_confirm_file_op(
+					lua,
+					"aip.scaffold.render",
+					&[&item.dest_full],
+					wks_dir,
+					"write (binary file)",
+				)?,
+			}
+		}
+
+		ensure_file_dir(&item.dest_full).map_err(Error::from)?;
+		std::fs::write(item.dest_full.as_std_path(), &item.content)
+			.map_err(|err| Error::cc(format!("aip.scaffold.render - Cannot write `{}`", item.rel_dest), err))?;
+
+		if existing.is_some() {
+			summary.overwritten.push(item.rel_dest);
+		} else {
+			summary.created.push(item.rel_dest);
+		}
+	}
+
+	let rel_dest_dir = dest_full.diff(wks_dir).unwrap_or_else(|| dest_full.clone());
+	get_hub().publish_sync(format!(
+		"-> Lua aip.scaffold.render called on: {rel_dest_dir} ({} created, {} overwritten, {} skipped, {} unchanged)",
+		summary.created.len(),
+		summary.overwritten.len(),
+		summary.skipped.len(),
+		summary.unchanged.len()
+	));
+
+	summary.into_lua_value(lua)
+}
+
+// region:    --- Support
+
+/// A file to write (rendered, or copied as is)
+struct ScaffoldItem {
+	dest_full: SPath,
+	/// The destination path relative to the workspace (for the summary and messages)
+	rel_dest: String,
+	content: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct ScaffoldSummary {
+	created: Vec<String>,
+	overwritten: Vec<String>,
+	skipped: Vec<String>,
+	unchanged: Vec<String>,
+}
+
+impl ScaffoldSummary {
+	fn into_lua_value(self, lua: &Lua) -> mlua::Result<Value> {
+		let table = lua.create_table()?;
+		table.set("created", self.created)?;
+		table.set("overwritten", self.overwritten)?;
+		table.set("skipped", self.skipped)?;
+		table.set("unchanged", self.unchanged)?;
+		Ok(Value::Table(table))
+	}
+}
+
+fn build_scaffold_items(
+	lua: &Lua,
+	template_full: &SPath,
+	dest_full: &SPath,
+	wks_dir: &SPath,
+	vars: &serde_json::Value,
+	options: &ScaffoldOptions,
+) -> Result<Vec<ScaffoldItem>> {
+	let include_matcher = options
+		.globs
+		.as_ref()
+		.map(|globs| to_glob_matcher(globs, "globs"))
+		.transpose()?;
+	let raw_matcher = options
+		.raw_globs
+		.as_ref()
+		.map(|globs| to_glob_matcher(globs, "raw_globs"))
+		.transpose()?;
+
+	let mut items = Vec::new();
+	for entry in WalkDir::new(template_full.as_std_path()).sort_by_file_name() {
+		let entry = entry.map_err(|err| Error::cc("aip.scaffold.render - Cannot list the template dir", err))?;
+		if !entry.file_type().is_file() {
+			continue;
+		}
+		let template_path = SPath::from_walkdir_entry(entry)?;
+		let Some(rel_template) = template_path.diff(template_full) else {
+			continue;
+		};
+		let rel_template = rel_template.as_str().replace('\\', "/");
+
+		if let Some(is_included) = include_matcher.as_ref()
+			&& !is_included(&rel_template)
+		{
+			continue;
+		}
+		check_access_read(lua, &template_path, "aip.scaffold.render")?;
+
+		// -- The destination path
+		let rel_out = rel_template.strip_suffix(TEMPLATE_EXT).unwrap_or(&rel_template);
+		let rel_out = if rel_out.contains("{{") {
+			hbs_render(rel_out, vars).map_err(|err| {
+				Error::custom(format!(
+					"aip.scaffold.render - Cannot render path `{rel_template}`. {err}"
+				))
+			})?
+		} else {
+			rel_out.to_string()
+		};
+		check_rel_out(&rel_template, &rel_out)?;
+
+		// -- The content
+		let bytes = std::fs::read(template_path.as_std_path()).map_err(|err| {
+			Error::cc(
+				format!("aip.scaffold.render - Cannot read template `{rel_template}`"),
+				err,
+			)
+		})?;
+		let is_raw = raw_matcher.as_ref().is_some_and(|is_raw| is_raw(&rel_template));
+		let content = match std::str::from_utf8(&bytes) {
+			Ok(template) if !is_raw => hbs_render(template, vars)
+				.map_err(|err| Error::custom(format!("aip.scaffold.render - Cannot render `{rel_template}`. {err}")))?
+				.into_bytes(),
+			_ => bytes,
+		};
+
+		let item_full = dest_full.join(&rel_out);
+		let rel_dest = item_full.diff(wks_dir).unwrap_or_else(|| item_full.clone()).to_string();
+		items.push(ScaffoldItem {
+			dest_full: item_full,
+			rel_dest,
+			content,
+		});
+	}
+
+	Ok(items)
+}
+
+/// The rendered path must stay under the destination dir
+fn check_rel_out(rel_template: &str, rel_out: &str) -> Result<()> {
+	let is_valid = !rel_out.trim().is_empty()
+		&& !rel_out.starts_with('/')
+		&& !rel_out.contains(':')
+		&& rel_out.split(['/', '\\']).all(|part| !part.is_empty() && part != "..");
+	if is_valid {
+		Ok(())
+	} else {
+		Err(Error::custom(format!(
+			"aip.scaffold.render - The template `{rel_template}` renders to the invalid path `{rel_out}` (must be relative to the destination dir, without `..`)"
+		)))
+	}
+}
+
+/// The matcher of the template relative paths
+fn to_glob_matcher(globs: &[String], name: &str) -> Result<impl Fn(&str) -> bool> {
+	let glob_refs: Vec<&str> = globs.iter().map(String::as_str).collect();
+	let glob_set = get_glob_set(&glob_refs)
+		.map_err(|err| Error::custom(format!("aip.scaffold.render - Invalid {name} {globs:?}. {err}")))?;
+	Ok(move |path: &str| glob_set.is_match(path))
+}
+
+/// Ask the user to overwrite an existing file (the "prompt" conflict policy)
+fn ask_overwrite(item: &ScaffoldItem, existing: &[u8]) -> Result<bool> {
+	let preview = match (std::str::from_utf8(existing), std::str::from_utf8(&item.content)) {
+		(Ok(original), Ok(content)) => WriteConfirm::diff_preview(&item.rel_dest, Some(original), content),
+		_ => format!("{}: binary file ({} bytes)", item.rel_dest, item.content.len()),
+	};
+	let answer = ask_user_choice(
+		format!(
+			"\n-? aip.scaffold.render '{}' already exists\n   Overwrite it?\n",
+			item.rel_dest
+		),
+		&["overwrite", "skip"],
+		"skip",
+		preview,
+	)?;
+
+	Ok(matches!(
+		answer.trim().to_lowercase().as_str(),
+		"overwrite" | "o" | "yes" | "y" | "1"
+	))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{SANDBOX_01_WKS_DIR, eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_scaffold;
+	use simple_fs::SPath;
+
+	#[tokio::test]
+	async fn test_lua_scaffold_render_conflicts() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_scaffold::init_module, "scaffold").await?;
+		let fx_dir = ".tmp/test_lua_scaffold_render_conflicts";
+		let fx_full_dir = SPath::new(SANDBOX_01_WKS_DIR).join(fx_dir);
+		if fx_full_dir.exists() {
+			std::fs::remove_dir_all(fx_full_dir.as_std_path())?;
+		}
+		let tmpl_dir = fx_full_dir.join("tmpl");
+		std::fs::create_dir_all(tmpl_dir.join("src").as_std_path())?;
+		std::fs::write(tmpl_dir.join("Cargo.toml.hbs").as_std_path(), "name = \"{{name}}\"")?;
+		std::fs::write(
+			tmpl_dir.join("src/{{name}}.rs").as_std_path(),
+			"// {{name}} v{{version}}",
+		)?;
+		std::fs::write(tmpl_dir.join("raw.txt").as_std_path(), "${{ raw }}")?;
+		let script = format!(
+			r#"
+local vars = {{ name = "demo", version = 1 }}
+local first = aip.scaffold.render("{fx_dir}/tmpl", "{fx_dir}/out", vars, {{ raw_globs = "raw.txt" }})
+vars.version = 2
+local second = aip.scaffold.render("{fx_dir}/tmpl", "{fx_dir}/out", vars, {{ raw_globs = {{"raw.txt"}} }})
+local err_ok = pcall(function() return aip.scaffold.render("{fx_dir}/tmpl", "{fx_dir}/out", vars, {{ conflict = "error" }}) end)
+local third = aip.scaffold.render("{fx_dir}/tmpl", "{fx_dir}/out", vars, {{ conflict = "overwrite", globs = "src/**" }})
+return {{ first = first, second = second, err_ok = err_ok, third = third }}
+		"#
+		);
+
+		// -- Exec
+		let res = eval_lua(&lua, &script)?;
+
+		// -- Check
+		let out_dir = fx_full_dir.join("out");
+		assert_eq!(res["first"]["created"].as_array().map(|v| v.len()), Some(3));
+		assert_eq!(
+			res["first"]["created"][0].as_str(),
+			Some(".tmp/test_lua_scaffold_render_conflicts/out/Cargo.toml")
+		);
+		assert_eq!(res["second"]["unchanged"].as_array().map(|v| v.len()), Some(2));
+		assert_eq!(
+			res["second"]["skipped"][0].as_str(),
+			Some(".tmp/test_lua_scaffold_render_conflicts/out/src/demo.rs")
+		);
+		assert_eq!(res["err_ok"].as_bool(), Some(false));
+		assert_eq!(res["third"]["overwritten"].as_array().map(|v| v.len()), Some(1));
+		assert_eq!(
+			std::fs::read_to_string(out_dir.join("Cargo.toml").as_std_path())?,
+			"name = \"demo\""
+		);
+		assert_eq!(
+			std::fs::read_to_string(out_dir.join("src/demo.rs").as_std_path())?,
+			"// demo v2"
+		);
+		assert_eq!(
+			std::fs::read_to_string(out_dir.join("raw.txt").as_std_path())?,
+			"${{ raw }}"
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_scaffold_render_invalid_path() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_scaffold::init_module, "scaffold").await?;
+		let fx_dir = ".tmp/test_lua_scaffold_render_invalid_path";
+		let tmpl_dir = SPath::new(SANDBOX_01_WKS_DIR).join(fx_dir).join("tmpl");
+		std::fs::create_dir_all(tmpl_dir.as_std_path())?;
+		std::fs::write(tmpl_dir.join("{{name}}.txt").as_std_path(), "hello")?;
+		let script =
+			format!(r#"return aip.scaffold.render("{fx_dir}/tmpl", "{fx_dir}/out", {{ name = "../escape" }})"#);
+
+		// -- Exec
+		let err = eval_lua(&lua, &script).err().ok_or("Should be an error")?;
+
+		// -- Check
+		assert!(err.to_string().contains("invalid path"));
+		assert!(!SPath::new(SANDBOX_01_WKS_DIR).join(fx_dir).join("escape.txt").exists());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -17,13 +17,19 @@
 //!   Returns `true` if the version is a prerelease (has a prerelease component).
 //! - `aip.semver.valid(version: string): boolean`
 //!   Returns `true` if the version string is a valid semantic version.
+//! - `aip.semver.satisfies(version: string, range: string, options?: {include_prerelease?: boolean}): boolean`
+//!   Returns `true` if the version matches the range (e.g., `^1.2`, `~1.2.3`, `>=1.2 <2`, `1.2.3 - 2.0.0`, `^1 || ^2`).
+//! - `aip.semver.sort(versions: string[], options?: {desc?: boolean}): string[]`
+//!   Returns the versions sorted by semver precedence.
+//! - `aip.semver.max_satisfying(versions: string[], range: string, options?: {include_prerelease?: boolean}): string | nil`
+//!   Returns the highest version matching the range.
 //!
 //! ---
 
 use crate::Result;
 use crate::runtime::Runtime;
 use mlua::{Lua, Table, Value};
-use semver::Version;
+use semver::{Comparator, Op, Prerelease, Version, VersionReq};
 
 pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
@@ -32,6 +38,9 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	table.set("parse", lua.create_function(parse)?)?;
 	table.set("is_prerelease", lua.create_function(is_prerelease)?)?;
 	table.set("valid", lua.create_function(valid)?)?;
+	table.set("satisfies", lua.create_function(satisfies)?)?;
+	table.set("sort", lua.create_function(sort)?)?;
+	table.set("max_satisfying", lua.create_function(max_satisfying)?)?;
 
 	Ok(table)
 }
@@ -241,6 +250,232 @@ fn valid(_lua: &Lua, version: String) -> mlua::Result<bool> {
 	Ok(parse_version(&version).is_ok())
 }
 
+/// ## Lua Documentation
+///
+/// Returns `true` if the version matches the range.
+///
+/// ```lua
+/// -- API Signature
+/// aip.semver.satisfies(version: string, range: string, options?: {include_prerelease?: boolean}): boolean
+/// ```
+///
+/// ### Arguments
+///
+/// - `version: string`: The semantic version string to check.
+/// - `range: string`: The version range. Supported syntax:
+///   - Caret: `^1.2.3` (`>=1.2.3, <2.0.0`), `^0.2.3` (`>=0.2.3, <0.3.0`)
+///   - Tilde: `~1.2.3` (`>=1.2.3, <1.3.0`), `~1.2` (`>=1.2.0, <1.3.0`)
+///   - Comparators: `>=1.2.0, <2.0.0` or `>=1.2.0 <2.0.0` (all must match)
+///   - Wildcards: `1.x`, `1.2.*`, `*`
+///   - Hyphen range: `1.2.3 - 2.0.0` (`>=1.2.3, <=2.0.0`)
+///   - Alternatives: `^1.2 || ^2.0` (any must match)
+///   - A bare version `1.2.3` is a caret range (same as `^1.2.3`), use `=1.2.3` for an exact match.
+/// - `options?: table`:
+///   - `include_prerelease?: boolean` (default `false`): By default, a prerelease version (e.g., `1.3.0-beta.1`) only matches
+///     when a comparator of the range has a prerelease with the same `major.minor.patch` (e.g., `>=1.3.0-alpha`).
+///     When `true`, prerelease versions match like release versions.
+///
+/// ### Returns
+///
+/// `boolean`: `true` if the version matches the range.
+///
+/// ### Example
+///
+/// ```lua
+/// print(aip.semver.satisfies("1.4.2", "^1.2"))                 -- Output: true
+/// print(aip.semver.satisfies("2.0.0", "^1.2"))                 -- Output: false
+/// print(aip.semver.satisfies("1.2.9", "~1.2.3"))               -- Output: true
+/// print(aip.semver.satisfies("1.5.0", ">=1.2 <2"))             -- Output: true
+/// print(aip.semver.satisfies("2.1.0", "^1 || ^2"))             -- Output: true
+/// print(aip.semver.satisfies("1.3.0-beta.1", "^1.2"))          -- Output: false
+/// print(aip.semver.satisfies("1.3.0-beta.1", "^1.2", {include_prerelease = true})) -- Output: true
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the `version` or the `range` is invalid.
+fn satisfies(_lua: &Lua, (version, range, options): (String, String, Option<Value>)) -> mlua::Result<bool> {
+	let include_prerelease = get_bool_option(options, "include_prerelease")?;
+	let v = parse_version(&version).map_err(|e| mlua::Error::runtime(format!("Invalid version '{version}': {e}")))?;
+	let reqs = parse_range(&range).map_err(|e| mlua::Error::runtime(format!("Invalid range '{range}': {e}")))?;
+
+	Ok(range_matches(&reqs, &v, include_prerelease))
+}
+
+/// ## Lua Documentation
+///
+/// Returns the versions sorted by semver precedence.
+///
+/// ```lua
+/// -- API Signature
+/// aip.semver.sort(versions: string[], options?: {desc?: boolean}): string[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `versions: string[]`: The semantic version strings to sort.
+/// - `options?: table`:
+///   - `desc?: boolean` (default `false`): When `true`, the highest version is first.
+///
+/// ### Returns
+///
+/// `string[]`: A new list with the same version strings, sorted (prerelease versions are before their release, e.g., `1.0.0-beta < 1.0.0`).
+///
+/// ### Example
+///
+/// ```lua
+/// local sorted = aip.semver.sort({"1.10.0", "1.2.0", "1.2.0-rc.1", "0.9.1"})
+/// -- {"0.9.1", "1.2.0-rc.1", "1.2.0", "1.10.0"}
+/// local latest_first = aip.semver.sort({"1.10.0", "1.2.0"}, {desc = true})
+/// -- {"1.10.0", "1.2.0"}
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if one of the versions is invalid.
+fn sort(_lua: &Lua, (versions, options): (Vec<String>, Option<Value>)) -> mlua::Result<Vec<String>> {
+	let desc = get_bool_option(options, "desc")?;
+
+	let mut versions = parse_versions(versions)?;
+	versions.sort_by(|(a, _), (b, _)| if desc { b.cmp(a) } else { a.cmp(b) });
+
+	Ok(versions.into_iter().map(|(_, version)| version).collect())
+}
+
+/// ## Lua Documentation
+///
+/// Returns the highest version matching the range.
+///
+/// ```lua
+/// -- API Signature
+/// aip.semver.max_satisfying(versions: string[], range: string, options?: {include_prerelease?: boolean}): string | nil
+/// ```
+///
+/// ### Arguments
+///
+/// - `versions: string[]`: The candidate semantic version strings (e.g., the published versions of a pack).
+/// - `range: string`: The version range (see `aip.semver.satisfies` for the syntax).
+/// - `options?: table`:
+///   - `include_prerelease?: boolean` (default `false`): Same as for `aip.semver.satisfies`.
+///
+/// ### Returns
+///
+/// `string | nil`: The highest matching version string, or `nil` if none matches.
+///
+/// ### Example
+///
+/// ```lua
+/// local versions = {"1.2.0", "1.4.2", "1.5.0-beta.1", "2.0.0"}
+/// print(aip.semver.max_satisfying(versions, "^1.2"))   -- Output: 1.4.2
+/// print(aip.semver.max_satisfying(versions, "^1.2", {include_prerelease = true})) -- Output: 1.5.0-beta.1
+/// print(aip.semver.max_satisfying(versions, "^3"))     -- Output: nil
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if one of the versions or the range is invalid.
+fn max_satisfying(
+	_lua: &Lua,
+	(versions, range, options): (Vec<String>, String, Option<Value>),
+) -> mlua::Result<Option<String>> {
+	let include_prerelease = get_bool_option(options, "include_prerelease")?;
+	let reqs = parse_range(&range).map_err(|e| mlua::Error::runtime(format!("Invalid range '{range}': {e}")))?;
+
+	let max = parse_versions(versions)?
+		.into_iter()
+		.filter(|(v, _)| range_matches(&reqs, v, include_prerelease))
+		.max_by(|(a, _), (b, _)| a.cmp(b))
+		.map(|(_, version)| version);
+
+	Ok(max)
+}
+
+// region:    --- Support
+
+/// Parse a range into its `||` alternatives (see `aip.semver.satisfies` for the syntax).
+fn parse_range(range: &str) -> Result<Vec<VersionReq>> {
+	let mut reqs = Vec::new();
+	for alternative in range.split("||") {
+		let alternative = alternative.trim();
+		if alternative.is_empty() {
+			return Err(crate::Error::custom("empty range alternative"));
+		}
+
+		// -- Hyphen range (`1.2.3 - 2.0.0`)
+		let req_str = if let Some((from, to)) = alternative.split_once(" - ") {
+			format!(">={}, <={}", from.trim(), to.trim())
+		}
+		// -- Comma or space separated comparators (an operator can be followed by a space, e.g., `>= 1.2`)
+		else {
+			let mut comparators: Vec<String> = Vec::new();
+			let mut pending_op: Option<&str> = None;
+			for token in alternative.split([',', ' ']).filter(|t| !t.is_empty()) {
+				if token.chars().all(|c| matches!(c, '<' | '>' | '=' | '^' | '~')) {
+					pending_op = Some(token);
+					continue;
+				}
+				comparators.push(format!("{}{token}", pending_op.take().unwrap_or_default()));
+			}
+			if let Some(op) = pending_op {
+				return Err(crate::Error::custom(format!("operator '{op}' without version")));
+			}
+			comparators.join(", ")
+		};
+
+		reqs.push(VersionReq::parse(&req_str).map_err(crate::Error::custom)?);
+	}
+	Ok(reqs)
+}
+
+/// Returns true if the version matches one of the range alternatives.
+///
+/// NOTE: With `include_prerelease`, a `>=major.minor.patch-0` comparator (always true for the prereleases of this version)
+///       is added, since the semver crate only matches a prerelease when a comparator has a prerelease on the same version.
+fn range_matches(reqs: &[VersionReq], version: &Version, include_prerelease: bool) -> bool {
+	reqs.iter().any(|req| {
+		if req.matches(version) {
+			return true;
+		}
+		if !include_prerelease || version.pre.is_empty() {
+			return false;
+		}
+		let mut req = req.clone();
+		req.comparators.push(Comparator {
+			op: Op::GreaterEq,
+			major: version.major,
+			minor: Some(version.minor),
+			patch: Some(version.patch),
+			pre: Prerelease::new("0").unwrap_or(Prerelease::EMPTY),
+		});
+		req.matches(version)
+	})
+}
+
+/// Parse the versions, and returns them with their original strings
+fn parse_versions(versions: Vec<String>) -> mlua::Result<Vec<(Version, String)>> {
+	versions
+		.into_iter()
+		.map(|version| {
+			let v = parse_version(&version)
+				.map_err(|e| mlua::Error::runtime(format!("Invalid version '{version}': {e}")))?;
+			Ok((v, version))
+		})
+		.collect()
+}
+
+fn get_bool_option(options: Option<Value>, name: &str) -> mlua::Result<bool> {
+	match options {
+		Some(Value::Table(options)) => Ok(options.get::<Option<bool>>(name)?.unwrap_or(false)),
+		None | Some(Value::Nil) => Ok(false),
+		Some(other) => Err(mlua::Error::runtime(format!(
+			"aip.semver options must be a table, but was {}",
+			other.type_name()
+		))),
+	}
+}
+
+// endregion: --- Support
+
 /// Helper function to parse a version string using the `semver` crate.
 fn parse_version(version: &str) -> Result<Version> {
 	let version = Version::parse(version).map_err(crate::Error::custom)?;
@@ -406,6 +641,77 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_lua_semver_satisfies() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_semver::init_module, "semver").await?;
+
+		// (version, range, include_prerelease, expected)
+		let test_cases = [
+			("1.4.2", "^1.2", false, true),
+			("2.0.0", "^1.2", false, false),
+			("0.2.9", "^0.2.3", false, true),
+			("0.3.0", "^0.2.3", false, false),
+			("1.2.9", "~1.2.3", false, true),
+			("1.3.0", "~1.2.3", false, false),
+			("1.5.0", ">=1.2 <2", false, true),
+			("1.5.0", ">= 1.2, < 1.5", false, false),
+			("1.9.9", "1.x", false, true),
+			("2.0.0", "1.2.3 - 2.0.0", false, true),
+			("2.0.1", "1.2.3 - 2.0.0", false, false),
+			("2.1.0", "^1 || ^2", false, true),
+			("1.3.0-beta.1", "^1.2", false, false),
+			("1.3.0-beta.1", "^1.2", true, true),
+			("1.3.0-beta.2", ">=1.3.0-beta.1", false, true),
+			("2.0.0-beta.1", "^1.2", true, false),
+		];
+
+		for (version, range, include_prerelease, expected) in test_cases {
+			let script = format!(
+				r#"return aip.semver.satisfies("{version}", "{range}", {{include_prerelease = {include_prerelease}}})"#
+			);
+			let result: bool = eval_lua(&lua, &script)?.as_bool().ok_or("should be bool")?;
+			assert_eq!(
+				result, expected,
+				"Failed for satisfies(\"{version}\", \"{range}\", {include_prerelease}): expected {expected}, got {result}"
+			);
+		}
+		assert!(eval_lua(&lua, r#"return aip.semver.satisfies("1.0.0", ">=")"#).is_err());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_semver_sort_and_max_satisfying() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_semver::init_module, "semver").await?;
+		let script = r#"
+local versions = {"1.10.0", "1.2.0", "2.0.0", "1.2.0-rc.1", "0.9.1", "1.5.0-beta.1"}
+return {
+	sorted      = aip.semver.sort(versions),
+	sorted_desc = aip.semver.sort(versions, {desc = true}),
+	max         = aip.semver.max_satisfying(versions, "^1.2"),
+	max_pre     = aip.semver.max_satisfying(versions, ">=1.3 <1.6", {include_prerelease = true}),
+	max_none    = aip.semver.max_satisfying(versions, "^3"),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(
+			res["sorted"],
+			serde_json::json!(["0.9.1", "1.2.0-rc.1", "1.2.0", "1.5.0-beta.1", "1.10.0", "2.0.0"])
+		);
+		assert_eq!(res["sorted_desc"][0], "2.0.0");
+		assert_eq!(res["max"], "1.10.0");
+		assert_eq!(res["max_pre"], "1.5.0-beta.1");
+		assert!(res.get("max_none").is_none());
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
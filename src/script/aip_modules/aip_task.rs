@@ -9,6 +9,7 @@
 //! ### Functions
 //!
 //! - `aip.task.set_label(label: string)`
+//! - `aip.task.set_progress(progress: number, msg?: string)`
 //! - `aip.task.pin(iden: string, content: string | {label?: string, content: string})`
 //! - `aip.task.pin(iden: string, priority: number, content: string | {label?: string, content: string})`
 //!
@@ -20,7 +21,7 @@ use crate::script::LuaValueExt;
 use crate::script::support::create_pin;
 use mlua::{Lua, Table, Value, Variadic};
 
-/// Registers the `task.set_label` and `task.pin` helpers in Lua.
+/// Registers the `task.set_label`, `task.set_progress`, and `task.pin` helpers in Lua.
 pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
@@ -32,6 +33,15 @@ pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 		table.set("set_label", set_label_fn)?;
 	}
 
+	// -- task.set_progress
+	{
+		let rt = runtime.clone();
+		let set_progress_fn = lua.create_function(move |lua, (progress, msg): (Value, Option<Value>)| {
+			set_task_progress(lua, &rt, progress, msg).map_err(mlua::Error::external)
+		})?;
+		table.set("set_progress", set_progress_fn)?;
+	}
+
 	// -- task.pin
 	{
 		let rt = runtime.clone();
@@ -67,14 +77,52 @@ fn set_task_label(lua: &Lua, runtime: &Runtime, label: Value) -> Result<()> {
 	Ok(())
 }
 
+/// Set the task progress (from 0.0 to 1.0) with an optional step message (e.g., `"parsing"`),
+/// shown as the task progress bar in the TUI tasks overview.
+fn set_task_progress(lua: &Lua, runtime: &Runtime, progress: Value, msg: Option<Value>) -> Result<()> {
+	let progress = progress
+		.x_as_f64()
+		.filter(|p| (0.0..=1.0).contains(p))
+		.ok_or("aip.task.set_progress(progress, msg?) – expected <number> between 0 and 1 for parameter `progress`.")?;
+	let msg = match msg {
+		None | Some(Value::Nil) => None,
+		Some(msg) => Some(
+			msg.x_as_lua_str()
+				.ok_or("aip.task.set_progress(progress, msg?) – expected <string> for parameter `msg`.")?
+				.to_string(),
+		),
+	};
+
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	let mm = runtime.mm();
+
+	let task_id = ctx
+		.get_task_id(mm)?
+		.ok_or("Cannot call 'aip.task.set_progress(...)' outside of a task context.")?;
+
+	let task_u = TaskForUpdate {
+		progress: Some(progress),
+		progress_msg: msg,
+		..Default::default()
+	};
+
+	TaskBmc::update(mm, task_id, task_u)?;
+
+	Ok(())
+}
+
 // region:    --- Tests
 
 #[cfg(test)]
 mod tests {
-	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
 
 	use crate::_test_support::run_reflective_agent_with_runtime;
+	use crate::model::base::DbBmc as _;
+	use crate::model::{Task, TaskBmc};
 	use crate::runtime::Runtime;
+	use serde_json::Value;
+	use uuid::Uuid;
 
 	#[tokio::test(flavor = "multi_thread")]
 	async fn test_lua_task_set_label_simple() -> Result<()> {
@@ -82,21 +130,53 @@ mod tests {
 		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
 		let fx_code = r#"
 aip.task.set_label("My Custom Label")
-return "OK"
+return { res = "OK", task_uid = CTX.TASK_UID }
 		"#;
 
 		// -- Exec
 		let res = run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
 
 		// -- Check
-		assert_eq!(res.as_str().unwrap_or_default(), "OK");
+		assert_eq!(res["res"].as_str(), Some("OK"));
 		// check task label was updated
-		let tasks = crate::model::TaskBmc::list_for_run(runtime.mm(), 1.into())?;
-		assert_eq!(tasks.len(), 1);
-		assert_eq!(tasks[0].label, Some("My Custom Label".to_string()));
+		let task = get_task_of_res(&runtime, &res)?;
+		assert_eq!(task.label, Some("My Custom Label".to_string()));
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_task_set_progress_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+aip.task.set_progress(0.4, "parsing")
+local ok = pcall(aip.task.set_progress, 1.5)
+return { res = ok and "SHOULD FAIL" or "OK", task_uid = CTX.TASK_UID }
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
+
+		// -- Check
+		assert_eq!(res["res"].as_str(), Some("OK"));
+		let task = get_task_of_res(&runtime, &res)?;
+		assert_eq!(task.progress, Some(0.4));
+		assert_eq!(task.progress_msg.as_deref(), Some("parsing"));
 
 		Ok(())
 	}
+
+	// region:    --- Support
+
+	/// The task of the `task_uid` returned by the agent (`CTX.TASK_UID`)
+	fn get_task_of_res(runtime: &Runtime, res: &Value) -> Result<Task> {
+		let task_uid = res["task_uid"].as_str().ok_or("Should have task_uid")?;
+		let task_id = TaskBmc::get_id_for_uid(runtime.mm(), Uuid::parse_str(task_uid)?)?;
+		Ok(TaskBmc::get(runtime.mm(), task_id)?)
+	}
+
+	// endregion: --- Support
 }
 
 // endregion: --- Tests
@@ -13,12 +13,15 @@ use super::{
 	remove_last_line,
 	remove_last_lines,
 	replace_markers_with_default_parkers,
+	// text_token.rs
+	split_by_tokens,
 	// text_split.rs
 	split_first,
 	// text_split_lines.rs
 	split_first_line,
 	split_last,
 	split_last_line,
+	token_count,
 	// text_trim.rs
 	trim,
 	trim_end,
@@ -67,6 +70,10 @@ pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
 	table.set("split_first_line", lua.create_function(split_first_line)?)?;
 	table.set("split_last_line", lua.create_function(split_last_line)?)?;
 
+	// --- Functions from text_token.rs
+	table.set("token_count", lua.create_function(token_count)?)?;
+	table.set("split_by_tokens", lua.create_function(split_by_tokens)?)?;
+
 	// --- Functions from text_trim.rs
 	table.set("trim", lua.create_function(trim)?)?;
 	table.set("trim_start", lua.create_function(trim_start)?)?;
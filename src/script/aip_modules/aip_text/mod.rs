@@ -4,6 +4,7 @@ mod text_common;
 mod text_formatter;
 mod text_split;
 mod text_split_line;
+mod text_token;
 mod text_trim;
 
 mod init;
@@ -13,6 +14,7 @@ pub use text_common::*;
 pub use text_formatter::*;
 pub use text_split::*;
 pub use text_split_line::*;
+pub use text_token::*;
 pub use text_trim::*;
 
 // endregion: --- Modules
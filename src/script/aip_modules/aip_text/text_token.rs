@@ -0,0 +1,212 @@
+// region:    --- Token
+
+use crate::script::LuaValueExt as _;
+use crate::script::support::into_option_string;
+use crate::support::text::{self, TokenEncoding, TokenSplitOptions};
+use mlua::{IntoLua as _, Lua, Value};
+
+/// ## Lua Documentation
+///
+/// Returns the number of tokens of `text` for a model (or an encoding name),
+/// to chunk the inputs to fit the model context window.
+///
+/// ```lua
+/// -- API Signature
+/// aip.text.token_count(text: string | nil, model?: string): integer | nil
+/// ```
+///
+/// The text is encoded with the tiktoken BPE of the model family encoding
+/// (`o200k_base` for GPT-4o, GPT-4.1, GPT-5, o-series; `cl100k_base` for GPT-4, GPT-3.5, and the other providers).
+/// The count is exact for the OpenAI models, and an estimate for the other providers (own tokenizers).
+///
+/// ### Arguments
+///
+/// - `text: string | nil`: The text to count.
+/// - `model?: string`: The model name (e.g., `"gpt-4o-mini"`, `"claude-sonnet-4-5"`), or an encoding name
+///   (`"cl100k_base"`, `"o200k_base"`). Defaults to `cl100k_base`.
+///
+/// ### Returns
+///
+/// The token count, or `nil` if `text` is `nil`.
+///
+/// ### Example
+///
+/// ```lua
+/// local count = aip.text.token_count("Hello, world!", "gpt-4o") -- 4
+/// if aip.text.token_count(content, "gpt-4o") > 100000 then
+///   -- split the content
+/// end
+/// ```
+pub fn token_count(_lua: &Lua, (text_val, model): (Value, Option<String>)) -> mlua::Result<Value> {
+	let Some(text_content) = into_option_string(text_val, "aip.text.token_count")? else {
+		return Ok(Value::Nil);
+	};
+
+	let encoding = model.as_deref().map(TokenEncoding::for_model).unwrap_or(TokenEncoding::Cl100k);
+	let count = text::token_count(&text_content, encoding);
+
+	Ok(Value::Integer(count as i64))
+}
+
+/// ## Lua Documentation
+///
+/// Splits `content` into chunks of at most `max_tokens` (counted as `aip.text.token_count`),
+/// on the paragraph, heading, and code block boundaries when possible, for map-reduce style agents
+/// (e.g., summarize each chunk, then summarize the summaries).
+///
+/// ```lua
+/// -- API Signature
+/// aip.text.split_by_tokens(content: string | nil, max_tokens: integer, options?: {model?: string, overlap?: integer}): string[] | nil
+/// ```
+///
+/// The content is split into blocks (paragraphs, headings with their following paragraph, fenced code blocks),
+/// grouped into chunks up to `max_tokens`. A block larger than `max_tokens` is split by lines (then words).
+///
+/// ### Arguments
+///
+/// - `content: string | nil`: The content to split.
+/// - `max_tokens: integer`: The maximum tokens of a chunk (must be greater than 0).
+/// - `options?: table`:
+///   - `model?: string`: The model or encoding name for the token count (see `aip.text.token_count`), default `cl100k_base`.
+///   - `overlap?: integer`: The tokens of the end of a chunk repeated at the start of the next one (whole lines), default `0`.
+///     Must be less than `max_tokens`.
+///
+/// ### Returns
+///
+/// The list of chunks (empty for an empty content), or `nil` if `content` is `nil`.
+/// Without overlap, the concatenation of the chunks is the content.
+///
+/// ### Example
+///
+/// ```lua
+/// local chunks = aip.text.split_by_tokens(file.content, 4000, {model = "gpt-4o-mini", overlap = 200})
+/// for i, chunk in ipairs(chunks) do
+///   -- summarize each chunk
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if `max_tokens` is not greater than 0, or if `overlap` is not less than `max_tokens`.
+pub fn split_by_tokens(
+	lua: &Lua,
+	(content_val, max_tokens, options): (Value, i64, Option<Value>),
+) -> mlua::Result<Value> {
+	let Some(content) = into_option_string(content_val, "aip.text.split_by_tokens")? else {
+		return Ok(Value::Nil);
+	};
+
+	if max_tokens <= 0 {
+		return Err(crate::Error::custom(format!(
+			"aip.text.split_by_tokens - max_tokens must be greater than 0, but was {max_tokens}"
+		))
+		.into());
+	}
+	let options = options.unwrap_or(Value::Nil);
+	let encoding = options
+		.x_get_string("model")
+		.map(|model| TokenEncoding::for_model(&model))
+		.unwrap_or(TokenEncoding::Cl100k);
+	let overlap = options.x_get_i64("overlap").unwrap_or(0).max(0);
+	if overlap >= max_tokens {
+		return Err(crate::Error::custom(format!(
+			"aip.text.split_by_tokens - overlap ({overlap}) must be less than max_tokens ({max_tokens})"
+		))
+		.into());
+	}
+
+	let chunks = text::split_by_tokens(
+		&content,
+		&TokenSplitOptions {
+			max_tokens: max_tokens as usize,
+			overlap_tokens: overlap as usize,
+			encoding,
+		},
+	);
+
+	chunks.into_lua(lua)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_text;
+
+	#[tokio::test]
+	async fn test_lua_text_token_count_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_text::init_module, "text").await?;
+		let script = r#"
+return {
+	default  = aip.text.token_count("Hello, world!"),
+	model    = aip.text.token_count("Hello, world!", "gpt-4o"),
+	cjk_4    = aip.text.token_count("日本語のテキストです", "cl100k_base"),
+	cjk_4o   = aip.text.token_count("日本語のテキストです", "o200k_base"),
+	none     = aip.text.token_count(nil),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["default"].as_i64(), Some(4));
+		assert_eq!(res["model"].as_i64(), Some(4));
+		let cjk_4 = res["cjk_4"].as_i64().ok_or("cjk_4 should be int")?;
+		let cjk_4o = res["cjk_4o"].as_i64().ok_or("cjk_4o should be int")?;
+		assert!(cjk_4o < cjk_4);
+		assert!(res.get("none").is_none());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_text_split_by_tokens_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_text::init_module, "text").await?;
+		let script = r##"
+local paragraph = string.rep("The quick brown fox jumps over the lazy dog.\n", 3)
+local content = "# Title\n\n" .. paragraph .. "\n" .. paragraph .. "\n" .. paragraph
+local chunks = aip.text.split_by_tokens(content, 50, {model = "gpt-4o"})
+return {
+	count    = #chunks,
+	joined   = table.concat(chunks) == content,
+	first    = chunks[1],
+	overlap  = aip.text.split_by_tokens(content, 50, {overlap = 12})[2],
+	none     = aip.text.split_by_tokens(nil, 50),
+}
+		"##;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["count"].as_i64(), Some(3));
+		assert_eq!(res["joined"].as_bool(), Some(true));
+		assert!(
+			res["first"]
+				.as_str()
+				.ok_or("first should be string")?
+				.starts_with("# Title\n\nThe quick")
+		);
+		assert!(
+			res["overlap"]
+				.as_str()
+				.ok_or("overlap should be string")?
+				.starts_with("The quick brown fox jumps over the lazy dog.\n\nThe quick")
+		);
+		assert!(res.get("none").is_none());
+		assert!(eval_lua(&lua, r#"return aip.text.split_by_tokens("abc", 0)"#).is_err());
+		assert!(eval_lua(&lua, r#"return aip.text.split_by_tokens("abc", 10, {overlap = 10})"#).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
+
+// endregion: --- Token
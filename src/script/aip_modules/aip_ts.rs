@@ -0,0 +1,245 @@
+//! Defines the `aip.ts` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.ts` module parses code into syntax trees, with the tree-sitter node kinds and capture queries,
+//! so that the code analysis agents can work on real syntax trees rather than regexes.
+//!
+//! The languages are `rust`, `javascript`, `typescript` (and `tsx`), `python`, and `go`.
+//!
+//! ### Functions
+//!
+//! - `aip.ts.parse(code: string, options: {lang: string}): CodeNode`
+//! - `aip.ts.query(code: string, query: string, options: {lang: string}): {name: string, node: CodeNode}[]`
+//! - `aip.ts.extract(code: string, node_kind: string, options: {lang: string}): CodeNode[]`
+
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::script::serde_value_to_lua_value;
+use crate::support::code::{CodeLang, CodeNode, CodeTree, parse_code};
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, _runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let parse_fn = lua.create_function(ts_parse)?;
+	let query_fn = lua.create_function(ts_query)?;
+	let extract_fn = lua.create_function(ts_extract)?;
+
+	table.set("parse", parse_fn)?;
+	table.set("query", query_fn)?;
+	table.set("extract", extract_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Parses the code into a syntax tree.
+///
+/// ```lua
+/// -- API Signature
+/// aip.ts.parse(code: string, options: {lang: string}): CodeNode
+/// ```
+///
+/// ### Arguments
+///
+/// - `code: string`: The source code.
+/// - `options: table`:
+///   - `lang: string`: The language name or file extension (`rust`/`rs`, `javascript`/`js`, `typescript`/`ts`, `tsx`, `python`/`py`, `go`).
+///
+/// ### Returns
+///
+/// - `CodeNode`: The root node (`source_file` for Rust and Go, `program` for JavaScript and TypeScript, `module` for Python),
+///   with the named nodes only (e.g., no punctuation).
+///
+/// ```ts
+/// {
+///   kind: string,          // the tree-sitter node kind (e.g., "function_item", "struct_item", "impl_item", "mod_item")
+///   name?: string,         // the "name" field text (for "impl_item", the "type" field, e.g., "Foo")
+///   start_line: number,    // 1-based
+///   end_line: number,      // 1-based, inclusive
+///   text: string,          // the source of the node
+///   children?: CodeNode[]  // the named child nodes (e.g., the "declaration_list" of an "impl_item")
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local root = aip.ts.parse(aip.file.load("src/main.rs").content, { lang = "rust" })
+/// for _, node in ipairs(root.children) do
+///   print(node.kind .. " " .. (node.name or ""))
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the language is not supported, or the code has a syntax error.
+fn ts_parse(lua: &Lua, (code, options): (String, Option<Value>)) -> mlua::Result<Value> {
+	let tree = parse_with_options(&code, options, "aip.ts.parse")?;
+	code_node_to_lua(lua, &tree.root())
+}
+
+/// ## Lua Documentation
+///
+/// Runs a tree-sitter capture query on the code syntax tree.
+///
+/// ```lua
+/// -- API Signature
+/// aip.ts.query(code: string, query: string, options: {lang: string}): {name: string, node: CodeNode}[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `code: string`: The source code.
+/// - `query: string`: The tree-sitter query, e.g., `"(function_item) @fn (struct_item) @type"`,
+///   or `"(function_item name: (identifier) @name)"`.
+/// - `options: table`:
+///   - `lang: string`: The language (see `aip.ts.parse`).
+///
+/// ### Returns
+///
+/// - `{name: string, node: CodeNode}[]`: The captures in the tree order, with the capture name (without the `@`).
+///
+/// ### Example
+///
+/// ```lua
+/// local captures = aip.ts.query(code, "(function_item) @fn", { lang = "rust" })
+/// for _, cap in ipairs(captures) do
+///   print(cap.name .. " " .. cap.node.name .. " at line " .. cap.node.start_line)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the query is invalid, or for the `aip.ts.parse` errors.
+fn ts_query(lua: &Lua, (code, query, options): (String, String, Option<Value>)) -> mlua::Result<Value> {
+	let tree = parse_with_options(&code, options, "aip.ts.query")?;
+	let captures = tree
+		.query(&query)
+		.map_err(|err| Error::custom(format!("aip.ts.query - {err}")))?;
+
+	let table = lua.create_table()?;
+	for capture in captures {
+		let capture_table = lua.create_table()?;
+		capture_table.set("name", capture.name)?;
+		capture_table.set("node", code_node_to_lua(lua, &capture.node)?)?;
+		table.push(capture_table)?;
+	}
+
+	Ok(Value::Table(table))
+}
+
+/// ## Lua Documentation
+///
+/// Extracts the nodes of a kind from the code syntax tree (at any depth).
+///
+/// ```lua
+/// -- API Signature
+/// aip.ts.extract(code: string, node_kind: string, options: {lang: string}): CodeNode[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `code: string`: The source code.
+/// - `node_kind: string`: The tree-sitter node kind (e.g., `"function_item"`).
+/// - `options: table`:
+///   - `lang: string`: The language (see `aip.ts.parse`).
+///
+/// ### Returns
+///
+/// - `CodeNode[]`: The nodes of this kind, in the tree order (empty if none).
+///
+/// ### Example
+///
+/// ```lua
+/// local structs = aip.ts.extract(code, "struct_item", { lang = "rs" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error for the `aip.ts.parse` errors.
+fn ts_extract(lua: &Lua, (code, node_kind, options): (String, String, Option<Value>)) -> mlua::Result<Value> {
+	let tree = parse_with_options(&code, options, "aip.ts.extract")?;
+
+	let table = lua.create_table()?;
+	for node in tree.find_kind(node_kind.trim()) {
+		table.push(code_node_to_lua(lua, &node)?)?;
+	}
+
+	Ok(Value::Table(table))
+}
+
+// region:    --- Support
+
+fn parse_with_options(code: &str, options: Option<Value>, fn_name: &str) -> Result<CodeTree> {
+	let lang = options.x_get_string("lang").ok_or_else(|| {
+		Error::custom(format!(
+			"{fn_name} - 'options.lang' is required (e.g., {{lang = \"rust\"}})"
+		))
+	})?;
+	let lang = CodeLang::from_name(&lang).map_err(|err| Error::custom(format!("{fn_name} - {err}")))?;
+
+	parse_code(lang, code).map_err(|err| Error::custom(format!("{fn_name} - {err}")))
+}
+
+fn code_node_to_lua(lua: &Lua, node: &CodeNode) -> mlua::Result<Value> {
+	let value = serde_json::to_value(node).map_err(|err| Error::custom(format!("Cannot convert code node. {err}")))?;
+	Ok(serde_value_to_lua_value(lua, value)?)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_ts;
+
+	#[tokio::test]
+	async fn test_lua_ts_parse_query_extract() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_ts::init_module, "ts").await?;
+		let script = r#"
+local code = "struct Foo;\n\nimpl Foo {\n    fn new() -> Self { Foo }\n}\n"
+local root = aip.ts.parse(code, { lang = "rust" })
+local captures = aip.ts.query(code, "(function_item) @fn", { lang = "rs" })
+local structs = aip.ts.extract(code, "struct_item", { lang = "rust" })
+local py_fns = aip.ts.extract("def a():\n    return 1\n", "function_definition", { lang = "py" })
+local ok, err = pcall(function() return aip.ts.parse("fn a( {", { lang = "rust" }) end)
+return {
+  root_kind = root.kind,
+  impl_name = root.children[2].name,
+  capture = captures[1].name .. ":" .. captures[1].node.name,
+  struct_line = structs[1].start_line,
+  py_fn_name = py_fns[1].name,
+  rust_err = tostring(err)
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res["root_kind"].as_str(), Some("source_file"));
+		assert_eq!(res["impl_name"].as_str(), Some("Foo"));
+		assert_eq!(res["capture"].as_str(), Some("fn:new"));
+		assert_eq!(res["struct_line"].as_i64(), Some(1));
+		assert_eq!(res["py_fn_name"].as_str(), Some("a"));
+		assert_contains(
+			res["rust_err"].as_str().ok_or("Should have err")?,
+			"syntax error at line 1",
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -15,7 +15,9 @@
 
 use crate::runtime::Runtime;
 use crate::script::LuaValueExt;
-use crate::script::aip_modules::support::{base_dir_and_globs, list_files_with_options};
+use crate::script::aip_modules::support::{
+	base_dir_and_globs, check_access_list, list_base_path, list_files_with_options,
+};
 use crate::support::{AsStrsExt, W};
 use crate::types::Extrude;
 use crate::{Error, Result};
@@ -151,6 +153,12 @@ fn load_files_context(
 	let absolute = options.x_get_bool("absolute").unwrap_or(false);
 
 	let file_refs = list_files_with_options(runtime, base_path.as_ref(), &include_globs.x_as_strs(), absolute, true)?;
+	let file_refs = check_access_list(
+		lua,
+		list_base_path(runtime, base_path.as_ref()),
+		file_refs,
+		"aip.udiffx.load_files_context",
+	)?;
 
 	if file_refs.is_empty() {
 		return Ok(Value::Nil);
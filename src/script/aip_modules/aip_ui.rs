@@ -0,0 +1,253 @@
+//! Defines the `aip_ui` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.ui` module allows agents to annotate the run/task timeline displayed in the TUI.
+//!
+//! ### Functions
+//!
+//! - `aip.ui.marker(marker: string | MarkerOptions)`
+//! - `aip.ui.marker(task: string | number | boolean | nil, marker: string | MarkerOptions)`
+
+use crate::model::base::DbBmc as _;
+use crate::model::{Id, LogBmc, LogForCreate, LogKind, RuntimeCtx, TaskBmc};
+use crate::runtime::Runtime;
+use crate::script::LuaValueExt as _;
+use crate::types::uc;
+use crate::{Error, Result};
+use mlua::{FromLua as _, Lua, Table, Value, Variadic};
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let marker_fn = lua
+		.create_function(move |lua, args: Variadic<Value>| ui_marker(lua, &rt, args).map_err(mlua::Error::external))?;
+	table.set("marker", marker_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Adds a visual marker on the task or run timeline (e.g., "applied 3 edits", "needs human review").
+///
+/// ```lua
+/// -- API Signatures
+/// aip.ui.marker(marker: string | MarkerOptions)
+/// aip.ui.marker(task: string | number | boolean | nil, marker: string | MarkerOptions)
+/// ```
+///
+/// ### Arguments
+///
+/// - `task?: string | number | boolean | nil` - The timeline target.
+///   - `nil` (or omitted): the current task when in a task stage, otherwise the run.
+///   - `string`: a task uid (e.g., `CTX.TASK_UID`).
+///   - `number`: a task number of the current run (e.g., `CTX.TASK_NUM`).
+///   - `false`: the run timeline, even when called from a task stage.
+/// - `marker: string | MarkerOptions` - The marker label, or a table:
+///   ```ts
+///   type MarkerOptions = {
+///     label: string,                                   // The text displayed on the timeline
+///     level?: "info" | "success" | "warn" | "error",   // (default "info")
+///     link?: string                                    // Optional file path or URL
+///   }
+///   ```
+///   When `link` is an image file, the TUI shows an inline preview of it.
+///
+/// ### Example
+///
+/// ```lua
+/// aip.ui.marker("applied 3 edits")
+/// aip.ui.marker({ label = "needs human review", level = "warn", link = "src/main.rs" })
+/// -- From `# After All`, mark the first task
+/// aip.ui.marker(1, { label = "largest diff", level = "success" })
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if there is no run context, if the task cannot be found in the current run, or if the marker is invalid.
+fn ui_marker(lua: &Lua, runtime: &Runtime, args: Variadic<Value>) -> Result<()> {
+	let (task_arg, marker_arg) = match args.len() {
+		1 => (None, args.into_iter().next()),
+		2 => {
+			let mut args = args.into_iter();
+			(args.next(), args.next())
+		}
+		_ => {
+			return Err(Error::custom(
+				"aip.ui.marker(...) - expected 1 or 2 parameters: (marker) or (task, marker).",
+			));
+		}
+	};
+
+	let marker_arg = marker_arg.ok_or("aip.ui.marker(...) - expected a marker argument.")?;
+	let marker = uc::TimelineMarker::from_lua(marker_arg, lua)?;
+
+	let ctx = RuntimeCtx::extract_from_global(lua)?;
+	let mm = runtime.mm();
+	let run_id = ctx
+		.get_run_id(mm)?
+		.ok_or("Cannot call 'aip.ui.marker(...)' outside of a run context.")?;
+
+	let task_id: Option<Id> = match task_arg {
+		None | Some(Value::Nil) => ctx.get_task_id(mm)?,
+		Some(Value::Boolean(false)) => None,
+		Some(Value::Boolean(true)) => Some(
+			ctx.get_task_id(mm)?
+				.ok_or("aip.ui.marker(true, ...) - Cannot target the current task outside of a task context.")?,
+		),
+		Some(Value::String(task_uid)) => {
+			let task_uid = task_uid.to_str()?;
+			let task_uid = uuid::Uuid::parse_str(&task_uid)
+				.map_err(|err| Error::custom(format!("aip.ui.marker - task uid '{task_uid}' is not valid. {err}")))?;
+			let task = TaskBmc::get(mm, TaskBmc::get_id_for_uid(mm, task_uid)?)?;
+			if task.run_id != run_id {
+				return Err(Error::custom(format!(
+					"aip.ui.marker - task uid '{task_uid}' is not a task of the current run."
+				)));
+			}
+			Some(task.id)
+		}
+		Some(other) => {
+			let task_num = other
+				.x_as_i64()
+				.ok_or("aip.ui.marker(task, marker) - 'task' must be a task uid, a task number, a boolean, or nil.")?;
+			let task = TaskBmc::list_for_run(mm, run_id)?
+				.into_iter()
+				.find(|t| t.idx.map(|idx| idx + 1) == Some(task_num))
+				.ok_or_else(|| Error::custom(format!("aip.ui.marker - no task number '{task_num}' in current run.")))?;
+			Some(task.id)
+		}
+	};
+
+	let message = serde_json::to_string(&marker).map_err(|err| Error::cc("Cannot serialize marker", err))?;
+
+	LogBmc::create(
+		mm,
+		LogForCreate {
+			run_id,
+			task_id,
+			kind: Some(LogKind::AgentMarker),
+			step: None,
+			stage: ctx.stage(),
+			message: Some(message),
+		},
+	)?;
+
+	Ok(())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use crate::_test_support::{assert_contains, create_run, create_task, run_reflective_agent_with_runtime};
+	use crate::model::{LogBmc, LogKind, TaskBmc};
+	use crate::runtime::Runtime;
+	use crate::types::uc;
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ui_marker_task_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+aip.ui.marker({ label = "needs human review", level = "warn", link = "src/main.rs" })
+return "OK"
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
+
+		// -- Check
+		assert_eq!(res.as_str().unwrap_or_default(), "OK");
+		let logs = LogBmc::list_for_task(runtime.mm(), 0.into())?;
+		let log = logs
+			.iter()
+			.find(|l| l.kind == Some(LogKind::AgentMarker))
+			.ok_or("Should have a marker log")?;
+		let marker: uc::TimelineMarker = serde_json::from_str(log.message.as_deref().ok_or("Should have message")?)?;
+		assert_eq!(marker.label, "needs human review");
+		assert_eq!(marker.level, uc::MarkerLevel::Warn);
+		assert_eq!(marker.link.as_deref(), Some("src/main.rs"));
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ui_marker_run_level() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+aip.ui.marker(false, "applied 3 edits")
+return "OK"
+		"#;
+
+		// -- Exec
+		run_reflective_agent_with_runtime(fx_code, None, runtime.clone()).await?;
+
+		// -- Check
+		let logs = LogBmc::list_for_run_only(runtime.mm(), 0.into())?;
+		let log = logs
+			.iter()
+			.find(|l| l.kind == Some(LogKind::AgentMarker))
+			.ok_or("Should have a run marker log")?;
+		assert_contains(log.message.as_deref().unwrap_or_default(), "applied 3 edits");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ui_marker_task_uid_other_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let other_run_id = create_run(runtime.mm(), "other-run")?;
+		let other_task_id = create_task(runtime.mm(), other_run_id, 0)?;
+		let other_task_uid = TaskBmc::get(runtime.mm(), other_task_id)?.uid;
+		let fx_code = format!(
+			r#"
+aip.ui.marker("{other_task_uid}", "not my task")
+return "OK"
+		"#
+		);
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(&fx_code, None, runtime.clone()).await;
+
+		// -- Check
+		let err = res.err().ok_or("Should have failed")?;
+		assert_contains(&err.to_string(), "is not a task of the current run");
+		let logs = LogBmc::list_for_task(runtime.mm(), other_task_id)?;
+		assert!(
+			!logs.iter().any(|l| l.kind == Some(LogKind::AgentMarker)),
+			"Should not have a marker on the other run task"
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn test_lua_ui_marker_invalid_level() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let fx_code = r#"
+aip.ui.marker({ label = "oops", level = "critical" })
+return "OK"
+		"#;
+
+		// -- Exec
+		let res = run_reflective_agent_with_runtime(fx_code, None, runtime).await;
+
+		// -- Check
+		let err = res.err().ok_or("Should have failed")?;
+		assert_contains(&err.to_string(), "marker level 'critical' not supported");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,238 @@
+//! Defines the `aip.vec` module, used in the lua engine.
+//!
+//! ---
+//!
+//! ## Lua documentation
+//!
+//! The `aip.vec` module exposes a local vector index, persisted in the workspace (`.aipack/.session/_vec.db`),
+//! to do embedding-based retrieval fully inside aipack (e.g., with the `aip.embed.generate` vectors).
+//!
+//! ### Functions
+//!
+//! - `aip.vec.upsert(index: string, id: string, vector: number[], meta?: table)`
+//! - `aip.vec.search(index: string, vector: number[], k?: integer): VecHit[]`
+//! - `aip.vec.delete(index: string, id: string): boolean`
+
+use crate::runtime::Runtime;
+use crate::script::{lua_value_to_serde_value, serde_value_to_lua_value};
+use crate::support::vec_store::VecStore;
+use crate::{Error, Result};
+use mlua::{Lua, Table, Value};
+
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
+	let table = lua.create_table()?;
+
+	let rt = runtime.clone();
+	let upsert_fn = lua.create_function(move |lua, args| vec_upsert(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let search_fn = lua.create_function(move |lua, args| vec_search(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let delete_fn = lua.create_function(move |lua, args| vec_delete(lua, &rt, args))?;
+
+	table.set("upsert", upsert_fn)?;
+	table.set("search", search_fn)?;
+	table.set("delete", delete_fn)?;
+
+	Ok(table)
+}
+
+/// ## Lua Documentation
+///
+/// Inserts (or replaces) the vector `id` of `index`, with an optional meta table (e.g., the source file and chunk).
+///
+/// ```lua
+/// -- API Signature
+/// aip.vec.upsert(index: string, id: string, vector: number[], meta?: table)
+/// ```
+///
+/// ### Arguments
+///
+/// - `index: string`: The index name (e.g., `"docs"`). The indexes are created on the first upsert.
+/// - `id: string`: The vector id in the index (e.g., `"src/main.rs#2"`).
+/// - `vector: number[]`: The vector (all the vectors of an index must have the same dimensions).
+/// - `meta?: table`: Data returned with the search hits (stored as JSON).
+///
+/// ### Example
+///
+/// ```lua
+/// local res = aip.embed.generate(chunks)
+/// for i, vector in ipairs(res.vectors) do
+///   aip.vec.upsert("docs", file.path .. "#" .. i, vector, {path = file.path, content = chunks[i]})
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if there is no workspace, if the vector is empty or not a list of numbers,
+/// or if its dimensions differ from the index vectors.
+fn vec_upsert(
+	_lua: &Lua,
+	runtime: &Runtime,
+	(index, id, vector, meta): (String, String, Value, Option<Value>),
+) -> mlua::Result<()> {
+	let vector = vector_from_value(vector, "aip.vec.upsert")?;
+	let meta = meta.map(lua_value_to_serde_value).transpose()?.filter(|meta| !meta.is_null());
+
+	let store = open_store(runtime, "aip.vec.upsert")?;
+	store.upsert(&index, &id, &vector, meta.as_ref())?;
+
+	Ok(())
+}
+
+/// ## Lua Documentation
+///
+/// Returns the `k` vectors of `index` closest to `vector` (cosine similarity, highest first).
+///
+/// ```lua
+/// -- API Signature
+/// aip.vec.search(index: string, vector: number[], k?: integer): VecHit[]
+/// ```
+///
+/// ### Arguments
+///
+/// - `index: string`: The index name.
+/// - `vector: number[]`: The query vector (e.g., the `aip.embed.generate` vector of the question).
+/// - `k?: integer`: The max number of hits (default `5`).
+///
+/// ### Returns
+///
+/// ```ts
+/// {
+///   id: string,
+///   score: number, // cosine similarity, -1.0 to 1.0 (higher is closer)
+///   meta?: table,  // The upsert meta
+/// }[]
+/// ```
+///
+/// An empty list if the index does not exist.
+///
+/// ### Example
+///
+/// ```lua
+/// local query = aip.embed.generate(question).vectors[1]
+/// for _, hit in ipairs(aip.vec.search("docs", query, 3)) do
+///   print(hit.score, hit.meta.path)
+/// end
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if there is no workspace, or if the vector dimensions differ from the index vectors.
+fn vec_search(lua: &Lua, runtime: &Runtime, (index, vector, k): (String, Value, Option<i64>)) -> mlua::Result<Value> {
+	let vector = vector_from_value(vector, "aip.vec.search")?;
+	let k = k.unwrap_or(5).max(0) as usize;
+
+	let store = open_store(runtime, "aip.vec.search")?;
+	let hits = store.search(&index, &vector, k)?;
+
+	let res = lua.create_table()?;
+	for hit in hits {
+		let hit_table = lua.create_table()?;
+		hit_table.set("id", hit.id)?;
+		hit_table.set("score", hit.score)?;
+		if let Some(meta) = hit.meta {
+			hit_table.set("meta", serde_value_to_lua_value(lua, meta)?)?;
+		}
+		res.push(hit_table)?;
+	}
+
+	Ok(Value::Table(res))
+}
+
+/// ## Lua Documentation
+///
+/// Deletes the vector `id` of `index`.
+///
+/// ```lua
+/// -- API Signature
+/// aip.vec.delete(index: string, id: string): boolean
+/// ```
+///
+/// ### Returns
+///
+/// `true` if the vector existed, `false` otherwise.
+///
+/// ### Error
+///
+/// Returns an error if there is no workspace.
+fn vec_delete(_lua: &Lua, runtime: &Runtime, (index, id): (String, String)) -> mlua::Result<bool> {
+	let store = open_store(runtime, "aip.vec.delete")?;
+	let deleted = store.delete(&index, &id)?;
+	Ok(deleted)
+}
+
+// region:    --- Support
+
+fn open_store(runtime: &Runtime, fn_name: &str) -> Result<VecStore> {
+	let path = runtime.dir_context().aipack_paths().vec_db_path().ok_or_else(|| {
+		Error::custom(format!(
+			"{fn_name} requires a aipack workspace (the vector store is in '.aipack/.session/_vec.db')"
+		))
+	})?;
+	VecStore::open(&path)
+}
+
+fn vector_from_value(value: Value, fn_name: &str) -> Result<Vec<f32>> {
+	let err = || Error::custom(format!("{fn_name} - 'vector' must be a list of numbers"));
+	let Value::Table(table) = value else {
+		return Err(err());
+	};
+	let mut vector = Vec::new();
+	for item in table.sequence_values::<Value>() {
+		match item? {
+			Value::Number(num) => vector.push(num as f32),
+			Value::Integer(num) => vector.push(num as f32),
+			_ => return Err(err()),
+		}
+	}
+	Ok(vector)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{eval_lua, setup_lua};
+	use crate::script::aip_modules::aip_vec;
+
+	#[tokio::test]
+	async fn test_lua_vec_upsert_search_delete() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_vec::init_module, "vec").await?;
+		let script = r#"
+local index = "test_lua_vec_upsert_search_delete"
+aip.vec.delete(index, "a")
+aip.vec.delete(index, "b")
+aip.vec.upsert(index, "a", {1, 0, 0}, {path = "a.md"})
+aip.vec.upsert(index, "b", {0, 1, 0})
+local hits = aip.vec.search(index, {0.9, 0.1, 0}, 1)
+return {
+	hits      = hits,
+	deleted   = aip.vec.delete(index, "b"),
+	deleted_2 = aip.vec.delete(index, "b"),
+	none      = aip.vec.search("test_lua_vec_no_index", {1, 0}),
+}
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let hits = res["hits"].as_array().ok_or("hits should be array")?;
+		assert_eq!(hits.len(), 1);
+		assert_eq!(hits[0]["id"].as_str(), Some("a"));
+		assert_eq!(hits[0]["meta"]["path"].as_str(), Some("a.md"));
+		assert_eq!(res["deleted"].as_bool(), Some(true));
+		assert_eq!(res["deleted_2"].as_bool(), Some(false));
+		assert!(res["none"].as_array().is_none_or(|hits| hits.is_empty()));
+		assert!(eval_lua(&lua, r#"return aip.vec.search("x", {"a"})"#).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -10,6 +10,11 @@
 //!
 //! - `aip.web.get(url: string, options?: WebOptions): WebResponse`
 //! - `aip.web.post(url: string, data: string | table, options?: WebOptions): WebResponse`
+//! - `aip.web.put(url: string, data: string | table, options?: WebOptions): WebResponse`
+//! - `aip.web.patch(url: string, data: string | table, options?: WebOptions): WebResponse`
+//! - `aip.web.delete(url: string, options?: WebOptions): WebResponse`
+//! - `aip.web.head(url: string, options?: WebOptions): WebResponse`
+//! - `aip.web.fetch_as_markdown(url: string, options?: WebOptions): WebMarkdown`
 //! - `aip.web.parse_url(url: string | nil): table | nil`
 //! - `aip.web.resolve_href(href: string | nil, base_url: string): string | nil`
 //!
@@ -26,6 +31,9 @@
 //!   user_agent?: string | boolean,
 //!   headers?: table,                  -- { header_name: string | string[] }
 //!   redirect_limit?: number,          -- number of redirects to follow (default 5)
+//!   follow_redirects?: boolean,       -- if false, 3xx responses are returned as is (default true)
+//!   timeout_ms?: number,              -- total request timeout in milliseconds (default none)
+//!   body_type?: "text" | "json" | "form" | "multipart", -- data encoding for post/put/patch (default: string as text, table as json)
 //!   parse?: boolean                   -- If true, attempts to parse JSON response content (Content-Type: application/json). Content defaults to string otherwise.
 //! }
 //!
@@ -36,27 +44,47 @@
 //!  - If undefined, will default to `aipack` or what is in the `.headers``
 //! ```
 
+use crate::dir_context::PathResolver;
 use crate::hub::get_hub;
 use crate::runtime::Runtime;
+use crate::script::aip_modules::support::{check_access_read, check_pack_capability};
 use crate::script::support::into_option_string;
 use crate::support::W;
-use crate::types::{DEFAULT_UA_AIPACK, DEFAULT_UA_BROWSER, WebOptions, WebResponse};
+use crate::support::html;
+use crate::types::{DEFAULT_UA_AIPACK, DEFAULT_UA_BROWSER, PackCapability, WebBodyType, WebOptions, WebResponse};
 use crate::{Error, Result};
 use mlua::{FromLua as _, IntoLua, Lua, LuaSerdeExt, Table, Value};
-use reqwest::{Client, header};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, Method, RequestBuilder, header};
 use std::collections::HashMap;
 use url::Url;
 
-pub fn init_module(lua: &Lua, _runtime_context: &Runtime) -> Result<Table> {
+pub fn init_module(lua: &Lua, runtime: &Runtime) -> Result<Table> {
 	let table = lua.create_table()?;
 
-	let web_get_fn = lua.create_function(web_get)?;
-	let web_post_fn = lua.create_function(web_post)?;
+	let rt = runtime.clone();
+	let web_get_fn = lua.create_function(move |lua, args| web_get(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let web_post_fn = lua.create_function(move |lua, args| web_post(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let web_put_fn = lua.create_function(move |lua, args| web_put(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let web_patch_fn = lua.create_function(move |lua, args| web_patch(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let web_delete_fn = lua.create_function(move |lua, args| web_delete(lua, &rt, args))?;
+	let rt = runtime.clone();
+	let web_head_fn = lua.create_function(move |lua, args| web_head(lua, &rt, args))?;
+	let fetch_as_markdown_fn = lua.create_function(web_fetch_as_markdown)?;
 	let parse_url_fn = lua.create_function(web_parse_url)?;
 	let resolve_href_fn = lua.create_function(web_resolve_href)?;
 
 	table.set("get", web_get_fn)?;
 	table.set("post", web_post_fn)?;
+	table.set("put", web_put_fn)?;
+	table.set("patch", web_patch_fn)?;
+	table.set("delete", web_delete_fn)?;
+	table.set("head", web_head_fn)?;
+	table.set("fetch_as_markdown", fetch_as_markdown_fn)?;
 	table.set("parse_url", parse_url_fn)?;
 	table.set("resolve_href", resolve_href_fn)?;
 
@@ -261,7 +289,7 @@ impl IntoLua for W<Url> {
 /// ### Arguments
 ///
 /// - `url: string`: The URL to make the GET request to.
-/// - `options?: WebOptions`: Optional web request options (user_agent, headers, redirect_limit)
+/// - `options?: WebOptions`: Optional web request options (user_agent, headers, redirect_limit, follow_redirects, timeout_ms, parse)
 ///
 /// ### Returns (WebResponse)
 ///
@@ -272,8 +300,9 @@ impl IntoLua for W<Url> {
 ///   success: boolean, // Indicates if the request was successful (status code 2xx)
 ///   status: number,   // The HTTP status code of the response
 ///   url: string,      // The URL that was requested
-///   content: string | table, // The body of the response. Defaults to string, but can be a table (parsed JSON) if `WebOptions.parse` is true and `Content-Type` is `application/json`.
+///   content: string | table, // The body of the response. Defaults to string, but can be a table (parsed JSON) if `WebOptions.parse` is true and `Content-Type` is JSON.
 ///   content_type?: string, // The value of the Content-Type header, if present
+///   headers?: table,  // The response headers { header_name: string | string[] } (names are lowercase)
 ///   error?: string,   // Contains network error, parsing error, or generic status error if not 2xx
 /// }
 /// ```
@@ -289,49 +318,16 @@ impl IntoLua for W<Url> {
 /// local response = aip.web.get("https://api.example.com", {
 ///   user_agent = "true",
 ///   headers = { ["Authorization"] = "Bearer token123" },
-///   redirect_limit = 10
+///   redirect_limit = 10,
+///   timeout_ms = 5000
 /// })
 /// ```
 ///
 /// ### Error
 ///
-/// Returns an error if the web request cannot be made (e.g., invalid URL, network error).  Does not throw an error for non-2xx status codes. Check the `success` field in the `WebResponse`.
-fn web_get(lua: &Lua, (url, opts): (String, Option<Value>)) -> mlua::Result<Value> {
-	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
-	let res: mlua::Result<Value> = tokio::task::block_in_place(|| {
-		rt.block_on(async {
-			let mut builder = Client::builder();
-
-			let opts_val = opts.unwrap_or(Value::Nil);
-			let web_opts = WebOptions::from_lua(opts_val, lua)?;
-			let parse_response = web_opts.parse;
-			builder = web_opts.apply_to_reqwest_builder(builder);
-
-			let client = builder.build().map_err(crate::Error::from)?;
-
-			let res: mlua::Result<Value> = match client.get(&url).send().await {
-				Ok(response) => {
-					let web_res = WebResponse::from_reqwest_response(response, parse_response).await?;
-					Ok(web_res.into_lua(lua)?)
-				}
-				Err(err) => Err(crate::Error::custom(format!(
-					"\
-Fail to do aip.web.get for url: {url}
-Cause: {err}"
-				))
-				.into()),
-			};
-
-			if res.is_ok() {
-				get_hub().publish_sync(format!("-> lua web::get OK ({url}) "));
-			}
-
-			// return the Result<Dynamic, Error>
-			res
-		})
-	});
-
-	res
+/// Returns an error if the web request cannot be made (e.g., invalid URL, network error, timeout).  Does not throw an error for non-2xx status codes. Check the `success` field in the `WebResponse`.
+fn web_get(lua: &Lua, runtime: &Runtime, (url, opts): (String, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::GET, url, None, opts)
 }
 
 /// ## Lua Documentation
@@ -346,8 +342,12 @@ Cause: {err}"
 /// ### Arguments
 ///
 /// - `url: string`: The URL to make the POST request to.
-/// - `data: string | table`: The data to send in the request body.  If a string is provided, the `Content-Type` header will be set to `plain/text`. If a table is provided, the `Content-Type` header will be set to `application/json` and the table will be serialized as JSON.
-/// - `options?: WebOptions`: Optional web request options (user_agent, headers, redirect_limit)
+/// - `data: string | table`: The data to send in the request body, encoded per `options.body_type`.
+///   - By default, a string is sent with `Content-Type: plain/text`, and a table is serialized as JSON with `Content-Type: application/json`.
+///   - `body_type = "form"`: the table is sent as `application/x-www-form-urlencoded` (array values repeat the name).
+///   - `body_type = "multipart"`: the table is sent as `multipart/form-data`. A value can be a file part
+///     `{ path: string, file_name?: string, content_type?: string }`, where `path` is relative to the workspace root.
+/// - `options?: WebOptions`: Optional web request options (user_agent, headers, redirect_limit, follow_redirects, timeout_ms, body_type, parse)
 ///
 /// ### Returns (WebResponse)
 ///
@@ -358,8 +358,9 @@ Cause: {err}"
 ///   success: boolean, // Indicates if the request was successful (status code 2xx)
 ///   status: number,   // The HTTP status code of the response
 ///   url: string,      // The URL that was requested
-///   content: string | table, // The body of the response. Defaults to string, but can be a table (parsed JSON) if `WebOptions.parse` is true and `Content-Type` is `application/json`.
+///   content: string | table, // The body of the response. Defaults to string, but can be a table (parsed JSON) if `WebOptions.parse` is true and `Content-Type` is JSON.
 ///   content_type?: string, // The value of the Content-Type Header, if present
+///   headers?: table,  // The response headers { header_name: string | string[] } (names are lowercase)
 ///   error?: string,   // Contains network error, parsing error, or generic status error if not 2xx
 /// }
 /// ```
@@ -373,6 +374,15 @@ Cause: {err}"
 /// -- POST with JSON data
 /// local response = aip.web.post("https://example.com/api", { key1 = "value1", key2 = "value2" })
 ///
+/// -- POST form-encoded
+/// local response = aip.web.post("https://example.com/login", { user = "jen", tags = {"a", "b"} }, { body_type = "form" })
+///
+/// -- POST multipart with a file upload
+/// local response = aip.web.post("https://example.com/upload", {
+///   title = "Screenshot",
+///   file  = { path = "docs/screenshot.png", content_type = "image/png" }
+/// }, { body_type = "multipart" })
+///
 /// -- POST with options
 /// local response = aip.web.post("https://api.example.com", { data = "value" }, {
 ///   user_agent = "MyApp/1.0",
@@ -382,8 +392,209 @@ Cause: {err}"
 ///
 /// ### Error
 ///
-/// Returns an error if the web request cannot be made (e.g., invalid URL, network error, data serialization error). Does not throw an error for non-2xx status codes. Check the `success` field in the `WebResponse`.
-fn web_post(lua: &Lua, (url, data, opts): (String, Value, Option<Value>)) -> mlua::Result<Value> {
+/// Returns an error if the web request cannot be made (e.g., invalid URL, network error, timeout, data serialization error, multipart file not found). Does not throw an error for non-2xx status codes. Check the `success` field in the `WebResponse`.
+fn web_post(lua: &Lua, runtime: &Runtime, (url, data, opts): (String, Value, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::POST, url, Some(data), opts)
+}
+
+/// ## Lua Documentation
+///
+/// Makes an HTTP PUT request to the specified URL with the given data.
+///
+/// ```lua
+/// -- API Signature
+/// aip.web.put(url: string, data: string | table, options?: WebOptions): WebResponse
+/// ```
+///
+/// Same `data`, `options`, and `WebResponse` as `aip.web.post(...)`.
+///
+/// ### Example
+///
+/// ```lua
+/// local response = aip.web.put("https://example.com/api/items/12", { name = "updated" }, { parse = true })
+/// ```
+fn web_put(lua: &Lua, runtime: &Runtime, (url, data, opts): (String, Value, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::PUT, url, Some(data), opts)
+}
+
+/// ## Lua Documentation
+///
+/// Makes an HTTP PATCH request to the specified URL with the given data.
+///
+/// ```lua
+/// -- API Signature
+/// aip.web.patch(url: string, data: string | table, options?: WebOptions): WebResponse
+/// ```
+///
+/// Same `data`, `options`, and `WebResponse` as `aip.web.post(...)`.
+///
+/// ### Example
+///
+/// ```lua
+/// local response = aip.web.patch("https://example.com/api/items/12", { status = "done" })
+/// ```
+fn web_patch(lua: &Lua, runtime: &Runtime, (url, data, opts): (String, Value, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::PATCH, url, Some(data), opts)
+}
+
+/// ## Lua Documentation
+///
+/// Makes an HTTP DELETE request to the specified URL.
+///
+/// ```lua
+/// -- API Signature
+/// aip.web.delete(url: string, options?: WebOptions): WebResponse
+/// ```
+///
+/// Same `options` and `WebResponse` as `aip.web.get(...)`.
+///
+/// ### Example
+///
+/// ```lua
+/// local response = aip.web.delete("https://example.com/api/items/12", {
+///   headers = { ["Authorization"] = "Bearer token123" }
+/// })
+/// ```
+fn web_delete(lua: &Lua, runtime: &Runtime, (url, opts): (String, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::DELETE, url, None, opts)
+}
+
+/// ## Lua Documentation
+///
+/// Makes an HTTP HEAD request to the specified URL (the response `content` is empty).
+///
+/// ```lua
+/// -- API Signature
+/// aip.web.head(url: string, options?: WebOptions): WebResponse
+/// ```
+///
+/// Same `options` and `WebResponse` as `aip.web.get(...)`.
+///
+/// ### Example
+///
+/// ```lua
+/// local response = aip.web.head("https://example.com/big-file.zip")
+/// print(response.headers["content-length"])
+/// ```
+fn web_head(lua: &Lua, runtime: &Runtime, (url, opts): (String, Option<Value>)) -> mlua::Result<Value> {
+	web_send(lua, runtime, Method::HEAD, url, None, opts)
+}
+
+/// ## Lua Documentation
+///
+/// Fetches a web page and returns its main content as clean markdown (readability style),
+/// without the navigation, ads, scripts, and other boilerplate (e.g., for research agents).
+///
+/// ```lua
+/// -- API Signature
+/// aip.web.fetch_as_markdown(url: string, options?: WebOptions): WebMarkdown
+/// ```
+///
+/// The main content is the `<article>` / `<main>` element when present, otherwise the block with the most
+/// paragraph text. The relative links and images are made absolute (with the final url, after the redirects).
+/// Non-HTML text responses (e.g., `text/plain`, `text/markdown`) are returned as is.
+///
+/// ### Arguments
+///
+/// - `url: string`: The URL of the page.
+/// - `options?: WebOptions`: Same as `aip.web.get(...)` (e.g., `user_agent = aip.web.UA_BROWSER` for the sites blocking bots).
+///
+/// ### Returns (WebMarkdown)
+///
+/// ```ts
+/// {
+///   url: string,      // The final url (after the redirects)
+///   status: number,
+///   title?: string,   // The page title (og:title, <title>, or first <h1>)
+///   content: string,  // The markdown of the main content
+/// }
+/// ```
+///
+/// ### Example
+///
+/// ```lua
+/// local page = aip.web.fetch_as_markdown("https://blog.rust-lang.org/2025/02/20/Rust-1.85.0.html")
+/// print(page.title)
+/// print(page.content)
+/// ```
+///
+/// ### Error
+///
+/// Returns an error if the request fails, the response status is not 2xx, or the content is not text.
+fn web_fetch_as_markdown(lua: &Lua, (url, opts): (String, Option<Value>)) -> mlua::Result<Value> {
+	check_pack_capability(lua, PackCapability::Net, "aip.web.fetch_as_markdown")?;
+
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let web_res = tokio::task::block_in_place(|| {
+		rt.block_on(async {
+			let web_opts = WebOptions::from_lua(opts.unwrap_or(Value::Nil), lua)?;
+			let client = web_opts
+				.apply_to_reqwest_builder(Client::builder())
+				.build()
+				.map_err(crate::Error::from)?;
+			let response = client.get(&url).send().await.map_err(|err| {
+				crate::Error::custom(format!(
+					"\
+Fail to do aip.web.fetch_as_markdown for url: {url}
+Cause: {err}"
+				))
+			})?;
+			mlua::Result::Ok(WebResponse::from_reqwest_response(response, None).await?)
+		})
+	})?;
+
+	if !web_res.status.is_success() {
+		return Err(Error::custom(format!(
+			"aip.web.fetch_as_markdown failed for url: {url}\nStatus: {}",
+			web_res.status
+		))
+		.into());
+	}
+
+	let mime = web_res
+		.content_type
+		.as_deref()
+		.and_then(|ct| ct.split(';').next())
+		.map(|mime| mime.trim().to_ascii_lowercase());
+	let (title, content) = match mime.as_deref() {
+		None | Some("text/html") | Some("application/xhtml+xml") => {
+			let article = html::extract_article(&web_res.content, Some(&web_res.url));
+			let content = article.to_md()?;
+			(article.title, content)
+		}
+		Some(mime) if mime.starts_with("text/") => (None, web_res.content),
+		Some(mime) => {
+			return Err(Error::custom(format!(
+				"aip.web.fetch_as_markdown cannot convert the '{mime}' content of url: {url}"
+			))
+			.into());
+		}
+	};
+
+	get_hub().publish_sync(format!("-> lua web::fetch_as_markdown OK ({url}) "));
+
+	let res = lua.create_table()?;
+	res.set("url", web_res.url)?;
+	res.set("status", web_res.status.as_u16())?;
+	res.set("title", title)?;
+	res.set("content", content)?;
+	Ok(Value::Table(res))
+}
+
+// region:    --- Support
+
+/// Build the client from the options, send the request (with the optional body), and return the WebResponse.
+fn web_send(
+	lua: &Lua,
+	runtime: &Runtime,
+	method: Method,
+	url: String,
+	data: Option<Value>,
+	opts: Option<Value>,
+) -> mlua::Result<Value> {
+	let fn_name = method.as_str().to_lowercase();
+	check_pack_capability(lua, PackCapability::Net, &format!("aip.web.{fn_name}"))?;
+
 	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
 	let res: mlua::Result<Value> = tokio::task::block_in_place(|| {
 		rt.block_on(async {
@@ -392,35 +603,15 @@ fn web_post(lua: &Lua, (url, data, opts): (String, Value, Option<Value>)) -> mlu
 			let opts_val = opts.unwrap_or(Value::Nil);
 			let web_opts = WebOptions::from_lua(opts_val, lua)?;
 			let parse_response = web_opts.parse;
+			let body_type = web_opts.body_type;
 			builder = web_opts.apply_to_reqwest_builder(builder);
 
 			let client = builder.build().map_err(crate::Error::from)?;
 
-			let mut request_builder = client.post(&url);
+			let mut request_builder = client.request(method, &url);
 
-			// Set Content-Type and body based on the type of 'data'
-			match data {
-				Value::String(s) => {
-					request_builder = request_builder
-						.header(header::CONTENT_TYPE, "plain/text")
-						.body(s.to_string_lossy());
-				}
-				Value::Table(table) => {
-					let json: serde_json::Value = serde_json::to_value(table).map_err(|err| {
-						crate::Error::custom(format!(
-							"Cannot searlize to json the argument given to the post.\n    Cause: {err}"
-						))
-					})?;
-					// mlua provides the serialize features.
-					request_builder = request_builder
-						.header(header::CONTENT_TYPE, "application/json")
-						.body(json.to_string());
-				}
-				_ => {
-					return Err(mlua::Error::RuntimeError(
-						"Data must be a string or a table".to_string(),
-					));
-				}
+			if let Some(data) = data {
+				request_builder = apply_body(lua, runtime, request_builder, data, body_type, &fn_name).await?;
 			}
 
 			let res: mlua::Result<Value> = match request_builder.send().await {
@@ -430,14 +621,14 @@ fn web_post(lua: &Lua, (url, data, opts): (String, Value, Option<Value>)) -> mlu
 				}
 				Err(err) => Err(crate::Error::custom(format!(
 					"\
-Fail to do aip.web.post for url: {url}
+Fail to do aip.web.{fn_name} for url: {url}
 Cause: {err}"
 				))
 				.into()),
 			};
 
 			if res.is_ok() {
-				get_hub().publish_sync(format!("-> lua web::post OK ({url}) "));
+				get_hub().publish_sync(format!("-> lua web::{fn_name} OK ({url}) "));
 			}
 
 			// return the Result<Dynamic, Error>
@@ -448,6 +639,136 @@ Cause: {err}"
 	res
 }
 
+/// Set the Content-Type and body based on the `body_type` and the type of `data`
+async fn apply_body(
+	lua: &Lua,
+	runtime: &Runtime,
+	request_builder: RequestBuilder,
+	data: Value,
+	body_type: Option<WebBodyType>,
+	fn_name: &str,
+) -> mlua::Result<RequestBuilder> {
+	let request_builder = match (body_type, data) {
+		// -- String data (sent as is)
+		(None | Some(WebBodyType::Text), Value::String(s)) => request_builder
+			.header(header::CONTENT_TYPE, "plain/text")
+			.body(s.to_string_lossy()),
+		(Some(WebBodyType::Json), Value::String(s)) => request_builder
+			.header(header::CONTENT_TYPE, "application/json")
+			.body(s.to_string_lossy()),
+		(Some(WebBodyType::Form), Value::String(s)) => request_builder
+			.header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+			.body(s.to_string_lossy()),
+
+		// -- Table data
+		(None | Some(WebBodyType::Json), Value::Table(table)) => {
+			let json: serde_json::Value = serde_json::to_value(table).map_err(|err| {
+				crate::Error::custom(format!(
+					"Cannot searlize to json the argument given to the {fn_name}.\n    Cause: {err}"
+				))
+			})?;
+			// mlua provides the serialize features.
+			request_builder
+				.header(header::CONTENT_TYPE, "application/json")
+				.body(json.to_string())
+		}
+		(Some(WebBodyType::Form), Value::Table(table)) => {
+			let mut pairs: Vec<(String, String)> = Vec::new();
+			for pair in table.pairs::<String, Value>() {
+				let (name, value) = pair?;
+				match value {
+					Value::Table(values) => {
+						for value in values.sequence_values::<Value>() {
+							pairs.push((name.clone(), form_value_to_string(value?, &name)?));
+						}
+					}
+					value => pairs.push((name.clone(), form_value_to_string(value, &name)?)),
+				}
+			}
+			request_builder.form(&pairs)
+		}
+		(Some(WebBodyType::Multipart), Value::Table(table)) => {
+			let form = build_multipart_form(lua, runtime, table, fn_name).await?;
+			request_builder.multipart(form)
+		}
+
+		(Some(body_type), Value::String(_)) => {
+			let body_type: &'static str = body_type.into();
+			return Err(Error::custom(format!(
+				"aip.web.{fn_name} - body_type '{body_type}' requires the data to be a table"
+			))
+			.into());
+		}
+		_ => {
+			return Err(mlua::Error::RuntimeError(
+				"Data must be a string or a table".to_string(),
+			));
+		}
+	};
+
+	Ok(request_builder)
+}
+
+/// Build the multipart form from a table of `name: string | number | boolean | { path, file_name?, content_type? }`
+async fn build_multipart_form(lua: &Lua, runtime: &Runtime, table: Table, fn_name: &str) -> mlua::Result<Form> {
+	let mut form = Form::new();
+
+	for pair in table.pairs::<String, Value>() {
+		let (name, value) = pair?;
+		let part = match value {
+			Value::Table(file_table) => {
+				let path: String = file_table.get::<Option<String>>("path")?.ok_or_else(|| {
+					Error::custom(format!(
+						"multipart part '{name}' must be a string, number, boolean, or a file part table {{ path = \"...\" }}"
+					))
+				})?;
+				let full_path = runtime.dir_context().resolve_path(
+					runtime.session(),
+					path.clone().into(),
+					PathResolver::WksDir,
+					None,
+				)?;
+				check_access_read(lua, &full_path, &format!("aip.web.{fn_name}"))?;
+				let mut part = Part::file(full_path.as_std_path())
+					.await
+					.map_err(|err| Error::cc(format!("Cannot read multipart file '{path}' for part '{name}'"), err))?;
+				if let Some(file_name) = file_table.get::<Option<String>>("file_name")? {
+					part = part.file_name(file_name);
+				}
+				if let Some(content_type) = file_table.get::<Option<String>>("content_type")? {
+					part = part.mime_str(&content_type).map_err(|err| {
+						Error::cc(
+							format!("Invalid content_type '{content_type}' for multipart part '{name}'"),
+							err,
+						)
+					})?;
+				}
+				part
+			}
+			value => Part::text(form_value_to_string(value, &name)?),
+		};
+		form = form.part(name, part);
+	}
+
+	Ok(form)
+}
+
+fn form_value_to_string(value: Value, name: &str) -> mlua::Result<String> {
+	match value {
+		Value::String(s) => Ok(s.to_string_lossy()),
+		Value::Integer(num) => Ok(num.to_string()),
+		Value::Number(num) => Ok(num.to_string()),
+		Value::Boolean(b) => Ok(b.to_string()),
+		other => Err(Error::custom(format!(
+			"Form value for '{name}' must be a string, number, or boolean, but was a {}",
+			other.type_name()
+		))
+		.into()),
+	}
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -500,6 +821,141 @@ return res
 		Ok(())
 	}
 
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_put_form_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+local url = "https://postman-echo.com/put"
+return aip.web.put(url, {name = "jen", count = 3}, {body_type = "form", parse = true, timeout_ms = 20000})
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_i64("status")?, 200, "status code");
+		let content = res.pointer("/content").ok_or("Should have content")?;
+		assert_eq!(content.x_get_str("/form/name")?, "jen");
+		assert_eq!(content.x_get_str("/form/count")?, "3");
+		assert_contains(
+			content.x_get_str("/headers/content-type")?,
+			"application/x-www-form-urlencoded",
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_post_multipart_file_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+local url = "https://postman-echo.com/post"
+return aip.web.post(url, {
+  title = "Some title",
+  file  = { path = "file-01.txt", content_type = "text/plain" }
+}, {body_type = "multipart", parse = true})
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_i64("status")?, 200, "status code");
+		let content = res.pointer("/content").ok_or("Should have content")?;
+		assert_eq!(content.x_get_str("/form/title")?, "Some title");
+		assert!(
+			content.pointer("/files/file-01.txt").is_some(),
+			"Should have uploaded 'file-01.txt'"
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+return aip.web.delete("https://postman-echo.com/delete?id=12", {parse = true})
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_i64("status")?, 200, "status code");
+		assert_eq!(res.x_get_str("/content/args/id")?, "12");
+		assert!(res.x_get_str("/headers/content-type").is_ok(), "Should have headers");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_get_no_follow_redirects() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+local url = "https://postman-echo.com/redirect-to?url=https://postman-echo.com/get"
+return aip.web.get(url, {follow_redirects = false})
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		assert_eq!(res.x_get_i64("status")?, 302, "status code");
+		assert!(!res.x_get_bool("success")?, "success should be false");
+		assert_eq!(res.x_get_str("/headers/location")?, "https://postman-echo.com/get");
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_post_body_type_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+return aip.web.post("https://postman-echo.com/post", "some text", {body_type = "multipart"})
+		"#;
+
+		// -- Exec
+		let err = match eval_lua(&lua, script) {
+			Ok(_) => return Err("Should have returned an error".into()),
+			Err(e) => e,
+		};
+
+		// -- Check
+		assert_contains(
+			&err.to_string(),
+			"body_type 'multipart' requires the data to be a table",
+		);
+
+		Ok(())
+	}
+
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn test_script_aip_web_fetch_as_markdown_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let lua = setup_lua(aip_web::init_module, "web").await?;
+		let script = r#"
+local url = "https://phet-dev.colorado.edu/html/build-an-atom/0.0.0-3/simple-text-only-test-page.html"
+return aip.web.fetch_as_markdown(url)
+		"#;
+
+		// -- Exec
+		let res = eval_lua(&lua, script)?;
+
+		// -- Check
+		let content = res.x_get_str("content")?;
+		assert_contains(content, "This page tests that simple text can be");
+		assert!(!content.contains("<p>"), "should be markdown");
+		assert_eq!(res.x_get_i64("status")?, 200, "status code");
+
+		Ok(())
+	}
+
 	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 	async fn test_script_aip_web_get_invalid_url() -> Result<()> {
 		// -- Setup & Fixtures
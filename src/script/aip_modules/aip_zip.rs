@@ -130,7 +130,7 @@ fn zip_create(
 	};
 
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.zip.create requires a aipack workspace setup")?;
-	check_access_write(&dest_zip_path, wks_dir)
+	check_access_write(lua, &dest_zip_path, wks_dir)
 		.map_err(|err| Error::custom(format!("aip.zip.create failed. {err}")))?;
 
 	zip::zip_dir_with_globs(&src_dir_path, &dest_zip_path, options.globs.as_ref())
@@ -220,7 +220,7 @@ fn zip_extract(
 	};
 
 	let wks_dir = dir_context.try_wks_dir_with_err_ctx("aip.zip.extract requires a aipack workspace setup")?;
-	check_access_write(&dest_dir_path, wks_dir)
+	check_access_write(lua, &dest_dir_path, wks_dir)
 		.map_err(|err| Error::custom(format!("aip.zip.extract failed. {err}")))?;
 
 	let extracted_files = zip::unzip_file_with_entries_and_globs(&src_zip_path, &dest_dir_path, options.globs.as_ref())
@@ -3,16 +3,30 @@
 mod support;
 
 pub mod aip_agent;
+pub mod aip_ai;
+pub mod aip_api;
+pub mod aip_bin;
+pub mod aip_blob;
+pub mod aip_bus;
 pub mod aip_cmd;
 pub mod aip_code;
 pub mod aip_csv;
+pub mod aip_ctx;
+pub mod aip_db;
 pub mod aip_editor;
+pub mod aip_embed;
+pub mod aip_encode;
+pub mod aip_env;
+pub mod aip_feed;
 pub mod aip_file;
 pub mod aip_flow;
+pub mod aip_gh;
 pub mod aip_git;
+pub mod aip_graphql;
 pub mod aip_hash;
 pub mod aip_hbs;
 pub mod aip_html;
+pub mod aip_image;
 pub mod aip_json;
 pub mod aip_lua;
 pub mod aip_md;
@@ -20,6 +34,7 @@ pub mod aip_path;
 pub mod aip_pdf;
 pub mod aip_run;
 pub mod aip_rust;
+pub mod aip_scaffold;
 pub mod aip_semver;
 pub mod aip_shape;
 pub mod aip_tag;
@@ -27,8 +42,11 @@ pub mod aip_task;
 pub mod aip_text;
 pub mod aip_time;
 pub mod aip_toml;
+pub mod aip_ts;
 pub mod aip_udiffx;
+pub mod aip_ui;
 pub mod aip_uuid;
+pub mod aip_vec;
 pub mod aip_web;
 pub mod aip_yaml;
 pub mod aip_zip;
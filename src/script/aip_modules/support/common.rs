@@ -1,7 +1,12 @@
 use crate::dir_context::{PathResolver, find_to_run_pack_dir, resolve_pack_ref_base_path};
+use crate::hub::{HubEvent, get_hub};
+use crate::model::RuntimeCtx;
 use crate::runtime::Runtime;
 use crate::script::support::{get_value_prop_as_string, into_vec_of_strings};
-use crate::types::{DestOptions, FileRecord, FileRef, PackRef};
+use crate::tui_v1::PromptParams;
+use crate::types::{
+	DestOptions, FileRecord, FileRef, PackCapabilities, PackCapability, PackRef, ResUsage, WriteConfirm,
+};
 use crate::{Error, Result};
 use mlua::{FromLua as _, Lua, Value};
 use simple_fs::SPath;
@@ -13,13 +18,21 @@ use std::str::FromStr;
 /// TODO: Need to fix that. Should check that is workspace dir, or in .aipack-base/ dir, but right now, poor check.
 // Check that if three is .., it ist still in a .aipack-base
 // TODO: Would probably need to check that it can only write in it's own support folder
-pub fn check_access_write(full_path: &SPath, wks_dir: &SPath) -> Result<()> {
+///
+/// NOTE: For the installed packs, the write is also checked against the pack paths and capabilities
+///       (see `PackCapabilities::check_write`), and the pack.toml `paths_allow` can be written.
+pub fn check_access_write(lua: &Lua, full_path: &SPath, wks_dir: &SPath) -> Result<()> {
 	tracing::debug!("->> check_access_write: full_path={full_path}, wks_dir={wks_dir}");
+	let mut path_allowed = false;
+	if let Some(capabilities) = lua.app_data_ref::<PackCapabilities>() {
+		capabilities.check_write(full_path, "file write")?;
+		path_allowed = capabilities.is_path_allowed(full_path);
+	}
 	if let Some(rel_path) = full_path.diff(wks_dir)
 		&& rel_path.as_str().starts_with("..")
 	{
-		// allow the .aipack-base
-		if !full_path.as_str().contains(".aipack-base") {
+		// allow the .aipack-base (and the paths the user consented to at the pack install)
+		if !full_path.as_str().contains(".aipack-base") && !path_allowed {
 			return Err(Error::custom(format!(
 				"Save file protection - The path `{rel_path}` does not belong to the workspace dir `{wks_dir}` or to the .aipack-base.\nCannot save file out of workspace or aipack base at this point"
 			)));
@@ -28,10 +41,196 @@ pub fn check_access_write(full_path: &SPath, wks_dir: &SPath) -> Result<()> {
 	Ok(())
 }
 
+/// Check the read access of a file for the installed packs (see `PackCapabilities::check_read`).
+/// (`what` is the call, e.g., `aip.file.load`)
+pub fn check_access_read(lua: &Lua, full_path: &SPath, what: &str) -> Result<()> {
+	if let Some(capabilities) = lua.app_data_ref::<PackCapabilities>() {
+		capabilities.check_read(full_path, what)?;
+	}
+	Ok(())
+}
+
+/// Check the read access of listed files for the installed packs (the `[sandbox] paths_deny` files are removed).
+///
+/// The `file_refs` paths are relative to the `base_path` (or absolute), as returned by `list_files_with_options`
+/// (see `list_base_path`).
+pub fn check_access_list(
+	lua: &Lua,
+	base_path: Option<&SPath>,
+	file_refs: Vec<FileRef>,
+	what: &str,
+) -> Result<Vec<FileRef>> {
+	let Some(capabilities) = lua.app_data_ref::<PackCapabilities>() else {
+		return Ok(file_refs);
+	};
+	if let Some(base_path) = base_path {
+		capabilities.check_read(base_path, what)?;
+	}
+	let mut checked_refs = Vec::with_capacity(file_refs.len());
+	for file_ref in file_refs {
+		let full_path = match base_path {
+			Some(base_path) if !file_ref.spath.is_absolute() => base_path.join(&file_ref.spath),
+			_ => file_ref.spath.clone(),
+		};
+		if capabilities.is_path_denied(&full_path) {
+			continue;
+		}
+		capabilities.check_read(&full_path, what)?;
+		checked_refs.push(file_ref);
+	}
+	Ok(checked_refs)
+}
+
+/// Returns the base path of the `list_files_with_options` relative paths (the workspace dir when no base path)
+pub fn list_base_path<'a>(runtime: &'a Runtime, base_path: Option<&'a SPath>) -> Option<&'a SPath> {
+	base_path.or(runtime.dir_context().wks_dir())
+}
+
+/// Returns true if the path is denied to the installed pack by the user config `[sandbox] paths_deny`
+/// (e.g., for `aip.path.exists`, which reports the denied paths as not existing).
+pub fn is_access_denied(lua: &Lua, full_path: &SPath) -> bool {
+	lua.app_data_ref::<PackCapabilities>()
+		.is_some_and(|capabilities| capabilities.is_path_denied(full_path))
+}
+
+/// Check that the running pack declared this capability (only for the installed packs, see `PackCapabilities`).
+pub fn check_pack_capability(lua: &Lua, capability: PackCapability, what: &str) -> Result<()> {
+	if let Some(capabilities) = lua.app_data_ref::<PackCapabilities>() {
+		capabilities.check(capability, what)?;
+	}
+	Ok(())
+}
+
+/// Ask the user to approve the write when the agent has `confirm_writes = true` (see `WriteConfirm`).
+///
+/// - `what` is the call (e.g., `aip.file.save`), and `content` the new file content
+///   (or the appended content when `is_append`).
+/// - The calling task waits for the answer (the TUI queues the approvals of the concurrent tasks).
+/// - Returns an error if the user does not approve the write.
+pub fn check_confirm_write(
+	lua: &Lua,
+	what: &str,
+	full_path: &SPath,
+	wks_dir: &SPath,
+	content: &str,
+	is_append: bool,
+) -> Result<()> {
+	let rel_path = full_path.diff(wks_dir).unwrap_or_else(|| full_path.clone());
+	match lua.app_data_ref::<WriteConfirm>() {
+		Some(write_confirm) if !write_confirm.is_allowed(rel_path.as_str()) => (),
+		_ => return Ok(()),
+	}
+
+	// -- Compute the diff preview
+	let original = if full_path.exists() {
+		Some(simple_fs::read_to_string(full_path)?)
+	} else {
+		None
+	};
+	let new_content = match (is_append, original.as_deref()) {
+		(true, Some(original)) => Cow::Owned(format!("{original}{content}")),
+		_ => Cow::Borrowed(content),
+	};
+	// No change, nothing to approve
+	if original.as_deref() == Some(new_content.as_ref()) {
+		return Ok(());
+	}
+	let preview = WriteConfirm::diff_preview(rel_path.as_str(), original.as_deref(), &new_content);
+
+	ask_confirm_write(what, rel_path.as_str(), preview)
+}
+
+/// Ask the user to approve a file operation (e.g., delete, move) when the agent has `confirm_writes = true`
+/// (see `check_confirm_write`).
+///
+/// - `full_paths` are the changed paths (e.g., the source and destination of a move), asked once
+///   if any is not allowed by the `confirm_writes_allow` globs.
+/// - `operation` is the preview of the operation (e.g., `delete (to the trash)`, `move to 'docs/b.md'`).
+pub fn check_confirm_file_op(
+	lua: &Lua,
+	what: &str,
+	full_paths: &[&SPath],
+	wks_dir: &SPath,
+	operation: &str,
+) -> Result<()> {
+	let Some(write_confirm) = lua.app_data_ref::<WriteConfirm>() else {
+		return Ok(());
+	};
+	let rel_paths: Vec<SPath> = full_paths
+		.iter()
+		.map(|full_path| full_path.diff(wks_dir).unwrap_or_else(|| (*full_path).clone()))
+		.collect();
+	if rel_paths.iter().all(|rel_path| write_confirm.is_allowed(rel_path.as_str())) {
+		return Ok(());
+	}
+	drop(write_confirm);
+
+	let Some(rel_path) = rel_paths.first() else {
+		return Ok(());
+	};
+	ask_confirm_write(what, rel_path.as_str(), format!("{rel_path}: {operation}"))
+}
+
+fn ask_confirm_write(what: &str, rel_path: &str, preview: String) -> Result<()> {
+	let answer = ask_user_choice(
+		format!("\n-? {what} '{rel_path}' (confirm_writes)\n   Apply this write?\n"),
+		&["yes", "no"],
+		"no",
+		preview,
+	)?;
+
+	let approved = matches!(answer.trim().to_lowercase().as_str(), "yes" | "y" | "1");
+	if approved {
+		Ok(())
+	} else {
+		Err(Error::custom(format!(
+			"{what} - The write to '{rel_path}' was not approved by the user (agent option confirm_writes = true)"
+		)))
+	}
+}
+
+/// Ask the user to pick one of the `choices` (with the `preview`, e.g., a diff), and wait for the answer.
+///
+/// Returns the `default` choice when there is no answer (e.g., the prompt was closed).
+pub fn ask_user_choice(message: String, choices: &[&str], default: &str, preview: String) -> Result<String> {
+	let (params, rx) = PromptParams::new(message);
+	let params = params
+		.with_choices(
+			choices.iter().map(|choice| choice.to_string()).collect(),
+			Some(default.to_string()),
+		)
+		.with_preview(preview);
+	let rt = tokio::runtime::Handle::try_current().map_err(Error::TokioTryCurrent)?;
+	let answer = tokio::task::block_in_place(|| {
+		rt.block_on(async {
+			get_hub().publish(HubEvent::Prompt(params)).await;
+			rx.recv().await
+		})
+	})?;
+
+	Ok(answer.unwrap_or_else(|| default.to_string()))
+}
+
 /// Check if delete access is granted.
 ///
 /// Same logic as write, but deletion is never allowed in `.aipack-base`.
-pub fn check_access_delete(full_path: &SPath, wks_dir: &SPath) -> Result<()> {
+/// Add the resource usage to the current run, when in a run context.
+/// NOTE: Should not fail the file or command operation (the errors are ignored).
+pub fn rec_res_usage(lua: &Lua, runtime: &Runtime, usage: ResUsage) {
+	let Ok(ctx) = RuntimeCtx::extract_from_global(lua) else {
+		return;
+	};
+	if let Ok(Some(run_id)) = ctx.get_run_id(runtime.mm()) {
+		let _ = runtime.rt_model().add_res_usage(run_id, usage);
+	}
+}
+
+pub fn check_access_delete(lua: &Lua, full_path: &SPath, wks_dir: &SPath) -> Result<()> {
+	// The installed packs cannot delete the `[sandbox] paths_deny` files
+	if let Some(capabilities) = lua.app_data_ref::<PackCapabilities>() {
+		capabilities.check_write(full_path, "file delete")?;
+	}
+
 	// Never allow delete operations in .aipack-base
 	if full_path.as_str().contains(".aipack-base") {
 		return Err(Error::custom(
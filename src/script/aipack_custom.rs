@@ -26,7 +26,8 @@ pub struct DataResponse {
 	pub input: Option<Value>,
 	pub data: Option<Value>,
 	pub attachments: Option<Value>,
-	pub options: Option<Value>, // AgentOptions
+	pub options: Option<Value>,  // AgentOptions
+	pub messages: Option<Value>, // Vec<ConvMessage>
 }
 
 #[derive(Debug, Default)]
@@ -34,6 +35,7 @@ pub struct BeforeAllResponse {
 	pub inputs: Option<Vec<Value>>,
 	pub before_all: Option<Value>,
 	pub options: Option<Value>,
+	pub tools: Option<Value>, // Vec<AgentTool>
 }
 
 /// Return of the `AipackCustom::from_value` allowing to avoid cloning in case it's not a AipackCustom.
@@ -111,13 +113,14 @@ fn parse_before_all_response(custom_data: Option<Value>) -> Result<BeforeAllResp
 	};
 
 	const ERROR_CAUSE: &str =
-		"aip.flow.before_all_response(arg) - 'arg' can only have `.inputs`, `.options`, `.before_all`)";
+		"aip.flow.before_all_response(arg) - 'arg' can only have `.inputs`, `.options`, `.before_all`, `.tools`)";
 
 	let before_all_response = match custom_data {
 		Value::Object(mut obj) => {
 			let all_inputs = obj.remove("inputs");
 			let before_all = obj.remove("before_all");
 			let options = obj.remove("options");
+			let tools = obj.remove("tools");
 
 			let inputs = match all_inputs {
 				Some(Value::Array(new_inputs)) => Some(new_inputs),
@@ -159,6 +162,7 @@ fn parse_before_all_response(custom_data: Option<Value>) -> Result<BeforeAllResp
 				inputs,
 				before_all,
 				options,
+				tools,
 			}
 		}
 		_ => BeforeAllResponse::default(),
@@ -173,7 +177,7 @@ fn parse_data_response(custom_data: Option<Value>) -> Result<DataResponse> {
 		return Ok(DataResponse::default());
 	};
 
-	const ERROR_CAUSE: &str = "aip.flow.data_response(arg) argumen can can only have `.input`, `.options`)";
+	const ERROR_CAUSE: &str = "aip.flow.data_response(arg) argumen can can only have `.input`, `.data`, `.attachments`, `.options`, `.messages`)";
 
 	let before_all_response = match custom_data {
 		Value::Object(mut obj) => {
@@ -181,6 +185,7 @@ fn parse_data_response(custom_data: Option<Value>) -> Result<DataResponse> {
 			let data = obj.remove("data");
 			let attachments = obj.remove("attachments");
 			let options = obj.remove("options");
+			let messages = obj.remove("messages");
 
 			let keys: Vec<String> = obj.keys().map(|k| k.to_string()).collect();
 			if !keys.is_empty() {
@@ -194,6 +199,7 @@ fn parse_data_response(custom_data: Option<Value>) -> Result<DataResponse> {
 				data,
 				attachments,
 				options,
+				messages,
 			}
 		}
 		_ => DataResponse::default(),
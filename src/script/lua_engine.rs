@@ -1,12 +1,14 @@
-use crate::Result;
 use crate::hub::{HubEvent, get_hub};
 use crate::model::{LogKind, RuntimeCtx};
 use crate::run::Literals;
 use crate::runtime::Runtime;
-use crate::script::aip_modules::aip_lua;
+use crate::script::aip_modules::{aip_ctx, aip_lua};
 use crate::script::serde_value_to_lua_value;
 use crate::script::support::process_lua_eval_result;
+use crate::types::{PackCapabilities, PackCapability};
+use crate::{Error, Result};
 use mlua::{IntoLua, Lua, Table, Value};
+use simple_fs::SPath;
 
 pub struct LuaEngine {
 	#[allow(unused)]
@@ -63,6 +65,28 @@ impl LuaEngine {
 		let engine = LuaEngine::new(runtime, name)?;
 		let lua = &engine.lua;
 
+		// -- Set the eventual pack capabilities
+		// NOTE: As app data (rather than in CTX), so that the scripts cannot change them
+		if let Some(pack_capabilities) = ctx.pack_capabilities() {
+			init_pack_capabilities(lua, pack_capabilities)?;
+			lua.set_app_data(pack_capabilities.clone());
+		}
+
+		// -- Set the eventual writes to confirm (same as above, as app data)
+		if let Some(write_confirm) = ctx.write_confirm() {
+			lua.set_app_data(write_confirm.clone());
+		}
+
+		// -- Set the eventual env variables (same as above, as app data)
+		if let Some(run_env) = ctx.run_env() {
+			lua.set_app_data(run_env.clone());
+		}
+
+		// -- Set the `aip.ctx` (the run/task identifiers and context, with the lua naming)
+		let aip_ctx = aip_ctx::create_ctx_table(lua, &engine.runtime, ctx, &rt_ctx)?;
+		let aip: Table = lua.globals().get("aip")?;
+		aip.set("ctx", aip_ctx)?;
+
 		// -- Create and Augment CTX with the eventual uids
 		let ctx = ctx.to_lua(&engine)?;
 		let ctx = if let Value::Table(ctx) = ctx {
@@ -96,7 +120,6 @@ impl LuaEngine {
 		// TODO: Might need to become USERMETA data to avoid mutability
 		let globals = lua.globals();
 		globals.set("CTX", ctx)?;
-
 		Ok(engine)
 	}
 }
@@ -193,6 +216,113 @@ impl LuaEngine {
 	}
 }
 
+// region:    --- Pack Capabilities
+
+/// Replace the Lua std functions of the capabilities the pack did not declare with functions failing with the capability error
+/// (the `aip` functions check the capabilities themselves).
+///
+/// - `os.execute` and `io.popen` require `exec`.
+/// - `os.getenv` requires `secrets`.
+/// - The file reads and writes of `io.open`, `io.input`, `io.lines`, `io.output`, `os.remove`, `os.rename`,
+///   `dofile`, and `loadfile` are checked against the pack paths (see `PackCapabilities::check_read/check_write`).
+fn init_pack_capabilities(lua: &Lua, pack_capabilities: &PackCapabilities) -> Result<()> {
+	let globals = lua.globals();
+	let std_fns = [
+		(PackCapability::Exec, "os", "execute"),
+		(PackCapability::Exec, "io", "popen"),
+		(PackCapability::Secrets, "os", "getenv"),
+	];
+	for (capability, module, fn_name) in std_fns {
+		if pack_capabilities.has(capability) {
+			continue;
+		}
+		let Ok(Value::Table(module_table)) = globals.get::<Value>(module) else {
+			continue;
+		};
+		let pack_capabilities = pack_capabilities.clone();
+		let what = format!("{module}.{fn_name}");
+		let deny_fn = lua.create_function(move |_, _: mlua::MultiValue| -> mlua::Result<()> {
+			pack_capabilities.check(capability, &what)?;
+			Ok(())
+		})?;
+		module_table.set(fn_name, deny_fn)?;
+	}
+
+	// -- The file accesses, checked against the pack paths (the std functions resolve from the current dir)
+	let file_fns: [StdFileFn; 8] = [
+		("io", "open", &[0], Some(1), false),
+		("io", "input", &[0], None, false),
+		("io", "lines", &[0], None, false),
+		("io", "output", &[0], None, true),
+		("os", "remove", &[0], None, true),
+		("os", "rename", &[0, 1], None, true),
+		("_G", "dofile", &[0], None, false),
+		("_G", "loadfile", &[0], None, false),
+	];
+	for (module, fn_name, path_idxs, mode_idx, is_write) in file_fns {
+		let module_table = if module == "_G" {
+			globals.clone()
+		} else {
+			let Ok(Value::Table(module_table)) = globals.get::<Value>(module) else {
+				continue;
+			};
+			module_table
+		};
+		let Ok(std_fn) = module_table.get::<mlua::Function>(fn_name) else {
+			continue;
+		};
+		let pack_capabilities = pack_capabilities.clone();
+		let what = if module == "_G" {
+			fn_name.to_string()
+		} else {
+			format!("{module}.{fn_name}")
+		};
+		let checked_fn = lua.create_function(move |_, args: mlua::MultiValue| {
+			let is_write = match mode_idx {
+				Some(mode_idx) => match args.get(mode_idx) {
+					Some(Value::String(mode)) => mode.to_string_lossy().contains(['w', 'a', '+']),
+					_ => false,
+				},
+				None => is_write,
+			};
+			for path_idx in path_idxs {
+				// NOTE: io.input/io.output also accept a file handle (already opened)
+				if let Some(Value::String(path)) = args.get(*path_idx) {
+					check_std_access(&pack_capabilities, &path.to_string_lossy(), is_write, &what)?;
+				}
+			}
+			std_fn.call::<mlua::MultiValue>(args)
+		})?;
+		module_table.set(fn_name, checked_fn)?;
+	}
+
+	Ok(())
+}
+
+/// (module, fn_name, the arg indexes of the paths, the mode arg index, is write without mode)
+type StdFileFn = (&'static str, &'static str, &'static [usize], Option<usize>, bool);
+
+/// Check the file access of a Lua std file function (e.g., `io.open(path, "w")`), see `PackCapabilities::check_read/check_write`.
+fn check_std_access(pack_capabilities: &PackCapabilities, path: &str, is_write: bool, what: &str) -> Result<()> {
+	let path = SPath::new(path);
+	let full_path = if path.is_absolute() {
+		path
+	} else {
+		let current_dir = SPath::from_std_path_buf(std::env::current_dir()?)?;
+		current_dir.join(path)
+	}
+	.into_collapsed();
+
+	let res = if is_write {
+		pack_capabilities.check_write(&full_path, what)
+	} else {
+		pack_capabilities.check_read(&full_path, what)
+	};
+	res.map_err(|err| Error::custom(format!("{what} - {err}")))
+}
+
+// endregion: --- Pack Capabilities
+
 // region:    --- null
 
 fn init_null(lua: &Lua) -> Result<()> {
@@ -364,10 +494,10 @@ fn init_aip(lua_vm: &Lua, runtime: &Runtime) -> Result<()> {
 		table, lua_vm, runtime, // -- The lua module names that refers to aip_...
 		flow, file, git, web, text, rust, path, md, tag, json, toml, csv, yaml, //
 		html, cmd, lua, code, hbs, semver, agent, uuid, hash, time, shape, pdf, editor, zip, //
-		udiffx
+		udiffx, image, embed, vec, blob, db, api, env, graphql, feed, encode, bin, ts, scaffold, ai, gh, bus
 	);
 
-	init_and_set!(table, lua_vm, runtime, run, task);
+	init_and_set!(table, lua_vm, runtime, run, task, ui);
 
 	let globals = lua_vm.globals();
 	// NOTE: now the aipack utilities are below `aip`,
@@ -411,6 +541,69 @@ return "Hello " .. my_name .. " - " .. square_root
 		assert_eq!(res, "Hello Lua World - 5.0");
 		Ok(())
 	}
+
+	#[tokio::test]
+	async fn test_lua_engine_pack_capabilities_std_fns() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let engine = LuaEngine::new(runtime.clone(), "test_lua_engine_pack_capabilities_std_fns")?;
+		let fx_capabilities = PackCapabilities::new(
+			"ns_b@pack_b_2",
+			vec![PackCapability::Secrets],
+			simple_fs::SPath::new("/tmp/.aipack-base/support/pack/ns_b/pack_b_2"),
+		);
+
+		// -- Exec
+		init_pack_capabilities(&engine.lua, &fx_capabilities)?;
+		let getenv_res = engine.eval(r#"return type(os.getenv("HOME"))"#, None).await?;
+		let execute_res = engine.eval(r#"return os.execute("echo hello")"#, None).await;
+
+		// -- Check
+		assert_eq!(serde_json::to_value(getenv_res)?, "string");
+		let err = execute_res.err().ok_or("os.execute should fail")?;
+		assert!(err.to_string().contains("requires the 'exec' capability"));
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_engine_pack_capabilities_std_files() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+		let engine = LuaEngine::new(runtime.clone(), "test_lua_engine_pack_capabilities_std_files")?;
+		let fx_capabilities = PackCapabilities::new(
+			"ns_b@pack_b_2",
+			vec![],
+			simple_fs::SPath::new("/tmp/.aipack-base/support/pack/ns_b/pack_b_2"),
+		)
+		.with_paths(
+			SPath::new("/tmp/.aipack-base/pack/installed/ns_b/pack_b_2"),
+			Some(SPath::from_std_path_buf(std::env::current_dir()?)?),
+			vec![],
+			vec!["src/**".to_string()],
+		)?;
+
+		// -- Exec
+		init_pack_capabilities(&engine.lua, &fx_capabilities)?;
+		let read_res = engine.eval(r#"return io.open("Cargo.toml", "r") ~= nil"#, None).await?;
+		let read_outside_res = engine.eval(r#"return io.open("/etc/hosts", "r")"#, None).await;
+		let read_denied_res = engine.eval(r#"return io.lines("src/main.rs")"#, None).await;
+		let write_res = engine.eval(r#"return io.open("/etc/aipack-test.txt", "w")"#, None).await;
+		let rename_res = engine.eval(r#"return os.rename("Cargo.toml", "../Cargo.toml")"#, None).await;
+
+		// -- Check
+		assert_eq!(serde_json::to_value(read_res)?, true);
+		let err = read_outside_res.err().ok_or("io.open read outside should fail")?;
+		assert!(err.to_string().contains("requires the 'read-outside-workspace' capability"));
+		let err = read_denied_res.err().ok_or("io.lines of a denied path should fail")?;
+		assert!(err.to_string().contains("[sandbox] paths_deny"));
+		let err = write_res.err().ok_or("io.open write should fail")?;
+		assert!(err.to_string().contains("requires the 'write-outside-workspace' capability"));
+		let err = rename_res.err().ok_or("os.rename should fail")?;
+		assert!(err.to_string().contains("requires the 'write-outside-workspace' capability"));
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
@@ -0,0 +1,426 @@
+//! The Lua static checks of the lint (`aip lint-lua`).
+//!
+//! NOTE: These are token based (no scopes), so they are conservative:
+//!       a name used anywhere else in the code is considered used.
+
+use super::lua_fmt::format_lua;
+use super::lua_lexer::{Token, TokenKind, tokenize};
+use super::{LuaIssue, LuaIssueKind};
+use crate::model::RuntimeCtx;
+use crate::run::Literals;
+use crate::runtime::Runtime;
+use crate::{Error, Result};
+use std::collections::{HashMap, HashSet};
+
+/// The names known by the aip Lua engine (to check the `aip.*` calls and the shadowed globals)
+#[derive(Debug, Default)]
+pub struct LuaLintRegistry {
+	/// The `aip` paths, without the `aip.` prefix (e.g., `file`, `file.load`)
+	aip_paths: HashSet<String>,
+	/// The Lua globals (e.g., `string`, `print`, `aip`, `CTX`)
+	globals: HashSet<String>,
+}
+
+/// Constructors
+impl LuaLintRegistry {
+	pub fn new(
+		aip_paths: impl IntoIterator<Item = impl Into<String>>,
+		globals: impl IntoIterator<Item = impl Into<String>>,
+	) -> Self {
+		Self {
+			aip_paths: aip_paths.into_iter().map(Into::into).collect(),
+			globals: globals.into_iter().map(Into::into).collect(),
+		}
+	}
+
+	/// Build the registry from a new Lua engine of this runtime (the actual `aip` modules and globals).
+	pub async fn from_runtime(runtime: &Runtime) -> Result<Self> {
+		let lua_engine = runtime.new_lua_engine_with_ctx(&Literals::default(), RuntimeCtx::default())?;
+		let res = lua_engine.eval(REGISTRY_SCRIPT, None).await?;
+		let res = serde_json::to_value(res).map_err(|err| Error::cc("Cannot read the Lua registry", err))?;
+
+		let names = |name: &str| -> Vec<String> {
+			res.get(name)
+				.and_then(|v| v.as_array())
+				.map(|names| names.iter().filter_map(|n| n.as_str().map(String::from)).collect())
+				.unwrap_or_default()
+		};
+
+		Ok(Self::new(names("aip"), names("globals")))
+	}
+}
+
+const REGISTRY_SCRIPT: &str = r#"
+local aip_paths = {}
+local function walk(prefix, tbl, depth)
+	for k, v in pairs(tbl) do
+		if type(k) == "string" then
+			local path = prefix == "" and k or (prefix .. "." .. k)
+			table.insert(aip_paths, path)
+			if type(v) == "table" and depth < 3 then
+				walk(path, v, depth + 1)
+			end
+		end
+	end
+end
+walk("", aip, 1)
+
+local globals = {}
+for k in pairs(_G) do
+	if type(k) == "string" then
+		table.insert(globals, k)
+	end
+end
+
+return { aip = aip_paths, globals = globals }
+"#;
+
+/// Check the Lua code (syntax, formatting, unused locals, undefined `aip.*` calls, shadowed globals).
+///
+/// The issue lines are 1 based, in this code.
+/// When the code has a syntax error, only the syntax error is returned.
+pub fn check_lua(code: &str, registry: &LuaLintRegistry) -> Vec<LuaIssue> {
+	if let Some(issue) = check_syntax(code) {
+		return vec![issue];
+	}
+
+	let tokens = tokenize(code);
+	let tokens: Vec<&Token> = tokens.iter().filter(|t| t.kind != TokenKind::Comment).collect();
+	let decls = declarations(&tokens);
+
+	let mut issues = Vec::new();
+	issues.extend(check_format(code));
+	issues.extend(check_unused(&tokens, &decls));
+	issues.extend(check_shadowed(&decls, registry));
+	issues.extend(check_aip_calls(&tokens, &decls, registry));
+
+	issues.sort_by_key(|issue| issue.line);
+	issues
+}
+
+// region:    --- Checks
+
+fn check_syntax(code: &str) -> Option<LuaIssue> {
+	let lua = mlua::Lua::new();
+	let err = lua.load(code).set_name("=stage").into_function().err()?;
+
+	let msg = err.to_string();
+	// e.g., `syntax error: stage:3: unexpected symbol near 'x'`
+	let (line, msg) = match msg.split_once("stage:") {
+		Some((_, rest)) => match rest.split_once(':') {
+			Some((line, msg)) => (line.trim().parse::<usize>().unwrap_or(1), msg.trim().to_string()),
+			None => (1, rest.trim().to_string()),
+		},
+		None => (1, msg),
+	};
+
+	Some(LuaIssue {
+		line,
+		kind: LuaIssueKind::Syntax,
+		msg,
+	})
+}
+
+fn check_format(code: &str) -> Option<LuaIssue> {
+	let formatted = format_lua(code);
+	let code = code.trim_end();
+	if formatted == code {
+		return None;
+	}
+
+	let line = code
+		.lines()
+		.zip(formatted.lines())
+		.position(|(a, b)| a != b)
+		.unwrap_or_else(|| code.lines().count().min(formatted.lines().count()))
+		+ 1;
+
+	Some(LuaIssue {
+		line,
+		kind: LuaIssueKind::Format,
+		msg: "Not formatted (use '--fix' to format)".to_string(),
+	})
+}
+
+fn check_unused(tokens: &[&Token], decls: &[Decl]) -> Vec<LuaIssue> {
+	// The name counts, without the field names (e.g., `item.name`)
+	let mut name_counts: HashMap<&str, usize> = HashMap::new();
+	for (idx, token) in tokens.iter().enumerate() {
+		if token.kind == TokenKind::Name && !is_field(tokens, idx) {
+			*name_counts.entry(token.text).or_default() += 1;
+		}
+	}
+
+	decls
+		.iter()
+		.filter(|decl| decl.kind != DeclKind::Param && !decl.name.starts_with('_'))
+		.filter(|decl| name_counts.get(decl.name).copied().unwrap_or_default() <= 1)
+		.map(|decl| {
+			let what = match decl.kind {
+				DeclKind::For => "loop variable",
+				_ => "local",
+			};
+			LuaIssue {
+				line: decl.line + 1,
+				kind: LuaIssueKind::Unused,
+				msg: format!("Unused {what} '{}'", decl.name),
+			}
+		})
+		.collect()
+}
+
+fn check_shadowed(decls: &[Decl], registry: &LuaLintRegistry) -> Vec<LuaIssue> {
+	decls
+		.iter()
+		.filter(|decl| registry.globals.contains(decl.name))
+		.map(|decl| {
+			let what = match decl.kind {
+				DeclKind::Local => "Local",
+				DeclKind::For => "Loop variable",
+				DeclKind::Param => "Parameter",
+			};
+			LuaIssue {
+				line: decl.line + 1,
+				kind: LuaIssueKind::Shadowed,
+				msg: format!("{what} '{}' shadows the global '{}'", decl.name, decl.name),
+			}
+		})
+		.collect()
+}
+
+fn check_aip_calls(tokens: &[&Token], decls: &[Decl], registry: &LuaLintRegistry) -> Vec<LuaIssue> {
+	// NOTE: When `aip` is a local, it is not the aip module
+	if decls.iter().any(|decl| decl.name == "aip") {
+		return Vec::new();
+	}
+
+	let mut issues = Vec::new();
+	for (idx, token) in tokens.iter().enumerate() {
+		if token.kind != TokenKind::Name || token.text != "aip" || is_field(tokens, idx) {
+			continue;
+		}
+
+		// -- The path (e.g., `file.load` of `aip.file.load`)
+		let mut parts: Vec<&str> = Vec::new();
+		let mut next = idx + 1;
+		while let (Some(dot), Some(name)) = (tokens.get(next), tokens.get(next + 1)) {
+			if !dot.is_symbol(".") || name.kind != TokenKind::Name {
+				break;
+			}
+			parts.push(name.text);
+			next += 2;
+		}
+
+		let is_call = tokens
+			.get(next)
+			.is_some_and(|t| t.is_symbol("(") || t.is_symbol("{") || t.kind == TokenKind::Str);
+		if parts.is_empty() || !is_call {
+			continue;
+		}
+
+		let path = parts.join(".");
+		if !registry.aip_paths.contains(&path) {
+			issues.push(LuaIssue {
+				line: token.line + 1,
+				kind: LuaIssueKind::Undefined,
+				msg: format!("Undefined 'aip.{path}' (not an aip function)"),
+			});
+		}
+	}
+
+	issues
+}
+
+// endregion: --- Checks
+
+// region:    --- Declarations
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeclKind {
+	Local,
+	For,
+	Param,
+}
+
+#[derive(Debug)]
+struct Decl<'a> {
+	name: &'a str,
+	kind: DeclKind,
+	/// 0 based
+	line: usize,
+}
+
+/// The `local` names, the `for` variables, and the function parameters
+fn declarations<'a>(tokens: &[&Token<'a>]) -> Vec<Decl<'a>> {
+	let mut decls = Vec::new();
+
+	for (idx, token) in tokens.iter().enumerate() {
+		if token.is_keyword("local") {
+			match tokens.get(idx + 1) {
+				// `local function name(...)` (the params are handled with the `function` keyword)
+				Some(next) if next.is_keyword("function") => {
+					if let Some(name) = tokens.get(idx + 2).filter(|t| t.kind == TokenKind::Name) {
+						decls.push(Decl::new(name, DeclKind::Local));
+					}
+				}
+				// `local a <const>, b = ...`
+				_ => {
+					let mut next = idx + 1;
+					while let Some(name) = tokens.get(next).filter(|t| t.kind == TokenKind::Name) {
+						decls.push(Decl::new(name, DeclKind::Local));
+						next += 1;
+						// the attribute (e.g., `<const>`)
+						if tokens.get(next).is_some_and(|t| t.is_symbol("<")) {
+							next += 3;
+						}
+						if !tokens.get(next).is_some_and(|t| t.is_symbol(",")) {
+							break;
+						}
+						next += 1;
+					}
+				}
+			}
+		} else if token.is_keyword("for") {
+			let mut next = idx + 1;
+			while let Some(name) = tokens.get(next).filter(|t| t.kind == TokenKind::Name) {
+				decls.push(Decl::new(name, DeclKind::For));
+				if !tokens.get(next + 1).is_some_and(|t| t.is_symbol(",")) {
+					break;
+				}
+				next += 2;
+			}
+		} else if token.is_keyword("function") {
+			let Some(open) = tokens[idx..].iter().position(|t| t.is_symbol("(")) else {
+				continue;
+			};
+			for param in tokens[idx + open + 1..].iter().take_while(|t| !t.is_symbol(")")) {
+				if param.kind == TokenKind::Name {
+					decls.push(Decl::new(param, DeclKind::Param));
+				}
+			}
+		}
+	}
+
+	decls
+}
+
+impl<'a> Decl<'a> {
+	fn new(token: &Token<'a>, kind: DeclKind) -> Self {
+		Self {
+			name: token.text,
+			kind,
+			line: token.line,
+		}
+	}
+}
+
+/// Returns true when the name token is a field (e.g., `name` of `item.name` or `item:name()`)
+fn is_field(tokens: &[&Token], idx: usize) -> bool {
+	idx > 0 && (tokens[idx - 1].is_symbol(".") || tokens[idx - 1].is_symbol(":"))
+}
+
+// endregion: --- Declarations
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	fn fx_registry() -> LuaLintRegistry {
+		LuaLintRegistry::new(
+			["file", "file.load", "run", "run.pin"],
+			["aip", "string", "table", "print", "ipairs"],
+		)
+	}
+
+	#[test]
+	fn test_lua_checks_check_lua_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let code = r#"local file = aip.file.load(input.path)
+local unused_var = 1
+local _ignored = 2
+for idx, item in ipairs(data.items) do
+	print(item.name)
+end
+local function fmt(string)
+	return aip.file.loadd(string)
+end
+aip.run.pin("file", fmt(file.content))
+-- aip.nope.call()
+return aip.nope "call""#;
+
+		// -- Exec
+		let issues = check_lua(code, &fx_registry());
+
+		// -- Check
+		let issues: Vec<(usize, LuaIssueKind, &str)> =
+			issues.iter().map(|i| (i.line, i.kind, i.msg.as_str())).collect();
+		assert_eq!(
+			issues,
+			[
+				(2, LuaIssueKind::Unused, "Unused local 'unused_var'"),
+				(4, LuaIssueKind::Unused, "Unused loop variable 'idx'"),
+				(
+					7,
+					LuaIssueKind::Shadowed,
+					"Parameter 'string' shadows the global 'string'"
+				),
+				(
+					8,
+					LuaIssueKind::Undefined,
+					"Undefined 'aip.file.loadd' (not an aip function)"
+				),
+				(
+					12,
+					LuaIssueKind::Undefined,
+					"Undefined 'aip.nope' (not an aip function)"
+				),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_lua_checks_check_lua_syntax_and_format() -> Result<()> {
+		// -- Setup & Fixtures
+		let registry = fx_registry();
+
+		// -- Exec
+		let syntax_issues = check_lua("local a = 1\nif a then\nreturn a", &registry);
+		let format_issues = check_lua("if true then\n    print(\"ok\")\nend", &registry);
+
+		// -- Check
+		assert_eq!(syntax_issues.len(), 1);
+		assert_eq!(syntax_issues[0].kind, LuaIssueKind::Syntax);
+		assert_eq!(syntax_issues[0].line, 3);
+		assert_eq!(format_issues.len(), 1);
+		assert_eq!(format_issues[0].kind, LuaIssueKind::Format);
+		assert_eq!(format_issues[0].line, 2);
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_lua_checks_registry_from_runtime() -> Result<()> {
+		// -- Setup & Fixtures
+		let runtime = Runtime::new_test_runtime_sandbox_01().await?;
+
+		// -- Exec
+		let registry = LuaLintRegistry::from_runtime(&runtime).await?;
+
+		// -- Check
+		assert!(registry.aip_paths.contains("file.load"));
+		assert!(registry.aip_paths.contains("flow.skip_if"));
+		assert!(!registry.aip_paths.contains("file.loadd"));
+		assert!(registry.globals.contains("string"));
+		assert!(registry.globals.contains("aip"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
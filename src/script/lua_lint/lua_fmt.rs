@@ -0,0 +1,213 @@
+//! The Lua formatter of the lint (`aip lint-lua --fix`).
+//!
+//! A simple normalizer (not a full pretty printer), so that the multi-author packs stay consistent:
+//! - Indents the lines with tabs, by block depth (`function`, `do`, `then`, `repeat`, `else`, and the brackets).
+//!   Several blocks opened on the same line (e.g., `foo(function()`) are one level.
+//! - Indents the continuation lines (previous line ending with a binary operator) one more level.
+//! - Removes the trailing whitespaces, the repeated empty lines, and the leading and trailing empty lines.
+//! - Does not change the content of the multi-line strings and comments.
+
+use super::lua_lexer::{Token, TokenKind, tokenize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DepthEvent {
+	Open,
+	Close,
+}
+
+const CONTINUATION_OPS: &[&str] = &[
+	"and", "or", "..", "+", "-", "*", "/", "//", "%", "^", "=", "==", "~=", "<", ">", "<=", ">=",
+];
+
+/// Returns the formatted Lua code (without trailing new line).
+pub fn format_lua(code: &str) -> String {
+	let tokens = tokenize(code);
+	let lines: Vec<&str> = code.lines().collect();
+
+	// -- The tokens by line, and the lines inside a multi-line token (kept as is)
+	let mut line_tokens: Vec<Vec<&Token>> = vec![Vec::new(); lines.len()];
+	// The line starts inside a multi-line token (not re-indented)
+	let mut starts_inside = vec![false; lines.len()];
+	// The line ends inside a multi-line token (not trimmed at the end)
+	let mut ends_inside = vec![false; lines.len()];
+	for token in tokens.iter() {
+		if let Some(toks) = line_tokens.get_mut(token.line) {
+			toks.push(token);
+		}
+		for line in token.line..token.end_line {
+			if let Some(ends_inside) = ends_inside.get_mut(line) {
+				*ends_inside = true;
+			}
+			if let Some(starts_inside) = starts_inside.get_mut(line + 1) {
+				*starts_inside = true;
+			}
+		}
+	}
+
+	// -- Compute the indent of each line
+	// The open count of each indent level
+	let mut levels: Vec<usize> = Vec::new();
+	let mut out: Vec<String> = Vec::new();
+	let mut continuation = false;
+
+	for (idx, line) in lines.iter().enumerate() {
+		let toks = &line_tokens[idx];
+
+		// The indent is the depth after the leading closing tokens (e.g., `end`, `}`, `else`)
+		let mut indent: Option<usize> = None;
+		// The level pushed by this line (several openings on the same line are one level)
+		let mut line_level: Option<usize> = None;
+		for token in toks.iter() {
+			let events = depth_events(token);
+			if events.is_empty() && indent.is_none() {
+				indent = Some(levels.len());
+			}
+			for event in events {
+				match event {
+					DepthEvent::Close => {
+						if let Some(count) = levels.last_mut() {
+							*count -= 1;
+							if *count == 0 {
+								levels.pop();
+								if line_level == Some(levels.len()) {
+									line_level = None;
+								}
+							}
+						}
+					}
+					DepthEvent::Open => {
+						if indent.is_none() {
+							indent = Some(levels.len());
+						}
+						let top_level = levels.len().checked_sub(1);
+						match levels.last_mut() {
+							Some(count) if line_level.is_some() && line_level == top_level => *count += 1,
+							_ => {
+								line_level = Some(levels.len());
+								levels.push(1);
+							}
+						}
+					}
+				}
+			}
+		}
+		let indent = indent.unwrap_or(levels.len()) + usize::from(continuation);
+
+		// -- The formatted line
+		let line = if ends_inside[idx] { line } else { line.trim_end() };
+		if starts_inside[idx] {
+			out.push(line.to_string());
+		} else if line.trim().is_empty() {
+			out.push(String::new());
+		} else {
+			out.push(format!("{}{}", "\t".repeat(indent), line.trim_start()));
+		}
+
+		// -- The next line is a continuation when this one ends with a binary operator
+		//    (and did not open a level, e.g., `print("a" ..` is already indented by the parenthesis)
+		// NOTE: A comment line or an empty line does not change the continuation
+		if let Some(last) = toks.iter().rev().find(|t| t.kind != TokenKind::Comment) {
+			let is_op = matches!(last.kind, TokenKind::Symbol | TokenKind::Keyword);
+			continuation =
+				line_level.is_none() && is_op && CONTINUATION_OPS.contains(&last.text) && last.end_line == idx;
+		}
+	}
+
+	// -- Remove the repeated, leading, and trailing empty lines
+	let mut res: Vec<String> = Vec::with_capacity(out.len());
+	for (idx, line) in out.into_iter().enumerate() {
+		let is_empty = line.is_empty() && !starts_inside[idx];
+		if is_empty && res.last().is_none_or(|last| last.is_empty()) {
+			continue;
+		}
+		res.push(line);
+	}
+	while res.last().is_some_and(|last| last.is_empty()) {
+		res.pop();
+	}
+
+	res.join("\n")
+}
+
+fn depth_events(token: &Token) -> &'static [DepthEvent] {
+	match (token.kind, token.text) {
+		(TokenKind::Keyword, "function" | "do" | "then" | "repeat") => &[DepthEvent::Open],
+		(TokenKind::Keyword, "end" | "until" | "elseif") => &[DepthEvent::Close],
+		(TokenKind::Keyword, "else") => &[DepthEvent::Close, DepthEvent::Open],
+		(TokenKind::Symbol, "(" | "{" | "[") => &[DepthEvent::Open],
+		(TokenKind::Symbol, ")" | "}" | "]") => &[DepthEvent::Close],
+		_ => &[],
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_lua_fmt_format_lua_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let code = r#"
+
+local items = {}
+for _, file in ipairs(inputs) do
+  if file.ext == "rs" then
+        table.insert(items, file)
+  elseif file.ext == "md" then
+ items[#items + 1] = {
+   path = file.path,
+   content = [[
+  keep
+ as is]]
+ }
+  else
+    print("skip " ..
+    file.path)
+  end
+
+
+end
+aip.run.pin("items", function(item)
+  return item
+end)
+return items
+
+"#;
+
+		// -- Exec
+		let formatted = format_lua(code);
+
+		// -- Check
+		let expected = r#"local items = {}
+for _, file in ipairs(inputs) do
+	if file.ext == "rs" then
+		table.insert(items, file)
+	elseif file.ext == "md" then
+		items[#items + 1] = {
+			path = file.path,
+			content = [[
+  keep
+ as is]]
+		}
+	else
+		print("skip " ..
+			file.path)
+	end
+
+end
+aip.run.pin("items", function(item)
+	return item
+end)
+return items"#;
+		assert_eq!(formatted, expected);
+		assert_eq!(format_lua(&formatted), formatted, "should be stable");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
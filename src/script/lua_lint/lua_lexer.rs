@@ -0,0 +1,224 @@
+//! A light Lua lexer for the lint (formatting and static checks).
+//!
+//! NOTE: Not a full Lua parser. It only needs the tokens with their lines,
+//!       and to know what is in strings and comments (so that they are never changed or checked).
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+	Name,
+	Keyword,
+	Number,
+	Str,
+	Comment,
+	Symbol,
+}
+
+#[derive(Debug, Clone)]
+pub struct Token<'a> {
+	pub kind: TokenKind,
+	pub text: &'a str,
+	/// The line of the token start (0 based)
+	pub line: usize,
+	/// The line of the token end (0 based), different from `line` for the multi-line strings and comments
+	pub end_line: usize,
+}
+
+impl Token<'_> {
+	pub fn is_keyword(&self, keyword: &str) -> bool {
+		self.kind == TokenKind::Keyword && self.text == keyword
+	}
+
+	pub fn is_symbol(&self, symbol: &str) -> bool {
+		self.kind == TokenKind::Symbol && self.text == symbol
+	}
+}
+
+const KEYWORDS: &[&str] = &[
+	"and", "break", "do", "else", "elseif", "end", "false", "for", "function", "goto", "if", "in", "local", "nil",
+	"not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+const SYMBOLS_3: &[&str] = &["..."];
+const SYMBOLS_2: &[&str] = &["..", "==", "~=", "<=", ">=", "::", "//", "<<", ">>"];
+
+/// Tokenize the Lua code (never fails, an unknown or unterminated token goes to the end of the line or code,
+/// the syntax errors are reported by the Lua `load`).
+pub fn tokenize(code: &str) -> Vec<Token<'_>> {
+	let bytes = code.as_bytes();
+	let mut tokens = Vec::new();
+	let mut pos = 0;
+	let mut line = 0;
+
+	while pos < bytes.len() {
+		let c = bytes[pos];
+
+		// -- Whitespaces
+		if c == b'\n' {
+			line += 1;
+			pos += 1;
+			continue;
+		}
+		if c.is_ascii_whitespace() {
+			pos += 1;
+			continue;
+		}
+
+		let start = pos;
+		let kind = if code[pos..].starts_with("--") {
+			pos = match long_bracket_level(bytes, pos + 2) {
+				Some(level) => long_bracket_end(bytes, pos + 2, level),
+				None => line_end(bytes, pos),
+			};
+			TokenKind::Comment
+		} else if c == b'"' || c == b'\'' {
+			pos = quoted_end(bytes, pos);
+			TokenKind::Str
+		} else if let Some(level) = (c == b'[').then(|| long_bracket_level(bytes, pos)).flatten() {
+			pos = long_bracket_end(bytes, pos, level);
+			TokenKind::Str
+		} else if c.is_ascii_digit() || (c == b'.' && bytes.get(pos + 1).is_some_and(|b| b.is_ascii_digit())) {
+			pos = number_end(bytes, pos);
+			TokenKind::Number
+		} else if c.is_ascii_alphabetic() || c == b'_' {
+			while pos < bytes.len() && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_') {
+				pos += 1;
+			}
+			if KEYWORDS.contains(&&code[start..pos]) {
+				TokenKind::Keyword
+			} else {
+				TokenKind::Name
+			}
+		} else {
+			let rest = &code[pos..];
+			let len = if SYMBOLS_3.iter().any(|s| rest.starts_with(s)) {
+				3
+			} else if SYMBOLS_2.iter().any(|s| rest.starts_with(s)) {
+				2
+			} else {
+				// NOTE: Can be a multi-byte char (not valid Lua, but must stay on a char boundary)
+				rest.chars().next().map(|c| c.len_utf8()).unwrap_or(1)
+			};
+			pos += len;
+			TokenKind::Symbol
+		};
+
+		let text = &code[start..pos];
+		let end_line = line + text.matches('\n').count();
+		tokens.push(Token {
+			kind,
+			text,
+			line,
+			end_line,
+		});
+		line = end_line;
+	}
+
+	tokens
+}
+
+// region:    --- Support
+
+/// Returns the level of the long bracket opening at `pos` (e.g., `[[` is 0, `[==[` is 2)
+fn long_bracket_level(bytes: &[u8], pos: usize) -> Option<usize> {
+	if bytes.get(pos) != Some(&b'[') {
+		return None;
+	}
+	let level = bytes[pos + 1..].iter().take_while(|b| **b == b'=').count();
+	(bytes.get(pos + 1 + level) == Some(&b'[')).then_some(level)
+}
+
+/// Returns the position after the closing long bracket (or the end of the code)
+fn long_bracket_end(bytes: &[u8], pos: usize, level: usize) -> usize {
+	let close = format!("]{}]", "=".repeat(level));
+	let content_start = pos + level + 2;
+	match find(&bytes[content_start..], close.as_bytes()) {
+		Some(idx) => content_start + idx + close.len(),
+		None => bytes.len(),
+	}
+}
+
+/// Returns the position after the closing quote (or the end of the line when not terminated)
+fn quoted_end(bytes: &[u8], pos: usize) -> usize {
+	let quote = bytes[pos];
+	let mut pos = pos + 1;
+	while pos < bytes.len() {
+		match bytes[pos] {
+			b'\\' => pos += 2,
+			b'\n' => return pos,
+			b if b == quote => return pos + 1,
+			_ => pos += 1,
+		}
+	}
+	bytes.len()
+}
+
+fn number_end(bytes: &[u8], pos: usize) -> usize {
+	let mut pos = pos;
+	while pos < bytes.len() {
+		let b = bytes[pos];
+		let is_exp_sign = (b == b'+' || b == b'-') && matches!(bytes[pos - 1], b'e' | b'E' | b'p' | b'P');
+		if b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || is_exp_sign {
+			pos += 1;
+		} else {
+			break;
+		}
+	}
+	pos
+}
+
+fn line_end(bytes: &[u8], pos: usize) -> usize {
+	find(&bytes[pos..], b"\n").map(|idx| pos + idx).unwrap_or(bytes.len())
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_lua_lexer_tokenize_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let code = "local s = \"a -- b\" .. [==[\nend]]\n]==] -- comment\nreturn s ~= 1.5e-3";
+
+		// -- Exec
+		let tokens = tokenize(code);
+
+		// -- Check
+		let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+		assert_eq!(
+			texts,
+			[
+				"local",
+				"s",
+				"=",
+				"\"a -- b\"",
+				"..",
+				"[==[\nend]]\n]==]",
+				"-- comment",
+				"return",
+				"s",
+				"~=",
+				"1.5e-3"
+			]
+		);
+		let long_str = &tokens[5];
+		assert_eq!(long_str.kind, TokenKind::Str);
+		assert_eq!((long_str.line, long_str.end_line), (0, 2));
+		assert_eq!(tokens[6].kind, TokenKind::Comment);
+		assert_eq!(tokens[7].line, 3);
+		assert!(tokens[7].is_keyword("return"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
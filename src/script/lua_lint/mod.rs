@@ -0,0 +1,30 @@
+//! The Lua lint of the agent stage scripts (`aip lint-lua`), formatting and static checks.
+
+// region:    --- Modules
+
+mod lua_checks;
+mod lua_fmt;
+mod lua_lexer;
+
+pub use lua_checks::*;
+pub use lua_fmt::*;
+
+// endregion: --- Modules
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum LuaIssueKind {
+	Syntax,
+	Format,
+	Unused,
+	Undefined,
+	Shadowed,
+}
+
+#[derive(Debug)]
+pub struct LuaIssue {
+	/// The line in the Lua code (1 based)
+	pub line: usize,
+	pub kind: LuaIssueKind,
+	pub msg: String,
+}
@@ -39,3 +39,45 @@ impl FromLua for uc::Marker {
 	}
 }
 // endregion: --- Marker
+
+// region:    --- TimelineMarker
+
+impl FromLua for uc::TimelineMarker {
+	fn from_lua(lua_value: mlua::Value, _lua: &mlua::Lua) -> mlua::Result<Self> {
+		if let mlua::Value::Table(table) = lua_value {
+			let label = table.x_get_string("label").ok_or_else(|| mlua::Error::FromLuaConversionError {
+				from: "table",
+				to: "TimelineMarker".to_string(),
+				message: Some("marker table must have a 'label' string property".to_string()),
+			})?;
+			let level = match table.x_get_string("level") {
+				Some(level) => level
+					.parse::<uc::MarkerLevel>()
+					.map_err(|_| mlua::Error::FromLuaConversionError {
+						from: "string",
+						to: "MarkerLevel".to_string(),
+						message: Some(format!(
+							"marker level '{level}' not supported. Must be 'info', 'success', 'warn', or 'error'"
+						)),
+					})?,
+				None => uc::MarkerLevel::default(),
+			};
+			let link = table.x_get_string("link");
+			Ok(uc::TimelineMarker { label, level, link })
+		} else if let Some(label) = lua_value.as_string() {
+			Ok(uc::TimelineMarker {
+				label: label.to_string_lossy(),
+				level: uc::MarkerLevel::default(),
+				link: None,
+			})
+		} else {
+			Err(mlua::Error::FromLuaConversionError {
+				from: lua_value.type_name(),
+				to: "TimelineMarker".to_string(),
+				message: Some("expected a string or a table with 'label', 'level?', and 'link?' keys".to_string()),
+			})
+		}
+	}
+}
+
+// endregion: --- TimelineMarker
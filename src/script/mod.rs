@@ -13,11 +13,13 @@ mod support;
 
 mod aipack_custom;
 mod lua_engine;
+mod lua_lint;
 mod lua_uc;
 
 pub use aipack_custom::*;
 pub use lua_engine::*;
 pub use lua_helpers::*;
+pub use lua_lint::*;
 #[cfg(test)] // Needed for test only (beside this script module)
 pub use support::process_lua_eval_result;
 
@@ -1,5 +1,6 @@
 use crate::dir_context::PathResolver;
 use crate::runtime::Runtime;
+use crate::types::PackCapabilities;
 use crate::{Error, Result};
 use mlua::{Lua, Table, Value};
 use std::collections::{BTreeSet, HashSet};
@@ -109,14 +110,17 @@ pub fn get_value_prop_as_string(
 
 // region:    --- Common Paths Support
 
-pub fn path_exists(runtime: &Runtime, path: &str) -> bool {
+/// NOTE: The paths denied to the installed pack (user config `[sandbox] paths_deny`) are reported as not existing.
+pub fn path_exists(lua: &Lua, runtime: &Runtime, path: &str) -> bool {
 	let dir_context = runtime.dir_context();
 	// Resolve the path relative to the workspace directory
 	let full_path = dir_context
 		.resolve_path(runtime.session(), path.into(), PathResolver::WksDir, None)
 		.ok();
 
-	full_path.map(|p| p.exists()).unwrap_or(false)
+	full_path
+		.map(|p| p.exists() && !lua.app_data_ref::<PackCapabilities>().is_some_and(|c| c.is_path_denied(&p)))
+		.unwrap_or(false)
 }
 
 // endregion: --- Common Paths Support
@@ -0,0 +1,270 @@
+//! Tolerant parsers for the model (AI) outputs (used by `aip.ai`).
+//!
+//! The model outputs often deviate cosmetically from the requested format
+//! (e.g., prose around the JSON, wrapping markdown fences, trailing commas, single quotes),
+//! so these parsers try to get the value anyway.
+
+use crate::support::jsons::parse_jsonc_to_serde_value;
+use crate::support::md::MdBlockIter;
+use lazy_regex::regex_captures;
+use serde_json::Value;
+
+/// Remove the markdown fence wrapping the whole content (the content is trimmed).
+///
+/// The closing fence is optional (e.g., for a truncated output).
+/// If the content does not start with a fence, the trimmed content is returned.
+pub fn strip_fences(content: &str) -> &str {
+	let content = content.trim();
+	let fence = if content.starts_with("````") {
+		"````"
+	} else if content.starts_with("```") {
+		"```"
+	} else {
+		return content;
+	};
+
+	// Remove the opening fence line (with its info string)
+	let Some((_, rest)) = content.split_once('\n') else {
+		return "";
+	};
+	// Remove the closing fence, if it is the last line
+	let rest = match rest.trim_end().strip_suffix(fence) {
+		Some(inner) if inner.is_empty() || inner.ends_with('\n') => inner,
+		_ => rest,
+	};
+
+	rest.trim_matches('\n')
+}
+
+/// Extract the first JSON value (object or array) of the content.
+///
+/// In order:
+/// - The whole content (without the wrapping fences)
+/// - The first fenced block that parses
+/// - The first `{...}` or `[...]` in the prose that parses
+///
+/// The JSON is parsed leniently (comments, trailing commas, single quotes, unquoted property names).
+///
+/// Returns `None` if no JSON value is found.
+pub fn extract_json(content: &str) -> Option<Value> {
+	let content = strip_fences(content);
+	if let Some(value) = parse_lenient(content) {
+		return Some(value);
+	}
+
+	// -- The fenced blocks
+	for block in MdBlockIter::new(content, None, None) {
+		if let Some(value) = parse_lenient(&block.content) {
+			return Some(value);
+		}
+	}
+
+	// -- The first balanced object or array in the prose
+	for (start, c) in content.char_indices() {
+		if c != '{' && c != '[' {
+			continue;
+		}
+		if let Some(end) = find_json_end(content, start)
+			&& let Some(value) = parse_lenient(&content[start..end])
+			&& (value.is_object() || value.is_array())
+		{
+			return Some(value);
+		}
+	}
+
+	None
+}
+
+/// Parse the numbered (e.g., `1.` or `1)`) or bulleted (`-`, `*`, `+`) list items of the content.
+///
+/// The more indented lines (e.g., sub items or wrapped text) are appended to their item,
+/// and the non list lines (e.g., the intro prose) are ignored.
+pub fn parse_list(content: &str) -> Vec<String> {
+	let content = strip_fences(content);
+	let mut items: Vec<String> = Vec::new();
+	let mut base_indent: Option<usize> = None;
+	// Whether the last non empty line was part of the current item
+	let mut in_item = false;
+
+	for line in content.lines() {
+		let text = line.trim_start();
+		if text.is_empty() {
+			continue;
+		}
+		let indent = line.len() - text.len();
+		let item_text = regex_captures!(r"^(?:\d+[.)]|[-*+•])\s+(.*)$", text).map(|(_, item_text)| item_text);
+
+		match (item_text, base_indent) {
+			(Some(item_text), None) => {
+				base_indent = Some(indent);
+				items.push(item_text.trim().to_string());
+				in_item = true;
+			}
+			(Some(item_text), Some(base)) if indent <= base => {
+				items.push(item_text.trim().to_string());
+				in_item = true;
+			}
+			_ => match items.last_mut() {
+				// The more indented lines continue the current item
+				Some(last) if in_item && base_indent.is_some_and(|base| indent > base) => {
+					last.push(' ');
+					last.push_str(text.trim());
+				}
+				_ => in_item = false,
+			},
+		}
+	}
+
+	items
+}
+
+/// Append the continuation of a (max tokens) truncated model output to the output so far.
+///
+/// The continuation often repeats the end of the output (overlap), or reopens the markdown fence
+/// the output was cut in, so both are removed before appending.
+pub fn stitch_continuation(content: &str, continuation: &str) -> String {
+	let mut continuation = continuation;
+
+	// -- Remove the reopened fence (when the content was cut inside a fenced block)
+	let open_fence = content.lines().filter(|line| line.trim_start().starts_with("```")).count() % 2 == 1;
+	if open_fence && continuation.trim_start().starts_with("```") {
+		continuation = continuation.trim_start().split_once('\n').map(|(_, rest)| rest).unwrap_or("");
+	}
+
+	// -- Remove the overlap (the longest content suffix starting the continuation)
+	let max_overlap = content.len().min(continuation.len()).min(MAX_CONTINUATION_OVERLAP);
+	let overlap = (1..=max_overlap)
+		.rev()
+		.filter(|&len| content.is_char_boundary(content.len() - len) && continuation.is_char_boundary(len))
+		.find(|&len| content[content.len() - len..] == continuation[..len])
+		// a few chars overlap might be a coincidence (e.g., a space or a new line)
+		.filter(|&len| len >= MIN_CONTINUATION_OVERLAP)
+		.unwrap_or(0);
+
+	format!("{content}{}", &continuation[overlap..])
+}
+
+// region:    --- Support
+
+/// The max length (in bytes) of the repeated output searched at the start of a continuation
+const MAX_CONTINUATION_OVERLAP: usize = 500;
+/// The min length (in bytes) of the repeated output removed from a continuation
+const MIN_CONTINUATION_OVERLAP: usize = 8;
+
+fn parse_lenient(content: &str) -> Option<Value> {
+	let content = content.trim();
+	if content.is_empty() {
+		return None;
+	}
+	parse_jsonc_to_serde_value(content).ok().flatten()
+}
+
+/// Returns the end byte index (exclusive) of the object or array starting at `start`,
+/// skipping the brackets in the (double or single quoted) strings.
+fn find_json_end(content: &str, start: usize) -> Option<usize> {
+	let mut depth = 0_usize;
+	let mut in_string: Option<char> = None;
+	let mut escaped = false;
+
+	for (idx, c) in content[start..].char_indices() {
+		if let Some(quote) = in_string {
+			if escaped {
+				escaped = false;
+			} else if c == '\\' {
+				escaped = true;
+			} else if c == quote {
+				in_string = None;
+			}
+			continue;
+		}
+		match c {
+			'"' | '\'' => in_string = Some(c),
+			'{' | '[' => depth += 1,
+			'}' | ']' => {
+				depth = depth.saturating_sub(1);
+				if depth == 0 {
+					return Some(start + idx + 1);
+				}
+			}
+			_ => (),
+		}
+	}
+
+	None
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_support_ai_parse_extract_json() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_prose = "Sure! Here is the result (as requested):\n{'name': 'demo', tags: ['a', 'b',], note: \"x }\",}\nHope it helps.";
+		let fx_fenced = "```json\n[1, 2, 3,]\n```";
+		let fx_block_in_prose = "Result:\n\n```json\n{\"ok\": true}\n```\n\nDone [1].";
+
+		// -- Exec & Check
+		assert_eq!(
+			extract_json(fx_prose),
+			Some(json!({"name": "demo", "tags": ["a", "b"], "note": "x }"}))
+		);
+		assert_eq!(extract_json(fx_fenced), Some(json!([1, 2, 3])));
+		assert_eq!(extract_json(fx_block_in_prose), Some(json!({"ok": true})));
+		assert_eq!(extract_json("No json here."), None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_ai_parse_parse_list_and_strip_fences() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_list = "Here are the steps:\n\n1. Install the tool\n   with cargo\n2) Run it\n   - sub item\n\nSome notes.\n3. Done";
+
+		// -- Exec & Check
+		assert_eq!(
+			parse_list(fx_list),
+			vec!["Install the tool with cargo", "Run it - sub item", "Done"]
+		);
+		assert_eq!(parse_list("- a\n* b\n+ c"), vec!["a", "b", "c"]);
+		assert_eq!(strip_fences("\n```rust\nfn main() {}\n```\n"), "fn main() {}");
+		assert_eq!(strip_fences("```md\npartial"), "partial");
+		assert_eq!(strip_fences("no fences"), "no fences");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_ai_parse_stitch_continuation() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_code = "Here it is:\n\n```rust\nfn main() {\n    println!(\"hello";
+
+		// -- Exec & Check
+		// overlap removed
+		assert_eq!(
+			stitch_continuation(fx_code, "    println!(\"hello world\");\n}\n```"),
+			"Here it is:\n\n```rust\nfn main() {\n    println!(\"hello world\");\n}\n```"
+		);
+		// reopened fence removed
+		assert_eq!(
+			stitch_continuation(fx_code, "```rust\n world\");\n}\n```"),
+			"Here it is:\n\n```rust\nfn main() {\n    println!(\"hello world\");\n}\n```"
+		);
+		// short coincidental overlap kept
+		assert_eq!(
+			stitch_continuation("The end of a", " a sentence."),
+			"The end of a a sentence."
+		);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
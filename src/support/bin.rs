@@ -0,0 +1,232 @@
+//! The binary data support (used by `aip.bin`): the file range read, the hexdump, and the struct unpack.
+//!
+//! The unpack format is the Python `struct` one, with the standard sizes and no alignment (as with `<` or `>`):
+//! - Byte order (first char): `<` little-endian, `>` or `!` big-endian, `=` or `@` native (default).
+//! - Codes (with an optional count, e.g., `4H`): `x` pad byte, `c` char, `b`/`B` 8 bit, `?` bool,
+//!   `h`/`H` 16 bit, `i`/`I`/`l`/`L` 32 bit, `q`/`Q` 64 bit, `f` float, `d` double,
+//!   and `s` bytes (the count is the length, e.g., `4s`).
+
+use crate::{Error, Result};
+use simple_fs::SPath;
+use std::io::{Read as _, Seek as _, SeekFrom};
+
+/// An unpacked value
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinValue {
+	Int(i64),
+	UInt(u64),
+	Float(f64),
+	Bool(bool),
+	Bytes(Vec<u8>),
+}
+
+/// Read `len` bytes at `offset` (to the end of the file if no `len`), fewer if the file ends before.
+pub fn read_file_range(path: &SPath, offset: u64, len: Option<u64>) -> Result<Vec<u8>> {
+	let mut file =
+		std::fs::File::open(path.as_std_path()).map_err(|err| Error::cc(format!("Cannot open file '{path}'"), err))?;
+	file.seek(SeekFrom::Start(offset))
+		.map_err(|err| Error::cc(format!("Cannot seek to {offset} in file '{path}'"), err))?;
+
+	let mut bytes = Vec::new();
+	let res = match len {
+		Some(len) => file.take(len).read_to_end(&mut bytes),
+		None => file.read_to_end(&mut bytes),
+	};
+	res.map_err(|err| Error::cc(format!("Cannot read file '{path}'"), err))?;
+
+	Ok(bytes)
+}
+
+/// The classic hexdump (`offset  hex bytes  |ascii|`), with `width` bytes per line,
+/// and the offsets starting at `base_offset` (e.g., the read offset).
+pub fn hexdump(bytes: &[u8], base_offset: u64, width: usize) -> String {
+	let width = width.max(1);
+	let mut out = String::new();
+
+	for (line_idx, chunk) in bytes.chunks(width).enumerate() {
+		let offset = base_offset + (line_idx * width) as u64;
+		out.push_str(&format!("{offset:08x}  "));
+
+		for idx in 0..width {
+			match chunk.get(idx) {
+				Some(byte) => out.push_str(&format!("{byte:02x} ")),
+				None => out.push_str("   "),
+			}
+			// Extra space at the half of the 16 bytes lines (as `hexdump -C`)
+			if width == 16 && idx == 7 {
+				out.push(' ');
+			}
+		}
+
+		out.push_str(" |");
+		out.extend(chunk.iter().map(|b| {
+			if b.is_ascii_graphic() || *b == b' ' {
+				*b as char
+			} else {
+				'.'
+			}
+		}));
+		out.push_str("|\n");
+	}
+
+	out
+}
+
+/// Unpack the bytes with the struct format (see the module doc), from the start of the bytes.
+///
+/// Returns the values (the pad bytes have none).
+pub fn unpack(fmt: &str, bytes: &[u8]) -> Result<Vec<BinValue>> {
+	let items: String = fmt.chars().filter(|c| !c.is_whitespace()).collect();
+
+	// NOTE: Without byte order prefix, native (but without the native alignment)
+	let (little_endian, items) = match items.chars().next() {
+		Some('<') => (true, &items[1..]),
+		Some('>' | '!') => (false, &items[1..]),
+		Some('=' | '@') => (cfg!(target_endian = "little"), &items[1..]),
+		_ => (cfg!(target_endian = "little"), items.as_str()),
+	};
+
+	unpack_items(fmt, items, bytes, little_endian)
+}
+
+// region:    --- Support
+
+fn unpack_items(fmt: &str, items: &str, bytes: &[u8], little_endian: bool) -> Result<Vec<BinValue>> {
+	let mut values = Vec::new();
+	let mut pos = 0;
+	let mut count: Option<usize> = None;
+
+	for code in items.chars() {
+		if let Some(digit) = code.to_digit(10) {
+			count = Some(count.unwrap_or(0) * 10 + digit as usize);
+			continue;
+		}
+		let n = count.take().unwrap_or(1);
+
+		// -- The bytes string (the count is its length)
+		if code == 's' {
+			let field = take(bytes, &mut pos, n, fmt)?;
+			values.push(BinValue::Bytes(field.to_vec()));
+			continue;
+		}
+
+		let size = match code {
+			'x' | 'c' | 'b' | 'B' | '?' => 1,
+			'h' | 'H' => 2,
+			'i' | 'I' | 'l' | 'L' | 'f' => 4,
+			'q' | 'Q' | 'd' => 8,
+			_ => {
+				return Err(Error::custom(format!(
+					"Invalid unpack format '{fmt}', unknown code '{code}'"
+				)));
+			}
+		};
+
+		for _ in 0..n {
+			let field = take(bytes, &mut pos, size, fmt)?;
+			if code == 'x' {
+				continue;
+			}
+			let raw = read_uint(field, little_endian);
+			let value = match code {
+				'c' => BinValue::Bytes(field.to_vec()),
+				'?' => BinValue::Bool(raw != 0),
+				'B' | 'H' | 'I' | 'L' | 'Q' => BinValue::UInt(raw),
+				'b' => BinValue::Int(raw as u8 as i8 as i64),
+				'h' => BinValue::Int(raw as u16 as i16 as i64),
+				'i' | 'l' => BinValue::Int(raw as u32 as i32 as i64),
+				'q' => BinValue::Int(raw as i64),
+				'f' => BinValue::Float(f32::from_bits(raw as u32) as f64),
+				// 'd'
+				_ => BinValue::Float(f64::from_bits(raw)),
+			};
+			values.push(value);
+		}
+	}
+
+	if count.is_some() {
+		return Err(Error::custom(format!(
+			"Invalid unpack format '{fmt}', count without a code"
+		)));
+	}
+
+	Ok(values)
+}
+
+fn take<'a>(bytes: &'a [u8], pos: &mut usize, size: usize, fmt: &str) -> Result<&'a [u8]> {
+	let end = pos.checked_add(size).unwrap_or(usize::MAX);
+	let field = bytes.get(*pos..end).ok_or_else(|| {
+		Error::custom(format!(
+			"Cannot unpack '{fmt}', it requires at least {end} bytes, but has {}",
+			bytes.len()
+		))
+	})?;
+	*pos = end;
+	Ok(field)
+}
+
+fn read_uint(field: &[u8], little_endian: bool) -> u64 {
+	if little_endian {
+		field.iter().rev().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+	} else {
+		field.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_bin_unpack_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		// ELF header start: magic, class 64, little-endian, version 1, then e_type 2 (LE u16)
+		let fx_bytes = [0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0xfe, 0xff, 2, 0, 0, 0, 0x80, 0x3f];
+
+		// -- Exec
+		let values = unpack("<4s3Bx hH f", &fx_bytes)?;
+
+		// -- Check
+		assert_eq!(
+			values,
+			vec![
+				BinValue::Bytes(b"\x7fELF".to_vec()),
+				BinValue::UInt(2),
+				BinValue::UInt(1),
+				BinValue::UInt(1),
+				BinValue::Int(-2),
+				BinValue::UInt(2),
+				BinValue::Float(1.0),
+			]
+		);
+		assert_eq!(unpack(">H", &[0x01, 0x02])?, vec![BinValue::UInt(0x0102)]);
+		assert!(unpack("<I", &[0x01, 0x02]).is_err());
+		assert!(unpack("<k", &[0x01]).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_bin_hexdump_simple() -> Result<()> {
+		// -- Exec
+		let dump = hexdump(b"Hello, aipack!\x00\x01\xff", 16, 16);
+
+		// -- Check
+		let lines: Vec<&str> = dump.lines().collect();
+		assert_eq!(
+			lines[0],
+			"00000010  48 65 6c 6c 6f 2c 20 61  69 70 61 63 6b 21 00 01  |Hello, aipack!..|"
+		);
+		assert_eq!(lines[1], format!("00000020  ff{}|.|", " ".repeat(48)));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,513 @@
+use crate::support::blob::sigv4::{
+	SigV4Creds, SigV4Request, canonical_query, presign_query, sha256_hex, sign_headers, uri_encode,
+};
+use crate::support::blob::{BlobProvider, BlobUrl};
+use crate::support::cred::get_secret;
+use crate::{Error, Result};
+use quick_xml::events::Event;
+use quick_xml::reader::Reader;
+use reqwest::{Client, Method, Response, header};
+use time::OffsetDateTime;
+
+/// The max validity of the presigned urls (SigV4 limit)
+pub const PRESIGN_MAX_EXPIRES_SECS: u64 = 7 * 24 * 3600;
+
+const GCS_ENDPOINT: &str = "https://storage.googleapis.com";
+
+/// The object storage client of a provider, with its credentials.
+#[derive(Debug, Clone)]
+pub struct BlobClient {
+	provider: BlobProvider,
+	access_key_id: String,
+	secret_access_key: String,
+	session_token: Option<String>,
+	region: String,
+	/// The S3 compatible endpoint (`AWS_ENDPOINT_URL`), requested path-style
+	endpoint: Option<String>,
+	client: Client,
+}
+
+/// A downloaded object
+#[derive(Debug)]
+pub struct BlobObject {
+	pub content: Vec<u8>,
+	pub content_type: Option<String>,
+	pub etag: Option<String>,
+}
+
+/// A listed object
+#[derive(Debug, Default)]
+pub struct BlobItem {
+	pub key: String,
+	pub size: u64,
+	pub last_modified: Option<String>,
+	pub etag: Option<String>,
+}
+
+/// Constructor
+impl BlobClient {
+	/// Create the client of the provider with the credentials of the environment variables
+	/// (or of the `aipack_secrets` keychain, with the same names).
+	///
+	/// - S3: `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, optional `AWS_SESSION_TOKEN`,
+	///   `AWS_REGION` (or `AWS_DEFAULT_REGION`, default `us-east-1`), and optional `AWS_ENDPOINT_URL` (S3 compatible services).
+	/// - GCS: `GCS_ACCESS_KEY_ID`, `GCS_SECRET_ACCESS_KEY` (HMAC keys of a service account).
+	pub fn new(provider: BlobProvider) -> Result<Self> {
+		let (access_key_env, secret_key_env) = match provider {
+			BlobProvider::S3 => ("AWS_ACCESS_KEY_ID", "AWS_SECRET_ACCESS_KEY"),
+			BlobProvider::Gcs => ("GCS_ACCESS_KEY_ID", "GCS_SECRET_ACCESS_KEY"),
+		};
+		let access_key_id =
+			get_env_or_secret(access_key_env).ok_or_else(|| missing_cred_err(provider, access_key_env))?;
+		let secret_access_key =
+			get_env_or_secret(secret_key_env).ok_or_else(|| missing_cred_err(provider, secret_key_env))?;
+
+		let (session_token, region, endpoint) = match provider {
+			BlobProvider::S3 => (
+				get_env_or_secret("AWS_SESSION_TOKEN"),
+				get_env("AWS_REGION")
+					.or_else(|| get_env("AWS_DEFAULT_REGION"))
+					.unwrap_or_else(|| "us-east-1".to_string()),
+				get_env("AWS_ENDPOINT_URL_S3")
+					.or_else(|| get_env("AWS_ENDPOINT_URL"))
+					.map(|endpoint| endpoint.trim_end_matches('/').to_string()),
+			),
+			BlobProvider::Gcs => (None, "auto".to_string(), Some(GCS_ENDPOINT.to_string())),
+		};
+
+		Ok(Self {
+			provider,
+			access_key_id,
+			secret_access_key,
+			session_token,
+			region,
+			endpoint,
+			client: Client::new(),
+		})
+	}
+}
+
+/// Operations
+impl BlobClient {
+	pub async fn get(&self, url: &BlobUrl) -> Result<BlobObject> {
+		let res = self.send(Method::GET, url, &[], None, None).await?;
+		let res = check_response(res, "get", url).await?;
+
+		let content_type = header_value(&res, header::CONTENT_TYPE);
+		let etag = header_value(&res, header::ETAG).map(|etag| etag.trim_matches('"').to_string());
+		let content = res
+			.bytes()
+			.await
+			.map_err(|err| Error::custom(format!("Cannot read the blob '{url}'. Cause: {err}")))?;
+
+		Ok(BlobObject {
+			content: content.to_vec(),
+			content_type,
+			etag,
+		})
+	}
+
+	/// Upload the object, returns its etag (if returned by the provider)
+	pub async fn put(&self, url: &BlobUrl, content: Vec<u8>, content_type: Option<&str>) -> Result<Option<String>> {
+		if url.key.is_empty() {
+			return Err(Error::custom(format!(
+				"Cannot put the blob '{url}'. The object key is missing"
+			)));
+		}
+		let res = self.send(Method::PUT, url, &[], Some(content), content_type).await?;
+		let res = check_response(res, "put", url).await?;
+
+		Ok(header_value(&res, header::ETAG).map(|etag| etag.trim_matches('"').to_string()))
+	}
+
+	/// List the objects of the url key prefix (up to `limit`)
+	pub async fn list(&self, url: &BlobUrl, limit: usize) -> Result<Vec<BlobItem>> {
+		let bucket_url = url.with_key("");
+		let mut items = Vec::new();
+		if limit == 0 {
+			return Ok(items);
+		}
+		let mut continuation_token: Option<String> = None;
+
+		loop {
+			let max_keys = (limit - items.len()).min(1000);
+			let mut query = vec![
+				("list-type".to_string(), "2".to_string()),
+				("max-keys".to_string(), max_keys.to_string()),
+			];
+			if !url.key.is_empty() {
+				query.push(("prefix".to_string(), url.key.clone()));
+			}
+			if let Some(token) = continuation_token.take() {
+				query.push(("continuation-token".to_string(), token));
+			}
+
+			let res = self.send(Method::GET, &bucket_url, &query, None, None).await?;
+			let res = check_response(res, "list", url).await?;
+			let xml = res
+				.text()
+				.await
+				.map_err(|err| Error::custom(format!("Cannot read the blob list of '{url}'. Cause: {err}")))?;
+			let page = parse_list_xml(&xml)?;
+
+			items.extend(page.items);
+			match page.next_continuation_token {
+				Some(token) if page.is_truncated && items.len() < limit => continuation_token = Some(token),
+				_ => break,
+			}
+		}
+
+		items.truncate(limit);
+		Ok(items)
+	}
+
+	/// Returns a url to `GET` or `PUT` the object without credentials, valid for `expires_secs`.
+	pub fn presign(&self, url: &BlobUrl, method: Method, expires_secs: u64) -> Result<String> {
+		if !(1..=PRESIGN_MAX_EXPIRES_SECS).contains(&expires_secs) {
+			return Err(Error::custom(format!(
+				"Cannot presign the blob '{url}'. The expiration must be between 1 and {PRESIGN_MAX_EXPIRES_SECS} seconds"
+			)));
+		}
+		let (base_url, host, canonical_uri) = self.request_location(url);
+		let req = SigV4Request {
+			method: method.as_str(),
+			host: &host,
+			canonical_uri: &canonical_uri,
+			query: &[],
+		};
+		let query = presign_query(&self.creds(), &req, expires_secs, OffsetDateTime::now_utc());
+
+		Ok(format!("{base_url}{canonical_uri}?{query}"))
+	}
+}
+
+// region:    --- Support
+
+impl BlobClient {
+	async fn send(
+		&self,
+		method: Method,
+		url: &BlobUrl,
+		query: &[(String, String)],
+		body: Option<Vec<u8>>,
+		content_type: Option<&str>,
+	) -> Result<Response> {
+		let (base_url, host, canonical_uri) = self.request_location(url);
+		let body = body.unwrap_or_default();
+
+		let req = SigV4Request {
+			method: method.as_str(),
+			host: &host,
+			canonical_uri: &canonical_uri,
+			query,
+		};
+		let mut headers: Vec<(String, String)> = Vec::new();
+		if let Some(content_type) = content_type {
+			headers.push(("content-type".to_string(), content_type.to_string()));
+		}
+		let payload_hash = sha256_hex(&body);
+		let authorization = sign_headers(
+			&self.creds(),
+			&req,
+			&mut headers,
+			&payload_hash,
+			OffsetDateTime::now_utc(),
+		);
+
+		let mut request_url = format!("{base_url}{canonical_uri}");
+		if !query.is_empty() {
+			request_url.push('?');
+			request_url.push_str(&canonical_query(query));
+		}
+
+		let mut builder = self
+			.client
+			.request(method, &request_url)
+			.header(header::AUTHORIZATION, authorization);
+		for (name, value) in headers {
+			builder = builder.header(name, value);
+		}
+		if !body.is_empty() {
+			builder = builder.body(body);
+		}
+
+		builder.send().await.map_err(|err| {
+			Error::custom(format!(
+				"Cannot reach the {} storage for '{url}'. Cause: {err}",
+				self.provider_name()
+			))
+		})
+	}
+
+	/// Returns `(base_url, host, canonical_uri)` of the object
+	/// (virtual-hosted style for AWS, path-style for the custom endpoints and the buckets with dots)
+	fn request_location(&self, url: &BlobUrl) -> (String, String, String) {
+		let key_path = format!("/{}", uri_encode(&url.key, false));
+		let bucket_path = format!("/{}", uri_encode(&url.bucket, true));
+		match &self.endpoint {
+			Some(endpoint) => {
+				let host = endpoint.split_once("://").map(|(_, host)| host).unwrap_or(endpoint);
+				let host = host.split('/').next().unwrap_or(host).to_string();
+				(endpoint.clone(), host, format!("{bucket_path}{key_path}"))
+			}
+			None if url.bucket.contains('.') => {
+				let host = format!("s3.{}.amazonaws.com", self.region);
+				(format!("https://{host}"), host, format!("{bucket_path}{key_path}"))
+			}
+			None => {
+				let host = format!("{}.s3.{}.amazonaws.com", url.bucket, self.region);
+				(format!("https://{host}"), host, key_path)
+			}
+		}
+	}
+
+	fn creds(&self) -> SigV4Creds<'_> {
+		SigV4Creds {
+			access_key_id: &self.access_key_id,
+			secret_access_key: &self.secret_access_key,
+			session_token: self.session_token.as_deref(),
+			region: &self.region,
+		}
+	}
+
+	fn provider_name(&self) -> &'static str {
+		match self.provider {
+			BlobProvider::S3 => "S3",
+			BlobProvider::Gcs => "GCS",
+		}
+	}
+}
+
+async fn check_response(res: Response, action: &str, url: &BlobUrl) -> Result<Response> {
+	if res.status().is_success() {
+		return Ok(res);
+	}
+	let status = res.status();
+	let body = res.text().await.unwrap_or_default();
+	let (code, message) = parse_error_xml(&body);
+	let cause = match (code, message) {
+		(Some(code), Some(message)) => format!("{code} - {message}"),
+		(Some(code), None) => code,
+		_ => status.to_string(),
+	};
+	Err(Error::custom(format!(
+		"Cannot {action} the blob '{url}' (HTTP {}). Cause: {cause}",
+		status.as_u16()
+	)))
+}
+
+fn header_value(res: &Response, name: header::HeaderName) -> Option<String> {
+	res.headers()
+		.get(name)
+		.and_then(|value| value.to_str().ok())
+		.map(|value| value.to_string())
+}
+
+fn get_env(name: &str) -> Option<String> {
+	std::env::var(name).ok().filter(|value| !value.trim().is_empty())
+}
+
+fn get_env_or_secret(name: &str) -> Option<String> {
+	get_env(name).or_else(|| get_secret(name))
+}
+
+fn missing_cred_err(provider: BlobProvider, name: &str) -> Error {
+	Error::custom(format!(
+		"Missing the '{name}' credential for the '{}://' blobs (environment variable, or keychain 'aipack_secrets/{name}')",
+		provider.scheme()
+	))
+}
+
+// endregion: --- Support
+
+// region:    --- XML Support
+
+struct ListPage {
+	items: Vec<BlobItem>,
+	is_truncated: bool,
+	next_continuation_token: Option<String>,
+}
+
+/// Parse the `ListObjectsV2` result
+fn parse_list_xml(xml: &str) -> Result<ListPage> {
+	let mut page = ListPage {
+		items: Vec::new(),
+		is_truncated: false,
+		next_continuation_token: None,
+	};
+
+	let mut current: Option<BlobItem> = None;
+	for (path, text) in xml_text_elements(xml)? {
+		match path.as_str() {
+			"ListBucketResult/IsTruncated" => page.is_truncated = text == "true",
+			"ListBucketResult/NextContinuationToken" => page.next_continuation_token = Some(text),
+			"ListBucketResult/Contents/Key" => {
+				if let Some(item) = current.take() {
+					page.items.push(item);
+				}
+				current = Some(BlobItem {
+					key: text,
+					..Default::default()
+				});
+			}
+			"ListBucketResult/Contents/Size" => {
+				if let Some(item) = current.as_mut() {
+					item.size = text.parse().unwrap_or_default();
+				}
+			}
+			"ListBucketResult/Contents/LastModified" => {
+				if let Some(item) = current.as_mut() {
+					item.last_modified = Some(text);
+				}
+			}
+			"ListBucketResult/Contents/ETag" => {
+				if let Some(item) = current.as_mut() {
+					item.etag = Some(text.trim_matches('"').to_string());
+				}
+			}
+			_ => (),
+		}
+	}
+	if let Some(item) = current.take() {
+		page.items.push(item);
+	}
+
+	Ok(page)
+}
+
+/// Returns the `(Code, Message)` of a error response
+fn parse_error_xml(xml: &str) -> (Option<String>, Option<String>) {
+	let mut code = None;
+	let mut message = None;
+	for (path, text) in xml_text_elements(xml).unwrap_or_default() {
+		match path.as_str() {
+			"Error/Code" => code = Some(text),
+			"Error/Message" => message = Some(text),
+			_ => (),
+		}
+	}
+	(code, message)
+}
+
+/// Returns the `(element path, text)` of the text elements (e.g., `("ListBucketResult/Contents/Key", "docs/a.md")`)
+fn xml_text_elements(xml: &str) -> Result<Vec<(String, String)>> {
+	let mut reader = Reader::from_str(xml);
+	let mut path: Vec<String> = Vec::new();
+	let mut text = String::new();
+	let mut res = Vec::new();
+
+	loop {
+		match reader.read_event() {
+			Ok(Event::Start(e)) => {
+				path.push(String::from_utf8_lossy(e.local_name().as_ref()).to_string());
+				text.clear();
+			}
+			Ok(Event::Text(e)) => {
+				let value = e
+					.decode()
+					.map_err(|err| Error::custom(format!("Invalid blob XML. Cause: {err}")))?;
+				text.push_str(&value);
+			}
+			Ok(Event::GeneralRef(e)) => {
+				if let Ok(Some(ch)) = e.resolve_char_ref() {
+					text.push(ch);
+				} else {
+					let name = e
+						.decode()
+						.map_err(|err| Error::custom(format!("Invalid blob XML. Cause: {err}")))?;
+					let entity = match name.as_ref() {
+						"quot" => "\"",
+						"amp" => "&",
+						"lt" => "<",
+						"gt" => ">",
+						"apos" => "'",
+						_ => "",
+					};
+					text.push_str(entity);
+				}
+			}
+			Ok(Event::End(_)) => {
+				if !text.is_empty() {
+					res.push((path.join("/"), std::mem::take(&mut text)));
+				}
+				path.pop();
+			}
+			Ok(Event::Eof) => break,
+			Ok(_) => (),
+			Err(err) => return Err(Error::custom(format!("Invalid blob XML. Cause: {err}"))),
+		}
+	}
+
+	Ok(res)
+}
+
+// endregion: --- XML Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_blob_client_parse_list_xml() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>my-bucket</Name>
+  <Prefix>docs/</Prefix>
+  <KeyCount>2</KeyCount>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken>
+  <Contents>
+    <Key>docs/a &amp; b.md</Key>
+    <LastModified>2026-10-01T10:00:00.000Z</LastModified>
+    <ETag>&quot;9b2cf535f27731c974343645a3985328&quot;</ETag>
+    <Size>120</Size>
+  </Contents>
+  <Contents>
+    <Key>docs/c.md</Key>
+    <Size>42</Size>
+  </Contents>
+</ListBucketResult>"#;
+
+		// -- Exec
+		let page = parse_list_xml(fx_xml)?;
+
+		// -- Check
+		assert!(page.is_truncated);
+		assert_eq!(
+			page.next_continuation_token.as_deref(),
+			Some("1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=")
+		);
+		assert_eq!(page.items.len(), 2);
+		assert_eq!(page.items[0].key, "docs/a & b.md");
+		assert_eq!(page.items[0].size, 120);
+		assert_eq!(page.items[0].etag.as_deref(), Some("9b2cf535f27731c974343645a3985328"));
+		assert_eq!(page.items[1].key, "docs/c.md");
+		assert_eq!(page.items[1].size, 42);
+		assert!(page.items[1].last_modified.is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_blob_client_parse_error_xml() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message><Key>a.md</Key></Error>"#;
+
+		// -- Exec
+		let (code, message) = parse_error_xml(fx_xml);
+
+		// -- Check
+		assert_eq!(code.as_deref(), Some("NoSuchKey"));
+		assert_eq!(message.as_deref(), Some("The specified key does not exist."));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
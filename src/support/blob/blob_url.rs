@@ -0,0 +1,104 @@
+use crate::{Error, Result};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobProvider {
+	/// `s3://` (AWS S3, or a S3 compatible service with `AWS_ENDPOINT_URL`)
+	S3,
+	/// `gs://` (Google Cloud Storage, with HMAC keys)
+	Gcs,
+}
+
+impl BlobProvider {
+	pub fn scheme(&self) -> &'static str {
+		match self {
+			BlobProvider::S3 => "s3",
+			BlobProvider::Gcs => "gs",
+		}
+	}
+}
+
+/// An object (or prefix) location, e.g., `s3://my-bucket/docs/report.md` or `gs://my-bucket/docs/`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobUrl {
+	pub provider: BlobProvider,
+	pub bucket: String,
+	/// The object key (or the list prefix), empty for the bucket root
+	pub key: String,
+}
+
+impl BlobUrl {
+	/// The url of another key of the same bucket (e.g., for the listed objects)
+	pub fn with_key(&self, key: impl Into<String>) -> BlobUrl {
+		BlobUrl {
+			provider: self.provider,
+			bucket: self.bucket.clone(),
+			key: key.into(),
+		}
+	}
+}
+
+impl FromStr for BlobUrl {
+	type Err = Error;
+
+	fn from_str(url: &str) -> Result<Self> {
+		let (provider, rest) = if let Some(rest) = url.strip_prefix("s3://") {
+			(BlobProvider::S3, rest)
+		} else if let Some(rest) = url.strip_prefix("gs://") {
+			(BlobProvider::Gcs, rest)
+		} else {
+			return Err(Error::custom(format!(
+				"Invalid blob url '{url}'. Must start with 's3://' or 'gs://' (e.g., 's3://my-bucket/path/file.md')"
+			)));
+		};
+
+		let (bucket, key) = rest.split_once('/').unwrap_or((rest, ""));
+		if bucket.is_empty() {
+			return Err(Error::custom(format!(
+				"Invalid blob url '{url}'. The bucket name is missing"
+			)));
+		}
+
+		Ok(BlobUrl {
+			provider,
+			bucket: bucket.to_string(),
+			key: key.to_string(),
+		})
+	}
+}
+
+impl std::fmt::Display for BlobUrl {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}://{}/{}", self.provider.scheme(), self.bucket, self.key)
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_blob_url_parse() -> Result<()> {
+		// -- Exec
+		let s3_url: BlobUrl = "s3://my-bucket/docs/report.md".parse()?;
+		let gs_root: BlobUrl = "gs://my-bucket".parse()?;
+
+		// -- Check
+		assert_eq!(s3_url.provider, BlobProvider::S3);
+		assert_eq!(s3_url.bucket, "my-bucket");
+		assert_eq!(s3_url.key, "docs/report.md");
+		assert_eq!(s3_url.to_string(), "s3://my-bucket/docs/report.md");
+		assert_eq!(gs_root.provider, BlobProvider::Gcs);
+		assert_eq!(gs_root.key, "");
+		assert!("https://my-bucket/a.md".parse::<BlobUrl>().is_err());
+		assert!("s3:///a.md".parse::<BlobUrl>().is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
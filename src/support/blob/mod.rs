@@ -0,0 +1,17 @@
+//! The object storage support (used by `aip.blob`), for the `s3://bucket/key` and `gs://bucket/key` objects.
+//!
+//! - The requests are signed with AWS SigV4 (see `sigv4`), which GCS also accepts with its HMAC keys (interoperability API).
+//! - The credentials come from the environment variables, or from the `aipack_secrets` keychain (see `BlobClient::new`).
+//! - The S3 compatible services (MinIO, R2, ...) are supported with `AWS_ENDPOINT_URL` (path-style requests).
+//!
+
+// region:    --- Modules
+
+mod blob_client;
+mod blob_url;
+mod sigv4;
+
+pub use blob_client::*;
+pub use blob_url::*;
+
+// endregion: --- Modules
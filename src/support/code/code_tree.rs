@@ -0,0 +1,322 @@
+//! The multi-language syntax tree (used by `aip.ts`), parsed with the tree-sitter grammars.
+//!
+//! The tree has the named nodes only (e.g., no punctuation), and the queries are the tree-sitter queries
+//! (e.g., `(function_item name: (identifier) @name) @fn`).
+
+use crate::{Error, Result};
+use serde::Serialize;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, StreamingIterator as _};
+
+/// A language of the `aip.ts` functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLang {
+	Rust,
+	JavaScript,
+	TypeScript,
+	Tsx,
+	Python,
+	Go,
+}
+
+impl CodeLang {
+	/// From the language name or file extension (e.g., `rust`, `rs`, `ts`, `py`).
+	pub fn from_name(name: &str) -> Result<Self> {
+		let lang = match name.trim().trim_start_matches('.').to_lowercase().as_str() {
+			"rust" | "rs" => Self::Rust,
+			"javascript" | "js" | "mjs" | "cjs" | "jsx" => Self::JavaScript,
+			"typescript" | "ts" | "mts" | "cts" => Self::TypeScript,
+			"tsx" => Self::Tsx,
+			"python" | "py" => Self::Python,
+			"go" | "golang" => Self::Go,
+			other => {
+				return Err(Error::custom(format!(
+					"Language '{other}' not supported (supported: rust, javascript, typescript, tsx, python, go)"
+				)));
+			}
+		};
+		Ok(lang)
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Rust => "rust",
+			Self::JavaScript => "javascript",
+			Self::TypeScript => "typescript",
+			Self::Tsx => "tsx",
+			Self::Python => "python",
+			Self::Go => "go",
+		}
+	}
+
+	fn language(&self) -> Language {
+		match self {
+			Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+			Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+			Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+			Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+			Self::Python => tree_sitter_python::LANGUAGE.into(),
+			Self::Go => tree_sitter_go::LANGUAGE.into(),
+		}
+	}
+}
+
+/// A syntax tree node
+#[derive(Debug, Clone, Serialize)]
+pub struct CodeNode {
+	/// The tree-sitter node kind (e.g., `source_file`, `function_item`, `impl_item`)
+	pub kind: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	/// 1-based, inclusive
+	pub start_line: usize,
+	pub end_line: usize,
+	pub text: String,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub children: Vec<CodeNode>,
+}
+
+/// A query capture (the capture name, without the `@`, and the node)
+#[derive(Debug, Clone)]
+pub struct CodeCapture {
+	pub name: String,
+	pub node: CodeNode,
+}
+
+/// A parsed code (the tree-sitter tree and its source)
+pub struct CodeTree {
+	lang: CodeLang,
+	code: String,
+	tree: tree_sitter::Tree,
+}
+
+/// Parse the code (`source_file` root for Rust and Go, `program` for JavaScript and TypeScript, `module` for Python).
+///
+/// NOTE: Returns an error if the code has a syntax error (tree-sitter would return a partial tree).
+pub fn parse_code(lang: CodeLang, code: &str) -> Result<CodeTree> {
+	let mut parser = Parser::new();
+	parser
+		.set_language(&lang.language())
+		.map_err(|err| Error::custom(format!("Cannot load the '{}' grammar. {err}", lang.name())))?;
+	let tree = parser
+		.parse(code, None)
+		.ok_or_else(|| Error::custom(format!("Cannot parse the '{}' code", lang.name())))?;
+
+	if let Some(error_node) = first_error_node(tree.root_node()) {
+		return Err(Error::custom(format!(
+			"Cannot parse the '{}' code, syntax error at line {}",
+			lang.name(),
+			error_node.start_position().row + 1
+		)));
+	}
+
+	Ok(CodeTree {
+		lang,
+		code: code.to_string(),
+		tree,
+	})
+}
+
+impl CodeTree {
+	/// The root node, with all of its named nodes.
+	pub fn root(&self) -> CodeNode {
+		self.code_node(self.tree.root_node())
+	}
+
+	/// The nodes of this kind, in the tree order (the nested ones as well).
+	pub fn find_kind(&self, kind: &str) -> Vec<CodeNode> {
+		let mut nodes = Vec::new();
+		let mut stack = vec![self.tree.root_node()];
+		while let Some(node) = stack.pop() {
+			if node.kind() == kind {
+				nodes.push(self.code_node(node));
+			}
+			let mut cursor = node.walk();
+			let children: Vec<Node> = node.named_children(&mut cursor).collect();
+			stack.extend(children.into_iter().rev());
+		}
+		nodes
+	}
+
+	/// Run the tree-sitter query (e.g., `(function_item) @fn (struct_item) @type`), and returns the captures in the tree order.
+	pub fn query(&self, query: &str) -> Result<Vec<CodeCapture>> {
+		let query = Query::new(&self.lang.language(), query)
+			.map_err(|err| Error::custom(format!("Invalid query '{query}'. {err}")))?;
+		let capture_names = query.capture_names();
+
+		let mut captures = Vec::new();
+		let mut cursor = QueryCursor::new();
+		let mut matches = cursor.captures(&query, self.tree.root_node(), self.code.as_bytes());
+		while let Some((query_match, capture_idx)) = matches.next() {
+			let capture = query_match.captures()[*capture_idx];
+			captures.push(CodeCapture {
+				name: capture_names[capture.index as usize].to_string(),
+				node: self.code_node(capture.node),
+			});
+		}
+
+		Ok(captures)
+	}
+
+	fn code_node(&self, node: Node) -> CodeNode {
+		let source = self.code.as_bytes();
+		let text = node.utf8_text(source).unwrap_or_default().to_string();
+
+		// the name (or, e.g., the self type of a Rust `impl`), when on one line
+		let name = node
+			.child_by_field_name("name")
+			.or_else(|| node.child_by_field_name("type"))
+			.and_then(|name| name.utf8_text(source).ok())
+			.filter(|name| !name.contains('\n'))
+			.map(|name| name.to_string());
+
+		let mut cursor = node.walk();
+		let children = node.named_children(&mut cursor).map(|child| self.code_node(child)).collect();
+
+		CodeNode {
+			kind: node.kind().to_string(),
+			name,
+			start_line: node.start_position().row + 1,
+			end_line: end_line(node),
+			text,
+			children,
+		}
+	}
+}
+
+// region:    --- Support
+
+/// The 1-based, inclusive, end line (a node ending with its new line ends on this line).
+fn end_line(node: Node) -> usize {
+	let (start, end) = (node.start_position(), node.end_position());
+	if end.column == 0 && end.row > start.row {
+		end.row
+	} else {
+		end.row + 1
+	}
+}
+
+fn first_error_node(root: Node) -> Option<Node> {
+	if !root.has_error() {
+		return None;
+	}
+	let mut stack = vec![root];
+	while let Some(node) = stack.pop() {
+		if node.is_error() || node.is_missing() {
+			return Some(node);
+		}
+		let mut cursor = node.walk();
+		let children: Vec<Node> = node.children(&mut cursor).filter(|child| child.has_error()).collect();
+		stack.extend(children.into_iter().rev());
+	}
+	Some(root)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	const FX_CODE: &str = r#"pub struct Foo;
+
+impl Foo {
+	pub fn new() -> Self {
+		Self
+	}
+}
+
+mod tests {
+	fn test_new() {}
+}
+
+fn main() {}
+"#;
+
+	#[test]
+	fn test_support_code_code_tree_parse_rust() -> Result<()> {
+		// -- Exec
+		let tree = parse_code(CodeLang::from_name("rs")?, FX_CODE)?;
+
+		// -- Check
+		let root = tree.root();
+		assert_eq!(root.kind, "source_file");
+		let kinds: Vec<&str> = root.children.iter().map(|n| n.kind.as_str()).collect();
+		assert_eq!(kinds, vec!["struct_item", "impl_item", "mod_item", "function_item"]);
+		assert_eq!(root.children[1].name.as_deref(), Some("Foo"));
+		let fns = tree.find_kind("function_item");
+		let fn_names: Vec<&str> = fns.iter().filter_map(|n| n.name.as_deref()).collect();
+		assert_eq!(fn_names, vec!["new", "test_new", "main"]);
+		assert_eq!((fns[0].start_line, fns[0].end_line), (4, 6));
+		assert!(parse_code(CodeLang::Rust, "fn main( {").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_code_code_tree_parse_other_langs() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_codes = [
+			(
+				"py",
+				"class Foo:\n    def new(self):\n        pass\n",
+				"function_definition",
+			),
+			("js", "function foo() {}\nclass Bar {}\n", "function_declaration"),
+			(
+				"ts",
+				"interface Foo { a: number }\nfunction foo(): void {}\n",
+				"function_declaration",
+			),
+			(
+				"tsx",
+				"const a = <div>{b}</div>;\nfunction foo() {}\n",
+				"function_declaration",
+			),
+			("go", "package main\n\nfunc main() {\n}\n", "function_declaration"),
+		];
+
+		for (lang, code, fn_kind) in fx_codes {
+			// -- Exec
+			let tree = parse_code(CodeLang::from_name(lang)?, code)?;
+
+			// -- Check
+			let fns = tree.find_kind(fn_kind);
+			assert_eq!(fns.len(), 1, "{lang} should have one {fn_kind}");
+			assert!(fns[0].name.is_some(), "{lang} {fn_kind} should have a name");
+		}
+		let err = parse_code(CodeLang::Python, "def a(:\n    pass\n")
+			.err()
+			.ok_or("Should have err")?;
+		assert!(err.to_string().contains("syntax error at line 1"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_code_code_tree_query() -> Result<()> {
+		// -- Setup & Fixtures
+		let tree = parse_code(CodeLang::Rust, FX_CODE)?;
+
+		// -- Exec
+		let captures = tree.query("(function_item) @fn (struct_item) @type")?;
+		let name_captures = tree.query("(impl_item (declaration_list (function_item name: (identifier) @name)))")?;
+
+		// -- Check
+		let names: Vec<String> = captures
+			.iter()
+			.map(|c| format!("{}:{}", c.name, c.node.name.as_deref().unwrap_or_default()))
+			.collect();
+		assert_eq!(names, vec!["type:Foo", "fn:new", "fn:test_new", "fn:main"]);
+		let names: Vec<&str> = name_captures.iter().map(|c| c.node.text.as_str()).collect();
+		assert_eq!(names, vec!["new"]);
+		assert!(tree.query("(not_a_kind) @x").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
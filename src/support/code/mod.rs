@@ -1,7 +1,11 @@
 // region:    --- Modules
 
+mod code_tree;
 mod rust;
+mod rust_items;
 
+pub use code_tree::*;
 pub use rust::*;
+pub use rust_items::*;
 
 // endregion: --- Modules
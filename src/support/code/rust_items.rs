@@ -0,0 +1,402 @@
+//! The Rust items extraction (used by `aip.rust`), with a real Rust parser (syn).
+//!
+//! The items are the top level ones, the inline `mod` ones, and the `impl` and `trait` fns,
+//! with their line spans (1-based, inclusive), doc comments, and source code (the full lines).
+
+use crate::{Error, Result};
+use proc_macro2::{LineColumn, Span};
+use serde::Serialize;
+use syn::spanned::Spanned as _;
+use syn::{Attribute, Expr, ImplItem, Item, Lit, Meta, TraitItem, Visibility};
+
+/// A Rust item of a source file
+#[derive(Debug, Clone, Serialize)]
+pub struct RustItem {
+	/// `fn`, `struct`, `enum`, `union`, `trait`, `impl`, `type`, `const`, `static`, `mod`, `macro`
+	pub kind: &'static str,
+	/// The item name (for `impl`, the self type, e.g., `Foo`, or `Display for Foo`)
+	pub name: String,
+	/// The path of the containing `mod`, `impl` self type, or `trait` (e.g., `tests`, `Foo`, `my_mod::Foo`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub parent: Option<String>,
+	pub start_line: usize,
+	pub end_line: usize,
+	/// The doc comments (`///` or `#[doc = "..."]`), without the comment markers
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub doc: Option<String>,
+	/// The fn signature, for the `fn` items (e.g., `pub async fn load(path: &str) -> Result<String>`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+	/// The source code of the item lines (including its doc comments and attributes)
+	pub code: String,
+}
+
+impl RustItem {
+	/// The name with its parent path (e.g., `Foo::new`)
+	pub fn qualified_name(&self) -> String {
+		match &self.parent {
+			Some(parent) => format!("{parent}::{}", self.name),
+			None => self.name.clone(),
+		}
+	}
+}
+
+/// Parse the Rust code and returns its items, in source order (an `impl` or `mod` before its items).
+pub fn parse_rust_items(content: &str) -> Result<Vec<RustItem>> {
+	let res = syn::parse_file(content)
+		.map_err(|err| {
+			let start = err.span().start();
+			Error::custom(format!(
+				"Cannot parse Rust code (line {}, column {}). {err}",
+				start.line,
+				start.column + 1
+			))
+		})
+		.map(|file| {
+			let source = Source::new(content);
+			let mut items = Vec::new();
+			collect_items(&source, &file.items, None, &mut items);
+			items
+		});
+
+	// NOTE: The parsed spans are kept in a thread local source map, released here as the items have been computed.
+	proc_macro2::extra::invalidate_current_thread_spans();
+
+	res
+}
+
+/// Returns the `fn` items matching the name, either the fn name (e.g., `new`),
+/// or the end of its qualified name (e.g., `Foo::new`, `tests::test_new`).
+pub fn find_rust_fns<'a>(items: &'a [RustItem], name: &str) -> Vec<&'a RustItem> {
+	let name = name.trim();
+	items
+		.iter()
+		.filter(|item| item.kind == "fn")
+		.filter(|item| {
+			if name.contains("::") {
+				let qualified_name = item.qualified_name();
+				qualified_name == name || qualified_name.ends_with(&format!("::{name}"))
+			} else {
+				item.name == name
+			}
+		})
+		.collect()
+}
+
+// region:    --- Collect
+
+fn collect_items(source: &Source, items: &[Item], parent: Option<&str>, out: &mut Vec<RustItem>) {
+	for item in items {
+		let (kind, name, attrs) = match item {
+			Item::Fn(item_fn) => {
+				let signature = fn_signature(source, &item_fn.vis, item_fn.sig.span(), Some(item_fn.block.span()));
+				out.push(source.item(
+					"fn",
+					item_fn.sig.ident.to_string(),
+					parent,
+					&item_fn.attrs,
+					item,
+					signature,
+				));
+				continue;
+			}
+			Item::Struct(v) => ("struct", v.ident.to_string(), &v.attrs),
+			Item::Enum(v) => ("enum", v.ident.to_string(), &v.attrs),
+			Item::Union(v) => ("union", v.ident.to_string(), &v.attrs),
+			Item::Type(v) => ("type", v.ident.to_string(), &v.attrs),
+			Item::Const(v) => ("const", v.ident.to_string(), &v.attrs),
+			Item::Static(v) => ("static", v.ident.to_string(), &v.attrs),
+			Item::Macro(v) => match &v.ident {
+				Some(ident) => ("macro", ident.to_string(), &v.attrs),
+				None => continue,
+			},
+			Item::Trait(item_trait) => {
+				let name = item_trait.ident.to_string();
+				out.push(source.item("trait", name.clone(), parent, &item_trait.attrs, item, None));
+
+				let trait_path = join_path(parent, &name);
+				for trait_item in item_trait.items.iter() {
+					if let TraitItem::Fn(trait_fn) = trait_item {
+						let block_span = trait_fn.default.as_ref().map(|block| block.span());
+						let signature = fn_signature(source, &Visibility::Inherited, trait_fn.sig.span(), block_span);
+						let name = trait_fn.sig.ident.to_string();
+						out.push(source.item("fn", name, Some(&trait_path), &trait_fn.attrs, trait_item, signature));
+					}
+				}
+				continue;
+			}
+			Item::Impl(item_impl) => {
+				let self_ty = source.text(item_impl.self_ty.span());
+				let name = match &item_impl.trait_ {
+					Some((bang, path, _)) => {
+						let bang = if bang.is_some() { "!" } else { "" };
+						format!("{bang}{} for {self_ty}", source.text(path.span()))
+					}
+					None => self_ty.clone(),
+				};
+				out.push(source.item("impl", name, parent, &item_impl.attrs, item, None));
+
+				let impl_path = join_path(parent, &self_ty);
+				for impl_item in item_impl.items.iter() {
+					if let ImplItem::Fn(impl_fn) = impl_item {
+						let signature =
+							fn_signature(source, &impl_fn.vis, impl_fn.sig.span(), Some(impl_fn.block.span()));
+						let name = impl_fn.sig.ident.to_string();
+						out.push(source.item("fn", name, Some(&impl_path), &impl_fn.attrs, impl_item, signature));
+					}
+				}
+				continue;
+			}
+			Item::Mod(item_mod) => {
+				let name = item_mod.ident.to_string();
+				out.push(source.item("mod", name.clone(), parent, &item_mod.attrs, item, None));
+
+				if let Some((_, mod_items)) = &item_mod.content {
+					let mod_path = join_path(parent, &name);
+					collect_items(source, mod_items, Some(&mod_path), out);
+				}
+				continue;
+			}
+			// `use`, `extern crate`, `extern` blocks, and the verbatim items
+			_ => continue,
+		};
+
+		out.push(source.item(kind, name, parent, attrs, item, None));
+	}
+}
+
+/// The fn signature source, from the visibility (or `fn` signature) to the body (or the end of the signature).
+fn fn_signature(source: &Source, vis: &Visibility, sig_span: Span, block_span: Option<Span>) -> Option<String> {
+	let start = match vis {
+		Visibility::Inherited => sig_span.start(),
+		_ => vis.span().start(),
+	};
+	let end = match block_span {
+		Some(block_span) => block_span.start(),
+		None => sig_span.end(),
+	};
+	let signature = source.text_between(start, end);
+	let signature = signature.trim();
+	(!signature.is_empty()).then(|| signature.to_string())
+}
+
+fn doc_comments(attrs: &[Attribute]) -> Option<String> {
+	let lines: Vec<String> = attrs
+		.iter()
+		.filter(|attr| attr.path().is_ident("doc"))
+		.filter_map(|attr| match &attr.meta {
+			Meta::NameValue(name_value) => match &name_value.value {
+				Expr::Lit(expr_lit) => match &expr_lit.lit {
+					Lit::Str(lit_str) => Some(lit_str.value()),
+					_ => None,
+				},
+				_ => None,
+			},
+			_ => None,
+		})
+		.flat_map(|doc| {
+			doc.lines()
+				.map(|line| line.strip_prefix(' ').unwrap_or(line).to_string())
+				.collect::<Vec<_>>()
+		})
+		.collect();
+
+	let doc = lines.join("\n");
+	let doc = doc.trim();
+	(!doc.is_empty()).then(|| doc.to_string())
+}
+
+fn join_path(parent: Option<&str>, name: &str) -> String {
+	match parent {
+		Some(parent) => format!("{parent}::{name}"),
+		None => name.to_string(),
+	}
+}
+
+// endregion: --- Collect
+
+// region:    --- Source
+
+/// The source content, with its line offsets (to get the text of the spans)
+struct Source<'a> {
+	content: &'a str,
+	line_starts: Vec<usize>,
+}
+
+impl<'a> Source<'a> {
+	fn new(content: &'a str) -> Self {
+		let line_starts = std::iter::once(0)
+			.chain(content.match_indices('\n').map(|(idx, _)| idx + 1))
+			.collect();
+		Self { content, line_starts }
+	}
+
+	fn item(
+		&self,
+		kind: &'static str,
+		name: String,
+		parent: Option<&str>,
+		attrs: &[Attribute],
+		spanned: &impl syn::spanned::Spanned,
+		signature: Option<String>,
+	) -> RustItem {
+		let span = spanned.span();
+		let start_line = span.start().line;
+		let end_line = span.end().line.max(start_line);
+
+		RustItem {
+			kind,
+			name,
+			parent: parent.map(|p| p.to_string()),
+			start_line,
+			end_line,
+			doc: doc_comments(attrs),
+			signature,
+			code: self.lines(start_line, end_line),
+		}
+	}
+
+	/// The full lines text (1-based, inclusive)
+	fn lines(&self, start_line: usize, end_line: usize) -> String {
+		let start = self.line_start(start_line);
+		let end = self.line_starts.get(end_line).copied().unwrap_or(self.content.len());
+		self.content[start..end].trim_end_matches(['\n', '\r']).to_string()
+	}
+
+	fn text(&self, span: Span) -> String {
+		self.text_between(span.start(), span.end()).to_string()
+	}
+
+	fn text_between(&self, start: LineColumn, end: LineColumn) -> &str {
+		let start = self.offset(start);
+		let end = self.offset(end).max(start);
+		&self.content[start..end]
+	}
+
+	fn line_start(&self, line: usize) -> usize {
+		self.line_starts
+			.get(line.saturating_sub(1))
+			.copied()
+			.unwrap_or(self.content.len())
+	}
+
+	/// The byte offset of the line column (the column is in chars)
+	fn offset(&self, line_column: LineColumn) -> usize {
+		let line_start = self.line_start(line_column.line);
+		let line = &self.content[line_start..];
+		let in_line = line
+			.char_indices()
+			.nth(line_column.column)
+			.map(|(idx, _)| idx)
+			.unwrap_or(line.len());
+		line_start + in_line
+	}
+}
+
+// endregion: --- Source
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	const FX_CODE: &str = r#"//! Module doc
+
+use std::fmt;
+
+/// The Foo
+/// (with the name)
+#[derive(Debug)]
+pub struct Foo {
+	name: String,
+}
+
+impl Foo {
+	/// Create a Foo
+	pub fn new(name: impl Into<String>) -> Self {
+		Self { name: name.into() }
+	}
+}
+
+impl fmt::Display for Foo {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Foo({})", self.name)
+	}
+}
+
+pub async fn load(path: &str) -> std::io::Result<String> {
+	tokio::fs::read_to_string(path).await
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_new() {}
+}
+"#;
+
+	#[test]
+	fn test_support_code_rust_items_parse_simple() -> Result<()> {
+		// -- Exec
+		let items = parse_rust_items(FX_CODE)?;
+
+		// -- Check
+		let names: Vec<String> = items.iter().map(|i| format!("{} {}", i.kind, i.qualified_name())).collect();
+		assert_eq!(
+			names,
+			vec![
+				"struct Foo",
+				"impl Foo",
+				"fn Foo::new",
+				"impl fmt::Display for Foo",
+				"fn Foo::fmt",
+				"fn load",
+				"mod tests",
+				"fn tests::test_new"
+			]
+		);
+
+		let foo = &items[0];
+		assert_eq!((foo.start_line, foo.end_line), (5, 10));
+		assert_eq!(foo.doc.as_deref(), Some("The Foo\n(with the name)"));
+		assert!(foo.code.starts_with("/// The Foo\n"));
+		assert!(foo.code.ends_with("\tname: String,\n}"));
+
+		let new_fn = &items[2];
+		assert_eq!((new_fn.start_line, new_fn.end_line), (13, 16));
+		assert_eq!(new_fn.doc.as_deref(), Some("Create a Foo"));
+		assert_eq!(
+			new_fn.signature.as_deref(),
+			Some("pub fn new(name: impl Into<String>) -> Self")
+		);
+		assert!(new_fn.code.starts_with("\t/// Create a Foo\n\tpub fn new("));
+
+		let load_fn = &items[5];
+		assert_eq!(
+			load_fn.signature.as_deref(),
+			Some("pub async fn load(path: &str) -> std::io::Result<String>")
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_code_rust_items_find_fns() -> Result<()> {
+		// -- Setup & Fixtures
+		let items = parse_rust_items(FX_CODE)?;
+
+		// -- Exec & Check
+		assert_eq!(find_rust_fns(&items, "new").len(), 1);
+		assert_eq!(find_rust_fns(&items, "Foo::fmt")[0].start_line, 20);
+		assert_eq!(find_rust_fns(&items, "tests::test_new").len(), 1);
+		assert!(find_rust_fns(&items, "Bar::new").is_empty());
+		assert!(parse_rust_items("fn broken( {").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
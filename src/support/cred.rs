@@ -20,6 +20,12 @@ pub fn get_or_prompt_api_key(key_name: &str) -> Result<String> {
 	Ok(api_key)
 }
 
+/// Get a secret from the local keychain (`aipack_secrets/<name>`), without prompting
+/// (None when absent, or when no keychain is available).
+pub fn get_secret(name: &str) -> Option<String> {
+	Entry::new(KEY_SERVICE, name).ok()?.get_password().ok()
+}
+
 // region:    --- Support
 
 // Get the value from the local keychain, or prompt the user to save and return.
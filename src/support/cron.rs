@@ -0,0 +1,218 @@
+//! Minimal cron expressions (used by `aip schedule`).
+//!
+//! The 5 standard fields `minute hour day-of-month month day-of-week`, each one with
+//! `*`, numbers, lists (`1,15`), ranges (`1-5`), and steps (`*/15`, `0-30/10`),
+//! or the `@hourly`, `@daily`, `@weekly`, `@monthly`, `@yearly` shortcuts.
+//!
+//! As in the standard cron, when both the day-of-month and the day-of-week are restricted,
+//! a day matching either of them matches.
+
+use crate::{Error, Result};
+use std::str::FromStr;
+use time::{Duration, OffsetDateTime};
+
+/// The max minutes looked ahead for the next match (5 years, so that the Feb 29 expressions match)
+const MAX_LOOKAHEAD_MINUTES: i64 = 60 * 24 * 366 * 5;
+
+#[derive(Debug, Clone)]
+pub struct CronExpr {
+	minutes: u64,
+	hours: u64,
+	days: u64,
+	months: u64,
+	weekdays: u64,
+	days_star: bool,
+	weekdays_star: bool,
+}
+
+impl CronExpr {
+	/// Returns true if the date time minute matches the expression (the seconds are ignored)
+	pub fn matches(&self, dt: &OffsetDateTime) -> bool {
+		let day_ok = has_bit(self.days, dt.day() as u32);
+		let weekday_ok = has_bit(self.weekdays, dt.weekday().number_days_from_sunday() as u32);
+		let day_match = match (self.days_star, self.weekdays_star) {
+			(false, false) => day_ok || weekday_ok,
+			_ => day_ok && weekday_ok,
+		};
+
+		day_match
+			&& has_bit(self.minutes, dt.minute() as u32)
+			&& has_bit(self.hours, dt.hour() as u32)
+			&& has_bit(self.months, dt.month() as u32)
+	}
+
+	/// Returns the next matching minute strictly after the date time (None if none in the next years)
+	pub fn next_after(&self, dt: &OffsetDateTime) -> Option<OffsetDateTime> {
+		let start = dt.replace_second(0).ok()?.replace_nanosecond(0).ok()?;
+		(1..=MAX_LOOKAHEAD_MINUTES)
+			.map(|minutes| start + Duration::minutes(minutes))
+			.find(|candidate| self.matches(candidate))
+	}
+}
+
+impl FromStr for CronExpr {
+	type Err = Error;
+
+	fn from_str(expr: &str) -> Result<Self> {
+		let expr = match expr.trim() {
+			"@hourly" => "0 * * * *",
+			"@daily" | "@midnight" => "0 0 * * *",
+			"@weekly" => "0 0 * * 0",
+			"@monthly" => "0 0 1 * *",
+			"@yearly" | "@annually" => "0 0 1 1 *",
+			other => other,
+		};
+
+		let fields: Vec<&str> = expr.split_whitespace().collect();
+		let [minute, hour, day, month, weekday] = fields.as_slice() else {
+			return Err(Error::custom(format!(
+				"Cron expression '{expr}' is invalid.\nCause: must have 5 fields 'minute hour day-of-month month day-of-week' (e.g., '0 9 * * 1')"
+			)));
+		};
+
+		let parse = |name: &str, field: &str, min: u32, max: u32| {
+			parse_field(field, min, max).map_err(|cause| {
+				Error::custom(format!(
+					"Cron expression '{expr}' is invalid.\nCause: {name} field '{field}' {cause}"
+				))
+			})
+		};
+
+		// NOTE: The day-of-week 7 is also Sunday
+		let mut weekdays = parse("day-of-week", weekday, 0, 7)?;
+		if has_bit(weekdays, 7) {
+			weekdays |= 1;
+		}
+
+		Ok(CronExpr {
+			minutes: parse("minute", minute, 0, 59)?,
+			hours: parse("hour", hour, 0, 23)?,
+			days: parse("day-of-month", day, 1, 31)?,
+			months: parse("month", month, 1, 12)?,
+			weekdays,
+			days_star: day.starts_with('*'),
+			weekdays_star: weekday.starts_with('*'),
+		})
+	}
+}
+
+// region:    --- Support
+
+fn has_bit(bits: u64, num: u32) -> bool {
+	bits & (1 << num) != 0
+}
+
+/// Parse a field into its bit set (bit n set when n matches)
+fn parse_field(field: &str, min: u32, max: u32) -> core::result::Result<u64, String> {
+	let mut bits = 0;
+
+	for part in field.split(',') {
+		let (range, step) = match part.split_once('/') {
+			Some((range, step)) => {
+				let step: u32 = step.parse().map_err(|_| format!("has an invalid step '{step}'"))?;
+				if step == 0 {
+					return Err("has a step of 0".to_string());
+				}
+				(range, step)
+			}
+			None => (part, 1),
+		};
+
+		let parse_num = |num: &str| -> core::result::Result<u32, String> {
+			let num: u32 = num.parse().map_err(|_| format!("has an invalid value '{num}'"))?;
+			if num < min || num > max {
+				return Err(format!("value '{num}' is not within {min}-{max}"));
+			}
+			Ok(num)
+		};
+
+		let (start, end) = match range {
+			"*" => (min, max),
+			range => match range.split_once('-') {
+				Some((start, end)) => (parse_num(start)?, parse_num(end)?),
+				// e.g., `5/15` is from 5 to the max
+				None if step > 1 => (parse_num(range)?, max),
+				None => {
+					let num = parse_num(range)?;
+					(num, num)
+				}
+			},
+		};
+		if start > end {
+			return Err(format!("has an invalid range '{range}'"));
+		}
+
+		for num in (start..=end).step_by(step as usize) {
+			bits |= 1 << num;
+		}
+	}
+
+	Ok(bits)
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use time::{Date, Month, PrimitiveDateTime, Time};
+
+	fn dt(day: u8, hour: u8, minute: u8) -> Result<OffsetDateTime> {
+		let date = Date::from_calendar_date(2025, Month::January, day)?;
+		Ok(PrimitiveDateTime::new(date, Time::from_hms(hour, minute, 0)?).assume_utc())
+	}
+
+	#[test]
+	fn test_support_cron_matches() -> Result<()> {
+		// -- Setup & Fixtures
+		// 2025-01-06 is a Monday
+		let monday_9 = dt(6, 9, 0)?;
+		let tuesday_9 = dt(7, 9, 0)?;
+
+		// -- Exec
+		let weekly: CronExpr = "0 9 * * 1".parse()?;
+		let every_15: CronExpr = "*/15 8-18 * * 1-5".parse()?;
+		let day_or_weekday: CronExpr = "0 9 15 * 1".parse()?;
+
+		// -- Check
+		assert!(weekly.matches(&monday_9));
+		assert!(!weekly.matches(&tuesday_9));
+		assert!(!weekly.matches(&dt(6, 9, 1)?));
+		assert!(every_15.matches(&dt(7, 10, 45)?));
+		assert!(!every_15.matches(&dt(11, 10, 45)?), "saturday");
+		assert!(day_or_weekday.matches(&monday_9));
+		assert!(day_or_weekday.matches(&dt(15, 9, 0)?), "day 15 (a wednesday)");
+		assert!(!day_or_weekday.matches(&tuesday_9));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_cron_next_after_and_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let weekly: CronExpr = "0 9 * * 1".parse()?;
+		let daily: CronExpr = "@daily".parse()?;
+
+		// -- Exec
+		let next = weekly
+			.next_after(&(dt(6, 9, 0)? + Duration::seconds(30)))
+			.ok_or("Should have next")?;
+		let next_daily = daily.next_after(&dt(6, 9, 0)?).ok_or("Should have next")?;
+
+		// -- Check
+		assert_eq!(next, dt(13, 9, 0)?);
+		assert_eq!(next_daily, dt(7, 0, 0)?);
+		assert!("0 9 * *".parse::<CronExpr>().is_err());
+		assert!("60 9 * * *".parse::<CronExpr>().is_err());
+		assert!("*/0 * * * *".parse::<CronExpr>().is_err());
+		assert!("0 9 * * 1-x".parse::<CronExpr>().is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
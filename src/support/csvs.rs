@@ -1,6 +1,8 @@
 use crate::types::{CsvContent, CsvOptions};
 use crate::{Error, Result};
+use encoding_rs::{CoderResult, Decoder, Encoding, UTF_8};
 use std::collections::HashMap;
+use std::io::{Read, Write as _};
 use std::path::Path;
 
 pub fn save_csv(path: impl AsRef<Path>, content: &CsvContent, options: Option<CsvOptions>) -> Result<()> {
@@ -21,19 +23,36 @@ fn write_csv(path: impl AsRef<Path>, content: &CsvContent, options: Option<CsvOp
 		remap_keys_to_labels(&mut headers, labels);
 	}
 
-	// Determine if we write headers
-	let file_exists = path.exists();
+	// Determine if we write headers (an empty file is considered new)
+	let file_exists = std::fs::metadata(path).is_ok_and(|meta| meta.len() > 0);
 	let write_headers = if append && file_exists {
 		false
 	} else {
 		!options.skip_header_row.unwrap_or(false) && !headers.is_empty()
 	};
+	let encoding = get_encoding(options.encoding.as_deref())?;
 
-	// Setup writer
+	// Setup writer (buffered, so that it can be encoded before being written)
 	let builder = options.into_writer_builder();
+	let mut wtr = builder.from_writer(Vec::new());
+
+	if write_headers {
+		wtr.write_record(&headers)
+			.map_err(|e| Error::custom(format!("Failed to write headers: {e}")))?;
+	}
+
+	for row in &content.rows {
+		wtr.write_record(row)
+			.map_err(|e| Error::custom(format!("Failed to write row: {e}")))?;
+	}
+
+	let bytes = wtr
+		.into_inner()
+		.map_err(|e| Error::custom(format!("Failed to retrieve CSV buffer: {e}")))?;
+	let bytes = encode_csv_bytes(bytes, encoding)?;
 
 	// If append, we need to open in append mode.
-	let file = if append {
+	let mut file = if append {
 		simple_fs::ensure_file_dir(path)?;
 		std::fs::OpenOptions::new()
 			.create(true)
@@ -46,20 +65,8 @@ fn write_csv(path: impl AsRef<Path>, content: &CsvContent, options: Option<CsvOp
 			.map_err(|e| Error::custom(format!("Failed to create CSV file '{}': {e}", path.display())))?
 	};
 
-	let mut wtr = builder.from_writer(file);
-
-	if write_headers {
-		wtr.write_record(&headers)
-			.map_err(|e| Error::custom(format!("Failed to write headers: {e}")))?;
-	}
-
-	for row in &content.rows {
-		wtr.write_record(row)
-			.map_err(|e| Error::custom(format!("Failed to write row: {e}")))?;
-	}
-
-	wtr.flush()
-		.map_err(|e| Error::custom(format!("Failed to flush CSV writer: {e}")))?;
+	file.write_all(&bytes)
+		.map_err(|e| Error::custom(format!("Failed to write CSV file '{}': {e}", path.display())))?;
 
 	Ok(())
 }
@@ -154,7 +161,14 @@ pub fn parse_csv(content: &str, options: Option<CsvOptions>) -> Result<CsvConten
 
 pub fn load_csv(path: impl AsRef<Path>, options: Option<CsvOptions>) -> Result<CsvContent> {
 	let path = path.as_ref();
-	let content = simple_fs::read_to_string(path)?;
+	let encoding = get_encoding(options.as_ref().and_then(|o| o.encoding.as_deref()))?;
+	let content = if encoding == UTF_8 {
+		simple_fs::read_to_string(path)?
+	} else {
+		let bytes = std::fs::read(path)
+			.map_err(|e| Error::custom(format!("Failed to read CSV file '{}': {e}", path.display())))?;
+		encoding.decode(&bytes).0.into_owned()
+	};
 
 	parse_csv(&content, options)
 		.map_err(|e| Error::custom(format!("Failed to parse CSV file '{}': {}", path.display(), e)))
@@ -163,30 +177,11 @@ pub fn load_csv(path: impl AsRef<Path>, options: Option<CsvOptions>) -> Result<C
 pub fn load_csv_headers(path: impl AsRef<Path>, options: Option<CsvOptions>) -> Result<Vec<String>> {
 	let path = path.as_ref();
 	let mut options = options.unwrap_or_default();
+	options.has_header = Some(true);
 
-	let header_labels = options.header_labels.take();
-
-	let mut builder = options.into_reader_builder();
-	builder.has_headers(true);
-
-	let file = std::fs::File::open(path)
-		.map_err(|e| Error::custom(format!("Failed to open CSV file '{}': {e}", path.display())))?;
-	let mut rdr = builder.from_reader(file);
-
-	let headers = rdr.headers().map_err(|e| {
-		Error::custom(format!(
-			"Failed to read CSV headers from file '{}': {e}",
-			path.display()
-		))
-	})?;
+	let reader = CsvRowReader::open(path, Some(options))?;
 
-	let mut headers: Vec<String> = headers.iter().map(|s| s.to_string()).collect();
-
-	if let Some(labels) = &header_labels {
-		remap_labels_to_keys(&mut headers, labels);
-	}
-
-	Ok(headers)
+	Ok(reader.headers().map(|headers| headers.to_vec()).unwrap_or_default())
 }
 
 /// Remap CSV headers (labels) to internal keys.
@@ -219,6 +214,176 @@ pub fn remap_keys_to_labels(headers: &mut [String], header_labels: &HashMap<Stri
 	}
 }
 
+// region:    --- CsvRowReader
+
+/// The incremental CSV file reader (one row at a time, without loading the whole file).
+///
+/// Honors the `CsvOptions` (with `has_header` default true, like `load_csv`).
+pub struct CsvRowReader {
+	rdr: csv::Reader<Box<dyn Read + Send>>,
+	/// The headers (remapped with the `header_labels`), when `has_header`
+	headers: Option<Vec<String>>,
+	skip_empty_lines: bool,
+	record: csv::StringRecord,
+}
+
+impl CsvRowReader {
+	pub fn open(path: impl AsRef<Path>, options: Option<CsvOptions>) -> Result<Self> {
+		let path = path.as_ref();
+		let mut options = options.unwrap_or_default();
+		let has_header = options.has_header.unwrap_or(true);
+		let skip_empty_lines = options.skip_empty_lines.unwrap_or(true);
+		let header_labels = options.header_labels.take();
+		let encoding = get_encoding(options.encoding.as_deref())?;
+
+		let file = std::fs::File::open(path)
+			.map_err(|e| Error::custom(format!("Failed to open CSV file '{}': {e}", path.display())))?;
+		let source: Box<dyn Read + Send> = if encoding == UTF_8 {
+			Box::new(file)
+		} else {
+			Box::new(DecodeReader::new(file, encoding))
+		};
+
+		let mut builder = options.into_reader_builder();
+		builder.has_headers(has_header).flexible(true);
+		let mut rdr = builder.from_reader(source);
+
+		let headers = if has_header {
+			let hdr = rdr.headers().map_err(|e| {
+				Error::custom(format!(
+					"Failed to read CSV headers from file '{}': {e}",
+					path.display()
+				))
+			})?;
+			let mut headers: Vec<String> = hdr.iter().map(|s| s.to_string()).collect();
+			if let Some(labels) = &header_labels {
+				remap_labels_to_keys(&mut headers, labels);
+			}
+			Some(headers)
+		} else {
+			None
+		};
+
+		Ok(Self {
+			rdr,
+			headers,
+			skip_empty_lines,
+			record: csv::StringRecord::new(),
+		})
+	}
+
+	pub fn headers(&self) -> Option<&[String]> {
+		self.headers.as_deref()
+	}
+
+	/// Returns the next row, or `None` at the end of the file.
+	pub fn next_row(&mut self) -> Result<Option<Vec<String>>> {
+		loop {
+			let has_record = self
+				.rdr
+				.read_record(&mut self.record)
+				.map_err(|e| Error::custom(format!("Failed to read CSV record: {e}")))?;
+			if !has_record {
+				return Ok(None);
+			}
+			if self.skip_empty_lines && self.record.iter().all(|s| s.trim().is_empty()) {
+				continue;
+			}
+			return Ok(Some(self.record.iter().map(|s| s.to_string()).collect()));
+		}
+	}
+}
+
+/// Decodes the inner reader bytes (of the encoding) to UTF-8, chunk by chunk.
+struct DecodeReader<R> {
+	inner: R,
+	decoder: Decoder,
+	in_buf: Vec<u8>,
+	in_start: usize,
+	in_end: usize,
+	out_buf: Vec<u8>,
+	out_start: usize,
+	out_end: usize,
+	eof: bool,
+	done: bool,
+}
+
+impl<R: Read> DecodeReader<R> {
+	fn new(inner: R, encoding: &'static Encoding) -> Self {
+		let decoder = encoding.new_decoder();
+		let out_len = decoder.max_utf8_buffer_length(8 * 1024).unwrap_or(32 * 1024);
+		Self {
+			inner,
+			decoder,
+			in_buf: vec![0; 8 * 1024],
+			in_start: 0,
+			in_end: 0,
+			out_buf: vec![0; out_len],
+			out_start: 0,
+			out_end: 0,
+			eof: false,
+			done: false,
+		}
+	}
+}
+
+impl<R: Read> Read for DecodeReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+		while self.out_start == self.out_end && !self.done {
+			if self.in_start == self.in_end && !self.eof {
+				self.in_end = self.inner.read(&mut self.in_buf)?;
+				self.in_start = 0;
+				self.eof = self.in_end == 0;
+			}
+			let (res, read, written, _) =
+				self.decoder
+					.decode_to_utf8(&self.in_buf[self.in_start..self.in_end], &mut self.out_buf, self.eof);
+			self.in_start += read;
+			self.out_start = 0;
+			self.out_end = written;
+			self.done = self.eof && res == CoderResult::InputEmpty;
+		}
+
+		let len = buf.len().min(self.out_end - self.out_start);
+		buf[..len].copy_from_slice(&self.out_buf[self.out_start..self.out_start + len]);
+		self.out_start += len;
+		Ok(len)
+	}
+}
+
+// endregion: --- CsvRowReader
+
+// region:    --- Support
+
+fn get_encoding(label: Option<&str>) -> Result<&'static Encoding> {
+	match label {
+		None => Ok(UTF_8),
+		Some(label) => Encoding::for_label(label.trim().as_bytes())
+			.ok_or_else(|| Error::custom(format!("CSV encoding '{label}' is not supported"))),
+	}
+}
+
+/// Encode the CSV (UTF-8) bytes to the encoding.
+///
+/// NOTE: The UTF-16 encodings are decode only (they are written as UTF-8, per the encoding standard).
+fn encode_csv_bytes(bytes: Vec<u8>, encoding: &'static Encoding) -> Result<Vec<u8>> {
+	if encoding == UTF_8 {
+		return Ok(bytes);
+	}
+	let text =
+		String::from_utf8(bytes).map_err(|e| Error::custom(format!("Failed to convert CSV buffer to UTF-8: {e}")))?;
+	let (encoded, _, had_errors) = encoding.encode(&text);
+	if had_errors {
+		return Err(Error::custom(format!(
+			"Some CSV characters cannot be encoded in '{}'",
+			encoding.name()
+		)));
+	}
+	Ok(encoded.into_owned())
+}
+
+// endregion: --- Support
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -137,3 +137,34 @@ fn test_support_csv_load_headers_remapped() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+fn test_support_csv_row_reader_encoding() -> Result<()> {
+	// -- Setup & Fixtures
+	let fx_path = SPath::new("tests-data/sandbox-01/.tmp/test_support_csv_row_reader_encoding.csv");
+	let options = CsvOptions {
+		encoding: Some("latin1".to_string()),
+		..Default::default()
+	};
+	let content = CsvContent {
+		headers: vec!["name".to_string(), "city".to_string()],
+		rows: vec![vec!["José".to_string(), "Zürich".to_string()]],
+	};
+	save_csv(&fx_path, &content, Some(options.clone()))?;
+
+	// -- Exec
+	let bytes = std::fs::read(fx_path.as_std_path())?;
+	let mut reader = CsvRowReader::open(&fx_path, Some(options))?;
+	let headers = reader.headers().map(|h| h.to_vec());
+	let row = reader.next_row()?;
+	let end = reader.next_row()?;
+
+	// -- Check
+	assert_eq!(bytes, b"name,city\nJos\xe9,Z\xfcrich\n");
+	assert_eq!(headers.ok_or("Should have headers")?.x_as_strs(), vec!["name", "city"]);
+	assert_eq!(row.ok_or("Should have row")?.x_as_strs(), vec!["José", "Zürich"]);
+	assert!(end.is_none());
+
+	std::fs::remove_file(fx_path.as_std_path())?;
+	Ok(())
+}
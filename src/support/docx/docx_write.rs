@@ -0,0 +1,527 @@
+//! The DOCX (Word) writer (used by `aip.file.save_docx`).
+//!
+//! The document is a list of blocks (headings, paragraphs, list items, code blocks, quotes, and tables),
+//! given directly or converted from markdown (with `md_to_docx_blocks`).
+
+use crate::{Error, Result};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use std::io::Write as _;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// A text run, with its inline styles
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocxRun {
+	pub text: String,
+	pub bold: bool,
+	pub italic: bool,
+	pub strike: bool,
+	pub code: bool,
+	/// The hyperlink url
+	pub link: Option<String>,
+}
+
+impl DocxRun {
+	pub fn plain(text: impl Into<String>) -> Self {
+		Self {
+			text: text.into(),
+			..Default::default()
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocxBlock {
+	/// `level` from 1 to 6
+	Heading {
+		level: u8,
+		runs: Vec<DocxRun>,
+	},
+	Paragraph {
+		runs: Vec<DocxRun>,
+	},
+	Quote {
+		runs: Vec<DocxRun>,
+	},
+	/// `list_id` identifies the list (the ordered lists restart their numbering for each list), `level` starts at 0
+	ListItem {
+		ordered: bool,
+		list_id: usize,
+		level: u8,
+		runs: Vec<DocxRun>,
+	},
+	Code {
+		text: String,
+	},
+	/// The first row is the header row
+	Table {
+		rows: Vec<Vec<Vec<DocxRun>>>,
+	},
+}
+
+// region:    --- Markdown
+
+/// Convert the markdown (CommonMark with the GFM tables and strikethrough) to the docx blocks.
+///
+/// NOTE: The images are written as their alt text, and the html as text.
+pub fn md_to_docx_blocks(md: &str) -> Vec<DocxBlock> {
+	let parser = Parser::new_ext(md, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+
+	let mut blocks: Vec<DocxBlock> = Vec::new();
+	let mut runs: Vec<DocxRun> = Vec::new();
+	let mut style = DocxRun::default();
+
+	// (ordered, list_id)
+	let mut lists: Vec<(bool, usize)> = Vec::new();
+	let mut list_count = 0;
+	let mut quote_depth = 0;
+	let mut code: Option<String> = None;
+	let mut table_rows: Vec<Vec<Vec<DocxRun>>> = Vec::new();
+	let mut table_row: Vec<Vec<DocxRun>> = Vec::new();
+
+	// Push the pending runs as a paragraph (list item or quote, depending on the context)
+	let flush = |blocks: &mut Vec<DocxBlock>, runs: &mut Vec<DocxRun>, lists: &[(bool, usize)], quote_depth: usize| {
+		if runs.iter().all(|run| run.text.trim().is_empty()) {
+			runs.clear();
+			return;
+		}
+		let runs = std::mem::take(runs);
+		let block = match lists.last() {
+			Some((ordered, list_id)) => DocxBlock::ListItem {
+				ordered: *ordered,
+				list_id: *list_id,
+				level: (lists.len() - 1) as u8,
+				runs,
+			},
+			None if quote_depth > 0 => DocxBlock::Quote { runs },
+			None => DocxBlock::Paragraph { runs },
+		};
+		blocks.push(block);
+	};
+
+	for event in parser {
+		match event {
+			// -- Blocks
+			// A paragraph in a list item can follow the (tight) item text
+			Event::Start(Tag::Heading { .. }) | Event::Start(Tag::Paragraph) if !runs.is_empty() => {
+				flush(&mut blocks, &mut runs, &lists, quote_depth);
+			}
+			Event::End(TagEnd::Heading(level)) => {
+				blocks.push(DocxBlock::Heading {
+					level: level as u8,
+					runs: std::mem::take(&mut runs),
+				});
+			}
+			Event::End(TagEnd::Paragraph) | Event::End(TagEnd::Item) => {
+				flush(&mut blocks, &mut runs, &lists, quote_depth);
+			}
+			Event::Start(Tag::List(start)) => {
+				// The item text before a nested list
+				flush(&mut blocks, &mut runs, &lists, quote_depth);
+				lists.push((start.is_some(), list_count));
+				list_count += 1;
+			}
+			Event::End(TagEnd::List(_)) => {
+				lists.pop();
+			}
+			Event::Start(Tag::BlockQuote(_)) => quote_depth += 1,
+			Event::End(TagEnd::BlockQuote(_)) => quote_depth -= 1,
+			Event::Start(Tag::CodeBlock(_)) => code = Some(String::new()),
+			Event::End(TagEnd::CodeBlock) => {
+				if let Some(text) = code.take() {
+					blocks.push(DocxBlock::Code {
+						text: text.trim_end_matches('\n').to_string(),
+					});
+				}
+			}
+
+			// -- Tables
+			Event::End(TagEnd::TableCell) => table_row.push(std::mem::take(&mut runs)),
+			Event::End(TagEnd::TableHead) | Event::End(TagEnd::TableRow) => {
+				table_rows.push(std::mem::take(&mut table_row));
+			}
+			Event::End(TagEnd::Table) => {
+				blocks.push(DocxBlock::Table {
+					rows: std::mem::take(&mut table_rows),
+				});
+			}
+
+			// -- Inline styles
+			Event::Start(Tag::Strong) => style.bold = true,
+			Event::End(TagEnd::Strong) => style.bold = false,
+			Event::Start(Tag::Emphasis) => style.italic = true,
+			Event::End(TagEnd::Emphasis) => style.italic = false,
+			Event::Start(Tag::Strikethrough) => style.strike = true,
+			Event::End(TagEnd::Strikethrough) => style.strike = false,
+			Event::Start(Tag::Link { dest_url, .. }) => style.link = Some(dest_url.to_string()),
+			Event::End(TagEnd::Link) => style.link = None,
+
+			// -- Texts
+			Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => match code.as_mut() {
+				Some(code) => code.push_str(&text),
+				None => runs.push(DocxRun {
+					text: text.to_string(),
+					..style.clone()
+				}),
+			},
+			Event::Code(text) => runs.push(DocxRun {
+				text: text.to_string(),
+				code: true,
+				..style.clone()
+			}),
+			Event::SoftBreak => runs.push(DocxRun::plain(" ")),
+			Event::HardBreak => runs.push(DocxRun::plain("\n")),
+			Event::Rule => blocks.push(DocxBlock::Paragraph { runs: Vec::new() }),
+			_ => (),
+		}
+	}
+	flush(&mut blocks, &mut runs, &lists, quote_depth);
+
+	blocks
+}
+
+// endregion: --- Markdown
+
+// region:    --- Write
+
+/// Write the blocks as a docx file (overwrite).
+pub fn write_docx(path: impl AsRef<Path>, blocks: &[DocxBlock]) -> Result<()> {
+	let path = path.as_ref();
+	let bytes = docx_bytes(blocks)?;
+	std::fs::write(path, bytes)
+		.map_err(|err| Error::custom(format!("Cannot write docx file '{}'. Cause: {err}", path.display())))?;
+	Ok(())
+}
+
+/// Returns the docx file content (zip) of the blocks.
+pub fn docx_bytes(blocks: &[DocxBlock]) -> Result<Vec<u8>> {
+	let mut links: Vec<String> = Vec::new();
+	let mut ordered_lists: Vec<usize> = Vec::new();
+
+	// -- The document body
+	let mut body = String::new();
+	for block in blocks {
+		match block {
+			DocxBlock::Heading { level, runs } => {
+				let level = (*level).clamp(1, 6);
+				push_paragraph(
+					&mut body,
+					&format!("<w:pStyle w:val=\"Heading{level}\"/>"),
+					runs,
+					&mut links,
+				);
+			}
+			DocxBlock::Paragraph { runs } => push_paragraph(&mut body, "", runs, &mut links),
+			DocxBlock::Quote { runs } => push_paragraph(&mut body, "<w:pStyle w:val=\"Quote\"/>", runs, &mut links),
+			DocxBlock::ListItem {
+				ordered,
+				list_id,
+				level,
+				runs,
+			} => {
+				// The num 1 is for the bullets, and the ordered lists have their own num (to restart at 1)
+				let num_id = if *ordered {
+					let idx = match ordered_lists.iter().position(|id| id == list_id) {
+						Some(idx) => idx,
+						None => {
+							ordered_lists.push(*list_id);
+							ordered_lists.len() - 1
+						}
+					};
+					idx + 2
+				} else {
+					1
+				};
+				let ppr = format!(
+					"<w:pStyle w:val=\"ListParagraph\"/><w:numPr><w:ilvl w:val=\"{}\"/><w:numId w:val=\"{num_id}\"/></w:numPr>",
+					(*level).min(8)
+				);
+				push_paragraph(&mut body, &ppr, runs, &mut links);
+			}
+			DocxBlock::Code { text } => {
+				push_paragraph(
+					&mut body,
+					"<w:pStyle w:val=\"Code\"/>",
+					&[DocxRun::plain(text.as_str())],
+					&mut links,
+				);
+			}
+			DocxBlock::Table { rows } => push_table(&mut body, rows, &mut links),
+		}
+	}
+
+	let document = format!(
+		r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><w:body>{body}<w:sectPr><w:pgSz w:w="12240" w:h="15840"/><w:pgMar w:top="1440" w:right="1440" w:bottom="1440" w:left="1440" w:header="720" w:footer="720" w:gutter="0"/></w:sectPr></w:body></w:document>"#
+	);
+
+	// -- The document relationships (with the hyperlinks)
+	let mut rels = String::from(
+		r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rIdStyles" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/><Relationship Id="rIdNumbering" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering" Target="numbering.xml"/>"#,
+	);
+	for (idx, link) in links.iter().enumerate() {
+		rels.push_str(&format!(
+			r#"<Relationship Id="rIdLink{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink" Target="{}" TargetMode="External"/>"#,
+			idx + 1,
+			escape_xml(link)
+		));
+	}
+	rels.push_str("</Relationships>");
+
+	// -- Zip
+	let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+	let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+	let parts: [(&str, &str); 6] = [
+		("[Content_Types].xml", CONTENT_TYPES_XML),
+		("_rels/.rels", RELS_XML),
+		("word/document.xml", &document),
+		("word/_rels/document.xml.rels", &rels),
+		("word/styles.xml", STYLES_XML),
+		("word/numbering.xml", &numbering_xml(ordered_lists.len())),
+	];
+	for (name, content) in parts {
+		zip.start_file(name, options)
+			.map_err(|err| Error::custom(format!("Cannot write docx part '{name}'. Cause: {err}")))?;
+		zip.write_all(content.as_bytes())
+			.map_err(|err| Error::custom(format!("Cannot write docx part '{name}'. Cause: {err}")))?;
+	}
+	let cursor = zip
+		.finish()
+		.map_err(|err| Error::custom(format!("Cannot finish docx zip. Cause: {err}")))?;
+
+	Ok(cursor.into_inner())
+}
+
+fn push_paragraph(out: &mut String, ppr: &str, runs: &[DocxRun], links: &mut Vec<String>) {
+	out.push_str("<w:p>");
+	if !ppr.is_empty() {
+		out.push_str(&format!("<w:pPr>{ppr}</w:pPr>"));
+	}
+	for run in runs {
+		push_run(out, run, links);
+	}
+	out.push_str("</w:p>");
+}
+
+fn push_run(out: &mut String, run: &DocxRun, links: &mut Vec<String>) {
+	if run.text.is_empty() {
+		return;
+	}
+
+	let mut rpr = String::new();
+	if run.link.is_some() {
+		rpr.push_str("<w:rStyle w:val=\"Hyperlink\"/>");
+	}
+	if run.code {
+		rpr.push_str("<w:rFonts w:ascii=\"Courier New\" w:hAnsi=\"Courier New\" w:cs=\"Courier New\"/>");
+	}
+	if run.bold {
+		rpr.push_str("<w:b/>");
+	}
+	if run.italic {
+		rpr.push_str("<w:i/>");
+	}
+	if run.strike {
+		rpr.push_str("<w:strike/>");
+	}
+
+	let mut xml = String::from("<w:r>");
+	if !rpr.is_empty() {
+		xml.push_str(&format!("<w:rPr>{rpr}</w:rPr>"));
+	}
+	for (idx, line) in run.text.split('\n').enumerate() {
+		if idx > 0 {
+			xml.push_str("<w:br/>");
+		}
+		if !line.is_empty() {
+			xml.push_str(&format!("<w:t xml:space=\"preserve\">{}</w:t>", escape_xml(line)));
+		}
+	}
+	xml.push_str("</w:r>");
+
+	match &run.link {
+		Some(link) => {
+			let idx = match links.iter().position(|l| l == link) {
+				Some(idx) => idx,
+				None => {
+					links.push(link.clone());
+					links.len() - 1
+				}
+			};
+			out.push_str(&format!("<w:hyperlink r:id=\"rIdLink{}\">{xml}</w:hyperlink>", idx + 1));
+		}
+		None => out.push_str(&xml),
+	}
+}
+
+fn push_table(out: &mut String, rows: &[Vec<Vec<DocxRun>>], links: &mut Vec<String>) {
+	let col_count = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+	if col_count == 0 {
+		return;
+	}
+
+	out.push_str(
+		"<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/></w:tblPr><w:tblGrid>",
+	);
+	for _ in 0..col_count {
+		out.push_str("<w:gridCol/>");
+	}
+	out.push_str("</w:tblGrid>");
+	for (row_idx, row) in rows.iter().enumerate() {
+		out.push_str("<w:tr>");
+		if row_idx == 0 {
+			out.push_str("<w:trPr><w:tblHeader/></w:trPr>");
+		}
+		for col_idx in 0..col_count {
+			out.push_str("<w:tc><w:tcPr><w:tcW w:w=\"0\" w:type=\"auto\"/></w:tcPr>");
+			let runs: Vec<DocxRun> = match row.get(col_idx) {
+				// The header cells are bold
+				Some(runs) if row_idx == 0 => runs
+					.iter()
+					.map(|run| DocxRun {
+						bold: true,
+						..run.clone()
+					})
+					.collect(),
+				Some(runs) => runs.clone(),
+				None => Vec::new(),
+			};
+			push_paragraph(out, "", &runs, links);
+			out.push_str("</w:tc>");
+		}
+		out.push_str("</w:tr>");
+	}
+	out.push_str("</w:tbl>");
+}
+
+/// The num 1 is the bullet list, and the nums 2.. are the ordered lists (each restarting at 1)
+fn numbering_xml(ordered_count: usize) -> String {
+	let bullets = ["•", "◦", "▪"];
+	let mut bullet_levels = String::new();
+	let mut decimal_levels = String::new();
+	for lvl in 0..9 {
+		let ind = 720 * (lvl + 1);
+		bullet_levels.push_str(&format!(
+			r#"<w:lvl w:ilvl="{lvl}"><w:start w:val="1"/><w:numFmt w:val="bullet"/><w:lvlText w:val="{}"/><w:lvlJc w:val="left"/><w:pPr><w:ind w:left="{ind}" w:hanging="360"/></w:pPr></w:lvl>"#,
+			bullets[lvl % bullets.len()]
+		));
+		decimal_levels.push_str(&format!(
+			r#"<w:lvl w:ilvl="{lvl}"><w:start w:val="1"/><w:numFmt w:val="decimal"/><w:lvlText w:val="%{}."/><w:lvlJc w:val="left"/><w:pPr><w:ind w:left="{ind}" w:hanging="360"/></w:pPr></w:lvl>"#,
+			lvl + 1
+		));
+	}
+
+	let mut xml = format!(
+		r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:abstractNum w:abstractNumId="0"><w:multiLevelType w:val="hybridMultilevel"/>{bullet_levels}</w:abstractNum><w:abstractNum w:abstractNumId="1"><w:multiLevelType w:val="hybridMultilevel"/>{decimal_levels}</w:abstractNum><w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num>"#
+	);
+	for idx in 0..ordered_count {
+		xml.push_str(&format!(
+			r#"<w:num w:numId="{}"><w:abstractNumId w:val="1"/><w:lvlOverride w:ilvl="0"><w:startOverride w:val="1"/></w:lvlOverride></w:num>"#,
+			idx + 2
+		));
+	}
+	xml.push_str("</w:numbering>");
+	xml
+}
+
+fn escape_xml(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+}
+
+const CONTENT_TYPES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/word/numbering.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.numbering+xml"/></Types>"#;
+
+const RELS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+const STYLES_XML: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:cs="Calibri"/><w:sz w:val="22"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="160" w:line="259" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="36"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="30"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="80"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading4"><w:name w:val="heading 4"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="3"/></w:pPr><w:rPr><w:b/><w:i/><w:sz w:val="24"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading5"><w:name w:val="heading 5"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="4"/></w:pPr><w:rPr><w:b/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading6"><w:name w:val="heading 6"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:outlineLvl w:val="5"/></w:pPr><w:rPr><w:i/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="ListParagraph"><w:name w:val="List Paragraph"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="60"/><w:ind w:left="720"/><w:contextualSpacing/></w:pPr></w:style><w:style w:type="paragraph" w:styleId="Quote"><w:name w:val="Quote"/><w:basedOn w:val="Normal"/><w:pPr><w:ind w:left="720" w:right="720"/></w:pPr><w:rPr><w:i/><w:color w:val="404040"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Code"><w:name w:val="Code"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0" w:line="240" w:lineRule="auto"/><w:shd w:val="clear" w:color="auto" w:fill="F2F2F2"/></w:pPr><w:rPr><w:rFonts w:ascii="Courier New" w:hAnsi="Courier New" w:cs="Courier New"/><w:sz w:val="20"/></w:rPr></w:style><w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/><w:rPr><w:color w:val="0563C1"/><w:u w:val="single"/></w:rPr></w:style><w:style w:type="table" w:styleId="TableGrid"><w:name w:val="Table Grid"/><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:left w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:right w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="auto"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="auto"/></w:tblBorders><w:tblCellMar><w:left w:w="108" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style></w:styles>"#;
+
+// endregion: --- Write
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::support::docx::docx_convert;
+	use simple_fs::SPath;
+
+	#[test]
+	fn test_support_docx_write_md_to_docx_blocks() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_md = "# Report\n\nSome **bold** and [link](https://example.com).\n\n- one\n  - nested\n- two\n\n1. first\n2. second\n\n| Name | Qty |\n|---|---|\n| Apple | 12 |\n\n```sh\nls -la\n```\n";
+
+		// -- Exec
+		let blocks = md_to_docx_blocks(fx_md);
+
+		// -- Check
+		assert!(matches!(&blocks[0], DocxBlock::Heading { level: 1, runs } if runs[0].text == "Report"));
+		let DocxBlock::Paragraph { runs } = &blocks[1] else {
+			return Err("Should be a paragraph".into());
+		};
+		assert!(runs.iter().any(|run| run.bold && run.text == "bold"));
+		assert!(runs.iter().any(|run| run.link.as_deref() == Some("https://example.com")));
+		let items: Vec<(bool, u8, &str)> = blocks
+			.iter()
+			.filter_map(|block| match block {
+				DocxBlock::ListItem {
+					ordered, level, runs, ..
+				} => Some((*ordered, *level, runs[0].text.as_str())),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(
+			items,
+			vec![
+				(false, 0, "one"),
+				(false, 1, "nested"),
+				(false, 0, "two"),
+				(true, 0, "first"),
+				(true, 0, "second")
+			]
+		);
+		assert!(
+			matches!(&blocks[blocks.len() - 2], DocxBlock::Table { rows } if rows.len() == 2 && rows[1][0][0].text == "Apple")
+		);
+		assert_eq!(
+			blocks.last(),
+			Some(&DocxBlock::Code {
+				text: "ls -la".to_string()
+			})
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_docx_write_docx_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = SPath::new("tests-data/sandbox-01/.tmp/test_support_docx_write");
+		std::fs::create_dir_all(&fx_dir)?;
+		let fx_path = fx_dir.join("report.docx");
+		let blocks = md_to_docx_blocks("# Title\n\nHello, welcome.\n\n- item a\n- item b\n");
+
+		// -- Exec
+		write_docx(&fx_path, &blocks)?;
+		let md = docx_convert(fx_path.std_path())?;
+
+		// -- Check
+		assert!(md.contains("# Title"), "{md}");
+		assert!(md.contains("Hello, welcome."), "{md}");
+		assert!(md.contains("- item b"), "{md}");
+
+		std::fs::remove_dir_all(&fx_dir)?;
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
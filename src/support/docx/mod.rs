@@ -1,8 +1,10 @@
 // region:    --- Modules
 
 mod docx_impl;
+mod docx_write;
 mod md_support;
 
 pub use docx_impl::*;
+pub use docx_write::*;
 
 // endregion: --- Modules
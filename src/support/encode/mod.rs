@@ -0,0 +1,18 @@
+//! The binary encodings of the JSON values (used by `aip.encode`).
+//!
+//! - MessagePack (see `msgpack`), schemaless.
+//! - Protobuf (see `proto_codec`), with the message types of a descriptor set (see `proto_descriptor`).
+//!
+
+// region:    --- Modules
+
+mod msgpack;
+mod proto_codec;
+mod proto_descriptor;
+mod proto_wire;
+
+pub use msgpack::*;
+pub use proto_codec::*;
+pub use proto_descriptor::*;
+
+// endregion: --- Modules
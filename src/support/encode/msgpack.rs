@@ -0,0 +1,324 @@
+//! MessagePack encoding of the JSON values (the values from and to Lua).
+//!
+//! NOTE: The `bin` values are decoded as strings when they are valid UTF-8, otherwise as arrays of bytes,
+//!       and the `ext` values (e.g., timestamps) are not supported.
+
+use crate::{Error, Result};
+use serde_json::{Map, Number, Value};
+
+/// Max nesting depth of the decoded values (protection against the malformed inputs)
+const MAX_DEPTH: usize = 256;
+
+pub fn msgpack_encode(value: &Value) -> Result<Vec<u8>> {
+	let mut buf = Vec::new();
+	write_value(&mut buf, value)?;
+	Ok(buf)
+}
+
+pub fn msgpack_decode(bytes: &[u8]) -> Result<Value> {
+	let mut reader = Reader { bytes, pos: 0 };
+	let value = reader.read_value(0)?;
+	if reader.pos != bytes.len() {
+		return Err(msgpack_err(format!(
+			"{} trailing bytes after the value",
+			bytes.len() - reader.pos
+		)));
+	}
+	Ok(value)
+}
+
+// region:    --- Encode
+
+fn write_value(buf: &mut Vec<u8>, value: &Value) -> Result<()> {
+	match value {
+		Value::Null => buf.push(0xc0),
+		Value::Bool(false) => buf.push(0xc2),
+		Value::Bool(true) => buf.push(0xc3),
+		Value::Number(n) => {
+			if let Some(i) = n.as_i64() {
+				write_int(buf, i);
+			} else if let Some(u) = n.as_u64() {
+				buf.push(0xcf);
+				buf.extend_from_slice(&u.to_be_bytes());
+			} else {
+				let f = n.as_f64().ok_or_else(|| msgpack_err(format!("Invalid number '{n}'")))?;
+				buf.push(0xcb);
+				buf.extend_from_slice(&f.to_be_bytes());
+			}
+		}
+		Value::String(s) => {
+			write_len(buf, s.len(), [Some(0xa0), Some(0xd9), Some(0xda), Some(0xdb)])?;
+			buf.extend_from_slice(s.as_bytes());
+		}
+		Value::Array(items) => {
+			write_len(buf, items.len(), [Some(0x90), None, Some(0xdc), Some(0xdd)])?;
+			for item in items {
+				write_value(buf, item)?;
+			}
+		}
+		Value::Object(map) => {
+			write_len(buf, map.len(), [Some(0x80), None, Some(0xde), Some(0xdf)])?;
+			for (key, value) in map {
+				write_value(buf, &Value::String(key.clone()))?;
+				write_value(buf, value)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+fn write_int(buf: &mut Vec<u8>, i: i64) {
+	match i {
+		0..=0x7f => buf.push(i as u8),
+		-32..=-1 => buf.push(i as i8 as u8),
+		0x80..=0xff => buf.extend_from_slice(&[0xcc, i as u8]),
+		0x100..=0xffff => {
+			buf.push(0xcd);
+			buf.extend_from_slice(&(i as u16).to_be_bytes());
+		}
+		0x1_0000..=0xffff_ffff => {
+			buf.push(0xce);
+			buf.extend_from_slice(&(i as u32).to_be_bytes());
+		}
+		_ if i > 0 => {
+			buf.push(0xcf);
+			buf.extend_from_slice(&(i as u64).to_be_bytes());
+		}
+		-128..=-33 => buf.extend_from_slice(&[0xd0, i as i8 as u8]),
+		-32768..=-129 => {
+			buf.push(0xd1);
+			buf.extend_from_slice(&(i as i16).to_be_bytes());
+		}
+		-2_147_483_648..=-32769 => {
+			buf.push(0xd2);
+			buf.extend_from_slice(&(i as i32).to_be_bytes());
+		}
+		_ => {
+			buf.push(0xd3);
+			buf.extend_from_slice(&i.to_be_bytes());
+		}
+	}
+}
+
+/// Write the length header with the `[fix, 8, 16, 32]` markers (the fix marker holds up to 31 for str, 15 otherwise).
+fn write_len(buf: &mut Vec<u8>, len: usize, markers: [Option<u8>; 4]) -> Result<()> {
+	let [fix, m8, m16, m32] = markers;
+	let fix_max = if m8.is_some() { 31 } else { 15 };
+	match (len, fix, m8, m16, m32) {
+		(0..=31, Some(fix), ..) if len <= fix_max => buf.push(fix | len as u8),
+		(0..=0xff, _, Some(m8), ..) => buf.extend_from_slice(&[m8, len as u8]),
+		(0..=0xffff, _, _, Some(m16), _) => {
+			buf.push(m16);
+			buf.extend_from_slice(&(len as u16).to_be_bytes());
+		}
+		(0..=0xffff_ffff, _, _, _, Some(m32)) => {
+			buf.push(m32);
+			buf.extend_from_slice(&(len as u32).to_be_bytes());
+		}
+		_ => return Err(msgpack_err(format!("Length {len} too large"))),
+	}
+	Ok(())
+}
+
+// endregion: --- Encode
+
+// region:    --- Decode
+
+struct Reader<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+
+impl Reader<'_> {
+	fn read_value(&mut self, depth: usize) -> Result<Value> {
+		if depth > MAX_DEPTH {
+			return Err(msgpack_err("Max nesting depth exceeded"));
+		}
+		let marker = self.read_u8()?;
+		let value = match marker {
+			0x00..=0x7f => Value::from(marker),
+			0x80..=0x8f => self.read_map((marker & 0x0f) as usize, depth)?,
+			0x90..=0x9f => self.read_array((marker & 0x0f) as usize, depth)?,
+			0xa0..=0xbf => self.read_str((marker & 0x1f) as usize)?,
+			0xc0 => Value::Null,
+			0xc2 => Value::Bool(false),
+			0xc3 => Value::Bool(true),
+			0xc4 => {
+				let len = self.read_u8()? as usize;
+				self.read_bin(len)?
+			}
+			0xc5 => {
+				let len = self.read_be::<2>()? as usize;
+				self.read_bin(len)?
+			}
+			0xc6 => {
+				let len = self.read_be::<4>()? as usize;
+				self.read_bin(len)?
+			}
+			0xca => {
+				let bits = self.read_be::<4>()? as u32;
+				float_value(f32::from_bits(bits) as f64)?
+			}
+			0xcb => float_value(f64::from_bits(self.read_be::<8>()?))?,
+			0xcc => Value::from(self.read_u8()?),
+			0xcd => Value::from(self.read_be::<2>()?),
+			0xce => Value::from(self.read_be::<4>()?),
+			0xcf => Value::from(self.read_be::<8>()?),
+			0xd0 => Value::from(self.read_u8()? as i8),
+			0xd1 => Value::from(self.read_be::<2>()? as u16 as i16),
+			0xd2 => Value::from(self.read_be::<4>()? as u32 as i32),
+			0xd3 => Value::from(self.read_be::<8>()? as i64),
+			0xd9 => {
+				let len = self.read_u8()? as usize;
+				self.read_str(len)?
+			}
+			0xda => {
+				let len = self.read_be::<2>()? as usize;
+				self.read_str(len)?
+			}
+			0xdb => {
+				let len = self.read_be::<4>()? as usize;
+				self.read_str(len)?
+			}
+			0xdc => {
+				let len = self.read_be::<2>()? as usize;
+				self.read_array(len, depth)?
+			}
+			0xdd => {
+				let len = self.read_be::<4>()? as usize;
+				self.read_array(len, depth)?
+			}
+			0xde => {
+				let len = self.read_be::<2>()? as usize;
+				self.read_map(len, depth)?
+			}
+			0xdf => {
+				let len = self.read_be::<4>()? as usize;
+				self.read_map(len, depth)?
+			}
+			0xe0..=0xff => Value::from(marker as i8),
+			0xc7..=0xc9 | 0xd4..=0xd8 => return Err(msgpack_err("The ext types are not supported")),
+			0xc1 => return Err(msgpack_err("Invalid marker 0xc1")),
+		};
+		Ok(value)
+	}
+
+	fn read_array(&mut self, len: usize, depth: usize) -> Result<Value> {
+		let mut items = Vec::with_capacity(len.min(1024));
+		for _ in 0..len {
+			items.push(self.read_value(depth + 1)?);
+		}
+		Ok(Value::Array(items))
+	}
+
+	fn read_map(&mut self, len: usize, depth: usize) -> Result<Value> {
+		let mut map = Map::new();
+		for _ in 0..len {
+			let key = match self.read_value(depth + 1)? {
+				Value::String(key) => key,
+				// NOTE: The non-string keys (e.g., integers) become their JSON string (as for the Lua tables to JSON)
+				other => other.to_string(),
+			};
+			let value = self.read_value(depth + 1)?;
+			map.insert(key, value);
+		}
+		Ok(Value::Object(map))
+	}
+
+	fn read_str(&mut self, len: usize) -> Result<Value> {
+		let bytes = self.read_bytes(len)?;
+		let s = std::str::from_utf8(bytes).map_err(|err| msgpack_err(format!("Invalid UTF-8 str. {err}")))?;
+		Ok(Value::String(s.to_string()))
+	}
+
+	fn read_bin(&mut self, len: usize) -> Result<Value> {
+		let bytes = self.read_bytes(len)?;
+		Ok(match std::str::from_utf8(bytes) {
+			Ok(s) => Value::String(s.to_string()),
+			Err(_) => Value::Array(bytes.iter().map(|b| Value::from(*b)).collect()),
+		})
+	}
+
+	fn read_u8(&mut self) -> Result<u8> {
+		Ok(self.read_bytes(1)?[0])
+	}
+
+	fn read_be<const N: usize>(&mut self) -> Result<u64> {
+		let bytes = self.read_bytes(N)?;
+		Ok(bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64))
+	}
+
+	fn read_bytes(&mut self, len: usize) -> Result<&[u8]> {
+		let end = self
+			.pos
+			.checked_add(len)
+			.filter(|end| *end <= self.bytes.len())
+			.ok_or_else(|| msgpack_err("Unexpected end of input"))?;
+		let bytes = &self.bytes[self.pos..end];
+		self.pos = end;
+		Ok(bytes)
+	}
+}
+
+fn float_value(f: f64) -> Result<Value> {
+	Number::from_f64(f)
+		.map(Value::Number)
+		.ok_or_else(|| msgpack_err(format!("Unsupported float '{f}' (NaN or infinite)")))
+}
+
+// endregion: --- Decode
+
+fn msgpack_err(msg: impl Into<String>) -> Error {
+	Error::custom(format!("MessagePack - {}", msg.into()))
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_support_encode_msgpack_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_value = json!({
+			"name": "acme",
+			"count": 300,
+			"neg": -200,
+			"big": 5_000_000_000_i64,
+			"ratio": 0.5,
+			"ok": true,
+			"none": null,
+			"tags": ["a", "b"],
+			"long": "x".repeat(40),
+		});
+
+		// -- Exec
+		let bytes = msgpack_encode(&fx_value)?;
+		let value = msgpack_decode(&bytes)?;
+
+		// -- Check
+		assert_eq!(value, fx_value);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_encode_msgpack_spec_bytes() -> Result<()> {
+		// -- Exec & Check
+		// {"compact": true, "schema": 0} from the msgpack.org example
+		let bytes = msgpack_encode(&json!({"compact": true, "schema": 0}))?;
+		assert_eq!(hex::encode(&bytes), "82a7636f6d70616374c3a6736368656d6100");
+		assert_eq!(msgpack_encode(&json!(-33))?, vec![0xd0, 0xdf]);
+		assert_eq!(msgpack_encode(&json!(200))?, vec![0xcc, 0xc8]);
+		assert_eq!(msgpack_decode(&[0xc4, 0x02, 0xff, 0x00])?, json!([255, 0]));
+		assert!(msgpack_decode(&[0x92, 0x01]).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,543 @@
+//! The Protobuf encoding of the JSON values (the values from and to Lua), with the message types of a descriptor set.
+//!
+//! The JSON mapping:
+//! - The fields are keyed by their proto name (the `json_name` is also accepted on encode).
+//! - The 64 bit integers are numbers (the strings are also accepted on encode).
+//! - The enums are their value names (the numbers are also accepted on encode, and the unknown values decode as numbers).
+//! - The `bytes` are base64 strings, and the maps are objects.
+//! - The fields missing from the bytes are omitted (no default values), and the unknown fields are skipped.
+
+use crate::support::encode::proto_descriptor::{FieldDesc, MessageDesc, ProtoDescriptors, kind};
+use crate::support::encode::proto_wire::{
+	WIRE_FIXED32, WIRE_FIXED64, WIRE_LEN, WIRE_VARINT, WireReader, WireValue, proto_err, write_len_field, write_tag,
+	write_varint,
+};
+use crate::{Error, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Map, Number, Value};
+
+/// Max nesting depth of the messages (protection against the recursive types and malformed inputs)
+const MAX_DEPTH: usize = 100;
+
+pub fn proto_encode(descriptors: &ProtoDescriptors, message_type: &str, value: &Value) -> Result<Vec<u8>> {
+	let message = descriptors.message(message_type)?;
+	let mut buf = Vec::new();
+	Codec { descriptors }.encode_message(&mut buf, message, value, 0)?;
+	Ok(buf)
+}
+
+pub fn proto_decode(descriptors: &ProtoDescriptors, message_type: &str, bytes: &[u8]) -> Result<Value> {
+	let message = descriptors.message(message_type)?;
+	let map = Codec { descriptors }.decode_message(message, bytes, 0)?;
+	Ok(Value::Object(map))
+}
+
+struct Codec<'a> {
+	descriptors: &'a ProtoDescriptors,
+}
+
+// region:    --- Encode
+
+impl Codec<'_> {
+	fn encode_message(&self, buf: &mut Vec<u8>, message: &MessageDesc, value: &Value, depth: usize) -> Result<()> {
+		if depth > MAX_DEPTH {
+			return Err(proto_err("Max message nesting depth exceeded"));
+		}
+		let Value::Object(obj) = value else {
+			return Err(proto_err(format!(
+				"Message '{}' value must be an object, but was: {value}",
+				message.full_name
+			)));
+		};
+
+		for (key, field_value) in obj {
+			let field = message
+				.fields
+				.iter()
+				.find(|f| &f.name == key || f.json_name.as_ref() == Some(key))
+				.ok_or_else(|| proto_err(format!("Message '{}' has no field '{key}'", message.full_name)))?;
+			self.encode_field(buf, message, field, field_value, depth)
+				.map_err(|err| Error::custom(format!("Field '{}.{key}'. {err}", message.full_name)))?;
+		}
+
+		Ok(())
+	}
+
+	fn encode_field(
+		&self,
+		buf: &mut Vec<u8>,
+		message: &MessageDesc,
+		field: &FieldDesc,
+		value: &Value,
+		depth: usize,
+	) -> Result<()> {
+		if value.is_null() {
+			return Ok(());
+		}
+
+		// -- Map (repeated map entries)
+		if let Some(entry) = self.map_entry(field)? {
+			let Value::Object(obj) = value else {
+				return Err(proto_err(format!("Map value must be an object, but was: {value}")));
+			};
+			let (key_field, value_field) = map_entry_fields(entry)?;
+			for (key, item) in obj {
+				let mut entry_buf = Vec::new();
+				let key_value = match key_field.kind {
+					kind::STRING => Value::String(key.clone()),
+					kind::BOOL => Value::Bool(key == "true"),
+					_ => Value::String(key.clone()), // numbers are parsed from the strings
+				};
+				self.encode_single(&mut entry_buf, key_field, &key_value, depth)?;
+				self.encode_single(&mut entry_buf, value_field, item, depth)?;
+				write_len_field(buf, field.number, &entry_buf);
+			}
+			return Ok(());
+		}
+
+		// -- Repeated
+		if field.repeated {
+			let Value::Array(items) = value else {
+				return Err(proto_err(format!("Repeated value must be an array, but was: {value}")));
+			};
+			let packed = is_packable(field.kind) && field.packed.unwrap_or(message.proto3);
+			if packed {
+				let mut packed_buf = Vec::new();
+				for item in items {
+					self.encode_scalar(&mut packed_buf, field, item)?;
+				}
+				write_len_field(buf, field.number, &packed_buf);
+			} else {
+				for item in items {
+					self.encode_single(buf, field, item, depth)?;
+				}
+			}
+			return Ok(());
+		}
+
+		self.encode_single(buf, field, value, depth)
+	}
+
+	/// Encode the value with its tag.
+	fn encode_single(&self, buf: &mut Vec<u8>, field: &FieldDesc, value: &Value, depth: usize) -> Result<()> {
+		match field.kind {
+			kind::STRING => {
+				let s = value
+					.as_str()
+					.ok_or_else(|| proto_err(format!("Expected a string, but was: {value}")))?;
+				write_len_field(buf, field.number, s.as_bytes());
+			}
+			kind::BYTES => {
+				let s = value
+					.as_str()
+					.ok_or_else(|| proto_err(format!("Expected a base64 string, but was: {value}")))?;
+				let bytes = BASE64
+					.decode(s)
+					.map_err(|err| proto_err(format!("Invalid base64 bytes. {err}")))?;
+				write_len_field(buf, field.number, &bytes);
+			}
+			kind::MESSAGE => {
+				let message = self.descriptors.message(type_name(field)?)?;
+				let mut message_buf = Vec::new();
+				self.encode_message(&mut message_buf, message, value, depth + 1)?;
+				write_len_field(buf, field.number, &message_buf);
+			}
+			_ => {
+				write_tag(buf, field.number, wire_type(field.kind));
+				self.encode_scalar(buf, field, value)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Encode the numeric, bool, or enum value (without tag, as in the packed fields).
+	fn encode_scalar(&self, buf: &mut Vec<u8>, field: &FieldDesc, value: &Value) -> Result<()> {
+		match field.kind {
+			kind::DOUBLE => buf.extend_from_slice(&to_f64(value)?.to_le_bytes()),
+			kind::FLOAT => buf.extend_from_slice(&(to_f64(value)? as f32).to_le_bytes()),
+			kind::INT64 | kind::INT32 => write_varint(buf, to_i64(value)? as u64),
+			kind::UINT64 | kind::UINT32 => write_varint(buf, to_u64(value)?),
+			kind::SINT32 | kind::SINT64 => {
+				let i = to_i64(value)?;
+				write_varint(buf, ((i << 1) ^ (i >> 63)) as u64);
+			}
+			kind::FIXED64 => buf.extend_from_slice(&to_u64(value)?.to_le_bytes()),
+			kind::SFIXED64 => buf.extend_from_slice(&to_i64(value)?.to_le_bytes()),
+			kind::FIXED32 => buf.extend_from_slice(&(to_u64(value)? as u32).to_le_bytes()),
+			kind::SFIXED32 => buf.extend_from_slice(&(to_i64(value)? as i32).to_le_bytes()),
+			kind::BOOL => {
+				let b = value
+					.as_bool()
+					.ok_or_else(|| proto_err(format!("Expected a boolean, but was: {value}")))?;
+				write_varint(buf, b as u64);
+			}
+			kind::ENUM => {
+				let number = match value {
+					Value::String(name) => {
+						let enum_desc = self.descriptors.enum_desc(type_name(field)?)?;
+						enum_desc
+							.values
+							.iter()
+							.find(|(n, _)| n == name)
+							.map(|(_, number)| *number)
+							.ok_or_else(|| proto_err(format!("Unknown enum value '{name}'")))?
+					}
+					_ => to_i64(value)? as i32,
+				};
+				write_varint(buf, number as i64 as u64);
+			}
+			other => return Err(proto_err(format!("Unsupported field type {other}"))),
+		}
+		Ok(())
+	}
+}
+
+// endregion: --- Encode
+
+// region:    --- Decode
+
+impl Codec<'_> {
+	fn decode_message(&self, message: &MessageDesc, bytes: &[u8], depth: usize) -> Result<Map<String, Value>> {
+		if depth > MAX_DEPTH {
+			return Err(proto_err("Max message nesting depth exceeded"));
+		}
+
+		let mut obj = Map::new();
+		let mut reader = WireReader::new(bytes);
+		while !reader.is_done() {
+			let (number, wire_value) = reader.read_field()?;
+			// NOTE: The unknown fields are skipped (e.g., the fields of a newer schema)
+			let Some(field) = message.fields.iter().find(|f| f.number == number) else {
+				continue;
+			};
+			self.decode_field(&mut obj, field, wire_value, depth)
+				.map_err(|err| Error::custom(format!("Field '{}.{}'. {err}", message.full_name, field.name)))?;
+		}
+
+		Ok(obj)
+	}
+
+	fn decode_field(
+		&self,
+		obj: &mut Map<String, Value>,
+		field: &FieldDesc,
+		wire_value: WireValue,
+		depth: usize,
+	) -> Result<()> {
+		// -- Map entry
+		if let Some(entry) = self.map_entry(field)? {
+			let WireValue::Len(bytes) = wire_value else {
+				return Err(proto_err("Invalid wire type for a map entry"));
+			};
+			let mut entry_obj = self.decode_message(entry, bytes, depth + 1)?;
+			let (key_field, value_field) = map_entry_fields(entry)?;
+			let key = match entry_obj.remove(&key_field.name) {
+				Some(Value::String(key)) => key,
+				Some(key) => key.to_string(),
+				None => default_key(key_field.kind),
+			};
+			let value = entry_obj.remove(&value_field.name).unwrap_or(Value::Null);
+			let map = obj.entry(field.name.clone()).or_insert_with(|| Value::Object(Map::new()));
+			if let Value::Object(map) = map {
+				map.insert(key, value);
+			}
+			return Ok(());
+		}
+
+		// -- Repeated (packed or not)
+		if field.repeated {
+			let mut values = Vec::new();
+			match wire_value {
+				WireValue::Len(bytes) if is_packable(field.kind) => {
+					let mut reader = WireReader::new(bytes);
+					while !reader.is_done() {
+						let item = match wire_type(field.kind) {
+							WIRE_FIXED64 => WireValue::Fixed64(u64::from_le_bytes(reader.read_array::<8>()?)),
+							WIRE_FIXED32 => WireValue::Fixed32(u32::from_le_bytes(reader.read_array::<4>()?)),
+							_ => WireValue::Varint(reader.read_varint()?),
+						};
+						values.push(self.decode_value(field, item, depth)?);
+					}
+				}
+				_ => values.push(self.decode_value(field, wire_value, depth)?),
+			}
+			let list = obj.entry(field.name.clone()).or_insert_with(|| Value::Array(Vec::new()));
+			if let Value::Array(list) = list {
+				list.extend(values);
+			}
+			return Ok(());
+		}
+
+		let value = self.decode_value(field, wire_value, depth)?;
+		obj.insert(field.name.clone(), value);
+		Ok(())
+	}
+
+	fn decode_value(&self, field: &FieldDesc, wire_value: WireValue, depth: usize) -> Result<Value> {
+		let value = match (field.kind, wire_value) {
+			(kind::INT64, WireValue::Varint(v)) => Value::from(v as i64),
+			(kind::UINT64, WireValue::Varint(v)) => Value::from(v),
+			(kind::INT32, WireValue::Varint(v)) => Value::from(v as i32),
+			(kind::UINT32, WireValue::Varint(v)) => Value::from(v as u32),
+			(kind::SINT32 | kind::SINT64, WireValue::Varint(v)) => Value::from((v >> 1) as i64 ^ -((v & 1) as i64)),
+			(kind::BOOL, WireValue::Varint(v)) => Value::Bool(v != 0),
+			(kind::ENUM, WireValue::Varint(v)) => {
+				let number = v as i32;
+				let enum_desc = self.descriptors.enum_desc(type_name(field)?)?;
+				match enum_desc.values.iter().find(|(_, n)| *n == number) {
+					Some((name, _)) => Value::String(name.clone()),
+					None => Value::from(number),
+				}
+			}
+			(kind::DOUBLE, WireValue::Fixed64(v)) => float_value(f64::from_bits(v))?,
+			(kind::FIXED64, WireValue::Fixed64(v)) => Value::from(v),
+			(kind::SFIXED64, WireValue::Fixed64(v)) => Value::from(v as i64),
+			(kind::FLOAT, WireValue::Fixed32(v)) => float_value(f32::from_bits(v) as f64)?,
+			(kind::FIXED32, WireValue::Fixed32(v)) => Value::from(v),
+			(kind::SFIXED32, WireValue::Fixed32(v)) => Value::from(v as i32),
+			(kind::STRING, WireValue::Len(bytes)) => Value::String(
+				std::str::from_utf8(bytes)
+					.map_err(|err| proto_err(format!("Invalid UTF-8 string. {err}")))?
+					.to_string(),
+			),
+			(kind::BYTES, WireValue::Len(bytes)) => Value::String(BASE64.encode(bytes)),
+			(kind::MESSAGE, WireValue::Len(bytes)) => {
+				let message = self.descriptors.message(type_name(field)?)?;
+				Value::Object(self.decode_message(message, bytes, depth + 1)?)
+			}
+			(kind, wire_value) => {
+				return Err(proto_err(format!(
+					"Wire value {wire_value:?} does not match the field type {kind}"
+				)));
+			}
+		};
+		Ok(value)
+	}
+}
+
+// endregion: --- Decode
+
+// region:    --- Support
+
+impl Codec<'_> {
+	/// The map entry message of the field, if it is a map field.
+	fn map_entry(&self, field: &FieldDesc) -> Result<Option<&MessageDesc>> {
+		if !field.repeated || field.kind != kind::MESSAGE {
+			return Ok(None);
+		}
+		let message = self.descriptors.message(type_name(field)?)?;
+		Ok(message.map_entry.then_some(message))
+	}
+}
+
+fn map_entry_fields(entry: &MessageDesc) -> Result<(&FieldDesc, &FieldDesc)> {
+	let key = entry.fields.iter().find(|f| f.number == 1);
+	let value = entry.fields.iter().find(|f| f.number == 2);
+	key.zip(value)
+		.ok_or_else(|| proto_err(format!("Invalid map entry '{}'", entry.full_name)))
+}
+
+fn default_key(kind: u32) -> String {
+	match kind {
+		kind::STRING => String::new(),
+		kind::BOOL => "false".to_string(),
+		_ => "0".to_string(),
+	}
+}
+
+fn type_name(field: &FieldDesc) -> Result<&str> {
+	field
+		.type_name
+		.as_deref()
+		.ok_or_else(|| proto_err(format!("Field '{}' has no type name", field.name)))
+}
+
+fn is_packable(kind: u32) -> bool {
+	!matches!(kind, kind::STRING | kind::BYTES | kind::MESSAGE | kind::GROUP)
+}
+
+fn wire_type(kind: u32) -> u32 {
+	match kind {
+		kind::DOUBLE | kind::FIXED64 | kind::SFIXED64 => WIRE_FIXED64,
+		kind::FLOAT | kind::FIXED32 | kind::SFIXED32 => WIRE_FIXED32,
+		kind::STRING | kind::BYTES | kind::MESSAGE => WIRE_LEN,
+		_ => WIRE_VARINT,
+	}
+}
+
+fn to_i64(value: &Value) -> Result<i64> {
+	match value {
+		Value::Number(n) => n.as_i64().or_else(|| n.as_f64().filter(|f| f.fract() == 0.0).map(|f| f as i64)),
+		Value::String(s) => s.trim().parse::<i64>().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| proto_err(format!("Expected an integer, but was: {value}")))
+}
+
+fn to_u64(value: &Value) -> Result<u64> {
+	match value {
+		Value::Number(n) => n
+			.as_u64()
+			.or_else(|| n.as_f64().filter(|f| f.fract() == 0.0 && *f >= 0.0).map(|f| f as u64)),
+		Value::String(s) => s.trim().parse::<u64>().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| proto_err(format!("Expected an unsigned integer, but was: {value}")))
+}
+
+fn to_f64(value: &Value) -> Result<f64> {
+	match value {
+		Value::Number(n) => n.as_f64(),
+		Value::String(s) => s.trim().parse::<f64>().ok(),
+		_ => None,
+	}
+	.ok_or_else(|| proto_err(format!("Expected a number, but was: {value}")))
+}
+
+fn float_value(f: f64) -> Result<Value> {
+	Number::from_f64(f)
+		.map(Value::Number)
+		.ok_or_else(|| proto_err(format!("Unsupported float '{f}' (NaN or infinite)")))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	// -- Support: build a FileDescriptorSet (as `protoc --descriptor_set_out`)
+	fn fx_field(name: &str, number: u64, label: u64, kind: u64, type_name: Option<&str>) -> Vec<u8> {
+		let mut buf = Vec::new();
+		write_len_field(&mut buf, 1, name.as_bytes());
+		write_tag(&mut buf, 3, WIRE_VARINT);
+		write_varint(&mut buf, number);
+		write_tag(&mut buf, 4, WIRE_VARINT);
+		write_varint(&mut buf, label);
+		write_tag(&mut buf, 5, WIRE_VARINT);
+		write_varint(&mut buf, kind);
+		if let Some(type_name) = type_name {
+			write_len_field(&mut buf, 6, type_name.as_bytes());
+		}
+		buf
+	}
+
+	/// ```proto
+	/// syntax = "proto3";
+	/// package acme.v1;
+	/// message User {
+	///   string name = 1; int32 age = 2; repeated int32 scores = 3; Role role = 4;
+	///   map<string, int64> counts = 5; bytes avatar = 6; repeated Address addresses = 7; sint64 delta = 8;
+	///   message Address { string city = 1; }
+	/// }
+	/// enum Role { ROLE_UNSPECIFIED = 0; ROLE_ADMIN = 1; }
+	/// ```
+	fn fx_descriptors() -> Result<ProtoDescriptors> {
+		let mut address = Vec::new();
+		write_len_field(&mut address, 1, b"Address");
+		write_len_field(&mut address, 2, &fx_field("city", 1, 1, 9, None));
+
+		let mut counts_entry = Vec::new();
+		write_len_field(&mut counts_entry, 1, b"CountsEntry");
+		write_len_field(&mut counts_entry, 2, &fx_field("key", 1, 1, 9, None));
+		write_len_field(&mut counts_entry, 2, &fx_field("value", 2, 1, 3, None));
+		write_len_field(&mut counts_entry, 7, &[0x38, 0x01]); // map_entry = true
+
+		let mut user = Vec::new();
+		write_len_field(&mut user, 1, b"User");
+		write_len_field(&mut user, 2, &fx_field("name", 1, 1, 9, None));
+		write_len_field(&mut user, 2, &fx_field("age", 2, 1, 5, None));
+		write_len_field(&mut user, 2, &fx_field("scores", 3, 3, 5, None));
+		write_len_field(&mut user, 2, &fx_field("role", 4, 1, 14, Some(".acme.v1.Role")));
+		write_len_field(
+			&mut user,
+			2,
+			&fx_field("counts", 5, 3, 11, Some(".acme.v1.User.CountsEntry")),
+		);
+		write_len_field(&mut user, 2, &fx_field("avatar", 6, 1, 12, None));
+		write_len_field(
+			&mut user,
+			2,
+			&fx_field("addresses", 7, 3, 11, Some(".acme.v1.User.Address")),
+		);
+		write_len_field(&mut user, 2, &fx_field("delta", 8, 1, 18, None));
+		write_len_field(&mut user, 3, &address);
+		write_len_field(&mut user, 3, &counts_entry);
+
+		let mut role = Vec::new();
+		write_len_field(&mut role, 1, b"Role");
+		for (name, number) in [("ROLE_UNSPECIFIED", 0), ("ROLE_ADMIN", 1)] {
+			let mut value = Vec::new();
+			write_len_field(&mut value, 1, name.as_bytes());
+			write_tag(&mut value, 2, WIRE_VARINT);
+			write_varint(&mut value, number);
+			write_len_field(&mut role, 2, &value);
+		}
+
+		let mut file = Vec::new();
+		write_len_field(&mut file, 1, b"acme.proto");
+		write_len_field(&mut file, 2, b"acme.v1");
+		write_len_field(&mut file, 4, &user);
+		write_len_field(&mut file, 5, &role);
+		write_len_field(&mut file, 12, b"proto3");
+
+		let mut set = Vec::new();
+		write_len_field(&mut set, 1, &file);
+
+		Ok(ProtoDescriptors::from_descriptor_set(&set)?)
+	}
+
+	#[test]
+	fn test_support_encode_proto_roundtrip() -> Result<()> {
+		// -- Setup & Fixtures
+		let descriptors = fx_descriptors()?;
+		let fx_value = json!({
+			"name": "Jen",
+			"age": 150,
+			"scores": [1, 2, 300],
+			"role": "ROLE_ADMIN",
+			"counts": {"a": 1, "b": 5_000_000_000_i64},
+			"avatar": "AAEC",
+			"addresses": [{"city": "Paris"}, {"city": "Lyon"}],
+			"delta": -3,
+		});
+
+		// -- Exec
+		let bytes = proto_encode(&descriptors, "acme.v1.User", &fx_value)?;
+		let value = proto_decode(&descriptors, ".acme.v1.User", &bytes)?;
+
+		// -- Check
+		assert_eq!(value, fx_value);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_encode_proto_wire_bytes() -> Result<()> {
+		// -- Setup & Fixtures
+		let descriptors = fx_descriptors()?;
+
+		// -- Exec & Check
+		// The protobuf.dev example (150 as varint), and the packed repeated (proto3)
+		let bytes = proto_encode(&descriptors, "acme.v1.User", &json!({"age": 150}))?;
+		assert_eq!(hex::encode(&bytes), "109601");
+		let bytes = proto_encode(&descriptors, "acme.v1.User", &json!({"scores": [3, 270]}))?;
+		assert_eq!(hex::encode(&bytes), "1a03038e02");
+		// Non-packed repeated is accepted on decode, and the unknown field 15 is skipped
+		let value = proto_decode(&descriptors, "acme.v1.User", &hex::decode("18031801780a")?)?;
+		assert_eq!(value, json!({"scores": [3, 1]}));
+		// Errors
+		assert!(proto_encode(&descriptors, "acme.v1.User", &json!({"nope": 1})).is_err());
+		assert!(proto_encode(&descriptors, "acme.v1.Nope", &json!({})).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,247 @@
+//! The Protobuf descriptor sets (`protoc --descriptor_set_out=app.desc --include_imports app.proto`).
+//!
+//! Only the parts needed by the codec are read (messages, fields, enums, map entries, packed option).
+
+use crate::Result;
+use crate::support::encode::proto_wire::{WireReader, WireValue, proto_err};
+use std::collections::HashMap;
+
+/// The messages and enums of a descriptor set, by their full name (e.g., `acme.v1.User`).
+#[derive(Debug, Default)]
+pub struct ProtoDescriptors {
+	messages: HashMap<String, MessageDesc>,
+	enums: HashMap<String, EnumDesc>,
+}
+
+#[derive(Debug, Default)]
+pub struct MessageDesc {
+	pub full_name: String,
+	pub fields: Vec<FieldDesc>,
+	pub map_entry: bool,
+	pub proto3: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct FieldDesc {
+	pub name: String,
+	pub json_name: Option<String>,
+	pub number: u32,
+	/// The `FieldDescriptorProto.Type` (e.g., 5 for int32, 9 for string, 11 for message)
+	pub kind: u32,
+	pub repeated: bool,
+	/// The full name of the message or enum type (without the leading dot)
+	pub type_name: Option<String>,
+	pub packed: Option<bool>,
+}
+
+#[derive(Debug, Default)]
+pub struct EnumDesc {
+	pub values: Vec<(String, i32)>,
+}
+
+/// Field type ids (FieldDescriptorProto.Type)
+pub(super) mod kind {
+	pub const DOUBLE: u32 = 1;
+	pub const FLOAT: u32 = 2;
+	pub const INT64: u32 = 3;
+	pub const UINT64: u32 = 4;
+	pub const INT32: u32 = 5;
+	pub const FIXED64: u32 = 6;
+	pub const FIXED32: u32 = 7;
+	pub const BOOL: u32 = 8;
+	pub const STRING: u32 = 9;
+	pub const GROUP: u32 = 10;
+	pub const MESSAGE: u32 = 11;
+	pub const BYTES: u32 = 12;
+	pub const UINT32: u32 = 13;
+	pub const ENUM: u32 = 14;
+	pub const SFIXED32: u32 = 15;
+	pub const SFIXED64: u32 = 16;
+	pub const SINT32: u32 = 17;
+	pub const SINT64: u32 = 18;
+}
+
+impl ProtoDescriptors {
+	/// Parse the serialized `FileDescriptorSet`.
+	pub fn from_descriptor_set(bytes: &[u8]) -> Result<Self> {
+		let mut descriptors = ProtoDescriptors::default();
+		let mut reader = WireReader::new(bytes);
+		while !reader.is_done() {
+			if let (1, WireValue::Len(file)) = reader.read_field()? {
+				descriptors.add_file(file)?;
+			}
+		}
+		if descriptors.messages.is_empty() {
+			return Err(proto_err("No message types in the descriptor set"));
+		}
+		Ok(descriptors)
+	}
+
+	/// The message by its full name (the leading dot is optional).
+	pub fn message(&self, name: &str) -> Result<&MessageDesc> {
+		let name = name.trim_start_matches('.');
+		self.messages.get(name).ok_or_else(|| {
+			let mut names: Vec<&str> = self
+				.messages
+				.values()
+				.filter(|m| !m.map_entry)
+				.map(|m| m.full_name.as_str())
+				.collect();
+			names.sort();
+			proto_err(format!(
+				"Message type '{name}' not found in the descriptor set (available: {})",
+				names.join(", ")
+			))
+		})
+	}
+
+	pub fn enum_desc(&self, name: &str) -> Result<&EnumDesc> {
+		self.enums
+			.get(name)
+			.ok_or_else(|| proto_err(format!("Enum type '{name}' not found in the descriptor set")))
+	}
+
+	// -- FileDescriptorProto
+	fn add_file(&mut self, bytes: &[u8]) -> Result<()> {
+		let mut package = String::new();
+		let mut proto3 = false;
+		let mut messages: Vec<&[u8]> = Vec::new();
+		let mut enums: Vec<&[u8]> = Vec::new();
+
+		let mut reader = WireReader::new(bytes);
+		while !reader.is_done() {
+			match reader.read_field()? {
+				(2, WireValue::Len(b)) => package = to_string(b)?,
+				(4, WireValue::Len(b)) => messages.push(b),
+				(5, WireValue::Len(b)) => enums.push(b),
+				(12, WireValue::Len(b)) => proto3 = b == b"proto3",
+				_ => (),
+			}
+		}
+
+		for message in messages {
+			self.add_message(message, &package, proto3)?;
+		}
+		for enum_bytes in enums {
+			self.add_enum(enum_bytes, &package)?;
+		}
+		Ok(())
+	}
+
+	// -- DescriptorProto
+	fn add_message(&mut self, bytes: &[u8], scope: &str, proto3: bool) -> Result<()> {
+		let mut message = MessageDesc {
+			proto3,
+			..Default::default()
+		};
+		let mut name = String::new();
+		let mut nested: Vec<&[u8]> = Vec::new();
+		let mut enums: Vec<&[u8]> = Vec::new();
+
+		let mut reader = WireReader::new(bytes);
+		while !reader.is_done() {
+			match reader.read_field()? {
+				(1, WireValue::Len(b)) => name = to_string(b)?,
+				(2, WireValue::Len(b)) => message.fields.push(parse_field(b)?),
+				(3, WireValue::Len(b)) => nested.push(b),
+				(4, WireValue::Len(b)) => enums.push(b),
+				(7, WireValue::Len(b)) => message.map_entry = read_bool_option(b, 7)?.unwrap_or(false),
+				_ => (),
+			}
+		}
+
+		message.full_name = scoped_name(scope, &name);
+		for nested in nested {
+			self.add_message(nested, &message.full_name, proto3)?;
+		}
+		for enum_bytes in enums {
+			self.add_enum(enum_bytes, &message.full_name)?;
+		}
+		self.messages.insert(message.full_name.clone(), message);
+		Ok(())
+	}
+
+	// -- EnumDescriptorProto
+	fn add_enum(&mut self, bytes: &[u8], scope: &str) -> Result<()> {
+		let mut name = String::new();
+		let mut enum_desc = EnumDesc::default();
+
+		let mut reader = WireReader::new(bytes);
+		while !reader.is_done() {
+			match reader.read_field()? {
+				(1, WireValue::Len(b)) => name = to_string(b)?,
+				(2, WireValue::Len(b)) => {
+					let (mut value_name, mut number) = (String::new(), 0);
+					let mut value_reader = WireReader::new(b);
+					while !value_reader.is_done() {
+						match value_reader.read_field()? {
+							(1, WireValue::Len(b)) => value_name = to_string(b)?,
+							(2, WireValue::Varint(v)) => number = v as i32,
+							_ => (),
+						}
+					}
+					enum_desc.values.push((value_name, number));
+				}
+				_ => (),
+			}
+		}
+
+		self.enums.insert(scoped_name(scope, &name), enum_desc);
+		Ok(())
+	}
+}
+
+// region:    --- Support
+
+// -- FieldDescriptorProto
+fn parse_field(bytes: &[u8]) -> Result<FieldDesc> {
+	let mut field = FieldDesc::default();
+	let mut reader = WireReader::new(bytes);
+	while !reader.is_done() {
+		match reader.read_field()? {
+			(1, WireValue::Len(b)) => field.name = to_string(b)?,
+			(3, WireValue::Varint(v)) => field.number = v as u32,
+			(4, WireValue::Varint(v)) => field.repeated = v == 3,
+			(5, WireValue::Varint(v)) => field.kind = v as u32,
+			(6, WireValue::Len(b)) => field.type_name = Some(to_string(b)?.trim_start_matches('.').to_string()),
+			(8, WireValue::Len(b)) => field.packed = read_bool_option(b, 2)?,
+			(10, WireValue::Len(b)) => field.json_name = Some(to_string(b)?),
+			_ => (),
+		}
+	}
+	if field.kind == kind::GROUP {
+		return Err(proto_err(format!(
+			"Field '{}' is a group (groups are not supported)",
+			field.name
+		)));
+	}
+	Ok(field)
+}
+
+/// Read the bool option of the options message (e.g., `MessageOptions.map_entry`, `FieldOptions.packed`).
+fn read_bool_option(bytes: &[u8], number: u32) -> Result<Option<bool>> {
+	let mut value = None;
+	let mut reader = WireReader::new(bytes);
+	while !reader.is_done() {
+		if let (n, WireValue::Varint(v)) = reader.read_field()?
+			&& n == number
+		{
+			value = Some(v != 0);
+		}
+	}
+	Ok(value)
+}
+
+fn scoped_name(scope: &str, name: &str) -> String {
+	if scope.is_empty() {
+		name.to_string()
+	} else {
+		format!("{scope}.{name}")
+	}
+}
+
+fn to_string(bytes: &[u8]) -> Result<String> {
+	String::from_utf8(bytes.to_vec()).map_err(|err| proto_err(format!("Invalid UTF-8 in the descriptor. {err}")))
+}
+
+// endregion: --- Support
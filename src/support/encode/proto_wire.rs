@@ -0,0 +1,111 @@
+//! The Protobuf wire format (the reader of the fields, and the writers of the tags and values).
+
+use crate::{Error, Result};
+
+pub(super) const WIRE_VARINT: u32 = 0;
+pub(super) const WIRE_FIXED64: u32 = 1;
+pub(super) const WIRE_LEN: u32 = 2;
+pub(super) const WIRE_FIXED32: u32 = 5;
+
+/// A field value as read from the wire (the interpretation depends on the field type)
+#[derive(Debug, Clone, Copy)]
+pub(super) enum WireValue<'a> {
+	Varint(u64),
+	Fixed64(u64),
+	Len(&'a [u8]),
+	Fixed32(u32),
+}
+
+pub(super) struct WireReader<'a> {
+	bytes: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> WireReader<'a> {
+	pub fn new(bytes: &'a [u8]) -> Self {
+		Self { bytes, pos: 0 }
+	}
+
+	pub fn is_done(&self) -> bool {
+		self.pos >= self.bytes.len()
+	}
+
+	/// Read the next field, as `(field_number, value)`.
+	pub fn read_field(&mut self) -> Result<(u32, WireValue<'a>)> {
+		let tag = self.read_varint()?;
+		let number = (tag >> 3) as u32;
+		if number == 0 {
+			return Err(proto_err("Invalid field number 0"));
+		}
+		let value = match (tag & 0x7) as u32 {
+			WIRE_VARINT => WireValue::Varint(self.read_varint()?),
+			WIRE_FIXED64 => WireValue::Fixed64(u64::from_le_bytes(self.read_array::<8>()?)),
+			WIRE_LEN => {
+				let len = self.read_varint()? as usize;
+				WireValue::Len(self.read_bytes(len)?)
+			}
+			WIRE_FIXED32 => WireValue::Fixed32(u32::from_le_bytes(self.read_array::<4>()?)),
+			wire_type => {
+				return Err(proto_err(format!(
+					"Unsupported wire type {wire_type} for field {number} (groups are not supported)"
+				)));
+			}
+		};
+		Ok((number, value))
+	}
+
+	pub fn read_varint(&mut self) -> Result<u64> {
+		let mut value: u64 = 0;
+		for shift in (0..64).step_by(7) {
+			let byte = self.read_bytes(1)?[0];
+			value |= ((byte & 0x7f) as u64) << shift;
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+		Err(proto_err("Invalid varint (more than 10 bytes)"))
+	}
+
+	pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+		let mut array = [0u8; N];
+		array.copy_from_slice(self.read_bytes(N)?);
+		Ok(array)
+	}
+
+	fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+		let end = self
+			.pos
+			.checked_add(len)
+			.filter(|end| *end <= self.bytes.len())
+			.ok_or_else(|| proto_err("Unexpected end of input"))?;
+		let bytes = &self.bytes[self.pos..end];
+		self.pos = end;
+		Ok(bytes)
+	}
+}
+
+// region:    --- Writers
+
+pub(super) fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+	while value >= 0x80 {
+		buf.push((value as u8) | 0x80);
+		value >>= 7;
+	}
+	buf.push(value as u8);
+}
+
+pub(super) fn write_tag(buf: &mut Vec<u8>, number: u32, wire_type: u32) {
+	write_varint(buf, ((number as u64) << 3) | wire_type as u64);
+}
+
+pub(super) fn write_len_field(buf: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+	write_tag(buf, number, WIRE_LEN);
+	write_varint(buf, bytes.len() as u64);
+	buf.extend_from_slice(bytes);
+}
+
+// endregion: --- Writers
+
+pub(super) fn proto_err(msg: impl Into<String>) -> Error {
+	Error::custom(format!("Protobuf - {}", msg.into()))
+}
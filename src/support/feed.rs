@@ -0,0 +1,455 @@
+//! RSS (2.0 and 1.0/RDF) and Atom feed parsing for `aip.feed`.
+//!
+//! The entries have their date normalized to RFC 3339 UTC (e.g., `2024-06-03T14:05:00Z`),
+//! from the RFC 2822 (RSS `pubDate`), RFC 3339 / ISO 8601 (Atom, `dc:date`), or the common variants of them.
+
+use crate::{Error, Result};
+use quick_xml::XmlVersion;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use serde::Serialize;
+use time::format_description::well_known::{Rfc2822, Rfc3339};
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedKind {
+	Rss,
+	Atom,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Feed {
+	pub kind: FeedKind,
+	pub title: Option<String>,
+	pub link: Option<String>,
+	pub description: Option<String>,
+	/// The feed update date, normalized (RFC 3339 UTC)
+	pub updated: Option<String>,
+	pub entries: Vec<FeedEntry>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FeedEntry {
+	/// The RSS `guid`, or Atom `id`
+	pub id: Option<String>,
+	pub title: Option<String>,
+	pub link: Option<String>,
+	/// The normalized date (RFC 3339 UTC) of the published date (or the updated date if absent)
+	pub date: Option<String>,
+	/// The date as in the feed (e.g., when it could not be normalized)
+	pub date_raw: Option<String>,
+	pub author: Option<String>,
+	/// The RSS `description` (when it has a `content:encoded`), or Atom `summary`
+	pub summary: Option<String>,
+	/// The RSS `content:encoded` (or `description`), or Atom `content` (or `summary`)
+	pub content: Option<String>,
+}
+
+/// Parse a RSS or Atom feed.
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+	let mut reader = Reader::from_str(xml);
+
+	let mut kind: Option<FeedKind> = None;
+	let mut feed = Feed {
+		kind: FeedKind::Rss,
+		title: None,
+		link: None,
+		description: None,
+		updated: None,
+		entries: Vec::new(),
+	};
+	let mut entry: Option<EntryBuilder> = None;
+	// The element being captured (local name, depth), its text includes the text of the nested elements (e.g., xhtml)
+	let mut capture: Option<(String, usize)> = None;
+	let mut text = String::new();
+	let mut depth: usize = 0;
+
+	loop {
+		let event = reader
+			.read_event()
+			.map_err(|err| feed_err(format!("Invalid XML. Cause: {err}")))?;
+		match event {
+			Event::Start(e) if capture.is_none() => {
+				let name = local_name(&e);
+				depth += 1;
+
+				// -- Root element (the feed kind)
+				if kind.is_none() {
+					kind = Some(feed_kind(&name)?);
+					continue;
+				}
+
+				match name.as_str() {
+					"channel" => {}
+					"item" | "entry" => entry = Some(EntryBuilder::default()),
+					_ => {
+						if name == "link" {
+							set_atom_link(&e, &mut feed, entry.as_mut());
+						}
+						capture = Some((name, depth));
+						text.clear();
+					}
+				}
+			}
+			Event::Empty(e) if capture.is_none() => {
+				if kind.is_none() {
+					kind = Some(feed_kind(&local_name(&e))?);
+				} else if local_name(&e) == "link" {
+					set_atom_link(&e, &mut feed, entry.as_mut());
+				}
+			}
+			Event::Start(_) => depth += 1,
+			// -- The text of the captured element (the other text is ignored)
+			Event::Text(e) if capture.is_some() => {
+				let value = e.decode().map_err(|err| feed_err(format!("Invalid XML text. Cause: {err}")))?;
+				text.push_str(&value);
+			}
+			Event::CData(e) if capture.is_some() => {
+				text.push_str(&String::from_utf8_lossy(&e));
+			}
+			Event::GeneralRef(e) if capture.is_some() => {
+				if let Ok(Some(ch)) = e.resolve_char_ref() {
+					text.push(ch);
+				} else {
+					let name = e
+						.decode()
+						.map_err(|err| feed_err(format!("Invalid XML entity. Cause: {err}")))?;
+					text.push_str(match name.as_ref() {
+						"quot" => "\"",
+						"amp" => "&",
+						"lt" => "<",
+						"gt" => ">",
+						"apos" => "'",
+						_ => "",
+					});
+				}
+			}
+			Event::End(e) => {
+				let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+				match capture.take() {
+					Some((capture_name, capture_depth)) if capture_depth == depth => {
+						let value = std::mem::take(&mut text).trim().to_string();
+						match entry.as_mut() {
+							Some(entry) => entry.set(&capture_name, value),
+							None => set_feed_field(&mut feed, &capture_name, value),
+						}
+					}
+					other => {
+						capture = other;
+						if capture.is_none()
+							&& matches!(name.as_str(), "item" | "entry")
+							&& let Some(entry) = entry.take()
+						{
+							feed.entries.push(entry.build());
+						}
+					}
+				}
+				depth = depth.saturating_sub(1);
+			}
+			Event::Eof => break,
+			_ => (),
+		}
+	}
+
+	feed.kind = kind.ok_or_else(|| feed_err("Empty feed"))?;
+	Ok(feed)
+}
+
+/// Normalize a feed date to RFC 3339 UTC (e.g., `Mon, 03 Jun 2024 14:05:00 GMT` -> `2024-06-03T14:05:00Z`).
+///
+/// Accepts RFC 2822 (with the named zones, e.g., `GMT`, `EST`, and without weekday), RFC 3339,
+/// and ISO 8601 dates without offset (UTC), or without time (midnight UTC).
+pub fn normalize_feed_date(date: &str) -> Option<String> {
+	let date = date.trim();
+	if date.is_empty() {
+		return None;
+	}
+
+	let dt = OffsetDateTime::parse(date, &Rfc3339)
+		.ok()
+		.or_else(|| parse_rfc2822_lenient(date))
+		.or_else(|| parse_iso_lenient(date))?;
+
+	dt.to_offset(UtcOffset::UTC).format(&Rfc3339).ok()
+}
+
+// region:    --- Support
+
+#[derive(Default)]
+struct EntryBuilder {
+	entry: FeedEntry,
+	published: Option<String>,
+	updated: Option<String>,
+	description: Option<String>,
+	content_encoded: Option<String>,
+}
+
+impl EntryBuilder {
+	fn set(&mut self, name: &str, value: String) {
+		if value.is_empty() {
+			return;
+		}
+		let entry = &mut self.entry;
+		match name {
+			"title" => entry.title = Some(value),
+			"link" => entry.link = entry.link.take().or(Some(value)),
+			"guid" | "id" => entry.id = Some(value),
+			"pubDate" | "published" | "issued" => self.published = Some(value),
+			"date" => self.published = self.published.take().or(Some(value)),
+			"updated" | "modified" => self.updated = Some(value),
+			"author" | "creator" => entry.author = entry.author.take().or(Some(value)),
+			// RSS `description`, Atom `summary`
+			"description" | "summary" => self.description = Some(value),
+			// RSS `content:encoded`, Atom `content`
+			"encoded" | "content" => self.content_encoded = Some(value),
+			_ => {}
+		}
+	}
+
+	fn build(self) -> FeedEntry {
+		let mut entry = self.entry;
+		let date_raw = self.published.or(self.updated);
+		entry.date = date_raw.as_deref().and_then(normalize_feed_date);
+		entry.date_raw = date_raw;
+		match self.content_encoded {
+			Some(content) => {
+				entry.content = Some(content);
+				entry.summary = self.description;
+			}
+			None => entry.content = self.description,
+		}
+		entry
+	}
+}
+
+fn set_feed_field(feed: &mut Feed, name: &str, value: String) {
+	if value.is_empty() {
+		return;
+	}
+	match name {
+		"title" => feed.title = feed.title.take().or(Some(value)),
+		"link" => feed.link = feed.link.take().or(Some(value)),
+		"description" | "subtitle" => feed.description = feed.description.take().or(Some(value)),
+		"updated" | "lastBuildDate" | "pubDate" | "date" if feed.updated.is_none() => {
+			feed.updated = normalize_feed_date(&value).or(Some(value));
+		}
+		_ => {}
+	}
+}
+
+fn feed_kind(root_name: &str) -> Result<FeedKind> {
+	match root_name {
+		"rss" | "RDF" => Ok(FeedKind::Rss),
+		"feed" => Ok(FeedKind::Atom),
+		_ => Err(feed_err(format!(
+			"Not an RSS or Atom feed (root element '{root_name}')"
+		))),
+	}
+}
+
+/// The Atom links are attributes (the alternate one, or the first without rel)
+fn set_atom_link(e: &BytesStart, feed: &mut Feed, entry: Option<&mut EntryBuilder>) {
+	let Some(href) = get_attr(e, b"href") else {
+		return;
+	};
+	if !matches!(get_attr(e, b"rel").as_deref(), None | Some("alternate")) {
+		return;
+	}
+	match entry {
+		Some(entry) => entry.entry.link = entry.entry.link.take().or(Some(href)),
+		None => feed.link = feed.link.take().or(Some(href)),
+	}
+}
+
+fn local_name(e: &BytesStart) -> String {
+	String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+}
+
+fn get_attr(e: &BytesStart, key: &[u8]) -> Option<String> {
+	for attr in e.attributes().with_checks(false).flatten() {
+		if attr.key.local_name().as_ref() == key {
+			let v = attr.normalized_value(XmlVersion::Implicit1_0);
+			return v.ok().map(|v| v.to_string());
+		}
+	}
+	None
+}
+
+/// RFC 2822, with the named zones (e.g., `GMT`, `EST`), without weekday, and single digit days.
+fn parse_rfc2822_lenient(date: &str) -> Option<OffsetDateTime> {
+	if let Ok(dt) = OffsetDateTime::parse(date, &Rfc2822) {
+		return Some(dt);
+	}
+
+	// -- Remove the weekday (e.g., `Mon, `), and normalize the zone
+	let date = date.split_once(',').map(|(_, rest)| rest.trim()).unwrap_or(date);
+	let mut parts: Vec<&str> = date.split_whitespace().collect();
+	let zone = parts.pop()?;
+	let offset = match zone.to_ascii_uppercase().as_str() {
+		"GMT" | "UT" | "UTC" | "Z" => "+0000".to_string(),
+		"EST" => "-0500".to_string(),
+		"EDT" => "-0400".to_string(),
+		"CST" => "-0600".to_string(),
+		"CDT" => "-0500".to_string(),
+		"MST" => "-0700".to_string(),
+		"MDT" => "-0600".to_string(),
+		"PST" => "-0800".to_string(),
+		"PDT" => "-0700".to_string(),
+		_ if zone.starts_with(['+', '-']) => zone.replace(':', ""),
+		_ => return None,
+	};
+	// `3 Jun 2024 14:05` -> `03 Jun 2024 14:05:00`
+	let [day, month, year, time] = parts.as_slice() else {
+		return None;
+	};
+	let time = if time.len() == 5 {
+		format!("{time}:00")
+	} else {
+		time.to_string()
+	};
+	let normalized = format!("Mon, {day:0>2} {month} {year} {time} {offset}");
+
+	// NOTE: The weekday is not checked by `time` against the date
+	OffsetDateTime::parse(&normalized, &Rfc2822).ok()
+}
+
+/// ISO 8601 without offset (e.g., `2024-06-03T14:05:00`, UTC), or without time (e.g., `2024-06-03`, midnight UTC).
+fn parse_iso_lenient(date: &str) -> Option<OffsetDateTime> {
+	let date_time_fmt = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+	let date_fmt = format_description!("[year]-[month]-[day]");
+
+	let date_no_frac = date.split('.').next().unwrap_or(date).replace(' ', "T");
+	if let Ok(dt) = PrimitiveDateTime::parse(&date_no_frac, &date_time_fmt) {
+		return Some(dt.assume_utc());
+	}
+	time::Date::parse(date, &date_fmt).ok().map(|d| d.midnight().assume_utc())
+}
+
+fn feed_err(msg: impl Into<String>) -> Error {
+	Error::custom(format!("aip.feed - {}", msg.into()))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_feed_parse_rss() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rss = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>Acme Releases</title>
+    <link>https://acme.com/releases</link>
+    <atom:link href="https://acme.com/releases/rss.xml" rel="self" type="application/rss+xml"/>
+    <description>The Acme releases</description>
+    <image><title>Acme Logo</title><url>https://acme.com/logo.png</url></image>
+    <item>
+      <title>v1.2.0 &amp; more</title>
+      <link>https://acme.com/releases/v1.2.0</link>
+      <guid>acme-v1.2.0</guid>
+      <pubDate>Mon, 3 Jun 2024 14:05 EST</pubDate>
+      <description>Short notes</description>
+      <content:encoded><![CDATA[<p>Full <b>notes</b></p>]]></content:encoded>
+    </item>
+    <item>
+      <title>v1.1.0</title>
+      <pubDate>not a date</pubDate>
+      <description>Only description</description>
+    </item>
+  </channel>
+</rss>"#;
+
+		// -- Exec
+		let feed = parse_feed(fx_rss)?;
+
+		// -- Check
+		assert_eq!(feed.kind, FeedKind::Rss);
+		assert_eq!(feed.title.as_deref(), Some("Acme Releases"));
+		assert_eq!(feed.link.as_deref(), Some("https://acme.com/releases"));
+		assert_eq!(feed.entries.len(), 2);
+		let first = &feed.entries[0];
+		assert_eq!(first.title.as_deref(), Some("v1.2.0 & more"));
+		assert_eq!(first.date.as_deref(), Some("2024-06-03T19:05:00Z"));
+		assert_eq!(first.content.as_deref(), Some("<p>Full <b>notes</b></p>"));
+		assert_eq!(first.summary.as_deref(), Some("Short notes"));
+		let second = &feed.entries[1];
+		assert!(second.date.is_none());
+		assert_eq!(second.date_raw.as_deref(), Some("not a date"));
+		assert_eq!(second.content.as_deref(), Some("Only description"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_feed_parse_atom() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_atom = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Acme Blog</title>
+  <link href="https://acme.com/blog/atom.xml" rel="self"/>
+  <link href="https://acme.com/blog"/>
+  <updated>2024-06-04T08:00:00+02:00</updated>
+  <entry>
+    <title type="html">Hello</title>
+    <link rel="alternate" href="https://acme.com/blog/hello"/>
+    <id>urn:acme:hello</id>
+    <updated>2024-06-04T08:00:00+02:00</updated>
+    <published>2024-06-03</published>
+    <author><name>Jo</name></author>
+    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml">Hello <b>world</b></div></content>
+  </entry>
+</feed>"#;
+
+		// -- Exec
+		let feed = parse_feed(fx_atom)?;
+
+		// -- Check
+		assert_eq!(feed.kind, FeedKind::Atom);
+		assert_eq!(feed.link.as_deref(), Some("https://acme.com/blog"));
+		assert_eq!(feed.updated.as_deref(), Some("2024-06-04T06:00:00Z"));
+		let entry = &feed.entries[0];
+		assert_eq!(entry.link.as_deref(), Some("https://acme.com/blog/hello"));
+		assert_eq!(entry.id.as_deref(), Some("urn:acme:hello"));
+		assert_eq!(entry.date.as_deref(), Some("2024-06-03T00:00:00Z"));
+		assert_eq!(entry.author.as_deref(), Some("Jo"));
+		assert_eq!(entry.content.as_deref(), Some("Hello world"));
+		assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_feed_normalize_feed_date() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(
+			normalize_feed_date("Tue, 04 Jun 2024 09:30:00 +0000").as_deref(),
+			Some("2024-06-04T09:30:00Z")
+		);
+		assert_eq!(
+			normalize_feed_date("04 Jun 2024 09:30:00 GMT").as_deref(),
+			Some("2024-06-04T09:30:00Z")
+		);
+		assert_eq!(
+			normalize_feed_date("2024-06-04T11:30:00.123+02:00").as_deref(),
+			Some("2024-06-04T09:30:00.123Z")
+		);
+		assert_eq!(
+			normalize_feed_date("2024-06-04 09:30:00").as_deref(),
+			Some("2024-06-04T09:30:00Z")
+		);
+		assert!(normalize_feed_date("yesterday").is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,263 @@
+//! The file metadata (permissions, owner, symlink target, times), the mode change, and the symlink creation
+//! (used by `aip.file.metadata`, `aip.file.chmod`, and `aip.file.symlink`).
+//!
+//! NOTE: The unix mode, uid/gid, and owner are only available on unix.
+//!       On the other platforms, the mode change only sets (or clears) the readonly flag (from the owner write bit).
+
+use crate::{Error, Result};
+use serde::Serialize;
+use simple_fs::SPath;
+use std::fs::Metadata;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The metadata of a file, dir, or symlink
+#[derive(Debug, Clone, Serialize)]
+pub struct FileMetadata {
+	/// `file`, `dir`, or `symlink`
+	pub kind: &'static str,
+	pub size: u64,
+	pub readonly: bool,
+	/// The unix mode permission bits (e.g., `0o755` is `493`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mode: Option<u32>,
+	/// The unix permissions (e.g., `rwxr-xr-x`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub permissions: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub uid: Option<u32>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub gid: Option<u32>,
+	/// The owner user name (when found)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub owner: Option<String>,
+	/// The symlink target (as stored in the link), for the `symlink` kind
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub symlink_target: Option<String>,
+	/// Times in microseconds since epoch (as the `FileInfo` ones)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ctime: Option<i64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub mtime: Option<i64>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub atime: Option<i64>,
+}
+
+/// Read the metadata of the path (without following the symlink for the `kind` and `symlink_target`).
+///
+/// Returns `None` if the path does not exist.
+pub fn read_file_metadata(path: &SPath) -> Result<Option<FileMetadata>> {
+	let Ok(link_meta) = std::fs::symlink_metadata(path.as_std_path()) else {
+		return Ok(None);
+	};
+
+	let is_symlink = link_meta.file_type().is_symlink();
+	let symlink_target = if is_symlink {
+		let target = std::fs::read_link(path.as_std_path())
+			.map_err(|err| Error::cc(format!("Cannot read symlink '{path}'"), err))?;
+		Some(target.to_string_lossy().replace('\\', "/"))
+	} else {
+		None
+	};
+
+	// NOTE: For a symlink, the other metadata are the target ones (the link ones when broken)
+	let meta = if is_symlink {
+		std::fs::metadata(path.as_std_path()).unwrap_or(link_meta)
+	} else {
+		link_meta
+	};
+
+	let kind = if is_symlink {
+		"symlink"
+	} else if meta.is_dir() {
+		"dir"
+	} else {
+		"file"
+	};
+
+	let (mode, uid, gid) = unix_mode_ids(&meta);
+
+	Ok(Some(FileMetadata {
+		kind,
+		size: meta.len(),
+		readonly: meta.permissions().readonly(),
+		mode,
+		permissions: mode.map(format_permissions),
+		uid,
+		gid,
+		owner: uid.and_then(user_name),
+		symlink_target,
+		ctime: epoch_us(meta.created()),
+		mtime: epoch_us(meta.modified()),
+		atime: epoch_us(meta.accessed()),
+	}))
+}
+
+/// Compute the new mode from the current mode and the chmod spec,
+/// either octal (e.g., `755`, `0o644`), or symbolic (e.g., `u+x`, `go-w`, `a=r,u+w`).
+pub fn compute_file_mode(current: u32, spec: &str) -> Result<u32> {
+	let spec = spec.trim();
+	let octal = spec.strip_prefix("0o").unwrap_or(spec);
+	if !octal.is_empty() && octal.chars().all(|c| c.is_digit(8)) {
+		return u32::from_str_radix(octal, 8)
+			.ok()
+			.filter(|mode| *mode <= 0o7777)
+			.ok_or_else(|| Error::custom(format!("Invalid file mode '{spec}'")));
+	}
+
+	let mut mode = current & 0o7777;
+	for clause in spec.split(',') {
+		let op_idx = clause
+			.find(['+', '-', '='])
+			.ok_or_else(|| Error::custom(format!("Invalid file mode '{spec}', no '+', '-', or '=' in '{clause}'")))?;
+		let (who, rest) = clause.split_at(op_idx);
+		let op = &rest[..1];
+		let perms = &rest[1..];
+
+		let who_mask = if who.is_empty() || who.contains('a') {
+			0o777
+		} else {
+			who.chars().try_fold(0, |acc, c| match c {
+				'u' => Ok(acc | 0o700),
+				'g' => Ok(acc | 0o070),
+				'o' => Ok(acc | 0o007),
+				_ => Err(Error::custom(format!("Invalid file mode '{spec}', unknown who '{c}'"))),
+			})?
+		};
+		let perm_bits = perms.chars().try_fold(0, |acc, c| match c {
+			'r' => Ok(acc | 0o444),
+			'w' => Ok(acc | 0o222),
+			'x' => Ok(acc | 0o111),
+			_ => Err(Error::custom(format!(
+				"Invalid file mode '{spec}', unknown permission '{c}'"
+			))),
+		})?;
+		let bits = who_mask & perm_bits;
+
+		mode = match op {
+			"+" => mode | bits,
+			"-" => mode & !bits,
+			// "="
+			_ => (mode & !who_mask) | bits,
+		};
+	}
+
+	Ok(mode)
+}
+
+/// Set the mode of the path (on non unix, only the readonly flag, from the owner write bit).
+pub fn set_file_mode(path: &SPath, mode: u32) -> Result<()> {
+	let meta = std::fs::metadata(path.as_std_path())
+		.map_err(|err| Error::cc(format!("Cannot read metadata of '{path}'"), err))?;
+	let mut permissions = meta.permissions();
+
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt as _;
+		permissions.set_mode(mode);
+	}
+	#[cfg(not(unix))]
+	{
+		permissions.set_readonly(mode & 0o200 == 0);
+	}
+
+	std::fs::set_permissions(path.as_std_path(), permissions)
+		.map_err(|err| Error::cc(format!("Cannot set the mode of '{path}'"), err))?;
+
+	Ok(())
+}
+
+/// Create the `link` symlink to the `target` (stored as given, so a relative target is relative to the link dir).
+///
+/// (On windows, the dir or file symlink is chosen from the target, which requires the symlink privilege.)
+pub fn create_symlink(target: &str, link: &SPath) -> Result<()> {
+	let res = {
+		#[cfg(unix)]
+		{
+			std::os::unix::fs::symlink(target, link.as_std_path())
+		}
+		#[cfg(windows)]
+		{
+			let target_full = match link.parent() {
+				Some(dir) if !SPath::new(target).is_absolute() => dir.join(target),
+				_ => SPath::new(target),
+			};
+			if target_full.is_dir() {
+				std::os::windows::fs::symlink_dir(target, link.as_std_path())
+			} else {
+				std::os::windows::fs::symlink_file(target, link.as_std_path())
+			}
+		}
+	};
+
+	res.map_err(|err| Error::cc(format!("Cannot create symlink '{link}' to '{target}'"), err))?;
+
+	Ok(())
+}
+
+// region:    --- Support
+
+fn unix_mode_ids(meta: &Metadata) -> (Option<u32>, Option<u32>, Option<u32>) {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::MetadataExt as _;
+		(Some(meta.mode() & 0o7777), Some(meta.uid()), Some(meta.gid()))
+	}
+	#[cfg(not(unix))]
+	{
+		let _ = meta;
+		(None, None, None)
+	}
+}
+
+fn user_name(uid: u32) -> Option<String> {
+	let uid: sysinfo::Uid = uid.to_string().parse().ok()?;
+	let users = sysinfo::Users::new_with_refreshed_list();
+	users.get_user_by_id(&uid).map(|user| user.name().to_string())
+}
+
+/// e.g., `0o755` to `rwxr-xr-x`
+fn format_permissions(mode: u32) -> String {
+	let mut out = String::with_capacity(9);
+	for shift in [6, 3, 0] {
+		let bits = (mode >> shift) & 0o7;
+		out.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+		out.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+		out.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+	}
+	out
+}
+
+fn epoch_us(time: std::io::Result<SystemTime>) -> Option<i64> {
+	let duration = time.ok()?.duration_since(UNIX_EPOCH).ok()?;
+	i64::try_from(duration.as_micros()).ok()
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_files_file_meta_compute_file_mode() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(compute_file_mode(0o644, "755")?, 0o755);
+		assert_eq!(compute_file_mode(0o644, "0o600")?, 0o600);
+		assert_eq!(compute_file_mode(0o644, "u+x")?, 0o744);
+		assert_eq!(compute_file_mode(0o664, "go-w")?, 0o644);
+		assert_eq!(compute_file_mode(0o777, "a=r,u+w")?, 0o644);
+		assert_eq!(compute_file_mode(0o600, "+x")?, 0o711);
+		assert!(compute_file_mode(0o644, "u+k").is_err());
+		assert!(compute_file_mode(0o644, "z+x").is_err());
+		assert!(compute_file_mode(0o644, "rwx").is_err());
+		assert_eq!(format_permissions(0o751), "rwxr-x--x");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
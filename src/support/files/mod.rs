@@ -3,11 +3,13 @@
 mod file_common;
 mod file_hash_blake3;
 mod file_hash_sha;
+mod file_meta;
 mod safer_deletes;
 
 pub use file_common::*;
 pub use file_hash_blake3::*;
 pub use file_hash_sha::*;
+pub use file_meta::*;
 pub use safer_deletes::*;
 
 // endregion: --- Modules
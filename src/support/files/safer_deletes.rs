@@ -28,6 +28,13 @@ pub fn safer_trash_dir(path: &SPath, delete_check: Option<DeleteCheck>) -> Resul
 	Ok(simple_fs::safer_trash_dir(path, options)?)
 }
 
+/// Will do a safer permanent delete (not moved to the trash)
+/// returns true if it was deleted (if not exists, return false)
+/// error if not a file
+pub fn safer_remove_file(path: &SPath) -> Result<bool> {
+	Ok(simple_fs::safer_remove_file(path, ())?)
+}
+
 // region:    --- Support
 
 fn to_options(check: Option<DeleteCheck>) -> SaferTrashOptions<'static> {
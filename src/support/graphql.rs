@@ -0,0 +1,164 @@
+//! The GraphQL over HTTP support of `aip.graphql` (the request body, the response parsing, and the introspection query).
+//!
+//! The responses with `data` or `errors` are GraphQL responses (even with a non-2xx status, as the GraphQL over HTTP spec allows),
+//! the others are transport errors.
+
+use serde_json::{Map, Value, json};
+
+/// The schema introspection query (types with their fields, args, input fields, and enum values)
+pub const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind
+      name
+      description
+      fields(includeDeprecated: true) {
+        name
+        description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+        isDeprecated
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      interfaces { ...TypeRef }
+      enumValues(includeDeprecated: true) { name description isDeprecated }
+      possibleTypes { ...TypeRef }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}
+"#;
+
+#[derive(Debug)]
+pub struct GraphqlResponse {
+	pub data: Option<Value>,
+	/// The GraphQL errors (e.g., `{message, locations?, path?, extensions?}`)
+	pub errors: Vec<Value>,
+	pub extensions: Option<Value>,
+}
+
+impl GraphqlResponse {
+	/// Parse the response body.
+	/// Returns None when the body is not a GraphQL response (not JSON, or without `data` and `errors`).
+	pub fn from_body(body: &str) -> Option<Self> {
+		let Ok(Value::Object(mut body)) = serde_json::from_str::<Value>(body) else {
+			return None;
+		};
+		if !body.contains_key("data") && !body.contains_key("errors") {
+			return None;
+		}
+		let data = body.remove("data").filter(|data| !data.is_null());
+		let errors = match body.remove("errors") {
+			Some(Value::Array(errors)) => errors,
+			Some(Value::Null) | None => Vec::new(),
+			Some(other) => vec![other],
+		};
+		let extensions = body.remove("extensions");
+
+		Some(GraphqlResponse {
+			data,
+			errors,
+			extensions,
+		})
+	}
+
+	/// The error messages, with their path (e.g., `Not authorized (at repository.issues)`), joined with `; `
+	pub fn errors_summary(&self) -> String {
+		self.errors
+			.iter()
+			.map(|error| {
+				let message = error.get("message").and_then(Value::as_str).unwrap_or("Unknown error");
+				match error.get("path").and_then(Value::as_array) {
+					Some(path) if !path.is_empty() => {
+						let path: Vec<String> = path
+							.iter()
+							.map(|p| p.as_str().map(str::to_string).unwrap_or_else(|| p.to_string()))
+							.collect();
+						format!("{message} (at {})", path.join("."))
+					}
+					_ => message.to_string(),
+				}
+			})
+			.collect::<Vec<_>>()
+			.join("; ")
+	}
+}
+
+/// The POST body `{query, variables?, operationName?}`
+pub fn request_body(query: &str, variables: Option<Value>, operation_name: Option<&str>) -> Value {
+	let mut body = Map::new();
+	body.insert("query".to_string(), json!(query));
+	if let Some(variables) = variables.filter(|v| !v.is_null()) {
+		// NOTE: an empty Lua table is an empty array, the variables must be an object
+		let variables = match variables {
+			Value::Array(values) if values.is_empty() => json!({}),
+			variables => variables,
+		};
+		body.insert("variables".to_string(), variables);
+	}
+	if let Some(operation_name) = operation_name {
+		body.insert("operationName".to_string(), json!(operation_name));
+	}
+	Value::Object(body)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_graphql_response_from_body() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_partial = r#"{
+			"data": { "repository": { "name": "aipack", "issues": null } },
+			"errors": [{ "message": "Not authorized", "path": ["repository", "issues"] }, { "message": "Too complex" }]
+		}"#;
+
+		// -- Exec
+		let partial = GraphqlResponse::from_body(fx_partial).ok_or("should be a graphql response")?;
+		let ok =
+			GraphqlResponse::from_body(r#"{"data": {"viewer": {"login": "jc"}}}"#).ok_or("should be a response")?;
+
+		// -- Check
+		assert_eq!(partial.errors.len(), 2);
+		assert_eq!(
+			partial.errors_summary(),
+			"Not authorized (at repository.issues); Too complex"
+		);
+		assert!(partial.data.is_some());
+		assert!(ok.errors.is_empty());
+		assert!(GraphqlResponse::from_body("<html>Bad Gateway</html>").is_none());
+		assert!(GraphqlResponse::from_body(r#"{"message": "Not Found"}"#).is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_graphql_request_body() -> Result<()> {
+		// -- Exec
+		let body = request_body("query { a }", Some(json!([])), Some("GetA"));
+		let body_no_vars = request_body("query { a }", None, None);
+
+		// -- Check
+		assert_eq!(body["variables"], json!({}));
+		assert_eq!(body["operationName"], "GetA");
+		assert!(body_no_vars.get("variables").is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,17 +3,19 @@
 use crate::Result;
 use handlebars::Handlebars;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
 // endregion: --- Modules
 
-static HANDLEBARS: LazyLock<Arc<Handlebars>> = LazyLock::new(|| {
+static HANDLEBARS: LazyLock<Arc<Handlebars>> = LazyLock::new(|| Arc::new(new_handlebars()));
+
+fn new_handlebars() -> Handlebars<'static> {
 	let mut handlebars = Handlebars::new();
 	// Disable escaping globally
 	handlebars.register_escape_fn(|s| s.to_string());
-
-	Arc::new(handlebars)
-});
+	handlebars
+}
 
 pub fn hbs_render<T>(hbs_tmpl: &str, data_root: &T) -> Result<String>
 where
@@ -24,6 +26,26 @@ where
 	Ok(res)
 }
 
+/// Same as `hbs_render`, with the named partials (e.g., `{{> style}}`), like the agent `# Meta` `partials`.
+pub fn hbs_render_with_partials<T>(hbs_tmpl: &str, partials: &HashMap<String, String>, data_root: &T) -> Result<String>
+where
+	T: Serialize,
+{
+	if partials.is_empty() {
+		return hbs_render(hbs_tmpl, data_root);
+	}
+
+	// NOTE: The partials are per agent, so a new registry (cheap compared to the AI call)
+	let mut handlebars = new_handlebars();
+	for (name, content) in partials {
+		handlebars
+			.register_partial(name, content)
+			.map_err(|err| crate::Error::custom(format!("Handlebars partial '{name}' is invalid. Cause: {err}")))?;
+	}
+	let res = handlebars.render_template(hbs_tmpl, data_root)?;
+	Ok(res)
+}
+
 // region:    --- Tests
 
 #[cfg(test)]
@@ -32,8 +54,29 @@ mod tests {
 
 	use crate::_test_support::assert_contains;
 	use crate::runtime::Runtime;
-	use crate::support::hbs::hbs_render;
+	use crate::support::hbs::{hbs_render, hbs_render_with_partials};
 	use serde_json::json;
+	use std::collections::HashMap;
+
+	#[test]
+	fn test_hbs_render_with_partials() -> Result<()> {
+		// -- Setup & Fixtures
+		let partials = HashMap::from([
+			("style".to_string(), "Be {{tone}}.".to_string()),
+			("base_instruction".to_string(), "Review the code.\n".to_string()),
+		]);
+		let tmpl = "{{> base_instruction}}\n{{> style}} <{{name}}>";
+
+		// -- Exec
+		let res = hbs_render_with_partials(tmpl, &partials, &json!({"tone": "concise", "name": "<main>"}))?;
+		let missing_res = hbs_render_with_partials("{{> nope}}", &partials, &json!({}));
+
+		// -- Check
+		assert_eq!(res, "Review the code.\nBe concise. <<main>>");
+		assert!(missing_res.is_err(), "missing partial should fail");
+
+		Ok(())
+	}
 
 	#[tokio::test]
 	async fn test_hbs_with_lua_ok() -> Result<()> {
@@ -1,4 +1,13 @@
 //! HTML Utilities
+
+// region:    --- Modules
+
+mod readability;
+
+pub use readability::*;
+
+// endregion: --- Modules
+
 use crate::{Error, Result};
 use htmlr::SlimOptions;
 
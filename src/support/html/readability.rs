@@ -0,0 +1,429 @@
+//! A readability style extraction of the main content of a html page (e.g., the article of a blog post),
+//! without the navigation, ads, scripts, and the other boilerplate blocks.
+//!
+//! The main content is the `<article>` / `<main>` element when present, otherwise the element
+//! with the best paragraph score (text length and commas, minus the link density, plus the class/id hints).
+
+use crate::Result;
+use crate::support::html::to_md;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::collections::HashMap;
+use url::Url;
+
+/// The elements never part of the content
+const REMOVED_TAGS: &[&str] = &[
+	"script", "style", "noscript", "template", "iframe", "object", "embed", "svg", "canvas", "nav", "aside", "footer",
+	"form", "button", "input", "select", "textarea", "dialog", "menu", "link", "meta", "head",
+];
+
+/// The class/id prefixes of the boilerplate blocks (e.g., `main-nav`, `share-buttons`, `advertisement`)
+const NEGATIVE_HINTS: &[&str] = &[
+	"nav",
+	"menu",
+	"footer",
+	"sidebar",
+	"comment",
+	"share",
+	"social",
+	"related",
+	"promo",
+	"banner",
+	"advert",
+	"ad",
+	"ads",
+	"sponsor",
+	"cookie",
+	"consent",
+	"newsletter",
+	"subscribe",
+	"popup",
+	"modal",
+	"breadcrumb",
+	"widget",
+	"masthead",
+];
+
+/// The class/id prefixes of the content blocks
+const POSITIVE_HINTS: &[&str] = &["article", "content", "entry", "main", "post", "story", "body", "text", "blog"];
+
+/// The explicit main content selectors, tried first
+const MAIN_SELECTORS: &str = r#"article, main, [role="main"], [itemprop="articleBody"]"#;
+
+/// The min text length of an explicit main content (below, the paragraph scoring is used)
+const MAIN_MIN_TEXT_LEN: usize = 200;
+
+/// The min text length of a scored paragraph
+const PARAGRAPH_MIN_TEXT_LEN: usize = 25;
+
+const VOID_TAGS: &[&str] = &["br", "hr", "img"];
+
+/// The extracted main content of a html page
+#[derive(Debug, Clone)]
+pub struct HtmlArticle {
+	pub title: Option<String>,
+	/// The cleaned html of the main content (boilerplate removed, links made absolute when a base url is given)
+	pub content_html: String,
+}
+
+impl HtmlArticle {
+	/// The markdown of the content, with the title as `# ` heading when the content has no `h1`
+	pub fn to_md(&self) -> Result<String> {
+		let md = to_md(self.content_html.clone())?;
+		let md = collapse_blank_lines(&md);
+
+		let res = match self.title.as_deref() {
+			Some(title) if !self.content_html.contains("<h1") => format!("# {title}\n\n{md}"),
+			_ => md,
+		};
+		Ok(res)
+	}
+}
+
+/// Extract the main content of a html page.
+///
+/// `base_url` is the page url, used to make the relative `href` and `src` absolute.
+pub fn extract_article(html_content: &str, base_url: Option<&str>) -> HtmlArticle {
+	let html = Html::parse_document(html_content);
+	let base_url = base_url.and_then(|url| Url::parse(url).ok());
+
+	let title = extract_title(&html);
+	let content_html = match find_main_element(&html) {
+		Some(main_el) => {
+			let mut out = String::new();
+			write_clean(main_el, base_url.as_ref(), &mut out);
+			out
+		}
+		None => String::new(),
+	};
+
+	HtmlArticle { title, content_html }
+}
+
+// region:    --- Main Element
+
+fn find_main_element(html: &Html) -> Option<ElementRef<'_>> {
+	// -- The explicit main content (the longest, if many)
+	let main_el = select_all(html, MAIN_SELECTORS)
+		.into_iter()
+		.map(|el| (visible_text_len(el), el))
+		.filter(|(len, _)| *len >= MAIN_MIN_TEXT_LEN)
+		.max_by_key(|(len, _)| *len)
+		.map(|(_, el)| el);
+	if main_el.is_some() {
+		return main_el;
+	}
+
+	// -- The paragraph scoring (the score of a paragraph goes to its parent, and half to its grand parent)
+	let mut scores: HashMap<_, (ElementRef, f64)> = HashMap::new();
+	for para in select_all(html, "p, pre, td, blockquote") {
+		if is_removed(para) {
+			continue;
+		}
+		let text = visible_text(para);
+		let text_len = text.trim().chars().count();
+		if text_len < PARAGRAPH_MIN_TEXT_LEN {
+			continue;
+		}
+		let score = 1.0 + text.matches(',').count() as f64 + (text_len as f64 / 100.0).min(3.0);
+
+		let parent = para.parent().and_then(ElementRef::wrap);
+		let grand_parent = parent.and_then(|el| el.parent()).and_then(ElementRef::wrap);
+		for (el, share) in [(parent, 1.0), (grand_parent, 0.5)] {
+			if let Some(el) = el {
+				let entry = scores.entry(el.id()).or_insert((el, hint_weight(el)));
+				entry.1 += score * share;
+			}
+		}
+	}
+
+	let best = scores
+		.into_values()
+		.map(|(el, score)| (el, score * (1.0 - link_density(el))))
+		.max_by(|(_, a), (_, b)| a.total_cmp(b))
+		.map(|(el, _)| el);
+
+	best.or_else(|| select_all(html, "body").into_iter().next())
+}
+
+fn extract_title(html: &Html) -> Option<String> {
+	let og_title = select_all(html, r#"meta[property="og:title"]"#)
+		.into_iter()
+		.find_map(|el| el.value().attr("content").map(str::to_string));
+	let title = og_title
+		.or_else(|| select_all(html, "title").into_iter().next().map(visible_text))
+		.or_else(|| select_all(html, "h1").into_iter().next().map(visible_text))?;
+
+	let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+	if title.is_empty() { None } else { Some(title) }
+}
+
+// endregion: --- Main Element
+
+// region:    --- Clean Html
+
+/// Write the clean html of the children of `el` (only the content tags, and the `href`, `src`, `alt`, `title` attributes).
+fn write_clean(el: ElementRef, base_url: Option<&Url>, out: &mut String) {
+	for child in el.children() {
+		match child.value() {
+			Node::Text(text) => out.push_str(&escape_html(text, false)),
+			Node::Element(_) => {
+				let Some(child_el) = ElementRef::wrap(child) else {
+					continue;
+				};
+				if is_removed(child_el) || is_link_block(child_el) {
+					continue;
+				}
+
+				let name = child_el.value().name();
+				out.push('<');
+				out.push_str(name);
+				for attr_name in ["href", "src", "alt", "title"] {
+					if let Some(value) = child_el.value().attr(attr_name) {
+						let value = match (attr_name, base_url) {
+							("href" | "src", Some(base_url)) => base_url
+								.join(value)
+								.map(|url| url.to_string())
+								.unwrap_or_else(|_| value.to_string()),
+							_ => value.to_string(),
+						};
+						out.push_str(&format!(" {attr_name}=\"{}\"", escape_html(&value, true)));
+					}
+				}
+				out.push('>');
+
+				if VOID_TAGS.contains(&name) {
+					continue;
+				}
+				write_clean(child_el, base_url, out);
+				out.push_str(&format!("</{name}>"));
+			}
+			_ => (),
+		}
+	}
+}
+
+/// The non-content elements (boilerplate tags, negative class/id hints, hidden elements)
+fn is_removed(el: ElementRef) -> bool {
+	let value = el.value();
+	if REMOVED_TAGS.contains(&value.name()) {
+		return true;
+	}
+	if value.attr("hidden").is_some() || value.attr("aria-hidden") == Some("true") {
+		return true;
+	}
+	if let Some(style) = value.attr("style") {
+		let style = style.replace(' ', "").to_lowercase();
+		if style.contains("display:none") || style.contains("visibility:hidden") {
+			return true;
+		}
+	}
+	hint_tokens(el).any(|token| has_hint(&token, NEGATIVE_HINTS))
+}
+
+/// The `div` / `section` / `ul` blocks mostly made of links (e.g., "related articles", tag clouds)
+fn is_link_block(el: ElementRef) -> bool {
+	matches!(el.value().name(), "div" | "section" | "ul" | "ol")
+		&& visible_text_len(el) < 500
+		&& link_density(el) > 0.6
+		&& !select_in(el, "p").iter().any(|p| visible_text_len(*p) >= 80)
+}
+
+// endregion: --- Clean Html
+
+// region:    --- Support
+
+fn select_all<'a>(html: &'a Html, selectors: &str) -> Vec<ElementRef<'a>> {
+	match Selector::parse(selectors) {
+		Ok(selector) => html.select(&selector).collect(),
+		Err(_) => Vec::new(),
+	}
+}
+
+fn select_in<'a>(el: ElementRef<'a>, selectors: &str) -> Vec<ElementRef<'a>> {
+	match Selector::parse(selectors) {
+		Ok(selector) => el.select(&selector).collect(),
+		Err(_) => Vec::new(),
+	}
+}
+
+/// The text of the element, without the text of the removed elements
+fn visible_text(el: ElementRef) -> String {
+	let mut text = String::new();
+	for child in el.children() {
+		match child.value() {
+			Node::Text(t) => text.push_str(t),
+			Node::Element(_) => {
+				if let Some(child_el) = ElementRef::wrap(child)
+					&& !is_removed(child_el)
+				{
+					text.push_str(&visible_text(child_el));
+				}
+			}
+			_ => (),
+		}
+	}
+	text
+}
+
+fn visible_text_len(el: ElementRef) -> usize {
+	visible_text(el).split_whitespace().map(|word| word.chars().count() + 1).sum()
+}
+
+/// The ratio of the link text over the text (0.0 to 1.0)
+fn link_density(el: ElementRef) -> f64 {
+	let text_len = visible_text_len(el);
+	if text_len == 0 {
+		return 0.0;
+	}
+	let link_len: usize = select_in(el, "a").into_iter().map(visible_text_len).sum();
+	(link_len as f64 / text_len as f64).min(1.0)
+}
+
+/// The class/id weight (+25 per positive hint, -25 per negative hint)
+fn hint_weight(el: ElementRef) -> f64 {
+	hint_tokens(el)
+		.map(|token| {
+			if has_hint(&token, NEGATIVE_HINTS) {
+				-25.0
+			} else if has_hint(&token, POSITIVE_HINTS) {
+				25.0
+			} else {
+				0.0
+			}
+		})
+		.sum()
+}
+
+/// The lowercase words of the class and id (e.g., `main-nav` -> `main`, `nav`)
+fn hint_tokens<'a>(el: ElementRef<'a>) -> impl Iterator<Item = String> + 'a {
+	let value = el.value();
+	[value.attr("class"), value.attr("id")]
+		.into_iter()
+		.flatten()
+		.flat_map(|attr| attr.split(|c: char| !c.is_ascii_alphanumeric()))
+		.filter(|token| !token.is_empty())
+		.map(|token| token.to_ascii_lowercase())
+}
+
+/// `ad` and `ads` must match the whole token (not `address`, `adjust`), the other hints are prefixes
+fn has_hint(token: &str, hints: &[&str]) -> bool {
+	hints.iter().any(|hint| {
+		if hint.len() <= 3 && hint.starts_with("ad") {
+			token == *hint
+		} else {
+			token.starts_with(hint)
+		}
+	})
+}
+
+fn escape_html(text: &str, is_attr: bool) -> String {
+	let mut res = String::with_capacity(text.len());
+	for c in text.chars() {
+		match c {
+			'&' => res.push_str("&amp;"),
+			'<' => res.push_str("&lt;"),
+			'>' => res.push_str("&gt;"),
+			'"' if is_attr => res.push_str("&quot;"),
+			_ => res.push(c),
+		}
+	}
+	res
+}
+
+/// Collapse the 3+ new lines into one blank line, and trim
+fn collapse_blank_lines(md: &str) -> String {
+	let mut res = String::with_capacity(md.len());
+	let mut blank_count = 0;
+	for line in md.lines() {
+		if line.trim().is_empty() {
+			blank_count += 1;
+			if blank_count > 1 {
+				continue;
+			}
+		} else {
+			blank_count = 0;
+		}
+		res.push_str(line.trim_end());
+		res.push('\n');
+	}
+	res.trim().to_string()
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	const FX_PAGE: &str = r#"
+<html>
+<head><title>Site - The Rust Ownership</title><script>var tracking = 1;</script></head>
+<body>
+	<nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+	<div class="main-nav"><a href="/a">A</a></div>
+	<div id="content">
+		<div class="post-body">
+			<h2>Ownership</h2>
+			<p>Ownership is a set of rules that govern how a Rust program manages memory, checked by the compiler.</p>
+			<p>If any of the rules are violated, the program won't compile, and none of the features slow down the program.</p>
+			<p>See the <a href="book/ch04.html">book chapter</a> for more, with examples, exercises, and details.</p>
+			<div class="share-buttons"><a href="https://x.com/share">Share</a></div>
+		</div>
+		<div class="related"><a href="/p1">Post 1</a> <a href="/p2">Post 2</a></div>
+	</div>
+	<aside>Subscribe to the newsletter for more posts, with tips, news, and more.</aside>
+	<footer>Copyright, all rights reserved, 2025.</footer>
+</body>
+</html>
+"#;
+
+	#[test]
+	fn test_html_readability_extract_article_scored() -> Result<()> {
+		// -- Exec
+		let article = extract_article(FX_PAGE, Some("https://example.com/blog/ownership.html"));
+		let md = article.to_md()?;
+
+		// -- Check
+		assert_eq!(article.title.as_deref(), Some("Site - The Rust Ownership"));
+		assert!(md.starts_with("# Site - The Rust Ownership"), "{md}");
+		assert!(md.contains("Ownership is a set of rules"), "{md}");
+		assert!(md.contains("(https://example.com/blog/book/ch04.html)"), "{md}");
+		for boilerplate in ["Home", "Share", "Post 1", "Subscribe", "Copyright", "tracking"] {
+			assert!(!md.contains(boilerplate), "'{boilerplate}' should be removed:\n{md}");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_html_readability_extract_article_main_tag() -> Result<()> {
+		// -- Setup & Fixtures
+		let html = format!(
+			r#"<html><head><meta property="og:title" content="OG Title"></head><body>
+<div class="sidebar"><p>{sidebar}</p></div>
+<article><h1>The Article</h1><p>{para}</p><script>alert(1)</script></article>
+</body></html>"#,
+			sidebar = "Sidebar text, with many, many, many, commas. ".repeat(10),
+			para = "The article paragraph text. ".repeat(10),
+		);
+
+		// -- Exec
+		let article = extract_article(&html, None);
+		let md = article.to_md()?;
+
+		// -- Check
+		assert_eq!(article.title.as_deref(), Some("OG Title"));
+		assert!(md.starts_with("# The Article"), "{md}");
+		assert!(md.contains("The article paragraph text."), "{md}");
+		assert!(!md.contains("Sidebar"), "{md}");
+		assert!(!md.contains("alert"), "{md}");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,203 @@
+use crate::Result;
+use crate::error::Error;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as B64;
+use image::{DynamicImage, ImageFormat, ImageReader};
+use simple_fs::{SPath, ensure_file_dir};
+use std::io::Cursor;
+
+#[derive(Debug, Clone)]
+pub struct ImageInfo {
+	pub width: u32,
+	pub height: u32,
+	/// e.g., `png`, `jpeg`, `gif`, `webp`
+	pub format: String,
+	/// The file size in bytes
+	pub size: u64,
+}
+
+/// The max dimensions (keeps the aspect ratio, never upscales) and the eventual output format.
+#[derive(Debug, Clone, Default)]
+pub struct ImageResizeOptions {
+	pub max_width: Option<u32>,
+	pub max_height: Option<u32>,
+	pub format: Option<ImageFormat>,
+}
+
+impl ImageResizeOptions {
+	fn is_noop(&self) -> bool {
+		self.max_width.is_none() && self.max_height.is_none() && self.format.is_none()
+	}
+}
+
+/// Read the image dimensions and format (without decoding the whole image).
+pub fn image_info(path: &SPath) -> Result<ImageInfo> {
+	let reader = open_reader(path)?;
+	let format = reader.format().ok_or_else(|| format!("Unknown image format for '{path}'"))?;
+	let (width, height) = reader
+		.into_dimensions()
+		.map_err(|err| Error::cc(format!("Cannot read image dimensions of '{path}'"), err))?;
+	let size = std::fs::metadata(path.as_std_path())?.len();
+
+	Ok(ImageInfo {
+		width,
+		height,
+		format: format_name(format).to_string(),
+		size,
+	})
+}
+
+/// Resize (fit within the max dimensions) and save the image to `dest`.
+/// The output format is the `options.format`, or the one of the `dest` extension.
+pub fn resize_image(src: &SPath, dest: &SPath, options: &ImageResizeOptions) -> Result<ImageInfo> {
+	let img = load_image(src)?;
+	let img = fit_image(img, options);
+
+	let format = match options.format {
+		Some(format) => format,
+		None => ImageFormat::from_path(dest.as_std_path())
+			.map_err(|err| Error::cc(format!("Cannot infer the image format of '{dest}'"), err))?,
+	};
+
+	ensure_file_dir(dest).map_err(Error::from)?;
+	let bytes = encode_image(&img, format)?;
+	std::fs::write(dest.as_std_path(), &bytes)?;
+
+	Ok(ImageInfo {
+		width: img.width(),
+		height: img.height(),
+		format: format_name(format).to_string(),
+		size: bytes.len() as u64,
+	})
+}
+
+/// Returns the `(content_type, base64_content)` of the image, resized/converted if options.
+/// NOTE: Without options, the file bytes are encoded as is.
+pub fn image_to_b64(path: &SPath, options: &ImageResizeOptions) -> Result<(String, String)> {
+	if options.is_noop() {
+		let format = open_reader(path)?
+			.format()
+			.ok_or_else(|| format!("Unknown image format for '{path}'"))?;
+		let bytes = std::fs::read(path.as_std_path())?;
+		return Ok((format.to_mime_type().to_string(), B64.encode(bytes)));
+	}
+
+	let reader = open_reader(path)?;
+	let src_format = reader.format().ok_or_else(|| format!("Unknown image format for '{path}'"))?;
+	let img = reader
+		.decode()
+		.map_err(|err| Error::cc(format!("Cannot decode image '{path}'"), err))?;
+	let img = fit_image(img, options);
+
+	let format = options.format.unwrap_or(src_format);
+	let bytes = encode_image(&img, format)?;
+
+	Ok((format.to_mime_type().to_string(), B64.encode(bytes)))
+}
+
+/// Parse a format name (e.g., `png`, `jpeg`, `jpg`, `webp`, `gif`).
+pub fn parse_image_format(name: &str) -> Result<ImageFormat> {
+	ImageFormat::from_extension(name.trim().to_lowercase())
+		.filter(|format| {
+			matches!(
+				format,
+				ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif | ImageFormat::WebP
+			)
+		})
+		.ok_or_else(|| Error::custom(format!("Image format '{name}' not supported (png, jpeg, gif, webp)")))
+}
+
+// region:    --- Support
+
+fn open_reader(path: &SPath) -> Result<ImageReader<std::io::BufReader<std::fs::File>>> {
+	ImageReader::open(path.as_std_path())
+		.map_err(|err| Error::cc(format!("Cannot open image '{path}'"), err))?
+		.with_guessed_format()
+		.map_err(|err| Error::cc(format!("Cannot read image '{path}'"), err))
+}
+
+fn load_image(path: &SPath) -> Result<DynamicImage> {
+	open_reader(path)?
+		.decode()
+		.map_err(|err| Error::cc(format!("Cannot decode image '{path}'"), err))
+}
+
+/// Fit the image within the max dimensions (keeps the aspect ratio, never upscales).
+fn fit_image(img: DynamicImage, options: &ImageResizeOptions) -> DynamicImage {
+	let max_width = options.max_width.unwrap_or(u32::MAX).max(1);
+	let max_height = options.max_height.unwrap_or(u32::MAX).max(1);
+	if img.width() <= max_width && img.height() <= max_height {
+		return img;
+	}
+	img.resize(max_width, max_height, image::imageops::FilterType::Lanczos3)
+}
+
+fn encode_image(img: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>> {
+	// NOTE: JPEG does not support the alpha channel
+	let img = match format {
+		ImageFormat::Jpeg => DynamicImage::ImageRgb8(img.to_rgb8()),
+		_ => img.clone(),
+	};
+	let mut bytes = Vec::new();
+	img.write_to(&mut Cursor::new(&mut bytes), format)
+		.map_err(|err| Error::cc(format!("Cannot encode image as {}", format_name(format)), err))?;
+	Ok(bytes)
+}
+
+fn format_name(format: ImageFormat) -> &'static str {
+	match format {
+		ImageFormat::Jpeg => "jpeg",
+		other => other.extensions_str().first().copied().unwrap_or("unknown"),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use image::{Rgba, RgbaImage};
+
+	#[test]
+	fn test_support_images_resize_info_b64() -> Result<()> {
+		// -- Setup & Fixtures
+		let dir = SPath::new("tests-data/sandbox-01/.tmp/test_support_images");
+		let src = dir.join("src.png");
+		let dest = dir.join("dest.jpg");
+		ensure_file_dir(&src)?;
+		RgbaImage::from_pixel(200, 100, Rgba([10, 20, 30, 255])).save(src.as_std_path())?;
+
+		// -- Exec
+		let src_info = image_info(&src)?;
+		let dest_info = resize_image(
+			&src,
+			&dest,
+			&ImageResizeOptions {
+				max_width: Some(50),
+				..Default::default()
+			},
+		)?;
+		let (content_type, content) = image_to_b64(&dest, &ImageResizeOptions::default())?;
+
+		// -- Check
+		assert_eq!(
+			(src_info.width, src_info.height, src_info.format.as_str()),
+			(200, 100, "png")
+		);
+		assert_eq!(
+			(dest_info.width, dest_info.height, dest_info.format.as_str()),
+			(50, 25, "jpeg")
+		);
+		assert_eq!(image_info(&dest)?.format, "jpeg");
+		assert_eq!(content_type, "image/jpeg");
+		assert_eq!(B64.decode(content)?.len() as u64, dest_info.size);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -59,3 +59,179 @@ pub fn into_values<T: serde::Serialize>(vals: Vec<T>) -> Result<Vec<Value>> {
 
 	Ok(inputs)
 }
+
+// region:    --- Json Schema
+
+/// Validate a value against a (subset of) JSON schema, and returns the validation errors (empty when valid).
+///
+/// Supported keywords: `type` (string or list), `enum`, `const`, `properties`, `required`,
+/// `additionalProperties` (bool or schema), `items`, `minItems`, `maxItems`, `anyOf`.
+/// (other keywords are ignored)
+pub fn validate_json_schema(value: &Value, schema: &Value) -> Vec<String> {
+	let mut errors = Vec::new();
+	validate_schema_node(value, schema, "$", &mut errors);
+	errors
+}
+
+fn validate_schema_node(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+	let Some(schema) = schema.as_object() else {
+		// NOTE: `true` / `{}` schema accept everything, `false` nothing
+		if schema == &Value::Bool(false) {
+			errors.push(format!("{path}: no value allowed"));
+		}
+		return;
+	};
+
+	// -- type
+	if let Some(typ) = schema.get("type") {
+		let types: Vec<&str> = match typ {
+			Value::String(typ) => vec![typ.as_str()],
+			Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+			_ => Vec::new(),
+		};
+		if !types.is_empty() && !types.iter().any(|typ| is_json_type(value, typ)) {
+			errors.push(format!(
+				"{path}: expected type '{}' but got '{}'",
+				types.join("|"),
+				json_type_name(value)
+			));
+			// NOTE: No need to check further this node
+			return;
+		}
+	}
+
+	// -- enum & const
+	if let Some(Value::Array(variants)) = schema.get("enum")
+		&& !variants.contains(value)
+	{
+		errors.push(format!(
+			"{path}: value {value} is not one of {}",
+			Value::Array(variants.clone())
+		));
+	}
+	if let Some(const_value) = schema.get("const")
+		&& const_value != value
+	{
+		errors.push(format!("{path}: value {value} must be {const_value}"));
+	}
+
+	// -- anyOf
+	if let Some(Value::Array(sub_schemas)) = schema.get("anyOf")
+		&& !sub_schemas
+			.iter()
+			.any(|sub_schema| validate_json_schema(value, sub_schema).is_empty())
+	{
+		errors.push(format!("{path}: value does not match any of the 'anyOf' schemas"));
+	}
+
+	// -- object
+	if let Value::Object(obj) = value {
+		let properties = schema.get("properties").and_then(|p| p.as_object());
+		if let Some(Value::Array(required)) = schema.get("required") {
+			for name in required.iter().filter_map(|n| n.as_str()) {
+				if !obj.contains_key(name) {
+					errors.push(format!("{path}: missing required property '{name}'"));
+				}
+			}
+		}
+		for (name, prop_value) in obj {
+			let prop_path = format!("{path}.{name}");
+			match properties.and_then(|props| props.get(name)) {
+				Some(prop_schema) => validate_schema_node(prop_value, prop_schema, &prop_path, errors),
+				None => match schema.get("additionalProperties") {
+					Some(Value::Bool(false)) => errors.push(format!("{path}: property '{name}' is not allowed")),
+					Some(additional_schema @ Value::Object(_)) => {
+						validate_schema_node(prop_value, additional_schema, &prop_path, errors)
+					}
+					_ => (),
+				},
+			}
+		}
+	}
+
+	// -- array
+	if let Value::Array(items) = value {
+		if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64())
+			&& (items.len() as u64) < min
+		{
+			errors.push(format!("{path}: expected at least {min} items but got {}", items.len()));
+		}
+		if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64())
+			&& (items.len() as u64) > max
+		{
+			errors.push(format!("{path}: expected at most {max} items but got {}", items.len()));
+		}
+		if let Some(item_schema) = schema.get("items") {
+			for (idx, item) in items.iter().enumerate() {
+				validate_schema_node(item, item_schema, &format!("{path}[{idx}]"), errors);
+			}
+		}
+	}
+}
+
+fn is_json_type(value: &Value, typ: &str) -> bool {
+	match typ {
+		"string" => value.is_string(),
+		"number" => value.is_number(),
+		"integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|v| v.fract() == 0.0),
+		"boolean" => value.is_boolean(),
+		"object" => value.is_object(),
+		"array" => value.is_array(),
+		"null" => value.is_null(),
+		// NOTE: Unknown types are not validated
+		_ => true,
+	}
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+	match value {
+		Value::Null => "null",
+		Value::Bool(_) => "boolean",
+		Value::Number(_) => "number",
+		Value::String(_) => "string",
+		Value::Array(_) => "array",
+		Value::Object(_) => "object",
+	}
+}
+
+// endregion: --- Json Schema
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_jsons_validate_json_schema() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_schema = json!({
+			"type": "object",
+			"properties": {
+				"title": {"type": "string"},
+				"level": {"enum": ["low", "high"]},
+				"tags": {"type": "array", "items": {"type": "string"}}
+			},
+			"required": ["title", "tags"],
+			"additionalProperties": false
+		});
+
+		// -- Exec
+		let valid = validate_json_schema(&json!({"title": "t", "level": "low", "tags": ["a"]}), &fx_schema);
+		let invalid = validate_json_schema(&json!({"level": "mid", "tags": ["a", 2], "other": 1}), &fx_schema);
+
+		// -- Check
+		assert!(valid.is_empty(), "should be valid, but {valid:?}");
+		assert_eq!(invalid.len(), 4, "{invalid:?}");
+		assert!(invalid.iter().any(|e| e.contains("missing required property 'title'")));
+		assert!(invalid.iter().any(|e| e.contains("$.tags[1]: expected type 'string'")));
+		assert!(invalid.iter().any(|e| e.contains("property 'other' is not allowed")));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
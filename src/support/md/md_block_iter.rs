@@ -1,5 +1,7 @@
 use crate::support::md::InBlockState;
 use crate::types::{Extrude, MdBlock}; // new import to support 3/6 ticks
+use crate::{Error, Result};
+use std::collections::BTreeMap;
 
 /// Represents an iterator over Markdown code blocks with optional language filtering.
 pub struct MdBlockIter<'a> {
@@ -11,6 +13,8 @@ pub struct MdBlockIter<'a> {
 	lines: std::str::Lines<'a>,
 	/// The eventual extrude content
 	extruded_content: Vec<&'a str>,
+	/// The number of lines consumed (for the block line spans)
+	line_num: usize,
 }
 
 /// Constructor and main iterator function
@@ -22,7 +26,7 @@ impl<'a> MdBlockIter<'a> {
 	/// * `content` - The Markdown content to iterate over.
 	/// * `lang_filter` - An optional language filter:
 	///     - `None`: Any code block is returned.
-	///     - `Some(s)`: Only code blocks with a matching language are returned.
+	///     - `Some(s)`: Only code blocks with a matching language (or full info string) are returned.
 	///       - If `s` is an empty string, only code blocks without a specified language are returned.
 	pub fn new(content: &'a str, lang_filter: Option<&'a str>, extrude: Option<Extrude>) -> Self {
 		MdBlockIter {
@@ -30,6 +34,7 @@ impl<'a> MdBlockIter<'a> {
 			lang_filter,
 			extrude,
 			extruded_content: Vec::new(),
+			line_num: 0,
 		}
 	}
 
@@ -40,26 +45,30 @@ impl<'a> MdBlockIter<'a> {
 	fn next_block(&mut self) -> Option<MdBlock> {
 		// Use InBlockState to manage code block boundaries (3 or 6 ticks)
 		let mut block_state = InBlockState::Out;
-		let mut current_lang: Option<String> = None;
+		let mut current_info: Option<&'a str> = None;
+		let mut start_line = 0;
 		let mut captured_content: Option<Vec<&'a str>> = None;
 		let extrude_content = matches!(self.extrude, Some(Extrude::Content));
 
 		for line in self.lines.by_ref() {
+			self.line_num += 1;
 			let previous_state = block_state;
 			block_state = block_state.compute_new(line);
 
 			// Detect entering a new code block
 			if previous_state.is_out() && !block_state.is_out() {
-				let lang = match block_state {
+				let info = match block_state {
 					InBlockState::In4 => line.strip_prefix("````").unwrap_or(line).trim(),
 					InBlockState::In3 => line.strip_prefix("```").unwrap_or(line).trim(),
 					_ => line.trim(), // unreachable
 				};
-				// Store the language for later use when constructing MdBlock.
-				current_lang = Some(lang.to_string());
+				let lang = info.split_whitespace().next().unwrap_or_default();
+				// Store the info string for later use when constructing MdBlock.
+				current_info = Some(info);
+				start_line = self.line_num;
 				captured_content = match self.lang_filter {
 					Some(filter) => {
-						if filter == lang {
+						if filter == lang || filter == info {
 							Some(Vec::new())
 						} else {
 							if extrude_content {
@@ -78,16 +87,22 @@ impl<'a> MdBlockIter<'a> {
 			if !previous_state.is_out() && block_state.is_out() {
 				if let Some(content) = captured_content.take() {
 					let joined = content.join("");
+					let info = current_info.unwrap_or_default();
+					let (lang, attrs) = parse_fence_info(info);
 					let block = MdBlock {
-						lang: current_lang.clone(),
+						info: (lang != info).then(|| info.to_string()),
+						lang: Some(lang),
 						content: joined,
+						attrs: (!attrs.is_empty()).then_some(attrs),
+						start_line: Some(start_line),
+						end_line: Some(self.line_num),
 					};
 					return Some(block);
 				} else if extrude_content {
 					self.extruded_content.push(line);
 					self.extruded_content.push("\n");
 				}
-				current_lang = None;
+				current_info = None;
 				continue;
 			}
 
@@ -137,6 +152,65 @@ impl Iterator for MdBlockIter<'_> {
 	}
 }
 
+/// Replace the content of the block (between its fence lines) of the `start_line` / `end_line` span (1 based),
+/// which must be a block of the content (e.g., an `MdBlock` from this content).
+pub fn md_replace_block(content: &str, start_line: usize, end_line: usize, new_content: &str) -> Result<String> {
+	let is_block = MdBlockIter::new(content, None, None)
+		.any(|block| block.start_line == Some(start_line) && block.end_line == Some(end_line));
+	if !is_block {
+		return Err(Error::custom(format!(
+			"No code block at lines {start_line} to {end_line} (the content might have changed)"
+		)));
+	}
+
+	let lines: Vec<&str> = content.lines().collect();
+	let mut out: Vec<&str> = Vec::with_capacity(lines.len());
+	out.extend(&lines[..start_line]);
+	out.extend(new_content.trim_end_matches('\n').lines());
+	out.extend(&lines[end_line - 1..]);
+
+	let mut res = out.join("\n");
+	if content.ends_with('\n') {
+		res.push('\n');
+	}
+	Ok(res)
+}
+
+/// Parse the fence info string into the lang (the first word) and the attributes.
+///
+/// e.g., `rust file=src/main.rs title="Main file" no_run` gives
+/// `("rust", {file: "src/main.rs", title: "Main file", no_run: true})`
+pub fn parse_fence_info(info: &str) -> (String, BTreeMap<String, serde_json::Value>) {
+	let mut tokens: Vec<String> = Vec::new();
+	let mut token = String::new();
+	let mut in_quote = false;
+	for c in info.chars() {
+		match c {
+			'"' => in_quote = !in_quote,
+			c if c.is_whitespace() && !in_quote => {
+				if !token.is_empty() {
+					tokens.push(std::mem::take(&mut token));
+				}
+			}
+			c => token.push(c),
+		}
+	}
+	if !token.is_empty() {
+		tokens.push(token);
+	}
+
+	let mut tokens = tokens.into_iter();
+	let lang = tokens.next().unwrap_or_default();
+	let attrs = tokens
+		.map(|token| match token.split_once('=') {
+			Some((key, value)) => (key.to_string(), serde_json::Value::String(value.to_string())),
+			None => (token, serde_json::Value::Bool(true)),
+		})
+		.collect();
+
+	(lang, attrs)
+}
+
 // region:    --- Tests
 
 #[path = "../../_tests/tests_support_md_block_iter.rs"]
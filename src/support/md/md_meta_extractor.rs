@@ -161,6 +161,7 @@ fn extract_md_blocks_and_content(content: &str, extrude: bool) -> Result<(Vec<Md
 						let md_block = MdBlock {
 							lang: meta_block.0,
 							content: meta_block.1.join("\n"),
+							..Default::default()
 						};
 						md_blocks.push(md_block);
 						current_meta_block = None
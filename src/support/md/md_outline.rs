@@ -0,0 +1,293 @@
+//! The markdown heading tree (outline), and the section update and insert by heading path
+//! (used by `aip.md.outline`, `aip.md.update_section`, and `aip.md.insert_section`).
+//!
+//! A section is the heading line and all the lines until the next heading of the same or upper level
+//! (so, including its sub sections). The headings in the code blocks are ignored.
+
+use crate::support::md::InBlockState;
+use crate::types::MdHeading;
+use crate::{Error, Result};
+use serde::Serialize;
+
+/// A heading of the outline, with its section line span (1 based, inclusive)
+#[derive(Debug, Clone, Serialize)]
+pub struct MdOutlineHeading {
+	pub level: usize,
+	pub name: String,
+	/// The heading line
+	pub start_line: usize,
+	/// The last line of the section (including the sub sections)
+	pub end_line: usize,
+	pub children: Vec<MdOutlineHeading>,
+}
+
+/// Where to insert the new section, relative to the heading path section
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MdInsertPosition {
+	/// Before the heading line
+	Before,
+	/// After the section (and its sub sections)
+	#[default]
+	After,
+}
+
+impl MdInsertPosition {
+	pub fn from_name(name: &str) -> Result<Self> {
+		match name {
+			"before" => Ok(Self::Before),
+			"after" => Ok(Self::After),
+			other => Err(Error::custom(format!(
+				"position '{other}' is not valid (must be 'before' or 'after')"
+			))),
+		}
+	}
+}
+
+/// Returns the heading tree of the markdown content
+pub fn md_outline(content: &str) -> Vec<MdOutlineHeading> {
+	let lines: Vec<&str> = content.lines().collect();
+	let headings = list_headings(&lines);
+
+	// -- Build the flat list with the section end lines
+	let mut flat: Vec<MdOutlineHeading> = Vec::with_capacity(headings.len());
+	for (i, (line_idx, level, name)) in headings.iter().enumerate() {
+		let end_idx = headings[i + 1..]
+			.iter()
+			.find(|(_, next_level, _)| next_level <= level)
+			.map(|(next_idx, _, _)| next_idx - 1)
+			.unwrap_or(lines.len().saturating_sub(1));
+		flat.push(MdOutlineHeading {
+			level: *level,
+			name: name.to_string(),
+			start_line: line_idx + 1,
+			end_line: end_idx + 1,
+			children: Vec::new(),
+		});
+	}
+
+	// -- Nest (from the end, so each heading takes its following sub headings)
+	let mut roots: Vec<MdOutlineHeading> = Vec::new();
+	for heading in flat.into_iter().rev() {
+		let mut heading = heading;
+		while roots.first().is_some_and(|next| next.start_line <= heading.end_line) {
+			heading.children.push(roots.remove(0));
+		}
+		roots.insert(0, heading);
+	}
+
+	roots
+}
+
+/// Replace the body (all the lines after the heading line, including the sub sections) of the heading path section.
+pub fn md_update_section(content: &str, heading_path: &[String], new_body: &str) -> Result<String> {
+	let outline = md_outline(content);
+	let section = find_section(&outline, heading_path)?;
+	let lines: Vec<&str> = content.lines().collect();
+
+	let body = new_body.trim_matches('\n');
+	let mut new_lines: Vec<&str> = vec![lines[section.start_line - 1]];
+	if !body.is_empty() {
+		new_lines.push("");
+		new_lines.extend(body.lines());
+	}
+
+	Ok(splice_lines(
+		content,
+		&lines,
+		section.start_line - 1,
+		section.end_line,
+		&new_lines,
+	))
+}
+
+/// Insert the new section (the markdown with its heading line) before or after the heading path section,
+/// or at the end of the content when no heading path.
+pub fn md_insert_section(
+	content: &str,
+	heading_path: Option<&[String]>,
+	new_section: &str,
+	position: MdInsertPosition,
+) -> Result<String> {
+	let lines: Vec<&str> = content.lines().collect();
+	let line_idx = match heading_path {
+		Some(heading_path) => {
+			let outline = md_outline(content);
+			let section = find_section(&outline, heading_path)?;
+			match position {
+				MdInsertPosition::Before => section.start_line - 1,
+				MdInsertPosition::After => section.end_line,
+			}
+		}
+		None => lines.len(),
+	};
+
+	let new_section = new_section.trim_matches('\n');
+	let new_lines: Vec<&str> = new_section.lines().collect();
+
+	Ok(splice_lines(content, &lines, line_idx, line_idx, &new_lines))
+}
+
+// region:    --- Support
+
+/// Returns the `(line_idx, level, name)` of the heading lines (not in the code blocks)
+fn list_headings<'a>(lines: &[&'a str]) -> Vec<(usize, usize, &'a str)> {
+	let mut block_state = InBlockState::Out;
+	let mut headings = Vec::new();
+	for (idx, line) in lines.iter().enumerate() {
+		let was_out = block_state.is_out();
+		block_state = block_state.compute_new(line);
+		if !was_out || !block_state.is_out() {
+			continue;
+		}
+		if let Some((level, name)) = MdHeading::peek_line(line) {
+			headings.push((idx, level, name.trim()));
+		}
+	}
+	headings
+}
+
+/// Find the section of the heading path, where each heading is a descendant of the previous one.
+///
+/// A path item is the heading name (e.g., `Install`), or the heading with its level (e.g., `## Install`).
+fn find_section<'a>(outline: &'a [MdOutlineHeading], heading_path: &[String]) -> Result<&'a MdOutlineHeading> {
+	let Some((first, rest)) = heading_path.split_first() else {
+		return Err(Error::custom("The heading path cannot be empty"));
+	};
+
+	let mut found = find_descendant(outline, first);
+	for item in rest {
+		found = found.and_then(|heading| find_descendant(&heading.children, item));
+	}
+
+	found.ok_or_else(|| Error::custom(format!("Heading path {heading_path:?} not found")))
+}
+
+fn find_descendant<'a>(headings: &'a [MdOutlineHeading], path_item: &str) -> Option<&'a MdOutlineHeading> {
+	let (level, name) = match MdHeading::peek_line(path_item.trim()) {
+		Some((level, name)) => (Some(level), name.trim()),
+		None => (None, path_item.trim()),
+	};
+	for heading in headings {
+		if heading.name == name && level.is_none_or(|level| level == heading.level) {
+			return Some(heading);
+		}
+		if let Some(found) = find_descendant(&heading.children, path_item) {
+			return Some(found);
+		}
+	}
+	None
+}
+
+/// Replace the `lines[start..end]` with the new lines, with a blank line around them (when not at the edges),
+/// and keep the content trailing new line.
+fn splice_lines(content: &str, lines: &[&str], start: usize, end: usize, new_lines: &[&str]) -> String {
+	let before = &lines[..start];
+	let after = &lines[end.min(lines.len())..];
+
+	let mut out: Vec<&str> = Vec::with_capacity(lines.len() + new_lines.len() + 2);
+	out.extend(before);
+	// Trim the blank lines around the splice, then add one
+	while out.last().is_some_and(|line| line.trim().is_empty()) {
+		out.pop();
+	}
+	if !out.is_empty() {
+		out.push("");
+	}
+	out.extend(new_lines);
+	let after_start = after.iter().position(|line| !line.trim().is_empty()).unwrap_or(after.len());
+	let after = &after[after_start..];
+	if !after.is_empty() {
+		out.push("");
+		out.extend(after);
+	}
+
+	let mut res = out.join("\n");
+	if content.ends_with('\n') || after.is_empty() {
+		res.push('\n');
+	}
+	res
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	const FX_MD: &str = "\
+Intro
+
+# Guide
+
+## Install
+
+Run it.
+
+```sh
+# not a heading
+```
+
+### Linux
+
+apt
+
+## Usage
+
+Use it.
+";
+
+	#[test]
+	fn test_support_md_outline_md_outline() -> Result<()> {
+		// -- Exec
+		let outline = md_outline(FX_MD);
+
+		// -- Check
+		assert_eq!(outline.len(), 1);
+		let guide = &outline[0];
+		assert_eq!(
+			(guide.name.as_str(), guide.start_line, guide.end_line),
+			("Guide", 3, 19)
+		);
+		let names: Vec<&str> = guide.children.iter().map(|h| h.name.as_str()).collect();
+		assert_eq!(names, vec!["Install", "Usage"]);
+		let install = &guide.children[0];
+		assert_eq!((install.start_line, install.end_line), (5, 16));
+		assert_eq!(install.children[0].name, "Linux");
+		assert_eq!((install.children[0].start_line, install.children[0].end_line), (13, 16));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_md_outline_update_insert_section() -> Result<()> {
+		// -- Exec
+		let updated = md_update_section(
+			FX_MD,
+			&["Guide".to_string(), "## Install".to_string()],
+			"New install.\n",
+		)?;
+		let inserted = md_insert_section(
+			&updated,
+			Some(&["Usage".to_string()]),
+			"## FAQ\n\nAsk.",
+			MdInsertPosition::Before,
+		)?;
+		let appended = md_insert_section("# Doc\n", None, "## End", MdInsertPosition::After)?;
+
+		// -- Check
+		assert_eq!(
+			inserted,
+			"Intro\n\n# Guide\n\n## Install\n\nNew install.\n\n## FAQ\n\nAsk.\n\n## Usage\n\nUse it.\n"
+		);
+		assert_eq!(appended, "# Doc\n\n## End\n");
+		assert!(md_update_section(FX_MD, &["Nope".to_string()], "x").is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,261 @@
+//! The markdown (GFM) table parsing and rendering (used by `aip.md.parse_table` and `aip.md.table`).
+
+use crate::support::md::InBlockState;
+
+/// A parsed markdown table (the cells are trimmed and unescaped)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MdTable {
+	pub headers: Vec<String>,
+	/// The rows have the headers length (the missing cells are empty)
+	pub rows: Vec<Vec<String>>,
+}
+
+/// The column alignment (from the delimiter row, e.g., `:---:`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MdTableAlign {
+	#[default]
+	None,
+	Left,
+	Center,
+	Right,
+}
+
+impl MdTableAlign {
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"left" => Some(Self::Left),
+			"center" => Some(Self::Center),
+			"right" => Some(Self::Right),
+			"none" => Some(Self::None),
+			_ => None,
+		}
+	}
+}
+
+/// Parse the first table of the markdown content (the tables in the code blocks are ignored).
+///
+/// Returns `None` if there is no table (a header row followed by a delimiter row).
+pub fn parse_md_table(content: &str) -> Option<MdTable> {
+	let lines: Vec<&str> = content.lines().collect();
+	let mut block_state = InBlockState::Out;
+
+	for (idx, line) in lines.iter().enumerate() {
+		block_state = block_state.compute_new(line.trim_start());
+		if !block_state.is_out() || line.trim_start().starts_with("```") {
+			continue;
+		}
+
+		let Some(next_line) = lines.get(idx + 1) else {
+			break;
+		};
+		if !line.contains('|') {
+			continue;
+		}
+		let headers = split_row(line);
+		let Some(aligns) = parse_delimiter_row(next_line) else {
+			continue;
+		};
+		if aligns.len() != headers.len() {
+			continue;
+		}
+
+		let mut rows = Vec::new();
+		for row_line in lines[idx + 2..].iter() {
+			let trimmed = row_line.trim();
+			if trimmed.is_empty() || !trimmed.contains('|') {
+				break;
+			}
+			let mut cells = split_row(row_line);
+			cells.resize(headers.len(), String::new());
+			rows.push(cells);
+		}
+
+		return Some(MdTable { headers, rows });
+	}
+
+	None
+}
+
+/// Render the aligned markdown table (the cells are escaped, `|` to `\|` and the new lines to `<br>`).
+///
+/// NOTE: The column widths are in chars (so, the wide chars, e.g., CJK or emojis, might be slightly off).
+pub fn render_md_table(headers: &[String], aligns: &[MdTableAlign], rows: &[Vec<String>]) -> String {
+	let headers: Vec<String> = headers.iter().map(|h| escape_cell(h)).collect();
+	let rows: Vec<Vec<String>> = rows
+		.iter()
+		.map(|row| {
+			(0..headers.len())
+				.map(|i| row.get(i).map(|c| escape_cell(c)).unwrap_or_default())
+				.collect()
+		})
+		.collect();
+	let align_of = |i: usize| aligns.get(i).copied().unwrap_or_default();
+
+	// -- Compute the widths (min 3 for the delimiter row)
+	let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count().max(3)).collect();
+	for row in rows.iter() {
+		for (i, cell) in row.iter().enumerate() {
+			widths[i] = widths[i].max(cell.chars().count());
+		}
+	}
+
+	// -- Render
+	let mut out = String::new();
+	push_row(&mut out, &headers, &widths, &align_of);
+	let delimiters: Vec<String> = widths
+		.iter()
+		.enumerate()
+		.map(|(i, width)| match align_of(i) {
+			MdTableAlign::None => "-".repeat(*width),
+			MdTableAlign::Left => format!(":{}", "-".repeat(width - 1)),
+			MdTableAlign::Center => format!(":{}:", "-".repeat(width - 2)),
+			MdTableAlign::Right => format!("{}:", "-".repeat(width - 1)),
+		})
+		.collect();
+	out.push_str(&format!("| {} |\n", delimiters.join(" | ")));
+	for row in rows.iter() {
+		push_row(&mut out, row, &widths, &align_of);
+	}
+
+	out
+}
+
+// region:    --- Support
+
+fn push_row(out: &mut String, cells: &[String], widths: &[usize], align_of: &impl Fn(usize) -> MdTableAlign) {
+	let cells: Vec<String> = cells
+		.iter()
+		.enumerate()
+		.map(|(i, cell)| {
+			let pad = widths[i].saturating_sub(cell.chars().count());
+			match align_of(i) {
+				MdTableAlign::Right => format!("{}{cell}", " ".repeat(pad)),
+				MdTableAlign::Center => format!("{}{cell}{}", " ".repeat(pad / 2), " ".repeat(pad - pad / 2)),
+				MdTableAlign::None | MdTableAlign::Left => format!("{cell}{}", " ".repeat(pad)),
+			}
+		})
+		.collect();
+	out.push_str(&format!("| {} |\n", cells.join(" | ")));
+}
+
+/// Split the row cells on the unescaped `|` (without the leading and trailing pipes)
+fn split_row(line: &str) -> Vec<String> {
+	let line = line.trim();
+	let line = line.strip_prefix('|').unwrap_or(line);
+	let line = match line.strip_suffix('|') {
+		Some(rest) if !rest.ends_with('\\') => rest,
+		_ => line,
+	};
+
+	let mut cells = Vec::new();
+	let mut cell = String::new();
+	let mut chars = line.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' if chars.peek() == Some(&'|') => {
+				cell.push('|');
+				chars.next();
+			}
+			'|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+			_ => cell.push(c),
+		}
+	}
+	cells.push(cell.trim().to_string());
+
+	cells
+}
+
+/// Returns the column alignments if the line is a delimiter row (e.g., `| --- | :---: |`)
+fn parse_delimiter_row(line: &str) -> Option<Vec<MdTableAlign>> {
+	if !line.contains('-') {
+		return None;
+	}
+	split_row(line)
+		.iter()
+		.map(|cell| {
+			let left = cell.starts_with(':');
+			let right = cell.ends_with(':');
+			let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+			if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+				return None;
+			}
+			Some(match (left, right) {
+				(true, true) => MdTableAlign::Center,
+				(true, false) => MdTableAlign::Left,
+				(false, true) => MdTableAlign::Right,
+				(false, false) => MdTableAlign::None,
+			})
+		})
+		.collect()
+}
+
+fn escape_cell(cell: &str) -> String {
+	cell.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_md_table_parse_md_table() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_content = r#"
+Some intro.
+
+```md
+| not | this |
+| --- | ---- |
+```
+
+| Name | Value `a\|b` | Note
+|:-----|------:|---
+| one  | 1 | first
+two | 2
+
+After the table.
+"#;
+
+		// -- Exec
+		let table = parse_md_table(fx_content).ok_or("Should have table")?;
+
+		// -- Check
+		assert_eq!(table.headers, vec!["Name", "Value `a|b`", "Note"]);
+		assert_eq!(table.rows, vec![vec!["one", "1", "first"], vec!["two", "2", ""]]);
+		assert!(parse_md_table("| a | b |\nno delimiter").is_none());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_md_table_render_md_table() -> Result<()> {
+		// -- Setup & Fixtures
+		let headers = vec!["name".to_string(), "count".to_string()];
+		let aligns = vec![MdTableAlign::None, MdTableAlign::Right];
+		let rows = vec![vec!["a|b".to_string(), "12".to_string()], vec!["line\nbreak".to_string()]];
+
+		// -- Exec
+		let md = render_md_table(&headers, &aligns, &rows);
+
+		// -- Check
+		let expected = "\
+| name          | count |
+| ------------- | ----: |
+| a\\|b          |    12 |
+| line<br>break |       |
+";
+		assert_eq!(md, expected);
+		let table = parse_md_table(&md).ok_or("Should reparse")?;
+		assert_eq!(table.rows[0], vec!["a|b", "12"]);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -3,16 +3,20 @@
 mod in_block_state;
 mod md_block_iter;
 mod md_meta_extractor;
+mod md_outline;
 mod md_ref_iter;
 mod md_section_iter;
 mod md_section_split;
+mod md_table;
 mod outer_block;
 
 pub use in_block_state::*;
 pub use md_block_iter::*;
 pub use md_meta_extractor::*;
+pub use md_outline::*;
 pub use md_ref_iter::MdRefIter;
 pub use md_section_iter::*;
+pub use md_table::*;
 pub use outer_block::*;
 
 // endregion: --- Modules
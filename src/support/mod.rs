@@ -11,17 +11,27 @@ pub use cow_lines::*;
 pub use str_ext::*;
 pub use vec_ext::*;
 
+pub mod ai_parse;
+pub mod bin;
+pub mod blob;
 pub mod code;
 pub mod consts;
 pub mod cred;
+pub mod cron;
 pub mod csvs;
+pub mod db_query;
 pub mod docx;
 pub mod editor;
+pub mod encode;
+pub mod feed;
 pub mod files;
+pub mod graphql;
 pub mod hbs;
 pub mod html;
+pub mod images;
 pub mod jsons;
 pub mod md;
+pub mod openapi;
 pub mod os;
 pub mod paths;
 pub mod pdf;
@@ -29,7 +39,9 @@ pub mod proc;
 pub mod text;
 pub mod time;
 pub mod tomls;
+pub mod vec_store;
 pub mod webc;
+pub mod xlsx;
 pub mod yamls;
 pub mod zip;
 
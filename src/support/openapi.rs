@@ -0,0 +1,617 @@
+//! The OpenAPI spec model of `aip.api.from_openapi` (the operations, their parameters, and their request body).
+//!
+//! - OpenAPI 3.x and Swagger 2.0 specs, as JSON or YAML.
+//! - The local `$ref` (e.g., `#/components/parameters/Limit`) of the parameters and request bodies are resolved.
+//! - The operations without `operationId` get one from their method and path (e.g., `GET /users/{id}` -> `get_users_id`).
+
+use crate::support::{jsons, yamls};
+use crate::{Error, Result};
+use serde_json::{Map, Value};
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// The request body argument name of an operation call (e.g., `client.create_user({ body = {...} })`)
+pub const BODY_ARG: &str = "body";
+
+#[derive(Debug, Clone)]
+pub struct OpenApiSpec {
+	pub title: Option<String>,
+	/// From the first `servers` url (OpenAPI 3), or `schemes`/`host`/`basePath` (Swagger 2).
+	/// Can be relative (e.g., `/api/v1`), see `OpenApiSpec::resolve_base_url`.
+	pub base_url: Option<String>,
+	/// The first `apiKey` security scheme `(location, name)`, used as the default of the api key auth
+	pub api_key_scheme: Option<(ParamLocation, String)>,
+	pub operations: Vec<OpenApiOp>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenApiOp {
+	pub id: String,
+	/// Uppercase (e.g., `GET`)
+	pub method: String,
+	/// The path template (e.g., `/users/{id}`)
+	pub path: String,
+	pub summary: Option<String>,
+	pub params: Vec<OpenApiParam>,
+	pub body: Option<OpenApiBody>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenApiParam {
+	pub name: String,
+	pub location: ParamLocation,
+	pub required: bool,
+	/// The schema type (e.g., `string`, `integer`, `array`)
+	pub schema_type: Option<String>,
+	pub enum_values: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenApiBody {
+	pub required: bool,
+	pub content_type: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamLocation {
+	Path,
+	Query,
+	Header,
+	Cookie,
+}
+
+impl ParamLocation {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			ParamLocation::Path => "path",
+			ParamLocation::Query => "query",
+			ParamLocation::Header => "header",
+			ParamLocation::Cookie => "cookie",
+		}
+	}
+
+	fn from_str(location: &str) -> Option<Self> {
+		match location {
+			"path" => Some(ParamLocation::Path),
+			"query" => Some(ParamLocation::Query),
+			"header" => Some(ParamLocation::Header),
+			"cookie" => Some(ParamLocation::Cookie),
+			_ => None,
+		}
+	}
+}
+
+/// The parts of an operation request, built from the call arguments (see `OpenApiOp::build_request`)
+#[derive(Debug, Default)]
+pub struct OpenApiRequest {
+	pub url: String,
+	pub query: Vec<(String, String)>,
+	pub headers: Vec<(String, String)>,
+	pub body: Option<Value>,
+}
+
+// region:    --- Spec Parsing
+
+impl OpenApiSpec {
+	/// Parse a JSON or YAML spec content
+	pub fn parse(content: &str) -> Result<Self> {
+		let value = if content.trim_start().starts_with('{') {
+			jsons::parse_jsonc_to_serde_value(content)?
+		} else {
+			yamls::parse(content)?.into_first()
+		};
+		let value = value.ok_or_else(|| Error::custom("OpenAPI spec is empty"))?;
+		Self::from_value(&value)
+	}
+
+	pub fn from_value(root: &Value) -> Result<Self> {
+		if root.get("openapi").is_none() && root.get("swagger").is_none() {
+			return Err(Error::custom(
+				"Not an OpenAPI spec (no 'openapi' or 'swagger' version property)",
+			));
+		}
+		let paths = root
+			.get("paths")
+			.and_then(Value::as_object)
+			.ok_or_else(|| Error::custom("OpenAPI spec has no 'paths'"))?;
+
+		let title = root.pointer("/info/title").and_then(Value::as_str).map(str::to_string);
+		let consumes = root.pointer("/consumes/0").and_then(Value::as_str);
+
+		let mut operations: Vec<OpenApiOp> = Vec::new();
+		for (path, path_item) in paths {
+			let path_item = resolve_ref(root, path_item);
+			let path_params = path_item.get("parameters").and_then(Value::as_array);
+
+			for method in METHODS {
+				let Some(op) = path_item.get(*method) else {
+					continue;
+				};
+				let op = parse_operation(root, path, method, op, path_params, consumes)?;
+				if operations.iter().any(|other| other.id == op.id) {
+					return Err(Error::custom(format!(
+						"OpenAPI spec has a duplicate operation id '{}' ({} {})",
+						op.id, op.method, op.path
+					)));
+				}
+				operations.push(op);
+			}
+		}
+
+		Ok(OpenApiSpec {
+			title,
+			base_url: parse_base_url(root),
+			api_key_scheme: parse_api_key_scheme(root),
+			operations,
+		})
+	}
+
+	/// Returns the absolute base url, joining a relative spec base url to the `spec_url` (when the spec was fetched)
+	pub fn resolve_base_url(&self, spec_url: Option<&str>) -> Option<String> {
+		let base_url = self.base_url.as_deref()?;
+		if base_url.starts_with("http://") || base_url.starts_with("https://") {
+			return Some(base_url.to_string());
+		}
+		let spec_url = url::Url::parse(spec_url?).ok()?;
+		spec_url.join(base_url).ok().map(|url| url.to_string())
+	}
+}
+
+fn parse_operation(
+	root: &Value,
+	path: &str,
+	method: &str,
+	op: &Value,
+	path_params: Option<&Vec<Value>>,
+	consumes: Option<&str>,
+) -> Result<OpenApiOp> {
+	let id = match op.get("operationId").and_then(Value::as_str) {
+		Some(id) => id.to_string(),
+		None => default_op_id(method, path),
+	};
+
+	// -- The params (the operation params override the path params of the same name and location)
+	let mut params: Vec<OpenApiParam> = Vec::new();
+	let mut body: Option<OpenApiBody> = None;
+	let op_params = op.get("parameters").and_then(Value::as_array);
+	for param in path_params.into_iter().chain(op_params).flatten() {
+		let param = resolve_ref(root, param);
+		let name = param
+			.get("name")
+			.and_then(Value::as_str)
+			.ok_or_else(|| Error::custom(format!("OpenAPI parameter without name in '{id}'")))?;
+		let location = param.get("in").and_then(Value::as_str).unwrap_or_default();
+		let required = param.get("required").and_then(Value::as_bool).unwrap_or(false);
+
+		// Swagger 2 body and form params
+		match location {
+			"body" => {
+				body = Some(OpenApiBody {
+					required,
+					content_type: consumes.unwrap_or("application/json").to_string(),
+				});
+				continue;
+			}
+			"formData" => {
+				let body = body.get_or_insert_with(|| OpenApiBody {
+					required: false,
+					content_type: "application/x-www-form-urlencoded".to_string(),
+				});
+				body.required |= required;
+				continue;
+			}
+			_ => (),
+		}
+
+		let location = ParamLocation::from_str(location).ok_or_else(|| {
+			Error::custom(format!(
+				"OpenAPI parameter '{name}' of '{id}' has an unknown location '{location}'"
+			))
+		})?;
+		let schema = param.get("schema").map(|schema| resolve_ref(root, schema)).unwrap_or(param);
+		let param = OpenApiParam {
+			name: name.to_string(),
+			location,
+			// NOTE: path params are always required
+			required: required || location == ParamLocation::Path,
+			schema_type: schema.get("type").and_then(Value::as_str).map(str::to_string),
+			enum_values: schema.get("enum").and_then(Value::as_array).cloned(),
+		};
+
+		params.retain(|other| !(other.name == param.name && other.location == param.location));
+		params.push(param);
+	}
+
+	// -- The OpenAPI 3 request body
+	if let Some(request_body) = op.get("requestBody") {
+		let request_body = resolve_ref(root, request_body);
+		let content_types: Vec<&str> = request_body
+			.get("content")
+			.and_then(Value::as_object)
+			.map(|content| content.keys().map(String::as_str).collect())
+			.unwrap_or_default();
+		let content_type = content_types
+			.iter()
+			.find(|ct| **ct == "application/json")
+			.or_else(|| content_types.iter().find(|ct| ct.ends_with("+json")))
+			.or_else(|| content_types.first())
+			.copied()
+			.unwrap_or("application/json");
+		body = Some(OpenApiBody {
+			required: request_body.get("required").and_then(Value::as_bool).unwrap_or(false),
+			content_type: content_type.to_string(),
+		});
+	}
+
+	Ok(OpenApiOp {
+		id,
+		method: method.to_uppercase(),
+		path: path.to_string(),
+		summary: op.get("summary").and_then(Value::as_str).map(str::to_string),
+		params,
+		body,
+	})
+}
+
+fn parse_base_url(root: &Value) -> Option<String> {
+	// -- OpenAPI 3
+	if let Some(server) = root.pointer("/servers/0") {
+		let mut url = server.get("url").and_then(Value::as_str)?.to_string();
+		if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+			for (name, variable) in variables {
+				if let Some(default) = variable.get("default").and_then(Value::as_str) {
+					url = url.replace(&format!("{{{name}}}"), default);
+				}
+			}
+		}
+		return Some(url.trim_end_matches('/').to_string());
+	}
+
+	// -- Swagger 2
+	let base_path = root.get("basePath").and_then(Value::as_str).unwrap_or_default();
+	match root.get("host").and_then(Value::as_str) {
+		Some(host) => {
+			let scheme = root.pointer("/schemes/0").and_then(Value::as_str).unwrap_or("https");
+			Some(format!("{scheme}://{host}{}", base_path.trim_end_matches('/')))
+		}
+		None if !base_path.is_empty() => Some(base_path.trim_end_matches('/').to_string()),
+		None => None,
+	}
+}
+
+fn parse_api_key_scheme(root: &Value) -> Option<(ParamLocation, String)> {
+	let schemes = root
+		.pointer("/components/securitySchemes")
+		.or_else(|| root.get("securityDefinitions"))
+		.and_then(Value::as_object)?;
+	schemes.values().map(|scheme| resolve_ref(root, scheme)).find_map(|scheme| {
+		if scheme.get("type").and_then(Value::as_str) != Some("apiKey") {
+			return None;
+		}
+		let location = ParamLocation::from_str(scheme.get("in").and_then(Value::as_str)?)?;
+		let name = scheme.get("name").and_then(Value::as_str)?;
+		Some((location, name.to_string()))
+	})
+}
+
+/// e.g., `get` `/users/{id}/posts` -> `get_users_id_posts`
+fn default_op_id(method: &str, path: &str) -> String {
+	let mut id = method.to_string();
+	for segment in path.split('/').filter(|s| !s.is_empty()) {
+		let segment: String = segment
+			.chars()
+			.filter(|c| *c != '{' && *c != '}')
+			.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+			.collect();
+		id.push('_');
+		id.push_str(&segment);
+	}
+	id
+}
+
+/// Resolve the local `$ref` (e.g., `#/components/schemas/User`), returning the value itself otherwise.
+fn resolve_ref<'a>(root: &'a Value, value: &'a Value) -> &'a Value {
+	let mut value = value;
+	// NOTE: follow a few chained refs, but not a cycle
+	for _ in 0..8 {
+		match value.get("$ref").and_then(Value::as_str) {
+			Some(reference) if reference.starts_with("#/") => match root.pointer(&reference[1..]) {
+				Some(target) => value = target,
+				None => break,
+			},
+			_ => break,
+		}
+	}
+	value
+}
+
+// endregion: --- Spec Parsing
+
+// region:    --- Request Building
+
+impl OpenApiOp {
+	/// Validate the call arguments (the parameters by name, and the `body`), and build the request parts.
+	pub fn build_request(&self, base_url: &str, args: &Map<String, Value>) -> Result<OpenApiRequest> {
+		self.validate_args(args)?;
+
+		let mut path = self.path.clone();
+		let mut req = OpenApiRequest::default();
+		let mut cookies: Vec<String> = Vec::new();
+
+		for param in self.params.iter() {
+			let Some(value) = args.get(&param.name).filter(|v| !v.is_null()) else {
+				continue;
+			};
+			match param.location {
+				ParamLocation::Path => {
+					let value = encode_path_segment(&value_to_string(value));
+					path = path.replace(&format!("{{{}}}", param.name), &value);
+				}
+				ParamLocation::Query => match value {
+					// NOTE: the default `form` style with `explode`, i.e., `ids=1&ids=2`
+					Value::Array(values) => {
+						for value in values {
+							req.query.push((param.name.clone(), value_to_string(value)));
+						}
+					}
+					value => req.query.push((param.name.clone(), value_to_string(value))),
+				},
+				ParamLocation::Header => req.headers.push((param.name.clone(), value_to_string(value))),
+				ParamLocation::Cookie => cookies.push(format!("{}={}", param.name, value_to_string(value))),
+			}
+		}
+		if !cookies.is_empty() {
+			req.headers.push(("Cookie".to_string(), cookies.join("; ")));
+		}
+
+		req.url = format!("{}{path}", base_url.trim_end_matches('/'));
+		req.body = args.get(BODY_ARG).filter(|v| !v.is_null()).cloned();
+
+		Ok(req)
+	}
+
+	fn validate_args(&self, args: &Map<String, Value>) -> Result<()> {
+		let id = &self.id;
+
+		// -- Check the unknown args
+		for name in args.keys() {
+			let is_body = name == BODY_ARG && self.body.is_some();
+			if !is_body && !self.params.iter().any(|p| &p.name == name) {
+				let mut names: Vec<&str> = self.params.iter().map(|p| p.name.as_str()).collect();
+				if self.body.is_some() {
+					names.push(BODY_ARG);
+				}
+				return Err(Error::custom(format!(
+					"Operation '{id}' has no parameter '{name}'. Parameters: {}",
+					if names.is_empty() {
+						"(none)".to_string()
+					} else {
+						names.join(", ")
+					}
+				)));
+			}
+		}
+
+		// -- Check the params
+		for param in self.params.iter() {
+			let name = &param.name;
+			match args.get(name).filter(|v| !v.is_null()) {
+				None if param.required => {
+					return Err(Error::custom(format!(
+						"Operation '{id}' requires the {} parameter '{name}'",
+						param.location.as_str()
+					)));
+				}
+				None => (),
+				Some(value) => {
+					if let Some(schema_type) = param.schema_type.as_deref()
+						&& !is_value_of_type(value, schema_type)
+					{
+						return Err(Error::custom(format!(
+							"Operation '{id}' parameter '{name}' must be of type '{schema_type}' (was: {value})"
+						)));
+					}
+					if let Some(enum_values) = param.enum_values.as_ref()
+						&& !enum_values
+							.iter()
+							.any(|v| v == value || v.as_str() == Some(&value_to_string(value)))
+					{
+						let allowed: Vec<String> = enum_values.iter().map(value_to_string).collect();
+						return Err(Error::custom(format!(
+							"Operation '{id}' parameter '{name}' must be one of: {} (was: {value})",
+							allowed.join(", ")
+						)));
+					}
+				}
+			}
+		}
+
+		// -- Check the body
+		if let Some(body) = self.body.as_ref()
+			&& body.required
+			&& args.get(BODY_ARG).is_none_or(Value::is_null)
+		{
+			return Err(Error::custom(format!(
+				"Operation '{id}' requires a '{BODY_ARG}' ({})",
+				body.content_type
+			)));
+		}
+
+		Ok(())
+	}
+}
+
+fn is_value_of_type(value: &Value, schema_type: &str) -> bool {
+	match schema_type {
+		"integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|v| v.fract() == 0.0),
+		"number" => value.is_number(),
+		"boolean" => value.is_boolean(),
+		// NOTE: numbers are accepted for the string params (e.g., ids)
+		"string" => value.is_string() || value.is_number(),
+		"array" => value.is_array(),
+		"object" => value.is_object(),
+		_ => true,
+	}
+}
+
+fn value_to_string(value: &Value) -> String {
+	match value {
+		Value::String(s) => s.to_string(),
+		Value::Number(num) => match num.as_f64() {
+			Some(v) if !num.is_i64() && !num.is_u64() && v.fract() == 0.0 => format!("{}", v as i64),
+			_ => num.to_string(),
+		},
+		Value::Array(values) => values.iter().map(value_to_string).collect::<Vec<_>>().join(","),
+		other => other.to_string(),
+	}
+}
+
+/// Encode everything but the unreserved characters
+fn encode_path_segment(value: &str) -> String {
+	let mut res = String::with_capacity(value.len());
+	for byte in value.bytes() {
+		match byte {
+			b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => res.push(byte as char),
+			_ => res.push_str(&format!("%{byte:02X}")),
+		}
+	}
+	res
+}
+
+// endregion: --- Request Building
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	fn get_op<'a>(spec: &'a OpenApiSpec, id: &str) -> Option<&'a OpenApiOp> {
+		spec.operations.iter().find(|op| op.id == id)
+	}
+
+	const FX_SPEC_YAML: &str = r#"
+openapi: 3.0.3
+info:
+  title: Users API
+servers:
+  - url: https://api.acme.com/{version}
+    variables:
+      version:
+        default: v1
+components:
+  parameters:
+    Limit:
+      name: limit
+      in: query
+      schema: { type: integer }
+  securitySchemes:
+    key:
+      type: apiKey
+      in: header
+      name: X-Acme-Key
+paths:
+  /users:
+    get:
+      operationId: listUsers
+      parameters:
+        - $ref: '#/components/parameters/Limit'
+        - name: status
+          in: query
+          schema: { type: string, enum: [active, disabled] }
+    post:
+      operationId: createUser
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema: { type: object }
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        schema: { type: string }
+    get:
+      summary: Get a user
+"#;
+
+	#[test]
+	fn test_support_openapi_parse() -> Result<()> {
+		// -- Exec
+		let spec = OpenApiSpec::parse(FX_SPEC_YAML)?;
+
+		// -- Check
+		assert_eq!(spec.title.as_deref(), Some("Users API"));
+		assert_eq!(spec.base_url.as_deref(), Some("https://api.acme.com/v1"));
+		assert_eq!(
+			spec.api_key_scheme,
+			Some((ParamLocation::Header, "X-Acme-Key".to_string()))
+		);
+		let ids: Vec<&str> = spec.operations.iter().map(|op| op.id.as_str()).collect();
+		assert_eq!(ids, vec!["listUsers", "createUser", "get_users_id"]);
+		let list_users = get_op(&spec, "listUsers").ok_or("should have listUsers")?;
+		assert_eq!(list_users.params[0].name, "limit");
+		assert_eq!(list_users.params[0].schema_type.as_deref(), Some("integer"));
+		let get_user = get_op(&spec, "get_users_id").ok_or("should have get_users_id")?;
+		assert_eq!(get_user.method, "GET");
+		assert!(get_user.params[0].required, "path params are required");
+		assert!(
+			get_op(&spec, "createUser")
+				.and_then(|op| op.body.as_ref())
+				.is_some_and(|b| b.required)
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_openapi_build_request() -> Result<()> {
+		// -- Setup & Fixtures
+		let spec = OpenApiSpec::parse(FX_SPEC_YAML)?;
+		let base_url = spec.base_url.clone().ok_or("should have base_url")?;
+		let list_users = get_op(&spec, "listUsers").ok_or("should have listUsers")?;
+		let get_user = get_op(&spec, "get_users_id").ok_or("should have get_users_id")?;
+		let create_user = get_op(&spec, "createUser").ok_or("should have createUser")?;
+		let args = |value: Value| value.as_object().cloned().unwrap_or_default();
+
+		// -- Exec
+		let list_req = list_users.build_request(&base_url, &args(json!({"limit": 10, "status": "active"})))?;
+		let get_req = get_user.build_request(&base_url, &args(json!({"id": "a b/1"})))?;
+
+		// -- Check
+		assert_eq!(list_req.url, "https://api.acme.com/v1/users");
+		assert_eq!(
+			list_req.query,
+			vec![
+				("limit".to_string(), "10".to_string()),
+				("status".to_string(), "active".to_string())
+			]
+		);
+		assert_eq!(get_req.url, "https://api.acme.com/v1/users/a%20b%2F1");
+		// validation errors
+		let err = list_users
+			.build_request(&base_url, &args(json!({"limit": "ten"})))
+			.err()
+			.ok_or("should fail")?;
+		assert!(err.to_string().contains("must be of type 'integer'"));
+		let err = list_users
+			.build_request(&base_url, &args(json!({"status": "gone"})))
+			.err()
+			.ok_or("should fail")?;
+		assert!(err.to_string().contains("must be one of: active, disabled"));
+		let err = list_users
+			.build_request(&base_url, &args(json!({"page": 2})))
+			.err()
+			.ok_or("should fail")?;
+		assert!(err.to_string().contains("has no parameter 'page'"));
+		assert!(get_user.build_request(&base_url, &args(json!({}))).is_err());
+		assert!(create_user.build_request(&base_url, &args(json!({}))).is_err());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -98,6 +98,27 @@ pub fn new_run_command(program: &str) -> Command {
 	}
 }
 
+/// Opens the url with the default OS handler (e.g., the default browser).
+/// Returns the program name used to open the url.
+pub fn open_url(url: &str) -> crate::Result<&'static str> {
+	let (program, mut cmd) = match current_os() {
+		OsType::Mac => ("open", Command::new("open")),
+		OsType::Windows => {
+			let mut cmd = Command::new("cmd");
+			// NOTE: The empty "" is the window title for `start`
+			cmd.args(["/C", "start", ""]);
+			("start", cmd)
+		}
+		OsType::Linux | OsType::Unknown => ("xdg-open", Command::new("xdg-open")),
+	};
+
+	cmd.arg(url)
+		.spawn()
+		.map_err(|err| format!("Failed to open url '{url}' with '{program}'.\nCause: {err}"))?;
+
+	Ok(program)
+}
+
 // endregion: --- General Os Type
 
 // region:    --- Messages
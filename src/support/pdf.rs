@@ -1,9 +1,11 @@
 use crate::Result;
 use crate::error::Error;
 use derive_more::{Deref, From, Into};
-use lopdf::{Document, Object, ObjectId, dictionary};
+use lopdf::content::Content;
+use lopdf::{Document, Encoding, Object, ObjectId, decode_text_string, dictionary};
 use simple_fs::{SPath, ensure_dir};
 use std::collections::BTreeMap;
+use std::process::Command;
 
 #[derive(From, Into, Deref)]
 pub struct PdfDoc {
@@ -73,6 +75,336 @@ pub fn split_pdf_pages(pdf_path: &SPath, dest_dir: &SPath) -> Result<Vec<SPath>>
 	Ok(created_files)
 }
 
+// region:    --- Text & Metadata
+
+/// The document information (from the trailer `Info` dictionary)
+#[derive(Debug, Clone, Default)]
+pub struct PdfMeta {
+	pub title: Option<String>,
+	pub author: Option<String>,
+	pub subject: Option<String>,
+	pub page_count: usize,
+}
+
+/// The text of a page (`page` starts at 1)
+#[derive(Debug, Clone)]
+pub struct PdfPageText {
+	pub page: usize,
+	pub text: String,
+}
+
+pub fn pdf_meta(pdf: &PdfDoc) -> PdfMeta {
+	let info = pdf.trailer.get(b"Info").ok().and_then(|info| match info {
+		Object::Reference(id) => pdf.get_dictionary(*id).ok(),
+		Object::Dictionary(dict) => Some(dict),
+		_ => None,
+	});
+	let get_text = |key: &[u8]| {
+		info.and_then(|info| info.get(key).ok())
+			.and_then(|value| decode_text_string(value).ok())
+			.filter(|value| !value.trim().is_empty())
+	};
+
+	PdfMeta {
+		title: get_text(b"Title"),
+		author: get_text(b"Author"),
+		subject: get_text(b"Subject"),
+		page_count: page_count(pdf),
+	}
+}
+
+/// Parse the page ranges spec (e.g., `"1-3,5,8-"`, where `8-` is until the last page)
+/// into the sorted and deduped page numbers (starting at 1).
+pub fn parse_page_ranges(spec: &str, page_count: usize) -> Result<Vec<usize>> {
+	let parse_num = |num: &str| {
+		num.trim()
+			.parse::<usize>()
+			.ok()
+			.filter(|num| *num >= 1)
+			.ok_or_else(|| Error::custom(format!("Invalid page number '{num}' in pages '{spec}'")))
+	};
+
+	let mut pages = Vec::new();
+	for part in spec.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+		let (start, end) = match part.split_once('-') {
+			Some((start, end)) => {
+				let start = if start.trim().is_empty() { 1 } else { parse_num(start)? };
+				let end = if end.trim().is_empty() {
+					page_count
+				} else {
+					parse_num(end)?
+				};
+				(start, end)
+			}
+			None => {
+				let num = parse_num(part)?;
+				(num, num)
+			}
+		};
+		if end > page_count {
+			return Err(Error::custom(format!(
+				"Page {end} out of range in pages '{spec}' (the document has {page_count} pages)"
+			)));
+		}
+		pages.extend(start..=end);
+	}
+	pages.sort_unstable();
+	pages.dedup();
+
+	Ok(pages)
+}
+
+/// Extract the text of the pages (`pages` start at 1).
+///
+/// When `layout` is true, the text fragments are placed by their positions
+/// (the lines by their vertical positions, and the columns approximated from the font sizes),
+/// so that the tables and multi columns pages keep their alignment.
+pub fn extract_pdf_pages_text(pdf: &PdfDoc, pages: &[usize], layout: bool) -> Result<Vec<PdfPageText>> {
+	let mut res = Vec::with_capacity(pages.len());
+	for &page in pages {
+		let text = if layout {
+			extract_page_text_layout(pdf, page)?
+		} else {
+			pdf.extract_text(&[page as u32])
+				.map_err(|err| Error::cc(format!("Cannot extract text of page {page}"), err))?
+		};
+		res.push(PdfPageText { page, text });
+	}
+	Ok(res)
+}
+
+/// Render the page (starting at 1) to an image file with the `pdftoppm` command (poppler).
+///
+/// - `format`: `png` or `jpeg`
+/// - `dest_stem`: the destination file path without the extension
+///
+/// Returns the created image path.
+pub fn render_pdf_page_image(
+	pdf_path: &SPath,
+	page: usize,
+	dest_stem: &SPath,
+	dpi: u32,
+	format: &str,
+) -> Result<SPath> {
+	let (format_arg, ext) = match format {
+		"png" => ("-png", "png"),
+		"jpeg" | "jpg" => ("-jpeg", "jpg"),
+		other => {
+			return Err(Error::custom(format!(
+				"Image format '{other}' not supported (png or jpeg)"
+			)));
+		}
+	};
+	if let Some(parent) = dest_stem.parent() {
+		ensure_dir(parent.as_std_path()).map_err(Error::from)?;
+	}
+
+	let page = page.to_string();
+	let output = Command::new("pdftoppm")
+		.args([format_arg, "-r", &dpi.to_string(), "-f", &page, "-l", &page, "-singlefile"])
+		.arg(pdf_path.as_std_path())
+		.arg(dest_stem.as_std_path())
+		.output()
+		.map_err(|err| {
+			Error::custom(format!(
+				"Cannot execute 'pdftoppm' (install poppler, e.g., poppler-utils, to render the pdf pages).\nCause: {err}"
+			))
+		})?;
+	if !output.status.success() {
+		return Err(Error::custom(format!(
+			"'pdftoppm' failed for page {page} of {pdf_path}. Stderr: {}",
+			String::from_utf8_lossy(&output.stderr)
+		)));
+	}
+
+	Ok(SPath::new(format!("{dest_stem}.{ext}")))
+}
+
+// endregion: --- Text & Metadata
+
+// region:    --- Layout
+
+/// A positioned text fragment (in the page user space, `y` going up)
+struct TextFrag {
+	x: f32,
+	y: f32,
+	size: f32,
+	text: String,
+}
+
+fn extract_page_text_layout(pdf: &PdfDoc, page: usize) -> Result<String> {
+	let page_id = *pdf
+		.get_pages()
+		.get(&(page as u32))
+		.ok_or_else(|| format!("No page found for {page}"))?;
+	let encodings: BTreeMap<Vec<u8>, Encoding> = pdf
+		.get_page_fonts(page_id)
+		.map_err(Error::custom)?
+		.into_iter()
+		.filter_map(|(name, font)| font.get_font_encoding(pdf).ok().map(|encoding| (name, encoding)))
+		.collect();
+	let content = Content::decode(&pdf.get_page_content(page_id)).map_err(Error::custom)?;
+
+	// -- Collect the positioned fragments
+	// The text matrix and line matrix as [a, b, c, d, e, f]
+	let identity = [1., 0., 0., 1., 0., 0.];
+	let mut tm: [f32; 6] = identity;
+	let mut tlm: [f32; 6] = identity;
+	let mut leading = 0.;
+	let mut font_size = 0.;
+	let mut encoding: Option<&Encoding> = None;
+	let mut frags: Vec<TextFrag> = Vec::new();
+
+	let num = |obj: &Object| obj.as_float().ok().unwrap_or(0.);
+	let move_line = |tlm: &mut [f32; 6], tx: f32, ty: f32| {
+		tlm[4] += tx * tlm[0] + ty * tlm[2];
+		tlm[5] += tx * tlm[1] + ty * tlm[3];
+	};
+
+	for op in content.operations.iter() {
+		let operands = &op.operands;
+		match op.operator.as_str() {
+			"BT" => {
+				tm = identity;
+				tlm = identity;
+			}
+			"Tf" => {
+				encoding = operands
+					.first()
+					.and_then(|name| name.as_name().ok())
+					.and_then(|name| encodings.get(name));
+				font_size = operands.get(1).map(num).unwrap_or(0.);
+			}
+			"TL" => leading = operands.first().map(num).unwrap_or(0.),
+			"Td" | "TD" => {
+				let (tx, ty) = (
+					operands.first().map(num).unwrap_or(0.),
+					operands.get(1).map(num).unwrap_or(0.),
+				);
+				if op.operator == "TD" {
+					leading = -ty;
+				}
+				move_line(&mut tlm, tx, ty);
+				tm = tlm;
+			}
+			"Tm" if operands.len() == 6 => {
+				for (i, operand) in operands.iter().enumerate() {
+					tlm[i] = num(operand);
+				}
+				tm = tlm;
+			}
+			"T*" => {
+				move_line(&mut tlm, 0., -leading);
+				tm = tlm;
+			}
+			"Tj" | "TJ" | "'" | "\"" => {
+				if op.operator != "Tj" && op.operator != "TJ" {
+					move_line(&mut tlm, 0., -leading);
+					tm = tlm;
+				}
+				let Some(encoding) = encoding else {
+					continue;
+				};
+				let items: Vec<&Object> = match op.operator.as_str() {
+					"TJ" => operands
+						.first()
+						.and_then(|arr| arr.as_array().ok())
+						.map(|arr| arr.iter().collect()),
+					"\"" => operands.get(2).map(|item| vec![item]),
+					_ => operands.first().map(|item| vec![item]),
+				}
+				.unwrap_or_default();
+
+				let size = font_size * tm[3].abs().max(tm[0].abs());
+				let mut text = String::new();
+				for item in items {
+					match item {
+						Object::String(bytes, _) => {
+							if let Ok(decoded) = Document::decode_text(encoding, bytes) {
+								text.push_str(&decoded);
+							}
+						}
+						// A large negative adjustment is a word space
+						other => {
+							if num(other) < -250. && !text.ends_with(' ') {
+								text.push(' ');
+							}
+						}
+					}
+				}
+				if text.trim().is_empty() {
+					continue;
+				}
+				let (x, y) = (tm[4], tm[5]);
+				// Approximate the advance with an average glyph width of half the font size
+				tm[4] += text.chars().count() as f32 * font_size * 0.5 * tm[0];
+				frags.push(TextFrag { x, y, size, text });
+			}
+			_ => (),
+		}
+	}
+
+	Ok(layout_frags(frags))
+}
+
+/// Place the fragments in lines (by `y`) and columns (by `x`, in average char widths).
+fn layout_frags(mut frags: Vec<TextFrag>) -> String {
+	if frags.is_empty() {
+		return String::new();
+	}
+
+	// -- Group in lines (top to bottom)
+	frags.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x.total_cmp(&b.x)));
+	let mut lines: Vec<(f32, f32, Vec<TextFrag>)> = Vec::new(); // (y, size, frags)
+	for frag in frags {
+		match lines.last_mut() {
+			Some((y, size, line_frags)) if (*y - frag.y).abs() <= size.max(frag.size) * 0.5 => line_frags.push(frag),
+			_ => lines.push((frag.y, frag.size, vec![frag])),
+		}
+	}
+
+	// -- The char width and left margin
+	let mut sizes: Vec<f32> = lines.iter().flat_map(|(_, _, line)| line.iter().map(|f| f.size)).collect();
+	sizes.sort_by(|a, b| a.total_cmp(b));
+	let char_width = (sizes[sizes.len() / 2] * 0.5).max(1.);
+	let min_x = lines
+		.iter()
+		.flat_map(|(_, _, line)| line.iter().map(|f| f.x))
+		.fold(f32::MAX, f32::min);
+
+	// -- Render
+	let mut out = String::new();
+	let mut prev: Option<(f32, f32)> = None; // (y, size)
+	for (y, size, mut line_frags) in lines {
+		if let Some((prev_y, prev_size)) = prev {
+			out.push('\n');
+			// The larger vertical gaps are blank lines (e.g., between paragraphs)
+			if prev_y - y > prev_size.max(size) * 2. {
+				out.push('\n');
+			}
+		}
+		line_frags.sort_by(|a, b| a.x.total_cmp(&b.x));
+		let mut line = String::new();
+		for frag in line_frags {
+			let col = ((frag.x - min_x) / char_width).round().max(0.) as usize;
+			let len = line.chars().count();
+			if col > len {
+				line.push_str(&" ".repeat(col - len));
+			} else if len > 0 && !line.ends_with(' ') && !frag.text.starts_with(' ') {
+				line.push(' ');
+			}
+			line.push_str(&frag.text);
+		}
+		out.push_str(line.trim_end());
+		prev = Some((y, size));
+	}
+	out.push('\n');
+
+	out
+}
+
+// endregion: --- Layout
+
 // region:    --- Support
 
 fn extract_page(source_doc: &Document, page_id: ObjectId) -> Result<Document> {
@@ -200,3 +532,96 @@ fn copy_object_deep(
 }
 
 // endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use lopdf::content::Operation;
+
+	/// Create a pdf document with one page per `pages` item, each a list of `(x, y, text)`
+	fn new_fx_pdf(pages: &[&[(i64, i64, &str)]]) -> Result<PdfDoc> {
+		let mut doc = Document::with_version("1.5");
+		let pages_id = doc.new_object_id();
+		let font_id = doc.add_object(dictionary! {
+			"Type" => "Font",
+			"Subtype" => "Type1",
+			"BaseFont" => "Courier",
+		});
+		let resources_id = doc.add_object(dictionary! {
+			"Font" => dictionary! { "F1" => font_id },
+		});
+		let mut kids: Vec<Object> = Vec::new();
+		for page_items in pages {
+			let mut operations = Vec::new();
+			for (x, y, text) in page_items.iter() {
+				operations.push(Operation::new("BT", vec![]));
+				operations.push(Operation::new("Tf", vec!["F1".into(), 10.into()]));
+				operations.push(Operation::new("Td", vec![(*x).into(), (*y).into()]));
+				operations.push(Operation::new("Tj", vec![Object::string_literal(*text)]));
+				operations.push(Operation::new("ET", vec![]));
+			}
+			let content = Content { operations };
+			let content_id = doc.add_object(lopdf::Stream::new(dictionary! {}, content.encode()?));
+			let page_id = doc.add_object(dictionary! {
+				"Type" => "Page",
+				"Parent" => pages_id,
+				"Contents" => content_id,
+				"Resources" => resources_id,
+				"MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+			});
+			kids.push(page_id.into());
+		}
+		let count = kids.len() as i64;
+		doc.objects.insert(
+			pages_id,
+			Object::Dictionary(dictionary! { "Type" => "Pages", "Kids" => kids, "Count" => count }),
+		);
+		let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+		doc.trailer.set("Root", catalog_id);
+		let info_id = doc.add_object(dictionary! { "Title" => Object::string_literal("Quarterly Report") });
+		doc.trailer.set("Info", info_id);
+
+		Ok(doc.into())
+	}
+
+	#[test]
+	fn test_support_pdf_parse_page_ranges() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(parse_page_ranges("1-3, 5,8-", 9)?, vec![1, 2, 3, 5, 8, 9]);
+		assert_eq!(parse_page_ranges("2,2,-2", 4)?, vec![1, 2]);
+		assert!(parse_page_ranges("3-5", 4).is_err());
+		assert!(parse_page_ranges("0", 4).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_pdf_extract_pages_text_and_meta() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_pdf = new_fx_pdf(&[
+			&[(50, 800, "Page one")],
+			&[(50, 800, "Name"), (150, 800, "Qty"), (50, 785, "Apple"), (150, 785, "12")],
+		])?;
+
+		// -- Exec
+		let meta = pdf_meta(&fx_pdf);
+		let plain = extract_pdf_pages_text(&fx_pdf, &[2], false)?;
+		let layout = extract_pdf_pages_text(&fx_pdf, &[2], true)?;
+
+		// -- Check
+		assert_eq!(meta.title.as_deref(), Some("Quarterly Report"));
+		assert_eq!(meta.page_count, 2);
+		assert_eq!(plain[0].page, 2);
+		assert!(plain[0].text.contains("Apple"));
+		// 100 points at 5 points per char is 20 columns
+		assert_eq!(layout[0].text, "Name                Qty\nApple               12\n");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -1,5 +1,9 @@
+use crate::types::ResUsage;
 use crate::{Error, Result};
+use std::collections::HashMap;
 use std::process::Stdio;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
 use tokio::process::Command;
 
 #[derive(Debug, Clone, Default)]
@@ -101,3 +105,107 @@ pub async fn proc_exec(cmd: &str, args: &[&str], options: Option<&ProcOptions>)
 
 	Ok(())
 }
+
+// region:    --- ProcUsageSampler
+
+/// The resource usage sampler of a spawned process and its sub processes (for the run resource usage).
+///
+/// NOTE: This is sampled (see `ProcUsageSampler::INTERVAL`), so the sub processes starting and ending
+///       between two samples are not counted, and the peak memory is the max of the sampled memory.
+pub struct ProcUsageSampler {
+	sys: System,
+	pid: Pid,
+	/// The last sampled (cpu ms, read bytes, written bytes) of each process of the tree
+	procs: HashMap<Pid, (u64, u64, u64)>,
+	peak_mem: u64,
+	last_sample: Instant,
+}
+
+/// Constructor
+impl ProcUsageSampler {
+	pub const INTERVAL: Duration = Duration::from_millis(250);
+
+	/// Start sampling the process (sampled right away).
+	pub fn start(pid: u32) -> Self {
+		let mut sampler = Self {
+			sys: System::new(),
+			pid: Pid::from_u32(pid),
+			procs: HashMap::new(),
+			peak_mem: 0,
+			last_sample: Instant::now(),
+		};
+		sampler.sample();
+		sampler
+	}
+}
+
+/// Sampling
+impl ProcUsageSampler {
+	/// Sample when the last sample is older than `INTERVAL`.
+	pub fn maybe_sample(&mut self) {
+		if self.last_sample.elapsed() >= Self::INTERVAL {
+			self.sample();
+		}
+	}
+
+	/// Sample the process, and its sub processes.
+	pub fn sample(&mut self) {
+		self.sys.refresh_processes_specifics(
+			ProcessesToUpdate::All,
+			true,
+			ProcessRefreshKind::nothing().with_memory().with_cpu().with_disk_usage(),
+		);
+		self.last_sample = Instant::now();
+
+		let mut tree_mem = 0;
+		for (pid, process) in self.sys.processes() {
+			if process.thread_kind().is_some() || !self.is_in_tree(*pid) {
+				continue;
+			}
+			let disk_usage = process.disk_usage();
+			tree_mem += process.memory();
+			self.procs.insert(
+				*pid,
+				(
+					process.accumulated_cpu_time(),
+					disk_usage.total_read_bytes,
+					disk_usage.total_written_bytes,
+				),
+			);
+		}
+		self.peak_mem = self.peak_mem.max(tree_mem);
+	}
+
+	/// The usage of the sampled processes.
+	pub fn usage(&self) -> ResUsage {
+		let (cpu_ms, read_bytes, write_bytes) = self
+			.procs
+			.values()
+			.fold((0, 0, 0), |(cpu, read, write), (p_cpu, p_read, p_write)| {
+				(cpu + p_cpu, read + p_read, write + p_write)
+			});
+
+		ResUsage {
+			cpu_ms: cpu_ms as i64,
+			peak_mem: self.peak_mem as i64,
+			read_bytes: read_bytes as i64,
+			write_bytes: write_bytes as i64,
+		}
+	}
+
+	fn is_in_tree(&self, mut pid: Pid) -> bool {
+		// NOTE: Bounded, in case of a parent cycle (pid reuse)
+		for _ in 0..64 {
+			if pid == self.pid {
+				return true;
+			}
+			match self.sys.process(pid).and_then(|p| p.parent()) {
+				Some(parent) => pid = parent,
+				None => return false,
+			}
+		}
+		false
+	}
+}
+
+// endregion: --- ProcUsageSampler
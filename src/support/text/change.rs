@@ -7,6 +7,15 @@ const LINE_MARKER_SEARCH_START: &str = "<<<<<<< SEARCH";
 const LINE_MARKER_SEP: &str = "=======";
 const LINE_MARKER_REPLACE_END: &str = ">>>>>>> REPLACE";
 
+/// A single search/replace edit.
+///
+/// Built from a change block, a unified diff hunk, or given directly (e.g., Lua `{search, replace}` edits).
+#[derive(Debug, Clone)]
+pub struct ChangeEdit {
+	pub search: String,
+	pub replace: String,
+}
+
 /// Applies changes to an original content string.
 ///
 /// 1.  **Simple Replacement Mode**: If no line in `changes` exactly matches `<<<<<<< SEARCH`
@@ -47,7 +56,37 @@ pub fn apply_changes(original_content: impl Into<String>, changes: impl Into<Str
 	}
 
 	// Block Processing Mode
-	let requests = process_change_requests(&changes_str)?;
+	let edits = process_change_requests(&changes_str)?
+		.into_iter()
+		.map(|req| ChangeEdit {
+			search: changes_str[req.search_start_idx..req.search_end_idx].to_string(),
+			replace: changes_str[req.replace_start_idx..req.replace_end_idx].to_string(),
+		})
+		.collect();
+
+	Ok(apply_edits(original_content, edits))
+}
+
+/// Applies a unified diff (single file) to an original content string.
+///
+/// Each `@@` hunk becomes a search/replace edit (context and `-` lines as search, context and `+` lines as replace).
+/// The `@@ -l,s +l,s @@` line numbers are not used, the hunks are located by content (first occurrence).
+pub fn apply_udiff(original_content: impl Into<String>, udiff: &str) -> Result<(String, ChangesInfo)> {
+	let edits = udiff_to_edits(udiff)?;
+	Ok(apply_edits(original_content.into(), edits))
+}
+
+/// Returns true if the changes look like a unified diff (starts with `diff --git`, `--- `, or `@@`).
+pub fn is_udiff(changes: &str) -> bool {
+	changes
+		.lines()
+		.find(|line| !line.trim().is_empty())
+		.is_some_and(|line| line.starts_with("@@") || line.starts_with("--- ") || line.starts_with("diff --git"))
+}
+
+/// Applies the edits in order. An edit that cannot be applied is reported in the `failed_changes`
+/// (with its 1-based `idx`), the other edits are still applied.
+pub fn apply_edits(original_content: String, edits: Vec<ChangeEdit>) -> (String, ChangesInfo) {
 	let mut current_content = original_content;
 	let mut changed_count = 0;
 	let mut failed_changes = Vec::new();
@@ -83,10 +122,9 @@ pub fn apply_changes(original_content: impl Into<String>, changes: impl Into<Str
 		(content, false)
 	}
 
-	for req in requests {
-		// Extract patterns using indices. process_change_requests ensures valid indices for changes_str.
-		let search_pattern = &changes_str[req.search_start_idx..req.search_end_idx];
-		let replace_pattern = &changes_str[req.replace_start_idx..req.replace_end_idx];
+	for (idx, edit) in edits.into_iter().enumerate() {
+		let search_pattern = edit.search.as_str();
+		let replace_pattern = edit.replace.as_str();
 
 		let (content, changed) = if replace_pattern.is_empty() && !search_pattern.is_empty() {
 			let (content, changed) = replace_first_remove_line(current_content, search_pattern);
@@ -118,8 +156,9 @@ pub fn apply_changes(original_content: impl Into<String>, changes: impl Into<Str
 			changed_count += 1;
 		} else {
 			failed_changes.push(FailChange {
-				search: search_pattern.to_string(),
-				replace: replace_pattern.to_string(),
+				idx: idx + 1,
+				search: edit.search,
+				replace: edit.replace,
 				reason: "Search block not found in content".to_string(),
 			});
 		}
@@ -127,13 +166,13 @@ pub fn apply_changes(original_content: impl Into<String>, changes: impl Into<Str
 		current_content = content;
 	}
 
-	Ok((
+	(
 		current_content,
 		ChangesInfo {
 			changed_count,
 			failed_changes,
 		},
-	))
+	)
 }
 
 #[derive(Debug)] // For easier debugging if needed during development
@@ -262,6 +301,119 @@ fn process_change_requests(changes_str: &str) -> Result<Vec<ChangeRequestIndices
 	}
 }
 
+/// Parses a single file unified diff into search/replace edits (one per `@@` hunk).
+///
+/// - The file headers (`diff --git`, `index`, `--- a/..` + `+++ b/..`) are skipped.
+/// - Empty lines in a hunk are treated as empty context lines (common in LLM generated diffs).
+/// - Hunks without `+` or `-` lines are ignored.
+fn udiff_to_edits(udiff: &str) -> Result<Vec<ChangeEdit>> {
+	let mut edits = Vec::new();
+	let mut hunk: Option<UdiffHunk> = None;
+	let mut file_count = 0;
+	let mut in_git_header = false;
+
+	let mut lines = udiff.lines().map(|line| line.strip_suffix('\r').unwrap_or(line)).peekable();
+	while let Some(line) = lines.next() {
+		// -- File headers
+		let is_file_header = line.starts_with("diff --git")
+			|| line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ "));
+		if is_file_header {
+			if let Some(hunk) = hunk.take() {
+				hunk.push_to(&mut edits)?;
+			}
+			// NOTE: The `---`/`+++` lines of a `diff --git` are the same file
+			if !(line.starts_with("--- ") && in_git_header) {
+				file_count += 1;
+			}
+			in_git_header = line.starts_with("diff --git");
+			if file_count > 1 {
+				return Err(Error::custom(
+					"Unified diff with multiple files is not supported (one diff per file)",
+				));
+			}
+			if line.starts_with("--- ") {
+				lines.next(); // the `+++ ` line
+			}
+			continue;
+		}
+
+		if line.starts_with("@@") {
+			in_git_header = false;
+			if let Some(hunk) = hunk.take() {
+				hunk.push_to(&mut edits)?;
+			}
+			hunk = Some(UdiffHunk {
+				is_new_file: line.starts_with("@@ -0,0 "),
+				..Default::default()
+			});
+			continue;
+		}
+
+		// Before the first hunk (e.g., `index ...`), ignore
+		let Some(hunk) = hunk.as_mut() else {
+			continue;
+		};
+
+		if line.starts_with('\\') {
+			// e.g., `\ No newline at end of file`
+			continue;
+		} else if let Some(rest) = line.strip_prefix('-') {
+			hunk.search.push(rest);
+			hunk.has_change = true;
+		} else if let Some(rest) = line.strip_prefix('+') {
+			hunk.replace.push(rest);
+			hunk.has_change = true;
+		} else {
+			let rest = line.strip_prefix(' ').unwrap_or(line);
+			hunk.search.push(rest);
+			hunk.replace.push(rest);
+		}
+	}
+	if let Some(hunk) = hunk.take() {
+		hunk.push_to(&mut edits)?;
+	}
+
+	if edits.is_empty() {
+		return Err(Error::custom("Unified diff does not have any '@@' hunk with changes"));
+	}
+
+	Ok(edits)
+}
+
+#[derive(Default)]
+struct UdiffHunk<'a> {
+	search: Vec<&'a str>,
+	replace: Vec<&'a str>,
+	has_change: bool,
+	/// `@@ -0,0 ...` hunk (the file creation)
+	is_new_file: bool,
+}
+
+impl UdiffHunk<'_> {
+	fn push_to(self, edits: &mut Vec<ChangeEdit>) -> Result<()> {
+		if !self.has_change {
+			return Ok(());
+		}
+		// NOTE: Without context or removed lines, the hunk cannot be located (unless it creates the file)
+		if self.search.is_empty() && !self.is_new_file {
+			return Err(Error::custom(format!(
+				"Unified diff hunk {} has no context or removed lines to locate it",
+				edits.len() + 1
+			)));
+		}
+		let mut replace = self.replace.join("\n");
+		// The created file content ends with a newline (like with `git apply`)
+		if self.is_new_file {
+			replace.push('\n');
+		}
+		edits.push(ChangeEdit {
+			search: self.search.join("\n"),
+			replace,
+		});
+		Ok(())
+	}
+}
+
 #[cfg(test)]
 #[path = "change_tests.rs"]
 mod tests;
@@ -543,3 +543,122 @@ fn test_support_text_apply_change_crlf_windows() -> Result {
 
 	Ok(())
 }
+
+#[test]
+fn test_support_text_apply_udiff_multiple_hunks() -> Result {
+	// -- Setup & Fixtures
+	let original = "fn a() {\n\tprintln!(\"a\");\n}\n\nfn b() {\n\tprintln!(\"b\");\n}\n";
+	let udiff = r#"--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn a() {
+-	println!("a");
++	println!("AA");
+ }
+@@ -5,3 +5,4 @@
+ fn b() {
+ 	println!("b");
++	println!("bb");
+ }
+"#;
+
+	// -- Exec
+	let (result, info) = apply_udiff(original, udiff)?;
+
+	// -- Check
+	assert_eq!(
+		result,
+		"fn a() {\n\tprintln!(\"AA\");\n}\n\nfn b() {\n\tprintln!(\"b\");\n\tprintln!(\"bb\");\n}\n"
+	);
+	assert_eq!(info.changed_count, 2);
+	assert!(info.failed_changes.is_empty());
+
+	Ok(())
+}
+
+#[test]
+fn test_support_text_apply_udiff_hunk_not_found() -> Result {
+	// -- Setup & Fixtures
+	let original = "one\ntwo\nthree\n";
+	let udiff = "@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n@@ -9,2 +9,2 @@\n nine\n-ten\n+TEN\n";
+
+	// -- Exec
+	let (result, info) = apply_udiff(original, udiff)?;
+
+	// -- Check
+	assert_eq!(result, "one\nTWO\nthree\n");
+	assert_eq!(info.changed_count, 1);
+	let failed = info.failed_changes.first().ok_or("Should have one failed change")?;
+	assert_eq!(failed.idx, 2);
+	assert_eq!(failed.search, "nine\nten");
+
+	Ok(())
+}
+
+#[test]
+fn test_support_text_apply_udiff_invalid() -> Result {
+	// -- Setup & Fixtures
+	let fx_udiffs = [
+		// multiple files
+		"--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-a\n+b\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +1 @@\n-a\n+b\n",
+		// no context to locate the hunk
+		"@@ -3,0 +4 @@\n+added\n",
+		// no hunk
+		"--- a/a.rs\n+++ b/a.rs\n",
+	];
+
+	// -- Exec & Check
+	for fx_udiff in fx_udiffs {
+		assert!(apply_udiff("a\n", fx_udiff).is_err(), "Should fail for: {fx_udiff}");
+	}
+
+	Ok(())
+}
+
+#[test]
+fn test_support_text_apply_udiff_git_header_and_new_file() -> Result {
+	// -- Setup & Fixtures
+	let udiff = "diff --git a/new.md b/new.md\nnew file mode 100644\nindex 0000000..e69de29\n--- /dev/null\n+++ b/new.md\n@@ -0,0 +1,2 @@\n+# Title\n+Hello\n";
+
+	// -- Exec
+	let (result, info) = apply_udiff("", udiff)?;
+
+	// -- Check
+	assert!(is_udiff(udiff));
+	assert!(!is_udiff("<<<<<<< SEARCH\na\n=======\nb\n>>>>>>> REPLACE"));
+	assert_eq!(result, "# Title\nHello\n");
+	assert_eq!(info.changed_count, 1);
+
+	Ok(())
+}
+
+#[test]
+fn test_support_text_apply_edits_with_failed_idx() -> Result {
+	// -- Setup & Fixtures
+	let original = "alpha beta gamma".to_string();
+	let edits = vec![
+		ChangeEdit {
+			search: "alpha".to_string(),
+			replace: "ALPHA".to_string(),
+		},
+		ChangeEdit {
+			search: "delta".to_string(),
+			replace: "DELTA".to_string(),
+		},
+		ChangeEdit {
+			search: "gamma".to_string(),
+			replace: "GAMMA".to_string(),
+		},
+	];
+
+	// -- Exec
+	let (result, info) = apply_edits(original, edits);
+
+	// -- Check
+	assert_eq!(result, "ALPHA beta GAMMA");
+	assert_eq!(info.changed_count, 2);
+	assert_eq!(info.failed_changes.len(), 1);
+	assert_eq!(info.failed_changes[0].idx, 2);
+
+	Ok(())
+}
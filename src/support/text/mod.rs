@@ -4,12 +4,18 @@ mod change;
 mod formatters;
 mod hash;
 mod line_block_iter;
+mod secrets;
 mod text_common;
+mod token_count;
+mod token_split;
 
 pub use change::*;
 pub use formatters::*;
 pub use hash::*;
 pub use line_block_iter::*;
+pub use secrets::*;
 pub use text_common::*;
+pub use token_count::*;
+pub use token_split::*;
 
 // endregion: --- Modules
@@ -0,0 +1,135 @@
+//! Token count, to chunk the inputs to fit the model context windows.
+//!
+//! The text is encoded with the tiktoken BPE of the model family encoding (`cl100k_base` or `o200k_base`).
+//!
+//! NOTE: The count is exact for the OpenAI models. For the other providers (own tokenizers),
+//!       the `cl100k_base` count is an estimate.
+
+use crate::support::text::format_num;
+use tiktoken_rs::CoreBPE;
+
+// region:    --- TokenEncoding
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenEncoding {
+	/// GPT-4 / GPT-3.5 (and the default for the other providers)
+	Cl100k,
+	/// GPT-4o, GPT-4.1, GPT-5, o-series (larger vocabulary, more efficient on non-English text)
+	O200k,
+}
+
+impl TokenEncoding {
+	/// Returns the encoding for a model name (e.g., `gpt-4o-mini`, `openai::gpt-5`, `claude-sonnet-4-5`),
+	/// or an encoding name (`cl100k_base`, `o200k_base`).
+	pub fn for_model(model: &str) -> Self {
+		// NOTE: Strip the genai namespace (e.g., `openai::gpt-5`)
+		let model = model.rsplit("::").next().unwrap_or(model).to_lowercase();
+
+		const O200K_PREFIXES: &[&str] = &[
+			"o200k",
+			"gpt-4o",
+			"chatgpt-4o",
+			"gpt-4.1",
+			"gpt-4.5",
+			"gpt-5",
+			"gpt-oss",
+			"o1",
+			"o3",
+			"o4",
+		];
+		if O200K_PREFIXES.iter().any(|prefix| model.starts_with(prefix)) {
+			TokenEncoding::O200k
+		} else {
+			TokenEncoding::Cl100k
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			TokenEncoding::Cl100k => "cl100k_base",
+			TokenEncoding::O200k => "o200k_base",
+		}
+	}
+
+	/// The BPE of the encoding (loaded on the first call)
+	fn bpe(&self) -> &'static CoreBPE {
+		match self {
+			TokenEncoding::Cl100k => tiktoken_rs::cl100k_base_singleton(),
+			TokenEncoding::O200k => tiktoken_rs::o200k_base_singleton(),
+		}
+	}
+}
+
+// endregion: --- TokenEncoding
+
+/// Returns the number of tokens of `text` for the encoding (the special tokens are counted as text).
+pub fn token_count(text: &str, encoding: TokenEncoding) -> usize {
+	if text.is_empty() {
+		return 0;
+	}
+	encoding.bpe().encode_ordinary(text).len()
+}
+
+/// Returns the estimated prompt tokens of chat messages (`contents`), with the per message overhead.
+pub fn prompt_token_count<'a>(contents: impl IntoIterator<Item = &'a str>, encoding: TokenEncoding) -> usize {
+	// NOTE: The chat format adds ~4 tokens per message (role and separators), and ~3 to prime the reply
+	contents
+		.into_iter()
+		.map(|content| token_count(content, encoding) + 4)
+		.sum::<usize>()
+		+ 3
+}
+
+/// Format a token count estimate for display (e.g., `~1,234 tokens (o200k_base estimate)`)
+pub fn format_token_estimate(count: usize, encoding: TokenEncoding) -> String {
+	format!("~{} tokens ({} estimate)", format_num(count as i64), encoding.as_str())
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_text_token_count_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		// (text, expected cl100k count)
+		let cases = [
+			("", 0),
+			("Hello world", 2),
+			("Hello, world!", 4),
+			("I can't do it.", 6),
+			("12345", 2),
+			("internationalization", 2),
+			("fn main() {\n    println!(\"hi\");\n}\n", 10),
+		];
+
+		// -- Exec & Check
+		for (text, expected) in cases {
+			assert_eq!(token_count(text, TokenEncoding::Cl100k), expected, "text: {text:?}");
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_text_token_count_encoding() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(TokenEncoding::for_model("gpt-4o-mini"), TokenEncoding::O200k);
+		assert_eq!(TokenEncoding::for_model("openai::gpt-5"), TokenEncoding::O200k);
+		assert_eq!(TokenEncoding::for_model("o3-mini"), TokenEncoding::O200k);
+		assert_eq!(TokenEncoding::for_model("gpt-4-turbo"), TokenEncoding::Cl100k);
+		assert_eq!(TokenEncoding::for_model("claude-sonnet-4-5"), TokenEncoding::Cl100k);
+		assert_eq!(TokenEncoding::for_model("cl100k_base"), TokenEncoding::Cl100k);
+		// O200k is more efficient on non-English text
+		let text = "日本語のテキストです";
+		assert!(token_count(text, TokenEncoding::O200k) < token_count(text, TokenEncoding::Cl100k));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
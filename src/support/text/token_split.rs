@@ -0,0 +1,263 @@
+//! Split a content into chunks of at most `max_tokens` (counted with `token_count`),
+//! on the paragraph, heading, and code block boundaries when possible.
+
+use crate::support::text::{TokenEncoding, token_count};
+
+#[derive(Debug, Clone)]
+pub struct TokenSplitOptions {
+	pub max_tokens: usize,
+	/// The tokens of the end of a chunk repeated at the start of the next chunk (whole lines)
+	pub overlap_tokens: usize,
+	pub encoding: TokenEncoding,
+}
+
+/// Split `content` into chunks of at most `max_tokens`.
+///
+/// The content is split into blocks (paragraphs, headings with their following paragraph, fenced code blocks),
+/// which are grouped into chunks. A block larger than `max_tokens` is split by lines (then words, then chars).
+///
+/// NOTE: Without overlap, the concatenation of the chunks is the content.
+pub fn split_by_tokens(content: &str, options: &TokenSplitOptions) -> Vec<String> {
+	let max_tokens = options.max_tokens.max(1);
+
+	// -- Build the units (each at most max_tokens)
+	let mut units: Vec<(&str, usize)> = Vec::new();
+	for block in split_blocks(content) {
+		push_units(&mut units, block, max_tokens, options.encoding, SplitLevel::Block);
+	}
+
+	// -- Group the units into chunks
+	let mut chunks: Vec<String> = Vec::new();
+	let mut current: Vec<(&str, usize)> = Vec::new();
+	let mut current_tokens = 0;
+
+	for (unit, tokens) in units {
+		// NOTE: `current` always has a new unit here (the overlap is followed by a unit)
+		if !current.is_empty() && current_tokens + tokens > max_tokens {
+			chunks.push(current.iter().map(|(unit, _)| *unit).collect());
+
+			// Start the next chunk with the overlap (the last lines of the chunk, if they fit with the unit)
+			let overlap_budget = options.overlap_tokens.min(max_tokens - tokens);
+			current = overlap_units(&current, overlap_budget, options.encoding);
+			current_tokens = current.iter().map(|(_, tokens)| tokens).sum();
+		}
+		current.push((unit, tokens));
+		current_tokens += tokens;
+	}
+	if !current.is_empty() {
+		chunks.push(current.iter().map(|(unit, _)| *unit).collect());
+	}
+
+	chunks
+}
+
+// region:    --- Support
+
+#[derive(Debug, Clone, Copy)]
+enum SplitLevel {
+	Block,
+	Line,
+	Word,
+}
+
+/// Push the `text` as one unit if it fits in `max_tokens`, otherwise split it at the next level.
+fn push_units<'a>(
+	units: &mut Vec<(&'a str, usize)>,
+	text: &'a str,
+	max_tokens: usize,
+	encoding: TokenEncoding,
+	level: SplitLevel,
+) {
+	let tokens = token_count(text, encoding);
+	if tokens <= max_tokens {
+		units.push((text, tokens));
+		return;
+	}
+
+	match level {
+		SplitLevel::Block => {
+			for line in text.split_inclusive('\n') {
+				push_units(units, line, max_tokens, encoding, SplitLevel::Line);
+			}
+		}
+		SplitLevel::Line => {
+			for word in text.split_inclusive(char::is_whitespace) {
+				push_units(units, word, max_tokens, encoding, SplitLevel::Word);
+			}
+		}
+		// NOTE: A piece of n chars is at most n tokens, so max_tokens chars always fit
+		SplitLevel::Word => {
+			let mut start = 0;
+			for (char_idx, (byte_idx, _)) in text.char_indices().enumerate() {
+				if char_idx > 0 && char_idx % max_tokens == 0 {
+					let piece = &text[start..byte_idx];
+					units.push((piece, token_count(piece, encoding)));
+					start = byte_idx;
+				}
+			}
+			let piece = &text[start..];
+			units.push((piece, token_count(piece, encoding)));
+		}
+	}
+}
+
+/// Returns the last lines of the chunk units which fit in `budget` tokens.
+fn overlap_units<'a>(chunk: &[(&'a str, usize)], budget: usize, encoding: TokenEncoding) -> Vec<(&'a str, usize)> {
+	let mut overlap: Vec<(&'a str, usize)> = Vec::new();
+	let mut overlap_tokens = 0;
+
+	'units: for (unit, _) in chunk.iter().rev() {
+		let lines: Vec<&str> = unit.split_inclusive('\n').collect();
+		for line in lines.into_iter().rev() {
+			let tokens = token_count(line, encoding);
+			if overlap_tokens + tokens > budget {
+				break 'units;
+			}
+			overlap.push((line, tokens));
+			overlap_tokens += tokens;
+		}
+	}
+
+	overlap.reverse();
+	overlap
+}
+
+/// Split the content into blocks (each block ends with its trailing blank lines):
+/// - A fenced code block (```` ``` ```` or `~~~`) is one block.
+/// - A heading starts a block, and stays with the paragraph which follows it.
+/// - A blank line ends a paragraph block.
+fn split_blocks(content: &str) -> Vec<&str> {
+	let mut blocks = Vec::new();
+	let mut block_start = 0;
+	let mut offset = 0;
+	let mut fence: Option<&str> = None;
+	// The block ended with blank lines (the next non blank line starts a new block)
+	let mut after_blank = false;
+	// The block only has heading (and blank) lines so far
+	let mut only_heading = false;
+
+	for line in content.split_inclusive('\n') {
+		let trimmed = line.trim_start();
+		let line_start = offset;
+		offset += line.len();
+
+		// -- In a code block, until the closing fence
+		if let Some(fence_marker) = fence {
+			if trimmed.trim_end() == fence_marker {
+				fence = None;
+				after_blank = true;
+			}
+			continue;
+		}
+
+		if trimmed.trim().is_empty() {
+			after_blank = true;
+			continue;
+		}
+
+		let fence_marker = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker));
+		let is_heading = trimmed.starts_with('#') && trimmed.trim_start_matches('#').starts_with([' ', '\t', '\n']);
+
+		let starts_block = fence_marker.is_some() || is_heading || (after_blank && !only_heading);
+		if starts_block && line_start > block_start {
+			blocks.push(&content[block_start..line_start]);
+			block_start = line_start;
+			only_heading = false;
+		}
+
+		if fence_marker.is_some() {
+			// NOTE: The closing fence is the same marker (e.g., "```" for "```rust")
+			fence = fence_marker;
+			only_heading = false;
+		} else {
+			only_heading = is_heading && (only_heading || line_start == block_start);
+		}
+		after_blank = false;
+	}
+
+	if block_start < content.len() {
+		blocks.push(&content[block_start..]);
+	}
+
+	blocks
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_support_text_token_split_blocks() -> Result<()> {
+		// -- Setup & Fixtures
+		let content = "# Title\n\nFirst paragraph\nstill first.\n\nSecond paragraph.\n```rust\nfn main() {\n\n}\n```\nAfter code.\n";
+
+		// -- Exec
+		let blocks = split_blocks(content);
+
+		// -- Check
+		assert_eq!(
+			blocks,
+			vec![
+				"# Title\n\nFirst paragraph\nstill first.\n\n",
+				"Second paragraph.\n",
+				"```rust\nfn main() {\n\n}\n```\n",
+				"After code.\n",
+			]
+		);
+		assert_eq!(blocks.concat(), content);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_support_text_token_split_by_tokens() -> Result<()> {
+		// -- Setup & Fixtures
+		let paragraph = "The quick brown fox jumps over the lazy dog.\n";
+		let content = format!(
+			"{}\n{}\n{}",
+			paragraph.repeat(3),
+			paragraph.repeat(3),
+			paragraph.repeat(3)
+		);
+		let options = TokenSplitOptions {
+			max_tokens: 40,
+			overlap_tokens: 0,
+			encoding: TokenEncoding::Cl100k,
+		};
+
+		// -- Exec
+		let chunks = split_by_tokens(&content, &options);
+		let overlap_chunks = split_by_tokens(
+			&content,
+			&TokenSplitOptions {
+				max_tokens: 50,
+				overlap_tokens: 12,
+				..options.clone()
+			},
+		);
+		let long_word_chunks = split_by_tokens(&"x".repeat(500), &options);
+
+		// -- Check
+		// each paragraph (3 lines of 10 tokens) is a chunk
+		assert_eq!(chunks.len(), 3);
+		assert_eq!(chunks.concat(), content);
+		assert!(chunks.iter().all(|c| token_count(c, TokenEncoding::Cl100k) <= 40));
+		// with overlap, the chunks start with the last line of the previous chunk
+		assert_eq!(overlap_chunks.len(), 3);
+		assert_eq!(overlap_chunks[1], format!("{paragraph}\n{}\n", paragraph.repeat(3)));
+		assert!(overlap_chunks.iter().all(|c| token_count(c, TokenEncoding::Cl100k) <= 50));
+		assert!(long_word_chunks.len() > 1);
+		assert_eq!(long_word_chunks.concat(), "x".repeat(500));
+		assert!(split_by_tokens("", &options).is_empty());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -49,6 +49,17 @@ pub fn now_rfc3339_utc_sec() -> Result<String> {
 	now_fmt_utc(&RFC3339_SEC)
 }
 
+/// Returns the local now (with the system time zone, or UTC if it cannot be determined).
+pub fn now_local() -> OffsetDateTime {
+	use time_tz::OffsetDateTimeExt as _;
+
+	let now_utc = OffsetDateTime::now_utc();
+	match time_tz::system::get_timezone() {
+		Ok(tz) => now_utc.to_timezone(tz),
+		Err(_) => now_utc,
+	}
+}
+
 pub fn today_local() -> Result<String> {
 	let now_utc = OffsetDateTime::now_utc();
 
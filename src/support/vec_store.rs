@@ -0,0 +1,234 @@
+//! A small local vector store (sqlite file), for the embedding-based retrieval of `aip.vec`.
+//!
+//! The vectors are grouped by index (e.g., `"docs"`), each with an id and an optional JSON meta.
+//! The search is an exact cosine similarity scan of the index (fine for the agent scale, up to ~100k vectors).
+
+use crate::{Error, Result};
+use rusqlite::{Connection, OptionalExtension as _, params};
+use serde_json::Value;
+use simple_fs::{SPath, ensure_file_dir};
+use std::time::Duration;
+
+const CREATE_TABLE_SQL: &str = "
+CREATE TABLE IF NOT EXISTS vec (
+	idx     TEXT NOT NULL,
+	id      TEXT NOT NULL,
+	dims    INTEGER NOT NULL,
+	vector  BLOB NOT NULL, -- f32 little endian
+	meta    TEXT,          -- json
+	PRIMARY KEY (idx, id)
+) STRICT";
+
+#[derive(Debug, Clone)]
+pub struct VecHit {
+	pub id: String,
+	/// The cosine similarity (-1.0 to 1.0, higher is closer)
+	pub score: f64,
+	pub meta: Option<Value>,
+}
+
+pub struct VecStore {
+	con: Connection,
+}
+
+/// Constructors
+impl VecStore {
+	/// Open (or create) the vector store file db (e.g., `.aipack/.session/_vec.db`).
+	pub fn open(path: &SPath) -> Result<Self> {
+		ensure_file_dir(path).map_err(|err| Error::cc(format!("Cannot create dir for '{path}'"), err))?;
+		let con = Connection::open(path.as_std_path())
+			.map_err(|err| Error::cc(format!("Cannot open vector store '{path}'"), err))?;
+		// NOTE: The tasks can write concurrently (each with its own connection)
+		con.busy_timeout(Duration::from_secs(10)).map_err(sql_err)?;
+		con.execute(CREATE_TABLE_SQL, ()).map_err(sql_err)?;
+		Ok(Self { con })
+	}
+}
+
+/// Operations
+impl VecStore {
+	/// Insert or replace the vector `id` of `index`.
+	///
+	/// Fails if the vector dimensions differ from the other vectors of the index.
+	pub fn upsert(&self, index: &str, id: &str, vector: &[f32], meta: Option<&Value>) -> Result<()> {
+		validate_vector(vector)?;
+		if let Some(dims) = self.index_dims(index)?
+			&& dims != vector.len()
+		{
+			return Err(Error::custom(format!(
+				"Vector dimensions mismatch for index '{index}': expected {dims}, but was {}",
+				vector.len()
+			)));
+		}
+
+		let meta = meta.map(|meta| meta.to_string());
+		self.con
+			.execute(
+				"INSERT OR REPLACE INTO vec (idx, id, dims, vector, meta) VALUES (?1, ?2, ?3, ?4, ?5)",
+				params![index, id, vector.len() as i64, vector_to_blob(vector), meta],
+			)
+			.map_err(sql_err)?;
+		Ok(())
+	}
+
+	/// Returns the `k` vectors of `index` closest to `vector` (cosine similarity, highest first).
+	pub fn search(&self, index: &str, vector: &[f32], k: usize) -> Result<Vec<VecHit>> {
+		validate_vector(vector)?;
+		if let Some(dims) = self.index_dims(index)?
+			&& dims != vector.len()
+		{
+			return Err(Error::custom(format!(
+				"Vector dimensions mismatch for index '{index}': expected {dims}, but was {}",
+				vector.len()
+			)));
+		}
+
+		let mut stmt = self
+			.con
+			.prepare("SELECT id, vector, meta FROM vec WHERE idx = ?1")
+			.map_err(sql_err)?;
+		let rows = stmt
+			.query_map([index], |row| {
+				Ok((
+					row.get::<_, String>(0)?,
+					row.get::<_, Vec<u8>>(1)?,
+					row.get::<_, Option<String>>(2)?,
+				))
+			})
+			.map_err(sql_err)?;
+
+		let query_norm = norm(vector);
+		let mut hits: Vec<(String, f64, Option<String>)> = Vec::new();
+		for row in rows {
+			let (id, blob, meta) = row.map_err(sql_err)?;
+			let score = cosine(vector, query_norm, &blob_to_vector(&blob));
+			hits.push((id, score, meta));
+		}
+
+		// NOTE: The id as the tie breaker, so that the results are stable
+		hits.sort_by(|(id_a, a, _), (id_b, b, _)| b.total_cmp(a).then_with(|| id_a.cmp(id_b)));
+		hits.truncate(k);
+
+		hits.into_iter()
+			.map(|(id, score, meta)| {
+				let meta = meta.map(|meta| serde_json::from_str(&meta)).transpose()?;
+				Ok(VecHit { id, score, meta })
+			})
+			.collect()
+	}
+
+	/// Delete the vector `id` of `index`. Returns true if it existed.
+	pub fn delete(&self, index: &str, id: &str) -> Result<bool> {
+		let count = self
+			.con
+			.execute("DELETE FROM vec WHERE idx = ?1 AND id = ?2", [index, id])
+			.map_err(sql_err)?;
+		Ok(count > 0)
+	}
+
+	#[allow(unused)]
+	pub fn count(&self, index: &str) -> Result<usize> {
+		let count: i64 = self
+			.con
+			.query_row("SELECT COUNT(*) FROM vec WHERE idx = ?1", [index], |row| row.get(0))
+			.map_err(sql_err)?;
+		Ok(count as usize)
+	}
+}
+
+// region:    --- Support
+
+fn sql_err(err: rusqlite::Error) -> Error {
+	Error::cc("Vector store error", err)
+}
+
+impl VecStore {
+	fn index_dims(&self, index: &str) -> Result<Option<usize>> {
+		let dims: Option<i64> = self
+			.con
+			.query_row("SELECT dims FROM vec WHERE idx = ?1 LIMIT 1", [index], |row| row.get(0))
+			.optional()
+			.map_err(sql_err)?;
+		Ok(dims.map(|dims| dims as usize))
+	}
+}
+
+fn validate_vector(vector: &[f32]) -> Result<()> {
+	if vector.is_empty() {
+		return Err(Error::custom("Vector cannot be empty"));
+	}
+	if vector.iter().any(|v| !v.is_finite()) {
+		return Err(Error::custom("Vector values must be finite numbers"));
+	}
+	Ok(())
+}
+
+fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
+	vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
+	blob.chunks_exact(4)
+		.map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+		.collect()
+}
+
+fn norm(vector: &[f32]) -> f64 {
+	vector.iter().map(|v| (*v as f64) * (*v as f64)).sum::<f64>().sqrt()
+}
+
+/// Cosine similarity (0.0 if one of the vectors is all zeros)
+fn cosine(query: &[f32], query_norm: f64, vector: &[f32]) -> f64 {
+	let dot: f64 = query.iter().zip(vector).map(|(a, b)| (*a as f64) * (*b as f64)).sum();
+	let denom = query_norm * norm(vector);
+	if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_support_vec_store_upsert_search_delete() -> Result<()> {
+		// -- Setup & Fixtures
+		let path = SPath::new("tests-data/sandbox-01/.tmp/test_support_vec_store/_vec.db");
+		if path.exists() {
+			std::fs::remove_file(path.as_std_path())?;
+		}
+		let store = VecStore::open(&path)?;
+
+		// -- Exec
+		store.upsert("docs", "a", &[1.0, 0.0, 0.0], Some(&json!({"file": "a.md"})))?;
+		store.upsert("docs", "b", &[0.7, 0.7, 0.0], None)?;
+		store.upsert("docs", "c", &[0.0, 0.0, 1.0], None)?;
+		store.upsert("other", "a", &[1.0, 0.0], None)?;
+		store.upsert("docs", "c", &[0.0, 1.0, 0.0], None)?; // replace
+		let hits = store.search("docs", &[1.0, 0.1, 0.0], 2)?;
+		let dims_res = store.upsert("docs", "d", &[1.0, 0.0], None);
+		let deleted = store.delete("docs", "a")?;
+		let deleted_again = store.delete("docs", "a")?;
+
+		// -- Check
+		assert_eq!(hits.len(), 2);
+		assert_eq!(hits[0].id, "a");
+		assert_eq!(hits[0].meta, Some(json!({"file": "a.md"})));
+		assert_eq!(hits[1].id, "b");
+		assert!(hits[0].score > hits[1].score);
+		assert!(dims_res.is_err());
+		assert!(deleted);
+		assert!(!deleted_again);
+		assert_eq!(store.count("docs")?, 2);
+		assert_eq!(store.count("other")?, 1);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,331 @@
+//! The XLSX (Excel) read support (used by `aip.file.load_xlsx`).
+//!
+//! Reads the cell values (shared strings, inline strings, numbers, booleans, and the formula cached values)
+//! of the worksheets, without the styles (so, the dates are their serial numbers).
+
+use crate::{Error, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// A worksheet with its rows of cell values.
+#[derive(Debug, Clone)]
+pub struct XlsxSheet {
+	pub name: String,
+	/// The rows by row number (`rows[0]` is the row 1), and the cells by column (`Value::Null` when empty).
+	/// The empty rows are empty vecs.
+	pub rows: Vec<Vec<Value>>,
+}
+
+/// Load the worksheets (in the workbook order) for which `select(sheet_idx, sheet_name)` is true (`sheet_idx` is 0 based).
+pub fn load_xlsx(path: impl AsRef<Path>, select: impl Fn(usize, &str) -> bool) -> Result<Vec<XlsxSheet>> {
+	let path = path.as_ref();
+	let file = std::fs::File::open(path)
+		.map_err(|err| Error::custom(format!("Cannot open xlsx file '{}'. Cause: {err}", path.display())))?;
+	let mut archive = ZipArchive::new(file)
+		.map_err(|err| Error::custom(format!("Invalid xlsx file '{}'. Cause: {err}", path.display())))?;
+
+	read_xlsx(&mut archive, select)
+		.map_err(|err| Error::custom(format!("Cannot read xlsx file '{}'. {err}", path.display())))
+}
+
+fn read_xlsx<R: Read + Seek>(
+	archive: &mut ZipArchive<R>,
+	select: impl Fn(usize, &str) -> bool,
+) -> Result<Vec<XlsxSheet>> {
+	let workbook = read_zip_text(archive, "xl/workbook.xml")?.ok_or("Missing 'xl/workbook.xml'")?;
+	let rels = read_zip_text(archive, "xl/_rels/workbook.xml.rels")?.unwrap_or_default();
+	let shared_strings = match read_zip_text(archive, "xl/sharedStrings.xml")? {
+		Some(xml) => parse_shared_strings(&xml)?,
+		None => Vec::new(),
+	};
+
+	let targets = parse_rel_targets(&rels)?;
+	let mut sheets = Vec::new();
+	for (idx, (name, rel_id)) in parse_workbook_sheets(&workbook)?.into_iter().enumerate() {
+		if !select(idx, &name) {
+			continue;
+		}
+		let part = targets
+			.get(&rel_id)
+			.cloned()
+			.unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", idx + 1));
+		let xml = read_zip_text(archive, &part)?.ok_or_else(|| format!("Missing sheet part '{part}'"))?;
+		let rows = parse_sheet_rows(&xml, &shared_strings)?;
+		sheets.push(XlsxSheet { name, rows });
+	}
+
+	Ok(sheets)
+}
+
+// region:    --- Parts Parsers
+
+/// Returns the `(name, relationship_id)` of the workbook sheets
+fn parse_workbook_sheets(xml: &str) -> Result<Vec<(String, String)>> {
+	let mut reader = Reader::from_str(xml);
+	let mut sheets = Vec::new();
+	loop {
+		match reader.read_event().map_err(xml_err)? {
+			Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"sheet" => {
+				let name = get_attr(&e, b"name").unwrap_or_default();
+				// NOTE: The `r:id` prefix can vary, so match the `id` attribute with a prefix
+				let rel_id = e
+					.attributes()
+					.with_checks(false)
+					.flatten()
+					.find(|attr| attr.key.local_name().as_ref() == b"id" && attr.key.prefix().is_some())
+					.map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+					.unwrap_or_default();
+				sheets.push((name, rel_id));
+			}
+			Event::Eof => break,
+			_ => (),
+		}
+	}
+	Ok(sheets)
+}
+
+/// Returns the `relationship_id -> zip part path` map
+fn parse_rel_targets(xml: &str) -> Result<HashMap<String, String>> {
+	let mut reader = Reader::from_str(xml);
+	let mut targets = HashMap::new();
+	loop {
+		match reader.read_event().map_err(xml_err)? {
+			Event::Start(e) | Event::Empty(e) if e.local_name().as_ref() == b"Relationship" => {
+				if let (Some(id), Some(target)) = (get_attr(&e, b"Id"), get_attr(&e, b"Target")) {
+					let target = match target.strip_prefix('/') {
+						Some(absolute) => absolute.to_string(),
+						None => format!("xl/{target}"),
+					};
+					targets.insert(id, target);
+				}
+			}
+			Event::Eof => break,
+			_ => (),
+		}
+	}
+	Ok(targets)
+}
+
+/// Returns the shared strings (the rich text runs are concatenated, the phonetic runs are ignored)
+fn parse_shared_strings(xml: &str) -> Result<Vec<String>> {
+	let mut reader = Reader::from_str(xml);
+	let mut strings = Vec::new();
+	let mut text = String::new();
+	let mut in_t = false;
+	let mut in_phonetic = false;
+	loop {
+		match reader.read_event().map_err(xml_err)? {
+			Event::Start(e) => match e.local_name().as_ref() {
+				b"si" => text.clear(),
+				b"t" => in_t = !in_phonetic,
+				b"rPh" => in_phonetic = true,
+				_ => (),
+			},
+			Event::End(e) => match e.local_name().as_ref() {
+				b"si" => strings.push(std::mem::take(&mut text)),
+				b"t" => in_t = false,
+				b"rPh" => in_phonetic = false,
+				_ => (),
+			},
+			Event::Empty(e) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+			Event::Eof => break,
+			event if in_t => push_text(&event, &mut text)?,
+			_ => (),
+		}
+	}
+	Ok(strings)
+}
+
+fn parse_sheet_rows(xml: &str, shared_strings: &[String]) -> Result<Vec<Vec<Value>>> {
+	let mut reader = Reader::from_str(xml);
+	let mut rows: Vec<Vec<Value>> = Vec::new();
+	// The current row cells, and the current cell (col_idx, type, text)
+	let mut row_idx = 0;
+	let mut cells: Vec<Value> = Vec::new();
+	let mut cell: Option<(usize, Option<String>)> = None;
+	let mut text = String::new();
+	let mut in_text = false;
+
+	loop {
+		match reader.read_event().map_err(xml_err)? {
+			Event::Start(e) => match e.local_name().as_ref() {
+				b"row" => {
+					row_idx = get_attr(&e, b"r")
+						.and_then(|r| r.parse::<usize>().ok())
+						.unwrap_or(rows.len() + 1);
+					cells.clear();
+				}
+				b"c" => {
+					let col_idx = get_attr(&e, b"r").and_then(|r| col_idx_from_ref(&r)).unwrap_or(cells.len());
+					cell = Some((col_idx, get_attr(&e, b"t")));
+					text.clear();
+				}
+				// `v` is the value (or formula cached value), `t` is the inline string text
+				b"v" | b"t" => in_text = cell.is_some(),
+				_ => (),
+			},
+			Event::Empty(e) if e.local_name().as_ref() == b"row" => {
+				row_idx = get_attr(&e, b"r")
+					.and_then(|r| r.parse::<usize>().ok())
+					.unwrap_or(rows.len() + 1);
+				set_row(&mut rows, row_idx, Vec::new());
+			}
+			Event::End(e) => match e.local_name().as_ref() {
+				b"v" | b"t" => in_text = false,
+				b"c" => {
+					if let Some((col_idx, typ)) = cell.take()
+						&& !text.is_empty()
+					{
+						if cells.len() <= col_idx {
+							cells.resize(col_idx + 1, Value::Null);
+						}
+						cells[col_idx] = cell_value(typ.as_deref(), std::mem::take(&mut text), shared_strings);
+					}
+				}
+				b"row" => set_row(&mut rows, row_idx, std::mem::take(&mut cells)),
+				_ => (),
+			},
+			Event::Eof => break,
+			event if in_text => push_text(&event, &mut text)?,
+			_ => (),
+		}
+	}
+
+	Ok(rows)
+}
+
+// endregion: --- Parts Parsers
+
+// region:    --- Support
+
+fn read_zip_text<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<String>> {
+	let mut file = match archive.by_name(name) {
+		Ok(file) => file,
+		Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+		Err(err) => return Err(Error::custom(format!("Cannot read '{name}'. Cause: {err}"))),
+	};
+	let mut content = String::new();
+	file.read_to_string(&mut content)
+		.map_err(|err| Error::custom(format!("Cannot read '{name}'. Cause: {err}")))?;
+	Ok(Some(content))
+}
+
+fn cell_value(typ: Option<&str>, text: String, shared_strings: &[String]) -> Value {
+	match typ {
+		Some("s") => text
+			.trim()
+			.parse::<usize>()
+			.ok()
+			.and_then(|idx| shared_strings.get(idx))
+			.map(|s| Value::String(s.clone()))
+			.unwrap_or(Value::Null),
+		Some("b") => Value::Bool(text.trim() == "1"),
+		Some("str") | Some("inlineStr") | Some("e") | Some("d") => Value::String(text),
+		_ => match text.trim().parse::<f64>() {
+			Ok(num) if num.fract() == 0.0 && num.abs() < 9_007_199_254_740_992.0 => Value::from(num as i64),
+			Ok(num) => Value::from(num),
+			Err(_) => Value::String(text),
+		},
+	}
+}
+
+/// The 0 based column index of the cell reference (e.g., `"AB12"` -> 27)
+fn col_idx_from_ref(cell_ref: &str) -> Option<usize> {
+	let letters: Vec<u8> = cell_ref.bytes().take_while(|b| b.is_ascii_alphabetic()).collect();
+	if letters.is_empty() {
+		return None;
+	}
+	let col = letters.iter().fold(0_usize, |acc, b| {
+		acc * 26 + (b.to_ascii_uppercase() - b'A' + 1) as usize
+	});
+	Some(col - 1)
+}
+
+fn set_row(rows: &mut Vec<Vec<Value>>, row_num: usize, cells: Vec<Value>) {
+	let row_num = row_num.max(1);
+	if rows.len() < row_num {
+		rows.resize(row_num, Vec::new());
+	}
+	rows[row_num - 1] = cells;
+}
+
+fn push_text(event: &Event, text: &mut String) -> Result<()> {
+	match event {
+		Event::Text(e) => text.push_str(&e.decode().map_err(xml_err)?),
+		Event::CData(e) => text.push_str(&String::from_utf8_lossy(e)),
+		Event::GeneralRef(e) => {
+			if let Ok(Some(ch)) = e.resolve_char_ref() {
+				text.push(ch);
+			} else {
+				let name = e.decode().map_err(xml_err)?;
+				text.push_str(match name.as_ref() {
+					"quot" => "\"",
+					"amp" => "&",
+					"lt" => "<",
+					"gt" => ">",
+					"apos" => "'",
+					_ => "",
+				});
+			}
+		}
+		_ => (),
+	}
+	Ok(())
+}
+
+fn get_attr(e: &BytesStart, key: &[u8]) -> Option<String> {
+	e.attributes()
+		.with_checks(false)
+		.flatten()
+		.find(|attr| attr.key.as_ref() == key)
+		.map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+}
+
+fn xml_err(err: impl std::fmt::Display) -> Error {
+	Error::custom(format!("Invalid xlsx XML. Cause: {err}"))
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn test_support_xlsx_load_xlsx() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_path = "tests-data/sandbox-01/other/simple.xlsx";
+
+		// -- Exec
+		let sheets = load_xlsx(fx_path, |_, _| true)?;
+		let notes = load_xlsx(fx_path, |_, name| name == "Notes")?;
+
+		// -- Check
+		let names: Vec<&str> = sheets.iter().map(|s| s.name.as_str()).collect();
+		assert_eq!(names, vec!["People", "Notes"]);
+		let people = &sheets[0];
+		assert_eq!(people.rows.len(), 5);
+		assert_eq!(people.rows[0], vec![json!("Name"), json!("Age"), json!("Active")]);
+		assert_eq!(people.rows[1], vec![json!("Alice"), json!(30), json!(true)]);
+		assert_eq!(people.rows[2], vec![json!("Bob & Co"), json!(25.5), json!(false)]);
+		assert!(people.rows[3].is_empty());
+		assert_eq!(people.rows[4], vec![json!("Carl"), Value::Null, json!(true)]);
+		assert_eq!(notes.len(), 1);
+		assert_eq!(notes[0].rows[2], vec![json!("total"), json!("42")]);
+		assert_eq!(col_idx_from_ref("AB12"), Some(27));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
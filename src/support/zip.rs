@@ -13,10 +13,49 @@ use zip::{CompressionMethod, ZipArchive, ZipWriter};
 /// `dest_file` is the destination file path for the zip archive.
 ///
 /// This function recursively adds files and subdirectories from `src_dir` to the zip archive.
+///
+/// The archive is deterministic (entries sorted by name, fixed timestamps and permissions),
+/// so zipping the same content twice gives the same bytes.
+#[allow(unused)]
 pub fn zip_dir(src_dir: impl AsRef<SPath>, dest_file: impl AsRef<SPath>) -> Result<()> {
 	zip_dir_with_globs(src_dir, dest_file, None::<&[String]>)
 }
 
+/// Same as `zip_dir`, but skips the `exclude_names` files (relative archive paths, e.g., `.aipack-meta.toml`).
+pub fn zip_dir_excluding(
+	src_dir: impl AsRef<SPath>,
+	dest_file: impl AsRef<SPath>,
+	exclude_names: &[&str],
+) -> Result<()> {
+	zip_dir_impl(src_dir.as_ref(), dest_file.as_ref(), |name| {
+		Ok(!exclude_names.contains(&name))
+	})
+}
+
+/// Appends a file entry (archive path, content) at the end of the `zip_file` archive
+/// (with the same fixed timestamp and permissions as `zip_dir`).
+pub fn append_file(zip_file: impl AsRef<SPath>, name: &str, content: &[u8]) -> Result<()> {
+	let zip_file = zip_file.as_ref();
+	let file = File::options().read(true).write(true).open(zip_file.as_std_path())?;
+	let mut zip = ZipWriter::new_append(file).map_err(|err| Error::Zip {
+		zip_file: zip_file.name().to_string(),
+		cause: format!("Fail to open archive for append. Cause {err}"),
+	})?;
+
+	zip.start_file(name, deterministic_options().unix_permissions(0o644))
+		.map_err(|err| Error::Zip {
+			zip_file: zip_file.name().to_string(),
+			cause: format!("Fail zip.start_file '{name}'. Cause {err}"),
+		})?;
+	io::Write::write_all(&mut zip, content)?;
+
+	zip.finish().map_err(|err| Error::Zip {
+		zip_file: zip_file.name().to_string(),
+		cause: format!("Fail zip.finish. Cause {err}"),
+	})?;
+	Ok(())
+}
+
 /// Creates a zip archive from the directory `src_dir` and writes it to `dest_file`,
 /// optionally filtering source files by relative archive-style glob paths.
 ///
@@ -28,9 +67,19 @@ pub fn zip_dir_with_globs(
 	dest_file: impl AsRef<SPath>,
 	globs: Option<impl AsRef<[String]>>,
 ) -> Result<()> {
-	let src_dir = src_dir.as_ref();
-	let dest_file = dest_file.as_ref();
+	zip_dir_impl(src_dir.as_ref(), dest_file.as_ref(), |name| {
+		matches_zip_globs(name, globs.as_ref())
+	})
+}
+
+/// Deflated (most standard), with a fixed timestamp, so the archive only depends on the content.
+fn deterministic_options() -> SimpleFileOptions {
+	SimpleFileOptions::default()
+		.compression_method(CompressionMethod::Deflated)
+		.last_modified_time(zip::DateTime::default())
+}
 
+fn zip_dir_impl(src_dir: &SPath, dest_file: &SPath, include_file: impl Fn(&str) -> Result<bool>) -> Result<()> {
 	if !src_dir.exists() {
 		return Err(Error::ZipFail {
 			zip_dir: src_dir.to_string(),
@@ -48,11 +97,12 @@ pub fn zip_dir_with_globs(
 	let file = File::create(dest_file)?;
 	let mut zip = ZipWriter::new(file);
 
-	// Set default options with deflated compression (most standard).
-	let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+	// NOTE: Fixed permissions as well, so the archive does not depend on the umask.
+	let dir_options = deterministic_options().unix_permissions(0o755);
+	let file_options = deterministic_options().unix_permissions(0o644);
 
-	// Walk through the directory.
-	for entry in WalkDir::new(src_dir) {
+	// Walk through the directory (sorted, for a stable entry order).
+	for entry in WalkDir::new(src_dir).sort_by_file_name() {
 		let entry = entry.map_err(|err| Error::ZipFail {
 			zip_dir: src_dir.to_string(),
 			cause: format!("Fail to zip directory. Error on entry. Cause {err}"),
@@ -81,17 +131,17 @@ pub fn zip_dir_with_globs(
 			} else {
 				format!("{name}/")
 			};
-			zip.add_directory(&dir_name, options).map_err(|err| Error::ZipFail {
+			zip.add_directory(&dir_name, dir_options).map_err(|err| Error::ZipFail {
 				zip_dir: src_dir.to_string(),
 				cause: format!("Fail add directory '{dir_name}'. Cause {err}"),
 			})?;
 		} else {
-			if !matches_zip_globs(&name, globs.as_ref())? {
+			if !include_file(&name)? {
 				continue;
 			}
 
 			// Add file entry to zip archive.
-			zip.start_file(&name, options).map_err(|err| Error::ZipFail {
+			zip.start_file(&name, file_options).map_err(|err| Error::ZipFail {
 				zip_dir: src_dir.to_string(),
 				cause: format!("Fail zip.start_file '{name}'. Cause {err}"),
 			})?;
@@ -255,6 +305,38 @@ pub fn extract_text_content(src_zip_path: impl AsRef<SPath>, content_path: &str)
 	Ok(content)
 }
 
+/// Returns the (archive path, content) of the file entries (the directory entries are skipped), in archive order.
+pub fn read_file_entries(src_zip_path: impl AsRef<SPath>) -> Result<Vec<(String, Vec<u8>)>> {
+	let src_zip_path = src_zip_path.as_ref();
+	let file = File::open(src_zip_path)?;
+
+	let mut archive = ZipArchive::new(file).map_err(|err| Error::Zip {
+		zip_file: src_zip_path.name().to_string(),
+		cause: err.to_string(),
+	})?;
+
+	let mut entries = Vec::with_capacity(archive.len());
+	for i in 0..archive.len() {
+		let mut file = archive.by_index(i).map_err(|err| Error::Zip {
+			zip_file: src_zip_path.name().to_string(),
+			cause: format!("Fail to get item by_index {i}.\nCause: {err}"),
+		})?;
+		if file.is_dir() {
+			continue;
+		}
+		let entry_name = file.name().to_string();
+		let mut data: Vec<u8> = Vec::new();
+		file.read_to_end(&mut data).map_err(|err| Error::ZipContent {
+			zip_file: src_zip_path.name().to_string(),
+			content_path: entry_name.clone(),
+			cause: format!("Fail to read content. Cause: {err}"),
+		})?;
+		entries.push((entry_name, data));
+	}
+
+	Ok(entries)
+}
+
 pub fn list_entries_with_globs(
 	src_zip_path: impl AsRef<SPath>,
 	globs: Option<impl AsRef<[String]>>,
@@ -373,6 +455,35 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_support_zip_dir_is_deterministic() -> Result<()> {
+		// -- Setup & Fixtures
+		let root = gen_test_dir_path();
+		let src_dir = root.join("source");
+		std::fs::create_dir_all(src_dir.join("b_dir").as_std_path())?;
+		std::fs::write(src_dir.join("z.txt").as_std_path(), "z file")?;
+		std::fs::write(src_dir.join("a.txt").as_std_path(), "a file")?;
+		std::fs::write(src_dir.join("b_dir/c.txt").as_std_path(), "c file")?;
+		let zip_1 = root.join("archive-1.zip");
+		let zip_2 = root.join("archive-2.zip");
+
+		// -- Exec
+		zip_dir(&src_dir, &zip_1)?;
+		// NOTE: Touch a file, so only its modified time changes
+		std::fs::write(src_dir.join("a.txt").as_std_path(), "a file")?;
+		zip_dir(&src_dir, &zip_2)?;
+
+		// -- Check
+		assert_eq!(std::fs::read(zip_1.as_std_path())?, std::fs::read(zip_2.as_std_path())?);
+		let entries = list_entries_with_globs(&zip_1, None::<&[String]>)?;
+		assert_eq!(entries, vec!["a.txt", "b_dir/", "b_dir/c.txt", "z.txt"]);
+
+		// -- Cleanup
+		let _ = remove_test_dir(&root);
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_matches_zip_globs_uses_archive_style_paths() -> Result<()> {
 		// -- Setup & Fixtures
@@ -1,6 +1,7 @@
 use super::event::{AppActionEvent, AppEvent, ScrollDir};
 use super::{AppTx, ExitTx};
 use crate::Result;
+use crate::exec::cli::InfoArgs;
 use crate::exec::{ExecActionEvent, ExecutorTx};
 use crate::hub::HubEvent;
 use crate::model::{LogBmc, LogForCreate, LogKind, ModelEvent, ModelManager};
@@ -105,10 +106,33 @@ async fn handle_action_event(
 			//
 			executor_tx.send(ExecActionEvent::Redo).await;
 		}
+		AppActionEvent::RedoTasks { task_idxs, instruction } => {
+			executor_tx
+				.send(ExecActionEvent::RedoTasks {
+					task_idxs: task_idxs.clone(),
+					instruction: instruction.clone(),
+				})
+				.await;
+		}
+		AppActionEvent::ChatFollowUp(message) => {
+			executor_tx.send(ExecActionEvent::ChatFollowUp(message.clone())).await;
+		}
+		AppActionEvent::CancelTasks(task_ids) => {
+			executor_tx.send(ExecActionEvent::CancelTasks(task_ids.clone())).await;
+		}
+		AppActionEvent::ExportTasksOutputs(task_ids) => {
+			executor_tx.send(ExecActionEvent::ExportTasksOutputs(task_ids.clone())).await;
+		}
 		AppActionEvent::CancelRun => {
 			//
 			executor_tx.send(ExecActionEvent::CancelRun).await;
 		}
+		AppActionEvent::PauseRun => {
+			executor_tx.send(ExecActionEvent::PauseRun).await;
+		}
+		AppActionEvent::ResumeRun => {
+			executor_tx.send(ExecActionEvent::ResumeRun).await;
+		}
 		AppActionEvent::Scroll(_) => (),
 		AppActionEvent::ScrollPage(_) => (),
 		AppActionEvent::ScrollToEnd(_) => (),
@@ -119,7 +143,14 @@ async fn handle_action_event(
 			executor_tx.send(ExecActionEvent::WorkCancel(*id)).await;
 		}
 		AppActionEvent::Run(run_args) => {
-			executor_tx.send(ExecActionEvent::Run(run_args.clone())).await;
+			executor_tx.send(ExecActionEvent::Run(run_args.as_ref().clone())).await;
+		}
+		AppActionEvent::PackInfo(pack_ref) => {
+			let info_args = InfoArgs {
+				pack_ref: pack_ref.clone(),
+				json: false,
+			};
+			executor_tx.send(ExecActionEvent::CmdInfo(info_args)).await;
 		}
 	}
 	Ok(())
@@ -1,13 +1,16 @@
 use super::{AppStateCore, SysState};
 use crate::Result;
-use crate::model::{Id, ModelEvent, ModelManager, Task};
+use crate::dir_context::{AipackPaths, DirContext};
+use crate::model::{Id, ModelEvent, ModelManager, Task, new_history_store};
 use crate::support::time::now_micro;
 use crate::tui::core::event::{AppActionEvent, LastAppEvent};
 use crate::tui::core::{
-	AppStage, ConfigTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab, RunTasksInfo, ScrollZones,
+	AppStage, ConfigTab, MouseEvt, OverviewTasksFilter, OverviewTasksMode, OverviewTasksSort, RunItemStore, RunTab,
+	RunTasksInfo, ScrollZones, UserPrompt,
 };
-use crate::tui::view::PopupView;
+use crate::tui::view::{PopupMode, PopupView};
 use crossterm::event::MouseEvent;
+use std::collections::{HashSet, VecDeque};
 
 /// Public wrapper around AppStateCore.
 ///
@@ -21,6 +24,22 @@ impl AppState {
 	pub fn new(mm: ModelManager, last_app_event: LastAppEvent) -> Result<Self> {
 		let sys_state = SysState::new()?;
 
+		// NOTE: An invalid `[tui.quick_actions]` should not prevent the TUI to start, so, shown as a popup.
+		let (quick_actions, popup) = match AppState::load_quick_actions() {
+			Ok(quick_actions) => (quick_actions, None),
+			Err(err) => (
+				Vec::new(),
+				Some(PopupView {
+					content: format!(
+						"Quick actions config error
+{err}"
+					),
+					mode: PopupMode::User,
+					is_err: true,
+				}),
+			),
+		};
+
 		let inner = AppStateCore {
 			// -- Debug
 			debug_clr: 0,
@@ -39,22 +58,42 @@ impl AppState {
 			// -- RunsView
 			run_idx: None,
 			run_id: None,
+			runs_nav_expanded_ids: HashSet::new(),
+			runs_nav_collapsed_ids: HashSet::new(),
 
 			running_tick_start: None,
 
+			// -- RunSplitView
+			split_run_id: None,
+
 			// -- RunMainView
 			run_tab: RunTab::Tasks, // Tasks tab by default
+			runs_paused: false,
 
 			// -- RunOverview
 			overview_tasks_mode: OverviewTasksMode::Auto,
+			overview_tasks_sort: None,
+			overview_tasks_filter: OverviewTasksFilter::All,
 
 			// -- RunTasksView
 			task_idx: None,
+			task_prompt_edit: None,
+			selected_tasks_run_id: None,
+			selected_task_idxs: Default::default(),
+
+			// -- RunChatView
+			chat_input: None,
+
+			// -- PackInfoView
+			pack_info: None,
 
 			// -- Data
 			run_item_store: RunItemStore::default(),
 			tasks: Vec::new(),
 			run_tasks_info: None,
+			split_tasks: Vec::new(),
+			conv_msgs: Vec::new(),
+			conv_msgs_run_id: None,
 
 			// -- Stage & Work
 			stage: AppStage::Normal,
@@ -64,12 +103,17 @@ impl AppState {
 
 			// -- System & Event
 			mm,
+			history_mm: None,
 			last_app_event,
 
 			// -- Action
 			do_redraw: false,
 			do_action: None,
 			to_send_action: None,
+			pending_actions: VecDeque::new(),
+
+			// -- Quick Actions
+			quick_actions,
 
 			// -- SysState
 			time: now_micro(), // the current time
@@ -83,10 +127,13 @@ impl AppState {
 			clipboard: None,
 
 			// -- Popup
-			popup: None,
+			popup,
 			popup_start_us: None,
 
 			installed_start_us: None,
+
+			// -- User Prompts
+			user_prompts: VecDeque::new(),
 		};
 
 		Ok(Self { core: inner })
@@ -105,6 +152,50 @@ impl AppState {
 	}
 }
 
+/// Constructor & event processing for test
+#[cfg(test)]
+impl AppState {
+	/// A new app state processed once, as the TUI loop does at start (e.g., to load the runs of the `mm`).
+	pub(in crate::tui::core) fn new_for_test(mm: ModelManager) -> Result<Self> {
+		let mut state = AppState::new(mm, LastAppEvent::default())?;
+		// NOTE: The eventual quick actions config popup is not relevant for the tests
+		state.clear_popup();
+		super::process_app_state(&mut state, super::ProcessAppStateOpts::default());
+		Ok(state)
+	}
+
+	/// Process the app event as the TUI loop does (with the eventual redraw event),
+	/// and return the last action event to send (if any).
+	pub(in crate::tui::core) fn process_event_for_test(
+		&mut self,
+		app_event: impl Into<crate::tui::core::event::AppEvent>,
+	) -> Option<AppActionEvent> {
+		use crate::tui::core::event::AppEvent;
+
+		self.core.last_app_event = app_event.into().into();
+		super::process_app_state(self, super::ProcessAppStateOpts::default());
+		let mut action_event = self.take_action_event_to_send();
+
+		if self.should_redraw() {
+			self.core.do_redraw = false;
+			self.core.last_app_event = AppEvent::DoRedraw.into();
+			super::process_app_state(self, super::ProcessAppStateOpts::default());
+			action_event = self.take_action_event_to_send().or(action_event);
+		}
+
+		action_event
+	}
+
+	/// Process the key (without modifiers) as the TUI loop does (see `process_event_for_test`).
+	pub(in crate::tui::core) fn process_key_for_test(
+		&mut self,
+		code: crossterm::event::KeyCode,
+	) -> Option<AppActionEvent> {
+		let key_event = crossterm::event::KeyEvent::new(code, crossterm::event::KeyModifiers::NONE);
+		self.process_event_for_test(crossterm::event::Event::Key(key_event))
+	}
+}
+
 /// Debug
 impl AppState {
 	pub fn debug_clr(&self) -> u8 {
@@ -164,6 +255,10 @@ impl AppState {
 	pub fn show_runs(&self) -> bool {
 		self.core.show_runs
 	}
+
+	pub fn runs_paused(&self) -> bool {
+		self.core.runs_paused
+	}
 }
 
 /// OverviewView
@@ -171,6 +266,14 @@ impl AppState {
 	pub fn overview_tasks_mode(&self) -> OverviewTasksMode {
 		self.core.overview_tasks_mode
 	}
+
+	pub fn overview_tasks_sort(&self) -> Option<&OverviewTasksSort> {
+		self.core.overview_tasks_sort.as_ref()
+	}
+
+	pub fn overview_tasks_filter(&self) -> OverviewTasksFilter {
+		self.core.overview_tasks_filter
+	}
 }
 
 /// RunTasksView
@@ -202,10 +305,29 @@ impl AppState {
 
 /// System & Event
 impl AppState {
+	/// The model manager of the displayed runs (the runs history db when in history mode)
 	pub fn mm(&self) -> &ModelManager {
+		self.core.history_mm.as_ref().unwrap_or(&self.core.mm)
+	}
+
+	/// The model manager of the current session (for the events and works)
+	pub fn live_mm(&self) -> &ModelManager {
 		&self.core.mm
 	}
 
+	pub fn is_history_mode(&self) -> bool {
+		self.core.history_mm.is_some()
+	}
+
+	/// Open the runs history db (`.aipack/.session/_history.db`, or the config `[store] history_db`)
+	pub(in crate::tui::core) fn open_history_mm() -> Result<ModelManager> {
+		let dir_context = DirContext::new(AipackPaths::new()?)?;
+		let history_db = dir_context
+			.history_db()?
+			.ok_or("No workspace `.aipack/` for the runs history")?;
+		Ok(new_history_store(&history_db, &dir_context.history_scope()).open()?)
+	}
+
 	pub fn last_app_event(&self) -> &LastAppEvent {
 		&self.core.last_app_event
 	}
@@ -238,6 +360,10 @@ impl AppState {
 
 /// Popup
 impl AppState {
+	pub fn user_prompt(&self) -> Option<&UserPrompt> {
+		self.core.user_prompts.front()
+	}
+
 	pub fn popup(&self) -> Option<&PopupView> {
 		self.core.popup.as_ref()
 	}
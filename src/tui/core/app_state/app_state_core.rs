@@ -1,13 +1,15 @@
 use super::SysState;
-use crate::model::{ErrRec, Id, ModelManager, Task};
+use crate::exec::PackInfo;
+use crate::model::{ConvMsg, ErrRec, Id, ModelManager, Task};
 use crate::tui::core::event::{AppActionEvent, LastAppEvent};
 use crate::tui::core::{
-	AppStage, ConfigTab, MouseEvt, OverviewTasksMode, RunItemStore, RunTab, RunTasksInfo, ScrollIden, ScrollZone,
-	ScrollZones, UiAction,
+	AppStage, ConfigTab, MouseEvt, OverviewTasksFilter, OverviewTasksMode, OverviewTasksSort, QuickAction,
+	RunItemStore, RunTab, RunTasksInfo, ScrollIden, ScrollZone, ScrollZones, TaskPromptEdit, UiAction, UserPrompt,
 };
 use crate::tui::view::PopupView;
 use arboard::Clipboard;
 use ratatui::layout::Position;
+use std::collections::{BTreeSet, HashSet, VecDeque};
 
 /// Inner representation of the application state.
 ///
@@ -40,32 +42,72 @@ pub(in crate::tui::core) struct AppStateCore {
 	// -- RunsView
 	pub run_idx: Option<i32>,
 	pub run_id: Option<Id>,
+	/// The runs nav tree runs expanded/collapsed by the user (`d`/`a` keys),
+	/// otherwise, only the tree of the current run is expanded
+	pub runs_nav_expanded_ids: HashSet<Id>,
+	pub runs_nav_collapsed_ids: HashSet<Id>,
+
+	// -- RunSplitView
+	/// The run pinned on the left side of the split view (the selected run is on the right)
+	pub split_run_id: Option<Id>,
 
 	// -- RunMainView
 	pub run_tab: RunTab,
+	/// If the runs were paused from the TUI (`p` key)
+	pub runs_paused: bool,
 
 	// -- RunOverview
 	pub overview_tasks_mode: OverviewTasksMode,
+	/// The tasks overview sort (`o` key), none for the task order (kept for the session)
+	pub overview_tasks_sort: Option<OverviewTasksSort>,
+	/// The tasks overview quick filter (`f` key, kept for the session)
+	pub overview_tasks_filter: OverviewTasksFilter,
 
 	// -- RunTasksView
 	pub task_idx: Option<i32>,
+	/// The last edited task prompt (`E` key), used by the task redo (`R` key)
+	pub task_prompt_edit: Option<TaskPromptEdit>,
+	/// The selected task idxs (`space` key) of this run, for the redo of the selected tasks (`R` key)
+	pub selected_tasks_run_id: Option<Id>,
+	pub selected_task_idxs: BTreeSet<usize>,
+
+	// -- RunChatView
+	/// The follow-up message being written (`Enter` key in the chat tab), None when the input is not active
+	pub chat_input: Option<String>,
+
+	// -- PackInfoView
+	/// The info of the current run agent pack (`I` key), set when received from the executor
+	pub pack_info: Option<Box<PackInfo>>,
 
 	// -- Data
 	pub run_item_store: RunItemStore,
 	pub tasks: Vec<Task>,
 	pub run_tasks_info: Option<RunTasksInfo>,
+	/// The tasks of the pinned split run
+	pub split_tasks: Vec<Task>,
+	/// The conversation of the current run (chat tab)
+	pub conv_msgs: Vec<ConvMsg>,
+	pub conv_msgs_run_id: Option<Id>,
 
 	/// Time of when the current run started
 	pub running_tick_start: Option<i64>,
 
 	// -- System & Event
 	pub mm: ModelManager,
+	/// The runs history db, when in history mode (`h` key)
+	pub history_mm: Option<ModelManager>,
 	pub last_app_event: LastAppEvent,
 
 	// -- Action State
 	pub do_redraw: bool, // to move to Action
 	pub do_action: Option<UiAction>,
 	pub to_send_action: Option<AppActionEvent>,
+	/// The next actions of a quick action sequence (one per cycle)
+	pub pending_actions: VecDeque<UiAction>,
+
+	// -- Quick Actions
+	/// The user quick actions from the config `[tui.quick_actions]`
+	pub quick_actions: Vec<QuickAction>,
 
 	// -- SysState
 	pub time: i64,
@@ -83,6 +125,10 @@ pub(in crate::tui::core) struct AppStateCore {
 	pub popup_start_us: Option<i64>,
 
 	pub installed_start_us: Option<i64>,
+
+	// -- User Prompts
+	/// The pending user prompts (e.g., `aip.flow.ask_user`), the first one is shown
+	pub user_prompts: VecDeque<UserPrompt>,
 }
 
 impl AppStateCore {
@@ -102,6 +148,18 @@ impl AppStateCore {
 		self.overview_tasks_mode = self.overview_tasks_mode.next(self.tasks.len());
 		self.overview_tasks_mode
 	}
+
+	/// Sort by the next built-in sort or display column of the tasks (back to the task order after the last one)
+	pub fn next_overview_tasks_sort(&mut self) -> Option<&OverviewTasksSort> {
+		let col_names = Task::ui_col_names(&self.tasks);
+		self.overview_tasks_sort = OverviewTasksSort::next(self.overview_tasks_sort.as_ref(), col_names);
+		self.overview_tasks_sort.as_ref()
+	}
+
+	pub fn next_overview_tasks_filter(&mut self) -> OverviewTasksFilter {
+		self.overview_tasks_filter = self.overview_tasks_filter.next();
+		self.overview_tasks_filter
+	}
 }
 
 /// Scroll Inner impl
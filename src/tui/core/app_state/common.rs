@@ -33,4 +33,6 @@ pub enum AppStage {
 	Installed,
 	PromptInstall(Id),
 	Config(ConfigTab),
+	/// The pack detail view of the current run agent pack (`I` key)
+	PackInfo,
 }
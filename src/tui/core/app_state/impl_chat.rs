@@ -0,0 +1,183 @@
+use crate::Result;
+use crate::model::{ConvMsg, Task};
+use crate::tui::core::event::AppActionEvent;
+use crate::tui::core::{AppState, UiAction};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Chat (the conversation of the current run, and the follow-up message input of the chat tab)
+impl AppState {
+	pub fn conv_msgs(&self) -> &[ConvMsg] {
+		&self.core.conv_msgs
+	}
+
+	/// The follow-up message being written (None when the input is not active)
+	pub fn chat_input(&self) -> Option<&str> {
+		self.core.chat_input.as_deref()
+	}
+
+	/// The task of the current run streaming its AI response (a chat follow-up), until its AI stage ends
+	pub fn chat_streaming_task(&self) -> Option<&Task> {
+		self.tasks()
+			.iter()
+			.rev()
+			.find(|task| task.ai_stream.is_some() && task.ai_end.is_none() && !task.is_ended())
+	}
+
+	/// Process the key for the chat input, and return true if the key was for the input.
+	/// - `Enter` activates the input, and then sends the message (`SendChatMessage` action).
+	/// - `Esc` leaves the input (the message is dropped).
+	pub(in crate::tui::core) fn process_chat_key(&mut self, key: &KeyEvent) -> bool {
+		let Some(input) = self.core.chat_input.as_mut() else {
+			if key.code == KeyCode::Enter {
+				self.core.chat_input = Some(String::new());
+				return true;
+			}
+			return false;
+		};
+
+		match key.code {
+			KeyCode::Esc => self.core.chat_input = None,
+			KeyCode::Enter => {
+				let message = input.trim().to_string();
+				self.core.chat_input = None;
+				if !message.is_empty() {
+					self.set_action(UiAction::SendChatMessage(message));
+				}
+			}
+			KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+			KeyCode::Backspace => {
+				input.pop();
+			}
+			_ => (),
+		}
+
+		true
+	}
+
+	/// The follow-up action event of the message.
+	/// NOTE: Only the last live run can be followed up (the executor keeps only this run redo context).
+	pub(in crate::tui::core) fn chat_follow_up_action_event(&self, message: String) -> Result<AppActionEvent> {
+		if self.is_history_mode() {
+			return Err("Cannot follow up a run of the runs history\nPress 'h' to go back to the live runs".into());
+		}
+
+		let last_root_run_id = self.run_items().iter().find(|r| r.is_root()).map(|r| r.id());
+		if self.current_run_item().map(|r| r.id()) != last_root_run_id {
+			return Err("Only the last run can be followed up".into());
+		}
+
+		if self.run_items().iter().any(|r| r.is_running()) {
+			return Err("Agent currently running, wait until done".into());
+		}
+
+		Ok(AppActionEvent::ChatFollowUp(message))
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{assert_contains, create_run};
+	use crate::model::{EpochUs, ModelManager, RunBmc, RunForUpdate};
+	use crate::support::time::now_micro;
+	use crate::tui::core::event::AppActionEvent;
+	use crate::tui::core::{AppState, RunTab};
+	use crossterm::event::KeyCode;
+
+	#[tokio::test]
+	async fn test_tui_app_state_chat_submit_message() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = seed_ended_runs(&["agent-chat"]).await?;
+		let mut state = AppState::new_for_test(mm)?;
+		state.set_run_tab(RunTab::Chat);
+
+		// -- Exec
+		state.process_key_for_test(KeyCode::Enter);
+		for c in "hi q".chars() {
+			state.process_key_for_test(KeyCode::Char(c));
+		}
+		let input_before_send = state.chat_input().map(|s| s.to_string());
+		let action_event = state.process_key_for_test(KeyCode::Enter);
+
+		// -- Check
+		// NOTE: The 'q' is for the input (not the quit shortcut)
+		assert_eq!(input_before_send.as_deref(), Some("hi q"));
+		assert!(state.chat_input().is_none());
+		match action_event {
+			Some(AppActionEvent::ChatFollowUp(message)) => assert_eq!(message, "hi q"),
+			other => return Err(format!("Should be a ChatFollowUp, but was {other:?}").into()),
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tui_app_state_chat_submit_esc_and_empty() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = seed_ended_runs(&["agent-chat"]).await?;
+		let mut state = AppState::new_for_test(mm)?;
+		state.set_run_tab(RunTab::Chat);
+
+		// -- Exec & Check - Esc drops the message
+		state.process_key_for_test(KeyCode::Enter);
+		state.process_key_for_test(KeyCode::Char('a'));
+		let action_event = state.process_key_for_test(KeyCode::Esc);
+		assert!(action_event.is_none());
+		assert!(state.chat_input().is_none());
+
+		// -- Exec & Check - An empty message is not sent
+		state.process_key_for_test(KeyCode::Enter);
+		state.process_key_for_test(KeyCode::Char(' '));
+		let action_event = state.process_key_for_test(KeyCode::Enter);
+		assert!(action_event.is_none());
+		assert!(state.chat_input().is_none());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tui_app_state_chat_submit_err_not_last_run() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = seed_ended_runs(&["agent-chat-1", "agent-chat-2"]).await?;
+		let mut state = AppState::new_for_test(mm)?;
+		state.set_run_tab(RunTab::Chat);
+		// select the older run
+		state.process_key_for_test(KeyCode::Char('s'));
+
+		// -- Exec
+		state.process_key_for_test(KeyCode::Enter);
+		state.process_key_for_test(KeyCode::Char('x'));
+		let action_event = state.process_key_for_test(KeyCode::Enter);
+
+		// -- Check
+		assert!(action_event.is_none());
+		let popup = state.popup().ok_or("Should have an error popup")?;
+		assert!(popup.is_err);
+		assert_contains(&popup.content, "Only the last run can be followed up");
+
+		Ok(())
+	}
+
+	// region:    --- Support
+
+	/// Returns the mm with the ended runs of these agents (the last one is the latest run).
+	async fn seed_ended_runs(agent_names: &[&str]) -> Result<ModelManager> {
+		let mm = ModelManager::new().await?;
+		for agent_name in agent_names {
+			let run_id = create_run(&mm, agent_name)?;
+			let run_u = RunForUpdate {
+				end: Some(EpochUs::from(now_micro())),
+				..Default::default()
+			};
+			RunBmc::update(&mm, run_id, run_u)?;
+		}
+		Ok(mm)
+	}
+
+	// endregion: --- Support
+}
+
+// endregion: --- Tests
@@ -23,30 +23,35 @@ impl AppState {
 	}
 
 	pub fn current_run_cost_fmt(&self) -> String {
-		let cost = if let Some(run_item) = self.current_run_item() {
-			// NOTE: Not very elegant, but works.
-			let mut cost = run_item.run().total_cost;
-			if run_item.has_children() {
-				let children = self.all_run_children(run_item);
-				let addl_cost: f64 = children.iter().filter_map(|r| r.run().total_cost).sum();
-				if let Some(c) = cost.as_mut() {
-					*c += addl_cost;
-					cost
-				} else if addl_cost != 0. {
-					Some(addl_cost)
-				} else {
-					cost
-				}
-			} else {
-				cost
-			}
-		} else {
-			None
-		};
+		// NOTE: With the costs of the sub agent runs
+		let cost = self.current_run_item().and_then(|run_item| run_item.rolled_up_cost());
 
 		support::ui_fmt_cost(cost)
 	}
 
+	/// Returns the resource usage of the current run (e.g., `cpu 1.2s · mem 120 MB · r 3 KB · w 20 KB`),
+	/// or `None` when nothing was recorded (no `aip.cmd.exec` or `aip.file...` calls).
+	pub fn current_run_res_usage_fmt(&self) -> Option<String> {
+		let run = self.current_run_item()?.run();
+		let size_fmt = |bytes: i64| text::format_pretty_size(bytes.max(0) as u64, None).trim().to_string();
+
+		let mut parts: Vec<String> = Vec::new();
+		if let Some(cpu_ms) = run.res_cpu_ms.filter(|v| *v > 0) {
+			parts.push(format!("cpu {}", format_duration_us(cpu_ms * 1000)));
+		}
+		if let Some(peak_mem) = run.res_peak_mem.filter(|v| *v > 0) {
+			parts.push(format!("mem {}", size_fmt(peak_mem)));
+		}
+		if let Some(read_bytes) = run.res_read_bytes.filter(|v| *v > 0) {
+			parts.push(format!("r {}", size_fmt(read_bytes)));
+		}
+		if let Some(write_bytes) = run.res_write_bytes.filter(|v| *v > 0) {
+			parts.push(format!("w {}", size_fmt(write_bytes)));
+		}
+
+		(!parts.is_empty()).then(|| parts.join(" · "))
+	}
+
 	pub fn current_run_concurrency_txt(&self) -> String {
 		if let Some(run_item) = self.current_run_item()
 			&& let Some(concurrency) = run_item.run().concurrency
@@ -0,0 +1,124 @@
+use crate::Result;
+use crate::exec::PackInfo;
+use crate::tui::core::event::AppActionEvent;
+use crate::tui::core::{AppStage, AppState, ScrollIden};
+use crate::types::PackRef;
+
+/// Pack Info (the detail view of the current run agent pack, `I` key)
+impl AppState {
+	pub fn pack_info(&self) -> Option<&PackInfo> {
+		self.core.pack_info.as_deref()
+	}
+
+	/// The action event to get the info of the current run agent pack (e.g., `demo@proof` for `demo@proof/sub`).
+	pub(in crate::tui::core) fn pack_info_action_event(&self) -> Result<AppActionEvent> {
+		let run_item = self.current_run_item().ok_or("No run selected")?;
+		let agent_name = run_item.run().agent_name.as_deref().unwrap_or_default();
+
+		let pack_ref = agent_name
+			.contains('@')
+			.then(|| agent_name.parse::<PackRef>().ok())
+			.flatten()
+			.ok_or_else(|| format!("The run agent '{agent_name}' is not from a pack"))?;
+
+		Ok(AppActionEvent::PackInfo(format!(
+			"{}@{}",
+			pack_ref.namespace, pack_ref.name
+		)))
+	}
+
+	/// Show the received pack info in the pack detail view (from the top).
+	pub(in crate::tui::core) fn open_pack_info(&mut self, pack_info: PackInfo) {
+		self.core.pack_info = Some(Box::new(pack_info));
+		self.set_scroll(ScrollIden::PackInfoContent, 0);
+		self.set_stage(AppStage::PackInfo);
+	}
+
+	pub(in crate::tui::core) fn close_pack_info(&mut self) {
+		self.core.pack_info = None;
+		self.set_stage(AppStage::Normal);
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::create_run;
+	use crate::exec::{PackInfo, PackStatus};
+	use crate::hub::HubEvent;
+	use crate::model::ModelManager;
+	use crate::tui::core::event::{AppActionEvent, ScrollDir};
+	use crate::tui::core::{AppStage, AppState, ScrollIden};
+	use crate::tui_v1::PrintEvent;
+	use crossterm::event::KeyCode;
+	use std::sync::Arc;
+
+	#[tokio::test]
+	async fn test_tui_app_state_pack_info_show_and_close() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		create_run(&mm, "demo@proof/sub-agent")?;
+		let mut state = AppState::new_for_test(mm)?;
+		let pack_info = PackInfo {
+			pack_ref: "demo@proof".to_string(),
+			status: PackStatus::Installed,
+			path: "some/path/demo/proof".to_string(),
+			pack_toml: Default::default(),
+			readme: Some("# Demo Proof".to_string()),
+			changelog: None,
+			agents: Vec::new(),
+		};
+
+		// -- Exec & Check - Request the info of the run agent pack
+		let action_event = state.process_key_for_test(KeyCode::Char('I'));
+		let Some(AppActionEvent::PackInfo(pack_ref)) = action_event else {
+			return Err(format!("Should be a PackInfo action event, but was: {action_event:?}").into());
+		};
+		assert_eq!(pack_ref, "demo@proof");
+		assert_eq!(state.stage(), AppStage::Normal);
+
+		// -- Exec & Check - Receive the pack info (from the executor)
+		let print_event = PrintEvent::PackInfo(Box::new(pack_info));
+		state.process_event_for_test(HubEvent::Print(Arc::new(print_event)));
+		assert_eq!(state.stage(), AppStage::PackInfo);
+		assert_eq!(
+			state.pack_info().and_then(|p| p.readme.as_deref()),
+			Some("# Demo Proof")
+		);
+
+		// -- Exec & Check - The key scroll goes to the pack info content
+		state.process_event_for_test(AppActionEvent::Scroll(ScrollDir::Down));
+		assert_eq!(state.get_scroll(ScrollIden::PackInfoContent), 1);
+
+		// -- Exec & Check - Close
+		state.process_key_for_test(KeyCode::Esc);
+		assert_eq!(state.stage(), AppStage::Normal);
+		assert!(state.pack_info().is_none());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tui_app_state_pack_info_not_pack_agent() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		create_run(&mm, "my-agent.aip")?;
+		let mut state = AppState::new_for_test(mm)?;
+
+		// -- Exec
+		let action_event = state.process_key_for_test(KeyCode::Char('I'));
+
+		// -- Check
+		assert!(action_event.is_none(), "Should not request the pack info");
+		let popup = state.popup().ok_or("Should have an error popup")?;
+		assert!(popup.is_err);
+		assert!(popup.content.contains("is not from a pack"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,105 @@
+use crate::Result;
+use crate::dir_context::{AipackPaths, DirContext};
+use crate::exec::cli::RunArgs;
+use crate::model::TaskBmc;
+use crate::tui::core::event::AppActionEvent;
+use crate::tui::core::{AppState, QuickAction, QuickActionTarget, QuickStep, UiAction};
+use clap::Parser as _;
+
+/// Loader
+impl AppState {
+	/// Load the `[tui.quick_actions]` of the workspace config.
+	///
+	/// NOTE: If the config itself cannot be loaded, no quick actions (the run will report the config error).
+	pub(in crate::tui::core) fn load_quick_actions() -> Result<Vec<QuickAction>> {
+		let Ok(config) = AipackPaths::new()
+			.and_then(DirContext::new)
+			.and_then(|dir_context| dir_context.load_config())
+		else {
+			return Ok(Vec::new());
+		};
+
+		QuickAction::list_from_config_value(config.value())
+	}
+}
+
+/// QuickActions
+impl AppState {
+	pub fn quick_actions(&self) -> &[QuickAction] {
+		&self.core.quick_actions
+	}
+
+	pub fn has_quick_action(&self, key: char) -> bool {
+		self.core.quick_actions.iter().any(|qa| qa.key == key)
+	}
+
+	/// Expand the quick action of this key into the pending actions (executed one per cycle)
+	/// or into the action event to send (for the agent run).
+	pub(in crate::tui::core) fn start_quick_action(&mut self, key: char) -> Result<()> {
+		let quick_action = self
+			.core
+			.quick_actions
+			.iter()
+			.find(|qa| qa.key == key)
+			.cloned()
+			.ok_or_else(|| format!("No quick action for key '{key}'"))?;
+
+		match quick_action.target {
+			QuickActionTarget::Steps(steps) => {
+				let mut actions = Vec::with_capacity(steps.len());
+				for step in steps {
+					let action = match step {
+						QuickStep::Redo => UiAction::Redo,
+						QuickStep::CancelRun => UiAction::CancelRun,
+						QuickStep::TogglePauseRun => UiAction::TogglePauseRun,
+						QuickStep::ToggleRunsNav => UiAction::ToggleRunsNav,
+						QuickStep::ToggleHistory => UiAction::ToggleHistory,
+						QuickStep::ToggleSplitRun => UiAction::ToggleSplitRun,
+						QuickStep::CycleTasksOverview => UiAction::CycleTasksOverviewMode,
+						QuickStep::CopyOutput => UiAction::ToClipboardCopy(self.current_task_output()?),
+						QuickStep::CopyOutputRaw => UiAction::ToClipboardCopyRaw(self.current_task_output()?),
+						QuickStep::OpenOutput => {
+							let task_uid = self.current_task().map(|t| t.uid).ok_or("No task selected")?;
+							let file = std::env::temp_dir().join(format!("aipack-task-{task_uid}-output.md"));
+							std::fs::write(&file, self.current_task_output()?)?;
+							UiAction::OpenFile(file.to_string_lossy().to_string())
+						}
+						QuickStep::Quit => UiAction::Quit,
+					};
+					actions.push(action);
+				}
+				self.core.pending_actions.extend(actions);
+			}
+			QuickActionTarget::Agent(agent) => {
+				let output = self.current_task_output()?;
+				let run_args = RunArgs::try_parse_from(["run", agent.as_str(), &format!("--input={output}")])
+					.map_err(|err| format!("Cannot run agent '{agent}'. Cause: {err}"))?;
+				self.core.to_send_action = Some(AppActionEvent::Run(Box::new(run_args)));
+			}
+		}
+
+		self.trigger_redraw();
+
+		Ok(())
+	}
+
+	/// Set the next pending action (if no current action). Returns true if one was set.
+	pub(in crate::tui::core) fn next_pending_action(&mut self) -> bool {
+		if self.action().is_some() {
+			return false;
+		}
+		match self.core.pending_actions.pop_front() {
+			Some(action) => {
+				self.set_action(action);
+				true
+			}
+			None => false,
+		}
+	}
+
+	fn current_task_output(&self) -> Result<String> {
+		let task = self.current_task().ok_or("No task selected")?;
+		let output = TaskBmc::get_output_for_display(self.mm(), task)?.ok_or("The selected task has no output")?;
+		Ok(output)
+	}
+}
@@ -1,4 +1,4 @@
-use crate::model::Id;
+use crate::model::{Id, Task};
 use crate::support::time::tick_count;
 use crate::tui::core::{AppState, RunItem, RunTab};
 use crate::tui::support::offset_and_clamp_option_idx_in_len;
@@ -57,7 +57,69 @@ impl AppState {
 	pub fn visible_run_items_for_nav(&self) -> Vec<&RunItem> {
 		self.core
 			.run_item_store
-			.visible_items_for_root_branch(self.current_root_run_id())
+			.visible_items(|run_id| self.is_run_expanded_in_nav(run_id))
+	}
+
+	/// True when the sub agent runs of this run are displayed in the runs nav.
+	/// By default, only the tree of the current run is expanded (unless expanded/collapsed by the user).
+	pub fn is_run_expanded_in_nav(&self, run_id: Id) -> bool {
+		let core = &self.core;
+		if core.runs_nav_collapsed_ids.contains(&run_id) {
+			return false;
+		}
+		if core.runs_nav_expanded_ids.contains(&run_id) {
+			return true;
+		}
+		let Some(root_id) = self.current_root_run_id() else {
+			return false;
+		};
+		core.run_item_store
+			.get(run_id)
+			.is_some_and(|run_item| run_item.belongs_to_root_branch(root_id))
+	}
+
+	/// Expand (`d` key) or collapse (`a` key) the sub agent runs of the current run in the runs nav.
+	/// Collapsing a sub agent run without expanded sub runs collapses its parent (which becomes the current run).
+	pub fn set_current_run_expanded_in_nav(&mut self, expanded: bool) {
+		let Some(run_item) = self.current_run_item() else {
+			return;
+		};
+		let run_id = run_item.id();
+		let is_expanded = run_item.has_children() && self.is_run_expanded_in_nav(run_id);
+
+		let target_id = match (expanded, run_item.has_children(), run_item.parent_id()) {
+			(true, true, _) => run_id,
+			(false, _, _) if is_expanded => run_id,
+			(false, _, Some(parent_id)) => parent_id,
+			_ => return,
+		};
+		self.set_run_expanded_in_nav(target_id, expanded);
+	}
+
+	/// Toggle the sub agent runs of this run in the runs nav (e.g., click on the run tree arrow)
+	pub fn toggle_run_expanded_in_nav(&mut self, run_id: Id) {
+		let expanded = !self.is_run_expanded_in_nav(run_id);
+		self.set_run_expanded_in_nav(run_id, expanded);
+	}
+
+	fn set_run_expanded_in_nav(&mut self, run_id: Id, expanded: bool) {
+		let core = &mut self.core;
+		if expanded {
+			core.runs_nav_collapsed_ids.remove(&run_id);
+			core.runs_nav_expanded_ids.insert(run_id);
+		} else {
+			core.runs_nav_expanded_ids.remove(&run_id);
+			core.runs_nav_collapsed_ids.insert(run_id);
+		}
+
+		// NOTE: When the current run becomes hidden, the collapsed run becomes the current one.
+		if !expanded
+			&& self
+				.current_run_item()
+				.is_some_and(|run_item| run_item.ancestors().contains(&run_id))
+		{
+			self.set_run_id(run_id);
+		}
 	}
 
 	/// Move the run selection by `offset` within the currently visible nav list.
@@ -101,3 +163,115 @@ impl AppState {
 		self.core.run_tab = run_tab;
 	}
 }
+
+/// RunSplitView
+impl AppState {
+	/// The run pinned for the split view (if still in the runs list).
+	pub fn split_run_item(&self) -> Option<&RunItem> {
+		let split_run_id = self.core.split_run_id?;
+		self.core.run_item_store.items().iter().find(|r| r.id() == split_run_id)
+	}
+
+	pub fn split_tasks(&self) -> &[Task] {
+		&self.core.split_tasks
+	}
+
+	/// True when a run is pinned and another run is selected (both are displayed side by side).
+	pub fn is_split_view(&self) -> bool {
+		match (self.split_run_item(), self.current_run_item()) {
+			(Some(split_run), Some(current_run)) => split_run.id() != current_run.id(),
+			_ => false,
+		}
+	}
+
+	pub fn has_split_run(&self) -> bool {
+		self.core.split_run_id.is_some()
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use crate::_test_support::{create_run, create_task};
+	use crate::model::{EpochUs, Id, ModelManager, RunBmc, RunForUpdate};
+	use crate::support::time::now_micro;
+	use crate::tui::core::event::{AppActionEvent, ScrollDir};
+	use crate::tui::core::{AppState, ScrollIden};
+	use crossterm::event::KeyCode;
+
+	#[tokio::test]
+	async fn test_tui_app_state_split_pin_and_select() -> Result<()> {
+		// -- Setup & Fixtures
+		let (mm, run_1_id, run_2_id) = seed_two_ended_runs().await?;
+		let mut state = AppState::new_for_test(mm)?;
+
+		// -- Exec & Check - Pin the current run (the latest one)
+		assert_eq!(state.current_run_item().map(|r| r.id()), Some(run_2_id));
+		state.process_key_for_test(KeyCode::Char('v'));
+		assert_eq!(state.split_run_item().map(|r| r.id()), Some(run_2_id));
+		assert!(
+			!state.is_split_view(),
+			"Same run pinned and selected should not be a split view"
+		);
+
+		// -- Exec & Check - Select the other run (the pinned one stays on the side)
+		state.process_key_for_test(KeyCode::Char('s'));
+		assert_eq!(state.current_run_item().map(|r| r.id()), Some(run_1_id));
+		assert_eq!(state.split_run_item().map(|r| r.id()), Some(run_2_id));
+		assert!(state.is_split_view());
+		assert_eq!(state.split_tasks().len(), 2);
+
+		// -- Exec & Check - Unpin
+		state.process_key_for_test(KeyCode::Char('v'));
+		assert!(!state.has_split_run());
+		assert!(!state.is_split_view());
+		assert!(state.split_tasks().is_empty());
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_tui_app_state_split_key_scroll_focus() -> Result<()> {
+		// -- Setup & Fixtures
+		let (mm, _, _) = seed_two_ended_runs().await?;
+		let mut state = AppState::new_for_test(mm)?;
+		state.process_key_for_test(KeyCode::Char('v'));
+		state.process_key_for_test(KeyCode::Char('s'));
+
+		// -- Exec
+		state.process_event_for_test(AppActionEvent::Scroll(ScrollDir::Down));
+
+		// -- Check
+		// NOTE: The key scroll goes to the split content (both runs scroll together)
+		assert_eq!(state.get_scroll(ScrollIden::SplitContent), 1);
+		assert_eq!(state.get_scroll(ScrollIden::TaskContent), 0);
+
+		Ok(())
+	}
+
+	// region:    --- Support
+
+	/// Returns the mm, and the two ended runs ids (the second one is the latest, with 2 tasks).
+	async fn seed_two_ended_runs() -> Result<(ModelManager, Id, Id)> {
+		let mm = ModelManager::new().await?;
+		let run_1_id = create_run(&mm, "agent-model-a")?;
+		let run_2_id = create_run(&mm, "agent-model-b")?;
+		for run_id in [run_1_id, run_2_id] {
+			create_task(&mm, run_id, 0)?;
+			create_task(&mm, run_id, 1)?;
+			let run_u = RunForUpdate {
+				end: Some(EpochUs::from(now_micro())),
+				..Default::default()
+			};
+			RunBmc::update(&mm, run_id, run_u)?;
+		}
+		Ok((mm, run_1_id, run_2_id))
+	}
+
+	// endregion: --- Support
+}
+
+// endregion: --- Tests
@@ -0,0 +1,169 @@
+use crate::Result;
+use crate::dir_context::AipackPaths;
+use crate::model::{EndState, Id, RunningState, Task, TaskBmc};
+use crate::tui::core::event::AppActionEvent;
+use crate::tui::core::{AppState, TaskPromptEdit};
+use simple_fs::SPath;
+
+/// Task Redo (redo the current, selected, or failed tasks of the last run, eventually with the edited prompt),
+/// and the selected tasks cancel and export.
+impl AppState {
+	/// Write the rendered prompt of the current task into its edit file, and return this file path.
+	pub(in crate::tui::core) fn start_task_prompt_edit(&mut self) -> Result<SPath> {
+		let task_idx = self.current_redo_task_idx()?;
+		let task = self.current_task().ok_or("No task selected")?;
+		let prompt = TaskBmc::get_prompt(self.mm(), task)?.ok_or("No rendered prompt for this task (yet)")?;
+
+		let path = AipackPaths::new()?
+			.task_prompts_dir()
+			.ok_or("No workspace `.aipack/` for the prompt edit file")?
+			.join(format!("task-{task_idx}.md"));
+		simple_fs::ensure_file_dir(&path)?;
+		std::fs::write(&path, prompt)?;
+
+		self.core.task_prompt_edit = Some(TaskPromptEdit {
+			task_idx,
+			path: path.clone(),
+		});
+
+		Ok(path)
+	}
+
+	/// Toggle the selection of the current task (for the redo of the selected tasks)
+	pub(in crate::tui::core) fn toggle_current_task_selection(&mut self) -> Result<()> {
+		let task_idx = self.current_redo_task_idx()?;
+		let run_id = self.current_run_item().map(|r| r.id());
+
+		let core = &mut self.core;
+		if core.selected_tasks_run_id != run_id {
+			core.selected_tasks_run_id = run_id;
+			core.selected_task_idxs.clear();
+		}
+		if !core.selected_task_idxs.remove(&task_idx) {
+			core.selected_task_idxs.insert(task_idx);
+		}
+
+		Ok(())
+	}
+
+	pub fn is_task_selected(&self, task: &Task) -> bool {
+		self.core.selected_tasks_run_id == Some(task.run_id)
+			&& task
+				.idx
+				.is_some_and(|idx| self.core.selected_task_idxs.contains(&(idx as usize)))
+	}
+
+	/// The redo action event of the selected tasks, or, if none, of the current task.
+	/// If the prompt of the current task was edited (`E` key), the edited prompt is the instruction.
+	pub(in crate::tui::core) fn task_redo_action_event(&mut self) -> Result<AppActionEvent> {
+		let task_idx = self.current_redo_task_idx()?;
+
+		// -- The selected tasks (of the current run)
+		let run_id = self.current_run_item().map(|r| r.id());
+		if self.core.selected_tasks_run_id == run_id && !self.core.selected_task_idxs.is_empty() {
+			let task_idxs = std::mem::take(&mut self.core.selected_task_idxs).into_iter().collect();
+			return Ok(AppActionEvent::RedoTasks {
+				task_idxs,
+				instruction: None,
+			});
+		}
+
+		// -- The current task
+		let instruction = match self.core.task_prompt_edit.as_ref() {
+			Some(edit) if edit.task_idx == task_idx => Some(std::fs::read_to_string(&edit.path)?),
+			_ => None,
+		};
+
+		Ok(AppActionEvent::RedoTasks {
+			task_idxs: vec![task_idx],
+			instruction,
+		})
+	}
+
+	/// The redo action event of the failed tasks of the current run.
+	pub(in crate::tui::core) fn failed_tasks_redo_action_event(&self) -> Result<AppActionEvent> {
+		self.current_redo_task_idx()?;
+
+		let task_idxs: Vec<usize> = self
+			.tasks()
+			.iter()
+			.filter(|t| matches!(t.end_state, Some(EndState::Err)))
+			.filter_map(|t| t.idx.map(|idx| idx as usize))
+			.collect();
+		if task_idxs.is_empty() {
+			return Err("No failed tasks in this run".into());
+		}
+
+		Ok(AppActionEvent::RedoTasks {
+			task_idxs,
+			instruction: None,
+		})
+	}
+
+	/// The cancel action event of the selected tasks (or of the current task) not ended yet.
+	pub(in crate::tui::core) fn tasks_cancel_action_event(&mut self) -> Result<AppActionEvent> {
+		if self.is_history_mode() {
+			return Err("Cannot cancel a task of the runs history".into());
+		}
+
+		let task_ids: Vec<Id> = self
+			.take_selected_or_current_tasks()?
+			.into_iter()
+			.filter(|task| matches!(RunningState::from(task), RunningState::Waiting | RunningState::Running))
+			.map(|task| task.id)
+			.collect();
+		if task_ids.is_empty() {
+			return Err("The task(s) already ended".into());
+		}
+
+		Ok(AppActionEvent::CancelTasks(task_ids))
+	}
+
+	/// The export action event of the outputs of the selected tasks (or of the current task).
+	pub(in crate::tui::core) fn tasks_export_action_event(&mut self) -> Result<AppActionEvent> {
+		// NOTE: The executor exports from the live runs db
+		if self.is_history_mode() {
+			return Err("Cannot export the tasks of the runs history".into());
+		}
+
+		let task_ids = self.take_selected_or_current_tasks()?.into_iter().map(|task| task.id).collect();
+
+		Ok(AppActionEvent::ExportTasksOutputs(task_ids))
+	}
+
+	/// Take the selected tasks of the current run (in the task order), or, if none, the current task.
+	fn take_selected_or_current_tasks(&mut self) -> Result<Vec<Task>> {
+		let run_id = self.current_run_item().map(|r| r.id());
+		if self.core.selected_tasks_run_id == run_id && !self.core.selected_task_idxs.is_empty() {
+			let task_idxs = std::mem::take(&mut self.core.selected_task_idxs);
+			let tasks = self
+				.tasks()
+				.iter()
+				.filter(|task| task.idx.is_some_and(|idx| task_idxs.contains(&(idx as usize))))
+				.cloned()
+				.collect();
+			return Ok(tasks);
+		}
+
+		let task = self.current_task().ok_or("No task selected")?;
+		Ok(vec![task.clone()])
+	}
+
+	/// The idx of the current task, when it can be redone.
+	/// NOTE: Only the tasks of the last live run can be redone (the executor keeps only this run redo context).
+	fn current_redo_task_idx(&self) -> Result<usize> {
+		if self.is_history_mode() {
+			return Err("Cannot redo a task of the runs history\nPress 'h' to go back to the live runs".into());
+		}
+
+		let last_root_run_id = self.run_items().iter().find(|r| r.is_root()).map(|r| r.id());
+		if self.current_run_item().map(|r| r.id()) != last_root_run_id {
+			return Err("Only the tasks of the last run can be redone".into());
+		}
+
+		let task = self.current_task().ok_or("No task selected")?;
+		let task_idx = task.idx.ok_or("The task has no idx")?;
+
+		Ok(task_idx as usize)
+	}
+}
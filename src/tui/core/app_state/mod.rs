@@ -4,12 +4,16 @@ mod app_state_base;
 mod app_state_core;
 mod common;
 mod impl_action;
+mod impl_chat;
 mod impl_fmt;
 mod impl_model_state;
 mod impl_mouse;
+mod impl_pack_info;
+mod impl_quick_action;
 mod impl_run;
 mod impl_scroll;
 mod impl_sys;
+mod impl_task_redo;
 mod state_processor;
 mod sys_state;
 
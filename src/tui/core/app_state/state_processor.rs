@@ -1,12 +1,21 @@
-use crate::model::{EntityType, EpochUs, ErrBmc, InstallData, ModelEvent, RunBmc, TaskBmc, WorkBmc};
+use crate::hub::HubEvent;
+use crate::model::{
+	ConvMsgBmc, EntityType, EpochUs, ErrBmc, Id, InstallData, ModelEvent, ModelManager, Run, RunBmc, TaskBmc, WorkBmc,
+};
+use crate::support::text::redact_secrets;
 use crate::support::time::now_micro;
 use crate::tui::AppState;
+use crate::tui::core::event::LastAppEvent;
 use crate::tui::core::event::{AppActionEvent, ScrollDir};
-use crate::tui::core::{AppStage, ConfigTab, NavDir, RunItemStore, RunTab, ScrollIden, UiAction};
+use crate::tui::core::{
+	AppStage, ConfigTab, NavDir, RunItemStore, RunTab, ScrollIden, UiAction, UserPrompt, UserPromptKeyRes,
+};
 use crate::tui::support::offset_and_clamp_option_idx_in_len;
 use crate::tui::view::{PopupMode, PopupView};
+use crate::tui_v1::PrintEvent;
 use crossterm::event::{KeyCode, MouseEventKind};
 use simple_fs::SPath;
+use std::collections::HashMap;
 use std::time::Duration;
 
 const SCROLL_KEY_MAIN_VIEW: bool = true;
@@ -24,6 +33,21 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 	// -- Process Stage
 	process_stage(state);
 
+	// -- Process the received pack info (the answer of the `I` key)
+	if let Some(HubEvent::Print(print_event)) = state.last_app_event().as_hub_event()
+		&& let PrintEvent::PackInfo(pack_info) = print_event.as_ref()
+	{
+		let pack_info = pack_info.as_ref().clone();
+		state.open_pack_info(pack_info);
+		state.core_mut().do_redraw = true;
+	}
+
+	// -- Process the user prompts (the keys are consumed by the prompt input when one is pending)
+	process_user_prompts(state);
+
+	// -- Process the chat input (the keys are consumed by the input when active)
+	process_chat_input(state);
+
 	// -- Process actions (clipboard, show-text popup, tab switch)
 	process_actions(state);
 
@@ -107,8 +131,11 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 		// then, we override/fallback to the main view scroll zone.
 		if is_key_scroll && SCROLL_KEY_MAIN_VIEW {
 			zone_iden = match state.run_tab() {
+				_ if state.stage() == AppStage::PackInfo => Some(ScrollIden::PackInfoContent),
+				_ if state.is_split_view() => Some(ScrollIden::SplitContent),
 				RunTab::Overview => Some(ScrollIden::OverviewContent),
 				RunTab::Tasks => Some(ScrollIden::TaskContent),
+				RunTab::Chat => Some(ScrollIden::ChatContent),
 			};
 		}
 
@@ -144,6 +171,39 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 		state.core_mut().do_redraw = true;
 	}
 
+	// -- Toggle runs history mode
+	if let Some(KeyCode::Char('h')) = state.last_app_event().as_key_code() {
+		state.set_action(UiAction::ToggleHistory);
+	}
+
+	// -- Toggle split view (pin the current run)
+	if let Some(KeyCode::Char('v')) = state.last_app_event().as_key_code() {
+		state.set_action(UiAction::ToggleSplitRun);
+	}
+
+	// -- User quick actions (from the config `[tui.quick_actions]`)
+	if let AppStage::Normal = state.stage()
+		&& let Some(&KeyCode::Char(key)) = state.last_app_event().as_key_code()
+		&& state.has_quick_action(key)
+	{
+		state.set_action(UiAction::QuickAction(key));
+	}
+
+	// -- Task redo keys (edit the task prompt, and redo the task)
+	if let AppStage::Normal = state.stage()
+		&& state.run_tab() == RunTab::Tasks
+	{
+		match state.last_app_event().as_key_code() {
+			Some(KeyCode::Char('E')) => state.set_action(UiAction::EditTaskPrompt),
+			Some(KeyCode::Char('R')) => state.set_action(UiAction::RedoTask),
+			Some(KeyCode::Char('F')) => state.set_action(UiAction::RedoFailedTasks),
+			Some(KeyCode::Char('C')) => state.set_action(UiAction::CancelSelectedTasks),
+			Some(KeyCode::Char('X')) => state.set_action(UiAction::ExportSelectedTasks),
+			Some(KeyCode::Char(' ')) => state.set_action(UiAction::ToggleTaskSelection),
+			_ => (),
+		}
+	}
+
 	// -- Show config popup
 	// NOTE: For now, the Config popup is not finished, so disable for now.
 	// if let Some(KeyCode::Char('c')) = state.last_app_event().as_key_code() {
@@ -164,12 +224,31 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 		}
 	}
 
+	// -- Show pack info (detail view of the current run agent pack)
+	if let AppStage::Normal = state.stage()
+		&& let Some(KeyCode::Char('I')) = state.last_app_event().as_key_code()
+	{
+		state.set_action(UiAction::ShowPackInfo);
+	} else if let AppStage::PackInfo = state.stage()
+		&& let Some(KeyCode::Esc | KeyCode::Char('I')) = state.last_app_event().as_key_code()
+	{
+		state.set_action(UiAction::ClosePackInfo);
+	}
+
 	// -- Cycle tasks overview mode
 	if let Some(KeyCode::Char('t')) = state.last_app_event().as_key_code() {
 		state.core_mut().next_overview_tasks_mode();
 		state.core_mut().do_redraw = true;
 	}
 
+	// -- Cycle tasks overview sort and quick filter
+	if let Some(KeyCode::Char('o')) = state.last_app_event().as_key_code() {
+		state.set_action(UiAction::CycleTasksSort);
+	}
+	if let Some(KeyCode::Char('f')) = state.last_app_event().as_key_code() {
+		state.set_action(UiAction::CycleTasksFilter);
+	}
+
 	// -- Navigation inside the runs list
 	let runs_nav_offset: i32 = if state.core().show_runs
 		&& let Some(code) = state.last_app_event().as_key_code()
@@ -187,6 +266,15 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 		state.core_mut().do_redraw = true;
 	}
 
+	// -- Expand/Collapse the sub agent runs of the current run in the runs list
+	if state.core().show_runs
+		&& let Some(code) = state.last_app_event().as_key_code()
+		&& let KeyCode::Char(key @ ('a' | 'd')) = code
+	{
+		state.set_current_run_expanded_in_nav(*key == 'd');
+		state.core_mut().do_redraw = true;
+	}
+
 	let refresh = compute_refresh_decision(state, opts);
 	refresh_data(state, refresh);
 
@@ -317,6 +405,8 @@ pub fn process_app_state(state: &mut AppState, opts: ProcessAppStateOpts) {
 struct RefreshDecision {
 	refresh_runs: bool,
 	refresh_task_rows: bool,
+	refresh_split_tasks: bool,
+	refresh_conv_msgs: bool,
 	refresh_sys_err: bool,
 }
 
@@ -340,6 +430,11 @@ fn compute_refresh_decision(state: &AppState, opts: ProcessAppStateOpts) -> Refr
 		|| current_run_id != loaded_run_id
 		|| (opts.current_event_refreshes_tasks && current_run_id.is_some());
 
+	refresh.refresh_conv_msgs |= current_run_id != state.core().conv_msgs_run_id;
+
+	refresh.refresh_split_tasks = state.has_split_run()
+		&& (state.split_tasks().is_empty() || refresh.refresh_runs || opts.current_event_refreshes_tasks);
+
 	refresh
 }
 
@@ -358,6 +453,10 @@ fn apply_model_event_refresh(refresh: &mut RefreshDecision, state: &AppState, mo
 		| EntityType::Inout => {
 			refresh.refresh_sys_err = true;
 		}
+		EntityType::ConvMsg => {
+			refresh.refresh_conv_msgs = true;
+			refresh.refresh_sys_err = true;
+		}
 		EntityType::Work => {
 			refresh.refresh_runs = true;
 			refresh.refresh_sys_err = true;
@@ -377,6 +476,14 @@ fn refresh_data(state: &mut AppState, refresh: RefreshDecision) {
 	if refresh.refresh_task_rows {
 		refresh_tasks(state);
 	}
+
+	if refresh.refresh_split_tasks {
+		refresh_split_tasks(state);
+	}
+
+	if refresh.refresh_conv_msgs {
+		refresh_conv_msgs(state);
+	}
 }
 
 fn refresh_runs(state: &mut AppState) {
@@ -384,7 +491,8 @@ fn refresh_runs(state: &mut AppState) {
 	let prev_run_id = state.core().run_id;
 	let new_runs = RunBmc::list_for_display(state.mm(), None).unwrap_or_default();
 	let has_new_runs = new_runs.len() != state.run_items().len();
-	let run_item_store = RunItemStore::new(new_runs);
+	let parent_task_idxs = load_parent_task_idxs(state.mm(), &new_runs);
+	let run_item_store = RunItemStore::new(new_runs, &parent_task_idxs);
 	state.core_mut().run_item_store = run_item_store;
 
 	// only change if we have new runs
@@ -420,6 +528,14 @@ fn refresh_runs(state: &mut AppState) {
 	}
 }
 
+/// The idxs of the parent tasks of the sub agent runs (by task id)
+fn load_parent_task_idxs(mm: &ModelManager, runs: &[Run]) -> HashMap<Id, i64> {
+	runs.iter()
+		.filter_map(|run| run.parent_task_id)
+		.filter_map(|task_id| TaskBmc::get_ids(mm, task_id).ok().map(|ids| (task_id, ids.idx as i64)))
+		.collect()
+}
+
 fn refresh_sys_err(state: &mut AppState) {
 	let runs_len = state.run_items().len();
 
@@ -478,9 +594,31 @@ fn refresh_tasks(state: &mut AppState) {
 	}
 }
 
+fn refresh_conv_msgs(state: &mut AppState) {
+	let current_run_id = state.current_run_item().map(|r| r.id());
+	let conv_msgs = match current_run_id {
+		Some(run_id) => ConvMsgBmc::list_for_run(state.mm(), run_id).unwrap_or_default(),
+		None => Vec::new(),
+	};
+	state.core_mut().conv_msgs = conv_msgs;
+	state.core_mut().conv_msgs_run_id = current_run_id;
+}
+
+fn refresh_split_tasks(state: &mut AppState) {
+	// -- Unpin if the pinned run is no longer in the runs list
+	let Some(split_run_id) = state.split_run_item().map(|r| r.id()) else {
+		state.core_mut().split_run_id = None;
+		state.core_mut().split_tasks.clear();
+		return;
+	};
+
+	let tasks = TaskBmc::list_for_run(state.mm(), split_run_id).unwrap_or_default();
+	state.core_mut().split_tasks = tasks;
+}
+
 fn process_stage(state: &mut AppState) {
 	let current_stage = state.stage();
-	let mm = state.mm();
+	let mm = state.live_mm();
 
 	// -- Check for active "Install" work
 	if let Ok(Some(work)) = WorkBmc::get_active_install(mm) {
@@ -541,11 +679,68 @@ fn process_stage(state: &mut AppState) {
 			AppStage::Config(_) => {
 				// Stay in Config
 			}
+			AppStage::PackInfo => {
+				// Stay in PackInfo until closed
+			}
 		}
 	}
 }
 
+fn process_user_prompts(state: &mut AppState) {
+	// -- Queue the new prompt
+	if let Some(HubEvent::Prompt(params)) = state.last_app_event().as_hub_event() {
+		let user_prompt = UserPrompt::new(params.clone());
+		state.core_mut().user_prompts.push_back(user_prompt);
+		state.core_mut().do_redraw = true;
+		return;
+	}
+
+	// -- Process the key for the current prompt
+	let Some(key_event) = state.last_app_event().as_key_event().copied() else {
+		return;
+	};
+	let core = state.core_mut();
+	let Some(user_prompt) = core.user_prompts.front_mut() else {
+		return;
+	};
+	if let UserPromptKeyRes::Done = user_prompt.on_key(&key_event) {
+		core.user_prompts.pop_front();
+	}
+	// NOTE: The key was for the prompt input, so not for the other TUI shortcuts
+	core.last_app_event = LastAppEvent::default();
+	core.do_redraw = true;
+}
+
+fn process_chat_input(state: &mut AppState) {
+	if state.stage() != AppStage::Normal
+		|| state.run_tab() != RunTab::Chat
+		|| state.is_split_view()
+		|| state.user_prompt().is_some()
+	{
+		state.core_mut().chat_input = None;
+		return;
+	}
+
+	let Some(key_event) = state.last_app_event().as_key_event().copied() else {
+		return;
+	};
+	if state.process_chat_key(&key_event) {
+		// NOTE: The key was for the chat input, so not for the other TUI shortcuts
+		let core = state.core_mut();
+		core.last_app_event = LastAppEvent::default();
+		core.do_redraw = true;
+	}
+}
+
 fn process_actions(state: &mut AppState) {
+	// NOTE: A cancel also resumes the runs (see `RunCtrl::cancel`)
+	if let Some(AppActionEvent::CancelRun) = state.last_app_event().as_action_event() {
+		state.core_mut().runs_paused = false;
+	}
+
+	// -- Next action of a quick action sequence
+	state.next_pending_action();
+
 	if let Some(action) = state.action().cloned() {
 		match action {
 			UiAction::Quit => {
@@ -556,15 +751,159 @@ fn process_actions(state: &mut AppState) {
 				state.core_mut().to_send_action = Some(AppActionEvent::Redo);
 				state.clear_action();
 			}
+			UiAction::EditTaskPrompt => {
+				state.clear_action();
+				let res = state
+					.start_task_prompt_edit()
+					.and_then(|path| crate::support::editor::open_file_auto(&path).map(|editor| (path, editor)));
+				match res {
+					Ok((path, editor)) => state.set_popup(PopupView {
+						content: format!(
+							"Editing task prompt\n{path}\n(with {})\nSave, then press 'R' to redo the task with it",
+							editor.program()
+						),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: false,
+					}),
+					Err(err) => state.set_popup(PopupView {
+						content: format!("Cannot edit the task prompt\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: true,
+					}),
+				}
+			}
+			UiAction::RedoTask | UiAction::RedoFailedTasks => {
+				state.clear_action();
+				let res = if let UiAction::RedoTask = action {
+					state.task_redo_action_event()
+				} else {
+					state.failed_tasks_redo_action_event()
+				};
+				match res {
+					Ok(action_event) => state.core_mut().to_send_action = Some(action_event),
+					Err(err) => state.set_popup(PopupView {
+						content: format!("Cannot redo the task(s)\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: true,
+					}),
+				}
+			}
+			UiAction::CancelSelectedTasks | UiAction::ExportSelectedTasks => {
+				state.clear_action();
+				let (res, what) = if let UiAction::CancelSelectedTasks = action {
+					(state.tasks_cancel_action_event(), "cancel")
+				} else {
+					(state.tasks_export_action_event(), "export")
+				};
+				match res {
+					Ok(action_event) => state.core_mut().to_send_action = Some(action_event),
+					Err(err) => state.set_popup(PopupView {
+						content: format!("Cannot {what} the task(s)\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: true,
+					}),
+				}
+			}
+			UiAction::SendChatMessage(message) => {
+				state.clear_action();
+				match state.chat_follow_up_action_event(message) {
+					Ok(action_event) => state.core_mut().to_send_action = Some(action_event),
+					Err(err) => state.set_popup(PopupView {
+						content: format!("Cannot send the message\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: true,
+					}),
+				}
+			}
+			UiAction::ToggleTaskSelection => {
+				state.clear_action();
+				if let Err(err) = state.toggle_current_task_selection() {
+					state.set_popup(PopupView {
+						content: format!("Cannot select the task\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(2000)),
+						is_err: true,
+					});
+				}
+			}
 			UiAction::CancelRun => {
+				state.core_mut().runs_paused = false;
 				state.core_mut().to_send_action = Some(AppActionEvent::CancelRun);
 				state.clear_action();
 			}
+			UiAction::TogglePauseRun => {
+				let runs_paused = !state.core().runs_paused;
+				state.core_mut().runs_paused = runs_paused;
+				state.core_mut().to_send_action = Some(if runs_paused {
+					AppActionEvent::PauseRun
+				} else {
+					AppActionEvent::ResumeRun
+				});
+				state.clear_action();
+			}
 			UiAction::ToggleRunsNav => {
 				let show_runs = !state.core().show_runs;
 				state.core_mut().show_runs = show_runs;
 				state.clear_action();
 			}
+			UiAction::ToggleHistory => {
+				let popup = if state.is_history_mode() {
+					state.core_mut().history_mm = None;
+					Some(("Live runs".to_string(), false))
+				} else {
+					match AppState::open_history_mm() {
+						Ok(history_mm) => {
+							state.core_mut().history_mm = Some(history_mm);
+							Some(("Runs history\nPress 'h' to go back to the live runs".to_string(), false))
+						}
+						Err(err) => Some((format!("Cannot open the runs history\n{err}"), true)),
+					}
+				};
+
+				// -- Reset the runs selection and reload from the new model manager
+				{
+					let inner = state.core_mut();
+					inner.run_item_store = RunItemStore::default();
+					inner.run_idx = None;
+					inner.run_id = None;
+					inner.runs_nav_expanded_ids.clear();
+					inner.runs_nav_collapsed_ids.clear();
+					inner.task_idx = None;
+					inner.split_run_id = None;
+					inner.split_tasks.clear();
+					inner.conv_msgs.clear();
+					inner.conv_msgs_run_id = None;
+				}
+				refresh_runs(state);
+				refresh_tasks(state);
+
+				if let Some((content, is_err)) = popup {
+					state.set_popup(PopupView {
+						content,
+						mode: PopupMode::Timed(Duration::from_millis(1500)),
+						is_err,
+					});
+				}
+				state.trigger_redraw();
+				state.clear_action();
+			}
+			UiAction::ToggleSplitRun => {
+				let popup_msg = if state.has_split_run() {
+					state.core_mut().split_run_id = None;
+					state.core_mut().split_tasks.clear();
+					"Split view closed".to_string()
+				} else if let Some(run_id) = state.current_run_item().map(|r| r.id()) {
+					state.core_mut().split_run_id = Some(run_id);
+					"Run pinned for split view\nSelect another run to compare".to_string()
+				} else {
+					"No run to pin for split view".to_string()
+				};
+				state.set_popup(PopupView {
+					content: popup_msg,
+					mode: PopupMode::Timed(Duration::from_millis(1500)),
+					is_err: false,
+				});
+				state.clear_action();
+			}
 			UiAction::ShowConfig => {
 				state.set_stage(AppStage::Config(state.config_tab()));
 				state.clear_action();
@@ -578,51 +917,56 @@ fn process_actions(state: &mut AppState) {
 				state.set_stage(AppStage::Config(tab));
 				state.clear_action();
 			}
+			UiAction::ShowPackInfo => {
+				state.clear_action();
+				match state.pack_info_action_event() {
+					Ok(action_event) => state.core_mut().to_send_action = Some(action_event),
+					Err(err) => state.set_popup(PopupView {
+						content: format!("Cannot show the pack info\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(3000)),
+						is_err: true,
+					}),
+				}
+			}
+			UiAction::ClosePackInfo => {
+				state.close_pack_info();
+				state.clear_action();
+			}
 			UiAction::CycleTasksOverviewMode => {
 				state.core_mut().next_overview_tasks_mode();
 				state.clear_action();
 			}
+			UiAction::CycleTasksSort => {
+				state.core_mut().next_overview_tasks_sort();
+				state.clear_action();
+			}
+			UiAction::CycleTasksFilter => {
+				state.core_mut().next_overview_tasks_filter();
+				state.clear_action();
+			}
+			UiAction::QuickAction(key) => {
+				state.clear_action();
+				if let Err(err) = state.start_quick_action(key) {
+					state.core_mut().pending_actions.clear();
+					state.set_popup(PopupView {
+						content: format!("Quick action '{key}' failed\n{err}"),
+						mode: PopupMode::Timed(Duration::from_millis(2000)),
+						is_err: true,
+					});
+				}
+			}
 			UiAction::ToClipboardCopy(content) => {
-				// Ensure we have a clipboard instance
-				let ensure_clipboard: Result<(), String> = if state.core().clipboard.is_some() {
-					Ok(())
-				} else {
-					match arboard::Clipboard::new() {
-						Ok(cb) => {
-							state.core_mut().clipboard = Some(cb);
-							Ok(())
-						}
-						Err(err) => Err(format!("Clipboard init error: {err}")),
-					}
-				};
-
-				let mut is_err = false;
-				let popup_msg = match ensure_clipboard {
-					Ok(()) => {
-						if let Some(cb) = state.core_mut().clipboard.as_mut() {
-							match cb.set_text(content) {
-								Ok(()) => "Copied to clipboard".to_string(),
-								Err(err) => {
-									is_err = true;
-									format!("Clipboard error: {err}")
-								}
-							}
-						} else {
-							is_err = true;
-							"Clipboard unavailable".to_string()
-						}
-					}
-					Err(msg) => {
-						is_err = true;
-						msg
-					}
+				let redacted = redact_secrets(&content);
+				let ok_msg = match redacted.count {
+					0 => "Copied to clipboard".to_string(),
+					1 => "Copied to clipboard\n(1 secret redacted)".to_string(),
+					count => format!("Copied to clipboard\n({count} secrets redacted)"),
 				};
-
-				state.set_popup(PopupView {
-					content: popup_msg,
-					mode: PopupMode::Timed(Duration::from_millis(1000)),
-					is_err,
-				});
+				copy_to_clipboard(state, redacted.content, ok_msg);
+				state.clear_action();
+			}
+			UiAction::ToClipboardCopyRaw(content) => {
+				copy_to_clipboard(state, content, "Copied to clipboard (raw)".to_string());
 				state.clear_action();
 			}
 			UiAction::ShowText => {
@@ -657,6 +1001,25 @@ fn process_actions(state: &mut AppState) {
 				}
 				state.clear_action();
 			}
+			UiAction::OpenUrl(url) => {
+				match crate::support::os::open_url(&url) {
+					Ok(program) => {
+						state.set_popup(PopupView {
+							content: format!("Opening url\n{url}\n(with {program})"),
+							mode: PopupMode::Timed(Duration::from_millis(2000)),
+							is_err: false,
+						});
+					}
+					Err(err) => {
+						state.set_popup(PopupView {
+							content: format!("Failed to open url\n{url}\n(Cause: {err})"),
+							mode: PopupMode::Timed(Duration::from_millis(3000)),
+							is_err: true,
+						});
+					}
+				}
+				state.clear_action();
+			}
 			UiAction::WorkConfirm(id) => {
 				state.core_mut().to_send_action = Some(AppActionEvent::WorkConfirm(id));
 				state.trigger_redraw();
@@ -668,13 +1031,13 @@ fn process_actions(state: &mut AppState) {
 				state.clear_action();
 			}
 			UiAction::WorkRun(id) => {
-				let mm = state.mm().clone();
+				let mm = state.live_mm().clone();
 				if let Ok(work) = WorkBmc::get(&mm, id)
 					&& let Ok(Some(install_data)) = work.get_data_as::<InstallData>()
 					&& let Some(run_args_val) = install_data.run_args
 					&& let Ok(run_args) = serde_json::from_value::<crate::exec::cli::RunArgs>(run_args_val)
 				{
-					state.core_mut().to_send_action = Some(AppActionEvent::Run(run_args));
+					state.core_mut().to_send_action = Some(AppActionEvent::Run(Box::new(run_args)));
 				}
 				state.set_stage(AppStage::Normal);
 				state.trigger_redraw();
@@ -689,4 +1052,48 @@ fn process_actions(state: &mut AppState) {
 	}
 }
 
+/// Copy the content to the clipboard (initializing the clipboard if needed), and show the result popup.
+fn copy_to_clipboard(state: &mut AppState, content: String, ok_msg: String) {
+	// Ensure we have a clipboard instance
+	let ensure_clipboard: Result<(), String> = if state.core().clipboard.is_some() {
+		Ok(())
+	} else {
+		match arboard::Clipboard::new() {
+			Ok(cb) => {
+				state.core_mut().clipboard = Some(cb);
+				Ok(())
+			}
+			Err(err) => Err(format!("Clipboard init error: {err}")),
+		}
+	};
+
+	let mut is_err = false;
+	let popup_msg = match ensure_clipboard {
+		Ok(()) => {
+			if let Some(cb) = state.core_mut().clipboard.as_mut() {
+				match cb.set_text(content) {
+					Ok(()) => ok_msg,
+					Err(err) => {
+						is_err = true;
+						format!("Clipboard error: {err}")
+					}
+				}
+			} else {
+				is_err = true;
+				"Clipboard unavailable".to_string()
+			}
+		}
+		Err(msg) => {
+			is_err = true;
+			msg
+		}
+	};
+
+	state.set_popup(PopupView {
+		content: popup_msg,
+		mode: PopupMode::Timed(Duration::from_millis(1000)),
+		is_err,
+	});
+}
+
 // endregion: --- Action Processing
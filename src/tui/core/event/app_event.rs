@@ -50,11 +50,26 @@ pub enum ScrollDir {
 pub enum AppActionEvent {
 	Quit,
 	Redo,
+	/// Redo only these tasks of the last run (eventually with the edited prompt)
+	RedoTasks {
+		task_idxs: Vec<usize>,
+		instruction: Option<String>,
+	},
+	/// Send this follow-up message to the last run conversation (chat tab)
+	ChatFollowUp(String),
+	/// Cancel these tasks (when not ended)
+	CancelTasks(Vec<crate::model::Id>),
+	/// Export the outputs of these tasks to a markdown file
+	ExportTasksOutputs(Vec<crate::model::Id>),
 	CancelRun,
+	PauseRun,
+	ResumeRun,
 	Scroll(ScrollDir),
 	ScrollPage(ScrollDir),
 	ScrollToEnd(ScrollDir),
 	WorkConfirm(crate::model::Id),
 	WorkCancel(crate::model::Id),
-	Run(RunArgs),
+	Run(Box<RunArgs>),
+	/// Get the info of this pack (published back as a `PrintEvent::PackInfo`, see the `I` key)
+	PackInfo(String),
 }
@@ -56,6 +56,13 @@ impl LastAppEvent {
 		})
 	}
 
+	pub fn as_hub_event(&self) -> Option<&crate::hub::HubEvent> {
+		self.last_event.as_ref().and_then(|e| match e.as_ref() {
+			AppEvent::Hub(event) => Some(event),
+			_ => None,
+		})
+	}
+
 	pub fn as_model_event(&self) -> Option<&crate::model::ModelEvent> {
 		self.last_event.as_ref().and_then(|e| match e.as_ref() {
 			AppEvent::Model(event) => Some(event),
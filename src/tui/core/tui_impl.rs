@@ -88,7 +88,7 @@ async fn exec_app(
 	});
 
 	// -- Exec the first cli_args
-	let exec_cmd: ExecActionEvent = args.cmd.into();
+	let exec_cmd: ExecActionEvent = args.into();
 	tokio::spawn(async move {
 		// TODO: handle exceptions in both those cases
 		let _ = executor_tx.send(exec_cmd).await;
@@ -9,7 +9,7 @@ use crate::tui::core::app_state::{ProcessAppStateOpts, process_app_state};
 use crate::tui::core::tui_impl::AppRx;
 use crate::tui::core::{PingTimerTx, start_ping_timer};
 use crate::tui::{AppState, AppTx, ExitTx, MainView};
-use crossterm::event::Event as TermEvent;
+use crossterm::event::{Event as TermEvent, KeyCode, KeyModifiers};
 use ratatui::DefaultTerminal;
 use std::collections::HashMap;
 use tokio::task::JoinHandle;
@@ -62,15 +62,22 @@ pub fn run_ui_loop(
 				}
 
 				// -- Normal handle
-				let _ = handle_app_event(
-					&mut terminal,
-					app_state.mm(),
-					&executor_tx,
-					&app_tx,
-					&exit_tx,
-					&app_event,
-				)
-				.await;
+				// NOTE: When a user prompt is pending, or the chat input is active,
+				//       the keys are for its input (but Ctrl+C still quits)
+				let is_prompt_key = (app_state.user_prompt().is_some() || app_state.chat_input().is_some())
+					&& matches!(&app_event, AppEvent::Term(TermEvent::Key(key))
+						if !(key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)));
+				if !is_prompt_key {
+					let _ = handle_app_event(
+						&mut terminal,
+						app_state.live_mm(),
+						&executor_tx,
+						&app_tx,
+						&exit_tx,
+						&app_event,
+					)
+					.await;
+				}
 
 				// -- Process app sate
 				let process_opts = ProcessAppStateOpts {
@@ -153,6 +160,12 @@ impl Debouncer {
 					self.last_redraw_event = Some(app_event);
 				}
 			}
+			// NOTE: The prompts cannot be debounced (each one waits for its answer)
+			AppEvent::Hub(HubEvent::Prompt(params)) => self.ui_events.push(AppEvent::Hub(HubEvent::Prompt(params))),
+			// NOTE: The prints are the answers of the TUI requests (e.g., the pack info), so not debounced either
+			AppEvent::Hub(HubEvent::Print(print_event)) => {
+				self.ui_events.push(AppEvent::Hub(HubEvent::Print(print_event)))
+			}
 			AppEvent::Hub(hub_event) => self.last_redraw_event = Some(AppEvent::Hub(hub_event)),
 			AppEvent::Tick(tick) => self.tick_event = Some(AppEvent::Tick(tick)),
 		}
@@ -20,6 +20,9 @@ pub struct LinkZones {
 
 	/// Optional grouping for section-wide hover selection.
 	next_group_id: u32,
+
+	/// The image previews (e.g., marker link to an image file), rendered over their reserved blank lines.
+	image_zones: Vec<ImageZone>,
 }
 
 impl LinkZones {
@@ -62,6 +65,21 @@ impl LinkZones {
 		));
 	}
 
+	/// Push an image preview zone which will be rendered over the `height` (blank) lines from `rel_line_idx`.
+	pub fn push_image_zone(&mut self, rel_line_idx: usize, x_offset: u16, height: u16, path: impl Into<String>) {
+		let line_idx = self.current_line + rel_line_idx;
+		self.image_zones.push(ImageZone {
+			line_idx,
+			x_offset,
+			height,
+			path: path.into(),
+		});
+	}
+
+	pub fn image_zones(&self) -> &[ImageZone] {
+		&self.image_zones
+	}
+
 	pub fn into_zones(self) -> Vec<LinkZone> {
 		self.zones
 	}
@@ -123,6 +141,17 @@ impl LinkZone {
 	) -> Option<&'a mut [Span<'static>]> {
 		let mouse_evt = mouse_evt?;
 
+		let zone_area = self.zone_area(ref_area, scroll, spans)?;
+
+		if mouse_evt.is_over(zone_area) {
+			self.spans_slice_mut(spans)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the screen area of this zone (one line), if visible in the `ref_area` for this `scroll`.
+	pub fn zone_area(&self, ref_area: Rect, scroll: u16, spans: &[Span<'static>]) -> Option<Rect> {
 		// Ensure the zone line is within the visible body area rows.
 		let line_idx = self.line_idx;
 		let scroll_usize = scroll as usize;
@@ -135,21 +164,15 @@ impl LinkZone {
 		let before_spans = spans.get(0..self.span_start)?;
 		let before_width = before_spans.x_width();
 
-		let zone_spans = spans.get_mut(self.span_start..self.span_start + self.span_count)?;
+		let zone_spans = spans.get(self.span_start..self.span_start + self.span_count)?;
 
 		let visible_row = (line_idx - scroll_usize) as u16;
-		let zone_area = Rect {
+		Some(Rect {
 			x: ref_area.x + before_width,
 			y: ref_area.y + visible_row,
 			width: zone_spans.x_width(),
 			height: 1,
-		};
-
-		if mouse_evt.is_over(zone_area) {
-			Some(zone_spans)
-		} else {
-			None
-		}
+		})
 	}
 
 	/// Return a mutable slice for this zone span range on a given line's spans.
@@ -159,6 +182,41 @@ impl LinkZone {
 }
 // endregion: --- DataZone
 
+// region:    --- ImageZone
+
+#[derive(Debug, Clone)]
+pub struct ImageZone {
+	pub line_idx: usize,
+	pub x_offset: u16,
+	pub height: u16,
+	pub path: String,
+}
+
+impl ImageZone {
+	/// Returns the screen area of this image zone, only if fully visible in the `ref_area` for this `scroll`.
+	pub fn zone_area(&self, ref_area: Rect, scroll: u16) -> Option<Rect> {
+		let visible_top = scroll as usize;
+		let visible_bottom = visible_top + ref_area.height as usize;
+		if self.line_idx < visible_top || self.line_idx + self.height as usize > visible_bottom {
+			return None;
+		}
+
+		let width = ref_area.width.saturating_sub(self.x_offset);
+		if width == 0 {
+			return None;
+		}
+
+		Some(Rect {
+			x: ref_area.x + self.x_offset,
+			y: ref_area.y + (self.line_idx - visible_top) as u16,
+			width,
+			height: self.height,
+		})
+	}
+}
+
+// endregion: --- ImageZone
+
 // region:    --- DataKey
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -3,23 +3,33 @@
 mod link_zone;
 mod mouse_evt;
 mod nav_dir;
+mod overview_tasks_filter;
 mod overview_tasks_mode;
+mod overview_tasks_sort;
+mod quick_action;
 mod run_item;
 mod run_item_store;
 mod run_tab;
 mod run_tasks_info;
 mod scroll_zone;
+mod task_prompt_edit;
 mod ui_action;
+mod user_prompt;
 
 pub use link_zone::*;
 pub use mouse_evt::*;
 pub use nav_dir::*;
+pub use overview_tasks_filter::*;
 pub use overview_tasks_mode::*;
+pub use overview_tasks_sort::*;
+pub use quick_action::*;
 pub use run_item::*;
 pub use run_item_store::*;
 pub use run_tab::*;
 pub use run_tasks_info::*;
 pub use scroll_zone::*;
+pub use task_prompt_edit::*;
 pub use ui_action::*;
+pub use user_prompt::*;
 
 // endregion: --- Modules
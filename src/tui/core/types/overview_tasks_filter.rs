@@ -0,0 +1,40 @@
+use crate::model::{EndState, RunningState, Task};
+
+/// The tasks overview quick filter (`f` key)
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum OverviewTasksFilter {
+	#[default]
+	All,
+	/// The tasks ended in error
+	Errors,
+	/// The tasks not ended yet (queued or running)
+	Pending,
+}
+
+impl OverviewTasksFilter {
+	pub fn next(self) -> Self {
+		match self {
+			OverviewTasksFilter::All => OverviewTasksFilter::Errors,
+			OverviewTasksFilter::Errors => OverviewTasksFilter::Pending,
+			OverviewTasksFilter::Pending => OverviewTasksFilter::All,
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			OverviewTasksFilter::All => "all",
+			OverviewTasksFilter::Errors => "errors",
+			OverviewTasksFilter::Pending => "pending",
+		}
+	}
+
+	pub fn matches(&self, task: &Task) -> bool {
+		match self {
+			OverviewTasksFilter::All => true,
+			OverviewTasksFilter::Errors => RunningState::from(task) == RunningState::Ended(Some(EndState::Err)),
+			OverviewTasksFilter::Pending => {
+				matches!(RunningState::from(task), RunningState::Waiting | RunningState::Running)
+			}
+		}
+	}
+}
@@ -0,0 +1,134 @@
+use crate::model::{EndState, RunningState, Task};
+use crate::tui::support::cmp_display_values;
+use std::cmp::Ordering;
+
+/// The tasks overview sort (`o` key), cycling through the built-in sorts, then the display columns
+/// (from the data stage `_ui.cols`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum OverviewTasksSort {
+	/// Longest first
+	Duration,
+	/// Most expensive first
+	Cost,
+	/// Errors first, then running, queued, canceled, skipped, and done
+	Status,
+	Label,
+	Col(String),
+}
+
+impl OverviewTasksSort {
+	const BUILTINS: &[OverviewTasksSort] = &[Self::Duration, Self::Cost, Self::Status, Self::Label];
+
+	/// Returns the sort after the current one (none for the task order after the last display column)
+	pub fn next(current: Option<&Self>, col_names: Vec<String>) -> Option<Self> {
+		let mut all: Vec<Self> = Self::BUILTINS.to_vec();
+		all.extend(col_names.into_iter().map(Self::Col));
+
+		match current {
+			None => all.into_iter().next(),
+			Some(current) => {
+				let next_idx = all.iter().position(|sort| sort == current)? + 1;
+				all.into_iter().nth(next_idx)
+			}
+		}
+	}
+
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Duration => "duration",
+			Self::Cost => "cost",
+			Self::Status => "status",
+			Self::Label => "label",
+			Self::Col(name) => name,
+		}
+	}
+
+	/// Returns the sorted tasks (stable, so the tasks without value keep the task order, last)
+	pub fn sort_tasks(&self, mut tasks: Vec<Task>) -> Vec<Task> {
+		match self {
+			Self::Duration => tasks.sort_by(|a, b| cmp_desc_none_last(task_duration(a), task_duration(b))),
+			Self::Cost => tasks.sort_by(|a, b| cmp_desc_none_last(a.cost, b.cost)),
+			Self::Status => tasks.sort_by_key(status_rank),
+			Self::Label => tasks.sort_by(|a, b| cmp_display_none_last(a.label.as_deref(), b.label.as_deref())),
+			Self::Col(name) => {
+				// NOTE: The display columns are parsed once (not at each comparison)
+				let mut keyed: Vec<(Option<String>, Task)> =
+					tasks.into_iter().map(|task| (task.ui_cols().remove(name), task)).collect();
+				keyed.sort_by(|(a, _), (b, _)| cmp_display_none_last(a.as_deref(), b.as_deref()));
+				tasks = keyed.into_iter().map(|(_, task)| task).collect();
+			}
+		}
+		tasks
+	}
+}
+
+// region:    --- Support
+
+fn task_duration(task: &Task) -> Option<i64> {
+	Some(task.end?.as_i64() - task.start?.as_i64())
+}
+
+fn cmp_desc_none_last<T: PartialOrd>(a: Option<T>, b: Option<T>) -> Ordering {
+	match (a, b) {
+		(Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => Ordering::Equal,
+	}
+}
+
+fn cmp_display_none_last(a: Option<&str>, b: Option<&str>) -> Ordering {
+	match (a, b) {
+		(Some(a), Some(b)) => cmp_display_values(a, b),
+		(Some(_), None) => Ordering::Less,
+		(None, Some(_)) => Ordering::Greater,
+		(None, None) => Ordering::Equal,
+	}
+}
+
+fn status_rank(task: &Task) -> u8 {
+	match RunningState::from(task) {
+		RunningState::Ended(Some(EndState::Err)) => 0,
+		RunningState::Running => 1,
+		RunningState::Waiting => 2,
+		RunningState::Ended(Some(EndState::Cancel)) => 3,
+		RunningState::Ended(Some(EndState::Skip)) => 4,
+		RunningState::Ended(Some(EndState::Ok)) => 5,
+		RunningState::Ended(None) | RunningState::NotScheduled | RunningState::Unknown => 6,
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_tui_overview_tasks_sort_next() -> Result<()> {
+		// -- Setup & Fixtures
+		let col_names = || vec!["size".to_string()];
+
+		// -- Exec
+		let mut names: Vec<String> = Vec::new();
+		let mut sort = OverviewTasksSort::next(None, col_names());
+		while let Some(current) = sort {
+			names.push(current.name().to_string());
+			sort = OverviewTasksSort::next(Some(&current), col_names());
+		}
+
+		// -- Check
+		assert_eq!(names, ["duration", "cost", "status", "label", "size"]);
+		// The removed display column goes back to the task order
+		let removed_col = OverviewTasksSort::Col("gone".to_string());
+		assert!(OverviewTasksSort::next(Some(&removed_col), col_names()).is_none());
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,190 @@
+//! The user quick actions, defined in the config `[tui.quick_actions]`.
+//!
+//! A quick action binds a single key to either a sequence of UI steps or an agent run
+//! on the current task output.
+//!
+//! ```toml
+//! [tui.quick_actions]
+//! e = { label = "Edit Output", actions = ["open_output"] }
+//! g = { label = "Review", agent = "my-review-agent" }
+//! ```
+
+use crate::{Error, Result};
+use serde_json::Value;
+use strum::IntoEnumIterator as _;
+
+/// The keys already used by the TUI, which cannot be bound to a quick action.
+const RESERVED_KEYS: &str = "qrxpnhvtowsadikjlfMERFCX-=123 ";
+
+#[derive(Debug, Clone)]
+pub struct QuickAction {
+	pub key: char,
+	pub label: String,
+	pub target: QuickActionTarget,
+}
+
+#[derive(Debug, Clone)]
+pub enum QuickActionTarget {
+	/// The UI steps to execute in order
+	Steps(Vec<QuickStep>),
+	/// Run this agent with the current task output as input (like `aip run <agent> -i <output>`)
+	Agent(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::IntoStaticStr, strum::EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum QuickStep {
+	Redo,
+	CancelRun,
+	TogglePauseRun,
+	ToggleRunsNav,
+	ToggleHistory,
+	ToggleSplitRun,
+	CycleTasksOverview,
+	/// Copy the current task output to the clipboard (with the secrets redacted)
+	CopyOutput,
+	/// Copy the current task output to the clipboard as is (no secret redaction)
+	CopyOutputRaw,
+	/// Open the current task output in the editor
+	OpenOutput,
+	Quit,
+}
+
+/// Parsers
+impl QuickAction {
+	/// Parse the `[tui.quick_actions]` of the config value (empty if absent).
+	pub fn list_from_config_value(config_value: &Value) -> Result<Vec<QuickAction>> {
+		let Some(quick_actions) = config_value.pointer("/tui/quick_actions") else {
+			return Ok(Vec::new());
+		};
+		let quick_actions = quick_actions
+			.as_object()
+			.ok_or("[tui.quick_actions] must be a table of 'key = { label = ..., actions = [...] | agent = ... }'")?;
+
+		let mut res = Vec::with_capacity(quick_actions.len());
+		for (key_str, item) in quick_actions {
+			let mut chars = key_str.chars();
+			let key = match (chars.next(), chars.next()) {
+				(Some(key), None) => key,
+				_ => {
+					return Err(Error::custom(format!(
+						"[tui.quick_actions] key '{key_str}' must be a single character"
+					)));
+				}
+			};
+			if RESERVED_KEYS.contains(key) {
+				return Err(Error::custom(format!(
+					"[tui.quick_actions] key '{key}' is already used by the TUI (reserved keys: {RESERVED_KEYS})"
+				)));
+			}
+
+			let target = match (item.get("actions"), item.get("agent")) {
+				(Some(actions), None) => {
+					let actions = actions.as_array().ok_or_else(|| {
+						Error::custom(format!("[tui.quick_actions] '{key}' actions must be an array"))
+					})?;
+					let steps = actions
+						.iter()
+						.map(|action| {
+							action.as_str().and_then(|name| name.parse::<QuickStep>().ok()).ok_or_else(|| {
+								Error::custom(format!(
+									"[tui.quick_actions] '{key}' action '{action}' not supported. Supported: {}",
+									QuickStep::names().join(", ")
+								))
+							})
+						})
+						.collect::<Result<Vec<_>>>()?;
+					QuickActionTarget::Steps(steps)
+				}
+				(None, Some(Value::String(agent))) => QuickActionTarget::Agent(agent.to_string()),
+				_ => {
+					return Err(Error::custom(format!(
+						"[tui.quick_actions] '{key}' must have either 'actions = [...]' or 'agent = \"...\"'"
+					)));
+				}
+			};
+
+			let label = item
+				.get("label")
+				.and_then(|v| v.as_str())
+				.map(|s| s.to_string())
+				.unwrap_or_else(|| match &target {
+					QuickActionTarget::Steps(steps) => steps
+						.iter()
+						.map(|step| <&'static str>::from(*step))
+						.collect::<Vec<_>>()
+						.join(", "),
+					QuickActionTarget::Agent(agent) => agent.to_string(),
+				});
+
+			res.push(QuickAction { key, label, target });
+		}
+
+		Ok(res)
+	}
+}
+
+impl QuickStep {
+	fn names() -> Vec<&'static str> {
+		QuickStep::iter().map(<&'static str>::from).collect()
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use crate::support::tomls::parse_toml_into_json;
+
+	#[test]
+	fn test_tui_quick_action_list_from_config_value_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let config_value = parse_toml_into_json(
+			r#"
+[tui.quick_actions]
+e = { label = "Edit Output", actions = ["open_output"] }
+g = { agent = "my-review-agent" }
+		"#,
+		)?;
+
+		// -- Exec
+		let quick_actions = QuickAction::list_from_config_value(&config_value)?;
+
+		// -- Check
+		let edit = quick_actions.iter().find(|qa| qa.key == 'e').ok_or("Should have 'e'")?;
+		assert_eq!(edit.label, "Edit Output");
+		assert!(matches!(&edit.target, QuickActionTarget::Steps(steps) if steps == &[QuickStep::OpenOutput]));
+		let review = quick_actions.iter().find(|qa| qa.key == 'g').ok_or("Should have 'g'")?;
+		assert_eq!(review.label, "my-review-agent");
+		assert!(matches!(&review.target, QuickActionTarget::Agent(agent) if agent == "my-review-agent"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_tui_quick_action_list_from_config_value_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_configs = [
+			"[tui.quick_actions]\nr = { actions = [\"redo\"] }",
+			"[tui.quick_actions]\nee = { actions = [\"redo\"] }",
+			"[tui.quick_actions]\ne = { actions = [\"fly\"] }",
+			"[tui.quick_actions]\ne = { label = \"No target\" }",
+		];
+
+		// -- Exec & Check
+		for fx_config in fx_configs {
+			let config_value = parse_toml_into_json(fx_config)?;
+			assert!(
+				QuickAction::list_from_config_value(&config_value).is_err(),
+				"Should fail for: {fx_config}"
+			);
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -5,19 +5,26 @@ pub struct RunItem {
 	run: Run,
 	indent: u32,
 	ancestors: Vec<Id>,
+	/// The idx of the parent run task calling this sub agent run (if from a task)
+	parent_task_idx: Option<i64>,
 	pub(in crate::tui::core) all_children_ids: Vec<Id>,
+	/// The run cost plus the costs of all its sub agent runs
+	pub(in crate::tui::core) rolled_up_cost: Option<f64>,
 }
 
 // region:    --- RunItem Impl
 
 /// Constructor
 impl RunItem {
-	pub fn new(run: Run, indent: u32, ancestors: Vec<Id>) -> Self {
+	pub fn new(run: Run, indent: u32, ancestors: Vec<Id>, parent_task_idx: Option<i64>) -> Self {
+		let rolled_up_cost = run.total_cost;
 		Self {
 			run,
 			indent,
 			ancestors,
+			parent_task_idx,
 			all_children_ids: Vec::new(),
+			rolled_up_cost,
 		}
 	}
 }
@@ -41,6 +48,14 @@ impl RunItem {
 		self.run.parent_id
 	}
 
+	pub fn parent_task_idx(&self) -> Option<i64> {
+		self.parent_task_idx
+	}
+
+	pub fn rolled_up_cost(&self) -> Option<f64> {
+		self.rolled_up_cost
+	}
+
 	pub fn is_running(&self) -> bool {
 		!self.run.is_done()
 	}
@@ -14,14 +14,14 @@ impl RunItemStore {
 		&self.items
 	}
 
-	pub fn visible_items_for_root_branch(&self, root_id: Option<Id>) -> Vec<&RunItem> {
+	/// Returns the items with all of their ancestors expanded (the roots are always visible).
+	pub fn visible_items(&self, is_expanded: impl Fn(Id) -> bool) -> Vec<&RunItem> {
 		self.items
 			.iter()
-			.filter(|item| item.is_root() || root_id.is_some_and(|root_id| item.belongs_to_root_branch(root_id)))
+			.filter(|item| item.ancestors().iter().all(|ancestor_id| is_expanded(*ancestor_id)))
 			.collect()
 	}
 
-	#[allow(unused)]
 	pub fn get(&self, id: Id) -> Option<&RunItem> {
 		self.items_by_id.get(&id)
 	}
@@ -49,7 +49,8 @@ impl RunItemStore {
 
 /// Contrustor
 impl RunItemStore {
-	pub fn new(runs: Vec<Run>) -> Self {
+	/// - `parent_task_idxs` are the task idxs by task id, for the sub agent runs `parent_task_id`.
+	pub fn new(runs: Vec<Run>, parent_task_idxs: &HashMap<Id, i64>) -> Self {
 		// -- Early Exit
 		if runs.is_empty() {
 			return RunItemStore::default();
@@ -68,27 +69,31 @@ impl RunItemStore {
 		}
 
 		// -- Recursively Flatten
+		let task_idx = |run: &Run| run.parent_task_id.and_then(|task_id| parent_task_idxs.get(&task_id).copied());
+
 		fn push_with_children(
 			out: &mut Vec<RunItem>,
 			children_map: &mut HashMap<Id, Vec<Run>>,
+			task_idx: &dyn Fn(&Run) -> Option<i64>,
 			run: Run,
 			indent: u32,
 			ancestors: &[Id],
 		) {
 			let id = run.id;
 			// This is the item for the current run
-			out.push(RunItem::new(run, indent, ancestors.to_vec()));
+			let parent_task_idx = task_idx(&run);
+			out.push(RunItem::new(run, indent, ancestors.to_vec(), parent_task_idx));
 
 			if let Some(mut kids) = children_map.remove(&id) {
-				// Oldest → Newest
-				kids.sort_by_key(|r| r.id);
+				// Grouped by parent task (the ones not from a task first), then Oldest → Newest
+				kids.sort_by_key(|r| (task_idx(r), r.id));
 
 				// The ancestors for all the direct children of this run.
 				let mut child_ancestors = ancestors.to_vec();
 				child_ancestors.push(id);
 
 				for child in kids {
-					push_with_children(out, children_map, child, indent + 1, &child_ancestors);
+					push_with_children(out, children_map, task_idx, child, indent + 1, &child_ancestors);
 				}
 			}
 		}
@@ -96,7 +101,7 @@ impl RunItemStore {
 		let mut flat: Vec<RunItem> = Vec::new();
 
 		for run in root_runs {
-			push_with_children(&mut flat, &mut children_map, run, 0, &[]);
+			push_with_children(&mut flat, &mut children_map, &task_idx, run, 0, &[]);
 		}
 
 		// -- Orphan Handling (if any)
@@ -105,7 +110,7 @@ impl RunItemStore {
 			remaining.sort_by_key(|r| r.id);
 			for run in remaining {
 				// Note: orphans will have an empty ancestor list (besides themselve)
-				push_with_children(&mut flat, &mut HashMap::new(), run, 0, &[]);
+				push_with_children(&mut flat, &mut HashMap::new(), &task_idx, run, 0, &[]);
 			}
 		}
 
@@ -138,9 +143,15 @@ impl RunItemStore {
 			all_children_ids_by_id.insert(item.id(), all_children_ids);
 		}
 
-		// Now, update the `all_children_ids` for each item in the `flat` vec.
+		// -- Roll up the sub agent runs costs
+		let cost_by_id: HashMap<Id, Option<f64>> = flat.iter().map(|item| (item.id(), item.run().total_cost)).collect();
+
+		// Now, update the `all_children_ids` (and the rolled up cost) for each item in the `flat` vec.
 		for item in &mut flat {
 			if let Some(ids) = all_children_ids_by_id.get(&item.id()) {
+				let children_costs = ids.iter().filter_map(|id| cost_by_id.get(id).copied().flatten());
+				item.rolled_up_cost =
+					children_costs.fold(item.rolled_up_cost, |acc, cost| Some(acc.unwrap_or(0.) + cost));
 				item.all_children_ids = ids.clone();
 			}
 		}
@@ -153,3 +164,61 @@ impl RunItemStore {
 		}
 	}
 }
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+	use crate::_test_support::{create_run, create_task};
+	use crate::model::{ModelManager, RunBmc, RunForCreate, RunForUpdate};
+
+	#[tokio::test]
+	async fn test_tui_run_item_store_new_sub_runs() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = ModelManager::new().await?;
+		let root_id = create_run(&mm, "root")?;
+		let task_0_id = create_task(&mm, root_id, 0)?;
+		let task_1_id = create_task(&mm, root_id, 1)?;
+		let sub_run = |name: &str, parent_task_id: Id| RunForCreate {
+			parent_id: Some(root_id),
+			parent_task_id: Some(parent_task_id),
+			agent_name: Some(name.to_string()),
+			agent_path: None,
+			has_task_stages: None,
+			has_prompt_parts: None,
+		};
+		// NOTE: The sub run of the task 1 created before the one of the task 0
+		let sub_t1_id = RunBmc::create(&mm, sub_run("sub-t1", task_1_id))?;
+		let sub_t0_id = RunBmc::create(&mm, sub_run("sub-t0", task_0_id))?;
+		for (run_id, cost) in [(root_id, 1.0), (sub_t1_id, 0.5), (sub_t0_id, 0.25)] {
+			let run_u = RunForUpdate {
+				total_cost: Some(cost),
+				..Default::default()
+			};
+			RunBmc::update(&mm, run_id, run_u)?;
+		}
+		let runs = RunBmc::list_for_display(&mm, None)?;
+		let fx_parent_task_idxs = HashMap::from([(task_0_id, 0), (task_1_id, 1)]);
+
+		// -- Exec
+		let store = RunItemStore::new(runs, &fx_parent_task_idxs);
+
+		// -- Check
+		let ids: Vec<Id> = store.items().iter().map(|item| item.id()).collect();
+		assert_eq!(ids, vec![root_id, sub_t0_id, sub_t1_id]);
+		let root = store.get(root_id).ok_or("Should have root")?;
+		assert_eq!(root.rolled_up_cost(), Some(1.75));
+		let sub_t1 = store.get(sub_t1_id).ok_or("Should have sub-t1")?;
+		assert_eq!(sub_t1.parent_task_idx(), Some(1));
+		assert_eq!(sub_t1.rolled_up_cost(), Some(0.5));
+		assert_eq!(store.visible_items(|_| false).len(), 1);
+		assert_eq!(store.visible_items(|_| true).len(), 3);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
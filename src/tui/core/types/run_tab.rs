@@ -2,13 +2,16 @@
 pub enum RunTab {
 	Overview,
 	Tasks,
+	/// The run conversation, with the follow-up message input
+	Chat,
 }
 
 impl RunTab {
 	pub fn next(self) -> Self {
 		match self {
 			RunTab::Overview => RunTab::Tasks,
-			RunTab::Tasks => RunTab::Tasks,
+			RunTab::Tasks => RunTab::Chat,
+			RunTab::Chat => RunTab::Chat,
 		}
 	}
 
@@ -16,6 +19,7 @@ impl RunTab {
 		match self {
 			RunTab::Overview => RunTab::Overview,
 			RunTab::Tasks => RunTab::Overview,
+			RunTab::Chat => RunTab::Tasks,
 		}
 	}
 }
@@ -7,6 +7,9 @@ pub enum ScrollIden {
 	TasksNav,
 	TaskContent,
 	OverviewContent,
+	SplitContent,
+	ChatContent,
+	PackInfoContent,
 }
 
 #[derive(Debug, Default)]
@@ -72,6 +75,9 @@ impl Default for ScrollZones {
 		zones.insert(ScrollIden::TasksNav, ScrollZone::default());
 		zones.insert(ScrollIden::TaskContent, ScrollZone::default());
 		zones.insert(ScrollIden::OverviewContent, ScrollZone::default());
+		zones.insert(ScrollIden::SplitContent, ScrollZone::default());
+		zones.insert(ScrollIden::ChatContent, ScrollZone::default());
+		zones.insert(ScrollIden::PackInfoContent, ScrollZone::default());
 
 		Self { zones }
 	}
@@ -0,0 +1,11 @@
+use simple_fs::SPath;
+
+/// The rendered prompt of a task, written to a file for the user to edit (`E` key),
+/// and used for the redo of this task (`R` key).
+#[derive(Debug, Clone)]
+pub struct TaskPromptEdit {
+	/// The task idx in the run (same as the input idx)
+	pub task_idx: usize,
+	/// The file with the prompt text (e.g., `.aipack/.session/_task_prompts/task-0.md`)
+	pub path: SPath,
+}
@@ -11,9 +11,34 @@ pub enum UiAction {
 	// -- Global Actions
 	Quit,
 	Redo,
+	/// Write the current task prompt to a file and open it in the editor (`E` key)
+	EditTaskPrompt,
+	/// Redo the selected tasks, or the current task with the eventual edited prompt (`R` key)
+	RedoTask,
+	/// Redo the failed tasks of the run (`F` key)
+	RedoFailedTasks,
+	/// Toggle the selection of the current task for the redo, cancel, and export (`space` key)
+	ToggleTaskSelection,
+	/// Cancel the selected tasks, or the current task, when not ended (`C` key)
+	CancelSelectedTasks,
+	/// Export the outputs of the selected tasks, or the current task, to a markdown file (`X` key)
+	ExportSelectedTasks,
+	/// Send this follow-up message to the last run conversation (`Enter` key in the chat tab)
+	SendChatMessage(String),
 	CancelRun,
+	TogglePauseRun,
 	ToggleRunsNav,
+	/// Switch between the current session runs and the persisted runs history
+	ToggleHistory,
+	/// Pin the current run for the split view (or close the split view if already pinned)
+	ToggleSplitRun,
 	CycleTasksOverviewMode,
+	/// Sort the tasks overview by the next built-in sort (duration, cost, status, label) or display column
+	CycleTasksSort,
+	/// Filter the tasks overview by the next quick filter (all, errors, pending)
+	CycleTasksFilter,
+	/// Run the user quick action bound to this key (see `[tui.quick_actions]` config)
+	QuickAction(char),
 
 	// Configuration
 	#[allow(unused)]
@@ -21,6 +46,11 @@ pub enum UiAction {
 	CloseConfig,
 	SwitchConfigTab(ConfigTab),
 
+	// Pack Info
+	/// Show the detail view of the current run agent pack (`I` key)
+	ShowPackInfo,
+	ClosePackInfo,
+
 	// Go to the tasks tab and select this task_id
 	GoToTask {
 		task_id: Id,
@@ -38,9 +68,25 @@ pub enum UiAction {
 	#[allow(unused)]
 	ShowText,
 
-	// Copy the provided text into the clipboard
+	// Copy the provided text into the clipboard (with the secrets redacted)
 	ToClipboardCopy(String),
+	// Copy the provided text into the clipboard as is (explicit "copy raw")
+	ToClipboardCopyRaw(String),
 
 	// Open the file at the given path
 	OpenFile(String),
+
+	// Open the url in the default browser
+	OpenUrl(String),
+}
+
+impl UiAction {
+	/// Returns the hyperlink target (file path or url) if this action opens a link.
+	pub fn link_target(&self) -> Option<&str> {
+		match self {
+			UiAction::OpenFile(path) => Some(path),
+			UiAction::OpenUrl(url) => Some(url),
+			_ => None,
+		}
+	}
 }
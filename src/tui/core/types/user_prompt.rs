@@ -0,0 +1,127 @@
+//! A pending user prompt (e.g., from `aip.flow.ask_user`, `aip.flow.approve`, or a `confirm_writes` approval), with its input state.
+//!
+//! - With choices, `Left`/`Right` (or `Tab`) or the choice number select the choice.
+//! - Without choices, the keys edit the text input.
+//! - `Enter` sends the answer, `Esc` cancels (the answer is then `nil` in Lua).
+
+use crate::tui_v1::PromptParams;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug)]
+pub struct UserPrompt {
+	params: PromptParams,
+	input: String,
+	choice_idx: usize,
+}
+
+/// The outcome of a key for the user prompt
+pub enum UserPromptKeyRes {
+	/// The key changed (or not) the input, the prompt is still pending
+	Pending,
+	/// The prompt got answered or cancelled (and the answer sent)
+	Done,
+}
+
+/// Constructor
+impl UserPrompt {
+	pub fn new(params: PromptParams) -> Self {
+		let choice_idx = params
+			.default
+			.as_deref()
+			.and_then(|default| params.choices.iter().position(|c| c.eq_ignore_ascii_case(default)))
+			.unwrap_or_default();
+		Self {
+			params,
+			input: String::new(),
+			choice_idx,
+		}
+	}
+}
+
+/// Getters
+impl UserPrompt {
+	pub fn message(&self) -> &str {
+		self.params.message.trim()
+	}
+
+	pub fn choices(&self) -> &[String] {
+		&self.params.choices
+	}
+
+	pub fn default(&self) -> Option<&str> {
+		self.params.default.as_deref()
+	}
+
+	pub fn preview(&self) -> Option<&str> {
+		self.params.preview.as_deref()
+	}
+
+	pub fn input(&self) -> &str {
+		&self.input
+	}
+
+	pub fn choice_idx(&self) -> usize {
+		self.choice_idx
+	}
+}
+
+/// Input
+impl UserPrompt {
+	/// Process the key, and send the answer on `Enter` (or None on `Esc`).
+	pub fn on_key(&mut self, key: &KeyEvent) -> UserPromptKeyRes {
+		let choices_len = self.params.choices.len();
+
+		match key.code {
+			KeyCode::Esc => {
+				self.send(None);
+				return UserPromptKeyRes::Done;
+			}
+			KeyCode::Enter => {
+				let answer = match self.params.choices.get(self.choice_idx) {
+					Some(choice) => choice.clone(),
+					None => self.input.clone(),
+				};
+				self.send(Some(answer));
+				return UserPromptKeyRes::Done;
+			}
+			_ => (),
+		}
+
+		// -- Choices
+		if choices_len > 0 {
+			match key.code {
+				KeyCode::Left | KeyCode::Up | KeyCode::BackTab => {
+					self.choice_idx = (self.choice_idx + choices_len - 1) % choices_len;
+				}
+				KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+					self.choice_idx = (self.choice_idx + 1) % choices_len;
+				}
+				KeyCode::Char(c)
+					if let Some(num) = c.to_digit(10)
+						&& num >= 1 && (num as usize) <= choices_len =>
+				{
+					self.choice_idx = num as usize - 1;
+				}
+				_ => (),
+			}
+		}
+		// -- Text input
+		else {
+			match key.code {
+				KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.input.push(c),
+				KeyCode::Backspace => {
+					self.input.pop();
+				}
+				_ => (),
+			}
+		}
+
+		UserPromptKeyRes::Pending
+	}
+
+	fn send(&self, answer: Option<String>) {
+		// NOTE: The one shot channel has a capacity of 1, so this does not block.
+		//       If it fails, the asking task is already gone (e.g., cancelled), so nothing to do.
+		let _ = self.params.one_shot_res.clone().send_sync(answer);
+	}
+}
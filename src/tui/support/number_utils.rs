@@ -13,3 +13,61 @@ pub fn offset_and_clamp_option_idx_in_len(idx: &Option<i32>, offset: i32, len: u
 		(_, None) => Some(0),
 	}
 }
+
+/// Compare two display values (e.g., the task `_ui.cols` values), numerically when both are numbers
+/// with an eventual size suffix (e.g., `800b`, `12kb`, `1.5MB`), otherwise as text.
+///
+/// The numeric values are ordered before the text ones.
+pub fn cmp_display_values(a: &str, b: &str) -> std::cmp::Ordering {
+	match (parse_display_number(a), parse_display_number(b)) {
+		(Some(a), Some(b)) => a.total_cmp(&b),
+		(Some(_), None) => std::cmp::Ordering::Less,
+		(None, Some(_)) => std::cmp::Ordering::Greater,
+		(None, None) => a.cmp(b),
+	}
+}
+
+fn parse_display_number(val: &str) -> Option<f64> {
+	let val = val.trim();
+	let num_end = val
+		.char_indices()
+		.find(|(i, c)| !(c.is_ascii_digit() || *c == '.' || (*i == 0 && *c == '-')))
+		.map(|(i, _)| i)
+		.unwrap_or(val.len());
+	let num: f64 = val[..num_end].parse().ok()?;
+
+	let factor = match val[num_end..].trim().to_lowercase().as_str() {
+		"" | "b" | "%" => 1.,
+		"k" | "kb" => 1e3,
+		"m" | "mb" => 1e6,
+		"g" | "gb" => 1e9,
+		_ => return None,
+	};
+
+	Some(num * factor)
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_tui_number_utils_cmp_display_values() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut vals = vec!["rust", "12kb", "800b", "1.5MB", "go", "9"];
+
+		// -- Exec
+		vals.sort_by(|a, b| cmp_display_values(a, b));
+
+		// -- Check
+		assert_eq!(vals, vec!["9", "800b", "12kb", "1.5MB", "go", "rust"]);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
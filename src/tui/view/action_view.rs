@@ -1,4 +1,4 @@
-use crate::tui::core::{AppState, LinkZones, UiAction};
+use crate::tui::core::{AppState, LinkZones, RunTab, UiAction};
 use crate::tui::style;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -62,8 +62,68 @@ impl StatefulWidget for ActionView {
 			"] Cancel Run  ",
 			UiAction::CancelRun,
 		);
+		let p_label = if state.runs_paused() { "] Resume  " } else { "] Pause  " };
+		push_action(&mut all_spans, &mut link_zones, "p", p_label, UiAction::TogglePauseRun);
 		push_action(&mut all_spans, &mut link_zones, "q", "] Quit  ", UiAction::Quit);
 		push_action(&mut all_spans, &mut link_zones, "n", n_label, UiAction::ToggleRunsNav);
+		let h_label = if state.is_history_mode() {
+			"] Live Runs  "
+		} else {
+			"] History  "
+		};
+		push_action(&mut all_spans, &mut link_zones, "h", h_label, UiAction::ToggleHistory);
+		let v_label = if state.has_split_run() {
+			"] Close Split  "
+		} else {
+			"] Split  "
+		};
+		push_action(&mut all_spans, &mut link_zones, "v", v_label, UiAction::ToggleSplitRun);
+		push_action(
+			&mut all_spans,
+			&mut link_zones,
+			"I",
+			"] Pack Info  ",
+			UiAction::ShowPackInfo,
+		);
+
+		// -- Task redo actions (tasks tab only)
+		if state.run_tab() == RunTab::Tasks {
+			push_action(
+				&mut all_spans,
+				&mut link_zones,
+				"E",
+				"] Edit Prompt  ",
+				UiAction::EditTaskPrompt,
+			);
+			push_action(
+				&mut all_spans,
+				&mut link_zones,
+				"R",
+				"] Redo Task(s)  ",
+				UiAction::RedoTask,
+			);
+			push_action(
+				&mut all_spans,
+				&mut link_zones,
+				"F",
+				"] Redo Failed  ",
+				UiAction::RedoFailedTasks,
+			);
+			push_action(
+				&mut all_spans,
+				&mut link_zones,
+				"C",
+				"] Cancel  ",
+				UiAction::CancelSelectedTasks,
+			);
+			push_action(
+				&mut all_spans,
+				&mut link_zones,
+				"X",
+				"] Export  ",
+				UiAction::ExportSelectedTasks,
+			);
+		}
 
 		all_spans.push(Span::raw("  "));
 
@@ -76,6 +136,20 @@ impl StatefulWidget for ActionView {
 			UiAction::CycleTasksOverviewMode,
 		);
 
+		// -- User quick actions
+		if !state.quick_actions().is_empty() {
+			all_spans.push(Span::raw("  "));
+			for quick_action in state.quick_actions() {
+				push_action(
+					&mut all_spans,
+					&mut link_zones,
+					&quick_action.key.to_string(),
+					&format!("] {}  ", quick_action.label),
+					UiAction::QuickAction(quick_action.key),
+				);
+			}
+		}
+
 		let mut line = Line::from(all_spans);
 
 		// -- Handle mouse hover and click
@@ -1,7 +1,9 @@
 use crate::model::{Log, LogKind, Stage};
 use crate::tui::style;
 use crate::tui::view::comp;
-use ratatui::style::Color;
+use crate::tui::view::support::{IMAGE_PREVIEW_HEIGHT, is_image_path};
+use crate::types::uc::{self, MarkerLevel};
+use ratatui::style::{Color, Style};
 use ratatui::text::Line;
 
 /// NOTE: Add empty line after each log section
@@ -46,15 +48,13 @@ pub fn ui_for_log(log: &Log, max_width: u16, path_color: Option<Color>) -> Vec<L
 		(_, _) => "No Step not MSG for log",
 	};
 
-	let marker_txt_style = match kind {
-		LogKind::RunStep => ("Sys Step", style::STL_SECTION_MARKER),
-		LogKind::SysInfo => ("Sys Info", style::STL_SECTION_MARKER),
-		LogKind::SysWarn => ("Sys Warn", style::STL_SECTION_MARKER),
-		LogKind::SysError => ("Sys Error", style::STL_SECTION_MARKER),
-		LogKind::SysDebug => ("Sys Debug", style::STL_SECTION_MARKER),
-		LogKind::AgentPrint => ("Print:", style::STL_SECTION_MARKER),
-		LogKind::AgentSkip => ("■ Skip:", style::STL_SECTION_MARKER_SKIP),
-	};
+	// -- Timeline marker (from `aip.ui.marker(..)`)
+	if kind == LogKind::AgentMarker {
+		let (marker_txt_style, content, _) = timeline_marker_parts(log);
+		return super::ui_for_marker_section_str(&content, marker_txt_style, max_width, None, None, None, path_color);
+	}
+
+	let marker_txt_style = marker_txt_style_for_kind(kind);
 
 	super::ui_for_marker_section_str(content, marker_txt_style, max_width, None, None, None, path_color)
 }
@@ -89,28 +89,33 @@ pub fn ui_for_logs_with_hover<'a>(
 			continue;
 		};
 
-		// Prepare the original (pre-format) content to be copied on click.
-		let raw_content: String = match (log.message.as_ref(), log.kind.as_ref()) {
-			(_, Some(LogKind::RunStep)) => log.step_as_str().to_string(),
-			(Some(msg), _) => msg.clone(),
-			_ => "No Step not MSG for log".to_string(),
-		};
-
-		let marker_txt_style = match kind {
-			LogKind::RunStep => ("Sys Step", style::STL_SECTION_MARKER),
-			LogKind::SysInfo => ("Sys Info", style::STL_SECTION_MARKER),
-			LogKind::SysWarn => ("Sys Warn", style::STL_SECTION_MARKER),
-			LogKind::SysError => ("Sys Error", style::STL_SECTION_MARKER),
-			LogKind::SysDebug => ("Sys Debug", style::STL_SECTION_MARKER),
-			LogKind::AgentPrint => ("Print:", style::STL_SECTION_MARKER),
-			LogKind::AgentSkip => ("■ Skip:", style::STL_SECTION_MARKER_SKIP),
-		};
-
-		let is_hover_target = is_hover_log(log);
-		let action = if is_hover_target {
-			Some(UiAction::ToClipboardCopy(raw_content.clone()))
+		let mut image_link: Option<String> = None;
+
+		let (marker_txt_style, raw_content, action) = if kind == LogKind::AgentMarker {
+			let (marker_txt_style, label, link) = timeline_marker_parts(log);
+			image_link = link.clone().filter(|link| !is_url(link) && is_image_path(link));
+			// The link (if any) is opened on click (url or file), otherwise, copy the label.
+			let action = match link {
+				Some(link) if is_url(&link) => UiAction::OpenUrl(link),
+				Some(link) => UiAction::OpenFile(link),
+				None => UiAction::ToClipboardCopy(label.clone()),
+			};
+			(marker_txt_style, label, Some(action))
 		} else {
-			None
+			// Prepare the original (pre-format) content to be copied on click.
+			let raw_content: String = match (log.message.as_ref(), log.kind.as_ref()) {
+				(_, Some(LogKind::RunStep)) => log.step_as_str().to_string(),
+				(Some(msg), _) => msg.clone(),
+				_ => "No Step not MSG for log".to_string(),
+			};
+
+			let is_hover_target = is_hover_log(log);
+			let action = if is_hover_target {
+				Some(UiAction::ToClipboardCopy(raw_content.clone()))
+			} else {
+				None
+			};
+			(marker_txt_style_for_kind(kind), raw_content, action)
 		};
 
 		let lines = super::ui_for_marker_section_str(
@@ -125,6 +130,14 @@ pub fn ui_for_logs_with_hover<'a>(
 
 		all_lines.extend(lines);
 
+		// -- Reserve the lines for the image preview (rendered over them)
+		if let Some(image_link) = image_link {
+			let x_offset = super::marker_width_for_marker_txt(marker_txt_style.0) as u16 + 1;
+			link_zones.push_image_zone(0, x_offset, IMAGE_PREVIEW_HEIGHT, image_link);
+			all_lines.extend((0..IMAGE_PREVIEW_HEIGHT).map(|_| Line::default()));
+			link_zones.inc_current_line_by(IMAGE_PREVIEW_HEIGHT as usize);
+		}
+
 		// Add empty separator line (do not attach zones to this line)
 		all_lines.push(Line::default());
 		link_zones.inc_current_line_by(1);
@@ -137,6 +150,7 @@ pub fn is_hover_log(log: &Log) -> bool {
 	match log.kind {
 		Some(LogKind::AgentPrint) => true,
 		Some(LogKind::AgentSkip) => true,
+		Some(LogKind::AgentMarker) => true,
 		Some(LogKind::SysInfo) => {
 			if let Some(msg) = log.message.as_deref() {
 				msg.to_ascii_lowercase().contains("ping")
@@ -147,3 +161,50 @@ pub fn is_hover_log(log: &Log) -> bool {
 		_ => false,
 	}
 }
+
+// region:    --- Support
+
+fn marker_txt_style_for_kind(kind: LogKind) -> (&'static str, Style) {
+	match kind {
+		LogKind::RunStep => ("Sys Step", style::STL_SECTION_MARKER),
+		LogKind::SysInfo => ("Sys Info", style::STL_SECTION_MARKER),
+		LogKind::SysWarn => ("Sys Warn", style::STL_SECTION_MARKER),
+		LogKind::SysError => ("Sys Error", style::STL_SECTION_MARKER),
+		LogKind::SysDebug => ("Sys Debug", style::STL_SECTION_MARKER),
+		LogKind::AgentPrint => ("Print:", style::STL_SECTION_MARKER),
+		LogKind::AgentSkip => ("■ Skip:", style::STL_SECTION_MARKER_SKIP),
+		LogKind::AgentMarker => ("◆ Mark:", style::STL_SECTION_MARKER),
+	}
+}
+
+/// Returns the `((marker_txt, marker_style), label_with_link, link)` for a `LogKind::AgentMarker` log.
+fn timeline_marker_parts(log: &Log) -> ((&'static str, Style), String, Option<String>) {
+	let Some(marker) = log
+		.message
+		.as_deref()
+		.and_then(|msg| serde_json::from_str::<uc::TimelineMarker>(msg).ok())
+	else {
+		let content = log.message.clone().unwrap_or_else(|| "No marker content".to_string());
+		return (("◆ Mark:", style::STL_SECTION_MARKER), content, None);
+	};
+
+	let marker_txt_style = match marker.level {
+		MarkerLevel::Info => ("◆ Info:", style::STL_SECTION_MARKER_INPUT),
+		MarkerLevel::Success => ("◆ Done:", style::STL_SECTION_MARKER_OUTPUT),
+		MarkerLevel::Warn => ("◆ Warn:", style::STL_SECTION_MARKER_AI),
+		MarkerLevel::Error => ("◆ Error:", style::STL_SECTION_MARKER_ERR),
+	};
+
+	let content = match marker.link.as_deref() {
+		Some(link) => format!("{}\n→ {link}", marker.label),
+		None => marker.label,
+	};
+
+	(marker_txt_style, content, marker.link)
+}
+
+fn is_url(link: &str) -> bool {
+	link.starts_with("http://") || link.starts_with("https://")
+}
+
+// endregion: --- Support
@@ -67,7 +67,7 @@ pub fn ui_for_marker_section_str(
 		let segments = support::segment_line_path(line_content);
 
 		for seg in segments {
-			let style = if seg.file_path.is_some() {
+			let style = if seg.file_path.is_some() || seg.url.is_some() {
 				style::style_text_path(false, path_color)
 			} else {
 				style::STL_SECTION_TXT
@@ -78,6 +78,8 @@ pub fn ui_for_marker_section_str(
 			if let Some(lz) = lz_opt.as_mut() {
 				if let Some(path) = seg.file_path {
 					lz.push_link_zone(rel_line_idx, span_idx, 1, UiAction::OpenFile(path.to_string()));
+				} else if let Some(url) = seg.url {
+					lz.push_link_zone(rel_line_idx, span_idx, 1, UiAction::OpenUrl(url.to_string()));
 				} else if let (Some(gid), Some(act)) = (gid_opt, main_action_opt) {
 					lz.push_group_zone(rel_line_idx, span_idx, 1, gid, act.clone());
 				}
@@ -192,7 +194,7 @@ pub fn ui_for_marker_section(
 
 // region:    --- Support
 
-fn marker_width_for_marker_txt(marker_txt: &str) -> usize {
+pub(super) fn marker_width_for_marker_txt(marker_txt: &str) -> usize {
 	marker_txt.chars().count().max(MARKER_MIN_WIDTH)
 }
 
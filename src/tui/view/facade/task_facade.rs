@@ -1,10 +1,12 @@
 use crate::model::{EndState, RunningState, Task};
+use crate::run::{FINISH_CONTENT_FILTER, FINISH_MAX_TOKENS};
 use crate::support::text;
 use crate::tui::style;
 use crate::tui::support::UiExt as _;
 use crate::tui::view::comp::{self, el_running_ico};
 use ratatui::style::{Style, Stylize as _};
 use ratatui::text::Span;
+use std::collections::BTreeMap;
 
 impl Task {
 	pub fn fmt_label(&self, tasks_len: usize) -> String {
@@ -16,6 +18,30 @@ impl Task {
 		}
 	}
 
+	/// The display columns (from the data stage `_ui.cols`), empty if none
+	pub fn ui_cols(&self) -> BTreeMap<String, String> {
+		self.ui_cols
+			.as_deref()
+			.and_then(|cols| serde_json::from_str(cols).ok())
+			.unwrap_or_default()
+	}
+
+	/// The sorted display column names of all the tasks
+	pub fn ui_col_names(tasks: &[Task]) -> Vec<String> {
+		let names: std::collections::BTreeSet<String> =
+			tasks.iter().flat_map(|task| task.ui_cols().into_keys()).collect();
+		names.into_iter().collect()
+	}
+
+	/// The badge of the abnormal AI finish reasons (e.g., truncated by the max tokens)
+	pub fn fmt_finish_badge(&self) -> Option<&'static str> {
+		match self.finish_reason.as_deref()? {
+			FINISH_MAX_TOKENS => Some("[⚠ truncated: max tokens]"),
+			FINISH_CONTENT_FILTER => Some("[⚠ content filtered]"),
+			_ => None,
+		}
+	}
+
 	pub fn ui_label(&self, prefix: Option<&'static str>, width: u16, tasks_len: usize) -> Vec<Span<'static>> {
 		// base spans with the running icon
 		let mut spans: Vec<Span<'static>> = Vec::new();
@@ -104,10 +130,51 @@ impl Task {
 		spans
 	}
 
+	/// The progress bar (from `aip.task.set_progress`), empty if no progress
+	pub fn ui_progress(&self, width: u16) -> Vec<Span<'static>> {
+		let Some(progress) = self.progress else {
+			return Vec::new();
+		};
+
+		let mut spans = vec![
+			Span::styled("Progress:", style::STL_SECTION_MARKER_OUTPUT),
+			Span::styled(" ", style::STL_SECTION_MARKER_OUTPUT), // gap
+		];
+
+		let content_width = width.saturating_sub(spans.x_width()) as usize;
+		let bar_width = content_width.min(20).saturating_sub(2);
+		let filled = ((progress * bar_width as f64).round() as usize).min(bar_width);
+		let pct = format!(" {:>3.0}% ", progress * 100.);
+		let msg = self.progress_msg.as_deref().unwrap_or_default().replace("\n", " ");
+
+		let rest_width = content_width.saturating_sub(bar_width + pct.len() + 1);
+		let msg = text::truncate_with_ellipsis(&msg, rest_width, "..");
+		let msg = format!("{msg:<rest_width$}");
+
+		spans.push(Span::styled(
+			" ".repeat(filled),
+			Style::new().bg(style::CLR_BKG_RUNNING_DONE),
+		));
+		spans.push(Span::styled(
+			" ".repeat(bar_width - filled),
+			Style::new().bg(style::CLR_BKG_400),
+		));
+		spans.push(Span::raw(pct));
+		spans.push(Span::styled(msg, style::STL_FIELD_VAL));
+
+		spans
+	}
+
 	pub fn ui_skip(&self, width: u16) -> Vec<Span<'static>> {
 		if self.has_skip() {
+			// NOTE: The cached tasks are skipped as unchanged since the last run (agent option `incremental`)
+			let label = if self.cached == Some(true) {
+				"  Cached:"
+			} else {
+				" Skipped:"
+			};
 			let mut spans = vec![
-				Span::styled(" Skipped:", style::STL_SECTION_MARKER_SKIP),
+				Span::styled(label, style::STL_SECTION_MARKER_SKIP),
 				Span::styled(" ", style::STL_SECTION_MARKER_SKIP), // gap
 			];
 
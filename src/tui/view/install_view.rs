@@ -102,14 +102,14 @@ fn render_installing(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 
 	let mut detail_msg = String::new();
 	if let Some(work_id) = work_id
-		&& let Ok(work) = WorkBmc::get(state.mm(), work_id)
+		&& let Ok(work) = WorkBmc::get(state.live_mm(), work_id)
 		&& let Some(msg) = work.message
 	{
 		detail_msg = msg;
 	}
 
 	let mut log_msg = String::new();
-	if let Ok(logs) = LogBmc::list_for_run_only(state.mm(), 0.into())
+	if let Ok(logs) = LogBmc::list_for_run_only(state.live_mm(), 0.into())
 		&& let Some(last_log) = logs.last()
 		&& let Some(msg) = &last_log.message
 	{
@@ -148,7 +148,7 @@ fn render_installed(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 	let mut pack_info = "Unknown pack".to_string();
 
 	if let Some(work_id) = work_id
-		&& let Ok(work) = WorkBmc::get(state.mm(), work_id)
+		&& let Ok(work) = WorkBmc::get(state.live_mm(), work_id)
 		&& let Some(msg) = work.message
 	{
 		pack_info = msg;
@@ -1,4 +1,4 @@
-use super::{ActionView, ConfigView, InstallView, RunsView, SumView};
+use super::{ActionView, ConfigView, InstallView, PackInfoView, RunsView, SumView, UserPromptView};
 use crate::model::ErrRec;
 use crate::tui::AppState;
 use crate::tui::core::AppStage;
@@ -43,7 +43,8 @@ impl StatefulWidget for MainView {
 			| AppStage::Installing
 			| AppStage::Installed
 			| AppStage::PromptInstall(_)
-			| AppStage::Config(_) => {
+			| AppStage::Config(_)
+			| AppStage::PackInfo => {
 				if state.show_runs() {
 					RunMainView::clear_scroll_idens(state);
 					RunsView.render(content_a, buf, state);
@@ -62,6 +63,11 @@ impl StatefulWidget for MainView {
 			}
 		}
 
+		// -- Render the pending user prompt (e.g., `aip.flow.ask_user`)
+		if state.user_prompt().is_some() {
+			UserPromptView.render(content_a, buf, state);
+		}
+
 		// -- Render action
 		ActionView.render(action_a, buf, state);
 
@@ -70,6 +76,11 @@ impl StatefulWidget for MainView {
 			ConfigView.render(content_a, buf, state);
 		}
 
+		// -- Render popup pack info
+		if matches!(state.stage(), AppStage::PackInfo) {
+			PackInfoView.render(content_a, buf, state);
+		}
+
 		// -- Render popup overlay last (on top)
 		PopupOverlay.render(area, buf, state);
 	}
@@ -6,28 +6,36 @@ mod action_view;
 mod config_view;
 mod install_view;
 mod main_view;
+mod pack_info_view;
 mod popup_view;
+mod run_chat_view;
 mod run_main_view;
 mod run_overview;
+mod run_split_view;
 mod run_tasks_view;
 mod runs_nav_view;
 mod runs_view;
 mod sum_view;
 mod support;
 mod task_view;
+mod user_prompt_view;
 
 pub use action_view::*;
 pub use config_view::*;
 pub use install_view::*;
 pub use main_view::*;
+pub use pack_info_view::*;
 pub use popup_view::*;
+pub use run_chat_view::*;
 pub use run_main_view::*;
 pub use run_overview::*;
+pub use run_split_view::*;
 pub use run_tasks_view::*;
 pub use runs_nav_view::*;
 pub use runs_view::*;
 pub use sum_view::*;
 pub use task_view::*;
+pub use user_prompt_view::*;
 
 pub mod comp;
 pub mod style;
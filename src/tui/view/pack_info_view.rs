@@ -0,0 +1,152 @@
+//! The pack detail view (`I` key): the info of the current run agent pack (the `aip info <pack>` data).
+//!
+//! - The `pack.toml` metadata, the declared permissions, the agents (with their `# Meta`), the README, and the changelog.
+//! - `Up`/`Down` (and `PageUp`/`PageDown`) scroll, `Esc` or `I` closes it.
+
+use crate::exec::PackInfo;
+use crate::tui::core::ScrollIden;
+use crate::tui::view::{comp, support};
+use crate::tui::{AppState, style};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Margin, Rect};
+use ratatui::style::{Style, Stylize as _};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
+
+pub struct PackInfoView;
+
+impl StatefulWidget for PackInfoView {
+	type State = AppState;
+
+	fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		const SCROLL_IDEN: ScrollIden = ScrollIden::PackInfoContent;
+
+		// -- Layout centered popup (same as the config popup)
+		let popup_area = Rect {
+			x: area.x + 5,
+			y: area.y + 2,
+			width: area.width.saturating_sub(10),
+			height: area.height.saturating_sub(4),
+		};
+		let content_a = popup_area.inner(Margin::new(2, 1));
+
+		// -- Build the lines
+		let Some(pack_info) = state.pack_info() else {
+			return;
+		};
+		let title = format!(" PACK INFO - {} ", pack_info.pack_ref);
+		let lines = ui_for_pack_info(pack_info, content_a.width.saturating_sub(3)); // for scroll bar
+
+		// -- Clear area (black background)
+		Block::new().bg(style::CLR_BKG_BLACK).render(popup_area, buf);
+
+		Block::bordered()
+			.border_style(style::CLR_TXT_BLUE)
+			.title(title)
+			.title_style(style::STL_POPUP_TITLE)
+			.title_bottom(Line::styled(" [Esc] Close ", style::STL_POPUP_TITLE).right_aligned())
+			.render(popup_area, buf);
+
+		state.set_scroll_area(SCROLL_IDEN, content_a);
+		let line_count = lines.len();
+		let scroll = state.clamp_scroll(SCROLL_IDEN, line_count);
+
+		Paragraph::new(lines).scroll((scroll, 0)).render(content_a, buf);
+
+		// -- Render Scrollbar
+		let content_size = line_count.saturating_sub(content_a.height as usize);
+		let mut scrollbar_state = ScrollbarState::new(content_size).position(scroll as usize);
+
+		let scrollbar = Scrollbar::default()
+			.orientation(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+			.begin_symbol(Some("▲"))
+			.end_symbol(Some("▼"));
+		scrollbar.render(content_a, buf, &mut scrollbar_state);
+	}
+}
+
+// region:    --- UI Builders
+
+fn ui_for_pack_info(pack_info: &PackInfo, max_width: u16) -> Vec<Line<'static>> {
+	let mut all_lines: Vec<Line<'static>> = Vec::new();
+	let mut push_section = |content: &str, marker_txt: &str, marker_style: Style| {
+		let lines =
+			comp::ui_for_marker_section_str(content, (marker_txt, marker_style), max_width, None, None, None, None);
+		support::extend_lines(&mut all_lines, lines, true);
+	};
+
+	// -- Header
+	let status: &'static str = pack_info.status.into();
+	let version = pack_info
+		.pack_toml
+		.version
+		.as_deref()
+		.map(|v| format!(" v{v}"))
+		.unwrap_or_default();
+	let header = format!("{}{version} ({status})\n{}", pack_info.pack_ref, pack_info.path);
+	push_section(&header, "Pack:", style::STL_SECTION_MARKER_INPUT);
+
+	// -- Metadata
+	let pack_toml = &pack_info.pack_toml;
+	let tags = (!pack_toml.tags.is_empty()).then(|| pack_toml.tags.join(", "));
+	let fields = [
+		("author", pack_toml.author.as_deref()),
+		("license", pack_toml.license.as_deref()),
+		("homepage", pack_toml.homepage.as_deref()),
+		("repo", pack_toml.repo.as_deref()),
+		("tags", tags.as_deref()),
+	];
+	let metadata = fields
+		.iter()
+		.filter_map(|(name, value)| value.map(|value| format!("{name:<9}: {value}")))
+		.collect::<Vec<_>>();
+	if !metadata.is_empty() {
+		push_section(&metadata.join("\n"), "Meta:", style::STL_SECTION_MARKER);
+	}
+
+	// -- Permissions (declared in the `pack.toml`, consented to at install)
+	let permissions = if pack_toml.capabilities.is_empty() && pack_toml.paths_allow.is_empty() {
+		"none declared".to_string()
+	} else {
+		let join = |items: &[String]| {
+			if items.is_empty() {
+				"-".to_string()
+			} else {
+				items.join(", ")
+			}
+		};
+		format!(
+			"capabilities: {}\npaths allow : {}",
+			join(&pack_toml.capabilities),
+			join(&pack_toml.paths_allow)
+		)
+	};
+	push_section(&permissions, "Perms:", style::STL_SECTION_MARKER_SKIP);
+
+	// -- Agents
+	for agent in pack_info.agents.iter() {
+		let meta = &agent.meta;
+		let mut content = agent.agent_ref.to_string();
+		if let Some(description) = meta.description() {
+			content.push_str(&format!("\n{}", description.trim()));
+		}
+		for param in meta.params() {
+			content.push_str(&format!("\n- {}", param.signature()));
+		}
+		push_section(&content, "Agent:", style::STL_SECTION_MARKER_AI);
+	}
+
+	// -- README
+	if let Some(readme) = pack_info.readme.as_deref() {
+		push_section(readme.trim_end(), "README:", style::STL_SECTION_MARKER_OUTPUT);
+	}
+
+	// -- CHANGELOG
+	if let Some(changelog) = pack_info.changelog.as_deref() {
+		push_section(changelog.trim_end(), "Changes:", style::STL_SECTION_MARKER_OUTPUT);
+	}
+
+	all_lines
+}
+
+// endregion: --- UI Builders
@@ -0,0 +1,130 @@
+//! The chat tab of a run: the run conversation, and the follow-up message input.
+//!
+//! - `Enter` activates the input, then sends the message (a new turn of the last run conversation), `Esc` leaves it.
+//! - The AI response of the follow-up is streamed until its AI stage ends (each turn is a run with one task).
+
+use crate::model::TaskBmc;
+use crate::tui::core::ScrollIden;
+use crate::tui::view::{comp, support};
+use crate::tui::{AppState, style};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
+
+pub struct RunChatView;
+
+/// Component scroll identifiers
+impl RunChatView {
+	const CONTENT_SCROLL_IDEN: ScrollIden = ScrollIden::ChatContent;
+
+	const SCROLL_IDENS: &[&ScrollIden] = &[&Self::CONTENT_SCROLL_IDEN];
+
+	pub fn clear_scroll_idens(state: &mut AppState) {
+		state.clear_scroll_zone_areas(Self::SCROLL_IDENS);
+	}
+}
+
+impl StatefulWidget for RunChatView {
+	type State = AppState;
+
+	fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		const SCROLL_IDEN: ScrollIden = RunChatView::CONTENT_SCROLL_IDEN;
+
+		// -- Layout Conversation | Input
+		let [content_a, _gap_a, input_a] = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![Constraint::Fill(1), Constraint::Length(1), Constraint::Length(1)])
+			.areas(area.inner(ratatui::layout::Margin::new(1, 0)));
+
+		state.set_scroll_area(SCROLL_IDEN, content_a);
+
+		// -- Build the conversation lines
+		let lines = ui_for_conversation(state, content_a.width.saturating_sub(3)); // for scroll bar
+
+		// -- Follow the streamed response
+		if state.chat_streaming_task().is_some() {
+			state.set_scroll(SCROLL_IDEN, u16::MAX);
+		}
+		let line_count = lines.len();
+		let scroll = state.clamp_scroll(SCROLL_IDEN, line_count);
+
+		Paragraph::new(lines).scroll((scroll, 0)).render(content_a, buf);
+
+		// -- Render Scrollbar
+		let content_size = line_count.saturating_sub(content_a.height as usize);
+		let mut scrollbar_state = ScrollbarState::new(content_size).position(scroll as usize);
+
+		let scrollbar = Scrollbar::default()
+			.orientation(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+			.begin_symbol(Some("▲"))
+			.end_symbol(Some("▼"));
+		scrollbar.render(content_a, buf, &mut scrollbar_state);
+
+		// -- Render the input
+		render_input(input_a, buf, state);
+	}
+}
+
+fn render_input(area: Rect, buf: &mut Buffer, state: &AppState) {
+	let line = match state.chat_input() {
+		Some(input) => Line::from(vec![
+			Span::styled("> ", style::STL_TXT_ACTION),
+			Span::styled(input.to_string(), style::STL_TXT_ACT),
+			Span::styled("_", style::STL_TXT_SEL),
+			Span::styled("   [Enter] Send   [Esc] Cancel", style::STL_FIELD_LBL_DARK),
+		]),
+		None => Line::from(vec![
+			Span::styled("> ", style::STL_TXT_ACTION),
+			Span::styled(
+				"[Enter] Write a follow-up message to the last run",
+				style::STL_FIELD_LBL_DARK,
+			),
+		]),
+	};
+	line.render(area, buf);
+}
+
+// region:    --- UI Builders
+
+fn ui_for_conversation(state: &AppState, max_width: u16) -> Vec<Line<'static>> {
+	let mut all_lines: Vec<Line<'static>> = Vec::new();
+
+	// -- The recorded turns
+	for msg in state.conv_msgs() {
+		let role = msg.role.as_deref().unwrap_or_default();
+		let content = msg.content.as_deref().unwrap_or_default();
+		support::extend_lines(&mut all_lines, ui_for_message(role, content, max_width), true);
+	}
+
+	// -- The turn being streamed (recorded when its AI stage ends)
+	if let Some(task) = state.chat_streaming_task() {
+		if let Ok(Some(input)) = TaskBmc::get_input_for_display(state.mm(), task) {
+			support::extend_lines(&mut all_lines, ui_for_message("user", &input, max_width), true);
+		}
+		let ai_stream = task.ai_stream.as_deref().unwrap_or_default();
+		let content = if ai_stream.is_empty() { "..." } else { ai_stream };
+		support::extend_lines(&mut all_lines, ui_for_message("assistant", content, max_width), true);
+	}
+
+	if all_lines.is_empty() {
+		all_lines.push(Line::styled(
+			"No conversation for this run (see the agent option `conversation = true`)",
+			style::STL_FIELD_LBL_DARK,
+		));
+	}
+
+	all_lines
+}
+
+fn ui_for_message(role: &str, content: &str, max_width: u16) -> Vec<Line<'static>> {
+	let marker: (&str, Style) = match role {
+		"user" => ("User:", style::STL_SECTION_MARKER_INPUT),
+		"assistant" => ("Assistant:", style::STL_SECTION_MARKER_AI),
+		other => (other, style::STL_SECTION_MARKER),
+	};
+	comp::ui_for_marker_section_str(content, marker, max_width, None, None, None, None)
+}
+
+// endregion: --- UI Builders
@@ -1,7 +1,8 @@
-use crate::tui::core::RunTab;
+use crate::tui::core::{RunTab, UiAction};
 use crate::tui::view::support::RectExt as _;
-use crate::tui::view::{RunOverviewView, RunTasksView, comp};
+use crate::tui::view::{RunChatView, RunOverviewView, RunSplitView, RunTasksView, comp};
 use crate::tui::{AppState, style};
+use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Stylize as _;
@@ -14,6 +15,8 @@ impl RunMainView {
 	pub fn clear_scroll_idens(state: &mut AppState) {
 		RunTasksView::clear_scroll_idens(state);
 		RunOverviewView::clear_scroll_idens(state);
+		RunSplitView::clear_scroll_idens(state);
+		RunChatView::clear_scroll_idens(state);
 	}
 }
 
@@ -23,6 +26,21 @@ impl StatefulWidget for RunMainView {
 	fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
 		Block::new().bg(style::CLR_BKG_GRAY_DARKER).render(area, buf);
 
+		// -- Pause/Resume the runs with the `p` key
+		if let Some(KeyCode::Char('p')) = state.last_app_event().as_key_code() {
+			state.set_action(UiAction::TogglePauseRun);
+		}
+
+		// -- Render the split view if a run is pinned (and another one selected)
+		if state.is_split_view() {
+			RunTasksView::clear_scroll_idens(state);
+			RunOverviewView::clear_scroll_idens(state);
+			RunChatView::clear_scroll_idens(state);
+			RunSplitView.render(area, buf, state);
+			return;
+		}
+		RunSplitView::clear_scroll_idens(state);
+
 		// -- Layout Header | Tabs | Tab Content
 		let [header_a, _space_1, tabs_a, tabs_line, tab_content_a] = Layout::default()
 			.direction(Direction::Vertical)
@@ -45,12 +63,19 @@ impl StatefulWidget for RunMainView {
 		match selected_tab {
 			RunTab::Overview => {
 				RunTasksView::clear_scroll_idens(state);
+				RunChatView::clear_scroll_idens(state);
 				RunOverviewView.render(tab_content_a, buf, state);
 			}
 			RunTab::Tasks => {
 				RunOverviewView::clear_scroll_idens(state);
+				RunChatView::clear_scroll_idens(state);
 				RunTasksView.render(tab_content_a, buf, state);
 			}
+			RunTab::Chat => {
+				RunOverviewView::clear_scroll_idens(state);
+				RunTasksView::clear_scroll_idens(state);
+				RunChatView.render(tab_content_a, buf, state);
+			}
 		}
 	}
 }
@@ -62,7 +87,11 @@ fn render_header(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 	let agent_name = state.current_run_agent_name();
 	let model_name = state.tasks_cummulative_models(VAL_1_WIDTH as usize);
 	let cost_txt = state.current_run_cost_fmt();
-	let concurrency_txt = state.current_run_concurrency_txt();
+	let mut concurrency_txt = state.current_run_concurrency_txt();
+	// The resources, when recorded, follow the concurrency (the last column fills the row)
+	if let Some(res_usage_txt) = state.current_run_res_usage_fmt() {
+		concurrency_txt = format!("{concurrency_txt}   {res_usage_txt}");
+	}
 
 	// Tasks progress and optional cumulative duration.
 
@@ -174,20 +203,22 @@ fn render_header(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 
 fn render_tabs(tabs_a: Rect, tabs_line_a: Rect, buf: &mut Buffer, state: &mut AppState) -> RunTab {
 	// -- Layout Header | Tabs | Tab Content
-	let [_, tab_overview_a, _, tab_tasks_a] = Layout::default()
+	let [_, tab_overview_a, _, tab_tasks_a, _, tab_chat_a] = Layout::default()
 		.direction(Direction::Horizontal)
 		.constraints(vec![
 			Constraint::Length(1),  // gap 1
 			Constraint::Length(12), // tab_overview_a
 			Constraint::Length(1),  // gap
 			Constraint::Length(11), // tab_tasks_a
+			Constraint::Length(1),  // gap
+			Constraint::Length(8),  // tab_chat_a
 		])
 		.areas(tabs_a);
 
 	// -- Process UI Event for the tab
 	// NOTE: There would be an argument to say that this could be in the process_app_state(..)
 	//       But then, it will requires to have perhaps too much inner knowledge
-	process_for_run_tab_state(state, tab_overview_a, tab_tasks_a);
+	process_for_run_tab_state(state, tab_overview_a, tab_tasks_a, tab_chat_a);
 
 	let run_tab = state.run_tab();
 
@@ -222,6 +253,16 @@ fn render_tabs(tabs_a: Rect, tabs_line_a: Rect, buf: &mut Buffer, state: &mut Ap
 			.render(tab_tasks_a, buf);
 	}
 
+	// -- Render Chat
+	let tab_3_style = match (run_tab == RunTab::Chat, state.is_last_mouse_over(tab_chat_a)) {
+		// (active, hover)
+		(true, true) => style::STL_TAB_ACTIVE_HOVER,
+		(true, false) => style::STL_TAB_ACTIVE,
+		(false, true) => style::STL_TAB_DEFAULT_HOVER,
+		(false, false) => style::STL_TAB_DEFAULT,
+	};
+	Paragraph::new("Chat").centered().style(tab_3_style).render(tab_chat_a, buf);
+
 	// -- Render Line
 	// Trick to have a single line of tab active bkg color
 	let repeated = "▔".repeat(tabs_line_a.width as usize);
@@ -234,15 +275,17 @@ fn render_tabs(tabs_a: Rect, tabs_line_a: Rect, buf: &mut Buffer, state: &mut Ap
 
 // region:    --- UI Event Processing
 
-fn process_for_run_tab_state(state: &mut AppState, overview_a: Rect, tasks_a: Rect) {
+fn process_for_run_tab_state(state: &mut AppState, overview_a: Rect, tasks_a: Rect, chat_a: Rect) {
 	// -- Set the tab to Overview if not tasks
-	// NOTE: here we are conservative.
-	if let Some(false) = state.current_run_has_task_stages()
+	// NOTE: here we are conservative (the chat tab stays, to follow up the run).
+	let is_chat_tab = state.run_tab() == RunTab::Chat;
+	if !is_chat_tab
+		&& let Some(false) = state.current_run_has_task_stages()
 		&& state.tasks().is_empty()
 	{
 		state.set_run_tab(RunTab::Overview);
 		return;
-	} else if state.current_run_has_skip() && state.tasks().is_empty() {
+	} else if !is_chat_tab && state.current_run_has_skip() && state.tasks().is_empty() {
 		state.set_run_tab(RunTab::Overview);
 		return;
 	}
@@ -257,6 +300,9 @@ fn process_for_run_tab_state(state: &mut AppState, overview_a: Rect, tasks_a: Re
 		} else if mouse_evt.is_over(tasks_a) {
 			state.set_run_tab(RunTab::Tasks);
 			state.clear_mouse_evts(true);
+		} else if mouse_evt.is_over(chat_a) {
+			state.set_run_tab(RunTab::Chat);
+			state.clear_mouse_evts(true);
 		}
 	}
 }
@@ -1,14 +1,20 @@
 use crate::model::{EndState, Log, LogBmc, PinBmc, RunningState, Stage, Task};
+use crate::support::text;
 use crate::tui::AppState;
-use crate::tui::core::{LinkZones, ScrollIden, UiAction};
+use crate::tui::core::{LinkZones, OverviewTasksFilter, OverviewTasksMode, OverviewTasksSort, ScrollIden, UiAction};
 use crate::tui::support::UiExt as _;
 use crate::tui::view::support::{self, RectExt as _};
 use crate::tui::view::{comp, style};
+use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Color;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
+use std::collections::BTreeMap;
+
+/// The max width of a task display column (from the data stage `_ui.cols`)
+const TASK_COL_MAX_WIDTH: usize = 16;
 
 /// Placeholder view for *Before All* tab.
 pub struct RunOverviewView;
@@ -41,10 +47,24 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 	// -- Init the scroll area
 	state.set_scroll_area(SCROLL_IDEN, area);
 
-	// -- Determine tasks mode
-	let tasks_len = state.tasks().len();
+	// -- Sort & filter the tasks (the legend is for all the tasks)
+	let all_tasks = state.tasks();
+	let tasks_sort = state.overview_tasks_sort();
+	let tasks_filter = state.overview_tasks_filter();
+	let tasks = sort_filter_tasks(all_tasks, tasks_sort, tasks_filter);
+	let tasks_legend = if all_tasks.is_empty() {
+		Vec::new()
+	} else {
+		ui_for_tasks_legend(all_tasks, tasks.len(), tasks_sort, tasks_filter)
+	};
 
-	let is_grid = state.overview_tasks_mode().is_grid(tasks_len);
+	// -- Determine tasks mode
+	// NOTE: In auto mode, the list is used when the tasks have display columns (the grid does not show them),
+	//       and when no task match the filter (to show the legend)
+	let is_grid = state.overview_tasks_mode().is_grid(all_tasks.len())
+		&& !(state.overview_tasks_mode() == OverviewTasksMode::Auto
+			&& all_tasks.iter().any(|task| task.ui_cols.is_some()))
+		&& !tasks.is_empty();
 
 	// -- Prep
 	let Some(run_id) = state.current_run_item().map(|r| r.id()) else {
@@ -99,9 +119,9 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 
 	let task_section_start = all_lines.len();
 	let tasks_section_line_count = if is_grid {
-		task_grid_line_count(state.tasks(), max_width)
+		task_grid_line_count(&tasks, max_width)
 	} else {
-		task_list_line_count(state.tasks())
+		task_list_line_count(&tasks, &tasks_legend)
 	};
 	let after_task_section_start = task_section_start + tasks_section_line_count;
 
@@ -142,7 +162,8 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 			Vec::new()
 		};
 		lines.extend(ui_for_task_grid_viewport(
-			state.tasks(),
+			&tasks,
+			tasks_legend,
 			max_width,
 			task_section_start,
 			scroll as usize,
@@ -158,7 +179,8 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 			Vec::new()
 		};
 		lines.extend(ui_for_task_list_viewport(
-			state.tasks(),
+			&tasks,
+			tasks_legend,
 			max_width,
 			task_section_start,
 			scroll as usize,
@@ -175,6 +197,7 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 	all_lines.extend(after_task_lines);
 
 	// -- Perform the Click on a link zone
+	let image_previews = support::image_previews_for_zones(link_zones.image_zones(), area, scroll);
 	let zones = link_zones.into_zones();
 
 	// First pass: detect which zone (if any) is hovered.
@@ -229,7 +252,12 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 			}
 		}
 
-		if state.is_mouse_up_only() {
+		// Open the link (file or url) under the cursor with the `o` key
+		if let Some(KeyCode::Char('o')) = state.last_app_event().as_key_code()
+			&& action.link_target().is_some()
+		{
+			state.set_action(action);
+		} else if state.is_mouse_up_only() {
 			state.set_action(action);
 			// Note: Little trick to not show hover on the next tasks tab screen
 			state.clear_mouse_evts(true);
@@ -237,8 +265,15 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState) {
 	}
 
 	// -- Render All Content
+	let hyperlinks = support::hyperlinks_for_zones(&zones, &all_lines, area, scroll);
 	let p = Paragraph::new(all_lines).scroll((scroll, 0));
 	p.render(area, buf);
+	for hyperlink in hyperlinks {
+		hyperlink.render(buf);
+	}
+	for image_preview in image_previews {
+		image_preview.render(buf);
+	}
 
 	// -- Render Scrollbar
 	let content_size = line_count.saturating_sub(area.height as usize);
@@ -287,14 +322,36 @@ fn ui_for_after_all(
 	)
 }
 
-fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones) -> Vec<Line<'static>> {
-	if tasks.is_empty() {
+fn ui_for_task_list(
+	tasks: &[Task],
+	legend: Vec<Span<'static>>,
+	max_width: u16,
+	link_zones: &mut LinkZones,
+) -> Vec<Line<'static>> {
+	// NOTE: The legend is still shown when no task match the filter
+	if tasks.is_empty() && legend.is_empty() {
 		return Vec::new();
 	}
 
 	// -- Prep
 	let tasks_len = tasks.len();
 
+	// -- The display columns (from the data stage `_ui.cols`)
+	let col_names = Task::ui_col_names(tasks);
+	let tasks_cols: Vec<BTreeMap<String, String>> = tasks.iter().map(|task| task.ui_cols()).collect();
+	let col_widths: Vec<usize> = col_names
+		.iter()
+		.map(|name| {
+			let max_val_width = tasks_cols
+				.iter()
+				.filter_map(|cols| cols.get(name))
+				.map(|val| val.chars().count())
+				.max()
+				.unwrap_or_default();
+			max_val_width.max(name.len()).min(TASK_COL_MAX_WIDTH)
+		})
+		.collect();
+
 	// let mut line: u16 = 0;
 	let (marker, marker_spacer) = tasks_marker();
 	let marker_width = marker.x_width();
@@ -306,6 +363,14 @@ fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones)
 	let gap_span = Span::raw("  ");
 	let gap_width = gap_span.width() as u16;
 
+	let cols_width: usize = col_widths.iter().map(|w| w + gap_width as usize).sum();
+	let content_width = content_width.saturating_sub(cols_width as u16);
+	let label_width = if tasks.iter().any(|task| task.label.is_some()) {
+		24
+	} else {
+		12
+	};
+
 	let mut all_lines: Vec<Vec<Span<'static>>> = Vec::new();
 
 	// Not used in this case
@@ -315,25 +380,33 @@ fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones)
 	let [label_a, _, input_a, _, _ai_a, _, output_a] = Layout::default()
 		.direction(Direction::Horizontal)
 		.constraints(vec![
-			Constraint::Length(12),        // label_a
-			Constraint::Length(gap_width), // gap
-			Constraint::Fill(3),           // input_a
-			Constraint::Length(gap_width), // gap
-			Constraint::Length(6),         // ai_a (hardcode in task.ui_ai())
-			Constraint::Length(gap_width), // gap
-			Constraint::Fill(5),           // output_a
+			Constraint::Length(label_width), // label_a
+			Constraint::Length(gap_width),   // gap
+			Constraint::Fill(3),             // input_a
+			Constraint::Length(gap_width),   // gap
+			Constraint::Length(6),           // ai_a (hardcode in task.ui_ai())
+			Constraint::Length(gap_width),   // gap
+			Constraint::Fill(5),             // output_a
 		])
 		.areas(Rect::new(0, 0, content_width, 1));
 
 	// --  Build the UI lines
-	for (idx, task) in tasks.iter().enumerate() {
+	for (task_idx, task) in tasks.iter().enumerate() {
 		let mut task_line = task.ui_label(None, label_a.width, tasks_len);
 		let task_id = task.id;
 
 		// -- Link Zone
 		// +2 for the space + ico (from the ui_label), 2 to take space and label text
 		// NOTE: This should probably be part of the task facade (should not make those assumption here)
-		link_zones.push_link_zone(idx, marker_prefix_spans_len + 2, 2, UiAction::GoToTask { task_id });
+		link_zones.push_link_zone(task_idx, marker_prefix_spans_len + 2, 2, UiAction::GoToTask { task_id });
+
+		// -- Add the display columns
+		for (name, width) in col_names.iter().zip(col_widths.iter()) {
+			task_line.push(gap_span.clone());
+			let val = tasks_cols[task_idx].get(name).map(|v| v.as_str()).unwrap_or_default();
+			let val = text::truncate_with_ellipsis(val, *width, "..");
+			task_line.push(Span::styled(format!("{val:<width$}"), style::STL_FIELD_VAL));
+		}
 
 		// -- Gap
 		task_line.push(gap_span.clone());
@@ -352,10 +425,13 @@ fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones)
 		// -- Gap
 		task_line.push(gap_span.clone());
 
-		// -- Add Output or skip
+		// -- Add Output or skip (or the progress, until the output)
 		if task.has_skip() {
 			let skip_spans = task.ui_skip(output_a.width);
 			task_line.extend(skip_spans);
+		} else if task.output_short.is_none() && task.progress.is_some() {
+			let progress_spans = task.ui_progress(output_a.width);
+			task_line.extend(progress_spans);
 		} else {
 			let output_spans = task.ui_output(output_a.width);
 			task_line.extend(output_spans);
@@ -367,9 +443,8 @@ fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones)
 	}
 
 	// -- render legend (on bottom)
-	// build legend_line
 	all_lines.push(Vec::new());
-	all_lines.push(ui_for_legend(tasks));
+	all_lines.push(legend);
 
 	// -- Build the marker component
 	comp::ui_for_marker_section(marker, marker_spacer, all_lines)
@@ -377,17 +452,18 @@ fn ui_for_task_list(tasks: &[Task], max_width: u16, link_zones: &mut LinkZones)
 
 fn ui_for_task_list_viewport(
 	tasks: &[Task],
+	legend: Vec<Span<'static>>,
 	max_width: u16,
 	task_section_start: usize,
 	scroll: usize,
 	viewport_height: usize,
 	link_zones: &mut LinkZones,
 ) -> Vec<Line<'static>> {
-	if tasks.is_empty() {
+	let section_line_count = task_list_line_count(tasks, &legend);
+	if section_line_count == 0 {
 		return Vec::new();
 	}
 
-	let section_line_count = task_list_line_count(tasks);
 	let section_end = task_section_start + section_line_count;
 	let viewport_end = scroll.saturating_add(viewport_height);
 
@@ -402,7 +478,7 @@ fn ui_for_task_list_viewport(
 		return Vec::new();
 	}
 
-	let full_lines = ui_for_task_list(tasks, max_width, link_zones);
+	let full_lines = ui_for_task_list(tasks, legend, max_width, link_zones);
 	// Keep only the visible logical task-section lines and let the caller insert
 	// the top padding corresponding to the current scroll offset.
 	full_lines.into_iter().skip(local_start).take(local_end - local_start).collect()
@@ -428,8 +504,12 @@ fn tasks_marker() -> (Vec<Span<'static>>, Vec<Span<'static>>) {
 	(marker, marker_spacer)
 }
 
-fn task_list_line_count(tasks: &[Task]) -> usize {
-	if tasks.is_empty() { 0 } else { tasks.len() + 2 }
+fn task_list_line_count(tasks: &[Task], legend: &[Span<'static>]) -> usize {
+	if tasks.is_empty() && legend.is_empty() {
+		0
+	} else {
+		tasks.len() + 2
+	}
 }
 
 fn task_grid_line_count(tasks: &[Task], max_width: u16) -> usize {
@@ -470,6 +550,7 @@ fn task_grid_layout(tasks: &[Task], max_width: u16) -> OverviewTaskGridLayout {
 
 fn ui_for_task_grid_viewport(
 	tasks: &[Task],
+	legend: Vec<Span<'static>>,
 	max_width: u16,
 	task_section_start: usize,
 	scroll: usize,
@@ -546,7 +627,7 @@ fn ui_for_task_grid_viewport(
 			};
 			let mut spans = prefix;
 			spans.push(Span::raw(" ".repeat(layout.marker_spacer_width as usize)));
-			spans.extend(ui_for_legend(tasks));
+			spans.extend(legend.clone());
 			lines.push(Line::from(spans));
 		}
 	}
@@ -554,10 +635,47 @@ fn ui_for_task_grid_viewport(
 	lines
 }
 
+/// Returns the tasks to show, filtered and sorted
+fn sort_filter_tasks(tasks: &[Task], sort: Option<&OverviewTasksSort>, filter: OverviewTasksFilter) -> Vec<Task> {
+	let tasks: Vec<Task> = tasks.iter().filter(|task| filter.matches(task)).cloned().collect();
+	match sort {
+		Some(sort) => sort.sort_tasks(tasks),
+		None => tasks,
+	}
+}
+
+/// The tasks legend, with the counts of all the tasks, and the sort and filter
+fn ui_for_tasks_legend(
+	all_tasks: &[Task],
+	shown_len: usize,
+	sort: Option<&OverviewTasksSort>,
+	filter: OverviewTasksFilter,
+) -> Vec<Span<'static>> {
+	let mut legend_line = ui_for_legend(all_tasks);
+
+	let sort_name = sort.map(|sort| sort.name()).unwrap_or("task");
+	legend_line.push(Span::styled("Sort [o]:", style::STL_FIELD_LBL));
+	legend_line.push(Span::raw(format!(" {sort_name}  ")));
+
+	legend_line.push(Span::styled("Filter [f]:", style::STL_FIELD_LBL));
+	if filter == OverviewTasksFilter::All {
+		legend_line.push(Span::raw(format!(" {}", filter.name())));
+	} else {
+		legend_line.push(Span::raw(format!(
+			" {} ({shown_len}/{})",
+			filter.name(),
+			all_tasks.len()
+		)));
+	}
+
+	legend_line
+}
+
 fn ui_for_legend(tasks: &[Task]) -> Vec<Span<'static>> {
 	let mut count_done = 0;
 	let mut count_waiting = 0;
 	let mut count_skip = 0;
+	let mut count_cached = 0;
 	let mut count_err = 0;
 	let mut count_ai = 0;
 
@@ -574,6 +692,7 @@ fn ui_for_legend(tasks: &[Task]) -> Vec<Span<'static>> {
 			RunningState::Ended(end_state) => match end_state {
 				Some(EndState::Ok) => count_done += 1,
 				Some(EndState::Err) => count_err += 1,
+				Some(EndState::Skip) if task.cached == Some(true) => count_cached += 1,
 				Some(EndState::Skip) => count_skip += 1,
 				Some(EndState::Cancel) => (), // TODO: handle cancel
 				None => (),
@@ -596,6 +715,10 @@ fn ui_for_legend(tasks: &[Task]) -> Vec<Span<'static>> {
 		legend_line.push(Span::styled("Skip:", style::CLR_BKG_RUNNING_SKIP));
 		legend_line.push(Span::raw(format!(" {count_skip:<num_width$} ")));
 	}
+	if count_cached > 0 {
+		legend_line.push(Span::styled("Cached:", style::CLR_BKG_RUNNING_SKIP));
+		legend_line.push(Span::raw(format!(" {count_cached:<num_width$} ")));
+	}
 	if count_waiting > 0 {
 		legend_line.push(Span::styled("Queue:", style::CLR_TXT_650));
 		legend_line.push(Span::raw(format!(" {count_waiting:<num_width$} ")));
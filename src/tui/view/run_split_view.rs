@@ -0,0 +1,256 @@
+//! The split-run view, to compare two runs side by side (e.g., same agent on two models).
+//!
+//! - The left pane is the pinned run (`v` key), the right pane is the selected run.
+//! - The task selection (`i`/`k` keys) and the content scroll are synchronized between the two panes.
+
+use crate::model::{Run, Task};
+use crate::support::text::format_duration_us;
+use crate::support::time::now_micro;
+use crate::tui::core::{LinkZones, RunItem, ScrollIden, UiAction};
+use crate::tui::support::ui_fmt_cost;
+use crate::tui::view::support::RectExt as _;
+use crate::tui::view::{comp, support, task_view};
+use crate::tui::{AppState, style};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Color;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
+
+/// Renders the pinned run and the selected run side by side.
+pub struct RunSplitView;
+
+/// Component scroll identifiers
+impl RunSplitView {
+	const CONTENT_SCROLL_IDEN: ScrollIden = ScrollIden::SplitContent;
+
+	const SCROLL_IDENS: &[&ScrollIden] = &[&Self::CONTENT_SCROLL_IDEN];
+
+	pub fn clear_scroll_idens(state: &mut AppState) {
+		state.clear_scroll_zone_areas(Self::SCROLL_IDENS);
+	}
+}
+
+impl StatefulWidget for RunSplitView {
+	type State = AppState;
+
+	fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		const SCROLL_IDEN: ScrollIden = RunSplitView::CONTENT_SCROLL_IDEN;
+
+		let (Some(split_run_item), Some(current_run_item)) = (state.split_run_item(), state.current_run_item()) else {
+			Line::raw("No runs to compare").render(area, buf);
+			return;
+		};
+		let split_run_item = split_run_item.clone();
+		let current_run_item = current_run_item.clone();
+
+		// -- Process the go to task (task selection is shared by the two panes)
+		if let Some(UiAction::GoToTask { task_id }) = state.action() {
+			if let Some(task_idx) = state.tasks().iter().position(|t| t.id == *task_id) {
+				state.set_task_idx(Some(task_idx));
+			}
+			state.clear_action();
+		}
+		let task_idx = state.task_idx();
+
+		// -- Layout Left | Right
+		let [left_a, _sep_a, right_a] = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(vec![Constraint::Fill(1), Constraint::Length(1), Constraint::Fill(1)])
+			.areas(area);
+
+		let [left_header_a, left_body_a] = pane_layout(left_a);
+		let [right_header_a, right_body_a] = pane_layout(right_a);
+
+		// -- Render headers
+		render_pane_header(
+			left_header_a,
+			buf,
+			"Pinned",
+			&split_run_item,
+			state.split_tasks(),
+			task_idx,
+		);
+		render_pane_header(
+			right_header_a,
+			buf,
+			"Selected",
+			&current_run_item,
+			state.tasks(),
+			task_idx,
+		);
+
+		// -- Build the bodies
+		// NOTE: Both bodies share the same scroll zone (the union of the two areas) for synchronized scrolling.
+		let body_area = left_body_a.union(right_body_a);
+		state.set_scroll_area(SCROLL_IDEN, body_area);
+
+		let path_color = (state.debug_clr() != 0).then(|| Color::Indexed(state.debug_clr()));
+		let left_lines = ui_for_pane_body(
+			state,
+			split_run_item.run(),
+			state.split_tasks().get(task_idx.unwrap_or_default()),
+			left_body_a.width.saturating_sub(1),
+			path_color,
+		);
+		let right_lines = ui_for_pane_body(
+			state,
+			current_run_item.run(),
+			state.current_task(),
+			right_body_a.width.saturating_sub(3), // for scroll bar
+			path_color,
+		);
+
+		// -- Clamp scroll (on the longest body)
+		let line_count = left_lines.len().max(right_lines.len());
+		let scroll = state.clamp_scroll(SCROLL_IDEN, line_count);
+
+		// -- Render bodies
+		Paragraph::new(left_lines).scroll((scroll, 0)).render(left_body_a, buf);
+		Paragraph::new(right_lines).scroll((scroll, 0)).render(right_body_a, buf);
+
+		// -- Render Scrollbar
+		let content_size = line_count.saturating_sub(body_area.height as usize);
+		let mut scrollbar_state = ScrollbarState::new(content_size).position(scroll as usize);
+
+		let scrollbar = Scrollbar::default()
+			.orientation(ratatui::widgets::ScrollbarOrientation::VerticalRight)
+			.begin_symbol(Some("▲"))
+			.end_symbol(Some("▼"));
+		scrollbar.render(body_area, buf, &mut scrollbar_state);
+	}
+}
+
+// region:    --- Support
+
+/// Pane layout: Header (3 rows) | Gap | Body
+fn pane_layout(area: Rect) -> [Rect; 2] {
+	let [header_a, _gap_a, body_a] = Layout::default()
+		.direction(Direction::Vertical)
+		.constraints(vec![
+			Constraint::Length(3), // header
+			Constraint::Max(1),    // gap
+			Constraint::Fill(1),   // body
+		])
+		.areas(area);
+
+	[header_a, body_a]
+}
+
+fn render_pane_header(
+	area: Rect,
+	buf: &mut Buffer,
+	pane_label: &str,
+	run_item: &RunItem,
+	tasks: &[Task],
+	task_idx: Option<usize>,
+) {
+	let run = run_item.run();
+
+	// -- Prepare Data
+	let agent_name = run.agent_name.as_deref().unwrap_or("no agent");
+	let task = task_idx.and_then(|idx| tasks.get(idx));
+	let model_name = task.and_then(|t| t.model_ov.as_deref()).or(run.model.as_deref()).unwrap_or("-");
+	let cost_txt = ui_fmt_cost(run_item.rolled_up_cost());
+	let duration_us = match (run.start, run.end) {
+		(Some(start), Some(end)) => end.as_i64() - start.as_i64(),
+		(Some(start), None) => now_micro() - start.as_i64(),
+		_ => 0,
+	};
+	let duration_txt = format_duration_us(duration_us);
+	let done_tasks = tasks.iter().filter(|t| t.is_ended()).count();
+	let task_txt = match task_idx {
+		Some(idx) if idx < tasks.len() => format!("{}/{} (done {done_tasks})", idx + 1, tasks.len()),
+		_ => format!("-/{} (done {done_tasks})", tasks.len()),
+	};
+
+	// -- Render Row 1 (pane title)
+	let title = Line::from(vec![
+		comp::el_running_ico_with_flow(run_item, run.flow_redo_count),
+		Span::raw(format!(" {pane_label} ")),
+		Span::styled(format!(" {agent_name} "), style::STL_FIELD_VAL),
+	]);
+	Paragraph::new(title).style(style::STL_TAB_ACTIVE).render(area.x_row(1), buf);
+
+	// -- Render Row 2 & 3
+	let [lbl_1, val_1, lbl_2, val_2] = Layout::default()
+		.direction(Direction::Horizontal)
+		.constraints(vec![
+			Constraint::Length(7), // Model/Task
+			Constraint::Fill(1),   //
+			Constraint::Length(9), // Cost/Duration
+			Constraint::Length(10),
+		])
+		.spacing(1)
+		.areas(area);
+
+	let fields = [
+		(2, ("Model:", model_name.to_string()), ("Cost:", cost_txt)),
+		(3, ("Task:", task_txt), ("Duration:", duration_txt)),
+	];
+	for (row, (lbl_1_txt, val_1_txt), (lbl_2_txt, val_2_txt)) in fields {
+		Paragraph::new(lbl_1_txt)
+			.style(style::STL_FIELD_LBL)
+			.right_aligned()
+			.render(lbl_1.x_row(row), buf);
+		Paragraph::new(val_1_txt)
+			.style(style::STL_FIELD_VAL)
+			.render(val_1.x_row(row), buf);
+		Paragraph::new(lbl_2_txt)
+			.style(style::STL_FIELD_LBL)
+			.right_aligned()
+			.render(lbl_2.x_row(row), buf);
+		Paragraph::new(val_2_txt)
+			.style(style::STL_FIELD_VAL)
+			.render(val_2.x_row(row), buf);
+	}
+}
+
+/// The task sections (input, AI, output, error) of a pane.
+/// NOTE: The panes are for comparison, so no hover/click on their content (link zones are discarded).
+fn ui_for_pane_body(
+	state: &AppState,
+	run: &Run,
+	task: Option<&Task>,
+	max_width: u16,
+	path_color: Option<Color>,
+) -> Vec<Line<'static>> {
+	let mut all_lines: Vec<Line<'static>> = Vec::new();
+
+	let Some(task) = task else {
+		all_lines.push(Line::raw("No task at this position"));
+		return all_lines;
+	};
+
+	let mm = state.mm();
+	let mut link_zones = LinkZones::default();
+
+	support::extend_lines(
+		&mut all_lines,
+		task_view::ui_for_input(mm, task, max_width, &mut link_zones, path_color),
+		false,
+	);
+	support::extend_lines(
+		&mut all_lines,
+		task_view::ui_for_ai(run, task, max_width, &mut link_zones, path_color),
+		true,
+	);
+	if task.output_short.is_some() {
+		support::extend_lines(
+			&mut all_lines,
+			task_view::ui_for_output(mm, task, max_width, &mut link_zones, path_color),
+			false,
+		);
+	}
+	if let Some(err_id) = task.end_err_id {
+		support::extend_lines(
+			&mut all_lines,
+			comp::ui_for_err(mm, err_id, max_width, path_color),
+			true,
+		);
+	}
+
+	all_lines
+}
+
+// endregion: --- Support
@@ -216,7 +216,8 @@ fn render_tasks_nav(area: Rect, buf: &mut Buffer, selection_in_view: bool, state
 	#[allow(clippy::needless_range_loop)] // this is ok there I think
 	for idx in start_idx..end_idx {
 		let task = &tasks[idx];
-		let mut line = Line::from(task.ui_label(Some(" "), area.width, tasks_len));
+		let prefix = if state.is_task_selected(task) { "*" } else { " " };
+		let mut line = Line::from(task.ui_label(Some(prefix), area.width, tasks_len));
 		if task_sel_idx == idx {
 			line = line.style(style::STL_NAV_ITEM_HIGHLIGHT);
 			line = line.x_fg(style::CLR_TXT_BLACK);
@@ -6,7 +6,7 @@ use crate::tui::view::support::RectExt as _;
 use crate::tui::{AppState, style};
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::Stylize;
+use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget as _};
 
@@ -38,7 +38,12 @@ impl StatefulWidget for RunsNavView {
 			.areas(area);
 
 		// -- Render
-		Paragraph::new(" Runs: ")
+		let label = if state.is_history_mode() {
+			" History: "
+		} else {
+			" Runs: "
+		};
+		Paragraph::new(label)
 			.style(style::STL_FIELD_LBL)
 			.left_aligned()
 			.render(label_a, buf);
@@ -77,16 +82,33 @@ impl StatefulWidget for RunsNavView {
 					format!("Run {idx}")
 				};
 
-				let prefix = text::spaces_up_to_10(run_item.indent() + 1);
+				let prefix = text::spaces_up_to_10(run_item.indent());
+				let is_expanded = run_item.has_children() && state.is_run_expanded_in_nav(run_item.id());
+				let tree_ico = match (run_item.has_children(), is_expanded) {
+					(true, true) => "▾",
+					(true, false) => "▸",
+					(false, _) => " ",
+				};
 
 				// TODO: need to try to avoid clone
 				let label = run.label.clone().unwrap_or(label);
-				let mut line = Line::from(vec![
-					Span::raw(prefix),
-					run_ico,
-					Span::raw(" "),
-					Span::styled(label, style::STL_TXT),
-				]);
+				let mut spans = vec![Span::raw(prefix), Span::raw(tree_ico), run_ico, Span::raw(" ")];
+				// The sub agent runs are grouped by their parent task
+				if let Some(task_idx) = run_item.parent_task_idx() {
+					spans.push(Span::styled(
+						format!("Task-{task_idx} "),
+						Style::new().fg(style::CLR_TXT_700),
+					));
+				}
+				spans.push(Span::styled(label, style::STL_TXT));
+				if run_item.has_children() && !is_expanded {
+					let sub_runs_count = run_item.all_children_ids().len();
+					spans.push(Span::styled(
+						format!(" +{sub_runs_count}"),
+						Style::new().fg(style::CLR_TXT_700),
+					));
+				}
+				let mut line = Line::from(spans);
 
 				if current_run_id == Some(run_item.id()) {
 					line = line.style(style::STL_NAV_ITEM_HIGHLIGHT);
@@ -140,9 +162,17 @@ fn process_mouse_for_run_nav(state: &mut AppState, nav_a: Rect, scroll: u16) ->
 		let new_idx = new_idx as usize;
 
 		let visible_runs = state.visible_run_items_for_nav();
-		let Some(target_run_id) = visible_runs.get(new_idx).map(|r| r.id()) else {
+		let Some(target_run_item) = visible_runs.get(new_idx) else {
 			return false;
 		};
+		let target_run_id = target_run_item.id();
+
+		// -- Click on the tree arrow (expand/collapse the sub agent runs)
+		if target_run_item.has_children() && mouse_evt.x() == nav_a.x + target_run_item.indent() as u16 {
+			state.toggle_run_expanded_in_nav(target_run_id);
+			state.clear_mouse_evts(true);
+			return true;
+		}
 
 		if Some(target_run_id) != current_run_id {
 			state.set_run_id(target_run_id);
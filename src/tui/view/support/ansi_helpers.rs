@@ -0,0 +1,157 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Convert a line of a command output, with its ANSI escape codes, to a styled line.
+///
+/// - The SGR codes (colors, bold, dim, italic, underline, reverse, reset) become the span styles.
+/// - The other escape sequences (e.g., cursor moves, erase line) are dropped.
+/// - Only the text after the last carriage return is kept (e.g., for the progress bars rewriting their line).
+pub fn ansi_to_line(text: &str) -> Line<'static> {
+	let text = text.rsplit('\r').next().unwrap_or_default();
+
+	let mut spans: Vec<Span<'static>> = Vec::new();
+	let mut style = Style::default();
+	let mut buf = String::new();
+	let mut chars = text.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		match c {
+			'\x1b' => {
+				// -- CSI sequence `ESC [ params final`
+				if chars.peek() == Some(&'[') {
+					chars.next();
+					let mut params = String::new();
+					let mut final_char = None;
+					for c in chars.by_ref() {
+						if ('\x40'..='\x7e').contains(&c) {
+							final_char = Some(c);
+							break;
+						}
+						params.push(c);
+					}
+					if final_char == Some('m') {
+						if !buf.is_empty() {
+							spans.push(Span::styled(std::mem::take(&mut buf), style));
+						}
+						style = apply_sgr(style, &params);
+					}
+				}
+				// -- OSC sequence `ESC ] ... BEL` (or `ESC \`), e.g., the hyperlinks
+				else if chars.peek() == Some(&']') {
+					while let Some(c) = chars.next() {
+						if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+							break;
+						}
+					}
+				}
+				// -- Other two chars sequences
+				else {
+					chars.next();
+				}
+			}
+			'\t' => buf.push_str("    "),
+			c if c.is_control() => (),
+			c => buf.push(c),
+		}
+	}
+
+	if !buf.is_empty() {
+		spans.push(Span::styled(buf, style));
+	}
+
+	Line::from(spans)
+}
+
+// region:    --- Support
+
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+	let codes: Vec<u16> = params.split([';', ':']).map(|c| c.parse().unwrap_or(0)).collect();
+
+	let mut i = 0;
+	while i < codes.len() {
+		match codes[i] {
+			0 => style = Style::default(),
+			1 => style = style.add_modifier(Modifier::BOLD),
+			2 => style = style.add_modifier(Modifier::DIM),
+			3 => style = style.add_modifier(Modifier::ITALIC),
+			4 => style = style.add_modifier(Modifier::UNDERLINED),
+			7 => style = style.add_modifier(Modifier::REVERSED),
+			22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+			23 => style = style.remove_modifier(Modifier::ITALIC),
+			24 => style = style.remove_modifier(Modifier::UNDERLINED),
+			27 => style = style.remove_modifier(Modifier::REVERSED),
+			c @ 30..=37 => style = style.fg(Color::Indexed((c - 30) as u8)),
+			c @ 90..=97 => style = style.fg(Color::Indexed((c - 90 + 8) as u8)),
+			c @ 40..=47 => style = style.bg(Color::Indexed((c - 40) as u8)),
+			c @ 100..=107 => style = style.bg(Color::Indexed((c - 100 + 8) as u8)),
+			39 => style.fg = None,
+			49 => style.bg = None,
+			c @ (38 | 48) => {
+				let (color, consumed) = extended_color(&codes[i + 1..]);
+				if let Some(color) = color {
+					style = if c == 38 { style.fg(color) } else { style.bg(color) };
+				}
+				i += consumed;
+			}
+			_ => (),
+		}
+		i += 1;
+	}
+
+	style
+}
+
+/// Parse the `5;n` (256 colors) or `2;r;g;b` (true color) codes, returning the color and the number of consumed codes.
+fn extended_color(codes: &[u16]) -> (Option<Color>, usize) {
+	match codes {
+		[5, n, ..] => (Some(Color::Indexed(*n as u8)), 2),
+		[2, r, g, b, ..] => (Some(Color::Rgb(*r as u8, *g as u8, *b as u8)), 4),
+		_ => (None, codes.len()),
+	}
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_tui_support_ansi_to_line_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_text = "\x1b[1;32mCompiling\x1b[0m aipack \x1b[38;5;9merr\x1b[K\x1b[39m end";
+
+		// -- Exec
+		let line = ansi_to_line(fx_text);
+
+		// -- Check
+		let texts: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+		assert_eq!(texts, vec!["Compiling", " aipack ", "err", " end"]);
+		assert_eq!(
+			line.spans[0].style,
+			Style::default().fg(Color::Indexed(2)).add_modifier(Modifier::BOLD)
+		);
+		assert_eq!(line.spans[1].style, Style::default());
+		assert_eq!(line.spans[2].style.fg, Some(Color::Indexed(9)));
+		assert_eq!(line.spans[3].style.fg, None);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_tui_support_ansi_to_line_carriage_return() -> Result<()> {
+		// -- Exec
+		let line = ansi_to_line("progress 10%\rprogress 90%");
+
+		// -- Check
+		assert_eq!(line.to_string(), "progress 90%");
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
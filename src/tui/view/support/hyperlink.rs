@@ -0,0 +1,192 @@
+//! OSC 8 terminal hyperlinks for the link zones (file paths and urls).
+//!
+//! NOTE: ratatui does not support OSC 8 natively, so the hyperlink escape sequence is written
+//!       in the first cell of the link (with a forced width), and the other cells of the link are skipped
+//!       when diffing the buffer.
+
+use crate::support::files::home_dir;
+use crate::tui::core::LinkZone;
+use ratatui::buffer::{Buffer, CellDiffOption};
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use std::env;
+use std::num::NonZeroU16;
+use std::sync::OnceLock;
+
+/// A hyperlink to be rendered on top of an already rendered buffer area.
+#[derive(Debug, Clone)]
+pub struct Hyperlink {
+	pub area: Rect,
+	pub uri: String,
+}
+
+/// Returns the hyperlinks for the visible link zones which open a file or an url.
+/// Returns an empty list if the terminal does not support OSC 8 hyperlinks.
+pub fn hyperlinks_for_zones(
+	zones: &[LinkZone],
+	lines: &[Line<'static>],
+	ref_area: Rect,
+	scroll: u16,
+) -> Vec<Hyperlink> {
+	if !is_hyperlink_supported() {
+		return Vec::new();
+	}
+
+	zones
+		.iter()
+		// Only the zones for the links themselves (the group zones are for the whole sections)
+		.filter(|zone| zone.group_id.is_none())
+		.filter_map(|zone| {
+			let target = zone.action.link_target()?;
+			let line = lines.get(zone.line_idx)?;
+			let area = zone.zone_area(ref_area, scroll, &line.spans)?.intersection(ref_area);
+			if area.is_empty() {
+				return None;
+			}
+			Some(Hyperlink {
+				area,
+				uri: hyperlink_uri(target),
+			})
+		})
+		.collect()
+}
+
+impl Hyperlink {
+	/// Wraps the already rendered cells of the area into an OSC 8 hyperlink.
+	pub fn render(&self, buf: &mut Buffer) {
+		let Some(width) = NonZeroU16::new(self.area.width) else {
+			return;
+		};
+
+		let area = self.area.intersection(buf.area);
+		let y = area.y;
+		let text: String = (area.x..area.right()).map(|x| buf[(x, y)].symbol()).collect();
+
+		let first_cell = &mut buf[(area.x, y)];
+		first_cell.set_symbol(&format!("\x1B]8;;{}\x1B\\{text}\x1B]8;;\x1B\\", self.uri));
+		first_cell.set_diff_option(CellDiffOption::ForcedWidth(width));
+
+		for x in area.x + 1..area.right() {
+			buf[(x, y)].set_diff_option(CellDiffOption::Skip);
+		}
+	}
+}
+
+/// Returns true if the current terminal supports OSC 8 hyperlinks (cached).
+pub fn is_hyperlink_supported() -> bool {
+	static SUPPORTED: OnceLock<bool> = OnceLock::new();
+	*SUPPORTED.get_or_init(detect_hyperlink_support)
+}
+
+// region:    --- Support
+
+/// Urls are used as is, file paths become `file://` uris (relative to the current dir).
+fn hyperlink_uri(target: &str) -> String {
+	if target.starts_with("http://") || target.starts_with("https://") {
+		return target.to_string();
+	}
+
+	let path = if let Some(rest) = target.strip_prefix("~/") {
+		home_dir().join(rest).to_string()
+	} else if std::path::Path::new(target).is_absolute() {
+		target.to_string()
+	} else {
+		match env::current_dir() {
+			Ok(dir) => dir.join(target).to_string_lossy().to_string(),
+			Err(_) => target.to_string(),
+		}
+	};
+
+	let path = path.replace('\\', "/");
+	if path.starts_with('/') {
+		format!("file://{path}")
+	} else {
+		// e.g., Windows `C:/...`
+		format!("file:///{path}")
+	}
+}
+
+/// Best effort detection based on the terminal environment variables.
+fn detect_hyperlink_support() -> bool {
+	let env_var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+
+	if env_var("TMUX").is_some() || env_var("STY").is_some() {
+		return false;
+	}
+
+	if let Some(term_program) = env_var("TERM_PROGRAM") {
+		let term_program = term_program.to_lowercase();
+		if ["iterm", "wezterm", "vscode", "ghostty", "hyper", "zed", "rio"]
+			.iter()
+			.any(|name| term_program.contains(name))
+		{
+			return true;
+		}
+	}
+
+	if env_var("WT_SESSION").is_some() || env_var("KITTY_WINDOW_ID").is_some() || env_var("KONSOLE_VERSION").is_some() {
+		return true;
+	}
+
+	// VTE based terminals (e.g., GNOME Terminal) support it since 0.50
+	if let Some(vte_version) = env_var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) {
+		return vte_version >= 5000;
+	}
+
+	if let Some(term) = env_var("TERM") {
+		let term = term.to_lowercase();
+		return ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+			.iter()
+			.any(|name| term.contains(name));
+	}
+
+	false
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+
+	#[test]
+	fn test_hyperlink_uri_url_and_path() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(hyperlink_uri("https://aipack.ai/doc"), "https://aipack.ai/doc");
+		assert_eq!(hyperlink_uri("/tmp/some/file.rs"), "file:///tmp/some/file.rs");
+		let rel = hyperlink_uri("src/main.rs");
+		assert!(rel.starts_with("file://"));
+		assert!(rel.ends_with("/src/main.rs"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_hyperlink_render_osc8() -> Result<()> {
+		// -- Setup & Fixtures
+		let mut buf = Buffer::with_lines(["see src/main.rs now"]);
+		let link = Hyperlink {
+			area: Rect::new(4, 0, 11, 1),
+			uri: "file:///wks/src/main.rs".to_string(),
+		};
+
+		// -- Exec
+		link.render(&mut buf);
+
+		// -- Check
+		assert_eq!(
+			buf[(4, 0)].symbol(),
+			"\x1B]8;;file:///wks/src/main.rs\x1B\\src/main.rs\x1B]8;;\x1B\\"
+		);
+		assert_eq!(buf[(5, 0)].diff_option, CellDiffOption::Skip);
+		assert_eq!(buf[(15, 0)].diff_option, CellDiffOption::None);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
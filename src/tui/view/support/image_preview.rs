@@ -0,0 +1,447 @@
+//! Inline image previews for the image links (e.g., `aip.ui.marker({ label = "logo", link = "out/logo.png" })`).
+//!
+//! The image is rendered with the kitty, iTerm2, or sixel graphics protocol when the terminal supports one,
+//! otherwise, a placeholder (image name and dimensions) is rendered.
+//!
+//! NOTE: Like the hyperlinks, ratatui does not support graphics protocols, so the image escape sequence
+//!       is written in the first cell of the preview area (with a forced width), and the other cells
+//!       of the area are skipped when diffing the buffer.
+//! NOTE: The kitty images are not erased by the text written over them, so a kitty preview
+//!       stays on screen until it is rendered again (or the screen is cleared).
+
+use crate::tui::core::ImageZone;
+use crate::tui::style;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use ratatui::buffer::{Buffer, CellDiffOption};
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, BorderType, Paragraph, Widget as _};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::Cursor;
+use std::num::NonZeroU16;
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::SystemTime;
+use std::{env, fs};
+
+/// The number of lines reserved for an image preview.
+pub const IMAGE_PREVIEW_HEIGHT: u16 = 10;
+
+/// The maximum number of columns of an image preview.
+const IMAGE_PREVIEW_MAX_WIDTH: u16 = 48;
+
+/// Fallback cell size (in pixels) when the terminal does not report its pixel size.
+const DEFAULT_CELL_PX: (u32, u32) = (10, 20);
+
+/// The kitty protocol requires the payload to be sent in chunks of at most 4096 bytes.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+const IMAGE_EXTS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageProtocol {
+	Kitty,
+	Iterm2,
+	Sixel,
+	/// No graphics protocol, a placeholder is rendered.
+	Placeholder,
+}
+
+/// An image preview to be rendered on top of an already rendered buffer area.
+#[derive(Debug, Clone)]
+pub struct ImagePreview {
+	pub area: Rect,
+	pub path: String,
+}
+
+/// Returns true if the path (or file name) has a supported image extension.
+pub fn is_image_path(path: &str) -> bool {
+	let Some((_, ext)) = path.rsplit_once('.') else {
+		return false;
+	};
+	let ext = ext.to_lowercase();
+	IMAGE_EXTS.contains(&ext.as_str())
+}
+
+/// Returns the image previews for the fully visible image zones.
+pub fn image_previews_for_zones(zones: &[ImageZone], ref_area: Rect, scroll: u16) -> Vec<ImagePreview> {
+	zones
+		.iter()
+		.filter_map(|zone| {
+			let area = zone.zone_area(ref_area, scroll)?;
+			let area = Rect {
+				width: area.width.min(IMAGE_PREVIEW_MAX_WIDTH),
+				..area
+			};
+			Some(ImagePreview {
+				area,
+				path: zone.path.clone(),
+			})
+		})
+		.collect()
+}
+
+impl ImagePreview {
+	pub fn render(&self, buf: &mut Buffer) {
+		let area = self.area.intersection(buf.area);
+		if area.is_empty() {
+			return;
+		}
+
+		let protocol = image_protocol();
+		let sequence = match protocol {
+			ImageProtocol::Placeholder => None,
+			protocol => cached_image_sequence(&self.path, area.width, area.height, protocol),
+		};
+
+		match sequence {
+			Some(sequence) => render_sequence(&sequence, area, buf),
+			None => self.render_placeholder(area, buf),
+		}
+	}
+
+	fn render_placeholder(&self, area: Rect, buf: &mut Buffer) {
+		let name = self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path);
+		let info = match image::image_dimensions(&self.path) {
+			Ok((width, height)) => format!("{width} x {height} px"),
+			Err(_) => "cannot read image".to_string(),
+		};
+
+		let lines = vec![
+			Line::styled(name.to_string(), style::STL_TXT_PATH),
+			Line::styled(info, style::STL_FIELD_VAL),
+			Line::styled("(no inline image support)", style::STL_FIELD_VAL),
+		];
+
+		let block = Block::bordered()
+			.border_type(BorderType::Rounded)
+			.border_style(style::STL_FIELD_LBL)
+			.title(" image ");
+		Paragraph::new(lines).block(block).render(area, buf);
+	}
+}
+
+/// Returns the graphics protocol supported by the current terminal (cached).
+pub fn image_protocol() -> ImageProtocol {
+	static PROTOCOL: OnceLock<ImageProtocol> = OnceLock::new();
+	*PROTOCOL.get_or_init(detect_image_protocol)
+}
+
+// region:    --- Render Support
+
+/// Put the sequence in the first cell of the area, and skip all the other cells of the area.
+///
+/// NOTE: The cursor is saved and restored around the sequence (each protocol moves it differently),
+///       and then moved to the end of the first line, as ratatui expects from the forced width.
+fn render_sequence(sequence: &str, area: Rect, buf: &mut Buffer) {
+	let Some(width) = NonZeroU16::new(area.width) else {
+		return;
+	};
+
+	let first_cell = &mut buf[(area.x, area.y)];
+	first_cell.set_symbol(&format!("\x1B7{sequence}\x1B8\x1B[{width}C"));
+	first_cell.set_diff_option(CellDiffOption::ForcedWidth(width));
+
+	for y in area.y..area.bottom() {
+		for x in area.x..area.right() {
+			if (x, y) != (area.x, area.y) {
+				buf[(x, y)].set_diff_option(CellDiffOption::Skip);
+			}
+		}
+	}
+}
+
+type SequenceCacheKey = (String, Option<SystemTime>, u16, u16, ImageProtocol);
+
+/// Returns the (cached) image escape sequence, None if the image cannot be loaded.
+/// NOTE: The file modification time is part of the key, so regenerated images are reloaded.
+fn cached_image_sequence(path: &str, cols: u16, rows: u16, protocol: ImageProtocol) -> Option<Arc<str>> {
+	static CACHE: LazyLock<Mutex<HashMap<SequenceCacheKey, Option<Arc<str>>>>> = LazyLock::new(Default::default);
+
+	let mtime = fs::metadata(path).and_then(|meta| meta.modified()).ok();
+	let key = (path.to_string(), mtime, cols, rows, protocol);
+
+	let mut cache = CACHE.lock().unwrap_or_else(|err| err.into_inner());
+	if let Some(sequence) = cache.get(&key) {
+		return sequence.clone();
+	}
+
+	// Keep the cache small (previews are few, but the area changes with the terminal size)
+	if cache.len() > 64 {
+		cache.clear();
+	}
+
+	let sequence = image_sequence(path, cols, rows, protocol).ok().map(Arc::from);
+	cache.insert(key, sequence.clone());
+
+	sequence
+}
+
+fn image_sequence(path: &str, cols: u16, rows: u16, protocol: ImageProtocol) -> crate::Result<String> {
+	let (cell_w, cell_h) = cell_pixel_size();
+	let img = image::open(path).map_err(|err| crate::Error::cc(format!("Cannot open image '{path}'"), err))?;
+	let img = img.thumbnail(cols as u32 * cell_w, rows as u32 * cell_h);
+
+	// The cells actually covered by the thumbnail (keeps the aspect ratio)
+	let img_cols = img.width().div_ceil(cell_w).clamp(1, cols as u32);
+	let img_rows = img.height().div_ceil(cell_h).clamp(1, rows as u32);
+
+	let sequence = match protocol {
+		ImageProtocol::Kitty => kitty_sequence(&to_png(&img)?, kitty_image_id(path), img_cols, img_rows),
+		ImageProtocol::Iterm2 => iterm2_sequence(&to_png(&img)?, img_cols, img_rows),
+		ImageProtocol::Sixel => sixel_sequence(&img.to_rgba8()),
+		ImageProtocol::Placeholder => return Err("No image protocol for placeholder".into()),
+	};
+
+	Ok(sequence)
+}
+
+fn to_png(img: &DynamicImage) -> crate::Result<Vec<u8>> {
+	let mut png = Vec::new();
+	img.write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+		.map_err(|err| crate::Error::cc("Cannot encode image preview to png", err))?;
+	Ok(png)
+}
+
+/// The cell size in pixels, from the terminal when available.
+fn cell_pixel_size() -> (u32, u32) {
+	match crossterm::terminal::window_size() {
+		Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+			(size.width / size.columns).max(1) as u32,
+			(size.height / size.rows).max(1) as u32,
+		),
+		_ => DEFAULT_CELL_PX,
+	}
+}
+
+/// A stable (non zero) kitty image id per path, so that a new render replaces the previous placement.
+fn kitty_image_id(path: &str) -> u32 {
+	let mut hasher = DefaultHasher::new();
+	path.hash(&mut hasher);
+	(hasher.finish() as u32 & 0x00FF_FFFF).max(1)
+}
+
+// endregion: --- Render Support
+
+// region:    --- Protocol Encoders
+
+/// Kitty graphics protocol (png payload, chunked), displayed over `cols` x `rows` cells, cursor not moved.
+fn kitty_sequence(png: &[u8], id: u32, cols: u32, rows: u32) -> String {
+	let payload = BASE64.encode(png);
+	let chunks: Vec<&str> = payload
+		.as_bytes()
+		.chunks(KITTY_CHUNK_SIZE)
+		.map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+		.collect();
+
+	// First, delete the previous placement of this image (if any)
+	let mut seq = format!("\x1B_Ga=d,d=i,i={id},q=2\x1B\\");
+	let last_idx = chunks.len().saturating_sub(1);
+	for (idx, chunk) in chunks.iter().enumerate() {
+		let more = if idx < last_idx { 1 } else { 0 };
+		if idx == 0 {
+			let _ = write!(
+				seq,
+				"\x1B_Ga=T,f=100,i={id},c={cols},r={rows},C=1,q=2,m={more};{chunk}\x1B\\"
+			);
+		} else {
+			let _ = write!(seq, "\x1B_Gm={more};{chunk}\x1B\\");
+		}
+	}
+
+	seq
+}
+
+/// iTerm2 inline image protocol (also supported by WezTerm).
+fn iterm2_sequence(png: &[u8], cols: u32, rows: u32) -> String {
+	format!(
+		"\x1B]1337;File=inline=1;size={};width={cols};height={rows};preserveAspectRatio=1:{}\x07",
+		png.len(),
+		BASE64.encode(png)
+	)
+}
+
+/// Sixel encoding with the 6x6x6 color cube palette (transparent pixels are left untouched).
+fn sixel_sequence(img: &RgbaImage) -> String {
+	const LEVELS: usize = 6;
+	const PALETTE_SIZE: usize = LEVELS * LEVELS * LEVELS;
+
+	let (width, height) = (img.width() as usize, img.height() as usize);
+	let color_idx = |x: usize, y: usize| -> Option<usize> {
+		let px = img.get_pixel(x as u32, y as u32);
+		if px[3] < 128 {
+			return None;
+		}
+		let level = |v: u8| (v as usize * (LEVELS - 1) + 127) / 255;
+		Some(level(px[0]) * LEVELS * LEVELS + level(px[1]) * LEVELS + level(px[2]))
+	};
+
+	// -- Header & palette (rgb in percent)
+	let mut seq = format!("\x1BP0;1;0q\"1;1;{width};{height}");
+	for idx in 0..PALETTE_SIZE {
+		let percent = |level: usize| level * 100 / (LEVELS - 1);
+		let (r, g, b) = (idx / (LEVELS * LEVELS), (idx / LEVELS) % LEVELS, idx % LEVELS);
+		let _ = write!(seq, "#{idx};2;{};{};{}", percent(r), percent(g), percent(b));
+	}
+
+	// -- Bands of 6 pixel rows
+	for band_y in (0..height).step_by(6) {
+		let band_h = (height - band_y).min(6);
+		let band: Vec<Option<usize>> = (0..band_h)
+			.flat_map(|dy| (0..width).map(move |x| (x, band_y + dy)))
+			.map(|(x, y)| color_idx(x, y))
+			.collect();
+
+		let mut used = vec![false; PALETTE_SIZE];
+		for idx in band.iter().flatten() {
+			used[*idx] = true;
+		}
+
+		for color in (0..PALETTE_SIZE).filter(|idx| used[*idx]) {
+			let _ = write!(seq, "#{color}");
+			let sixels = (0..width).map(|x| {
+				let bits = (0..band_h)
+					.filter(|dy| band[dy * width + x] == Some(color))
+					.fold(0u8, |bits, dy| bits | (1 << dy));
+				(63 + bits) as char
+			});
+			push_sixels_rle(&mut seq, sixels);
+			// Graphics carriage return (next color on the same band)
+			seq.push('$');
+		}
+		// Graphics new line (next band)
+		seq.push('-');
+	}
+
+	seq.push_str("\x1B\\");
+	seq
+}
+
+fn push_sixels_rle(seq: &mut String, sixels: impl Iterator<Item = char>) {
+	let flush = |seq: &mut String, ch: char, count: usize| {
+		if count > 3 {
+			let _ = write!(seq, "!{count}{ch}");
+		} else {
+			seq.extend(std::iter::repeat_n(ch, count));
+		}
+	};
+
+	let mut current: Option<(char, usize)> = None;
+	for ch in sixels {
+		current = match current {
+			Some((prev, count)) if prev == ch => Some((prev, count + 1)),
+			Some((prev, count)) => {
+				flush(seq, prev, count);
+				Some((ch, 1))
+			}
+			None => Some((ch, 1)),
+		};
+	}
+	if let Some((ch, count)) = current {
+		flush(seq, ch, count);
+	}
+}
+
+// endregion: --- Protocol Encoders
+
+// region:    --- Support
+
+/// Best effort detection based on the terminal environment variables.
+fn detect_image_protocol() -> ImageProtocol {
+	let env_var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+
+	// Multiplexers do not pass through the graphics sequences by default
+	if env_var("TMUX").is_some() || env_var("STY").is_some() {
+		return ImageProtocol::Placeholder;
+	}
+
+	let term = env_var("TERM").unwrap_or_default().to_lowercase();
+	let term_program = env_var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+	let lc_terminal = env_var("LC_TERMINAL").unwrap_or_default().to_lowercase();
+
+	if env_var("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || term_program.contains("ghostty") {
+		return ImageProtocol::Kitty;
+	}
+
+	if term_program.contains("iterm") || lc_terminal.contains("iterm") || term_program.contains("wezterm") {
+		return ImageProtocol::Iterm2;
+	}
+
+	if term.contains("foot")
+		|| term.contains("mlterm")
+		|| term_program.contains("contour")
+		|| env_var("KONSOLE_VERSION").is_some()
+		|| env_var("WT_SESSION").is_some()
+	{
+		return ImageProtocol::Sixel;
+	}
+
+	ImageProtocol::Placeholder
+}
+
+// endregion: --- Support
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>;
+
+	use super::*;
+	use image::Rgba;
+
+	#[test]
+	fn test_image_preview_is_image_path() -> Result<()> {
+		// -- Exec & Check
+		assert!(is_image_path("out/logo.PNG"));
+		assert!(is_image_path("./some/photo.jpeg"));
+		assert!(!is_image_path("src/main.rs"));
+		assert!(!is_image_path("README"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_image_preview_sixel_simple() -> Result<()> {
+		// -- Setup & Fixtures
+		// 2 x 7 image: first band red, second band (1 row) transparent except one blue pixel
+		let mut img = RgbaImage::from_pixel(2, 7, Rgba([255, 0, 0, 255]));
+		img.put_pixel(0, 6, Rgba([0, 0, 0, 0]));
+		img.put_pixel(1, 6, Rgba([0, 0, 255, 255]));
+
+		// -- Exec
+		let seq = sixel_sequence(&img);
+
+		// -- Check
+		assert!(seq.starts_with("\x1BP0;1;0q\"1;1;2;7"));
+		assert!(seq.ends_with("-\x1B\\"));
+		// red is 5*36 = 180, with 6 rows set (63 + 0b111111 = '~')
+		assert!(seq.contains("#180~~$-"));
+		// blue is 5, only the second column, first row of the band (63 + 1 = '@')
+		assert!(seq.contains("#5?@$-"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_image_preview_kitty_chunks() -> Result<()> {
+		// -- Setup & Fixtures
+		let png = vec![7u8; KITTY_CHUNK_SIZE]; // base64 is bigger than one chunk
+
+		// -- Exec
+		let seq = kitty_sequence(&png, 42, 10, 5);
+
+		// -- Check
+		assert!(seq.starts_with("\x1B_Ga=d,d=i,i=42,q=2\x1B\\"));
+		assert!(seq.contains("\x1B_Ga=T,f=100,i=42,c=10,r=5,C=1,q=2,m=1;"));
+		assert!(seq.contains("\x1B_Gm=0;"));
+		assert_eq!(seq.matches("\x1B_G").count(), 3);
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
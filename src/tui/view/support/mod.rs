@@ -1,9 +1,15 @@
 // region:    --- Modules
 
+mod ansi_helpers;
+mod hyperlink;
+mod image_preview;
 mod line_helpers;
 mod rect_ext;
 mod text_helpers;
 
+pub use ansi_helpers::*;
+pub use hyperlink::*;
+pub use image_preview::*;
 pub use line_helpers::*;
 pub use rect_ext::*;
 pub use text_helpers::*;
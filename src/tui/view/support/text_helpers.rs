@@ -4,16 +4,21 @@ use std::sync::LazyLock;
 pub struct TextSeg<'a> {
 	pub text: String,
 	pub file_path: Option<&'a str>,
+	pub url: Option<&'a str>,
 }
 
 pub fn segment_line_path(line: &str) -> Vec<TextSeg<'_>> {
 	// Matches:
+	//   - URLs (http/https): https://aipack.ai/doc
 	//   - Paths with directories (optionally starting with ~): ~/foo/bar.rs, src/main.rs
 	//   - Standalone filenames with extension: tsconfig.json, Cargo.toml
 	//   - Dotfiles (with optional chained extensions): .env, .gitignore, .env.local
 	static RE: LazyLock<Regex> = LazyLock::new(|| {
 		Regex::new(
 			r#"(?x)
+			# URL (trailing punctuation is trimmed below)
+			https?://[^\s<>"'\x60]+
+			|
 			# Path with directory separator (permissive extension)
 			~?[a-zA-Z0-9_@\-\./]+/[a-zA-Z0-9_@\-\.]+\.[a-zA-Z0-9]{2,5}
 			|
@@ -36,13 +41,21 @@ pub fn segment_line_path(line: &str) -> Vec<TextSeg<'_>> {
 
 	for m in re.find_iter(line) {
 		let start = m.start();
-		let end = m.end();
+		let mut end = m.end();
+
+		// -- URL (trim trailing punctuation, e.g., "See https://aipack.ai.")
+		let is_url = m.as_str().starts_with("http://") || m.as_str().starts_with("https://");
+		if is_url {
+			let trimmed = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}']);
+			end = start + trimmed.len();
+		}
+
 		let text = &line[start..end];
 
 		// Post-filter: reject standalone filename matches (no '/') when followed by
 		// continuation characters (alphanumeric, hyphen, underscore, dot), which
 		// indicates the token is part of a longer identifier (e.g. model versions).
-		if !text.contains('/') && !text.starts_with('.') {
+		if !is_url && !text.contains('/') && !text.starts_with('.') {
 			let next_byte = line.as_bytes().get(end).copied();
 			if let Some(b) = next_byte
 				&& (b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.')
@@ -56,11 +69,14 @@ pub fn segment_line_path(line: &str) -> Vec<TextSeg<'_>> {
 			segments.push(TextSeg {
 				text: line[last_idx..start].to_string(),
 				file_path: None,
+				url: None,
 			});
 		}
+		let (file_path, url) = if is_url { (None, Some(text)) } else { (Some(text), None) };
 		segments.push(TextSeg {
 			text: text.to_string(),
-			file_path: Some(text),
+			file_path,
+			url,
 		});
 		last_idx = end;
 	}
@@ -69,6 +85,7 @@ pub fn segment_line_path(line: &str) -> Vec<TextSeg<'_>> {
 		segments.push(TextSeg {
 			text: line[last_idx..].to_string(),
 			file_path: None,
+			url: None,
 		});
 	}
 
@@ -76,6 +93,7 @@ pub fn segment_line_path(line: &str) -> Vec<TextSeg<'_>> {
 		segments.push(TextSeg {
 			text: line.to_string(),
 			file_path: None,
+			url: None,
 		});
 	}
 
@@ -431,6 +449,41 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_text_helpers_segment_line_path_url() -> Result<()> {
+		// -- Setup & Fixtures
+		let line = "See https://aipack.ai/doc/lua-apis.md for details";
+
+		// -- Exec
+		let segs = segment_line_path(line);
+
+		// -- Check
+		assert_eq!(segs.len(), 3);
+		assert_eq!(segs[0].text, "See ");
+		assert_eq!(segs[1].text, "https://aipack.ai/doc/lua-apis.md");
+		assert_eq!(segs[1].url, Some("https://aipack.ai/doc/lua-apis.md"));
+		assert!(segs[1].file_path.is_none());
+		assert_eq!(segs[2].text, " for details");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_text_helpers_segment_line_path_url_trailing_punctuation() -> Result<()> {
+		// -- Setup & Fixtures
+		let line = "(see http://localhost:8080/api).";
+
+		// -- Exec
+		let segs = segment_line_path(line);
+
+		// -- Check
+		assert_eq!(segs.len(), 3);
+		assert_eq!(segs[1].url, Some("http://localhost:8080/api"));
+		assert_eq!(segs[2].text, ").");
+
+		Ok(())
+	}
 }
 
 // endregion: --- Tests
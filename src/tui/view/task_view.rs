@@ -4,11 +4,12 @@ use crate::tui::core::{LinkZones, ScrollIden, UiAction};
 use crate::tui::view::support::RectExt as _;
 use crate::tui::view::{comp, support};
 use crate::tui::{AppState, style};
+use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Color;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
+use ratatui::widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState, StatefulWidget, Widget as _};
 use std::borrow::Cow;
 
 /// Renders the content of a task. For now, the logs.
@@ -66,11 +67,58 @@ impl StatefulWidget for TaskView {
 
 		render_header(header_a, buf, state, header_mode);
 
+		// -- Layout Logs | Cmd Tail (when the task is running a command)
+		let cmd_tail_height = match state.current_task() {
+			Some(task) if task.cmd_running == Some(true) => CMD_TAIL_HEIGHT.min(logs_a.height / 2),
+			_ => 0,
+		};
+		let [logs_a, cmd_tail_a] = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![Constraint::Fill(1), Constraint::Length(cmd_tail_height)])
+			.areas(logs_a);
+
 		// don't show the steps
 		render_body(logs_a, buf, state, false);
+
+		if cmd_tail_height > 0 {
+			render_cmd_tail(cmd_tail_a, buf, state);
+		}
 	}
 }
 
+/// The max height of the running command tail pane (with its title line)
+const CMD_TAIL_HEIGHT: u16 = 12;
+
+/// The live tail of the command the task is running (`aip.cmd.exec`), with the ANSI colors.
+fn render_cmd_tail(area: Rect, buf: &mut Buffer, state: &AppState) {
+	let Some(task) = state.current_task() else {
+		return;
+	};
+
+	let cmd_line = task.cmd_line.as_deref().unwrap_or_default();
+	let title_width = area.width.saturating_sub(8) as usize;
+	let block = Block::new()
+		.borders(Borders::TOP)
+		.border_style(style::STL_SECTION_MARKER)
+		.title(Line::from(vec![
+			Span::styled(" Cmd: ", style::STL_SECTION_MARKER_AI),
+			Span::styled(
+				format!(" {} ", truncate_with_ellipsis(cmd_line, title_width, "...")),
+				style::STL_FIELD_VAL,
+			),
+		]));
+	let inner_a = block.inner(area);
+	block.render(area, buf);
+
+	// -- Tail, the last lines that fit
+	let tail = task.cmd_tail.as_deref().unwrap_or_default();
+	let lines: Vec<&str> = tail.lines().collect();
+	let skip = lines.len().saturating_sub(inner_a.height as usize);
+	let lines: Vec<Line> = lines[skip..].iter().map(|l| support::ansi_to_line(l)).collect();
+
+	Paragraph::new(lines).render(inner_a, buf);
+}
+
 fn render_header(area: Rect, buf: &mut Buffer, state: &mut AppState, header_mode: HeaderMode) {
 	// Do nothing if None
 	if matches!(header_mode, HeaderMode::None) {
@@ -318,6 +366,7 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState, show_steps: b
 	let scroll = state.clamp_scroll(SCROLL_IDEN, line_count);
 
 	// -- Perform hover/click over link zones
+	let image_previews = support::image_previews_for_zones(link_zones.image_zones(), area, scroll);
 	let zones = link_zones.into_zones();
 
 	// First pass: detect which zone (if any) is hovered.
@@ -365,15 +414,27 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState, show_steps: b
 			}
 		}
 
-		if state.is_mouse_up_only() && state.is_last_mouse_over(area) {
+		// Open the link (file or url) under the cursor with the `o` key
+		if let Some(KeyCode::Char('o')) = state.last_app_event().as_key_code()
+			&& action.link_target().is_some()
+		{
+			state.set_action(action);
+		} else if state.is_mouse_up_only() && state.is_last_mouse_over(area) {
 			state.set_action(action);
 			state.clear_mouse_evts(true);
 		}
 	}
 
 	// -- Render All Content
+	let hyperlinks = support::hyperlinks_for_zones(&zones, &all_lines, area, scroll);
 	let p = Paragraph::new(all_lines).scroll((scroll, 0));
 	p.render(area, buf);
+	for hyperlink in hyperlinks {
+		hyperlink.render(buf);
+	}
+	for image_preview in image_previews {
+		image_preview.render(buf);
+	}
 
 	// -- Render Scrollbar
 	let content_size = line_count.saturating_sub(area.height as usize);
@@ -388,7 +449,7 @@ fn render_body(area: Rect, buf: &mut Buffer, state: &mut AppState, show_steps: b
 
 // region:    --- UI Builders
 
-fn ui_for_input(
+pub(super) fn ui_for_input(
 	mm: &ModelManager,
 	task: &Task,
 	max_width: u16,
@@ -436,7 +497,7 @@ fn ui_for_input(
 	}
 }
 
-fn ui_for_ai(
+pub(super) fn ui_for_ai(
 	run: &Run,
 	task: &Task,
 	max_width: u16,
@@ -475,10 +536,11 @@ fn ui_for_ai(
 		RunningState::Ended(Some(EndState::Ok)) => {
 			// let cost = state.current_task_cost_fmt();
 			// let compl = state.current_task_completion_tokens_fmt();
-			(
-				Some(format!("✔ AI model {model_names} responded.")),
-				marker_style_active,
-			)
+			let content = match task.fmt_finish_badge() {
+				Some(badge) => format!("✔ AI model {model_names} responded. {badge}"),
+				None => format!("✔ AI model {model_names} responded."),
+			};
+			(Some(content), marker_style_active)
 		}
 
 		RunningState::Ended(Some(EndState::Err)) => {
@@ -514,7 +576,7 @@ fn ui_for_ai(
 	}
 }
 
-fn ui_for_output(
+pub(super) fn ui_for_output(
 	mm: &ModelManager,
 	task: &Task,
 	max_width: u16,
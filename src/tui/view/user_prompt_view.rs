@@ -0,0 +1,146 @@
+use crate::tui::{AppState, style};
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::Stylize as _;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Clear, Padding, Paragraph, StatefulWidget, Widget as _, Wrap};
+
+/// The dialog of the pending user prompt (e.g., `aip.flow.ask_user`)
+/// When the prompt has a preview (e.g., the diff of a write to approve), the dialog is larger to show it.
+pub struct UserPromptView;
+
+impl StatefulWidget for UserPromptView {
+	type State = AppState;
+
+	fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+		let Some(user_prompt) = state.user_prompt() else {
+			return;
+		};
+
+		// -- Dialog layout
+		let preview_lines = user_prompt.preview().map(|p| p.lines().count() as u16).unwrap_or_default();
+		let (dialog_width, dialog_height) = if preview_lines > 0 {
+			(
+				110.min(area.width),
+				(14 + preview_lines).min(area.height.saturating_sub(4)),
+			)
+		} else {
+			(70.min(area.width), 12.min(area.height))
+		};
+
+		let [_, mid_v, _] = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![
+				Constraint::Fill(1),
+				Constraint::Length(dialog_height),
+				Constraint::Length(4),
+			])
+			.areas(area);
+
+		let [_, content_a, _] = Layout::default()
+			.direction(Direction::Horizontal)
+			.constraints(vec![
+				Constraint::Fill(1),
+				Constraint::Length(dialog_width),
+				Constraint::Fill(1),
+			])
+			.areas(mid_v);
+
+		// -- Clear and Background
+		Clear.render(content_a, buf);
+
+		let title = if preview_lines > 0 {
+			"  Agent Approval  "
+		} else {
+			"  Agent Question  "
+		};
+		let block = Block::bordered()
+			.border_type(BorderType::Rounded)
+			.border_style(style::CLR_TXT_YELLOW)
+			.bg(style::CLR_BKG_BLACK)
+			.padding(Padding::new(2, 2, 1, 0))
+			.title(Line::from(title).alignment(Alignment::Center));
+
+		let inner_area = block.inner(content_a);
+		block.render(content_a, buf);
+
+		let [preview_a, msg_a, _gap, input_a, _gap_2, hint_a] = Layout::default()
+			.direction(Direction::Vertical)
+			.constraints(vec![
+				Constraint::Length(preview_lines),
+				Constraint::Fill(1),
+				Constraint::Length(1),
+				Constraint::Length(1),
+				Constraint::Length(1),
+				Constraint::Length(1),
+			])
+			.areas(inner_area);
+
+		// -- Preview (diff lines colored)
+		if let Some(preview) = user_prompt.preview() {
+			let lines = preview
+				.lines()
+				.map(|line| {
+					let style = if line.starts_with("+++") || line.starts_with("---") {
+						style::STL_FIELD_LBL_DARK
+					} else if line.starts_with('+') {
+						style::STL_TXT.fg(style::CLR_TXT_GREEN)
+					} else if line.starts_with('-') {
+						style::STL_TXT.fg(style::CLR_TXT_RED)
+					} else if line.starts_with("@@") {
+						style::STL_TXT.fg(style::CLR_TXT_BLUE)
+					} else {
+						style::STL_TXT
+					};
+					Line::styled(line.to_string(), style)
+				})
+				.collect::<Vec<_>>();
+			Paragraph::new(lines).render(preview_a, buf);
+		}
+
+		// -- Message
+		Paragraph::new(user_prompt.message().trim_start_matches("-?").trim())
+			.wrap(Wrap { trim: false })
+			.style(style::STL_TXT)
+			.render(msg_a, buf);
+
+		// -- Input (choices or text)
+		let input_line = if user_prompt.choices().is_empty() {
+			let mut spans = vec![
+				Span::styled("> ", style::STL_TXT_ACTION),
+				Span::styled(user_prompt.input().to_string(), style::STL_TXT_ACT),
+				Span::styled("_", style::STL_TXT_SEL),
+			];
+			if user_prompt.input().is_empty()
+				&& let Some(default) = user_prompt.default()
+			{
+				spans.push(Span::styled(format!("  (default: {default})"), style::STL_FIELD_VAL));
+			}
+			Line::from(spans)
+		} else {
+			let mut spans = Vec::new();
+			for (idx, choice) in user_prompt.choices().iter().enumerate() {
+				let label = format!(" {}. {choice} ", idx + 1);
+				if idx == user_prompt.choice_idx() {
+					spans.push(Span::styled(label, style::STL_NAV_ITEM_HIGHLIGHT));
+				} else {
+					spans.push(Span::styled(label, style::STL_FIELD_VAL));
+				}
+				spans.push(Span::raw(" "));
+			}
+			Line::from(spans).alignment(Alignment::Center)
+		};
+		input_line.render(input_a, buf);
+
+		// -- Keys hint
+		let hint = if user_prompt.choices().is_empty() {
+			"[Enter] Send   [Esc] Cancel"
+		} else {
+			"[←/→ or 1-9] Select   [Enter] Send   [Esc] Cancel"
+		};
+		Line::from(hint)
+			.style(style::STL_FIELD_LBL_DARK)
+			.alignment(Alignment::Center)
+			.render(hint_a, buf);
+	}
+}
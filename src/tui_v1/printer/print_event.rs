@@ -1,4 +1,4 @@
-use crate::dir_context::PackDir;
+use crate::exec::{PackInfo, PackListItem};
 use derive_more::From;
 use genai::ModelIden;
 use std::collections::HashSet;
@@ -6,7 +6,9 @@ use std::collections::HashSet;
 #[derive(Debug, From)]
 pub enum PrintEvent {
 	#[from]
-	PackList(Vec<PackDir>),
+	PackList(Vec<PackListItem>),
+
+	PackInfo(Box<PackInfo>),
 
 	/// Single line info
 	InfoShort(String),
@@ -6,9 +6,13 @@ pub fn handle_print(print_event: Arc<PrintEvent>, interactive: bool) {
 	// TODO: Need to add proper error handling for the print functions
 	match &*print_event {
 		// -- Print pack list (aip list)
-		PrintEvent::PackList(pack_dirs) => {
-			let pack_dirs: Vec<&_> = pack_dirs.iter().collect();
-			printers::print_pack_list(&pack_dirs, interactive);
+		PrintEvent::PackList(items) => {
+			printers::print_pack_list(items, interactive);
+		}
+
+		// -- Print pack info (aip info)
+		PrintEvent::PackInfo(pack_info) => {
+			printers::print_pack_info(pack_info, interactive);
 		}
 
 		PrintEvent::InfoShort(info) => {
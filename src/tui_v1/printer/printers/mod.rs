@@ -5,6 +5,7 @@ mod print_api_keys;
 mod print_error_generic;
 mod print_error_key_env_missing;
 mod print_info;
+mod print_pack_info;
 mod print_pack_list;
 
 #[allow(unused)]
@@ -13,6 +14,7 @@ pub use print_api_keys::*;
 pub use print_error_generic::*;
 pub use print_error_key_env_missing::*;
 pub use print_info::*;
+pub use print_pack_info::*;
 pub use print_pack_list::*;
 
 // endregion: --- Modules
@@ -0,0 +1,97 @@
+use super::print_pack_list::print_agent_meta;
+use crate::exec::PackInfo;
+use crossterm::execute;
+use crossterm::style::{Attribute, Print, ResetColor, SetAttribute};
+use std::io::stdout;
+
+/// The max number of changelog lines printed (the full changelog is in the `--json`)
+const CHANGELOG_MAX_LINES: usize = 40;
+
+#[allow(unused_must_use)] // TODO: need to remove and make this function return error
+pub fn print_pack_info(pack_info: &PackInfo, _interactive: bool) {
+	let mut stdout = stdout();
+
+	// -- Header
+	let status: &'static str = pack_info.status.into();
+	let version = pack_info
+		.pack_toml
+		.version
+		.as_deref()
+		.map(|v| format!(" v{v}"))
+		.unwrap_or_default();
+	execute!(
+		stdout,
+		SetAttribute(Attribute::Bold),
+		Print(format!("\n{}{version}", pack_info.pack_ref)),
+		ResetColor,
+		SetAttribute(Attribute::Dim),
+		Print(format!("  ({status}) - {}\n", pack_info.path)),
+		SetAttribute(Attribute::Reset)
+	);
+
+	// -- Metadata
+	let pack_toml = &pack_info.pack_toml;
+	let join = |items: &[String]| (!items.is_empty()).then(|| items.join(", "));
+	let tags = join(&pack_toml.tags);
+	let capabilities = join(&pack_toml.capabilities);
+	let paths_allow = join(&pack_toml.paths_allow);
+	let fields = [
+		("author", pack_toml.author.as_deref()),
+		("license", pack_toml.license.as_deref()),
+		("homepage", pack_toml.homepage.as_deref()),
+		("repo", pack_toml.repo.as_deref()),
+		("tags", tags.as_deref()),
+		("capabilities", capabilities.as_deref()),
+		("paths allow", paths_allow.as_deref()),
+	];
+	for (name, value) in fields {
+		if let Some(value) = value {
+			execute!(stdout, Print(format!("  {name:<12}: {value}\n")));
+		}
+	}
+
+	// -- Agents
+	print_section_title("Agents");
+	for agent in pack_info.agents.iter() {
+		execute!(
+			stdout,
+			SetAttribute(Attribute::Bold),
+			Print(format!("• {}\n", agent.agent_ref)),
+			SetAttribute(Attribute::Reset)
+		);
+		print_agent_meta(&agent.meta);
+	}
+
+	// -- README
+	if let Some(readme) = pack_info.readme.as_deref() {
+		print_section_title("README.md");
+		execute!(stdout, Print(format!("{}\n", readme.trim_end())));
+	}
+
+	// -- CHANGELOG
+	if let Some(changelog) = pack_info.changelog.as_deref() {
+		print_section_title("CHANGELOG.md");
+		let lines = changelog.lines().collect::<Vec<_>>();
+		for line in lines.iter().take(CHANGELOG_MAX_LINES) {
+			execute!(stdout, Print(format!("{line}\n")));
+		}
+		if lines.len() > CHANGELOG_MAX_LINES {
+			execute!(
+				stdout,
+				SetAttribute(Attribute::Dim),
+				Print(format!("... ({} more lines)\n", lines.len() - CHANGELOG_MAX_LINES)),
+				SetAttribute(Attribute::Reset)
+			);
+		}
+	}
+}
+
+#[allow(unused_must_use)]
+fn print_section_title(title: &str) {
+	execute!(
+		stdout(),
+		SetAttribute(Attribute::Bold),
+		Print(format!("\n==== {title}\n\n")),
+		SetAttribute(Attribute::Reset)
+	);
+}
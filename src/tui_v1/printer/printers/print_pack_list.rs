@@ -1,52 +1,100 @@
-use crate::dir_context::PackDir;
+use crate::agent::AgentMeta;
+use crate::exec::{PackListItem, PackStatus};
 use crossterm::execute;
 use crossterm::style::{Attribute, Print, ResetColor, SetAttribute};
-use std::collections::HashSet;
 use std::io::stdout;
 
 #[allow(unused_must_use)] // TODO: need to remove and make this function return error
-pub fn print_pack_list(pack_dirs: &[&PackDir], _interactive: bool) {
+pub fn print_pack_list(items: &[PackListItem], _interactive: bool) {
 	let mut stdout = stdout();
 
 	let mut width = 0;
-	for pack_dir in pack_dirs.iter() {
-		width = width.max(pack_dir.namespace.len() + pack_dir.name.len());
+	for item in items.iter() {
+		width = width.max(item.namespace.len() + item.name.len());
 	}
 	width += 5;
 
-	let mut existing_set: HashSet<String> = HashSet::new();
-
-	// (active, pack_ref, pretty_path)
-	let data: Vec<(bool, String, String)> = pack_dirs
-		.iter()
-		.map(|p| {
-			let pack_ref = p.to_string();
-			let active = if existing_set.contains(&pack_ref) {
-				false
-			} else {
-				existing_set.insert(pack_ref.to_string());
-				true
-			};
-			(active, pack_ref, p.pretty_path())
-		})
-		.collect::<Vec<_>>();
+	if items.is_empty() {
+		execute!(stdout, Print("\nNo aipacks found.\n"));
+		return;
+	}
 
 	execute!(stdout, Print("\nListing all available aipacks:\n\n"));
 
-	for (active, name, path) in data.iter() {
-		let (bullet, weight_ref, weight_path) = if *active {
+	for item in items.iter() {
+		let (bullet, weight_ref, weight_path) = if item.active {
 			("•", Attribute::Bold, Attribute::Reset)
 		} else {
 			("-", Attribute::Dim, Attribute::Dim)
 		};
+		let name = &item.pack_ref;
+		let path = &item.path;
 		execute!(
 			stdout,
 			SetAttribute(weight_ref),
 			Print(format!("{bullet} {name:<width$}")),
 			ResetColor,
 			SetAttribute(weight_path),
-			Print(format!("- {path}\n")),
+			Print(format!("- {path}")),
 			ResetColor,
+			SetAttribute(Attribute::Dim),
+			Print(format!("  ({})\n", status_label(item))),
+			SetAttribute(Attribute::Reset)
+		);
+
+		// -- The eventual `# Meta` of the active pack main agent
+		if item.active {
+			print_agent_meta(&item.meta);
+		}
+	}
+}
+
+/// e.g., `custom`, `installed v0.1.0, capabilities: net`, or `outdated v0.1.0 -> v0.2.0`
+fn status_label(item: &PackListItem) -> String {
+	let status: &'static str = item.status.into();
+	let mut label = status.to_string();
+	if let Some(version) = item.version.as_deref() {
+		label.push_str(&format!(" v{version}"));
+	}
+	if item.status == PackStatus::Outdated
+		&& let Some(latest_version) = item.latest_version.as_deref()
+	{
+		label.push_str(&format!(" -> v{latest_version}"));
+	}
+	if !item.tags.is_empty() {
+		label.push_str(&format!(", tags: {}", item.tags.join(", ")));
+	}
+	if !item.capabilities.is_empty() {
+		label.push_str(&format!(", capabilities: {}", item.capabilities.join(", ")));
+	}
+	if !item.paths_allow.is_empty() {
+		label.push_str(&format!(", paths allow: {}", item.paths_allow.join(", ")));
+	}
+	label
+}
+
+/// Print the description, params, and examples of an agent meta (indented below the pack line)
+#[allow(unused_must_use)]
+pub(super) fn print_agent_meta(meta: &AgentMeta) {
+	let mut stdout = stdout();
+
+	if let Some(description) = meta.description() {
+		execute!(stdout, Print(format!("    {description}\n")));
+	}
+	for param in meta.params() {
+		let description = param.description.as_deref().map(|d| format!(" - {d}")).unwrap_or_default();
+		execute!(
+			stdout,
+			SetAttribute(Attribute::Dim),
+			Print(format!("    --arg {}{description}\n", param.signature())),
+			SetAttribute(Attribute::Reset)
+		);
+	}
+	for example in meta.examples() {
+		execute!(
+			stdout,
+			SetAttribute(Attribute::Dim),
+			Print(format!("    $ {example}\n")),
 			SetAttribute(Attribute::Reset)
 		);
 	}
@@ -4,41 +4,86 @@ use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt, BufReader};
 
 // region:    --- Types
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PromptParams {
 	pub message: String,
-	pub one_shot_res: OneShotTx<String>,
+	/// The answer choices (any text if empty)
+	pub choices: Vec<String>,
+	/// The answer when the user just press Enter
+	pub default: Option<String>,
+	/// The content to review before answering (e.g., the diff of a write to approve)
+	pub preview: Option<String>,
+	/// The user answer (None if the user cancelled the prompt, e.g., `Esc` in the TUI)
+	pub one_shot_res: OneShotTx<Option<String>>,
 }
 
 impl PromptParams {
-	pub fn new(message: impl Into<String>) -> (Self, OneShotRx<String>) {
+	pub fn new(message: impl Into<String>) -> (Self, OneShotRx<Option<String>>) {
 		let message = message.into();
-		let (tx, rx) = new_one_shot_channel::<String>("prompt-param-one-shot");
+		let (tx, rx) = new_one_shot_channel::<Option<String>>("prompt-param-one-shot");
 		(
 			Self {
 				message,
+				choices: Vec::new(),
+				default: None,
+				preview: None,
 				one_shot_res: tx,
 			},
 			rx,
 		)
 	}
+
+	pub fn with_choices(mut self, choices: Vec<String>, default: Option<String>) -> Self {
+		self.choices = choices;
+		self.default = default;
+		self
+	}
+
+	pub fn with_preview(mut self, preview: impl Into<String>) -> Self {
+		self.preview = Some(preview.into());
+		self
+	}
+
+	/// e.g., `[yes/no] (default: yes) ` (empty string if no choices and no default)
+	pub fn hint(&self) -> String {
+		let mut hint = String::new();
+		if !self.choices.is_empty() {
+			hint.push_str(&format!("[{}] ", self.choices.join("/")));
+		}
+		if let Some(default) = self.default.as_deref() {
+			hint.push_str(&format!("(default: {default}) "));
+		}
+		hint
+	}
 }
 
 // endregion: --- Types
 
 pub async fn prompt(param: PromptParams) -> Result<()> {
-	let PromptParams { message, one_shot_res } = param;
+	let hint = param.hint();
+	let PromptParams {
+		message,
+		preview,
+		one_shot_res,
+		..
+	} = param;
 
 	let mut stdout = io::stdout();
 	let mut stdin = BufReader::new(io::stdin());
 	let mut input = String::new();
 
+	if let Some(preview) = preview {
+		stdout.write_all(format!("\n{}\n", preview.trim_end()).as_bytes()).await?;
+	}
 	stdout.write_all(message.as_bytes()).await?;
+	if !hint.is_empty() {
+		stdout.write_all(format!("{hint}> ").as_bytes()).await?;
+	}
 	stdout.flush().await?;
 
 	stdin.read_line(&mut input).await?;
 
-	one_shot_res.send(input).await?;
+	one_shot_res.send(Some(input)).await?;
 
 	Ok(())
 }
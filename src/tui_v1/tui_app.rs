@@ -1,7 +1,7 @@
 use crate::Result;
 use crate::event::{Tx, new_channel};
 use crate::exec::cli::CliArgs;
-use crate::exec::{ExecActionEvent, ExecutorTx};
+use crate::exec::{ExecActionEvent, ExecStatusEvent, ExecutorTx};
 use crate::hub::{HubEvent, get_hub};
 use crate::term::{TermTitleGuard, safer_println};
 use crate::tui_v1::hub_event_handler::handle_hub_event;
@@ -50,7 +50,7 @@ impl TuiAppV1 {
 		let in_reader = self.start_app(exit_tx.into(), interactive)?;
 
 		// -- Exec the first cli_args
-		let exec_cmd: ExecActionEvent = cli_args.cmd.into();
+		let exec_cmd: ExecActionEvent = cli_args.into();
 		let executor_tx = self.executor_tx();
 
 		tokio::spawn(async move {
@@ -71,6 +71,29 @@ impl TuiAppV1 {
 		Ok(())
 	}
 
+	/// Start the app for a stdio server command (e.g., `aip mcp-serve`), where the stdout is the protocol channel.
+	///
+	/// The hub messages and errors are printed to the stderr, until the end of the exec.
+	pub async fn start_stdio_with_args(self, cli_args: CliArgs) -> Result<()> {
+		let hub_rx = get_hub().take_rx()?;
+
+		let exec_cmd: ExecActionEvent = cli_args.into();
+		self.executor_tx().send(exec_cmd).await;
+
+		while let Ok(event) = hub_rx.recv().await {
+			match event {
+				HubEvent::Message(msg) => eprintln!("{msg}"),
+				HubEvent::InfoShort(msg) => eprintln!("{msg}"),
+				HubEvent::LuaPrint(text) => eprintln!("{text}"),
+				HubEvent::Error { error } => eprintln!("Error: {error}"),
+				HubEvent::Executor(ExecStatusEvent::EndExec) | HubEvent::Quit => break,
+				_ => (),
+			}
+		}
+
+		Ok(())
+	}
+
 	/// Very rundemetary app for now, will become full Ratatui app
 	/// - It starts the handle_hub_event which is mostly for display
 	/// - And starts the handle_in_event to react to user input
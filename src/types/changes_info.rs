@@ -25,6 +25,8 @@ impl IntoLua for ChangesInfo {
 
 #[derive(Debug)]
 pub struct FailChange {
+	/// The 1-based index of the change (block, hunk, or edit) in the changes
+	pub idx: usize,
 	pub search: String,
 	pub replace: String,
 	pub reason: String,
@@ -33,6 +35,7 @@ pub struct FailChange {
 impl IntoLua for FailChange {
 	fn into_lua(self, lua: &Lua) -> mlua::Result<Value> {
 		let table = lua.create_table()?;
+		table.set("idx", self.idx)?;
 		table.set("search", self.search)?;
 		table.set("replace", self.replace)?;
 		table.set("reason", self.reason)?;
@@ -34,6 +34,10 @@ pub struct CsvOptions {
 
 	/// Writing only: Suppress header emission even if headers are available. Default: false.
 	pub skip_header_row: Option<bool>,
+
+	/// The file text encoding label (e.g., "latin1", "windows-1252", "utf-16le"). Default: "utf-8".
+	/// Only applies to the file read and write functions.
+	pub encoding: Option<String>,
 }
 
 impl FromLua for CsvOptions {
@@ -49,6 +53,7 @@ impl FromLua for CsvOptions {
 				let skip_empty_lines = table.x_get_bool("skip_empty_lines");
 				let comment = table.x_get_string("comment");
 				let skip_header_row = table.x_get_bool("skip_header_row");
+				let encoding = table.x_get_string("encoding");
 
 				let header_labels = if let Some(val) = table.x_get_value("header_labels") {
 					match val {
@@ -80,6 +85,7 @@ impl FromLua for CsvOptions {
 					skip_empty_lines,
 					comment,
 					skip_header_row,
+					encoding,
 				})
 			}
 			other => Err(mlua::Error::FromLuaConversionError {
@@ -1,15 +1,46 @@
-use crate::script::LuaValueExt;
+use crate::{Error, Result};
 use mlua::{FromLua, Lua, Value};
 
+/// The options of the `aip.file.copy`, `aip.file.move`, `aip.file.rename`, and `aip.file.symlink` APIs.
+///
+/// The `overwrite` can be a boolean (`true` is `"replace"`, `false` is `"error"`) or the policy name.
 #[derive(Debug, Default)]
 pub struct FileOverOptions {
-	/// If true (default), overwrite the destination if it exists.
-	pub overwrite: Option<bool>,
+	/// The policy when the destination exists (default `"error"`).
+	pub overwrite: Option<OverwritePolicy>,
+}
+
+/// What to do when the destination exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+	/// Fail (the default)
+	#[default]
+	Error,
+	/// Replace the destination
+	Replace,
+	/// Do nothing, and return the existing destination
+	Skip,
+	/// Move the destination to the trash, then write
+	Trash,
+}
+
+impl OverwritePolicy {
+	pub fn from_name(name: &str) -> Result<Self> {
+		match name {
+			"error" => Ok(Self::Error),
+			"replace" => Ok(Self::Replace),
+			"skip" => Ok(Self::Skip),
+			"trash" => Ok(Self::Trash),
+			other => Err(Error::custom(format!(
+				"overwrite '{other}' is not valid (must be a boolean, or 'error', 'replace', 'skip', or 'trash')"
+			))),
+		}
+	}
 }
 
 impl FileOverOptions {
-	pub fn overwrite(&self) -> bool {
-		self.overwrite.unwrap_or(false)
+	pub fn overwrite(&self) -> OverwritePolicy {
+		self.overwrite.unwrap_or_default()
 	}
 }
 
@@ -19,7 +50,19 @@ impl FromLua for FileOverOptions {
 			.as_table()
 			.ok_or(crate::Error::custom("FileOverOptions should be a table"))?;
 
-		let overwrite = table.x_get_bool("overwrite");
+		let overwrite = match table.get::<Value>("overwrite")? {
+			Value::Nil => None,
+			Value::Boolean(true) => Some(OverwritePolicy::Replace),
+			Value::Boolean(false) => Some(OverwritePolicy::Error),
+			Value::String(name) => Some(OverwritePolicy::from_name(&name.to_str()?)?),
+			other => {
+				return Err(Error::custom(format!(
+					"overwrite must be a boolean or a string, but was {}",
+					other.type_name()
+				))
+				.into());
+			}
+		};
 
 		Ok(Self { overwrite })
 	}
@@ -1,11 +1,20 @@
-use mlua::IntoLua;
+use mlua::{IntoLua, LuaSerdeExt as _};
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Represents a Markdown block with optional language and content.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MdBlock {
 	pub lang: Option<String>,
 	pub content: String,
+	/// The full fence info string (e.g., `rust file=src/main.rs`), when different from the lang
+	pub info: Option<String>,
+	/// The info string attributes after the lang (`key=value` or `key="some value"`, and `true` for the flags)
+	pub attrs: Option<BTreeMap<String, serde_json::Value>>,
+	/// The opening fence line (1 based)
+	pub start_line: Option<usize>,
+	/// The closing fence line (1 based)
+	pub end_line: Option<usize>,
 }
 
 impl MdBlock {
@@ -15,6 +24,7 @@ impl MdBlock {
 		MdBlock {
 			lang,
 			content: content.into(),
+			..Default::default()
 		}
 	}
 }
@@ -27,13 +37,25 @@ impl Serialize for MdBlock {
 		S: serde::Serializer,
 	{
 		use serde::ser::SerializeStruct;
-		let mut state = serializer.serialize_struct("MdBlock", 3)?;
+		let mut state = serializer.serialize_struct("MdBlock", 7)?;
 		state.serialize_field("_type", "MdBlock")?;
 
 		if let Some(lang) = &self.lang {
 			state.serialize_field("lang", lang)?;
 		}
 		state.serialize_field("content", &self.content)?;
+		if let Some(info) = &self.info {
+			state.serialize_field("info", info)?;
+		}
+		if let Some(attrs) = &self.attrs {
+			state.serialize_field("attrs", attrs)?;
+		}
+		if let Some(start_line) = self.start_line {
+			state.serialize_field("start_line", &start_line)?;
+		}
+		if let Some(end_line) = self.end_line {
+			state.serialize_field("end_line", &end_line)?;
+		}
 
 		state.end()
 	}
@@ -51,6 +73,12 @@ impl IntoLua for MdBlock {
 
 		table.set("lang", self.lang)?;
 		table.set("content", self.content)?;
+		table.set("info", self.info)?;
+		if let Some(attrs) = self.attrs {
+			table.set("attrs", lua.to_value(&attrs)?)?;
+		}
+		table.set("start_line", self.start_line)?;
+		table.set("end_line", self.end_line)?;
 		Ok(mlua::Value::Table(table))
 	}
 }
@@ -14,14 +14,19 @@ mod md_block;
 mod md_heading;
 mod md_ref;
 mod md_section;
+mod pack_capability;
 mod pack_identity;
 mod pack_ref;
+mod res_usage;
 mod run_agent_options;
 mod run_agent_response;
+mod run_env;
 mod save_options;
+mod scaffold_options;
 mod sort_by_globs_options;
 mod web_options;
 mod web_response;
+mod write_confirm;
 mod yaml_docs;
 mod zip_options;
 
@@ -39,13 +44,18 @@ pub use md_block::*;
 pub use md_heading::*;
 pub use md_ref::*;
 pub use md_section::*;
+pub use pack_capability::*;
 pub use pack_identity::*;
 pub use pack_ref::*;
+pub use res_usage::*;
 pub use run_agent_options::*;
 pub use run_agent_response::*;
+pub use run_env::*;
 pub use save_options::*;
+pub use scaffold_options::*;
 pub use web_options::*;
 pub use web_response::*;
+pub use write_confirm::*;
 pub use yaml_docs::*;
 pub use zip_options::*;
 
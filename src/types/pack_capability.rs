@@ -0,0 +1,685 @@
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use simple_fs::{SPath, get_glob_set};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// A capability a pack declares in its `pack.toml` (`[pack] capabilities = ["net", "exec"]`)
+/// and the user consents to at `aip install`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PackCapability {
+	/// `aip.web.*` requests
+	Net,
+	/// `aip.cmd.exec`, `aip.git.*`, Lua `os.execute` and `io.popen`
+	Exec,
+	/// File reads outside the workspace (other than the pack dir and the pack base support dir)
+	ReadOutsideWorkspace,
+	/// File writes outside the workspace (other than the pack base support dir)
+	WriteOutsideWorkspace,
+	/// Environment variables (Lua `os.getenv`), where API keys and tokens usually are
+	Secrets,
+}
+
+impl PackCapability {
+	pub const ALL: [PackCapability; 5] = [
+		PackCapability::Net,
+		PackCapability::Exec,
+		PackCapability::ReadOutsideWorkspace,
+		PackCapability::WriteOutsideWorkspace,
+		PackCapability::Secrets,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PackCapability::Net => "net",
+			PackCapability::Exec => "exec",
+			PackCapability::ReadOutsideWorkspace => "read-outside-workspace",
+			PackCapability::WriteOutsideWorkspace => "write-outside-workspace",
+			PackCapability::Secrets => "secrets",
+		}
+	}
+
+	pub fn description(&self) -> &'static str {
+		match self {
+			PackCapability::Net => "Make network requests (aip.web.*)",
+			PackCapability::Exec => "Execute commands (aip.cmd.exec, aip.git.*, os.execute, io.popen)",
+			PackCapability::ReadOutsideWorkspace => "Read files outside the workspace",
+			PackCapability::WriteOutsideWorkspace => "Write files outside the workspace",
+			PackCapability::Secrets => "Read environment variables (os.getenv), e.g., API keys",
+		}
+	}
+
+	/// Parse the `pack.toml` capability names (fails on unknown names)
+	pub fn list_from_names(names: &[String]) -> Result<Vec<PackCapability>> {
+		let mut capabilities = Vec::new();
+		for name in names {
+			let capability = PackCapability::from_str(name)?;
+			if !capabilities.contains(&capability) {
+				capabilities.push(capability);
+			}
+		}
+		Ok(capabilities)
+	}
+}
+
+impl FromStr for PackCapability {
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<Self> {
+		PackCapability::ALL
+			.into_iter()
+			.find(|capability| capability.as_str() == name)
+			.ok_or_else(|| {
+				let valid = PackCapability::ALL.map(|c| c.as_str()).join(", ");
+				Error::custom(format!(
+					"Invalid pack capability '{name}' (valid capabilities: {valid})"
+				))
+			})
+	}
+}
+
+impl std::fmt::Display for PackCapability {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+// region:    --- PackCapabilities
+
+/// The consented capabilities of an installed pack, enforced for the Lua calls of its agents.
+///
+/// The file accesses are also checked against paths (see `with_paths`):
+/// - The workspace, the pack dir (read only), and the pack base support dir are always accessible.
+/// - The pack.toml `paths_allow` globs (consented at install) are accessible without the `*-outside-workspace` capabilities.
+/// - The user config `[sandbox] paths_deny` globs are never accessible (even in the workspace).
+///
+/// NOTE: Only the packs installed with `aip install` (which records the consent) are enforced,
+///       the custom packs and the agent files are the user's own.
+///       But a sub agent run (`aip.agent.run`) is restricted by the capabilities of its caller (see `with_parent`),
+///       so that a pack cannot escalate by running an agent it wrote.
+#[derive(Debug, Clone)]
+pub struct PackCapabilities {
+	pack_identity: String,
+	granted: Vec<PackCapability>,
+	/// The `~/.aipack-base/support/pack/<namespace>/<name>/` dir, always writable by the pack
+	base_support_dir: SPath,
+	/// The installed pack dir, always readable by the pack
+	pack_dir: Option<SPath>,
+	wks_dir: Option<SPath>,
+	/// The absolute path globs of the pack.toml `paths_allow` (`~/` resolved)
+	paths_allow: PathGlobs,
+	/// The path globs of the user config `[sandbox] paths_deny` (the relative globs match the workspace relative paths)
+	paths_deny: PathGlobs,
+	/// The checked calls, shared by the clones (the Lua engines of the run), for the governance report
+	usage: Arc<Mutex<Vec<CapabilityUsage>>>,
+	/// The capabilities of the caller run (for a sub agent run), which must also allow the accesses
+	parent: Option<Arc<PackCapabilities>>,
+}
+
+/// The capability checks of a run (a check is a gated call, e.g., `aip.web.get`)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CapabilityUsage {
+	pub capability: PackCapability,
+	pub calls: u32,
+	pub denied: u32,
+}
+
+/// Constructors
+impl PackCapabilities {
+	pub fn new(pack_identity: impl Into<String>, granted: Vec<PackCapability>, base_support_dir: SPath) -> Self {
+		Self {
+			pack_identity: pack_identity.into(),
+			granted,
+			base_support_dir,
+			pack_dir: None,
+			wks_dir: None,
+			paths_allow: PathGlobs::default(),
+			paths_deny: PathGlobs::default(),
+			usage: Default::default(),
+			parent: None,
+		}
+	}
+
+	/// Restrict these capabilities by the ones of the caller run (the accesses must be allowed by both).
+	pub fn with_parent(mut self, parent: PackCapabilities) -> Self {
+		self.parent = Some(Arc::new(parent));
+		self
+	}
+
+	/// Set the paths the file access checks are based on (fails on invalid globs)
+	pub fn with_paths(
+		mut self,
+		pack_dir: SPath,
+		wks_dir: Option<SPath>,
+		paths_allow: Vec<String>,
+		paths_deny: Vec<String>,
+	) -> Result<Self> {
+		self.paths_allow = PathGlobs::new(paths_allow)
+			.map_err(|err| Error::custom(format!("Invalid pack.toml 'paths_allow'. {err}")))?;
+		self.paths_deny = PathGlobs::new(paths_deny)
+			.map_err(|err| Error::custom(format!("Invalid config '[sandbox] paths_deny'. {err}")))?;
+		self.pack_dir = Some(pack_dir);
+		self.wks_dir = wks_dir;
+		Ok(self)
+	}
+}
+
+/// Getters
+impl PackCapabilities {
+	/// The granted capabilities (also granted to the eventual caller run)
+	pub fn granted(&self) -> Vec<PackCapability> {
+		self.granted.iter().copied().filter(|c| self.has(*c)).collect()
+	}
+
+	/// The capability checks so far (in the order of the first check)
+	pub fn usage(&self) -> Vec<CapabilityUsage> {
+		self.usage.lock().map(|usage| usage.clone()).unwrap_or_default()
+	}
+}
+
+/// Checks
+impl PackCapabilities {
+	pub fn has(&self, capability: PackCapability) -> bool {
+		self.granted.contains(&capability) && self.parent.as_ref().is_none_or(|parent| parent.has(capability))
+	}
+
+	/// Returns an error if the capability was not declared by the pack, or by the caller run pack
+	/// (`what` is the call, e.g., `aip.web.get`)
+	pub fn check(&self, capability: PackCapability, what: &str) -> Result<()> {
+		self.check_own(capability, what)?;
+		match self.parent.as_ref() {
+			Some(parent) => parent.check(capability, &format!("{what} (from a sub agent)")),
+			None => Ok(()),
+		}
+	}
+
+	/// Check the capability against the pack declared ones only (not the caller run ones)
+	fn check_own(&self, capability: PackCapability, what: &str) -> Result<()> {
+		let granted = self.granted.contains(&capability);
+		self.record_usage(capability, granted);
+		if granted {
+			return Ok(());
+		}
+		let granted = if self.granted.is_empty() {
+			"none".to_string()
+		} else {
+			self.granted.iter().map(|c| c.as_str()).collect::<Vec<_>>().join(", ")
+		};
+		Err(Error::custom(format!(
+			"Pack '{}' cannot call '{what}', it requires the '{capability}' capability, which the pack did not declare (declared: {granted}).\n\
+			 The pack must add it to its pack.toml '[pack] capabilities', and be reinstalled.",
+			self.pack_identity
+		)))
+	}
+
+	/// Check the read of a file (`what` is the call, e.g., `aip.file.load`).
+	///
+	/// Outside of the workspace, the pack dir, the pack base support dir, and the `paths_allow`,
+	/// requires the `read-outside-workspace` capability.
+	///
+	/// NOTE: The path is checked with its symlinks resolved (see `resolve_real_path`).
+	pub fn check_read(&self, full_path: &SPath, what: &str) -> Result<()> {
+		let real_path = resolve_real_path(full_path);
+		self.check_not_denied(full_path, &real_path, what)?;
+		// NOTE: The pack own (installed) files are readable, whatever the caller run pack
+		if self.pack_dir.as_ref().is_some_and(|pack_dir| is_under(&real_path, pack_dir)) {
+			return Ok(());
+		}
+		if self.is_in_wks(&real_path)
+			|| is_under(&real_path, &self.base_support_dir)
+			|| self.is_path_allowed(&real_path)
+		{
+			return self.check_parent_read(full_path, what);
+		}
+		self.check_own(
+			PackCapability::ReadOutsideWorkspace,
+			&format!("{what} of '{full_path}'"),
+		)?;
+		self.check_parent_read(full_path, what)
+	}
+
+	/// Check the write of a file (`what` is the call, e.g., `aip.file.save`).
+	///
+	/// Outside of the workspace, the pack base support dir, and the `paths_allow`,
+	/// requires the `write-outside-workspace` capability.
+	///
+	/// NOTE: The path is checked with its symlinks resolved (see `resolve_real_path`).
+	pub fn check_write(&self, full_path: &SPath, what: &str) -> Result<()> {
+		let real_path = resolve_real_path(full_path);
+		self.check_not_denied(full_path, &real_path, what)?;
+		if self.is_in_wks(&real_path)
+			|| is_under(&real_path, &self.base_support_dir)
+			|| self.paths_allow.is_match(real_path.as_str())
+		{
+			return self.check_parent_write(full_path, what);
+		}
+		self.check_own(
+			PackCapability::WriteOutsideWorkspace,
+			&format!("{what} to '{full_path}'"),
+		)?;
+		self.check_parent_write(full_path, what)
+	}
+
+	/// Returns true if the path matches the pack.toml `paths_allow` (consented at install),
+	/// and the one of the eventual caller run pack
+	pub fn is_path_allowed(&self, full_path: &SPath) -> bool {
+		self.paths_allow.is_match(full_path.as_str())
+			&& self.parent.as_ref().is_none_or(|parent| parent.is_path_allowed(full_path))
+	}
+
+	/// Returns true if the path matches the user config `[sandbox] paths_deny`
+	/// (the full path, or the workspace relative path for the paths in the workspace)
+	pub fn is_path_denied(&self, full_path: &SPath) -> bool {
+		if self.paths_deny.is_match(full_path.as_str())
+			|| self.parent.as_ref().is_some_and(|parent| parent.is_path_denied(full_path))
+		{
+			return true;
+		}
+		self.wks_dir
+			.iter()
+			.flat_map(|wks_dir| [wks_dir.clone(), resolve_real_path(wks_dir)])
+			.any(|wks_dir| {
+				full_path.starts_with(&wks_dir)
+					&& full_path
+						.diff(&wks_dir)
+						.is_some_and(|rel_path| self.paths_deny.is_match(rel_path.as_str()))
+			})
+	}
+}
+
+// region:    --- Support
+
+impl PackCapabilities {
+	fn is_in_wks(&self, real_path: &SPath) -> bool {
+		self.wks_dir.as_ref().is_some_and(|wks_dir| is_under(real_path, wks_dir))
+	}
+
+	fn check_parent_read(&self, full_path: &SPath, what: &str) -> Result<()> {
+		match self.parent.as_ref() {
+			Some(parent) => parent.check_read(full_path, &format!("{what} (from a sub agent)")),
+			None => Ok(()),
+		}
+	}
+
+	fn check_parent_write(&self, full_path: &SPath, what: &str) -> Result<()> {
+		match self.parent.as_ref() {
+			Some(parent) => parent.check_write(full_path, &format!("{what} (from a sub agent)")),
+			None => Ok(()),
+		}
+	}
+
+	/// Check the path as given, and with its symlinks resolved
+	fn check_not_denied(&self, full_path: &SPath, real_path: &SPath, what: &str) -> Result<()> {
+		if !self.is_path_denied(full_path) && !self.is_path_denied(real_path) {
+			return Ok(());
+		}
+		Err(Error::custom(format!(
+			"Pack '{}' cannot access '{full_path}' ({what}), the path matches the user config '[sandbox] paths_deny'.",
+			self.pack_identity
+		)))
+	}
+
+	fn record_usage(&self, capability: PackCapability, granted: bool) {
+		let Ok(mut usage) = self.usage.lock() else {
+			return;
+		};
+		let item = match usage.iter_mut().find(|item| item.capability == capability) {
+			Some(item) => item,
+			None => {
+				usage.push(CapabilityUsage {
+					capability,
+					calls: 0,
+					denied: 0,
+				});
+				let last_idx = usage.len() - 1;
+				&mut usage[last_idx]
+			}
+		};
+		item.calls += 1;
+		if !granted {
+			item.denied += 1;
+		}
+	}
+}
+
+/// Compiled path globs (never match when empty)
+#[derive(Clone, Default)]
+struct PathGlobs {
+	globs: Vec<String>,
+	is_match: Option<Arc<PathMatchFn>>,
+}
+
+type PathMatchFn = dyn Fn(&str) -> bool + Send + Sync;
+
+impl PathGlobs {
+	fn new(globs: Vec<String>) -> Result<Self> {
+		if globs.is_empty() {
+			return Ok(Self::default());
+		}
+		let glob_refs = globs.iter().map(String::as_str).collect::<Vec<_>>();
+		let glob_set =
+			get_glob_set(&glob_refs).map_err(|err| Error::custom(format!("Invalid globs {globs:?}. {err}")))?;
+		Ok(Self {
+			globs,
+			is_match: Some(Arc::new(move |path: &str| glob_set.is_match(path))),
+		})
+	}
+
+	fn is_match(&self, path: &str) -> bool {
+		self.is_match.as_ref().is_some_and(|is_match| is_match(path))
+	}
+}
+
+impl std::fmt::Debug for PathGlobs {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_list().entries(&self.globs).finish()
+	}
+}
+
+/// Returns true if the (resolved) path is in the dir (as given, or with its symlinks resolved)
+fn is_under(real_path: &SPath, dir: &SPath) -> bool {
+	real_path.starts_with(dir) || real_path.starts_with(resolve_real_path(dir))
+}
+
+/// Max number of dangling symlinks followed by `resolve_real_path` (like the OS `ELOOP` limit)
+const MAX_SYMLINK_HOPS: usize = 40;
+
+/// Resolve the symlinks of a path, also for a path that does not exist yet (e.g., a file to be created).
+///
+/// The deepest existing ancestor is canonicalized (the dangling symlinks are followed),
+/// and the rest of the path is appended collapsed.
+fn resolve_real_path(full_path: &SPath) -> SPath {
+	let mut path = full_path.clone().into_collapsed();
+	for _ in 0..MAX_SYMLINK_HOPS {
+		let mut existing = path.clone();
+		let mut rest: Vec<String> = Vec::new();
+		loop {
+			if let Ok(real) = existing.canonicalize() {
+				return rest.iter().rev().fold(real, |acc, name| acc.join(name)).into_collapsed();
+			}
+			// -- A dangling symlink, follow its target (relative to the link dir)
+			if let Ok(target) = std::fs::read_link(existing.as_std_path()) {
+				let target = SPath::from_std_path_buf(target).unwrap_or_else(|_| existing.clone());
+				let target = match existing.parent() {
+					Some(link_dir) if !target.is_absolute() => link_dir.join(target),
+					_ => target,
+				};
+				path = rest.iter().rev().fold(target, |acc, name| acc.join(name)).into_collapsed();
+				break;
+			}
+			let Some(parent) = existing.parent() else {
+				return path;
+			};
+			rest.push(existing.name().to_string());
+			existing = parent;
+		}
+	}
+	path
+}
+
+// endregion: --- Support
+
+// endregion: --- PackCapabilities
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_pack_capability_list_from_names() -> Result<()> {
+		// -- Exec
+		let capabilities =
+			PackCapability::list_from_names(&["net".to_string(), "write-outside-workspace".to_string()])?;
+
+		// -- Check
+		assert_eq!(
+			capabilities,
+			vec![PackCapability::Net, PackCapability::WriteOutsideWorkspace]
+		);
+		let err = PackCapability::list_from_names(&["network".to_string()])
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("valid capabilities: net, exec"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_pack_capability_capabilities_check() -> Result<()> {
+		// -- Setup & Fixtures
+		let capabilities = PackCapabilities::new(
+			"acme@deploy-helper",
+			vec![PackCapability::Net],
+			SPath::new("/home/me/.aipack-base/support/pack/acme/deploy-helper"),
+		);
+
+		// -- Exec & Check
+		assert!(capabilities.check(PackCapability::Net, "aip.web.get").is_ok());
+		let err = capabilities
+			.check(PackCapability::Exec, "aip.cmd.exec")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("requires the 'exec' capability"));
+		assert!(
+			capabilities
+				.check_write(
+					&SPath::new("/home/me/.aipack-base/support/pack/acme/deploy-helper/cache.json"),
+					"aip.file.save"
+				)
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_write(&SPath::new("/home/me/.ssh/config"), "aip.file.save")
+				.is_err()
+		);
+		// the usage is shared by the clones
+		let _ = capabilities.clone().check(PackCapability::Net, "aip.web.post");
+		let usage = capabilities.usage();
+		assert_eq!(usage.len(), 3);
+		assert_eq!(
+			(usage[0].capability, usage[0].calls, usage[0].denied),
+			(PackCapability::Net, 2, 0)
+		);
+		assert_eq!((usage[1].capability, usage[1].denied), (PackCapability::Exec, 1));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_pack_capability_capabilities_with_parent() -> Result<()> {
+		// -- Setup & Fixtures
+		let parent = PackCapabilities::new(
+			"acme@caller",
+			vec![PackCapability::Net],
+			SPath::new("/home/me/.aipack-base/support/pack/acme/caller"),
+		)
+		.with_paths(
+			SPath::new("/home/me/.aipack-base/pack/installed/acme/caller"),
+			Some(SPath::new("/home/me/proj")),
+			Vec::new(),
+			Vec::new(),
+		)?;
+		let capabilities = PackCapabilities::new(
+			"acme@callee",
+			vec![PackCapability::Net, PackCapability::Exec, PackCapability::ReadOutsideWorkspace],
+			SPath::new("/home/me/.aipack-base/support/pack/acme/callee"),
+		)
+		.with_paths(
+			SPath::new("/home/me/.aipack-base/pack/installed/acme/callee"),
+			Some(SPath::new("/home/me/proj")),
+			Vec::new(),
+			Vec::new(),
+		)?
+		.with_parent(parent);
+
+		// -- Exec & Check
+		assert_eq!(capabilities.granted(), vec![PackCapability::Net]);
+		assert!(capabilities.check(PackCapability::Net, "aip.web.get").is_ok());
+		let err = capabilities
+			.check(PackCapability::Exec, "aip.cmd.exec")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(
+			err.to_string()
+				.contains("Pack 'acme@caller' cannot call 'aip.cmd.exec (from a sub agent)'")
+		);
+		assert!(
+			capabilities
+				.check_read(&SPath::new("/home/me/proj/src/main.rs"), "aip.file.load")
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_read(
+					&SPath::new("/home/me/.aipack-base/pack/installed/acme/callee/lua/utils.lua"),
+					"aip.file.load"
+				)
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_read(&SPath::new("/home/me/.ssh/config"), "aip.file.load")
+				.is_err()
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_pack_capability_capabilities_paths() -> Result<()> {
+		// -- Setup & Fixtures
+		let capabilities = PackCapabilities::new(
+			"acme@indexer",
+			Vec::new(),
+			SPath::new("/home/me/.aipack-base/support/pack/acme/indexer"),
+		)
+		.with_paths(
+			SPath::new("/home/me/.aipack-base/pack/installed/acme/indexer"),
+			Some(SPath::new("/home/me/proj")),
+			vec!["/home/me/.config/acme/**".to_string()],
+			vec!["**/.env".to_string(), "secrets/**".to_string(), "/home/me/.ssh/**".to_string()],
+		)?;
+
+		// -- Exec & Check
+		// workspace, pack dir, and paths_allow
+		assert!(
+			capabilities
+				.check_read(&SPath::new("/home/me/proj/src/main.rs"), "aip.file.load")
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_read(
+					&SPath::new("/home/me/.aipack-base/pack/installed/acme/indexer/lua/utils.lua"),
+					"aip.file.load"
+				)
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_read(&SPath::new("/home/me/.config/acme/a.toml"), "aip.file.load")
+				.is_ok()
+		);
+		assert!(
+			capabilities
+				.check_write(&SPath::new("/home/me/.config/acme/a.toml"), "aip.file.save")
+				.is_ok()
+		);
+		// outside without the capabilities
+		let err = capabilities
+			.check_read(&SPath::new("/etc/hosts"), "aip.file.load")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("'read-outside-workspace' capability"));
+		assert!(
+			capabilities
+				.check_write(
+					&SPath::new("/home/me/.aipack-base/pack/installed/acme/indexer/main.aip"),
+					"aip.file.save"
+				)
+				.is_err()
+		);
+		// paths_deny (even in the workspace)
+		let err = capabilities
+			.check_read(&SPath::new("/home/me/proj/api/.env"), "aip.file.load")
+			.err()
+			.ok_or("Should fail")?;
+		assert!(err.to_string().contains("[sandbox] paths_deny"));
+		assert!(
+			capabilities
+				.check_write(&SPath::new("/home/me/proj/secrets/key.pem"), "aip.file.save")
+				.is_err()
+		);
+		assert!(
+			capabilities
+				.check_read(&SPath::new("/home/me/.ssh/id_rsa"), "aip.file.load")
+				.is_err()
+		);
+		assert!(!capabilities.is_path_denied(&SPath::new("/home/me/proj/docs/secrets/readme.md")));
+
+		Ok(())
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn test_pack_capability_capabilities_symlink_escape() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_dir = SPath::new("tests-data/sandbox-01/.tmp/test_pack_capability_capabilities_symlink_escape");
+		let _ = std::fs::remove_dir_all(fx_dir.as_std_path());
+		let wks_dir = fx_dir.join("wks");
+		let outside_dir = fx_dir.join("outside");
+		std::fs::create_dir_all(wks_dir.as_std_path())?;
+		std::fs::create_dir_all(outside_dir.as_std_path())?;
+		std::fs::write(outside_dir.join("secret.txt").as_std_path(), "secret")?;
+		let wks_dir = wks_dir.canonicalize()?;
+		let outside_dir = outside_dir.canonicalize()?;
+		std::os::unix::fs::symlink(outside_dir.as_std_path(), wks_dir.join("escape").as_std_path())?;
+		std::os::unix::fs::symlink("../outside/new.txt", wks_dir.join("dangling.txt").as_std_path())?;
+		let capabilities = PackCapabilities::new(
+			"acme@escape",
+			Vec::new(),
+			SPath::new("/home/me/.aipack-base/support/pack/acme/escape"),
+		)
+		.with_paths(
+			SPath::new("/home/me/.aipack-base/pack/installed/acme/escape"),
+			Some(wks_dir.clone()),
+			Vec::new(),
+			Vec::new(),
+		)?;
+
+		// -- Exec & Check
+		assert!(
+			capabilities
+				.check_read(&wks_dir.join("escape/secret.txt"), "aip.file.load")
+				.is_err()
+		);
+		assert!(
+			capabilities
+				.check_write(&wks_dir.join("escape/new-dir/new.txt"), "aip.file.save")
+				.is_err()
+		);
+		assert!(
+			capabilities
+				.check_write(&wks_dir.join("dangling.txt"), "aip.file.save")
+				.is_err()
+		);
+		assert!(
+			capabilities
+				.check_read(&wks_dir.join("sub/../escape/secret.txt"), "aip.file.load")
+				.is_err()
+		);
+		assert!(capabilities.check_write(&wks_dir.join("sub/new.txt"), "aip.file.save").is_ok());
+
+		std::fs::remove_dir_all(fx_dir.as_std_path())?;
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
@@ -0,0 +1,36 @@
+/// The resource usage of a run beyond its AI tokens, added to the run (see `RtModel::add_res_usage`).
+/// - From the processes spawned by `aip.cmd.exec` (cpu, peak memory, and disk blocks read/written)
+/// - From the files layer (`aip.file.load`, `aip.file.save`, ... content bytes)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResUsage {
+	/// The user + system cpu time of the spawned processes, in milliseconds
+	pub cpu_ms: i64,
+	/// The max resident memory of the spawned processes, in bytes
+	pub peak_mem: i64,
+	pub read_bytes: i64,
+	pub write_bytes: i64,
+}
+
+/// Constructors
+impl ResUsage {
+	pub fn from_read(bytes: usize) -> Self {
+		Self {
+			read_bytes: bytes as i64,
+			..Default::default()
+		}
+	}
+
+	pub fn from_write(bytes: usize) -> Self {
+		Self {
+			write_bytes: bytes as i64,
+			..Default::default()
+		}
+	}
+}
+
+/// Getters
+impl ResUsage {
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+}
@@ -1,5 +1,6 @@
 // region:    --- RunCommandResponse
 
+use crate::run::RunRedoData;
 use crate::script::{serde_value_to_lua_value, serde_values_to_lua_values};
 use mlua::IntoLua;
 use serde::Serialize;
@@ -13,6 +14,9 @@ pub struct RunAgentResponse {
 	pub outputs: Option<Vec<Value>>,
 	pub after_all: Option<Value>,
 	pub redo_requested: bool,
+	/// The data to redo some tasks of this run (for the TUI task redo)
+	#[serde(skip)]
+	pub run_redo_data: Option<RunRedoData>,
 }
 
 impl IntoLua for RunAgentResponse {
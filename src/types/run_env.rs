@@ -0,0 +1,131 @@
+use crate::agent::EnvValue;
+use crate::support::cred::get_secret;
+use crate::support::text::register_secret;
+use crate::types::{PackCapabilities, PackCapability};
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// The agent option `env`, resolved for the run.
+///
+/// The variables are injected in the `aip.cmd.exec` commands, and read with `aip.env.get`.
+/// The secret values are registered to be masked (see `support::text::register_secret`).
+#[derive(Debug, Clone, Default)]
+pub struct RunEnv {
+	/// The `(name, value)` variables, sorted by name
+	vars: Vec<(String, String)>,
+}
+
+/// Constructors
+impl RunEnv {
+	/// Resolve the env values (fails if a secret is not found, so that the run fails before its first task).
+	///
+	/// The secrets require the `secrets` capability when the pack capabilities are enforced.
+	pub fn new(env: &HashMap<String, EnvValue>, pack_capabilities: Option<&PackCapabilities>) -> Result<Self> {
+		let mut vars = Vec::with_capacity(env.len());
+		for (name, value) in env {
+			let value = match value {
+				EnvValue::Value(value) => value.clone(),
+				EnvValue::Secret { secret } => {
+					if let Some(pack_capabilities) = pack_capabilities {
+						pack_capabilities
+							.check(PackCapability::Secrets, &format!("agent option 'env.{name}' secret"))?;
+					}
+					let value = get_secret(secret)
+						.or_else(|| std::env::var(secret).ok())
+						.filter(|value| !value.is_empty())
+						.ok_or_else(|| {
+							Error::custom(format!(
+								"Agent option 'env.{name}' secret '{secret}' not found (keychain 'aipack_secrets/{secret}', or environment variable)"
+							))
+						})?;
+					register_secret(&value);
+					value
+				}
+			};
+			vars.push((name.clone(), value));
+		}
+		vars.sort();
+		Ok(Self { vars })
+	}
+}
+
+/// Getters
+impl RunEnv {
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.vars.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+	}
+
+	pub fn vars(&self) -> &[(String, String)] {
+		&self.vars
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_types_run_env_new() -> Result<()> {
+		// -- Setup & Fixtures
+		let env = HashMap::from([
+			("APP_ENV".to_string(), EnvValue::Value("staging".to_string())),
+			("APP_REGION".to_string(), EnvValue::Value("eu".to_string())),
+		]);
+		let env_missing = HashMap::from([(
+			"TOKEN".to_string(),
+			EnvValue::Secret {
+				secret: "AIPACK_TEST_RUN_ENV_NOT_SET".to_string(),
+			},
+		)]);
+
+		// -- Exec
+		let run_env = RunEnv::new(&env, None)?;
+
+		// -- Check
+		assert_eq!(run_env.get("APP_ENV"), Some("staging"));
+		assert_eq!(run_env.get("APP_REGION"), Some("eu"));
+		assert_eq!(run_env.vars()[0].0, "APP_ENV");
+		let err = RunEnv::new(&env_missing, None).err().ok_or("Should fail")?;
+		assert!(err.to_string().contains("secret 'AIPACK_TEST_RUN_ENV_NOT_SET' not found"));
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_types_run_env_new_secret_no_capability() -> Result<()> {
+		// -- Setup & Fixtures
+		let env = HashMap::from([(
+			"TOKEN".to_string(),
+			EnvValue::Secret {
+				secret: "AIPACK_TEST_RUN_ENV_NOT_SET".to_string(),
+			},
+		)]);
+		let capabilities = PackCapabilities::new(
+			"acme@restricted",
+			vec![PackCapability::Net],
+			simple_fs::SPath::new("/home/me/.aipack-base/support/pack/acme/restricted"),
+		);
+
+		// -- Exec
+		let res = RunEnv::new(&env, Some(&capabilities));
+
+		// -- Check
+		let err = res.err().ok_or("Should fail")?;
+		assert!(err.to_string().contains("requires the 'secrets' capability"));
+		let capabilities = PackCapabilities::new(
+			"acme@secrets",
+			vec![PackCapability::Secrets],
+			simple_fs::SPath::new("/home/me/.aipack-base/support/pack/acme/secrets"),
+		);
+		let err = RunEnv::new(&env, Some(&capabilities)).err().ok_or("Should fail")?;
+		assert!(err.to_string().contains("secret 'AIPACK_TEST_RUN_ENV_NOT_SET' not found"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
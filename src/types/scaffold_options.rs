@@ -0,0 +1,93 @@
+use crate::script::LuaValueExt;
+use crate::{Error, Result};
+use mlua::{FromLua, Lua, Table, Value};
+
+/// The options of the `aip.scaffold.render` API.
+#[derive(Debug, Default, Clone)]
+pub struct ScaffoldOptions {
+	/// The policy when a destination file exists (default `"skip"`).
+	pub conflict: ConflictPolicy,
+	/// The template files to render (relative to the template dir, default all)
+	pub globs: Option<Vec<String>>,
+	/// The template files copied as is, without rendering (e.g., `"**/*.png"`)
+	pub raw_globs: Option<Vec<String>>,
+}
+
+/// What to do when a scaffolded file exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+	/// Keep the existing file (the default)
+	#[default]
+	Skip,
+	/// Replace the existing file
+	Overwrite,
+	/// Ask the user for each existing file (with the diff preview)
+	Prompt,
+	/// Fail on the first existing file (before writing anything)
+	Error,
+}
+
+impl ConflictPolicy {
+	pub fn from_name(name: &str) -> Result<Self> {
+		match name {
+			"skip" => Ok(Self::Skip),
+			"overwrite" => Ok(Self::Overwrite),
+			"prompt" => Ok(Self::Prompt),
+			"error" => Ok(Self::Error),
+			other => Err(Error::custom(format!(
+				"conflict '{other}' is not valid (must be 'skip', 'overwrite', 'prompt', or 'error')"
+			))),
+		}
+	}
+}
+
+impl FromLua for ScaffoldOptions {
+	fn from_lua(value: Value, _lua: &Lua) -> mlua::Result<Self> {
+		match value {
+			Value::Nil => Ok(Self::default()),
+			Value::Table(table) => {
+				let conflict = match table.x_get_string("conflict") {
+					Some(name) => ConflictPolicy::from_name(&name)?,
+					None => ConflictPolicy::default(),
+				};
+				let globs = get_globs(&table, "globs")?;
+				let raw_globs = get_globs(&table, "raw_globs")?;
+
+				Ok(Self {
+					conflict,
+					globs,
+					raw_globs,
+				})
+			}
+			other => Err(mlua::Error::FromLuaConversionError {
+				from: other.type_name(),
+				to: "ScaffoldOptions".to_string(),
+				message: Some("Expected nil or a table for ScaffoldOptions".into()),
+			}),
+		}
+	}
+}
+
+// region:    --- Support
+
+/// The glob or list of globs of the `name` property
+fn get_globs(table: &Table, name: &str) -> mlua::Result<Option<Vec<String>>> {
+	match table.x_get_value(name) {
+		None => Ok(None),
+		Some(Value::String(glob)) => Ok(Some(vec![glob.to_str()?.to_string()])),
+		Some(Value::Table(globs)) => {
+			let globs = globs
+				.sequence_values::<String>()
+				.collect::<mlua::Result<Vec<_>>>()
+				.map_err(|err| Error::custom(format!("ScaffoldOptions.{name} must be a list of strings. {err}")))?;
+			Ok(Some(globs))
+		}
+		Some(other) => Err(mlua::Error::FromLuaConversionError {
+			from: other.type_name(),
+			to: "Vec<String>".to_string(),
+			message: Some(format!("ScaffoldOptions.{name} must be a string or a list of strings")),
+		}),
+	}
+}
+
+// endregion: --- Support
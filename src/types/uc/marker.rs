@@ -5,3 +5,31 @@ pub struct Marker {
 	pub label: String,
 	pub content: String,
 }
+
+// region:    --- TimelineMarker
+
+/// A marker added to a run or task timeline (via `aip.ui.marker(...)`).
+/// Stored as the JSON message of a `LogKind::AgentMarker` log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineMarker {
+	pub label: String,
+	#[serde(default)]
+	pub level: MarkerLevel,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub link: Option<String>,
+}
+
+#[derive(
+	Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, strum::EnumString, strum::IntoStaticStr,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum MarkerLevel {
+	#[default]
+	Info,
+	Success,
+	Warn,
+	Error,
+}
+
+// endregion: --- TimelineMarker
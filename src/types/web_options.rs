@@ -4,6 +4,7 @@ use reqwest::ClientBuilder;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::redirect::Policy;
 use std::collections::HashMap;
+use std::time::Duration;
 
 pub const DEFAULT_REDIRECT_LIMIT: i32 = 5;
 pub const DEFAULT_UA_AIPACK: &str = "aipack";
@@ -30,12 +31,37 @@ pub struct WebOptions {
 	/// will use .redirect(Policy::limited(n))
 	pub redirect_limit: Option<i32>,
 
+	/// If false, redirects are not followed (the 3xx response is returned as is).
+	/// Defaults to true (following up to `redirect_limit`).
+	pub follow_redirects: Option<bool>,
+
+	/// Total request timeout in milliseconds (no timeout by default).
+	pub timeout_ms: Option<u64>,
+
+	/// How the request `data` is sent (for `post`, `put`, `patch`).
+	/// When `None`, a string is sent as text, and a table as JSON.
+	pub body_type: Option<WebBodyType>,
+
 	/// If true, attempts to parse response content (e.g., JSON) based on Content-Type header.
 	/// If set, the `content` field in `WebResponse` will be the parsed Lua value, otherwise a string.
 	/// since: 0.8.6
 	pub parse: Option<bool>,
 }
 
+/// The request body encoding of the `data` given to `aip.web.post`, `put`, `patch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString, strum::IntoStaticStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum WebBodyType {
+	/// `plain/text` (string data only)
+	Text,
+	/// `application/json` (table is serialized, string is sent as is)
+	Json,
+	/// `application/x-www-form-urlencoded` (table of name/value, string is sent as is)
+	Form,
+	/// `multipart/form-data` (table of name/value or file part `{path = "..."}`)
+	Multipart,
+}
+
 impl FromLua for WebOptions {
 	fn from_lua(value: Value, _lua: &Lua) -> mlua::Result<Self> {
 		match value {
@@ -52,6 +78,28 @@ impl FromLua for WebOptions {
 				// -- Extract redirect_limit
 				let redirect_limit = table.x_get_i64("redirect_limit").map(|v| v as i32);
 
+				// -- Extract follow_redirects & timeout_ms
+				let follow_redirects = table.x_get_bool("follow_redirects");
+				let timeout_ms = table.x_get_i64("timeout_ms").map(|v| v.max(0) as u64);
+
+				// -- Extract body_type
+				let body_type = match table.x_get_string("body_type") {
+					Some(body_type) => {
+						Some(
+							body_type
+								.parse::<WebBodyType>()
+								.map_err(|_| mlua::Error::FromLuaConversionError {
+									from: "string",
+									to: "WebBodyType".to_string(),
+									message: Some(format!(
+										"body_type '{body_type}' not supported. Must be 'text', 'json', 'form', or 'multipart'"
+									)),
+								})?,
+						)
+					}
+					None => None,
+				};
+
 				// -- Extract parse
 				let parse = table.x_get_bool("parse");
 
@@ -89,6 +137,9 @@ impl FromLua for WebOptions {
 					user_agent,
 					headers,
 					redirect_limit,
+					follow_redirects,
+					timeout_ms,
+					body_type,
 					parse,
 				})
 			}
@@ -105,9 +156,19 @@ impl WebOptions {
 	/// Apply web options to a reqwest ClientBuilder.
 	/// Consumes self and returns the modified builder.
 	pub fn apply_to_reqwest_builder(mut self, mut client_builder: ClientBuilder) -> ClientBuilder {
-		// Apply redirect limit
-		let limit = self.redirect_limit.unwrap_or(DEFAULT_REDIRECT_LIMIT);
-		client_builder = client_builder.redirect(Policy::limited(limit as usize));
+		// Apply redirect policy
+		let policy = if self.follow_redirects == Some(false) {
+			Policy::none()
+		} else {
+			let limit = self.redirect_limit.unwrap_or(DEFAULT_REDIRECT_LIMIT);
+			Policy::limited(limit as usize)
+		};
+		client_builder = client_builder.redirect(policy);
+
+		// Apply timeout
+		if let Some(timeout_ms) = self.timeout_ms {
+			client_builder = client_builder.timeout(Duration::from_millis(timeout_ms));
+		}
 
 		// region:    --- Extract & Set user_agent
 
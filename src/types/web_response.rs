@@ -5,12 +5,12 @@ use reqwest::header::HeaderMap;
 use reqwest::{Response, StatusCode, header};
 use std::collections::HashMap;
 
-/// Represents the result of an HTTP request made by `aip.web.get` or `aip.web.post`.
+/// Represents the result of an HTTP request made by the `aip.web` request functions (`get`, `post`, `put`, `patch`, `delete`, `head`).
 ///
 /// This structure is converted to a Lua table for agent scripts.
 ///
 /// NOTE: The `content` field is a raw string by default. It is parsed into a Lua table (JSON) only if
-/// `WebOptions.parse` is set to `true` and the response `content_type` is `application/json`.
+/// `WebOptions.parse` is set to `true` and the response `content_type` is JSON (`application/json` or `+json`).
 ///
 /// ## Lua Documentation
 ///
@@ -51,7 +51,8 @@ impl IntoLua for WebResponse {
 		table.set("url", self.url)?;
 
 		let content_type_str = self.content_type.as_deref().unwrap_or_default();
-		let should_parse_json = self.parse.unwrap_or(false) && content_type_str.starts_with("application/json");
+		let should_parse_json =
+			self.parse.unwrap_or(false) && is_json_content_type(content_type_str) && !self.content.is_empty();
 
 		let content_lua_value = if should_parse_json {
 			// Attempt to parse JSON
@@ -148,6 +149,12 @@ impl WebResponse {
 
 // region:    --- Support
 
+/// True for `application/json` and the `+json` suffixed types (e.g., `application/problem+json`).
+fn is_json_content_type(content_type: &str) -> bool {
+	let mime = content_type.split(';').next().unwrap_or_default().trim();
+	mime.eq_ignore_ascii_case("application/json") || mime.to_ascii_lowercase().ends_with("+json")
+}
+
 fn transform_headers(headers: HeaderMap) -> HashMap<String, Vec<String>> {
 	headers
 		.into_iter()
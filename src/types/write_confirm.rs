@@ -0,0 +1,119 @@
+use crate::{Error, Result};
+use diffy::DiffOptions;
+use simple_fs::get_glob_set;
+
+/// The max number of diff lines shown in the approval prompt (the rest is summarized)
+const PREVIEW_MAX_LINES: usize = 40;
+
+/// The `confirm_writes = true` agent option, enforced for the `aip.file.save`, `append`, and `save_changes` calls.
+///
+/// The writes of the files not matching `confirm_writes_allow` wait for the user approval (with a diff preview).
+#[derive(Debug, Clone)]
+pub struct WriteConfirm {
+	/// The workspace relative globs of the files written without approval
+	allow_globs: Vec<String>,
+}
+
+/// Constructors
+impl WriteConfirm {
+	pub fn new(allow_globs: Vec<String>) -> Result<Self> {
+		// Validate the globs early (so that a bad glob fails the run, not the first write)
+		if !allow_globs.is_empty() {
+			let glob_refs = allow_globs.iter().map(String::as_str).collect::<Vec<_>>();
+			get_glob_set(&glob_refs).map_err(|err| {
+				Error::custom(format!(
+					"Agent option 'confirm_writes_allow' has invalid globs {allow_globs:?}. {err}"
+				))
+			})?;
+		}
+		Ok(Self { allow_globs })
+	}
+}
+
+/// Checks
+impl WriteConfirm {
+	/// Returns true if the file (workspace relative path) can be written without approval
+	pub fn is_allowed(&self, rel_path: &str) -> bool {
+		if self.allow_globs.is_empty() {
+			return false;
+		}
+		let glob_refs = self.allow_globs.iter().map(String::as_str).collect::<Vec<_>>();
+		get_glob_set(&glob_refs)
+			.map(|glob_set| glob_set.is_match(rel_path))
+			.unwrap_or(false)
+	}
+}
+
+/// Preview
+impl WriteConfirm {
+	/// The unified diff of the write (truncated to `PREVIEW_MAX_LINES` lines).
+	/// NOTE: `original` is None when the file does not exist yet.
+	pub fn diff_preview(rel_path: &str, original: Option<&str>, content: &str) -> String {
+		let original_name = if original.is_some() {
+			format!("a/{rel_path}")
+		} else {
+			"/dev/null".to_string()
+		};
+		let patch = DiffOptions::new()
+			.set_context_len(2)
+			.set_original_filename(original_name)
+			.set_modified_filename(format!("b/{rel_path}"))
+			.create_patch(original.unwrap_or_default(), content)
+			.to_string();
+
+		let lines = patch.lines().collect::<Vec<_>>();
+		if lines.len() <= PREVIEW_MAX_LINES {
+			return patch;
+		}
+		let mut preview = lines[..PREVIEW_MAX_LINES].join("\n");
+		preview.push_str(&format!(
+			"\n... ({} more diff lines)\n",
+			lines.len() - PREVIEW_MAX_LINES
+		));
+		preview
+	}
+}
+
+// region:    --- Tests
+
+#[cfg(test)]
+mod tests {
+	type Result<T> = core::result::Result<T, Box<dyn std::error::Error>>; // For tests.
+
+	use super::*;
+
+	#[test]
+	fn test_types_write_confirm_is_allowed() -> Result<()> {
+		// -- Setup & Fixtures
+		let write_confirm = WriteConfirm::new(vec![".aipack/**".to_string(), "docs/*.md".to_string()])?;
+
+		// -- Exec & Check
+		assert!(write_confirm.is_allowed(".aipack/.session/out.md"));
+		assert!(write_confirm.is_allowed("docs/README.md"));
+		assert!(!write_confirm.is_allowed("src/main.rs"));
+		assert!(!WriteConfirm::new(Vec::new())?.is_allowed("docs/README.md"));
+		assert!(WriteConfirm::new(vec!["src/[".to_string()]).is_err());
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_types_write_confirm_diff_preview() -> Result<()> {
+		// -- Exec
+		let changed = WriteConfirm::diff_preview("src/main.rs", Some("one\ntwo\nthree\n"), "one\n2\nthree\n");
+		let created = WriteConfirm::diff_preview("new.md", None, "hello\n");
+		let long = WriteConfirm::diff_preview("long.txt", None, &"line\n".repeat(100));
+
+		// -- Check
+		assert!(changed.contains("--- a/src/main.rs\n+++ b/src/main.rs"));
+		assert!(changed.contains("-two\n+2\n"));
+		assert!(created.contains("--- /dev/null\n+++ b/new.md"));
+		assert!(created.contains("+hello"));
+		assert_eq!(long.lines().count(), PREVIEW_MAX_LINES + 1);
+		assert!(long.ends_with("(63 more diff lines)\n"));
+
+		Ok(())
+	}
+}
+
+// endregion: --- Tests
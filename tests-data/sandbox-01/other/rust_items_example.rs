@@ -0,0 +1,20 @@
+/// A 2D point
+pub struct Point {
+	pub x: f64,
+	pub y: f64,
+}
+
+impl Point {
+	pub fn new(x: f64, y: f64) -> Self {
+		Self { x, y }
+	}
+
+	/// The distance to the origin
+	pub fn norm(&self) -> f64 {
+		self.x.hypot(self.y)
+	}
+}
+
+fn origin_dist(point: &Point) -> f64 {
+	point.norm()
+}